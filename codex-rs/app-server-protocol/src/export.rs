@@ -19,6 +19,7 @@ use schemars::schema_for;
 use serde::Serialize;
 use serde_json::Map;
 use serde_json::Value;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ffi::OsStr;
@@ -176,6 +177,64 @@ pub fn generate_json(out_dir: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Every definition of the protocol, as served by `protocol/describe`.
+pub struct ProtocolDefinitions {
+    /// The bundle written to `codex_app_server_protocol.schemas.json`.
+    pub json_schema: Value,
+    /// TypeScript sources keyed by their path under the output directory,
+    /// e.g. `v2/Thread.ts`. Empty unless requested.
+    pub typescript: BTreeMap<String, String>,
+}
+
+/// Generate the JSON Schema bundle and, if asked, the TypeScript definitions
+/// in a scratch directory that is removed afterwards.
+pub fn describe_protocol(include_typescript: bool) -> Result<ProtocolDefinitions> {
+    struct ScratchDir(PathBuf);
+
+    impl Drop for ScratchDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    let scratch = ScratchDir(std::env::temp_dir().join(format!(
+        "codex_app_server_protocol_{}",
+        uuid::Uuid::now_v7()
+    )));
+    let json_dir = scratch.0.join("json");
+    generate_json(&json_dir)?;
+    let bundle_path = json_dir.join("codex_app_server_protocol.schemas.json");
+    let bundle = fs::read(&bundle_path)
+        .with_context(|| format!("Failed to read {}", bundle_path.display()))?;
+    let json_schema = serde_json::from_slice(&bundle)?;
+
+    let mut typescript = BTreeMap::new();
+    if include_typescript {
+        let ts_dir = scratch.0.join("ts");
+        let options = GenerateTsOptions {
+            run_prettier: false,
+            ..GenerateTsOptions::default()
+        };
+        generate_ts_with_options(&ts_dir, None, options)?;
+        for file in ts_files_in_recursive(&ts_dir)? {
+            let name = file
+                .strip_prefix(&ts_dir)?
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let contents = fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            typescript.insert(name, contents);
+        }
+    }
+
+    Ok(ProtocolDefinitions {
+        json_schema,
+        typescript,
+    })
+}
+
 fn build_schema_bundle(schemas: Vec<GeneratedSchema>) -> Result<Value> {
     const SPECIAL_DEFINITIONS: &[&str] = &[
         "ClientNotification",
@@ -2,6 +2,8 @@ mod export;
 mod jsonrpc_lite;
 mod protocol;
 
+pub use export::ProtocolDefinitions;
+pub use export::describe_protocol;
 pub use export::generate_json;
 pub use export::generate_ts;
 pub use export::generate_types;
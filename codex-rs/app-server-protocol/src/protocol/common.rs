@@ -100,6 +100,20 @@ client_request_definitions! {
     },
 
     /// NEW APIs
+    // Server lifecycle
+    Shutdown => "shutdown" {
+        params: #[serde(default)] v2::ShutdownParams,
+        response: v2::ShutdownResponse,
+    },
+    ProtocolDescribe => "protocol/describe" {
+        params: #[serde(default)] v2::ProtocolDescribeParams,
+        response: v2::ProtocolDescribeResponse,
+    },
+    NotificationsSubscribe => "notifications/subscribe" {
+        params: #[serde(default)] v2::NotificationsSubscribeParams,
+        response: v2::NotificationsSubscribeResponse,
+    },
+
     // Thread lifecycle
     ThreadStart => "thread/start" {
         params: v2::ThreadStartParams,
@@ -109,18 +123,122 @@ client_request_definitions! {
         params: v2::ThreadResumeParams,
         response: v2::ThreadResumeResponse,
     },
+    ThreadFork => "thread/fork" {
+        params: v2::ThreadForkParams,
+        response: v2::ThreadForkResponse,
+    },
     ThreadArchive => "thread/archive" {
         params: v2::ThreadArchiveParams,
         response: v2::ThreadArchiveResponse,
     },
+    ThreadExport => "thread/export" {
+        params: v2::ThreadExportParams,
+        response: v2::ThreadExportResponse,
+    },
+    ThreadBundleExport => "thread/bundle/export" {
+        params: v2::ThreadBundleExportParams,
+        response: v2::ThreadBundleExportResponse,
+    },
+    ThreadBundleImport => "thread/bundle/import" {
+        params: v2::ThreadBundleImportParams,
+        response: v2::ThreadBundleImportResponse,
+    },
     ThreadList => "thread/list" {
         params: v2::ThreadListParams,
         response: v2::ThreadListResponse,
     },
+    ThreadsSearch => "threads/search" {
+        params: v2::ThreadSearchParams,
+        response: v2::ThreadSearchResponse,
+    },
+    ThreadsCreateFromTemplate => "threads/createFromTemplate" {
+        params: v2::ThreadCreateFromTemplateParams,
+        response: v2::ThreadStartResponse,
+    },
+    ThreadTemplateList => "threads/templates/list" {
+        params: v2::ThreadTemplateListParams,
+        response: v2::ThreadTemplateListResponse,
+    },
     ThreadCompact => "thread/compact" {
         params: v2::ThreadCompactParams,
         response: v2::ThreadCompactResponse,
     },
+    ThreadSetProfile => "thread/setProfile" {
+        params: v2::ThreadSetProfileParams,
+        response: v2::ThreadSetProfileResponse,
+    },
+    ThreadSetTerminalSize => "thread/setTerminalSize" {
+        params: v2::ThreadSetTerminalSizeParams,
+        response: v2::ThreadSetTerminalSizeResponse,
+    },
+    ThreadRename => "thread/rename" {
+        params: v2::ThreadRenameParams,
+        response: v2::ThreadRenameResponse,
+    },
+    ExecutionGetOutput => "execution/getOutput" {
+        params: v2::ExecutionGetOutputParams,
+        response: v2::ExecutionGetOutputResponse,
+    },
+    ExecutionAssess => "execution/assess" {
+        params: v2::ExecutionAssessParams,
+        response: v2::ExecutionAssessResponse,
+    },
+    BackgroundTaskRead => "backgroundTask/read" {
+        params: v2::BackgroundTaskReadParams,
+        response: v2::BackgroundTaskReadResponse,
+    },
+    BackgroundTaskTerminate => "backgroundTask/terminate" {
+        params: v2::BackgroundTaskTerminateParams,
+        response: v2::BackgroundTaskTerminateResponse,
+    },
+    WorkspaceInstructionList => "workspaceInstruction/list" {
+        params: v2::WorkspaceInstructionListParams,
+        response: v2::WorkspaceInstructionListResponse,
+    },
+    WorkspaceInstructionAdd => "workspaceInstruction/add" {
+        params: v2::WorkspaceInstructionAddParams,
+        response: v2::WorkspaceInstructionAddResponse,
+    },
+    WorkspaceInstructionUpdate => "workspaceInstruction/update" {
+        params: v2::WorkspaceInstructionUpdateParams,
+        response: v2::WorkspaceInstructionUpdateResponse,
+    },
+    WorkspaceInstructionRemove => "workspaceInstruction/remove" {
+        params: v2::WorkspaceInstructionRemoveParams,
+        response: v2::WorkspaceInstructionRemoveResponse,
+    },
+    ThreadContextAdd => "thread/context/add" {
+        params: v2::ThreadContextAddParams,
+        response: v2::ThreadContextAddResponse,
+    },
+    ThreadContextRemove => "thread/context/remove" {
+        params: v2::ThreadContextRemoveParams,
+        response: v2::ThreadContextRemoveResponse,
+    },
+    ThreadContextList => "thread/context/list" {
+        params: v2::ThreadContextListParams,
+        response: v2::ThreadContextListResponse,
+    },
+    ThreadUsage => "thread/usage" {
+        params: v2::ThreadUsageParams,
+        response: v2::ThreadUsageResponse,
+    },
+    CommandList => "commands/list" {
+        params: v2::CommandListParams,
+        response: v2::CommandListResponse,
+    },
+    CommandRun => "commands/run" {
+        params: v2::CommandRunParams,
+        response: v2::CommandRunResponse,
+    },
+    ExperimentStats => "experiment/stats" {
+        params: v2::ExperimentStatsParams,
+        response: v2::ExperimentStatsResponse,
+    },
+    ConfigValidate => "config/validate" {
+        params: v2::ConfigValidateParams,
+        response: v2::ConfigValidateResponse,
+    },
     TurnStart => "turn/start" {
         params: v2::TurnStartParams,
         response: v2::TurnStartResponse,
@@ -159,6 +277,11 @@ client_request_definitions! {
         response: v2::GetAccountRateLimitsResponse,
     },
 
+    McpServerAuthRead => "mcpServer/auth/read" {
+        params: v2::McpServerAuthReadParams,
+        response: v2::McpServerAuthReadResponse,
+    },
+
     FeedbackUpload => "feedback/upload" {
         params: v2::FeedbackUploadParams,
         response: v2::FeedbackUploadResponse,
@@ -169,6 +292,16 @@ client_request_definitions! {
         response: v2::GetAccountResponse,
     },
 
+    AccountsList => "accounts/list" {
+        params: #[ts(type = "undefined")] #[serde(skip_serializing_if = "Option::is_none")] Option<()>,
+        response: v2::AccountsListResponse,
+    },
+
+    AccountsSwitch => "accounts/switch" {
+        params: v2::AccountsSwitchParams,
+        response: v2::AccountsSwitchResponse,
+    },
+
     /// DEPRECATED APIs below
     NewConversation {
         params: v1::NewConversationParams,
@@ -263,6 +396,11 @@ client_request_definitions! {
         params: v1::ExecOneOffCommandParams,
         response: v1::ExecOneOffCommandResponse,
     },
+    /// Fetch one file of a patch approval that was too large to inline.
+    GetPatchContent {
+        params: v1::GetPatchContentParams,
+        response: v1::GetPatchContentResponse,
+    },
 }
 
 /// Generates an `enum ServerRequest` where each variant is a request that the
@@ -437,6 +575,23 @@ server_request_definitions! {
         params: v2::CommandExecutionRequestApprovalParams,
         response: v2::CommandExecutionRequestApprovalResponse,
     },
+    /// Sent when a command or patch needs a directory outside the writable
+    /// roots to become writable.
+    WriteScopeRequestApproval => "item/writeScope/requestApproval" {
+        params: v2::WriteScopeRequestApprovalParams,
+        response: v2::WriteScopeRequestApprovalResponse,
+    },
+    /// Sent when an MCP server asks the user for structured input.
+    McpServerElicitationRequest => "mcpServer/elicitation/request" {
+        params: v2::McpServerElicitationRequestParams,
+        response: v2::McpServerElicitationRequestResponse,
+    },
+    /// Sent when the model asks the user a question with the `ask_user`
+    /// tool. The turn waits for the answer.
+    UserInputRequested => "item/userInput/request" {
+        params: v2::UserInputRequestedParams,
+        response: v2::UserInputRequestedResponse,
+    },
 
     /// DEPRECATED APIs below
     /// Request to approve a patch.
@@ -481,15 +636,23 @@ pub struct FuzzyFileSearchResponse {
 server_notification_definitions! {
     /// NEW NOTIFICATIONS
     ThreadStarted => "thread/started" (v2::ThreadStartedNotification),
+    ThreadCompacted => "thread/compacted" (v2::ThreadCompactedNotification),
+    ThreadSubAgentUpdated => "thread/subAgent/updated" (v2::ThreadSubAgentUpdatedNotification),
+    ThreadProfileChanged => "thread/profileChanged" (v2::ThreadProfileChangedNotification),
+    ThreadMetadataChanged => "thread/metadataChanged" (v2::ThreadMetadataChangedNotification),
+    TurnQueued => "turn/queued" (v2::TurnQueuedNotification),
     TurnStarted => "turn/started" (v2::TurnStartedNotification),
     TurnCompleted => "turn/completed" (v2::TurnCompletedNotification),
+    TurnHeartbeat => "turn/heartbeat" (v2::TurnHeartbeatNotification),
     ItemStarted => "item/started" (v2::ItemStartedNotification),
     ItemCompleted => "item/completed" (v2::ItemCompletedNotification),
     AgentMessageDelta => "item/agentMessage/delta" (v2::AgentMessageDeltaNotification),
     CommandExecutionOutputDelta => "item/commandExecution/outputDelta" (v2::CommandExecutionOutputDeltaNotification),
     McpToolCallProgress => "item/mcpToolCall/progress" (v2::McpToolCallProgressNotification),
+    FileChangeDiagnostics => "item/fileChange/diagnostics" (v2::FileChangeDiagnosticsNotification),
     AccountUpdated => "account/updated" (v2::AccountUpdatedNotification),
     AccountRateLimitsUpdated => "account/rateLimits/updated" (v2::AccountRateLimitsUpdatedNotification),
+    AccountLoginDeviceCode => "account/login/deviceCode" (v2::AccountLoginDeviceCodeNotification),
     ReasoningSummaryTextDelta => "item/reasoning/summaryTextDelta" (v2::ReasoningSummaryTextDeltaNotification),
     ReasoningSummaryPartAdded => "item/reasoning/summaryPartAdded" (v2::ReasoningSummaryPartAddedNotification),
     ReasoningTextDelta => "item/reasoning/textDelta" (v2::ReasoningTextDeltaNotification),
@@ -497,6 +660,9 @@ server_notification_definitions! {
     /// Notifies the user of world-writable directories on Windows, which cannot be protected by the sandbox.
     WindowsWorldWritableWarning => "windows/worldWritableWarning" (v2::WindowsWorldWritableWarningNotification),
 
+    /// Warns that a v1 method the client just called is deprecated.
+    DeprecationNotice => "deprecationNotice" (v2::DeprecationNoticeNotification),
+
     #[serde(rename = "account/login/completed")]
     #[ts(rename = "account/login/completed")]
     #[strum(serialize = "account/login/completed")]
@@ -663,6 +829,38 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn deserialize_shutdown_without_params() -> Result<()> {
+        let request: ClientRequest = serde_json::from_value(json!({
+            "method": "shutdown",
+            "id": 3,
+        }))?;
+        assert_eq!(
+            ClientRequest::Shutdown {
+                request_id: RequestId::Integer(3),
+                params: v2::ShutdownParams::default(),
+            },
+            request,
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn deserialize_protocol_describe_without_params() -> Result<()> {
+        let request: ClientRequest = serde_json::from_value(json!({
+            "method": "protocol/describe",
+            "id": 4,
+        }))?;
+        assert_eq!(
+            ClientRequest::ProtocolDescribe {
+                request_id: RequestId::Integer(4),
+                params: v2::ProtocolDescribeParams::default(),
+            },
+            request,
+        );
+        Ok(())
+    }
+
     #[test]
     fn serialize_account_login_api_key() -> Result<()> {
         let request = ClientRequest::LoginAccount {
@@ -704,6 +902,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn serialize_account_login_chatgpt_device_code() -> Result<()> {
+        let request = ClientRequest::LoginAccount {
+            request_id: RequestId::Integer(3),
+            params: v2::LoginAccountParams::ChatgptDeviceCode,
+        };
+        assert_eq!(
+            json!({
+                "method": "account/login/start",
+                "id": 3,
+                "params": {
+                    "type": "chatgptDeviceCode"
+                }
+            }),
+            serde_json::to_value(&request)?,
+        );
+        Ok(())
+    }
+
     #[test]
     fn serialize_account_logout() -> Result<()> {
         let request = ClientRequest::LogoutAccount {
@@ -21,6 +21,37 @@ pub fn build_turns_from_event_msgs(events: &[EventMsg]) -> Vec<Turn> {
     builder.finish()
 }
 
+/// Number of user messages to keep when forking `turns` at `item_id`, or
+/// `None` if no item has that id.
+///
+/// Forking at a user message cuts right before it; forking at any other item
+/// keeps the whole turn that contains it.
+pub fn user_messages_before_fork_point(turns: &[Turn], item_id: &str) -> Option<usize> {
+    let is_user_message = |item: &ThreadItem| matches!(item, ThreadItem::UserMessage { .. });
+    let mut kept = 0;
+    for turn in turns {
+        let mut seen_in_turn = 0;
+        for item in &turn.items {
+            if item.id() == item_id {
+                return Some(if is_user_message(item) {
+                    kept + seen_in_turn
+                } else {
+                    kept + turn
+                        .items
+                        .iter()
+                        .filter(|item| is_user_message(item))
+                        .count()
+                });
+            }
+            if is_user_message(item) {
+                seen_in_turn += 1;
+            }
+        }
+        kept += seen_in_turn;
+    }
+    None
+}
+
 struct ThreadHistoryBuilder {
     turns: Vec<Turn>,
     current_turn: Option<PendingTurn>,
@@ -199,6 +230,8 @@ impl From<PendingTurn> for Turn {
             id: value.id,
             items: value.items,
             status: value.status,
+            commit: None,
+            diff: None,
         }
     }
 }
@@ -406,4 +439,31 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn fork_point_cuts_before_user_messages_and_after_other_items() {
+        let events = vec![
+            EventMsg::UserMessage(UserMessageEvent {
+                message: "First turn".into(),
+                images: None,
+            }),
+            EventMsg::AgentMessage(AgentMessageEvent {
+                message: "Reply one".into(),
+            }),
+            EventMsg::UserMessage(UserMessageEvent {
+                message: "Second turn".into(),
+                images: None,
+            }),
+            EventMsg::AgentMessage(AgentMessageEvent {
+                message: "Reply two".into(),
+            }),
+        ];
+        let turns = build_turns_from_event_msgs(&events);
+
+        assert_eq!(user_messages_before_fork_point(&turns, "item-1"), Some(0));
+        assert_eq!(user_messages_before_fork_point(&turns, "item-2"), Some(1));
+        assert_eq!(user_messages_before_fork_point(&turns, "item-3"), Some(1));
+        assert_eq!(user_messages_before_fork_point(&turns, "item-4"), Some(2));
+        assert_eq!(user_messages_before_fork_point(&turns, "item-5"), None);
+    }
 }
@@ -10,8 +10,10 @@ use codex_protocol::config_types::Verbosity;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::DeferredFileChange;
 use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::FileChange;
+use codex_protocol::protocol::PatchFileStatus;
 use codex_protocol::protocol::ReviewDecision;
 use codex_protocol::protocol::SandboxCommandAssessment;
 use codex_protocol::protocol::SandboxPolicy;
@@ -208,6 +210,14 @@ pub struct ApplyPatchApprovalParams {
     /// When set, the agent is asking the user to allow writes under this root
     /// for the remainder of the session (unclear if this is honored today).
     pub grant_root: Option<PathBuf>,
+    /// Files omitted from `file_changes` because the patch was too large to
+    /// inline.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deferred_file_changes: Vec<DeferredFileChange>,
+    /// Per-file result of dry-running the patch against the working tree, so
+    /// clients can warn when approving would fail or clobber concurrent edits.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub file_statuses: HashMap<PathBuf, PatchFileStatus>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -216,6 +226,24 @@ pub struct ApplyPatchApprovalResponse {
     pub decision: ReviewDecision,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPatchContentParams {
+    pub conversation_id: ConversationId,
+    /// `call_id` of the pending [ApplyPatchApprovalParams].
+    pub call_id: String,
+    /// One of the paths listed in `deferred_file_changes`.
+    pub path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPatchContentResponse {
+    /// The full change, or `None` when the approval is no longer pending or
+    /// the path was not part of the patch.
+    pub change: Option<FileChange>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct ExecCommandApprovalParams {
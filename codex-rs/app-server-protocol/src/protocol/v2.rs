@@ -8,12 +8,21 @@ use codex_protocol::approvals::SandboxCommandAssessment as CoreSandboxCommandAss
 use codex_protocol::config_types::ReasoningEffort;
 use codex_protocol::config_types::ReasoningSummary;
 use codex_protocol::items::AgentMessageContent as CoreAgentMessageContent;
+use codex_protocol::items::CommandOutputTruncation as CoreCommandOutputTruncation;
 use codex_protocol::items::TurnItem as CoreTurnItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::parse_command::ParsedCommand as CoreParsedCommand;
 use codex_protocol::protocol::CreditsSnapshot as CoreCreditsSnapshot;
+use codex_protocol::protocol::FileChange as CoreFileChange;
+use codex_protocol::protocol::FileDiagnostics as CoreFileDiagnostics;
 use codex_protocol::protocol::RateLimitSnapshot as CoreRateLimitSnapshot;
 use codex_protocol::protocol::RateLimitWindow as CoreRateLimitWindow;
+use codex_protocol::protocol::RequestTokenUsage as CoreRequestTokenUsage;
+use codex_protocol::protocol::TokenUsage as CoreTokenUsage;
+use codex_protocol::protocol::TurnActivity as CoreTurnActivity;
+use codex_protocol::protocol::TurnCommit as CoreTurnCommit;
+use codex_protocol::protocol::TurnDiffSummary as CoreTurnDiffSummary;
+use codex_protocol::protocol::UsageAttribution as CoreUsageAttribution;
 use codex_protocol::user_input::UserInput as CoreUserInput;
 use mcp_types::ContentBlock as McpContentBlock;
 use schemars::JsonSchema;
@@ -55,7 +64,55 @@ v2_enum_from_core!(
 
 v2_enum_from_core!(
     pub enum SandboxMode from codex_protocol::config_types::SandboxMode {
-        ReadOnly, WorkspaceWrite, DangerFullAccess
+        ReadOnly, WorkspaceWrite, DangerFullAccess, Audit
+    }
+);
+
+v2_enum_from_core!(
+    pub enum CompactionTrigger from codex_protocol::protocol::CompactionTrigger {
+        Manual, Auto
+    }
+);
+
+v2_enum_from_core!(
+    pub enum BackgroundTaskStatus from codex_protocol::protocol::BackgroundTaskStatus {
+        Running, Exited, Terminated
+    }
+);
+
+v2_enum_from_core!(
+    pub enum SubAgentStatus from codex_protocol::protocol::SubAgentStatus {
+        Running, Completed, Failed
+    }
+);
+
+v2_enum_from_core!(
+    pub enum ForgeProvider from codex_protocol::protocol::ForgeProvider {
+        Github, Gitlab
+    }
+);
+
+v2_enum_from_core!(
+    pub enum PatchApplyStatus from codex_protocol::items::FileChangeStatus {
+        InProgress, Completed, MergedWithConflicts, Failed
+    }
+);
+
+v2_enum_from_core!(
+    pub enum CommandExecutionStatus from codex_protocol::items::CommandExecutionStatus {
+        InProgress, Completed, Failed, Declined
+    }
+);
+
+v2_enum_from_core!(
+    pub enum BlockedAction from codex_protocol::items::BlockedAction {
+        ApplyPatch, UnsandboxedCommand, SandboxDenied
+    }
+);
+
+v2_enum_from_core!(
+    pub enum WriteScopeDecision from codex_protocol::protocol::WriteScopeDecision {
+        GrantForSession, GrantForProject, Deny
     }
 );
 
@@ -83,6 +140,7 @@ pub enum ApprovalDecision {
 pub enum SandboxPolicy {
     DangerFullAccess,
     ReadOnly,
+    Audit,
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
     WorkspaceWrite {
@@ -104,6 +162,7 @@ impl SandboxPolicy {
                 codex_protocol::protocol::SandboxPolicy::DangerFullAccess
             }
             SandboxPolicy::ReadOnly => codex_protocol::protocol::SandboxPolicy::ReadOnly,
+            SandboxPolicy::Audit => codex_protocol::protocol::SandboxPolicy::Audit,
             SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 network_access,
@@ -126,6 +185,7 @@ impl From<codex_protocol::protocol::SandboxPolicy> for SandboxPolicy {
                 SandboxPolicy::DangerFullAccess
             }
             codex_protocol::protocol::SandboxPolicy::ReadOnly => SandboxPolicy::ReadOnly,
+            codex_protocol::protocol::SandboxPolicy::Audit => SandboxPolicy::Audit,
             codex_protocol::protocol::SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 network_access,
@@ -262,6 +322,11 @@ pub enum LoginAccountParams {
     #[serde(rename = "chatgpt")]
     #[ts(rename = "chatgpt")]
     Chatgpt,
+    /// ChatGPT login for machines without a local browser: the user enters a
+    /// one-time code on another device, announced via `account/login/deviceCode`.
+    #[serde(rename = "chatgptDeviceCode")]
+    #[ts(rename = "chatgptDeviceCode")]
+    ChatgptDeviceCode,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -281,6 +346,13 @@ pub enum LoginAccountResponse {
         /// URL the client should open in a browser to initiate the OAuth flow.
         auth_url: String,
     },
+    #[serde(rename = "chatgptDeviceCode", rename_all = "camelCase")]
+    #[ts(rename = "chatgptDeviceCode", rename_all = "camelCase")]
+    ChatgptDeviceCode {
+        // Use plain String for identifiers to avoid TS/JSON Schema quirks around uuid-specific types.
+        // Convert to/from UUIDs at the application layer as needed.
+        login_id: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -323,6 +395,43 @@ pub struct GetAccountResponse {
     pub requires_openai_auth: bool,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct StoredAccount {
+    /// `None` for the default account.
+    pub label: Option<String>,
+    /// `None` when no credentials are stored under the label.
+    pub account: Option<Account>,
+    /// Whether new threads use this account.
+    pub active: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct AccountsListResponse {
+    pub data: Vec<StoredAccount>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct AccountsSwitchParams {
+    /// Label of the stored account to use; `None` for the default account.
+    /// The label does not need credentials yet: `account/login/start` then
+    /// stores them under it.
+    pub label: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct AccountsSwitchResponse {
+    /// `None` when the account still has to log in.
+    pub account: Option<Account>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -382,6 +491,75 @@ pub struct FeedbackUploadResponse {
     pub thread_id: String,
 }
 
+// Server lifecycle APIs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ShutdownParams {
+    /// How long running turns may keep going before they are interrupted.
+    /// Defaults to 30 seconds.
+    #[serde(default)]
+    pub grace_period_ms: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ShutdownResponse {
+    /// Threads whose turn was still running when the grace period ended and
+    /// had to be interrupted.
+    pub interrupted_thread_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ProtocolDescribeParams {
+    /// Also return the TypeScript definitions.
+    #[serde(default)]
+    pub include_typescript: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ProtocolDescribeResponse {
+    /// Version of the server that generated the definitions.
+    pub server_version: String,
+    /// API versions the server speaks. v1 types live at the root of the
+    /// schema's `definitions` (and of the TypeScript tree); each later
+    /// version has its own namespace, e.g. `definitions.v2` and `v2/`.
+    pub api_versions: Vec<String>,
+    /// JSON Schema (draft-07) for every request, response, and notification.
+    pub json_schema: JsonValue,
+    /// TypeScript sources keyed by path, e.g. `v2/Thread.ts`; only set when
+    /// `includeTypescript` was requested.
+    pub typescript: Option<HashMap<String, String>>,
+}
+
+/// Narrows the notifications sent to this client. Each call replaces the
+/// previous options; an empty request restores the default of receiving
+/// everything. Server requests such as approvals are always sent.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct NotificationsSubscribeParams {
+    /// Only send notifications for these threads. Notifications that do not
+    /// belong to a thread, such as `account/updated`, are always sent.
+    pub thread_ids: Option<Vec<String>>,
+    /// Only send these notification methods. An entry ending in `/*` matches
+    /// every method under that prefix, e.g. `turn/*` or `codex/event/*`.
+    pub kinds: Option<Vec<String>>,
+    /// Send streaming deltas such as `item/agentMessage/delta` and
+    /// `item/reasoning/textDelta`. Defaults to true.
+    pub include_deltas: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct NotificationsSubscribeResponse {}
+
 // === Threads, Turns, and Items ===
 // Thread APIs
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema, TS)]
@@ -396,6 +574,16 @@ pub struct ThreadStartParams {
     pub config: Option<HashMap<String, JsonValue>>,
     pub base_instructions: Option<String>,
     pub developer_instructions: Option<String>,
+    /// Replaces `tools.enabled` from config.toml for this thread.
+    pub enabled_tools: Option<Vec<String>>,
+    /// Replaces `tools.disabled` from config.toml for this thread.
+    pub disabled_tools: Option<Vec<String>>,
+    /// Label of the stored account this thread uses instead of the
+    /// server's current one.
+    pub account: Option<String>,
+    /// Environment variables set for every command the thread runs, added to
+    /// `shell_environment_policy.set` from config.toml.
+    pub env: Option<HashMap<String, String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -444,6 +632,12 @@ pub struct ThreadResumeParams {
     pub config: Option<HashMap<String, serde_json::Value>>,
     pub base_instructions: Option<String>,
     pub developer_instructions: Option<String>,
+
+    /// When true, every item of the resumed history is also sent as an
+    /// `item/completed` notification after the response, so clients that
+    /// render from notifications can prime their view of the thread.
+    #[serde(default)]
+    pub replay_history: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -459,6 +653,33 @@ pub struct ThreadResumeResponse {
     pub reasoning_effort: Option<ReasoningEffort>,
 }
 
+/// Creates a new thread whose history is a prefix of an existing thread.
+///
+/// Forking at a user message keeps everything before that message, so the
+/// message can be edited and sent again. Forking at any other item keeps the
+/// whole turn that contains it. The original thread is left untouched.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadForkParams {
+    pub thread_id: String,
+    /// Id of an item in the `turns` returned by `thread/resume`.
+    pub item_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadForkResponse {
+    pub thread: Thread,
+    pub model: String,
+    pub model_provider: String,
+    pub cwd: PathBuf,
+    pub approval_policy: AskForApproval,
+    pub sandbox: SandboxPolicy,
+    pub reasoning_effort: Option<ReasoningEffort>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -471,6 +692,75 @@ pub struct ThreadArchiveParams {
 #[ts(export_to = "v2/")]
 pub struct ThreadArchiveResponse {}
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum ThreadExportFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadExportParams {
+    pub thread_id: String,
+    pub format: ThreadExportFormat,
+    /// File the transcript is written to. Parent directories are created and
+    /// an existing file is overwritten.
+    pub output_path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadExportResponse {
+    pub output_path: PathBuf,
+    /// Number of transcript entries (messages, commands, patches, ...) written.
+    pub entry_count: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadBundleExportParams {
+    pub thread_id: String,
+    /// File the bundle is written to. Parent directories are created and an
+    /// existing file is overwritten.
+    pub output_path: PathBuf,
+    /// Include the full output of the thread's commands.
+    #[serde(default)]
+    pub include_exec_logs: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadBundleExportResponse {
+    pub output_path: PathBuf,
+    pub exec_log_count: u32,
+    /// Number of secrets replaced with placeholders in the bundle.
+    pub redacted_secret_count: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadBundleImportParams {
+    pub bundle_path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadBundleImportResponse {
+    /// Id of the imported thread; pass it to `thread/resume` to continue it.
+    pub thread_id: String,
+    pub rollout_path: PathBuf,
+    pub exec_log_count: u32,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -494,6 +784,61 @@ pub struct ThreadListResponse {
     pub next_cursor: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchParams {
+    /// Terms to search for; threads must contain every term.
+    pub query: String,
+    /// Maximum number of threads to return; defaults to 20.
+    pub limit: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchResponse {
+    /// Matching threads, best match first.
+    pub data: Vec<ThreadSearchResult>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchResult {
+    pub thread_id: String,
+    /// [UNSTABLE] Path to the thread on disk.
+    pub path: PathBuf,
+    pub cwd: Option<PathBuf>,
+    /// When the thread was started, as recorded in the rollout.
+    pub started_at: Option<String>,
+    /// Best matching items in the thread, best match first.
+    pub matches: Vec<ThreadSearchMatch>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSearchMatch {
+    pub kind: ThreadSearchMatchKind,
+    pub timestamp: String,
+    /// Excerpt around the match; matched terms are wrapped in `**`.
+    pub snippet: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum ThreadSearchMatchKind {
+    UserMessage,
+    AgentMessage,
+    Reasoning,
+    Command,
+    Patch,
+    ToolCall,
+    WebSearch,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -509,48 +854,738 @@ pub struct ThreadCompactResponse {}
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct Thread {
-    pub id: String,
-    /// Usually the first user message in the thread, if available.
-    pub preview: String,
-    pub model_provider: String,
-    /// Unix timestamp (in seconds) when the thread was created.
-    pub created_at: i64,
-    /// [UNSTABLE] Path to the thread on disk.
-    pub path: PathBuf,
-    /// Only populated on a `thread/resume` response.
-    /// For all other responses and notifications returning a Thread,
-    /// the turns field will be an empty list.
-    pub turns: Vec<Turn>,
+pub struct ThreadSetProfileParams {
+    pub thread_id: String,
+    /// Name of a profile under `[profiles]` in `config.toml`.
+    pub profile: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct AccountUpdatedNotification {
-    pub auth_mode: Option<AuthMode>,
+pub struct ThreadSetProfileResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSetTerminalSizeParams {
+    pub thread_id: String,
+    pub rows: i32,
+    pub cols: i32,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct Turn {
-    pub id: String,
-    /// Only populated on a `thread/resume` response.
-    /// For all other responses and notifications returning a Turn,
-    /// the items field will be an empty list.
-    pub items: Vec<ThreadItem>,
-    #[serde(flatten)]
-    pub status: TurnStatus,
+pub struct ThreadSetTerminalSizeResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadRenameParams {
+    pub thread_id: String,
+    pub title: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
-pub struct TurnError {
-    pub message: String,
+pub struct ThreadRenameResponse {}
+
+// Execution APIs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ExecutionGetOutputParams {
+    pub thread_id: String,
+    /// Id of the command execution item whose output should be returned.
+    pub call_id: String,
+}
+
+/// Complete, untruncated output of a command execution, read from the archive
+/// written when the `exec_output_archive` feature is enabled.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ExecutionGetOutputResponse {
+    pub stdout: String,
+    pub stderr: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ExecutionAssessParams {
+    /// Command as the model would pass it to the shell tool, e.g.
+    /// `["bash", "-lc", "make build"]`.
+    pub command: Vec<String>,
+    pub cwd: Option<String>,
+    pub approval_policy: Option<AskForApproval>,
+    pub sandbox: Option<SandboxMode>,
+    /// Config overrides, applied the same way as in `thread/start`.
+    pub config: Option<HashMap<String, JsonValue>>,
+    #[serde(default)]
+    pub with_escalated_permissions: bool,
+}
+
+/// What would happen if the command were run with the given settings. Nothing
+/// is executed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ExecutionAssessResponse {
+    /// Strictest decision of the execpolicy rules matching the command, or
+    /// null when no rule matched.
+    pub policy_decision: Option<ExecPolicyDecision>,
+    /// Whether a remembered approval rule covers the command.
+    pub approved_by_rule: bool,
+    pub approval: ExecutionApproval,
+    pub sandbox: ExecutionSandbox,
+    /// Inherited environment variables the command would not see.
+    pub scrubbed_env_vars: Vec<String>,
+    /// Settings the assessment used, after applying the overrides.
+    pub approval_policy: AskForApproval,
+    pub sandbox_policy: SandboxPolicy,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum ExecPolicyDecision {
+    Allow,
+    Prompt,
+    Forbidden,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[ts(tag = "type")]
+#[ts(export_to = "v2/")]
+pub enum ExecutionApproval {
+    /// The command would run without asking.
+    Skip,
+    /// The user would be asked to approve the command.
+    NeedsApproval { reason: Option<String> },
+    /// The command would be refused.
+    Forbidden { reason: String },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum ExecutionSandbox {
+    None,
+    MacosSeatbelt,
+    LinuxSeccomp,
+    WindowsRestrictedToken,
+}
+
+// Background task APIs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct BackgroundTaskReadParams {
+    pub thread_id: String,
+    /// Id reported in the `background_task_update` event, e.g. `bg-1`.
+    pub task_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct BackgroundTaskReadResponse {
+    pub command: String,
+    pub status: BackgroundTaskStatus,
+    pub exit_code: Option<i32>,
+    /// The most recent output, stdout and stderr interleaved.
+    pub output: String,
+    /// Bytes dropped from the start of `output` to keep the buffer bounded.
+    #[ts(type = "number")]
+    pub dropped_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct BackgroundTaskTerminateParams {
+    pub thread_id: String,
+    pub task_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct BackgroundTaskTerminateResponse {}
+
+// Workspace instruction APIs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WorkspaceInstruction {
+    pub id: String,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WorkspaceInstructionListParams {
+    /// Any directory inside the project; instructions are stored per project root.
+    pub cwd: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WorkspaceInstructionListResponse {
+    pub data: Vec<WorkspaceInstruction>,
+}
+
+/// Saves an instruction, typically after the user confirms a
+/// `workspace_instruction_proposed` event.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WorkspaceInstructionAddParams {
+    pub cwd: PathBuf,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WorkspaceInstructionAddResponse {
+    pub instruction: WorkspaceInstruction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WorkspaceInstructionUpdateParams {
+    pub cwd: PathBuf,
+    pub id: String,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WorkspaceInstructionUpdateResponse {
+    pub instruction: WorkspaceInstruction,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WorkspaceInstructionRemoveParams {
+    pub cwd: PathBuf,
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WorkspaceInstructionRemoveResponse {}
+
+// Pinned context APIs
+/// Pins a file or directory as context for every later request in the
+/// thread. Pinned files are re-read each time, so edits are picked up.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextAddParams {
+    pub thread_id: String,
+    /// Absolute path to an existing file or directory.
+    pub path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextAddResponse {
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextRemoveParams {
+    pub thread_id: String,
+    pub path: PathBuf,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextRemoveResponse {
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextListParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadContextListResponse {
+    pub paths: Vec<PathBuf>,
+}
+
+// Thread usage
+v2_enum_from_core!(
+    pub enum ModelTask from codex_protocol::protocol::ModelTask {
+        Main, Summarization, RiskAssessment, CommitMessage, ThreadTitle
+    }
+);
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUsageParams {
+    pub thread_id: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadUsageResponse {
+    /// Totals per source, largest first. A request's usage is shared evenly
+    /// among the items it produced.
+    pub by_source: Vec<SourceTokenUsage>,
+    /// Total usage of requests that retried a failed attempt.
+    pub retries: TokenCounts,
+    /// Every model request that reported usage, oldest first.
+    pub requests: Vec<RequestTokenUsage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SourceTokenUsage {
+    /// Name of the tool called, or `message`, `reasoning`, `web_search`,
+    /// `compaction`, or `other`.
+    pub source: String,
+    pub usage: TokenCounts,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct RequestTokenUsage {
+    pub turn_id: String,
+    pub task: ModelTask,
+    pub model: String,
+    /// Failed attempts before this request went through; non-zero for
+    /// retries.
+    pub attempt: u32,
+    pub items: Vec<UsageAttribution>,
+    pub usage: TokenCounts,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct UsageAttribution {
+    /// Call id for tool calls, otherwise the item id when known.
+    pub id: Option<String>,
+    pub source: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TokenCounts {
+    pub input_tokens: i64,
+    pub cached_input_tokens: i64,
+    pub output_tokens: i64,
+    pub reasoning_output_tokens: i64,
+    pub total_tokens: i64,
+}
+
+impl From<CoreTokenUsage> for TokenCounts {
+    fn from(value: CoreTokenUsage) -> Self {
+        Self {
+            input_tokens: value.input_tokens,
+            cached_input_tokens: value.cached_input_tokens,
+            output_tokens: value.output_tokens,
+            reasoning_output_tokens: value.reasoning_output_tokens,
+            total_tokens: value.total_tokens,
+        }
+    }
+}
+
+impl From<CoreUsageAttribution> for UsageAttribution {
+    fn from(value: CoreUsageAttribution) -> Self {
+        Self {
+            id: value.id,
+            source: value.source,
+        }
+    }
+}
+
+impl From<CoreRequestTokenUsage> for RequestTokenUsage {
+    fn from(value: CoreRequestTokenUsage) -> Self {
+        Self {
+            turn_id: value.turn_id,
+            task: value.task.into(),
+            model: value.model,
+            attempt: value.attempt,
+            items: value.items.into_iter().map(Into::into).collect(),
+            usage: value.usage.into(),
+        }
+    }
+}
+
+// Thread template APIs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadTemplateListParams {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadTemplateListResponse {
+    /// Every template, sorted by name, including invalid ones.
+    pub data: Vec<ThreadTemplate>,
+}
+
+/// A template from `[templates.<name>]` in config.toml or
+/// `$CODEX_HOME/templates/<name>.toml`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadTemplate {
+    pub name: String,
+    /// File the template was read from; `None` when defined in config.toml.
+    pub path: Option<PathBuf>,
+    pub description: Option<String>,
+    pub model: Option<String>,
+    pub model_provider: Option<String>,
+    pub reasoning_effort: Option<ReasoningEffort>,
+    pub approval_policy: Option<AskForApproval>,
+    pub sandbox: Option<SandboxMode>,
+    pub developer_instructions: Option<String>,
+    /// Relative paths are resolved against the new thread's `cwd`.
+    pub pinned_context: Vec<PathBuf>,
+    pub enabled_tools: Option<Vec<String>>,
+    pub disabled_tools: Option<Vec<String>>,
+    /// Why the template cannot be used; empty when it can.
+    pub errors: Vec<String>,
+}
+
+/// Starts a thread from a template. Fields set here take precedence over
+/// the template's.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadCreateFromTemplateParams {
+    pub template: String,
+    pub cwd: Option<String>,
+    pub model: Option<String>,
+    pub approval_policy: Option<AskForApproval>,
+    pub sandbox: Option<SandboxMode>,
+    pub config: Option<HashMap<String, JsonValue>>,
+}
+
+// Custom command APIs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CommandListParams {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CommandListResponse {
+    pub data: Vec<CustomCommand>,
+}
+
+/// A command defined in `$CODEX_HOME/commands/<name>.md`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CustomCommand {
+    pub name: String,
+    pub path: PathBuf,
+    pub description: Option<String>,
+    /// Placeholders that `command/run` must supply, e.g. `VERSION`.
+    pub args: Vec<String>,
+    pub model: Option<String>,
+    pub approval_policy: Option<AskForApproval>,
+    pub template: String,
+}
+
+impl From<codex_protocol::custom_commands::CustomCommand> for CustomCommand {
+    fn from(value: codex_protocol::custom_commands::CustomCommand) -> Self {
+        Self {
+            name: value.name,
+            path: value.path,
+            description: value.description,
+            args: value.args,
+            model: value.model,
+            approval_policy: value.approval_policy.map(AskForApproval::from),
+            template: value.template,
+        }
+    }
+}
+
+/// Runs a custom command in a thread. The turn it starts streams the usual
+/// `turn/*` and `item/*` notifications; an unknown command or missing args
+/// surface as an `error` notification.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CommandRunParams {
+    pub thread_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub args: HashMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CommandRunResponse {}
+
+// Experiment APIs
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ExperimentStatsParams {
+    /// Name of the experiment under `[experiments]` in config.toml.
+    pub experiment: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ExperimentStatsResponse {
+    /// Per-variant outcome metrics, sorted by variant label. Variants without
+    /// recorded turns are omitted.
+    pub variants: Vec<ExperimentVariantStats>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ExperimentVariantStats {
+    pub variant: String,
+    pub conversations: i64,
+    pub turns: i64,
+    /// Turns that ended with a final agent message rather than an abort.
+    pub successful_turns: i64,
+    pub success_rate: f64,
+    /// Tokens consumed across all conversations assigned to the variant.
+    pub total_tokens: i64,
+    pub average_turns_per_conversation: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigValidateParams {
+    /// Contents to validate instead of `config.toml` in the Codex home, e.g.
+    /// unsaved edits in an editor.
+    pub contents: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigValidateResponse {
+    /// Problems found, in document order. Empty when the config is valid.
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum ConfigDiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ConfigDiagnostic {
+    pub severity: ConfigDiagnosticSeverity,
+    /// Dotted path of the offending key, e.g. `profiles.work.sandbox_mode`.
+    pub path: String,
+    pub message: String,
+    /// 1-based line.
+    pub line: u32,
+    /// 1-based column.
+    pub column: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct Thread {
+    pub id: String,
+    /// Usually the first user message in the thread, if available.
+    pub preview: String,
+    /// Set by `thread/rename`, or generated after the first exchange when
+    /// the `thread_titles` feature is enabled.
+    pub title: Option<String>,
+    pub model_provider: String,
+    /// Unix timestamp (in seconds) when the thread was created.
+    pub created_at: i64,
+    /// [UNSTABLE] Path to the thread on disk.
+    pub path: PathBuf,
+    /// Only populated on `thread/resume` and `thread/fork` responses.
+    /// For all other responses and notifications returning a Thread,
+    /// the turns field will be an empty list.
+    pub turns: Vec<Turn>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct AccountUpdatedNotification {
+    pub auth_mode: Option<AuthMode>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct Turn {
+    pub id: String,
+    /// Only populated on a `thread/resume` response.
+    /// For all other responses and notifications returning a Turn,
+    /// the items field will be an empty list.
+    pub items: Vec<ThreadItem>,
+    #[serde(flatten)]
+    pub status: TurnStatus,
+    /// Commit of the turn's changes, set on `turn/completed` when
+    /// `auto_commit` is enabled and the turn changed files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<TurnCommit>,
+    /// Diffstat of the files the turn changed, set on `turn/completed` when
+    /// the `ghost_commit` feature is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff: Option<TurnDiffSummary>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnCommit {
+    pub sha: String,
+    pub branch: String,
+    pub message: String,
+}
+
+impl From<CoreTurnCommit> for TurnCommit {
+    fn from(value: CoreTurnCommit) -> Self {
+        Self {
+            sha: value.commit,
+            branch: value.branch,
+            message: value.message,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnDiffSummary {
+    pub files_changed: usize,
+    pub insertions: u64,
+    pub deletions: u64,
+    pub files: Vec<TurnDiffFile>,
+}
+
+/// Lines added and removed in one file. Binary files have no line counts.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnDiffFile {
+    /// Path relative to the repository root.
+    pub path: PathBuf,
+    pub insertions: u64,
+    pub deletions: u64,
+}
+
+impl From<CoreTurnDiffSummary> for TurnDiffSummary {
+    fn from(value: CoreTurnDiffSummary) -> Self {
+        Self {
+            files_changed: value.files_changed,
+            insertions: value.insertions,
+            deletions: value.deletions,
+            files: value
+                .files
+                .into_iter()
+                .map(|file| TurnDiffFile {
+                    path: file.path,
+                    insertions: file.insertions,
+                    deletions: file.deletions,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnError {
+    pub message: String,
+    /// Structured classification of the error, when Codex reported one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<ErrorInfo>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ErrorInfo {
+    pub category: ErrorCategory,
+    pub code: ErrorCode,
+    pub retryable: bool,
+    pub origin: ErrorOrigin,
+}
+
+impl From<codex_protocol::protocol::CodexErrorInfo> for ErrorInfo {
+    fn from(value: codex_protocol::protocol::CodexErrorInfo) -> Self {
+        Self {
+            category: value.category.into(),
+            code: value.code.into(),
+            retryable: value.retryable,
+            origin: value.origin.into(),
+        }
+    }
 }
 
+v2_enum_from_core!(
+    pub enum ErrorCategory from codex_protocol::protocol::ErrorCategory {
+        Auth, Usage, ContextWindow, Provider, InvalidRequest, Sandbox, Config, Interrupted,
+        Internal
+    }
+);
+
+v2_enum_from_core!(
+    pub enum ErrorCode from codex_protocol::protocol::ErrorCode {
+        Unauthorized, RefreshTokenFailed, UsageLimitReached, UsageNotIncluded, QuotaExceeded,
+        TokenBudgetExhausted, ContextWindowExceeded, StreamDisconnected, ConnectionFailed,
+        ServerOverloaded, RetryLimitReached, UnexpectedStatus, BadRequest, UnsupportedOperation,
+        ConversationNotFound, SandboxDenied, SandboxTimeout, SandboxUnavailable, MissingEnvVar,
+        InvalidConfig, McpServerFailed, Interrupted, Internal
+    }
+);
+
+v2_enum_from_core!(
+    pub enum ErrorOrigin from codex_protocol::protocol::ErrorOrigin {
+        ModelProvider, Sandbox, McpServer, Git, Codex
+    }
+);
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(tag = "status", rename_all = "camelCase")]
 #[ts(tag = "status", export_to = "v2/")]
@@ -580,6 +1615,10 @@ pub struct TurnStartParams {
     pub effort: Option<ReasoningEffort>,
     /// Override the reasoning summary for this turn and subsequent turns.
     pub summary: Option<ReasoningSummary>,
+    /// Environment variables for the commands of this turn and subsequent
+    /// turns, on top of those given to `thread/start`. Replaces the ones
+    /// from an earlier `turn/start`; pass an empty map to clear them.
+    pub env: Option<HashMap<String, String>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -621,6 +1660,28 @@ pub enum ReviewTarget {
     Custom { instructions: String },
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub enum ReviewSeverity {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl ReviewSeverity {
+    /// Map the reviewer's `[P0]`–`[P3]` priority to a severity.
+    pub fn from_priority(priority: i32) -> Self {
+        match priority {
+            i32::MIN..=0 => ReviewSeverity::Critical,
+            1 => ReviewSeverity::High,
+            2 => ReviewSeverity::Medium,
+            _ => ReviewSeverity::Low,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -647,9 +1708,19 @@ pub struct TurnInterruptResponse {}
 #[ts(tag = "type")]
 #[ts(export_to = "v2/")]
 pub enum UserInput {
-    Text { text: String },
-    Image { url: String },
-    LocalImage { path: PathBuf },
+    Text {
+        text: String,
+    },
+    Image {
+        url: String,
+    },
+    LocalImage {
+        path: PathBuf,
+    },
+    /// Audio file transcribed to text before the turn starts.
+    LocalAudio {
+        path: PathBuf,
+    },
 }
 
 impl UserInput {
@@ -658,6 +1729,7 @@ impl UserInput {
             UserInput::Text { text } => CoreUserInput::Text { text },
             UserInput::Image { url } => CoreUserInput::Image { image_url: url },
             UserInput::LocalImage { path } => CoreUserInput::LocalImage { path },
+            UserInput::LocalAudio { path } => CoreUserInput::LocalAudio { path },
         }
     }
 }
@@ -668,6 +1740,7 @@ impl From<CoreUserInput> for UserInput {
             CoreUserInput::Text { text } => UserInput::Text { text },
             CoreUserInput::Image { image_url } => UserInput::Image { url: image_url },
             CoreUserInput::LocalImage { path } => UserInput::LocalImage { path },
+            CoreUserInput::LocalAudio { path } => UserInput::LocalAudio { path },
             _ => unreachable!("unsupported user input variant"),
         }
     }
@@ -697,6 +1770,9 @@ pub enum ThreadItem {
     #[ts(rename_all = "camelCase")]
     CommandExecution {
         id: String,
+        /// The tool call that ran the command. A call that is run again
+        /// produces a new item with the same `callId`.
+        call_id: String,
         /// The command to be executed.
         command: String,
         /// The command's working directory.
@@ -712,6 +1788,10 @@ pub enum ThreadItem {
         exit_code: Option<i32>,
         /// The duration of the command execution in milliseconds.
         duration_ms: Option<i64>,
+        /// What the server's output policy left out of the streamed deltas or
+        /// of `aggregatedOutput`, if anything.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output_truncation: Option<CommandOutputTruncation>,
     },
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
@@ -736,6 +1816,9 @@ pub enum ThreadItem {
     WebSearch { id: String, query: String },
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
+    FileRead { id: String, path: PathBuf },
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
     TodoList { id: String, items: Vec<TodoItem> },
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
@@ -743,6 +1826,93 @@ pub enum ThreadItem {
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
     CodeReview { id: String, review: String },
+    /// One structured finding of a completed code review, for rendering
+    /// inline annotations.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    ReviewFinding {
+        id: String,
+        /// Id of the `codeReview` item the finding belongs to.
+        review_id: String,
+        title: String,
+        /// Markdown explanation of the issue.
+        body: String,
+        path: PathBuf,
+        /// First line of the finding, 1-based.
+        line_start: u32,
+        /// Last line of the finding, inclusive.
+        line_end: u32,
+        severity: ReviewSeverity,
+        confidence_score: f32,
+        /// Replacement text for `lineStart..=lineEnd`, when the fix is small
+        /// enough to suggest inline.
+        suggestion: Option<String>,
+    },
+    /// A pull request (GitHub) or merge request (GitLab) opened for the
+    /// current branch.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    PullRequest {
+        id: String,
+        provider: ForgeProvider,
+        url: String,
+        /// Pull request number, or the merge request's project-scoped iid.
+        number: i64,
+        title: String,
+        /// Branch the request merges from.
+        head: String,
+        /// Branch the request merges into.
+        base: String,
+    },
+    /// A tool call refused by the sandbox policy, e.g. a file edit in audit
+    /// mode.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    BlockedAction {
+        id: String,
+        action: BlockedAction,
+        reason: String,
+    },
+}
+
+impl ThreadItem {
+    pub fn id(&self) -> &str {
+        match self {
+            ThreadItem::UserMessage { id, .. }
+            | ThreadItem::AgentMessage { id, .. }
+            | ThreadItem::Reasoning { id, .. }
+            | ThreadItem::CommandExecution { id, .. }
+            | ThreadItem::FileChange { id, .. }
+            | ThreadItem::McpToolCall { id, .. }
+            | ThreadItem::WebSearch { id, .. }
+            | ThreadItem::FileRead { id, .. }
+            | ThreadItem::TodoList { id, .. }
+            | ThreadItem::ImageView { id, .. }
+            | ThreadItem::CodeReview { id, .. }
+            | ThreadItem::ReviewFinding { id, .. }
+            | ThreadItem::PullRequest { id, .. }
+            | ThreadItem::BlockedAction { id, .. } => id,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CommandOutputTruncation {
+    /// Bytes dropped from the middle of `aggregatedOutput`.
+    pub omitted_bytes: u64,
+    /// Bytes of output that were not sent as output deltas.
+    pub unstreamed_bytes: u64,
+}
+
+impl From<CoreCommandOutputTruncation> for CommandOutputTruncation {
+    fn from(value: CoreCommandOutputTruncation) -> Self {
+        Self {
+            omitted_bytes: value.omitted_bytes,
+            unstreamed_bytes: value.unstreamed_bytes,
+        }
+    }
 }
 
 impl From<CoreTurnItem> for ThreadItem {
@@ -771,19 +1941,49 @@ impl From<CoreTurnItem> for ThreadItem {
                 id: search.id,
                 query: search.query,
             },
+            CoreTurnItem::CommandExecution(command) => ThreadItem::CommandExecution {
+                id: command.id,
+                call_id: command.call_id,
+                command: shlex::try_join(command.command.iter().map(String::as_str))
+                    .unwrap_or_else(|_| command.command.join(" ")),
+                cwd: command.cwd,
+                status: command.status.into(),
+                command_actions: command
+                    .parsed_cmd
+                    .into_iter()
+                    .map(CommandAction::from)
+                    .collect(),
+                aggregated_output: command.aggregated_output,
+                exit_code: command.exit_code,
+                duration_ms: command.duration_ms,
+                output_truncation: command.output_truncation.map(Into::into),
+            },
+            CoreTurnItem::FileRead(read) => ThreadItem::FileRead {
+                id: read.id,
+                path: read.path,
+            },
+            CoreTurnItem::FileChange(change) => {
+                let mut changes: Vec<FileUpdateChange> = change
+                    .changes
+                    .into_iter()
+                    .map(|(path, change)| FileUpdateChange::from_core(path, change))
+                    .collect();
+                changes.sort_by(|a, b| a.path.cmp(&b.path));
+                ThreadItem::FileChange {
+                    id: change.id,
+                    changes,
+                    status: change.status.into(),
+                }
+            }
+            CoreTurnItem::BlockedAction(blocked) => ThreadItem::BlockedAction {
+                id: blocked.id,
+                action: blocked.action.into(),
+                reason: blocked.reason,
+            },
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
-#[serde(rename_all = "camelCase")]
-#[ts(export_to = "v2/")]
-pub enum CommandExecutionStatus {
-    InProgress,
-    Completed,
-    Failed,
-}
-
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -793,6 +1993,21 @@ pub struct FileUpdateChange {
     pub diff: String,
 }
 
+impl FileUpdateChange {
+    fn from_core(path: PathBuf, change: CoreFileChange) -> Self {
+        let (kind, diff) = match change {
+            CoreFileChange::Add { content } => (PatchChangeKind::Add, content),
+            CoreFileChange::Delete { content } => (PatchChangeKind::Delete, content),
+            CoreFileChange::Update { unified_diff, .. } => (PatchChangeKind::Update, unified_diff),
+        };
+        Self {
+            path: path.to_string_lossy().into_owned(),
+            kind,
+            diff,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -802,14 +2017,6 @@ pub enum PatchChangeKind {
     Update,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
-#[serde(rename_all = "camelCase")]
-#[ts(export_to = "v2/")]
-pub enum PatchApplyStatus {
-    Completed,
-    Failed,
-}
-
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -852,6 +2059,70 @@ pub struct ThreadStartedNotification {
     pub thread: Thread,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadCompactedNotification {
+    pub thread_id: String,
+    pub trigger: CompactionTrigger,
+    /// Tokens in the context window before compaction, if known.
+    pub tokens_before: Option<i64>,
+    /// Estimated tokens in the context window after compaction.
+    pub tokens_after: Option<i64>,
+}
+
+/// The thread switched profiles after `thread/setProfile`; subsequent turns
+/// use these settings.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadProfileChangedNotification {
+    pub thread_id: String,
+    pub profile: String,
+    pub model: String,
+    pub model_provider: String,
+    pub reasoning_effort: Option<ReasoningEffort>,
+    pub approval_policy: AskForApproval,
+    pub sandbox: SandboxPolicy,
+    /// Settings whose effective value changed.
+    pub changes: Vec<SettingChange>,
+}
+
+/// The thread's title changed after `thread/rename` or was generated.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadMetadataChangedNotification {
+    pub thread_id: String,
+    pub title: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct SettingChange {
+    /// Setting name as written in `config.toml`, e.g. `sandbox_mode`.
+    pub setting: String,
+    pub previous: String,
+    pub current: String,
+}
+
+/// A sub-agent thread spawned by a turn of `parentThreadId` started or
+/// finished.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ThreadSubAgentUpdatedNotification {
+    pub parent_thread_id: String,
+    pub thread_id: String,
+    /// Id of the `spawn_agent` tool call that started the sub-agent.
+    pub call_id: String,
+    pub model: String,
+    pub status: SubAgentStatus,
+    /// The sub-agent's final message once it completed, or why it failed.
+    pub message: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -866,6 +2137,8 @@ pub struct Usage {
     pub input_tokens: i32,
     pub cached_input_tokens: i32,
     pub output_tokens: i32,
+    /// Estimated cost in USD; present only when a price is configured for the model.
+    pub cost_usd: Option<f64>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -873,6 +2146,59 @@ pub struct Usage {
 #[ts(export_to = "v2/")]
 pub struct TurnCompletedNotification {
     pub turn: Turn,
+    /// Cumulative thread usage as of the end of this turn.
+    pub usage: Option<Usage>,
+}
+
+/// The turn waits for a slot because the server's concurrent turn limits are
+/// reached; `turn/started` follows once it runs.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnQueuedNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    /// 1-based position in the server-wide queue when the turn was queued.
+    pub position: u32,
+}
+
+/// Sent periodically while a turn is running, so clients can tell a slow turn
+/// from a stuck one.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct TurnHeartbeatNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    /// Milliseconds since the turn started.
+    pub elapsed_ms: u64,
+    pub activity: TurnActivity,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[ts(tag = "type")]
+#[ts(export_to = "v2/")]
+pub enum TurnActivity {
+    WaitingOnModel,
+    RunningCommand { command: String },
+    RunningTool { server: String, tool: String },
+    ApplyingPatch,
+    WaitingOnApproval,
+    WaitingOnUser,
+}
+
+impl From<CoreTurnActivity> for TurnActivity {
+    fn from(value: CoreTurnActivity) -> Self {
+        match value {
+            CoreTurnActivity::WaitingOnModel => Self::WaitingOnModel,
+            CoreTurnActivity::RunningCommand { command } => Self::RunningCommand { command },
+            CoreTurnActivity::RunningTool { server, tool } => Self::RunningTool { server, tool },
+            CoreTurnActivity::ApplyingPatch => Self::ApplyingPatch,
+            CoreTurnActivity::WaitingOnApproval => Self::WaitingOnApproval,
+            CoreTurnActivity::WaitingOnUser => Self::WaitingOnUser,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -930,6 +2256,10 @@ pub struct ReasoningTextDeltaNotification {
 pub struct CommandExecutionOutputDeltaNotification {
     pub item_id: String,
     pub delta: String,
+    /// Base64 of the chunk's bytes when they are not valid UTF-8, such as
+    /// binary output; `delta` then holds a lossy rendering of them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -937,6 +2267,87 @@ pub struct CommandExecutionOutputDeltaNotification {
 #[ts(export_to = "v2/")]
 pub struct McpToolCallProgressNotification {
     pub item_id: String,
+    /// Progress so far; increases with each update.
+    pub progress: f64,
+    /// Total amount of work, when the server knows it.
+    pub total: Option<f64>,
+    pub message: Option<String>,
+}
+
+/// Diagnostics language servers reported for the files a `fileChange` item
+/// changed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct FileChangeDiagnosticsNotification {
+    pub thread_id: String,
+    pub turn_id: String,
+    pub item_id: String,
+    pub files: Vec<FileDiagnostics>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct FileDiagnostics {
+    pub path: PathBuf,
+    /// Every diagnostic the servers currently report for the file; empty
+    /// when it has none, so stale markers can be cleared.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Lines and columns are 1-based; columns count UTF-16 code units.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    pub line: u32,
+    pub column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+    pub message: String,
+    pub source: Option<String>,
+    pub code: Option<String>,
+}
+
+v2_enum_from_core!(
+    pub enum DiagnosticSeverity from codex_protocol::protocol::DiagnosticSeverity {
+        Error, Warning, Information, Hint
+    }
+);
+
+impl From<CoreFileDiagnostics> for FileDiagnostics {
+    fn from(value: CoreFileDiagnostics) -> Self {
+        Self {
+            path: value.path,
+            diagnostics: value
+                .diagnostics
+                .into_iter()
+                .map(|diagnostic| Diagnostic {
+                    severity: diagnostic.severity.into(),
+                    line: diagnostic.line,
+                    column: diagnostic.column,
+                    end_line: diagnostic.end_line,
+                    end_column: diagnostic.end_column,
+                    message: diagnostic.message,
+                    source: diagnostic.source,
+                    code: diagnostic.code,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Sent the first time a client calls a deprecated v1 method.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct DeprecationNoticeNotification {
+    /// The deprecated method, e.g. `sendUserMessage`.
+    pub method: String,
+    /// The v2 method to use instead, if there is one yet.
+    pub replacement: Option<String>,
     pub message: String,
 }
 
@@ -958,7 +2369,9 @@ pub struct CommandExecutionRequestApprovalParams {
     pub item_id: String,
     /// Optional explanatory reason (e.g. request for network access).
     pub reason: Option<String>,
-    /// Optional model-provided risk assessment describing the blocked command.
+    /// Optional risk assessment describing the blocked command, from the model
+    /// when enabled and from local heuristics and execpolicy otherwise. Unset
+    /// when nothing risky was found.
     pub risk: Option<SandboxCommandAssessment>,
 }
 
@@ -982,6 +2395,100 @@ pub struct CommandExecutionRequestApprovalResponse {
     pub accept_settings: Option<CommandExecutionRequestAcceptSettings>,
 }
 
+/// Sent when a command or patch targets paths outside the sandbox's writable
+/// roots, asking whether `root` may become writable.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WriteScopeRequestApprovalParams {
+    pub thread_id: String,
+    pub turn_id: String,
+    /// The command execution or file change item that needs the root.
+    pub item_id: String,
+    /// The directory that would become writable.
+    pub root: PathBuf,
+    /// The paths outside the writable roots that the item targets.
+    pub paths: Vec<PathBuf>,
+    /// The blocked command, when the item is a command execution.
+    pub command: Option<Vec<String>>,
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct WriteScopeRequestApprovalResponse {
+    pub decision: WriteScopeDecision,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerElicitationRequestParams {
+    pub thread_id: String,
+    pub server_name: String,
+    pub message: String,
+    /// Flat JSON Schema object (`properties`, `required`) describing the
+    /// fields the server wants.
+    pub requested_schema: JsonValue,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerElicitationRequestResponse {
+    pub decision: ApprovalDecision,
+    /// Answer matching `requestedSchema`. Only read when the decision is
+    /// `accept`.
+    #[serde(default)]
+    pub content: Option<JsonValue>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct UserInputRequestedParams {
+    pub thread_id: String,
+    pub turn_id: String,
+    /// Id of the `ask_user` tool call waiting on the answer.
+    pub call_id: String,
+    pub question: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct UserInputRequestedResponse {
+    /// The user's answer, or `null` if they did not answer.
+    #[serde(default)]
+    pub answer: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerAuthReadParams {
+    /// Name of the MCP server as configured under `mcp_servers`.
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct McpServerAuthReadResponse {
+    /// Scopes configured for the server and requested on login.
+    pub requested_scopes: Option<Vec<String>>,
+    /// Whether Codex holds OAuth credentials for the server.
+    pub logged_in: bool,
+    /// Scopes granted to the stored token, when the authorization server
+    /// reports them.
+    pub granted_scopes: Option<Vec<String>>,
+    /// Unverified claims of the access token when it is a JWT.
+    pub claims: Option<JsonValue>,
+    /// Unix timestamp (in seconds) when the access token expires.
+    pub expires_at: Option<i64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -1057,11 +2564,27 @@ pub struct AccountLoginCompletedNotification {
     pub error: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct AccountLoginDeviceCodeNotification {
+    pub login_id: String,
+    /// Page where the user enters `userCode`, on any device with a browser.
+    pub verification_url: String,
+    pub user_code: String,
+    /// Seconds until the code expires.
+    pub expires_in_secs: u64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use codex_protocol::items::AgentMessageContent;
     use codex_protocol::items::AgentMessageItem;
+    use codex_protocol::items::CommandExecutionItem;
+    use codex_protocol::items::FileChangeItem;
+    use codex_protocol::items::FileChangeStatus;
+    use codex_protocol::items::FileReadItem;
     use codex_protocol::items::ReasoningItem;
     use codex_protocol::items::TurnItem;
     use codex_protocol::items::UserMessageItem;
@@ -1152,5 +2675,93 @@ mod tests {
                 query: "docs".to_string(),
             }
         );
+
+        let read_item = TurnItem::FileRead(FileReadItem {
+            id: "read-1".to_string(),
+            path: PathBuf::from("/repo/src/lib.rs"),
+        });
+
+        assert_eq!(
+            ThreadItem::from(read_item),
+            ThreadItem::FileRead {
+                id: "read-1".to_string(),
+                path: PathBuf::from("/repo/src/lib.rs"),
+            }
+        );
+
+        let command_item = TurnItem::CommandExecution(CommandExecutionItem {
+            id: "item-1".to_string(),
+            call_id: "call-1".to_string(),
+            command: vec!["echo".to_string(), "hello world".to_string()],
+            cwd: PathBuf::from("/repo"),
+            parsed_cmd: Vec::new(),
+            status: codex_protocol::items::CommandExecutionStatus::Failed,
+            aggregated_output: Some("boom".to_string()),
+            exit_code: Some(2),
+            duration_ms: Some(15),
+            output_truncation: Some(codex_protocol::items::CommandOutputTruncation {
+                omitted_bytes: 10,
+                unstreamed_bytes: 0,
+            }),
+        });
+
+        assert_eq!(
+            ThreadItem::from(command_item),
+            ThreadItem::CommandExecution {
+                id: "item-1".to_string(),
+                call_id: "call-1".to_string(),
+                command: "echo 'hello world'".to_string(),
+                cwd: PathBuf::from("/repo"),
+                status: CommandExecutionStatus::Failed,
+                command_actions: Vec::new(),
+                aggregated_output: Some("boom".to_string()),
+                exit_code: Some(2),
+                duration_ms: Some(15),
+                output_truncation: Some(CommandOutputTruncation {
+                    omitted_bytes: 10,
+                    unstreamed_bytes: 0,
+                }),
+            }
+        );
+
+        let change_item = TurnItem::FileChange(FileChangeItem {
+            id: "patch-1".to_string(),
+            changes: HashMap::from([
+                (
+                    PathBuf::from("b.txt"),
+                    CoreFileChange::Update {
+                        unified_diff: "-old\n+new\n".to_string(),
+                        move_path: None,
+                    },
+                ),
+                (
+                    PathBuf::from("a.txt"),
+                    CoreFileChange::Add {
+                        content: "hello\n".to_string(),
+                    },
+                ),
+            ]),
+            status: FileChangeStatus::Completed,
+        });
+
+        assert_eq!(
+            ThreadItem::from(change_item),
+            ThreadItem::FileChange {
+                id: "patch-1".to_string(),
+                changes: vec![
+                    FileUpdateChange {
+                        path: "a.txt".to_string(),
+                        kind: PatchChangeKind::Add,
+                        diff: "hello\n".to_string(),
+                    },
+                    FileUpdateChange {
+                        path: "b.txt".to_string(),
+                        kind: PatchChangeKind::Update,
+                        diff: "-old\n+new\n".to_string(),
+                    },
+                ],
+                status: PatchApplyStatus::Completed,
+            }
+        );
     }
 }
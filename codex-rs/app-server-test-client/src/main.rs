@@ -517,7 +517,12 @@ impl CodexClient {
                     }
                 }
                 ServerNotification::McpToolCallProgress(payload) => {
-                    println!("< MCP tool progress: {}", payload.message);
+                    let total = payload
+                        .total
+                        .map(|total| format!("/{total}"))
+                        .unwrap_or_default();
+                    let message = payload.message.unwrap_or_default();
+                    println!("< MCP tool progress: {}{total} {message}", payload.progress);
                 }
                 _ => {
                     println!("[UNKNOWN SERVER NOTIFICATION] {server_notification:?}");
@@ -1,51 +1,78 @@
 use crate::codex_message_processor::ApiVersion;
 use crate::codex_message_processor::PendingInterrupts;
+use crate::codex_message_processor::PendingPatchContents;
 use crate::codex_message_processor::TurnSummary;
 use crate::codex_message_processor::TurnSummaryStore;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::output_delta::DecodedDelta;
+use crate::output_delta::OutputDecoder;
+use crate::v1_compat;
 use codex_app_server_protocol::AccountRateLimitsUpdatedNotification;
 use codex_app_server_protocol::AgentMessageDeltaNotification;
 use codex_app_server_protocol::ApplyPatchApprovalParams;
 use codex_app_server_protocol::ApplyPatchApprovalResponse;
 use codex_app_server_protocol::ApprovalDecision;
-use codex_app_server_protocol::CommandAction as V2ParsedCommand;
 use codex_app_server_protocol::CommandExecutionOutputDeltaNotification;
 use codex_app_server_protocol::CommandExecutionRequestApprovalParams;
 use codex_app_server_protocol::CommandExecutionRequestApprovalResponse;
-use codex_app_server_protocol::CommandExecutionStatus;
-use codex_app_server_protocol::ExecCommandApprovalParams;
-use codex_app_server_protocol::ExecCommandApprovalResponse;
-use codex_app_server_protocol::InterruptConversationResponse;
+use codex_app_server_protocol::FileChangeDiagnosticsNotification;
+use codex_app_server_protocol::FileDiagnostics;
+use codex_app_server_protocol::GetPatchContentResponse;
 use codex_app_server_protocol::ItemCompletedNotification;
 use codex_app_server_protocol::ItemStartedNotification;
+use codex_app_server_protocol::McpServerElicitationRequestParams;
+use codex_app_server_protocol::McpServerElicitationRequestResponse;
 use codex_app_server_protocol::McpToolCallError;
+use codex_app_server_protocol::McpToolCallProgressNotification;
 use codex_app_server_protocol::McpToolCallResult;
 use codex_app_server_protocol::McpToolCallStatus;
 use codex_app_server_protocol::ReasoningSummaryPartAddedNotification;
 use codex_app_server_protocol::ReasoningSummaryTextDeltaNotification;
 use codex_app_server_protocol::ReasoningTextDeltaNotification;
+use codex_app_server_protocol::ReviewSeverity;
 use codex_app_server_protocol::SandboxCommandAssessment as V2SandboxCommandAssessment;
 use codex_app_server_protocol::ServerNotification;
 use codex_app_server_protocol::ServerRequestPayload;
+use codex_app_server_protocol::SettingChange;
+use codex_app_server_protocol::ThreadCompactedNotification;
 use codex_app_server_protocol::ThreadItem;
+use codex_app_server_protocol::ThreadMetadataChangedNotification;
+use codex_app_server_protocol::ThreadProfileChangedNotification;
+use codex_app_server_protocol::ThreadSubAgentUpdatedNotification;
 use codex_app_server_protocol::Turn;
+use codex_app_server_protocol::TurnCommit;
 use codex_app_server_protocol::TurnCompletedNotification;
+use codex_app_server_protocol::TurnDiffSummary;
 use codex_app_server_protocol::TurnError;
-use codex_app_server_protocol::TurnInterruptResponse;
+use codex_app_server_protocol::TurnHeartbeatNotification;
 use codex_app_server_protocol::TurnStatus;
+use codex_app_server_protocol::Usage;
+use codex_app_server_protocol::UserInputRequestedParams;
+use codex_app_server_protocol::UserInputRequestedResponse;
+use codex_app_server_protocol::WriteScopeRequestApprovalParams;
+use codex_app_server_protocol::WriteScopeRequestApprovalResponse;
 use codex_core::CodexConversation;
-use codex_core::parse_command::shlex_join;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::ElicitationAction;
+use codex_core::protocol::ElicitationRequestEvent;
+use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
-use codex_core::protocol::ExecApprovalRequestEvent;
-use codex_core::protocol::ExecCommandEndEvent;
+use codex_core::protocol::ExecCommandOutputDeltaEvent;
+use codex_core::protocol::ExecOutputStream;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::Op;
+use codex_core::protocol::PatchContentResponseEvent;
 use codex_core::protocol::ReviewDecision;
+use codex_core::protocol::TokenCountEvent;
+use codex_core::protocol::UserInputRequestEvent;
+use codex_core::protocol::WriteScopeApprovalRequestEvent;
+use codex_core::protocol::WriteScopeDecision;
 use codex_core::review_format::format_review_findings_block;
 use codex_protocol::ConversationId;
+use codex_protocol::items::TurnItem as CoreTurnItem;
+use codex_protocol::protocol::ReviewFinding;
 use codex_protocol::protocol::ReviewOutputEvent;
 use std::convert::TryFrom;
 use std::sync::Arc;
@@ -54,25 +81,37 @@ use tracing::error;
 
 type JsonValue = serde_json::Value;
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn apply_bespoke_event_handling(
     event: Event,
     conversation_id: ConversationId,
     conversation: Arc<CodexConversation>,
     outgoing: Arc<OutgoingMessageSender>,
     pending_interrupts: PendingInterrupts,
+    pending_patch_contents: PendingPatchContents,
     turn_summary_store: TurnSummaryStore,
     api_version: ApiVersion,
 ) {
     let Event { id: event_id, msg } = event;
     match msg {
-        EventMsg::TaskComplete(_ev) => {
-            handle_turn_complete(conversation_id, event_id, &outgoing, &turn_summary_store).await;
+        EventMsg::TaskComplete(ev) => {
+            handle_turn_complete(
+                conversation_id,
+                event_id,
+                ev.turn_commit.map(TurnCommit::from),
+                ev.diff_summary.map(TurnDiffSummary::from),
+                &outgoing,
+                &turn_summary_store,
+            )
+            .await;
         }
         EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
             call_id,
             changes,
             reason,
             grant_root,
+            deferred_changes,
+            file_statuses,
         }) => {
             let params = ApplyPatchApprovalParams {
                 conversation_id,
@@ -80,6 +119,8 @@ pub(crate) async fn apply_bespoke_event_handling(
                 file_changes: changes,
                 reason,
                 grant_root,
+                deferred_file_changes: deferred_changes,
+                file_statuses,
             };
             let rx = outgoing
                 .send_request(ServerRequestPayload::ApplyPatchApproval(params))
@@ -88,50 +129,114 @@ pub(crate) async fn apply_bespoke_event_handling(
                 on_patch_approval_response(event_id, rx, conversation).await;
             });
         }
-        EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
-            call_id,
+        EventMsg::PatchContentResponse(PatchContentResponseEvent { change, .. }) => {
+            let request_id = pending_patch_contents.lock().await.remove(&event_id);
+            if let Some(request_id) = request_id {
+                outgoing
+                    .send_response(request_id, GetPatchContentResponse { change })
+                    .await;
+            }
+        }
+        EventMsg::ExecApprovalRequest(request) => {
+            let payload = match api_version {
+                ApiVersion::V1 => ServerRequestPayload::ExecCommandApproval(
+                    v1_compat::exec_approval_params(conversation_id, request),
+                ),
+                ApiVersion::V2 => ServerRequestPayload::CommandExecutionRequestApproval(
+                    CommandExecutionRequestApprovalParams {
+                        thread_id: conversation_id.to_string(),
+                        turn_id: request.turn_id,
+                        item_id: request.item_id,
+                        reason: request.reason,
+                        risk: request.risk.map(V2SandboxCommandAssessment::from),
+                    },
+                ),
+            };
+            let rx = outgoing.send_request(payload).await;
+            tokio::spawn(async move {
+                on_command_execution_request_approval_response(
+                    event_id,
+                    rx,
+                    conversation,
+                    api_version,
+                )
+                .await;
+            });
+        }
+        EventMsg::WriteScopeApprovalRequest(WriteScopeApprovalRequestEvent {
+            call_id: _,
+            item_id,
             turn_id,
+            root,
+            paths,
             command,
-            cwd,
             reason,
-            risk,
-            parsed_cmd,
         }) => match api_version {
             ApiVersion::V1 => {
-                let params = ExecCommandApprovalParams {
-                    conversation_id,
-                    call_id,
+                submit_write_scope_decision(
+                    &conversation,
+                    event_id,
+                    v1_compat::V1_WRITE_SCOPE_DECISION,
+                )
+                .await;
+            }
+            ApiVersion::V2 => {
+                let params = WriteScopeRequestApprovalParams {
+                    thread_id: conversation_id.to_string(),
+                    turn_id,
+                    item_id,
+                    root,
+                    paths,
                     command,
-                    cwd,
                     reason,
-                    risk,
-                    parsed_cmd,
                 };
                 let rx = outgoing
-                    .send_request(ServerRequestPayload::ExecCommandApproval(params))
+                    .send_request(ServerRequestPayload::WriteScopeRequestApproval(params))
                     .await;
                 tokio::spawn(async move {
-                    on_exec_approval_response(event_id, rx, conversation).await;
+                    on_write_scope_request_approval_response(event_id, rx, conversation).await;
                 });
             }
+        },
+        EventMsg::ElicitationRequest(ElicitationRequestEvent {
+            server_name,
+            id,
+            message,
+            requested_schema,
+        }) => {
+            let params = McpServerElicitationRequestParams {
+                thread_id: conversation_id.to_string(),
+                server_name: server_name.clone(),
+                message,
+                requested_schema: serde_json::to_value(requested_schema).unwrap_or(JsonValue::Null),
+            };
+            let rx = outgoing
+                .send_request(ServerRequestPayload::McpServerElicitationRequest(params))
+                .await;
+            tokio::spawn(async move {
+                on_elicitation_response(server_name, id, rx, conversation).await;
+            });
+        }
+        EventMsg::UserInputRequest(UserInputRequestEvent {
+            call_id,
+            turn_id,
+            question,
+        }) => match api_version {
+            ApiVersion::V1 => {
+                submit_user_input_answer(&conversation, call_id, None).await;
+            }
             ApiVersion::V2 => {
-                let params = CommandExecutionRequestApprovalParams {
+                let params = UserInputRequestedParams {
                     thread_id: conversation_id.to_string(),
-                    turn_id: turn_id.clone(),
-                    // Until we migrate the core to be aware of a first class CommandExecutionItem
-                    // and emit the corresponding EventMsg, we repurpose the call_id as the item_id.
-                    item_id: call_id.clone(),
-                    reason,
-                    risk: risk.map(V2SandboxCommandAssessment::from),
+                    turn_id,
+                    call_id: call_id.clone(),
+                    question,
                 };
                 let rx = outgoing
-                    .send_request(ServerRequestPayload::CommandExecutionRequestApproval(
-                        params,
-                    ))
+                    .send_request(ServerRequestPayload::UserInputRequested(params))
                     .await;
                 tokio::spawn(async move {
-                    on_command_execution_request_approval_response(event_id, rx, conversation)
-                        .await;
+                    on_user_input_requested_response(call_id, rx, conversation).await;
                 });
             }
         },
@@ -142,6 +247,32 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .send_server_notification(ServerNotification::ItemStarted(notification))
                 .await;
         }
+        EventMsg::McpToolCallProgress(progress_event) => {
+            let notification = McpToolCallProgressNotification {
+                item_id: progress_event.call_id,
+                progress: progress_event.progress,
+                total: progress_event.total,
+                message: progress_event.message,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::McpToolCallProgress(notification))
+                .await;
+        }
+        EventMsg::PatchDiagnostics(diagnostics_event) => {
+            let notification = FileChangeDiagnosticsNotification {
+                thread_id: conversation_id.to_string(),
+                turn_id: event_id,
+                item_id: diagnostics_event.call_id,
+                files: diagnostics_event
+                    .files
+                    .into_iter()
+                    .map(FileDiagnostics::from)
+                    .collect(),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::FileChangeDiagnostics(notification))
+                .await;
+        }
         EventMsg::McpToolCallEnd(end_event) => {
             let notification = construct_mcp_tool_call_end_notification(end_event).await;
             outgoing
@@ -191,6 +322,7 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .await;
         }
         EventMsg::TokenCount(token_count_event) => {
+            handle_token_count(conversation_id, &token_count_event, &turn_summary_store).await;
             if let Some(rate_limits) = token_count_event.rate_limits {
                 outgoing
                     .send_server_notification(ServerNotification::AccountRateLimitsUpdated(
@@ -201,8 +333,75 @@ pub(crate) async fn apply_bespoke_event_handling(
                     .await;
             }
         }
+        EventMsg::ContextCompacted(compacted_event) => {
+            let notification = ThreadCompactedNotification {
+                thread_id: conversation_id.to_string(),
+                trigger: compacted_event.trigger.into(),
+                tokens_before: compacted_event.tokens_before,
+                tokens_after: compacted_event.tokens_after,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadCompacted(notification))
+                .await;
+        }
+        EventMsg::ProfileChanged(profile_event) => {
+            let notification = ThreadProfileChangedNotification {
+                thread_id: conversation_id.to_string(),
+                profile: profile_event.profile,
+                model: profile_event.model,
+                model_provider: profile_event.model_provider_id,
+                reasoning_effort: profile_event.reasoning_effort,
+                approval_policy: profile_event.approval_policy.into(),
+                sandbox: profile_event.sandbox_policy.into(),
+                changes: profile_event
+                    .changes
+                    .into_iter()
+                    .map(|change| SettingChange {
+                        setting: change.setting,
+                        previous: change.previous,
+                        current: change.current,
+                    })
+                    .collect(),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadProfileChanged(notification))
+                .await;
+        }
+        EventMsg::ThreadMetadataChanged(metadata_event) => {
+            let notification = ThreadMetadataChangedNotification {
+                thread_id: conversation_id.to_string(),
+                title: metadata_event.title,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadMetadataChanged(notification))
+                .await;
+        }
+        EventMsg::TurnHeartbeat(heartbeat_event) => {
+            let notification = TurnHeartbeatNotification {
+                thread_id: conversation_id.to_string(),
+                turn_id: event_id,
+                elapsed_ms: heartbeat_event.elapsed_ms,
+                activity: heartbeat_event.activity.into(),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::TurnHeartbeat(notification))
+                .await;
+        }
+        EventMsg::SubAgentUpdate(sub_agent_event) => {
+            let notification = ThreadSubAgentUpdatedNotification {
+                parent_thread_id: conversation_id.to_string(),
+                thread_id: sub_agent_event.conversation_id.to_string(),
+                call_id: sub_agent_event.call_id,
+                model: sub_agent_event.model,
+                status: sub_agent_event.status.into(),
+                message: sub_agent_event.message,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ThreadSubAgentUpdated(notification))
+                .await;
+        }
         EventMsg::Error(ev) => {
-            handle_error(conversation_id, ev.message, &turn_summary_store).await;
+            handle_error(conversation_id, ev, &turn_summary_store).await;
         }
         EventMsg::EnteredReviewMode(review_request) => {
             let notification = ItemStartedNotification {
@@ -216,6 +415,13 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .await;
         }
         EventMsg::ItemStarted(item_started_event) => {
+            if let CoreTurnItem::CommandExecution(command) = &item_started_event.item {
+                let mut map = turn_summary_store.lock().await;
+                map.entry(conversation_id)
+                    .or_default()
+                    .command_items
+                    .insert(command.call_id.clone(), command.id.clone());
+            }
             let item: ThreadItem = item_started_event.item.clone().into();
             let notification = ItemStartedNotification { item };
             outgoing
@@ -223,16 +429,61 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .await;
         }
         EventMsg::ItemCompleted(item_completed_event) => {
+            if let CoreTurnItem::CommandExecution(command) = &item_completed_event.item {
+                let decoders: Vec<OutputDecoder> = {
+                    let mut map = turn_summary_store.lock().await;
+                    match map.get_mut(&conversation_id) {
+                        Some(summary) => [ExecOutputStream::Stdout, ExecOutputStream::Stderr]
+                            .into_iter()
+                            .filter_map(|stream| {
+                                summary
+                                    .output_decoders
+                                    .remove(&(command.call_id.clone(), stream))
+                            })
+                            .collect(),
+                        None => Vec::new(),
+                    }
+                };
+                for decoder in decoders {
+                    if let Some(decoded) = decoder.finish() {
+                        send_output_delta(&outgoing, command.id.clone(), decoded).await;
+                    }
+                }
+            }
             let item: ThreadItem = item_completed_event.item.clone().into();
             let notification = ItemCompletedNotification { item };
             outgoing
                 .send_server_notification(ServerNotification::ItemCompleted(notification))
                 .await;
         }
+        EventMsg::PullRequestCreated(event) => {
+            let item = ThreadItem::PullRequest {
+                id: event_id,
+                provider: event.provider.into(),
+                url: event.url,
+                number: event.number,
+                title: event.title,
+                head: event.head,
+                base: event.base,
+            };
+            outgoing
+                .send_server_notification(ServerNotification::ItemStarted(
+                    ItemStartedNotification { item: item.clone() },
+                ))
+                .await;
+            outgoing
+                .send_server_notification(ServerNotification::ItemCompleted(
+                    ItemCompletedNotification { item },
+                ))
+                .await;
+        }
         EventMsg::ExitedReviewMode(review_event) => {
-            let review_text = match review_event.review_output {
-                Some(output) => render_review_output_text(&output),
-                None => REVIEW_FALLBACK_MESSAGE.to_string(),
+            let (review_text, findings) = match review_event.review_output {
+                Some(output) => (
+                    render_review_output_text(&output),
+                    review_finding_items(&event_id, output.findings),
+                ),
+                None => (REVIEW_FALLBACK_MESSAGE.to_string(), Vec::new()),
             };
             let notification = ItemCompletedNotification {
                 item: ThreadItem::CodeReview {
@@ -243,79 +494,44 @@ pub(crate) async fn apply_bespoke_event_handling(
             outgoing
                 .send_server_notification(ServerNotification::ItemCompleted(notification))
                 .await;
-        }
-        EventMsg::ExecCommandBegin(exec_command_begin_event) => {
-            let item = ThreadItem::CommandExecution {
-                id: exec_command_begin_event.call_id.clone(),
-                command: shlex_join(&exec_command_begin_event.command),
-                cwd: exec_command_begin_event.cwd,
-                status: CommandExecutionStatus::InProgress,
-                command_actions: exec_command_begin_event
-                    .parsed_cmd
-                    .into_iter()
-                    .map(V2ParsedCommand::from)
-                    .collect(),
-                aggregated_output: None,
-                exit_code: None,
-                duration_ms: None,
-            };
-            let notification = ItemStartedNotification { item };
-            outgoing
-                .send_server_notification(ServerNotification::ItemStarted(notification))
-                .await;
+            for item in findings {
+                outgoing
+                    .send_server_notification(ServerNotification::ItemStarted(
+                        ItemStartedNotification { item: item.clone() },
+                    ))
+                    .await;
+                outgoing
+                    .send_server_notification(ServerNotification::ItemCompleted(
+                        ItemCompletedNotification { item },
+                    ))
+                    .await;
+            }
         }
         EventMsg::ExecCommandOutputDelta(exec_command_output_delta_event) => {
-            let notification = CommandExecutionOutputDeltaNotification {
-                item_id: exec_command_output_delta_event.call_id.clone(),
-                delta: String::from_utf8_lossy(&exec_command_output_delta_event.chunk).to_string(),
-            };
-            outgoing
-                .send_server_notification(ServerNotification::CommandExecutionOutputDelta(
-                    notification,
-                ))
-                .await;
-        }
-        EventMsg::ExecCommandEnd(exec_command_end_event) => {
-            let ExecCommandEndEvent {
+            let ExecCommandOutputDeltaEvent {
                 call_id,
-                command,
-                cwd,
-                parsed_cmd,
-                aggregated_output,
-                exit_code,
-                duration,
-                ..
-            } = exec_command_end_event;
-
-            let status = if exit_code == 0 {
-                CommandExecutionStatus::Completed
-            } else {
-                CommandExecutionStatus::Failed
-            };
-
-            let aggregated_output = if aggregated_output.is_empty() {
-                None
-            } else {
-                Some(aggregated_output)
-            };
-
-            let duration_ms = i64::try_from(duration.as_millis()).unwrap_or(i64::MAX);
-
-            let item = ThreadItem::CommandExecution {
-                id: call_id,
-                command: shlex_join(&command),
-                cwd,
-                status,
-                command_actions: parsed_cmd.into_iter().map(V2ParsedCommand::from).collect(),
-                aggregated_output,
-                exit_code: Some(exit_code),
-                duration_ms: Some(duration_ms),
+                stream,
+                chunk,
+            } = exec_command_output_delta_event;
+            let (item_id, decoded) = {
+                let mut map = turn_summary_store.lock().await;
+                match map.get_mut(&conversation_id) {
+                    Some(summary) => (
+                        summary.command_items.get(&call_id).cloned(),
+                        summary
+                            .output_decoders
+                            .entry((call_id.clone(), stream))
+                            .or_default()
+                            .push(&chunk),
+                    ),
+                    // Output of a command the turn never announced is
+                    // decoded chunk by chunk.
+                    None => (None, OutputDecoder::decode(&chunk)),
+                }
             };
-
-            let notification = ItemCompletedNotification { item };
-            outgoing
-                .send_server_notification(ServerNotification::ItemCompleted(notification))
-                .await;
+            if let Some(decoded) = decoded {
+                send_output_delta(&outgoing, item_id.unwrap_or(call_id), decoded).await;
+            }
         }
         // If this is a TurnAborted, reply to any pending interrupt requests.
         EventMsg::TurnAborted(turn_aborted_event) => {
@@ -325,18 +541,13 @@ pub(crate) async fn apply_bespoke_event_handling(
             };
             if !pending.is_empty() {
                 for (rid, ver) in pending {
-                    match ver {
-                        ApiVersion::V1 => {
-                            let response = InterruptConversationResponse {
-                                abort_reason: turn_aborted_event.reason.clone(),
-                            };
-                            outgoing.send_response(rid, response).await;
-                        }
-                        ApiVersion::V2 => {
-                            let response = TurnInterruptResponse {};
-                            outgoing.send_response(rid, response).await;
-                        }
-                    }
+                    v1_compat::respond_to_interrupt(
+                        &outgoing,
+                        rid,
+                        ver,
+                        &turn_aborted_event.reason,
+                    )
+                    .await;
                 }
             }
 
@@ -348,9 +559,12 @@ pub(crate) async fn apply_bespoke_event_handling(
     }
 }
 
-async fn emit_turn_completed_with_status(
+pub(crate) async fn emit_turn_completed_with_status(
     event_id: String,
     status: TurnStatus,
+    commit: Option<TurnCommit>,
+    diff: Option<TurnDiffSummary>,
+    usage: Option<Usage>,
     outgoing: &OutgoingMessageSender,
 ) {
     let notification = TurnCompletedNotification {
@@ -358,7 +572,10 @@ async fn emit_turn_completed_with_status(
             id: event_id,
             items: vec![],
             status,
+            commit,
+            diff,
         },
+        usage,
     };
     outgoing
         .send_server_notification(ServerNotification::TurnCompleted(notification))
@@ -376,20 +593,22 @@ async fn find_and_remove_turn_summary(
 async fn handle_turn_complete(
     conversation_id: ConversationId,
     event_id: String,
+    commit: Option<TurnCommit>,
+    diff: Option<TurnDiffSummary>,
     outgoing: &OutgoingMessageSender,
     turn_summary_store: &TurnSummaryStore,
 ) {
-    let turn_summary = find_and_remove_turn_summary(conversation_id, turn_summary_store).await;
+    let mut turn_summary = find_and_remove_turn_summary(conversation_id, turn_summary_store).await;
+    flush_output_decoders(&mut turn_summary, outgoing).await;
 
-    let status = if let Some(message) = turn_summary.last_error_message {
-        TurnStatus::Failed {
-            error: TurnError { message },
-        }
+    let status = if let Some(error) = turn_summary.last_error {
+        TurnStatus::Failed { error }
     } else {
         TurnStatus::Completed
     };
 
-    emit_turn_completed_with_status(event_id, status, outgoing).await;
+    emit_turn_completed_with_status(event_id, status, commit, diff, turn_summary.usage, outgoing)
+        .await;
 }
 
 async fn handle_turn_interrupted(
@@ -398,18 +617,87 @@ async fn handle_turn_interrupted(
     outgoing: &OutgoingMessageSender,
     turn_summary_store: &TurnSummaryStore,
 ) {
-    find_and_remove_turn_summary(conversation_id, turn_summary_store).await;
+    let mut turn_summary = find_and_remove_turn_summary(conversation_id, turn_summary_store).await;
+    flush_output_decoders(&mut turn_summary, outgoing).await;
+
+    emit_turn_completed_with_status(
+        event_id,
+        TurnStatus::Interrupted,
+        None,
+        None,
+        turn_summary.usage,
+        outgoing,
+    )
+    .await;
+}
+
+async fn handle_token_count(
+    conversation_id: ConversationId,
+    event: &TokenCountEvent,
+    turn_summary_store: &TurnSummaryStore,
+) {
+    let Some(info) = &event.info else {
+        return;
+    };
+    let total = &info.total_token_usage;
+    let usage = Usage {
+        input_tokens: saturating_i32(total.input_tokens),
+        cached_input_tokens: saturating_i32(total.cached_input_tokens),
+        output_tokens: saturating_i32(total.output_tokens),
+        cost_usd: event.cost.map(|cost| cost.total_usd),
+    };
+    let mut map = turn_summary_store.lock().await;
+    map.entry(conversation_id).or_default().usage = Some(usage);
+}
 
-    emit_turn_completed_with_status(event_id, TurnStatus::Interrupted, outgoing).await;
+fn saturating_i32(value: i64) -> i32 {
+    i32::try_from(value).unwrap_or(i32::MAX)
+}
+
+/// Sends the bytes still held back for commands that never completed, e.g.
+/// because the turn was interrupted, and drops their decoders.
+async fn flush_output_decoders(turn_summary: &mut TurnSummary, outgoing: &OutgoingMessageSender) {
+    for ((call_id, _), decoder) in std::mem::take(&mut turn_summary.output_decoders) {
+        if let Some(decoded) = decoder.finish() {
+            let item_id = turn_summary
+                .command_items
+                .get(&call_id)
+                .cloned()
+                .unwrap_or(call_id);
+            send_output_delta(outgoing, item_id, decoded).await;
+        }
+    }
+}
+
+async fn send_output_delta(
+    outgoing: &OutgoingMessageSender,
+    item_id: String,
+    decoded: DecodedDelta,
+) {
+    let DecodedDelta { delta, raw } = decoded;
+    let notification = CommandExecutionOutputDeltaNotification {
+        item_id,
+        delta,
+        raw,
+    };
+    outgoing
+        .send_server_notification(ServerNotification::CommandExecutionOutputDelta(
+            notification,
+        ))
+        .await;
 }
 
 async fn handle_error(
     conversation_id: ConversationId,
-    message: String,
+    event: ErrorEvent,
     turn_summary_store: &TurnSummaryStore,
 ) {
+    let error = TurnError {
+        message: event.message,
+        info: Some(event.info.into()),
+    };
     let mut map = turn_summary_store.lock().await;
-    map.entry(conversation_id).or_default().last_error_message = Some(message);
+    map.entry(conversation_id).or_default().last_error = Some(error);
 }
 
 async fn on_patch_approval_response(
@@ -454,44 +742,70 @@ async fn on_patch_approval_response(
     }
 }
 
-async fn on_exec_approval_response(
-    event_id: String,
+async fn on_elicitation_response(
+    server_name: String,
+    request_id: mcp_types::RequestId,
     receiver: oneshot::Receiver<JsonValue>,
     conversation: Arc<CodexConversation>,
 ) {
-    let response = receiver.await;
-    let value = match response {
-        Ok(value) => value,
+    // Anything other than a well-formed answer declines the request.
+    let response = match receiver.await {
+        Ok(value) => serde_json::from_value::<McpServerElicitationRequestResponse>(value)
+            .unwrap_or_else(|err| {
+                error!("failed to deserialize McpServerElicitationRequestResponse: {err}");
+                McpServerElicitationRequestResponse {
+                    decision: ApprovalDecision::Decline,
+                    content: None,
+                }
+            }),
         Err(err) => {
             error!("request failed: {err:?}");
-            return;
+            McpServerElicitationRequestResponse {
+                decision: ApprovalDecision::Decline,
+                content: None,
+            }
         }
     };
 
-    // Try to deserialize `value` and then make the appropriate call to `codex`.
-    let response =
-        serde_json::from_value::<ExecCommandApprovalResponse>(value).unwrap_or_else(|err| {
-            error!("failed to deserialize ExecCommandApprovalResponse: {err}");
-            // If we cannot deserialize the response, we deny the request to be
-            // conservative.
-            ExecCommandApprovalResponse {
-                decision: ReviewDecision::Denied,
-            }
-        });
-
+    let decision = match response.decision {
+        ApprovalDecision::Accept => ElicitationAction::Accept,
+        ApprovalDecision::Decline => ElicitationAction::Decline,
+        ApprovalDecision::Cancel => ElicitationAction::Cancel,
+    };
     if let Err(err) = conversation
-        .submit(Op::ExecApproval {
-            id: event_id,
-            decision: response.decision,
+        .submit(Op::ResolveElicitation {
+            server_name,
+            request_id,
+            decision,
+            content: response.content,
         })
         .await
     {
-        error!("failed to submit ExecApproval: {err}");
+        error!("failed to submit ResolveElicitation: {err}");
     }
 }
 
 const REVIEW_FALLBACK_MESSAGE: &str = "Reviewer failed to output a response.";
 
+fn review_finding_items(review_id: &str, findings: Vec<ReviewFinding>) -> Vec<ThreadItem> {
+    findings
+        .into_iter()
+        .enumerate()
+        .map(|(index, finding)| ThreadItem::ReviewFinding {
+            id: format!("{review_id}-finding-{index}"),
+            review_id: review_id.to_string(),
+            title: finding.title,
+            body: finding.body,
+            path: finding.code_location.absolute_file_path,
+            line_start: finding.code_location.line_range.start,
+            line_end: finding.code_location.line_range.end,
+            severity: ReviewSeverity::from_priority(finding.priority),
+            confidence_score: finding.confidence_score,
+            suggestion: finding.suggestion,
+        })
+        .collect()
+}
+
 fn render_review_output_text(output: &ReviewOutputEvent) -> String {
     let mut sections = Vec::new();
     let explanation = output.overall_explanation.trim();
@@ -516,6 +830,7 @@ async fn on_command_execution_request_approval_response(
     event_id: String,
     receiver: oneshot::Receiver<JsonValue>,
     conversation: Arc<CodexConversation>,
+    api_version: ApiVersion,
 ) {
     let response = receiver.await;
     let value = match response {
@@ -526,14 +841,13 @@ async fn on_command_execution_request_approval_response(
         }
     };
 
-    let response = serde_json::from_value::<CommandExecutionRequestApprovalResponse>(value)
-        .unwrap_or_else(|err| {
-            error!("failed to deserialize CommandExecutionRequestApprovalResponse: {err}");
-            CommandExecutionRequestApprovalResponse {
-                decision: ApprovalDecision::Decline,
-                accept_settings: None,
-            }
-        });
+    let response = v1_compat::exec_approval_response(api_version, value).unwrap_or_else(|err| {
+        error!("failed to deserialize CommandExecutionRequestApprovalResponse: {err}");
+        CommandExecutionRequestApprovalResponse {
+            decision: ApprovalDecision::Decline,
+            accept_settings: None,
+        }
+    });
 
     let CommandExecutionRequestApprovalResponse {
         decision,
@@ -559,6 +873,77 @@ async fn on_command_execution_request_approval_response(
     }
 }
 
+async fn on_write_scope_request_approval_response(
+    event_id: String,
+    receiver: oneshot::Receiver<JsonValue>,
+    conversation: Arc<CodexConversation>,
+) {
+    let decision = match receiver.await {
+        Ok(value) => serde_json::from_value::<WriteScopeRequestApprovalResponse>(value)
+            .map(|response| response.decision.to_core())
+            .unwrap_or_else(|err| {
+                error!("failed to deserialize WriteScopeRequestApprovalResponse: {err}");
+                WriteScopeDecision::Deny
+            }),
+        Err(err) => {
+            error!("request failed: {err:?}");
+            WriteScopeDecision::Deny
+        }
+    };
+    submit_write_scope_decision(&conversation, event_id, decision).await;
+}
+
+async fn submit_write_scope_decision(
+    conversation: &CodexConversation,
+    event_id: String,
+    decision: WriteScopeDecision,
+) {
+    if let Err(err) = conversation
+        .submit(Op::WriteScopeApproval {
+            id: event_id,
+            decision,
+        })
+        .await
+    {
+        error!("failed to submit WriteScopeApproval: {err}");
+    }
+}
+
+async fn on_user_input_requested_response(
+    call_id: String,
+    receiver: oneshot::Receiver<JsonValue>,
+    conversation: Arc<CodexConversation>,
+) {
+    // Anything other than a well-formed answer counts as no answer.
+    let answer = match receiver.await {
+        Ok(value) => match serde_json::from_value::<UserInputRequestedResponse>(value) {
+            Ok(response) => response.answer,
+            Err(err) => {
+                error!("failed to deserialize UserInputRequestedResponse: {err}");
+                None
+            }
+        },
+        Err(err) => {
+            error!("request failed: {err:?}");
+            None
+        }
+    };
+    submit_user_input_answer(&conversation, call_id, answer).await;
+}
+
+async fn submit_user_input_answer(
+    conversation: &CodexConversation,
+    call_id: String,
+    answer: Option<String>,
+) {
+    if let Err(err) = conversation
+        .submit(Op::UserInputAnswer { call_id, answer })
+        .await
+    {
+        error!("failed to submit UserInputAnswer: {err}");
+    }
+}
+
 /// similar to handle_mcp_tool_call_begin in exec
 async fn construct_mcp_tool_call_notification(
     begin_event: McpToolCallBeginEvent,
@@ -622,13 +1007,26 @@ mod tests {
     use anyhow::Result;
     use anyhow::anyhow;
     use anyhow::bail;
+    use codex_app_server_protocol::ErrorCategory;
+    use codex_app_server_protocol::ErrorCode as V2ErrorCode;
+    use codex_app_server_protocol::ErrorInfo;
+    use codex_app_server_protocol::ErrorOrigin as V2ErrorOrigin;
+    use codex_core::protocol::CodexErrorInfo;
+    use codex_core::protocol::CostEstimate;
+    use codex_core::protocol::ErrorCode;
+    use codex_core::protocol::ErrorOrigin;
     use codex_core::protocol::McpInvocation;
+    use codex_core::protocol::TokenUsage;
+    use codex_core::protocol::TokenUsageInfo;
+    use codex_protocol::protocol::ReviewCodeLocation;
+    use codex_protocol::protocol::ReviewLineRange;
     use mcp_types::CallToolResult;
     use mcp_types::ContentBlock;
     use mcp_types::TextContent;
     use pretty_assertions::assert_eq;
     use serde_json::Value as JsonValue;
     use std::collections::HashMap;
+    use std::path::PathBuf;
     use std::time::Duration;
     use tokio::sync::Mutex;
     use tokio::sync::mpsc;
@@ -637,15 +1035,35 @@ mod tests {
         Arc::new(Mutex::new(HashMap::new()))
     }
 
+    fn error_event(message: &str) -> ErrorEvent {
+        ErrorEvent {
+            message: message.to_string(),
+            http_status_code: None,
+            info: CodexErrorInfo::new(ErrorCode::ContextWindowExceeded, ErrorOrigin::ModelProvider),
+        }
+    }
+
+    fn turn_error(message: &str) -> TurnError {
+        TurnError {
+            message: message.to_string(),
+            info: Some(ErrorInfo {
+                category: ErrorCategory::ContextWindow,
+                code: V2ErrorCode::ContextWindowExceeded,
+                retryable: false,
+                origin: V2ErrorOrigin::ModelProvider,
+            }),
+        }
+    }
+
     #[tokio::test]
     async fn test_handle_error_records_message() -> Result<()> {
         let conversation_id = ConversationId::new();
         let turn_summary_store = new_turn_summary_store();
 
-        handle_error(conversation_id, "boom".to_string(), &turn_summary_store).await;
+        handle_error(conversation_id, error_event("boom"), &turn_summary_store).await;
 
         let turn_summary = find_and_remove_turn_summary(conversation_id, &turn_summary_store).await;
-        assert_eq!(turn_summary.last_error_message, Some("boom".to_string()));
+        assert_eq!(turn_summary.last_error, Some(turn_error("boom")));
         Ok(())
     }
 
@@ -660,6 +1078,8 @@ mod tests {
         handle_turn_complete(
             conversation_id,
             event_id.clone(),
+            None,
+            None,
             &outgoing,
             &turn_summary_store,
         )
@@ -680,12 +1100,73 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_handle_turn_complete_reports_usage_and_cost() -> Result<()> {
+        let conversation_id = ConversationId::new();
+        let event_id = "complete_usage1".to_string();
+        let turn_summary_store = new_turn_summary_store();
+        let usage = TokenUsage {
+            input_tokens: 1_200,
+            cached_input_tokens: 200,
+            output_tokens: 300,
+            reasoning_output_tokens: 0,
+            total_tokens: 1_500,
+        };
+        let token_count = TokenCountEvent {
+            info: Some(TokenUsageInfo {
+                total_token_usage: usage.clone(),
+                last_token_usage: usage,
+                model_context_window: None,
+            }),
+            rate_limits: None,
+            cost: Some(CostEstimate {
+                total_usd: 0.25,
+                last_usd: 0.25,
+            }),
+            breakdown: None,
+        };
+        handle_token_count(conversation_id, &token_count, &turn_summary_store).await;
+        let (tx, mut rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let outgoing = Arc::new(OutgoingMessageSender::new(tx));
+
+        handle_turn_complete(
+            conversation_id,
+            event_id.clone(),
+            None,
+            None,
+            &outgoing,
+            &turn_summary_store,
+        )
+        .await;
+
+        let msg = rx
+            .recv()
+            .await
+            .ok_or_else(|| anyhow!("should send one notification"))?;
+        match msg {
+            OutgoingMessage::AppServerNotification(ServerNotification::TurnCompleted(n)) => {
+                assert_eq!(n.turn.id, event_id);
+                assert_eq!(
+                    n.usage,
+                    Some(Usage {
+                        input_tokens: 1_200,
+                        cached_input_tokens: 200,
+                        output_tokens: 300,
+                        cost_usd: Some(0.25),
+                    })
+                );
+            }
+            other => bail!("unexpected message: {other:?}"),
+        }
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_handle_turn_interrupted_emits_interrupted_with_error() -> Result<()> {
         let conversation_id = ConversationId::new();
         let event_id = "interrupt1".to_string();
         let turn_summary_store = new_turn_summary_store();
-        handle_error(conversation_id, "oops".to_string(), &turn_summary_store).await;
+        handle_error(conversation_id, error_event("oops"), &turn_summary_store).await;
         let (tx, mut rx) = mpsc::channel(CHANNEL_CAPACITY);
         let outgoing = Arc::new(OutgoingMessageSender::new(tx));
 
@@ -712,18 +1193,71 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_handle_turn_interrupted_flushes_held_back_output() -> Result<()> {
+        let conversation_id = ConversationId::new();
+        let turn_summary_store = new_turn_summary_store();
+        let mut decoder = OutputDecoder::default();
+        assert_eq!(decoder.push(&[0xe2, 0x9c]), None);
+        turn_summary_store.lock().await.insert(
+            conversation_id,
+            TurnSummary {
+                command_items: HashMap::from([("call1".to_string(), "item1".to_string())]),
+                output_decoders: HashMap::from([(
+                    ("call1".to_string(), ExecOutputStream::Stdout),
+                    decoder,
+                )]),
+                ..Default::default()
+            },
+        );
+        let (tx, mut rx) = mpsc::channel(CHANNEL_CAPACITY);
+        let outgoing = Arc::new(OutgoingMessageSender::new(tx));
+
+        handle_turn_interrupted(
+            conversation_id,
+            "interrupt1".to_string(),
+            &outgoing,
+            &turn_summary_store,
+        )
+        .await;
+
+        match rx.recv().await {
+            Some(OutgoingMessage::AppServerNotification(
+                ServerNotification::CommandExecutionOutputDelta(n),
+            )) => assert_eq!(
+                n,
+                CommandExecutionOutputDeltaNotification {
+                    item_id: "item1".to_string(),
+                    delta: "\u{fffd}".to_string(),
+                    raw: Some("4pw=".to_string()),
+                }
+            ),
+            other => bail!("unexpected message: {other:?}"),
+        }
+        match rx.recv().await {
+            Some(OutgoingMessage::AppServerNotification(ServerNotification::TurnCompleted(n))) => {
+                assert_eq!(n.turn.status, TurnStatus::Interrupted);
+            }
+            other => bail!("unexpected message: {other:?}"),
+        }
+        assert!(turn_summary_store.lock().await.is_empty());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_handle_turn_complete_emits_failed_with_error() -> Result<()> {
         let conversation_id = ConversationId::new();
         let event_id = "complete_err1".to_string();
         let turn_summary_store = new_turn_summary_store();
-        handle_error(conversation_id, "bad".to_string(), &turn_summary_store).await;
+        handle_error(conversation_id, error_event("bad"), &turn_summary_store).await;
         let (tx, mut rx) = mpsc::channel(CHANNEL_CAPACITY);
         let outgoing = Arc::new(OutgoingMessageSender::new(tx));
 
         handle_turn_complete(
             conversation_id,
             event_id.clone(),
+            None,
+            None,
             &outgoing,
             &turn_summary_store,
         )
@@ -739,9 +1273,7 @@ mod tests {
                 assert_eq!(
                     n.turn.status,
                     TurnStatus::Failed {
-                        error: TurnError {
-                            message: "bad".to_string(),
-                        }
+                        error: turn_error("bad")
                     }
                 );
             }
@@ -791,10 +1323,12 @@ mod tests {
 
         // Turn 1 on conversation A
         let a_turn1 = "a_turn1".to_string();
-        handle_error(conversation_a, "a1".to_string(), &turn_summary_store).await;
+        handle_error(conversation_a, error_event("a1"), &turn_summary_store).await;
         handle_turn_complete(
             conversation_a,
             a_turn1.clone(),
+            None,
+            None,
             &outgoing,
             &turn_summary_store,
         )
@@ -802,10 +1336,12 @@ mod tests {
 
         // Turn 1 on conversation B
         let b_turn1 = "b_turn1".to_string();
-        handle_error(conversation_b, "b1".to_string(), &turn_summary_store).await;
+        handle_error(conversation_b, error_event("b1"), &turn_summary_store).await;
         handle_turn_complete(
             conversation_b,
             b_turn1.clone(),
+            None,
+            None,
             &outgoing,
             &turn_summary_store,
         )
@@ -816,6 +1352,8 @@ mod tests {
         handle_turn_complete(
             conversation_a,
             a_turn2.clone(),
+            None,
+            None,
             &outgoing,
             &turn_summary_store,
         )
@@ -832,9 +1370,7 @@ mod tests {
                 assert_eq!(
                     n.turn.status,
                     TurnStatus::Failed {
-                        error: TurnError {
-                            message: "a1".to_string(),
-                        }
+                        error: turn_error("a1")
                     }
                 );
             }
@@ -852,9 +1388,7 @@ mod tests {
                 assert_eq!(
                     n.turn.status,
                     TurnStatus::Failed {
-                        error: TurnError {
-                            message: "b1".to_string(),
-                        }
+                        error: turn_error("b1")
                     }
                 );
             }
@@ -981,4 +1515,106 @@ mod tests {
 
         assert_eq!(notification, expected);
     }
+
+    fn review_finding(priority: i32, suggestion: Option<&str>) -> ReviewFinding {
+        ReviewFinding {
+            title: format!("[P{priority}] Finding"),
+            body: "Explanation.".to_string(),
+            confidence_score: 0.8,
+            priority,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from("/repo/src/lib.rs"),
+                line_range: ReviewLineRange { start: 10, end: 12 },
+            },
+            suggestion: suggestion.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn review_finding_items_have_stable_ids() {
+        let findings = vec![review_finding(1, None), review_finding(2, None)];
+
+        let ids = |items: Vec<ThreadItem>| -> Vec<String> {
+            items.iter().map(|item| item.id().to_string()).collect()
+        };
+        let first = ids(review_finding_items("review-1", findings.clone()));
+        let second = ids(review_finding_items("review-1", findings));
+
+        assert_eq!(
+            first,
+            vec![
+                "review-1-finding-0".to_string(),
+                "review-1-finding-1".to_string()
+            ]
+        );
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn review_finding_items_map_priority_to_severity() {
+        let findings = [-1, 0, 1, 2, 3, 7]
+            .into_iter()
+            .map(|priority| review_finding(priority, None))
+            .collect();
+
+        let severities: Vec<ReviewSeverity> = review_finding_items("review-1", findings)
+            .into_iter()
+            .map(|item| match item {
+                ThreadItem::ReviewFinding { severity, .. } => severity,
+                other => panic!("expected a review finding, got {other:?}"),
+            })
+            .collect();
+
+        assert_eq!(
+            severities,
+            vec![
+                ReviewSeverity::Critical,
+                ReviewSeverity::Critical,
+                ReviewSeverity::High,
+                ReviewSeverity::Medium,
+                ReviewSeverity::Low,
+                ReviewSeverity::Low,
+            ]
+        );
+    }
+
+    #[test]
+    fn review_finding_items_carry_location_and_suggestion() {
+        let findings = vec![
+            review_finding(1, Some("let x = 1;")),
+            review_finding(2, None),
+        ];
+
+        let items = review_finding_items("review-1", findings);
+
+        assert_eq!(
+            items,
+            vec![
+                ThreadItem::ReviewFinding {
+                    id: "review-1-finding-0".to_string(),
+                    review_id: "review-1".to_string(),
+                    title: "[P1] Finding".to_string(),
+                    body: "Explanation.".to_string(),
+                    path: PathBuf::from("/repo/src/lib.rs"),
+                    line_start: 10,
+                    line_end: 12,
+                    severity: ReviewSeverity::High,
+                    confidence_score: 0.8,
+                    suggestion: Some("let x = 1;".to_string()),
+                },
+                ThreadItem::ReviewFinding {
+                    id: "review-1-finding-1".to_string(),
+                    review_id: "review-1".to_string(),
+                    title: "[P2] Finding".to_string(),
+                    body: "Explanation.".to_string(),
+                    path: PathBuf::from("/repo/src/lib.rs"),
+                    line_start: 10,
+                    line_end: 12,
+                    severity: ReviewSeverity::Medium,
+                    confidence_score: 0.8,
+                    suggestion: None,
+                },
+            ]
+        );
+    }
 }
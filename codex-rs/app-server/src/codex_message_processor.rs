@@ -1,15 +1,29 @@
 use crate::bespoke_event_handling::apply_bespoke_event_handling;
+use crate::bespoke_event_handling::emit_turn_completed_with_status;
 use crate::error_code::INTERNAL_ERROR_CODE;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::fuzzy_file_search::run_fuzzy_file_search;
+use crate::image_input::prepare_turn_input;
 use crate::models::supported_models;
+use crate::observer::in_thread;
+use crate::outgoing_message::NotificationFilter;
 use crate::outgoing_message::OutgoingMessageSender;
 use crate::outgoing_message::OutgoingNotification;
+use crate::output_delta::OutputDecoder;
+use crate::shutdown::ShutdownTracker;
+use crate::turn_queue::Admission;
+use crate::turn_queue::QueuedTurn;
+use crate::turn_queue::TurnQueue;
+use crate::turn_queue::start_turns;
 use chrono::DateTime;
 use chrono::Utc;
 use codex_app_server_protocol::Account;
 use codex_app_server_protocol::AccountLoginCompletedNotification;
+use codex_app_server_protocol::AccountLoginDeviceCodeNotification;
 use codex_app_server_protocol::AccountUpdatedNotification;
+use codex_app_server_protocol::AccountsListResponse;
+use codex_app_server_protocol::AccountsSwitchParams;
+use codex_app_server_protocol::AccountsSwitchResponse;
 use codex_app_server_protocol::AddConversationListenerParams;
 use codex_app_server_protocol::AddConversationSubscriptionResponse;
 use codex_app_server_protocol::ArchiveConversationParams;
@@ -17,14 +31,36 @@ use codex_app_server_protocol::ArchiveConversationResponse;
 use codex_app_server_protocol::AskForApproval;
 use codex_app_server_protocol::AuthMode;
 use codex_app_server_protocol::AuthStatusChangeNotification;
+use codex_app_server_protocol::BackgroundTaskReadParams;
+use codex_app_server_protocol::BackgroundTaskReadResponse;
+use codex_app_server_protocol::BackgroundTaskTerminateParams;
+use codex_app_server_protocol::BackgroundTaskTerminateResponse;
 use codex_app_server_protocol::CancelLoginAccountParams;
 use codex_app_server_protocol::CancelLoginAccountResponse;
 use codex_app_server_protocol::CancelLoginChatGptResponse;
 use codex_app_server_protocol::ClientRequest;
+use codex_app_server_protocol::CommandListParams;
+use codex_app_server_protocol::CommandListResponse;
+use codex_app_server_protocol::CommandRunParams;
+use codex_app_server_protocol::CommandRunResponse;
+use codex_app_server_protocol::ConfigDiagnostic;
+use codex_app_server_protocol::ConfigDiagnosticSeverity;
+use codex_app_server_protocol::ConfigValidateParams;
+use codex_app_server_protocol::ConfigValidateResponse;
 use codex_app_server_protocol::ConversationGitInfo;
 use codex_app_server_protocol::ConversationSummary;
 use codex_app_server_protocol::ExecOneOffCommandParams;
 use codex_app_server_protocol::ExecOneOffCommandResponse;
+use codex_app_server_protocol::ExecPolicyDecision;
+use codex_app_server_protocol::ExecutionApproval;
+use codex_app_server_protocol::ExecutionAssessParams;
+use codex_app_server_protocol::ExecutionAssessResponse;
+use codex_app_server_protocol::ExecutionGetOutputParams;
+use codex_app_server_protocol::ExecutionGetOutputResponse;
+use codex_app_server_protocol::ExecutionSandbox;
+use codex_app_server_protocol::ExperimentStatsParams;
+use codex_app_server_protocol::ExperimentStatsResponse;
+use codex_app_server_protocol::ExperimentVariantStats;
 use codex_app_server_protocol::FeedbackUploadParams;
 use codex_app_server_protocol::FeedbackUploadResponse;
 use codex_app_server_protocol::FuzzyFileSearchParams;
@@ -36,11 +72,13 @@ use codex_app_server_protocol::GetAuthStatusParams;
 use codex_app_server_protocol::GetAuthStatusResponse;
 use codex_app_server_protocol::GetConversationSummaryParams;
 use codex_app_server_protocol::GetConversationSummaryResponse;
+use codex_app_server_protocol::GetPatchContentParams;
 use codex_app_server_protocol::GetUserAgentResponse;
 use codex_app_server_protocol::GetUserSavedConfigResponse;
 use codex_app_server_protocol::GitDiffToRemoteResponse;
 use codex_app_server_protocol::InputItem as WireInputItem;
 use codex_app_server_protocol::InterruptConversationParams;
+use codex_app_server_protocol::ItemCompletedNotification;
 use codex_app_server_protocol::JSONRPCErrorError;
 use codex_app_server_protocol::ListConversationsParams;
 use codex_app_server_protocol::ListConversationsResponse;
@@ -51,10 +89,16 @@ use codex_app_server_protocol::LoginChatGptCompleteNotification;
 use codex_app_server_protocol::LoginChatGptResponse;
 use codex_app_server_protocol::LogoutAccountResponse;
 use codex_app_server_protocol::LogoutChatGptResponse;
+use codex_app_server_protocol::McpServerAuthReadParams;
+use codex_app_server_protocol::McpServerAuthReadResponse;
 use codex_app_server_protocol::ModelListParams;
 use codex_app_server_protocol::ModelListResponse;
 use codex_app_server_protocol::NewConversationParams;
 use codex_app_server_protocol::NewConversationResponse;
+use codex_app_server_protocol::NotificationsSubscribeParams;
+use codex_app_server_protocol::NotificationsSubscribeResponse;
+use codex_app_server_protocol::ProtocolDescribeParams;
+use codex_app_server_protocol::ProtocolDescribeResponse;
 use codex_app_server_protocol::RemoveConversationListenerParams;
 use codex_app_server_protocol::RemoveConversationSubscriptionResponse;
 use codex_app_server_protocol::RequestId;
@@ -71,30 +115,81 @@ use codex_app_server_protocol::ServerNotification;
 use codex_app_server_protocol::SessionConfiguredNotification;
 use codex_app_server_protocol::SetDefaultModelParams;
 use codex_app_server_protocol::SetDefaultModelResponse;
+use codex_app_server_protocol::SourceTokenUsage;
+use codex_app_server_protocol::StoredAccount;
 use codex_app_server_protocol::Thread;
 use codex_app_server_protocol::ThreadArchiveParams;
 use codex_app_server_protocol::ThreadArchiveResponse;
+use codex_app_server_protocol::ThreadBundleExportParams;
+use codex_app_server_protocol::ThreadBundleExportResponse;
+use codex_app_server_protocol::ThreadBundleImportParams;
+use codex_app_server_protocol::ThreadBundleImportResponse;
+use codex_app_server_protocol::ThreadContextAddParams;
+use codex_app_server_protocol::ThreadContextAddResponse;
+use codex_app_server_protocol::ThreadContextListParams;
+use codex_app_server_protocol::ThreadContextListResponse;
+use codex_app_server_protocol::ThreadContextRemoveParams;
+use codex_app_server_protocol::ThreadContextRemoveResponse;
+use codex_app_server_protocol::ThreadCreateFromTemplateParams;
+use codex_app_server_protocol::ThreadExportFormat;
+use codex_app_server_protocol::ThreadExportParams;
+use codex_app_server_protocol::ThreadExportResponse;
+use codex_app_server_protocol::ThreadForkParams;
+use codex_app_server_protocol::ThreadForkResponse;
 use codex_app_server_protocol::ThreadItem;
 use codex_app_server_protocol::ThreadListParams;
 use codex_app_server_protocol::ThreadListResponse;
+use codex_app_server_protocol::ThreadMetadataChangedNotification;
+use codex_app_server_protocol::ThreadRenameParams;
+use codex_app_server_protocol::ThreadRenameResponse;
 use codex_app_server_protocol::ThreadResumeParams;
 use codex_app_server_protocol::ThreadResumeResponse;
+use codex_app_server_protocol::ThreadSearchMatch;
+use codex_app_server_protocol::ThreadSearchMatchKind;
+use codex_app_server_protocol::ThreadSearchParams;
+use codex_app_server_protocol::ThreadSearchResponse;
+use codex_app_server_protocol::ThreadSearchResult;
+use codex_app_server_protocol::ThreadSetProfileParams;
+use codex_app_server_protocol::ThreadSetProfileResponse;
+use codex_app_server_protocol::ThreadSetTerminalSizeParams;
+use codex_app_server_protocol::ThreadSetTerminalSizeResponse;
 use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::ThreadStartResponse;
 use codex_app_server_protocol::ThreadStartedNotification;
+use codex_app_server_protocol::ThreadTemplate as V2ThreadTemplate;
+use codex_app_server_protocol::ThreadTemplateListParams;
+use codex_app_server_protocol::ThreadTemplateListResponse;
+use codex_app_server_protocol::ThreadUsageParams;
+use codex_app_server_protocol::ThreadUsageResponse;
 use codex_app_server_protocol::Turn;
+use codex_app_server_protocol::TurnError;
 use codex_app_server_protocol::TurnInterruptParams;
+use codex_app_server_protocol::TurnInterruptResponse;
+use codex_app_server_protocol::TurnQueuedNotification;
 use codex_app_server_protocol::TurnStartParams;
 use codex_app_server_protocol::TurnStartResponse;
 use codex_app_server_protocol::TurnStartedNotification;
 use codex_app_server_protocol::TurnStatus;
+use codex_app_server_protocol::Usage;
 use codex_app_server_protocol::UserInfoResponse;
 use codex_app_server_protocol::UserInput as V2UserInput;
 use codex_app_server_protocol::UserSavedConfig;
 use codex_app_server_protocol::WindowsWorldWritableWarningNotification;
+use codex_app_server_protocol::WorkspaceInstruction;
+use codex_app_server_protocol::WorkspaceInstructionAddParams;
+use codex_app_server_protocol::WorkspaceInstructionAddResponse;
+use codex_app_server_protocol::WorkspaceInstructionListParams;
+use codex_app_server_protocol::WorkspaceInstructionListResponse;
+use codex_app_server_protocol::WorkspaceInstructionRemoveParams;
+use codex_app_server_protocol::WorkspaceInstructionRemoveResponse;
+use codex_app_server_protocol::WorkspaceInstructionUpdateParams;
+use codex_app_server_protocol::WorkspaceInstructionUpdateResponse;
 use codex_app_server_protocol::build_turns_from_event_msgs;
+use codex_app_server_protocol::describe_protocol;
+use codex_app_server_protocol::user_messages_before_fork_point;
 use codex_backend_client::Client as BackendClient;
 use codex_core::AuthManager;
+use codex_core::CodexAuth;
 use codex_core::CodexConversation;
 use codex_core::ConversationManager;
 use codex_core::Cursor as RolloutCursor;
@@ -104,28 +199,78 @@ use codex_core::NewConversation;
 use codex_core::RolloutRecorder;
 use codex_core::SessionMeta;
 use codex_core::auth::CLIENT_ID;
+use codex_core::auth::list_accounts;
 use codex_core::auth::login_with_api_key;
+use codex_core::background_tasks::BackgroundTaskManager;
+use codex_core::background_tasks::BackgroundTaskSnapshot;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
 use codex_core::config::ConfigToml;
 use codex_core::config::edit::ConfigEditsBuilder;
+use codex_core::config::template::ThreadTemplate;
+use codex_core::config::types::McpServerTransportConfig;
+use codex_core::config::validate::ConfigDiagnostic as CoreConfigDiagnostic;
+use codex_core::config::validate::DiagnosticSeverity;
+use codex_core::config::validate::validate_config_file;
+use codex_core::config::validate::validate_config_toml;
 use codex_core::config_loader::load_config_as_toml;
+use codex_core::custom_commands::commands_dir;
+use codex_core::custom_commands::discover_commands_in;
 use codex_core::default_client::get_codex_user_agent;
 use codex_core::exec::ExecParams;
+use codex_core::exec::SandboxType;
+use codex_core::exec_assessment::ApprovalOutcome;
+use codex_core::exec_assessment::CommandAssessment;
+use codex_core::exec_assessment::assess_command;
 use codex_core::exec_env::create_env;
+use codex_core::exec_env::is_valid_env_var_name;
+use codex_core::exec_log::ArchivedExecOutput;
+use codex_core::exec_log::read_exec_output;
+use codex_core::experiments::ExperimentVariantStats as CoreExperimentVariantStats;
+use codex_core::experiments::experiment_stats;
 use codex_core::features::Feature;
 use codex_core::find_conversation_path_by_id_str;
 use codex_core::get_platform_sandbox;
 use codex_core::git_info::git_diff_to_remote;
+use codex_core::history_index::HistoryIndex;
+use codex_core::history_index::IndexedItemKind;
+use codex_core::history_index::ThreadSearchHit;
+use codex_core::mcp::auth::oauth_token_info;
 use codex_core::parse_cursor;
+use codex_core::pinned_context::add_pinned_context;
+use codex_core::pinned_context::list_pinned_context;
+use codex_core::pinned_context::remove_pinned_context;
 use codex_core::protocol::EventMsg;
+use codex_core::protocol::ExecOutputStream;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::Submission;
 use codex_core::read_head_for_summary;
+use codex_core::thread_bundle::export_thread_bundle;
+use codex_core::thread_bundle::import_thread_bundle;
+use codex_core::thread_metadata::normalize_title;
+use codex_core::thread_metadata::read_thread_metadata;
+use codex_core::thread_metadata::thread_metadata_path;
+use codex_core::thread_metadata::write_thread_metadata;
+use codex_core::thread_templates::LoadedTemplate;
+use codex_core::thread_templates::apply_template;
+use codex_core::thread_templates::list_templates;
+use codex_core::thread_templates::load_template;
+use codex_core::thread_templates::resolve_pinned_context;
+use codex_core::transcript_export::TranscriptFormat;
+use codex_core::transcript_export::export_transcript;
+use codex_core::workspace_instructions::WorkspaceInstruction as CoreWorkspaceInstruction;
+use codex_core::workspace_instructions::add_workspace_instruction;
+use codex_core::workspace_instructions::load_workspace_instructions;
+use codex_core::workspace_instructions::remove_workspace_instruction;
+use codex_core::workspace_instructions::update_workspace_instruction;
+use codex_execpolicy2::Decision;
 use codex_feedback::CodexFeedback;
 use codex_login::ServerOptions as LoginServerOptions;
 use codex_login::ShutdownHandle;
+use codex_login::complete_device_code_login;
+use codex_login::request_device_code;
 use codex_login::run_login_server;
 use codex_protocol::ConversationId;
 use codex_protocol::config_types::ForcedLoginMethod;
@@ -135,10 +280,12 @@ use codex_protocol::protocol::GitInfo;
 use codex_protocol::protocol::RateLimitSnapshot as CoreRateLimitSnapshot;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::SessionMetaLine;
+use codex_protocol::protocol::TokenUsageBreakdown;
 use codex_protocol::protocol::USER_MESSAGE_BEGIN;
 use codex_protocol::user_input::UserInput as CoreInputItem;
 use codex_utils_json_to_toml::json_to_toml;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::ffi::OsStr;
 use std::io::Error as IoError;
 use std::path::Path;
@@ -150,6 +297,7 @@ use std::time::Duration;
 use tokio::select;
 use tokio::sync::Mutex;
 use tokio::sync::oneshot;
+use tokio::task::AbortHandle;
 use tracing::error;
 use tracing::info;
 use tracing::warn;
@@ -157,25 +305,44 @@ use uuid::Uuid;
 
 type PendingInterruptQueue = Vec<(RequestId, ApiVersion)>;
 pub(crate) type PendingInterrupts = Arc<Mutex<HashMap<ConversationId, PendingInterruptQueue>>>;
+/// `getPatchContent` requests keyed by the id of the submission that fetches them.
+pub(crate) type PendingPatchContents = Arc<Mutex<HashMap<String, RequestId>>>;
 
 /// Per-conversation accumulation of the latest states e.g. error message while a turn runs.
 #[derive(Default, Clone)]
 pub(crate) struct TurnSummary {
-    pub(crate) last_error_message: Option<String>,
+    pub(crate) last_error: Option<TurnError>,
+    pub(crate) usage: Option<Usage>,
+    /// Item ids of the turn's commands by call id, for their output deltas.
+    pub(crate) command_items: HashMap<String, String>,
+    /// Bytes held back from the output deltas of running commands.
+    pub(crate) output_decoders: HashMap<(String, ExecOutputStream), OutputDecoder>,
 }
 
 pub(crate) type TurnSummaryStore = Arc<Mutex<HashMap<ConversationId, TurnSummary>>>;
 
 // Duration before a ChatGPT login attempt is abandoned.
 const LOGIN_CHATGPT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+/// How an in-flight login attempt is stopped.
+enum LoginCancelHandle {
+    /// The local redirect server of a browser login.
+    Server(ShutdownHandle),
+    /// The task polling for a device-code login.
+    Task(AbortHandle),
+}
+
 struct ActiveLogin {
-    shutdown_handle: ShutdownHandle,
+    cancel_handle: LoginCancelHandle,
     login_id: Uuid,
 }
 
 impl Drop for ActiveLogin {
     fn drop(&mut self) {
-        self.shutdown_handle.shutdown();
+        match &self.cancel_handle {
+            LoginCancelHandle::Server(shutdown_handle) => shutdown_handle.shutdown(),
+            LoginCancelHandle::Task(abort_handle) => abort_handle.abort(),
+        }
     }
 }
 
@@ -190,9 +357,12 @@ pub(crate) struct CodexMessageProcessor {
     active_login: Arc<Mutex<Option<ActiveLogin>>>,
     // Queue of pending interrupt requests per conversation. We reply when TurnAborted arrives.
     pending_interrupts: PendingInterrupts,
+    pending_patch_contents: PendingPatchContents,
     turn_summary_store: TurnSummaryStore,
     pending_fuzzy_searches: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
     feedback: CodexFeedback,
+    shutdown_tracker: Arc<ShutdownTracker>,
+    turn_queue: Arc<TurnQueue>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -233,6 +403,8 @@ impl CodexMessageProcessor {
         codex_linux_sandbox_exe: Option<PathBuf>,
         config: Arc<Config>,
         feedback: CodexFeedback,
+        shutdown_tracker: Arc<ShutdownTracker>,
+        turn_queue: Arc<TurnQueue>,
     ) -> Self {
         Self {
             auth_manager,
@@ -243,9 +415,12 @@ impl CodexMessageProcessor {
             conversation_listeners: HashMap::new(),
             active_login: Arc::new(Mutex::new(None)),
             pending_interrupts: Arc::new(Mutex::new(HashMap::new())),
+            pending_patch_contents: Arc::new(Mutex::new(HashMap::new())),
             turn_summary_store: Arc::new(Mutex::new(HashMap::new())),
             pending_fuzzy_searches: Arc::new(Mutex::new(HashMap::new())),
             feedback,
+            shutdown_tracker,
+            turn_queue,
         }
     }
 
@@ -339,6 +514,15 @@ impl CodexMessageProcessor {
             ClientRequest::Initialize { .. } => {
                 panic!("Initialize should be handled in MessageProcessor");
             }
+            ClientRequest::Shutdown { .. } => {
+                panic!("Shutdown should be handled in MessageProcessor");
+            }
+            ClientRequest::ProtocolDescribe { request_id, params } => {
+                self.protocol_describe(request_id, params).await;
+            }
+            ClientRequest::NotificationsSubscribe { request_id, params } => {
+                self.notifications_subscribe(request_id, params).await;
+            }
             // === v2 Thread/Turn APIs ===
             ClientRequest::ThreadStart { request_id, params } => {
                 self.thread_start(request_id, params).await;
@@ -346,12 +530,33 @@ impl CodexMessageProcessor {
             ClientRequest::ThreadResume { request_id, params } => {
                 self.thread_resume(request_id, params).await;
             }
+            ClientRequest::ThreadFork { request_id, params } => {
+                self.thread_fork(request_id, params).await;
+            }
             ClientRequest::ThreadArchive { request_id, params } => {
                 self.thread_archive(request_id, params).await;
             }
+            ClientRequest::ThreadExport { request_id, params } => {
+                self.thread_export(request_id, params).await;
+            }
+            ClientRequest::ThreadBundleExport { request_id, params } => {
+                self.thread_bundle_export(request_id, params).await;
+            }
+            ClientRequest::ThreadBundleImport { request_id, params } => {
+                self.thread_bundle_import(request_id, params).await;
+            }
             ClientRequest::ThreadList { request_id, params } => {
                 self.thread_list(request_id, params).await;
             }
+            ClientRequest::ThreadsSearch { request_id, params } => {
+                self.threads_search(request_id, params).await;
+            }
+            ClientRequest::ThreadsCreateFromTemplate { request_id, params } => {
+                self.threads_create_from_template(request_id, params).await;
+            }
+            ClientRequest::ThreadTemplateList { request_id, params } => {
+                self.thread_template_list(request_id, params).await;
+            }
             ClientRequest::ThreadCompact {
                 request_id,
                 params: _,
@@ -359,6 +564,63 @@ impl CodexMessageProcessor {
                 self.send_unimplemented_error(request_id, "thread/compact")
                     .await;
             }
+            ClientRequest::ThreadSetProfile { request_id, params } => {
+                self.thread_set_profile(request_id, params).await;
+            }
+            ClientRequest::ThreadSetTerminalSize { request_id, params } => {
+                self.thread_set_terminal_size(request_id, params).await;
+            }
+            ClientRequest::ThreadRename { request_id, params } => {
+                self.thread_rename(request_id, params).await;
+            }
+            ClientRequest::ExecutionGetOutput { request_id, params } => {
+                self.execution_get_output(request_id, params).await;
+            }
+            ClientRequest::ExecutionAssess { request_id, params } => {
+                self.execution_assess(request_id, params).await;
+            }
+            ClientRequest::BackgroundTaskRead { request_id, params } => {
+                self.background_task_read(request_id, params).await;
+            }
+            ClientRequest::BackgroundTaskTerminate { request_id, params } => {
+                self.background_task_terminate(request_id, params).await;
+            }
+            ClientRequest::WorkspaceInstructionList { request_id, params } => {
+                self.workspace_instruction_list(request_id, params).await;
+            }
+            ClientRequest::WorkspaceInstructionAdd { request_id, params } => {
+                self.workspace_instruction_add(request_id, params).await;
+            }
+            ClientRequest::WorkspaceInstructionUpdate { request_id, params } => {
+                self.workspace_instruction_update(request_id, params).await;
+            }
+            ClientRequest::WorkspaceInstructionRemove { request_id, params } => {
+                self.workspace_instruction_remove(request_id, params).await;
+            }
+            ClientRequest::ThreadContextAdd { request_id, params } => {
+                self.thread_context_add(request_id, params).await;
+            }
+            ClientRequest::ThreadContextRemove { request_id, params } => {
+                self.thread_context_remove(request_id, params).await;
+            }
+            ClientRequest::ThreadContextList { request_id, params } => {
+                self.thread_context_list(request_id, params).await;
+            }
+            ClientRequest::ThreadUsage { request_id, params } => {
+                self.thread_usage(request_id, params).await;
+            }
+            ClientRequest::CommandList { request_id, params } => {
+                self.command_list(request_id, params).await;
+            }
+            ClientRequest::CommandRun { request_id, params } => {
+                self.command_run(request_id, params).await;
+            }
+            ClientRequest::ExperimentStats { request_id, params } => {
+                self.experiment_stats(request_id, params).await;
+            }
+            ClientRequest::ConfigValidate { request_id, params } => {
+                self.config_validate(request_id, params).await;
+            }
             ClientRequest::TurnStart { request_id, params } => {
                 self.turn_start(request_id, params).await;
             }
@@ -398,6 +660,15 @@ impl CodexMessageProcessor {
             ClientRequest::GetAccount { request_id, params } => {
                 self.get_account(request_id, params).await;
             }
+            ClientRequest::AccountsList {
+                request_id,
+                params: _,
+            } => {
+                self.list_accounts(request_id).await;
+            }
+            ClientRequest::AccountsSwitch { request_id, params } => {
+                self.switch_account(request_id, params).await;
+            }
             ClientRequest::ResumeConversation { request_id, params } => {
                 self.handle_resume_conversation(request_id, params).await;
             }
@@ -470,12 +741,18 @@ impl CodexMessageProcessor {
             ClientRequest::ExecOneOffCommand { request_id, params } => {
                 self.exec_one_off_command(request_id, params).await;
             }
+            ClientRequest::GetPatchContent { request_id, params } => {
+                self.get_patch_content(request_id, params).await;
+            }
             ClientRequest::GetAccountRateLimits {
                 request_id,
                 params: _,
             } => {
                 self.get_account_rate_limits(request_id).await;
             }
+            ClientRequest::McpServerAuthRead { request_id, params } => {
+                self.read_mcp_server_auth(request_id, params).await;
+            }
             ClientRequest::FeedbackUpload { request_id, params } => {
                 self.upload_feedback(request_id, params).await;
             }
@@ -500,6 +777,9 @@ impl CodexMessageProcessor {
             LoginAccountParams::Chatgpt => {
                 self.login_chatgpt_v2(request_id).await;
             }
+            LoginAccountParams::ChatgptDeviceCode => {
+                self.login_chatgpt_device_code_v2(request_id).await;
+            }
         }
     }
 
@@ -527,7 +807,7 @@ impl CodexMessageProcessor {
         }
 
         match login_with_api_key(
-            &self.config.codex_home,
+            &self.auth_manager.credentials_home(),
             &params.api_key,
             self.config.cli_auth_credentials_store_mode,
         ) {
@@ -610,7 +890,7 @@ impl CodexMessageProcessor {
         Ok(LoginServerOptions {
             open_browser: false,
             ..LoginServerOptions::new(
-                config.codex_home.clone(),
+                self.auth_manager.credentials_home(),
                 CLIENT_ID.to_string(),
                 config.forced_chatgpt_workspace_id.clone(),
                 config.cli_auth_credentials_store_mode,
@@ -633,7 +913,7 @@ impl CodexMessageProcessor {
                             drop(existing);
                         }
                         *guard = Some(ActiveLogin {
-                            shutdown_handle: shutdown_handle.clone(),
+                            cancel_handle: LoginCancelHandle::Server(shutdown_handle.clone()),
                             login_id,
                         });
                     }
@@ -723,7 +1003,7 @@ impl CodexMessageProcessor {
                             drop(existing);
                         }
                         *guard = Some(ActiveLogin {
-                            shutdown_handle: shutdown_handle.clone(),
+                            cancel_handle: LoginCancelHandle::Server(shutdown_handle.clone()),
                             login_id,
                         });
                     }
@@ -802,6 +1082,91 @@ impl CodexMessageProcessor {
         }
     }
 
+    async fn login_chatgpt_device_code_v2(&mut self, request_id: RequestId) {
+        let opts = match self.login_chatgpt_common().await {
+            Ok(opts) => opts,
+            Err(err) => {
+                self.outgoing.send_error(request_id, err).await;
+                return;
+            }
+        };
+
+        let login_id = Uuid::new_v4();
+        let response = codex_app_server_protocol::LoginAccountResponse::ChatgptDeviceCode {
+            login_id: login_id.to_string(),
+        };
+        self.outgoing.send_response(request_id, response).await;
+
+        // The code is announced from the task so that requesting it counts as
+        // part of the cancellable attempt.
+        let outgoing = self.outgoing.clone();
+        let flow = tokio::spawn(async move {
+            let device_code = request_device_code(&opts).await?;
+            outgoing
+                .send_server_notification(ServerNotification::AccountLoginDeviceCode(
+                    AccountLoginDeviceCodeNotification {
+                        login_id: login_id.to_string(),
+                        verification_url: device_code.verification_url.clone(),
+                        user_code: device_code.user_code.clone(),
+                        expires_in_secs: device_code.expires_in.as_secs(),
+                    },
+                ))
+                .await;
+            complete_device_code_login(opts, device_code).await
+        });
+
+        // Replace active login if present.
+        {
+            let mut guard = self.active_login.lock().await;
+            if let Some(existing) = guard.take() {
+                drop(existing);
+            }
+            *guard = Some(ActiveLogin {
+                cancel_handle: LoginCancelHandle::Task(flow.abort_handle()),
+                login_id,
+            });
+        }
+
+        // Spawn background task to monitor completion.
+        let outgoing_clone = self.outgoing.clone();
+        let active_login = self.active_login.clone();
+        let auth_manager = self.auth_manager.clone();
+        tokio::spawn(async move {
+            let (success, error_msg) = match flow.await {
+                Ok(Ok(())) => (true, None),
+                Ok(Err(err)) => (false, Some(format!("Device code login error: {err}"))),
+                Err(err) if err.is_cancelled() => (false, Some("Login was cancelled".to_string())),
+                Err(err) => (false, Some(format!("Device code login error: {err}"))),
+            };
+
+            let payload_v2 = AccountLoginCompletedNotification {
+                login_id: Some(login_id.to_string()),
+                success,
+                error: error_msg,
+            };
+            outgoing_clone
+                .send_server_notification(ServerNotification::AccountLoginCompleted(payload_v2))
+                .await;
+
+            if success {
+                auth_manager.reload();
+
+                let payload_v2 = AccountUpdatedNotification {
+                    auth_mode: auth_manager.auth().map(|a| a.mode),
+                };
+                outgoing_clone
+                    .send_server_notification(ServerNotification::AccountUpdated(payload_v2))
+                    .await;
+            }
+
+            // Clear the active login if it matches this attempt. It may have been replaced or cancelled.
+            let mut guard = active_login.lock().await;
+            if guard.as_ref().map(|l| l.login_id) == Some(login_id) {
+                *guard = None;
+            }
+        });
+    }
+
     async fn cancel_login_chatgpt_common(
         &mut self,
         login_id: Uuid,
@@ -991,29 +1356,12 @@ impl CodexMessageProcessor {
             return;
         }
 
-        let account = match self.auth_manager.auth() {
-            Some(auth) => Some(match auth.mode {
-                AuthMode::ApiKey => Account::ApiKey {},
-                AuthMode::ChatGPT => {
-                    let email = auth.get_account_email();
-                    let plan_type = auth.account_plan_type();
-
-                    match (email, plan_type) {
-                        (Some(email), Some(plan_type)) => Account::Chatgpt { email, plan_type },
-                        _ => {
-                            let error = JSONRPCErrorError {
-                                code: INVALID_REQUEST_ERROR_CODE,
-                                message:
-                                    "email and plan type are required for chatgpt authentication"
-                                        .to_string(),
-                                data: None,
-                            };
-                            self.outgoing.send_error(request_id, error).await;
-                            return;
-                        }
-                    }
-                }
-            }),
+        let account = match self.auth_manager.auth().as_ref().map(account_for_auth) {
+            Some(Ok(account)) => Some(account),
+            Some(Err(error)) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
             None => None,
         };
 
@@ -1024,6 +1372,148 @@ impl CodexMessageProcessor {
         self.outgoing.send_response(request_id, response).await;
     }
 
+    async fn list_accounts(&self, request_id: RequestId) {
+        let codex_home = self.config.codex_home.clone();
+        let mode = self.config.cli_auth_credentials_store_mode;
+        let stored = tokio::task::spawn_blocking(move || {
+            let default_auth = CodexAuth::from_auth_storage(&codex_home, mode)?;
+            let accounts = list_accounts(&codex_home, mode)?;
+            Ok::<_, std::io::Error>((default_auth, accounts))
+        })
+        .await
+        .map_err(std::io::Error::other)
+        .and_then(|stored| stored);
+        let (default_auth, accounts) = match stored {
+            Ok(stored) => stored,
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to read stored accounts: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let active = self.auth_manager.account();
+        let entries = std::iter::once((None, default_auth)).chain(
+            accounts
+                .into_iter()
+                .map(|account| (Some(account.label), Some(account.auth))),
+        );
+        let mut data = Vec::new();
+        for (label, auth) in entries {
+            // Accounts whose details cannot be read are still listed, so
+            // they can be switched to and logged in again.
+            let account = auth.as_ref().and_then(|auth| account_for_auth(auth).ok());
+            data.push(StoredAccount {
+                active: label == active,
+                label,
+                account,
+            });
+        }
+        self.outgoing
+            .send_response(request_id, AccountsListResponse { data })
+            .await;
+    }
+
+    async fn switch_account(&mut self, request_id: RequestId, params: AccountsSwitchParams) {
+        // A login in progress would store its credentials under the old account.
+        {
+            let mut guard = self.active_login.lock().await;
+            if let Some(active) = guard.take() {
+                drop(active);
+            }
+        }
+
+        if let Err(err) = self.auth_manager.switch_account(params.label) {
+            let code = if err.kind() == std::io::ErrorKind::InvalidInput {
+                INVALID_REQUEST_ERROR_CODE
+            } else {
+                INTERNAL_ERROR_CODE
+            };
+            let error = JSONRPCErrorError {
+                code,
+                message: format!("failed to switch account: {err}"),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        }
+
+        let auth = self.auth_manager.auth();
+        let account = match auth.as_ref().map(account_for_auth) {
+            Some(Ok(account)) => Some(account),
+            Some(Err(error)) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+            None => None,
+        };
+        self.outgoing
+            .send_response(request_id, AccountsSwitchResponse { account })
+            .await;
+
+        let payload = AccountUpdatedNotification {
+            auth_mode: auth.map(|auth| auth.mode),
+        };
+        self.outgoing
+            .send_server_notification(ServerNotification::AccountUpdated(payload))
+            .await;
+    }
+
+    async fn read_mcp_server_auth(&self, request_id: RequestId, params: McpServerAuthReadParams) {
+        let McpServerAuthReadParams { name } = params;
+        let Some(server) = self.config.mcp_servers.get(&name) else {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("no MCP server named `{name}` is configured"),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        };
+
+        let requested_scopes = match &server.transport {
+            McpServerTransportConfig::StreamableHttp { scopes, .. } => scopes.clone(),
+            McpServerTransportConfig::Stdio { .. } => None,
+        };
+        let token_info =
+            match oauth_token_info(&name, server, self.config.mcp_oauth_credentials_store_mode) {
+                Ok(token_info) => token_info,
+                Err(err) => {
+                    let error = JSONRPCErrorError {
+                        code: INTERNAL_ERROR_CODE,
+                        message: format!("failed to read OAuth credentials for `{name}`: {err}"),
+                        data: None,
+                    };
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            };
+
+        let response = match token_info {
+            Some(info) => McpServerAuthReadResponse {
+                requested_scopes,
+                logged_in: true,
+                granted_scopes: info.granted_scopes,
+                claims: info.claims,
+                expires_at: info
+                    .expires_at
+                    .and_then(|millis| i64::try_from(millis / 1000).ok()),
+            },
+            None => McpServerAuthReadResponse {
+                requested_scopes,
+                logged_in: false,
+                granted_scopes: None,
+                claims: None,
+                expires_at: None,
+            },
+        };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
     async fn get_user_agent(&self, request_id: RequestId) {
         let user_agent = get_codex_user_agent();
         let response = GetUserAgentResponse { user_agent };
@@ -1306,7 +1796,7 @@ impl CodexMessageProcessor {
     }
 
     async fn thread_start(&mut self, request_id: RequestId, params: ThreadStartParams) {
-        let overrides = self.build_thread_config_overrides(
+        let mut overrides = self.build_thread_config_overrides(
             params.model,
             params.model_provider,
             params.cwd,
@@ -1315,8 +1805,74 @@ impl CodexMessageProcessor {
             params.base_instructions,
             params.developer_instructions,
         );
+        overrides.tools_enabled = params.enabled_tools;
+        overrides.tools_disabled = params.disabled_tools;
+        overrides.account = params.account;
+
+        if let Err(error) = validate_env(params.env.as_ref()) {
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        }
+
+        self.start_thread(request_id, overrides, params.config, params.env, None)
+            .await;
+    }
+
+    async fn threads_create_from_template(
+        &mut self,
+        request_id: RequestId,
+        params: ThreadCreateFromTemplateParams,
+    ) {
+        let ThreadCreateFromTemplateParams {
+            template: name,
+            cwd,
+            model,
+            approval_policy,
+            sandbox,
+            config,
+        } = params;
+        let template = match load_template(&self.config, &name).await {
+            Ok(template) => template,
+            Err(message) => {
+                self.send_invalid_request_error(request_id, message).await;
+                return;
+            }
+        };
+
+        let mut overrides = self.build_thread_config_overrides(
+            model,
+            None,
+            cwd,
+            approval_policy,
+            sandbox,
+            None,
+            None,
+        );
+        apply_template(&template, &mut overrides);
+        let mut config = config.unwrap_or_default();
+        if let Some(effort) = template.model_reasoning_effort
+            && let Ok(effort) = serde_json::to_value(effort)
+        {
+            config
+                .entry("model_reasoning_effort".to_string())
+                .or_insert(effort);
+        }
+
+        self.start_thread(request_id, overrides, Some(config), None, Some(&template))
+            .await;
+    }
 
-        let config = match derive_config_from_params(overrides, params.config).await {
+    /// Creates a thread from `overrides`, pins the template's context when
+    /// there is one, and replies with the new thread.
+    async fn start_thread(
+        &mut self,
+        request_id: RequestId,
+        overrides: ConfigOverrides,
+        cli_overrides: Option<HashMap<String, serde_json::Value>>,
+        env: Option<HashMap<String, String>>,
+        template: Option<&ThreadTemplate>,
+    ) {
+        let mut config = match derive_config_from_params(overrides, cli_overrides).await {
             Ok(config) => config,
             Err(err) => {
                 let error = JSONRPCErrorError {
@@ -1329,6 +1885,22 @@ impl CodexMessageProcessor {
             }
         };
 
+        if let Some(env) = env {
+            config.shell_environment_policy.r#set.extend(env);
+        }
+
+        let pinned_context = template
+            .map(|template| resolve_pinned_context(template, &config.cwd))
+            .unwrap_or_default();
+        if let Some(missing) = pinned_context.iter().find(|path| !path.exists()) {
+            self.send_invalid_request_error(
+                request_id,
+                format!("pinned context path does not exist: {}", missing.display()),
+            )
+            .await;
+            return;
+        }
+
         match self.conversation_manager.new_conversation(config).await {
             Ok(new_conv) => {
                 let NewConversation {
@@ -1336,6 +1908,16 @@ impl CodexMessageProcessor {
                     session_configured,
                     ..
                 } = new_conv;
+                for path in &pinned_context {
+                    if let Err(err) =
+                        add_pinned_context(&self.config.codex_home, conversation_id, path).await
+                    {
+                        self.outgoing
+                            .send_error(request_id, pinned_context_error(err))
+                            .await;
+                        return;
+                    }
+                }
                 let rollout_path = session_configured.rollout_path.clone();
                 let fallback_provider = self.config.model_provider_id.as_str();
 
@@ -1347,7 +1929,7 @@ impl CodexMessageProcessor {
                 )
                 .await
                 {
-                    Ok(summary) => summary_to_thread(summary),
+                    Ok(summary) => summary_to_thread(summary).await,
                     Err(err) => {
                         self.send_internal_error(
                             request_id,
@@ -1435,8 +2017,10 @@ impl CodexMessageProcessor {
         }
     }
 
-    async fn thread_archive(&mut self, request_id: RequestId, params: ThreadArchiveParams) {
-        let conversation_id = match ConversationId::from_string(&params.thread_id) {
+    async fn thread_fork(&mut self, request_id: RequestId, params: ThreadForkParams) {
+        let ThreadForkParams { thread_id, item_id } = params;
+
+        let conversation_id = match ConversationId::from_string(&thread_id) {
             Ok(id) => id,
             Err(err) => {
                 let error = JSONRPCErrorError {
@@ -1449,7 +2033,7 @@ impl CodexMessageProcessor {
             }
         };
 
-        let rollout_path = match find_conversation_path_by_id_str(
+        let path = match find_conversation_path_by_id_str(
             &self.config.codex_home,
             &conversation_id.to_string(),
         )
@@ -1457,44 +2041,923 @@ impl CodexMessageProcessor {
         {
             Ok(Some(p)) => p,
             Ok(None) => {
-                let error = JSONRPCErrorError {
-                    code: INVALID_REQUEST_ERROR_CODE,
-                    message: format!("no rollout found for conversation id {conversation_id}"),
-                    data: None,
-                };
-                self.outgoing.send_error(request_id, error).await;
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("no rollout found for conversation id {conversation_id}"),
+                )
+                .await;
                 return;
             }
             Err(err) => {
-                let error = JSONRPCErrorError {
-                    code: INVALID_REQUEST_ERROR_CODE,
-                    message: format!("failed to locate conversation id {conversation_id}: {err}"),
-                    data: None,
-                };
-                self.outgoing.send_error(request_id, error).await;
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("failed to locate conversation id {conversation_id}: {err}"),
+                )
+                .await;
                 return;
             }
         };
 
-        match self
-            .archive_conversation_common(conversation_id, &rollout_path)
-            .await
-        {
-            Ok(()) => {
-                let response = ThreadArchiveResponse {};
-                self.outgoing.send_response(request_id, response).await;
-            }
+        let history = match RolloutRecorder::get_rollout_history(&path).await {
+            Ok(history) => history,
             Err(err) => {
-                self.outgoing.send_error(request_id, err).await;
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("failed to load rollout `{}`: {err}", path.display()),
+                )
+                .await;
+                return;
             }
-        }
-    }
+        };
 
-    async fn thread_list(&self, request_id: RequestId, params: ThreadListParams) {
-        let ThreadListParams {
-            cursor,
-            limit,
-            model_providers,
+        // Item ids are assigned while rebuilding turns from the rollout, the
+        // same way `thread/resume` does, so the ids the client saw map back to
+        // the same position here.
+        let turns = history
+            .get_event_msgs()
+            .as_deref()
+            .map_or_else(Vec::new, build_turns_from_event_msgs);
+        let Some(nth_user_message) = user_messages_before_fork_point(&turns, &item_id) else {
+            self.send_invalid_request_error(
+                request_id,
+                format!("no item {item_id} in thread {conversation_id}"),
+            )
+            .await;
+            return;
+        };
+        let total_user_messages = turns
+            .iter()
+            .flat_map(|turn| turn.items.iter())
+            .filter(|item| matches!(item, ThreadItem::UserMessage { .. }))
+            .count();
+
+        let config = self.config.as_ref().clone();
+        let fallback_model_provider = config.model_provider_id.clone();
+        // Copying the rollout prefix keeps any ghost snapshots recorded in
+        // it, so undo in the fork restores the same commits as the original.
+        let forked = if nth_user_message < total_user_messages {
+            self.conversation_manager
+                .fork_conversation(nth_user_message, config, path)
+                .await
+        } else {
+            self.conversation_manager
+                .resume_conversation_with_history(
+                    config,
+                    InitialHistory::Forked(history.get_rollout_items()),
+                    self.auth_manager.clone(),
+                )
+                .await
+        };
+
+        match forked {
+            Ok(NewConversation {
+                conversation_id,
+                session_configured,
+                ..
+            }) => {
+                let SessionConfiguredEvent {
+                    rollout_path,
+                    initial_messages,
+                    ..
+                } = session_configured;
+
+                let mut thread = match read_summary_from_rollout(
+                    rollout_path.as_path(),
+                    fallback_model_provider.as_str(),
+                )
+                .await
+                {
+                    Ok(summary) => summary_to_thread(summary).await,
+                    Err(err) => {
+                        self.send_internal_error(
+                            request_id,
+                            format!(
+                                "failed to load rollout `{}` for conversation {conversation_id}: {err}",
+                                rollout_path.display()
+                            ),
+                        )
+                        .await;
+                        return;
+                    }
+                };
+
+                // Auto-attach a conversation listener to the new thread.
+                if let Err(err) = self
+                    .attach_conversation_listener(conversation_id, false, ApiVersion::V2)
+                    .await
+                {
+                    tracing::warn!(
+                        "failed to attach listener for conversation {}: {}",
+                        conversation_id,
+                        err.message
+                    );
+                }
+
+                let notif = ThreadStartedNotification {
+                    thread: thread.clone(),
+                };
+                thread.turns = initial_messages
+                    .as_deref()
+                    .map_or_else(Vec::new, build_turns_from_event_msgs);
+
+                let response = ThreadForkResponse {
+                    thread,
+                    model: session_configured.model,
+                    model_provider: session_configured.model_provider_id,
+                    cwd: session_configured.cwd,
+                    approval_policy: session_configured.approval_policy.into(),
+                    sandbox: session_configured.sandbox_policy.into(),
+                    reasoning_effort: session_configured.reasoning_effort,
+                };
+
+                self.outgoing.send_response(request_id, response).await;
+                self.outgoing
+                    .send_server_notification(ServerNotification::ThreadStarted(notif))
+                    .await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("error forking thread: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn thread_archive(&mut self, request_id: RequestId, params: ThreadArchiveParams) {
+        let conversation_id = match ConversationId::from_string(&params.thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("invalid thread id: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let rollout_path = match find_conversation_path_by_id_str(
+            &self.config.codex_home,
+            &conversation_id.to_string(),
+        )
+        .await
+        {
+            Ok(Some(p)) => p,
+            Ok(None) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("no rollout found for conversation id {conversation_id}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("failed to locate conversation id {conversation_id}: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        match self
+            .archive_conversation_common(conversation_id, &rollout_path)
+            .await
+        {
+            Ok(()) => {
+                let response = ThreadArchiveResponse {};
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.outgoing.send_error(request_id, err).await;
+            }
+        }
+    }
+
+    async fn thread_export(&self, request_id: RequestId, params: ThreadExportParams) {
+        let ThreadExportParams {
+            thread_id,
+            format,
+            output_path,
+        } = params;
+        let conversation_id = match ConversationId::from_string(&thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("invalid thread id: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let rollout_path = match find_conversation_path_by_id_str(
+            &self.config.codex_home,
+            &conversation_id.to_string(),
+        )
+        .await
+        {
+            Ok(Some(p)) => p,
+            Ok(None) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("no rollout found for conversation id {conversation_id}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("failed to locate conversation id {conversation_id}: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        let format = match format {
+            ThreadExportFormat::Markdown => TranscriptFormat::Markdown,
+            ThreadExportFormat::Html => TranscriptFormat::Html,
+            ThreadExportFormat::Json => TranscriptFormat::Json,
+        };
+        match export_transcript(&rollout_path, format, &output_path).await {
+            Ok(entry_count) => {
+                let response = ThreadExportResponse {
+                    output_path,
+                    entry_count: u32::try_from(entry_count).unwrap_or(u32::MAX),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!(
+                        "failed to export conversation {conversation_id} to {}: {err}",
+                        output_path.display()
+                    ),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn thread_bundle_export(&self, request_id: RequestId, params: ThreadBundleExportParams) {
+        let ThreadBundleExportParams {
+            thread_id,
+            output_path,
+            include_exec_logs,
+        } = params;
+        let conversation_id = match ConversationId::from_string(&thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                self.send_invalid_request_error(request_id, format!("invalid thread id: {err}"))
+                    .await;
+                return;
+            }
+        };
+        let rollout_path = match find_conversation_path_by_id_str(
+            &self.config.codex_home,
+            &conversation_id.to_string(),
+        )
+        .await
+        {
+            Ok(Some(p)) => p,
+            Ok(None) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("no rollout found for conversation id {conversation_id}"),
+                )
+                .await;
+                return;
+            }
+            Err(err) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("failed to locate conversation id {conversation_id}: {err}"),
+                )
+                .await;
+                return;
+            }
+        };
+
+        match export_thread_bundle(
+            &self.config.codex_home,
+            &rollout_path,
+            &output_path,
+            include_exec_logs,
+        )
+        .await
+        {
+            Ok(summary) => {
+                let response = ThreadBundleExportResponse {
+                    output_path,
+                    exec_log_count: u32::try_from(summary.exec_log_count).unwrap_or(u32::MAX),
+                    redacted_secret_count: summary
+                        .redacted_secrets
+                        .iter()
+                        .map(|secret| secret.count)
+                        .sum(),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.send_internal_error(
+                    request_id,
+                    format!(
+                        "failed to export conversation {conversation_id} to {}: {err}",
+                        output_path.display()
+                    ),
+                )
+                .await;
+            }
+        }
+    }
+
+    async fn thread_bundle_import(&self, request_id: RequestId, params: ThreadBundleImportParams) {
+        let ThreadBundleImportParams { bundle_path } = params;
+        match import_thread_bundle(&self.config.codex_home, &bundle_path).await {
+            Ok(imported) => {
+                let response = ThreadBundleImportResponse {
+                    thread_id: imported.thread_id.to_string(),
+                    rollout_path: imported.rollout_path,
+                    exec_log_count: u32::try_from(imported.exec_log_count).unwrap_or(u32::MAX),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("failed to import {}: {err}", bundle_path.display()),
+                )
+                .await;
+            }
+        }
+    }
+
+    async fn execution_get_output(&self, request_id: RequestId, params: ExecutionGetOutputParams) {
+        let ExecutionGetOutputParams { thread_id, call_id } = params;
+        let conversation_id = match ConversationId::from_string(&thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("invalid thread id: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        match read_exec_output(&self.config.codex_home, &conversation_id, &call_id).await {
+            Ok(ArchivedExecOutput { stdout, stderr }) => {
+                let response = ExecutionGetOutputResponse { stdout, stderr };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    std::io::ErrorKind::NotFound | std::io::ErrorKind::InvalidInput
+                ) =>
+            {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("no archived output for call {call_id} in thread {thread_id}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to read archived output for call {call_id}: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn background_task_read(&self, request_id: RequestId, params: BackgroundTaskReadParams) {
+        let BackgroundTaskReadParams { thread_id, task_id } = params;
+        let conversation_id = match ConversationId::from_string(&thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("invalid thread id: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        match BackgroundTaskManager::shared().snapshot(conversation_id, &task_id) {
+            Ok(snapshot) => {
+                let BackgroundTaskSnapshot {
+                    command,
+                    status,
+                    exit_code,
+                    output,
+                    dropped_bytes,
+                    ..
+                } = snapshot;
+                let response = BackgroundTaskReadResponse {
+                    command,
+                    status: status.into(),
+                    exit_code,
+                    output,
+                    dropped_bytes,
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: err.to_string(),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn background_task_terminate(
+        &self,
+        request_id: RequestId,
+        params: BackgroundTaskTerminateParams,
+    ) {
+        let BackgroundTaskTerminateParams { thread_id, task_id } = params;
+        let conversation_id = match ConversationId::from_string(&thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("invalid thread id: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        match BackgroundTaskManager::shared().terminate(conversation_id, &task_id) {
+            Ok(()) => {
+                self.outgoing
+                    .send_response(request_id, BackgroundTaskTerminateResponse {})
+                    .await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: err.to_string(),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn workspace_instruction_list(
+        &self,
+        request_id: RequestId,
+        params: WorkspaceInstructionListParams,
+    ) {
+        match load_workspace_instructions(&params.cwd).await {
+            Ok(instructions) => {
+                let data = instructions
+                    .into_iter()
+                    .map(workspace_instruction_to_v2)
+                    .collect();
+                let response = WorkspaceInstructionListResponse { data };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.outgoing
+                    .send_error(request_id, workspace_instruction_error(err))
+                    .await;
+            }
+        }
+    }
+
+    async fn workspace_instruction_add(
+        &self,
+        request_id: RequestId,
+        params: WorkspaceInstructionAddParams,
+    ) {
+        let WorkspaceInstructionAddParams { cwd, text } = params;
+        match add_workspace_instruction(&cwd, &text).await {
+            Ok(instruction) => {
+                let response = WorkspaceInstructionAddResponse {
+                    instruction: workspace_instruction_to_v2(instruction),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                self.outgoing
+                    .send_error(request_id, workspace_instruction_error(err))
+                    .await;
+            }
+        }
+    }
+
+    async fn workspace_instruction_update(
+        &self,
+        request_id: RequestId,
+        params: WorkspaceInstructionUpdateParams,
+    ) {
+        let WorkspaceInstructionUpdateParams { cwd, id, text } = params;
+        match update_workspace_instruction(&cwd, &id, &text).await {
+            Ok(Some(instruction)) => {
+                let response = WorkspaceInstructionUpdateResponse {
+                    instruction: workspace_instruction_to_v2(instruction),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Ok(None) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("workspace instruction not found: {id}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+            Err(err) => {
+                self.outgoing
+                    .send_error(request_id, workspace_instruction_error(err))
+                    .await;
+            }
+        }
+    }
+
+    async fn workspace_instruction_remove(
+        &self,
+        request_id: RequestId,
+        params: WorkspaceInstructionRemoveParams,
+    ) {
+        let WorkspaceInstructionRemoveParams { cwd, id } = params;
+        match remove_workspace_instruction(&cwd, &id).await {
+            Ok(true) => {
+                let response = WorkspaceInstructionRemoveResponse {};
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Ok(false) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("workspace instruction not found: {id}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+            Err(err) => {
+                self.outgoing
+                    .send_error(request_id, workspace_instruction_error(err))
+                    .await;
+            }
+        }
+    }
+
+    async fn thread_context_add(&self, request_id: RequestId, params: ThreadContextAddParams) {
+        let ThreadContextAddParams { thread_id, path } = params;
+        let conversation_id = match ConversationId::from_string(&thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("invalid thread id: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        match add_pinned_context(&self.config.codex_home, conversation_id, &path).await {
+            Ok(paths) => {
+                self.outgoing
+                    .send_response(request_id, ThreadContextAddResponse { paths })
+                    .await;
+            }
+            Err(err) => {
+                self.outgoing
+                    .send_error(request_id, pinned_context_error(err))
+                    .await;
+            }
+        }
+    }
+
+    async fn thread_context_remove(
+        &self,
+        request_id: RequestId,
+        params: ThreadContextRemoveParams,
+    ) {
+        let ThreadContextRemoveParams { thread_id, path } = params;
+        let conversation_id = match ConversationId::from_string(&thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("invalid thread id: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        match remove_pinned_context(&self.config.codex_home, conversation_id, &path).await {
+            Ok(Some(paths)) => {
+                self.outgoing
+                    .send_response(request_id, ThreadContextRemoveResponse { paths })
+                    .await;
+            }
+            Ok(None) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("path is not pinned: {}", path.display()),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+            Err(err) => {
+                self.outgoing
+                    .send_error(request_id, pinned_context_error(err))
+                    .await;
+            }
+        }
+    }
+
+    async fn thread_context_list(&self, request_id: RequestId, params: ThreadContextListParams) {
+        let conversation_id = match ConversationId::from_string(&params.thread_id) {
+            Ok(id) => id,
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message: format!("invalid thread id: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        match list_pinned_context(&self.config.codex_home, conversation_id).await {
+            Ok(paths) => {
+                self.outgoing
+                    .send_response(request_id, ThreadContextListResponse { paths })
+                    .await;
+            }
+            Err(err) => {
+                self.outgoing
+                    .send_error(request_id, pinned_context_error(err))
+                    .await;
+            }
+        }
+    }
+
+    async fn thread_usage(&self, request_id: RequestId, params: ThreadUsageParams) {
+        let path = match find_conversation_path_by_id_str(
+            &self.config.codex_home,
+            &params.thread_id,
+        )
+        .await
+        {
+            Ok(Some(path)) => path,
+            Ok(None) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("no rollout found for thread id {}", params.thread_id),
+                )
+                .await;
+                return;
+            }
+            Err(err) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("failed to locate thread id {}: {err}", params.thread_id),
+                )
+                .await;
+                return;
+            }
+        };
+        let history = match RolloutRecorder::get_rollout_history(&path).await {
+            Ok(history) => history,
+            Err(err) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("failed to load rollout `{}`: {err}", path.display()),
+                )
+                .await;
+                return;
+            }
+        };
+
+        let rollout_items = history.get_rollout_items();
+        let requests = TokenUsageBreakdown::requests_from(rollout_items.iter().filter_map(
+            |item| match item {
+                RolloutItem::EventMsg(EventMsg::TokenCount(event)) => event.breakdown.as_ref(),
+                _ => None,
+            },
+        ));
+        let mut breakdown = TokenUsageBreakdown::default();
+        for request in &requests {
+            breakdown.record(request.clone());
+        }
+        let mut by_source: Vec<SourceTokenUsage> = breakdown
+            .by_source
+            .into_iter()
+            .map(|(source, usage)| SourceTokenUsage {
+                source,
+                usage: usage.into(),
+            })
+            .collect();
+        by_source.sort_by(|a, b| b.usage.total_tokens.cmp(&a.usage.total_tokens));
+        let response = ThreadUsageResponse {
+            by_source,
+            retries: breakdown.retries.into(),
+            requests: requests.into_iter().map(Into::into).collect(),
+        };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
+    async fn thread_template_list(&self, request_id: RequestId, _params: ThreadTemplateListParams) {
+        let data = list_templates(&self.config)
+            .await
+            .into_iter()
+            .map(thread_template_to_v2)
+            .collect();
+        self.outgoing
+            .send_response(request_id, ThreadTemplateListResponse { data })
+            .await;
+    }
+
+    async fn command_list(&self, request_id: RequestId, _params: CommandListParams) {
+        let data = discover_commands_in(&commands_dir(&self.config.codex_home))
+            .await
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        self.outgoing
+            .send_response(request_id, CommandListResponse { data })
+            .await;
+    }
+
+    async fn command_run(&self, request_id: RequestId, params: CommandRunParams) {
+        let CommandRunParams {
+            thread_id,
+            name,
+            args,
+        } = params;
+
+        let (_, conversation) = match self.conversation_from_thread_id(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        // The turn's progress, or an unknown-command error, arrives as events.
+        match conversation.submit(Op::RunCommand { name, args }).await {
+            Ok(_) => {
+                self.outgoing
+                    .send_response(request_id, CommandRunResponse {})
+                    .await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to run command: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn experiment_stats(&self, request_id: RequestId, params: ExperimentStatsParams) {
+        let ExperimentStatsParams { experiment } = params;
+        match experiment_stats(&self.config.codex_home, &experiment).await {
+            Ok(stats) => {
+                let variants = stats
+                    .into_iter()
+                    .map(experiment_variant_stats_to_v2)
+                    .collect();
+                let response = ExperimentStatsResponse { variants };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                let code = match err.kind() {
+                    std::io::ErrorKind::InvalidInput => INVALID_REQUEST_ERROR_CODE,
+                    _ => INTERNAL_ERROR_CODE,
+                };
+                let error = JSONRPCErrorError {
+                    code,
+                    message: format!("failed to read stats for experiment {experiment}: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn execution_assess(&self, request_id: RequestId, params: ExecutionAssessParams) {
+        let ExecutionAssessParams {
+            command,
+            cwd,
+            approval_policy,
+            sandbox,
+            config,
+            with_escalated_permissions,
+        } = params;
+        if command.is_empty() {
+            self.send_invalid_request_error(request_id, "command must not be empty".to_string())
+                .await;
+            return;
+        }
+
+        let overrides = self.build_thread_config_overrides(
+            None,
+            None,
+            cwd,
+            approval_policy,
+            sandbox,
+            None,
+            None,
+        );
+        let config = match derive_config_from_params(overrides, config).await {
+            Ok(config) => config,
+            Err(err) => {
+                self.send_invalid_request_error(
+                    request_id,
+                    format!("error deriving config: {err}"),
+                )
+                .await;
+                return;
+            }
+        };
+
+        match assess_command(&config, &command, with_escalated_permissions).await {
+            Ok(assessment) => {
+                let response = command_assessment_to_v2(assessment, &config);
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to load execpolicy: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn config_validate(&self, request_id: RequestId, params: ConfigValidateParams) {
+        let ConfigValidateParams { contents } = params;
+        let diagnostics = match contents {
+            Some(contents) => validate_config_toml(&contents),
+            None => match validate_config_file(&self.config.codex_home).await {
+                Ok(diagnostics) => diagnostics,
+                Err(err) => {
+                    let error = JSONRPCErrorError {
+                        code: INTERNAL_ERROR_CODE,
+                        message: format!("failed to read config.toml: {err}"),
+                        data: None,
+                    };
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            },
+        };
+        let response = ConfigValidateResponse {
+            diagnostics: diagnostics
+                .into_iter()
+                .map(config_diagnostic_to_v2)
+                .collect(),
+        };
+        self.outgoing.send_response(request_id, response).await;
+    }
+
+    async fn thread_list(&self, request_id: RequestId, params: ThreadListParams) {
+        let ThreadListParams {
+            cursor,
+            limit,
+            model_providers,
         } = params;
 
         let page_size = limit.unwrap_or(25).max(1) as usize;
@@ -1510,12 +2973,51 @@ impl CodexMessageProcessor {
             }
         };
 
-        let data = summaries.into_iter().map(summary_to_thread).collect();
+        let mut data = Vec::with_capacity(summaries.len());
+        for summary in summaries {
+            data.push(summary_to_thread(summary).await);
+        }
 
         let response = ThreadListResponse { data, next_cursor };
         self.outgoing.send_response(request_id, response).await;
     }
 
+    async fn threads_search(&self, request_id: RequestId, params: ThreadSearchParams) {
+        let ThreadSearchParams { query, limit } = params;
+        let limit = limit.unwrap_or(20).max(1) as usize;
+        let codex_home = self.config.codex_home.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let mut index = HistoryIndex::open(&codex_home)?;
+            index.sync()?;
+            index.search(&query, limit)
+        })
+        .await;
+
+        match result {
+            Ok(Ok(hits)) => {
+                let data = hits.into_iter().map(search_hit_to_result).collect();
+                let response = ThreadSearchResponse { data };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Ok(Err(err)) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to search threads: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("thread search task failed: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
     async fn thread_resume(&mut self, request_id: RequestId, params: ThreadResumeParams) {
         let ThreadResumeParams {
             thread_id,
@@ -1529,6 +3031,7 @@ impl CodexMessageProcessor {
             config: cli_overrides,
             base_instructions,
             developer_instructions,
+            replay_history,
         } = params;
 
         let overrides_requested = model.is_some()
@@ -1681,7 +3184,7 @@ impl CodexMessageProcessor {
                 )
                 .await
                 {
-                    Ok(summary) => summary_to_thread(summary),
+                    Ok(summary) => summary_to_thread(summary).await,
                     Err(err) => {
                         self.send_internal_error(
                             request_id,
@@ -1707,8 +3210,26 @@ impl CodexMessageProcessor {
                     sandbox: session_configured.sandbox_policy.into(),
                     reasoning_effort: session_configured.reasoning_effort,
                 };
+                let replayed_items: Vec<ThreadItem> = if replay_history {
+                    response
+                        .thread
+                        .turns
+                        .iter()
+                        .flat_map(|turn| turn.items.iter().cloned())
+                        .collect()
+                } else {
+                    Vec::new()
+                };
 
                 self.outgoing.send_response(request_id, response).await;
+
+                for item in replayed_items {
+                    self.outgoing
+                        .send_server_notification(ServerNotification::ItemCompleted(
+                            ItemCompletedNotification { item },
+                        ))
+                        .await;
+                }
             }
             Err(err) => {
                 let error = JSONRPCErrorError {
@@ -2293,7 +3814,12 @@ impl CodexMessageProcessor {
                 .codex_home
                 .join(codex_core::ARCHIVED_SESSIONS_SUBDIR);
             tokio::fs::create_dir_all(&archive_folder).await?;
-            tokio::fs::rename(&canonical_rollout_path, &archive_folder.join(&file_name)).await?;
+            let archived_path = archive_folder.join(&file_name);
+            tokio::fs::rename(&canonical_rollout_path, &archived_path).await?;
+            let metadata_path = thread_metadata_path(&canonical_rollout_path);
+            if tokio::fs::try_exists(&metadata_path).await? {
+                tokio::fs::rename(&metadata_path, thread_metadata_path(&archived_path)).await?;
+            }
             Ok(())
         }
         .await;
@@ -2431,21 +3957,151 @@ impl CodexMessageProcessor {
         let _ = conversation.submit(Op::Interrupt).await;
     }
 
-    async fn turn_start(&self, request_id: RequestId, params: TurnStartParams) {
-        let (_, conversation) = match self.conversation_from_thread_id(&params.thread_id).await {
-            Ok(v) => v,
-            Err(error) => {
-                self.outgoing.send_error(request_id, error).await;
+    async fn get_patch_content(&self, request_id: RequestId, params: GetPatchContentParams) {
+        let GetPatchContentParams {
+            conversation_id,
+            call_id,
+            path,
+        } = params;
+        let Ok(conversation) = self
+            .conversation_manager
+            .get_conversation(conversation_id)
+            .await
+        else {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("conversation not found: {conversation_id}"),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        };
+
+        // Record the request before submitting so the reply, which arrives as
+        // a PatchContentResponse event under this id, cannot be missed.
+        let sub_id = Uuid::new_v4().to_string();
+        self.pending_patch_contents
+            .lock()
+            .await
+            .insert(sub_id.clone(), request_id.clone());
+        let submission = Submission {
+            id: sub_id.clone(),
+            op: Op::GetPatchContent { call_id, path },
+        };
+        if let Err(err) = conversation.submit_with_id(submission).await {
+            self.pending_patch_contents.lock().await.remove(&sub_id);
+            let error = JSONRPCErrorError {
+                code: INTERNAL_ERROR_CODE,
+                message: format!("failed to get patch content: {err}"),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+        }
+    }
+
+    async fn notifications_subscribe(
+        &self,
+        request_id: RequestId,
+        params: NotificationsSubscribeParams,
+    ) {
+        let NotificationsSubscribeParams {
+            thread_ids,
+            kinds,
+            include_deltas,
+        } = params;
+        let thread_ids = match thread_ids
+            .map(|ids| {
+                ids.iter()
+                    .map(|id| ConversationId::from_string(id))
+                    .collect::<Result<HashSet<_>, _>>()
+            })
+            .transpose()
+        {
+            Ok(thread_ids) => thread_ids,
+            Err(err) => {
+                self.send_invalid_request_error(request_id, format!("invalid thread id: {err}"))
+                    .await;
                 return;
             }
         };
+        self.outgoing.set_notification_filter(NotificationFilter {
+            thread_ids,
+            kinds,
+            skip_deltas: !include_deltas.unwrap_or(true),
+        });
+        self.outgoing
+            .send_response(request_id, NotificationsSubscribeResponse {})
+            .await;
+    }
+
+    async fn protocol_describe(&self, request_id: RequestId, params: ProtocolDescribeParams) {
+        let ProtocolDescribeParams { include_typescript } = params;
+        // Generation writes to a scratch directory, so keep it off the runtime.
+        let definitions = match tokio::task::spawn_blocking(move || {
+            describe_protocol(include_typescript)
+        })
+        .await
+        {
+            Ok(definitions) => definitions,
+            Err(err) => Err(err.into()),
+        };
+        match definitions {
+            Ok(definitions) => {
+                let response = ProtocolDescribeResponse {
+                    server_version: env!("CARGO_PKG_VERSION").to_string(),
+                    api_versions: vec!["v1".to_string(), "v2".to_string()],
+                    json_schema: definitions.json_schema,
+                    typescript: include_typescript
+                        .then(|| definitions.typescript.into_iter().collect()),
+                };
+                self.outgoing.send_response(request_id, response).await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to describe protocol: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn turn_start(&self, request_id: RequestId, params: TurnStartParams) {
+        let (conversation_id, conversation) =
+            match self.conversation_from_thread_id(&params.thread_id).await {
+                Ok(v) => v,
+                Err(error) => {
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            };
 
-        // Map v2 input items to core input items.
-        let mapped_items: Vec<CoreInputItem> = params
-            .input
-            .into_iter()
-            .map(V2UserInput::into_core)
-            .collect();
+        // Map v2 input items to core input items, validating and downscaling
+        // attached images off the async runtime.
+        let input = params.input;
+        let mapped_items: Vec<CoreInputItem> =
+            match tokio::task::spawn_blocking(move || prepare_turn_input(input)).await {
+                Ok(Ok(items)) => items,
+                Ok(Err(message)) => {
+                    let error = JSONRPCErrorError {
+                        code: INVALID_REQUEST_ERROR_CODE,
+                        message,
+                        data: None,
+                    };
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+                Err(err) => {
+                    let error = JSONRPCErrorError {
+                        code: INTERNAL_ERROR_CODE,
+                        message: format!("failed to prepare turn input: {err}"),
+                        data: None,
+                    };
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            };
 
         let has_any_overrides = params.cwd.is_some()
             || params.approval_policy.is_some()
@@ -2454,51 +4110,89 @@ impl CodexMessageProcessor {
             || params.effort.is_some()
             || params.summary.is_some();
 
-        // If any overrides are provided, update the session turn context first.
+        if let Err(error) = validate_env(params.env.as_ref()) {
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        }
+
+        // Overrides update the session turn context right before the input
+        // is submitted, which may be later if the turn has to wait for a slot.
+        let mut overrides = Vec::new();
         if has_any_overrides {
-            let _ = conversation
-                .submit(Op::OverrideTurnContext {
-                    cwd: params.cwd,
-                    approval_policy: params.approval_policy.map(AskForApproval::to_core),
-                    sandbox_policy: params.sandbox_policy.map(|p| p.to_core()),
-                    model: params.model,
-                    effort: params.effort.map(Some),
-                    summary: params.summary,
-                })
-                .await;
+            overrides.push(Op::OverrideTurnContext {
+                cwd: params.cwd,
+                approval_policy: params.approval_policy.map(AskForApproval::to_core),
+                sandbox_policy: params.sandbox_policy.map(|p| p.to_core()),
+                model: params.model,
+                effort: params.effort.map(Some),
+                summary: params.summary,
+            });
+        }
+        if let Some(env) = params.env {
+            overrides.push(Op::SetEnvironment { env });
         }
 
-        // Start the turn by submitting the user input. Return its submission id as turn_id.
-        let turn_id = conversation
-            .submit(Op::UserInput {
+        let turn = QueuedTurn {
+            conversation_id,
+            conversation,
+            turn_id: Uuid::new_v4().to_string(),
+            overrides,
+            input: Op::UserInput {
                 items: mapped_items,
-            })
-            .await;
+            },
+        };
+        let turn_id = turn.turn_id.clone();
+        let in_progress = Turn {
+            id: turn_id.clone(),
+            items: vec![],
+            status: TurnStatus::InProgress,
+            commit: None,
+            diff: None,
+        };
 
-        match turn_id {
-            Ok(turn_id) => {
-                let turn = Turn {
-                    id: turn_id.clone(),
-                    items: vec![],
-                    status: TurnStatus::InProgress,
-                };
+        match self.turn_queue.admit(turn) {
+            Admission::Start(turn) => match turn.submit().await {
+                Ok(()) => {
+                    let response = TurnStartResponse {
+                        turn: in_progress.clone(),
+                    };
+                    self.outgoing.send_response(request_id, response).await;
 
-                let response = TurnStartResponse { turn: turn.clone() };
+                    // Emit v2 turn/started notification.
+                    let notif = TurnStartedNotification { turn: in_progress };
+                    in_thread(
+                        conversation_id,
+                        self.outgoing
+                            .send_server_notification(ServerNotification::TurnStarted(notif)),
+                    )
+                    .await;
+                }
+                Err(err) => {
+                    let error = JSONRPCErrorError {
+                        code: INTERNAL_ERROR_CODE,
+                        message: format!("failed to start turn: {err}"),
+                        data: None,
+                    };
+                    self.outgoing.send_error(request_id, error).await;
+                    let released = self.turn_queue.abandon(conversation_id, &turn_id);
+                    start_turns(&self.turn_queue, released, &self.outgoing).await;
+                }
+            },
+            Admission::Queued { position } => {
+                let response = TurnStartResponse { turn: in_progress };
                 self.outgoing.send_response(request_id, response).await;
 
-                // Emit v2 turn/started notification.
-                let notif = TurnStartedNotification { turn };
-                self.outgoing
-                    .send_server_notification(ServerNotification::TurnStarted(notif))
-                    .await;
-            }
-            Err(err) => {
-                let error = JSONRPCErrorError {
-                    code: INTERNAL_ERROR_CODE,
-                    message: format!("failed to start turn: {err}"),
-                    data: None,
+                let notif = TurnQueuedNotification {
+                    thread_id: conversation_id.to_string(),
+                    turn_id,
+                    position: u32::try_from(position).unwrap_or(u32::MAX),
                 };
-                self.outgoing.send_error(request_id, error).await;
+                in_thread(
+                    conversation_id,
+                    self.outgoing
+                        .send_server_notification(ServerNotification::TurnQueued(notif)),
+                )
+                .await;
             }
         }
     }
@@ -2509,13 +4203,14 @@ impl CodexMessageProcessor {
             target,
             append_to_original_thread,
         } = params;
-        let (_, conversation) = match self.conversation_from_thread_id(&thread_id).await {
-            Ok(v) => v,
-            Err(error) => {
-                self.outgoing.send_error(request_id, error).await;
-                return;
-            }
-        };
+        let (conversation_id, conversation) =
+            match self.conversation_from_thread_id(&thread_id).await {
+                Ok(v) => v,
+                Err(error) => {
+                    self.outgoing.send_error(request_id, error).await;
+                    return;
+                }
+            };
 
         let (review_request, display_text) =
             match Self::review_request_from_target(target, append_to_original_thread) {
@@ -2541,14 +4236,19 @@ impl CodexMessageProcessor {
                     id: turn_id.clone(),
                     items,
                     status: TurnStatus::InProgress,
+                    commit: None,
+                    diff: None,
                 };
                 let response = TurnStartResponse { turn: turn.clone() };
                 self.outgoing.send_response(request_id, response).await;
 
                 let notif = TurnStartedNotification { turn };
-                self.outgoing
-                    .send_server_notification(ServerNotification::TurnStarted(notif))
-                    .await;
+                in_thread(
+                    conversation_id,
+                    self.outgoing
+                        .send_server_notification(ServerNotification::TurnStarted(notif)),
+                )
+                .await;
             }
             Err(err) => {
                 let error = JSONRPCErrorError {
@@ -2562,7 +4262,7 @@ impl CodexMessageProcessor {
     }
 
     async fn turn_interrupt(&mut self, request_id: RequestId, params: TurnInterruptParams) {
-        let TurnInterruptParams { thread_id, .. } = params;
+        let TurnInterruptParams { thread_id, turn_id } = params;
 
         let (conversation_id, conversation) =
             match self.conversation_from_thread_id(&thread_id).await {
@@ -2573,6 +4273,23 @@ impl CodexMessageProcessor {
                 }
             };
 
+        // A turn still waiting for a slot is simply dropped from the queue.
+        if let Some(turn) = self.turn_queue.cancel(conversation_id, &turn_id) {
+            self.outgoing
+                .send_response(request_id, TurnInterruptResponse {})
+                .await;
+            emit_turn_completed_with_status(
+                turn.turn_id,
+                TurnStatus::Interrupted,
+                None,
+                None,
+                None,
+                &self.outgoing,
+            )
+            .await;
+            return;
+        }
+
         // Record the pending interrupt so we can reply when TurnAborted arrives.
         {
             let mut map = self.pending_interrupts.lock().await;
@@ -2585,6 +4302,148 @@ impl CodexMessageProcessor {
         let _ = conversation.submit(Op::Interrupt).await;
     }
 
+    async fn thread_set_profile(&self, request_id: RequestId, params: ThreadSetProfileParams) {
+        let ThreadSetProfileParams { thread_id, profile } = params;
+
+        let (_, conversation) = match self.conversation_from_thread_id(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        // The outcome arrives as `thread/profileChanged` or an error event.
+        match conversation.submit(Op::SetProfile { profile }).await {
+            Ok(_) => {
+                self.outgoing
+                    .send_response(request_id, ThreadSetProfileResponse {})
+                    .await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to set profile: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn thread_set_terminal_size(
+        &self,
+        request_id: RequestId,
+        params: ThreadSetTerminalSizeParams,
+    ) {
+        let ThreadSetTerminalSizeParams {
+            thread_id,
+            rows,
+            cols,
+        } = params;
+
+        let (_, conversation) = match self.conversation_from_thread_id(&thread_id).await {
+            Ok(v) => v,
+            Err(error) => {
+                self.outgoing.send_error(request_id, error).await;
+                return;
+            }
+        };
+
+        match conversation
+            .submit(Op::SetTerminalSize { rows, cols })
+            .await
+        {
+            Ok(_) => {
+                self.outgoing
+                    .send_response(request_id, ThreadSetTerminalSizeResponse {})
+                    .await;
+            }
+            Err(err) => {
+                let error = JSONRPCErrorError {
+                    code: INTERNAL_ERROR_CODE,
+                    message: format!("failed to set terminal size: {err}"),
+                    data: None,
+                };
+                self.outgoing.send_error(request_id, error).await;
+            }
+        }
+    }
+
+    async fn thread_rename(&self, request_id: RequestId, params: ThreadRenameParams) {
+        let ThreadRenameParams { thread_id, title } = params;
+
+        let Some(title) = normalize_title(&title) else {
+            self.send_invalid_request_error(request_id, "title must not be empty".to_string())
+                .await;
+            return;
+        };
+
+        // A loaded thread saves the title itself; the outcome arrives as
+        // `thread/metadataChanged` or an error event.
+        if let Ok((_, conversation)) = self.conversation_from_thread_id(&thread_id).await {
+            match conversation.submit(Op::SetThreadTitle { title }).await {
+                Ok(_) => {
+                    self.outgoing
+                        .send_response(request_id, ThreadRenameResponse {})
+                        .await;
+                }
+                Err(err) => {
+                    self.send_internal_error(request_id, format!("failed to rename thread: {err}"))
+                        .await;
+                }
+            }
+            return;
+        }
+
+        let rollout_path =
+            match find_conversation_path_by_id_str(&self.config.codex_home, &thread_id).await {
+                Ok(Some(path)) => path,
+                Ok(None) => {
+                    self.send_invalid_request_error(
+                        request_id,
+                        format!("no rollout found for thread id {thread_id}"),
+                    )
+                    .await;
+                    return;
+                }
+                Err(err) => {
+                    self.send_invalid_request_error(
+                        request_id,
+                        format!("failed to locate thread id {thread_id}: {err}"),
+                    )
+                    .await;
+                    return;
+                }
+            };
+        let result = async {
+            let mut metadata = read_thread_metadata(&rollout_path).await?;
+            metadata.title = Some(title);
+            write_thread_metadata(&rollout_path, &metadata).await?;
+            std::io::Result::Ok(metadata)
+        }
+        .await;
+        match result {
+            Ok(metadata) => {
+                self.outgoing
+                    .send_response(request_id, ThreadRenameResponse {})
+                    .await;
+                self.outgoing
+                    .send_server_notification(ServerNotification::ThreadMetadataChanged(
+                        ThreadMetadataChangedNotification {
+                            thread_id,
+                            title: metadata.title,
+                        },
+                    ))
+                    .await;
+            }
+            Err(err) => {
+                self.send_internal_error(request_id, format!("failed to rename thread: {err}"))
+                    .await;
+            }
+        }
+    }
+
     async fn add_conversation_listener(
         &mut self,
         request_id: RequestId,
@@ -2660,7 +4519,10 @@ impl CodexMessageProcessor {
 
         let outgoing_for_task = self.outgoing.clone();
         let pending_interrupts = self.pending_interrupts.clone();
+        let pending_patch_contents = self.pending_patch_contents.clone();
         let turn_summary_store = self.turn_summary_store.clone();
+        let shutdown_tracker = self.shutdown_tracker.clone();
+        let turn_queue = self.turn_queue.clone();
         let api_version_for_task = api_version;
         tokio::spawn(async move {
             loop {
@@ -2704,23 +4566,30 @@ impl CodexMessageProcessor {
                             conversation_id.to_string().into(),
                         );
 
-                        outgoing_for_task
-                            .send_notification(OutgoingNotification {
-                                method,
-                                params: Some(params.into()),
-                            })
-                            .await;
+                        in_thread(conversation_id, async {
+                            outgoing_for_task
+                                .send_notification(OutgoingNotification {
+                                    method,
+                                    params: Some(params.into()),
+                                })
+                                .await;
 
-                        apply_bespoke_event_handling(
-                            event.clone(),
-                            conversation_id,
-                            conversation.clone(),
-                            outgoing_for_task.clone(),
-                            pending_interrupts.clone(),
-                            turn_summary_store.clone(),
-                            api_version_for_task,
-                        )
+                            apply_bespoke_event_handling(
+                                event.clone(),
+                                conversation_id,
+                                conversation.clone(),
+                                outgoing_for_task.clone(),
+                                pending_interrupts.clone(),
+                                pending_patch_contents.clone(),
+                                turn_summary_store.clone(),
+                                api_version_for_task,
+                            )
+                            .await;
+                        })
                         .await;
+                        shutdown_tracker.observe(conversation_id, &event.msg);
+                        let released = turn_queue.observe(conversation_id, &event);
+                        start_turns(&turn_queue, released, &outgoing_for_task).await;
                     }
                 }
             }
@@ -2874,6 +4743,7 @@ impl CodexMessageProcessor {
             self.config.sandbox_policy,
             codex_protocol::protocol::SandboxPolicy::WorkspaceWrite { .. }
                 | codex_protocol::protocol::SandboxPolicy::ReadOnly
+                | codex_protocol::protocol::SandboxPolicy::Audit
         ) {
             return;
         }
@@ -3025,6 +4895,35 @@ fn extract_conversation_summary(
     })
 }
 
+fn account_for_auth(auth: &CodexAuth) -> Result<Account, JSONRPCErrorError> {
+    match auth.mode {
+        AuthMode::ApiKey => Ok(Account::ApiKey {}),
+        AuthMode::ChatGPT => match (auth.get_account_email(), auth.account_plan_type()) {
+            (Some(email), Some(plan_type)) => Ok(Account::Chatgpt { email, plan_type }),
+            _ => Err(JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: "email and plan type are required for chatgpt authentication".to_string(),
+                data: None,
+            }),
+        },
+    }
+}
+
+fn validate_env(env: Option<&HashMap<String, String>>) -> Result<(), JSONRPCErrorError> {
+    match env
+        .into_iter()
+        .flat_map(HashMap::keys)
+        .find(|name| !is_valid_env_var_name(name))
+    {
+        Some(name) => Err(JSONRPCErrorError {
+            code: INVALID_REQUEST_ERROR_CODE,
+            message: format!("invalid environment variable name `{name}`"),
+            data: None,
+        }),
+        None => Ok(()),
+    }
+}
+
 fn map_git_info(git_info: &GitInfo) -> ConversationGitInfo {
     ConversationGitInfo {
         sha: git_info.commit_hash.clone(),
@@ -3041,7 +4940,7 @@ fn parse_datetime(timestamp: Option<&str>) -> Option<DateTime<Utc>> {
     })
 }
 
-fn summary_to_thread(summary: ConversationSummary) -> Thread {
+async fn summary_to_thread(summary: ConversationSummary) -> Thread {
     let ConversationSummary {
         conversation_id,
         path,
@@ -3052,10 +4951,18 @@ fn summary_to_thread(summary: ConversationSummary) -> Thread {
     } = summary;
 
     let created_at = parse_datetime(timestamp.as_deref());
+    let title = match read_thread_metadata(&path).await {
+        Ok(metadata) => metadata.title,
+        Err(err) => {
+            warn!("failed to read metadata of thread {conversation_id}: {err}");
+            None
+        }
+    };
 
     Thread {
         id: conversation_id.to_string(),
         preview,
+        title,
         model_provider,
         created_at: created_at.map(|dt| dt.timestamp()).unwrap_or(0),
         path,
@@ -3063,6 +4970,170 @@ fn summary_to_thread(summary: ConversationSummary) -> Thread {
     }
 }
 
+fn search_hit_to_result(hit: ThreadSearchHit) -> ThreadSearchResult {
+    let ThreadSearchHit {
+        thread_id,
+        path,
+        cwd,
+        started_at,
+        matches,
+    } = hit;
+    let matches = matches
+        .into_iter()
+        .map(|item| ThreadSearchMatch {
+            kind: match item.kind {
+                IndexedItemKind::UserMessage => ThreadSearchMatchKind::UserMessage,
+                IndexedItemKind::AgentMessage => ThreadSearchMatchKind::AgentMessage,
+                IndexedItemKind::Reasoning => ThreadSearchMatchKind::Reasoning,
+                IndexedItemKind::Command => ThreadSearchMatchKind::Command,
+                IndexedItemKind::Patch => ThreadSearchMatchKind::Patch,
+                IndexedItemKind::ToolCall => ThreadSearchMatchKind::ToolCall,
+                IndexedItemKind::WebSearch => ThreadSearchMatchKind::WebSearch,
+            },
+            timestamp: item.timestamp,
+            snippet: item.snippet,
+        })
+        .collect();
+    ThreadSearchResult {
+        thread_id,
+        path,
+        cwd,
+        started_at,
+        matches,
+    }
+}
+
+fn workspace_instruction_to_v2(instruction: CoreWorkspaceInstruction) -> WorkspaceInstruction {
+    let CoreWorkspaceInstruction { id, text } = instruction;
+    WorkspaceInstruction { id, text }
+}
+
+fn workspace_instruction_error(err: std::io::Error) -> JSONRPCErrorError {
+    let code = match err.kind() {
+        std::io::ErrorKind::InvalidInput => INVALID_REQUEST_ERROR_CODE,
+        _ => INTERNAL_ERROR_CODE,
+    };
+    JSONRPCErrorError {
+        code,
+        message: format!("workspace instructions: {err}"),
+        data: None,
+    }
+}
+
+fn thread_template_to_v2(loaded: LoadedTemplate) -> V2ThreadTemplate {
+    let LoadedTemplate {
+        name,
+        path,
+        template,
+        errors,
+    } = loaded;
+    V2ThreadTemplate {
+        name,
+        path,
+        description: template.description,
+        model: template.model,
+        model_provider: template.model_provider,
+        reasoning_effort: template.model_reasoning_effort,
+        approval_policy: template.approval_policy.map(Into::into),
+        sandbox: template.sandbox_mode.map(Into::into),
+        developer_instructions: template.developer_instructions,
+        pinned_context: template.pinned_context,
+        enabled_tools: template.tools_enabled,
+        disabled_tools: template.tools_disabled,
+        errors,
+    }
+}
+
+fn pinned_context_error(err: std::io::Error) -> JSONRPCErrorError {
+    let code = match err.kind() {
+        std::io::ErrorKind::InvalidInput => INVALID_REQUEST_ERROR_CODE,
+        _ => INTERNAL_ERROR_CODE,
+    };
+    JSONRPCErrorError {
+        code,
+        message: format!("pinned context: {err}"),
+        data: None,
+    }
+}
+
+fn experiment_variant_stats_to_v2(stats: CoreExperimentVariantStats) -> ExperimentVariantStats {
+    let CoreExperimentVariantStats {
+        variant,
+        conversations,
+        turns,
+        successful_turns,
+        success_rate,
+        total_tokens,
+        average_turns_per_conversation,
+    } = stats;
+    ExperimentVariantStats {
+        variant,
+        conversations,
+        turns,
+        successful_turns,
+        success_rate,
+        total_tokens,
+        average_turns_per_conversation,
+    }
+}
+
+fn config_diagnostic_to_v2(diagnostic: CoreConfigDiagnostic) -> ConfigDiagnostic {
+    let CoreConfigDiagnostic {
+        severity,
+        path,
+        message,
+        line,
+        column,
+    } = diagnostic;
+    ConfigDiagnostic {
+        severity: match severity {
+            DiagnosticSeverity::Error => ConfigDiagnosticSeverity::Error,
+            DiagnosticSeverity::Warning => ConfigDiagnosticSeverity::Warning,
+        },
+        path,
+        message,
+        line: u32::try_from(line).unwrap_or(u32::MAX),
+        column: u32::try_from(column).unwrap_or(u32::MAX),
+    }
+}
+
+fn command_assessment_to_v2(
+    assessment: CommandAssessment,
+    config: &Config,
+) -> ExecutionAssessResponse {
+    let CommandAssessment {
+        policy_decision,
+        approved_by_rule,
+        approval,
+        sandbox,
+        scrubbed_env_vars,
+    } = assessment;
+    ExecutionAssessResponse {
+        policy_decision: policy_decision.map(|decision| match decision {
+            Decision::Allow => ExecPolicyDecision::Allow,
+            Decision::Prompt => ExecPolicyDecision::Prompt,
+            Decision::Forbidden => ExecPolicyDecision::Forbidden,
+        }),
+        approved_by_rule,
+        approval: match approval {
+            ApprovalOutcome::Skip => ExecutionApproval::Skip,
+            ApprovalOutcome::NeedsApproval { reason } => {
+                ExecutionApproval::NeedsApproval { reason }
+            }
+            ApprovalOutcome::Forbidden { reason } => ExecutionApproval::Forbidden { reason },
+        },
+        sandbox: match sandbox {
+            SandboxType::None => ExecutionSandbox::None,
+            SandboxType::MacosSeatbelt => ExecutionSandbox::MacosSeatbelt,
+            SandboxType::LinuxSeccomp => ExecutionSandbox::LinuxSeccomp,
+            SandboxType::WindowsRestrictedToken => ExecutionSandbox::WindowsRestrictedToken,
+        },
+        scrubbed_env_vars,
+        approval_policy: config.approval_policy.into(),
+        sandbox_policy: config.sandbox_policy.clone().into(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
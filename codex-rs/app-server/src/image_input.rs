@@ -0,0 +1,117 @@
+//! Validation and downscaling of images attached to `turn/start`.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use codex_app_server_protocol::UserInput as V2UserInput;
+use codex_protocol::user_input::UserInput as CoreInputItem;
+use codex_utils_image::load_and_resize_to_fit;
+use codex_utils_image::resize_to_fit;
+
+/// Largest image accepted before downscaling, matching the Responses API.
+pub(crate) const MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+
+/// Map `turn/start` input to core input. Local images and base64 `data:` URLs
+/// must be PNG or JPEG of at most [`MAX_IMAGE_BYTES`]. Data URLs are
+/// downscaled here so the rollout keeps the image that was sent; local images
+/// are downscaled when the request is built. Remote URLs are passed through.
+pub(crate) fn prepare_turn_input(input: Vec<V2UserInput>) -> Result<Vec<CoreInputItem>, String> {
+    input.into_iter().map(prepare_item).collect()
+}
+
+fn prepare_item(item: V2UserInput) -> Result<CoreInputItem, String> {
+    match item {
+        V2UserInput::Image { url } if url.starts_with("data:") => {
+            let bytes = decode_data_url(&url)?;
+            let image = resize_to_fit(bytes).map_err(|err| format!("invalid image: {err}"))?;
+            Ok(CoreInputItem::Image {
+                image_url: image.into_data_url(),
+            })
+        }
+        V2UserInput::LocalImage { path } => {
+            let len = std::fs::metadata(&path)
+                .map_err(|err| format!("failed to read image at {}: {err}", path.display()))?
+                .len();
+            check_size(usize::try_from(len).unwrap_or(usize::MAX))?;
+            load_and_resize_to_fit(&path).map_err(|err| err.to_string())?;
+            Ok(CoreInputItem::LocalImage { path })
+        }
+        other => Ok(other.into_core()),
+    }
+}
+
+fn decode_data_url(url: &str) -> Result<Vec<u8>, String> {
+    let Some((header, data)) = url.split_once(',') else {
+        return Err("malformed image data URL".to_string());
+    };
+    if !header.ends_with(";base64") {
+        return Err("image data URLs must be base64-encoded".to_string());
+    }
+    // Reject oversized payloads before decoding them.
+    check_size(data.len() / 4 * 3)?;
+    let bytes = BASE64_STANDARD
+        .decode(data.trim())
+        .map_err(|err| format!("invalid base64 image data: {err}"))?;
+    check_size(bytes.len())?;
+    Ok(bytes)
+}
+
+fn check_size(len: usize) -> Result<(), String> {
+    if len > MAX_IMAGE_BYTES {
+        return Err(format!(
+            "image is {len} bytes; the limit is {MAX_IMAGE_BYTES} bytes"
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    // 1x1 transparent PNG.
+    const PIXEL_PNG: &str = "iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNkYPhfDwAChwGA60e6kgAAAABJRU5ErkJggg==";
+
+    #[test]
+    fn data_url_images_are_reencoded() {
+        let items = prepare_turn_input(vec![V2UserInput::Image {
+            url: format!("data:image/png;base64,{PIXEL_PNG}"),
+        }])
+        .expect("valid image");
+        assert_eq!(
+            items,
+            vec![CoreInputItem::Image {
+                image_url: format!("data:image/png;base64,{PIXEL_PNG}"),
+            }]
+        );
+    }
+
+    #[test]
+    fn rejects_data_urls_that_are_not_images() {
+        let err = prepare_turn_input(vec![V2UserInput::Image {
+            url: format!(
+                "data:image/png;base64,{}",
+                BASE64_STANDARD.encode("not an image")
+            ),
+        }])
+        .expect_err("text is not an image");
+        assert_eq!(
+            err,
+            "invalid image: unsupported image format; expected PNG or JPEG"
+        );
+    }
+
+    #[test]
+    fn remote_urls_pass_through() {
+        let items = prepare_turn_input(vec![V2UserInput::Image {
+            url: "https://example.com/a.png".to_string(),
+        }])
+        .expect("remote url");
+        assert_eq!(
+            items,
+            vec![CoreInputItem::Image {
+                image_url: "https://example.com/a.png".to_string(),
+            }]
+        );
+    }
+}
@@ -3,21 +3,25 @@
 use codex_common::CliConfigOverrides;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
-use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use std::io::ErrorKind;
 use std::io::Result as IoResult;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::message_processor::MessageProcessor;
+use crate::observer::ObserverHub;
 use crate::outgoing_message::OutgoingMessage;
 use crate::outgoing_message::OutgoingMessageSender;
 use codex_app_server_protocol::JSONRPCMessage;
+use codex_app_server_protocol::ShutdownParams;
 use codex_feedback::CodexFeedback;
 use tokio::io::AsyncBufReadExt;
 use tokio::io::AsyncWriteExt;
 use tokio::io::BufReader;
 use tokio::io::{self};
 use tokio::sync::mpsc;
+use tokio::sync::oneshot;
 use tracing::Level;
 use tracing::debug;
 use tracing::error;
@@ -32,18 +36,28 @@ mod bespoke_event_handling;
 mod codex_message_processor;
 mod error_code;
 mod fuzzy_file_search;
+mod image_input;
 mod message_processor;
 mod models;
+mod observer;
 mod outgoing_message;
+mod output_delta;
+mod shutdown;
+mod turn_queue;
+mod v1_compat;
 
 /// Size of the bounded channels used to communicate between tasks. The value
 /// is a balance between throughput and memory usage – 128 messages should be
 /// plenty for an interactive CLI.
 const CHANNEL_CAPACITY: usize = 128;
 
+/// How long a graceful shutdown waits for queued messages to reach stdout.
+const STDOUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
 pub async fn run_main(
     codex_linux_sandbox_exe: Option<PathBuf>,
     cli_config_overrides: CliConfigOverrides,
+    observer_socket: Option<PathBuf>,
 ) -> IoResult<()> {
     // Set up channels.
     let (incoming_tx, mut incoming_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
@@ -112,32 +126,70 @@ pub async fn run_main(
         .with(stderr_fmt)
         .with(feedback_layer)
         .with(otel.as_ref().map(|provider| {
-            OpenTelemetryTracingBridge::new(&provider.logger).with_filter(
-                tracing_subscriber::filter::filter_fn(codex_core::otel_init::codex_export_filter),
-            )
+            provider
+                .log_layer()
+                .with_filter(tracing_subscriber::filter::filter_fn(
+                    codex_core::otel_init::codex_export_filter,
+                ))
         }))
         .try_init();
 
-    // Task: process incoming messages.
+    // Task: process incoming messages. Returns whether the server was shut
+    // down gracefully, as opposed to the client closing stdin.
+    let observers = Arc::new(ObserverHub::default());
+    if let Some(socket_path) = &observer_socket {
+        observer::listen(socket_path, observers.clone()).map_err(|e| {
+            std::io::Error::new(
+                e.kind(),
+                format!(
+                    "error listening for observers on {}: {e}",
+                    socket_path.display()
+                ),
+            )
+        })?;
+        info!("accepting observers on {}", socket_path.display());
+    }
+
     let processor_handle = tokio::spawn({
-        let outgoing_message_sender = OutgoingMessageSender::new(outgoing_tx);
+        let outgoing_message_sender =
+            OutgoingMessageSender::new(outgoing_tx).with_observers(observers);
+        let (shutdown_done_tx, mut shutdown_done_rx) = oneshot::channel();
         let mut processor = MessageProcessor::new(
             outgoing_message_sender,
             codex_linux_sandbox_exe,
-            std::sync::Arc::new(config),
+            Arc::new(config),
             feedback.clone(),
+            shutdown_done_tx,
         );
         async move {
-            while let Some(msg) = incoming_rx.recv().await {
-                match msg {
-                    JSONRPCMessage::Request(r) => processor.process_request(r).await,
-                    JSONRPCMessage::Response(r) => processor.process_response(r).await,
-                    JSONRPCMessage::Notification(n) => processor.process_notification(n).await,
-                    JSONRPCMessage::Error(e) => processor.process_error(e),
+            let terminate = shutdown::terminate_signal();
+            tokio::pin!(terminate);
+            loop {
+                tokio::select! {
+                    msg = incoming_rx.recv() => {
+                        let Some(msg) = msg else {
+                            info!("processor task exited (channel closed)");
+                            return false;
+                        };
+                        match msg {
+                            JSONRPCMessage::Request(r) => processor.process_request(r).await,
+                            JSONRPCMessage::Response(r) => processor.process_response(r).await,
+                            JSONRPCMessage::Notification(n) => {
+                                processor.process_notification(n).await
+                            }
+                            JSONRPCMessage::Error(e) => processor.process_error(e),
+                        }
+                    }
+                    _ = &mut terminate, if !processor.is_shutting_down() => {
+                        info!("received termination signal");
+                        processor.shutdown(None, ShutdownParams::default());
+                    }
+                    _ = &mut shutdown_done_rx => {
+                        info!("processor task exited (shutdown complete)");
+                        return true;
+                    }
                 }
             }
-
-            info!("processor task exited (channel closed)");
         }
     });
 
@@ -156,6 +208,10 @@ pub async fn run_main(
                         error!("Failed to write to stdout: {e}");
                         break;
                     }
+                    if let Err(e) = stdout.flush().await {
+                        error!("Failed to flush stdout: {e}");
+                        break;
+                    }
                 }
                 Err(e) => error!("Failed to serialize JSONRPCMessage: {e}"),
             }
@@ -164,10 +220,17 @@ pub async fn run_main(
         info!("stdout writer exited (channel closed)");
     });
 
-    // Wait for all tasks to finish.  The typical exit path is the stdin reader
-    // hitting EOF which, once it drops `incoming_tx`, propagates shutdown to
-    // the processor and then to the stdout task.
-    let _ = tokio::join!(stdin_reader_handle, processor_handle, stdout_writer_handle);
+    // After a graceful shutdown, give the writer a moment to drain the
+    // remaining messages (the shutdown response among them) and exit without
+    // waiting on stdin, whose blocking read cannot be cancelled.
+    if processor_handle.await.unwrap_or(false) {
+        let _ = tokio::time::timeout(STDOUT_DRAIN_TIMEOUT, stdout_writer_handle).await;
+        std::process::exit(0);
+    }
+
+    // Otherwise stdin hit EOF which, once the reader drops `incoming_tx`,
+    // propagates shutdown to the processor and then to the stdout task.
+    let _ = tokio::join!(stdin_reader_handle, stdout_writer_handle);
 
     Ok(())
 }
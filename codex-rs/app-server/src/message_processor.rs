@@ -1,8 +1,14 @@
 use std::path::PathBuf;
 
+use crate::bespoke_event_handling::emit_turn_completed_with_status;
 use crate::codex_message_processor::CodexMessageProcessor;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::shutdown;
+use crate::shutdown::DEFAULT_GRACE_PERIOD;
+use crate::shutdown::ShutdownTracker;
+use crate::turn_queue::TurnQueue;
+use crate::v1_compat::V1Usage;
 use codex_app_server_protocol::ClientInfo;
 use codex_app_server_protocol::ClientRequest;
 use codex_app_server_protocol::InitializeResponse;
@@ -11,6 +17,10 @@ use codex_app_server_protocol::JSONRPCErrorError;
 use codex_app_server_protocol::JSONRPCNotification;
 use codex_app_server_protocol::JSONRPCRequest;
 use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ShutdownParams;
+use codex_app_server_protocol::ShutdownResponse;
+use codex_app_server_protocol::TurnStatus;
 use codex_core::AuthManager;
 use codex_core::ConversationManager;
 use codex_core::config::Config;
@@ -19,11 +29,20 @@ use codex_core::default_client::get_codex_user_agent;
 use codex_feedback::CodexFeedback;
 use codex_protocol::protocol::SessionSource;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use tracing::info;
 
 pub(crate) struct MessageProcessor {
     outgoing: Arc<OutgoingMessageSender>,
     codex_message_processor: CodexMessageProcessor,
     initialized: bool,
+    conversation_manager: Arc<ConversationManager>,
+    shutdown_tracker: Arc<ShutdownTracker>,
+    turn_queue: Arc<TurnQueue>,
+    v1_usage: V1Usage,
+    /// Taken when shutdown starts; fired once it has finished.
+    shutdown_done: Option<oneshot::Sender<()>>,
 }
 
 impl MessageProcessor {
@@ -34,10 +53,12 @@ impl MessageProcessor {
         codex_linux_sandbox_exe: Option<PathBuf>,
         config: Arc<Config>,
         feedback: CodexFeedback,
+        shutdown_done: oneshot::Sender<()>,
     ) -> Self {
         let outgoing = Arc::new(outgoing);
-        let auth_manager = AuthManager::shared(
+        let auth_manager = AuthManager::shared_with_account(
             config.codex_home.clone(),
+            config.account.clone(),
             false,
             config.cli_auth_credentials_store_mode,
         );
@@ -45,19 +66,28 @@ impl MessageProcessor {
             auth_manager.clone(),
             SessionSource::VSCode,
         ));
+        let shutdown_tracker = Arc::new(ShutdownTracker::default());
+        let turn_queue = Arc::new(TurnQueue::new(config.app_server.clone()));
         let codex_message_processor = CodexMessageProcessor::new(
             auth_manager,
-            conversation_manager,
+            conversation_manager.clone(),
             outgoing.clone(),
             codex_linux_sandbox_exe,
             config,
             feedback,
+            shutdown_tracker.clone(),
+            turn_queue.clone(),
         );
 
         Self {
             outgoing,
             codex_message_processor,
             initialized: false,
+            conversation_manager,
+            shutdown_tracker,
+            turn_queue,
+            v1_usage: V1Usage::default(),
+            shutdown_done: Some(shutdown_done),
         }
     }
 
@@ -89,6 +119,16 @@ impl MessageProcessor {
             }
         };
 
+        if self.is_shutting_down() {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: "Server is shutting down".to_string(),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        }
+
         match codex_request {
             // Handle Initialize internally so CodexMessageProcessor does not have to concern
             // itself with the `initialized` bool.
@@ -134,6 +174,13 @@ impl MessageProcessor {
             }
         }
 
+        self.v1_usage.record(&request.method, &self.outgoing).await;
+
+        if let ClientRequest::Shutdown { request_id, params } = codex_request {
+            self.shutdown(Some(request_id), params);
+            return;
+        }
+
         self.codex_message_processor
             .process_request(codex_request)
             .await;
@@ -152,6 +199,51 @@ impl MessageProcessor {
         self.outgoing.notify_client_response(id, result).await
     }
 
+    pub(crate) fn is_shutting_down(&self) -> bool {
+        self.shutdown_done.is_none()
+    }
+
+    /// Stop accepting requests and drain the conversations in the
+    /// background, so that responses to approval requests keep being
+    /// processed while turns finish. Answers `request_id` when done.
+    pub(crate) fn shutdown(&mut self, request_id: Option<RequestId>, params: ShutdownParams) {
+        let Some(shutdown_done) = self.shutdown_done.take() else {
+            return;
+        };
+        let grace_period = params
+            .grace_period_ms
+            .map_or(DEFAULT_GRACE_PERIOD, Duration::from_millis);
+        info!("shutting down; running turns have {grace_period:?} to finish");
+
+        let conversation_manager = self.conversation_manager.clone();
+        let shutdown_tracker = self.shutdown_tracker.clone();
+        let outgoing = self.outgoing.clone();
+        let queued = self.turn_queue.clear();
+        tokio::spawn(async move {
+            // Turns still waiting for a slot never start.
+            for turn in queued {
+                emit_turn_completed_with_status(
+                    turn.turn_id,
+                    TurnStatus::Interrupted,
+                    None,
+                    None,
+                    None,
+                    &outgoing,
+                )
+                .await;
+            }
+            let interrupted =
+                shutdown::drain(&conversation_manager, &shutdown_tracker, grace_period).await;
+            if let Some(request_id) = request_id {
+                let response = ShutdownResponse {
+                    interrupted_thread_ids: interrupted.iter().map(ToString::to_string).collect(),
+                };
+                outgoing.send_response(request_id, response).await;
+            }
+            let _ = shutdown_done.send(());
+        });
+    }
+
     /// Handle an error object received from the peer.
     pub(crate) fn process_error(&mut self, err: JSONRPCError) {
         tracing::error!("<- error: {:?}", err);
@@ -0,0 +1,410 @@
+//! Read-only observer connections.
+//!
+//! With `--observer-socket <PATH>`, the app server also accepts connections on
+//! a Unix domain socket. An observer subscribes to threads with
+//! `thread/observe` and from then on receives every notification and server
+//! request the main client receives for those threads. Every other request is
+//! rejected, so an observer can watch a thread but never start turns or
+//! answer approvals.
+//!
+//! Messages are attributed to a thread through [`in_thread`]: whatever the
+//! wrapped future sends through [`OutgoingMessageSender`] is mirrored to the
+//! thread's observers.
+//!
+//! [`OutgoingMessageSender`]: crate::outgoing_message::OutgoingMessageSender
+
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use codex_app_server_protocol::InitializeResponse;
+use codex_app_server_protocol::JSONRPCErrorError;
+use codex_app_server_protocol::JSONRPCRequest;
+use codex_core::default_client::get_codex_user_agent;
+use codex_protocol::ConversationId;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::error_code::INVALID_REQUEST_ERROR_CODE;
+use crate::outgoing_message::OutgoingError;
+use crate::outgoing_message::OutgoingMessage;
+use crate::outgoing_message::OutgoingResponse;
+
+tokio::task_local! {
+    static CURRENT_THREAD: ConversationId;
+}
+
+/// Runs `future` with its outgoing messages attributed to `thread_id`.
+pub(crate) async fn in_thread<F: Future>(thread_id: ConversationId, future: F) -> F::Output {
+    CURRENT_THREAD.scope(thread_id, future).await
+}
+
+/// Thread the current task's outgoing messages are attributed to, if any.
+pub(crate) fn current_thread() -> Option<ConversationId> {
+    CURRENT_THREAD.try_with(|thread_id| *thread_id).ok()
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThreadObserveParams {
+    thread_id: String,
+}
+
+struct Observer {
+    id: u64,
+    threads: HashSet<ConversationId>,
+    sender: mpsc::Sender<String>,
+}
+
+/// The connected observers and the threads each one watches.
+#[derive(Default)]
+pub(crate) struct ObserverHub {
+    next_id: AtomicU64,
+    observers: Mutex<Vec<Observer>>,
+}
+
+impl ObserverHub {
+    /// Mirrors `message` to the observers of the current thread, if any.
+    pub(crate) fn publish(&self, message: &OutgoingMessage) {
+        let Some(thread_id) = current_thread() else {
+            return;
+        };
+        let Ok(mut observers) = self.observers.lock() else {
+            return;
+        };
+        if !observers
+            .iter()
+            .any(|observer| observer.threads.contains(&thread_id))
+        {
+            return;
+        }
+        let line = match serde_json::to_string(message) {
+            Ok(line) => line,
+            Err(err) => {
+                warn!("failed to serialize message for observers: {err}");
+                return;
+            }
+        };
+        observers.retain(|observer| {
+            if !observer.threads.contains(&thread_id) {
+                return true;
+            }
+            let delivered = observer.sender.try_send(line.clone()).is_ok();
+            if !delivered {
+                warn!(
+                    "disconnecting observer {}: it is not keeping up",
+                    observer.id
+                );
+            }
+            delivered
+        });
+    }
+
+    fn register(&self, sender: mpsc::Sender<String>) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut observers) = self.observers.lock() {
+            observers.push(Observer {
+                id,
+                threads: HashSet::new(),
+                sender,
+            });
+        }
+        id
+    }
+
+    fn unregister(&self, id: u64) {
+        if let Ok(mut observers) = self.observers.lock() {
+            observers.retain(|observer| observer.id != id);
+        }
+    }
+
+    fn set_observing(&self, id: u64, thread_id: ConversationId, observing: bool) {
+        let Ok(mut observers) = self.observers.lock() else {
+            return;
+        };
+        if let Some(observer) = observers.iter_mut().find(|observer| observer.id == id) {
+            if observing {
+                observer.threads.insert(thread_id);
+            } else {
+                observer.threads.remove(&thread_id);
+            }
+        }
+    }
+
+    /// Answers a request from observer `id`. Only `initialize`,
+    /// `thread/observe`, and `thread/unobserve` are allowed.
+    fn handle_request(&self, id: u64, request: JSONRPCRequest) -> OutgoingMessage {
+        let JSONRPCRequest {
+            id: request_id,
+            method,
+            params,
+        } = request;
+        let result = match method.as_str() {
+            "initialize" => serde_json::to_value(InitializeResponse {
+                user_agent: get_codex_user_agent(),
+            })
+            .map_err(|err| err.to_string()),
+            "thread/observe" | "thread/unobserve" => parse_thread_id(params).map(|thread_id| {
+                self.set_observing(id, thread_id, method == "thread/observe");
+                json!({})
+            }),
+            _ => Err(format!(
+                "observer connections are read-only; `{method}` is not allowed"
+            )),
+        };
+        match result {
+            Ok(result) => OutgoingMessage::Response(OutgoingResponse {
+                id: request_id,
+                result,
+            }),
+            Err(message) => OutgoingMessage::Error(OutgoingError {
+                id: request_id,
+                error: JSONRPCErrorError {
+                    code: INVALID_REQUEST_ERROR_CODE,
+                    message,
+                    data: None,
+                },
+            }),
+        }
+    }
+}
+
+fn parse_thread_id(params: Option<serde_json::Value>) -> Result<ConversationId, String> {
+    let params: ThreadObserveParams = serde_json::from_value(params.unwrap_or_default())
+        .map_err(|err| format!("invalid params: {err}"))?;
+    ConversationId::from_string(&params.thread_id)
+        .map_err(|err| format!("invalid thread id: {err}"))
+}
+
+#[cfg(unix)]
+mod socket {
+    use std::io;
+    use std::os::unix::fs::DirBuilderExt;
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use codex_app_server_protocol::JSONRPCMessage;
+    use tokio::io::AsyncBufReadExt;
+    use tokio::io::AsyncWriteExt;
+    use tokio::io::BufReader;
+    use tokio::net::UnixListener;
+    use tokio::net::UnixStream;
+    use tokio::sync::mpsc;
+    use tracing::debug;
+    use tracing::error;
+
+    use super::ObserverHub;
+
+    /// Messages buffered per observer. An observer that falls this far behind
+    /// is disconnected rather than slowing down the main client.
+    const OBSERVER_CHANNEL_CAPACITY: usize = 1024;
+
+    /// Binds `socket_path` and serves observers until the process exits. A
+    /// stale socket left by a previous run is replaced; any other existing
+    /// file is an error. Observers see everything the threads they watch do,
+    /// so the socket is only accessible to the current user.
+    pub(crate) fn listen(socket_path: &Path, hub: Arc<ObserverHub>) -> io::Result<()> {
+        if let Ok(metadata) = std::fs::symlink_metadata(socket_path)
+            && metadata.file_type().is_socket()
+        {
+            std::fs::remove_file(socket_path)?;
+        }
+        let listener = bind_private(socket_path)?;
+        tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        tokio::spawn(serve(stream, hub.clone()));
+                    }
+                    Err(err) => {
+                        error!("failed to accept observer connection: {err}");
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Binds the socket inside a fresh 0700 directory next to `socket_path`
+    /// and moves it into place only once it is 0600, so there is no moment at
+    /// which another user could connect to it.
+    fn bind_private(socket_path: &Path) -> io::Result<UnixListener> {
+        let file_name = socket_path.file_name().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid observer socket path {}", socket_path.display()),
+            )
+        })?;
+        let mut private_dir_name = std::ffi::OsString::from(".");
+        private_dir_name.push(file_name);
+        private_dir_name.push(format!(".{}", std::process::id()));
+        let private_dir: PathBuf = socket_path.with_file_name(private_dir_name);
+        std::fs::DirBuilder::new()
+            .mode(0o700)
+            .create(&private_dir)?;
+
+        let staged_path = private_dir.join(file_name);
+        let result = UnixListener::bind(&staged_path).and_then(|listener| {
+            std::fs::set_permissions(&staged_path, std::fs::Permissions::from_mode(0o600))?;
+            std::fs::rename(&staged_path, socket_path)?;
+            Ok(listener)
+        });
+        let _ = std::fs::remove_file(&staged_path);
+        let _ = std::fs::remove_dir(&private_dir);
+        result
+    }
+
+    async fn serve(stream: UnixStream, hub: Arc<ObserverHub>) {
+        let (reader, mut writer) = stream.into_split();
+        let (sender, mut receiver) = mpsc::channel::<String>(OBSERVER_CHANNEL_CAPACITY);
+        let id = hub.register(sender.clone());
+
+        let writer_handle = tokio::spawn(async move {
+            while let Some(mut line) = receiver.recv().await {
+                line.push('\n');
+                if writer.write_all(line.as_bytes()).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let request = match serde_json::from_str::<JSONRPCMessage>(&line) {
+                Ok(JSONRPCMessage::Request(request)) => request,
+                // Observers cannot answer server requests, so their
+                // responses and notifications are dropped.
+                Ok(_) => continue,
+                Err(err) => {
+                    debug!("ignoring invalid message from observer {id}: {err}");
+                    continue;
+                }
+            };
+            let reply = hub.handle_request(id, request);
+            let Ok(reply) = serde_json::to_string(&reply) else {
+                continue;
+            };
+            if sender.send(reply).await.is_err() {
+                break;
+            }
+        }
+
+        hub.unregister(id);
+        drop(sender);
+        let _ = writer_handle.await;
+    }
+}
+
+#[cfg(unix)]
+pub(crate) use socket::listen;
+
+#[cfg(not(unix))]
+pub(crate) fn listen(
+    _socket_path: &std::path::Path,
+    _hub: std::sync::Arc<ObserverHub>,
+) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "observer sockets are only supported on Unix",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_app_server_protocol::RequestId;
+    use pretty_assertions::assert_eq;
+
+    use crate::outgoing_message::OutgoingNotification;
+
+    fn request(method: &str, params: serde_json::Value) -> JSONRPCRequest {
+        JSONRPCRequest {
+            id: RequestId::Integer(1),
+            method: method.to_string(),
+            params: Some(params),
+        }
+    }
+
+    fn notification(method: &str) -> OutgoingMessage {
+        OutgoingMessage::Notification(OutgoingNotification {
+            method: method.to_string(),
+            params: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn observers_receive_only_the_threads_they_observe() {
+        let hub = ObserverHub::default();
+        let (sender, mut receiver) = mpsc::channel(8);
+        let id = hub.register(sender);
+        let observed = ConversationId::new();
+        let other = ConversationId::new();
+
+        let reply = hub.handle_request(
+            id,
+            request(
+                "thread/observe",
+                json!({ "threadId": observed.to_string() }),
+            ),
+        );
+        assert!(matches!(reply, OutgoingMessage::Response(_)));
+
+        hub.publish(&notification("unattributed"));
+        in_thread(other, async { hub.publish(&notification("other")) }).await;
+        in_thread(observed, async { hub.publish(&notification("observed")) }).await;
+
+        assert_eq!(
+            receiver.try_recv().ok(),
+            Some(r#"{"method":"observed"}"#.to_string())
+        );
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn mutating_requests_are_rejected() {
+        let hub = ObserverHub::default();
+        let (sender, _receiver) = mpsc::channel(8);
+        let id = hub.register(sender);
+
+        for method in ["turn/start", "thread/start", "turn/interrupt"] {
+            let OutgoingMessage::Error(error) = hub.handle_request(id, request(method, json!({})))
+            else {
+                panic!("{method} should be rejected");
+            };
+            assert_eq!(error.error.code, INVALID_REQUEST_ERROR_CODE);
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn socket_is_private_from_the_start() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let socket_path = dir.path().join("observer.sock");
+
+        listen(&socket_path, std::sync::Arc::new(ObserverHub::default())).expect("listen");
+
+        let mode = std::fs::metadata(&socket_path)
+            .expect("socket metadata")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+        tokio::net::UnixStream::connect(&socket_path)
+            .await
+            .expect("connect");
+        let entries: Vec<_> = std::fs::read_dir(dir.path())
+            .expect("read dir")
+            .map(|entry| entry.expect("dir entry").file_name())
+            .collect();
+        assert_eq!(entries, vec![std::ffi::OsString::from("observer.sock")]);
+    }
+}
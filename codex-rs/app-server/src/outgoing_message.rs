@@ -1,4 +1,8 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::RwLock;
 use std::sync::atomic::AtomicI64;
 use std::sync::atomic::Ordering;
 
@@ -8,19 +12,40 @@ use codex_app_server_protocol::Result;
 use codex_app_server_protocol::ServerNotification;
 use codex_app_server_protocol::ServerRequest;
 use codex_app_server_protocol::ServerRequestPayload;
+use codex_protocol::ConversationId;
 use serde::Serialize;
 use tokio::sync::Mutex;
 use tokio::sync::mpsc;
+use tokio::sync::mpsc::error::SendError;
+use tokio::sync::mpsc::error::TrySendError;
 use tokio::sync::oneshot;
 use tracing::warn;
 
 use crate::error_code::INTERNAL_ERROR_CODE;
+use crate::observer::ObserverHub;
+use crate::observer::current_thread;
+
+/// Messages held back while the outgoing channel is full, beyond which
+/// senders wait for the client to catch up.
+const MAX_BACKLOG: usize = 128;
 
 /// Sends messages to the client and manages request callbacks.
+///
+/// When the client reads slower than messages are produced and the channel
+/// fills up, streaming deltas are held in a small backlog instead, where
+/// consecutive deltas for the same item are merged into one. A slow client
+/// therefore receives fewer, larger deltas rather than stalling the
+/// conversation. Everything else waits for the backlog to drain first, so
+/// the order of messages is preserved. A background task hands the backlog
+/// to the channel as soon as the client makes room, so held deltas are not
+/// stuck waiting for the next message.
 pub(crate) struct OutgoingMessageSender {
     next_request_id: AtomicI64,
     sender: mpsc::Sender<OutgoingMessage>,
+    backlog: Arc<Mutex<VecDeque<OutgoingMessage>>>,
     request_id_to_callback: Mutex<HashMap<RequestId, oneshot::Sender<Result>>>,
+    observers: Arc<ObserverHub>,
+    filter: RwLock<NotificationFilter>,
 }
 
 impl OutgoingMessageSender {
@@ -28,8 +53,97 @@ impl OutgoingMessageSender {
         Self {
             next_request_id: AtomicI64::new(0),
             sender,
+            backlog: Arc::default(),
             request_id_to_callback: Mutex::new(HashMap::new()),
+            observers: Arc::default(),
+            filter: RwLock::default(),
+        }
+    }
+
+    /// Replaces the client's `notifications/subscribe` options. Observers are
+    /// not affected.
+    pub(crate) fn set_notification_filter(&self, filter: NotificationFilter) {
+        if let Ok(mut current) = self.filter.write() {
+            *current = filter;
+        }
+    }
+
+    fn wants_notification(&self, method: &str) -> bool {
+        self.filter
+            .read()
+            .map(|filter| filter.allows(method, current_thread()))
+            .unwrap_or(true)
+    }
+
+    /// Mirrors requests and notifications sent within
+    /// [`in_thread`](crate::observer::in_thread) to `observers`.
+    pub(crate) fn with_observers(mut self, observers: Arc<ObserverHub>) -> Self {
+        self.observers = observers;
+        self
+    }
+
+    /// Queues `message` for the client, merging it into the backlog when it
+    /// is a delta and the channel is full.
+    async fn enqueue(
+        &self,
+        message: OutgoingMessage,
+    ) -> std::result::Result<(), SendError<OutgoingMessage>> {
+        let mut backlog = self.backlog.lock().await;
+        while let Some(front) = backlog.pop_front() {
+            match self.sender.try_send(front) {
+                Ok(()) => {}
+                Err(TrySendError::Full(front)) => {
+                    backlog.push_front(front);
+                    break;
+                }
+                Err(TrySendError::Closed(_)) => {
+                    backlog.clear();
+                    return Err(SendError(message));
+                }
+            }
+        }
+
+        if is_mergeable_delta(&message) {
+            let message = match backlog.back_mut() {
+                Some(last) => match merge_delta(last, message) {
+                    None => return Ok(()),
+                    Some(message) => message,
+                },
+                None => match self.sender.try_send(message) {
+                    Ok(()) => return Ok(()),
+                    Err(TrySendError::Full(message)) => {
+                        tokio::spawn(flush_backlog(
+                            self.sender.clone(),
+                            Arc::clone(&self.backlog),
+                        ));
+                        message
+                    }
+                    Err(TrySendError::Closed(message)) => return Err(SendError(message)),
+                },
+            };
+            backlog.push_back(message);
+            if backlog.len() > MAX_BACKLOG {
+                return self.drain(&mut backlog).await;
+            }
+            return Ok(());
         }
+
+        self.drain(&mut backlog).await?;
+        self.sender.send(message).await
+    }
+
+    /// Waits until every message in `backlog` has been handed to the channel.
+    async fn drain(
+        &self,
+        backlog: &mut VecDeque<OutgoingMessage>,
+    ) -> std::result::Result<(), SendError<OutgoingMessage>> {
+        while let Some(front) = backlog.pop_front() {
+            if let Err(err) = self.sender.send(front).await {
+                backlog.clear();
+                return Err(err);
+            }
+        }
+        Ok(())
     }
 
     pub(crate) async fn send_request(
@@ -46,7 +160,8 @@ impl OutgoingMessageSender {
 
         let outgoing_message =
             OutgoingMessage::Request(request.request_with_id(outgoing_message_id.clone()));
-        if let Err(err) = self.sender.send(outgoing_message).await {
+        self.observers.publish(&outgoing_message);
+        if let Err(err) = self.enqueue(outgoing_message).await {
             warn!("failed to send request {outgoing_message_id:?} to client: {err:?}");
             let mut request_id_to_callback = self.request_id_to_callback.lock().await;
             request_id_to_callback.remove(&outgoing_message_id);
@@ -76,7 +191,7 @@ impl OutgoingMessageSender {
         match serde_json::to_value(response) {
             Ok(result) => {
                 let outgoing_message = OutgoingMessage::Response(OutgoingResponse { id, result });
-                if let Err(err) = self.sender.send(outgoing_message).await {
+                if let Err(err) = self.enqueue(outgoing_message).await {
                     warn!("failed to send response to client: {err:?}");
                 }
             }
@@ -95,11 +210,13 @@ impl OutgoingMessageSender {
     }
 
     pub(crate) async fn send_server_notification(&self, notification: ServerNotification) {
-        if let Err(err) = self
-            .sender
-            .send(OutgoingMessage::AppServerNotification(notification))
-            .await
-        {
+        let method = notification.to_string();
+        let outgoing_message = OutgoingMessage::AppServerNotification(notification);
+        self.observers.publish(&outgoing_message);
+        if !self.wants_notification(&method) {
+            return;
+        }
+        if let Err(err) = self.enqueue(outgoing_message).await {
             warn!("failed to send server notification to client: {err:?}");
         }
     }
@@ -107,20 +224,154 @@ impl OutgoingMessageSender {
     /// All notifications should be migrated to [`ServerNotification`] and
     /// [`OutgoingMessage::Notification`] should be removed.
     pub(crate) async fn send_notification(&self, notification: OutgoingNotification) {
+        let wanted = self.wants_notification(&notification.method);
         let outgoing_message = OutgoingMessage::Notification(notification);
-        if let Err(err) = self.sender.send(outgoing_message).await {
+        self.observers.publish(&outgoing_message);
+        if !wanted {
+            return;
+        }
+        if let Err(err) = self.enqueue(outgoing_message).await {
             warn!("failed to send notification to client: {err:?}");
         }
     }
 
     pub(crate) async fn send_error(&self, id: RequestId, error: JSONRPCErrorError) {
         let outgoing_message = OutgoingMessage::Error(OutgoingError { id, error });
-        if let Err(err) = self.sender.send(outgoing_message).await {
+        if let Err(err) = self.enqueue(outgoing_message).await {
             warn!("failed to send error to client: {err:?}");
         }
     }
 }
 
+/// Which notifications the client asked for with `notifications/subscribe`.
+/// The default delivers everything. Server requests, responses, and
+/// notifications that do not belong to a thread are never filtered by thread.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct NotificationFilter {
+    /// Deliver only notifications for these threads.
+    pub thread_ids: Option<HashSet<ConversationId>>,
+    /// Deliver only these methods. An entry ending in `/*` matches every
+    /// method under that prefix, e.g. `turn/*`.
+    pub kinds: Option<Vec<String>>,
+    /// Drop streaming deltas such as `item/agentMessage/delta`.
+    pub skip_deltas: bool,
+}
+
+impl NotificationFilter {
+    fn allows(&self, method: &str, thread_id: Option<ConversationId>) -> bool {
+        if self.skip_deltas && is_delta(method) {
+            return false;
+        }
+        if let (Some(thread_ids), Some(thread_id)) = (&self.thread_ids, thread_id)
+            && !thread_ids.contains(&thread_id)
+        {
+            return false;
+        }
+        self.kinds.as_ref().is_none_or(|kinds| {
+            kinds.iter().any(|kind| match kind.strip_suffix('*') {
+                Some(prefix) if prefix.ends_with('/') => method.starts_with(prefix),
+                _ => kind == method,
+            })
+        })
+    }
+}
+
+/// Notifications that stream a piece of an item that is delivered whole by
+/// `item/completed` (or its v1 `codex/event/*` counterpart).
+/// Hands `backlog` to the channel one message at a time as capacity frees
+/// up, and returns once it is empty.
+///
+/// The channel slot is reserved before the backlog is locked, so a sender
+/// that holds the lock while waiting for capacity is never starved by the
+/// slot reserved here: the reservation is released until the lock is free.
+async fn flush_backlog(
+    sender: mpsc::Sender<OutgoingMessage>,
+    backlog: Arc<Mutex<VecDeque<OutgoingMessage>>>,
+) {
+    loop {
+        let Ok(permit) = sender.reserve().await else {
+            backlog.lock().await.clear();
+            return;
+        };
+        let mut pending = match backlog.try_lock() {
+            Ok(pending) => pending,
+            Err(_) => {
+                drop(permit);
+                drop(backlog.lock().await);
+                continue;
+            }
+        };
+        let Some(front) = pending.pop_front() else {
+            return;
+        };
+        permit.send(front);
+    }
+}
+
+fn is_delta(method: &str) -> bool {
+    method.ends_with("Delta")
+        || method.ends_with("/delta")
+        || method.ends_with("_delta")
+        || matches!(
+            method,
+            "item/reasoning/summaryPartAdded" | "codex/event/agent_reasoning_section_break"
+        )
+}
+
+fn is_mergeable_delta(message: &OutgoingMessage) -> bool {
+    matches!(
+        message,
+        OutgoingMessage::AppServerNotification(
+            ServerNotification::AgentMessageDelta(_)
+                | ServerNotification::ReasoningSummaryTextDelta(_)
+                | ServerNotification::ReasoningTextDelta(_)
+                | ServerNotification::CommandExecutionOutputDelta(_)
+        )
+    )
+}
+
+/// Appends `next` to `last` when both are deltas of the same item and part.
+/// Returns `next` unchanged when they cannot be merged.
+fn merge_delta(last: &mut OutgoingMessage, next: OutgoingMessage) -> Option<OutgoingMessage> {
+    let (
+        OutgoingMessage::AppServerNotification(last_notification),
+        OutgoingMessage::AppServerNotification(next_notification),
+    ) = (&mut *last, &next)
+    else {
+        return Some(next);
+    };
+    let merged = match (last_notification, next_notification) {
+        (ServerNotification::AgentMessageDelta(a), ServerNotification::AgentMessageDelta(b))
+            if a.item_id == b.item_id =>
+        {
+            a.delta.push_str(&b.delta);
+            true
+        }
+        (
+            ServerNotification::ReasoningSummaryTextDelta(a),
+            ServerNotification::ReasoningSummaryTextDelta(b),
+        ) if a.item_id == b.item_id && a.summary_index == b.summary_index => {
+            a.delta.push_str(&b.delta);
+            true
+        }
+        (ServerNotification::ReasoningTextDelta(a), ServerNotification::ReasoningTextDelta(b))
+            if a.item_id == b.item_id && a.content_index == b.content_index =>
+        {
+            a.delta.push_str(&b.delta);
+            true
+        }
+        (
+            ServerNotification::CommandExecutionOutputDelta(a),
+            ServerNotification::CommandExecutionOutputDelta(b),
+        ) if a.item_id == b.item_id && a.raw.is_none() && b.raw.is_none() => {
+            a.delta.push_str(&b.delta);
+            true
+        }
+        _ => false,
+    };
+    if merged { None } else { Some(next) }
+}
+
 /// Outgoing message from the server to the client.
 #[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
@@ -158,12 +409,14 @@ mod tests {
     use codex_app_server_protocol::AccountLoginCompletedNotification;
     use codex_app_server_protocol::AccountRateLimitsUpdatedNotification;
     use codex_app_server_protocol::AccountUpdatedNotification;
+    use codex_app_server_protocol::AgentMessageDeltaNotification;
     use codex_app_server_protocol::AuthMode;
     use codex_app_server_protocol::LoginChatGptCompleteNotification;
     use codex_app_server_protocol::RateLimitSnapshot;
     use codex_app_server_protocol::RateLimitWindow;
     use pretty_assertions::assert_eq;
     use serde_json::json;
+    use std::time::Duration;
     use uuid::Uuid;
 
     use super::*;
@@ -274,4 +527,123 @@ mod tests {
             "ensure the notification serializes correctly"
         );
     }
+
+    #[test]
+    fn notification_filter_matches_threads_kinds_and_deltas() {
+        let watched = ConversationId::new();
+        let other = ConversationId::new();
+        let filter = NotificationFilter {
+            thread_ids: Some(HashSet::from([watched])),
+            kinds: Some(vec!["turn/*".to_string(), "item/completed".to_string()]),
+            skip_deltas: true,
+        };
+
+        assert!(filter.allows("turn/completed", Some(watched)));
+        assert!(filter.allows("item/completed", Some(watched)));
+        assert!(!filter.allows("item/started", Some(watched)));
+        assert!(!filter.allows("turn/completed", Some(other)));
+        assert!(filter.allows("turn/completed", None));
+
+        let deltas_only = NotificationFilter {
+            skip_deltas: true,
+            ..NotificationFilter::default()
+        };
+        for method in [
+            "item/agentMessage/delta",
+            "item/reasoning/textDelta",
+            "item/commandExecution/outputDelta",
+            "codex/event/agent_message_delta",
+        ] {
+            assert!(!deltas_only.allows(method, Some(watched)), "{method}");
+        }
+        assert!(deltas_only.allows("codex/event/agent_message", Some(watched)));
+        assert!(NotificationFilter::default().allows("item/agentMessage/delta", None));
+    }
+
+    fn agent_delta(item_id: &str, delta: &str) -> ServerNotification {
+        ServerNotification::AgentMessageDelta(AgentMessageDeltaNotification {
+            item_id: item_id.to_string(),
+            delta: delta.to_string(),
+        })
+    }
+
+    #[tokio::test]
+    async fn backlog_is_flushed_once_the_channel_has_room() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let outgoing = OutgoingMessageSender::new(tx);
+
+        outgoing
+            .send_server_notification(agent_delta("a", "He"))
+            .await;
+        outgoing
+            .send_server_notification(agent_delta("a", "llo"))
+            .await;
+
+        let mut received = Vec::new();
+        for _ in 0..2 {
+            let message = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .expect("backlog was not flushed")
+                .expect("channel closed");
+            let OutgoingMessage::AppServerNotification(ServerNotification::AgentMessageDelta(
+                delta,
+            )) = message
+            else {
+                panic!("unexpected message {message:?}");
+            };
+            received.push(delta.delta);
+        }
+
+        assert_eq!(received, vec!["He".to_string(), "llo".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn deltas_are_merged_while_the_channel_is_full() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let outgoing = OutgoingMessageSender::new(tx);
+
+        outgoing
+            .send_server_notification(agent_delta("a", "He"))
+            .await;
+        outgoing
+            .send_server_notification(agent_delta("a", "llo"))
+            .await;
+        outgoing
+            .send_server_notification(agent_delta("a", ", "))
+            .await;
+        outgoing
+            .send_server_notification(agent_delta("b", "other"))
+            .await;
+        outgoing
+            .send_server_notification(agent_delta("a", "world"))
+            .await;
+
+        let mut received = Vec::new();
+        let drain = async {
+            while let Some(message) = rx.recv().await {
+                let OutgoingMessage::AppServerNotification(ServerNotification::AgentMessageDelta(
+                    delta,
+                )) = message
+                else {
+                    break;
+                };
+                received.push((delta.item_id, delta.delta));
+            }
+        };
+        let completed = outgoing.send_notification(OutgoingNotification {
+            method: "done".to_string(),
+            params: None,
+        });
+        tokio::join!(completed, drain);
+
+        assert_eq!(
+            received,
+            vec![
+                ("a".to_string(), "He".to_string()),
+                ("a".to_string(), "llo, ".to_string()),
+                ("b".to_string(), "other".to_string()),
+                ("a".to_string(), "world".to_string()),
+            ]
+        );
+    }
 }
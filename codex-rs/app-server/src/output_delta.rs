@@ -0,0 +1,125 @@
+//! Turns the raw byte chunks of `ExecCommandOutputDelta` events into the
+//! text of `item/commandExecution/outputDelta` notifications.
+//!
+//! A command's output is read in fixed-size chunks, so a multi-byte UTF-8
+//! character can straddle two of them. Such a partial character is held back
+//! and sent with the next chunk. A chunk that is not valid UTF-8 for any other
+//! reason is treated as binary and also sent as base64 in `raw`.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+
+/// One decoded chunk: its text, and its base64 bytes when they are binary.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct DecodedDelta {
+    pub(crate) delta: String,
+    pub(crate) raw: Option<String>,
+}
+
+/// Decoder for one stream of one command.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct OutputDecoder {
+    /// Start of a character cut off at the end of the last chunk.
+    pending: Vec<u8>,
+}
+
+impl OutputDecoder {
+    /// Decodes `chunk` after any bytes held back from the previous one.
+    /// Returns `None` when everything is held back.
+    pub(crate) fn push(&mut self, chunk: &[u8]) -> Option<DecodedDelta> {
+        let mut bytes = std::mem::take(&mut self.pending);
+        bytes.extend_from_slice(chunk);
+        match std::str::from_utf8(&bytes) {
+            Ok(_) => text(bytes),
+            // Only the end is incomplete: hold it back for the next chunk.
+            Err(err) if err.error_len().is_none() => {
+                self.pending = bytes.split_off(err.valid_up_to());
+                text(bytes)
+            }
+            Err(_) => Some(binary(&bytes)),
+        }
+    }
+
+    /// Decodes a chunk on its own, holding nothing back.
+    pub(crate) fn decode(chunk: &[u8]) -> Option<DecodedDelta> {
+        match std::str::from_utf8(chunk) {
+            Ok(_) => text(chunk.to_vec()),
+            Err(_) => Some(binary(chunk)),
+        }
+    }
+
+    /// Flushes bytes still held back once the command has ended; a
+    /// character that was never completed is binary.
+    pub(crate) fn finish(self) -> Option<DecodedDelta> {
+        (!self.pending.is_empty()).then(|| binary(&self.pending))
+    }
+}
+
+fn text(bytes: Vec<u8>) -> Option<DecodedDelta> {
+    if bytes.is_empty() {
+        return None;
+    }
+    let delta = String::from_utf8(bytes)
+        .unwrap_or_else(|err| String::from_utf8_lossy(err.as_bytes()).into_owned());
+    Some(DecodedDelta { delta, raw: None })
+}
+
+fn binary(bytes: &[u8]) -> DecodedDelta {
+    DecodedDelta {
+        delta: String::from_utf8_lossy(bytes).into_owned(),
+        raw: Some(BASE64_STANDARD.encode(bytes)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn text_delta(delta: &str) -> Option<DecodedDelta> {
+        Some(DecodedDelta {
+            delta: delta.to_string(),
+            raw: None,
+        })
+    }
+
+    #[test]
+    fn characters_split_across_chunks_are_rejoined() {
+        let bytes = "né ✓".as_bytes();
+        let mut decoder = OutputDecoder::default();
+
+        assert_eq!(text_delta("n"), decoder.push(&bytes[..2]));
+        assert_eq!(None, decoder.push(&bytes[2..3]));
+        assert_eq!(text_delta("é "), decoder.push(&bytes[3..5]));
+        assert_eq!(text_delta("✓"), decoder.push(&bytes[5..]));
+        assert_eq!(None, decoder.finish());
+    }
+
+    #[test]
+    fn invalid_bytes_are_sent_as_base64() {
+        let mut decoder = OutputDecoder::default();
+
+        assert_eq!(
+            Some(DecodedDelta {
+                delta: "\u{fffd}PNG".to_string(),
+                raw: Some("iVBORw==".to_string()),
+            }),
+            decoder.push(&[0x89, b'P', b'N', b'G'])
+        );
+        assert_eq!(text_delta("ok"), decoder.push(b"ok"));
+    }
+
+    #[test]
+    fn finish_flushes_an_incomplete_character() {
+        let mut decoder = OutputDecoder::default();
+
+        assert_eq!(text_delta("a"), decoder.push(&[b'a', 0xe2, 0x9c]));
+        assert_eq!(
+            Some(DecodedDelta {
+                delta: "\u{fffd}".to_string(),
+                raw: Some("4pw=".to_string()),
+            }),
+            decoder.finish()
+        );
+    }
+}
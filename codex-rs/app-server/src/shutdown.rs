@@ -0,0 +1,206 @@
+//! Graceful shutdown of the app-server.
+//!
+//! A `shutdown` request or SIGTERM stops the server from accepting new
+//! requests, gives running turns a grace period to finish, interrupts the
+//! ones still running, and then shuts every conversation down so that
+//! rollouts are flushed and MCP servers are stopped before the process exits.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use codex_core::ConversationManager;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::ConversationId;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+use tracing::info;
+use tracing::warn;
+
+/// How long running turns may keep going when the client does not say.
+pub(crate) const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// How long interrupted turns, and then conversations asked to shut down,
+/// get to report back before the server stops waiting for them.
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+struct TrackerState {
+    /// Conversations with a turn in progress.
+    running: HashSet<ConversationId>,
+    /// Conversations asked to shut down that have not confirmed it yet.
+    closing: HashSet<ConversationId>,
+}
+
+/// Turn and shutdown progress of every conversation, fed by the conversation
+/// listeners after they have forwarded each event to the client.
+#[derive(Default)]
+pub(crate) struct ShutdownTracker {
+    state: Mutex<TrackerState>,
+    changed: Notify,
+}
+
+impl ShutdownTracker {
+    pub(crate) fn observe(&self, conversation_id: ConversationId, msg: &EventMsg) {
+        let changed = self.update(|state| match msg {
+            EventMsg::TaskStarted(_) => state.running.insert(conversation_id),
+            EventMsg::TaskComplete(_) | EventMsg::TurnAborted(_) => {
+                state.running.remove(&conversation_id)
+            }
+            EventMsg::ShutdownComplete => {
+                let was_running = state.running.remove(&conversation_id);
+                state.closing.remove(&conversation_id) || was_running
+            }
+            _ => false,
+        });
+        if changed {
+            self.changed.notify_waiters();
+        }
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut TrackerState) -> T) -> T {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        f(&mut state)
+    }
+
+    /// Wait until `done` holds or `timeout` passes; returns whether it held.
+    async fn wait_for(&self, timeout: Duration, done: impl Fn(&TrackerState) -> bool) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let notified = self.changed.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.update(|state| done(state)) {
+                return true;
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return self.update(|state| done(state));
+            }
+        }
+    }
+}
+
+/// Let running turns finish within `grace_period`, interrupt the rest, and
+/// shut every conversation down. Returns the conversations whose turn had to
+/// be interrupted.
+pub(crate) async fn drain(
+    conversation_manager: &ConversationManager,
+    tracker: &ShutdownTracker,
+    grace_period: Duration,
+) -> Vec<ConversationId> {
+    tracker
+        .wait_for(grace_period, |state| state.running.is_empty())
+        .await;
+
+    let interrupted: Vec<ConversationId> =
+        tracker.update(|state| state.running.iter().copied().collect());
+    for conversation_id in &interrupted {
+        info!("interrupting the running turn of {conversation_id} before shutdown");
+        if let Ok(conversation) = conversation_manager
+            .get_conversation(*conversation_id)
+            .await
+            && let Err(err) = conversation.submit(Op::Interrupt).await
+        {
+            warn!("failed to interrupt {conversation_id}: {err}");
+        }
+    }
+    if !tracker
+        .wait_for(SETTLE_TIMEOUT, |state| state.running.is_empty())
+        .await
+    {
+        warn!("some turns did not acknowledge the interrupt before shutdown");
+    }
+
+    let conversations = conversation_manager.remove_all_conversations().await;
+    tracker.update(|state| {
+        state.closing = conversations.iter().map(|(id, _)| *id).collect();
+    });
+    for (conversation_id, conversation) in conversations {
+        if let Err(err) = conversation.submit(Op::Shutdown).await {
+            warn!("failed to shut down {conversation_id}: {err}");
+            tracker.update(|state| state.closing.remove(&conversation_id));
+        }
+    }
+    if !tracker
+        .wait_for(SETTLE_TIMEOUT, |state| state.closing.is_empty())
+        .await
+    {
+        warn!("some conversations did not confirm shutdown in time");
+    }
+
+    interrupted
+}
+
+/// Resolves when the process is asked to terminate: SIGTERM on Unix, Ctrl-C
+/// elsewhere.
+pub(crate) async fn terminate_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::SignalKind;
+        use tokio::signal::unix::signal;
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(err) => {
+                warn!("failed to listen for SIGTERM: {err}");
+                std::future::pending::<()>().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            warn!("failed to listen for Ctrl-C: {err}");
+            std::future::pending::<()>().await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::TaskCompleteEvent;
+    use codex_core::protocol::TaskStartedEvent;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn waits_for_running_turns_to_complete() {
+        let tracker = Arc::new(ShutdownTracker::default());
+        let conversation_id = ConversationId::new();
+        tracker.observe(
+            conversation_id,
+            &EventMsg::TaskStarted(TaskStartedEvent {
+                model_context_window: None,
+            }),
+        );
+        assert!(
+            !tracker
+                .wait_for(Duration::from_millis(10), |state| state.running.is_empty())
+                .await
+        );
+
+        let completer = Arc::clone(&tracker);
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            completer.observe(
+                conversation_id,
+                &EventMsg::TaskComplete(TaskCompleteEvent {
+                    last_agent_message: None,
+                    turn_commit: None,
+                    diff_summary: None,
+                }),
+            );
+        });
+        assert!(
+            tracker
+                .wait_for(Duration::from_secs(5), |state| state.running.is_empty())
+                .await
+        );
+    }
+}
@@ -0,0 +1,403 @@
+//! Back-pressure for `turn/start`.
+//!
+//! `app_server.max_concurrent_turns` caps the turns running across all
+//! threads and `app_server.max_turns_per_thread` the turns in flight on one
+//! thread. A `turn/start` over either limit is answered right away but waits
+//! in a FIFO queue, announced with `turn/queued`, and is submitted to its
+//! conversation (followed by `turn/started`) once a running turn finishes.
+//!
+//! Turns are tracked by the submission id the server gives them, which is
+//! also the id of the events of that turn. Input sent while a turn is
+//! running joins that turn instead of starting its own, so a submitted turn
+//! that has not started by the time the running one ends is released with it
+//! and only counted again if it does start.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::mem;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use codex_app_server_protocol::ServerNotification;
+use codex_app_server_protocol::Turn;
+use codex_app_server_protocol::TurnError;
+use codex_app_server_protocol::TurnStartedNotification;
+use codex_app_server_protocol::TurnStatus;
+use codex_core::CodexConversation;
+use codex_core::config::types::AppServerConfig;
+use codex_core::error::Result as CodexResult;
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::Submission;
+use codex_protocol::ConversationId;
+use tracing::warn;
+
+use crate::bespoke_event_handling::emit_turn_completed_with_status;
+use crate::observer::in_thread;
+use crate::outgoing_message::OutgoingMessageSender;
+
+/// A `turn/start` that has been admitted or is waiting for a slot.
+pub(crate) struct QueuedTurn {
+    pub(crate) conversation_id: ConversationId,
+    pub(crate) conversation: Arc<CodexConversation>,
+    pub(crate) turn_id: String,
+    /// Ops such as `Op::OverrideTurnContext` to apply right before the input.
+    pub(crate) overrides: Vec<Op>,
+    pub(crate) input: Op,
+}
+
+impl QueuedTurn {
+    /// Submit the turn to its conversation under its own id.
+    pub(crate) async fn submit(&self) -> CodexResult<()> {
+        for op in &self.overrides {
+            self.conversation.submit(op.clone()).await?;
+        }
+        self.conversation
+            .submit_with_id(Submission {
+                id: self.turn_id.clone(),
+                op: self.input.clone(),
+            })
+            .await
+    }
+
+    fn in_progress(&self) -> Turn {
+        Turn {
+            id: self.turn_id.clone(),
+            items: vec![],
+            status: TurnStatus::InProgress,
+            commit: None,
+            diff: None,
+        }
+    }
+}
+
+pub(crate) enum Admission {
+    /// Submit the turn now; a slot has been reserved for it.
+    Start(Box<QueuedTurn>),
+    /// The turn waits at this 1-based position of the queue.
+    Queued { position: usize },
+}
+
+#[derive(Default)]
+struct ThreadTurns {
+    /// Turns whose `TaskStarted` has been seen.
+    started: HashSet<String>,
+    /// Turns submitted but not started yet.
+    submitted: HashSet<String>,
+    /// Turns that were still waiting to start when the last turn ended; they
+    /// most likely joined it.
+    released: HashSet<String>,
+}
+
+impl ThreadTurns {
+    fn in_flight(&self) -> usize {
+        self.started.len() + self.submitted.len()
+    }
+}
+
+#[derive(Default)]
+struct QueueState {
+    threads: HashMap<ConversationId, ThreadTurns>,
+    pending: VecDeque<QueuedTurn>,
+}
+
+pub(crate) struct TurnQueue {
+    limits: AppServerConfig,
+    state: Mutex<QueueState>,
+}
+
+impl TurnQueue {
+    pub(crate) fn new(limits: AppServerConfig) -> Self {
+        Self {
+            limits,
+            state: Mutex::new(QueueState::default()),
+        }
+    }
+
+    /// Reserve a slot for `turn`, or queue it when a limit is reached.
+    pub(crate) fn admit(&self, turn: QueuedTurn) -> Admission {
+        self.update(|state| {
+            if self.has_capacity(state, turn.conversation_id) {
+                reserve(state, &turn);
+                Admission::Start(Box::new(turn))
+            } else {
+                state.pending.push_back(turn);
+                Admission::Queued {
+                    position: state.pending.len(),
+                }
+            }
+        })
+    }
+
+    /// Follow the turns of `conversation_id` through its events. Returns the
+    /// queued turns that got a slot and should be started now.
+    pub(crate) fn observe(
+        &self,
+        conversation_id: ConversationId,
+        event: &Event,
+    ) -> Vec<QueuedTurn> {
+        self.update(|state| {
+            match &event.msg {
+                EventMsg::TaskStarted(_) => {
+                    if let Some(thread) = state.threads.get_mut(&conversation_id)
+                        && (thread.submitted.remove(&event.id) || thread.released.remove(&event.id))
+                    {
+                        thread.started.insert(event.id.clone());
+                    }
+                    return Vec::new();
+                }
+                EventMsg::TaskComplete(_) | EventMsg::TurnAborted(_) => {
+                    if let Some(thread) = state.threads.get_mut(&conversation_id) {
+                        thread.started.remove(&event.id);
+                        thread.released = mem::take(&mut thread.submitted);
+                    }
+                }
+                EventMsg::ShutdownComplete => {
+                    state.threads.remove(&conversation_id);
+                    state
+                        .pending
+                        .retain(|turn| turn.conversation_id != conversation_id);
+                }
+                _ => return Vec::new(),
+            }
+            self.release(state)
+        })
+    }
+
+    /// Give back the slot of a turn that could not be submitted.
+    pub(crate) fn abandon(
+        &self,
+        conversation_id: ConversationId,
+        turn_id: &str,
+    ) -> Vec<QueuedTurn> {
+        self.update(|state| {
+            if let Some(thread) = state.threads.get_mut(&conversation_id) {
+                thread.submitted.remove(turn_id);
+            }
+            self.release(state)
+        })
+    }
+
+    /// Take a turn that is still waiting out of the queue.
+    pub(crate) fn cancel(
+        &self,
+        conversation_id: ConversationId,
+        turn_id: &str,
+    ) -> Option<QueuedTurn> {
+        self.update(|state| {
+            let index = state.pending.iter().position(|turn| {
+                turn.conversation_id == conversation_id && turn.turn_id == turn_id
+            })?;
+            state.pending.remove(index)
+        })
+    }
+
+    /// Empty the queue, e.g. on shutdown. Returns the turns that were waiting.
+    pub(crate) fn clear(&self) -> Vec<QueuedTurn> {
+        self.update(|state| state.pending.drain(..).collect())
+    }
+
+    fn has_capacity(&self, state: &QueueState, conversation_id: ConversationId) -> bool {
+        let running: usize = state.threads.values().map(ThreadTurns::in_flight).sum();
+        let on_thread = state
+            .threads
+            .get(&conversation_id)
+            .map_or(0, ThreadTurns::in_flight);
+        self.limits
+            .max_concurrent_turns
+            .is_none_or(|max| running < max)
+            && self
+                .limits
+                .max_turns_per_thread
+                .is_none_or(|max| on_thread < max)
+    }
+
+    /// Reserve slots for queued turns in FIFO order, skipping the ones whose
+    /// thread is still at its limit.
+    fn release(&self, state: &mut QueueState) -> Vec<QueuedTurn> {
+        state
+            .threads
+            .retain(|_, thread| thread.in_flight() > 0 || !thread.released.is_empty());
+        let mut ready = Vec::new();
+        let mut index = 0;
+        while index < state.pending.len() {
+            let conversation_id = state.pending[index].conversation_id;
+            if self.has_capacity(state, conversation_id)
+                && let Some(turn) = state.pending.remove(index)
+            {
+                reserve(state, &turn);
+                ready.push(turn);
+            } else {
+                index += 1;
+            }
+        }
+        ready
+    }
+
+    fn update<T>(&self, f: impl FnOnce(&mut QueueState) -> T) -> T {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        f(&mut state)
+    }
+}
+
+fn reserve(state: &mut QueueState, turn: &QueuedTurn) {
+    state
+        .threads
+        .entry(turn.conversation_id)
+        .or_default()
+        .submitted
+        .insert(turn.turn_id.clone());
+}
+
+/// Submit turns that left the queue and announce them with `turn/started`.
+/// A turn that cannot be submitted completes as failed and frees its slot.
+pub(crate) async fn start_turns(
+    queue: &TurnQueue,
+    mut turns: Vec<QueuedTurn>,
+    outgoing: &OutgoingMessageSender,
+) {
+    while let Some(turn) = turns.pop() {
+        match turn.submit().await {
+            Ok(()) => {
+                let notification = TurnStartedNotification {
+                    turn: turn.in_progress(),
+                };
+                in_thread(
+                    turn.conversation_id,
+                    outgoing
+                        .send_server_notification(ServerNotification::TurnStarted(notification)),
+                )
+                .await;
+            }
+            Err(err) => {
+                warn!("failed to start queued turn {}: {err}", turn.turn_id);
+                let status = TurnStatus::Failed {
+                    error: TurnError {
+                        message: format!("failed to start turn: {err}"),
+                        info: None,
+                    },
+                };
+                in_thread(
+                    turn.conversation_id,
+                    emit_turn_completed_with_status(
+                        turn.turn_id.clone(),
+                        status,
+                        None,
+                        None,
+                        None,
+                        outgoing,
+                    ),
+                )
+                .await;
+                turns.extend(queue.abandon(turn.conversation_id, &turn.turn_id));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::TaskCompleteEvent;
+    use codex_core::protocol::TaskStartedEvent;
+    use pretty_assertions::assert_eq;
+
+    fn event(turn_id: &str, msg: EventMsg) -> Event {
+        Event {
+            id: turn_id.to_string(),
+            msg,
+        }
+    }
+
+    fn started(turn_id: &str) -> Event {
+        event(
+            turn_id,
+            EventMsg::TaskStarted(TaskStartedEvent {
+                model_context_window: None,
+            }),
+        )
+    }
+
+    fn completed(turn_id: &str) -> Event {
+        event(
+            turn_id,
+            EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message: None,
+                turn_commit: None,
+                diff_summary: None,
+            }),
+        )
+    }
+
+    fn queue(
+        max_concurrent_turns: Option<usize>,
+        max_turns_per_thread: Option<usize>,
+    ) -> TurnQueue {
+        TurnQueue::new(AppServerConfig {
+            max_concurrent_turns,
+            max_turns_per_thread,
+        })
+    }
+
+    fn set_running(queue: &TurnQueue, conversation_id: ConversationId, turn_id: &str) {
+        queue.update(|state| {
+            state
+                .threads
+                .entry(conversation_id)
+                .or_default()
+                .started
+                .insert(turn_id.to_string());
+        });
+    }
+
+    fn in_flight(queue: &TurnQueue, conversation_id: ConversationId) -> usize {
+        queue.update(|state| {
+            state
+                .threads
+                .get(&conversation_id)
+                .map_or(0, ThreadTurns::in_flight)
+        })
+    }
+
+    #[test]
+    fn limits_apply_per_thread_and_overall() {
+        let queue = queue(Some(2), Some(1));
+        let a = ConversationId::new();
+        let b = ConversationId::new();
+        set_running(&queue, a, "a1");
+        assert!(queue.update(|state| !queue.has_capacity(state, a)));
+        assert!(queue.update(|state| queue.has_capacity(state, b)));
+
+        set_running(&queue, b, "b1");
+        assert!(queue.update(|state| !queue.has_capacity(state, b)));
+
+        assert!(queue.observe(a, &completed("a1")).is_empty());
+        assert!(queue.update(|state| queue.has_capacity(state, a)));
+    }
+
+    #[test]
+    fn turns_not_started_are_released_with_the_running_turn() {
+        let queue = queue(Some(1), None);
+        let conversation_id = ConversationId::new();
+        set_running(&queue, conversation_id, "a");
+        queue.update(|state| {
+            if let Some(thread) = state.threads.get_mut(&conversation_id) {
+                thread.submitted.insert("joined".to_string());
+                thread.submitted.insert("next".to_string());
+            }
+        });
+        assert_eq!(in_flight(&queue, conversation_id), 3);
+
+        queue.observe(conversation_id, &completed("a"));
+        assert_eq!(in_flight(&queue, conversation_id), 0);
+
+        // A released turn that does start is counted again.
+        queue.observe(conversation_id, &started("next"));
+        assert_eq!(in_flight(&queue, conversation_id), 1);
+    }
+}
@@ -0,0 +1,206 @@
+//! Compatibility layer for the deprecated v1 API.
+//!
+//! Handlers work with v2 semantics. Where a v1 client needs a different
+//! request or response shape, the translation lives here, so the v1 surface
+//! can eventually be removed by deleting this module and its call sites.
+//!
+//! Every v1 request is counted with a `codex.deprecated_api_call` telemetry
+//! event, and the first call of each deprecated method on a connection is
+//! answered with a `deprecationNotice` notification naming its replacement.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use codex_app_server_protocol::ApprovalDecision;
+use codex_app_server_protocol::CommandExecutionRequestAcceptSettings;
+use codex_app_server_protocol::CommandExecutionRequestApprovalResponse;
+use codex_app_server_protocol::DeprecationNoticeNotification;
+use codex_app_server_protocol::ExecCommandApprovalParams;
+use codex_app_server_protocol::ExecCommandApprovalResponse;
+use codex_app_server_protocol::InterruptConversationResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ServerNotification;
+use codex_app_server_protocol::TurnInterruptResponse;
+use codex_core::protocol::ExecApprovalRequestEvent;
+use codex_core::protocol::ReviewDecision;
+use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::WriteScopeDecision;
+use codex_otel::deprecation::deprecated_api_call;
+use codex_protocol::ConversationId;
+use serde_json::Value as JsonValue;
+
+use crate::codex_message_processor::ApiVersion;
+use crate::outgoing_message::OutgoingMessageSender;
+
+/// v1 methods and the v2 method that replaces each, if any.
+const V1_METHODS: &[(&str, Option<&str>)] = &[
+    ("newConversation", Some("thread/start")),
+    ("getConversationSummary", Some("thread/list")),
+    ("listConversations", Some("thread/list")),
+    ("resumeConversation", Some("thread/resume")),
+    ("archiveConversation", Some("thread/archive")),
+    ("sendUserMessage", Some("turn/start")),
+    ("sendUserTurn", Some("turn/start")),
+    ("interruptConversation", Some("turn/interrupt")),
+    ("addConversationListener", Some("thread/start")),
+    ("removeConversationListener", None),
+    ("gitDiffToRemote", None),
+    ("loginApiKey", Some("account/login/start")),
+    ("loginChatGpt", Some("account/login/start")),
+    ("cancelLoginChatGpt", Some("account/login/cancel")),
+    ("logoutChatGpt", Some("account/logout")),
+    ("getAuthStatus", Some("account/read")),
+    ("getUserSavedConfig", None),
+    ("setDefaultModel", None),
+    ("getUserAgent", None),
+    ("userInfo", Some("account/read")),
+    ("fuzzyFileSearch", None),
+    ("execOneOffCommand", None),
+];
+
+/// v1 has no request for widening the sandbox, so v1 clients keep the
+/// sandbox as configured.
+pub(crate) const V1_WRITE_SCOPE_DECISION: WriteScopeDecision = WriteScopeDecision::Deny;
+
+/// Calls of v1 methods on this connection.
+#[derive(Default)]
+pub(crate) struct V1Usage {
+    calls: Mutex<HashMap<&'static str, u64>>,
+}
+
+impl V1Usage {
+    /// Count `method` if it belongs to v1, and warn the client the first time
+    /// it calls a method that has a replacement.
+    pub(crate) async fn record(&self, method: &str, outgoing: &OutgoingMessageSender) {
+        let Some(&(method, replacement)) = V1_METHODS.iter().find(|(name, _)| *name == method)
+        else {
+            return;
+        };
+        let call_count = {
+            let mut calls = match self.calls.lock() {
+                Ok(calls) => calls,
+                Err(poisoned) => poisoned.into_inner(),
+            };
+            let count = calls.entry(method).or_default();
+            *count += 1;
+            *count
+        };
+        deprecated_api_call(method, replacement, call_count);
+
+        if call_count == 1
+            && let Some(replacement) = replacement
+        {
+            let notification = DeprecationNoticeNotification {
+                method: method.to_string(),
+                replacement: Some(replacement.to_string()),
+                message: format!(
+                    "`{method}` is deprecated and will be removed; use `{replacement}` instead."
+                ),
+            };
+            outgoing
+                .send_server_notification(ServerNotification::DeprecationNotice(notification))
+                .await;
+        }
+    }
+}
+
+/// The v1 `execCommandApproval` request for a command awaiting approval.
+pub(crate) fn exec_approval_params(
+    conversation_id: ConversationId,
+    request: ExecApprovalRequestEvent,
+) -> ExecCommandApprovalParams {
+    let ExecApprovalRequestEvent {
+        call_id,
+        item_id: _,
+        turn_id: _,
+        command,
+        cwd,
+        reason,
+        risk,
+        parsed_cmd,
+        proposed_rules: _,
+    } = request;
+    ExecCommandApprovalParams {
+        conversation_id,
+        call_id,
+        command,
+        cwd,
+        reason,
+        risk,
+        parsed_cmd,
+    }
+}
+
+/// Read a client's answer to a command approval request as the v2 answer.
+pub(crate) fn exec_approval_response(
+    api_version: ApiVersion,
+    value: JsonValue,
+) -> serde_json::Result<CommandExecutionRequestApprovalResponse> {
+    match api_version {
+        ApiVersion::V2 => serde_json::from_value(value),
+        ApiVersion::V1 => {
+            let ExecCommandApprovalResponse { decision } = serde_json::from_value(value)?;
+            let (decision, for_session) = match decision {
+                ReviewDecision::Approved => (ApprovalDecision::Accept, false),
+                ReviewDecision::ApprovedForSession => (ApprovalDecision::Accept, true),
+                ReviewDecision::Denied => (ApprovalDecision::Decline, false),
+                ReviewDecision::Abort => (ApprovalDecision::Cancel, false),
+            };
+            Ok(CommandExecutionRequestApprovalResponse {
+                decision,
+                accept_settings: for_session
+                    .then_some(CommandExecutionRequestAcceptSettings { for_session }),
+            })
+        }
+    }
+}
+
+/// Answer an interrupt request once the turn has been aborted.
+pub(crate) async fn respond_to_interrupt(
+    outgoing: &OutgoingMessageSender,
+    request_id: RequestId,
+    api_version: ApiVersion,
+    reason: &TurnAbortReason,
+) {
+    match api_version {
+        ApiVersion::V1 => {
+            let response = InterruptConversationResponse {
+                abort_reason: reason.clone(),
+            };
+            outgoing.send_response(request_id, response).await;
+        }
+        ApiVersion::V2 => {
+            outgoing
+                .send_response(request_id, TurnInterruptResponse {})
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn v1_exec_decisions_map_onto_v2() {
+        let for_session = exec_approval_response(
+            ApiVersion::V1,
+            json!({ "decision": "approved_for_session" }),
+        )
+        .expect("v1 response");
+        assert_eq!(
+            for_session,
+            CommandExecutionRequestApprovalResponse {
+                decision: ApprovalDecision::Accept,
+                accept_settings: Some(CommandExecutionRequestAcceptSettings { for_session: true }),
+            }
+        );
+
+        let abort = exec_approval_response(ApiVersion::V1, json!({ "decision": "abort" }))
+            .expect("v1 response");
+        assert_eq!(abort.decision, ApprovalDecision::Cancel);
+        assert_eq!(abort.accept_settings, None);
+    }
+}
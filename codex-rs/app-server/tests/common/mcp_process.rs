@@ -18,9 +18,13 @@ use codex_app_server_protocol::CancelLoginAccountParams;
 use codex_app_server_protocol::CancelLoginChatGptParams;
 use codex_app_server_protocol::ClientInfo;
 use codex_app_server_protocol::ClientNotification;
+use codex_app_server_protocol::ExecutionAssessParams;
+use codex_app_server_protocol::ExecutionGetOutputParams;
+use codex_app_server_protocol::ExperimentStatsParams;
 use codex_app_server_protocol::FeedbackUploadParams;
 use codex_app_server_protocol::GetAccountParams;
 use codex_app_server_protocol::GetAuthStatusParams;
+use codex_app_server_protocol::GetPatchContentParams;
 use codex_app_server_protocol::InitializeParams;
 use codex_app_server_protocol::InterruptConversationParams;
 use codex_app_server_protocol::JSONRPCError;
@@ -30,6 +34,7 @@ use codex_app_server_protocol::JSONRPCRequest;
 use codex_app_server_protocol::JSONRPCResponse;
 use codex_app_server_protocol::ListConversationsParams;
 use codex_app_server_protocol::LoginApiKeyParams;
+use codex_app_server_protocol::McpServerAuthReadParams;
 use codex_app_server_protocol::ModelListParams;
 use codex_app_server_protocol::NewConversationParams;
 use codex_app_server_protocol::RemoveConversationListenerParams;
@@ -41,11 +46,20 @@ use codex_app_server_protocol::SendUserTurnParams;
 use codex_app_server_protocol::ServerRequest;
 use codex_app_server_protocol::SetDefaultModelParams;
 use codex_app_server_protocol::ThreadArchiveParams;
+use codex_app_server_protocol::ThreadExportParams;
+use codex_app_server_protocol::ThreadForkParams;
 use codex_app_server_protocol::ThreadListParams;
+use codex_app_server_protocol::ThreadRenameParams;
 use codex_app_server_protocol::ThreadResumeParams;
+use codex_app_server_protocol::ThreadSearchParams;
 use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadUsageParams;
 use codex_app_server_protocol::TurnInterruptParams;
 use codex_app_server_protocol::TurnStartParams;
+use codex_app_server_protocol::WorkspaceInstructionAddParams;
+use codex_app_server_protocol::WorkspaceInstructionListParams;
+use codex_app_server_protocol::WorkspaceInstructionRemoveParams;
+use codex_app_server_protocol::WorkspaceInstructionUpdateParams;
 use std::process::Command as StdCommand;
 use tokio::process::Command;
 
@@ -227,6 +241,15 @@ impl McpProcess {
         self.send_request("interruptConversation", params).await
     }
 
+    /// Send a `getPatchContent` JSON-RPC request.
+    pub async fn send_get_patch_content_request(
+        &mut self,
+        params: GetPatchContentParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("getPatchContent", params).await
+    }
+
     /// Send a `getAuthStatus` JSON-RPC request.
     pub async fn send_get_auth_status_request(
         &mut self,
@@ -310,6 +333,15 @@ impl McpProcess {
         self.send_request("thread/resume", params).await
     }
 
+    /// Send a `thread/fork` JSON-RPC request.
+    pub async fn send_thread_fork_request(
+        &mut self,
+        params: ThreadForkParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/fork", params).await
+    }
+
     /// Send a `thread/archive` JSON-RPC request.
     pub async fn send_thread_archive_request(
         &mut self,
@@ -319,6 +351,116 @@ impl McpProcess {
         self.send_request("thread/archive", params).await
     }
 
+    /// Send a `thread/rename` JSON-RPC request.
+    pub async fn send_thread_rename_request(
+        &mut self,
+        params: ThreadRenameParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/rename", params).await
+    }
+
+    /// Send a `threads/search` JSON-RPC request.
+    pub async fn send_threads_search_request(
+        &mut self,
+        params: ThreadSearchParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("threads/search", params).await
+    }
+
+    /// Send a `thread/export` JSON-RPC request.
+    pub async fn send_thread_export_request(
+        &mut self,
+        params: ThreadExportParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/export", params).await
+    }
+
+    /// Send a `thread/usage` JSON-RPC request.
+    pub async fn send_thread_usage_request(
+        &mut self,
+        params: ThreadUsageParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("thread/usage", params).await
+    }
+
+    /// Send an `execution/assess` JSON-RPC request.
+    pub async fn send_execution_assess_request(
+        &mut self,
+        params: ExecutionAssessParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("execution/assess", params).await
+    }
+
+    /// Send an `execution/getOutput` JSON-RPC request.
+    pub async fn send_execution_get_output_request(
+        &mut self,
+        params: ExecutionGetOutputParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("execution/getOutput", params).await
+    }
+
+    /// Send a `workspaceInstruction/list` JSON-RPC request.
+    pub async fn send_workspace_instruction_list_request(
+        &mut self,
+        params: WorkspaceInstructionListParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("workspaceInstruction/list", params).await
+    }
+
+    /// Send a `workspaceInstruction/add` JSON-RPC request.
+    pub async fn send_workspace_instruction_add_request(
+        &mut self,
+        params: WorkspaceInstructionAddParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("workspaceInstruction/add", params).await
+    }
+
+    /// Send a `workspaceInstruction/update` JSON-RPC request.
+    pub async fn send_workspace_instruction_update_request(
+        &mut self,
+        params: WorkspaceInstructionUpdateParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("workspaceInstruction/update", params)
+            .await
+    }
+
+    /// Send a `workspaceInstruction/remove` JSON-RPC request.
+    pub async fn send_workspace_instruction_remove_request(
+        &mut self,
+        params: WorkspaceInstructionRemoveParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("workspaceInstruction/remove", params)
+            .await
+    }
+
+    /// Send an `experiment/stats` JSON-RPC request.
+    pub async fn send_experiment_stats_request(
+        &mut self,
+        params: ExperimentStatsParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("experiment/stats", params).await
+    }
+
+    /// Send an `mcpServer/auth/read` JSON-RPC request.
+    pub async fn send_mcp_server_auth_read_request(
+        &mut self,
+        params: McpServerAuthReadParams,
+    ) -> anyhow::Result<i64> {
+        let params = Some(serde_json::to_value(params)?);
+        self.send_request("mcpServer/auth/read", params).await
+    }
+
     /// Send a `thread/list` JSON-RPC request.
     pub async fn send_thread_list_request(
         &mut self,
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use codex_core::ROLLOUT_SCHEMA_VERSION;
 use codex_protocol::ConversationId;
 use codex_protocol::protocol::SessionMeta;
 use codex_protocol::protocol::SessionSource;
@@ -46,6 +47,7 @@ pub fn create_fake_rollout(
         instructions: None,
         source: SessionSource::Cli,
         model_provider: model_provider.map(str::to_string),
+        schema_version: ROLLOUT_SCHEMA_VERSION,
     })?;
 
     let lines = [
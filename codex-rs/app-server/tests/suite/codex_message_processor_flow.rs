@@ -1,5 +1,6 @@
 use anyhow::Result;
 use app_test_support::McpProcess;
+use app_test_support::create_apply_patch_sse_response;
 use app_test_support::create_final_assistant_message_sse_response;
 use app_test_support::create_mock_chat_completions_server;
 use app_test_support::create_shell_sse_response;
@@ -7,6 +8,8 @@ use app_test_support::to_response;
 use codex_app_server_protocol::AddConversationListenerParams;
 use codex_app_server_protocol::AddConversationSubscriptionResponse;
 use codex_app_server_protocol::ExecCommandApprovalParams;
+use codex_app_server_protocol::GetPatchContentParams;
+use codex_app_server_protocol::GetPatchContentResponse;
 use codex_app_server_protocol::InputItem;
 use codex_app_server_protocol::JSONRPCNotification;
 use codex_app_server_protocol::JSONRPCResponse;
@@ -21,6 +24,7 @@ use codex_app_server_protocol::SendUserTurnParams;
 use codex_app_server_protocol::SendUserTurnResponse;
 use codex_app_server_protocol::ServerRequest;
 use codex_core::protocol::AskForApproval;
+use codex_core::protocol::FileChange;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol_config_types::ReasoningEffort;
 use codex_core::protocol_config_types::ReasoningSummary;
@@ -331,6 +335,141 @@ async fn test_send_user_turn_changes_approval_policy_behavior() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_get_patch_content_returns_deferred_changes() -> Result<()> {
+    if env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return Ok(());
+    }
+
+    let tmp = TempDir::new()?;
+    let codex_home = tmp.path().join("codex_home");
+    std::fs::create_dir(&codex_home)?;
+    let working_directory = tmp.path().join("workdir");
+    std::fs::create_dir(&working_directory)?;
+
+    // Large enough that the approval request defers the file's body.
+    let content: String = (0..40_000).map(|i| format!("line {i}\n")).collect();
+    let added_lines: String = content.lines().map(|line| format!("+{line}\n")).collect();
+    let patch = format!("*** Begin Patch\n*** Add File: big.txt\n{added_lines}*** End Patch");
+    let responses = vec![
+        create_apply_patch_sse_response(&patch, "patch-call")?,
+        create_final_assistant_message_sse_response("done")?,
+    ];
+    let server = create_mock_chat_completions_server(responses).await;
+    create_config_toml(&codex_home, &server.uri())?;
+
+    let mut mcp = McpProcess::new(&codex_home).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let new_conv_id = mcp
+        .send_new_conversation_request(NewConversationParams {
+            cwd: Some(working_directory.to_string_lossy().into_owned()),
+            ..Default::default()
+        })
+        .await?;
+    let NewConversationResponse {
+        conversation_id, ..
+    } = to_response::<NewConversationResponse>(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp.read_stream_until_response_message(RequestId::Integer(new_conv_id)),
+        )
+        .await??,
+    )?;
+    let add_listener_id = mcp
+        .send_add_conversation_listener_request(AddConversationListenerParams {
+            conversation_id,
+            experimental_raw_events: false,
+        })
+        .await?;
+    let _: AddConversationSubscriptionResponse = to_response::<AddConversationSubscriptionResponse>(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp.read_stream_until_response_message(RequestId::Integer(add_listener_id)),
+        )
+        .await??,
+    )?;
+
+    let send_user_id = mcp
+        .send_send_user_message_request(SendUserMessageParams {
+            conversation_id,
+            items: vec![codex_app_server_protocol::InputItem::Text {
+                text: "write a big file".to_string(),
+            }],
+        })
+        .await?;
+    let _: SendUserMessageResponse = to_response::<SendUserMessageResponse>(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp.read_stream_until_response_message(RequestId::Integer(send_user_id)),
+        )
+        .await??,
+    )?;
+
+    let request = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_request_message(),
+    )
+    .await??;
+    let ServerRequest::ApplyPatchApproval { request_id, params } = request else {
+        panic!("expected ApplyPatchApproval request, got: {request:?}");
+    };
+    assert!(params.file_changes.is_empty());
+    assert_eq!(params.deferred_file_changes.len(), 1);
+    let path = params.deferred_file_changes[0].path.clone();
+    assert!(path.ends_with("big.txt"));
+
+    // While the approval is pending, its body can be fetched.
+    let content_id = mcp
+        .send_get_patch_content_request(GetPatchContentParams {
+            conversation_id,
+            call_id: params.call_id.clone(),
+            path: path.clone(),
+        })
+        .await?;
+    let GetPatchContentResponse { change } = to_response::<GetPatchContentResponse>(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp.read_stream_until_response_message(RequestId::Integer(content_id)),
+        )
+        .await??,
+    )?;
+    assert_eq!(change, Some(FileChange::Add { content }));
+
+    // Paths outside the patch come back empty.
+    let missing_id = mcp
+        .send_get_patch_content_request(GetPatchContentParams {
+            conversation_id,
+            call_id: params.call_id,
+            path: working_directory.join("other.txt"),
+        })
+        .await?;
+    let GetPatchContentResponse { change } = to_response::<GetPatchContentResponse>(
+        timeout(
+            DEFAULT_READ_TIMEOUT,
+            mcp.read_stream_until_response_message(RequestId::Integer(missing_id)),
+        )
+        .await??,
+    )?;
+    assert_eq!(change, None);
+
+    mcp.send_response(
+        request_id,
+        serde_json::json!({ "decision": codex_core::protocol::ReviewDecision::Denied }),
+    )
+    .await?;
+    timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("codex/event/task_complete"),
+    )
+    .await??;
+
+    Ok(())
+}
+
 // Helper: minimal config.toml pointing at mock provider.
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
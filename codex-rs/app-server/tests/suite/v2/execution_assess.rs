@@ -0,0 +1,105 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::to_response;
+use codex_app_server_protocol::AskForApproval;
+use codex_app_server_protocol::ExecPolicyDecision;
+use codex_app_server_protocol::ExecutionApproval;
+use codex_app_server_protocol::ExecutionAssessParams;
+use codex_app_server_protocol::ExecutionAssessResponse;
+use codex_app_server_protocol::ExecutionSandbox;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::SandboxMode;
+use codex_app_server_protocol::SandboxPolicy;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn execution_assess_reports_policy_and_approval_without_running() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+    let workdir = TempDir::new()?;
+    let marker = workdir.path().join("marker");
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_execution_assess_request(ExecutionAssessParams {
+            command: vec![
+                "bash".to_string(),
+                "-lc".to_string(),
+                format!("touch {} && rm -rf build", marker.display()),
+            ],
+            cwd: Some(workdir.path().display().to_string()),
+            approval_policy: Some(AskForApproval::OnRequest),
+            sandbox: Some(SandboxMode::ReadOnly),
+            config: None,
+            with_escalated_permissions: false,
+        })
+        .await?;
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    let assessment = to_response::<ExecutionAssessResponse>(response)?;
+
+    assert_eq!(
+        assessment.policy_decision,
+        Some(ExecPolicyDecision::Forbidden)
+    );
+    assert!(matches!(
+        assessment.approval,
+        ExecutionApproval::Forbidden { .. }
+    ));
+    assert!(!assessment.approved_by_rule);
+    assert_eq!(assessment.approval_policy, AskForApproval::OnRequest);
+    assert_eq!(assessment.sandbox_policy, SandboxPolicy::ReadOnly);
+    assert!(!marker.exists(), "assessing must not run the command");
+
+    let request_id = mcp
+        .send_execution_assess_request(ExecutionAssessParams {
+            command: vec!["ls".to_string()],
+            cwd: Some(workdir.path().display().to_string()),
+            approval_policy: Some(AskForApproval::OnRequest),
+            sandbox: None,
+            config: None,
+            with_escalated_permissions: true,
+        })
+        .await?;
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+    let assessment = to_response::<ExecutionAssessResponse>(response)?;
+    assert_eq!(assessment.policy_decision, None);
+    assert_eq!(assessment.sandbox, ExecutionSandbox::None);
+
+    Ok(())
+}
+
+fn create_config_toml(codex_home: &Path) -> std::io::Result<()> {
+    std::fs::write(
+        codex_home.join("config.toml"),
+        r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "workspace-write"
+
+[features]
+exec_policy = true
+"#,
+    )?;
+    let policy_dir = codex_home.join("policy");
+    std::fs::create_dir_all(&policy_dir)?;
+    std::fs::write(
+        policy_dir.join("default.codexpolicy"),
+        r#"prefix_rule(pattern=["rm"], decision="forbidden")"#,
+    )
+}
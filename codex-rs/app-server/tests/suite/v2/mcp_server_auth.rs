@@ -0,0 +1,90 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::McpServerAuthReadParams;
+use codex_app_server_protocol::McpServerAuthReadResponse;
+use codex_app_server_protocol::RequestId;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn mcp_server_auth_read_reports_configured_scopes_without_login() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_mcp_server_auth_read_request(McpServerAuthReadParams {
+            name: "docs".to_string(),
+        })
+        .await?;
+    let response: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+
+    assert_eq!(
+        to_response::<McpServerAuthReadResponse>(response)?,
+        McpServerAuthReadResponse {
+            requested_scopes: Some(vec!["docs:read".to_string()]),
+            logged_in: false,
+            granted_scopes: None,
+            claims: None,
+            expires_at: None,
+        }
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn mcp_server_auth_read_rejects_unknown_server() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let request_id = mcp
+        .send_mcp_server_auth_read_request(McpServerAuthReadParams {
+            name: "missing".to_string(),
+        })
+        .await?;
+    let error: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(request_id)),
+    )
+    .await??;
+
+    assert_eq!(
+        error.error.message,
+        "no MCP server named `missing` is configured"
+    );
+
+    Ok(())
+}
+
+fn create_config_toml(codex_home: &Path) -> std::io::Result<()> {
+    std::fs::write(
+        codex_home.join("config.toml"),
+        r#"
+model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+mcp_oauth_credentials_store = "file"
+
+[mcp_servers.docs]
+url = "http://127.0.0.1:1/mcp"
+scopes = ["docs:read"]
+"#,
+    )
+}
@@ -1,10 +1,20 @@
 mod account;
+mod execution_assess;
+mod execution_get_output;
+mod experiment_stats;
+mod mcp_server_auth;
 mod model_list;
 mod rate_limits;
 mod review;
 mod thread_archive;
+mod thread_export;
+mod thread_fork;
 mod thread_list;
+mod thread_rename;
 mod thread_resume;
+mod thread_search;
 mod thread_start;
+mod thread_usage;
 mod turn_interrupt;
 mod turn_start;
+mod workspace_instruction;
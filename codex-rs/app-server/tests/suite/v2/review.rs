@@ -9,6 +9,7 @@ use codex_app_server_protocol::JSONRPCError;
 use codex_app_server_protocol::JSONRPCNotification;
 use codex_app_server_protocol::JSONRPCResponse;
 use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ReviewSeverity;
 use codex_app_server_protocol::ReviewStartParams;
 use codex_app_server_protocol::ReviewTarget;
 use codex_app_server_protocol::ThreadItem;
@@ -16,7 +17,9 @@ use codex_app_server_protocol::ThreadStartParams;
 use codex_app_server_protocol::ThreadStartResponse;
 use codex_app_server_protocol::TurnStartResponse;
 use codex_app_server_protocol::TurnStatus;
+use pretty_assertions::assert_eq;
 use serde_json::json;
+use std::path::PathBuf;
 use tempfile::TempDir;
 use tokio::time::timeout;
 
@@ -35,7 +38,8 @@ async fn review_start_runs_review_turn_and_emits_code_review_item() -> Result<()
                 "code_location": {
                     "absolute_file_path": "/tmp/file.rs",
                     "line_range": {"start": 10, "end": 20}
-                }
+                },
+                "suggestion": "let style = Style::default().dim();"
             }
         ],
         "overall_correctness": "good",
@@ -130,6 +134,29 @@ async fn review_start_runs_review_turn_and_emits_code_review_item() -> Result<()
     assert!(review.contains("Prefer Stylize helpers"));
     assert!(review.contains("/tmp/file.rs:10-20"));
 
+    let finding_notif: JSONRPCNotification = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("item/completed"),
+    )
+    .await??;
+    let completed: ItemCompletedNotification =
+        serde_json::from_value(finding_notif.params.expect("params must be present"))?;
+    assert_eq!(
+        completed.item,
+        ThreadItem::ReviewFinding {
+            id: format!("{turn_id}-finding-0"),
+            review_id: turn_id.clone(),
+            title: "Prefer Stylize helpers".to_string(),
+            body: "Use .dim()/.bold() chaining instead of manual Style.".to_string(),
+            path: PathBuf::from("/tmp/file.rs"),
+            line_start: 10,
+            line_end: 20,
+            severity: ReviewSeverity::High,
+            confidence_score: 0.9,
+            suggestion: Some("let style = Style::default().dim();".to_string()),
+        }
+    );
+
     Ok(())
 }
 
@@ -0,0 +1,80 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadExportFormat;
+use codex_app_server_protocol::ThreadExportParams;
+use codex_app_server_protocol::ThreadExportResponse;
+use codex_app_server_protocol::ThreadStartParams;
+use codex_app_server_protocol::ThreadStartResponse;
+use pretty_assertions::assert_eq;
+use std::path::Path;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_export_writes_json_transcript() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path())?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let start_id = mcp
+        .send_thread_start_request(ThreadStartParams {
+            model: Some("mock-model".to_string()),
+            ..Default::default()
+        })
+        .await?;
+    let start_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(start_id)),
+    )
+    .await??;
+    let ThreadStartResponse { thread, .. } = to_response::<ThreadStartResponse>(start_resp)?;
+
+    let output_path = codex_home.path().join("exports").join("transcript.json");
+    let export_id = mcp
+        .send_thread_export_request(ThreadExportParams {
+            thread_id: thread.id.clone(),
+            format: ThreadExportFormat::Json,
+            output_path: output_path.clone(),
+        })
+        .await?;
+    let export_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(export_id)),
+    )
+    .await??;
+    let response = to_response::<ThreadExportResponse>(export_resp)?;
+    assert_eq!(
+        response,
+        ThreadExportResponse {
+            output_path: output_path.clone(),
+            entry_count: 0,
+        }
+    );
+
+    let transcript: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&output_path)?)?;
+    assert_eq!(transcript["version"], 1);
+    assert_eq!(transcript["thread_id"], thread.id);
+    assert_eq!(transcript["entries"], serde_json::json!([]));
+
+    Ok(())
+}
+
+fn create_config_toml(codex_home: &Path) -> std::io::Result<()> {
+    let config_toml = codex_home.join("config.toml");
+    std::fs::write(config_toml, config_contents())
+}
+
+fn config_contents() -> &'static str {
+    r#"model = "mock-model"
+approval_policy = "never"
+sandbox_mode = "read-only"
+"#
+}
@@ -0,0 +1,99 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::ThreadListParams;
+use codex_app_server_protocol::ThreadListResponse;
+use codex_app_server_protocol::ThreadMetadataChangedNotification;
+use codex_app_server_protocol::ThreadRenameParams;
+use codex_app_server_protocol::ThreadRenameResponse;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_rename_titles_a_stored_thread() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        r#"
+model = "mock-model"
+approval_policy = "never"
+"#,
+    )?;
+    let thread_id = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-02T12-00-00",
+        "2025-01-02T12:00:00Z",
+        "Hello",
+        Some("mock_provider"),
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let rename_id = mcp
+        .send_thread_rename_request(ThreadRenameParams {
+            thread_id: thread_id.clone(),
+            title: "  Say   hello ".to_string(),
+        })
+        .await?;
+    let rename_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(rename_id)),
+    )
+    .await??;
+    let _: ThreadRenameResponse = to_response::<ThreadRenameResponse>(rename_resp)?;
+
+    let notification = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_notification_message("thread/metadataChanged"),
+    )
+    .await??;
+    let changed: ThreadMetadataChangedNotification =
+        serde_json::from_value(notification.params.expect("params"))?;
+    assert_eq!(
+        changed,
+        ThreadMetadataChangedNotification {
+            thread_id: thread_id.clone(),
+            title: Some("Say hello".to_string()),
+        }
+    );
+
+    let list_id = mcp
+        .send_thread_list_request(ThreadListParams {
+            cursor: None,
+            limit: Some(10),
+            model_providers: Some(vec!["mock_provider".to_string()]),
+        })
+        .await?;
+    let list_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(list_id)),
+    )
+    .await??;
+    let ThreadListResponse { data, .. } = to_response::<ThreadListResponse>(list_resp)?;
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0].id, thread_id);
+    assert_eq!(data[0].title.as_deref(), Some("Say hello"));
+
+    let blank_id = mcp
+        .send_thread_rename_request(ThreadRenameParams {
+            thread_id,
+            title: " ".to_string(),
+        })
+        .await?;
+    let blank_err: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(blank_id)),
+    )
+    .await??;
+    assert_eq!(blank_err.error.message, "title must not be empty");
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::create_fake_rollout;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::ModelTask;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::RequestTokenUsage;
+use codex_app_server_protocol::SourceTokenUsage;
+use codex_app_server_protocol::ThreadUsageParams;
+use codex_app_server_protocol::ThreadUsageResponse;
+use codex_app_server_protocol::TokenCounts;
+use codex_app_server_protocol::UsageAttribution;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ModelTask as CoreModelTask;
+use codex_protocol::protocol::RequestTokenUsage as CoreRequestTokenUsage;
+use codex_protocol::protocol::TokenCountEvent;
+use codex_protocol::protocol::TokenUsage;
+use codex_protocol::protocol::TokenUsageBreakdown;
+use codex_protocol::protocol::UsageAttribution as CoreUsageAttribution;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn thread_usage_lists_requests_and_totals_by_source() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    std::fs::write(
+        codex_home.path().join("config.toml"),
+        "model = \"mock-model\"\napproval_policy = \"never\"\n",
+    )?;
+    let thread_id = create_fake_rollout(
+        codex_home.path(),
+        "2025-01-05T12-00-00",
+        "2025-01-05T12:00:00Z",
+        "hello",
+        Some("mock_provider"),
+    )?;
+
+    let mut breakdown = TokenUsageBreakdown::default();
+    breakdown.record(request(0, &["shell", "shell"], 200));
+    let first = breakdown.clone();
+    breakdown.record(request(1, &["message"], 50));
+    // The second token count repeats the first request and must not be
+    // counted twice.
+    append_token_counts(
+        &rollout_path(codex_home.path())?,
+        &[&first, &first, &breakdown],
+    )?;
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let usage_id = mcp
+        .send_thread_usage_request(ThreadUsageParams { thread_id })
+        .await?;
+    let usage_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(usage_id)),
+    )
+    .await??;
+    let response = to_response::<ThreadUsageResponse>(usage_resp)?;
+
+    assert_eq!(
+        response.by_source,
+        vec![
+            SourceTokenUsage {
+                source: "shell".to_string(),
+                usage: counts(200),
+            },
+            SourceTokenUsage {
+                source: "message".to_string(),
+                usage: counts(50),
+            },
+        ]
+    );
+    assert_eq!(response.retries, counts(50));
+    assert_eq!(
+        response.requests,
+        vec![
+            RequestTokenUsage {
+                turn_id: "turn-0".to_string(),
+                task: ModelTask::Main,
+                model: "mock-model".to_string(),
+                attempt: 0,
+                items: vec![
+                    UsageAttribution {
+                        id: None,
+                        source: "shell".to_string(),
+                    },
+                    UsageAttribution {
+                        id: None,
+                        source: "shell".to_string(),
+                    },
+                ],
+                usage: counts(200),
+            },
+            RequestTokenUsage {
+                turn_id: "turn-1".to_string(),
+                task: ModelTask::Main,
+                model: "mock-model".to_string(),
+                attempt: 1,
+                items: vec![UsageAttribution {
+                    id: None,
+                    source: "message".to_string(),
+                }],
+                usage: counts(50),
+            },
+        ]
+    );
+
+    Ok(())
+}
+
+fn request(index: u32, sources: &[&str], input_tokens: i64) -> CoreRequestTokenUsage {
+    CoreRequestTokenUsage {
+        turn_id: format!("turn-{index}"),
+        task: CoreModelTask::Main,
+        model: "mock-model".to_string(),
+        attempt: index,
+        items: sources
+            .iter()
+            .map(|source| CoreUsageAttribution {
+                id: None,
+                source: source.to_string(),
+            })
+            .collect(),
+        usage: TokenUsage {
+            input_tokens,
+            total_tokens: input_tokens,
+            ..Default::default()
+        },
+    }
+}
+
+fn counts(input_tokens: i64) -> TokenCounts {
+    TokenCounts {
+        input_tokens,
+        total_tokens: input_tokens,
+        ..Default::default()
+    }
+}
+
+fn rollout_path(codex_home: &Path) -> Result<PathBuf> {
+    let dir = codex_home.join("sessions/2025/01/05");
+    let entry = std::fs::read_dir(dir)?
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("rollout file missing"))??;
+    Ok(entry.path())
+}
+
+fn append_token_counts(path: &Path, breakdowns: &[&TokenUsageBreakdown]) -> Result<()> {
+    let mut file = std::fs::OpenOptions::new().append(true).open(path)?;
+    for breakdown in breakdowns {
+        let event = EventMsg::TokenCount(TokenCountEvent {
+            info: None,
+            rate_limits: None,
+            cost: None,
+            breakdown: Some((*breakdown).clone()),
+        });
+        let line = json!({
+            "timestamp": "2025-01-05T12:00:01Z",
+            "type": "event_msg",
+            "payload": serde_json::to_value(event)?,
+        });
+        writeln!(file, "{line}")?;
+    }
+    Ok(())
+}
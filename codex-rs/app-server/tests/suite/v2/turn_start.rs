@@ -5,6 +5,7 @@ use app_test_support::create_mock_chat_completions_server;
 use app_test_support::create_mock_chat_completions_server_unchecked;
 use app_test_support::create_shell_sse_response;
 use app_test_support::to_response;
+use codex_app_server_protocol::CommandExecutionRequestApprovalParams;
 use codex_app_server_protocol::CommandExecutionStatus;
 use codex_app_server_protocol::ItemStartedNotification;
 use codex_app_server_protocol::JSONRPCNotification;
@@ -252,11 +253,12 @@ async fn turn_start_exec_approval_toggle_v2() -> Result<()> {
         })
         .await?;
     // Acknowledge RPC
-    timeout(
+    let first_turn_resp: JSONRPCResponse = timeout(
         DEFAULT_READ_TIMEOUT,
         mcp.read_stream_until_response_message(RequestId::Integer(first_turn_id)),
     )
     .await??;
+    let TurnStartResponse { turn: first_turn } = to_response::<TurnStartResponse>(first_turn_resp)?;
 
     // Receive elicitation
     let server_req = timeout(
@@ -267,7 +269,32 @@ async fn turn_start_exec_approval_toggle_v2() -> Result<()> {
     let ServerRequest::CommandExecutionRequestApproval { request_id, params } = server_req else {
         panic!("expected CommandExecutionRequestApproval request");
     };
-    assert_eq!(params.item_id, "call1");
+    // The command's item was started before its approval was requested.
+    let command_item_id = timeout(DEFAULT_READ_TIMEOUT, async {
+        loop {
+            let notification = mcp
+                .read_stream_until_notification_message("item/started")
+                .await?;
+            let item_started: ItemStartedNotification =
+                serde_json::from_value(notification.params.expect("item/started params"))?;
+            if let ThreadItem::CommandExecution { id, .. } = item_started.item {
+                return Ok::<String, anyhow::Error>(id);
+            }
+        }
+    })
+    .await??;
+
+    // The request names the item of the command run, not the model's call id.
+    assert_eq!(
+        params,
+        CommandExecutionRequestApprovalParams {
+            thread_id: thread.id.clone(),
+            turn_id: first_turn.id,
+            item_id: command_item_id,
+            reason: None,
+            risk: None,
+        }
+    );
 
     // Approve and wait for task completion
     mcp.send_response(
@@ -398,6 +425,7 @@ async fn turn_start_updates_sandbox_and_cwd_between_turns_v2() -> Result<()> {
             model: Some("mock-model".to_string()),
             effort: Some(ReasoningEffort::Medium),
             summary: Some(ReasoningSummary::Auto),
+            env: None,
         })
         .await?;
     timeout(
@@ -424,6 +452,7 @@ async fn turn_start_updates_sandbox_and_cwd_between_turns_v2() -> Result<()> {
             model: Some("mock-model".to_string()),
             effort: Some(ReasoningEffort::Medium),
             summary: Some(ReasoningSummary::Auto),
+            env: None,
         })
         .await?;
     timeout(
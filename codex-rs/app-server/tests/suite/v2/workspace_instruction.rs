@@ -0,0 +1,126 @@
+use anyhow::Result;
+use app_test_support::McpProcess;
+use app_test_support::to_response;
+use codex_app_server_protocol::JSONRPCError;
+use codex_app_server_protocol::JSONRPCResponse;
+use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::WorkspaceInstruction;
+use codex_app_server_protocol::WorkspaceInstructionAddParams;
+use codex_app_server_protocol::WorkspaceInstructionAddResponse;
+use codex_app_server_protocol::WorkspaceInstructionListParams;
+use codex_app_server_protocol::WorkspaceInstructionListResponse;
+use codex_app_server_protocol::WorkspaceInstructionRemoveParams;
+use codex_app_server_protocol::WorkspaceInstructionRemoveResponse;
+use codex_app_server_protocol::WorkspaceInstructionUpdateParams;
+use codex_app_server_protocol::WorkspaceInstructionUpdateResponse;
+use codex_core::workspace_instructions::WORKSPACE_INSTRUCTIONS_FILENAME;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[tokio::test]
+async fn workspace_instructions_can_be_added_listed_updated_and_removed() -> Result<()> {
+    let codex_home = TempDir::new()?;
+    let project = TempDir::new()?;
+    let cwd = project.path().to_path_buf();
+
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let add_id = mcp
+        .send_workspace_instruction_add_request(WorkspaceInstructionAddParams {
+            cwd: cwd.clone(),
+            text: "never touch generated/ files".to_string(),
+        })
+        .await?;
+    let add_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(add_id)),
+    )
+    .await??;
+    let WorkspaceInstructionAddResponse { instruction } =
+        to_response::<WorkspaceInstructionAddResponse>(add_resp)?;
+    assert_eq!(instruction.text, "never touch generated/ files");
+    assert!(
+        project
+            .path()
+            .join(".codex")
+            .join(WORKSPACE_INSTRUCTIONS_FILENAME)
+            .exists()
+    );
+
+    let update_id = mcp
+        .send_workspace_instruction_update_request(WorkspaceInstructionUpdateParams {
+            cwd: cwd.clone(),
+            id: instruction.id.clone(),
+            text: "never edit generated/ or vendor/".to_string(),
+        })
+        .await?;
+    let update_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(update_id)),
+    )
+    .await??;
+    let updated = to_response::<WorkspaceInstructionUpdateResponse>(update_resp)?;
+    let expected = WorkspaceInstruction {
+        id: instruction.id.clone(),
+        text: "never edit generated/ or vendor/".to_string(),
+    };
+    assert_eq!(
+        updated,
+        WorkspaceInstructionUpdateResponse {
+            instruction: expected.clone(),
+        }
+    );
+
+    let list_id = mcp
+        .send_workspace_instruction_list_request(WorkspaceInstructionListParams {
+            cwd: cwd.clone(),
+        })
+        .await?;
+    let list_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(list_id)),
+    )
+    .await??;
+    assert_eq!(
+        to_response::<WorkspaceInstructionListResponse>(list_resp)?,
+        WorkspaceInstructionListResponse {
+            data: vec![expected],
+        }
+    );
+
+    let remove_id = mcp
+        .send_workspace_instruction_remove_request(WorkspaceInstructionRemoveParams {
+            cwd: cwd.clone(),
+            id: instruction.id.clone(),
+        })
+        .await?;
+    let remove_resp: JSONRPCResponse = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Integer(remove_id)),
+    )
+    .await??;
+    let _: WorkspaceInstructionRemoveResponse = to_response(remove_resp)?;
+
+    let missing_id = mcp
+        .send_workspace_instruction_remove_request(WorkspaceInstructionRemoveParams {
+            cwd,
+            id: instruction.id,
+        })
+        .await?;
+    let missing_err: JSONRPCError = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_error_message(RequestId::Integer(missing_id)),
+    )
+    .await??;
+    assert!(
+        missing_err.error.message.contains("not found"),
+        "unexpected error: {}",
+        missing_err.error.message
+    );
+
+    Ok(())
+}
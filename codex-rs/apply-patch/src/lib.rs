@@ -13,7 +13,7 @@ use anyhow::Result;
 pub use parser::Hunk;
 pub use parser::ParseError;
 use parser::ParseError::*;
-use parser::UpdateFileChunk;
+pub use parser::UpdateFileChunk;
 pub use parser::parse_patch;
 use similar::TextDiff;
 use thiserror::Error;
@@ -179,6 +179,79 @@ impl ApplyPatchAction {
         &self.changes
     }
 
+    /// Re-check every change against the current filesystem without writing
+    /// anything, so callers can tell whether applying the patch now would
+    /// fail or clobber edits made since the patch was verified.
+    pub fn dry_run(&self) -> HashMap<PathBuf, DryRunOutcome> {
+        let hunks = parse_patch(&self.patch)
+            .map(|args| args.hunks)
+            .unwrap_or_default();
+        self.changes
+            .iter()
+            .map(|(path, change)| {
+                let outcome = match change {
+                    ApplyPatchFileChange::Add { .. } => {
+                        if path.exists() {
+                            DryRunOutcome::Conflict(format!(
+                                "{} already exists and would be overwritten",
+                                path.display()
+                            ))
+                        } else {
+                            DryRunOutcome::Clean
+                        }
+                    }
+                    ApplyPatchFileChange::Delete { content } => {
+                        match std::fs::read_to_string(path) {
+                            Ok(current) if current == *content => DryRunOutcome::Clean,
+                            Ok(_) => DryRunOutcome::Changed,
+                            Err(err) => DryRunOutcome::Conflict(format!(
+                                "Failed to read {}: {err}",
+                                path.display()
+                            )),
+                        }
+                    }
+                    ApplyPatchFileChange::Update {
+                        move_path,
+                        new_content,
+                        ..
+                    } => self.dry_run_update(&hunks, path, move_path.as_deref(), new_content),
+                };
+                (path.clone(), outcome)
+            })
+            .collect()
+    }
+
+    fn dry_run_update(
+        &self,
+        hunks: &[Hunk],
+        path: &Path,
+        move_path: Option<&Path>,
+        expected_content: &str,
+    ) -> DryRunOutcome {
+        if let Some(dest) = move_path
+            && dest.exists()
+        {
+            return DryRunOutcome::Conflict(format!(
+                "{} already exists and would be overwritten",
+                dest.display()
+            ));
+        }
+        let chunks = hunks.iter().find_map(|hunk| match hunk {
+            Hunk::UpdateFile { chunks, .. } if hunk.resolve_path(&self.cwd) == path => Some(chunks),
+            _ => None,
+        });
+        let Some(chunks) = chunks else {
+            return DryRunOutcome::Clean;
+        };
+        match derive_new_contents_from_chunks(path, chunks) {
+            Ok(AppliedPatch { new_contents, .. }) if new_contents == expected_content => {
+                DryRunOutcome::Clean
+            }
+            Ok(_) => DryRunOutcome::Changed,
+            Err(err) => DryRunOutcome::Conflict(err.to_string()),
+        }
+    }
+
     /// Should be used exclusively for testing. (Not worth the overhead of
     /// creating a feature flag for this.)
     pub fn new_add_for_test(path: &Path, content: String) -> Self {
@@ -211,6 +284,18 @@ impl ApplyPatchAction {
     }
 }
 
+/// Result of [`ApplyPatchAction::dry_run`] for a single file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DryRunOutcome {
+    /// The change still applies and produces the verified result.
+    Clean,
+    /// The change still applies, but the file changed since the patch was
+    /// verified, so the result differs from what was reviewed.
+    Changed,
+    /// The change can no longer be applied as written.
+    Conflict(String),
+}
+
 /// cwd must be an absolute path so that we can resolve relative paths in the
 /// patch.
 pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApplyPatchVerified {
@@ -648,6 +733,20 @@ fn derive_new_contents_from_chunks(
         }
     };
 
+    let new_contents = apply_chunks_to_contents(path, &original_contents, chunks)?;
+    Ok(AppliedPatch {
+        original_contents,
+        new_contents,
+    })
+}
+
+/// Apply update `chunks` to `original_contents` rather than to the file on
+/// disk. `path` is only used in error messages.
+pub fn apply_chunks_to_contents(
+    path: &Path,
+    original_contents: &str,
+    chunks: &[UpdateFileChunk],
+) -> std::result::Result<String, ApplyPatchError> {
     let mut original_lines: Vec<String> = original_contents.split('\n').map(String::from).collect();
 
     // Drop the trailing empty element that results from the final newline so
@@ -662,11 +761,7 @@ fn derive_new_contents_from_chunks(
     if !new_lines.last().is_some_and(String::is_empty) {
         new_lines.push(String::new());
     }
-    let new_contents = new_lines.join("\n");
-    Ok(AppliedPatch {
-        original_contents,
-        new_contents,
-    })
+    Ok(new_lines.join("\n"))
 }
 
 /// Compute a list of replacements needed to transform `original_lines` into the
@@ -1669,4 +1764,57 @@ g
         let result = apply_patch(&patch, &mut stdout, &mut stderr);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_dry_run_reports_changed_and_conflicting_files() {
+        let dir = tempdir().unwrap();
+        let updated = dir.path().join("updated.txt");
+        let edited = dir.path().join("edited.txt");
+        let removed = dir.path().join("removed.txt");
+        let added = dir.path().join("added.txt");
+        fs::write(&updated, "one\n").unwrap();
+        fs::write(&edited, "two\nextra\n").unwrap();
+        fs::write(&removed, "three\n").unwrap();
+
+        let patch = wrap_patch(
+            r#"*** Update File: updated.txt
+@@
+-one
++ONE
+*** Update File: edited.txt
+@@
+-two
++TWO
+*** Update File: removed.txt
+@@
+-three
++THREE
+*** Add File: added.txt
++four"#,
+        );
+        let argv = vec!["apply_patch".to_string(), patch];
+        let action = match maybe_parse_apply_patch_verified(&argv, dir.path()) {
+            MaybeApplyPatchVerified::Body(action) => action,
+            other => panic!("expected verified body, got {other:?}"),
+        };
+
+        fs::write(&edited, "two\nchanged concurrently\n").unwrap();
+        fs::remove_file(&removed).unwrap();
+        fs::write(&added, "created concurrently\n").unwrap();
+
+        let outcomes = action.dry_run();
+        assert_eq!(outcomes.get(&updated), Some(&DryRunOutcome::Clean));
+        assert_eq!(outcomes.get(&edited), Some(&DryRunOutcome::Changed));
+        assert!(matches!(
+            outcomes.get(&removed),
+            Some(DryRunOutcome::Conflict(reason)) if reason.contains("removed.txt")
+        ));
+        assert_eq!(
+            outcomes.get(&added),
+            Some(&DryRunOutcome::Conflict(format!(
+                "{} already exists and would be overwritten",
+                added.display()
+            )))
+        );
+    }
 }
@@ -3,6 +3,8 @@ use codex_common::CliConfigOverrides;
 use codex_core::CodexAuth;
 use codex_core::auth::AuthCredentialsStoreMode;
 use codex_core::auth::CLIENT_ID;
+use codex_core::auth::CredentialArchive;
+use codex_core::auth::list_accounts;
 use codex_core::auth::login_with_api_key;
 use codex_core::auth::logout;
 use codex_core::config::Config;
@@ -13,6 +15,8 @@ use codex_login::run_login_server;
 use codex_protocol::config_types::ForcedLoginMethod;
 use std::io::IsTerminal;
 use std::io::Read;
+use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 
 pub async fn login_with_chatgpt(
@@ -47,7 +51,7 @@ pub async fn run_login_with_chatgpt(cli_config_overrides: CliConfigOverrides) ->
     let forced_chatgpt_workspace_id = config.forced_chatgpt_workspace_id.clone();
 
     match login_with_chatgpt(
-        config.codex_home,
+        config.credentials_home(),
         forced_chatgpt_workspace_id,
         config.cli_auth_credentials_store_mode,
     )
@@ -76,7 +80,7 @@ pub async fn run_login_with_api_key(
     }
 
     match login_with_api_key(
-        &config.codex_home,
+        &config.credentials_home(),
         &api_key,
         config.cli_auth_credentials_store_mode,
     ) {
@@ -131,7 +135,7 @@ pub async fn run_login_with_device_code(
     }
     let forced_chatgpt_workspace_id = config.forced_chatgpt_workspace_id.clone();
     let mut opts = ServerOptions::new(
-        config.codex_home,
+        config.credentials_home(),
         client_id.unwrap_or(CLIENT_ID.to_string()),
         forced_chatgpt_workspace_id,
         config.cli_auth_credentials_store_mode,
@@ -154,7 +158,10 @@ pub async fn run_login_with_device_code(
 pub async fn run_login_status(cli_config_overrides: CliConfigOverrides) -> ! {
     let config = load_config_or_exit(cli_config_overrides).await;
 
-    match CodexAuth::from_auth_storage(&config.codex_home, config.cli_auth_credentials_store_mode) {
+    match CodexAuth::from_auth_storage(
+        &config.credentials_home(),
+        config.cli_auth_credentials_store_mode,
+    ) {
         Ok(Some(auth)) => match auth.mode {
             AuthMode::ApiKey => match auth.get_token().await {
                 Ok(api_key) => {
@@ -185,7 +192,10 @@ pub async fn run_login_status(cli_config_overrides: CliConfigOverrides) -> ! {
 pub async fn run_logout(cli_config_overrides: CliConfigOverrides) -> ! {
     let config = load_config_or_exit(cli_config_overrides).await;
 
-    match logout(&config.codex_home, config.cli_auth_credentials_store_mode) {
+    match logout(
+        &config.credentials_home(),
+        config.cli_auth_credentials_store_mode,
+    ) {
         Ok(true) => {
             eprintln!("Successfully logged out");
             std::process::exit(0);
@@ -201,6 +211,175 @@ pub async fn run_logout(cli_config_overrides: CliConfigOverrides) -> ! {
     }
 }
 
+/// Print the default account and every labelled account with stored
+/// credentials; the one the configuration selects is marked with `*`.
+pub async fn run_login_list(cli_config_overrides: CliConfigOverrides) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+    let mode = config.cli_auth_credentials_store_mode;
+
+    let default_auth = match CodexAuth::from_auth_storage(&config.codex_home, mode) {
+        Ok(auth) => auth,
+        Err(e) => {
+            eprintln!("Error reading stored accounts: {e}");
+            std::process::exit(1);
+        }
+    };
+    let accounts = match list_accounts(&config.codex_home, mode) {
+        Ok(accounts) => accounts,
+        Err(e) => {
+            eprintln!("Error reading stored accounts: {e}");
+            std::process::exit(1);
+        }
+    };
+
+    let describe = |auth: Option<&CodexAuth>| match auth {
+        None => "not logged in".to_string(),
+        Some(auth) => match auth.mode {
+            AuthMode::ApiKey => "API key".to_string(),
+            AuthMode::ChatGPT => match auth.get_account_email() {
+                Some(email) => format!("ChatGPT ({email})"),
+                None => "ChatGPT".to_string(),
+            },
+        },
+    };
+    let marker = |selected: bool| if selected { "*" } else { " " };
+    println!(
+        "{} (default)  {}",
+        marker(config.account.is_none()),
+        describe(default_auth.as_ref())
+    );
+    for account in &accounts {
+        println!(
+            "{} {}  {}",
+            marker(config.account.as_deref() == Some(account.label.as_str())),
+            account.label,
+            describe(Some(&account.auth))
+        );
+    }
+    std::process::exit(0);
+}
+
+/// Write every stored credential to `output`, encrypted with a passphrase.
+pub async fn run_auth_export(cli_config_overrides: CliConfigOverrides, output: PathBuf) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+
+    let archive = match CredentialArchive::collect(&config) {
+        Ok(archive) => archive,
+        Err(e) => {
+            eprintln!("Error reading stored credentials: {e}");
+            std::process::exit(1);
+        }
+    };
+    if archive.accounts.is_empty() && archive.mcp_oauth_tokens.is_empty() {
+        eprintln!("No stored credentials to export");
+        std::process::exit(1);
+    }
+
+    let passphrase = read_passphrase(true);
+    let result = archive
+        .encrypt(&passphrase)
+        .and_then(|sealed| write_private_file(&output, &sealed));
+    match result {
+        Ok(()) => {
+            eprintln!(
+                "Exported {} account(s) and {} MCP server login(s) to {}",
+                archive.accounts.len(),
+                archive.mcp_oauth_tokens.len(),
+                output.display()
+            );
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error exporting credentials: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Store the credentials from a file written by [`run_auth_export`].
+pub async fn run_auth_import(cli_config_overrides: CliConfigOverrides, input: PathBuf) -> ! {
+    let config = load_config_or_exit(cli_config_overrides).await;
+
+    let sealed = match std::fs::read(&input) {
+        Ok(sealed) => sealed,
+        Err(e) => {
+            eprintln!("Error reading {}: {e}", input.display());
+            std::process::exit(1);
+        }
+    };
+    let passphrase = read_passphrase(false);
+    let result = CredentialArchive::decrypt(&sealed, &passphrase)
+        .and_then(|archive| archive.restore(&config).map(|()| archive));
+    match result {
+        Ok(archive) => {
+            eprintln!(
+                "Imported {} account(s) and {} MCP server login(s)",
+                archive.accounts.len(),
+                archive.mcp_oauth_tokens.len()
+            );
+            std::process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("Error importing credentials: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Prompt for the archive passphrase, or read it from the first line of
+/// stdin when stdin is not a terminal.
+fn read_passphrase(confirm: bool) -> String {
+    let passphrase = if std::io::stdin().is_terminal() {
+        let passphrase = match rpassword::prompt_password("Passphrase: ") {
+            Ok(passphrase) => passphrase,
+            Err(e) => {
+                eprintln!("Failed to read passphrase: {e}");
+                std::process::exit(1);
+            }
+        };
+        if confirm {
+            match rpassword::prompt_password("Repeat passphrase: ") {
+                Ok(repeated) if repeated == passphrase => {}
+                Ok(_) => {
+                    eprintln!("Passphrases do not match");
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("Failed to read passphrase: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        passphrase
+    } else {
+        let mut line = String::new();
+        if let Err(e) = std::io::stdin().read_line(&mut line) {
+            eprintln!("Failed to read passphrase from stdin: {e}");
+            std::process::exit(1);
+        }
+        line.trim_end_matches(['\r', '\n']).to_string()
+    };
+
+    if passphrase.is_empty() {
+        eprintln!("The passphrase must not be empty");
+        std::process::exit(1);
+    }
+    passphrase
+}
+
+fn write_private_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.truncate(true).write(true).create(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(contents)?;
+    file.flush()
+}
+
 async fn load_config_or_exit(cli_config_overrides: CliConfigOverrides) -> Config {
     let cli_overrides = match cli_config_overrides.parse_overrides() {
         Ok(v) => v,
@@ -10,6 +10,9 @@ use codex_cli::LandlockCommand;
 use codex_cli::SeatbeltCommand;
 use codex_cli::WindowsCommand;
 use codex_cli::login::read_api_key_from_stdin;
+use codex_cli::login::run_auth_export;
+use codex_cli::login::run_auth_import;
+use codex_cli::login::run_login_list;
 use codex_cli::login::run_login_status;
 use codex_cli::login::run_login_with_api_key;
 use codex_cli::login::run_login_with_chatgpt;
@@ -18,12 +21,16 @@ use codex_cli::login::run_logout;
 use codex_cloud_tasks::Cli as CloudTasksCli;
 use codex_common::CliConfigOverrides;
 use codex_exec::Cli as ExecCli;
+use codex_protocol::protocol::SessionSource;
 use codex_responses_api_proxy::Args as ResponsesApiProxyArgs;
 use codex_tui::AppExitInfo;
 use codex_tui::Cli as TuiCli;
 use codex_tui::update_action::UpdateAction;
 use owo_colors::OwoColorize;
+use std::io::IsTerminal;
+use std::io::Read;
 use std::path::PathBuf;
+use std::sync::Arc;
 use supports_color::Stream;
 
 mod mcp_cmd;
@@ -32,9 +39,23 @@ mod wsl_paths;
 
 use crate::mcp_cmd::McpCli;
 
+use codex_core::AuthManager;
+use codex_core::ConversationManager;
 use codex_core::config::Config;
 use codex_core::config::ConfigOverrides;
+use codex_core::config::find_codex_home;
+use codex_core::config::validate::DiagnosticSeverity;
+use codex_core::config::validate::validate_config_file;
 use codex_core::features::is_known_feature_key;
+use codex_core::find_conversation_path_by_id_str;
+use codex_core::pull_request::delete_forge_token;
+use codex_core::pull_request::save_forge_token;
+use codex_core::scheduler::Scheduler;
+use codex_core::thread_bundle::BUNDLE_EXTENSION;
+use codex_core::thread_bundle::export_thread_bundle;
+use codex_core::thread_bundle::import_thread_bundle;
+use codex_core::transcript_export::TranscriptFormat;
+use codex_core::transcript_export::export_transcript;
 
 /// Codex CLI
 ///
@@ -77,6 +98,9 @@ enum Subcommand {
     /// Remove stored authentication credentials.
     Logout(LogoutCommand),
 
+    /// Move stored credentials between machines.
+    Auth(AuthCommand),
+
     /// [experimental] Run Codex as an MCP server and manage MCP servers.
     Mcp(McpCli),
 
@@ -100,6 +124,12 @@ enum Subcommand {
     /// Resume a previous interactive session (picker by default; use --last to continue the most recent).
     Resume(ResumeCommand),
 
+    /// Export a recorded session as a Markdown, HTML, or JSON transcript, or as a portable bundle.
+    Export(ExportCommand),
+
+    /// Import a session bundle written by `codex export --bundle`.
+    Import(ImportCommand),
+
     /// [EXPERIMENTAL] Browse tasks from Codex Cloud and apply changes locally.
     #[clap(name = "cloud", alias = "cloud-tasks")]
     Cloud(CloudTasksCli),
@@ -114,6 +144,15 @@ enum Subcommand {
 
     /// Inspect feature flags.
     Features(FeaturesCli),
+
+    /// Check config.toml for mistakes.
+    Config(ConfigCli),
+
+    /// [experimental] Run the prompts configured under `[[schedules]]`.
+    Schedule(ScheduleCli),
+
+    /// [experimental] Manage GitHub/GitLab tokens used to open pull requests.
+    Forge(ForgeCli),
 }
 
 #[derive(Debug, Parser)]
@@ -142,6 +181,59 @@ struct ResumeCommand {
     config_overrides: TuiCli,
 }
 
+#[derive(Debug, Parser)]
+struct ExportCommand {
+    /// Conversation/session id (UUID) to export.
+    #[arg(value_name = "SESSION_ID")]
+    session_id: String,
+
+    /// Transcript format.
+    #[arg(long = "format", value_enum, default_value_t = ExportFormat::Markdown)]
+    format: ExportFormat,
+
+    /// File to write. Defaults to `codex-<SESSION_ID>.<ext>` in the current directory.
+    #[arg(short = 'o', long = "output", value_name = "FILE")]
+    output: Option<PathBuf>,
+
+    /// Write a compressed bundle that `codex import` can restore on another
+    /// machine instead of a transcript. Secrets are redacted.
+    #[arg(long = "bundle", default_value_t = false, conflicts_with = "format")]
+    bundle: bool,
+
+    /// Include the full output of the session's commands in the bundle.
+    #[arg(
+        long = "include-exec-logs",
+        default_value_t = false,
+        requires = "bundle"
+    )]
+    include_exec_logs: bool,
+}
+
+#[derive(Debug, Parser)]
+struct ImportCommand {
+    /// Bundle file to import.
+    #[arg(value_name = "FILE")]
+    bundle: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum ExportFormat {
+    #[value(alias = "md")]
+    Markdown,
+    Html,
+    Json,
+}
+
+impl From<ExportFormat> for TranscriptFormat {
+    fn from(format: ExportFormat) -> Self {
+        match format {
+            ExportFormat::Markdown => TranscriptFormat::Markdown,
+            ExportFormat::Html => TranscriptFormat::Html,
+            ExportFormat::Json => TranscriptFormat::Json,
+        }
+    }
+}
+
 #[derive(Debug, Parser)]
 struct SandboxArgs {
     #[command(subcommand)]
@@ -184,6 +276,11 @@ struct LoginCommand {
     #[arg(long = "device-auth")]
     use_device_code: bool,
 
+    /// Store the credentials under this account label instead of replacing
+    /// the default account.
+    #[arg(long = "account", value_name = "LABEL")]
+    account: Option<String>,
+
     /// EXPERIMENTAL: Use custom OAuth issuer base URL (advanced)
     /// Override the OAuth issuer base URL (advanced)
     #[arg(long = "experimental_issuer", value_name = "URL", hide = true)]
@@ -201,12 +298,44 @@ struct LoginCommand {
 enum LoginSubcommand {
     /// Show login status.
     Status,
+
+    /// List the stored accounts.
+    List,
 }
 
 #[derive(Debug, Parser)]
 struct LogoutCommand {
     #[clap(skip)]
     config_overrides: CliConfigOverrides,
+
+    /// Remove the credentials stored under this account label.
+    #[arg(long = "account", value_name = "LABEL")]
+    account: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+struct AuthCommand {
+    #[clap(skip)]
+    config_overrides: CliConfigOverrides,
+
+    #[command(subcommand)]
+    action: AuthSubcommand,
+}
+
+#[derive(Debug, clap::Subcommand)]
+enum AuthSubcommand {
+    /// Write every stored Codex and MCP OAuth credential to a
+    /// passphrase-encrypted file.
+    Export {
+        #[arg(long = "output", short = 'o', value_name = "FILE")]
+        output: PathBuf,
+    },
+
+    /// Store the credentials from a file written by `codex auth export`.
+    Import {
+        #[arg(value_name = "FILE")]
+        input: PathBuf,
+    },
 }
 
 #[derive(Debug, Parser)]
@@ -214,6 +343,10 @@ struct AppServerCommand {
     /// Omit to run the app server; specify a subcommand for tooling.
     #[command(subcommand)]
     subcommand: Option<AppServerSubcommand>,
+
+    /// Also accept read-only observer connections on this Unix socket.
+    #[arg(long = "observer-socket", value_name = "PATH")]
+    observer_socket: Option<PathBuf>,
 }
 
 #[derive(Debug, clap::Subcommand)]
@@ -373,6 +506,59 @@ enum FeaturesSubcommand {
     List,
 }
 
+#[derive(Debug, Parser)]
+struct ConfigCli {
+    #[command(subcommand)]
+    sub: ConfigSubcommand,
+}
+
+#[derive(Debug, Parser)]
+enum ConfigSubcommand {
+    /// Report unknown keys, type mismatches, and conflicting options in config.toml.
+    Doctor,
+}
+
+#[derive(Debug, Parser)]
+struct ScheduleCli {
+    #[command(subcommand)]
+    sub: ScheduleSubcommand,
+}
+
+#[derive(Debug, Parser)]
+enum ScheduleSubcommand {
+    /// List configured schedules with their next run time.
+    List,
+
+    /// Stay in the foreground and start each schedule's run when it comes due.
+    Run {
+        /// Run this schedule once right away and exit instead.
+        #[arg(long = "now", value_name = "NAME")]
+        now: Option<String>,
+    },
+}
+
+#[derive(Debug, Parser)]
+struct ForgeCli {
+    #[command(subcommand)]
+    sub: ForgeSubcommand,
+}
+
+#[derive(Debug, Parser)]
+enum ForgeSubcommand {
+    /// Store an API token for a forge host, read from stdin.
+    Login {
+        /// Host of the forge, e.g. `github.com` or `gitlab.example.com`.
+        #[arg(value_name = "HOST")]
+        host: String,
+    },
+
+    /// Remove the stored API token for a forge host.
+    Logout {
+        #[arg(value_name = "HOST")]
+        host: String,
+    },
+}
+
 fn stage_str(stage: codex_core::features::Stage) -> &'static str {
     use codex_core::features::Stage;
     match stage {
@@ -437,7 +623,12 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
         }
         Some(Subcommand::AppServer(app_server_cli)) => match app_server_cli.subcommand {
             None => {
-                codex_app_server::run_main(codex_linux_sandbox_exe, root_config_overrides).await?;
+                codex_app_server::run_main(
+                    codex_linux_sandbox_exe,
+                    root_config_overrides,
+                    app_server_cli.observer_socket,
+                )
+                .await?;
             }
             Some(AppServerSubcommand::GenerateTs(gen_cli)) => {
                 codex_app_server_protocol::generate_ts(
@@ -471,10 +662,14 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
                 &mut login_cli.config_overrides,
                 root_config_overrides.clone(),
             );
+            push_account_override(&mut login_cli.config_overrides, login_cli.account);
             match login_cli.action {
                 Some(LoginSubcommand::Status) => {
                     run_login_status(login_cli.config_overrides).await;
                 }
+                Some(LoginSubcommand::List) => {
+                    run_login_list(login_cli.config_overrides).await;
+                }
                 None => {
                     if login_cli.use_device_code {
                         run_login_with_device_code(
@@ -502,8 +697,23 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
                 &mut logout_cli.config_overrides,
                 root_config_overrides.clone(),
             );
+            push_account_override(&mut logout_cli.config_overrides, logout_cli.account);
             run_logout(logout_cli.config_overrides).await;
         }
+        Some(Subcommand::Auth(mut auth_cli)) => {
+            prepend_config_flags(
+                &mut auth_cli.config_overrides,
+                root_config_overrides.clone(),
+            );
+            match auth_cli.action {
+                AuthSubcommand::Export { output } => {
+                    run_auth_export(auth_cli.config_overrides, output).await;
+                }
+                AuthSubcommand::Import { input } => {
+                    run_auth_import(auth_cli.config_overrides, input).await;
+                }
+            }
+        }
         Some(Subcommand::Completion(completion_cli)) => {
             print_completion(completion_cli);
         }
@@ -549,6 +759,12 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
                 .await?;
             }
         },
+        Some(Subcommand::Export(export_cli)) => {
+            run_export_command(export_cli).await?;
+        }
+        Some(Subcommand::Import(import_cli)) => {
+            run_import_command(import_cli).await?;
+        }
         Some(Subcommand::Apply(mut apply_cli)) => {
             prepend_config_flags(
                 &mut apply_cli.config_overrides,
@@ -565,6 +781,22 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
             tokio::task::spawn_blocking(move || codex_stdio_to_uds::run(socket_path.as_path()))
                 .await??;
         }
+        Some(Subcommand::Schedule(ScheduleCli { sub })) => {
+            let cli_kv_overrides = root_config_overrides
+                .parse_overrides()
+                .map_err(anyhow::Error::msg)?;
+            let overrides = ConfigOverrides {
+                config_profile: interactive.config_profile.clone(),
+                ..Default::default()
+            };
+            run_schedule_command(sub, cli_kv_overrides, overrides).await?;
+        }
+        Some(Subcommand::Forge(ForgeCli { sub })) => {
+            run_forge_command(sub)?;
+        }
+        Some(Subcommand::Config(ConfigCli { sub })) => match sub {
+            ConfigSubcommand::Doctor => run_config_doctor().await?,
+        },
         Some(Subcommand::Features(FeaturesCli { sub })) => match sub {
             FeaturesSubcommand::List => {
                 // Respect root-level `-c` overrides plus top-level flags like `--profile`.
@@ -600,6 +832,171 @@ async fn cli_main(codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()
     Ok(())
 }
 
+async fn run_schedule_command(
+    sub: ScheduleSubcommand,
+    cli_kv_overrides: Vec<(String, toml::Value)>,
+    overrides: ConfigOverrides,
+) -> anyhow::Result<()> {
+    let config = Config::load_with_cli_overrides(cli_kv_overrides.clone(), overrides).await?;
+    let auth_manager = AuthManager::shared_with_account(
+        config.codex_home.clone(),
+        config.account.clone(),
+        true,
+        config.cli_auth_credentials_store_mode,
+    );
+    let conversation_manager =
+        Arc::new(ConversationManager::new(auth_manager, SessionSource::Exec));
+    let scheduler = Scheduler::new(&config, cli_kv_overrides, conversation_manager)?;
+
+    match sub {
+        ScheduleSubcommand::List => {
+            if config.schedules.is_empty() {
+                println!("No schedules configured. Add [[schedules]] entries to config.toml.");
+            }
+            for (name, next) in scheduler.upcoming() {
+                let next = next.map_or_else(
+                    || "never".to_string(),
+                    |next| next.format("%Y-%m-%d %H:%M").to_string(),
+                );
+                println!("{name}\t{next}");
+            }
+        }
+        ScheduleSubcommand::Run { now: Some(name) } => {
+            let Some(schedule) = config.schedules.iter().find(|s| s.name == name) else {
+                anyhow::bail!("no schedule named {name}");
+            };
+            let outcome = scheduler.run_now(schedule).await?;
+            match outcome.error {
+                Some(error) => anyhow::bail!("run failed (thread {}): {error}", outcome.thread_id),
+                None => println!(
+                    "{}",
+                    outcome
+                        .last_assistant_message
+                        .unwrap_or_else(|| format!("Run finished in thread {}", outcome.thread_id))
+                ),
+            }
+        }
+        ScheduleSubcommand::Run { now: None } => {
+            if config.schedules.is_empty() {
+                anyhow::bail!("no schedules configured; add [[schedules]] entries to config.toml");
+            }
+            Arc::new(scheduler).run().await;
+        }
+    }
+    Ok(())
+}
+
+async fn run_config_doctor() -> anyhow::Result<()> {
+    let codex_home = find_codex_home()?;
+    let diagnostics = validate_config_file(&codex_home).await?;
+    let config_path = codex_home.join("config.toml");
+    if diagnostics.is_empty() {
+        println!("{}: no problems found", config_path.display());
+        return Ok(());
+    }
+
+    let mut errors = 0;
+    for diagnostic in &diagnostics {
+        let severity = match diagnostic.severity {
+            DiagnosticSeverity::Error => {
+                errors += 1;
+                "error"
+            }
+            DiagnosticSeverity::Warning => "warning",
+        };
+        println!(
+            "{}:{}:{}: {severity}: {}",
+            config_path.display(),
+            diagnostic.line,
+            diagnostic.column,
+            diagnostic.message
+        );
+    }
+    if errors > 0 {
+        anyhow::bail!("config.toml has {errors} error(s)");
+    }
+    Ok(())
+}
+
+fn run_forge_command(sub: ForgeSubcommand) -> anyhow::Result<()> {
+    match sub {
+        ForgeSubcommand::Login { host } => {
+            let mut stdin = std::io::stdin();
+            if stdin.is_terminal() {
+                anyhow::bail!(
+                    "expected the token on stdin, e.g. `printenv GITHUB_TOKEN | codex forge login {host}`"
+                );
+            }
+            let mut buffer = String::new();
+            stdin.read_to_string(&mut buffer)?;
+            let token = buffer.trim();
+            if token.is_empty() {
+                anyhow::bail!("no token provided via stdin");
+            }
+            save_forge_token(&host, token)?;
+            println!("Saved token for {host}");
+        }
+        ForgeSubcommand::Logout { host } => {
+            if delete_forge_token(&host)? {
+                println!("Removed token for {host}");
+            } else {
+                println!("No token stored for {host}");
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run_export_command(cmd: ExportCommand) -> anyhow::Result<()> {
+    let ExportCommand {
+        session_id,
+        format,
+        output,
+        bundle,
+        include_exec_logs,
+    } = cmd;
+    let codex_home = find_codex_home()?;
+    let Some(rollout_path) = find_conversation_path_by_id_str(&codex_home, &session_id).await?
+    else {
+        anyhow::bail!("no recorded session found for id {session_id}");
+    };
+    if bundle {
+        let output = output
+            .unwrap_or_else(|| PathBuf::from(format!("codex-{session_id}.{BUNDLE_EXTENSION}")));
+        let summary =
+            export_thread_bundle(&codex_home, &rollout_path, &output, include_exec_logs).await?;
+        let redacted: u32 = summary
+            .redacted_secrets
+            .iter()
+            .map(|secret| secret.count)
+            .sum();
+        println!(
+            "Wrote bundle with {} command logs and {redacted} redacted secrets to {}",
+            summary.exec_log_count,
+            output.display()
+        );
+        return Ok(());
+    }
+    let format = TranscriptFormat::from(format);
+    let output = output
+        .unwrap_or_else(|| PathBuf::from(format!("codex-{session_id}.{}", format.extension())));
+    let entries = export_transcript(&rollout_path, format, &output).await?;
+    println!("Wrote {entries} entries to {}", output.display());
+    Ok(())
+}
+
+async fn run_import_command(cmd: ImportCommand) -> anyhow::Result<()> {
+    let codex_home = find_codex_home()?;
+    let imported = import_thread_bundle(&codex_home, &cmd.bundle).await?;
+    println!(
+        "Imported session {} to {}",
+        imported.thread_id,
+        imported.rollout_path.display()
+    );
+    println!("Continue it with `codex resume {}`", imported.thread_id);
+    Ok(())
+}
+
 /// Prepend root-level overrides so they have lower precedence than
 /// CLI-specific ones specified after the subcommand (if any).
 fn prepend_config_flags(
@@ -611,6 +1008,15 @@ fn prepend_config_flags(
         .splice(0..0, cli_config_overrides.raw_overrides);
 }
 
+/// `--account <label>` is shorthand for `-c account="<label>"`.
+fn push_account_override(config_overrides: &mut CliConfigOverrides, account: Option<String>) {
+    if let Some(account) = account {
+        config_overrides
+            .raw_overrides
+            .push(format!("account=\"{account}\""));
+    }
+}
+
 /// Build the final `TuiCli` for a `codex resume` invocation.
 fn finalize_resume_interactive(
     mut interactive: TuiCli,
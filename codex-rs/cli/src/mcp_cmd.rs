@@ -13,6 +13,7 @@ use codex_core::config::edit::ConfigEditsBuilder;
 use codex_core::config::find_codex_home;
 use codex_core::config::load_global_mcp_servers;
 use codex_core::config::types::McpServerConfig;
+use codex_core::config::types::McpServerStartup;
 use codex_core::config::types::McpServerTransportConfig;
 use codex_core::features::Feature;
 use codex_core::mcp::auth::compute_auth_statuses;
@@ -151,7 +152,8 @@ pub struct LoginArgs {
     /// Name of the MCP server to authenticate with oauth.
     pub name: String,
 
-    /// Comma-separated list of OAuth scopes to request.
+    /// Comma-separated list of OAuth scopes to request. Defaults to the
+    /// `scopes` configured for the server.
     #[arg(long, value_delimiter = ',', value_name = "SCOPE,SCOPE")]
     pub scopes: Vec<String>,
 }
@@ -250,6 +252,7 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
             bearer_token_env_var,
             http_headers: None,
             env_http_headers: None,
+            scopes: None,
         },
         AddMcpTransportArgs { .. } => bail!("exactly one of --command or --url must be provided"),
     };
@@ -261,6 +264,11 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
         tool_timeout_sec: None,
         enabled_tools: None,
         disabled_tools: None,
+        startup: McpServerStartup::Eager,
+        idle_timeout_sec: None,
+        warm_pool: false,
+        tool_aliases: None,
+        sampling: None,
     };
 
     servers.insert(name.clone(), new_entry);
@@ -278,6 +286,7 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
         bearer_token_env_var: None,
         http_headers,
         env_http_headers,
+        scopes,
     } = transport
     {
         match supports_oauth_login(&url).await {
@@ -295,7 +304,7 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
                         config.mcp_oauth_credentials_store_mode,
                         http_headers.clone(),
                         env_http_headers.clone(),
-                        &Vec::new(),
+                        &scopes.unwrap_or_default(),
                     )
                     .await?;
                     println!("Successfully logged in.");
@@ -364,15 +373,27 @@ async fn run_login(config_overrides: &CliConfigOverrides, login_args: LoginArgs)
         bail!("No MCP server named '{name}' found.");
     };
 
-    let (url, http_headers, env_http_headers) = match &server.transport {
+    let (url, http_headers, env_http_headers, configured_scopes) = match &server.transport {
         McpServerTransportConfig::StreamableHttp {
             url,
             http_headers,
             env_http_headers,
+            scopes,
             ..
-        } => (url.clone(), http_headers.clone(), env_http_headers.clone()),
+        } => (
+            url.clone(),
+            http_headers.clone(),
+            env_http_headers.clone(),
+            scopes.clone(),
+        ),
         _ => bail!("OAuth login is only supported for streamable HTTP servers."),
     };
+    // Scopes passed on the command line take precedence over the configured ones.
+    let scopes = if scopes.is_empty() {
+        configured_scopes.unwrap_or_default()
+    } else {
+        scopes
+    };
 
     perform_oauth_login(
         &name,
@@ -460,6 +481,7 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
                         bearer_token_env_var,
                         http_headers,
                         env_http_headers,
+                        scopes,
                     } => {
                         serde_json::json!({
                             "type": "streamable_http",
@@ -467,6 +489,7 @@ async fn run_list(config_overrides: &CliConfigOverrides, list_args: ListArgs) ->
                             "bearer_token_env_var": bearer_token_env_var,
                             "http_headers": http_headers,
                             "env_http_headers": env_http_headers,
+                            "scopes": scopes,
                         })
                     }
                 };
@@ -706,12 +729,14 @@ async fn run_get(config_overrides: &CliConfigOverrides, get_args: GetArgs) -> Re
                 bearer_token_env_var,
                 http_headers,
                 env_http_headers,
+                scopes,
             } => serde_json::json!({
                 "type": "streamable_http",
                 "url": url,
                 "bearer_token_env_var": bearer_token_env_var,
                 "http_headers": http_headers,
                 "env_http_headers": env_http_headers,
+                "scopes": scopes,
             }),
         };
         let output = serde_json::to_string_pretty(&serde_json::json!({
@@ -783,6 +808,7 @@ async fn run_get(config_overrides: &CliConfigOverrides, get_args: GetArgs) -> Re
             bearer_token_env_var,
             http_headers,
             env_http_headers,
+            scopes,
         } => {
             println!("  transport: streamable_http");
             println!("  url: {url}");
@@ -814,6 +840,10 @@ async fn run_get(config_overrides: &CliConfigOverrides, get_args: GetArgs) -> Re
                 _ => "-".to_string(),
             };
             println!("  env_http_headers: {env_headers_display}");
+            if scopes.is_some() {
+                let scopes_display = format_tool_list(scopes);
+                println!("  scopes: {scopes_display}");
+            }
         }
     }
     if let Some(timeout) = server.startup_timeout_sec {
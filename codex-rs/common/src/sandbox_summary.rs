@@ -4,6 +4,7 @@ pub fn summarize_sandbox_policy(sandbox_policy: &SandboxPolicy) -> String {
     match sandbox_policy {
         SandboxPolicy::DangerFullAccess => "danger-full-access".to_string(),
         SandboxPolicy::ReadOnly => "read-only".to_string(),
+        SandboxPolicy::Audit => "audit".to_string(),
         SandboxPolicy::WorkspaceWrite {
             writable_roots,
             network_access,
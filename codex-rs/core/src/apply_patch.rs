@@ -1,17 +1,48 @@
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::features::Feature;
 use crate::function_tool::FunctionCallError;
+use crate::protocol::AskForApproval;
+use crate::protocol::DeferredFileChange;
+use crate::protocol::DeferredFileChangeKind;
 use crate::protocol::FileChange;
+use crate::protocol::PatchFileStatus;
 use crate::protocol::ReviewDecision;
+use crate::protocol::SandboxPolicy;
 use crate::safety::SafetyCheck;
 use crate::safety::assess_patch_safety;
+use crate::safety::paths_outside_writable_roots;
+use crate::write_grants::grant_root_for;
 use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchArgs;
 use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::DryRunOutcome;
+use codex_apply_patch::Hunk;
+use codex_apply_patch::MaybeApplyPatch;
+use codex_apply_patch::MaybeApplyPatchVerified;
+use codex_git::MergeLabels;
+use codex_git::merge_file_contents;
+use codex_git::read_file_at_commit;
+use codex_protocol::items::BlockedAction;
+use sha2::Digest;
+use sha2::Sha256;
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 
 pub const CODEX_APPLY_PATCH_ARG1: &str = "--codex-run-as-apply-patch";
 
+/// Patch approval requests whose change bodies exceed this many bytes are sent
+/// as per-file summaries; clients fetch the bodies they need on demand.
+pub(crate) const MAX_INLINE_PATCH_APPROVAL_BYTES: usize = 256 * 1024;
+
+/// Conflict marker labels used when a patch is merged against a snapshot.
+const MERGE_LABELS: MergeLabels<'static> = MergeLabels {
+    ours: "current",
+    base: "snapshot",
+    theirs: "patch",
+};
+
 pub(crate) enum InternalApplyPatchInvocation {
     /// The `apply_patch` call was handled programmatically, without any sort
     /// of sandbox, because the user explicitly approved it. This is the
@@ -39,10 +70,15 @@ pub(crate) async fn apply_patch(
     call_id: &str,
     action: ApplyPatchAction,
 ) -> InternalApplyPatchInvocation {
+    let sandbox_policy = match sandbox_policy_for_patch(sess, turn_context, call_id, &action).await
+    {
+        Ok(sandbox_policy) => sandbox_policy,
+        Err(invocation) => return invocation,
+    };
     match assess_patch_safety(
         &action,
         turn_context.approval_policy,
-        &turn_context.sandbox_policy,
+        &sandbox_policy,
         &turn_context.cwd,
     ) {
         SafetyCheck::AutoApprove {
@@ -60,6 +96,7 @@ pub(crate) async fn apply_patch(
             // give the user the option to expand the set of writable roots so
             // that similar patches can be auto-approved in the future during
             // this session.
+            let file_statuses = dry_run_patch(sess, &action).await;
             let rx_approve = sess
                 .request_patch_approval(
                     turn_context,
@@ -67,6 +104,7 @@ pub(crate) async fn apply_patch(
                     convert_apply_patch_to_protocol(&action),
                     None,
                     None,
+                    file_statuses,
                 )
                 .await;
             match rx_approve.await.unwrap_or_default() {
@@ -83,10 +121,252 @@ pub(crate) async fn apply_patch(
                 }
             }
         }
-        SafetyCheck::Reject { reason } => InternalApplyPatchInvocation::Output(Err(
-            FunctionCallError::RespondToModel(format!("patch rejected: {reason}")),
-        )),
+        SafetyCheck::Reject { reason } => {
+            if turn_context.sandbox_policy.is_audit() {
+                sess.emit_blocked_action(turn_context, call_id, BlockedAction::ApplyPatch, &reason)
+                    .await;
+            }
+            InternalApplyPatchInvocation::Output(Err(FunctionCallError::RespondToModel(format!(
+                "patch rejected: {reason}"
+            ))))
+        }
+    }
+}
+
+/// The turn's sandbox policy widened by granted roots. Under
+/// `workspace-write`, a patch that writes outside the writable roots first
+/// asks the user to make the enclosing directory writable.
+async fn sandbox_policy_for_patch(
+    sess: &Session,
+    turn_context: &TurnContext,
+    call_id: &str,
+    action: &ApplyPatchAction,
+) -> Result<SandboxPolicy, InternalApplyPatchInvocation> {
+    let sandbox_policy = sess
+        .sandbox_policy_with_grants(&turn_context.sandbox_policy)
+        .await;
+    if turn_context.approval_policy == AskForApproval::Never
+        || !matches!(sandbox_policy, SandboxPolicy::WorkspaceWrite { .. })
+    {
+        return Ok(sandbox_policy);
+    }
+    let paths = paths_outside_writable_roots(action, &sandbox_policy, &turn_context.cwd);
+    let Some(root) = grant_root_for(&paths) else {
+        return Ok(sandbox_policy);
+    };
+    let decision = sess
+        .request_write_scope(
+            turn_context,
+            call_id.to_owned(),
+            root.clone(),
+            paths,
+            None,
+            Some("patch writes outside the writable roots".to_string()),
+        )
+        .await;
+    if !decision.is_granted() {
+        return Err(InternalApplyPatchInvocation::Output(Err(
+            FunctionCallError::RespondToModel(format!(
+                "patch rejected: the user did not make {} writable",
+                root.display()
+            )),
+        )));
+    }
+    sess.grant_write_root(root, decision).await;
+    Ok(sess
+        .sandbox_policy_with_grants(&turn_context.sandbox_policy)
+        .await)
+}
+
+/// A patch whose context no longer matched the working tree and was rebuilt
+/// by three-way merging it against the latest ghost snapshot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PatchMerge {
+    /// Files that now contain conflict markers.
+    pub(crate) conflicted_paths: Vec<PathBuf>,
+}
+
+impl PatchMerge {
+    pub(crate) fn has_conflicts(&self) -> bool {
+        !self.conflicted_paths.is_empty()
+    }
+
+    /// Explanation prepended to the tool output so the model knows the patch
+    /// was not applied verbatim.
+    pub(crate) fn note(&self) -> String {
+        if !self.has_conflicts() {
+            return "Patch context no longer matched the files; the patch was merged with the current contents.\n".to_string();
+        }
+        let mut note = "Patch context no longer matched the files; the patch was merged with the current contents, leaving conflict markers (<<<<<<< current / >>>>>>> patch) in:\n".to_string();
+        for path in &self.conflicted_paths {
+            note.push_str(&format!("- {}\n", path.display()));
+        }
+        note.push_str("Resolve the conflicts in these files before continuing.\n");
+        note
+    }
+}
+
+/// Parse and verify an `apply_patch` invocation. When the patch no longer
+/// applies because the files drifted since the model read them and the
+/// `apply_patch_merge` feature is enabled, retry by three-way merging each
+/// update against the latest ghost snapshot.
+pub(crate) async fn verify_apply_patch(
+    sess: &Session,
+    turn_context: &TurnContext,
+    command: &[String],
+    cwd: &Path,
+) -> (MaybeApplyPatchVerified, Option<PatchMerge>) {
+    let verified = codex_apply_patch::maybe_parse_apply_patch_verified(command, cwd);
+    if !matches!(verified, MaybeApplyPatchVerified::CorrectnessError(_))
+        || !sess.enabled(Feature::ApplyPatchMerge).await
+    {
+        return (verified, None);
+    }
+    let MaybeApplyPatch::Body(args) = codex_apply_patch::maybe_parse_apply_patch(command) else {
+        return (verified, None);
+    };
+    let Some(snapshot_id) = sess.latest_ghost_snapshot_id().await else {
+        return (verified, None);
+    };
+
+    let repo_path = turn_context.cwd.clone();
+    let cwd = cwd.to_path_buf();
+    let merged = tokio::task::spawn_blocking(move || {
+        merge_patch_with_snapshot(&args, &cwd, &repo_path, &snapshot_id)
+            .map(|(patch, merge)| (patch, cwd, merge))
+    })
+    .await;
+    let (patch, cwd, merge) = match merged {
+        Ok(Ok(merged)) => merged,
+        Ok(Err(err)) => {
+            tracing::debug!("apply_patch merge fallback failed: {err}");
+            return (verified, None);
+        }
+        Err(err) => {
+            tracing::warn!("apply_patch merge fallback panicked: {err}");
+            return (verified, None);
+        }
+    };
+
+    let command = vec!["apply_patch".to_string(), patch];
+    match codex_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd) {
+        merged @ MaybeApplyPatchVerified::Body(_) => (merged, Some(merge)),
+        _ => (verified, None),
+    }
+}
+
+/// Rebuild `args` as a patch that applies to the current working tree. Update
+/// hunks that still apply are kept as-is; the rest are merged with the file as
+/// it was in `snapshot_id`. Every update is emitted as a whole-file
+/// replacement so the result goes through the usual approval and sandbox path.
+fn merge_patch_with_snapshot(
+    args: &ApplyPatchArgs,
+    cwd: &Path,
+    repo_path: &Path,
+    snapshot_id: &str,
+) -> Result<(String, PatchMerge), String> {
+    let effective_cwd = match &args.workdir {
+        Some(dir) => cwd.join(dir),
+        None => cwd.to_path_buf(),
+    };
+    let mut patch = String::from("*** Begin Patch\n");
+    let mut conflicted_paths = Vec::new();
+    for hunk in &args.hunks {
+        let path = hunk.resolve_path(&effective_cwd);
+        match hunk {
+            Hunk::AddFile { contents, .. } => {
+                patch.push_str(&format!("*** Add File: {}\n", path.display()));
+                push_lines(&mut patch, '+', contents);
+            }
+            Hunk::DeleteFile { .. } => {
+                patch.push_str(&format!("*** Delete File: {}\n", path.display()));
+            }
+            Hunk::UpdateFile {
+                move_path, chunks, ..
+            } => {
+                let current = std::fs::read_to_string(&path)
+                    .map_err(|err| format!("failed to read {}: {err}", path.display()))?;
+                let new_contents =
+                    match codex_apply_patch::apply_chunks_to_contents(&path, &current, chunks) {
+                        Ok(contents) => contents,
+                        Err(_) => {
+                            let base = read_file_at_commit(repo_path, snapshot_id, &path)
+                                .map_err(|err| err.to_string())?
+                                .ok_or_else(|| {
+                                    format!("{} is not in the snapshot", path.display())
+                                })?;
+                            let theirs =
+                                codex_apply_patch::apply_chunks_to_contents(&path, &base, chunks)
+                                    .map_err(|err| err.to_string())?;
+                            let merged =
+                                merge_file_contents(&current, &base, &theirs, MERGE_LABELS)
+                                    .map_err(|err| err.to_string())?;
+                            if merged.has_conflicts {
+                                conflicted_paths.push(path.clone());
+                            }
+                            merged.contents
+                        }
+                    };
+                patch.push_str(&format!("*** Update File: {}\n", path.display()));
+                if let Some(dest) = move_path {
+                    patch.push_str(&format!(
+                        "*** Move to: {}\n",
+                        effective_cwd.join(dest).display()
+                    ));
+                }
+                patch.push_str("@@\n");
+                push_lines(&mut patch, '-', &current);
+                push_lines(&mut patch, '+', &new_contents);
+            }
+        }
+    }
+    patch.push_str("*** End Patch");
+    Ok((patch, PatchMerge { conflicted_paths }))
+}
+
+fn push_lines(patch: &mut String, prefix: char, contents: &str) {
+    for line in contents.lines() {
+        patch.push(prefix);
+        patch.push_str(line);
+        patch.push('\n');
+    }
+}
+
+/// Check each file in `action` against the working tree so the approval
+/// request can warn when approving would fail or clobber concurrent edits.
+pub(crate) async fn dry_run_patch(
+    sess: &Session,
+    action: &ApplyPatchAction,
+) -> HashMap<PathBuf, PatchFileStatus> {
+    let mut statuses = HashMap::new();
+    for (path, outcome) in action.dry_run() {
+        let status = match outcome {
+            DryRunOutcome::Conflict(reason) => PatchFileStatus::Conflict { reason },
+            DryRunOutcome::Changed => PatchFileStatus::ModifiedSinceRead,
+            DryRunOutcome::Clean if sess.file_modified_since_seen(&path).await => {
+                PatchFileStatus::ModifiedSinceRead
+            }
+            DryRunOutcome::Clean => PatchFileStatus::AppliesCleanly,
+        };
+        statuses.insert(path, status);
+    }
+    statuses
+}
+
+/// Every file `action` writes, including move destinations.
+pub(crate) fn patch_write_paths(action: &ApplyPatchAction) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for (path, change) in action.changes() {
+        paths.push(path.clone());
+        if let ApplyPatchFileChange::Update {
+            move_path: Some(dest),
+            ..
+        } = change
+        {
+            paths.push(dest.clone());
+        }
     }
+    paths
 }
 
 pub(crate) fn convert_apply_patch_to_protocol(
@@ -116,10 +396,62 @@ pub(crate) fn convert_apply_patch_to_protocol(
     result
 }
 
+/// Returns per-file summaries when `changes` is too large to inline in an
+/// approval request, or `None` when the changes should be sent as-is.
+pub(crate) fn defer_large_patch_changes(
+    changes: &HashMap<PathBuf, FileChange>,
+) -> Option<Vec<DeferredFileChange>> {
+    let total_bytes: usize = changes.values().map(|c| file_change_body(c).len()).sum();
+    if total_bytes <= MAX_INLINE_PATCH_APPROVAL_BYTES {
+        return None;
+    }
+    let mut deferred: Vec<DeferredFileChange> = changes
+        .iter()
+        .map(|(path, change)| summarize_file_change(path, change))
+        .collect();
+    deferred.sort_by(|a, b| a.path.cmp(&b.path));
+    Some(deferred)
+}
+
+fn file_change_body(change: &FileChange) -> &str {
+    match change {
+        FileChange::Add { content } | FileChange::Delete { content } => content,
+        FileChange::Update { unified_diff, .. } => unified_diff,
+    }
+}
+
+fn summarize_file_change(path: &Path, change: &FileChange) -> DeferredFileChange {
+    let body = file_change_body(change);
+    let (kind, added_lines, removed_lines) = match change {
+        FileChange::Add { .. } => (DeferredFileChangeKind::Add, body.lines().count(), 0),
+        FileChange::Delete { .. } => (DeferredFileChangeKind::Delete, 0, body.lines().count()),
+        FileChange::Update { .. } => {
+            let (added, removed) =
+                body.lines()
+                    .fold((0, 0), |(added, removed), line| match line.chars().next() {
+                        Some('+') => (added + 1, removed),
+                        Some('-') => (added, removed + 1),
+                        _ => (added, removed),
+                    });
+            (DeferredFileChangeKind::Update, added, removed)
+        }
+    };
+    DeferredFileChange {
+        path: path.to_path_buf(),
+        kind,
+        added_lines: added_lines as i64,
+        removed_lines: removed_lines as i64,
+        sha256: format!("{:x}", Sha256::digest(body.as_bytes())),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::codex::make_session_and_context;
     use pretty_assertions::assert_eq;
+    use std::time::Duration;
+    use std::time::SystemTime;
 
     use tempfile::tempdir;
 
@@ -139,4 +471,150 @@ mod tests {
             })
         );
     }
+
+    #[tokio::test]
+    async fn dry_run_flags_files_modified_since_seen() {
+        let (session, _turn_context) = make_session_and_context();
+        let tmp = tempdir().expect("tmp");
+        let path = tmp.path().join("a.txt");
+        std::fs::write(&path, "one\n").expect("write file");
+        let argv = vec![
+            "apply_patch".to_string(),
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n-one\n+two\n*** End Patch".to_string(),
+        ];
+        let action = match codex_apply_patch::maybe_parse_apply_patch_verified(&argv, tmp.path()) {
+            MaybeApplyPatchVerified::Body(action) => action,
+            other => panic!("expected verified patch, got {other:?}"),
+        };
+
+        // Files Codex has not seen are judged on content alone.
+        assert_eq!(
+            dry_run_patch(&session, &action).await,
+            HashMap::from([(path.clone(), PatchFileStatus::AppliesCleanly)])
+        );
+
+        session.record_file_seen(&path).await;
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::now() + Duration::from_secs(60)))
+            .expect("bump mtime");
+
+        assert_eq!(
+            dry_run_patch(&session, &action).await,
+            HashMap::from([(path, PatchFileStatus::ModifiedSinceRead)])
+        );
+    }
+
+    #[test]
+    fn drifted_patch_is_merged_against_snapshot() {
+        let tmp = tempdir().expect("tmp");
+        let repo = tmp.path();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .current_dir(repo)
+                .args([
+                    "-c",
+                    "user.name=Tester",
+                    "-c",
+                    "user.email=test@example.com",
+                ])
+                .args(args)
+                .status()
+                .expect("run git");
+            assert!(status.success(), "git {args:?} failed");
+        };
+        git(&["init", "--initial-branch=main"]);
+        let path = repo.join("a.txt");
+        std::fs::write(&path, "one\ntwo\nthree\n").expect("write file");
+        git(&["add", "."]);
+        git(&["commit", "-m", "snapshot"]);
+        // Someone else edited the line the patch targets.
+        std::fs::write(&path, "one\nTWO\nthree\n").expect("edit file");
+
+        let argv = vec![
+            "apply_patch".to_string(),
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n one\n-two\n+2\n*** End Patch"
+                .to_string(),
+        ];
+        let MaybeApplyPatch::Body(args) = codex_apply_patch::maybe_parse_apply_patch(&argv) else {
+            panic!("expected apply_patch body");
+        };
+        let (patch, merge) =
+            merge_patch_with_snapshot(&args, repo, repo, "HEAD").expect("merge patch");
+
+        assert_eq!(
+            merge,
+            PatchMerge {
+                conflicted_paths: vec![path.clone()],
+            }
+        );
+        let command = vec!["apply_patch".to_string(), patch];
+        let action = match codex_apply_patch::maybe_parse_apply_patch_verified(&command, repo) {
+            MaybeApplyPatchVerified::Body(action) => action,
+            other => panic!("expected verified patch, got {other:?}"),
+        };
+        match action.changes().get(&path) {
+            Some(ApplyPatchFileChange::Update { new_content, .. }) => assert_eq!(
+                new_content,
+                "one\n<<<<<<< current\nTWO\n=======\n2\n>>>>>>> patch\nthree\n"
+            ),
+            other => panic!("expected update, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn small_patches_are_not_deferred() {
+        let changes = HashMap::from([(
+            PathBuf::from("a.txt"),
+            FileChange::Add {
+                content: "hello\n".to_string(),
+            },
+        )]);
+
+        assert_eq!(defer_large_patch_changes(&changes), None);
+    }
+
+    #[test]
+    fn large_patches_are_summarized_per_file() {
+        let added = "line\n".repeat(MAX_INLINE_PATCH_APPROVAL_BYTES / 5 + 1);
+        let unified_diff = "@@ -1,2 +1,2 @@\n keep\n-old\n+new\n".to_string();
+        let changes = HashMap::from([
+            (
+                PathBuf::from("b.txt"),
+                FileChange::Update {
+                    unified_diff: unified_diff.clone(),
+                    move_path: None,
+                },
+            ),
+            (
+                PathBuf::from("a.txt"),
+                FileChange::Add {
+                    content: added.clone(),
+                },
+            ),
+        ]);
+
+        let deferred = defer_large_patch_changes(&changes);
+
+        assert_eq!(
+            deferred,
+            Some(vec![
+                DeferredFileChange {
+                    path: PathBuf::from("a.txt"),
+                    kind: DeferredFileChangeKind::Add,
+                    added_lines: (MAX_INLINE_PATCH_APPROVAL_BYTES / 5 + 1) as i64,
+                    removed_lines: 0,
+                    sha256: format!("{:x}", Sha256::digest(added.as_bytes())),
+                },
+                DeferredFileChange {
+                    path: PathBuf::from("b.txt"),
+                    kind: DeferredFileChangeKind::Update,
+                    added_lines: 1,
+                    removed_lines: 1,
+                    sha256: format!("{:x}", Sha256::digest(unified_diff.as_bytes())),
+                },
+            ])
+        );
+    }
 }
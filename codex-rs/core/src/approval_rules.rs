@@ -0,0 +1,339 @@
+//! Durable "always allow" rules for exec approvals.
+//!
+//! When a user approves a command they can ask Codex to remember the decision
+//! as an [`ApprovalRule`]. Rules are stored per project in
+//! `~/.codex/approval_rules.json`, keyed by the project root (the git
+//! repository root, or the session cwd outside of a repository), and let
+//! matching sandboxed commands skip the approval prompt. They never override
+//! a command execpolicy forbids.
+
+use std::collections::BTreeMap;
+use std::io;
+use std::io::ErrorKind;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::ApprovalRule;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::fs;
+use tracing::warn;
+
+use crate::git_info::get_git_repo_root;
+use crate::parse_command::parse_shell_plain_commands;
+
+/// Filename that stores remembered approval rules inside `~/.codex`.
+const APPROVAL_RULES_FILENAME: &str = "approval_rules.json";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ApprovalRulesFile {
+    #[serde(default)]
+    projects: BTreeMap<PathBuf, Vec<ApprovalRule>>,
+}
+
+/// Approval rules remembered for a single project.
+#[derive(Debug)]
+pub(crate) struct ApprovalRuleStore {
+    path: PathBuf,
+    cwd: PathBuf,
+    project: PathBuf,
+    rules: Vec<ApprovalRule>,
+}
+
+impl ApprovalRuleStore {
+    /// Load the rules remembered for the project containing `cwd`. A missing
+    /// or unreadable store yields an empty rule set. The file is small and
+    /// only read when the session enters a project, so this reads it
+    /// synchronously.
+    pub(crate) fn load(codex_home: &Path, cwd: &Path) -> Self {
+        Self::load_from(codex_home.join(APPROVAL_RULES_FILENAME), cwd)
+    }
+
+    /// Switch to the rules of the project containing `cwd` when the session
+    /// moved into another project.
+    pub(crate) fn follow_cwd(&mut self, cwd: &Path) {
+        if cwd == self.cwd {
+            return;
+        }
+        let project = get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+        if project == self.project {
+            self.cwd = cwd.to_path_buf();
+            return;
+        }
+        *self = Self::load_from(self.path.clone(), cwd);
+    }
+
+    fn load_from(path: PathBuf, cwd: &Path) -> Self {
+        let project = get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+        let rules = match parse_rules_file(std::fs::read_to_string(&path)) {
+            Ok(mut file) => file.projects.remove(&project).unwrap_or_default(),
+            Err(err) => {
+                warn!(
+                    "failed to load approval rules from {}: {err}",
+                    path.display()
+                );
+                Vec::new()
+            }
+        };
+        Self {
+            path,
+            cwd: cwd.to_path_buf(),
+            project,
+            rules,
+        }
+    }
+
+    pub(crate) fn rules(&self) -> &[ApprovalRule] {
+        &self.rules
+    }
+
+    /// Whether a remembered rule approves `command` run from `cwd`. Commands
+    /// wrapped in `bash -lc`, `powershell -Command`, or `cmd /c` match only if
+    /// every command in the script does.
+    pub(crate) fn approves(&self, command: &[String], cwd: &Path) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+        if self.matches_any(command, cwd) {
+            return true;
+        }
+        parse_shell_plain_commands(command).is_some_and(|commands| {
+            !commands.is_empty()
+                && commands
+                    .iter()
+                    .all(|command| self.matches_any(command, cwd))
+        })
+    }
+
+    /// Remember `rule` and persist it. Adding a known rule is a no-op.
+    pub(crate) async fn add(&mut self, rule: ApprovalRule) -> io::Result<()> {
+        if self.rules.contains(&rule) {
+            return Ok(());
+        }
+        self.rules.push(rule);
+        self.persist().await
+    }
+
+    /// Forget `rule`. Returns whether it was remembered.
+    pub(crate) async fn remove(&mut self, rule: &ApprovalRule) -> io::Result<bool> {
+        let before = self.rules.len();
+        self.rules.retain(|existing| existing != rule);
+        if self.rules.len() == before {
+            return Ok(false);
+        }
+        self.persist().await?;
+        Ok(true)
+    }
+
+    fn matches_any(&self, command: &[String], cwd: &Path) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| rule_matches(rule, command, cwd))
+    }
+
+    async fn persist(&self) -> io::Result<()> {
+        // Re-read so rules written by other sessions for other projects survive.
+        let mut file = parse_rules_file(fs::read_to_string(&self.path).await)?;
+        if self.rules.is_empty() {
+            file.projects.remove(&self.project);
+        } else {
+            file.projects
+                .insert(self.project.clone(), self.rules.clone());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let contents = serde_json::to_string_pretty(&file).map_err(io::Error::other)?;
+        fs::write(&self.path, contents).await
+    }
+}
+
+/// Rules to offer alongside an approval prompt for `command`, from most to
+/// least specific.
+pub(crate) fn proposed_rules(command: &[String], cwd: &Path) -> Vec<ApprovalRule> {
+    let command = match parse_shell_plain_commands(command) {
+        Some(mut commands) if commands.len() == 1 => commands.remove(0),
+        Some(_) => {
+            return vec![ApprovalRule::ExactCommand {
+                command: command.to_vec(),
+            }];
+        }
+        None => command.to_vec(),
+    };
+
+    let mut rules = Vec::new();
+    if !command.is_empty() {
+        rules.push(ApprovalRule::ExactCommand {
+            command: command.clone(),
+        });
+    }
+    if let [program, subcommand, ..] = command.as_slice()
+        && !subcommand.starts_with('-')
+    {
+        rules.push(ApprovalRule::ProgramSubcommand {
+            program: program.clone(),
+            subcommand: subcommand.clone(),
+        });
+    }
+    if let Some(parent) = command
+        .first()
+        .and_then(|program| program_path(program, cwd))
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+    {
+        rules.push(ApprovalRule::PathPrefix { path: parent });
+    }
+    rules
+}
+
+fn rule_matches(rule: &ApprovalRule, command: &[String], cwd: &Path) -> bool {
+    match rule {
+        ApprovalRule::ExactCommand { command: approved } => approved.as_slice() == command,
+        ApprovalRule::ProgramSubcommand {
+            program,
+            subcommand,
+        } => matches!(command, [p, s, ..] if p == program && s == subcommand),
+        ApprovalRule::PathPrefix { path } => command
+            .first()
+            .and_then(|program| program_path(program, cwd))
+            .is_some_and(|program| program.starts_with(path)),
+    }
+}
+
+/// Resolve `program` to a path when it names one (contains a separator);
+/// bare names looked up on `PATH` have no path to match against.
+fn program_path(program: &str, cwd: &Path) -> Option<PathBuf> {
+    if Path::new(program).components().count() < 2 {
+        return None;
+    }
+    Some(normalize(&cwd.join(program)))
+}
+
+/// Lexically resolve `.` and `..` so `./scripts/../bin/x` cannot escape a
+/// prefix check.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+fn parse_rules_file(contents: io::Result<String>) -> io::Result<ApprovalRulesFile> {
+    match contents {
+        Ok(contents) => serde_json::from_str(&contents).map_err(io::Error::other),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(ApprovalRulesFile::default()),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| (*arg).to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn rules_persist_per_project() {
+        let codex_home = tempdir().expect("codex home");
+        let project_a = tempdir().expect("project a");
+        let project_b = tempdir().expect("project b");
+        let rule = ApprovalRule::ProgramSubcommand {
+            program: "git".to_string(),
+            subcommand: "status".to_string(),
+        };
+
+        let mut store = ApprovalRuleStore::load(codex_home.path(), project_a.path());
+        store.add(rule.clone()).await.expect("add rule");
+
+        let reloaded = ApprovalRuleStore::load(codex_home.path(), project_a.path());
+        assert_eq!(reloaded.rules(), std::slice::from_ref(&rule));
+        assert!(reloaded.approves(&argv(&["git", "status", "--short"]), project_a.path()));
+        assert!(reloaded.approves(
+            &argv(&["bash", "-lc", "git status && git status -s"]),
+            project_a.path()
+        ));
+        assert!(!reloaded.approves(
+            &argv(&["bash", "-lc", "git status && rm -rf target"]),
+            project_a.path()
+        ));
+
+        let other = ApprovalRuleStore::load(codex_home.path(), project_b.path());
+        assert!(other.rules().is_empty());
+
+        let mut store = reloaded;
+        assert!(store.remove(&rule).await.expect("remove rule"));
+        let reloaded = ApprovalRuleStore::load(codex_home.path(), project_a.path());
+        assert!(reloaded.rules().is_empty());
+    }
+
+    #[tokio::test]
+    async fn following_cwd_switches_to_the_new_projects_rules() {
+        let codex_home = tempdir().expect("codex home");
+        let project_a = tempdir().expect("project a");
+        let project_b = tempdir().expect("project b");
+        let rule = ApprovalRule::ExactCommand {
+            command: argv(&["make", "deploy"]),
+        };
+
+        let mut store = ApprovalRuleStore::load(codex_home.path(), project_a.path());
+        store.add(rule.clone()).await.expect("add rule");
+        assert!(store.approves(&argv(&["make", "deploy"]), project_a.path()));
+
+        store.follow_cwd(project_b.path());
+        assert!(store.rules().is_empty());
+        assert!(!store.approves(&argv(&["make", "deploy"]), project_b.path()));
+
+        store.follow_cwd(project_a.path());
+        assert_eq!(store.rules(), std::slice::from_ref(&rule));
+    }
+
+    #[test]
+    fn path_prefix_matches_programs_under_directory() {
+        let cwd = Path::new("/repo");
+        let rule = ApprovalRule::PathPrefix {
+            path: PathBuf::from("/repo/scripts"),
+        };
+
+        assert!(rule_matches(&rule, &argv(&["./scripts/lint.sh"]), cwd));
+        assert!(!rule_matches(&rule, &argv(&["./scripts/../bin/x"]), cwd));
+        assert!(!rule_matches(&rule, &argv(&["lint.sh"]), cwd));
+    }
+
+    #[test]
+    fn proposes_rules_from_most_to_least_specific() {
+        let cwd = Path::new("/repo");
+
+        assert_eq!(
+            proposed_rules(&argv(&["bash", "-lc", "./scripts/test.sh unit"]), cwd),
+            vec![
+                ApprovalRule::ExactCommand {
+                    command: argv(&["./scripts/test.sh", "unit"]),
+                },
+                ApprovalRule::ProgramSubcommand {
+                    program: "./scripts/test.sh".to_string(),
+                    subcommand: "unit".to_string(),
+                },
+                ApprovalRule::PathPrefix {
+                    path: PathBuf::from("/repo/scripts"),
+                },
+            ]
+        );
+        assert_eq!(
+            proposed_rules(&argv(&["ls", "-la"]), cwd),
+            vec![ApprovalRule::ExactCommand {
+                command: argv(&["ls", "-la"]),
+            }]
+        );
+    }
+}
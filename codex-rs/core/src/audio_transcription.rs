@@ -0,0 +1,152 @@
+//! Speech-to-text for audio files attached to user input.
+//!
+//! Each [`UserInput::LocalAudio`] is sent to the `/audio/transcriptions`
+//! endpoint of the configured provider and replaced by a text item that names
+//! the file and carries the transcript, so history keeps both.
+
+use std::path::Path;
+
+use codex_protocol::user_input::UserInput;
+use reqwest::multipart::Form;
+use reqwest::multipart::Part;
+use serde::Deserialize;
+
+use crate::codex::TurnContext;
+use crate::default_client::create_client;
+use crate::error::CodexErr;
+use crate::error::ConnectionFailedError;
+use crate::error::Result;
+use crate::error::UnexpectedResponseError;
+
+/// Largest audio file the transcription endpoint accepts.
+const MAX_AUDIO_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Extensions the transcription endpoint understands.
+const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "flac", "m4a", "mp3", "mp4", "mpeg", "mpga", "oga", "ogg", "wav", "webm",
+];
+
+#[derive(Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+}
+
+/// Replace every audio item in `items` with its transcript. Other items are
+/// returned unchanged.
+pub(crate) async fn transcribe_audio_inputs(
+    turn_context: &TurnContext,
+    items: Vec<UserInput>,
+) -> Result<Vec<UserInput>> {
+    if !items
+        .iter()
+        .any(|item| matches!(item, UserInput::LocalAudio { .. }))
+    {
+        return Ok(items);
+    }
+
+    let mut transcribed = Vec::with_capacity(items.len());
+    for item in items {
+        match item {
+            UserInput::LocalAudio { path } => {
+                let transcript = transcribe(turn_context, &path).await?;
+                transcribed.push(UserInput::Text {
+                    text: transcript_text(&path, &transcript),
+                });
+            }
+            other => transcribed.push(other),
+        }
+    }
+    Ok(transcribed)
+}
+
+fn transcript_text(path: &Path, transcript: &str) -> String {
+    format!(
+        "Transcript of audio `{}`:\n{}",
+        path.display(),
+        transcript.trim()
+    )
+}
+
+async fn transcribe(turn_context: &TurnContext, path: &Path) -> Result<String> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if !SUPPORTED_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(CodexErr::UnsupportedOperation(format!(
+            "cannot transcribe `{}`: supported audio formats are {}",
+            path.display(),
+            SUPPORTED_EXTENSIONS.join(", ")
+        )));
+    }
+    let len = tokio::fs::metadata(path).await?.len();
+    if len > MAX_AUDIO_BYTES {
+        return Err(CodexErr::UnsupportedOperation(format!(
+            "cannot transcribe `{}`: the file is {len} bytes and the limit is {MAX_AUDIO_BYTES}",
+            path.display()
+        )));
+    }
+    let bytes = tokio::fs::read(path).await?;
+
+    let config = turn_context.client.config();
+    let settings = &config.audio_transcription;
+    let provider = match settings.model_provider.as_deref() {
+        Some(id) => config.model_providers.get(id).ok_or_else(|| {
+            CodexErr::UnsupportedOperation(format!(
+                "audio_transcription.model_provider `{id}` is not in model_providers"
+            ))
+        })?,
+        None => turn_context.client.provider(),
+    };
+    let auth = turn_context
+        .client
+        .get_auth_manager()
+        .and_then(|manager| manager.auth());
+    let client = create_client();
+    let request = provider
+        .create_transcription_request_builder(&client, &auth)
+        .await?;
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| format!("audio.{extension}"));
+    let form = Form::new()
+        .text("model", settings.model.clone())
+        .part("file", Part::bytes(bytes).file_name(file_name));
+
+    let response = request
+        .multipart(form)
+        .send()
+        .await
+        .map_err(|source| CodexErr::ConnectionFailed(ConnectionFailedError { source }))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|source| CodexErr::ConnectionFailed(ConnectionFailedError { source }))?;
+    if !status.is_success() {
+        return Err(CodexErr::UnexpectedStatus(UnexpectedResponseError {
+            status,
+            body,
+            request_id: None,
+        }));
+    }
+    let TranscriptionResponse { text } = serde_json::from_str(&body)?;
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn transcript_names_the_audio_file() {
+        assert_eq!(
+            transcript_text(Path::new("/tmp/note.m4a"), " fix the build \n"),
+            "Transcript of audio `/tmp/note.m4a`:\nfix the build"
+        );
+    }
+}
@@ -1,3 +1,5 @@
+mod accounts;
+mod archive;
 mod storage;
 
 use chrono::Utc;
@@ -18,6 +20,13 @@ use std::time::Duration;
 use codex_app_server_protocol::AuthMode;
 use codex_protocol::config_types::ForcedLoginMethod;
 
+pub use crate::auth::accounts::ACCOUNTS_DIR;
+pub use crate::auth::accounts::StoredAccount;
+pub use crate::auth::accounts::account_credentials_home;
+pub use crate::auth::accounts::list_accounts;
+pub use crate::auth::accounts::validate_account_label;
+pub use crate::auth::archive::ArchivedAccount;
+pub use crate::auth::archive::CredentialArchive;
 pub use crate::auth::storage::AuthCredentialsStoreMode;
 pub use crate::auth::storage::AuthDotJson;
 use crate::auth::storage::AuthStorageBackend;
@@ -338,6 +347,8 @@ pub fn save_auth(
     auth: &AuthDotJson,
     auth_credentials_store_mode: AuthCredentialsStoreMode,
 ) -> std::io::Result<()> {
+    // Named accounts live in their own directory, which may not exist yet.
+    std::fs::create_dir_all(codex_home)?;
     let storage = create_auth_storage(codex_home.to_path_buf(), auth_credentials_store_mode);
     storage.save(auth)
 }
@@ -356,8 +367,9 @@ pub fn load_auth_dot_json(
 }
 
 pub async fn enforce_login_restrictions(config: &Config) -> std::io::Result<()> {
+    let credentials_home = config.credentials_home();
     let Some(auth) = load_auth(
-        &config.codex_home,
+        &credentials_home,
         true,
         config.cli_auth_credentials_store_mode,
     )?
@@ -381,7 +393,7 @@ pub async fn enforce_login_restrictions(config: &Config) -> std::io::Result<()>
 
         if let Some(message) = method_violation {
             return logout_with_message(
-                &config.codex_home,
+                &credentials_home,
                 message,
                 config.cli_auth_credentials_store_mode,
             );
@@ -397,7 +409,7 @@ pub async fn enforce_login_restrictions(config: &Config) -> std::io::Result<()>
             Ok(data) => data,
             Err(err) => {
                 return logout_with_message(
-                    &config.codex_home,
+                    &credentials_home,
                     format!(
                         "Failed to load ChatGPT credentials while enforcing workspace restrictions: {err}. Logging out."
                     ),
@@ -418,7 +430,7 @@ pub async fn enforce_login_restrictions(config: &Config) -> std::io::Result<()>
                 ),
             };
             return logout_with_message(
-                &config.codex_home,
+                &credentials_home,
                 message,
                 config.cli_auth_credentials_store_mode,
             );
@@ -638,6 +650,9 @@ use std::sync::RwLock;
 #[derive(Clone, Debug)]
 struct CachedAuth {
     auth: Option<CodexAuth>,
+    /// Label of the account the credentials belong to; `None` for the
+    /// default account.
+    account: Option<String>,
 }
 
 #[cfg(test)]
@@ -1097,9 +1112,25 @@ impl AuthManager {
         codex_home: PathBuf,
         enable_codex_api_key_env: bool,
         auth_credentials_store_mode: AuthCredentialsStoreMode,
+    ) -> Self {
+        Self::new_with_account(
+            codex_home,
+            None,
+            enable_codex_api_key_env,
+            auth_credentials_store_mode,
+        )
+    }
+
+    /// Like [`AuthManager::new`], but for the credentials stored under the
+    /// account label `account` (`None` for the default account).
+    pub fn new_with_account(
+        codex_home: PathBuf,
+        account: Option<String>,
+        enable_codex_api_key_env: bool,
+        auth_credentials_store_mode: AuthCredentialsStoreMode,
     ) -> Self {
         let auth = load_auth(
-            &codex_home,
+            &account_credentials_home(&codex_home, account.as_deref()),
             enable_codex_api_key_env,
             auth_credentials_store_mode,
         )
@@ -1107,7 +1138,7 @@ impl AuthManager {
         .flatten();
         Self {
             codex_home,
-            inner: RwLock::new(CachedAuth { auth }),
+            inner: RwLock::new(CachedAuth { auth, account }),
             enable_codex_api_key_env,
             auth_credentials_store_mode,
         }
@@ -1115,7 +1146,10 @@ impl AuthManager {
 
     /// Create an AuthManager with a specific CodexAuth, for testing only.
     pub fn from_auth_for_testing(auth: CodexAuth) -> Arc<Self> {
-        let cached = CachedAuth { auth: Some(auth) };
+        let cached = CachedAuth {
+            auth: Some(auth),
+            account: None,
+        };
         Arc::new(Self {
             codex_home: PathBuf::new(),
             inner: RwLock::new(cached),
@@ -1129,11 +1163,22 @@ impl AuthManager {
         self.inner.read().ok().and_then(|c| c.auth.clone())
     }
 
+    /// Label of the account in use; `None` for the default account.
+    pub fn account(&self) -> Option<String> {
+        self.inner.read().ok().and_then(|c| c.account.clone())
+    }
+
+    /// Directory holding the credentials of the account in use. Logging in
+    /// writes here.
+    pub fn credentials_home(&self) -> PathBuf {
+        account_credentials_home(&self.codex_home, self.account().as_deref())
+    }
+
     /// Force a reload of the auth information from auth.json. Returns
     /// whether the auth value changed.
     pub fn reload(&self) -> bool {
         let new_auth = load_auth(
-            &self.codex_home,
+            &self.credentials_home(),
             self.enable_codex_api_key_env,
             self.auth_credentials_store_mode,
         )
@@ -1148,6 +1193,36 @@ impl AuthManager {
         }
     }
 
+    /// Use the credentials stored under `account` from now on. The account
+    /// does not need credentials yet; logging in afterwards stores them
+    /// under its label.
+    pub fn switch_account(&self, account: Option<String>) -> std::io::Result<()> {
+        if let Some(label) = account.as_deref() {
+            validate_account_label(label)?;
+        }
+        let auth = load_auth(
+            &account_credentials_home(&self.codex_home, account.as_deref()),
+            self.enable_codex_api_key_env,
+            self.auth_credentials_store_mode,
+        )?;
+        if let Ok(mut guard) = self.inner.write() {
+            *guard = CachedAuth { auth, account };
+        }
+        Ok(())
+    }
+
+    /// A separate manager for the credentials stored under `account`, e.g.
+    /// for a conversation that uses a different account than the rest of
+    /// the process.
+    pub fn for_account(&self, account: Option<String>) -> Arc<Self> {
+        Arc::new(Self::new_with_account(
+            self.codex_home.clone(),
+            account,
+            self.enable_codex_api_key_env,
+            self.auth_credentials_store_mode,
+        ))
+    }
+
     fn auths_equal(a: &Option<CodexAuth>, b: &Option<CodexAuth>) -> bool {
         match (a, b) {
             (None, None) => true,
@@ -1169,6 +1244,22 @@ impl AuthManager {
         ))
     }
 
+    /// Convenience constructor returning an `Arc` wrapper around
+    /// [`AuthManager::new_with_account`].
+    pub fn shared_with_account(
+        codex_home: PathBuf,
+        account: Option<String>,
+        enable_codex_api_key_env: bool,
+        auth_credentials_store_mode: AuthCredentialsStoreMode,
+    ) -> Arc<Self> {
+        Arc::new(Self::new_with_account(
+            codex_home,
+            account,
+            enable_codex_api_key_env,
+            auth_credentials_store_mode,
+        ))
+    }
+
     /// Attempt to refresh the current auth token (if any). On success, reload
     /// the auth state from disk so other components observe refreshed token.
     /// If the token refresh fails in a permanent (non‑transient) way, logs out
@@ -1196,7 +1287,8 @@ impl AuthManager {
     /// reloads the in‑memory auth cache so callers immediately observe the
     /// unauthenticated state.
     pub fn logout(&self) -> std::io::Result<bool> {
-        let removed = super::auth::logout(&self.codex_home, self.auth_credentials_store_mode)?;
+        let removed =
+            super::auth::logout(&self.credentials_home(), self.auth_credentials_store_mode)?;
         // Always reload to clear any cached auth (even if file absent).
         self.reload();
         Ok(removed)
@@ -0,0 +1,179 @@
+//! Named accounts stored next to the default credentials.
+//!
+//! The default account keeps using `CODEX_HOME/auth.json` (or its keyring
+//! entry). Every labelled account gets its own credentials home under
+//! `CODEX_HOME/accounts/<label>/`, which the storage backends treat exactly
+//! like `CODEX_HOME`: an `auth.json` in that directory, or a keyring entry
+//! keyed by its path. Logging in, logging out, and refreshing tokens
+//! therefore work the same for every account.
+
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+use super::AuthCredentialsStoreMode;
+use super::CodexAuth;
+use super::load_auth;
+
+pub const ACCOUNTS_DIR: &str = "accounts";
+
+/// Credentials stored under an account label.
+#[derive(Debug, Clone)]
+pub struct StoredAccount {
+    pub label: String,
+    pub auth: CodexAuth,
+}
+
+/// Labels name a directory, so they are restricted to ASCII letters, digits,
+/// `-` and `_`.
+pub fn validate_account_label(label: &str) -> std::io::Result<()> {
+    let valid = !label.is_empty()
+        && label
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(std::io::Error::new(
+            ErrorKind::InvalidInput,
+            format!("invalid account label `{label}`: use ASCII letters, digits, `-` and `_` only"),
+        ))
+    }
+}
+
+/// Directory whose credentials belong to `account`; `CODEX_HOME` itself for
+/// the default account.
+pub fn account_credentials_home(codex_home: &Path, account: Option<&str>) -> PathBuf {
+    match account {
+        Some(label) => codex_home.join(ACCOUNTS_DIR).join(label),
+        None => codex_home.to_path_buf(),
+    }
+}
+
+/// Every labelled account that has credentials, sorted by label. The default
+/// account is not included.
+pub fn list_accounts(
+    codex_home: &Path,
+    auth_credentials_store_mode: AuthCredentialsStoreMode,
+) -> std::io::Result<Vec<StoredAccount>> {
+    let mut accounts = Vec::new();
+    for label in account_labels(codex_home)? {
+        let home = account_credentials_home(codex_home, Some(&label));
+        if let Some(auth) = load_auth(&home, false, auth_credentials_store_mode)? {
+            accounts.push(StoredAccount { label, auth });
+        }
+    }
+    Ok(accounts)
+}
+
+/// Labels of every account directory, sorted, whether or not it still holds
+/// credentials.
+pub(super) fn account_labels(codex_home: &Path) -> std::io::Result<Vec<String>> {
+    let entries = match std::fs::read_dir(codex_home.join(ACCOUNTS_DIR)) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut labels = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+        let Some(label) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if validate_account_label(&label).is_ok() {
+            labels.push(label);
+        }
+    }
+    labels.sort();
+    Ok(labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuthManager;
+    use crate::auth::login_with_api_key;
+    use codex_app_server_protocol::AuthMode;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[test]
+    fn lists_labelled_accounts_with_credentials() {
+        let codex_home = tempdir().expect("tempdir");
+        for (label, key) in [("work", "sk-work"), ("personal", "sk-personal")] {
+            login_with_api_key(
+                &account_credentials_home(codex_home.path(), Some(label)),
+                key,
+                AuthCredentialsStoreMode::File,
+            )
+            .expect("login");
+        }
+        login_with_api_key(
+            codex_home.path(),
+            "sk-default",
+            AuthCredentialsStoreMode::File,
+        )
+        .expect("login");
+        // A directory without credentials, e.g. after logging out, is skipped.
+        std::fs::create_dir_all(codex_home.path().join(ACCOUNTS_DIR).join("old"))
+            .expect("create dir");
+
+        let accounts =
+            list_accounts(codex_home.path(), AuthCredentialsStoreMode::File).expect("list");
+
+        assert_eq!(
+            accounts
+                .iter()
+                .map(|account| (account.label.as_str(), account.auth.mode))
+                .collect::<Vec<_>>(),
+            vec![("personal", AuthMode::ApiKey), ("work", AuthMode::ApiKey)]
+        );
+    }
+
+    #[tokio::test]
+    async fn auth_manager_switches_accounts_and_logs_in_to_the_selected_one() {
+        let codex_home = tempdir().expect("tempdir");
+        login_with_api_key(
+            codex_home.path(),
+            "sk-default",
+            AuthCredentialsStoreMode::File,
+        )
+        .expect("login");
+        let manager = AuthManager::new(
+            codex_home.path().to_path_buf(),
+            false,
+            AuthCredentialsStoreMode::File,
+        );
+
+        manager
+            .switch_account(Some("work".to_string()))
+            .expect("switch");
+        assert!(manager.auth().is_none());
+
+        login_with_api_key(
+            &manager.credentials_home(),
+            "sk-work",
+            AuthCredentialsStoreMode::File,
+        )
+        .expect("login");
+        manager.reload();
+        let token = manager.auth().expect("work auth").get_token().await;
+        assert_eq!(token.expect("token"), "sk-work");
+
+        manager.switch_account(None).expect("switch back");
+        let token = manager.auth().expect("default auth").get_token().await;
+        assert_eq!(token.expect("token"), "sk-default");
+        assert!(manager.switch_account(Some("../x".to_string())).is_err());
+    }
+
+    #[test]
+    fn rejects_labels_that_are_not_plain_names() {
+        assert!(validate_account_label("work-2").is_ok());
+        assert!(validate_account_label("").is_err());
+        assert!(validate_account_label("../work").is_err());
+        assert!(validate_account_label("my work").is_err());
+    }
+}
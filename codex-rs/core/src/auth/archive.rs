@@ -0,0 +1,297 @@
+//! Encrypted bundles of every stored credential, for moving to a new machine.
+//!
+//! An archive holds the Codex credentials of the default account and of every
+//! labelled account, plus the OAuth tokens of the configured MCP servers. It
+//! is read from and written back through the same storage backends Codex uses
+//! day to day, so credentials kept in the keyring are exported just like
+//! those in files, and imported ones land wherever the new machine's
+//! `cli_auth_credentials_store` / `mcp_oauth_credentials_store` point.
+//!
+//! On disk the archive is JSON sealed with ChaCha20-Poly1305 under a key
+//! derived from a passphrase with PBKDF2-HMAC-SHA256:
+//!
+//! ```text
+//! MAGIC | iterations (u32 BE) | salt (16) | nonce (12) | ciphertext + tag
+//! ```
+//!
+//! Everything before the nonce is authenticated as associated data.
+
+use std::io::ErrorKind;
+use std::num::NonZeroU32;
+use std::ops::RangeInclusive;
+
+use codex_rmcp_client::StoredOAuthTokens;
+use codex_rmcp_client::load_oauth_tokens;
+use codex_rmcp_client::save_oauth_tokens;
+use ring::aead::Aad;
+use ring::aead::CHACHA20_POLY1305;
+use ring::aead::LessSafeKey;
+use ring::aead::NONCE_LEN;
+use ring::aead::Nonce;
+use ring::aead::UnboundKey;
+use ring::pbkdf2;
+use ring::rand::SecureRandom;
+use ring::rand::SystemRandom;
+use serde::Deserialize;
+use serde::Serialize;
+
+use super::AuthDotJson;
+use super::accounts::account_credentials_home;
+use super::accounts::account_labels;
+use super::accounts::validate_account_label;
+use super::load_auth_dot_json;
+use super::save_auth;
+use crate::config::Config;
+use crate::config::types::McpServerTransportConfig;
+
+const MAGIC: &[u8] = b"codex-credentials\x01";
+const PBKDF2_ITERATIONS: u32 = 600_000;
+/// Archives asking for more rounds than this are rejected rather than left
+/// to stall the import.
+const MAX_PBKDF2_ITERATIONS: u32 = 10 * PBKDF2_ITERATIONS;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+/// Codex credentials stored under one account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedAccount {
+    /// `None` for the default account.
+    pub label: Option<String>,
+    pub auth: AuthDotJson,
+}
+
+/// Every credential exported from one machine.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CredentialArchive {
+    pub accounts: Vec<ArchivedAccount>,
+    pub mcp_oauth_tokens: Vec<StoredOAuthTokens>,
+}
+
+impl CredentialArchive {
+    /// Read every stored credential reachable from `config`: all accounts
+    /// under `CODEX_HOME`, and the OAuth tokens of each configured streamable
+    /// HTTP MCP server.
+    pub fn collect(config: &Config) -> std::io::Result<Self> {
+        let mut archive = Self {
+            accounts: collect_accounts(config)?,
+            mcp_oauth_tokens: Vec::new(),
+        };
+        let mut servers = config.mcp_servers.iter().collect::<Vec<_>>();
+        servers.sort_by_key(|(name, _)| name.as_str());
+        for (name, server) in servers {
+            let McpServerTransportConfig::StreamableHttp { url, .. } = &server.transport else {
+                continue;
+            };
+            if let Some(tokens) =
+                load_oauth_tokens(name, url, config.mcp_oauth_credentials_store_mode)
+                    .map_err(std::io::Error::other)?
+            {
+                archive.mcp_oauth_tokens.push(tokens);
+            }
+        }
+        Ok(archive)
+    }
+
+    /// Store every credential in the archive, replacing any already stored
+    /// for the same account or MCP server.
+    pub fn restore(&self, config: &Config) -> std::io::Result<()> {
+        for account in &self.accounts {
+            if let Some(label) = &account.label {
+                validate_account_label(label)?;
+            }
+            save_auth(
+                &account_credentials_home(&config.codex_home, account.label.as_deref()),
+                &account.auth,
+                config.cli_auth_credentials_store_mode,
+            )?;
+        }
+        for tokens in &self.mcp_oauth_tokens {
+            save_oauth_tokens(
+                &tokens.server_name,
+                tokens,
+                config.mcp_oauth_credentials_store_mode,
+            )
+            .map_err(std::io::Error::other)?;
+        }
+        Ok(())
+    }
+
+    /// Serialize and encrypt the archive with `passphrase`.
+    pub fn encrypt(&self, passphrase: &str) -> std::io::Result<Vec<u8>> {
+        self.encrypt_with_iterations(passphrase, PBKDF2_ITERATIONS)
+    }
+
+    fn encrypt_with_iterations(
+        &self,
+        passphrase: &str,
+        iterations: u32,
+    ) -> std::io::Result<Vec<u8>> {
+        let rng = SystemRandom::new();
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        rng.fill(&mut salt)
+            .and_then(|()| rng.fill(&mut nonce))
+            .map_err(|_| std::io::Error::other("failed to generate random bytes"))?;
+
+        let mut header = MAGIC.to_vec();
+        header.extend_from_slice(&iterations.to_be_bytes());
+        header.extend_from_slice(&salt);
+
+        let key = derive_key(passphrase, iterations, &salt)?;
+        let mut sealed = serde_json::to_vec(self)?;
+        key.seal_in_place_append_tag(
+            Nonce::assume_unique_for_key(nonce),
+            Aad::from(header.as_slice()),
+            &mut sealed,
+        )
+        .map_err(|_| std::io::Error::other("failed to encrypt credentials"))?;
+
+        let mut out = header;
+        out.extend_from_slice(&nonce);
+        out.append(&mut sealed);
+        Ok(out)
+    }
+
+    /// Decrypt an archive produced by [`CredentialArchive::encrypt`].
+    pub fn decrypt(data: &[u8], passphrase: &str) -> std::io::Result<Self> {
+        Self::decrypt_with_iterations(data, passphrase, PBKDF2_ITERATIONS..=MAX_PBKDF2_ITERATIONS)
+    }
+
+    fn decrypt_with_iterations(
+        data: &[u8],
+        passphrase: &str,
+        accepted_iterations: RangeInclusive<u32>,
+    ) -> std::io::Result<Self> {
+        let invalid = |message: &str| std::io::Error::new(ErrorKind::InvalidData, message);
+        let header_len = MAGIC.len() + 4 + SALT_LEN;
+        if data.len() < header_len + NONCE_LEN || !data.starts_with(MAGIC) {
+            return Err(invalid("not a Codex credentials archive"));
+        }
+        let (header, rest) = data.split_at(header_len);
+        let (iterations, salt) = header[MAGIC.len()..].split_at(4);
+        let iterations = u32::from_be_bytes(
+            iterations
+                .try_into()
+                .map_err(|_| invalid("not a Codex credentials archive"))?,
+        );
+        if !accepted_iterations.contains(&iterations) {
+            return Err(invalid("unsupported key derivation settings"));
+        }
+        let (nonce, sealed) = rest.split_at(NONCE_LEN);
+        let nonce =
+            Nonce::try_assume_unique_for_key(nonce).map_err(|_| invalid("corrupted archive"))?;
+
+        let key = derive_key(passphrase, iterations, salt)?;
+        let mut sealed = sealed.to_vec();
+        let plaintext = key
+            .open_in_place(nonce, Aad::from(header), &mut sealed)
+            .map_err(|_| invalid("wrong passphrase or corrupted archive"))?;
+        serde_json::from_slice(plaintext).map_err(std::io::Error::other)
+    }
+}
+
+fn collect_accounts(config: &Config) -> std::io::Result<Vec<ArchivedAccount>> {
+    let mode = config.cli_auth_credentials_store_mode;
+    let mut accounts = Vec::new();
+    if let Some(auth) = load_auth_dot_json(&config.codex_home, mode)? {
+        accounts.push(ArchivedAccount { label: None, auth });
+    }
+    for label in account_labels(&config.codex_home)? {
+        let home = account_credentials_home(&config.codex_home, Some(&label));
+        if let Some(auth) = load_auth_dot_json(&home, mode)? {
+            accounts.push(ArchivedAccount {
+                label: Some(label),
+                auth,
+            });
+        }
+    }
+    Ok(accounts)
+}
+
+fn derive_key(passphrase: &str, iterations: u32, salt: &[u8]) -> std::io::Result<LessSafeKey> {
+    let iterations = NonZeroU32::new(iterations).ok_or_else(|| {
+        std::io::Error::new(ErrorKind::InvalidData, "not a Codex credentials archive")
+    })?;
+    let mut key = [0u8; KEY_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations,
+        salt,
+        passphrase.as_bytes(),
+        &mut key,
+    );
+    let key = UnboundKey::new(&CHACHA20_POLY1305, &key)
+        .map_err(|_| std::io::Error::other("failed to derive encryption key"))?;
+    Ok(LessSafeKey::new(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const TEST_ITERATIONS: RangeInclusive<u32> = 1_000..=10_000;
+
+    fn archive() -> CredentialArchive {
+        CredentialArchive {
+            accounts: vec![ArchivedAccount {
+                label: Some("work".to_string()),
+                auth: AuthDotJson {
+                    openai_api_key: Some("sk-work".to_string()),
+                    tokens: None,
+                    last_refresh: None,
+                },
+            }],
+            mcp_oauth_tokens: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn round_trips_with_the_right_passphrase_only() {
+        let sealed = archive()
+            .encrypt_with_iterations("correct horse", 1_000)
+            .expect("encrypt");
+        assert!(sealed.starts_with(MAGIC));
+        assert!(!String::from_utf8_lossy(&sealed).contains("sk-work"));
+
+        assert_eq!(
+            CredentialArchive::decrypt_with_iterations(&sealed, "correct horse", TEST_ITERATIONS)
+                .expect("decrypt"),
+            archive()
+        );
+        let err = CredentialArchive::decrypt_with_iterations(&sealed, "wrong", TEST_ITERATIONS)
+            .expect_err("wrong passphrase");
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_iteration_counts_outside_the_accepted_range() {
+        let sealed = archive()
+            .encrypt_with_iterations("pass", 1_000)
+            .expect("encrypt");
+        // Too few rounds for a real archive.
+        let err = CredentialArchive::decrypt(&sealed, "pass").expect_err("too few rounds");
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+
+        // A crafted header asking for ~4 billion rounds fails before any
+        // key derivation instead of hanging.
+        let mut crafted = sealed;
+        crafted[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        let err = CredentialArchive::decrypt(&crafted, "pass").expect_err("too many rounds");
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "unsupported key derivation settings");
+    }
+
+    #[test]
+    fn rejects_tampered_headers_and_other_files() {
+        let mut sealed = archive()
+            .encrypt_with_iterations("pass", 1_000)
+            .expect("encrypt");
+        // Lowering the iteration count must not go unnoticed.
+        sealed[MAGIC.len() + 3] ^= 1;
+        assert!(
+            CredentialArchive::decrypt_with_iterations(&sealed, "pass", TEST_ITERATIONS).is_err()
+        );
+        assert!(CredentialArchive::decrypt(b"{\"accounts\":[]}", "pass").is_err());
+    }
+}
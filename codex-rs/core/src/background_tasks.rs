@@ -0,0 +1,442 @@
+//! Long-running commands detached from the turn that started them.
+//!
+//! `Op::RunBackground` starts a command (a dev server, a test watcher) that
+//! neither blocks the turn nor dies with it. Its stdout and stderr are
+//! interleaved into a ring buffer holding the most recent
+//! [`BACKGROUND_OUTPUT_MAX_BYTES`], which the model reads with the
+//! `background_task_output` tool and clients read through the app server.
+//! Tasks run until they exit, are stopped with `Op::TerminateBackground`, or
+//! their conversation shuts down.
+//!
+//! Task ids are unique across the process, so the app server can look tasks
+//! up without going through the conversation; lookups still check that the
+//! task belongs to the conversation asking for it.
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use async_channel::Sender;
+use codex_protocol::ConversationId;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::process::Child;
+use tokio::sync::Notify;
+use tracing::warn;
+
+use crate::exec::kill_child_process_group;
+use crate::protocol::BackgroundTaskStatus;
+use crate::protocol::BackgroundTaskUpdateEvent;
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::SandboxPolicy;
+use crate::spawn::StdioPolicy;
+use crate::spawn::spawn_child_async;
+
+/// Output kept per task; older output is dropped first.
+pub const BACKGROUND_OUTPUT_MAX_BYTES: usize = 256 * 1024;
+
+static SHARED: LazyLock<Arc<BackgroundTaskManager>> = LazyLock::new(Arc::default);
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum BackgroundTaskError {
+    #[error("no background task named {0} in this conversation")]
+    NotFound(String),
+}
+
+/// Point-in-time view of a background task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackgroundTaskSnapshot {
+    pub task_id: String,
+    pub command: String,
+    pub status: BackgroundTaskStatus,
+    pub exit_code: Option<i32>,
+    /// The most recent output, stdout and stderr interleaved.
+    pub output: String,
+    /// Bytes dropped from the start of the output to stay within the buffer.
+    pub dropped_bytes: u64,
+}
+
+#[derive(Default)]
+pub struct BackgroundTaskManager {
+    next_id: AtomicU64,
+    tasks: Mutex<HashMap<String, Arc<BackgroundTask>>>,
+}
+
+struct BackgroundTask {
+    id: String,
+    conversation_id: ConversationId,
+    command: String,
+    output: Mutex<OutputRing>,
+    state: Mutex<TaskState>,
+    terminate: Notify,
+}
+
+#[derive(Clone, Copy)]
+struct TaskState {
+    status: BackgroundTaskStatus,
+    exit_code: Option<i32>,
+}
+
+#[derive(Default)]
+struct OutputRing {
+    bytes: VecDeque<u8>,
+    dropped: u64,
+}
+
+impl OutputRing {
+    fn push(&mut self, chunk: &[u8]) {
+        self.bytes.extend(chunk);
+        let excess = self.bytes.len().saturating_sub(BACKGROUND_OUTPUT_MAX_BYTES);
+        if excess > 0 {
+            self.bytes.drain(..excess);
+            self.dropped += excess as u64;
+        }
+    }
+}
+
+impl BackgroundTaskManager {
+    /// The manager shared by every conversation in this process.
+    pub fn shared() -> Arc<Self> {
+        Arc::clone(&SHARED)
+    }
+
+    /// Start `argv` and return the new task's id. `Running` and the final
+    /// status are reported on `tx_event` under `sub_id`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn spawn(
+        &self,
+        conversation_id: ConversationId,
+        command: String,
+        argv: Vec<String>,
+        cwd: PathBuf,
+        env: HashMap<String, String>,
+        tx_event: Sender<Event>,
+        sub_id: String,
+    ) -> std::io::Result<String> {
+        let Some((program, args)) = argv.split_first() else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "background command is empty",
+            ));
+        };
+        // Background tasks are started by the user, like `!` shell commands,
+        // so they run outside the sandbox.
+        let mut child = spawn_child_async(
+            PathBuf::from(program),
+            args.to_vec(),
+            None,
+            cwd,
+            &SandboxPolicy::DangerFullAccess,
+            StdioPolicy::RedirectForShellTool,
+            env,
+        )
+        .await?;
+
+        let id = format!("bg-{}", self.next_id.fetch_add(1, Ordering::SeqCst) + 1);
+        let task = Arc::new(BackgroundTask {
+            id: id.clone(),
+            conversation_id,
+            command,
+            output: Mutex::new(OutputRing::default()),
+            state: Mutex::new(TaskState {
+                status: BackgroundTaskStatus::Running,
+                exit_code: None,
+            }),
+            terminate: Notify::new(),
+        });
+        if let Ok(mut tasks) = self.tasks.lock() {
+            tasks.insert(id.clone(), Arc::clone(&task));
+        }
+
+        if let Some(stdout) = child.stdout.take() {
+            tokio::spawn(capture_output(stdout, Arc::clone(&task)));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(capture_output(stderr, Arc::clone(&task)));
+        }
+        let _ = tx_event
+            .send(Event {
+                id: sub_id.clone(),
+                msg: EventMsg::BackgroundTaskUpdate(task.update()),
+            })
+            .await;
+        tokio::spawn(async move {
+            task.watch(child).await;
+            let _ = tx_event
+                .send(Event {
+                    id: sub_id,
+                    msg: EventMsg::BackgroundTaskUpdate(task.update()),
+                })
+                .await;
+        });
+        Ok(id)
+    }
+
+    pub fn snapshot(
+        &self,
+        conversation_id: ConversationId,
+        task_id: &str,
+    ) -> Result<BackgroundTaskSnapshot, BackgroundTaskError> {
+        self.get(conversation_id, task_id)
+            .map(|task| task.snapshot())
+    }
+
+    /// Every task of the conversation, oldest first.
+    pub fn list(&self, conversation_id: ConversationId) -> Vec<BackgroundTaskSnapshot> {
+        let Ok(tasks) = self.tasks.lock() else {
+            return Vec::new();
+        };
+        let mut snapshots: Vec<BackgroundTaskSnapshot> = tasks
+            .values()
+            .filter(|task| task.conversation_id == conversation_id)
+            .map(|task| task.snapshot())
+            .collect();
+        snapshots.sort_by_key(|snapshot| task_number(&snapshot.task_id));
+        snapshots
+    }
+
+    /// Ask the task to stop. Stopping a task that already exited is a no-op.
+    pub fn terminate(
+        &self,
+        conversation_id: ConversationId,
+        task_id: &str,
+    ) -> Result<(), BackgroundTaskError> {
+        self.get(conversation_id, task_id)?.terminate.notify_one();
+        Ok(())
+    }
+
+    /// Stop and forget every task of a conversation that is shutting down.
+    pub(crate) fn terminate_all(&self, conversation_id: ConversationId) {
+        let Ok(mut tasks) = self.tasks.lock() else {
+            return;
+        };
+        tasks.retain(|_, task| {
+            if task.conversation_id != conversation_id {
+                return true;
+            }
+            task.terminate.notify_one();
+            false
+        });
+    }
+
+    fn get(
+        &self,
+        conversation_id: ConversationId,
+        task_id: &str,
+    ) -> Result<Arc<BackgroundTask>, BackgroundTaskError> {
+        self.tasks
+            .lock()
+            .ok()
+            .and_then(|tasks| tasks.get(task_id).cloned())
+            .filter(|task| task.conversation_id == conversation_id)
+            .ok_or_else(|| BackgroundTaskError::NotFound(task_id.to_string()))
+    }
+}
+
+impl BackgroundTask {
+    async fn watch(&self, mut child: Child) {
+        let exited = tokio::select! {
+            status = child.wait() => Some(status),
+            () = self.terminate.notified() => None,
+        };
+        let state = match exited {
+            Some(status) => TaskState {
+                status: BackgroundTaskStatus::Exited,
+                exit_code: status.ok().and_then(|status| status.code()),
+            },
+            None => {
+                if let Err(err) = kill_child_process_group(&mut child) {
+                    warn!("failed to kill background task {}: {err}", self.id);
+                }
+                let _ = child.start_kill();
+                let _ = child.wait().await;
+                TaskState {
+                    status: BackgroundTaskStatus::Terminated,
+                    exit_code: None,
+                }
+            }
+        };
+        if let Ok(mut current) = self.state.lock() {
+            *current = state;
+        }
+    }
+
+    fn state(&self) -> TaskState {
+        self.state.lock().map_or(
+            TaskState {
+                status: BackgroundTaskStatus::Running,
+                exit_code: None,
+            },
+            |state| *state,
+        )
+    }
+
+    fn update(&self) -> BackgroundTaskUpdateEvent {
+        let TaskState { status, exit_code } = self.state();
+        BackgroundTaskUpdateEvent {
+            task_id: self.id.clone(),
+            command: self.command.clone(),
+            status,
+            exit_code,
+        }
+    }
+
+    fn snapshot(&self) -> BackgroundTaskSnapshot {
+        let TaskState { status, exit_code } = self.state();
+        let (output, dropped_bytes) = self.output.lock().map_or_else(
+            |_| (String::new(), 0),
+            |ring| {
+                let bytes: Vec<u8> = ring.bytes.iter().copied().collect();
+                (String::from_utf8_lossy(&bytes).into_owned(), ring.dropped)
+            },
+        );
+        BackgroundTaskSnapshot {
+            task_id: self.id.clone(),
+            command: self.command.clone(),
+            status,
+            exit_code,
+            output,
+            dropped_bytes,
+        }
+    }
+}
+
+async fn capture_output<R: AsyncRead + Unpin>(mut reader: R, task: Arc<BackgroundTask>) {
+    let mut buf = [0u8; 8192];
+    loop {
+        match reader.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                if let Ok(mut ring) = task.output.lock() {
+                    ring.push(&buf[..n]);
+                }
+            }
+        }
+    }
+}
+
+fn task_number(task_id: &str) -> u64 {
+    task_id
+        .strip_prefix("bg-")
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(u64::MAX)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn sh(script: &str) -> Vec<String> {
+        vec!["/bin/sh".to_string(), "-c".to_string(), script.to_string()]
+    }
+
+    #[expect(clippy::expect_used)]
+    async fn next_update(rx: &async_channel::Receiver<Event>) -> BackgroundTaskUpdateEvent {
+        let event = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+            .await
+            .expect("timed out waiting for update")
+            .expect("event channel closed");
+        match event.msg {
+            EventMsg::BackgroundTaskUpdate(update) => update,
+            other => panic!("unexpected event {other:?}"),
+        }
+    }
+
+    #[expect(clippy::expect_used)]
+    async fn spawn(
+        manager: &BackgroundTaskManager,
+        conversation_id: ConversationId,
+        script: &str,
+        tx: Sender<Event>,
+    ) -> String {
+        let env = std::env::vars().collect();
+        manager
+            .spawn(
+                conversation_id,
+                script.to_string(),
+                sh(script),
+                std::env::temp_dir(),
+                env,
+                tx,
+                "sub".to_string(),
+            )
+            .await
+            .expect("spawn")
+    }
+
+    #[tokio::test]
+    async fn captures_output_and_reports_exit() {
+        let manager = BackgroundTaskManager::default();
+        let conversation_id = ConversationId::new();
+        let (tx, rx) = async_channel::unbounded();
+
+        let id = spawn(
+            &manager,
+            conversation_id,
+            "echo out; echo err >&2; exit 3",
+            tx,
+        )
+        .await;
+
+        assert_eq!(next_update(&rx).await.status, BackgroundTaskStatus::Running);
+        let exited = next_update(&rx).await;
+        assert_eq!(exited.status, BackgroundTaskStatus::Exited);
+        assert_eq!(exited.exit_code, Some(3));
+
+        // Output readers can trail the exit briefly.
+        let mut snapshot = manager.snapshot(conversation_id, &id).expect("snapshot");
+        for _ in 0..100 {
+            if snapshot.output.contains("out") && snapshot.output.contains("err") {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            snapshot = manager.snapshot(conversation_id, &id).expect("snapshot");
+        }
+        assert!(snapshot.output.contains("out\n"), "{}", snapshot.output);
+        assert!(snapshot.output.contains("err\n"), "{}", snapshot.output);
+        assert_eq!(
+            manager.snapshot(ConversationId::new(), &id),
+            Err(BackgroundTaskError::NotFound(id))
+        );
+    }
+
+    #[tokio::test]
+    async fn terminate_stops_a_running_task() {
+        let manager = BackgroundTaskManager::default();
+        let conversation_id = ConversationId::new();
+        let (tx, rx) = async_channel::unbounded();
+
+        let id = spawn(&manager, conversation_id, "sleep 60", tx).await;
+        assert_eq!(next_update(&rx).await.status, BackgroundTaskStatus::Running);
+
+        manager.terminate(conversation_id, &id).expect("terminate");
+
+        assert_eq!(
+            next_update(&rx).await.status,
+            BackgroundTaskStatus::Terminated
+        );
+        assert_eq!(
+            manager.list(conversation_id)[0].status,
+            BackgroundTaskStatus::Terminated
+        );
+    }
+
+    #[test]
+    fn output_ring_keeps_the_most_recent_bytes() {
+        let mut ring = OutputRing::default();
+        ring.push(&vec![b'a'; BACKGROUND_OUTPUT_MAX_BYTES]);
+        ring.push(b"tail");
+
+        assert_eq!(ring.bytes.len(), BACKGROUND_OUTPUT_MAX_BYTES);
+        assert_eq!(ring.dropped, 4);
+        assert!(ring.bytes.iter().rev().take(4).eq(b"liat".iter()));
+    }
+}
@@ -118,6 +118,367 @@ pub fn parse_shell_lc_plain_commands(command: &[String]) -> Option<Vec<Vec<Strin
     try_parse_word_only_commands_sequence(&tree, script)
 }
 
+/// Like [`parse_shell_lc_plain_commands`], but keeps the commands joined by
+/// `|` together: each entry is one pipeline, in source order, and a command
+/// outside any pipeline is a pipeline of its own.
+pub fn parse_shell_lc_pipelines(command: &[String]) -> Option<Vec<Vec<Vec<String>>>> {
+    let (_, script) = extract_bash_command(command)?;
+    let tree = try_parse_shell(script)?;
+    try_parse_word_only_commands_sequence(&tree, script)?;
+    let mut pipelines = Vec::new();
+    collect_pipelines(tree.root_node(), script, &mut pipelines)?;
+    Some(pipelines)
+}
+
+fn collect_pipelines(node: Node, src: &str, out: &mut Vec<Vec<Vec<String>>>) -> Option<()> {
+    let mut cursor = node.walk();
+    match node.kind() {
+        "command" => out.push(vec![parse_plain_command_from_node(node, src)?]),
+        "pipeline" => out.push(
+            node.named_children(&mut cursor)
+                .map(|command| parse_plain_command_from_node(command, src))
+                .collect::<Option<Vec<_>>>()?,
+        ),
+        _ => {
+            for child in node.named_children(&mut cursor) {
+                collect_pipelines(child, src, out)?;
+            }
+        }
+    }
+    Some(())
+}
+
+/// How a script segment is joined to the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentJoin {
+    /// The first segment of the script.
+    Start,
+    /// `&&`: runs only if the previous segment succeeded.
+    And,
+    /// `;` or a newline: runs regardless of the previous segment.
+    Then,
+}
+
+/// One top-level part of a `bash -lc` script: a plain command or pipeline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptSegment {
+    pub join: SegmentJoin,
+    /// The segment's source text, runnable on its own with `bash -lc`.
+    pub source: String,
+    pub commands: Vec<Vec<String>>,
+}
+
+/// Builtins whose effect carries over to later commands in the same shell.
+/// Scripts using them cannot be run one segment at a time.
+const STATEFUL_BUILTINS: &[&str] = &[
+    "cd", "pushd", "popd", "source", ".", "set", "shopt", "umask", "ulimit", "alias", "unalias",
+    "exec", "eval", "hash", "export", "unset",
+];
+
+/// Splits a `bash -lc "..."` or `zsh -lc "..."` script at its top-level `&&`,
+/// `;`, and newline operators so that each segment can be approved and run on
+/// its own. Returns `None` unless the script is made of plain commands (see
+/// [`parse_shell_lc_plain_commands`]) with at least two segments, and also for
+/// scripts using `||` or builtins that change the shell's state.
+pub fn split_shell_lc_segments(command: &[String]) -> Option<Vec<ScriptSegment>> {
+    let (_, script) = extract_bash_command(command)?;
+    let tree = try_parse_shell(script)?;
+    let commands = try_parse_word_only_commands_sequence(&tree, script)?;
+    if commands.iter().any(|words| {
+        words
+            .first()
+            .is_some_and(|program| STATEFUL_BUILTINS.contains(&program.as_str()))
+    }) {
+        return None;
+    }
+
+    let mut segments = Vec::new();
+    let root = tree.root_node();
+    let mut cursor = root.walk();
+    let mut join = SegmentJoin::Start;
+    for child in root.children(&mut cursor) {
+        if child.is_named() {
+            collect_segments(child, script, join, &mut segments)?;
+            join = SegmentJoin::Then;
+        }
+    }
+    (segments.len() > 1).then_some(segments)
+}
+
+fn collect_segments(
+    node: Node,
+    src: &str,
+    join: SegmentJoin,
+    out: &mut Vec<ScriptSegment>,
+) -> Option<()> {
+    match node.kind() {
+        "list" => {
+            let left = node.named_child(0)?;
+            let right = node.named_child(1)?;
+            let operator = node.child(1)?;
+            if operator.kind() != "&&" {
+                return None;
+            }
+            collect_segments(left, src, join, out)?;
+            collect_segments(right, src, SegmentJoin::And, out)
+        }
+        "command" | "pipeline" => {
+            let source = node.utf8_text(src.as_bytes()).ok()?.to_owned();
+            let tree = try_parse_shell(&source)?;
+            let commands = try_parse_word_only_commands_sequence(&tree, &source)?;
+            out.push(ScriptSegment {
+                join,
+                source,
+                commands,
+            });
+            Some(())
+        }
+        _ => None,
+    }
+}
+
+/// Returns every command a `bash -lc "..."` or `zsh -lc "..."` script runs,
+/// for policy evaluation.
+///
+/// Unlike [`parse_shell_lc_plain_commands`], this accepts multi-line scripts,
+/// heredocs, redirections, and command substitutions. Commands inside `$(...)`
+/// or an unquoted heredoc body, and commands chained after a heredoc, are
+/// returned as commands of their own. Arguments that expand keep their source
+/// text, and output redirections are appended to their command as the
+/// operator followed by the target, e.g. `["cat", ">", "notes.txt"]` for
+/// `cat <<EOF > notes.txt`. Returns `None` for control flow, subshells,
+/// function definitions, assignments, and commands whose program name is not
+/// a literal word.
+pub fn parse_shell_lc_script_commands(command: &[String]) -> Option<Vec<Vec<String>>> {
+    let (_, script) = extract_bash_command(command)?;
+
+    let tree = try_parse_shell(script)?;
+    try_parse_script_commands(&tree, script)
+}
+
+/// See [`parse_shell_lc_script_commands`]. Commands are returned in source
+/// order.
+pub fn try_parse_script_commands(tree: &Tree, src: &str) -> Option<Vec<Vec<String>>> {
+    let root = tree.root_node();
+    if root.has_error() {
+        return None;
+    }
+    let mut commands = Vec::new();
+    collect_statement(root, src, &mut commands)?;
+    commands.sort_by_key(|(start, _)| *start);
+    Some(commands.into_iter().map(|(_, words)| words).collect())
+}
+
+/// A command found in a script, keyed by its start byte for ordering.
+type ScriptCommand = (usize, Vec<String>);
+
+/// Operators allowed between statements. A lone `&` (background job) is not.
+const STATEMENT_SEPARATORS: &[&str] = &["&&", "||", ";", "|", "|&"];
+
+fn collect_statement(node: Node, src: &str, out: &mut Vec<ScriptCommand>) -> Option<()> {
+    match node.kind() {
+        "program" | "list" | "pipeline" | "negated_command" => {
+            let mut cursor = node.walk();
+            for child in node.children(&mut cursor) {
+                if child.is_named() {
+                    collect_statement(child, src, out)?;
+                } else if !(STATEMENT_SEPARATORS.contains(&child.kind())
+                    || child.kind() == "!"
+                    || child.kind().trim().is_empty())
+                {
+                    return None;
+                }
+            }
+            Some(())
+        }
+        "comment" => Some(()),
+        "command" => {
+            let mut words = Vec::new();
+            let mut writes = Vec::new();
+            collect_command(node, src, &mut words, &mut writes, out)?;
+            words.extend(writes);
+            out.push((node.start_byte(), words));
+            Some(())
+        }
+        "redirected_statement" => collect_redirected_statement(node, src, out),
+        _ => None,
+    }
+}
+
+/// Handles `<command> <redirects..>`, where the redirects may include a
+/// heredoc followed by more arguments, a pipeline, or a `&&`/`||` chain.
+fn collect_redirected_statement(node: Node, src: &str, out: &mut Vec<ScriptCommand>) -> Option<()> {
+    let mut cursor = node.walk();
+    let mut body = None;
+    let mut words = Vec::new();
+    let mut writes = Vec::new();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "command" if body.is_none() => {
+                body = Some(child.start_byte());
+                collect_command(child, src, &mut words, &mut writes, out)?;
+            }
+            "file_redirect" => collect_file_redirect(child, src, &mut writes, out)?,
+            "heredoc_redirect" => collect_heredoc(child, src, &mut words, &mut writes, out)?,
+            "herestring_redirect" => collect_substitutions(child, src, out)?,
+            _ => return None,
+        }
+    }
+    words.extend(writes);
+    out.push((body?, words));
+    Some(())
+}
+
+fn collect_command(
+    node: Node,
+    src: &str,
+    words: &mut Vec<String>,
+    writes: &mut Vec<String>,
+    out: &mut Vec<ScriptCommand>,
+) -> Option<()> {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        match child.kind() {
+            "command_name" => {
+                let name = child.named_child(0)?;
+                if name.kind() != "word" {
+                    return None;
+                }
+                words.push(name.utf8_text(src.as_bytes()).ok()?.to_owned());
+            }
+            "file_redirect" => collect_file_redirect(child, src, writes, out)?,
+            "herestring_redirect" => collect_substitutions(child, src, out)?,
+            _ => words.push(argument_text(child, src, out)?),
+        }
+    }
+    Some(())
+}
+
+/// Records output redirections; input redirections and descriptor
+/// duplication such as `2>&1` do not write files.
+fn collect_file_redirect(
+    node: Node,
+    src: &str,
+    writes: &mut Vec<String>,
+    out: &mut Vec<ScriptCommand>,
+) -> Option<()> {
+    let mut descriptor = "";
+    let mut operator = None;
+    let mut targets = Vec::new();
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if child.kind() == "file_descriptor" {
+            descriptor = child.utf8_text(src.as_bytes()).ok()?;
+        } else if child.is_named() {
+            targets.push(argument_text(child, src, out)?);
+        } else if !child.kind().trim().is_empty() {
+            operator = Some(child.kind());
+        }
+    }
+    let operator = operator?;
+    let duplicates_descriptor = matches!(operator, ">&" | "<&")
+        && targets
+            .iter()
+            .all(|target| target == "-" || target.chars().all(|c| c.is_ascii_digit()));
+    if matches!(operator, ">" | ">>" | "&>" | "&>>" | ">|" | ">&") && !duplicates_descriptor {
+        writes.push(format!("{descriptor}{operator}"));
+        writes.extend(targets);
+    }
+    Some(())
+}
+
+/// A heredoc is input to its command, so only what it adds around that
+/// input matters: substitutions in an unquoted body, arguments and
+/// redirections after the delimiter, and chained commands.
+fn collect_heredoc(
+    node: Node,
+    src: &str,
+    words: &mut Vec<String>,
+    writes: &mut Vec<String>,
+    out: &mut Vec<ScriptCommand>,
+) -> Option<()> {
+    let mut expands = true;
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        match child.kind() {
+            "heredoc_start" => {
+                let delimiter = child.utf8_text(src.as_bytes()).ok()?;
+                expands = !delimiter.contains(['\'', '"', '\\']);
+            }
+            "heredoc_body" if expands => collect_substitutions(child, src, out)?,
+            "heredoc_body" | "heredoc_end" | "heredoc_content" | "file_descriptor" => {}
+            "file_redirect" => collect_file_redirect(child, src, writes, out)?,
+            "command" | "pipeline" | "list" | "redirected_statement" | "negated_command" => {
+                collect_statement(child, src, out)?;
+            }
+            "<<" | "<<-" | "&&" | "||" | "|" | "|&" => {}
+            kind if !child.is_named() && kind.trim().is_empty() => {}
+            _ if child.is_named() => words.push(argument_text(child, src, out)?),
+            _ => return None,
+        }
+    }
+    Some(())
+}
+
+/// Collects the commands inside every command or process substitution
+/// under `node`.
+fn collect_substitutions(node: Node, src: &str, out: &mut Vec<ScriptCommand>) -> Option<()> {
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if matches!(
+            child.kind(),
+            "command_substitution" | "process_substitution"
+        ) {
+            let mut inner = child.walk();
+            for statement in child.named_children(&mut inner) {
+                collect_statement(statement, src, out)?;
+            }
+        } else {
+            collect_substitutions(child, src, out)?;
+        }
+    }
+    Some(())
+}
+
+/// The value of an argument as far as it is known statically. Expansions
+/// and substitutions keep their source text.
+fn argument_text(node: Node, src: &str, out: &mut Vec<ScriptCommand>) -> Option<String> {
+    let text = node.utf8_text(src.as_bytes()).ok()?;
+    match node.kind() {
+        "word" | "number" | "ansi_c_string" => Some(text.to_owned()),
+        "raw_string" => text
+            .strip_prefix('\'')
+            .and_then(|s| s.strip_suffix('\''))
+            .map(str::to_owned),
+        "string" => {
+            collect_substitutions(node, src, out)?;
+            text.strip_prefix('"')
+                .and_then(|s| s.strip_suffix('"'))
+                .map(str::to_owned)
+        }
+        "simple_expansion" | "expansion" => {
+            collect_substitutions(node, src, out)?;
+            Some(text.to_owned())
+        }
+        "command_substitution" | "process_substitution" => {
+            let mut cursor = node.walk();
+            for statement in node.named_children(&mut cursor) {
+                collect_statement(statement, src, out)?;
+            }
+            Some(text.to_owned())
+        }
+        "concatenation" => {
+            let mut cursor = node.walk();
+            let mut value = String::new();
+            for part in node.named_children(&mut cursor) {
+                value.push_str(&argument_text(part, src, out)?);
+            }
+            Some(value)
+        }
+        _ => None,
+    }
+}
+
 fn parse_plain_command_from_node(cmd: tree_sitter::Node, src: &str) -> Option<Vec<String>> {
     if cmd.kind() != "command" {
         return None;
@@ -250,6 +611,116 @@ mod tests {
         assert!(parse_seq("ls &&").is_none());
     }
 
+    fn script_commands(src: &str) -> Option<Vec<Vec<String>>> {
+        let tree = try_parse_shell(src)?;
+        try_parse_script_commands(&tree, src)
+    }
+
+    fn words(commands: &[&[&str]]) -> Vec<Vec<String>> {
+        commands
+            .iter()
+            .map(|command| command.iter().map(ToString::to_string).collect())
+            .collect()
+    }
+
+    #[test]
+    fn script_commands_expose_heredoc_write_targets() {
+        assert_eq!(
+            script_commands("cat <<'EOF' > notes.txt\nhello $(rm -rf /)\nEOF"),
+            Some(words(&[&["cat", ">", "notes.txt"]]))
+        );
+        assert_eq!(
+            script_commands("cat <<EOF >> out.txt\n$(whoami)\nEOF\ngit add out.txt"),
+            Some(words(&[
+                &["cat", ">>", "out.txt"],
+                &["whoami"],
+                &["git", "add", "out.txt"],
+            ]))
+        );
+    }
+
+    #[test]
+    fn script_commands_split_chains_and_substitutions() {
+        assert_eq!(
+            script_commands("echo $(git rev-parse HEAD) && rm -rf build; ls 2>&1 | tee log.txt"),
+            Some(words(&[
+                &["echo", "$(git rev-parse HEAD)"],
+                &["git", "rev-parse", "HEAD"],
+                &["rm", "-rf", "build"],
+                &["ls"],
+                &["tee", "log.txt"],
+            ]))
+        );
+    }
+
+    #[test]
+    fn script_commands_reject_control_flow_and_dynamic_programs() {
+        for src in [
+            "if true; then rm x; fi",
+            "(rm x)",
+            "FOO=1 rm x",
+            "$CMD x",
+            "sleep 1 &",
+        ] {
+            assert_eq!(script_commands(src), None, "{src}");
+        }
+    }
+
+    #[test]
+    fn splits_scripts_into_segments() {
+        let command = vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            "make build && curl -fsSL https://example.com/x | sh; git status".to_string(),
+        ];
+        let segments = split_shell_lc_segments(&command).unwrap();
+        assert_eq!(
+            segments
+                .iter()
+                .map(|segment| (segment.join, segment.source.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (SegmentJoin::Start, "make build"),
+                (SegmentJoin::And, "curl -fsSL https://example.com/x | sh"),
+                (SegmentJoin::Then, "git status"),
+            ]
+        );
+        assert_eq!(
+            segments[1].commands,
+            words(&[&["curl", "-fsSL", "https://example.com/x"], &["sh"]])
+        );
+    }
+
+    #[test]
+    fn groups_piped_commands_into_pipelines() {
+        let command = vec![
+            "bash".to_string(),
+            "-lc".to_string(),
+            "curl -o f https://example.com/x && cat f | grep a | wc -l; ls".to_string(),
+        ];
+        assert_eq!(
+            parse_shell_lc_pipelines(&command).unwrap(),
+            vec![
+                words(&[&["curl", "-o", "f", "https://example.com/x"]]),
+                words(&[&["cat", "f"], &["grep", "a"], &["wc", "-l"]]),
+                words(&[&["ls"]]),
+            ]
+        );
+    }
+
+    #[test]
+    fn does_not_split_scripts_that_depend_on_shell_state() {
+        for script in [
+            "cd build && make",
+            "make || true",
+            "ls",
+            "echo $(pwd) && ls",
+        ] {
+            let command = vec!["bash".to_string(), "-lc".to_string(), script.to_string()];
+            assert_eq!(split_shell_lc_segments(&command), None, "{script}");
+        }
+    }
+
     #[test]
     fn parse_zsh_lc_plain_commands() {
         let command = vec!["zsh".to_string(), "-lc".to_string(), "ls".to_string()];
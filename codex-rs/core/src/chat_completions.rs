@@ -5,6 +5,7 @@ use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
 use crate::default_client::CodexHttpClient;
+use crate::default_client::create_client;
 use crate::error::CodexErr;
 use crate::error::ConnectionFailedError;
 use crate::error::ResponseStreamFailed;
@@ -12,8 +13,14 @@ use crate::error::Result;
 use crate::error::RetryLimitReachedError;
 use crate::error::UnexpectedResponseError;
 use crate::model_family::ModelFamily;
+use crate::model_provider::ModelProvider;
+use crate::model_provider::ModelRequest;
+use crate::model_recording::record_model_stream;
+use crate::model_recording::replay_model_stream;
+use crate::protocol::TokenUsage;
 use crate::tools::spec::create_tools_json_for_chat_completions_api;
 use crate::util::backoff;
+use async_trait::async_trait;
 use bytes::Bytes;
 use codex_otel::otel_event_manager::OtelEventManager;
 use codex_protocol::models::ContentItem;
@@ -36,6 +43,68 @@ use tokio::time::timeout;
 use tracing::debug;
 use tracing::trace;
 
+/// How long to wait after `finish_reason` for the trailing usage chunk.
+const TRAILING_USAGE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// [`ModelProvider`] for OpenAI-compatible Chat Completions endpoints,
+/// including local servers such as Ollama and vLLM.
+#[derive(Debug, Clone)]
+pub struct ChatCompletionsProvider {
+    provider: ModelProviderInfo,
+    client: CodexHttpClient,
+    show_raw_agent_reasoning: bool,
+}
+
+impl ChatCompletionsProvider {
+    pub fn new(provider: ModelProviderInfo, show_raw_agent_reasoning: bool) -> Self {
+        Self {
+            provider,
+            client: create_client(),
+            show_raw_agent_reasoning,
+        }
+    }
+}
+
+#[async_trait]
+impl ModelProvider for ChatCompletionsProvider {
+    async fn stream(&self, request: ModelRequest<'_>) -> Result<ResponseStream> {
+        // Create the raw streaming connection first.
+        let response_stream = stream_chat_completions(
+            request.prompt,
+            request.model_family,
+            &self.client,
+            &self.provider,
+            request.otel_event_manager,
+            request.session_source,
+        )
+        .await?;
+
+        // Wrap it with the aggregation adapter so callers see *only*
+        // the final assistant message per turn (matching the
+        // behaviour of the Responses API).
+        let mut aggregated = if self.show_raw_agent_reasoning {
+            AggregatedChatStream::streaming_mode(response_stream)
+        } else {
+            response_stream.aggregate()
+        };
+
+        // Bridge the aggregated stream back into a standard
+        // `ResponseStream` by forwarding events through a channel.
+        let (tx, rx) = mpsc::channel::<Result<ResponseEvent>>(16);
+
+        tokio::spawn(async move {
+            while let Some(ev) = aggregated.next().await {
+                // Exit early if receiver hung up.
+                if tx.send(ev).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(ResponseStream { rx_event: rx })
+    }
+}
+
 /// Implementation for the classic Chat Completions API.
 pub(crate) async fn stream_chat_completions(
     prompt: &Prompt,
@@ -334,6 +403,7 @@ pub(crate) async fn stream_chat_completions(
         "model": model_family.slug,
         "messages": messages,
         "stream": true,
+        "stream_options": {"include_usage": true},
         "tools": tools_json,
     });
 
@@ -343,6 +413,17 @@ pub(crate) async fn stream_chat_completions(
         payload.to_string()
     );
 
+    if let Some(replayed) = replay_model_stream().await {
+        let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+        tokio::spawn(process_chat_sse(
+            replayed?,
+            tx_event,
+            provider.stream_idle_timeout(),
+            otel_event_manager.clone(),
+        ));
+        return Ok(ResponseStream { rx_event });
+    }
+
     let mut attempt = 0;
     let max_retries = provider.request_max_retries();
     loop {
@@ -381,6 +462,7 @@ pub(crate) async fn stream_chat_completions(
                         request_id: None,
                     })
                 });
+                let stream = record_model_stream(&provider.get_full_url(&None), &payload, stream);
                 tokio::spawn(process_chat_sse(
                     stream,
                     tx_event,
@@ -490,6 +572,37 @@ async fn append_reasoning_text(
             .await;
     }
 }
+async fn send_completed(
+    tx_event: &mpsc::Sender<Result<ResponseEvent>>,
+    token_usage: Option<TokenUsage>,
+) {
+    let _ = tx_event
+        .send(Ok(ResponseEvent::Completed {
+            response_id: String::new(),
+            token_usage,
+        }))
+        .await;
+}
+
+/// Token usage from a Chat Completions `usage` object. Providers that do not
+/// support `stream_options.include_usage` send `null` or omit it.
+fn parse_usage(usage: &serde_json::Value) -> Option<TokenUsage> {
+    let count = |value: Option<&serde_json::Value>| value.and_then(serde_json::Value::as_i64);
+    let input_tokens = count(usage.get("prompt_tokens"))?;
+    let output_tokens = count(usage.get("completion_tokens"))?;
+    Some(TokenUsage {
+        input_tokens,
+        cached_input_tokens: count(usage.pointer("/prompt_tokens_details/cached_tokens"))
+            .unwrap_or(0),
+        output_tokens,
+        reasoning_output_tokens: count(
+            usage.pointer("/completion_tokens_details/reasoning_tokens"),
+        )
+        .unwrap_or(0),
+        total_tokens: count(usage.get("total_tokens")).unwrap_or(input_tokens + output_tokens),
+    })
+}
+
 /// Lightweight SSE processor for the Chat Completions streaming format. The
 /// output is mapped onto Codex's internal [`ResponseEvent`] so that the rest
 /// of the pipeline can stay agnostic of the underlying wire format.
@@ -519,10 +632,20 @@ async fn process_chat_sse<S>(
     let mut fn_call_state = FunctionCallState::default();
     let mut assistant_item: Option<ResponseItem> = None;
     let mut reasoning_item: Option<ResponseItem> = None;
+    let mut token_usage: Option<TokenUsage> = None;
+    // Set once a choice reports its `finish_reason`. With `include_usage`,
+    // the usage arrives in a separate chunk after that, so keep reading
+    // briefly before completing.
+    let mut finished = false;
 
     loop {
         let start = std::time::Instant::now();
-        let response = timeout(idle_timeout, stream.next()).await;
+        let wait = if finished {
+            TRAILING_USAGE_TIMEOUT
+        } else {
+            idle_timeout
+        };
+        let response = timeout(wait, stream.next()).await;
         let duration = start.elapsed();
         otel_event_manager.log_sse_event(&response, duration);
 
@@ -536,12 +659,11 @@ async fn process_chat_sse<S>(
             }
             Ok(None) => {
                 // Stream closed gracefully – emit Completed with dummy id.
-                let _ = tx_event
-                    .send(Ok(ResponseEvent::Completed {
-                        response_id: String::new(),
-                        token_usage: None,
-                    }))
-                    .await;
+                send_completed(&tx_event, token_usage).await;
+                return;
+            }
+            Err(_) if finished => {
+                send_completed(&tx_event, token_usage).await;
                 return;
             }
             Err(_) => {
@@ -567,12 +689,7 @@ async fn process_chat_sse<S>(
                 let _ = tx_event.send(Ok(ResponseEvent::OutputItemDone(item))).await;
             }
 
-            let _ = tx_event
-                .send(Ok(ResponseEvent::Completed {
-                    response_id: String::new(),
-                    token_usage: None,
-                }))
-                .await;
+            send_completed(&tx_event, token_usage).await;
             return;
         }
 
@@ -583,6 +700,17 @@ async fn process_chat_sse<S>(
         };
         trace!("chat_completions received SSE chunk: {chunk:?}");
 
+        if let Some(usage) = chunk.get("usage").and_then(parse_usage) {
+            token_usage = Some(usage);
+        }
+        if finished {
+            if token_usage.is_some() {
+                send_completed(&tx_event, token_usage).await;
+                return;
+            }
+            continue;
+        }
+
         let choice_opt = chunk.get("choices").and_then(|c| c.get(0));
 
         if let Some(choice) = choice_opt {
@@ -710,18 +838,16 @@ async fn process_chat_sse<S>(
                     _ => {}
                 }
 
-                // Emit Completed regardless of reason so the agent can advance.
-                let _ = tx_event
-                    .send(Ok(ResponseEvent::Completed {
-                        response_id: String::new(),
-                        token_usage: None,
-                    }))
-                    .await;
+                assistant_item = None;
+                reasoning_item = None;
 
-                // Prepare for potential next turn (should not happen in same stream).
-                // fn_call_state = FunctionCallState::default();
-
-                return; // End processing for this SSE stream.
+                // Complete regardless of reason so the agent can advance,
+                // once the usage chunk (if any) has arrived.
+                if token_usage.is_some() {
+                    send_completed(&tx_event, token_usage).await;
+                    return;
+                }
+                finished = true;
             }
         }
     }
@@ -33,8 +33,7 @@ use tracing::warn;
 use crate::AuthManager;
 use crate::auth::CodexAuth;
 use crate::auth::RefreshTokenError;
-use crate::chat_completions::AggregateStreamExt;
-use crate::chat_completions::stream_chat_completions;
+use crate::chat_completions::ChatCompletionsProvider;
 use crate::client_common::Prompt;
 use crate::client_common::Reasoning;
 use crate::client_common::ResponseEvent;
@@ -53,8 +52,14 @@ use crate::error::UnexpectedResponseError;
 use crate::error::UsageLimitReachedError;
 use crate::flags::CODEX_RS_SSE_FIXTURE;
 use crate::model_family::ModelFamily;
+use crate::model_family::derive_default_model_family;
+use crate::model_family::find_family_for_model;
+use crate::model_provider::ModelProvider;
+use crate::model_provider::ModelRequest;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
+use crate::model_recording::record_model_stream;
+use crate::model_recording::replay_model_stream;
 use crate::openai_model_info::get_model_info;
 use crate::protocol::CreditsSnapshot;
 use crate::protocol::RateLimitSnapshot;
@@ -103,6 +108,9 @@ pub struct ModelClient {
     effort: Option<ReasoningEffortConfig>,
     summary: ReasoningSummaryConfig,
     session_source: SessionSource,
+    /// Backend for non-Responses providers; `None` streams from the
+    /// Responses API directly.
+    model_provider: Option<Arc<dyn ModelProvider>>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -118,6 +126,13 @@ impl ModelClient {
         session_source: SessionSource,
     ) -> Self {
         let client = create_client();
+        let model_provider: Option<Arc<dyn ModelProvider>> = match provider.wire_api {
+            WireApi::Responses => None,
+            WireApi::Chat => Some(Arc::new(ChatCompletionsProvider::new(
+                provider.clone(),
+                config.show_raw_agent_reasoning,
+            ))),
+        };
 
         Self {
             config,
@@ -129,6 +144,7 @@ impl ModelClient {
             effort,
             summary,
             session_source,
+            model_provider,
         }
     }
 
@@ -141,9 +157,18 @@ impl ModelClient {
     }
 
     pub fn get_auto_compact_token_limit(&self) -> Option<i64> {
-        self.config.model_auto_compact_token_limit.or_else(|| {
+        let token_limit = self.config.model_auto_compact_token_limit.or_else(|| {
             get_model_info(&self.config.model_family).and_then(|info| info.auto_compact_token_limit)
-        })
+        });
+        let context_limit = self
+            .config
+            .model_auto_compact_context_percent
+            .zip(self.get_model_context_window())
+            .map(|(percent, window)| window.saturating_mul(i64::from(percent.min(100))) / 100);
+        match (token_limit, context_limit) {
+            (Some(token_limit), Some(context_limit)) => Some(token_limit.min(context_limit)),
+            (token_limit, context_limit) => token_limit.or(context_limit),
+        }
     }
 
     pub fn config(&self) -> Arc<Config> {
@@ -154,46 +179,26 @@ impl ModelClient {
         &self.provider
     }
 
-    pub async fn stream(&self, prompt: &Prompt) -> Result<ResponseStream> {
-        match self.provider.wire_api {
-            WireApi::Responses => self.stream_responses(prompt).await,
-            WireApi::Chat => {
-                // Create the raw streaming connection first.
-                let response_stream = stream_chat_completions(
-                    prompt,
-                    &self.config.model_family,
-                    &self.client,
-                    &self.provider,
-                    &self.otel_event_manager,
-                    &self.session_source,
-                )
-                .await?;
-
-                // Wrap it with the aggregation adapter so callers see *only*
-                // the final assistant message per turn (matching the
-                // behaviour of the Responses API).
-                let mut aggregated = if self.config.show_raw_agent_reasoning {
-                    crate::chat_completions::AggregatedChatStream::streaming_mode(response_stream)
-                } else {
-                    response_stream.aggregate()
-                };
-
-                // Bridge the aggregated stream back into a standard
-                // `ResponseStream` by forwarding events through a channel.
-                let (tx, rx) = mpsc::channel::<Result<ResponseEvent>>(16);
-
-                tokio::spawn(async move {
-                    use futures::StreamExt;
-                    while let Some(ev) = aggregated.next().await {
-                        // Exit early if receiver hung up.
-                        if tx.send(ev).await.is_err() {
-                            break;
-                        }
-                    }
-                });
+    /// Replace the backend chosen from the provider's `wire_api` with a
+    /// custom [`ModelProvider`].
+    pub fn with_model_provider(mut self, model_provider: Arc<dyn ModelProvider>) -> Self {
+        self.model_provider = Some(model_provider);
+        self
+    }
 
-                Ok(ResponseStream { rx_event: rx })
+    pub async fn stream(&self, prompt: &Prompt) -> Result<ResponseStream> {
+        match &self.model_provider {
+            Some(model_provider) => {
+                model_provider
+                    .stream(ModelRequest {
+                        prompt,
+                        model_family: &self.config.model_family,
+                        otel_event_manager: &self.otel_event_manager,
+                        session_source: &self.session_source,
+                    })
+                    .await
             }
+            None => self.stream_responses(prompt).await,
         }
     }
 
@@ -280,6 +285,17 @@ impl ModelClient {
             attach_item_ids(&mut payload_json, &input_with_instructions);
         }
 
+        if let Some(replayed) = replay_model_stream().await {
+            let (tx_event, rx_event) = mpsc::channel::<Result<ResponseEvent>>(1600);
+            tokio::spawn(process_sse(
+                replayed?,
+                tx_event,
+                self.provider.stream_idle_timeout(),
+                self.otel_event_manager.clone(),
+            ));
+            return Ok(ResponseStream { rx_event });
+        }
+
         let max_attempts = self.provider.request_max_retries();
         for attempt in 0..=max_attempts {
             match self
@@ -387,6 +403,8 @@ impl ModelClient {
                         request_id: request_id.clone(),
                     })
                 });
+                let stream =
+                    record_model_stream(&self.provider.get_full_url(&auth), payload_json, stream);
                 tokio::spawn(process_sse(
                     stream,
                     tx_event,
@@ -498,6 +516,24 @@ impl ModelClient {
         self.session_source.clone()
     }
 
+    /// A client for `model` that shares this client's provider, auth, and
+    /// reasoning settings. The context window is the model's own, not one
+    /// configured for the main model.
+    pub fn for_model(&self, model: &str) -> Self {
+        let mut config = (*self.config).clone();
+        config.model = model.to_string();
+        config.model_family =
+            find_family_for_model(model).unwrap_or_else(|| derive_default_model_family(model));
+        config.model_context_window =
+            get_model_info(&config.model_family).map(|info| info.context_window);
+        let mut client = self.clone();
+        client.otel_event_manager = self
+            .otel_event_manager
+            .with_model(model, config.model_family.slug.as_str());
+        client.config = Arc::new(config);
+        client
+    }
+
     /// Returns the currently configured model slug.
     pub fn get_model(&self) -> String {
         self.config.model.clone()
@@ -3,6 +3,7 @@ use crate::error::Result;
 use crate::model_family::ModelFamily;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::TokenUsage;
+use crate::tool_filter::ToolFilter;
 use codex_apply_patch::APPLY_PATCH_TOOL_INSTRUCTIONS;
 use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
@@ -46,6 +47,9 @@ pub struct Prompt {
 
     /// Optional the output schema for the model's response.
     pub output_schema: Option<Value>,
+
+    /// Tools the user withheld; described in the instructions.
+    pub(crate) tool_filter: ToolFilter,
 }
 
 impl Prompt {
@@ -63,13 +67,18 @@ impl Prompt {
             ToolSpec::Freeform(f) => f.name == "apply_patch",
             _ => false,
         });
-        if self.base_instructions_override.is_none()
+        let instructions = if self.base_instructions_override.is_none()
             && model.needs_special_apply_patch_instructions
             && !is_apply_patch_tool_present
+            && self.tool_filter.allows_builtin("apply_patch")
         {
             Cow::Owned(format!("{base}\n{APPLY_PATCH_TOOL_INSTRUCTIONS}"))
         } else {
             Cow::Borrowed(base)
+        };
+        match self.tool_filter.instructions() {
+            Some(restriction) => Cow::Owned(format!("{instructions}\n\n{restriction}")),
+            None => instructions,
         }
     }
 
@@ -364,6 +373,14 @@ pub struct ResponseStream {
     pub(crate) rx_event: mpsc::Receiver<Result<ResponseEvent>>,
 }
 
+impl ResponseStream {
+    /// Wrap the receiving end of a channel that a [`crate::model_provider::ModelProvider`]
+    /// feeds with events.
+    pub fn new(rx_event: mpsc::Receiver<Result<ResponseEvent>>) -> Self {
+        Self { rx_event }
+    }
+}
+
 impl Stream for ResponseStream {
     type Item = Result<ResponseEvent>;
 
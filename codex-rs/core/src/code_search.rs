@@ -0,0 +1,584 @@
+//! Embeddings index behind the `code_search` tool.
+//!
+//! Every tracked or unignored text file of the repository is split into
+//! chunks of `code_search.chunk_lines` lines, and each chunk is embedded with
+//! the configured provider's `/embeddings` endpoint. A query is embedded the
+//! same way and answered with the chunks closest to it by cosine similarity,
+//! which finds code by what it does ("where are retries handled?") rather
+//! than by name.
+//!
+//! The index is stored per repository under `CODEX_HOME/index/` and kept in
+//! memory for the session. Before every search the repository is listed
+//! again and only files whose size or modification time changed are
+//! re-embedded, so edits made during the session, by the model or anyone
+//! else, are picked up without re-indexing everything.
+
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use tokio::sync::Mutex;
+
+use crate::auth::CodexAuth;
+use crate::codex::TurnContext;
+use crate::config::types::CodeSearchConfig;
+use crate::default_client::create_client;
+use crate::model_provider_info::ModelProviderInfo;
+use crate::repo_map::git;
+
+const INDEX_DIR: &str = "index";
+/// Chunks sent per `/embeddings` request.
+const EMBED_BATCH: usize = 64;
+/// Longer chunks (minified files, generated tables) are cut before
+/// embedding to stay under the model's input limit.
+const MAX_CHUNK_CHARS: usize = 6_000;
+/// Lines of each hit shown in the tool output.
+const SNIPPET_LINES: usize = 15;
+
+/// Turns text into vectors whose cosine similarity reflects how related the
+/// texts are.
+#[async_trait]
+pub(crate) trait Embedder: Send + Sync {
+    /// Names the model; vectors from different embedders are not compared.
+    fn id(&self) -> String;
+
+    /// One vector per input, in order.
+    async fn embed(&self, inputs: Vec<String>) -> Result<Vec<Vec<f32>>, String>;
+}
+
+/// An [`Embedder`] backed by a model provider's `/embeddings` endpoint.
+pub(crate) struct ProviderEmbedder {
+    provider: ModelProviderInfo,
+    auth: Option<CodexAuth>,
+    model: String,
+}
+
+#[derive(Serialize)]
+struct EmbeddingsRequest<'a> {
+    model: &'a str,
+    input: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl ProviderEmbedder {
+    /// The provider and model configured under `code_search`, falling back to
+    /// the turn's model provider.
+    pub(crate) fn for_turn(turn_context: &TurnContext) -> Result<Self, String> {
+        let config = turn_context.client.config();
+        let settings = &config.code_search;
+        let provider = match settings.model_provider.as_deref() {
+            Some(id) => config.model_providers.get(id).cloned().ok_or_else(|| {
+                format!("code_search.model_provider `{id}` is not in model_providers")
+            })?,
+            None => turn_context.client.provider().clone(),
+        };
+        let auth = turn_context
+            .client
+            .get_auth_manager()
+            .and_then(|manager| manager.auth());
+        Ok(Self {
+            provider,
+            auth,
+            model: settings.model.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl Embedder for ProviderEmbedder {
+    fn id(&self) -> String {
+        format!("{}/{}", self.provider.name, self.model)
+    }
+
+    async fn embed(&self, inputs: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
+        let expected = inputs.len();
+        let client = create_client();
+        let request = self
+            .provider
+            .create_embeddings_request_builder(&client, &self.auth)
+            .await
+            .map_err(|err| err.to_string())?;
+        let response = request
+            .json(&EmbeddingsRequest {
+                model: &self.model,
+                input: inputs,
+            })
+            .send()
+            .await
+            .map_err(|err| format!("embeddings request failed: {err}"))?;
+        let status = response.status();
+        let body = response
+            .text()
+            .await
+            .map_err(|err| format!("embeddings request failed: {err}"))?;
+        if !status.is_success() {
+            return Err(format!("embeddings request failed with {status}: {body}"));
+        }
+        let EmbeddingsResponse { mut data } = serde_json::from_str(&body)
+            .map_err(|err| format!("unexpected embeddings response: {err}"))?;
+        if data.len() != expected {
+            return Err(format!(
+                "expected {expected} embeddings, the provider returned {}",
+                data.len()
+            ));
+        }
+        data.sort_by_key(|item| item.index);
+        Ok(data.into_iter().map(|item| item.embedding).collect())
+    }
+}
+
+/// A chunk of a file that matched a query.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SearchHit {
+    pub(crate) path: PathBuf,
+    pub(crate) start_line: usize,
+    pub(crate) end_line: usize,
+    pub(crate) score: f32,
+}
+
+/// The session's index, loaded from disk on first use.
+#[derive(Default)]
+pub(crate) struct CodeSearchIndex {
+    index: Mutex<Option<Index>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Index {
+    root: PathBuf,
+    embedder: String,
+    chunk_lines: usize,
+    /// Keyed by path relative to `root`.
+    files: BTreeMap<String, IndexedFile>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexedFile {
+    len: u64,
+    modified_ns: u64,
+    chunks: Vec<IndexedChunk>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IndexedChunk {
+    start_line: usize,
+    end_line: usize,
+    embedding: Vec<f32>,
+}
+
+/// A chunk waiting to be embedded.
+struct ChunkText {
+    start_line: usize,
+    end_line: usize,
+    text: String,
+}
+
+struct PendingFile {
+    path: String,
+    len: u64,
+    modified_ns: u64,
+    chunks: Vec<ChunkText>,
+}
+
+impl CodeSearchIndex {
+    /// Bring the index of the repository containing `cwd` up to date and
+    /// return the `limit` chunks closest to `query`, best first.
+    pub(crate) async fn search(
+        &self,
+        codex_home: &Path,
+        cwd: &Path,
+        config: &CodeSearchConfig,
+        embedder: &dyn Embedder,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>, String> {
+        let not_a_repo = || "the working directory is not inside a git repository".to_string();
+        let root = git(cwd, &["rev-parse", "--show-toplevel"])
+            .await
+            .ok_or_else(not_a_repo)?;
+        let root = PathBuf::from(root.trim());
+        let files = git(
+            &root,
+            &["ls-files", "--cached", "--others", "--exclude-standard"],
+        )
+        .await
+        .ok_or_else(not_a_repo)?;
+        let files = files.lines().map(str::to_string).collect::<Vec<_>>();
+
+        let mut cached = self.index.lock().await;
+        let index_path = index_path(codex_home, &root);
+        let mut index = match cached.take() {
+            Some(index) if index.root == root => index,
+            _ => load_index(&index_path).await.unwrap_or_else(|| Index {
+                root: root.clone(),
+                embedder: String::new(),
+                chunk_lines: 0,
+                files: BTreeMap::new(),
+            }),
+        };
+        if index.embedder != embedder.id() || index.chunk_lines != config.chunk_lines {
+            index.embedder = embedder.id();
+            index.chunk_lines = config.chunk_lines;
+            index.files.clear();
+        }
+
+        let updated = update_index(&mut index, &files, config, embedder).await;
+        if !matches!(updated, Ok(false)) {
+            save_index(&index_path, &index).await;
+        }
+        let ranked = async {
+            updated?;
+            let query = embedder
+                .embed(vec![query.to_string()])
+                .await?
+                .pop()
+                .ok_or_else(|| "the provider returned no embedding for the query".to_string())?;
+            Ok::<_, String>(rank(&index, &query, limit))
+        }
+        .await;
+        *cached = Some(index);
+        ranked
+    }
+}
+
+/// Re-embed the files in `files` (relative to the index root) that are new
+/// or changed, and drop the ones that are gone. Files embedded before an
+/// error stay in the index. Returns whether anything changed.
+async fn update_index(
+    index: &mut Index,
+    files: &[String],
+    config: &CodeSearchConfig,
+    embedder: &dyn Embedder,
+) -> Result<bool, String> {
+    let listed = files.iter().map(String::as_str).collect::<HashSet<_>>();
+    let before = index.files.len();
+    index.files.retain(|path, _| listed.contains(path.as_str()));
+    let mut changed = index.files.len() != before;
+
+    let mut batch = Vec::new();
+    let mut batch_chunks = 0;
+    for path in files {
+        let Some(pending) = pending_file(index, path, config).await else {
+            changed |= index.files.remove(path).is_some();
+            continue;
+        };
+        if pending.chunks.is_empty() {
+            // Unchanged since it was last embedded.
+            continue;
+        }
+        batch_chunks += pending.chunks.len();
+        batch.push(pending);
+        if batch_chunks >= EMBED_BATCH {
+            embed_files(index, std::mem::take(&mut batch), embedder).await?;
+            batch_chunks = 0;
+            changed = true;
+        }
+    }
+    if !batch.is_empty() {
+        embed_files(index, batch, embedder).await?;
+        changed = true;
+    }
+    Ok(changed)
+}
+
+/// The chunks of `path` to embed: empty when the indexed copy is current,
+/// `None` when the file should not be indexed at all.
+async fn pending_file(index: &Index, path: &str, config: &CodeSearchConfig) -> Option<PendingFile> {
+    let full_path = index.root.join(path);
+    let metadata = tokio::fs::metadata(&full_path).await.ok()?;
+    if !metadata.is_file() || metadata.len() > config.max_file_bytes {
+        return None;
+    }
+    let modified_ns = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |modified| {
+            u64::try_from(modified.as_nanos()).unwrap_or(u64::MAX)
+        });
+    let mut pending = PendingFile {
+        path: path.to_string(),
+        len: metadata.len(),
+        modified_ns,
+        chunks: Vec::new(),
+    };
+    if let Some(indexed) = index.files.get(path)
+        && indexed.len == pending.len
+        && indexed.modified_ns == pending.modified_ns
+    {
+        return Some(pending);
+    }
+    let bytes = tokio::fs::read(&full_path).await.ok()?;
+    if bytes.contains(&0) {
+        return None;
+    }
+    let text = String::from_utf8(bytes).ok()?;
+    pending.chunks = chunk_text(path, &text, config.chunk_lines);
+    if pending.chunks.is_empty() {
+        return None;
+    }
+    Some(pending)
+}
+
+async fn embed_files(
+    index: &mut Index,
+    files: Vec<PendingFile>,
+    embedder: &dyn Embedder,
+) -> Result<(), String> {
+    let texts = files
+        .iter()
+        .flat_map(|file| file.chunks.iter().map(|chunk| chunk.text.clone()))
+        .collect::<Vec<_>>();
+    let mut embeddings = Vec::with_capacity(texts.len());
+    for inputs in texts.chunks(EMBED_BATCH) {
+        let batch = embedder.embed(inputs.to_vec()).await?;
+        if batch.len() != inputs.len() {
+            return Err(format!(
+                "expected {} embeddings, got {}",
+                inputs.len(),
+                batch.len()
+            ));
+        }
+        embeddings.extend(batch);
+    }
+    let mut embeddings = embeddings.into_iter();
+    for file in files {
+        let chunks = file
+            .chunks
+            .into_iter()
+            .zip(embeddings.by_ref())
+            .map(|(chunk, embedding)| IndexedChunk {
+                start_line: chunk.start_line,
+                end_line: chunk.end_line,
+                embedding,
+            })
+            .collect();
+        index.files.insert(
+            file.path,
+            IndexedFile {
+                len: file.len,
+                modified_ns: file.modified_ns,
+                chunks,
+            },
+        );
+    }
+    Ok(())
+}
+
+/// Split `text` into chunks of `chunk_lines` lines, skipping blank ones.
+/// Each chunk is prefixed with its path, which often says as much about the
+/// code as the code itself.
+fn chunk_text(path: &str, text: &str, chunk_lines: usize) -> Vec<ChunkText> {
+    let lines = text.lines().collect::<Vec<_>>();
+    lines
+        .chunks(chunk_lines.max(1))
+        .enumerate()
+        .filter(|(_, lines)| lines.iter().any(|line| !line.trim().is_empty()))
+        .map(|(i, lines)| {
+            let start_line = i * chunk_lines.max(1) + 1;
+            let mut text = format!("{path}\n{}", lines.join("\n"));
+            if text.len() > MAX_CHUNK_CHARS {
+                let mut end = MAX_CHUNK_CHARS;
+                while !text.is_char_boundary(end) {
+                    end -= 1;
+                }
+                text.truncate(end);
+            }
+            ChunkText {
+                start_line,
+                end_line: start_line + lines.len() - 1,
+                text,
+            }
+        })
+        .collect()
+}
+
+fn rank(index: &Index, query: &[f32], limit: usize) -> Vec<SearchHit> {
+    let mut hits = index
+        .files
+        .iter()
+        .flat_map(|(path, file)| {
+            file.chunks.iter().map(move |chunk| SearchHit {
+                path: index.root.join(path),
+                start_line: chunk.start_line,
+                end_line: chunk.end_line,
+                score: cosine_similarity(query, &chunk.embedding),
+            })
+        })
+        .collect::<Vec<_>>();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(limit);
+    hits
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot = a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+/// Hits as `path:start-end (score)` headers followed by the first lines of
+/// each chunk, with paths relative to `cwd` where possible.
+pub(crate) async fn render_hits(hits: &[SearchHit], cwd: &Path) -> String {
+    let mut sections = Vec::with_capacity(hits.len());
+    for hit in hits {
+        let path = hit.path.strip_prefix(cwd).unwrap_or(&hit.path);
+        let mut section = format!(
+            "{}:{}-{} (score {:.2})",
+            path.display(),
+            hit.start_line,
+            hit.end_line,
+            hit.score
+        );
+        if let Ok(text) = tokio::fs::read_to_string(&hit.path).await {
+            let shown = (hit.end_line + 1 - hit.start_line).min(SNIPPET_LINES);
+            for line in text.lines().skip(hit.start_line - 1).take(shown) {
+                section.push('\n');
+                section.push_str(line);
+            }
+            if shown < hit.end_line + 1 - hit.start_line {
+                section.push_str("\n...");
+            }
+        }
+        sections.push(section);
+    }
+    sections.join("\n\n")
+}
+
+fn index_path(codex_home: &Path, root: &Path) -> PathBuf {
+    let digest = Sha256::digest(root.to_string_lossy().as_bytes());
+    codex_home.join(INDEX_DIR).join(format!("{digest:x}.json"))
+}
+
+async fn load_index(path: &Path) -> Option<Index> {
+    let bytes = tokio::fs::read(path).await.ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+async fn save_index(path: &Path, index: &Index) {
+    let result = async {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let bytes = serde_json::to_vec(index)?;
+        tokio::fs::write(path, bytes).await
+    }
+    .await;
+    if let Err(err) = result {
+        tracing::warn!("failed to save code search index {}: {err}", path.display());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use tempfile::tempdir;
+
+    /// Embeds text as counts of a few keywords, and counts the chunks it was
+    /// asked to embed.
+    #[derive(Default)]
+    struct KeywordEmbedder {
+        embedded: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Embedder for KeywordEmbedder {
+        fn id(&self) -> String {
+            "keywords".to_string()
+        }
+
+        async fn embed(&self, inputs: Vec<String>) -> Result<Vec<Vec<f32>>, String> {
+            self.embedded.fetch_add(inputs.len(), Ordering::SeqCst);
+            Ok(inputs
+                .iter()
+                .map(|text| {
+                    ["retry", "parse", "render"]
+                        .iter()
+                        .map(|word| text.matches(word).count() as f32)
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn chunks_skip_blank_runs_and_carry_their_path() {
+        let chunks = chunk_text("src/a.rs", "fn a() {}\n\n\n\nfn b() {}", 2);
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|chunk| (chunk.start_line, chunk.end_line, chunk.text.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (1, 2, "src/a.rs\nfn a() {}\n"),
+                (5, 5, "src/a.rs\nfn b() {}"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn ranks_by_meaning_and_reembeds_only_changed_files() {
+        let root = tempdir().expect("tempdir");
+        std::fs::write(root.path().join("net.rs"), "fn retry() { retry(); }").expect("write");
+        std::fs::write(root.path().join("parser.rs"), "fn parse() {}").expect("write");
+        let files = vec!["net.rs".to_string(), "parser.rs".to_string()];
+        let config = CodeSearchConfig::default();
+        let embedder = KeywordEmbedder::default();
+        let mut index = Index {
+            root: root.path().to_path_buf(),
+            embedder: embedder.id(),
+            chunk_lines: config.chunk_lines,
+            files: BTreeMap::new(),
+        };
+
+        assert!(
+            update_index(&mut index, &files, &config, &embedder)
+                .await
+                .expect("index")
+        );
+        assert_eq!(embedder.embedded.load(Ordering::SeqCst), 2);
+        let hits = rank(&index, &[1.0, 0.0, 0.0], 1);
+        assert_eq!(hits[0].path, root.path().join("net.rs"));
+
+        assert!(
+            !update_index(&mut index, &files, &config, &embedder)
+                .await
+                .expect("index")
+        );
+        assert_eq!(embedder.embedded.load(Ordering::SeqCst), 2);
+
+        std::fs::write(root.path().join("parser.rs"), "fn parse() { render() }\n").expect("write");
+        let files = vec!["parser.rs".to_string()];
+        assert!(
+            update_index(&mut index, &files, &config, &embedder)
+                .await
+                .expect("index")
+        );
+        assert_eq!(embedder.embedded.load(Ordering::SeqCst), 3);
+        assert_eq!(index.files.keys().collect::<Vec<_>>(), vec!["parser.rs"]);
+    }
+}
@@ -1,19 +1,39 @@
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::sync::PoisonError;
+use std::sync::RwLock as StdRwLock;
 use std::sync::atomic::AtomicU64;
 
 use crate::AuthManager;
+use crate::apply_patch::defer_large_patch_changes;
+use crate::approval_rules::ApprovalRuleStore;
+use crate::approval_rules::proposed_rules;
+use crate::background_tasks::BackgroundTaskManager;
 use crate::client_common::REVIEW_PROMPT;
 use crate::compact;
 use crate::compact::run_inline_auto_compact_task;
 use crate::compact::should_use_remote_compact_task;
 use crate::compact_remote::run_inline_remote_auto_compact_task;
+use crate::exec_log;
+use crate::experiments;
 use crate::features::Feature;
+use crate::file_locks::FileLockError;
+use crate::file_locks::FileLockGuard;
+use crate::file_locks::FileLockManager;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::HookPayload;
+use crate::hooks::run_hooks;
 use crate::parse_command::parse_command;
+use crate::parse_command::shlex_join;
 use crate::parse_turn_item;
+use crate::pinned_context::pinned_context_budget;
+use crate::pinned_context::pinned_context_item;
+use crate::plugins::PluginHost;
 use crate::response_processing::process_items;
 use crate::terminal;
 use crate::truncate::TruncationPolicy;
@@ -21,12 +41,20 @@ use crate::user_notification::UserNotifier;
 use crate::util::error_or_panic;
 use async_channel::Receiver;
 use async_channel::Sender;
+use chrono::Utc;
 use codex_protocol::ConversationId;
+use codex_protocol::items::BlockedAction;
+use codex_protocol::items::BlockedActionItem;
+use codex_protocol::items::CommandExecutionItem;
+use codex_protocol::items::CommandExecutionStatus;
+use codex_protocol::items::CommandOutputTruncation;
 use codex_protocol::items::TurnItem;
+use codex_protocol::protocol::ExecOutputArchiveItem;
 use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::HasLegacyEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
 use codex_protocol::protocol::ItemStartedEvent;
+use codex_protocol::protocol::ModelTask;
 use codex_protocol::protocol::RawResponseItemEvent;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::RolloutItem;
@@ -34,6 +62,8 @@ use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::TaskStartedEvent;
 use codex_protocol::protocol::TurnAbortReason;
 use codex_protocol::protocol::TurnContextItem;
+use codex_rmcp_client::McpProgress;
+use codex_utils_pty::TerminalSize;
 use futures::future::BoxFuture;
 use futures::prelude::*;
 use futures::stream::FuturesOrdered;
@@ -46,58 +76,117 @@ use mcp_types::ReadResourceRequestParams;
 use mcp_types::ReadResourceResult;
 use serde_json;
 use serde_json::Value;
+use std::time::Duration;
+use std::time::Instant;
+
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::oneshot;
 use tokio_util::sync::CancellationToken;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
 use tracing::warn;
+use uuid::Uuid;
 
 use crate::ModelProviderInfo;
 use crate::client::ModelClient;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
+use crate::code_search::CodeSearchIndex;
 use crate::compact::collect_user_messages;
 use crate::config::Config;
+use crate::config::ConfigOverrides;
+use crate::config::load_config_as_toml_with_cli_overrides;
+use crate::config::resolve_workspace_roots;
+use crate::config::types::ReasoningRedaction;
 use crate::config::types::ShellEnvironmentPolicy;
+use crate::config::types::ShellEnvironmentPolicyInherit;
 use crate::context_manager::ContextManager;
+use crate::cost::estimate_cost_usd;
 use crate::environment_context::EnvironmentContext;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
 use crate::error::http_status_code_value;
+use crate::exec::ExecToolCallOutput;
 #[cfg(test)]
 use crate::exec::StreamOutput;
+use crate::exec_env;
+use crate::lsp::LspManager;
 use crate::mcp::auth::compute_auth_statuses;
+use crate::mcp::sampling::SamplingModel;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::model_family::find_family_for_model;
+use crate::model_router::RoutedClient;
+use crate::network_proxy::NetworkProxy;
 use crate::openai_model_info::get_model_info;
 use crate::project_doc::get_user_instructions;
+use crate::project_doc::load_user_instructions;
 use crate::protocol::AgentMessageContentDeltaEvent;
 use crate::protocol::AgentReasoningSectionBreakEvent;
 use crate::protocol::ApplyPatchApprovalRequestEvent;
 use crate::protocol::AskForApproval;
 use crate::protocol::BackgroundEventEvent;
+use crate::protocol::BudgetExhaustedEvent;
+use crate::protocol::CodexErrorInfo;
 use crate::protocol::DeprecationNoticeEvent;
+use crate::protocol::ErrorCode;
+use crate::protocol::ErrorEvent;
+use crate::protocol::ErrorOrigin;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecApprovalRequestEvent;
+use crate::protocol::ExecCommandBeginEvent;
+use crate::protocol::ExecCommandEndEvent;
+use crate::protocol::FileLockWaitEvent;
+use crate::protocol::InstructionsChangedEvent;
 use crate::protocol::Op;
+use crate::protocol::PatchFileStatus;
+use crate::protocol::ProfileChangedEvent;
+use crate::protocol::ProviderHealthChangedEvent;
+use crate::protocol::ProviderHealthStatus;
 use crate::protocol::RateLimitSnapshot;
+use crate::protocol::RateLimitedEvent;
 use crate::protocol::ReasoningContentDeltaEvent;
 use crate::protocol::ReasoningRawContentDeltaEvent;
+use crate::protocol::RequestTokenUsage;
 use crate::protocol::ReviewDecision;
 use crate::protocol::SandboxCommandAssessment;
 use crate::protocol::SandboxPolicy;
+use crate::protocol::SecretsRedactedEvent;
 use crate::protocol::SessionConfiguredEvent;
+use crate::protocol::SettingChange;
 use crate::protocol::StreamErrorEvent;
 use crate::protocol::Submission;
+use crate::protocol::ThreadMetadataChangedEvent;
 use crate::protocol::TokenCountEvent;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
+use crate::protocol::TurnActivity;
+use crate::protocol::TurnCommit;
 use crate::protocol::TurnDiffEvent;
+use crate::protocol::TurnDiffFile;
+use crate::protocol::TurnDiffSummary;
+use crate::protocol::TurnHeartbeatEvent;
+use crate::protocol::UsageAttribution;
+use crate::protocol::UserInputRequestEvent;
 use crate::protocol::WarningEvent;
+use crate::protocol::WorktreeClosedEvent;
+use crate::protocol::WorktreeDiffEvent;
+use crate::protocol::WorktreeOutcome;
+use crate::protocol::WriteScopeApprovalRequestEvent;
+use crate::protocol::WriteScopeDecision;
+use crate::provider_health::ProviderHealth;
+use crate::pull_request::PullRequestRequest;
+use crate::rate_limit_scheduler::RateLimitKey;
+use crate::rate_limit_scheduler::RateLimitScheduler;
+use crate::reasoning_redaction::redact_event;
+use crate::reasoning_redaction::redact_rollout_item;
+use crate::redaction::RedactionCounts;
+use crate::redaction::redacted_secret_counts;
+use crate::repo_map::RepoMapCache;
+use crate::repo_map::repo_map_item;
 use crate::rollout::RolloutRecorder;
 use crate::rollout::RolloutRecorderParams;
 use crate::shell;
@@ -108,20 +197,29 @@ use crate::tasks::GhostSnapshotTask;
 use crate::tasks::ReviewTask;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
+use crate::thread_metadata;
+use crate::thread_metadata::ThreadMetadata;
+use crate::thread_metadata::normalize_title;
+use crate::thread_metadata::read_thread_metadata;
+use crate::thread_metadata::write_thread_metadata;
 use crate::tools::ToolRouter;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::parallel::ToolCallRuntime;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::tools::spec::ToolsConfig;
 use crate::tools::spec::ToolsConfigParams;
+use crate::turn_commit;
 use crate::turn_diff_tracker::TurnDiffTracker;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_instructions::DeveloperInstructions;
 use crate::user_instructions::UserInstructions;
 use crate::user_notification::UserNotification;
 use crate::util::backoff;
+use crate::worktree::SessionWorktree;
+use crate::write_grants::WriteGrantStore;
 use codex_async_utils::OrCancelExt;
 use codex_execpolicy2::Policy as ExecPolicy;
+use codex_git::diff_stat_against_commit;
 use codex_otel::otel_event_manager::OtelEventManager;
 use codex_protocol::config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
@@ -134,7 +232,6 @@ use codex_protocol::user_input::UserInput;
 use codex_utils_readiness::Readiness;
 use codex_utils_readiness::ReadinessFlag;
 use codex_utils_tokenizer::warm_model_cache;
-use reqwest::StatusCode;
 
 /// The high-level interface to the Codex system.
 /// It operates as a queue pair where you send submissions and receive events.
@@ -172,6 +269,15 @@ impl Codex {
             .await
             .map_err(|err| CodexErr::Fatal(format!("failed to load execpolicy: {err}")))?;
 
+        // A conversation configured for a different stored account than the
+        // process is using gets an auth manager of its own.
+        let auth_manager = match config.account.as_deref() {
+            Some(account) if auth_manager.account().as_deref() != Some(account) => {
+                auth_manager.for_account(Some(account.to_string()))
+            }
+            _ => auth_manager,
+        };
+
         let config = Arc::new(config);
 
         let session_configuration = SessionConfiguration {
@@ -186,10 +292,14 @@ impl Codex {
             approval_policy: config.approval_policy,
             sandbox_policy: config.sandbox_policy.clone(),
             cwd: config.cwd.clone(),
+            workspace_roots: config.workspace_roots.clone(),
+            token_budget: config.conversation_token_budget,
+            environment: HashMap::new(),
             original_config_do_not_use: Arc::clone(&config),
             features: config.features.clone(),
             exec_policy,
             session_source,
+            remote_enforced_keys: config.remote_enforced_keys.clone(),
         };
 
         // Generate a unique ID for the lifetime of this Codex session.
@@ -264,6 +374,10 @@ pub(crate) struct Session {
     pub(crate) active_turn: Mutex<Option<ActiveTurn>>,
     pub(crate) services: SessionServices,
     next_internal_sub_id: AtomicU64,
+    /// What the running turn is doing, as reported by heartbeats.
+    turn_activity: StdMutex<TurnActivity>,
+    /// Item ids of the commands that are running, by call id.
+    command_items: StdMutex<HashMap<String, String>>,
 }
 
 /// The context needed for a single turn of the conversation.
@@ -275,6 +389,9 @@ pub(crate) struct TurnContext {
     /// the model as well as sandbox policies are resolved against this path
     /// instead of `std::env::current_dir()`.
     pub(crate) cwd: PathBuf,
+    /// Additional workspace folders besides `cwd`. They are writable under
+    /// `workspace-write` and may be used as the `workdir` of tool calls.
+    pub(crate) workspace_roots: Vec<PathBuf>,
     pub(crate) developer_instructions: Option<String>,
     pub(crate) base_instructions: Option<String>,
     pub(crate) compact_prompt: Option<String>,
@@ -291,12 +408,58 @@ pub(crate) struct TurnContext {
 }
 
 impl TurnContext {
+    /// The same turn, sampled through `client` instead.
+    pub(crate) fn with_client(&self, client: ModelClient) -> Self {
+        Self {
+            sub_id: self.sub_id.clone(),
+            client,
+            cwd: self.cwd.clone(),
+            workspace_roots: self.workspace_roots.clone(),
+            developer_instructions: self.developer_instructions.clone(),
+            base_instructions: self.base_instructions.clone(),
+            compact_prompt: self.compact_prompt.clone(),
+            user_instructions: self.user_instructions.clone(),
+            approval_policy: self.approval_policy,
+            sandbox_policy: self.sandbox_policy.clone(),
+            shell_environment_policy: self.shell_environment_policy.clone(),
+            tools_config: self.tools_config.clone(),
+            final_output_json_schema: self.final_output_json_schema.clone(),
+            codex_linux_sandbox_exe: self.codex_linux_sandbox_exe.clone(),
+            tool_call_gate: Arc::clone(&self.tool_call_gate),
+            exec_policy: Arc::clone(&self.exec_policy),
+            truncation_policy: self.truncation_policy,
+        }
+    }
+
     pub(crate) fn resolve_path(&self, path: Option<String>) -> PathBuf {
         path.as_ref()
             .map(PathBuf::from)
             .map_or_else(|| self.cwd.clone(), |p| self.cwd.join(p))
     }
 
+    /// Checks the directory a command asked to run in. It has to exist, and
+    /// under `workspace-write` it has to lie inside one of the turn's writable
+    /// roots unless the command runs with escalated permissions.
+    pub(crate) fn check_workdir(&self, workdir: &Path, escalated: bool) -> Result<(), String> {
+        if !workdir.is_dir() {
+            return Err(format!(
+                "workdir {} does not exist or is not a directory",
+                workdir.display()
+            ));
+        }
+        if escalated || within_writable_roots(&self.sandbox_policy, &self.cwd, workdir) {
+            return Ok(());
+        }
+        let mut message = format!(
+            "workdir {} is outside the workspace; run the command from the working directory or a workspace root",
+            workdir.display()
+        );
+        if self.approval_policy == AskForApproval::OnRequest {
+            message.push_str(", or request escalated permissions to run it there");
+        }
+        Err(message)
+    }
+
     pub(crate) fn compact_prompt(&self) -> &str {
         self.compact_prompt
             .as_deref()
@@ -304,6 +467,22 @@ impl TurnContext {
     }
 }
 
+/// Whether `dir` lies inside one of the writable roots `sandbox_policy`
+/// grants for `cwd`. Always true outside `workspace-write`.
+fn within_writable_roots(sandbox_policy: &SandboxPolicy, cwd: &Path, dir: &Path) -> bool {
+    if !matches!(sandbox_policy, SandboxPolicy::WorkspaceWrite { .. }) {
+        return true;
+    }
+    let dir = dunce::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    sandbox_policy
+        .get_writable_roots_with_cwd(cwd)
+        .into_iter()
+        .any(|writable| {
+            let root = dunce::canonicalize(&writable.root).unwrap_or(writable.root);
+            dir.starts_with(root)
+        })
+}
+
 #[derive(Clone)]
 pub(crate) struct SessionConfiguration {
     /// Provider identifier ("openai", "openrouter", ...).
@@ -341,6 +520,20 @@ pub(crate) struct SessionConfiguration {
     /// operate deterministically.
     cwd: PathBuf,
 
+    /// Additional absolute workspace folders, e.g. the other folders of a
+    /// multi-root IDE workspace. Updated mid-session via
+    /// `Op::AddWorkspaceRoot` and `Op::RemoveWorkspaceRoot`.
+    workspace_roots: Vec<PathBuf>,
+
+    /// Maximum number of tokens the conversation may use. Updated mid-session
+    /// via `Op::SetTokenBudget`.
+    token_budget: Option<i64>,
+
+    /// Extra environment variables for every command the conversation runs,
+    /// layered over `shell_environment_policy.set`. Replaced mid-session via
+    /// `Op::SetEnvironment`.
+    environment: HashMap<String, String>,
+
     /// Set of feature flags for this session
     features: Features,
     /// Execpolicy policy, applied only when enabled by feature flag.
@@ -350,6 +543,10 @@ pub(crate) struct SessionConfiguration {
     original_config_do_not_use: Arc<Config>,
     /// Source of the session (cli, vscode, exec, mcp, ...)
     session_source: SessionSource,
+
+    /// Top-level config keys enforced by the remote overlay. Per-turn
+    /// overrides of the settings they control are dropped.
+    remote_enforced_keys: BTreeSet<String>,
 }
 
 impl SessionConfiguration {
@@ -375,6 +572,217 @@ impl SessionConfiguration {
         }
         next_configuration
     }
+
+    /// Drops the overrides in `updates` that would change a setting enforced
+    /// by remote config and returns the enforced keys they touched.
+    fn strip_enforced_overrides(&self, updates: &mut SessionSettingsUpdate) -> Vec<&'static str> {
+        let enforced = |key: &str| self.remote_enforced_keys.contains(key);
+        let mut ignored = Vec::new();
+        if updates
+            .model
+            .as_ref()
+            .is_some_and(|model| *model != self.model)
+            && enforced("model")
+        {
+            updates.model = None;
+            ignored.push("model");
+        }
+        if updates
+            .reasoning_effort
+            .is_some_and(|effort| effort != self.model_reasoning_effort)
+            && enforced("model_reasoning_effort")
+        {
+            updates.reasoning_effort = None;
+            ignored.push("model_reasoning_effort");
+        }
+        if updates
+            .reasoning_summary
+            .is_some_and(|summary| summary != self.model_reasoning_summary)
+            && enforced("model_reasoning_summary")
+        {
+            updates.reasoning_summary = None;
+            ignored.push("model_reasoning_summary");
+        }
+        if updates
+            .approval_policy
+            .is_some_and(|policy| policy != self.approval_policy)
+            && enforced("approval_policy")
+        {
+            updates.approval_policy = None;
+            ignored.push("approval_policy");
+        }
+        if updates
+            .sandbox_policy
+            .as_ref()
+            .is_some_and(|policy| *policy != self.sandbox_policy)
+        {
+            let key = ["sandbox_mode", "sandbox_workspace_write"]
+                .into_iter()
+                .find(|key| enforced(key));
+            if let Some(key) = key {
+                updates.sandbox_policy = None;
+                ignored.push(key);
+            }
+        }
+        ignored
+    }
+
+    /// Workspace roots other than the current `cwd`.
+    fn turn_workspace_roots(&self) -> Vec<PathBuf> {
+        self.workspace_roots
+            .iter()
+            .filter(|root| **root != self.cwd)
+            .cloned()
+            .collect()
+    }
+
+    /// The directories shared with MCP servers as roots: `cwd`, then the
+    /// other workspace roots.
+    fn mcp_roots(&self) -> Vec<PathBuf> {
+        std::iter::once(self.cwd.clone())
+            .chain(self.turn_workspace_roots())
+            .collect()
+    }
+
+    /// The sandbox policy extended so that every workspace root is writable
+    /// whenever `cwd` is.
+    fn turn_sandbox_policy(&self) -> SandboxPolicy {
+        let mut sandbox_policy = self.sandbox_policy.clone();
+        if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = &mut sandbox_policy {
+            for root in self.turn_workspace_roots() {
+                if !writable_roots.contains(&root) {
+                    writable_roots.push(root);
+                }
+            }
+        }
+        sandbox_policy
+    }
+
+    /// The configured environment policy with the conversation's variables
+    /// added to `set`, so `include_only` still applies to them.
+    fn turn_shell_environment_policy(&self) -> ShellEnvironmentPolicy {
+        let mut policy = self
+            .original_config_do_not_use
+            .shell_environment_policy
+            .clone();
+        policy.r#set.extend(
+            self.environment
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+        policy
+    }
+}
+
+/// Short description of the files a patch touches, for notifications.
+fn patch_summary(changes: &HashMap<PathBuf, FileChange>) -> String {
+    let mut paths: Vec<String> = changes
+        .keys()
+        .map(|path| path.display().to_string())
+        .collect();
+    paths.sort();
+    match paths.as_slice() {
+        [only] => only.clone(),
+        [first, rest @ ..] => format!("{first} and {} more file(s)", rest.len()),
+        [] => "no files".to_string(),
+    }
+}
+
+/// Effective settings that differ between two session configurations, as
+/// reported by `EventMsg::ProfileChanged`.
+fn profile_changes(
+    previous: &SessionConfiguration,
+    next: &SessionConfiguration,
+) -> Vec<SettingChange> {
+    fn effort(effort: Option<ReasoningEffortConfig>) -> String {
+        effort.map_or_else(|| "default".to_string(), |effort| effort.to_string())
+    }
+    fn sandbox(policy: &SandboxPolicy) -> String {
+        match policy {
+            SandboxPolicy::WorkspaceWrite {
+                network_access: true,
+                ..
+            } => format!("{policy} with network access"),
+            _ => policy.to_string(),
+        }
+    }
+    fn mcp_servers(configuration: &SessionConfiguration) -> String {
+        let mut names: Vec<&str> = configuration
+            .original_config_do_not_use
+            .mcp_servers
+            .iter()
+            .filter(|(_, server)| server.enabled)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        if names.is_empty() {
+            return "none".to_string();
+        }
+        names.sort_unstable();
+        names.join(", ")
+    }
+
+    let settings = [
+        (
+            "model_provider",
+            previous
+                .original_config_do_not_use
+                .model_provider_id
+                .clone(),
+            next.original_config_do_not_use.model_provider_id.clone(),
+        ),
+        ("model", previous.model.clone(), next.model.clone()),
+        (
+            "model_reasoning_effort",
+            effort(previous.model_reasoning_effort),
+            effort(next.model_reasoning_effort),
+        ),
+        (
+            "model_reasoning_summary",
+            previous.model_reasoning_summary.to_string(),
+            next.model_reasoning_summary.to_string(),
+        ),
+        (
+            "approval_policy",
+            previous.approval_policy.to_string(),
+            next.approval_policy.to_string(),
+        ),
+        (
+            "sandbox_mode",
+            sandbox(&previous.sandbox_policy),
+            sandbox(&next.sandbox_policy),
+        ),
+        ("mcp_servers", mcp_servers(previous), mcp_servers(next)),
+    ];
+    settings
+        .into_iter()
+        .filter(|(_, previous, current)| previous != current)
+        .map(|(setting, previous, current)| SettingChange {
+            setting: setting.to_string(),
+            previous,
+            current,
+        })
+        .collect()
+}
+
+fn exhausted_token_budget(state: &SessionState) -> Option<BudgetExhaustedEvent> {
+    let budget = state.session_configuration.token_budget?;
+    let used = state
+        .token_info()
+        .map(|info| info.total_token_usage.total_tokens)
+        .unwrap_or_default();
+    (used >= budget).then_some(BudgetExhaustedEvent { budget, used })
+}
+
+/// The provider and stored account whose rate-limit windows `turn_context`
+/// draws on.
+fn rate_limit_key(turn_context: &TurnContext) -> RateLimitKey {
+    RateLimitKey {
+        provider_id: turn_context.client.config().model_provider_id.clone(),
+        account: turn_context
+            .client
+            .get_auth_manager()
+            .and_then(|auth_manager| auth_manager.account()),
+    }
 }
 
 #[derive(Default, Clone)]
@@ -425,22 +833,29 @@ impl Session {
             session_configuration.session_source.clone(),
         );
 
-        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+        let sandbox_policy = session_configuration.turn_sandbox_policy();
+        let mut tools_config = ToolsConfig::new(&ToolsConfigParams {
             model_family: &model_family,
             features: &config.features,
         });
+        tools_config.tool_filter = config.tool_filter.clone();
+        // Audit mode never edits files, so the model is not offered the tool.
+        if sandbox_policy.is_audit() {
+            tools_config.apply_patch_tool_type = None;
+        }
 
         TurnContext {
             sub_id,
             client,
             cwd: session_configuration.cwd.clone(),
+            workspace_roots: session_configuration.turn_workspace_roots(),
             developer_instructions: session_configuration.developer_instructions.clone(),
             base_instructions: session_configuration.base_instructions.clone(),
             compact_prompt: session_configuration.compact_prompt.clone(),
             user_instructions: session_configuration.user_instructions.clone(),
             approval_policy: session_configuration.approval_policy,
-            sandbox_policy: session_configuration.sandbox_policy.clone(),
-            shell_environment_policy: config.shell_environment_policy.clone(),
+            sandbox_policy,
+            shell_environment_policy: session_configuration.turn_shell_environment_policy(),
             tools_config,
             final_output_json_schema: None,
             codex_linux_sandbox_exe: config.codex_linux_sandbox_exe.clone(),
@@ -451,7 +866,7 @@ impl Session {
     }
 
     async fn new(
-        session_configuration: SessionConfiguration,
+        mut session_configuration: SessionConfiguration,
         config: Arc<Config>,
         auth_manager: Arc<AuthManager>,
         tx_event: Sender<Event>,
@@ -557,6 +972,118 @@ impl Session {
             config.active_profile.clone(),
         );
 
+        let network_proxy = if session_configuration
+            .features
+            .enabled(Feature::NetworkProxy)
+        {
+            let allowed_hosts = config
+                .active_project
+                .network_allowed_hosts
+                .clone()
+                .unwrap_or_default();
+            match NetworkProxy::start(
+                allowed_hosts,
+                tx_event.clone(),
+                INITIAL_SUBMIT_ID.to_owned(),
+            )
+            .await
+            {
+                Ok(proxy) => Some(proxy),
+                Err(err) => {
+                    error!("failed to start network proxy: {err}");
+                    post_session_configured_events.push(Event {
+                        id: INITIAL_SUBMIT_ID.to_owned(),
+                        msg: EventMsg::Warning(WarningEvent {
+                            message: format!(
+                                "Network proxy failed to start; commands will not be restricted to network_allowed_hosts: {err}"
+                            ),
+                        }),
+                    });
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        if config.shell_environment_policy.inherit == ShellEnvironmentPolicyInherit::LoginShell
+            && let Err(err) = exec_env::load_login_shell_env(&default_shell).await
+        {
+            warn!("failed to load the login shell environment: {err}");
+            post_session_configured_events.push(Event {
+                id: INITIAL_SUBMIT_ID.to_owned(),
+                msg: EventMsg::Warning(WarningEvent {
+                    message: format!(
+                        "Could not load your login shell environment, so commands inherit Codex's environment instead: {err}"
+                    ),
+                }),
+            });
+        }
+
+        let worktree = if config.worktree_isolation {
+            match SessionWorktree::open_or_create(
+                &config.codex_home,
+                conversation_id,
+                &session_configuration.cwd,
+            )
+            .await
+            {
+                Ok(Some(worktree)) => {
+                    session_configuration.cwd = worktree.cwd();
+                    session_configuration.workspace_roots = session_configuration
+                        .workspace_roots
+                        .iter()
+                        .map(|root| worktree.map_path(root))
+                        .collect();
+                    Some(worktree)
+                }
+                Ok(None) => {
+                    post_session_configured_events.push(Event {
+                        id: INITIAL_SUBMIT_ID.to_owned(),
+                        msg: EventMsg::Warning(WarningEvent {
+                            message: format!(
+                                "worktree_isolation is enabled but {} is not in a git repository; working in place.",
+                                session_configuration.cwd.display()
+                            ),
+                        }),
+                    });
+                    None
+                }
+                Err(err) => {
+                    error!("failed to set up isolated worktree: {err:#}");
+                    post_session_configured_events.push(Event {
+                        id: INITIAL_SUBMIT_ID.to_owned(),
+                        msg: EventMsg::Warning(WarningEvent {
+                            message: format!(
+                                "Could not create an isolated worktree; working in place: {err:#}"
+                            ),
+                        }),
+                    });
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let plugins = if config.features.enabled(Feature::WasmPlugins) {
+            let codex_home = config.codex_home.clone();
+            let mcp_servers = config.mcp_servers.clone();
+            let (plugins, warnings) =
+                tokio::task::spawn_blocking(move || PluginHost::load(&codex_home, &mcp_servers))
+                    .await
+                    .unwrap_or_else(|err| (None, vec![format!("Could not load plugins: {err}")]));
+            for message in warnings {
+                post_session_configured_events.push(Event {
+                    id: INITIAL_SUBMIT_ID.to_owned(),
+                    msg: EventMsg::Warning(WarningEvent { message }),
+                });
+            }
+            plugins.map(Arc::new)
+        } else {
+            None
+        };
+
         // Create the mutable state for the Session.
         let state = SessionState::new(session_configuration.clone());
 
@@ -567,13 +1094,28 @@ impl Session {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: CancellationToken::new(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
-            notifier: UserNotifier::new(config.notify.clone()),
+            notifier: UserNotifier::from_config(&config),
             rollout: Mutex::new(Some(rollout_recorder)),
             user_shell: default_shell,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
-            auth_manager: Arc::clone(&auth_manager),
+            reasoning_redaction: config.reasoning_redaction,
+            exec_output: config.exec_output,
+            auth_manager: StdRwLock::new(Arc::clone(&auth_manager)),
             otel_event_manager,
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            approval_rules: Mutex::new(ApprovalRuleStore::load(&config.codex_home, &config.cwd)),
+            write_grants: Mutex::new(WriteGrantStore::load(&config.codex_home, &config.cwd)),
+            network_proxy,
+            file_locks: FileLockManager::shared(),
+            provider_health: Mutex::new(ProviderHealth::default()),
+            rate_limit_scheduler: RateLimitScheduler::shared(),
+            background_tasks: BackgroundTaskManager::shared(),
+            worktree: Mutex::new(worktree),
+            repo_map: RepoMapCache::default(),
+            lsp: LspManager::default(),
+            code_search: CodeSearchIndex::default(),
+            plugins,
+            sampling_model: SamplingModel::default(),
         };
 
         let sess = Arc::new(Session {
@@ -583,6 +1125,8 @@ impl Session {
             active_turn: Mutex::new(None),
             services,
             next_internal_sub_id: AtomicU64::new(0),
+            turn_activity: StdMutex::new(TurnActivity::default()),
+            command_items: StdMutex::new(HashMap::new()),
         });
 
         // Dispatch the SessionConfiguredEvent first and then report any errors.
@@ -609,6 +1153,16 @@ impl Session {
         for event in events {
             sess.send_event_raw(event).await;
         }
+        // Servers may request completions before the first turn starts.
+        let initial_context = Self::make_turn_context(
+            Some(sess.auth_manager()),
+            &sess.services.otel_event_manager,
+            session_configuration.provider.clone(),
+            &session_configuration,
+            conversation_id,
+            INITIAL_SUBMIT_ID.to_owned(),
+        );
+        sess.services.sampling_model.set(initial_context.client);
         sess.services
             .mcp_connection_manager
             .write()
@@ -619,6 +1173,10 @@ impl Session {
                 auth_statuses.clone(),
                 tx_event.clone(),
                 sess.services.mcp_startup_cancellation_token.clone(),
+                config.features.enabled(Feature::McpHealthMonitor),
+                &config.codex_home,
+                &sess.services.sampling_model,
+                &session_configuration.mcp_roots(),
             )
             .await;
 
@@ -645,6 +1203,16 @@ impl Session {
         }
     }
 
+    /// Flush the rollout and index what this session has recorded so far so
+    /// it shows up in history search.
+    pub(crate) async fn update_history_index(&self, turn_context: &TurnContext) {
+        self.flush_rollout().await;
+        if let Some(rollout_path) = self.rollout_path().await {
+            let codex_home = turn_context.client.config().codex_home.clone();
+            crate::history_index::spawn_index_rollout(codex_home, rollout_path);
+        }
+    }
+
     fn next_internal_sub_id(&self) -> String {
         let id = self
             .next_internal_sub_id
@@ -656,6 +1224,9 @@ impl Session {
         let turn_context = self.new_turn(SessionSettingsUpdate::default()).await;
         match conversation_history {
             InitialHistory::New => {
+                if self.enabled(Feature::RepoMap).await {
+                    self.services.repo_map.refresh(&turn_context.cwd).await;
+                }
                 // Build and record initial items (user instructions + environment context)
                 let items = self.build_initial_context(&turn_context);
                 self.record_conversation_items(&turn_context, &items).await;
@@ -702,6 +1273,15 @@ impl Session {
                         .await;
                 }
 
+                // Carry the usage breakdown over so its totals and request
+                // count continue where the rollout left off.
+                if let Some(breakdown) = rollout_items.iter().rev().find_map(|item| match item {
+                    RolloutItem::EventMsg(EventMsg::TokenCount(event)) => event.breakdown.clone(),
+                    _ => None,
+                }) {
+                    self.state.lock().await.usage_breakdown = breakdown;
+                }
+
                 // If persisting, persist all rollout items as-is (recorder filters)
                 if persist && !rollout_items.is_empty() {
                     self.persist_rollout_items(&rollout_items).await;
@@ -712,10 +1292,307 @@ impl Session {
         }
     }
 
+    /// Drops the per-turn overrides of settings that remote config enforces,
+    /// warning the client about each one that was ignored.
+    pub(crate) async fn drop_enforced_overrides(
+        &self,
+        sub_id: &str,
+        updates: &mut SessionSettingsUpdate,
+    ) {
+        let ignored = {
+            let state = self.state.lock().await;
+            state
+                .session_configuration
+                .strip_enforced_overrides(updates)
+        };
+        for key in ignored {
+            self.send_event_raw(Event {
+                id: sub_id.to_string(),
+                msg: EventMsg::Warning(WarningEvent {
+                    message: format!(
+                        "Ignored the override of `{key}`, which remote config enforces."
+                    ),
+                }),
+            })
+            .await;
+        }
+    }
+
     pub(crate) async fn update_settings(&self, updates: SessionSettingsUpdate) {
         let mut state = self.state.lock().await;
 
         state.session_configuration = state.session_configuration.apply(&updates);
+        let cwd = state.session_configuration.cwd.clone();
+        drop(state);
+        self.follow_cwd_project(&cwd).await;
+        self.sync_mcp_roots().await;
+    }
+
+    /// Switches the per-project stores to the project containing `cwd`, the
+    /// session's current working directory.
+    async fn follow_cwd_project(&self, cwd: &Path) {
+        self.services.approval_rules.lock().await.follow_cwd(cwd);
+        self.services.write_grants.lock().await.follow_cwd(cwd);
+    }
+
+    /// Sends the current workspace roots to the MCP servers, which are only
+    /// notified if the roots changed.
+    async fn sync_mcp_roots(&self) {
+        let roots = self.state.lock().await.session_configuration.mcp_roots();
+        self.services
+            .mcp_connection_manager
+            .read()
+            .await
+            .set_workspace_roots(&roots);
+    }
+
+    /// Adds `path` (resolved against `cwd`) to the workspace roots used by
+    /// subsequent turns.
+    pub(crate) async fn add_workspace_root(&self, path: PathBuf) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+        let configuration = &mut state.session_configuration;
+        let root = configuration.cwd.join(path);
+        if !root.is_dir() {
+            anyhow::bail!("workspace root {} is not a directory", root.display());
+        }
+        let mut roots = configuration.workspace_roots.clone();
+        roots.push(root);
+        configuration.workspace_roots = resolve_workspace_roots(roots, &configuration.cwd);
+        drop(state);
+        self.sync_mcp_roots().await;
+        Ok(())
+    }
+
+    async fn rollout_path(&self) -> Option<PathBuf> {
+        let guard = self.services.rollout.lock().await;
+        guard.as_ref().map(|rec| rec.rollout_path.clone())
+    }
+
+    /// Renames the thread, returning the event that reports it.
+    pub(crate) async fn set_thread_title(
+        &self,
+        title: String,
+    ) -> anyhow::Result<ThreadMetadataChangedEvent> {
+        let Some(title) = normalize_title(&title) else {
+            anyhow::bail!("the title is empty");
+        };
+        let Some(rollout_path) = self.rollout_path().await else {
+            anyhow::bail!("this thread is not recorded");
+        };
+        let mut metadata = read_thread_metadata(&rollout_path).await?;
+        metadata.title = Some(title);
+        write_thread_metadata(&rollout_path, &metadata).await?;
+        Ok(ThreadMetadataChangedEvent {
+            title: metadata.title,
+        })
+    }
+
+    /// With the `thread_titles` feature, names an untitled thread after the
+    /// turn that just finished. Only the first turn of a session tries, so
+    /// threads started before the feature was enabled are titled when
+    /// resumed.
+    pub(crate) async fn title_thread(
+        &self,
+        turn_context: &TurnContext,
+        last_agent_message: Option<&str>,
+    ) {
+        if !turn_context
+            .client
+            .config()
+            .features
+            .enabled(Feature::ThreadTitles)
+            || matches!(
+                turn_context.client.get_session_source(),
+                SessionSource::SubAgent(_)
+            )
+        {
+            return;
+        }
+        let first_user_message = {
+            let mut state = self.state.lock().await;
+            if std::mem::replace(&mut state.title_requested, true) {
+                return;
+            }
+            collect_user_messages(&state.clone_history().get_history())
+                .into_iter()
+                .next()
+        };
+        let (Some(user_message), Some(rollout_path)) =
+            (first_user_message, self.rollout_path().await)
+        else {
+            return;
+        };
+        if !matches!(
+            read_thread_metadata(&rollout_path).await,
+            Ok(ThreadMetadata { title: None })
+        ) {
+            return;
+        }
+        let Some(title) =
+            thread_metadata::generate_title(self, turn_context, &user_message, last_agent_message)
+                .await
+        else {
+            return;
+        };
+        // The thread may have been renamed while the title was generated.
+        let metadata = match read_thread_metadata(&rollout_path).await {
+            Ok(metadata) if metadata.title.is_none() => ThreadMetadata { title: Some(title) },
+            _ => return,
+        };
+        if let Err(err) = write_thread_metadata(&rollout_path, &metadata).await {
+            warn!("failed to save thread title: {err}");
+            return;
+        }
+        let event = ThreadMetadataChangedEvent {
+            title: metadata.title,
+        };
+        self.send_event(turn_context, EventMsg::ThreadMetadataChanged(event))
+            .await;
+    }
+
+    /// With `auto_commit` enabled, commit the files changed during the turn
+    /// to the conversation's turn branch.
+    pub(crate) async fn commit_turn_changes(
+        &self,
+        turn_context: &TurnContext,
+        last_agent_message: Option<&str>,
+    ) -> Option<TurnCommit> {
+        if !turn_context.client.config().auto_commit {
+            return None;
+        }
+        match turn_commit::commit_turn(self, turn_context, last_agent_message).await {
+            Ok(turn_commit) => turn_commit,
+            Err(err) => {
+                warn!("failed to commit turn changes: {err:#}");
+                let message = format!("Could not commit this turn's changes: {err:#}");
+                self.send_event(turn_context, EventMsg::Warning(WarningEvent { message }))
+                    .await;
+                None
+            }
+        }
+    }
+
+    /// Diffstat of the turn's changes against the ghost snapshot taken when
+    /// it started. `None` when ghost commits are off or no snapshot exists.
+    pub(crate) async fn turn_diff_summary(
+        &self,
+        turn_context: &TurnContext,
+    ) -> Option<TurnDiffSummary> {
+        if !self.enabled(Feature::GhostCommit).await {
+            return None;
+        }
+        // The snapshot is taken in the background while the turn starts.
+        turn_context.tool_call_gate.wait_ready().await;
+        let snapshot_id = self.latest_ghost_snapshot_id().await?;
+        let cwd = turn_context.cwd.clone();
+        match tokio::task::spawn_blocking(move || diff_stat_against_commit(&cwd, &snapshot_id))
+            .await
+        {
+            Ok(Ok(files)) => Some(TurnDiffSummary::new(
+                files
+                    .into_iter()
+                    .map(|file| TurnDiffFile {
+                        path: file.path,
+                        insertions: file.insertions,
+                        deletions: file.deletions,
+                    })
+                    .collect(),
+            )),
+            Ok(Err(err)) => {
+                warn!("failed to compute turn diff summary: {err}");
+                None
+            }
+            Err(err) => {
+                warn!("turn diff summary task panicked: {err}");
+                None
+            }
+        }
+    }
+
+    /// Id of the most recent ghost snapshot in the history.
+    pub(crate) async fn latest_ghost_snapshot_id(&self) -> Option<String> {
+        self.clone_history()
+            .await
+            .get_history()
+            .iter()
+            .rev()
+            .find_map(|item| match item {
+                ResponseItem::GhostSnapshot { ghost_commit } => Some(ghost_commit.id().to_string()),
+                _ => None,
+            })
+    }
+
+    /// The changes made in the session's isolated worktree.
+    pub(crate) async fn worktree_diff(&self) -> anyhow::Result<WorktreeDiffEvent> {
+        let guard = self.services.worktree.lock().await;
+        let Some(worktree) = guard.as_ref() else {
+            anyhow::bail!("this conversation is not using an isolated worktree");
+        };
+        Ok(WorktreeDiffEvent {
+            worktree_path: worktree.path().to_path_buf(),
+            branch: worktree.branch().to_string(),
+            base_commit: worktree.base_commit().to_string(),
+            unified_diff: worktree.diff().await?,
+        })
+    }
+
+    /// Merge back or discard the session's isolated worktree and continue in
+    /// the primary checkout.
+    pub(crate) async fn close_worktree(
+        &self,
+        outcome: WorktreeOutcome,
+    ) -> anyhow::Result<WorktreeClosedEvent> {
+        if self.active_turn.lock().await.is_some() {
+            anyhow::bail!("wait for the current turn to finish before closing the worktree");
+        }
+        let mut guard = self.services.worktree.lock().await;
+        let Some(worktree) = guard.as_ref() else {
+            anyhow::bail!("this conversation is not using an isolated worktree");
+        };
+        let conflicted_paths = match outcome {
+            WorktreeOutcome::Merged => worktree.merge().await?.conflicted_paths,
+            WorktreeOutcome::Discarded => {
+                worktree.discard().await?;
+                Vec::new()
+            }
+        };
+
+        let mut state = self.state.lock().await;
+        let configuration = &mut state.session_configuration;
+        configuration.cwd = worktree.original_cwd().to_path_buf();
+        configuration.workspace_roots = configuration
+            .workspace_roots
+            .iter()
+            .map(|root| worktree.unmap_path(root))
+            .collect();
+        let cwd = configuration.cwd.clone();
+        drop(state);
+        *guard = None;
+        self.sync_mcp_roots().await;
+        Ok(WorktreeClosedEvent {
+            outcome,
+            conflicted_paths,
+            cwd,
+        })
+    }
+
+    /// Removes `path` (resolved against `cwd`) from the workspace roots used
+    /// by subsequent turns.
+    pub(crate) async fn remove_workspace_root(&self, path: PathBuf) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+        let configuration = &mut state.session_configuration;
+        let root = configuration.cwd.join(path);
+        let root = dunce::canonicalize(&root).unwrap_or(root);
+        let before = configuration.workspace_roots.len();
+        configuration
+            .workspace_roots
+            .retain(|existing| existing != &root);
+        if configuration.workspace_roots.len() == before {
+            anyhow::bail!("{} is not a workspace root", root.display());
+        }
+        drop(state);
+        self.sync_mcp_roots().await;
+        Ok(())
     }
 
     pub(crate) async fn new_turn(&self, updates: SessionSettingsUpdate) -> Arc<TurnContext> {
@@ -734,9 +1611,10 @@ impl Session {
             state.session_configuration = session_configuration.clone();
             session_configuration
         };
+        self.follow_cwd_project(&session_configuration.cwd).await;
 
         let mut turn_context: TurnContext = Self::make_turn_context(
-            Some(Arc::clone(&self.services.auth_manager)),
+            Some(self.auth_manager()),
             &self.services.otel_event_manager,
             session_configuration.provider.clone(),
             &session_configuration,
@@ -746,14 +1624,43 @@ impl Session {
         if let Some(final_schema) = updates.final_output_json_schema {
             turn_context.final_output_json_schema = final_schema;
         }
+        self.services
+            .sampling_model
+            .set(turn_context.client.clone());
+        self.services
+            .mcp_connection_manager
+            .read()
+            .await
+            .set_workspace_roots(&session_configuration.mcp_roots());
         Arc::new(turn_context)
     }
 
-    fn build_environment_update_item(
+    /// Like [`Self::new_turn_with_sub_id`], but `overrides` apply to this turn
+    /// only and are not saved to the session configuration.
+    pub(crate) async fn new_turn_with_overrides(
         &self,
-        previous: Option<&Arc<TurnContext>>,
-        next: &TurnContext,
-    ) -> Option<ResponseItem> {
+        sub_id: String,
+        overrides: SessionSettingsUpdate,
+    ) -> Arc<TurnContext> {
+        let session_configuration = {
+            let state = self.state.lock().await;
+            state.session_configuration.apply(&overrides)
+        };
+        Arc::new(Self::make_turn_context(
+            Some(self.auth_manager()),
+            &self.services.otel_event_manager,
+            session_configuration.provider.clone(),
+            &session_configuration,
+            self.conversation_id,
+            sub_id,
+        ))
+    }
+
+    fn build_environment_update_item(
+        &self,
+        previous: Option<&Arc<TurnContext>>,
+        next: &TurnContext,
+    ) -> Option<ResponseItem> {
         let prev = previous?;
 
         let prev_context = EnvironmentContext::from(prev.as_ref());
@@ -767,6 +1674,38 @@ impl Session {
         )))
     }
 
+    /// Re-read the instruction files for the turn's working directory. When
+    /// the effective instructions differ from the ones last sent, record the
+    /// new ones in the conversation and tell the client.
+    async fn reload_user_instructions(&self, turn_context: &TurnContext) {
+        let mut config = turn_context.client.config().as_ref().clone();
+        config.cwd = turn_context.cwd.clone();
+        let loaded = load_user_instructions(&config).await;
+        {
+            let mut state = self.state.lock().await;
+            if state.session_configuration.user_instructions == loaded.text {
+                return;
+            }
+            state.session_configuration.user_instructions = loaded.text.clone();
+        }
+
+        if let Some(text) = loaded.text {
+            let item = ResponseItem::from(UserInstructions {
+                text,
+                directory: turn_context.cwd.to_string_lossy().into_owned(),
+            });
+            self.record_conversation_items(turn_context, std::slice::from_ref(&item))
+                .await;
+        }
+        self.send_event(
+            turn_context,
+            EventMsg::InstructionsChanged(InstructionsChangedEvent {
+                sources: loaded.sources,
+            }),
+        )
+        .await;
+    }
+
     /// Persist the event to rollout and send it to clients.
     pub(crate) async fn send_event(&self, turn_context: &TurnContext, msg: EventMsg) {
         let legacy_source = msg.clone();
@@ -787,6 +1726,12 @@ impl Session {
     }
 
     pub(crate) async fn send_event_raw(&self, event: Event) {
+        let Event { id, msg } = event;
+        let Some(msg) = redact_event(self.services.reasoning_redaction, msg) else {
+            return;
+        };
+        let event = Event { id, msg };
+        self.track_turn_activity(&event.msg);
         // Persist the event into rollout (recorder filters as needed)
         let rollout_items = vec![RolloutItem::EventMsg(event.msg.clone())];
         self.persist_rollout_items(&rollout_items).await;
@@ -795,7 +1740,141 @@ impl Session {
         }
     }
 
-    async fn emit_turn_item_started(&self, turn_context: &TurnContext, item: &TurnItem) {
+    /// Report that the turn is still alive and what it is doing.
+    pub(crate) async fn send_turn_heartbeat(&self, turn_context: &TurnContext, elapsed: Duration) {
+        let event = EventMsg::TurnHeartbeat(TurnHeartbeatEvent {
+            elapsed_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+            activity: self.turn_activity(),
+        });
+        self.send_event(turn_context, event).await;
+    }
+
+    /// Credentials for the stored account this session currently uses.
+    pub(crate) fn auth_manager(&self) -> Arc<AuthManager> {
+        Arc::clone(
+            &self
+                .services
+                .auth_manager
+                .read()
+                .unwrap_or_else(PoisonError::into_inner),
+        )
+    }
+
+    pub(crate) fn set_turn_activity(&self, activity: TurnActivity) {
+        *self
+            .turn_activity
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = activity;
+    }
+
+    fn turn_activity(&self) -> TurnActivity {
+        self.turn_activity
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Follow what the turn is doing from the events it emits.
+    fn track_turn_activity(&self, msg: &EventMsg) {
+        let activity = match msg {
+            EventMsg::TaskStarted(_)
+            | EventMsg::ExecCommandEnd(_)
+            | EventMsg::McpToolCallEnd(_)
+            | EventMsg::PatchApplyEnd(_) => TurnActivity::WaitingOnModel,
+            EventMsg::ExecCommandBegin(ev) => TurnActivity::RunningCommand {
+                command: shlex_join(&ev.command),
+            },
+            EventMsg::McpToolCallBegin(ev) => TurnActivity::RunningTool {
+                server: ev.invocation.server.clone(),
+                tool: ev.invocation.tool.clone(),
+            },
+            EventMsg::PatchApplyBegin(_) => TurnActivity::ApplyingPatch,
+            EventMsg::ExecApprovalRequest(_)
+            | EventMsg::ApplyPatchApprovalRequest(_)
+            | EventMsg::WriteScopeApprovalRequest(_)
+            | EventMsg::ElicitationRequest(_) => TurnActivity::WaitingOnApproval,
+            EventMsg::UserInputRequest(_) => TurnActivity::WaitingOnUser,
+            _ => return,
+        };
+        self.set_turn_activity(activity);
+    }
+
+    /// Send `ExecCommandBegin` and start a [`TurnItem::CommandExecution`]
+    /// with a fresh id for the run.
+    pub(crate) async fn send_exec_command_begin(
+        &self,
+        turn_context: &TurnContext,
+        event: ExecCommandBeginEvent,
+    ) {
+        let item_id = Uuid::new_v4().to_string();
+        self.command_items
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(event.call_id.clone(), item_id.clone());
+        let item = TurnItem::CommandExecution(CommandExecutionItem::started(item_id, &event));
+        self.send_event(turn_context, EventMsg::ExecCommandBegin(event))
+            .await;
+        self.emit_turn_item_started(turn_context, &item).await;
+    }
+
+    /// Send `ExecCommandEnd` and complete the item of the run, noting what
+    /// the output policy left out of it.
+    pub(crate) async fn send_exec_command_end(
+        &self,
+        turn_context: &TurnContext,
+        event: ExecCommandEndEvent,
+        output_truncation: Option<CommandOutputTruncation>,
+    ) {
+        self.finish_exec_command(turn_context, event, output_truncation, false)
+            .await;
+    }
+
+    /// Ends a command that was rejected, entirely or from one of its
+    /// segments on, so its item is reported as declined.
+    pub(crate) async fn send_exec_command_declined(
+        &self,
+        turn_context: &TurnContext,
+        event: ExecCommandEndEvent,
+    ) {
+        self.finish_exec_command(turn_context, event, None, true)
+            .await;
+    }
+
+    async fn finish_exec_command(
+        &self,
+        turn_context: &TurnContext,
+        event: ExecCommandEndEvent,
+        output_truncation: Option<CommandOutputTruncation>,
+        declined: bool,
+    ) {
+        let item_id = self
+            .command_items
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&event.call_id)
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let mut item = CommandExecutionItem::completed(item_id, &event, output_truncation);
+        if declined {
+            item.status = CommandExecutionStatus::Declined;
+        }
+        let item = TurnItem::CommandExecution(item);
+        self.send_event(turn_context, EventMsg::ExecCommandEnd(event))
+            .await;
+        self.emit_turn_item_completed(turn_context, item).await;
+    }
+
+    /// The item an approval request for `call_id` is about: the running
+    /// command of that call, or else the call itself.
+    fn item_id_for_call(&self, call_id: &str) -> String {
+        self.command_items
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(call_id)
+            .cloned()
+            .unwrap_or_else(|| call_id.to_string())
+    }
+
+    pub(crate) async fn emit_turn_item_started(&self, turn_context: &TurnContext, item: &TurnItem) {
         self.send_event(
             turn_context,
             EventMsg::ItemStarted(ItemStartedEvent {
@@ -807,7 +1886,11 @@ impl Session {
         .await;
     }
 
-    async fn emit_turn_item_completed(&self, turn_context: &TurnContext, item: TurnItem) {
+    pub(crate) async fn emit_turn_item_completed(
+        &self,
+        turn_context: &TurnContext,
+        item: TurnItem,
+    ) {
         self.send_event(
             turn_context,
             EventMsg::ItemCompleted(ItemCompletedEvent {
@@ -819,6 +1902,24 @@ impl Session {
         .await;
     }
 
+    /// Reports a tool call the sandbox policy refused as a completed
+    /// [`TurnItem::BlockedAction`].
+    pub(crate) async fn emit_blocked_action(
+        &self,
+        turn_context: &TurnContext,
+        call_id: &str,
+        action: BlockedAction,
+        reason: &str,
+    ) {
+        let item = TurnItem::BlockedAction(BlockedActionItem {
+            id: call_id.to_string(),
+            action,
+            reason: reason.to_string(),
+        });
+        self.emit_turn_item_started(turn_context, &item).await;
+        self.emit_turn_item_completed(turn_context, item).await;
+    }
+
     pub(crate) async fn assess_sandbox_command(
         &self,
         turn_context: &TurnContext,
@@ -828,9 +1929,9 @@ impl Session {
     ) -> Option<SandboxCommandAssessment> {
         let config = turn_context.client.config();
         let provider = turn_context.client.provider().clone();
-        let auth_manager = Arc::clone(&self.services.auth_manager);
+        let auth_manager = self.auth_manager();
         let otel = self.services.otel_event_manager.clone();
-        crate::sandboxing::assessment::assess_command(
+        let model_assessment = crate::sandboxing::assessment::assess_command(
             config,
             provider,
             auth_manager,
@@ -843,7 +1944,28 @@ impl Session {
             &turn_context.cwd,
             failure_message,
         )
-        .await
+        .await;
+        let model_assessment = match model_assessment {
+            Some((assessment, usage)) => {
+                if let Some(usage) = usage {
+                    self.persist_rollout_items(&[RolloutItem::ModelUsage(usage)])
+                        .await;
+                }
+                Some(assessment)
+            }
+            None => None,
+        };
+        // Fall back to local heuristics when the model-based assessment is
+        // disabled; commands they find nothing in carry no assessment.
+        model_assessment.or_else(|| {
+            if command.is_empty() {
+                return None;
+            }
+            crate::command_safety::command_risk::assess_command_risk(
+                &turn_context.exec_policy,
+                command,
+            )
+        })
     }
 
     /// Emit an exec approval request event and await the user's decision.
@@ -878,8 +2000,18 @@ impl Session {
             warn!("Overwriting existing pending approval for sub_id: {event_id}");
         }
 
+        self.notifier()
+            .notify(&UserNotification::ApprovalRequested {
+                thread_id: self.conversation_id.to_string(),
+                turn_id: turn_context.sub_id.clone(),
+                cwd: cwd.display().to_string(),
+                call_id: call_id.clone(),
+                summary: format!("Run {}", shlex_join(&command)),
+            });
         let parsed_cmd = parse_command(&command);
+        let proposed_rules = proposed_rules(&command, &cwd);
         let event = EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+            item_id: self.item_id_for_call(&call_id),
             call_id,
             turn_id: turn_context.sub_id.clone(),
             command,
@@ -887,11 +2019,164 @@ impl Session {
             reason,
             risk,
             parsed_cmd,
+            proposed_rules,
         });
         self.send_event(turn_context, event).await;
         rx_approve.await.unwrap_or_default()
     }
 
+    /// Ask the user to make `root` writable so a call that targets `paths`
+    /// outside the writable roots can proceed inside the sandbox. Resolves to
+    /// `Deny` if the task is aborted. Callers record grants with
+    /// [`Session::grant_write_root`].
+    pub(crate) async fn request_write_scope(
+        &self,
+        turn_context: &TurnContext,
+        call_id: String,
+        root: PathBuf,
+        paths: Vec<PathBuf>,
+        command: Option<Vec<String>>,
+        reason: Option<String>,
+    ) -> WriteScopeDecision {
+        let sub_id = turn_context.sub_id.clone();
+        let (tx_decision, rx_decision) = oneshot::channel();
+        let event_id = sub_id.clone();
+        let prev_entry = {
+            let mut active = self.active_turn.lock().await;
+            match active.as_mut() {
+                Some(at) => {
+                    let mut ts = at.turn_state.lock().await;
+                    ts.insert_pending_write_scope(sub_id, tx_decision)
+                }
+                None => None,
+            }
+        };
+        if prev_entry.is_some() {
+            warn!("Overwriting existing pending write scope request for sub_id: {event_id}");
+        }
+
+        self.notifier()
+            .notify(&UserNotification::ApprovalRequested {
+                thread_id: self.conversation_id.to_string(),
+                turn_id: turn_context.sub_id.clone(),
+                cwd: turn_context.cwd.display().to_string(),
+                call_id: call_id.clone(),
+                summary: format!("Allow writes to {}", root.display()),
+            });
+        let event = EventMsg::WriteScopeApprovalRequest(WriteScopeApprovalRequestEvent {
+            item_id: self.item_id_for_call(&call_id),
+            call_id,
+            turn_id: turn_context.sub_id.clone(),
+            root,
+            paths,
+            command,
+            reason,
+        });
+        self.send_event(turn_context, event).await;
+        rx_decision.await.unwrap_or_default()
+    }
+
+    pub async fn notify_write_scope(&self, sub_id: &str, decision: WriteScopeDecision) {
+        let entry = {
+            let mut active = self.active_turn.lock().await;
+            match active.as_mut() {
+                Some(at) => {
+                    let mut ts = at.turn_state.lock().await;
+                    ts.remove_pending_write_scope(sub_id)
+                }
+                None => None,
+            }
+        };
+        match entry {
+            Some(tx_decision) => {
+                tx_decision.send(decision).ok();
+            }
+            None => {
+                warn!("No pending write scope request found for sub_id: {sub_id}");
+            }
+        }
+    }
+
+    /// Ask the user `question` on behalf of the `ask_user` tool call
+    /// `call_id`. Resolves to `None` if the user did not answer or the task
+    /// is aborted.
+    pub(crate) async fn request_user_input(
+        &self,
+        turn_context: &TurnContext,
+        call_id: String,
+        question: String,
+    ) -> Option<String> {
+        let (tx_answer, rx_answer) = oneshot::channel();
+        let prev_entry = {
+            let mut active = self.active_turn.lock().await;
+            match active.as_mut() {
+                Some(at) => {
+                    let mut ts = at.turn_state.lock().await;
+                    ts.insert_pending_user_input(call_id.clone(), tx_answer)
+                }
+                None => None,
+            }
+        };
+        if prev_entry.is_some() {
+            warn!("Overwriting existing pending user input request for call_id: {call_id}");
+        }
+
+        let event = EventMsg::UserInputRequest(UserInputRequestEvent {
+            call_id,
+            turn_id: turn_context.sub_id.clone(),
+            question,
+        });
+        self.send_event(turn_context, event).await;
+        rx_answer.await.ok().flatten()
+    }
+
+    pub async fn notify_user_input(&self, call_id: &str, answer: Option<String>) {
+        let entry = {
+            let mut active = self.active_turn.lock().await;
+            match active.as_mut() {
+                Some(at) => {
+                    let mut ts = at.turn_state.lock().await;
+                    ts.remove_pending_user_input(call_id)
+                }
+                None => None,
+            }
+        };
+        match entry {
+            Some(tx_answer) => {
+                tx_answer.send(answer).ok();
+            }
+            None => {
+                warn!("No pending user input request found for call_id: {call_id}");
+            }
+        }
+    }
+
+    /// Record a root the user made writable. A grant that fails to persist
+    /// still applies to this session.
+    pub(crate) async fn grant_write_root(&self, root: PathBuf, decision: WriteScopeDecision) {
+        let mut grants = self.services.write_grants.lock().await;
+        if let Err(err) = grants.grant(root.clone(), decision).await {
+            warn!(
+                "failed to persist write grant for {}: {err}",
+                root.display()
+            );
+        }
+    }
+
+    /// `policy` widened by the roots the user granted.
+    pub(crate) async fn sandbox_policy_with_grants(&self, policy: &SandboxPolicy) -> SandboxPolicy {
+        self.services.write_grants.lock().await.apply(policy)
+    }
+
+    /// Whether a remembered approval rule for this project allows `command`.
+    pub(crate) async fn approved_by_rule(&self, command: &[String], cwd: &Path) -> bool {
+        self.services
+            .approval_rules
+            .lock()
+            .await
+            .approves(command, cwd)
+    }
+
     pub async fn request_patch_approval(
         &self,
         turn_context: &TurnContext,
@@ -899,6 +2184,7 @@ impl Session {
         changes: HashMap<PathBuf, FileChange>,
         reason: Option<String>,
         grant_root: Option<PathBuf>,
+        file_statuses: HashMap<PathBuf, PatchFileStatus>,
     ) -> oneshot::Receiver<ReviewDecision> {
         let sub_id = turn_context.sub_id.clone();
         // Add the tx_approve callback to the map before sending the request.
@@ -918,16 +2204,52 @@ impl Session {
             warn!("Overwriting existing pending approval for sub_id: {event_id}");
         }
 
+        self.notifier()
+            .notify(&UserNotification::ApprovalRequested {
+                thread_id: self.conversation_id.to_string(),
+                turn_id: turn_context.sub_id.clone(),
+                cwd: turn_context.cwd.display().to_string(),
+                call_id: call_id.clone(),
+                summary: format!("Edit {}", patch_summary(&changes)),
+            });
+        let (changes, deferred_changes) = match defer_large_patch_changes(&changes) {
+            Some(deferred_changes) => {
+                self.store_deferred_patch(call_id.clone(), changes).await;
+                (HashMap::new(), deferred_changes)
+            }
+            None => (changes, Vec::new()),
+        };
         let event = EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
             call_id,
             changes,
             reason,
             grant_root,
+            deferred_changes,
+            file_statuses,
         });
         self.send_event(turn_context, event).await;
         rx_approve
     }
 
+    async fn store_deferred_patch(&self, call_id: String, changes: HashMap<PathBuf, FileChange>) {
+        let active = self.active_turn.lock().await;
+        if let Some(at) = active.as_ref() {
+            let mut ts = at.turn_state.lock().await;
+            ts.insert_deferred_patch(call_id, changes);
+        }
+    }
+
+    pub(crate) async fn deferred_patch_change(
+        &self,
+        call_id: &str,
+        path: &Path,
+    ) -> Option<FileChange> {
+        let active = self.active_turn.lock().await;
+        let at = active.as_ref()?;
+        let ts = at.turn_state.lock().await;
+        ts.deferred_patch_change(call_id, path)
+    }
+
     pub async fn notify_approval(&self, sub_id: &str, decision: ReviewDecision) {
         let entry = {
             let mut active = self.active_turn.lock().await;
@@ -956,11 +2278,89 @@ impl Session {
         turn_context: &TurnContext,
         items: &[ResponseItem],
     ) {
+        let redacted = self.redact_response_items(turn_context, items).await;
+        let items = redacted.as_deref().unwrap_or(items);
         self.record_into_history(items, turn_context).await;
         self.persist_rollout_response_items(items).await;
         self.send_raw_response_items(turn_context, items).await;
     }
 
+    /// Replaces secrets in tool output and patches with placeholders when the
+    /// `secret_redaction` feature is enabled. Returns `None` when the items
+    /// can be recorded unchanged.
+    async fn redact_response_items(
+        &self,
+        turn_context: &TurnContext,
+        items: &[ResponseItem],
+    ) -> Option<Vec<ResponseItem>> {
+        let mut counts = RedactionCounts::new();
+        let redacted = {
+            let mut state = self.state.lock().await;
+            if !state
+                .session_configuration
+                .features
+                .enabled(Feature::SecretRedaction)
+            {
+                return None;
+            }
+            state.secret_redactor.redact_items(items, &mut counts)?
+        };
+        self.notify_secrets_redacted(turn_context, counts).await;
+        Some(redacted)
+    }
+
+    /// Redacts the aggregated output of an exec call before it is truncated
+    /// and formatted for the model, so that truncation cannot cut a secret in
+    /// half and leave part of it behind. Returns `None` when nothing matched
+    /// or the `secret_redaction` feature is disabled.
+    pub(crate) async fn redact_exec_output(
+        &self,
+        turn_context: &TurnContext,
+        output: &ExecToolCallOutput,
+    ) -> Option<ExecToolCallOutput> {
+        let mut counts = RedactionCounts::new();
+        let aggregated_output = {
+            let mut state = self.state.lock().await;
+            if !state
+                .session_configuration
+                .features
+                .enabled(Feature::SecretRedaction)
+            {
+                return None;
+            }
+            state
+                .secret_redactor
+                .redact_text(&output.aggregated_output.text, &mut counts)?
+        };
+        self.notify_secrets_redacted(turn_context, counts).await;
+        let mut redacted = output.clone();
+        redacted.aggregated_output.text = aggregated_output;
+        Some(redacted)
+    }
+
+    /// Puts back the secrets behind placeholders in a command or patch the
+    /// model wrote, so it runs against the real values.
+    pub(crate) async fn restore_redacted_secrets(&self, item: &mut ResponseItem) {
+        self.state
+            .lock()
+            .await
+            .secret_redactor
+            .restore_tool_call(item);
+    }
+
+    async fn notify_secrets_redacted(&self, turn_context: &TurnContext, counts: RedactionCounts) {
+        if counts.is_empty() {
+            return;
+        }
+        self.send_event(
+            turn_context,
+            EventMsg::SecretsRedacted(SecretsRedactedEvent {
+                counts: redacted_secret_counts(counts),
+            }),
+        )
+        .await;
+    }
+
     fn reconstruct_history_from_rollout(
         &self,
         turn_context: &TurnContext,
@@ -1040,7 +2440,7 @@ impl Session {
     }
 
     pub(crate) fn build_initial_context(&self, turn_context: &TurnContext) -> Vec<ResponseItem> {
-        let mut items = Vec::<ResponseItem>::with_capacity(3);
+        let mut items = Vec::<ResponseItem>::with_capacity(4);
         if let Some(developer_instructions) = turn_context.developer_instructions.as_deref() {
             items.push(DeveloperInstructions::new(developer_instructions.to_string()).into());
         }
@@ -1053,100 +2453,489 @@ impl Session {
                 .into(),
             );
         }
-        items.push(ResponseItem::from(EnvironmentContext::new(
-            Some(turn_context.cwd.clone()),
-            Some(turn_context.approval_policy),
-            Some(turn_context.sandbox_policy.clone()),
-            Some(self.user_shell().clone()),
-        )));
+        items.push(ResponseItem::from(
+            EnvironmentContext::new(
+                Some(turn_context.cwd.clone()),
+                Some(turn_context.approval_policy),
+                Some(turn_context.sandbox_policy.clone()),
+                Some(self.user_shell().clone()),
+            )
+            .with_workspace_roots(&turn_context.workspace_roots),
+        ));
+        if let Some(outline) = self.services.repo_map.current() {
+            items.push(repo_map_item(&outline));
+        }
         items
     }
 
+    /// Records which model served a routed request, when it was not simply
+    /// the turn's own model.
+    pub(crate) async fn record_model_usage(&self, routed: &RoutedClient) {
+        if let Some(usage) = routed.usage() {
+            self.persist_rollout_items(&[RolloutItem::ModelUsage(usage)])
+                .await;
+        }
+    }
+
     pub(crate) async fn persist_rollout_items(&self, items: &[RolloutItem]) {
         let recorder = {
             let guard = self.services.rollout.lock().await;
             guard.clone()
         };
-        if let Some(rec) = recorder
-            && let Err(e) = rec.record_items(items).await
-        {
+        let Some(rec) = recorder else {
+            return;
+        };
+        let redaction = self.services.reasoning_redaction;
+        let redacted: Vec<RolloutItem>;
+        let items = if redaction == ReasoningRedaction::None {
+            items
+        } else {
+            redacted = items
+                .iter()
+                .cloned()
+                .filter_map(|item| redact_rollout_item(redaction, item))
+                .collect();
+            &redacted
+        };
+        if let Err(e) = rec.record_items(items).await {
             error!("failed to record rollout items: {e:#}");
         }
     }
 
-    pub(crate) async fn clone_history(&self) -> ContextManager {
+    pub(crate) async fn clone_history(&self) -> ContextManager {
+        let state = self.state.lock().await;
+        state.clone_history()
+    }
+
+    /// Applies the usage reported for a model request. `None` when the
+    /// provider reported none.
+    pub(crate) async fn update_token_usage_info(
+        &self,
+        turn_context: &TurnContext,
+        request: Option<RequestTokenUsage>,
+    ) {
+        let newly_exhausted = {
+            let mut state = self.state.lock().await;
+            let was_exhausted = exhausted_token_budget(&state).is_some();
+            if let Some(request) = request {
+                state.update_token_info_from_usage(
+                    &request.usage,
+                    turn_context.client.get_model_context_window(),
+                );
+                let config = turn_context.client.config();
+                if let Some(price) = config.model_prices.get(&request.model) {
+                    state.add_cost(estimate_cost_usd(price, &request.usage));
+                }
+                state.usage_breakdown.record(request);
+            }
+            exhausted_token_budget(&state).filter(|_| !was_exhausted)
+        };
+        self.send_token_count_event(turn_context).await;
+        if let Some(event) = newly_exhausted {
+            self.send_event(turn_context, EventMsg::BudgetExhausted(event))
+                .await;
+        }
+    }
+
+    /// Tokens occupying the model's context window as of the last token usage
+    /// update.
+    pub(crate) async fn tokens_in_context_window(&self) -> Option<i64> {
+        let state = self.state.lock().await;
+        state
+            .token_info()
+            .map(|info| info.last_token_usage.tokens_in_context_window())
+    }
+
+    /// Budget and usage if the conversation has used up its token budget.
+    pub(crate) async fn exhausted_token_budget(&self) -> Option<BudgetExhaustedEvent> {
+        let state = self.state.lock().await;
+        exhausted_token_budget(&state)
+    }
+
+    pub(crate) async fn set_token_budget(&self, budget: Option<i64>) {
+        let mut state = self.state.lock().await;
+        state.session_configuration.token_budget = budget;
+    }
+
+    pub(crate) async fn set_environment(&self, env: HashMap<String, String>) {
+        let mut state = self.state.lock().await;
+        state.session_configuration.environment = env;
+    }
+
+    /// Move the conversation to `path`, resolved against the current `cwd`.
+    /// Under `workspace-write` the new directory has to be writable already,
+    /// so changing directory never widens what the sandbox allows.
+    pub(crate) async fn set_cwd(&self, path: PathBuf) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+        let configuration = &mut state.session_configuration;
+        let cwd = configuration.cwd.join(path);
+        let cwd = dunce::canonicalize(&cwd).unwrap_or(cwd);
+        if !cwd.is_dir() {
+            anyhow::bail!("working directory {} is not a directory", cwd.display());
+        }
+        if !within_writable_roots(
+            &configuration.turn_sandbox_policy(),
+            &configuration.cwd,
+            &cwd,
+        ) {
+            anyhow::bail!(
+                "cannot change the working directory to {}: it is outside the writable roots of the workspace-write sandbox",
+                cwd.display()
+            );
+        }
+        configuration.workspace_roots =
+            resolve_workspace_roots(configuration.workspace_roots.clone(), &cwd);
+        configuration.cwd = cwd.clone();
+        drop(state);
+        self.follow_cwd_project(&cwd).await;
+        self.sync_mcp_roots().await;
+        Ok(())
+    }
+
+    /// Re-derives the model, provider, account, approval policy, sandbox, and
+    /// MCP servers from `profile` in `config.toml` for subsequent turns.
+    /// `-c` overrides and launch flags the session started with still take
+    /// precedence over the profile.
+    pub(crate) async fn set_profile(&self, profile: String) -> anyhow::Result<ProfileChangedEvent> {
+        let previous = self.state.lock().await.session_configuration.clone();
+        let original = Arc::clone(&previous.original_config_do_not_use);
+        let cfg = load_config_as_toml_with_cli_overrides(
+            &original.codex_home,
+            original.cli_overrides.clone(),
+        )
+        .await?;
+        let overrides = ConfigOverrides {
+            config_profile: Some(profile.clone()),
+            cwd: Some(previous.cwd.clone()),
+            ..original.config_overrides.clone()
+        };
+        let profile_config = Config::load_from_base_config_with_overrides(
+            cfg,
+            overrides,
+            original.codex_home.clone(),
+        )?;
+
+        let mut next_config = (*original).clone();
+        next_config.model = profile_config.model.clone();
+        next_config.model_family = profile_config.model_family.clone();
+        next_config.model_provider_id = profile_config.model_provider_id.clone();
+        next_config.model_provider = profile_config.model_provider.clone();
+        next_config.model_reasoning_effort = profile_config.model_reasoning_effort;
+        next_config.model_reasoning_summary = profile_config.model_reasoning_summary;
+        next_config.model_verbosity = profile_config.model_verbosity;
+        next_config.approval_policy = profile_config.approval_policy;
+        next_config.sandbox_policy = profile_config.sandbox_policy.clone();
+        next_config.mcp_servers = profile_config.mcp_servers.clone();
+        next_config.account = profile_config.account.clone();
+        next_config.active_profile = Some(profile.clone());
+        let next_config = Arc::new(next_config);
+
+        // Later turns authenticate as the profile's account, and its
+        // rate-limit windows replace the previous account's.
+        let current_auth = self.auth_manager();
+        let account_changed = current_auth.account() != next_config.account;
+        if account_changed {
+            let auth_manager = current_auth.for_account(next_config.account.clone());
+            *self
+                .services
+                .auth_manager
+                .write()
+                .unwrap_or_else(PoisonError::into_inner) = auth_manager;
+        }
+
+        let next = {
+            let mut state = self.state.lock().await;
+            let configuration = &mut state.session_configuration;
+            configuration.provider = next_config.model_provider.clone();
+            configuration.model = next_config.model.clone();
+            configuration.model_reasoning_effort = next_config.model_reasoning_effort;
+            configuration.model_reasoning_summary = next_config.model_reasoning_summary;
+            configuration.approval_policy = next_config.approval_policy;
+            configuration.sandbox_policy = next_config.sandbox_policy.clone();
+            configuration.original_config_do_not_use = Arc::clone(&next_config);
+            let next = configuration.clone();
+            if account_changed {
+                state.clear_rate_limits();
+            }
+            next
+        };
+
+        if original.mcp_servers != next_config.mcp_servers {
+            self.restart_mcp_servers(&next_config).await;
+        }
+
+        Ok(ProfileChangedEvent {
+            profile,
+            model: next.model.clone(),
+            model_provider_id: next_config.model_provider_id.clone(),
+            reasoning_effort: next.model_reasoning_effort,
+            approval_policy: next.approval_policy,
+            sandbox_policy: next.sandbox_policy.clone(),
+            changes: profile_changes(&previous, &next),
+        })
+    }
+
+    /// Replaces the running MCP servers with the ones configured in `config`.
+    async fn restart_mcp_servers(&self, config: &Config) {
+        let workspace_roots = self.state.lock().await.session_configuration.mcp_roots();
+        let auth_statuses = compute_auth_statuses(
+            config.mcp_servers.iter(),
+            config.mcp_oauth_credentials_store_mode,
+        )
+        .await;
+        // Stop the old servers first so pooled ones can be picked up again.
+        self.services
+            .mcp_connection_manager
+            .write()
+            .await
+            .shutdown();
+        let mut mcp_connection_manager = McpConnectionManager::default();
+        mcp_connection_manager
+            .initialize(
+                config.mcp_servers.clone(),
+                config.mcp_oauth_credentials_store_mode,
+                auth_statuses,
+                self.tx_event.clone(),
+                self.services.mcp_startup_cancellation_token.clone(),
+                config.features.enabled(Feature::McpHealthMonitor),
+                &config.codex_home,
+                &self.services.sampling_model,
+                &workspace_roots,
+            )
+            .await;
+        *self.services.mcp_connection_manager.write().await = mcp_connection_manager;
+    }
+
+    pub(crate) async fn recompute_token_usage(&self, turn_context: &TurnContext) {
+        let Some(estimated_total_tokens) = self
+            .clone_history()
+            .await
+            .estimate_token_count(turn_context)
+        else {
+            return;
+        };
+        {
+            let mut state = self.state.lock().await;
+            let mut info = state.token_info().unwrap_or(TokenUsageInfo {
+                total_token_usage: TokenUsage::default(),
+                last_token_usage: TokenUsage::default(),
+                model_context_window: None,
+            });
+
+            info.last_token_usage = TokenUsage {
+                input_tokens: 0,
+                cached_input_tokens: 0,
+                output_tokens: 0,
+                reasoning_output_tokens: 0,
+                total_tokens: estimated_total_tokens.max(0),
+            };
+
+            if info.model_context_window.is_none() {
+                info.model_context_window = turn_context.client.get_model_context_window();
+            }
+
+            state.set_token_info(Some(info));
+        }
+        self.send_token_count_event(turn_context).await;
+    }
+
+    pub(crate) async fn update_rate_limits(
+        &self,
+        turn_context: &TurnContext,
+        new_rate_limits: RateLimitSnapshot,
+    ) {
+        self.services
+            .rate_limit_scheduler
+            .record(rate_limit_key(turn_context), new_rate_limits.clone());
+        let warnings = {
+            let mut state = self.state.lock().await;
+            state.set_rate_limits(new_rate_limits)
+        };
+        for (window, limits) in warnings {
+            self.notifier().notify(&UserNotification::RateLimitWarning {
+                thread_id: self.conversation_id.to_string(),
+                window: window.to_string(),
+                used_percent: limits.used_percent,
+                window_minutes: limits.window_minutes,
+                resets_at: limits.resets_at,
+            });
+        }
+        self.send_token_count_event(turn_context).await;
+    }
+
+    pub(crate) async fn set_terminal_size(&self, size: TerminalSize) {
+        let mut state = self.state.lock().await;
+        state.terminal_size = Some(size);
+    }
+
+    /// Take the advisory write locks for `paths` on behalf of tool call
+    /// `call_id`, queueing behind other tool calls (from any conversation)
+    /// that are writing the same files. Emits `FileLockWait` when the call
+    /// has to wait.
+    pub(crate) async fn lock_files_for_write(
+        &self,
+        turn_context: &TurnContext,
+        call_id: &str,
+        paths: &[PathBuf],
+    ) -> Result<FileLockGuard, FileLockError> {
+        let owner = format!("{}:{call_id}", self.conversation_id);
+        let blocked = match self.services.file_locks.try_acquire(&owner, paths) {
+            Ok(guard) => return Ok(guard),
+            Err(blocked) => blocked,
+        };
+        self.send_event(
+            turn_context,
+            EventMsg::FileLockWait(FileLockWaitEvent {
+                call_id: call_id.to_string(),
+                paths: blocked,
+            }),
+        )
+        .await;
+        self.services.file_locks.acquire(&owner, paths).await
+    }
+
+    /// Remember the current modification time of `path` so later patch
+    /// approvals can tell whether someone else edited it since.
+    pub(crate) async fn record_file_seen(&self, path: &Path) {
+        let Ok(mtime) = tokio::fs::metadata(path)
+            .await
+            .and_then(|metadata| metadata.modified())
+        else {
+            return;
+        };
+        let mut state = self.state.lock().await;
+        state.record_file_mtime(path.to_path_buf(), mtime);
+    }
+
+    /// Whether `path` was modified after Codex last read or wrote it. Files
+    /// Codex has not seen are never reported as modified.
+    pub(crate) async fn file_modified_since_seen(&self, path: &Path) -> bool {
+        let Some(seen) = self.state.lock().await.seen_file_mtime(path) else {
+            return false;
+        };
+        tokio::fs::metadata(path)
+            .await
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|current| current > seen)
+    }
+
+    /// Window size for commands run attached to a PTY, or `None` when shell
+    /// commands should use plain pipes.
+    pub(crate) async fn shell_pty_size(&self) -> Option<TerminalSize> {
         let state = self.state.lock().await;
-        state.clone_history()
+        state
+            .session_configuration
+            .features
+            .enabled(Feature::ShellPty)
+            .then(|| state.terminal_size.unwrap_or_default())
     }
 
-    pub(crate) async fn update_token_usage_info(
+    /// Archives the complete output of an exec call when the
+    /// `exec_output_archive` feature is enabled and records where it was
+    /// written in the rollout.
+    /// Directory the raw output of exec call `call_id` is spooled to, when
+    /// the `exec_output_archive` feature is enabled.
+    pub(crate) async fn exec_output_archive_dir(
         &self,
         turn_context: &TurnContext,
-        token_usage: Option<&TokenUsage>,
-    ) {
-        {
-            let mut state = self.state.lock().await;
-            if let Some(token_usage) = token_usage {
-                state.update_token_info_from_usage(
-                    token_usage,
-                    turn_context.client.get_model_context_window(),
-                );
-            }
+        call_id: &str,
+    ) -> Option<PathBuf> {
+        if !self.enabled(Feature::ExecOutputArchive).await {
+            return None;
         }
-        self.send_token_count_event(turn_context).await;
+        let codex_home = &turn_context.client.config().codex_home;
+        exec_log::exec_output_dir(codex_home, &self.conversation_id, call_id).ok()
     }
 
-    pub(crate) async fn recompute_token_usage(&self, turn_context: &TurnContext) {
-        let Some(estimated_total_tokens) = self
-            .clone_history()
-            .await
-            .estimate_token_count(turn_context)
-        else {
+    pub(crate) async fn archive_exec_output(
+        &self,
+        turn_context: &TurnContext,
+        call_id: &str,
+        output: &ExecToolCallOutput,
+    ) {
+        if !self.enabled(Feature::ExecOutputArchive).await {
             return;
-        };
-        {
-            let mut state = self.state.lock().await;
-            let mut info = state.token_info().unwrap_or(TokenUsageInfo {
-                total_token_usage: TokenUsage::default(),
-                last_token_usage: TokenUsage::default(),
-                model_context_window: None,
-            });
-
-            info.last_token_usage = TokenUsage {
-                input_tokens: 0,
-                cached_input_tokens: 0,
-                output_tokens: 0,
-                reasoning_output_tokens: 0,
-                total_tokens: estimated_total_tokens.max(0),
+        }
+        let codex_home = turn_context.client.config().codex_home.clone();
+        // Streamed calls spool their raw output while it is read; `output`
+        // only holds what the session's output policy retained.
+        let archived =
+            match exec_log::spooled_exec_output_dir(&codex_home, &self.conversation_id, call_id) {
+                Some(dir) => Ok(dir),
+                None => {
+                    exec_log::archive_exec_output(
+                        &codex_home,
+                        &self.conversation_id,
+                        call_id,
+                        &output.stdout.text,
+                        &output.stderr.text,
+                    )
+                    .await
+                }
             };
-
-            if info.model_context_window.is_none() {
-                info.model_context_window = turn_context.client.get_model_context_window();
+        match archived {
+            Ok(path) => {
+                let item = RolloutItem::ExecOutputArchive(ExecOutputArchiveItem {
+                    call_id: call_id.to_string(),
+                    path,
+                });
+                self.persist_rollout_items(&[item]).await;
             }
-
-            state.set_token_info(Some(info));
+            Err(err) => warn!("failed to archive output of exec call {call_id}: {err}"),
         }
-        self.send_token_count_event(turn_context).await;
     }
 
-    pub(crate) async fn update_rate_limits(
+    /// Records the outcome of a regular turn for the experiment variant this
+    /// conversation is enrolled in, if any.
+    pub(crate) async fn record_experiment_outcome(
         &self,
         turn_context: &TurnContext,
-        new_rate_limits: RateLimitSnapshot,
+        success: bool,
     ) {
+        let config = turn_context.client.config();
+        let Some(assignment) = config.experiment.as_ref() else {
+            return;
+        };
+        let total_tokens = {
+            let state = self.state.lock().await;
+            state
+                .token_info()
+                .map(|info| info.total_token_usage.total_tokens)
+                .unwrap_or_default()
+        };
+        if let Err(err) = experiments::record_turn_outcome(
+            &config.codex_home,
+            assignment,
+            self.conversation_id,
+            success,
+            total_tokens,
+        )
+        .await
         {
-            let mut state = self.state.lock().await;
-            state.set_rate_limits(new_rate_limits);
+            warn!(
+                "failed to record outcome for experiment {}: {err}",
+                assignment.experiment
+            );
         }
-        self.send_token_count_event(turn_context).await;
     }
 
     async fn send_token_count_event(&self, turn_context: &TurnContext) {
-        let (info, rate_limits) = {
+        let (info, rate_limits, cost, breakdown) = {
             let state = self.state.lock().await;
-            state.token_info_and_rate_limits()
+            let (info, rate_limits) = state.token_info_and_rate_limits();
+            let breakdown = state
+                .usage_breakdown
+                .last_request
+                .is_some()
+                .then(|| state.usage_breakdown.clone());
+            (info, rate_limits, state.cost_estimate, breakdown)
         };
-        let event = EventMsg::TokenCount(TokenCountEvent { info, rate_limits });
+        let event = EventMsg::TokenCount(TokenCountEvent {
+            info,
+            rate_limits,
+            cost,
+            breakdown,
+        });
         self.send_event(turn_context, event).await;
     }
 
@@ -1197,13 +2986,76 @@ impl Session {
         &self,
         turn_context: &TurnContext,
         message: impl Into<String>,
-        http_status_code: Option<StatusCode>,
+        err: &CodexErr,
     ) {
         let event = EventMsg::StreamError(StreamErrorEvent {
             message: message.into(),
-            http_status_code: http_status_code_value(http_status_code),
+            http_status_code: http_status_code_value(err.http_status_code()),
+            info: err.error_info(),
+        });
+        self.send_event(turn_context, event).await;
+    }
+
+    /// Count an outage-like error against the provider's circuit breaker,
+    /// announcing the provider as degraded when this failure opens it.
+    /// Returns whether the provider is currently degraded.
+    async fn record_provider_failure(&self, turn_context: &TurnContext, err: &CodexErr) -> bool {
+        let (opened, degraded) = {
+            let mut health = self.services.provider_health.lock().await;
+            (health.record_failure(Instant::now()), health.is_degraded())
+        };
+        if let Some(cooldown) = opened {
+            let event = EventMsg::ProviderHealthChanged(ProviderHealthChangedEvent {
+                provider: turn_context.client.get_provider().name,
+                status: ProviderHealthStatus::Degraded,
+                message: Some(err.to_string()),
+                retry_in_ms: Some(cooldown.as_millis() as u64),
+            });
+            self.send_event(turn_context, event).await;
+        }
+        degraded
+    }
+
+    async fn record_provider_success(&self, turn_context: &TurnContext) {
+        let recovered = self.services.provider_health.lock().await.record_success();
+        if recovered {
+            let event = EventMsg::ProviderHealthChanged(ProviderHealthChangedEvent {
+                provider: turn_context.client.get_provider().name,
+                status: ProviderHealthStatus::Healthy,
+                message: None,
+                retry_in_ms: None,
+            });
+            self.send_event(turn_context, event).await;
+        }
+    }
+
+    /// How long the next model request should wait for an exhausted
+    /// rate-limit window to reset. Emits `RateLimited` when it has to wait.
+    async fn rate_limit_pause(&self, turn_context: &TurnContext) -> Option<Duration> {
+        let pause_percent = turn_context.client.config().rate_limit_pause_percent?;
+        let now = Utc::now().timestamp();
+        let pause = self.services.rate_limit_scheduler.pause(
+            &rate_limit_key(turn_context),
+            pause_percent,
+            now,
+        )?;
+        let event = EventMsg::RateLimited(RateLimitedEvent {
+            resume_at: pause.resume_at,
+            window: pause.window.to_string(),
+            used_percent: pause.used_percent,
         });
         self.send_event(turn_context, event).await;
+        u64::try_from(pause.resume_at - now)
+            .ok()
+            .map(Duration::from_secs)
+    }
+
+    async fn provider_cooldown_remaining(&self) -> Option<Duration> {
+        self.services
+            .provider_health
+            .lock()
+            .await
+            .cooldown_remaining(Instant::now())
     }
 
     async fn maybe_start_ghost_snapshot(
@@ -1302,12 +3154,13 @@ impl Session {
         server: &str,
         tool: &str,
         arguments: Option<serde_json::Value>,
+        progress: Option<UnboundedSender<McpProgress>>,
     ) -> anyhow::Result<CallToolResult> {
         self.services
             .mcp_connection_manager
             .read()
             .await
-            .call_tool(server, tool, arguments)
+            .call_tool(server, tool, arguments, progress)
             .await
     }
 
@@ -1330,6 +3183,10 @@ impl Session {
         }
     }
 
+    pub(crate) fn conversation_id(&self) -> ConversationId {
+        self.conversation_id
+    }
+
     pub(crate) fn notifier(&self) -> &UserNotifier {
         &self.services.notifier
     }
@@ -1369,6 +3226,7 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             } => {
                 handlers::override_turn_context(
                     &sess,
+                    sub.id.clone(),
                     SessionSettingsUpdate {
                         cwd,
                         approval_policy,
@@ -1391,6 +3249,28 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::PatchApproval { id, decision } => {
                 handlers::patch_approval(&sess, id, decision).await;
             }
+            Op::WriteScopeApproval { id, decision } => {
+                sess.notify_write_scope(&id, decision).await;
+            }
+            Op::UserInputAnswer { call_id, answer } => {
+                sess.notify_user_input(&call_id, answer).await;
+            }
+            Op::ResolveElicitation {
+                server_name,
+                request_id,
+                decision,
+                content,
+            } => {
+                handlers::resolve_elicitation(
+                    &sess,
+                    sub.id.clone(),
+                    server_name,
+                    request_id,
+                    decision,
+                    content,
+                )
+                .await;
+            }
             Op::AddToHistory { text } => {
                 handlers::add_to_history(&sess, &config, text).await;
             }
@@ -1404,6 +3284,27 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::ListCustomPrompts => {
                 handlers::list_custom_prompts(&sess, sub.id.clone()).await;
             }
+            Op::ListMcpPrompts => {
+                handlers::list_mcp_prompts(&sess, sub.id.clone()).await;
+            }
+            Op::ReadMcpResource { server, uri } => {
+                handlers::read_mcp_resource(&sess, sub.id.clone(), server, uri).await;
+            }
+            Op::RunMcpPrompt {
+                server,
+                name,
+                arguments,
+            } => {
+                handlers::run_mcp_prompt(
+                    &sess,
+                    sub.id.clone(),
+                    server,
+                    name,
+                    arguments,
+                    &mut previous_context,
+                )
+                .await;
+            }
             Op::Undo => {
                 handlers::undo(&sess, sub.id.clone()).await;
             }
@@ -1419,6 +3320,35 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                 )
                 .await;
             }
+            Op::RunBackground { command } => {
+                handlers::run_background(&sess, sub.id.clone(), command).await;
+            }
+            Op::TerminateBackground { task_id } => {
+                handlers::terminate_background(&sess, sub.id.clone(), task_id).await;
+            }
+            Op::WorktreeDiff => {
+                handlers::worktree_diff(&sess, sub.id.clone()).await;
+            }
+            Op::MergeWorktree => {
+                handlers::close_worktree(&sess, sub.id.clone(), WorktreeOutcome::Merged).await;
+            }
+            Op::DiscardWorktree => {
+                handlers::close_worktree(&sess, sub.id.clone(), WorktreeOutcome::Discarded).await;
+            }
+            Op::CreatePullRequest {
+                title,
+                body,
+                base,
+                draft,
+            } => {
+                let request = PullRequestRequest {
+                    title,
+                    body,
+                    base,
+                    draft,
+                };
+                handlers::create_pull_request(&sess, sub.id.clone(), request).await;
+            }
             Op::Shutdown => {
                 if handlers::shutdown(&sess, sub.id.clone()).await {
                     break;
@@ -1427,6 +3357,53 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::Review { review_request } => {
                 handlers::review(&sess, &config, sub.id.clone(), review_request).await;
             }
+            Op::GetPatchContent { call_id, path } => {
+                handlers::get_patch_content(&sess, sub.id.clone(), call_id, path).await;
+            }
+            Op::SetTerminalSize { rows, cols } => {
+                handlers::set_terminal_size(&sess, rows, cols).await;
+            }
+            Op::AddWorkspaceRoot { path } => {
+                handlers::add_workspace_root(&sess, sub.id.clone(), path).await;
+            }
+            Op::RemoveWorkspaceRoot { path } => {
+                handlers::remove_workspace_root(&sess, sub.id.clone(), path).await;
+            }
+            Op::SetTokenBudget { budget } => {
+                handlers::set_token_budget(&sess, budget).await;
+            }
+            Op::SetEnvironment { env } => {
+                handlers::set_environment(&sess, env).await;
+            }
+            Op::SetCwd { path } => {
+                handlers::set_cwd(&sess, sub.id.clone(), path).await;
+            }
+            Op::AddApprovalRule { rule } => {
+                handlers::add_approval_rule(&sess, sub.id.clone(), rule).await;
+            }
+            Op::RemoveApprovalRule { rule } => {
+                handlers::remove_approval_rule(&sess, sub.id.clone(), rule).await;
+            }
+            Op::ListApprovalRules => {
+                handlers::list_approval_rules(&sess, sub.id.clone()).await;
+            }
+            Op::SetProfile { profile } => {
+                handlers::set_profile(&sess, sub.id.clone(), profile).await;
+            }
+            Op::SetThreadTitle { title } => {
+                handlers::set_thread_title(&sess, sub.id.clone(), title).await;
+            }
+            Op::RunCommand { name, args } => {
+                handlers::run_command(
+                    &sess,
+                    &config,
+                    sub.id.clone(),
+                    name,
+                    args,
+                    &mut previous_context,
+                )
+                .await;
+            }
             _ => {} // Ignore unknown ops; enum is non_exhaustive to allow extensions.
         }
     }
@@ -1435,28 +3412,54 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
 
 /// Operation handlers
 mod handlers {
+    use crate::audio_transcription::transcribe_audio_inputs;
     use crate::codex::Session;
     use crate::codex::SessionSettingsUpdate;
     use crate::codex::TurnContext;
 
     use crate::codex::spawn_review_thread;
     use crate::config::Config;
+    use crate::custom_commands::commands_dir;
+    use crate::custom_commands::find_command;
+    use crate::custom_commands::render_command;
+    use crate::error::CodexErr;
+    use crate::exec_env::create_env;
     use crate::mcp::auth::compute_auth_statuses;
+    use crate::mcp::content::MAX_MCP_CONTENT_BYTES;
+    use crate::mcp::content::limit_resource_contents;
+    use crate::mcp::content::prompt_to_user_input;
+    use crate::pull_request;
+    use crate::pull_request::PullRequestRequest;
     use crate::tasks::CompactTask;
     use crate::tasks::RegularTask;
     use crate::tasks::UndoTask;
     use crate::tasks::UserShellCommandTask;
+    use codex_keyring_store::DefaultKeyringStore;
     use codex_protocol::custom_prompts::CustomPrompt;
+    use codex_protocol::protocol::ApprovalRule;
+    use codex_protocol::protocol::CodexErrorInfo;
+    use codex_protocol::protocol::ElicitationAction;
+    use codex_protocol::protocol::ErrorCode;
     use codex_protocol::protocol::ErrorEvent;
+    use codex_protocol::protocol::ErrorOrigin;
     use codex_protocol::protocol::Event;
     use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::ListApprovalRulesResponseEvent;
     use codex_protocol::protocol::ListCustomPromptsResponseEvent;
+    use codex_protocol::protocol::McpListPromptsResponseEvent;
+    use codex_protocol::protocol::McpResourceReadResponseEvent;
     use codex_protocol::protocol::Op;
+    use codex_protocol::protocol::PatchContentResponseEvent;
     use codex_protocol::protocol::ReviewDecision;
     use codex_protocol::protocol::ReviewRequest;
     use codex_protocol::protocol::TurnAbortReason;
+    use codex_protocol::protocol::WarningEvent;
+    use codex_protocol::protocol::WorktreeOutcome;
 
     use codex_protocol::user_input::UserInput;
+    use codex_utils_pty::TerminalSize;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
     use std::sync::Arc;
     use tracing::info;
     use tracing::warn;
@@ -1465,43 +3468,249 @@ mod handlers {
         sess.interrupt_task().await;
     }
 
-    pub async fn override_turn_context(sess: &Session, updates: SessionSettingsUpdate) {
+    pub async fn override_turn_context(
+        sess: &Session,
+        sub_id: String,
+        mut updates: SessionSettingsUpdate,
+    ) {
+        sess.drop_enforced_overrides(&sub_id, &mut updates).await;
         sess.update_settings(updates).await;
     }
 
-    pub async fn user_input_or_turn(
+    pub async fn set_terminal_size(sess: &Session, rows: i32, cols: i32) {
+        sess.set_terminal_size(TerminalSize { rows, cols }).await;
+    }
+
+    pub async fn add_workspace_root(sess: &Session, sub_id: String, path: PathBuf) {
+        if let Err(err) = sess.add_workspace_root(path).await {
+            warn_workspace_root_update(sess, sub_id, err).await;
+        }
+    }
+
+    pub async fn remove_workspace_root(sess: &Session, sub_id: String, path: PathBuf) {
+        if let Err(err) = sess.remove_workspace_root(path).await {
+            warn_workspace_root_update(sess, sub_id, err).await;
+        }
+    }
+
+    pub async fn set_token_budget(sess: &Session, budget: Option<i64>) {
+        sess.set_token_budget(budget).await;
+    }
+
+    pub async fn set_environment(sess: &Session, env: HashMap<String, String>) {
+        sess.set_environment(env).await;
+    }
+
+    pub async fn set_cwd(sess: &Session, sub_id: String, path: PathBuf) {
+        if let Err(err) = sess.set_cwd(path).await {
+            let event = Event {
+                id: sub_id,
+                msg: EventMsg::Warning(WarningEvent {
+                    message: format!("{err:#}"),
+                }),
+            };
+            sess.send_event_raw(event).await;
+        }
+    }
+
+    pub async fn set_profile(sess: &Session, sub_id: String, profile: String) {
+        let msg = match sess.set_profile(profile.clone()).await {
+            Ok(event) => EventMsg::ProfileChanged(event),
+            Err(err) => EventMsg::Error(ErrorEvent {
+                message: format!("Failed to switch to profile {profile}: {err:#}"),
+                http_status_code: None,
+                info: CodexErrorInfo::new(ErrorCode::InvalidConfig, ErrorOrigin::Codex),
+            }),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    pub async fn set_thread_title(sess: &Session, sub_id: String, title: String) {
+        let msg = match sess.set_thread_title(title).await {
+            Ok(event) => EventMsg::ThreadMetadataChanged(event),
+            Err(err) => EventMsg::Error(ErrorEvent {
+                message: format!("Failed to rename the thread: {err:#}"),
+                http_status_code: None,
+                info: CodexErrorInfo::new(ErrorCode::BadRequest, ErrorOrigin::Codex),
+            }),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    pub async fn add_approval_rule(sess: &Session, sub_id: String, rule: ApprovalRule) {
+        let result = sess.services.approval_rules.lock().await.add(rule).await;
+        if let Err(err) = result {
+            warn_approval_rules_update(sess, sub_id, err).await;
+        }
+    }
+
+    pub async fn remove_approval_rule(sess: &Session, sub_id: String, rule: ApprovalRule) {
+        let result = sess
+            .services
+            .approval_rules
+            .lock()
+            .await
+            .remove(&rule)
+            .await;
+        if let Err(err) = result {
+            warn_approval_rules_update(sess, sub_id, err).await;
+        }
+    }
+
+    pub async fn list_approval_rules(sess: &Session, sub_id: String) {
+        let rules = sess.services.approval_rules.lock().await.rules().to_vec();
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::ListApprovalRulesResponse(ListApprovalRulesResponseEvent { rules }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    async fn warn_approval_rules_update(sess: &Session, sub_id: String, err: std::io::Error) {
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::Warning(WarningEvent {
+                message: format!("Failed to save approval rules: {err}"),
+            }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    async fn warn_workspace_root_update(sess: &Session, sub_id: String, err: anyhow::Error) {
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::Warning(WarningEvent {
+                message: format!("{err:#}"),
+            }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn user_input_or_turn(
+        sess: &Arc<Session>,
+        sub_id: String,
+        op: Op,
+        previous_context: &mut Option<Arc<TurnContext>>,
+    ) {
+        let (items, mut updates) = match op {
+            Op::UserTurn {
+                cwd,
+                approval_policy,
+                sandbox_policy,
+                model,
+                effort,
+                summary,
+                final_output_json_schema,
+                items,
+            } => (
+                items,
+                SessionSettingsUpdate {
+                    cwd: Some(cwd),
+                    approval_policy: Some(approval_policy),
+                    sandbox_policy: Some(sandbox_policy),
+                    model: Some(model),
+                    reasoning_effort: Some(effort),
+                    reasoning_summary: Some(summary),
+                    final_output_json_schema: Some(final_output_json_schema),
+                },
+            ),
+            Op::UserInput { items } => (items, SessionSettingsUpdate::default()),
+            _ => unreachable!(),
+        };
+
+        if reject_if_budget_exhausted(sess, &sub_id).await {
+            return;
+        }
+        sess.drop_enforced_overrides(&sub_id, &mut updates).await;
+        let current_context = sess.new_turn_with_sub_id(sub_id, updates).await;
+        submit_user_items(sess, current_context, items, previous_context).await;
+    }
+
+    /// Expands the custom command `name` and submits it as user input. The
+    /// command's model and approval policy apply to the turn it starts only.
+    pub async fn run_command(
+        sess: &Arc<Session>,
+        config: &Arc<Config>,
+        sub_id: String,
+        name: String,
+        args: HashMap<String, String>,
+        previous_context: &mut Option<Arc<TurnContext>>,
+    ) {
+        let dir = commands_dir(&config.codex_home);
+        let Some(command) = find_command(&dir, &name).await else {
+            let message = format!("No custom command /{name} in {}", dir.display());
+            send_bad_request(sess, sub_id, message).await;
+            return;
+        };
+        let text = match render_command(&command, &args) {
+            Ok(text) => text,
+            Err(err) => {
+                send_bad_request(sess, sub_id, err.to_string()).await;
+                return;
+            }
+        };
+
+        if reject_if_budget_exhausted(sess, &sub_id).await {
+            return;
+        }
+        let mut overrides = SessionSettingsUpdate {
+            model: command.model,
+            approval_policy: command.approval_policy,
+            ..Default::default()
+        };
+        sess.drop_enforced_overrides(&sub_id, &mut overrides).await;
+        let current_context = sess.new_turn_with_overrides(sub_id, overrides).await;
+        let items = vec![UserInput::Text { text }];
+        submit_user_items(sess, current_context, items, previous_context).await;
+    }
+
+    async fn send_bad_request(sess: &Session, sub_id: String, message: String) {
+        let msg = EventMsg::Error(ErrorEvent {
+            message,
+            http_status_code: None,
+            info: CodexErrorInfo::new(ErrorCode::BadRequest, ErrorOrigin::Codex),
+        });
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    /// Reports an exhausted token budget and returns whether one was found.
+    async fn reject_if_budget_exhausted(sess: &Session, sub_id: &str) -> bool {
+        let Some(exhausted) = sess.exhausted_token_budget().await else {
+            return false;
+        };
+        let err = CodexErr::TokenBudgetExhausted {
+            budget: exhausted.budget,
+            used: exhausted.used,
+        };
+        sess.send_event_raw(Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::BudgetExhausted(exhausted),
+        })
+        .await;
+        sess.send_event_raw(Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::Error(err.to_error_event(None)),
+        })
+        .await;
+        true
+    }
+
+    /// Adds `items` to the running task, or starts a new one with
+    /// `current_context`.
+    async fn submit_user_items(
         sess: &Arc<Session>,
-        sub_id: String,
-        op: Op,
+        current_context: Arc<TurnContext>,
+        items: Vec<UserInput>,
         previous_context: &mut Option<Arc<TurnContext>>,
     ) {
-        let (items, updates) = match op {
-            Op::UserTurn {
-                cwd,
-                approval_policy,
-                sandbox_policy,
-                model,
-                effort,
-                summary,
-                final_output_json_schema,
-                items,
-            } => (
-                items,
-                SessionSettingsUpdate {
-                    cwd: Some(cwd),
-                    approval_policy: Some(approval_policy),
-                    sandbox_policy: Some(sandbox_policy),
-                    model: Some(model),
-                    reasoning_effort: Some(effort),
-                    reasoning_summary: Some(summary),
-                    final_output_json_schema: Some(final_output_json_schema),
-                },
-            ),
-            Op::UserInput { items } => (items, SessionSettingsUpdate::default()),
-            _ => unreachable!(),
+        let items = match transcribe_audio_inputs(&current_context, items).await {
+            Ok(items) => items,
+            Err(err) => {
+                sess.send_event(&current_context, EventMsg::Error(err.to_error_event(None)))
+                    .await;
+                return;
+            }
         };
-
-        let current_context = sess.new_turn_with_sub_id(sub_id, updates).await;
         current_context
             .client
             .get_otel_event_manager()
@@ -1515,6 +3724,7 @@ mod handlers {
                 sess.record_conversation_items(&current_context, std::slice::from_ref(&env_item))
                     .await;
             }
+            sess.reload_user_instructions(&current_context).await;
 
             sess.spawn_task(Arc::clone(&current_context), items, RegularTask)
                 .await;
@@ -1540,6 +3750,110 @@ mod handlers {
         *previous_context = Some(turn_context);
     }
 
+    pub async fn run_background(sess: &Arc<Session>, sub_id: String, command: String) {
+        let turn_context = sess
+            .new_turn_with_sub_id(sub_id.clone(), SessionSettingsUpdate::default())
+            .await;
+        let use_login_shell = true;
+        let argv = sess
+            .user_shell()
+            .derive_exec_args(&command, use_login_shell);
+        let result = sess
+            .services
+            .background_tasks
+            .spawn(
+                sess.conversation_id,
+                command.clone(),
+                argv,
+                turn_context.cwd.clone(),
+                create_env(&turn_context.shell_environment_policy),
+                sess.get_tx_event(),
+                sub_id.clone(),
+            )
+            .await;
+        if let Err(err) = result {
+            let event = Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: format!("Failed to start background task `{command}`: {err}"),
+                    http_status_code: None,
+                    info: CodexErrorInfo::new(ErrorCode::Internal, ErrorOrigin::Codex),
+                }),
+            };
+            sess.send_event_raw(event).await;
+        }
+    }
+
+    pub async fn terminate_background(sess: &Session, sub_id: String, task_id: String) {
+        let result = sess
+            .services
+            .background_tasks
+            .terminate(sess.conversation_id, &task_id);
+        if let Err(err) = result {
+            let event = Event {
+                id: sub_id,
+                msg: EventMsg::Warning(WarningEvent {
+                    message: err.to_string(),
+                }),
+            };
+            sess.send_event_raw(event).await;
+        }
+    }
+
+    pub async fn worktree_diff(sess: &Session, sub_id: String) {
+        let msg = match sess.worktree_diff().await {
+            Ok(event) => EventMsg::WorktreeDiff(event),
+            Err(err) => EventMsg::Error(ErrorEvent {
+                message: format!("Failed to read the worktree diff: {err:#}"),
+                http_status_code: None,
+                info: CodexErrorInfo::new(ErrorCode::Internal, ErrorOrigin::Git),
+            }),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    pub async fn close_worktree(sess: &Session, sub_id: String, outcome: WorktreeOutcome) {
+        let msg = match sess.close_worktree(outcome).await {
+            Ok(event) => EventMsg::WorktreeClosed(event),
+            Err(err) => EventMsg::Error(ErrorEvent {
+                message: format!("Failed to close the worktree: {err:#}"),
+                http_status_code: None,
+                info: CodexErrorInfo::new(ErrorCode::Internal, ErrorOrigin::Git),
+            }),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    /// Pushing and talking to the forge can take a while, so this runs off
+    /// the submission loop.
+    pub async fn create_pull_request(
+        sess: &Arc<Session>,
+        sub_id: String,
+        request: PullRequestRequest,
+    ) {
+        let turn_context = sess
+            .new_turn_with_sub_id(sub_id.clone(), SessionSettingsUpdate::default())
+            .await;
+        let sess = Arc::clone(sess);
+        tokio::spawn(async move {
+            let msg = match pull_request::create_pull_request(
+                &turn_context.cwd,
+                &DefaultKeyringStore,
+                request,
+            )
+            .await
+            {
+                Ok(event) => EventMsg::PullRequestCreated(event),
+                Err(err) => EventMsg::Error(ErrorEvent {
+                    message: format!("Failed to open pull request: {err:#}"),
+                    http_status_code: None,
+                    info: CodexErrorInfo::new(ErrorCode::Internal, ErrorOrigin::Git),
+                }),
+            };
+            sess.send_event_raw(Event { id: sub_id, msg }).await;
+        });
+    }
+
     pub async fn exec_approval(sess: &Arc<Session>, id: String, decision: ReviewDecision) {
         match decision {
             ReviewDecision::Abort => {
@@ -1605,6 +3919,19 @@ mod handlers {
         });
     }
 
+    pub async fn get_patch_content(sess: &Session, sub_id: String, call_id: String, path: PathBuf) {
+        let change = sess.deferred_patch_change(&call_id, &path).await;
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::PatchContentResponse(PatchContentResponseEvent {
+                call_id,
+                path,
+                change,
+            }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
     pub async fn list_mcp_tools(sess: &Session, config: &Arc<Config>, sub_id: String) {
         let mcp_connection_manager = sess.services.mcp_connection_manager.read().await;
         let (tools, auth_status_entries, resources, resource_templates) = tokio::join!(
@@ -1652,6 +3979,119 @@ mod handlers {
         sess.send_event_raw(event).await;
     }
 
+    pub async fn resolve_elicitation(
+        sess: &Session,
+        sub_id: String,
+        server_name: String,
+        request_id: mcp_types::RequestId,
+        decision: ElicitationAction,
+        content: Option<serde_json::Value>,
+    ) {
+        if let Err(err) = sess
+            .services
+            .mcp_connection_manager
+            .read()
+            .await
+            .resolve_elicitation(server_name, request_id, decision, content)
+            .await
+        {
+            let event = Event {
+                id: sub_id,
+                msg: EventMsg::Warning(WarningEvent {
+                    message: format!("{err:#}"),
+                }),
+            };
+            sess.send_event_raw(event).await;
+        }
+    }
+
+    pub async fn list_mcp_prompts(sess: &Session, sub_id: String) {
+        let prompts = sess
+            .services
+            .mcp_connection_manager
+            .read()
+            .await
+            .list_all_prompts()
+            .await;
+        let event = Event {
+            id: sub_id,
+            msg: EventMsg::McpListPromptsResponse(McpListPromptsResponseEvent { prompts }),
+        };
+        sess.send_event_raw(event).await;
+    }
+
+    pub async fn read_mcp_resource(sess: &Session, sub_id: String, server: String, uri: String) {
+        let result = sess
+            .services
+            .mcp_connection_manager
+            .read()
+            .await
+            .read_resource(
+                &server,
+                mcp_types::ReadResourceRequestParams { uri: uri.clone() },
+            )
+            .await;
+        let msg = match result {
+            Ok(result) => {
+                let (contents, truncated) =
+                    limit_resource_contents(result.contents, MAX_MCP_CONTENT_BYTES);
+                EventMsg::McpResourceReadResponse(McpResourceReadResponseEvent {
+                    server,
+                    uri,
+                    contents,
+                    truncated,
+                })
+            }
+            Err(err) => EventMsg::Error(ErrorEvent {
+                message: format!("{err:#}"),
+                http_status_code: None,
+                info: CodexErrorInfo::new(ErrorCode::McpServerFailed, ErrorOrigin::McpServer),
+            }),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    pub async fn run_mcp_prompt(
+        sess: &Arc<Session>,
+        sub_id: String,
+        server: String,
+        name: String,
+        arguments: HashMap<String, String>,
+        previous_context: &mut Option<Arc<TurnContext>>,
+    ) {
+        let arguments = (!arguments.is_empty()).then(|| serde_json::json!(arguments));
+        let result = sess
+            .services
+            .mcp_connection_manager
+            .read()
+            .await
+            .get_prompt(&server, &name, arguments)
+            .await;
+        let items = match result {
+            Ok(prompt) => prompt_to_user_input(prompt),
+            Err(err) => {
+                let event = Event {
+                    id: sub_id,
+                    msg: EventMsg::Error(ErrorEvent {
+                        message: format!("{err:#}"),
+                        http_status_code: None,
+                        info: CodexErrorInfo::new(
+                            ErrorCode::McpServerFailed,
+                            ErrorOrigin::McpServer,
+                        ),
+                    }),
+                };
+                sess.send_event_raw(event).await;
+                return;
+            }
+        };
+        if items.is_empty() {
+            warn!("MCP prompt `{server}/{name}` expanded to no input; ignoring");
+            return;
+        }
+        user_input_or_turn(sess, sub_id, Op::UserInput { items }, previous_context).await;
+    }
+
     pub async fn undo(sess: &Arc<Session>, sub_id: String) {
         let turn_context = sess
             .new_turn_with_sub_id(sub_id, SessionSettingsUpdate::default())
@@ -1677,6 +4117,15 @@ mod handlers {
 
     pub async fn shutdown(sess: &Arc<Session>, sub_id: String) -> bool {
         sess.abort_all_tasks(TurnAbortReason::Interrupted).await;
+        sess.services
+            .background_tasks
+            .terminate_all(sess.conversation_id);
+        sess.services.mcp_startup_cancellation_token.cancel();
+        sess.services
+            .mcp_connection_manager
+            .write()
+            .await
+            .shutdown();
         info!("Shutting down Codex instance");
 
         // Gracefully flush and shutdown rollout recorder on session end so tests
@@ -1694,6 +4143,7 @@ mod handlers {
                 msg: EventMsg::Error(ErrorEvent {
                     message: "Failed to shutdown rollout recorder".to_string(),
                     http_status_code: None,
+                    info: CodexErrorInfo::default(),
                 }),
             };
             sess.send_event_raw(event).await;
@@ -1743,10 +4193,11 @@ async fn spawn_review_thread(
     review_features
         .disable(crate::features::Feature::WebSearchRequest)
         .disable(crate::features::Feature::ViewImageTool);
-    let tools_config = ToolsConfig::new(&ToolsConfigParams {
+    let mut tools_config = ToolsConfig::new(&ToolsConfigParams {
         model_family: &review_model_family,
         features: &review_features,
     });
+    tools_config.tool_filter = config.tool_filter.clone();
 
     let base_instructions = REVIEW_PROMPT.to_string();
     let review_prompt = review_request.prompt.clone();
@@ -1796,6 +4247,7 @@ async fn spawn_review_thread(
         sandbox_policy: parent_turn_context.sandbox_policy.clone(),
         shell_environment_policy: parent_turn_context.shell_environment_policy.clone(),
         cwd: parent_turn_context.cwd.clone(),
+        workspace_roots: parent_turn_context.workspace_roots.clone(),
         final_output_json_schema: None,
         codex_linux_sandbox_exe: parent_turn_context.codex_linux_sandbox_exe.clone(),
         tool_call_gate: Arc::new(ReadinessFlag::new()),
@@ -1848,6 +4300,28 @@ pub(crate) async fn run_task(
     });
     sess.send_event(&turn_context, event).await;
 
+    let pre_turn = HookPayload::PreTurn {
+        thread_id: sess.conversation_id.to_string(),
+        turn_id: turn_context.sub_id.clone(),
+        cwd: turn_context.cwd.clone(),
+        input_messages: input
+            .iter()
+            .filter_map(|item| match item {
+                UserInput::Text { text } => Some(text.clone()),
+                _ => None,
+            })
+            .collect(),
+    };
+    if let Err(blocked) = run_hooks(&turn_context, pre_turn).await {
+        let event = EventMsg::Error(ErrorEvent {
+            message: format!("Turn blocked: {blocked}"),
+            http_status_code: None,
+            info: CodexErrorInfo::new(ErrorCode::BadRequest, ErrorOrigin::Codex),
+        });
+        sess.send_event(&turn_context, event).await;
+        return None;
+    }
+
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
     sess.record_input_and_rollout_usermsg(turn_context.as_ref(), &initial_input_for_turn)
         .await;
@@ -1871,7 +4345,7 @@ pub(crate) async fn run_task(
             .collect::<Vec<ResponseItem>>();
 
         // Construct the input that we will send to the model.
-        let turn_input: Vec<ResponseItem> = {
+        let mut turn_input: Vec<ResponseItem> = {
             sess.record_conversation_items(&turn_context, &pending_input)
                 .await;
             sess.clone_history().await.get_history_for_prompt()
@@ -1885,6 +4359,17 @@ pub(crate) async fn run_task(
             })
             .map(|user_message| user_message.message())
             .collect::<Vec<String>>();
+        // Pinned context is re-read for every request and never recorded, so
+        // the model sees current contents without them piling up in history.
+        if let Some(pinned) = pinned_context_item(
+            &turn_context.client.config().codex_home,
+            sess.conversation_id,
+            pinned_context_budget(turn_context.client.get_model_context_window()),
+        )
+        .await
+        {
+            turn_input.push(pinned);
+        }
         match run_turn(
             Arc::clone(&sess),
             Arc::clone(&turn_context),
@@ -1898,6 +4383,7 @@ pub(crate) async fn run_task(
                 let TurnRunResult {
                     processed_items,
                     total_token_usage,
+                    stream_interrupted,
                 } = turn_output;
                 let limit = turn_context
                     .client
@@ -1923,7 +4409,9 @@ pub(crate) async fn run_task(
                     continue;
                 }
 
-                if responses.is_empty() {
+                // An interrupted stream leaves the model mid-response; ask it
+                // to carry on from the items that did arrive.
+                if responses.is_empty() && !stream_interrupted {
                     last_agent_message = get_last_assistant_message_from_turn(
                         &items_to_record_in_conversation_history,
                     );
@@ -1935,6 +4423,18 @@ pub(crate) async fn run_task(
                             input_messages: turn_input_messages,
                             last_assistant_message: last_agent_message.clone(),
                         });
+                    let post_turn = HookPayload::PostTurn {
+                        thread_id: sess.conversation_id.to_string(),
+                        turn_id: turn_context.sub_id.clone(),
+                        cwd: turn_context.cwd.clone(),
+                        last_assistant_message: last_agent_message.clone(),
+                    };
+                    if let Err(blocked) = run_hooks(&turn_context, post_turn).await {
+                        let event = EventMsg::Warning(WarningEvent {
+                            message: blocked.to_string(),
+                        });
+                        sess.send_event(&turn_context, event).await;
+                    }
                     break;
                 }
                 continue;
@@ -1948,7 +4448,14 @@ pub(crate) async fn run_task(
             }
             Err(e) => {
                 info!("Turn error: {e:#}");
-                sess.send_event(&turn_context, EventMsg::Error(e.to_error_event(None)))
+                let error_event = e.to_error_event(None);
+                sess.notifier().notify(&UserNotification::TurnFailed {
+                    thread_id: sess.conversation_id.to_string(),
+                    turn_id: turn_context.sub_id.clone(),
+                    cwd: turn_context.cwd.display().to_string(),
+                    error: error_event.message.clone(),
+                });
+                sess.send_event(&turn_context, EventMsg::Error(error_event))
                     .await;
                 // let the user continue the conversation
                 break;
@@ -1961,7 +4468,7 @@ pub(crate) async fn run_task(
 
 async fn run_turn(
     sess: Arc<Session>,
-    turn_context: Arc<TurnContext>,
+    mut turn_context: Arc<TurnContext>,
     turn_diff_tracker: SharedTurnDiffTracker,
     input: Vec<ResponseItem>,
     cancellation_token: CancellationToken,
@@ -1979,9 +4486,16 @@ async fn run_turn(
         Some(
             mcp_tools
                 .into_iter()
+                .filter(|(_, tool)| {
+                    turn_context
+                        .tools_config
+                        .tool_filter
+                        .allows_mcp_tool(&tool.server_name, &tool.tool_name)
+                })
                 .map(|(name, tool)| (name, tool.tool))
                 .collect(),
         ),
+        sess.services.plugins.as_deref(),
     ));
 
     let model_supports_parallel = turn_context
@@ -2015,10 +4529,38 @@ async fn run_turn(
         parallel_tool_calls,
         base_instructions_override: base_instructions,
         output_schema: turn_context.final_output_json_schema.clone(),
+        tool_filter: turn_context.tools_config.tool_filter.clone(),
     };
 
+    // While the provider is degraded, hold off until its cooldown expires
+    // rather than adding to the failures.
+    if let Some(cooldown) = sess.provider_cooldown_remaining().await {
+        warn!("provider degraded - waiting {cooldown:?} before sending the turn");
+        tokio::time::sleep(cooldown)
+            .or_cancel(&cancellation_token)
+            .await?;
+    }
+
+    // Near the end of a rate-limit window, wait for it to reset rather than
+    // have the provider reject requests partway through the turn.
+    while let Some(pause) = sess.rate_limit_pause(&turn_context).await {
+        warn!("rate limit nearly exhausted - waiting {pause:?} for the window to reset");
+        tokio::time::sleep(pause)
+            .or_cancel(&cancellation_token)
+            .await?;
+    }
+
+    // Start from the model an earlier turn fell back to, if any.
+    let unavailable = sess.state.lock().await.unavailable_models.clone();
+    let mut routed =
+        RoutedClient::new(&turn_context.client, ModelTask::Main).skipping(&unavailable);
+    if routed.client().get_model() != turn_context.client.get_model() {
+        turn_context = Arc::new(turn_context.with_client(routed.client().clone()));
+    }
     let mut retries = 0;
+    let mut degraded_wait = Duration::ZERO;
     loop {
+        let mut salvaged = Vec::new();
         match try_run_turn(
             Arc::clone(&router),
             Arc::clone(&sess),
@@ -2026,10 +4568,16 @@ async fn run_turn(
             Arc::clone(&turn_diff_tracker),
             &prompt,
             cancellation_token.child_token(),
+            u32::try_from(retries).unwrap_or(u32::MAX),
+            &mut salvaged,
         )
         .await
         {
-            Ok(output) => return Ok(output),
+            Ok(output) => {
+                sess.record_provider_success(&turn_context).await;
+                sess.record_model_usage(&routed).await;
+                return Ok(output);
+            }
             Err(CodexErr::TurnAborted {
                 dangling_artifacts: processed_items,
             }) => {
@@ -2054,15 +4602,30 @@ async fn run_turn(
             Err(CodexErr::UsageNotIncluded) => return Err(CodexErr::UsageNotIncluded),
             Err(e @ CodexErr::QuotaExceeded) => return Err(e),
             Err(e @ CodexErr::RefreshTokenFailed(_)) => return Err(e),
+            Err(e) if salvaged.is_empty() && routed.fall_back(&e) => {
+                sess.state
+                    .lock()
+                    .await
+                    .unavailable_models
+                    .extend(routed.unavailable_models().iter().cloned());
+                turn_context = Arc::new(turn_context.with_client(routed.client().clone()));
+                retries = 0;
+            }
             Err(e) => {
-                // Use the configured provider-specific stream retry budget.
+                let degraded =
+                    e.is_provider_outage() && sess.record_provider_failure(&turn_context, &e).await;
+                // Use the configured provider-specific stream retry budget;
+                // a degraded provider keeps being retried on its cooldown.
                 let max_retries = turn_context.client.get_provider().stream_max_retries();
-                if retries < max_retries {
+                if retries < max_retries || (degraded && degraded_wait < MAX_DEGRADED_WAIT) {
                     retries += 1;
-                    let delay = match e {
+                    let mut delay = match e {
                         CodexErr::Stream(_, Some(delay)) => delay,
                         _ => backoff(retries),
                     };
+                    if let Some(cooldown) = sess.provider_cooldown_remaining().await {
+                        delay = delay.max(cooldown);
+                    }
                     warn!(
                         "stream disconnected - retrying turn ({retries}/{max_retries} in {delay:?})...",
                     );
@@ -2070,14 +4633,25 @@ async fn run_turn(
                     // Surface retry information to any UI/front‑end so the
                     // user understands what is happening instead of staring
                     // at a seemingly frozen screen.
-                    sess.notify_stream_error(
-                        &turn_context,
-                        format!("Reconnecting... {retries}/{max_retries}"),
-                        e.http_status_code(),
-                    )
-                    .await;
+                    let message = if retries <= max_retries {
+                        format!("Reconnecting... {retries}/{max_retries}")
+                    } else {
+                        degraded_wait += delay;
+                        format!("Reconnecting in {}s...", delay.as_secs().max(1))
+                    };
+                    sess.notify_stream_error(&turn_context, message, &e).await;
 
                     tokio::time::sleep(delay).await;
+
+                    // Resume from what the dropped stream already delivered
+                    // instead of asking for the whole response again.
+                    if !salvaged.is_empty() {
+                        return Ok(TurnRunResult {
+                            processed_items: salvaged,
+                            total_token_usage: None,
+                            stream_interrupted: true,
+                        });
+                    }
                 } else {
                     return Err(e);
                 }
@@ -2096,12 +4670,20 @@ pub struct ProcessedResponseItem {
     pub response: Option<ResponseInputItem>,
 }
 
+/// Upper bound on the time a turn keeps retrying past its retry budget while
+/// the provider is degraded.
+const MAX_DEGRADED_WAIT: Duration = Duration::from_secs(10 * 60);
+
 #[derive(Debug)]
 struct TurnRunResult {
     processed_items: Vec<ProcessedResponseItem>,
     total_token_usage: Option<TokenUsage>,
+    /// The stream dropped before `response.completed`; `processed_items` is
+    /// what arrived before it did.
+    stream_interrupted: bool,
 }
 
+#[allow(clippy::too_many_arguments)]
 #[allow(clippy::too_many_arguments)]
 async fn try_run_turn(
     router: Arc<ToolRouter>,
@@ -2110,6 +4692,8 @@ async fn try_run_turn(
     turn_diff_tracker: SharedTurnDiffTracker,
     prompt: &Prompt,
     cancellation_token: CancellationToken,
+    attempt: u32,
+    salvaged: &mut Vec<ProcessedResponseItem>,
 ) -> CodexResult<TurnRunResult> {
     let rollout_item = RolloutItem::TurnContext(TurnContextItem {
         cwd: turn_context.cwd.clone(),
@@ -2121,6 +4705,7 @@ async fn try_run_turn(
     });
 
     sess.persist_rollout_items(&[rollout_item]).await;
+    sess.set_turn_activity(TurnActivity::WaitingOnModel);
     let mut stream = turn_context
         .client
         .clone()
@@ -2138,6 +4723,7 @@ async fn try_run_turn(
         FuturesOrdered::new();
 
     let mut active_item: Option<TurnItem> = None;
+    let mut produced = Vec::new();
 
     loop {
         // Poll the next item from the model stream. We must inspect *both* Ok and Err
@@ -2154,8 +4740,13 @@ async fn try_run_turn(
         };
 
         let event = match event {
-            Some(res) => res?,
+            Some(Ok(event)) => event,
+            Some(Err(err)) => {
+                *salvaged = output.try_collect().await?;
+                return Err(err);
+            }
             None => {
+                *salvaged = output.try_collect().await?;
                 return Err(CodexErr::Stream(
                     "stream closed before response.completed".into(),
                     None,
@@ -2171,6 +4762,7 @@ async fn try_run_turn(
             ResponseEvent::Created => {}
             ResponseEvent::OutputItemDone(item) => {
                 let previously_active_item = active_item.take();
+                produced.push(UsageAttribution::for_item(&item));
                 match ToolRouter::build_tool_call(sess.as_ref(), item.clone()).await {
                     Ok(Some(call)) => {
                         let payload_preview = call.payload.log_payload().into_owned();
@@ -2260,8 +4852,15 @@ async fn try_run_turn(
                 response_id: _,
                 token_usage,
             } => {
-                sess.update_token_usage_info(&turn_context, token_usage.as_ref())
-                    .await;
+                let request = token_usage.clone().map(|usage| RequestTokenUsage {
+                    turn_id: turn_context.sub_id.clone(),
+                    task: ModelTask::Main,
+                    model: turn_context.client.get_model(),
+                    attempt,
+                    items: std::mem::take(&mut produced),
+                    usage,
+                });
+                sess.update_token_usage_info(&turn_context, request).await;
                 let processed_items = output.try_collect().await?;
                 let unified_diff = {
                     let mut tracker = turn_diff_tracker.lock().await;
@@ -2275,6 +4874,7 @@ async fn try_run_turn(
                 let result = TurnRunResult {
                     processed_items,
                     total_token_usage: token_usage.clone(),
+                    stream_interrupted: false,
                 };
 
                 return Ok(result);
@@ -2389,13 +4989,15 @@ pub(crate) use tests::make_session_and_context;
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::ConfigOverrides;
     use crate::config::ConfigToml;
     use crate::exec::ExecToolCallOutput;
     use crate::tools::format_exec_output_str;
 
     use crate::protocol::CompactedItem;
+    use crate::protocol::ExecCommandBeginEvent;
     use crate::protocol::InitialHistory;
+    use crate::protocol::PatchApplyBeginEvent;
+    use crate::protocol::RateLimitWindow;
     use crate::protocol::ResumedHistory;
     use crate::state::TaskKind;
     use crate::tasks::SessionTask;
@@ -2500,6 +5102,7 @@ mod tests {
             aggregated_output: StreamOutput::new("Command output".to_string()),
             duration: StdDuration::from_secs(1),
             timed_out: true,
+            output_truncation: None,
         };
         let (_, turn_context) = make_session_and_context();
 
@@ -2617,10 +5220,14 @@ mod tests {
             approval_policy: config.approval_policy,
             sandbox_policy: config.sandbox_policy.clone(),
             cwd: config.cwd.clone(),
+            workspace_roots: config.workspace_roots.clone(),
+            token_budget: config.conversation_token_budget,
+            environment: HashMap::new(),
             original_config_do_not_use: Arc::clone(&config),
             features: Features::default(),
             exec_policy: Arc::new(codex_execpolicy2::Policy::empty()),
             session_source: SessionSource::Exec,
+            remote_enforced_keys: BTreeSet::new(),
         };
 
         let state = SessionState::new(session_configuration.clone());
@@ -2629,13 +5236,28 @@ mod tests {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: CancellationToken::new(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
-            notifier: UserNotifier::new(None),
+            notifier: UserNotifier::default(),
             rollout: Mutex::new(None),
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
-            auth_manager: Arc::clone(&auth_manager),
+            reasoning_redaction: config.reasoning_redaction,
+            exec_output: config.exec_output,
+            auth_manager: StdRwLock::new(Arc::clone(&auth_manager)),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            approval_rules: Mutex::new(ApprovalRuleStore::load(&config.codex_home, &config.cwd)),
+            write_grants: Mutex::new(WriteGrantStore::load(&config.codex_home, &config.cwd)),
+            network_proxy: None,
+            file_locks: FileLockManager::shared(),
+            provider_health: Mutex::new(ProviderHealth::default()),
+            rate_limit_scheduler: Arc::default(),
+            background_tasks: BackgroundTaskManager::shared(),
+            worktree: Mutex::new(None),
+            repo_map: RepoMapCache::default(),
+            lsp: LspManager::default(),
+            code_search: CodeSearchIndex::default(),
+            plugins: None,
+            sampling_model: SamplingModel::default(),
         };
 
         let turn_context = Session::make_turn_context(
@@ -2654,6 +5276,8 @@ mod tests {
             active_turn: Mutex::new(None),
             services,
             next_internal_sub_id: AtomicU64::new(0),
+            turn_activity: StdMutex::new(TurnActivity::default()),
+            command_items: StdMutex::new(HashMap::new()),
         };
 
         (session, turn_context)
@@ -2695,10 +5319,14 @@ mod tests {
             approval_policy: config.approval_policy,
             sandbox_policy: config.sandbox_policy.clone(),
             cwd: config.cwd.clone(),
+            workspace_roots: config.workspace_roots.clone(),
+            token_budget: config.conversation_token_budget,
+            environment: HashMap::new(),
             original_config_do_not_use: Arc::clone(&config),
             features: Features::default(),
             exec_policy: Arc::new(codex_execpolicy2::Policy::empty()),
             session_source: SessionSource::Exec,
+            remote_enforced_keys: BTreeSet::new(),
         };
 
         let state = SessionState::new(session_configuration.clone());
@@ -2707,13 +5335,28 @@ mod tests {
             mcp_connection_manager: Arc::new(RwLock::new(McpConnectionManager::default())),
             mcp_startup_cancellation_token: CancellationToken::new(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
-            notifier: UserNotifier::new(None),
+            notifier: UserNotifier::default(),
             rollout: Mutex::new(None),
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
-            auth_manager: Arc::clone(&auth_manager),
+            reasoning_redaction: config.reasoning_redaction,
+            exec_output: config.exec_output,
+            auth_manager: StdRwLock::new(Arc::clone(&auth_manager)),
             otel_event_manager: otel_event_manager.clone(),
             tool_approvals: Mutex::new(ApprovalStore::default()),
+            approval_rules: Mutex::new(ApprovalRuleStore::load(&config.codex_home, &config.cwd)),
+            write_grants: Mutex::new(WriteGrantStore::load(&config.codex_home, &config.cwd)),
+            network_proxy: None,
+            file_locks: FileLockManager::shared(),
+            provider_health: Mutex::new(ProviderHealth::default()),
+            rate_limit_scheduler: Arc::default(),
+            background_tasks: BackgroundTaskManager::shared(),
+            worktree: Mutex::new(None),
+            repo_map: RepoMapCache::default(),
+            lsp: LspManager::default(),
+            code_search: CodeSearchIndex::default(),
+            plugins: None,
+            sampling_model: SamplingModel::default(),
         };
 
         let turn_context = Arc::new(Session::make_turn_context(
@@ -2732,6 +5375,8 @@ mod tests {
             active_turn: Mutex::new(None),
             services,
             next_internal_sub_id: AtomicU64::new(0),
+            turn_activity: StdMutex::new(TurnActivity::default()),
+            command_items: StdMutex::new(HashMap::new()),
         });
 
         (session, turn_context, rx_event)
@@ -2766,6 +5411,194 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn profile_changes_lists_only_differing_settings() {
+        let (session, _turn_context) = make_session_and_context();
+        let mut previous = session.state.lock().await.session_configuration.clone();
+        previous.approval_policy = AskForApproval::OnRequest;
+        let mut next = previous.clone();
+        next.model = "o3".to_string();
+        next.approval_policy = AskForApproval::Never;
+
+        assert_eq!(
+            profile_changes(&previous, &next),
+            vec![
+                SettingChange {
+                    setting: "model".to_string(),
+                    previous: previous.model.clone(),
+                    current: "o3".to_string(),
+                },
+                SettingChange {
+                    setting: "approval_policy".to_string(),
+                    previous: "on-request".to_string(),
+                    current: "never".to_string(),
+                },
+            ]
+        );
+        assert_eq!(profile_changes(&previous, &previous), Vec::new());
+    }
+
+    fn workspace_write_without_tmp() -> SandboxPolicy {
+        SandboxPolicy::WorkspaceWrite {
+            writable_roots: Vec::new(),
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn workdir_must_stay_inside_the_workspace_under_workspace_write() {
+        let (_session, mut turn_context) = make_session_and_context();
+        let workspace = tempfile::tempdir().expect("workspace");
+        let outside = tempfile::tempdir().expect("outside");
+        std::fs::create_dir(workspace.path().join("sub")).expect("create sub");
+        turn_context.cwd = workspace.path().to_path_buf();
+        turn_context.sandbox_policy = workspace_write_without_tmp();
+        turn_context.approval_policy = AskForApproval::OnRequest;
+
+        assert_eq!(
+            turn_context.check_workdir(&workspace.path().join("sub"), false),
+            Ok(())
+        );
+        assert_eq!(
+            turn_context.check_workdir(&workspace.path().join("sub/.."), false),
+            Ok(())
+        );
+        let err = turn_context
+            .check_workdir(&workspace.path().join("../"), false)
+            .expect_err("parent of the workspace");
+        assert!(err.contains("request escalated permissions"), "{err}");
+        assert!(turn_context.check_workdir(outside.path(), false).is_err());
+        assert!(
+            turn_context
+                .check_workdir(&workspace.path().join("missing"), true)
+                .is_err()
+        );
+
+        assert_eq!(turn_context.check_workdir(outside.path(), true), Ok(()));
+        turn_context.sandbox_policy = SandboxPolicy::DangerFullAccess;
+        assert_eq!(turn_context.check_workdir(outside.path(), false), Ok(()));
+    }
+
+    #[tokio::test]
+    async fn set_cwd_moves_only_within_the_writable_roots() {
+        let (session, _turn_context) = make_session_and_context();
+        let workspace = tempfile::tempdir().expect("workspace");
+        let outside = tempfile::tempdir().expect("outside");
+        std::fs::create_dir(workspace.path().join("sub")).expect("create sub");
+        let workspace_path = dunce::canonicalize(workspace.path()).expect("canonicalize");
+        {
+            let mut state = session.state.lock().await;
+            state.session_configuration.cwd = workspace_path.clone();
+            state.session_configuration.sandbox_policy = workspace_write_without_tmp();
+        }
+
+        session
+            .set_cwd(PathBuf::from("sub"))
+            .await
+            .expect("move into sub");
+        assert!(session.set_cwd(outside.path().to_path_buf()).await.is_err());
+        assert!(session.set_cwd(PathBuf::from("missing")).await.is_err());
+
+        let state = session.state.lock().await;
+        assert_eq!(state.session_configuration.cwd, workspace_path.join("sub"));
+    }
+
+    fn primary_rate_limits(used_percent: f64) -> RateLimitSnapshot {
+        RateLimitSnapshot {
+            primary: Some(RateLimitWindow {
+                used_percent,
+                window_minutes: Some(300),
+                resets_at: None,
+            }),
+            secondary: None,
+            credits: None,
+        }
+    }
+
+    /// Feeds `usage` to the session one snapshot at a time and returns the
+    /// usage percentages that produced a rate-limit warning.
+    async fn rate_limit_warnings(session: &Session, usage: &[f64]) -> Vec<f64> {
+        let mut state = session.state.lock().await;
+        let mut warned = Vec::new();
+        for &used_percent in usage {
+            for (window, limits) in state.set_rate_limits(primary_rate_limits(used_percent)) {
+                assert_eq!(window, "primary");
+                warned.push(limits.used_percent);
+            }
+        }
+        warned
+    }
+
+    #[tokio::test]
+    async fn rate_limit_thresholds_each_warn_once_as_usage_climbs() {
+        let (session, _turn_context) = make_session_and_context();
+
+        let warned =
+            rate_limit_warnings(&session, &[10.0, 50.0, 75.0, 80.0, 90.0, 94.0, 95.0, 99.0]).await;
+
+        assert_eq!(warned, vec![75.0, 90.0, 95.0]);
+    }
+
+    #[tokio::test]
+    async fn rate_limit_warning_is_not_repeated_while_usage_stays_above_threshold() {
+        let (session, _turn_context) = make_session_and_context();
+
+        let warned = rate_limit_warnings(&session, &[91.0, 91.0, 92.0, 93.5]).await;
+
+        assert_eq!(warned, vec![91.0]);
+    }
+
+    #[tokio::test]
+    async fn rate_limit_warning_fires_again_after_usage_drops_below_threshold() {
+        let (session, _turn_context) = make_session_and_context();
+
+        // A window reset drops usage below every threshold; usage dipping
+        // under a single threshold re-arms only that one.
+        let warned =
+            rate_limit_warnings(&session, &[80.0, 5.0, 80.0, 96.0, 92.0, 96.0, 91.0]).await;
+
+        assert_eq!(warned, vec![80.0, 80.0, 96.0, 96.0]);
+    }
+
+    #[tokio::test]
+    async fn heartbeat_reports_the_running_command() {
+        let (session, turn_context) = make_session_and_context();
+        session
+            .send_event(
+                &turn_context,
+                EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+                    call_id: "call-1".to_string(),
+                    turn_id: turn_context.sub_id.clone(),
+                    command: vec!["cargo".to_string(), "test".to_string()],
+                    cwd: turn_context.cwd.clone(),
+                    parsed_cmd: Vec::new(),
+                    source: Default::default(),
+                    interaction_input: None,
+                }),
+            )
+            .await;
+        assert_eq!(
+            session.turn_activity(),
+            TurnActivity::RunningCommand {
+                command: "cargo test".to_string(),
+            }
+        );
+
+        session
+            .send_event(
+                &turn_context,
+                EventMsg::PatchApplyBegin(PatchApplyBeginEvent {
+                    call_id: "call-2".to_string(),
+                    auto_approved: true,
+                    changes: HashMap::new(),
+                }),
+            )
+            .await;
+        assert_eq!(session.turn_activity(), TurnActivity::ApplyingPatch);
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
     #[test_log::test]
     async fn abort_regular_task_emits_turn_aborted_only() {
@@ -2904,6 +5737,7 @@ mod tests {
                     .map(|(name, tool)| (name, tool.tool))
                     .collect(),
             ),
+            None,
         );
         let item = ResponseItem::CustomToolCall {
             id: None,
@@ -12,6 +12,9 @@ use codex_protocol::protocol::Op;
 use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::SubAgentSource;
 use codex_protocol::protocol::Submission;
+use codex_protocol::protocol::UserInputRequestEvent;
+use codex_protocol::protocol::WriteScopeApprovalRequestEvent;
+use codex_protocol::protocol::WriteScopeDecision;
 use codex_protocol::user_input::UserInput;
 use tokio_util::sync::CancellationToken;
 
@@ -30,22 +33,30 @@ use codex_protocol::protocol::InitialHistory;
 /// The returned `events_rx` yields non-approval events emitted by the sub-agent.
 /// Approval requests are handled via `parent_session` and are not surfaced.
 /// The returned `ops_tx` allows the caller to submit additional `Op`s to the sub-agent.
+/// `source` is recorded as the sub-agent's `SessionSource::SubAgent`.
 pub(crate) async fn run_codex_conversation_interactive(
-    config: Config,
+    mut config: Config,
     auth_manager: Arc<AuthManager>,
     parent_session: Arc<Session>,
     parent_ctx: Arc<TurnContext>,
     cancel_token: CancellationToken,
     initial_history: Option<InitialHistory>,
-) -> Result<Codex, CodexErr> {
+    source: SubAgentSource,
+) -> Result<CodexSpawnOk, CodexErr> {
     let (tx_sub, rx_sub) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
     let (tx_ops, rx_ops) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
+    // Sub-agents work in the parent's checkout, which may itself be the
+    // parent's isolated worktree.
+    config.worktree_isolation = false;
 
-    let CodexSpawnOk { codex, .. } = Codex::spawn(
+    let CodexSpawnOk {
+        codex,
+        conversation_id,
+    } = Codex::spawn(
         config,
         auth_manager,
         initial_history.unwrap_or(InitialHistory::New),
-        SessionSource::SubAgent(SubAgentSource::Review),
+        SessionSource::SubAgent(source),
     )
     .await?;
     let codex = Arc::new(codex);
@@ -77,16 +88,20 @@ pub(crate) async fn run_codex_conversation_interactive(
         forward_ops(codex_for_ops, rx_ops, cancel_token_ops).await;
     });
 
-    Ok(Codex {
-        next_id: AtomicU64::new(0),
-        tx_sub: tx_ops,
-        rx_event: rx_sub,
+    Ok(CodexSpawnOk {
+        codex: Codex {
+            next_id: AtomicU64::new(0),
+            tx_sub: tx_ops,
+            rx_event: rx_sub,
+        },
+        conversation_id,
     })
 }
 
 /// Convenience wrapper for one-time use with an initial prompt.
 ///
 /// Internally calls the interactive variant, then immediately submits the provided input.
+#[allow(clippy::too_many_arguments)]
 pub(crate) async fn run_codex_conversation_one_shot(
     config: Config,
     auth_manager: Arc<AuthManager>,
@@ -95,17 +110,22 @@ pub(crate) async fn run_codex_conversation_one_shot(
     parent_ctx: Arc<TurnContext>,
     cancel_token: CancellationToken,
     initial_history: Option<InitialHistory>,
-) -> Result<Codex, CodexErr> {
+    source: SubAgentSource,
+) -> Result<CodexSpawnOk, CodexErr> {
     // Use a child token so we can stop the delegate after completion without
     // requiring the caller to cancel the parent token.
     let child_cancel = cancel_token.child_token();
-    let io = run_codex_conversation_interactive(
+    let CodexSpawnOk {
+        codex: io,
+        conversation_id,
+    } = run_codex_conversation_interactive(
         config,
         auth_manager,
         parent_session,
         parent_ctx,
         child_cancel.clone(),
         initial_history,
+        source,
     )
     .await?;
 
@@ -142,10 +162,13 @@ pub(crate) async fn run_codex_conversation_one_shot(
     let (tx_closed, rx_closed) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
     drop(rx_closed);
 
-    Ok(Codex {
-        next_id: AtomicU64::new(0),
-        rx_event: rx_bridge,
-        tx_sub: tx_closed,
+    Ok(CodexSpawnOk {
+        codex: Codex {
+            next_id: AtomicU64::new(0),
+            rx_event: rx_bridge,
+            tx_sub: tx_closed,
+        },
+        conversation_id,
     })
 }
 
@@ -196,6 +219,33 @@ async fn forward_events(
                 )
                 .await;
             }
+            Event {
+                id,
+                msg: EventMsg::WriteScopeApprovalRequest(event),
+            } => {
+                handle_write_scope_approval(
+                    &codex,
+                    id,
+                    &parent_session,
+                    &parent_ctx,
+                    event,
+                    &cancel_token,
+                )
+                .await;
+            }
+            Event {
+                id: _,
+                msg: EventMsg::UserInputRequest(event),
+            } => {
+                handle_user_input_request(
+                    &codex,
+                    &parent_session,
+                    &parent_ctx,
+                    event,
+                    &cancel_token,
+                )
+                .await;
+            }
             other => {
                 let _ = tx_sub.send(other).await;
             }
@@ -263,6 +313,7 @@ async fn handle_patch_approval(
             event.changes,
             event.reason,
             event.grant_root,
+            event.file_statuses,
         )
         .await;
     let decision = await_approval_with_cancel(
@@ -275,6 +326,59 @@ async fn handle_patch_approval(
     let _ = codex.submit(Op::PatchApproval { id, decision }).await;
 }
 
+/// Handle a WriteScopeApprovalRequest by consulting the parent session and
+/// replying. The sub-agent records the grant itself.
+async fn handle_write_scope_approval(
+    codex: &Codex,
+    id: String,
+    parent_session: &Session,
+    parent_ctx: &TurnContext,
+    event: WriteScopeApprovalRequestEvent,
+    cancel_token: &CancellationToken,
+) {
+    let decision_fut = parent_session.request_write_scope(
+        parent_ctx,
+        parent_ctx.sub_id.clone(),
+        event.root,
+        event.paths,
+        event.command,
+        event.reason,
+    );
+    let decision = tokio::select! {
+        biased;
+        _ = cancel_token.cancelled() => {
+            parent_session
+                .notify_write_scope(&parent_ctx.sub_id, WriteScopeDecision::Deny)
+                .await;
+            WriteScopeDecision::Deny
+        }
+        decision = decision_fut => decision,
+    };
+    let _ = codex.submit(Op::WriteScopeApproval { id, decision }).await;
+}
+
+/// Handle a UserInputRequest by asking the user through the parent session
+/// and passing the answer back to the sub-agent.
+async fn handle_user_input_request(
+    codex: &Codex,
+    parent_session: &Session,
+    parent_ctx: &TurnContext,
+    event: UserInputRequestEvent,
+    cancel_token: &CancellationToken,
+) {
+    let call_id = event.call_id;
+    let answer_fut = parent_session.request_user_input(parent_ctx, call_id.clone(), event.question);
+    let answer = tokio::select! {
+        biased;
+        _ = cancel_token.cancelled() => {
+            parent_session.notify_user_input(&call_id, None).await;
+            None
+        }
+        answer = answer_fut => answer,
+    };
+    let _ = codex.submit(Op::UserInputAnswer { call_id, answer }).await;
+}
+
 /// Await an approval decision, aborting on cancellation.
 async fn await_approval_with_cancel<F>(
     fut: F,
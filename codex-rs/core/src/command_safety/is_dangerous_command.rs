@@ -3,8 +3,8 @@ use codex_protocol::protocol::SandboxPolicy;
 
 use crate::sandboxing::SandboxPermissions;
 
-use crate::bash::parse_shell_lc_plain_commands;
 use crate::is_safe_command::is_known_safe_command;
+use crate::parse_command::parse_shell_plain_commands;
 
 pub fn requires_initial_appoval(
     policy: AskForApproval,
@@ -40,8 +40,9 @@ pub fn command_might_be_dangerous(command: &[String]) -> bool {
         return true;
     }
 
-    // Support `bash -lc "<script>"` where the any part of the script might contain a dangerous command.
-    if let Some(all_commands) = parse_shell_lc_plain_commands(command)
+    // Support `bash -lc "<script>"` (and its PowerShell and cmd.exe
+    // equivalents) where any part of the script might contain a dangerous command.
+    if let Some(all_commands) = parse_shell_plain_commands(command)
         && all_commands
             .iter()
             .any(|cmd| is_dangerous_to_call_with_exec(cmd))
@@ -1,5 +1,5 @@
-use crate::bash::parse_shell_lc_plain_commands;
 use crate::command_safety::windows_safe_commands::is_safe_command_windows;
+use crate::parse_command::parse_shell_plain_commands;
 
 pub fn is_known_safe_command(command: &[String]) -> bool {
     let command: Vec<String> = command
@@ -21,13 +21,14 @@ pub fn is_known_safe_command(command: &[String]) -> bool {
         return true;
     }
 
-    // Support `bash -lc "..."` where the script consists solely of one or
-    // more "plain" commands (only bare words / quoted strings) combined with
-    // a conservative allow‑list of shell operators that themselves do not
-    // introduce side effects ( "&&", "||", ";", and "|" ). If every
+    // Support `bash -lc "..."` (and `powershell -Command` / `cmd /c`) where
+    // the script consists solely of one or more "plain" commands (only bare
+    // words / quoted strings) combined with a conservative allow‑list of
+    // shell operators that themselves do not introduce side effects ( "&&",
+    // "||", ";", and "|" ). If every
     // individual command in the script is itself a known‑safe command, then
     // the composite expression is considered safe.
-    if let Some(all_commands) = parse_shell_lc_plain_commands(&command)
+    if let Some(all_commands) = parse_shell_plain_commands(&command)
         && !all_commands.is_empty()
         && all_commands
             .iter()
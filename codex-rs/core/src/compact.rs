@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use crate::Prompt;
+use crate::client::ModelClient;
 use crate::client_common::ResponseEvent;
 use crate::codex::Session;
 use crate::codex::TurnContext;
@@ -8,12 +9,17 @@ use crate::codex::get_last_assistant_message_from_turn;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
 use crate::features::Feature;
+use crate::model_router::RoutedClient;
 use crate::protocol::AgentMessageEvent;
 use crate::protocol::CompactedItem;
+use crate::protocol::CompactionTrigger;
+use crate::protocol::ContextCompactedEvent;
 use crate::protocol::EventMsg;
 use crate::protocol::TaskStartedEvent;
 use crate::protocol::TurnContextItem;
 use crate::protocol::WarningEvent;
+use crate::response_cache::ResponseCache;
+use crate::response_cache::ResponseCacheKey;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::approx_token_count;
 use crate::truncate::truncate_text;
@@ -23,7 +29,10 @@ use codex_protocol::items::TurnItem;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ModelTask;
+use codex_protocol::protocol::RequestTokenUsage;
 use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::UsageAttribution;
 use codex_protocol::user_input::UserInput;
 use futures::prelude::*;
 use tracing::error;
@@ -34,8 +43,7 @@ const COMPACT_USER_MESSAGE_MAX_TOKENS: usize = 20_000;
 
 pub(crate) async fn should_use_remote_compact_task(session: &Session) -> bool {
     session
-        .services
-        .auth_manager
+        .auth_manager()
         .auth()
         .is_some_and(|auth| auth.mode == AuthMode::ChatGPT)
         && session.enabled(Feature::RemoteCompaction).await
@@ -48,7 +56,7 @@ pub(crate) async fn run_inline_auto_compact_task(
     let prompt = turn_context.compact_prompt().to_string();
     let input = vec![UserInput::Text { text: prompt }];
 
-    run_compact_task_inner(sess, turn_context, input).await;
+    run_compact_task_inner(sess, turn_context, input, CompactionTrigger::Auto).await;
 }
 
 pub(crate) async fn run_compact_task(
@@ -60,14 +68,16 @@ pub(crate) async fn run_compact_task(
         model_context_window: turn_context.client.get_model_context_window(),
     });
     sess.send_event(&turn_context, start_event).await;
-    run_compact_task_inner(sess.clone(), turn_context, input).await;
+    run_compact_task_inner(sess.clone(), turn_context, input, CompactionTrigger::Manual).await;
 }
 
 async fn run_compact_task_inner(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
     input: Vec<UserInput>,
+    trigger: CompactionTrigger,
 ) {
+    let tokens_before = sess.tokens_in_context_window().await;
     let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input);
 
     let mut history = sess.clone_history().await;
@@ -80,6 +90,7 @@ async fn run_compact_task_inner(
 
     let max_retries = turn_context.client.get_provider().stream_max_retries();
     let mut retries = 0;
+    let mut routed = RoutedClient::new(&turn_context.client, ModelTask::Summarization);
 
     let rollout_item = RolloutItem::TurnContext(TurnContextItem {
         cwd: turn_context.cwd.clone(),
@@ -97,7 +108,14 @@ async fn run_compact_task_inner(
             input: turn_input.clone(),
             ..Default::default()
         };
-        let attempt_result = drain_to_completed(&sess, turn_context.as_ref(), &prompt).await;
+        let attempt_result = drain_to_completed(
+            &sess,
+            turn_context.as_ref(),
+            routed.client(),
+            &prompt,
+            u32::try_from(retries).unwrap_or(u32::MAX),
+        )
+        .await;
 
         match attempt_result {
             Ok(()) => {
@@ -110,6 +128,7 @@ async fn run_compact_task_inner(
                     )
                     .await;
                 }
+                sess.record_model_usage(&routed).await;
                 break;
             }
             Err(CodexErr::Interrupted) => {
@@ -131,6 +150,10 @@ async fn run_compact_task_inner(
                     .await;
                 return;
             }
+            Err(e) if routed.fall_back(&e) => {
+                retries = 0;
+                continue;
+            }
             Err(e) => {
                 if retries < max_retries {
                     retries += 1;
@@ -138,7 +161,7 @@ async fn run_compact_task_inner(
                     sess.notify_stream_error(
                         turn_context.as_ref(),
                         format!("Reconnecting... {retries}/{max_retries}"),
-                        e.http_status_code(),
+                        &e,
                     )
                     .await;
                     tokio::time::sleep(delay).await;
@@ -168,6 +191,12 @@ async fn run_compact_task_inner(
     new_history.extend(ghost_snapshots);
     sess.replace_history(new_history).await;
     sess.recompute_token_usage(&turn_context).await;
+    let compacted = EventMsg::ContextCompacted(ContextCompactedEvent {
+        trigger,
+        tokens_before,
+        tokens_after: sess.tokens_in_context_window().await,
+    });
+    sess.send_event(&turn_context, compacted).await;
 
     let rollout_item = RolloutItem::Compacted(CompactedItem {
         message: summary_text.clone(),
@@ -292,9 +321,24 @@ fn build_compacted_history_with_limit(
 async fn drain_to_completed(
     sess: &Session,
     turn_context: &TurnContext,
+    client: &ModelClient,
     prompt: &Prompt,
+    attempt: u32,
 ) -> CodexResult<()> {
-    let mut stream = turn_context.client.clone().stream(prompt).await?;
+    let model = client.get_model();
+    let cache = ResponseCache::from_config(&client.config()).map(|cache| {
+        let key = ResponseCacheKey::for_prompt(&model, &client.get_model_family(), prompt);
+        (cache, key)
+    });
+    if let Some((cache, key)) = &cache
+        && let Some(items) = cache.get(key).await
+    {
+        sess.record_into_history(&items, turn_context).await;
+        return Ok(());
+    }
+
+    let mut stream = client.clone().stream(prompt).await?;
+    let mut output_items = Vec::new();
     loop {
         let maybe_event = stream.next().await;
         let Some(event) = maybe_event else {
@@ -307,13 +351,30 @@ async fn drain_to_completed(
             Ok(ResponseEvent::OutputItemDone(item)) => {
                 sess.record_into_history(std::slice::from_ref(&item), turn_context)
                     .await;
+                output_items.push(item);
             }
             Ok(ResponseEvent::RateLimits(snapshot)) => {
                 sess.update_rate_limits(turn_context, snapshot).await;
             }
             Ok(ResponseEvent::Completed { token_usage, .. }) => {
-                sess.update_token_usage_info(turn_context, token_usage.as_ref())
-                    .await;
+                let request = token_usage.map(|usage| RequestTokenUsage {
+                    turn_id: turn_context.sub_id.clone(),
+                    task: ModelTask::Summarization,
+                    model: model.clone(),
+                    attempt,
+                    items: output_items
+                        .iter()
+                        .map(|item| UsageAttribution {
+                            id: UsageAttribution::for_item(item).id,
+                            source: "compaction".to_string(),
+                        })
+                        .collect(),
+                    usage,
+                });
+                sess.update_token_usage_info(turn_context, request).await;
+                if let Some((cache, key)) = &cache {
+                    cache.put(key, &model, &output_items).await;
+                }
                 return Ok(());
             }
             Ok(_) => continue,
@@ -6,6 +6,8 @@ use crate::codex::TurnContext;
 use crate::error::Result as CodexResult;
 use crate::protocol::AgentMessageEvent;
 use crate::protocol::CompactedItem;
+use crate::protocol::CompactionTrigger;
+use crate::protocol::ContextCompactedEvent;
 use crate::protocol::EventMsg;
 use crate::protocol::RolloutItem;
 use crate::protocol::TaskStartedEvent;
@@ -15,7 +17,7 @@ pub(crate) async fn run_inline_remote_auto_compact_task(
     sess: Arc<Session>,
     turn_context: Arc<TurnContext>,
 ) {
-    run_remote_compact_task_inner(&sess, &turn_context).await;
+    run_remote_compact_task_inner(&sess, &turn_context, CompactionTrigger::Auto).await;
 }
 
 pub(crate) async fn run_remote_compact_task(sess: Arc<Session>, turn_context: Arc<TurnContext>) {
@@ -24,11 +26,15 @@ pub(crate) async fn run_remote_compact_task(sess: Arc<Session>, turn_context: Ar
     });
     sess.send_event(&turn_context, start_event).await;
 
-    run_remote_compact_task_inner(&sess, &turn_context).await;
+    run_remote_compact_task_inner(&sess, &turn_context, CompactionTrigger::Manual).await;
 }
 
-async fn run_remote_compact_task_inner(sess: &Arc<Session>, turn_context: &Arc<TurnContext>) {
-    if let Err(err) = run_remote_compact_task_inner_impl(sess, turn_context).await {
+async fn run_remote_compact_task_inner(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+    trigger: CompactionTrigger,
+) {
+    if let Err(err) = run_remote_compact_task_inner_impl(sess, turn_context, trigger).await {
         let event = err.to_error_event(Some("Error running remote compact task".to_string()));
         sess.send_event(turn_context, EventMsg::Error(event)).await;
     }
@@ -37,7 +43,9 @@ async fn run_remote_compact_task_inner(sess: &Arc<Session>, turn_context: &Arc<T
 async fn run_remote_compact_task_inner_impl(
     sess: &Arc<Session>,
     turn_context: &Arc<TurnContext>,
+    trigger: CompactionTrigger,
 ) -> CodexResult<()> {
+    let tokens_before = sess.tokens_in_context_window().await;
     let mut history = sess.clone_history().await;
     let prompt = Prompt {
         input: history.get_history_for_prompt(),
@@ -45,6 +53,7 @@ async fn run_remote_compact_task_inner_impl(
         parallel_tool_calls: false,
         base_instructions_override: turn_context.base_instructions.clone(),
         output_schema: None,
+        tool_filter: turn_context.tools_config.tool_filter.clone(),
     };
 
     let mut new_history = turn_context
@@ -64,6 +73,12 @@ async fn run_remote_compact_task_inner_impl(
     }
     sess.replace_history(new_history.clone()).await;
     sess.recompute_token_usage(turn_context).await;
+    let compacted = EventMsg::ContextCompacted(ContextCompactedEvent {
+        trigger,
+        tokens_before,
+        tokens_after: sess.tokens_in_context_window().await,
+    });
+    sess.send_event(turn_context, compacted).await;
 
     let compacted_item = CompactedItem {
         message: String::new(),
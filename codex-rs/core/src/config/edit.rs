@@ -49,7 +49,9 @@ pub enum ConfigEdit {
 
 // TODO(jif) move to a dedicated file
 mod document_helpers {
+    use crate::config::types::McpSamplingApproval;
     use crate::config::types::McpServerConfig;
+    use crate::config::types::McpServerStartup;
     use crate::config::types::McpServerTransportConfig;
     use toml_edit::Array as TomlArray;
     use toml_edit::InlineTable;
@@ -122,6 +124,7 @@ mod document_helpers {
                 bearer_token_env_var,
                 http_headers,
                 env_http_headers,
+                scopes,
             } => {
                 entry["url"] = value(url.clone());
                 if let Some(env_var) = bearer_token_env_var {
@@ -137,6 +140,11 @@ mod document_helpers {
                 {
                     entry["env_http_headers"] = table_from_pairs(headers.iter());
                 }
+                if let Some(scopes) = scopes
+                    && !scopes.is_empty()
+                {
+                    entry["scopes"] = array_from_iter(scopes.iter().cloned());
+                }
             }
         }
 
@@ -159,6 +167,34 @@ mod document_helpers {
         {
             entry["disabled_tools"] = array_from_iter(disabled_tools.iter().cloned());
         }
+        if config.startup == McpServerStartup::Lazy {
+            entry["startup"] = value("lazy");
+        }
+        if let Some(timeout) = config.idle_timeout_sec {
+            entry["idle_timeout_sec"] = value(timeout.as_secs_f64());
+        }
+        if config.warm_pool {
+            entry["warm_pool"] = value(true);
+        }
+        if let Some(aliases) = &config.tool_aliases
+            && !aliases.is_empty()
+        {
+            entry["tool_aliases"] = table_from_pairs(aliases.iter());
+        }
+        if let Some(sampling) = &config.sampling {
+            let mut table = TomlTable::new();
+            table.set_implicit(false);
+            if let Some(max_tokens) = sampling.max_tokens {
+                table["max_tokens"] = value(i64::from(max_tokens));
+            }
+            if let Some(models) = &sampling.models {
+                table["models"] = array_from_iter(models.iter().cloned());
+            }
+            if sampling.approval == McpSamplingApproval::Auto {
+                table["approval"] = value("auto");
+            }
+            entry["sampling"] = TomlItem::Table(table);
+        }
 
         TomlItem::Table(entry)
     }
@@ -577,6 +613,7 @@ impl ConfigEditsBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::types::McpServerStartup;
     use crate::config::types::McpServerTransportConfig;
     use codex_protocol::config_types::ReasoningEffort;
     use pretty_assertions::assert_eq;
@@ -903,6 +940,11 @@ existing = "value"
                 tool_timeout_sec: None,
                 enabled_tools: Some(vec!["one".to_string(), "two".to_string()]),
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         );
 
@@ -918,12 +960,18 @@ existing = "value"
                             .collect(),
                     ),
                     env_http_headers: None,
+                    scopes: Some(vec!["read".to_string()]),
                 },
                 enabled: false,
                 startup_timeout_sec: Some(std::time::Duration::from_secs(5)),
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: Some(vec!["forbidden".to_string()]),
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         );
 
@@ -939,6 +987,7 @@ existing = "value"
 [mcp_servers.http]
 url = \"https://example.com\"
 bearer_token_env_var = \"TOKEN\"
+scopes = [\"read\"]
 enabled = false
 startup_timeout_sec = 5.0
 disabled_tools = [\"forbidden\"]
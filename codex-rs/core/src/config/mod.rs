@@ -1,14 +1,31 @@
 use crate::auth::AuthCredentialsStoreMode;
+use crate::auth::account_credentials_home;
+use crate::auth::validate_account_label;
+use crate::config::types::AppServerConfig;
+use crate::config::types::AudioTranscriptionConfig;
+use crate::config::types::CodeSearchConfig;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
+use crate::config::types::ExecOutputConfig;
+use crate::config::types::ExperimentToml;
+use crate::config::types::ExperimentVariantToml;
 use crate::config::types::History;
+use crate::config::types::HooksConfig;
+use crate::config::types::LspServerConfig;
 use crate::config::types::McpServerConfig;
+use crate::config::types::ModelPrice;
+use crate::config::types::ModelRoutingConfig;
 use crate::config::types::Notice;
+use crate::config::types::NotificationWebhookConfig;
 use crate::config::types::Notifications;
 use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
 use crate::config::types::OtelExporterKind;
+use crate::config::types::ReasoningRedaction;
 use crate::config::types::ReasoningSummaryFormat;
+use crate::config::types::RemoteConfigToml;
+use crate::config::types::ResponseCacheConfig;
 use crate::config::types::SandboxWorkspaceWrite;
+use crate::config::types::ScheduleConfig;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
 use crate::config::types::Tui;
@@ -17,6 +34,8 @@ use crate::config_loader::LoadedConfigLayers;
 use crate::config_loader::load_config_as_toml;
 use crate::config_loader::load_config_layers_with_overrides;
 use crate::config_loader::merge_toml_values;
+use crate::experiments::ExperimentAssignment;
+use crate::experiments::assign_variant;
 use crate::features::Feature;
 use crate::features::FeatureOverrides;
 use crate::features::Features;
@@ -34,6 +53,7 @@ use crate::project_doc::DEFAULT_PROJECT_DOC_FILENAME;
 use crate::project_doc::LOCAL_PROJECT_DOC_FILENAME;
 use crate::protocol::AskForApproval;
 use crate::protocol::SandboxPolicy;
+use crate::tool_filter::ToolFilter;
 use codex_app_server_protocol::Tools;
 use codex_app_server_protocol::UserSavedConfig;
 use codex_protocol::config_types::ForcedLoginMethod;
@@ -43,23 +63,29 @@ use codex_protocol::config_types::SandboxMode;
 use codex_protocol::config_types::TrustLevel;
 use codex_protocol::config_types::Verbosity;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
+use codex_utils_http_client::HttpClientSettings;
 use dirs::home_dir;
 use dunce::canonicalize;
 use serde::Deserialize;
 use similar::DiffableStr;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::io::ErrorKind;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::config::profile::ConfigProfile;
+use crate::config::template::ThreadTemplate;
 use toml::Value as TomlValue;
 use toml_edit::DocumentMut;
 
 pub mod edit;
 pub mod profile;
+pub mod template;
 pub mod types;
+pub mod validate;
 
 pub const OPENAI_DEFAULT_MODEL: &str = "gpt-5.1-codex";
 const OPENAI_DEFAULT_REVIEW_MODEL: &str = "gpt-5.1-codex";
@@ -72,6 +98,12 @@ pub(crate) const PROJECT_DOC_MAX_BYTES: usize = 32 * 1024; // 32 KiB
 
 pub(crate) const CONFIG_TOML_FILE: &str = "config.toml";
 
+/// Default interval between heartbeats of a running turn.
+const DEFAULT_TURN_HEARTBEAT_INTERVAL_MS: u64 = 15_000;
+
+/// Default usage percentage of a rate-limit window at which turns pause.
+const DEFAULT_RATE_LIMIT_PAUSE_PERCENT: u8 = 98;
+
 /// Application configuration loaded from disk and merged with overrides.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Config {
@@ -92,6 +124,53 @@ pub struct Config {
     /// Token usage threshold triggering auto-compaction of conversation history.
     pub model_auto_compact_token_limit: Option<i64>,
 
+    /// Percentage of the model's context window that, once in use, triggers
+    /// auto-compaction even before `model_auto_compact_token_limit` is hit.
+    pub model_auto_compact_context_percent: Option<u8>,
+
+    /// Per-model prices used to estimate conversation cost, keyed by model
+    /// slug.
+    pub model_prices: HashMap<String, ModelPrice>,
+
+    /// Maximum number of tokens (input + output) a conversation may use.
+    /// Once exceeded, new turns are refused until the budget is raised.
+    pub conversation_token_budget: Option<i64>,
+
+    /// Cache for deterministic auxiliary model calls.
+    pub response_cache: ResponseCacheConfig,
+
+    /// Limits on concurrent turns when running as the app-server.
+    pub app_server: AppServerConfig,
+
+    /// How much of a command's output is streamed to clients and kept.
+    pub exec_output: ExecOutputConfig,
+
+    /// Provider and model used to transcribe attached audio.
+    pub audio_transcription: AudioTranscriptionConfig,
+
+    /// Provider, model, and chunking of the `code_search` index.
+    pub code_search: CodeSearchConfig,
+
+    /// Models for auxiliary requests and fallbacks for unavailable models.
+    pub model_routing: ModelRoutingConfig,
+
+    /// Proxy, CA bundle, and TLS settings shared by every outbound client.
+    pub http: HttpClientSettings,
+
+    /// Scripts run before and after turns, commands, and patches.
+    pub hooks: HooksConfig,
+
+    /// Language servers asked for diagnostics after each applied patch,
+    /// keyed by name.
+    pub lsp_servers: BTreeMap<String, LspServerConfig>,
+
+    /// Thread templates defined in `config.toml`. Templates stored under
+    /// `CODEX_HOME/templates/` are read when listed.
+    pub templates: BTreeMap<String, ThreadTemplate>,
+
+    /// Built-in and MCP tools the model may be offered.
+    pub tool_filter: ToolFilter,
+
     /// Key into the model_providers map that specifies which provider to use.
     pub model_provider_id: String,
 
@@ -122,6 +201,9 @@ pub struct Config {
     /// Defaults to `false`.
     pub show_raw_agent_reasoning: bool,
 
+    /// Reasoning removed from events and rollouts before they leave core.
+    pub reasoning_redaction: ReasoningRedaction,
+
     /// User-provided instructions from AGENTS.md.
     pub user_instructions: Option<String>,
 
@@ -156,6 +238,21 @@ pub struct Config {
     /// If unset the feature is disabled.
     pub notify: Option<Vec<String>>,
 
+    /// Notification types passed to `notify`, e.g.
+    /// `["approval-requested", "turn-failed"]`. `None` passes every type.
+    pub notify_types: Option<Vec<String>>,
+
+    /// Built-in desktop notifications (notify-rust on Linux/macOS, toast on
+    /// Windows), shown without an external program. `true` enables every
+    /// notification type; a list limits delivery to those types.
+    pub desktop_notifications: Notifications,
+
+    /// HTTP endpoints that receive every user notification.
+    pub notification_webhooks: Vec<NotificationWebhookConfig>,
+
+    /// Recurring prompts run by `codex schedule run`.
+    pub schedules: Vec<ScheduleConfig>,
+
     /// TUI notifications preference. When set, the TUI will send OSC 9 notifications on approvals
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
@@ -165,12 +262,37 @@ pub struct Config {
     /// resolved against this path.
     pub cwd: PathBuf,
 
+    /// Additional folders that make up the session's workspace alongside
+    /// `cwd`, e.g. the other folders of a multi-root IDE workspace. Always
+    /// absolute.
+    pub workspace_roots: Vec<PathBuf>,
+
+    /// Run the session in a dedicated `git worktree` of `cwd`'s repository
+    /// instead of the primary checkout.
+    pub worktree_isolation: bool,
+
+    /// Commit each turn's changes to `codex/turns/<conversation id>` with a
+    /// generated message when the turn completes.
+    pub auto_commit: bool,
+
+    /// How often a running turn reports a `TurnHeartbeat`; `None` disables
+    /// heartbeats.
+    pub turn_heartbeat_interval: Option<Duration>,
+
+    /// Usage percentage of a rate-limit window at which new model requests
+    /// wait for the window to reset; `None` never waits.
+    pub rate_limit_pause_percent: Option<f64>,
+
     /// Preferred store for CLI auth credentials.
     /// file (default): Use a file in the Codex home directory.
     /// keyring: Use an OS-specific keyring service.
     /// auto: Use the OS-specific keyring service if available, otherwise use a file.
     pub cli_auth_credentials_store_mode: AuthCredentialsStoreMode,
 
+    /// Label of the stored account whose credentials this session uses;
+    /// `None` for the default account.
+    pub account: Option<String>,
+
     /// Definition for MCP servers that Codex can reach out to for tool calls.
     pub mcp_servers: HashMap<String, McpServerConfig>,
 
@@ -192,6 +314,10 @@ pub struct Config {
     /// Additional filenames to try when looking for project-level docs.
     pub project_doc_fallback_filenames: Vec<String>,
 
+    /// Organization-wide instructions file, layered below the user's and the
+    /// repository's AGENTS.md.
+    pub org_instructions_file: Option<PathBuf>,
+
     /// Token budget applied when storing tool/function outputs in the context manager.
     pub tool_output_token_limit: Option<usize>,
 
@@ -274,6 +400,21 @@ pub struct Config {
 
     /// OTEL configuration (exporter type, endpoint, headers, etc.).
     pub otel: crate::config::types::OtelConfig,
+
+    /// Experiment variant this conversation is enrolled in, if any.
+    pub experiment: Option<ExperimentAssignment>,
+
+    /// Top-level keys set by the remote overlay. Per-turn overrides of the
+    /// settings they control are ignored for the whole session.
+    pub remote_enforced_keys: BTreeSet<String>,
+
+    /// `-c key=value` overrides this config was loaded with. Switching
+    /// profiles re-applies them so they keep precedence over the profile.
+    pub cli_overrides: Vec<(String, TomlValue)>,
+
+    /// Launch flags this config was loaded with, re-applied like
+    /// `cli_overrides` when switching profiles.
+    pub config_overrides: ConfigOverrides,
 }
 
 impl Config {
@@ -283,19 +424,22 @@ impl Config {
     ) -> std::io::Result<Self> {
         let codex_home = find_codex_home()?;
 
-        let root_value = load_resolved_config(
+        let (root_value, remote_enforced_keys) = load_resolved_config(
             &codex_home,
-            cli_overrides,
+            cli_overrides.clone(),
             crate::config_loader::LoaderOverrides::default(),
         )
         .await?;
 
-        let cfg: ConfigToml = root_value.try_into().map_err(|e| {
+        let mut cfg: ConfigToml = root_value.try_into().map_err(|e| {
             tracing::error!("Failed to deserialize overridden config: {e}");
             std::io::Error::new(std::io::ErrorKind::InvalidData, e)
         })?;
+        cfg.remote_enforced_keys = remote_enforced_keys;
 
-        Self::load_from_base_config_with_overrides(cfg, overrides, codex_home)
+        let mut config = Self::load_from_base_config_with_overrides(cfg, overrides, codex_home)?;
+        config.cli_overrides = cli_overrides;
+        Ok(config)
     }
 }
 
@@ -303,26 +447,28 @@ pub async fn load_config_as_toml_with_cli_overrides(
     codex_home: &Path,
     cli_overrides: Vec<(String, TomlValue)>,
 ) -> std::io::Result<ConfigToml> {
-    let root_value = load_resolved_config(
+    let (root_value, remote_enforced_keys) = load_resolved_config(
         codex_home,
         cli_overrides,
         crate::config_loader::LoaderOverrides::default(),
     )
     .await?;
 
-    let cfg: ConfigToml = root_value.try_into().map_err(|e| {
+    let mut cfg: ConfigToml = root_value.try_into().map_err(|e| {
         tracing::error!("Failed to deserialize overridden config: {e}");
         std::io::Error::new(std::io::ErrorKind::InvalidData, e)
     })?;
+    cfg.remote_enforced_keys = remote_enforced_keys;
 
     Ok(cfg)
 }
 
+/// The merged config, plus the top-level keys the remote overlay enforces.
 async fn load_resolved_config(
     codex_home: &Path,
     cli_overrides: Vec<(String, TomlValue)>,
     overrides: crate::config_loader::LoaderOverrides,
-) -> std::io::Result<TomlValue> {
+) -> std::io::Result<(TomlValue, BTreeSet<String>)> {
     let layers = load_config_layers_with_overrides(codex_home, overrides).await?;
     Ok(apply_overlays(layers, cli_overrides))
 }
@@ -330,22 +476,63 @@ async fn load_resolved_config(
 fn apply_overlays(
     layers: LoadedConfigLayers,
     cli_overrides: Vec<(String, TomlValue)>,
-) -> TomlValue {
+) -> (TomlValue, BTreeSet<String>) {
     let LoadedConfigLayers {
         mut base,
         managed_config,
         managed_preferences,
+        remote_config,
     } = layers;
 
     for (path, value) in cli_overrides.into_iter() {
         apply_toml_override(&mut base, &path, value);
     }
 
-    for overlay in [managed_config, managed_preferences].into_iter().flatten() {
+    let remote_enforced_keys: BTreeSet<String> = remote_config
+        .as_ref()
+        .and_then(TomlValue::as_table)
+        .map(|table| table.keys().cloned().collect())
+        .unwrap_or_default();
+
+    for overlay in [managed_config, managed_preferences, remote_config]
+        .into_iter()
+        .flatten()
+    {
         merge_toml_values(&mut base, &overlay);
     }
 
-    base
+    strip_enforced_keys(&mut base, &remote_enforced_keys);
+    (base, remote_enforced_keys)
+}
+
+/// Removes `enforced` keys from every profile and experiment variant, which
+/// would otherwise take precedence over the top-level values the remote
+/// overlay set.
+fn strip_enforced_keys(root: &mut TomlValue, enforced: &BTreeSet<String>) {
+    if enforced.is_empty() {
+        return;
+    }
+    let strip = |scoped: &mut TomlValue| {
+        if let Some(table) = scoped.as_table_mut() {
+            table.retain(|key, _| !enforced.contains(key));
+        }
+    };
+    if let Some(profiles) = root.get_mut("profiles").and_then(TomlValue::as_table_mut) {
+        profiles.iter_mut().for_each(|(_, profile)| strip(profile));
+    }
+    if let Some(experiments) = root
+        .get_mut("experiments")
+        .and_then(TomlValue::as_table_mut)
+    {
+        for (_, experiment) in experiments.iter_mut() {
+            if let Some(variants) = experiment
+                .get_mut("variants")
+                .and_then(TomlValue::as_table_mut)
+            {
+                variants.iter_mut().for_each(|(_, variant)| strip(variant));
+            }
+        }
+    }
 }
 
 pub async fn load_global_mcp_servers(
@@ -573,6 +760,50 @@ pub struct ConfigToml {
     /// Token usage threshold triggering auto-compaction of conversation history.
     pub model_auto_compact_token_limit: Option<i64>,
 
+    /// Percentage of the model's context window that, once in use, triggers
+    /// auto-compaction even before `model_auto_compact_token_limit` is hit.
+    pub model_auto_compact_context_percent: Option<u8>,
+
+    /// Per-model prices used to estimate conversation cost, keyed by model
+    /// slug, e.g. `[model_prices.gpt-5]`.
+    #[serde(default)]
+    pub model_prices: HashMap<String, ModelPrice>,
+
+    /// Maximum number of tokens (input + output) a conversation may use.
+    /// Once exceeded, new turns are refused until the budget is raised.
+    pub conversation_token_budget: Option<i64>,
+
+    /// Opt-in cache for deterministic auxiliary model calls.
+    pub response_cache: Option<ResponseCacheConfig>,
+
+    /// Concurrency limits and queuing for `turn/start` in the app-server.
+    pub app_server: Option<AppServerConfig>,
+
+    /// Streaming cap and head/tail retention for command output.
+    pub exec_output: Option<ExecOutputConfig>,
+
+    /// Provider and model for transcribing audio attached to user input.
+    pub audio_transcription: Option<AudioTranscriptionConfig>,
+
+    /// Embeddings provider and model for the `code_search` tool.
+    pub code_search: Option<CodeSearchConfig>,
+
+    /// Per-request-kind models (`summarization`, `risk_assessment`,
+    /// `commit_message`, `thread_title`) and fallbacks for when a model is
+    /// unavailable.
+    pub model_routing: Option<ModelRoutingConfig>,
+
+    /// Proxy, custom CA bundle, and minimum TLS version for outbound HTTP
+    /// requests (model provider, MCP servers, login, webhooks).
+    pub http: Option<HttpClientSettings>,
+
+    /// Language servers that report diagnostics for patched files.
+    #[serde(default)]
+    pub lsp_servers: BTreeMap<String, LspServerConfig>,
+
+    /// Lifecycle hook scripts that can veto turns, commands, and patches.
+    pub hooks: Option<HooksConfig>,
+
     /// Default approval policy for executing commands.
     pub approval_policy: Option<AskForApproval>,
 
@@ -589,6 +820,45 @@ pub struct ConfigToml {
     #[serde(default)]
     pub notify: Option<Vec<String>>,
 
+    /// Only pass these notification types to `notify`.
+    #[serde(default)]
+    pub notify_types: Option<Vec<String>>,
+
+    /// Show notifications with the OS notification center. Either a boolean
+    /// or a list of notification types such as `["approval-requested"]`.
+    #[serde(default)]
+    pub desktop_notifications: Option<Notifications>,
+
+    /// HTTP endpoints to POST notifications to, signed with HMAC-SHA256.
+    #[serde(default)]
+    pub notification_webhooks: Vec<NotificationWebhookConfig>,
+
+    /// Prompts to run on a cron schedule with `codex schedule run`.
+    #[serde(default)]
+    pub schedules: Vec<ScheduleConfig>,
+
+    /// Additional workspace folders. Relative paths are resolved against the
+    /// session cwd.
+    #[serde(default)]
+    pub workspace_roots: Option<Vec<PathBuf>>,
+
+    /// Work in a dedicated `git worktree` per conversation so the primary
+    /// checkout is only changed when the worktree is merged back.
+    pub worktree_isolation: Option<bool>,
+
+    /// Commit the changes of every completed turn to a dedicated branch with
+    /// a model-generated conventional-commit message.
+    pub auto_commit: Option<bool>,
+
+    /// Milliseconds between heartbeats of a running turn. Defaults to 15000;
+    /// `0` turns heartbeats off.
+    pub turn_heartbeat_interval_ms: Option<u64>,
+
+    /// Once the account has used this percentage of a rate-limit window, hold
+    /// new model requests until the window resets. Defaults to 98; `0` never
+    /// holds requests back.
+    pub rate_limit_pause_percent: Option<u8>,
+
     /// System instructions.
     pub instructions: Option<String>,
 
@@ -614,6 +884,10 @@ pub struct ConfigToml {
     #[serde(default)]
     pub cli_auth_credentials_store: Option<AuthCredentialsStoreMode>,
 
+    /// Label of the stored account to use instead of the default one, e.g.
+    /// after `codex login --account work`.
+    pub account: Option<String>,
+
     /// Definition for MCP servers that Codex can reach out to for tool calls.
     #[serde(default)]
     pub mcp_servers: HashMap<String, McpServerConfig>,
@@ -636,6 +910,19 @@ pub struct ConfigToml {
     /// Ordered list of fallback filenames to look for when AGENTS.md is missing.
     pub project_doc_fallback_filenames: Option<Vec<String>>,
 
+    /// Organization-wide instructions file. Relative paths are resolved
+    /// against `CODEX_HOME`.
+    pub org_instructions_file: Option<PathBuf>,
+
+    /// Signed config overlay fetched from an HTTPS endpoint. Keys set by the
+    /// overlay take precedence over every local layer.
+    pub remote_config: Option<RemoteConfigToml>,
+
+    /// Top-level keys set by the remote overlay. Command-line overrides for
+    /// these keys are ignored.
+    #[serde(skip)]
+    pub remote_enforced_keys: BTreeSet<String>,
+
     /// Token budget applied when storing tool/function outputs in the context manager.
     pub tool_output_token_limit: Option<usize>,
 
@@ -646,10 +933,25 @@ pub struct ConfigToml {
     #[serde(default)]
     pub profiles: HashMap<String, ConfigProfile>,
 
+    /// Named starting points for new threads.
+    #[serde(default)]
+    pub templates: BTreeMap<String, ThreadTemplate>,
+
     /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
     #[serde(default)]
     pub history: Option<History>,
 
+    /// A/B experiments keyed by name. See [`crate::experiments`].
+    #[serde(default)]
+    pub experiments: HashMap<String, ExperimentToml>,
+
+    /// Experiment from the `experiments` map to enroll this conversation in.
+    pub experiment: Option<String>,
+
+    /// Variant label to use for `experiment`. When unset, a variant is chosen
+    /// at random.
+    pub experiment_variant: Option<String>,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: Option<UriBasedFileOpener>,
@@ -665,6 +967,10 @@ pub struct ConfigToml {
     /// Defaults to `false`.
     pub show_raw_agent_reasoning: Option<bool>,
 
+    /// Remove reasoning from notifications and rollouts: `raw` drops raw
+    /// reasoning content, `all` drops summaries too. Defaults to `none`.
+    pub reasoning_redaction: Option<ReasoningRedaction>,
+
     pub model_reasoning_effort: Option<ReasoningEffort>,
     pub model_reasoning_summary: Option<ReasoningSummary>,
     /// Optional verbosity control for GPT-5 models (Responses API `text.verbosity`).
@@ -739,9 +1045,12 @@ impl From<ConfigToml> for UserSavedConfig {
     }
 }
 
-#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, Default)]
 pub struct ProjectConfig {
     pub trust_level: Option<TrustLevel>,
+    /// Hosts reachable through the network proxy when the `network_proxy`
+    /// feature is enabled. Entries may use `*.example.com` for subdomains.
+    pub network_allowed_hosts: Option<Vec<String>>,
 }
 
 impl ProjectConfig {
@@ -762,6 +1071,14 @@ pub struct ToolsToml {
     /// Enable the `view_image` tool that lets the agent attach local images.
     #[serde(default)]
     pub view_image: Option<bool>,
+
+    /// When set, only these tools are offered to the model.
+    #[serde(default)]
+    pub enabled: Option<Vec<String>>,
+
+    /// Tools never offered to the model.
+    #[serde(default)]
+    pub disabled: Option<Vec<String>>,
 }
 
 impl From<ToolsToml> for Tools {
@@ -818,6 +1135,7 @@ impl ConfigToml {
                 None => SandboxPolicy::new_workspace_write_policy(),
             },
             SandboxMode::DangerFullAccess => SandboxPolicy::DangerFullAccess,
+            SandboxMode::Audit => SandboxPolicy::new_audit_policy(),
         };
         let mut forced_auto_mode_downgraded_on_windows = false;
         if cfg!(target_os = "windows")
@@ -879,7 +1197,7 @@ impl ConfigToml {
 }
 
 /// Optional overrides for user configuration (e.g., from CLI flags).
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct ConfigOverrides {
     pub model: Option<String>,
     pub review_model: Option<String>,
@@ -898,6 +1216,12 @@ pub struct ConfigOverrides {
     pub experimental_sandbox_command_assessment: Option<bool>,
     /// Additional directories that should be treated as writable roots for this session.
     pub additional_writable_roots: Vec<PathBuf>,
+    /// Replaces `tools.enabled` for this session.
+    pub tools_enabled: Option<Vec<String>>,
+    /// Replaces `tools.disabled` for this session.
+    pub tools_disabled: Option<Vec<String>>,
+    /// Replaces `account` for this session.
+    pub account: Option<String>,
 }
 
 /// Resolves the OSS provider from CLI override, profile config, or global config.
@@ -937,6 +1261,7 @@ impl Config {
         codex_home: PathBuf,
     ) -> std::io::Result<Self> {
         let user_instructions = Self::load_instructions(Some(&codex_home));
+        let config_overrides = overrides.clone();
 
         // Destructure ConfigOverrides fully to ensure all overrides are applied.
         let ConfigOverrides {
@@ -956,8 +1281,39 @@ impl Config {
             tools_web_search_request: override_tools_web_search_request,
             experimental_sandbox_command_assessment: sandbox_command_assessment_override,
             additional_writable_roots,
+            tools_enabled: tools_enabled_override,
+            tools_disabled: tools_disabled_override,
+            account: account_override,
         } = overrides;
 
+        // Keys enforced by the remote overlay win over command-line flags and
+        // `Op::SetProfile`, just as they do over every local config layer.
+        let enforced = |key: &str| {
+            let enforced = cfg.remote_enforced_keys.contains(key);
+            if enforced {
+                tracing::warn!("ignoring override of `{key}`, which remote config enforces");
+            }
+            enforced
+        };
+        let model = model.filter(|_| !enforced("model"));
+        let override_review_model = override_review_model.filter(|_| !enforced("review_model"));
+        let approval_policy_override =
+            approval_policy_override.filter(|_| !enforced("approval_policy"));
+        let sandbox_mode = sandbox_mode.filter(|_| !enforced("sandbox_mode"));
+        let model_provider = model_provider.filter(|_| !enforced("model_provider"));
+        let developer_instructions =
+            developer_instructions.filter(|_| !enforced("developer_instructions"));
+        let compact_prompt = compact_prompt.filter(|_| !enforced("compact_prompt"));
+        let additional_writable_roots =
+            if !additional_writable_roots.is_empty() && enforced("sandbox_workspace_write") {
+                Vec::new()
+            } else {
+                additional_writable_roots
+            };
+        let tools_enabled_override = tools_enabled_override.filter(|_| !enforced("tools"));
+        let tools_disabled_override = tools_disabled_override.filter(|_| !enforced("tools"));
+        let account_override = account_override.filter(|_| !enforced("account"));
+
         let active_profile_name = config_profile_key
             .as_ref()
             .or(cfg.profile.as_ref())
@@ -976,6 +1332,27 @@ impl Config {
             None => ConfigProfile::default(),
         };
 
+        let (experiment, experiment_variant) = match cfg.experiment.as_ref() {
+            Some(name) => {
+                let definition = cfg.experiments.get(name).ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::NotFound,
+                        format!("experiment `{name}` not found"),
+                    )
+                })?;
+                let variant = assign_variant(name, definition, cfg.experiment_variant.as_deref())?;
+                let variant_config = definition.variants[&variant].clone();
+                (
+                    Some(ExperimentAssignment {
+                        experiment: name.clone(),
+                        variant,
+                    }),
+                    variant_config,
+                )
+            }
+            None => (None, ExperimentVariantToml::default()),
+        };
+
         let feature_overrides = FeatureOverrides {
             include_apply_patch_tool: include_apply_patch_tool_override,
             web_search_request: override_tools_web_search_request,
@@ -1020,9 +1397,11 @@ impl Config {
                 }
             })
             .collect();
-        let active_project = cfg
-            .get_active_project(&resolved_cwd)
-            .unwrap_or(ProjectConfig { trust_level: None });
+        let workspace_roots = resolve_workspace_roots(
+            cfg.workspace_roots.clone().unwrap_or_default(),
+            &resolved_cwd,
+        );
+        let active_project = cfg.get_active_project(&resolved_cwd).unwrap_or_default();
 
         let SandboxPolicyResolution {
             policy: mut sandbox_policy,
@@ -1036,6 +1415,7 @@ impl Config {
             }
         }
         let approval_policy = approval_policy_override
+            .or(experiment_variant.approval_policy)
             .or(config_profile.approval_policy)
             .or(cfg.approval_policy)
             .unwrap_or_else(|| {
@@ -1051,6 +1431,7 @@ impl Config {
             });
         let did_user_set_custom_approval_policy_or_sandbox_mode = approval_policy_override
             .is_some()
+            || experiment_variant.approval_policy.is_some()
             || config_profile.approval_policy.is_some()
             || cfg.approval_policy.is_some()
             || sandbox_mode.is_some()
@@ -1077,6 +1458,40 @@ impl Config {
             })?
             .clone();
 
+        let tools_toml = cfg.tools.clone().unwrap_or_default();
+        let tools_enabled = tools_enabled_override
+            .or(config_profile.tools_enabled.clone())
+            .or(tools_toml.enabled);
+        let tools_disabled = tools_disabled_override
+            .or(config_profile.tools_disabled.clone())
+            .or(tools_toml.disabled)
+            .unwrap_or_default();
+        let tool_filter =
+            ToolFilter::new(tools_enabled.as_deref(), &tools_disabled, &cfg.mcp_servers).map_err(
+                |err| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidInput,
+                        format!("invalid tools configuration: {err}"),
+                    )
+                },
+            )?;
+
+        let http = cfg.http.unwrap_or_default();
+        let http_client_config = http.resolve().map_err(|err| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid http configuration: {err}"),
+            )
+        })?;
+        codex_utils_http_client::install(http_client_config);
+
+        let account = account_override
+            .or(config_profile.account.clone())
+            .or(cfg.account.clone());
+        if let Some(label) = account.as_deref() {
+            validate_account_label(label)?;
+        }
+
         let shell_environment_policy = cfg.shell_environment_policy.into();
 
         let history = cfg.history.unwrap_or_default();
@@ -1101,6 +1516,7 @@ impl Config {
         let forced_login_method = cfg.forced_login_method;
 
         let model = model
+            .or(experiment_variant.model)
             .or(config_profile.model)
             .or(cfg.model)
             .unwrap_or_else(default_model);
@@ -1153,6 +1569,17 @@ impl Config {
         )?;
         let base_instructions = base_instructions.or(file_base_instructions);
         let developer_instructions = developer_instructions.or(cfg.developer_instructions);
+        let developer_instructions = if experiment_variant.prompt_fragments.is_empty() {
+            developer_instructions
+        } else {
+            Some(
+                developer_instructions
+                    .into_iter()
+                    .chain(experiment_variant.prompt_fragments)
+                    .collect::<Vec<_>>()
+                    .join("\n\n"),
+            )
+        };
 
         let experimental_compact_prompt_path = config_profile
             .experimental_compact_prompt_file
@@ -1177,15 +1604,52 @@ impl Config {
             model_context_window,
             model_max_output_tokens,
             model_auto_compact_token_limit,
+            model_auto_compact_context_percent: cfg.model_auto_compact_context_percent,
+            model_prices: cfg.model_prices,
+            conversation_token_budget: cfg.conversation_token_budget,
+            response_cache: cfg.response_cache.unwrap_or_default(),
+            app_server: cfg.app_server.unwrap_or_default(),
+            exec_output: cfg.exec_output.unwrap_or_default(),
+            audio_transcription: cfg.audio_transcription.unwrap_or_default(),
+            code_search: cfg.code_search.unwrap_or_default(),
+            model_routing: cfg.model_routing.unwrap_or_default(),
+            http,
+            hooks: cfg.hooks.unwrap_or_default(),
+            lsp_servers: cfg.lsp_servers,
+            templates: cfg.templates,
+            tool_filter,
             model_provider_id,
             model_provider,
             cwd: resolved_cwd,
+            workspace_roots,
+            worktree_isolation: cfg.worktree_isolation.unwrap_or(false),
+            auto_commit: cfg.auto_commit.unwrap_or(false),
+            turn_heartbeat_interval: match cfg
+                .turn_heartbeat_interval_ms
+                .unwrap_or(DEFAULT_TURN_HEARTBEAT_INTERVAL_MS)
+            {
+                0 => None,
+                ms => Some(Duration::from_millis(ms)),
+            },
+            rate_limit_pause_percent: match cfg
+                .rate_limit_pause_percent
+                .unwrap_or(DEFAULT_RATE_LIMIT_PAUSE_PERCENT)
+            {
+                0 => None,
+                percent => Some(f64::from(percent)),
+            },
             approval_policy,
             sandbox_policy,
             did_user_set_custom_approval_policy_or_sandbox_mode,
             forced_auto_mode_downgraded_on_windows,
             shell_environment_policy,
             notify: cfg.notify,
+            notify_types: cfg.notify_types,
+            desktop_notifications: cfg
+                .desktop_notifications
+                .unwrap_or(Notifications::Enabled(false)),
+            notification_webhooks: cfg.notification_webhooks,
+            schedules: cfg.schedules,
             user_instructions,
             base_instructions,
             developer_instructions,
@@ -1193,6 +1657,7 @@ impl Config {
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
             cli_auth_credentials_store_mode: cfg.cli_auth_credentials_store.unwrap_or_default(),
+            account,
             mcp_servers: cfg.mcp_servers,
             // The config.toml omits "_mode" because it's a config file. However, "_mode"
             // is important in code to differentiate the mode from the store implementation.
@@ -1212,6 +1677,7 @@ impl Config {
                     }
                 })
                 .collect(),
+            org_instructions_file: cfg.org_instructions_file.map(|path| codex_home.join(path)),
             tool_output_token_limit: cfg.tool_output_token_limit,
             codex_home,
             history,
@@ -1223,6 +1689,7 @@ impl Config {
                 .show_raw_agent_reasoning
                 .or(show_raw_agent_reasoning)
                 .unwrap_or(false),
+            reasoning_redaction: cfg.reasoning_redaction.unwrap_or_default(),
             model_reasoning_effort: config_profile
                 .model_reasoning_effort
                 .or(cfg.model_reasoning_effort),
@@ -1266,6 +1733,10 @@ impl Config {
                     exporter,
                 }
             },
+            experiment,
+            remote_enforced_keys: cfg.remote_enforced_keys,
+            cli_overrides: Vec::new(),
+            config_overrides,
         };
         Ok(config)
     }
@@ -1318,6 +1789,11 @@ impl Config {
         }
     }
 
+    /// Directory holding the credentials of the configured account.
+    pub fn credentials_home(&self) -> PathBuf {
+        account_credentials_home(&self.codex_home, self.account.as_deref())
+    }
+
     pub fn set_windows_sandbox_globally(&mut self, value: bool) {
         crate::safety::set_windows_sandbox_enabled(value);
         if value {
@@ -1329,6 +1805,24 @@ impl Config {
     }
 }
 
+/// Resolves workspace roots against `cwd`, canonicalizing where possible and
+/// dropping duplicates as well as `cwd` itself.
+pub(crate) fn resolve_workspace_roots(roots: Vec<PathBuf>, cwd: &Path) -> Vec<PathBuf> {
+    let mut resolved: Vec<PathBuf> = Vec::new();
+    for root in roots {
+        let absolute = if root.is_absolute() {
+            root
+        } else {
+            cwd.join(root)
+        };
+        let root = canonicalize(&absolute).unwrap_or(absolute);
+        if root != cwd && !resolved.contains(&root) {
+            resolved.push(root);
+        }
+    }
+    resolved
+}
+
 fn default_model() -> String {
     OPENAI_DEFAULT_MODEL.to_string()
 }
@@ -1378,6 +1872,7 @@ mod tests {
     use crate::config::edit::ConfigEditsBuilder;
     use crate::config::edit::apply_blocking;
     use crate::config::types::HistoryPersistence;
+    use crate::config::types::McpServerStartup;
     use crate::config::types::McpServerTransportConfig;
     use crate::config::types::Notifications;
     use crate::features::Feature;
@@ -1638,6 +2133,48 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn remote_enforced_sandbox_mode_beats_profile_and_cli() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        let layers = LoadedConfigLayers {
+            base: toml::from_str(
+                r#"
+sandbox_mode = "danger-full-access"
+profile = "yolo"
+
+[profiles.yolo]
+sandbox_mode = "danger-full-access"
+"#,
+            )?,
+            managed_config: None,
+            managed_preferences: None,
+            remote_config: Some(toml::from_str(r#"sandbox_mode = "read-only""#)?),
+        };
+        let (root, remote_enforced_keys) = apply_overlays(
+            layers,
+            vec![(
+                "sandbox_mode".to_string(),
+                TomlValue::String("danger-full-access".to_string()),
+            )],
+        );
+        let mut cfg: ConfigToml = root.try_into()?;
+        cfg.remote_enforced_keys = remote_enforced_keys;
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                sandbox_mode: Some(SandboxMode::DangerFullAccess),
+                config_profile: Some("yolo".to_string()),
+                cwd: Some(codex_home.path().to_path_buf()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(config.sandbox_policy, SandboxPolicy::ReadOnly);
+        Ok(())
+    }
+
     #[test]
     fn config_honors_explicit_keyring_auth_store_mode() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -1782,6 +2319,67 @@ trust_level = "trusted"
         Ok(())
     }
 
+    #[test]
+    fn experiment_variant_overrides_profile_and_appends_prompt_fragments() -> std::io::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+model = "base-model"
+approval_policy = "untrusted"
+developer_instructions = "Be careful."
+experiment = "prompt-tuning"
+experiment_variant = "terse"
+
+[experiments.prompt-tuning.variants.control]
+
+[experiments.prompt-tuning.variants.terse]
+model = "variant-model"
+approval_policy = "never"
+prompt_fragments = ["Keep answers short.", "Prefer small diffs."]
+"#,
+        )
+        .expect("experiment config should parse");
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+
+        assert_eq!(config.model, "variant-model");
+        assert_eq!(config.approval_policy, AskForApproval::Never);
+        assert_eq!(
+            config.developer_instructions.as_deref(),
+            Some("Be careful.\n\nKeep answers short.\n\nPrefer small diffs.")
+        );
+        assert_eq!(
+            config.experiment,
+            Some(ExperimentAssignment {
+                experiment: "prompt-tuning".to_string(),
+                variant: "terse".to_string(),
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_experiment_is_an_error() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let cfg = ConfigToml {
+            experiment: Some("missing".to_string()),
+            ..Default::default()
+        };
+
+        let err = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("unknown experiment");
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    }
+
     #[test]
     fn feature_table_overrides_legacy_flags() -> std::io::Result<()> {
         let codex_home = TempDir::new()?;
@@ -1922,6 +2520,11 @@ trust_level = "trusted"
                 tool_timeout_sec: Some(Duration::from_secs(5)),
                 enabled_tools: None,
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         );
 
@@ -2068,6 +2671,11 @@ bearer_token = "secret"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         )]);
 
@@ -2136,6 +2744,11 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         )]);
 
@@ -2184,6 +2797,11 @@ ZIG_VAR = "3"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         )]);
 
@@ -2224,12 +2842,18 @@ ZIG_VAR = "3"
                     bearer_token_env_var: Some("MCP_TOKEN".to_string()),
                     http_headers: None,
                     env_http_headers: None,
+                    scopes: None,
                 },
                 enabled: true,
                 startup_timeout_sec: Some(Duration::from_secs(2)),
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         )]);
 
@@ -2258,11 +2882,13 @@ startup_timeout_sec = 2.0
                 bearer_token_env_var,
                 http_headers,
                 env_http_headers,
+                scopes,
             } => {
                 assert_eq!(url, "https://example.com/mcp");
                 assert_eq!(bearer_token_env_var.as_deref(), Some("MCP_TOKEN"));
                 assert!(http_headers.is_none());
                 assert!(env_http_headers.is_none());
+                assert!(scopes.is_none());
             }
             other => panic!("unexpected transport {other:?}"),
         }
@@ -2286,12 +2912,18 @@ startup_timeout_sec = 2.0
                         "X-Auth".to_string(),
                         "DOCS_AUTH".to_string(),
                     )])),
+                    scopes: None,
                 },
                 enabled: true,
                 startup_timeout_sec: Some(Duration::from_secs(2)),
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         )]);
         apply_blocking(
@@ -2360,12 +2992,18 @@ X-Auth = "DOCS_AUTH"
                         "X-Auth".to_string(),
                         "DOCS_AUTH".to_string(),
                     )])),
+                    scopes: None,
                 },
                 enabled: true,
                 startup_timeout_sec: Some(Duration::from_secs(2)),
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         )]);
 
@@ -2387,12 +3025,18 @@ X-Auth = "DOCS_AUTH"
                     bearer_token_env_var: None,
                     http_headers: None,
                     env_http_headers: None,
+                    scopes: None,
                 },
                 enabled: true,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         );
         apply_blocking(
@@ -2417,11 +3061,13 @@ url = "https://example.com/mcp"
                 bearer_token_env_var,
                 http_headers,
                 env_http_headers,
+                scopes,
             } => {
                 assert_eq!(url, "https://example.com/mcp");
                 assert!(bearer_token_env_var.is_none());
                 assert!(http_headers.is_none());
                 assert!(env_http_headers.is_none());
+                assert!(scopes.is_none());
             }
             other => panic!("unexpected transport {other:?}"),
         }
@@ -2452,12 +3098,18 @@ url = "https://example.com/mcp"
                             "X-Auth".to_string(),
                             "DOCS_AUTH".to_string(),
                         )])),
+                        scopes: None,
                     },
                     enabled: true,
                     startup_timeout_sec: Some(Duration::from_secs(2)),
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    startup: McpServerStartup::Eager,
+                    idle_timeout_sec: None,
+                    warm_pool: false,
+                    tool_aliases: None,
+                    sampling: None,
                 },
             ),
             (
@@ -2475,6 +3127,11 @@ url = "https://example.com/mcp"
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    startup: McpServerStartup::Eager,
+                    idle_timeout_sec: None,
+                    warm_pool: false,
+                    tool_aliases: None,
+                    sampling: None,
                 },
             ),
         ]);
@@ -2555,6 +3212,11 @@ url = "https://example.com/mcp"
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         )]);
 
@@ -2597,6 +3259,11 @@ url = "https://example.com/mcp"
                 tool_timeout_sec: None,
                 enabled_tools: Some(vec!["allowed".to_string()]),
                 disabled_tools: Some(vec!["blocked".to_string()]),
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         )]);
 
@@ -2948,7 +3615,7 @@ model_verbosity = "high"
         };
         let o3_profile_config: Config = Config::load_from_base_config_with_overrides(
             fixture.cfg.clone(),
-            o3_profile_overrides,
+            o3_profile_overrides.clone(),
             fixture.codex_home(),
         )?;
         assert_eq!(
@@ -2959,6 +3626,9 @@ model_verbosity = "high"
                 model_context_window: Some(200_000),
                 model_max_output_tokens: Some(100_000),
                 model_auto_compact_token_limit: Some(180_000),
+                model_auto_compact_context_percent: None,
+                model_prices: HashMap::new(),
+                conversation_token_budget: None,
                 model_provider_id: "openai".to_string(),
                 model_provider: fixture.openai_provider.clone(),
                 approval_policy: AskForApproval::Never,
@@ -2968,13 +3638,37 @@ model_verbosity = "high"
                 shell_environment_policy: ShellEnvironmentPolicy::default(),
                 user_instructions: None,
                 notify: None,
+                response_cache: ResponseCacheConfig::default(),
+                app_server: AppServerConfig::default(),
+                exec_output: ExecOutputConfig::default(),
+                audio_transcription: AudioTranscriptionConfig::default(),
+                code_search: CodeSearchConfig::default(),
+                model_routing: ModelRoutingConfig::default(),
+                http: HttpClientSettings::default(),
+                hooks: HooksConfig::default(),
+                lsp_servers: BTreeMap::new(),
+                templates: BTreeMap::new(),
+                tool_filter: ToolFilter::default(),
+                notify_types: None,
+                desktop_notifications: Notifications::Enabled(false),
+                notification_webhooks: Vec::new(),
+                schedules: Vec::new(),
+                worktree_isolation: false,
+                auto_commit: false,
+                turn_heartbeat_interval: Some(Duration::from_millis(
+                    DEFAULT_TURN_HEARTBEAT_INTERVAL_MS
+                )),
+                rate_limit_pause_percent: Some(f64::from(DEFAULT_RATE_LIMIT_PAUSE_PERCENT)),
                 cwd: fixture.cwd(),
+                workspace_roots: Vec::new(),
                 cli_auth_credentials_store_mode: Default::default(),
+                account: None,
                 mcp_servers: HashMap::new(),
                 mcp_oauth_credentials_store_mode: Default::default(),
                 model_providers: fixture.model_provider_map.clone(),
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 project_doc_fallback_filenames: Vec::new(),
+                org_instructions_file: None,
                 tool_output_token_limit: None,
                 codex_home: fixture.codex_home(),
                 history: History::default(),
@@ -2982,6 +3676,7 @@ model_verbosity = "high"
                 codex_linux_sandbox_exe: None,
                 hide_agent_reasoning: false,
                 show_raw_agent_reasoning: false,
+                reasoning_redaction: ReasoningRedaction::None,
                 model_reasoning_effort: Some(ReasoningEffort::High),
                 model_reasoning_summary: ReasoningSummary::Detailed,
                 model_verbosity: None,
@@ -2998,12 +3693,16 @@ model_verbosity = "high"
                 use_experimental_use_rmcp_client: false,
                 features: Features::with_defaults(),
                 active_profile: Some("o3".to_string()),
-                active_project: ProjectConfig { trust_level: None },
+                active_project: ProjectConfig::default(),
                 windows_wsl_setup_acknowledged: false,
                 notices: Default::default(),
                 disable_paste_burst: false,
                 tui_notifications: Default::default(),
                 otel: OtelConfig::default(),
+                experiment: None,
+                remote_enforced_keys: BTreeSet::new(),
+                cli_overrides: Vec::new(),
+                config_overrides: o3_profile_overrides,
             },
             o3_profile_config
         );
@@ -3021,7 +3720,7 @@ model_verbosity = "high"
         };
         let gpt3_profile_config = Config::load_from_base_config_with_overrides(
             fixture.cfg.clone(),
-            gpt3_profile_overrides,
+            gpt3_profile_overrides.clone(),
             fixture.codex_home(),
         )?;
         let expected_gpt3_profile_config = Config {
@@ -3031,6 +3730,9 @@ model_verbosity = "high"
             model_context_window: Some(16_385),
             model_max_output_tokens: Some(4_096),
             model_auto_compact_token_limit: Some(14_746),
+            model_auto_compact_context_percent: None,
+            model_prices: HashMap::new(),
+            conversation_token_budget: None,
             model_provider_id: "openai-chat-completions".to_string(),
             model_provider: fixture.openai_chat_completions_provider.clone(),
             approval_policy: AskForApproval::UnlessTrusted,
@@ -3040,13 +3742,37 @@ model_verbosity = "high"
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            response_cache: ResponseCacheConfig::default(),
+            app_server: AppServerConfig::default(),
+            exec_output: ExecOutputConfig::default(),
+            audio_transcription: AudioTranscriptionConfig::default(),
+            code_search: CodeSearchConfig::default(),
+            model_routing: ModelRoutingConfig::default(),
+            http: HttpClientSettings::default(),
+            hooks: HooksConfig::default(),
+            lsp_servers: BTreeMap::new(),
+            templates: BTreeMap::new(),
+            tool_filter: ToolFilter::default(),
+            notify_types: None,
+            desktop_notifications: Notifications::Enabled(false),
+            notification_webhooks: Vec::new(),
+            schedules: Vec::new(),
+            worktree_isolation: false,
+            auto_commit: false,
+            turn_heartbeat_interval: Some(Duration::from_millis(
+                DEFAULT_TURN_HEARTBEAT_INTERVAL_MS,
+            )),
+            rate_limit_pause_percent: Some(f64::from(DEFAULT_RATE_LIMIT_PAUSE_PERCENT)),
             cwd: fixture.cwd(),
+            workspace_roots: Vec::new(),
             cli_auth_credentials_store_mode: Default::default(),
+            account: None,
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
+            org_instructions_file: None,
             tool_output_token_limit: None,
             codex_home: fixture.codex_home(),
             history: History::default(),
@@ -3054,6 +3780,7 @@ model_verbosity = "high"
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
+            reasoning_redaction: ReasoningRedaction::None,
             model_reasoning_effort: None,
             model_reasoning_summary: ReasoningSummary::default(),
             model_verbosity: None,
@@ -3070,12 +3797,16 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             features: Features::with_defaults(),
             active_profile: Some("gpt3".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig::default(),
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
             otel: OtelConfig::default(),
+            experiment: None,
+            remote_enforced_keys: BTreeSet::new(),
+            cli_overrides: Vec::new(),
+            config_overrides: gpt3_profile_overrides,
         };
 
         assert_eq!(expected_gpt3_profile_config, gpt3_profile_config);
@@ -3089,11 +3820,17 @@ model_verbosity = "high"
 
         let default_profile_config = Config::load_from_base_config_with_overrides(
             fixture.cfg.clone(),
-            default_profile_overrides,
+            default_profile_overrides.clone(),
             fixture.codex_home(),
         )?;
 
-        assert_eq!(expected_gpt3_profile_config, default_profile_config);
+        assert_eq!(
+            Config {
+                config_overrides: default_profile_overrides,
+                ..expected_gpt3_profile_config
+            },
+            default_profile_config
+        );
         Ok(())
     }
 
@@ -3108,7 +3845,7 @@ model_verbosity = "high"
         };
         let zdr_profile_config = Config::load_from_base_config_with_overrides(
             fixture.cfg.clone(),
-            zdr_profile_overrides,
+            zdr_profile_overrides.clone(),
             fixture.codex_home(),
         )?;
         let expected_zdr_profile_config = Config {
@@ -3118,6 +3855,9 @@ model_verbosity = "high"
             model_context_window: Some(200_000),
             model_max_output_tokens: Some(100_000),
             model_auto_compact_token_limit: Some(180_000),
+            model_auto_compact_context_percent: None,
+            model_prices: HashMap::new(),
+            conversation_token_budget: None,
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
             approval_policy: AskForApproval::OnFailure,
@@ -3127,13 +3867,37 @@ model_verbosity = "high"
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            response_cache: ResponseCacheConfig::default(),
+            app_server: AppServerConfig::default(),
+            exec_output: ExecOutputConfig::default(),
+            audio_transcription: AudioTranscriptionConfig::default(),
+            code_search: CodeSearchConfig::default(),
+            model_routing: ModelRoutingConfig::default(),
+            http: HttpClientSettings::default(),
+            hooks: HooksConfig::default(),
+            lsp_servers: BTreeMap::new(),
+            templates: BTreeMap::new(),
+            tool_filter: ToolFilter::default(),
+            notify_types: None,
+            desktop_notifications: Notifications::Enabled(false),
+            notification_webhooks: Vec::new(),
+            schedules: Vec::new(),
+            worktree_isolation: false,
+            auto_commit: false,
+            turn_heartbeat_interval: Some(Duration::from_millis(
+                DEFAULT_TURN_HEARTBEAT_INTERVAL_MS,
+            )),
+            rate_limit_pause_percent: Some(f64::from(DEFAULT_RATE_LIMIT_PAUSE_PERCENT)),
             cwd: fixture.cwd(),
+            workspace_roots: Vec::new(),
             cli_auth_credentials_store_mode: Default::default(),
+            account: None,
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
+            org_instructions_file: None,
             tool_output_token_limit: None,
             codex_home: fixture.codex_home(),
             history: History::default(),
@@ -3141,6 +3905,7 @@ model_verbosity = "high"
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
+            reasoning_redaction: ReasoningRedaction::None,
             model_reasoning_effort: None,
             model_reasoning_summary: ReasoningSummary::default(),
             model_verbosity: None,
@@ -3157,12 +3922,16 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             features: Features::with_defaults(),
             active_profile: Some("zdr".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig::default(),
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
             otel: OtelConfig::default(),
+            experiment: None,
+            remote_enforced_keys: BTreeSet::new(),
+            cli_overrides: Vec::new(),
+            config_overrides: zdr_profile_overrides,
         };
 
         assert_eq!(expected_zdr_profile_config, zdr_profile_config);
@@ -3181,7 +3950,7 @@ model_verbosity = "high"
         };
         let gpt5_profile_config = Config::load_from_base_config_with_overrides(
             fixture.cfg.clone(),
-            gpt5_profile_overrides,
+            gpt5_profile_overrides.clone(),
             fixture.codex_home(),
         )?;
         let expected_gpt5_profile_config = Config {
@@ -3191,6 +3960,9 @@ model_verbosity = "high"
             model_context_window: Some(272_000),
             model_max_output_tokens: Some(128_000),
             model_auto_compact_token_limit: Some(244_800),
+            model_auto_compact_context_percent: None,
+            model_prices: HashMap::new(),
+            conversation_token_budget: None,
             model_provider_id: "openai".to_string(),
             model_provider: fixture.openai_provider.clone(),
             approval_policy: AskForApproval::OnFailure,
@@ -3200,13 +3972,37 @@ model_verbosity = "high"
             shell_environment_policy: ShellEnvironmentPolicy::default(),
             user_instructions: None,
             notify: None,
+            response_cache: ResponseCacheConfig::default(),
+            app_server: AppServerConfig::default(),
+            exec_output: ExecOutputConfig::default(),
+            audio_transcription: AudioTranscriptionConfig::default(),
+            code_search: CodeSearchConfig::default(),
+            model_routing: ModelRoutingConfig::default(),
+            http: HttpClientSettings::default(),
+            hooks: HooksConfig::default(),
+            lsp_servers: BTreeMap::new(),
+            templates: BTreeMap::new(),
+            tool_filter: ToolFilter::default(),
+            notify_types: None,
+            desktop_notifications: Notifications::Enabled(false),
+            notification_webhooks: Vec::new(),
+            schedules: Vec::new(),
+            worktree_isolation: false,
+            auto_commit: false,
+            turn_heartbeat_interval: Some(Duration::from_millis(
+                DEFAULT_TURN_HEARTBEAT_INTERVAL_MS,
+            )),
+            rate_limit_pause_percent: Some(f64::from(DEFAULT_RATE_LIMIT_PAUSE_PERCENT)),
             cwd: fixture.cwd(),
+            workspace_roots: Vec::new(),
             cli_auth_credentials_store_mode: Default::default(),
+            account: None,
             mcp_servers: HashMap::new(),
             mcp_oauth_credentials_store_mode: Default::default(),
             model_providers: fixture.model_provider_map.clone(),
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
+            org_instructions_file: None,
             tool_output_token_limit: None,
             codex_home: fixture.codex_home(),
             history: History::default(),
@@ -3214,6 +4010,7 @@ model_verbosity = "high"
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
             show_raw_agent_reasoning: false,
+            reasoning_redaction: ReasoningRedaction::None,
             model_reasoning_effort: Some(ReasoningEffort::High),
             model_reasoning_summary: ReasoningSummary::Detailed,
             model_verbosity: Some(Verbosity::High),
@@ -3230,12 +4027,16 @@ model_verbosity = "high"
             use_experimental_use_rmcp_client: false,
             features: Features::with_defaults(),
             active_profile: Some("gpt5".to_string()),
-            active_project: ProjectConfig { trust_level: None },
+            active_project: ProjectConfig::default(),
             windows_wsl_setup_acknowledged: false,
             notices: Default::default(),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
             otel: OtelConfig::default(),
+            experiment: None,
+            remote_enforced_keys: BTreeSet::new(),
+            cli_overrides: Vec::new(),
+            config_overrides: gpt5_profile_overrides,
         };
 
         assert_eq!(expected_gpt5_profile_config, gpt5_profile_config);
@@ -3512,6 +4313,7 @@ trust_level = "untrusted"
             test_path.to_string_lossy().to_string(),
             ProjectConfig {
                 trust_level: Some(TrustLevel::Untrusted),
+                network_allowed_hosts: None,
             },
         );
 
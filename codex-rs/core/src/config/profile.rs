@@ -30,6 +30,12 @@ pub struct ConfigProfile {
     pub experimental_sandbox_command_assessment: Option<bool>,
     pub tools_web_search: Option<bool>,
     pub tools_view_image: Option<bool>,
+    /// Replaces `tools.enabled` while this profile is active.
+    pub tools_enabled: Option<Vec<String>>,
+    /// Replaces `tools.disabled` while this profile is active.
+    pub tools_disabled: Option<Vec<String>>,
+    /// Stored account to use while this profile is active.
+    pub account: Option<String>,
     /// Optional feature toggles scoped to this profile.
     #[serde(default)]
     pub features: Option<crate::features::FeaturesToml>,
@@ -0,0 +1,31 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+use crate::protocol::AskForApproval;
+use codex_protocol::config_types::ReasoningEffort;
+use codex_protocol::config_types::SandboxMode;
+
+/// Settings a new thread starts from, defined under `[templates.<name>]` in
+/// `config.toml` or in `CODEX_HOME/templates/<name>.toml`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ThreadTemplate {
+    /// Shown when templates are listed.
+    pub description: Option<String>,
+    pub model: Option<String>,
+    /// The key in the `model_providers` map identifying the
+    /// [`crate::ModelProviderInfo`] to use.
+    pub model_provider: Option<String>,
+    pub model_reasoning_effort: Option<ReasoningEffort>,
+    pub approval_policy: Option<AskForApproval>,
+    pub sandbox_mode: Option<SandboxMode>,
+    /// Added to the developer instructions of every turn.
+    pub developer_instructions: Option<String>,
+    /// Files and directories pinned to the thread's context. Relative paths
+    /// are resolved against the thread's working directory.
+    #[serde(default)]
+    pub pinned_context: Vec<PathBuf>,
+    /// Replaces `tools.enabled` for threads started from the template.
+    pub tools_enabled: Option<Vec<String>>,
+    /// Replaces `tools.disabled` for threads started from the template.
+    pub tools_disabled: Option<Vec<String>>,
+}
@@ -3,7 +3,9 @@
 // Note this file should generally be restricted to simple struct/enum
 // definitions that do not contain business logic.
 
+use codex_protocol::protocol::AskForApproval;
 use serde::Deserializer;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Duration;
@@ -43,6 +45,74 @@ pub struct McpServerConfig {
     /// Explicit deny-list of tools. These tools will be removed after applying `enabled_tools`.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub disabled_tools: Option<Vec<String>>,
+
+    /// Whether the server starts with the conversation or on first use.
+    #[serde(default)]
+    pub startup: McpServerStartup,
+
+    /// Stop a stdio server after this long without requests. It starts again
+    /// on next use.
+    #[serde(
+        default,
+        with = "option_duration_secs",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub idle_timeout_sec: Option<Duration>,
+
+    /// Keep the started server running when the conversation ends so the next
+    /// conversation in this process with the same configuration reuses it.
+    #[serde(default)]
+    pub warm_pool: bool,
+
+    /// Names to expose tools under, keyed by the name the server reports.
+    /// The model sees `mcp__<server>__<alias>`; calls still reach the
+    /// server's own tool.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_aliases: Option<HashMap<String, String>>,
+
+    /// Lets the server request model completions. Sampling is off unless
+    /// this table is present.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<McpSamplingConfig>,
+}
+
+/// `[mcp_servers.<name>.sampling]`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct McpSamplingConfig {
+    /// Completions are cut to this many tokens, or to the request's own
+    /// `maxTokens` when that is lower.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u32>,
+
+    /// Models the server may use. When unset, requests use the
+    /// conversation's model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub models: Option<Vec<String>>,
+
+    #[serde(default)]
+    pub approval: McpSamplingApproval,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum McpSamplingApproval {
+    /// Show each request to the user, who accepts or declines it.
+    #[default]
+    Ask,
+    /// Run requests without asking.
+    Auto,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum McpServerStartup {
+    /// Start when the conversation is created.
+    #[default]
+    Eager,
+    /// Start on the first request, offering the tools seen on the server's
+    /// previous run until then.
+    Lazy,
 }
 
 impl<'de> Deserialize<'de> for McpServerConfig {
@@ -70,6 +140,8 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             url: Option<String>,
             bearer_token: Option<String>,
             bearer_token_env_var: Option<String>,
+            #[serde(default)]
+            scopes: Option<Vec<String>>,
 
             // shared
             #[serde(default)]
@@ -84,6 +156,16 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             enabled_tools: Option<Vec<String>>,
             #[serde(default)]
             disabled_tools: Option<Vec<String>>,
+            #[serde(default)]
+            startup: McpServerStartup,
+            #[serde(default, with = "option_duration_secs")]
+            idle_timeout_sec: Option<Duration>,
+            #[serde(default)]
+            warm_pool: bool,
+            #[serde(default)]
+            tool_aliases: Option<HashMap<String, String>>,
+            #[serde(default)]
+            sampling: Option<McpSamplingConfig>,
         }
 
         let mut raw = RawMcpServerConfig::deserialize(deserializer)?;
@@ -100,6 +182,9 @@ impl<'de> Deserialize<'de> for McpServerConfig {
         let enabled = raw.enabled.unwrap_or_else(default_enabled);
         let enabled_tools = raw.enabled_tools.clone();
         let disabled_tools = raw.disabled_tools.clone();
+        if let Some(aliases) = &raw.tool_aliases {
+            validate_tool_aliases(aliases).map_err(SerdeError::custom)?;
+        }
 
         fn throw_if_set<E, T>(transport: &str, field: &str, value: Option<&T>) -> Result<(), E>
         where
@@ -123,6 +208,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             throw_if_set("stdio", "bearer_token", raw.bearer_token.as_ref())?;
             throw_if_set("stdio", "http_headers", raw.http_headers.as_ref())?;
             throw_if_set("stdio", "env_http_headers", raw.env_http_headers.as_ref())?;
+            throw_if_set("stdio", "scopes", raw.scopes.as_ref())?;
             McpServerTransportConfig::Stdio {
                 command,
                 args: raw.args.clone().unwrap_or_default(),
@@ -141,6 +227,7 @@ impl<'de> Deserialize<'de> for McpServerConfig {
                 bearer_token_env_var: raw.bearer_token_env_var.clone(),
                 http_headers: raw.http_headers.clone(),
                 env_http_headers: raw.env_http_headers.take(),
+                scopes: raw.scopes.take(),
             }
         } else {
             return Err(SerdeError::custom("invalid transport"));
@@ -153,10 +240,43 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             enabled,
             enabled_tools,
             disabled_tools,
+            startup: raw.startup,
+            idle_timeout_sec: raw.idle_timeout_sec,
+            warm_pool: raw.warm_pool,
+            tool_aliases: raw.tool_aliases,
+            sampling: raw.sampling,
         })
     }
 }
 
+/// Aliases become part of tool names sent to the model, so they are held to
+/// the same characters as server names, and no two tools may share one.
+fn validate_tool_aliases(aliases: &HashMap<String, String>) -> Result<(), String> {
+    let mut seen = HashMap::new();
+    for (tool, alias) in aliases {
+        if alias.is_empty()
+            || !alias
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(format!(
+                "invalid alias `{alias}` for tool `{tool}`: use only letters, digits, `_`, and `-`"
+            ));
+        }
+        if let Some(other) = seen.insert(alias.as_str(), tool.as_str()) {
+            let (first, second) = if other < tool.as_str() {
+                (other, tool.as_str())
+            } else {
+                (tool.as_str(), other)
+            };
+            return Err(format!(
+                "tools `{first}` and `{second}` both use the alias `{alias}`"
+            ));
+        }
+    }
+    Ok(())
+}
+
 const fn default_enabled() -> bool {
     true
 }
@@ -190,6 +310,10 @@ pub enum McpServerTransportConfig {
         /// HTTP headers where the value is sourced from an environment variable.
         #[serde(default, skip_serializing_if = "Option::is_none")]
         env_http_headers: Option<HashMap<String, String>>,
+        /// OAuth scopes to request when logging in to this server. When unset,
+        /// the authorization server's default scopes are used.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        scopes: Option<Vec<String>>,
     },
 }
 
@@ -271,6 +395,258 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// How much of the model's reasoning is kept in client notifications and in
+/// the rollout. The conversation history sent back to the model is never
+/// affected.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReasoningRedaction {
+    /// Keep reasoning as the model produced it.
+    #[default]
+    None,
+    /// Remove raw reasoning content and encrypted reasoning; keep summaries.
+    Raw,
+    /// Remove reasoning summaries too.
+    All,
+}
+
+/// Settings for the on-disk cache of auxiliary model calls (compaction
+/// summaries and sandbox command assessments), stored under
+/// `~/.codex/cache/responses`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ResponseCacheConfig {
+    /// When true, identical auxiliary requests are answered from the cache.
+    pub enabled: bool,
+
+    /// Seconds a cached response stays valid.
+    pub ttl_secs: u64,
+
+    /// Maximum number of cached responses; the oldest are evicted first.
+    pub max_entries: usize,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_secs: 24 * 60 * 60,
+            max_entries: 256,
+        }
+    }
+}
+
+/// Back-pressure the app-server applies to `turn/start`. Turns over a limit
+/// wait in a FIFO queue until a running turn finishes. Unset means unlimited.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct AppServerConfig {
+    /// Turns that may run at once across all threads.
+    pub max_concurrent_turns: Option<usize>,
+
+    /// Turns that may be in flight on a single thread, including input sent
+    /// while a turn is running that joins that turn.
+    pub max_turns_per_thread: Option<usize>,
+}
+
+/// How much of each command's output is streamed to clients and kept in
+/// memory.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct ExecOutputConfig {
+    /// Bytes per call streamed as output deltas before streaming falls back
+    /// to sampling.
+    pub stream_max_bytes: usize,
+
+    /// Past `stream_max_bytes`, stream one chunk in this many. 0 stops
+    /// streaming at the cap.
+    pub stream_sample_every: usize,
+
+    /// Bytes kept from the start of the output. With `retain_tail_bytes`,
+    /// bounds the output that is kept; the middle is dropped. Unset keeps
+    /// everything.
+    pub retain_head_bytes: Option<usize>,
+
+    /// Bytes kept from the end of the output.
+    pub retain_tail_bytes: Option<usize>,
+
+    /// Remove ANSI escape sequences from the output the model sees.
+    pub strip_ansi: bool,
+
+    /// Keep only the final state of lines rewritten with `\r`.
+    pub collapse_carriage_returns: bool,
+
+    /// Fold runs of progress-bar updates into their last line. Off by
+    /// default because folded lines are lost to the model.
+    pub fold_progress: bool,
+}
+
+impl Default for ExecOutputConfig {
+    fn default() -> Self {
+        Self {
+            stream_max_bytes: 1024 * 1024,
+            stream_sample_every: 0,
+            retain_head_bytes: None,
+            retain_tail_bytes: None,
+            strip_ansi: true,
+            collapse_carriage_returns: true,
+            fold_progress: false,
+        }
+    }
+}
+
+/// A language server queried for diagnostics in files changed by a patch.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct LspServerConfig {
+    /// Program that speaks the Language Server Protocol over stdio.
+    pub command: String,
+
+    #[serde(default)]
+    pub args: Vec<String>,
+
+    /// Variables set on top of the shell environment policy's environment.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+
+    /// File extensions, without the dot, this server is asked about.
+    pub extensions: Vec<String>,
+
+    /// Language identifier sent with opened files. Unset uses the extension.
+    #[serde(default)]
+    pub language_id: Option<String>,
+
+    /// How long to wait for the server to start and answer `initialize`.
+    #[serde(default = "default_lsp_startup_timeout_ms")]
+    pub startup_timeout_ms: u64,
+
+    /// How long to wait for diagnostics after a file changes.
+    #[serde(default = "default_lsp_diagnostics_timeout_ms")]
+    pub diagnostics_timeout_ms: u64,
+}
+
+const fn default_lsp_startup_timeout_ms() -> u64 {
+    10_000
+}
+
+const fn default_lsp_diagnostics_timeout_ms() -> u64 {
+    5_000
+}
+
+/// Models used for each kind of model request, with fallbacks for when a
+/// model is unavailable. Requests without a route use `model`.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct ModelRoutingConfig {
+    /// Tried in order when `model` is unavailable for a turn.
+    pub fallbacks: Vec<String>,
+
+    /// Summaries written when the conversation is compacted.
+    pub summarization: Option<ModelRoute>,
+
+    /// Risk assessments attached to command approval prompts.
+    pub risk_assessment: Option<ModelRoute>,
+
+    /// `auto_commit` commit messages.
+    pub commit_message: Option<ModelRoute>,
+
+    /// Thread titles generated by the `thread_titles` feature.
+    pub thread_title: Option<ModelRoute>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ModelRoute {
+    pub model: String,
+
+    /// Tried in order when `model` is unavailable, before falling back to
+    /// the main model.
+    #[serde(default)]
+    pub fallbacks: Vec<String>,
+}
+
+/// Embeddings index behind the `code_search` tool.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct CodeSearchConfig {
+    /// Key into `model_providers` of the provider that serves `/embeddings`.
+    /// Unset uses the session's model provider.
+    pub model_provider: Option<String>,
+
+    /// Embedding model.
+    pub model: String,
+
+    /// Larger files are not indexed.
+    pub max_file_bytes: u64,
+
+    /// Lines per indexed chunk.
+    pub chunk_lines: usize,
+}
+
+impl Default for CodeSearchConfig {
+    fn default() -> Self {
+        Self {
+            model_provider: None,
+            model: "text-embedding-3-small".to_string(),
+            max_file_bytes: 256 * 1024,
+            chunk_lines: 60,
+        }
+    }
+}
+
+/// Speech-to-text for audio files attached to user input.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct AudioTranscriptionConfig {
+    /// Key into `model_providers` of the provider that serves
+    /// `/audio/transcriptions`. Unset uses the session's model provider.
+    pub model_provider: Option<String>,
+
+    /// Transcription model.
+    pub model: String,
+}
+
+impl Default for AudioTranscriptionConfig {
+    fn default() -> Self {
+        Self {
+            model_provider: None,
+            model: "gpt-4o-mini-transcribe".to_string(),
+        }
+    }
+}
+
+/// Scripts run at lifecycle points. Each entry is an argv; the hook receives
+/// a JSON payload on stdin, and a non-zero exit blocks the action.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct HooksConfig {
+    /// Run before a turn is sent to the model.
+    pub pre_turn: Vec<Vec<String>>,
+
+    /// Run after a turn completes. Failures are reported as warnings.
+    pub post_turn: Vec<Vec<String>>,
+
+    /// Run before a command proposed by the model is executed.
+    pub pre_exec: Vec<Vec<String>>,
+
+    /// Run after a patch is applied. Failures are reported to the model.
+    pub post_patch: Vec<Vec<String>>,
+
+    /// How long a single hook may run before it is killed and treated as a
+    /// failure.
+    pub timeout_ms: u64,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            pre_turn: Vec::new(),
+            post_turn: Vec::new(),
+            pre_exec: Vec::new(),
+            post_patch: Vec::new(),
+            timeout_ms: 30_000,
+        }
+    }
+}
+
 // ===== OTEL configuration =====
 
 #[derive(Deserialize, Debug, Clone, PartialEq)]
@@ -290,6 +666,20 @@ pub struct OtelTlsConfig {
     pub client_private_key: Option<PathBuf>,
 }
 
+/// What an exporter strips from events before sending them.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct OtelScrubConfig {
+    /// Replace file paths in attribute values with a short hash.
+    #[serde(default)]
+    pub hash_paths: bool,
+    /// Drop prompt text and tool arguments and output.
+    #[serde(default)]
+    pub drop_content: bool,
+    /// Export only these attributes; entries ending in `*` match by prefix.
+    pub allowed_attributes: Option<Vec<String>>,
+}
+
 /// Which OTEL exporter to use.
 #[derive(Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "kebab-case")]
@@ -302,6 +692,8 @@ pub enum OtelExporterKind {
         protocol: OtelHttpProtocol,
         #[serde(default)]
         tls: Option<OtelTlsConfig>,
+        #[serde(default)]
+        scrub: OtelScrubConfig,
     },
     OtlpGrpc {
         endpoint: String,
@@ -309,6 +701,8 @@ pub enum OtelExporterKind {
         headers: HashMap<String, String>,
         #[serde(default)]
         tls: Option<OtelTlsConfig>,
+        #[serde(default)]
+        scrub: OtelScrubConfig,
     },
 }
 
@@ -356,6 +750,41 @@ impl Default for Notifications {
     }
 }
 
+/// An HTTP endpoint that receives user notifications as signed JSON POSTs.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NotificationWebhookConfig {
+    pub url: String,
+
+    /// Environment variable holding the secret used to sign each request
+    /// body with HMAC-SHA256. Requests are unsigned when unset.
+    #[serde(default)]
+    pub secret_env_var: Option<String>,
+
+    /// Only deliver these notification types. Defaults to all types.
+    #[serde(default)]
+    pub types: Option<Vec<String>>,
+}
+
+/// A prompt that `codex schedule run` sends to a fresh conversation on a
+/// recurring schedule.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleConfig {
+    /// Identifies the schedule in logs and notifications.
+    pub name: String,
+
+    /// Five-field cron expression (`minute hour day-of-month month
+    /// day-of-week`), evaluated in local time.
+    pub cron: String,
+
+    /// Sent as the only user message of each run.
+    pub prompt: String,
+
+    /// Working directory for each run. Defaults to the directory the
+    /// scheduler was started in.
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+}
+
 /// Collection of settings that are specific to the TUI.
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct Tui {
@@ -365,6 +794,24 @@ pub struct Tui {
     pub notifications: Notifications,
 }
 
+/// Where to fetch a centrally managed config overlay from, configured under
+/// `[remote_config]`. Usually set in `managed_config.toml` so that users
+/// cannot remove it.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RemoteConfigToml {
+    /// HTTPS URL serving the overlay as TOML or JSON.
+    pub url: String,
+    /// Base64-encoded Ed25519 public key the overlay must be signed with.
+    pub public_key: String,
+    /// How often to re-fetch the overlay while Codex runs. Defaults to one
+    /// hour.
+    pub refresh_interval_secs: Option<u64>,
+    /// Refuse to start when no verified overlay can be obtained, neither from
+    /// the endpoint nor from the local cache.
+    #[serde(default)]
+    pub required: bool,
+}
+
 /// Settings for notices we display to users via the tui and app-server clients
 /// (primarily the Codex IDE extension). NOTE: these are different from
 /// notifications - notices are warnings, NUX screens, acknowledgements, etc.
@@ -387,6 +834,35 @@ impl Notice {
     pub(crate) const TABLE_KEY: &'static str = "notice";
 }
 
+/// An A/B experiment declared under `[experiments.<name>]`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ExperimentToml {
+    /// Variants keyed by label, e.g. `[experiments.<name>.variants.control]`.
+    #[serde(default)]
+    pub variants: BTreeMap<String, ExperimentVariantToml>,
+}
+
+/// Configuration applied to conversations assigned to an experiment variant.
+/// Unset fields fall back to the active profile and the top-level config.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ExperimentVariantToml {
+    pub model: Option<String>,
+    pub approval_policy: Option<AskForApproval>,
+    /// Prompt fragments appended to the developer instructions, in order.
+    #[serde(default)]
+    pub prompt_fragments: Vec<String>,
+}
+
+/// Prices for a model under `[model_prices.<model>]`, in USD per million
+/// tokens. Used to estimate the cost of a conversation.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub struct ModelPrice {
+    pub input_per_million: f64,
+    /// Price of cached input tokens. Defaults to `input_per_million`.
+    pub cached_input_per_million: Option<f64>,
+    pub output_per_million: f64,
+}
+
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct SandboxWorkspaceWrite {
     #[serde(default)]
@@ -423,6 +899,12 @@ pub enum ShellEnvironmentPolicyInherit {
 
     /// Do not inherit any environment variables from the parent process.
     None,
+
+    /// Inherits the environment of the user's login shell, so variables set
+    /// by shell profiles and rc files (version managers such as nvm or pyenv,
+    /// `~/.cargo/env`, ...) are present even when Codex was started from a
+    /// GUI or a service. Resolved once per process.
+    LoginShell,
 }
 
 /// Policy for building the `env` when spawning a process via either the
@@ -663,6 +1145,7 @@ mod tests {
                 bearer_token_env_var: None,
                 http_headers: None,
                 env_http_headers: None,
+                scopes: None,
             }
         );
         assert!(cfg.enabled);
@@ -685,6 +1168,7 @@ mod tests {
                 bearer_token_env_var: Some("GITHUB_TOKEN".to_string()),
                 http_headers: None,
                 env_http_headers: None,
+                scopes: None,
             }
         );
         assert!(cfg.enabled);
@@ -711,10 +1195,67 @@ mod tests {
                     "X-Token".to_string(),
                     "TOKEN_ENV".to_string()
                 )])),
+                scopes: None,
+            }
+        );
+    }
+
+    #[test]
+    fn deserialize_streamable_http_server_config_with_scopes() {
+        let cfg: McpServerConfig = toml::from_str(
+            r#"
+            url = "https://example.com/mcp"
+            scopes = ["repo:read", "issues:write"]
+        "#,
+        )
+        .expect("should deserialize http config with scopes");
+
+        assert_eq!(
+            cfg.transport,
+            McpServerTransportConfig::StreamableHttp {
+                url: "https://example.com/mcp".to_string(),
+                bearer_token_env_var: None,
+                http_headers: None,
+                env_http_headers: None,
+                scopes: Some(vec!["repo:read".to_string(), "issues:write".to_string()]),
             }
         );
     }
 
+    #[test]
+    fn deserialize_server_config_with_lifecycle_options() {
+        let cfg: McpServerConfig = toml::from_str(
+            r#"
+            command = "echo"
+            startup = "lazy"
+            idle_timeout_sec = 300
+            warm_pool = true
+        "#,
+        )
+        .expect("should deserialize lifecycle options");
+
+        assert_eq!(cfg.startup, McpServerStartup::Lazy);
+        assert_eq!(cfg.idle_timeout_sec, Some(Duration::from_secs(300)));
+        assert!(cfg.warm_pool);
+
+        let cfg: McpServerConfig = toml::from_str(r#"command = "echo""#)
+            .expect("should deserialize without lifecycle options");
+        assert_eq!(cfg.startup, McpServerStartup::Eager);
+        assert_eq!(cfg.idle_timeout_sec, None);
+        assert!(!cfg.warm_pool);
+    }
+
+    #[test]
+    fn deserialize_rejects_scopes_for_stdio() {
+        toml::from_str::<McpServerConfig>(
+            r#"
+            command = "echo"
+            scopes = ["repo:read"]
+        "#,
+        )
+        .expect_err("should reject scopes for stdio transport");
+    }
+
     #[test]
     fn deserialize_server_config_with_tool_filters() {
         let cfg: McpServerConfig = toml::from_str(
@@ -730,6 +1271,65 @@ mod tests {
         assert_eq!(cfg.disabled_tools, Some(vec!["blocked".to_string()]));
     }
 
+    #[test]
+    fn deserialize_server_config_with_tool_aliases() {
+        let cfg: McpServerConfig = toml::from_str(
+            r#"
+            command = "echo"
+            tool_aliases = { search = "docs_search" }
+        "#,
+        )
+        .expect("should deserialize tool aliases");
+
+        assert_eq!(
+            cfg.tool_aliases,
+            Some(HashMap::from([(
+                "search".to_string(),
+                "docs_search".to_string()
+            )]))
+        );
+
+        toml::from_str::<McpServerConfig>(
+            r#"
+            command = "echo"
+            tool_aliases = { search = "docs.search" }
+        "#,
+        )
+        .expect_err("should reject aliases with invalid characters");
+
+        toml::from_str::<McpServerConfig>(
+            r#"
+            command = "echo"
+            tool_aliases = { search = "find", lookup = "find" }
+        "#,
+        )
+        .expect_err("should reject two tools sharing an alias");
+    }
+
+    #[test]
+    fn deserialize_server_config_with_sampling() {
+        let cfg: McpServerConfig = toml::from_str(
+            r#"
+            command = "echo"
+
+            [sampling]
+            max_tokens = 500
+            models = ["gpt-5-mini"]
+            approval = "auto"
+        "#,
+        )
+        .expect("should deserialize sampling settings");
+
+        assert_eq!(
+            cfg.sampling,
+            Some(McpSamplingConfig {
+                max_tokens: Some(500),
+                models: Some(vec!["gpt-5-mini".to_string()]),
+                approval: McpSamplingApproval::Auto,
+            })
+        );
+    }
+
     #[test]
     fn deserialize_rejects_command_and_url() {
         toml::from_str::<McpServerConfig>(
@@ -0,0 +1,810 @@
+//! Validation of `config.toml` with diagnostics that point at the offending
+//! line and column.
+//!
+//! Loading a config is deliberately lenient: unknown keys are ignored and the
+//! first type error aborts with a terse message. The validator walks the raw
+//! document against a description of the known keys so that every problem can
+//! be reported at once, including combinations of options that parse fine but
+//! do not make sense together.
+
+use std::borrow::Cow;
+use std::ops::Range;
+use std::path::Path;
+
+use serde::Serialize;
+use toml::Spanned;
+use toml::de::DeTable;
+use toml::de::DeValue;
+
+use crate::config::CONFIG_TOML_FILE;
+use crate::config::ConfigToml;
+use crate::features::is_known_feature_key;
+use crate::model_provider_info::built_in_model_providers;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    /// The config will fail to load or will not behave as written.
+    Error,
+    /// The config loads, but part of it is ignored or likely a mistake.
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ConfigDiagnostic {
+    pub severity: DiagnosticSeverity,
+    /// Dotted path of the key the diagnostic refers to, e.g.
+    /// `profiles.work.sandbox_mode`. Empty for document-level problems.
+    pub path: String,
+    pub message: String,
+    /// 1-based line of the offending key or value.
+    pub line: usize,
+    /// 1-based column, counted in characters.
+    pub column: usize,
+}
+
+/// Validates `$CODEX_HOME/config.toml`. A missing file is valid.
+pub async fn validate_config_file(codex_home: &Path) -> std::io::Result<Vec<ConfigDiagnostic>> {
+    let path = codex_home.join(CONFIG_TOML_FILE);
+    match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => Ok(validate_config_toml(&contents)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Validates the contents of a `config.toml`, returning diagnostics in
+/// document order.
+pub fn validate_config_toml(contents: &str) -> Vec<ConfigDiagnostic> {
+    let mut validator = Validator {
+        source: contents,
+        diagnostics: Vec::new(),
+    };
+    let root = match DeTable::parse(contents) {
+        Ok(root) => root,
+        Err(err) => {
+            validator.push(
+                DiagnosticSeverity::Error,
+                String::new(),
+                err.span().unwrap_or(0..0),
+                format!("invalid TOML: {}", err.message()),
+            );
+            return validator.diagnostics;
+        }
+    };
+    let root = root.get_ref();
+
+    validator.check_table(root, ROOT_FIELDS, "");
+    validator.check_conflicts(root);
+
+    // The walk above does not know every nested type (MCP servers, model
+    // providers, ...), so let serde have the final word. Only consult it when
+    // nothing else was wrong, since it would usually repeat the first error.
+    let has_errors = validator
+        .diagnostics
+        .iter()
+        .any(|d| d.severity == DiagnosticSeverity::Error);
+    if !has_errors && let Err(err) = toml::from_str::<ConfigToml>(contents) {
+        validator.push(
+            DiagnosticSeverity::Error,
+            String::new(),
+            err.span().unwrap_or(0..0),
+            err.message().to_string(),
+        );
+    }
+
+    validator
+        .diagnostics
+        .sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+    validator.diagnostics
+}
+
+/// Expected type of a value in the config.
+enum Shape {
+    Bool,
+    Integer,
+    String,
+    StringArray,
+    /// A string restricted to the listed values.
+    Enum(&'static [&'static str]),
+    /// A table whose keys are all known.
+    Table(&'static [(&'static str, Shape)]),
+    /// A table with arbitrary keys whose values all have the same shape.
+    Map(&'static Shape),
+    /// A table of feature toggles; keys must name a known feature.
+    Features,
+    /// Anything; checked by serde afterwards.
+    Any,
+}
+
+const APPROVAL_POLICIES: &[&str] = &["untrusted", "on-failure", "on-request", "never"];
+const SANDBOX_MODES: &[&str] = &[
+    "read-only",
+    "workspace-write",
+    "danger-full-access",
+    "audit",
+];
+
+const ROOT_FIELDS: &[(&str, Shape)] = &[
+    ("model", Shape::String),
+    ("review_model", Shape::String),
+    ("model_provider", Shape::String),
+    ("model_context_window", Shape::Integer),
+    ("model_max_output_tokens", Shape::Integer),
+    ("model_auto_compact_token_limit", Shape::Integer),
+    ("model_auto_compact_context_percent", Shape::Integer),
+    ("model_prices", Shape::Map(&Shape::Any)),
+    ("conversation_token_budget", Shape::Integer),
+    ("response_cache", Shape::Any),
+    ("app_server", Shape::Table(APP_SERVER_FIELDS)),
+    ("exec_output", Shape::Table(EXEC_OUTPUT_FIELDS)),
+    (
+        "audio_transcription",
+        Shape::Table(AUDIO_TRANSCRIPTION_FIELDS),
+    ),
+    ("code_search", Shape::Table(CODE_SEARCH_FIELDS)),
+    ("model_routing", Shape::Table(MODEL_ROUTING_FIELDS)),
+    ("http", Shape::Table(HTTP_FIELDS)),
+    ("hooks", Shape::Table(HOOKS_FIELDS)),
+    ("lsp_servers", Shape::Map(&Shape::Table(LSP_SERVER_FIELDS))),
+    ("approval_policy", Shape::Enum(APPROVAL_POLICIES)),
+    (
+        "shell_environment_policy",
+        Shape::Table(SHELL_ENVIRONMENT_POLICY_FIELDS),
+    ),
+    ("sandbox_mode", Shape::Enum(SANDBOX_MODES)),
+    (
+        "sandbox_workspace_write",
+        Shape::Table(SANDBOX_WORKSPACE_WRITE_FIELDS),
+    ),
+    ("notify", Shape::StringArray),
+    ("notify_types", Shape::StringArray),
+    ("desktop_notifications", Shape::Any),
+    ("notification_webhooks", Shape::Any),
+    ("schedules", Shape::Any),
+    ("workspace_roots", Shape::StringArray),
+    ("worktree_isolation", Shape::Bool),
+    ("auto_commit", Shape::Bool),
+    ("turn_heartbeat_interval_ms", Shape::Integer),
+    ("rate_limit_pause_percent", Shape::Integer),
+    ("instructions", Shape::String),
+    ("developer_instructions", Shape::String),
+    ("compact_prompt", Shape::String),
+    ("forced_chatgpt_workspace_id", Shape::String),
+    ("forced_login_method", Shape::String),
+    ("cli_auth_credentials_store", Shape::String),
+    ("account", Shape::String),
+    ("mcp_servers", Shape::Map(&Shape::Any)),
+    ("mcp_oauth_credentials_store", Shape::String),
+    ("model_providers", Shape::Map(&Shape::Any)),
+    ("project_doc_max_bytes", Shape::Integer),
+    ("project_doc_fallback_filenames", Shape::StringArray),
+    ("org_instructions_file", Shape::String),
+    ("remote_config", Shape::Table(REMOTE_CONFIG_FIELDS)),
+    ("tool_output_token_limit", Shape::Integer),
+    ("profile", Shape::String),
+    ("profiles", Shape::Map(&Shape::Table(PROFILE_FIELDS))),
+    ("templates", Shape::Map(&Shape::Table(TEMPLATE_FIELDS))),
+    ("history", Shape::Table(HISTORY_FIELDS)),
+    ("experiments", Shape::Map(&Shape::Any)),
+    ("experiment", Shape::String),
+    ("experiment_variant", Shape::String),
+    ("file_opener", Shape::String),
+    ("tui", Shape::Table(TUI_FIELDS)),
+    ("hide_agent_reasoning", Shape::Bool),
+    ("show_raw_agent_reasoning", Shape::Bool),
+    ("reasoning_redaction", Shape::Enum(&["none", "raw", "all"])),
+    ("model_reasoning_effort", Shape::String),
+    ("model_reasoning_summary", Shape::String),
+    ("model_verbosity", Shape::String),
+    ("model_supports_reasoning_summaries", Shape::Bool),
+    ("model_reasoning_summary_format", Shape::String),
+    ("chatgpt_base_url", Shape::String),
+    ("projects", Shape::Map(&Shape::Table(PROJECT_FIELDS))),
+    ("tools", Shape::Table(TOOLS_FIELDS)),
+    ("features", Shape::Features),
+    ("disable_paste_burst", Shape::Bool),
+    ("otel", Shape::Any),
+    ("windows_wsl_setup_acknowledged", Shape::Bool),
+    ("notice", Shape::Table(NOTICE_FIELDS)),
+    ("experimental_instructions_file", Shape::String),
+    ("experimental_compact_prompt_file", Shape::String),
+    ("experimental_use_unified_exec_tool", Shape::Bool),
+    ("experimental_use_rmcp_client", Shape::Bool),
+    ("experimental_use_freeform_apply_patch", Shape::Bool),
+    ("experimental_sandbox_command_assessment", Shape::Bool),
+    ("oss_provider", Shape::String),
+];
+
+const PROFILE_FIELDS: &[(&str, Shape)] = &[
+    ("model", Shape::String),
+    ("model_provider", Shape::String),
+    ("approval_policy", Shape::Enum(APPROVAL_POLICIES)),
+    ("sandbox_mode", Shape::Enum(SANDBOX_MODES)),
+    ("model_reasoning_effort", Shape::String),
+    ("model_reasoning_summary", Shape::String),
+    ("model_verbosity", Shape::String),
+    ("chatgpt_base_url", Shape::String),
+    ("experimental_instructions_file", Shape::String),
+    ("experimental_compact_prompt_file", Shape::String),
+    ("include_apply_patch_tool", Shape::Bool),
+    ("experimental_use_unified_exec_tool", Shape::Bool),
+    ("experimental_use_rmcp_client", Shape::Bool),
+    ("experimental_use_freeform_apply_patch", Shape::Bool),
+    ("experimental_sandbox_command_assessment", Shape::Bool),
+    ("tools_web_search", Shape::Bool),
+    ("tools_view_image", Shape::Bool),
+    ("tools_enabled", Shape::StringArray),
+    ("tools_disabled", Shape::StringArray),
+    ("account", Shape::String),
+    ("features", Shape::Features),
+    ("oss_provider", Shape::String),
+];
+
+const TEMPLATE_FIELDS: &[(&str, Shape)] = &[
+    ("description", Shape::String),
+    ("model", Shape::String),
+    ("model_provider", Shape::String),
+    ("model_reasoning_effort", Shape::String),
+    ("approval_policy", Shape::Enum(APPROVAL_POLICIES)),
+    ("sandbox_mode", Shape::Enum(SANDBOX_MODES)),
+    ("developer_instructions", Shape::String),
+    ("pinned_context", Shape::StringArray),
+    ("tools_enabled", Shape::StringArray),
+    ("tools_disabled", Shape::StringArray),
+];
+
+const SHELL_ENVIRONMENT_POLICY_FIELDS: &[(&str, Shape)] = &[
+    (
+        "inherit",
+        Shape::Enum(&["core", "all", "none", "login-shell"]),
+    ),
+    ("ignore_default_excludes", Shape::Bool),
+    ("exclude", Shape::StringArray),
+    ("set", Shape::Map(&Shape::String)),
+    ("include_only", Shape::StringArray),
+    ("experimental_use_profile", Shape::Bool),
+];
+
+const SANDBOX_WORKSPACE_WRITE_FIELDS: &[(&str, Shape)] = &[
+    ("writable_roots", Shape::StringArray),
+    ("network_access", Shape::Bool),
+    ("exclude_tmpdir_env_var", Shape::Bool),
+    ("exclude_slash_tmp", Shape::Bool),
+];
+
+const HISTORY_FIELDS: &[(&str, Shape)] = &[
+    ("persistence", Shape::Enum(&["save-all", "none"])),
+    ("max_bytes", Shape::Integer),
+];
+
+const APP_SERVER_FIELDS: &[(&str, Shape)] = &[
+    ("max_concurrent_turns", Shape::Integer),
+    ("max_turns_per_thread", Shape::Integer),
+];
+
+const EXEC_OUTPUT_FIELDS: &[(&str, Shape)] = &[
+    ("stream_max_bytes", Shape::Integer),
+    ("stream_sample_every", Shape::Integer),
+    ("retain_head_bytes", Shape::Integer),
+    ("retain_tail_bytes", Shape::Integer),
+    ("strip_ansi", Shape::Bool),
+    ("collapse_carriage_returns", Shape::Bool),
+    ("fold_progress", Shape::Bool),
+];
+
+const CODE_SEARCH_FIELDS: &[(&str, Shape)] = &[
+    ("model_provider", Shape::String),
+    ("model", Shape::String),
+    ("max_file_bytes", Shape::Integer),
+    ("chunk_lines", Shape::Integer),
+];
+
+const MODEL_ROUTING_FIELDS: &[(&str, Shape)] = &[
+    ("fallbacks", Shape::StringArray),
+    ("summarization", Shape::Table(MODEL_ROUTE_FIELDS)),
+    ("risk_assessment", Shape::Table(MODEL_ROUTE_FIELDS)),
+    ("commit_message", Shape::Table(MODEL_ROUTE_FIELDS)),
+    ("thread_title", Shape::Table(MODEL_ROUTE_FIELDS)),
+];
+
+const MODEL_ROUTE_FIELDS: &[(&str, Shape)] =
+    &[("model", Shape::String), ("fallbacks", Shape::StringArray)];
+
+const HTTP_FIELDS: &[(&str, Shape)] = &[
+    ("proxy", Shape::String),
+    ("no_proxy", Shape::StringArray),
+    ("ca_bundle", Shape::String),
+    ("trust_only_ca_bundle", Shape::Bool),
+    ("min_tls_version", Shape::Enum(&["1.2", "1.3"])),
+];
+
+const LSP_SERVER_FIELDS: &[(&str, Shape)] = &[
+    ("command", Shape::String),
+    ("args", Shape::StringArray),
+    ("env", Shape::Map(&Shape::String)),
+    ("extensions", Shape::StringArray),
+    ("language_id", Shape::String),
+    ("startup_timeout_ms", Shape::Integer),
+    ("diagnostics_timeout_ms", Shape::Integer),
+];
+
+const AUDIO_TRANSCRIPTION_FIELDS: &[(&str, Shape)] =
+    &[("model_provider", Shape::String), ("model", Shape::String)];
+
+const HOOKS_FIELDS: &[(&str, Shape)] = &[
+    ("pre_turn", Shape::Any),
+    ("post_turn", Shape::Any),
+    ("pre_exec", Shape::Any),
+    ("post_patch", Shape::Any),
+    ("timeout_ms", Shape::Integer),
+];
+
+const REMOTE_CONFIG_FIELDS: &[(&str, Shape)] = &[
+    ("url", Shape::String),
+    ("public_key", Shape::String),
+    ("refresh_interval_secs", Shape::Integer),
+    ("required", Shape::Bool),
+];
+
+const TUI_FIELDS: &[(&str, Shape)] = &[("notifications", Shape::Any)];
+
+const PROJECT_FIELDS: &[(&str, Shape)] = &[
+    ("trust_level", Shape::Enum(&["trusted", "untrusted"])),
+    ("network_allowed_hosts", Shape::StringArray),
+];
+
+const TOOLS_FIELDS: &[(&str, Shape)] = &[
+    ("web_search", Shape::Bool),
+    ("web_search_request", Shape::Bool),
+    ("view_image", Shape::Bool),
+    ("enabled", Shape::StringArray),
+    ("disabled", Shape::StringArray),
+];
+
+const NOTICE_FIELDS: &[(&str, Shape)] = &[
+    ("hide_full_access_warning", Shape::Bool),
+    ("hide_world_writable_warning", Shape::Bool),
+    ("hide_rate_limit_model_nudge", Shape::Bool),
+    ("hide_gpt5_1_migration_prompt", Shape::Bool),
+    ("hide_gpt_5_1_codex_max_migration_prompt", Shape::Bool),
+];
+
+struct Validator<'a> {
+    source: &'a str,
+    diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl Validator<'_> {
+    fn push(
+        &mut self,
+        severity: DiagnosticSeverity,
+        path: String,
+        span: Range<usize>,
+        message: String,
+    ) {
+        let (line, column) = line_column(self.source, span.start);
+        self.diagnostics.push(ConfigDiagnostic {
+            severity,
+            path,
+            message,
+            line,
+            column,
+        });
+    }
+
+    fn check_table(&mut self, table: &DeTable<'_>, fields: &[(&str, Shape)], prefix: &str) {
+        for (key, value) in table.iter() {
+            let name: &str = key.get_ref();
+            let path = join_path(prefix, name);
+            match fields.iter().find(|(field, _)| *field == name) {
+                Some((_, shape)) => self.check_value(value, shape, &path),
+                None => {
+                    let mut message = format!("unknown key `{name}` is ignored");
+                    if let Some(suggestion) =
+                        closest_match(name, fields.iter().map(|(field, _)| *field))
+                    {
+                        message.push_str(&format!("; did you mean `{suggestion}`?"));
+                    }
+                    self.push(DiagnosticSeverity::Warning, path, key.span(), message);
+                }
+            }
+        }
+    }
+
+    fn check_value(&mut self, value: &Spanned<DeValue<'_>>, shape: &Shape, path: &str) {
+        let expected = match shape {
+            Shape::Any => return,
+            Shape::Bool if matches!(value.get_ref(), DeValue::Boolean(_)) => return,
+            Shape::Integer if matches!(value.get_ref(), DeValue::Integer(_)) => return,
+            Shape::String if matches!(value.get_ref(), DeValue::String(_)) => return,
+            Shape::Bool => "a boolean",
+            Shape::Integer => "an integer",
+            Shape::String => "a string",
+            Shape::StringArray => {
+                let DeValue::Array(items) = value.get_ref() else {
+                    self.type_mismatch(value, "an array of strings", path);
+                    return;
+                };
+                for (index, item) in items.iter().enumerate() {
+                    self.check_value(item, &Shape::String, &format!("{path}[{index}]"));
+                }
+                return;
+            }
+            Shape::Enum(allowed) => {
+                let DeValue::String(actual) = value.get_ref() else {
+                    self.type_mismatch(value, "a string", path);
+                    return;
+                };
+                if !allowed.contains(&actual.as_ref()) {
+                    let options = allowed
+                        .iter()
+                        .map(|option| format!("`{option}`"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    self.push(
+                        DiagnosticSeverity::Error,
+                        path.to_string(),
+                        value.span(),
+                        format!("`{actual}` is not a valid value; expected one of {options}"),
+                    );
+                }
+                return;
+            }
+            Shape::Table(fields) => {
+                let DeValue::Table(table) = value.get_ref() else {
+                    self.type_mismatch(value, "a table", path);
+                    return;
+                };
+                self.check_table(table, fields, path);
+                return;
+            }
+            Shape::Map(entry) => {
+                let DeValue::Table(table) = value.get_ref() else {
+                    self.type_mismatch(value, "a table", path);
+                    return;
+                };
+                for (key, item) in table.iter() {
+                    let name: &str = key.get_ref();
+                    self.check_value(item, entry, &join_path(path, name));
+                }
+                return;
+            }
+            Shape::Features => {
+                let DeValue::Table(table) = value.get_ref() else {
+                    self.type_mismatch(value, "a table", path);
+                    return;
+                };
+                for (key, item) in table.iter() {
+                    let name: &str = key.get_ref();
+                    let item_path = join_path(path, name);
+                    if is_known_feature_key(name) {
+                        self.check_value(item, &Shape::Bool, &item_path);
+                    } else {
+                        self.push(
+                            DiagnosticSeverity::Warning,
+                            item_path,
+                            key.span(),
+                            format!("unknown feature `{name}` is ignored"),
+                        );
+                    }
+                }
+                return;
+            }
+        };
+        self.type_mismatch(value, expected, path);
+    }
+
+    fn type_mismatch(&mut self, value: &Spanned<DeValue<'_>>, expected: &str, path: &str) {
+        let actual = match value.get_ref() {
+            DeValue::String(_) => "a string",
+            DeValue::Integer(_) => "an integer",
+            DeValue::Float(_) => "a float",
+            DeValue::Boolean(_) => "a boolean",
+            DeValue::Datetime(_) => "a datetime",
+            DeValue::Array(_) => "an array",
+            DeValue::Table(_) => "a table",
+        };
+        self.push(
+            DiagnosticSeverity::Error,
+            path.to_string(),
+            value.span(),
+            format!("expected {expected}, found {actual}"),
+        );
+    }
+
+    /// Reports options that are individually valid but contradict each other.
+    fn check_conflicts(&mut self, root: &DeTable<'_>) {
+        let profiles = lookup(root, "profiles").and_then(|value| match value.get_ref() {
+            DeValue::Table(table) => Some(table),
+            _ => None,
+        });
+
+        if let Some(profile) = lookup(root, "profile")
+            && let DeValue::String(name) = profile.get_ref()
+            && profiles.and_then(|table| lookup(table, name)).is_none()
+        {
+            self.push(
+                DiagnosticSeverity::Error,
+                "profile".to_string(),
+                profile.span(),
+                format!("profile `{name}` is not defined under [profiles]"),
+            );
+        }
+
+        let user_providers: Vec<String> = lookup(root, "model_providers")
+            .and_then(|value| match value.get_ref() {
+                DeValue::Table(table) => Some(
+                    table
+                        .iter()
+                        .map(|(key, _)| key.get_ref().to_string())
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .unwrap_or_default();
+        let built_in = built_in_model_providers();
+        let mut scopes = vec![(String::new(), root)];
+        if let Some(profiles) = profiles {
+            for (name, profile) in profiles.iter() {
+                if let DeValue::Table(table) = profile.get_ref() {
+                    scopes.push((format!("profiles.{}", name.get_ref()), table));
+                }
+            }
+        }
+
+        for (prefix, table) in &scopes {
+            if let Some(provider) = lookup(table, "model_provider")
+                && let DeValue::String(id) = provider.get_ref()
+                && !built_in.contains_key(id.as_ref())
+                && !user_providers
+                    .iter()
+                    .any(|known| known.as_str() == id.as_ref())
+            {
+                self.push(
+                    DiagnosticSeverity::Error,
+                    join_path(prefix, "model_provider"),
+                    provider.span(),
+                    format!(
+                        "model provider `{id}` is not built in or defined under [model_providers]"
+                    ),
+                );
+            }
+
+            // Profiles inherit whatever they do not set from the top level.
+            let approval =
+                lookup(table, "approval_policy").or_else(|| lookup(root, "approval_policy"));
+            let sandbox = lookup(table, "sandbox_mode").or_else(|| lookup(root, "sandbox_mode"));
+            if let (Some(approval), Some(sandbox)) = (approval, sandbox)
+                && string_value(approval) == Some("never")
+                && string_value(sandbox) == Some("danger-full-access")
+            {
+                let span = lookup(table, "sandbox_mode")
+                    .or_else(|| lookup(table, "approval_policy"))
+                    .unwrap_or(sandbox)
+                    .span();
+                self.push(
+                    DiagnosticSeverity::Warning,
+                    join_path(prefix, "sandbox_mode"),
+                    span,
+                    "commands will run outside the sandbox without asking for approval".to_string(),
+                );
+            }
+        }
+
+        if let Some((key, _)) = lookup_entry(root, "sandbox_workspace_write")
+            && let Some(sandbox) = lookup(root, "sandbox_mode")
+            && let Some(mode) = string_value(sandbox)
+            && mode != "workspace-write"
+        {
+            self.push(
+                DiagnosticSeverity::Warning,
+                "sandbox_workspace_write".to_string(),
+                key.span(),
+                format!(
+                    "[sandbox_workspace_write] only applies when sandbox_mode is `workspace-write`, but it is `{mode}`"
+                ),
+            );
+        }
+    }
+}
+
+fn lookup<'a, 'i>(table: &'a DeTable<'i>, key: &str) -> Option<&'a Spanned<DeValue<'i>>> {
+    lookup_entry(table, key).map(|(_, value)| value)
+}
+
+fn lookup_entry<'a, 'i>(
+    table: &'a DeTable<'i>,
+    key: &str,
+) -> Option<(&'a Spanned<Cow<'i, str>>, &'a Spanned<DeValue<'i>>)> {
+    table
+        .iter()
+        .find(|(candidate, _)| candidate.get_ref().as_ref() == key)
+}
+
+fn string_value<'a>(value: &'a Spanned<DeValue<'_>>) -> Option<&'a str> {
+    match value.get_ref() {
+        DeValue::String(text) => Some(text.as_ref()),
+        _ => None,
+    }
+}
+
+fn join_path(prefix: &str, key: &str) -> String {
+    let key: Cow<'_, str> = if !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        Cow::Borrowed(key)
+    } else {
+        Cow::Owned(format!("\"{key}\""))
+    };
+    if prefix.is_empty() {
+        key.into_owned()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+/// Converts a byte offset into a 1-based line and character column.
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+/// Returns the candidate closest to `name` if it is plausibly a typo.
+fn closest_match<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let threshold = (name.chars().count() / 3).max(2);
+    candidates
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn summarize(contents: &str) -> Vec<(DiagnosticSeverity, String, usize, usize)> {
+        validate_config_toml(contents)
+            .into_iter()
+            .map(|d| (d.severity, d.path, d.line, d.column))
+            .collect()
+    }
+
+    #[test]
+    fn valid_config_has_no_diagnostics() {
+        let contents = r#"
+model = "gpt-5-codex"
+approval_policy = "on-request"
+sandbox_mode = "workspace-write"
+profile = "work"
+
+[sandbox_workspace_write]
+network_access = true
+
+[profiles.work]
+model_provider = "openai"
+
+[features]
+repo_map = true
+"#;
+        assert_eq!(validate_config_toml(contents), Vec::new());
+    }
+
+    #[test]
+    fn reports_unknown_keys_with_suggestions() {
+        let contents = "modle = \"o3\"\n\n[tui]\nnotificatons = true\n";
+        let diagnostics = validate_config_toml(contents);
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|d| (d.path.as_str(), d.line, d.column, d.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "modle",
+                    1,
+                    1,
+                    "unknown key `modle` is ignored; did you mean `model`?"
+                ),
+                (
+                    "tui.notificatons",
+                    4,
+                    1,
+                    "unknown key `notificatons` is ignored; did you mean `notifications`?"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_type_mismatches_and_invalid_enum_values() {
+        let contents = "hide_agent_reasoning = \"yes\"\n[profiles.ci]\nsandbox_mode = \"full\"\n";
+        assert_eq!(
+            summarize(contents),
+            vec![
+                (
+                    DiagnosticSeverity::Error,
+                    "hide_agent_reasoning".to_string(),
+                    1,
+                    24
+                ),
+                (
+                    DiagnosticSeverity::Error,
+                    "profiles.ci.sandbox_mode".to_string(),
+                    3,
+                    16
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_conflicting_options() {
+        let contents = r#"
+approval_policy = "never"
+sandbox_mode = "danger-full-access"
+profile = "missing"
+model_provider = "nowhere"
+
+[sandbox_workspace_write]
+network_access = true
+"#;
+        assert_eq!(
+            summarize(contents),
+            vec![
+                (
+                    DiagnosticSeverity::Warning,
+                    "sandbox_mode".to_string(),
+                    3,
+                    16
+                ),
+                (DiagnosticSeverity::Error, "profile".to_string(), 4, 11),
+                (
+                    DiagnosticSeverity::Error,
+                    "model_provider".to_string(),
+                    5,
+                    18
+                ),
+                (
+                    DiagnosticSeverity::Warning,
+                    "sandbox_workspace_write".to_string(),
+                    7,
+                    2
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let diagnostics = validate_config_toml("model = \n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostics[0].line, 1);
+    }
+}
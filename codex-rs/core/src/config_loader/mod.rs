@@ -1,7 +1,10 @@
 mod macos;
+mod remote;
 
 use crate::config::CONFIG_TOML_FILE;
+use crate::config::types::RemoteConfigToml;
 use macos::load_managed_admin_config_layer;
+use remote::load_remote_config_layer;
 use std::io;
 use std::path::Path;
 use std::path::PathBuf;
@@ -16,6 +19,7 @@ pub(crate) struct LoadedConfigLayers {
     pub base: TomlValue,
     pub managed_config: Option<TomlValue>,
     pub managed_preferences: Option<TomlValue>,
+    pub remote_config: Option<TomlValue>,
 }
 
 #[derive(Debug, Default)]
@@ -28,6 +32,11 @@ pub(crate) struct LoaderOverrides {
 // Configuration layering pipeline (top overrides bottom):
 //
 //        +-------------------------+
+//        | Remote config (**)      |
+//        +-------------------------+
+//                    ^
+//                    |
+//        +-------------------------+
 //        | Managed preferences (*) |
 //        +-------------------------+
 //                    ^
@@ -42,6 +51,8 @@ pub(crate) struct LoaderOverrides {
 //        +-------------------------+
 //
 // (*) Only available on macOS via managed device profiles.
+// (**) Only when `[remote_config]` is set; fetched over HTTPS and verified
+//      against a pinned public key.
 
 pub async fn load_config_as_toml(codex_home: &Path) -> io::Result<TomlValue> {
     load_config_as_toml_with_overrides(codex_home, LoaderOverrides::default()).await
@@ -95,10 +106,39 @@ async fn load_config_layers_internal(
     #[cfg(not(target_os = "macos"))]
     let managed_preferences = load_managed_admin_config_layer(None).await?;
 
+    let base = user_config.unwrap_or_else(default_empty_table);
+    let remote_config =
+        match remote_config_settings(&base, [&managed_config, &managed_preferences])? {
+            Some(settings) => load_remote_config_layer(codex_home, &settings).await?,
+            None => None,
+        };
+
     Ok(LoadedConfigLayers {
-        base: user_config.unwrap_or_else(default_empty_table),
+        base,
         managed_config,
         managed_preferences,
+        remote_config,
+    })
+}
+
+/// Reads `[remote_config]` with the managed layers taking precedence, so that
+/// an administrator-provided endpoint cannot be replaced in `config.toml`.
+fn remote_config_settings(
+    base: &TomlValue,
+    managed_layers: [&Option<TomlValue>; 2],
+) -> io::Result<Option<RemoteConfigToml>> {
+    let mut merged = base.clone();
+    for overlay in managed_layers.into_iter().flatten() {
+        merge_toml_values(&mut merged, overlay);
+    }
+    let Some(value) = merged.get("remote_config") else {
+        return Ok(None);
+    };
+    value.clone().try_into().map(Some).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid [remote_config]: {err}"),
+        )
     })
 }
 
@@ -164,9 +204,13 @@ fn apply_managed_layers(layers: LoadedConfigLayers) -> TomlValue {
         mut base,
         managed_config,
         managed_preferences,
+        remote_config,
     } = layers;
 
-    for overlay in [managed_config, managed_preferences].into_iter().flatten() {
+    for overlay in [managed_config, managed_preferences, remote_config]
+        .into_iter()
+        .flatten()
+    {
         merge_toml_values(&mut base, &overlay);
     }
 
@@ -0,0 +1,491 @@
+//! Remote config overlay.
+//!
+//! Organizations can point `[remote_config]` at an HTTPS endpoint that serves
+//! a TOML or JSON overlay together with a version in the
+//! `x-codex-config-version` header and an Ed25519 signature in the
+//! `x-codex-signature` header. The signature covers the configured URL, the
+//! version and the body, so an overlay cannot be replayed from another
+//! endpoint or rolled back to an older version. The overlay is verified
+//! against the pinned public key and merged on top of every local layer.
+//!
+//! Config loads use the last verified copy cached in `CODEX_HOME` and leave
+//! fetching to a background task. Only when there is no usable cache does a
+//! load fetch the overlay itself, at most once per URL per process.
+
+use std::collections::HashSet;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use ring::signature::ED25519;
+use ring::signature::UnparsedPublicKey;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::fs;
+use toml::Value as TomlValue;
+
+use crate::config::types::RemoteConfigToml;
+use crate::default_client::create_client;
+
+const REMOTE_CONFIG_CACHE_FILE: &str = "remote_config_cache.json";
+const SIGNATURE_HEADER: &str = "x-codex-signature";
+const VERSION_HEADER: &str = "x-codex-config-version";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// The refresh task is process-wide; later config loads reuse it.
+static REFRESH_TASK: OnceLock<()> = OnceLock::new();
+
+/// URLs a config load has already fetched in the foreground.
+static FOREGROUND_FETCHES: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+/// Signed overlay exactly as served, so that the cache can be verified again
+/// on every load.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SignedOverlay {
+    url: String,
+    version: u64,
+    body: String,
+    signature: String,
+}
+
+/// Returns the verified remote overlay. The cached copy is used when it
+/// verifies; otherwise the overlay is fetched once per process.
+pub(crate) async fn load_remote_config_layer(
+    codex_home: &Path,
+    settings: &RemoteConfigToml,
+) -> io::Result<Option<TomlValue>> {
+    if let Some((_, overlay)) = read_verified_cache(codex_home, settings).await {
+        spawn_refresh_task(codex_home, settings, Duration::ZERO);
+        return Ok(Some(overlay));
+    }
+
+    if claim_foreground_fetch(&settings.url) {
+        let fetched = fetch_overlay(settings)
+            .await
+            .and_then(|signed| verify_overlay(settings, &signed).map(|overlay| (signed, overlay)));
+        match fetched {
+            Ok((signed, overlay)) => {
+                if let Err(err) = write_cache(codex_home, &signed).await {
+                    tracing::warn!("failed to cache remote config: {err}");
+                }
+                spawn_refresh_task(codex_home, settings, refresh_interval(settings));
+                return Ok(Some(overlay));
+            }
+            Err(err) => {
+                tracing::warn!("failed to load remote config from {}: {err}", settings.url);
+            }
+        }
+    }
+    spawn_refresh_task(codex_home, settings, refresh_interval(settings));
+
+    if settings.required {
+        return Err(io::Error::other(format!(
+            "remote config from {} is required but could not be fetched or verified",
+            settings.url
+        )));
+    }
+    Ok(None)
+}
+
+fn claim_foreground_fetch(url: &str) -> bool {
+    FOREGROUND_FETCHES
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(url.to_string())
+}
+
+fn refresh_interval(settings: &RemoteConfigToml) -> Duration {
+    settings
+        .refresh_interval_secs
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_REFRESH_INTERVAL)
+        .max(Duration::from_secs(60))
+}
+
+/// Keeps the cache current while Codex runs. Sessions started after a
+/// refresh pick up the new overlay.
+fn spawn_refresh_task(codex_home: &Path, settings: &RemoteConfigToml, first_delay: Duration) {
+    if REFRESH_TASK.set(()).is_err() {
+        return;
+    }
+    let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+        return;
+    };
+    let codex_home = codex_home.to_path_buf();
+    let settings = settings.clone();
+    let interval = refresh_interval(&settings);
+    runtime.spawn(async move {
+        tokio::time::sleep(first_delay).await;
+        loop {
+            if let Err(err) = refresh_cache(&codex_home, &settings).await {
+                tracing::warn!(
+                    "failed to refresh remote config from {}: {err}",
+                    settings.url
+                );
+            }
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+/// Fetches the overlay and replaces the cache unless the fetched version is
+/// older than the cached one.
+async fn refresh_cache(codex_home: &Path, settings: &RemoteConfigToml) -> io::Result<()> {
+    let signed = fetch_overlay(settings).await?;
+    verify_overlay(settings, &signed)?;
+    if let Some((cached, _)) = read_verified_cache(codex_home, settings).await
+        && signed.version < cached.version
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "remote config version {} is older than cached version {}",
+                signed.version, cached.version
+            ),
+        ));
+    }
+    write_cache(codex_home, &signed).await
+}
+
+async fn fetch_overlay(settings: &RemoteConfigToml) -> io::Result<SignedOverlay> {
+    let url = reqwest::Url::parse(&settings.url)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    let loopback = matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    if url.scheme() != "https" && !loopback {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "remote config url must use https",
+        ));
+    }
+
+    let response = tokio::time::timeout(FETCH_TIMEOUT, create_client().get(url).send())
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "request timed out"))?
+        .map_err(io::Error::other)?;
+    if !response.status().is_success() {
+        return Err(io::Error::other(format!(
+            "unexpected status {}",
+            response.status()
+        )));
+    }
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+            .ok_or_else(|| io::Error::other(format!("response has no {name} header")))
+    };
+    let signature = header(SIGNATURE_HEADER)?;
+    let version = header(VERSION_HEADER)?
+        .trim()
+        .parse::<u64>()
+        .map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{VERSION_HEADER} is not a number: {err}"),
+            )
+        })?;
+    let body = tokio::time::timeout(FETCH_TIMEOUT, response.text())
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "request timed out"))?
+        .map_err(io::Error::other)?;
+
+    Ok(SignedOverlay {
+        url: settings.url.clone(),
+        version,
+        body,
+        signature,
+    })
+}
+
+/// Bytes the signature covers: the configured URL, the version and the body,
+/// one per line.
+fn signed_payload(url: &str, version: u64, body: &str) -> String {
+    format!("{url}\n{version}\n{body}")
+}
+
+/// Checks the signature over the URL, version and raw body and parses the
+/// body into a table. The overlay cannot change where it is fetched from or
+/// which key signs it.
+fn verify_overlay(settings: &RemoteConfigToml, signed: &SignedOverlay) -> io::Result<TomlValue> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    if signed.url != settings.url {
+        return Err(invalid(format!(
+            "overlay was fetched from {}, not {}",
+            signed.url, settings.url
+        )));
+    }
+    let public_key = BASE64_STANDARD
+        .decode(settings.public_key.trim())
+        .map_err(|err| {
+            invalid(format!(
+                "remote_config.public_key is not valid base64: {err}"
+            ))
+        })?;
+    let signature = BASE64_STANDARD
+        .decode(signed.signature.trim())
+        .map_err(|err| invalid(format!("signature is not valid base64: {err}")))?;
+    let payload = signed_payload(&settings.url, signed.version, &signed.body);
+    UnparsedPublicKey::new(&ED25519, &public_key)
+        .verify(payload.as_bytes(), &signature)
+        .map_err(|_| invalid("signature does not match remote_config.public_key".to_string()))?;
+
+    let mut overlay = parse_overlay(&signed.body)?;
+    if let TomlValue::Table(table) = &mut overlay {
+        table.remove("remote_config");
+    }
+    Ok(overlay)
+}
+
+fn parse_overlay(body: &str) -> io::Result<TomlValue> {
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let value = if body.trim_start().starts_with('{') {
+        let json: serde_json::Value = serde_json::from_str(body)
+            .map_err(|err| invalid(format!("remote config is not valid JSON: {err}")))?;
+        TomlValue::try_from(json).map_err(|err| {
+            invalid(format!(
+                "remote config cannot be represented as TOML: {err}"
+            ))
+        })?
+    } else {
+        toml::from_str::<TomlValue>(body)
+            .map_err(|err| invalid(format!("remote config is not valid TOML: {err}")))?
+    };
+    if !value.is_table() {
+        return Err(invalid("remote config must be a table".to_string()));
+    }
+    Ok(value)
+}
+
+fn cache_path(codex_home: &Path) -> PathBuf {
+    codex_home.join(REMOTE_CONFIG_CACHE_FILE)
+}
+
+async fn read_cache(codex_home: &Path) -> Option<SignedOverlay> {
+    let contents = fs::read_to_string(cache_path(codex_home)).await.ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+/// Returns the cached overlay when it was fetched from the configured URL and
+/// still verifies against the pinned key.
+async fn read_verified_cache(
+    codex_home: &Path,
+    settings: &RemoteConfigToml,
+) -> Option<(SignedOverlay, TomlValue)> {
+    let signed = read_cache(codex_home).await?;
+    if signed.url != settings.url {
+        return None;
+    }
+    match verify_overlay(settings, &signed) {
+        Ok(overlay) => Some((signed, overlay)),
+        Err(err) => {
+            tracing::warn!("ignoring cached remote config: {err}");
+            None
+        }
+    }
+}
+
+async fn write_cache(codex_home: &Path, signed: &SignedOverlay) -> io::Result<()> {
+    let contents = serde_json::to_string(signed).map_err(io::Error::other)?;
+    fs::create_dir_all(codex_home).await?;
+    fs::write(cache_path(codex_home), contents).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use ring::rand::SystemRandom;
+    use ring::signature::Ed25519KeyPair;
+    use ring::signature::KeyPair;
+    use tempfile::tempdir;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::method;
+
+    fn key_pair() -> Ed25519KeyPair {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&SystemRandom::new()).expect("generate key");
+        Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).expect("parse key")
+    }
+
+    fn settings(key_pair: &Ed25519KeyPair) -> RemoteConfigToml {
+        RemoteConfigToml {
+            // Nothing listens here, so fetching fails fast and the cache is used.
+            url: "http://127.0.0.1:9/codex.toml".to_string(),
+            public_key: BASE64_STANDARD.encode(key_pair.public_key().as_ref()),
+            refresh_interval_secs: None,
+            required: false,
+        }
+    }
+
+    fn sign(key_pair: &Ed25519KeyPair, url: &str, version: u64, body: &str) -> SignedOverlay {
+        let payload = signed_payload(url, version, body);
+        SignedOverlay {
+            url: url.to_string(),
+            version,
+            body: body.to_string(),
+            signature: BASE64_STANDARD.encode(key_pair.sign(payload.as_bytes()).as_ref()),
+        }
+    }
+
+    fn serve(signed: &SignedOverlay) -> ResponseTemplate {
+        ResponseTemplate::new(200)
+            .insert_header(SIGNATURE_HEADER, signed.signature.as_str())
+            .insert_header(VERSION_HEADER, signed.version.to_string().as_str())
+            .set_body_string(signed.body.clone())
+    }
+
+    #[test]
+    fn verifies_signed_toml_and_json_overlays() {
+        let key_pair = key_pair();
+        let settings = settings(&key_pair);
+
+        let toml_overlay = sign(
+            &key_pair,
+            &settings.url,
+            1,
+            "sandbox_mode = \"read-only\"\n[remote_config]\nurl = \"https://evil.example\"\n",
+        );
+        let overlay = verify_overlay(&settings, &toml_overlay).expect("verify toml");
+        assert_eq!(
+            overlay,
+            toml::from_str::<TomlValue>("sandbox_mode = \"read-only\"").expect("parse")
+        );
+
+        let json_overlay = sign(
+            &key_pair,
+            &settings.url,
+            1,
+            r#"{"approval_policy": "untrusted"}"#,
+        );
+        let overlay = verify_overlay(&settings, &json_overlay).expect("verify json");
+        assert_eq!(
+            overlay,
+            toml::from_str::<TomlValue>("approval_policy = \"untrusted\"").expect("parse")
+        );
+    }
+
+    #[test]
+    fn rejects_tampered_overlay() {
+        let key_pair = key_pair();
+        let settings = settings(&key_pair);
+        let mut signed = sign(
+            &key_pair,
+            &settings.url,
+            1,
+            "sandbox_mode = \"read-only\"\n",
+        );
+        signed.body = "sandbox_mode = \"danger-full-access\"\n".to_string();
+
+        let err = verify_overlay(&settings, &signed).expect_err("tampered body");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let mut signed = sign(
+            &key_pair,
+            &settings.url,
+            1,
+            "sandbox_mode = \"read-only\"\n",
+        );
+        signed.version = 2;
+        let err = verify_overlay(&settings, &signed).expect_err("tampered version");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn rejects_overlay_signed_for_another_url() {
+        let key_pair = key_pair();
+        let settings = settings(&key_pair);
+        let mut signed = sign(
+            &key_pair,
+            "https://example.com/other.toml",
+            1,
+            "sandbox_mode = \"danger-full-access\"\n",
+        );
+        signed.url = settings.url.clone();
+
+        let err = verify_overlay(&settings, &signed).expect_err("replayed overlay");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test]
+    async fn uses_verified_cache_without_fetching() {
+        let tmp = tempdir().expect("tempdir");
+        let key_pair = key_pair();
+        let mut settings = settings(&key_pair);
+        let signed = sign(&key_pair, &settings.url, 1, "model = \"o3\"\n");
+        write_cache(tmp.path(), &signed).await.expect("write cache");
+
+        let overlay = load_remote_config_layer(tmp.path(), &settings)
+            .await
+            .expect("load overlay");
+        assert_eq!(
+            overlay,
+            Some(toml::from_str::<TomlValue>("model = \"o3\"").expect("parse"))
+        );
+
+        settings.url = "http://127.0.0.1:9/other.toml".to_string();
+        settings.required = true;
+        let err = load_remote_config_layer(tmp.path(), &settings)
+            .await
+            .expect_err("cache for another url must not be used");
+        assert_eq!(err.kind(), io::ErrorKind::Other);
+    }
+
+    #[tokio::test]
+    async fn fetches_once_when_there_is_no_cache() {
+        let server = MockServer::start().await;
+        let tmp = tempdir().expect("tempdir");
+        let key_pair = key_pair();
+        let mut settings = settings(&key_pair);
+        settings.url = format!("{}/codex.toml", server.uri());
+        let signed = sign(&key_pair, &settings.url, 1, "model = \"o3\"\n");
+        Mock::given(method("GET"))
+            .respond_with(serve(&signed))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        for _ in 0..2 {
+            let overlay = load_remote_config_layer(tmp.path(), &settings)
+                .await
+                .expect("load overlay");
+            assert_eq!(
+                overlay,
+                Some(toml::from_str::<TomlValue>("model = \"o3\"").expect("parse"))
+            );
+        }
+        assert_eq!(read_cache(tmp.path()).await, Some(signed));
+    }
+
+    #[tokio::test]
+    async fn refresh_rejects_versions_older_than_the_cache() {
+        let server = MockServer::start().await;
+        let tmp = tempdir().expect("tempdir");
+        let key_pair = key_pair();
+        let mut settings = settings(&key_pair);
+        settings.url = format!("{}/codex.toml", server.uri());
+        let cached = sign(&key_pair, &settings.url, 2, "model = \"o3\"\n");
+        write_cache(tmp.path(), &cached).await.expect("write cache");
+        let stale = sign(&key_pair, &settings.url, 1, "model = \"gpt-4o\"\n");
+        Mock::given(method("GET"))
+            .respond_with(serve(&stale))
+            .mount(&server)
+            .await;
+
+        let err = refresh_cache(tmp.path(), &settings)
+            .await
+            .expect_err("stale overlay");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(read_cache(tmp.path()).await, Some(cached));
+    }
+}
@@ -166,6 +166,12 @@ impl ConversationManager {
         self.conversations.write().await.remove(conversation_id)
     }
 
+    /// Removes every conversation from the manager, e.g. to shut them all
+    /// down before the process exits.
+    pub async fn remove_all_conversations(&self) -> Vec<(ConversationId, Arc<CodexConversation>)> {
+        self.conversations.write().await.drain().collect()
+    }
+
     /// Fork an existing conversation by taking messages up to the given position
     /// (not including the message at the given position) and starting a new
     /// conversation with identical configuration (unless overridden by the
@@ -0,0 +1,56 @@
+//! Cost estimates derived from the `[model_prices]` config table.
+
+use crate::config::types::ModelPrice;
+use crate::protocol::TokenUsage;
+
+const TOKENS_PER_PRICE_UNIT: f64 = 1_000_000.0;
+
+/// Estimated USD cost of `usage` at `price`. Cached input tokens are billed at
+/// the cached rate when one is configured, otherwise at the input rate.
+pub(crate) fn estimate_cost_usd(price: &ModelPrice, usage: &TokenUsage) -> f64 {
+    let cached_rate = price
+        .cached_input_per_million
+        .unwrap_or(price.input_per_million);
+    let micro_usd = usage.non_cached_input() as f64 * price.input_per_million
+        + usage.cached_input() as f64 * cached_rate
+        + usage.output_tokens.max(0) as f64 * price.output_per_million;
+    micro_usd / TOKENS_PER_PRICE_UNIT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn usage(input: i64, cached: i64, output: i64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: input,
+            cached_input_tokens: cached,
+            output_tokens: output,
+            reasoning_output_tokens: 0,
+            total_tokens: input + output,
+        }
+    }
+
+    #[test]
+    fn bills_cached_input_at_cached_rate() {
+        let price = ModelPrice {
+            input_per_million: 2.0,
+            cached_input_per_million: Some(0.5),
+            output_per_million: 8.0,
+        };
+        let cost = estimate_cost_usd(&price, &usage(1_000_000, 400_000, 250_000));
+        assert_eq!(cost, 3.4);
+    }
+
+    #[test]
+    fn cached_input_falls_back_to_input_rate() {
+        let price = ModelPrice {
+            input_per_million: 1.0,
+            cached_input_per_million: None,
+            output_per_million: 4.0,
+        };
+        let cost = estimate_cost_usd(&price, &usage(500_000, 500_000, 0));
+        assert_eq!(cost, 0.5);
+    }
+}
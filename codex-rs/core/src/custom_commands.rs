@@ -0,0 +1,290 @@
+//! User-defined commands loaded from `$CODEX_HOME/commands/*.md`.
+//!
+//! A command is a Markdown prompt template whose optional frontmatter declares
+//! the `$NAME` placeholders it requires (`args`) and per-turn overrides for
+//! the `model` and `approval_policy`. Commands are re-read every time they are
+//! listed or run, so edits take effect without restarting the session.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::custom_commands::CustomCommand;
+use codex_protocol::protocol::AskForApproval;
+use tokio::fs;
+use tracing::warn;
+
+use crate::custom_prompts::split_frontmatter;
+
+/// Directory under `CODEX_HOME` that holds custom commands.
+pub const COMMANDS_DIR: &str = "commands";
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum CommandArgsError {
+    #[error("missing args for /{command}: {}", missing.join(", "))]
+    Missing {
+        command: String,
+        missing: Vec<String>,
+    },
+    #[error("/{command} does not take args: {}", unknown.join(", "))]
+    Unknown {
+        command: String,
+        unknown: Vec<String>,
+    },
+}
+
+/// Return the commands directory for `codex_home`.
+pub fn commands_dir(codex_home: &Path) -> PathBuf {
+    codex_home.join(COMMANDS_DIR)
+}
+
+/// Discover command files in `dir`, returning entries sorted by name. Files
+/// with invalid frontmatter are skipped with a warning. A missing or
+/// unreadable directory yields an empty list.
+pub async fn discover_commands_in(dir: &Path) -> Vec<CustomCommand> {
+    let mut out: Vec<CustomCommand> = Vec::new();
+    let mut entries = match fs::read_dir(dir).await {
+        Ok(entries) => entries,
+        Err(_) => return out,
+    };
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let is_file_like = fs::metadata(&path)
+            .await
+            .map(|m| m.is_file())
+            .unwrap_or(false);
+        let is_md = path
+            .extension()
+            .and_then(|s| s.to_str())
+            .map(|ext| ext.eq_ignore_ascii_case("md"))
+            .unwrap_or(false);
+        if !is_file_like || !is_md {
+            continue;
+        }
+        let Some(name) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        let content = match fs::read_to_string(&path).await {
+            Ok(s) => s,
+            Err(_) => continue,
+        };
+        match parse_command(name, path.clone(), &content) {
+            Ok(command) => out.push(command),
+            Err(err) => warn!("skipping custom command {}: {err}", path.display()),
+        }
+    }
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    out
+}
+
+/// Find the command called `name` in `dir`.
+pub async fn find_command(dir: &Path, name: &str) -> Option<CustomCommand> {
+    discover_commands_in(dir)
+        .await
+        .into_iter()
+        .find(|command| command.name == name)
+}
+
+/// Expand `command` with `args`, which must supply exactly the declared args.
+pub fn render_command(
+    command: &CustomCommand,
+    args: &HashMap<String, String>,
+) -> Result<String, CommandArgsError> {
+    let missing: Vec<String> = command
+        .args
+        .iter()
+        .filter(|name| !args.contains_key(*name))
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        return Err(CommandArgsError::Missing {
+            command: command.name.clone(),
+            missing,
+        });
+    }
+    let mut unknown: Vec<String> = args
+        .keys()
+        .filter(|name| !command.args.contains(*name))
+        .cloned()
+        .collect();
+    if !unknown.is_empty() {
+        unknown.sort();
+        return Err(CommandArgsError::Unknown {
+            command: command.name.clone(),
+            unknown,
+        });
+    }
+
+    let template = &command.template;
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template.as_str();
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        let len = placeholder_len(after);
+        match args.get(&after[..len]) {
+            Some(value) if len > 0 => out.push_str(value),
+            _ => out.push_str(&rest[pos..pos + 1 + len]),
+        }
+        rest = &after[len..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Length of the `[A-Z][A-Z0-9_]*` placeholder name at the start of `text`.
+fn placeholder_len(text: &str) -> usize {
+    let mut chars = text.char_indices();
+    match chars.next() {
+        Some((_, c)) if c.is_ascii_uppercase() => {}
+        _ => return 0,
+    }
+    chars
+        .find(|(_, c)| !(c.is_ascii_uppercase() || c.is_ascii_digit() || *c == '_'))
+        .map(|(idx, _)| idx)
+        .unwrap_or(text.len())
+}
+
+fn parse_command(name: String, path: PathBuf, content: &str) -> Result<CustomCommand, String> {
+    let Some((fields, template)) = split_frontmatter(content) else {
+        return Ok(CustomCommand {
+            name,
+            path,
+            description: None,
+            args: Vec::new(),
+            model: None,
+            approval_policy: None,
+            template: content.to_string(),
+        });
+    };
+
+    let mut command = CustomCommand {
+        name,
+        path,
+        description: None,
+        args: Vec::new(),
+        model: None,
+        approval_policy: None,
+        template,
+    };
+    for (key, val) in fields {
+        match key.as_str() {
+            "description" => command.description = Some(val),
+            "args" => {
+                for arg in val.split(',').map(str::trim).filter(|arg| !arg.is_empty()) {
+                    if placeholder_len(arg) != arg.len() {
+                        return Err(format!(
+                            "arg `{arg}` must be uppercase letters, digits, and underscores"
+                        ));
+                    }
+                    command.args.push(arg.to_string());
+                }
+            }
+            "model" => command.model = Some(val),
+            "approval_policy" | "approval-policy" => {
+                let policy: AskForApproval =
+                    serde_json::from_value(serde_json::Value::String(val.clone()))
+                        .map_err(|_| format!("unknown approval_policy `{val}`"))?;
+                command.approval_policy = Some(policy);
+            }
+            _ => {}
+        }
+    }
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn parses_frontmatter_overrides() {
+        let tmp = tempdir().expect("create TempDir");
+        let dir = tmp.path();
+        fs::write(
+            dir.join("release-notes.md"),
+            "---\ndescription: Draft release notes\nargs: VERSION, SINCE\nmodel: gpt-5-codex\napproval_policy: never\n---\nWrite notes for $VERSION since $SINCE.\n",
+        )
+        .expect("write command");
+        fs::write(dir.join("plain.md"), "Scan for secrets.").expect("write command");
+        fs::write(
+            dir.join("broken.md"),
+            "---\napproval_policy: sometimes\n---\nbody",
+        )
+        .expect("write command");
+
+        let found = discover_commands_in(dir).await;
+        assert_eq!(
+            found,
+            vec![
+                CustomCommand {
+                    name: "plain".to_string(),
+                    path: dir.join("plain.md"),
+                    description: None,
+                    args: Vec::new(),
+                    model: None,
+                    approval_policy: None,
+                    template: "Scan for secrets.".to_string(),
+                },
+                CustomCommand {
+                    name: "release-notes".to_string(),
+                    path: dir.join("release-notes.md"),
+                    description: Some("Draft release notes".to_string()),
+                    args: vec!["VERSION".to_string(), "SINCE".to_string()],
+                    model: Some("gpt-5-codex".to_string()),
+                    approval_policy: Some(AskForApproval::Never),
+                    template: "Write notes for $VERSION since $SINCE.\n".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn renders_declared_args_and_rejects_mismatches() {
+        let command = CustomCommand {
+            name: "release-notes".to_string(),
+            path: PathBuf::from("/commands/release-notes.md"),
+            description: None,
+            args: vec!["VERSION".to_string(), "SINCE".to_string()],
+            model: None,
+            approval_policy: None,
+            template: "Notes for $VERSION since $SINCE, costing $5 and $$.".to_string(),
+        };
+        let args = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        assert_eq!(
+            render_command(&command, &args(&[("VERSION", "1.2"), ("SINCE", "v1.1")])),
+            Ok("Notes for 1.2 since v1.1, costing $5 and $$.".to_string())
+        );
+        assert_eq!(
+            render_command(&command, &args(&[("VERSION", "1.2")])),
+            Err(CommandArgsError::Missing {
+                command: "release-notes".to_string(),
+                missing: vec!["SINCE".to_string()],
+            })
+        );
+        assert_eq!(
+            render_command(
+                &command,
+                &args(&[("VERSION", "1.2"), ("SINCE", "v1.1"), ("EXTRA", "x")])
+            ),
+            Err(CommandArgsError::Unknown {
+                command: "release-notes".to_string(),
+                unknown: vec!["EXTRA".to_string()],
+            })
+        );
+    }
+}
@@ -81,17 +81,35 @@ pub async fn discover_prompts_in_excluding(
 /// - `argument-hint` or `argument_hint`: brief hint string shown after the description
 ///   Returns (description, argument_hint, body_without_frontmatter).
 fn parse_frontmatter(content: &str) -> (Option<String>, Option<String>, String) {
-    let mut segments = content.split_inclusive('\n');
-    let Some(first_segment) = segments.next() else {
-        return (None, None, String::new());
+    let Some((fields, body)) = split_frontmatter(content) else {
+        return (None, None, content.to_string());
     };
+
+    let mut desc: Option<String> = None;
+    let mut hint: Option<String> = None;
+    for (key, val) in fields {
+        match key.as_str() {
+            "description" => desc = Some(val),
+            "argument-hint" | "argument_hint" => hint = Some(val),
+            _ => {}
+        }
+    }
+    (desc, hint, body)
+}
+
+/// Split YAML-like frontmatter delimited by `---` lines off the start of
+/// `content`. Returns the `key: value` pairs, with keys lowercased and
+/// surrounding quotes removed from values, and the body after the closing
+/// delimiter. Returns `None` when there is no terminated frontmatter.
+pub(crate) fn split_frontmatter(content: &str) -> Option<(Vec<(String, String)>, String)> {
+    let mut segments = content.split_inclusive('\n');
+    let first_segment = segments.next()?;
     let first_line = first_segment.trim_end_matches(['\r', '\n']);
     if first_line.trim() != "---" {
-        return (None, None, content.to_string());
+        return None;
     }
 
-    let mut desc: Option<String> = None;
-    let mut hint: Option<String> = None;
+    let mut fields = Vec::new();
     let mut frontmatter_closed = false;
     let mut consumed = first_segment.len();
 
@@ -121,11 +139,7 @@ fn parse_frontmatter(content: &str) -> (Option<String>, Option<String>, String)
                     val = val[1..val.len().saturating_sub(1)].to_string();
                 }
             }
-            match key.as_str() {
-                "description" => desc = Some(val),
-                "argument-hint" | "argument_hint" => hint = Some(val),
-                _ => {}
-            }
+            fields.push((key, val));
         }
 
         consumed += segment.len();
@@ -133,7 +147,7 @@ fn parse_frontmatter(content: &str) -> (Option<String>, Option<String>, String)
 
     if !frontmatter_closed {
         // Unterminated frontmatter: treat input as-is.
-        return (None, None, content.to_string());
+        return None;
     }
 
     let body = if consumed >= content.len() {
@@ -141,7 +155,7 @@ fn parse_frontmatter(content: &str) -> (Option<String>, Option<String>, String)
     } else {
         content[consumed..].to_string()
     };
-    (desc, hint, body)
+    Some((fields, body))
 }
 
 #[cfg(test)]
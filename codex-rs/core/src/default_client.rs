@@ -113,6 +113,17 @@ impl CodexRequestBuilder {
         self.map(|builder| builder.json(value))
     }
 
+    pub fn multipart(self, form: reqwest::multipart::Form) -> Self {
+        self.map(|builder| builder.multipart(form))
+    }
+
+    pub fn body<T>(self, body: T) -> Self
+    where
+        T: Into<reqwest::Body>,
+    {
+        self.map(|builder| builder.body(body))
+    }
+
     pub async fn send(self) -> Result<Response, reqwest::Error> {
         match self.builder.send().await {
             Ok(response) => {
@@ -264,7 +275,7 @@ pub fn create_client() -> CodexHttpClient {
     headers.insert("originator", originator().header_value.clone());
     let ua = get_codex_user_agent();
 
-    let mut builder = reqwest::Client::builder()
+    let mut builder = codex_utils_http_client::builder()
         // Set UA via dedicated helper to avoid header validation pitfalls
         .user_agent(ua)
         .default_headers(headers);
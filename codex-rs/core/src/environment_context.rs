@@ -28,6 +28,7 @@ pub(crate) struct EnvironmentContext {
     pub sandbox_mode: Option<SandboxMode>,
     pub network_access: Option<NetworkAccess>,
     pub writable_roots: Option<Vec<PathBuf>>,
+    pub workspace_roots: Option<Vec<PathBuf>>,
     pub shell: Option<Shell>,
 }
 
@@ -44,12 +45,15 @@ impl EnvironmentContext {
             sandbox_mode: match sandbox_policy {
                 Some(SandboxPolicy::DangerFullAccess) => Some(SandboxMode::DangerFullAccess),
                 Some(SandboxPolicy::ReadOnly) => Some(SandboxMode::ReadOnly),
+                Some(SandboxPolicy::Audit) => Some(SandboxMode::Audit),
                 Some(SandboxPolicy::WorkspaceWrite { .. }) => Some(SandboxMode::WorkspaceWrite),
                 None => None,
             },
             network_access: match sandbox_policy {
                 Some(SandboxPolicy::DangerFullAccess) => Some(NetworkAccess::Enabled),
-                Some(SandboxPolicy::ReadOnly) => Some(NetworkAccess::Restricted),
+                Some(SandboxPolicy::ReadOnly | SandboxPolicy::Audit) => {
+                    Some(NetworkAccess::Restricted)
+                }
                 Some(SandboxPolicy::WorkspaceWrite { network_access, .. }) => {
                     if network_access {
                         Some(NetworkAccess::Enabled)
@@ -69,10 +73,21 @@ impl EnvironmentContext {
                 }
                 _ => None,
             },
+            workspace_roots: None,
             shell,
         }
     }
 
+    /// Lists the workspace roots besides `cwd`, if there are any.
+    pub fn with_workspace_roots(mut self, workspace_roots: &[PathBuf]) -> Self {
+        self.workspace_roots = if workspace_roots.is_empty() {
+            None
+        } else {
+            Some(workspace_roots.to_vec())
+        };
+        self
+    }
+
     /// Compares two environment contexts, ignoring the shell. Useful when
     /// comparing turn to turn, since the initial environment_context will
     /// include the shell, and then it is not configurable from turn to turn.
@@ -83,6 +98,7 @@ impl EnvironmentContext {
             sandbox_mode,
             network_access,
             writable_roots,
+            workspace_roots,
             // should compare all fields except shell
             shell: _,
         } = other;
@@ -92,6 +108,7 @@ impl EnvironmentContext {
             && self.sandbox_mode == *sandbox_mode
             && self.network_access == *network_access
             && self.writable_roots == *writable_roots
+            && self.workspace_roots == *workspace_roots
     }
 
     pub fn diff(before: &TurnContext, after: &TurnContext) -> Self {
@@ -110,7 +127,12 @@ impl EnvironmentContext {
         } else {
             None
         };
-        EnvironmentContext::new(cwd, approval_policy, sandbox_policy, None)
+        let mut context = EnvironmentContext::new(cwd, approval_policy, sandbox_policy, None);
+        if before.workspace_roots != after.workspace_roots {
+            // An empty list tells the model that every extra root was removed.
+            context.workspace_roots = Some(after.workspace_roots.clone());
+        }
+        context
     }
 }
 
@@ -123,6 +145,7 @@ impl From<&TurnContext> for EnvironmentContext {
             // Shell is not configurable from turn to turn
             None,
         )
+        .with_workspace_roots(&turn_context.workspace_roots)
     }
 }
 
@@ -137,6 +160,7 @@ impl EnvironmentContext {
     ///   <approval_policy>...</approval_policy>
     ///   <sandbox_mode>...</sandbox_mode>
     ///   <writable_roots>...</writable_roots>
+    ///   <workspace_roots>...</workspace_roots>
     ///   <network_access>...</network_access>
     ///   <shell>...</shell>
     /// </environment_context>
@@ -169,6 +193,16 @@ impl EnvironmentContext {
             }
             lines.push("  </writable_roots>".to_string());
         }
+        if let Some(workspace_roots) = self.workspace_roots {
+            lines.push("  <workspace_roots>".to_string());
+            for workspace_root in workspace_roots {
+                lines.push(format!(
+                    "    <root>{}</root>",
+                    workspace_root.to_string_lossy()
+                ));
+            }
+            lines.push("  </workspace_roots>".to_string());
+        }
         if let Some(shell) = self.shell
             && let Some(shell_name) = shell.name()
         {
@@ -231,6 +265,45 @@ mod tests {
         assert_eq!(context.serialize_to_xml(), expected);
     }
 
+    #[test]
+    fn serialize_environment_context_with_workspace_roots() {
+        let context = EnvironmentContext::new(
+            Some(PathBuf::from("/repo")),
+            None,
+            Some(workspace_write_policy(vec!["/docs"], false)),
+            None,
+        )
+        .with_workspace_roots(&[PathBuf::from("/docs")]);
+
+        let expected = r#"<environment_context>
+  <cwd>/repo</cwd>
+  <sandbox_mode>workspace-write</sandbox_mode>
+  <network_access>restricted</network_access>
+  <writable_roots>
+    <root>/docs</root>
+  </writable_roots>
+  <workspace_roots>
+    <root>/docs</root>
+  </workspace_roots>
+</environment_context>"#;
+
+        assert_eq!(context.serialize_to_xml(), expected);
+    }
+
+    #[test]
+    fn equals_except_shell_compares_workspace_roots() {
+        let context1 = EnvironmentContext::new(
+            Some(PathBuf::from("/repo")),
+            Some(AskForApproval::OnRequest),
+            Some(SandboxPolicy::ReadOnly),
+            None,
+        );
+        let context2 = context1
+            .clone()
+            .with_workspace_roots(&[PathBuf::from("/docs")]);
+        assert!(!context1.equals_except_shell(&context2));
+    }
+
     #[test]
     fn serialize_read_only_environment_context() {
         let context = EnvironmentContext::new(
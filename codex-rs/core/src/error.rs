@@ -10,7 +10,10 @@ use chrono::Local;
 use chrono::Utc;
 use codex_async_utils::CancelErr;
 use codex_protocol::ConversationId;
+use codex_protocol::protocol::CodexErrorInfo;
+use codex_protocol::protocol::ErrorCode;
 use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::ErrorOrigin;
 use codex_protocol::protocol::RateLimitSnapshot;
 use reqwest::StatusCode;
 use serde_json;
@@ -78,6 +81,13 @@ pub enum CodexErr {
     )]
     ContextWindowExceeded,
 
+    /// Returned when a new turn is submitted after the conversation used up
+    /// its configured token budget.
+    #[error(
+        "This conversation used {used} tokens, exceeding its budget of {budget}. Raise the budget to continue."
+    )]
+    TokenBudgetExhausted { budget: i64, used: i64 },
+
     #[error("no conversation with id: {0}")]
     ConversationNotFound(ConversationId),
 
@@ -446,6 +456,22 @@ impl CodexErr {
         }
     }
 
+    /// Whether this error points at the provider being unavailable (dropped
+    /// connections, 5xx, 429) rather than at the request itself.
+    pub fn is_provider_outage(&self) -> bool {
+        match self {
+            CodexErr::Stream(..)
+            | CodexErr::ConnectionFailed(_)
+            | CodexErr::ResponseStreamFailed(_)
+            | CodexErr::InternalServerError
+            | CodexErr::RetryLimit(_) => true,
+            CodexErr::UnexpectedStatus(err) => {
+                err.status.is_server_error() || err.status == StatusCode::TOO_MANY_REQUESTS
+            }
+            _ => false,
+        }
+    }
+
     pub fn to_error_event(&self, message_prefix: Option<String>) -> ErrorEvent {
         let error_message = self.to_string();
         let message: String = match message_prefix {
@@ -456,8 +482,84 @@ impl CodexErr {
         ErrorEvent {
             message,
             http_status_code: http_status_code_value(self.http_status_code()),
+            info: self.error_info(),
         }
     }
+
+    /// Classify this error for clients; see [`CodexErrorInfo`].
+    pub fn error_info(&self) -> CodexErrorInfo {
+        let (code, origin) = match self {
+            CodexErr::TurnAborted { .. } | CodexErr::Interrupted => {
+                (ErrorCode::Interrupted, ErrorOrigin::Codex)
+            }
+            CodexErr::Stream(..) | CodexErr::ResponseStreamFailed(_) => {
+                (ErrorCode::StreamDisconnected, ErrorOrigin::ModelProvider)
+            }
+            CodexErr::ContextWindowExceeded => {
+                (ErrorCode::ContextWindowExceeded, ErrorOrigin::ModelProvider)
+            }
+            CodexErr::TokenBudgetExhausted { .. } => {
+                (ErrorCode::TokenBudgetExhausted, ErrorOrigin::Codex)
+            }
+            CodexErr::ConversationNotFound(_) => {
+                (ErrorCode::ConversationNotFound, ErrorOrigin::Codex)
+            }
+            CodexErr::UnexpectedStatus(err) => {
+                let code = match err.status {
+                    StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => ErrorCode::Unauthorized,
+                    StatusCode::BAD_REQUEST
+                    | StatusCode::NOT_FOUND
+                    | StatusCode::UNPROCESSABLE_ENTITY => ErrorCode::BadRequest,
+                    _ => ErrorCode::UnexpectedStatus,
+                };
+                let retryable =
+                    err.status.is_server_error() || err.status == StatusCode::TOO_MANY_REQUESTS;
+                return CodexErrorInfo::new(code, ErrorOrigin::ModelProvider)
+                    .with_retryable(retryable);
+            }
+            CodexErr::UsageLimitReached(_) => {
+                (ErrorCode::UsageLimitReached, ErrorOrigin::ModelProvider)
+            }
+            CodexErr::UsageNotIncluded => (ErrorCode::UsageNotIncluded, ErrorOrigin::ModelProvider),
+            CodexErr::QuotaExceeded => (ErrorCode::QuotaExceeded, ErrorOrigin::ModelProvider),
+            CodexErr::ConnectionFailed(_) => {
+                (ErrorCode::ConnectionFailed, ErrorOrigin::ModelProvider)
+            }
+            CodexErr::InternalServerError => {
+                (ErrorCode::ServerOverloaded, ErrorOrigin::ModelProvider)
+            }
+            CodexErr::RetryLimit(_) => (ErrorCode::RetryLimitReached, ErrorOrigin::ModelProvider),
+            CodexErr::RefreshTokenFailed(_) => {
+                (ErrorCode::RefreshTokenFailed, ErrorOrigin::ModelProvider)
+            }
+            CodexErr::Sandbox(SandboxErr::Denied { .. } | SandboxErr::Signal(_)) => {
+                (ErrorCode::SandboxDenied, ErrorOrigin::Sandbox)
+            }
+            CodexErr::Sandbox(SandboxErr::Timeout { .. }) => {
+                (ErrorCode::SandboxTimeout, ErrorOrigin::Sandbox)
+            }
+            CodexErr::Sandbox(_) | CodexErr::LandlockSandboxExecutableNotProvided => {
+                (ErrorCode::SandboxUnavailable, ErrorOrigin::Sandbox)
+            }
+            #[cfg(target_os = "linux")]
+            CodexErr::LandlockRuleset(_) | CodexErr::LandlockPathFd(_) => {
+                (ErrorCode::SandboxUnavailable, ErrorOrigin::Sandbox)
+            }
+            CodexErr::UnsupportedOperation(_) => {
+                (ErrorCode::UnsupportedOperation, ErrorOrigin::Codex)
+            }
+            CodexErr::EnvVar(_) => (ErrorCode::MissingEnvVar, ErrorOrigin::Codex),
+            CodexErr::SessionConfiguredNotFirstEvent
+            | CodexErr::Timeout
+            | CodexErr::Spawn
+            | CodexErr::InternalAgentDied
+            | CodexErr::Fatal(_)
+            | CodexErr::Io(_)
+            | CodexErr::Json(_)
+            | CodexErr::TokioJoin(_) => (ErrorCode::Internal, ErrorOrigin::Codex),
+        };
+        CodexErrorInfo::new(code, origin)
+    }
 }
 
 pub fn http_status_code_value(http_status_code: Option<StatusCode>) -> Option<u16> {
@@ -508,6 +610,7 @@ mod tests {
     use chrono::Duration as ChronoDuration;
     use chrono::TimeZone;
     use chrono::Utc;
+    use codex_protocol::protocol::ErrorCategory;
     use codex_protocol::protocol::RateLimitWindow;
     use pretty_assertions::assert_eq;
 
@@ -566,6 +669,7 @@ mod tests {
             aggregated_output: StreamOutput::new("aggregate detail".to_string()),
             duration: Duration::from_millis(10),
             timed_out: false,
+            output_truncation: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -582,6 +686,7 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(10),
             timed_out: false,
+            output_truncation: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -598,6 +703,7 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(8),
             timed_out: false,
+            output_truncation: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -614,6 +720,7 @@ mod tests {
             aggregated_output: StreamOutput::new(String::new()),
             duration: Duration::from_millis(5),
             timed_out: false,
+            output_truncation: None,
         };
         let err = CodexErr::Sandbox(SandboxErr::Denied {
             output: Box::new(output),
@@ -846,4 +953,35 @@ mod tests {
         );
         assert_eq!(event.http_status_code, None);
     }
+
+    #[test]
+    fn error_info_classifies_provider_failures() {
+        let unauthorized = CodexErr::UnexpectedStatus(UnexpectedResponseError {
+            status: StatusCode::UNAUTHORIZED,
+            body: "expired".to_string(),
+            request_id: None,
+        });
+        assert_eq!(
+            unauthorized.error_info(),
+            CodexErrorInfo {
+                category: ErrorCategory::Auth,
+                code: ErrorCode::Unauthorized,
+                retryable: false,
+                origin: ErrorOrigin::ModelProvider,
+            }
+        );
+
+        let unavailable = CodexErr::UnexpectedStatus(UnexpectedResponseError {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            body: String::new(),
+            request_id: None,
+        });
+        let info = unavailable.error_info();
+        assert_eq!(info.code, ErrorCode::UnexpectedStatus);
+        assert!(info.retryable);
+
+        let event = CodexErr::ContextWindowExceeded.to_error_event(None);
+        assert_eq!(event.info.category, ErrorCategory::ContextWindow);
+        assert!(!event.info.retryable);
+    }
 }
@@ -13,13 +13,14 @@ use codex_protocol::user_input::UserInput;
 use tracing::warn;
 use uuid::Uuid;
 
+use crate::repo_map::REPO_MAP_OPEN_TAG;
 use crate::user_instructions::UserInstructions;
 use crate::user_shell_command::is_user_shell_command_text;
 
 fn is_session_prefix(text: &str) -> bool {
     let trimmed = text.trim_start();
     let lowered = trimmed.to_ascii_lowercase();
-    lowered.starts_with("<environment_context>")
+    lowered.starts_with("<environment_context>") || lowered.starts_with(REPO_MAP_OPEN_TAG)
 }
 
 fn parse_user_message(message: &[ContentItem]) -> Option<UserMessageItem> {
@@ -6,18 +6,28 @@ use std::io;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::ExitStatus;
+use std::sync::Arc;
 use std::time::Duration;
 use std::time::Instant;
 
 use async_channel::Sender;
+use codex_protocol::items::CommandOutputTruncation;
+use codex_utils_pty::SpawnedPty;
+use codex_utils_pty::TerminalSize;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt;
 use tokio::io::BufReader;
 use tokio::process::Child;
+use tokio::sync::broadcast::error::RecvError;
 
+use crate::config::types::ExecOutputConfig;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::SandboxErr;
+use crate::exec_log::ExecOutputSpool;
+use crate::exec_output::RetainedOutput;
+use crate::exec_output::StreamBudget;
+use crate::exec_output::output_truncation;
 use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandOutputDeltaEvent;
@@ -26,6 +36,7 @@ use crate::protocol::SandboxPolicy;
 use crate::sandboxing::CommandSpec;
 use crate::sandboxing::ExecEnv;
 use crate::sandboxing::SandboxManager;
+use crate::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 
@@ -40,11 +51,7 @@ const EXEC_TIMEOUT_EXIT_CODE: i32 = 124; // conventional timeout exit code
 
 // I/O buffer sizing
 const READ_CHUNK_SIZE: usize = 8192; // bytes per read
-const AGGREGATE_BUFFER_INITIAL_CAPACITY: usize = 8 * 1024; // 8 KiB
-
-/// Limit the number of ExecCommandOutputDelta events emitted per exec call.
-/// Aggregation still collects full output; only the live event stream is capped.
-pub(crate) const MAX_EXEC_OUTPUT_DELTAS_PER_CALL: usize = 10_000;
+const PTY_DRAIN_IDLE_MS: u64 = 100; // wait for trailing PTY output after exit
 
 #[derive(Clone, Debug)]
 pub struct ExecParams {
@@ -82,6 +89,10 @@ pub struct StdoutStream {
     pub sub_id: String,
     pub call_id: String,
     pub tx_event: Sender<Event>,
+    /// How much of the output is streamed and kept.
+    pub policy: ExecOutputConfig,
+    /// Archive directory the raw output is spooled to while it is read.
+    pub archive_dir: Option<PathBuf>,
 }
 
 pub async fn process_exec_tool_call(
@@ -127,6 +138,7 @@ pub async fn process_exec_tool_call(
             sandbox_type,
             sandbox_cwd,
             codex_linux_sandbox_exe.as_ref(),
+            None,
         )
         .map_err(CodexErr::from)?;
 
@@ -148,6 +160,7 @@ pub(crate) async fn execute_exec_env(
         with_escalated_permissions,
         justification,
         arg0,
+        stdio_policy,
     } = env;
 
     let params = ExecParams {
@@ -161,7 +174,8 @@ pub(crate) async fn execute_exec_env(
     };
 
     let start = Instant::now();
-    let raw_output_result = exec(params, sandbox, sandbox_policy, stdout_stream).await;
+    let raw_output_result =
+        exec(params, sandbox, sandbox_policy, stdio_policy, stdout_stream).await;
     let duration = start.elapsed();
     finalize_exec_result(raw_output_result, sandbox, duration)
 }
@@ -244,6 +258,7 @@ async fn exec_windows_sandbox(
         stderr,
         aggregated_output,
         timed_out: capture.timed_out,
+        output_truncation: None,
     })
 }
 
@@ -283,6 +298,7 @@ fn finalize_exec_result(
                 aggregated_output,
                 duration,
                 timed_out,
+                output_truncation: raw_output.output_truncation,
             };
 
             if timed_out {
@@ -400,6 +416,7 @@ struct RawExecToolCallOutput {
     pub stderr: StreamOutput<Vec<u8>>,
     pub aggregated_output: StreamOutput<Vec<u8>>,
     pub timed_out: bool,
+    pub output_truncation: Option<CommandOutputTruncation>,
 }
 
 impl StreamOutput<String> {
@@ -420,6 +437,7 @@ impl StreamOutput<Vec<u8>> {
     }
 }
 
+#[cfg(target_os = "windows")]
 #[inline]
 fn append_all(dst: &mut Vec<u8>, src: &[u8]) {
     dst.extend_from_slice(src);
@@ -433,6 +451,8 @@ pub struct ExecToolCallOutput {
     pub aggregated_output: StreamOutput<String>,
     pub duration: Duration,
     pub timed_out: bool,
+    /// What the session's output policy left out, if anything.
+    pub output_truncation: Option<CommandOutputTruncation>,
 }
 
 #[cfg_attr(not(target_os = "windows"), allow(unused_variables))]
@@ -440,6 +460,7 @@ async fn exec(
     params: ExecParams,
     sandbox: SandboxType,
     sandbox_policy: &SandboxPolicy,
+    stdio_policy: StdioPolicy,
     stdout_stream: Option<StdoutStream>,
 ) -> Result<RawExecToolCallOutput> {
     #[cfg(target_os = "windows")]
@@ -463,6 +484,20 @@ async fn exec(
             "command args are empty",
         ))
     })?;
+    if let StdioPolicy::Pty(size) = stdio_policy {
+        return exec_pty(
+            program,
+            args,
+            &cwd,
+            env,
+            &arg0,
+            sandbox_policy,
+            size,
+            timeout,
+            stdout_stream,
+        )
+        .await;
+    }
     let arg0_ref = arg0.as_deref();
     let child = spawn_child_async(
         PathBuf::from(program),
@@ -470,13 +505,131 @@ async fn exec(
         arg0_ref,
         cwd,
         sandbox_policy,
-        StdioPolicy::RedirectForShellTool,
+        stdio_policy,
         env,
     )
     .await?;
     consume_truncated_output(child, timeout, stdout_stream).await
 }
 
+/// Runs the command attached to a pseudo-terminal. The terminal merges stdout
+/// and stderr, so all output is reported on the stdout stream and stderr is
+/// left empty. The process is killed when the timeout elapses. Like
+/// [`spawn_child_async`], the environment reports when network access is
+/// disabled and, on Linux, the process is terminated if Codex dies.
+#[allow(clippy::too_many_arguments)]
+async fn exec_pty(
+    program: &str,
+    args: &[String],
+    cwd: &Path,
+    mut env: HashMap<String, String>,
+    arg0: &Option<String>,
+    sandbox_policy: &SandboxPolicy,
+    size: TerminalSize,
+    timeout: Duration,
+    stdout_stream: Option<StdoutStream>,
+) -> Result<RawExecToolCallOutput> {
+    if !sandbox_policy.has_full_network_access() {
+        env.insert(
+            CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR.to_string(),
+            "1".to_string(),
+        );
+    }
+    let SpawnedPty {
+        session,
+        mut output_rx,
+        mut exit_rx,
+    } = codex_utils_pty::spawn_pty_process(program, args, cwd, &env, arg0, size)
+        .await
+        .map_err(|err| CodexErr::Io(io::Error::other(format!("failed to spawn PTY: {err}"))))?;
+
+    let policy = output_policy(stdout_stream.as_ref());
+    let budget = StreamBudget::new(&policy);
+    let mut output = RetainedOutput::new(&policy);
+    // The terminal merges both streams into stdout, so the stderr spool stays empty.
+    let mut spool = create_spool(stdout_stream.as_ref(), ExecOutputStream::Stdout);
+    if let Some(stderr_spool) = create_spool(stdout_stream.as_ref(), ExecOutputStream::Stderr) {
+        stderr_spool.finish();
+    }
+
+    let deadline = tokio::time::sleep(timeout);
+    tokio::pin!(deadline);
+    let mut output_open = true;
+    let (exit_status, timed_out) = loop {
+        tokio::select! {
+            chunk = output_rx.recv(), if output_open => match chunk {
+                Ok(chunk) => {
+                    record_pty_chunk(chunk, stdout_stream.as_ref(), &budget, &mut output, &mut spool)
+                        .await;
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    record_dropped_pty_chunks(skipped, &mut output, &mut spool);
+                }
+                Err(RecvError::Closed) => output_open = false,
+            },
+            code = &mut exit_rx => {
+                break (synthetic_exit_status_from_code(code.unwrap_or(-1)), false);
+            }
+            _ = &mut deadline => {
+                break (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + TIMEOUT_CODE), true);
+            }
+            _ = tokio::signal::ctrl_c() => {
+                break (synthetic_exit_status(EXIT_CODE_SIGNAL_BASE + SIGKILL_CODE), false);
+            }
+        }
+    };
+
+    // The reader keeps forwarding output that was buffered in the terminal
+    // when the process exited; collect it until the stream goes quiet.
+    if output_open && !timed_out {
+        loop {
+            match tokio::time::timeout(Duration::from_millis(PTY_DRAIN_IDLE_MS), output_rx.recv())
+                .await
+            {
+                Ok(Ok(chunk)) => {
+                    record_pty_chunk(
+                        chunk,
+                        stdout_stream.as_ref(),
+                        &budget,
+                        &mut output,
+                        &mut spool,
+                    )
+                    .await;
+                }
+                Ok(Err(RecvError::Lagged(skipped))) => {
+                    record_dropped_pty_chunks(skipped, &mut output, &mut spool);
+                }
+                Ok(Err(RecvError::Closed)) | Err(_) => break,
+            }
+        }
+    }
+    if let Some(spool) = spool {
+        spool.finish();
+    }
+    // Dropping the session kills the process if it is still running.
+    drop(session);
+
+    let output_truncation = output_truncation(output.omitted_bytes(), budget.unstreamed_bytes());
+    let output = output.into_bytes();
+    Ok(RawExecToolCallOutput {
+        exit_status,
+        stdout: StreamOutput {
+            text: output.clone(),
+            truncated_after_lines: None,
+        },
+        stderr: StreamOutput {
+            text: Vec::new(),
+            truncated_after_lines: None,
+        },
+        aggregated_output: StreamOutput {
+            text: output,
+            truncated_after_lines: None,
+        },
+        timed_out,
+        output_truncation,
+    })
+}
+
 /// Consumes the output of a child process, truncating it so it is suitable for
 /// use as the output of a `shell` tool call. Also enforces specified timeout.
 async fn consume_truncated_output(
@@ -500,16 +653,20 @@ async fn consume_truncated_output(
     })?;
 
     let (agg_tx, agg_rx) = async_channel::unbounded::<Vec<u8>>();
+    let policy = output_policy(stdout_stream.as_ref());
+    let budget = Arc::new(StreamBudget::new(&policy));
 
     let stdout_handle = tokio::spawn(read_capped(
         BufReader::new(stdout_reader),
         stdout_stream.clone(),
+        Arc::clone(&budget),
         false,
         Some(agg_tx.clone()),
     ));
     let stderr_handle = tokio::spawn(read_capped(
         BufReader::new(stderr_reader),
         stdout_stream.clone(),
+        Arc::clone(&budget),
         true,
         Some(agg_tx.clone()),
     ));
@@ -586,12 +743,13 @@ async fn consume_truncated_output(
 
     drop(agg_tx);
 
-    let mut combined_buf = Vec::with_capacity(AGGREGATE_BUFFER_INITIAL_CAPACITY);
+    let mut combined = RetainedOutput::new(&policy);
     while let Ok(chunk) = agg_rx.recv().await {
-        append_all(&mut combined_buf, &chunk);
+        combined.push(&chunk);
     }
+    let output_truncation = output_truncation(combined.omitted_bytes(), budget.unstreamed_bytes());
     let aggregated_output = StreamOutput {
-        text: combined_buf,
+        text: combined.into_bytes(),
         truncated_after_lines: None,
     };
 
@@ -601,20 +759,25 @@ async fn consume_truncated_output(
         stderr,
         aggregated_output,
         timed_out,
+        output_truncation,
     })
 }
 
 async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
     mut reader: R,
     stream: Option<StdoutStream>,
+    budget: Arc<StreamBudget>,
     is_stderr: bool,
     aggregate_tx: Option<Sender<Vec<u8>>>,
 ) -> io::Result<StreamOutput<Vec<u8>>> {
-    let mut buf = Vec::with_capacity(AGGREGATE_BUFFER_INITIAL_CAPACITY);
+    let mut buf = RetainedOutput::new(&output_policy(stream.as_ref()));
     let mut tmp = [0u8; READ_CHUNK_SIZE];
-    let mut emitted_deltas: usize = 0;
-
-    // No caps: append all bytes
+    let output_stream = if is_stderr {
+        ExecOutputStream::Stderr
+    } else {
+        ExecOutputStream::Stdout
+    };
+    let mut spool = create_spool(stream.as_ref(), output_stream.clone());
 
     loop {
         let n = reader.read(&mut tmp).await?;
@@ -623,41 +786,103 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
         }
 
         if let Some(stream) = &stream
-            && emitted_deltas < MAX_EXEC_OUTPUT_DELTAS_PER_CALL
+            && budget.admit(n)
         {
-            let chunk = tmp[..n].to_vec();
-            let msg = EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
-                call_id: stream.call_id.clone(),
-                stream: if is_stderr {
-                    ExecOutputStream::Stderr
-                } else {
-                    ExecOutputStream::Stdout
-                },
-                chunk,
-            });
-            let event = Event {
-                id: stream.sub_id.clone(),
-                msg,
-            };
-            #[allow(clippy::let_unit_value)]
-            let _ = stream.tx_event.send(event).await;
-            emitted_deltas += 1;
+            send_output_delta(stream, output_stream.clone(), tmp[..n].to_vec()).await;
         }
 
         if let Some(tx) = &aggregate_tx {
             let _ = tx.send(tmp[..n].to_vec()).await;
         }
 
-        append_all(&mut buf, &tmp[..n]);
+        if let Some(spool) = spool.as_mut() {
+            spool.write(&tmp[..n]);
+        }
+        buf.push(&tmp[..n]);
         // Continue reading to EOF to avoid back-pressure
     }
+    if let Some(spool) = spool {
+        spool.finish();
+    }
 
     Ok(StreamOutput {
-        text: buf,
+        text: buf.into_bytes(),
         truncated_after_lines: None,
     })
 }
 
+async fn record_pty_chunk(
+    chunk: Vec<u8>,
+    stream: Option<&StdoutStream>,
+    budget: &StreamBudget,
+    output: &mut RetainedOutput,
+    spool: &mut Option<ExecOutputSpool>,
+) {
+    if let Some(spool) = spool.as_mut() {
+        spool.write(&chunk);
+    }
+    output.push(&chunk);
+    if let Some(stream) = stream
+        && budget.admit(chunk.len())
+    {
+        send_output_delta(stream, ExecOutputStream::Stdout, chunk).await;
+    }
+}
+
+/// Notes in the output that the terminal reader got ahead of us and `skipped`
+/// chunks were dropped, so the output is not silently incomplete.
+fn record_dropped_pty_chunks(
+    skipped: u64,
+    output: &mut RetainedOutput,
+    spool: &mut Option<ExecOutputSpool>,
+) {
+    let marker = format!("\n[... {skipped} output chunks dropped ...]\n");
+    if let Some(spool) = spool.as_mut() {
+        spool.write(marker.as_bytes());
+    }
+    output.push(marker.as_bytes());
+}
+
+/// Spools `output_stream` to the call's archive, when it has one.
+fn create_spool(
+    stream: Option<&StdoutStream>,
+    output_stream: ExecOutputStream,
+) -> Option<ExecOutputSpool> {
+    let dir = stream?.archive_dir.as_deref()?;
+    ExecOutputSpool::create(dir, output_stream)
+}
+
+/// The output policy of the call; calls that are not streamed keep all of
+/// their output.
+fn output_policy(stream: Option<&StdoutStream>) -> ExecOutputConfig {
+    stream.map(|stream| stream.policy).unwrap_or_default()
+}
+
+async fn send_output_delta(stream: &StdoutStream, output_stream: ExecOutputStream, chunk: Vec<u8>) {
+    let msg = EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
+        call_id: stream.call_id.clone(),
+        stream: output_stream,
+        chunk,
+    });
+    let event = Event {
+        id: stream.sub_id.clone(),
+        msg,
+    };
+    #[allow(clippy::let_unit_value)]
+    let _ = stream.tx_event.send(event).await;
+}
+
+/// Builds an `ExitStatus` for a process that exited normally with `code`.
+#[cfg(unix)]
+fn synthetic_exit_status_from_code(code: i32) -> ExitStatus {
+    synthetic_exit_status((code & 0xff) << 8)
+}
+
+#[cfg(windows)]
+fn synthetic_exit_status_from_code(code: i32) -> ExitStatus {
+    synthetic_exit_status(code)
+}
+
 #[cfg(unix)]
 fn synthetic_exit_status(code: i32) -> ExitStatus {
     use std::os::unix::process::ExitStatusExt;
@@ -673,7 +898,7 @@ fn synthetic_exit_status(code: i32) -> ExitStatus {
 }
 
 #[cfg(unix)]
-fn kill_child_process_group(child: &mut Child) -> io::Result<()> {
+pub(crate) fn kill_child_process_group(child: &mut Child) -> io::Result<()> {
     use std::io::ErrorKind;
 
     if let Some(pid) = child.id() {
@@ -700,13 +925,14 @@ fn kill_child_process_group(child: &mut Child) -> io::Result<()> {
 }
 
 #[cfg(not(unix))]
-fn kill_child_process_group(_: &mut Child) -> io::Result<()> {
+pub(crate) fn kill_child_process_group(_: &mut Child) -> io::Result<()> {
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pretty_assertions::assert_eq;
     use std::time::Duration;
 
     fn make_exec_output(
@@ -722,6 +948,7 @@ mod tests {
             aggregated_output: StreamOutput::new(aggregated.to_string()),
             duration: Duration::from_millis(1),
             timed_out: false,
+            output_truncation: None,
         }
     }
 
@@ -796,7 +1023,14 @@ mod tests {
             arg0: None,
         };
 
-        let output = exec(params, SandboxType::None, &SandboxPolicy::ReadOnly, None).await?;
+        let output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::ReadOnly,
+            StdioPolicy::RedirectForShellTool,
+            None,
+        )
+        .await?;
         assert!(output.timed_out);
 
         let stdout = output.stdout.from_utf8_lossy().text;
@@ -823,4 +1057,135 @@ mod tests {
         assert!(killed, "grandchild process with pid {pid} is still alive");
         Ok(())
     }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn pty_stdio_policy_runs_command_in_terminal_of_requested_size() -> Result<()> {
+        let command = vec![
+            "/bin/sh".to_string(),
+            "-c".to_string(),
+            "test -t 1 && stty size; exit 3".to_string(),
+        ];
+        let params = ExecParams {
+            command,
+            cwd: std::env::current_dir()?,
+            timeout_ms: Some(5_000),
+            env: std::env::vars().collect(),
+            with_escalated_permissions: None,
+            justification: None,
+            arg0: None,
+        };
+
+        let output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::ReadOnly,
+            StdioPolicy::Pty(TerminalSize {
+                rows: 40,
+                cols: 120,
+            }),
+            None,
+        )
+        .await?;
+
+        assert_eq!(
+            (
+                output.exit_status.code(),
+                output.stdout.from_utf8_lossy().text.trim().to_string(),
+                output.stderr.text.is_empty(),
+                output.timed_out,
+            ),
+            (Some(3), "40 120".to_string(), true, false)
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn archive_spool_keeps_output_dropped_by_retention() -> Result<()> {
+        let codex_home = tempfile::TempDir::new()?;
+        let conversation_id = codex_protocol::ConversationId::new();
+        let archive_dir =
+            crate::exec_log::exec_output_dir(codex_home.path(), &conversation_id, "call_1")?;
+        let (tx_event, _rx_event) = async_channel::unbounded();
+        let stdout_stream = StdoutStream {
+            sub_id: "sub_1".to_string(),
+            call_id: "call_1".to_string(),
+            tx_event,
+            policy: ExecOutputConfig {
+                retain_head_bytes: Some(16),
+                retain_tail_bytes: Some(16),
+                ..Default::default()
+            },
+            archive_dir: Some(archive_dir),
+        };
+        let params = ExecParams {
+            command: vec![
+                "/bin/sh".to_string(),
+                "-c".to_string(),
+                "seq 1 1000; echo oops >&2".to_string(),
+            ],
+            cwd: std::env::current_dir()?,
+            timeout_ms: Some(5_000),
+            env: std::env::vars().collect(),
+            with_escalated_permissions: None,
+            justification: None,
+            arg0: None,
+        };
+
+        let output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::DangerFullAccess,
+            StdioPolicy::RedirectForShellTool,
+            Some(stdout_stream),
+        )
+        .await?;
+
+        let retained = output.stdout.from_utf8_lossy().text;
+        assert!(retained.contains("bytes omitted"), "{retained:?}");
+        let archived =
+            crate::exec_log::read_exec_output(codex_home.path(), &conversation_id, "call_1")
+                .await?;
+        assert_eq!(
+            archived,
+            crate::exec_log::ArchivedExecOutput {
+                stdout: (1..=1000).map(|i| format!("{i}\n")).collect(),
+                stderr: "oops\n".to_string(),
+            }
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn pty_stdio_policy_reports_disabled_network() -> Result<()> {
+        let mut env: HashMap<String, String> = std::env::vars().collect();
+        env.remove(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR);
+        let params = ExecParams {
+            command: vec![
+                "/bin/sh".to_string(),
+                "-c".to_string(),
+                format!("printf %s \"${CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR}\""),
+            ],
+            cwd: std::env::current_dir()?,
+            timeout_ms: Some(5_000),
+            env,
+            with_escalated_permissions: None,
+            justification: None,
+            arg0: None,
+        };
+
+        let output = exec(
+            params,
+            SandboxType::None,
+            &SandboxPolicy::ReadOnly,
+            StdioPolicy::Pty(TerminalSize::default()),
+            None,
+        )
+        .await?;
+
+        assert_eq!(output.stdout.from_utf8_lossy().text, "1");
+        Ok(())
+    }
 }
@@ -0,0 +1,154 @@
+//! Dry-run assessment of a command: what the approval and sandbox logic
+//! would decide for it under a config, without running it.
+//!
+//! The steps mirror the shell tool handler and the tool orchestrator, so
+//! clients can preview the effect of a settings change or check a policy they
+//! are writing.
+
+use codex_execpolicy2::Decision;
+use codex_protocol::protocol::AskForApproval;
+
+use crate::approval_rules::ApprovalRuleStore;
+use crate::config::Config;
+use crate::exec::SandboxType;
+use crate::exec_env::scrubbed_env_vars;
+use crate::exec_policy::ExecPolicyError;
+use crate::exec_policy::create_approval_requirement_for_command;
+use crate::exec_policy::exec_policy_for;
+use crate::exec_policy::strictest_decision;
+use crate::sandboxing::SandboxManager;
+use crate::sandboxing::SandboxPermissions;
+use crate::tools::sandboxing::ApprovalRequirement;
+use crate::tools::sandboxing::SandboxablePreference;
+
+/// Whether a command would run right away, prompt, or be refused.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApprovalOutcome {
+    Skip,
+    NeedsApproval { reason: Option<String> },
+    Forbidden { reason: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandAssessment {
+    /// Strictest execpolicy decision among the rules matching the command,
+    /// or `None` when no rule matched.
+    pub policy_decision: Option<Decision>,
+    /// Whether a remembered approval rule covers the command.
+    pub approved_by_rule: bool,
+    pub approval: ApprovalOutcome,
+    /// Sandbox the first attempt would run under.
+    pub sandbox: SandboxType,
+    /// Inherited environment variables the command would not see, sorted.
+    pub scrubbed_env_vars: Vec<String>,
+}
+
+/// Assesses `command` as if the model had asked to run it from `config.cwd`.
+pub async fn assess_command(
+    config: &Config,
+    command: &[String],
+    with_escalated_permissions: bool,
+) -> Result<CommandAssessment, ExecPolicyError> {
+    let policy = exec_policy_for(&config.features, &config.codex_home).await?;
+    let (policy_decision, _) = strictest_decision(&policy, command);
+    let approved_by_rule =
+        ApprovalRuleStore::load(&config.codex_home, &config.cwd).approves(command, &config.cwd);
+
+    let sandbox = if with_escalated_permissions {
+        SandboxType::None
+    } else {
+        SandboxManager::new().select_initial(&config.sandbox_policy, SandboxablePreference::Auto)
+    };
+
+    let approval = if with_escalated_permissions
+        && !matches!(config.approval_policy, AskForApproval::OnRequest)
+    {
+        ApprovalOutcome::Forbidden {
+            reason: format!(
+                "escalated permissions are only requested with the on-request approval policy, not {:?}",
+                config.approval_policy
+            ),
+        }
+    } else if config.sandbox_policy.is_audit() && sandbox == SandboxType::None {
+        ApprovalOutcome::Forbidden {
+            reason: "audit mode only runs commands inside a platform sandbox".to_string(),
+        }
+    } else {
+        match create_approval_requirement_for_command(
+            &policy,
+            command,
+            config.approval_policy,
+            &config.sandbox_policy,
+            SandboxPermissions::from(with_escalated_permissions),
+            approved_by_rule,
+        ) {
+            ApprovalRequirement::Skip => ApprovalOutcome::Skip,
+            ApprovalRequirement::NeedsApproval { reason } => {
+                ApprovalOutcome::NeedsApproval { reason }
+            }
+            ApprovalRequirement::Forbidden { reason } => ApprovalOutcome::Forbidden { reason },
+        }
+    };
+
+    Ok(CommandAssessment {
+        policy_decision,
+        approved_by_rule,
+        approval,
+        sandbox,
+        scrubbed_env_vars: scrubbed_env_vars(&config.shell_environment_policy),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigOverrides;
+    use crate::config::ConfigToml;
+    use codex_protocol::protocol::SandboxPolicy;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn config(codex_home: &TempDir, approval_policy: AskForApproval) -> Config {
+        Config::load_from_base_config_with_overrides(
+            ConfigToml::default(),
+            ConfigOverrides {
+                approval_policy: Some(approval_policy),
+                cwd: Some(codex_home.path().to_path_buf()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )
+        .expect("load config")
+    }
+
+    #[tokio::test]
+    async fn escalation_outside_on_request_is_forbidden_and_unsandboxed() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let command = vec!["cargo".to_string(), "build".to_string()];
+
+        let assessment =
+            assess_command(&config(&codex_home, AskForApproval::Never), &command, true)
+                .await
+                .expect("assess");
+        assert_eq!(assessment.sandbox, SandboxType::None);
+        assert!(matches!(
+            assessment.approval,
+            ApprovalOutcome::Forbidden { .. }
+        ));
+        assert_eq!(assessment.policy_decision, None);
+        assert!(!assessment.approved_by_rule);
+    }
+
+    #[tokio::test]
+    async fn known_safe_commands_skip_approval() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let mut config = config(&codex_home, AskForApproval::UnlessTrusted);
+        config.sandbox_policy = SandboxPolicy::ReadOnly;
+        let command = vec!["ls".to_string()];
+
+        let assessment = assess_command(&config, &command, false)
+            .await
+            .expect("assess");
+        assert_eq!(assessment.approval, ApprovalOutcome::Skip);
+    }
+}
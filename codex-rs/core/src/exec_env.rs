@@ -1,8 +1,27 @@
 use crate::config::types::EnvironmentVariablePattern;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyInherit;
+use crate::shell::Shell;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::OnceCell;
+
+/// Environment of the user's login shell, or why it could not be read.
+static LOGIN_SHELL_ENV: OnceCell<Result<HashMap<String, String>, String>> = OnceCell::const_new();
+
+/// Rc files can be slow, but a shell that never returns must not hold up the
+/// session.
+const LOGIN_SHELL_ENV_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Printed before the environment so output from rc files can be skipped.
+const LOGIN_SHELL_ENV_MARKER: &str = "__CODEX_LOGIN_SHELL_ENV__";
+
+/// Variables that describe the probe shell itself rather than the user's
+/// environment.
+const LOGIN_SHELL_PROBE_VARS: &[&str] = &["_", "OLDPWD", "PWD", "SHLVL"];
 
 /// Construct an environment map based on the rules in the specified policy. The
 /// resulting map can be passed directly to `Command::envs()` after calling
@@ -10,9 +29,104 @@ use std::collections::HashSet;
 /// process.
 ///
 /// The derivation follows the algorithm documented in the struct-level comment
-/// for [`ShellEnvironmentPolicy`].
+/// for [`ShellEnvironmentPolicy`]. With `inherit = "login-shell"` it starts
+/// from the environment loaded by [`load_login_shell_env`], or from the
+/// process environment if that has not been loaded or failed to load.
 pub fn create_env(policy: &ShellEnvironmentPolicy) -> HashMap<String, String> {
-    populate_env(std::env::vars(), policy)
+    populate_env(inherited_env(policy), policy)
+}
+
+/// Names of the inherited variables that [`create_env`] leaves out, sorted.
+/// Variables that `set` overrides are not included.
+pub fn scrubbed_env_vars(policy: &ShellEnvironmentPolicy) -> Vec<String> {
+    scrubbed_names(inherited_env(policy), policy)
+}
+
+fn inherited_env(policy: &ShellEnvironmentPolicy) -> HashMap<String, String> {
+    if policy.inherit == ShellEnvironmentPolicyInherit::LoginShell
+        && let Some(Ok(env)) = LOGIN_SHELL_ENV.get()
+    {
+        return env.clone();
+    }
+    std::env::vars().collect()
+}
+
+fn scrubbed_names(vars: HashMap<String, String>, policy: &ShellEnvironmentPolicy) -> Vec<String> {
+    let kept = populate_env(vars.clone(), policy);
+    let mut scrubbed: Vec<String> = vars
+        .into_keys()
+        .filter(|name| !kept.contains_key(name))
+        .collect();
+    scrubbed.sort();
+    scrubbed
+}
+
+/// Read the environment of the user's login shell the first time it is
+/// needed and cache it for the rest of the process. The shell runs as an
+/// interactive login shell, so both profile and rc files are sourced, with
+/// no stdin and a timeout.
+pub(crate) async fn load_login_shell_env(shell: &Shell) -> Result<(), String> {
+    LOGIN_SHELL_ENV
+        .get_or_init(|| resolve_login_shell_env(shell))
+        .await
+        .as_ref()
+        .map(|_| ())
+        .map_err(Clone::clone)
+}
+
+async fn resolve_login_shell_env(shell: &Shell) -> Result<HashMap<String, String>, String> {
+    let shell_path = match shell {
+        Shell::Zsh(zsh) => &zsh.shell_path,
+        Shell::Bash(bash) => &bash.shell_path,
+        Shell::PowerShell(_) | Shell::Unknown => {
+            return Err("only bash and zsh login shells are supported".to_string());
+        }
+    };
+    let script = format!("printf '%s' {LOGIN_SHELL_ENV_MARKER}; env -0");
+    let mut command = Command::new(shell_path);
+    command
+        .args(["-i", "-l", "-c", &script])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+    let output = tokio::time::timeout(LOGIN_SHELL_ENV_TIMEOUT, command.output())
+        .await
+        .map_err(|_| {
+            format!(
+                "{} did not finish within {}s",
+                shell_path.display(),
+                LOGIN_SHELL_ENV_TIMEOUT.as_secs()
+            )
+        })?
+        .map_err(|err| format!("failed to run {}: {err}", shell_path.display()))?;
+    if !output.status.success() {
+        return Err(format!(
+            "{} exited with {}",
+            shell_path.display(),
+            output.status
+        ));
+    }
+    parse_login_shell_env(&output.stdout)
+        .ok_or_else(|| format!("{} did not print its environment", shell_path.display()))
+}
+
+/// Parse the NUL-separated `env -0` output that follows the marker.
+fn parse_login_shell_env(stdout: &[u8]) -> Option<HashMap<String, String>> {
+    let stdout = String::from_utf8_lossy(stdout);
+    let (_, env) = stdout.split_once(LOGIN_SHELL_ENV_MARKER)?;
+    Some(
+        env.split('\0')
+            .filter_map(|entry| entry.split_once('='))
+            .filter(|(name, _)| is_valid_env_var_name(name))
+            .filter(|(name, _)| !LOGIN_SHELL_PROBE_VARS.contains(name))
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+    )
+}
+
+/// Whether `name` can be passed to a child process as a variable name.
+pub fn is_valid_env_var_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(['=', '\0'])
 }
 
 fn populate_env<I>(vars: I, policy: &ShellEnvironmentPolicy) -> HashMap<String, String>
@@ -22,7 +136,9 @@ where
     // Step 1 – determine the starting set of variables based on the
     // `inherit` strategy.
     let mut env_map: HashMap<String, String> = match policy.inherit {
-        ShellEnvironmentPolicyInherit::All => vars.into_iter().collect(),
+        ShellEnvironmentPolicyInherit::All | ShellEnvironmentPolicyInherit::LoginShell => {
+            vars.into_iter().collect()
+        }
         ShellEnvironmentPolicyInherit::None => HashMap::new(),
         ShellEnvironmentPolicyInherit::Core => {
             const CORE_VARS: &[&str] = &[
@@ -101,6 +217,20 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn scrubbed_names_lists_variables_left_out() {
+        let vars = make_vars(&[
+            ("PATH", "/usr/bin"),
+            ("API_KEY", "secret"),
+            ("EDITOR", "vim"),
+        ]);
+        let policy = ShellEnvironmentPolicy::default();
+        assert_eq!(
+            scrubbed_names(vars.into_iter().collect(), &policy),
+            vec!["API_KEY".to_string(), "EDITOR".to_string()]
+        );
+    }
+
     #[test]
     fn test_include_only() {
         let vars = make_vars(&[("PATH", "/usr/bin"), ("FOO", "bar")]);
@@ -191,4 +321,26 @@ mod tests {
         };
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn parses_login_shell_env_after_rc_file_output() {
+        let stdout = format!(
+            "Welcome back!\n{LOGIN_SHELL_ENV_MARKER}PATH=/home/user/.nvm/bin:/usr/bin\0MULTI=a\nb\0SHLVL=2\0"
+        );
+
+        let expected: HashMap<String, String> = hashmap! {
+            "PATH".to_string() => "/home/user/.nvm/bin:/usr/bin".to_string(),
+            "MULTI".to_string() => "a\nb".to_string(),
+        };
+        assert_eq!(parse_login_shell_env(stdout.as_bytes()), Some(expected));
+        assert_eq!(parse_login_shell_env(b"PATH=/usr/bin\0"), None);
+    }
+
+    #[test]
+    fn rejects_names_a_process_environment_cannot_hold() {
+        assert!(is_valid_env_var_name("NODE_OPTIONS"));
+        assert!(!is_valid_env_var_name(""));
+        assert!(!is_valid_env_var_name("A=B"));
+        assert!(!is_valid_env_var_name("A\0"));
+    }
 }
@@ -0,0 +1,279 @@
+//! Archive of the complete stdout/stderr produced by exec tool calls.
+//!
+//! `ExecCommandEnd` events only carry output that has been truncated for the
+//! model, which is not enough for post-mortems. When the
+//! `exec_output_archive` feature is enabled, each exec call's streams are
+//! written gzip-compressed to
+//! `~/.codex/exec-logs/<conversation_id>/<call_id>/{stdout,stderr}.gz` and
+//! the directory is referenced from the rollout via
+//! [`RolloutItem::ExecOutputArchive`](codex_protocol::protocol::RolloutItem).
+//! Calls that stream their output write it through an [`ExecOutputSpool`] as
+//! it is read, so the archive holds every byte even when the session's
+//! output policy keeps only the head and tail.
+
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ConversationId;
+use codex_protocol::protocol::ExecOutputStream;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use tracing::warn;
+
+/// Directory inside `~/.codex` that holds archived exec output.
+pub const EXEC_LOGS_SUBDIR: &str = "exec-logs";
+
+const STDOUT_FILENAME: &str = "stdout.gz";
+const STDERR_FILENAME: &str = "stderr.gz";
+
+/// Complete output of a single exec tool call, read back from the archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchivedExecOutput {
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Returns the archive directory for `call_id`. Call ids come from the model
+/// (or from API clients when reading), so anything that could escape the
+/// conversation directory is rejected.
+pub fn exec_output_dir(
+    codex_home: &Path,
+    conversation_id: &ConversationId,
+    call_id: &str,
+) -> io::Result<PathBuf> {
+    let is_safe = !call_id.is_empty()
+        && call_id != "."
+        && call_id != ".."
+        && !call_id.contains(['/', '\\', '\0']);
+    if !is_safe {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid exec call id: {call_id:?}"),
+        ));
+    }
+    Ok(codex_home
+        .join(EXEC_LOGS_SUBDIR)
+        .join(conversation_id.to_string())
+        .join(call_id))
+}
+
+/// Writes `stdout` and `stderr` for `call_id` to the archive and returns the
+/// directory that now holds them. Existing files for the same call are
+/// overwritten.
+pub(crate) async fn archive_exec_output(
+    codex_home: &Path,
+    conversation_id: &ConversationId,
+    call_id: &str,
+    stdout: &str,
+    stderr: &str,
+) -> io::Result<PathBuf> {
+    let dir = exec_output_dir(codex_home, conversation_id, call_id)?;
+    let stdout = stdout.as_bytes().to_vec();
+    let stderr = stderr.as_bytes().to_vec();
+    tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&dir)?;
+        write_compressed(&dir.join(STDOUT_FILENAME), &stdout)?;
+        write_compressed(&dir.join(STDERR_FILENAME), &stderr)?;
+        Ok(dir)
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Returns the archive directory of `call_id` when both of its streams were
+/// spooled while the call ran.
+pub(crate) fn spooled_exec_output_dir(
+    codex_home: &Path,
+    conversation_id: &ConversationId,
+    call_id: &str,
+) -> Option<PathBuf> {
+    let dir = exec_output_dir(codex_home, conversation_id, call_id).ok()?;
+    (dir.join(STDOUT_FILENAME).is_file() && dir.join(STDERR_FILENAME).is_file()).then_some(dir)
+}
+
+/// Writes one stream of a running exec call to the archive as it is read.
+/// A spool that fails to write removes its file, so the call falls back to
+/// archiving the output it kept.
+pub(crate) struct ExecOutputSpool {
+    path: PathBuf,
+    encoder: Option<GzEncoder<File>>,
+}
+
+impl ExecOutputSpool {
+    pub(crate) fn create(dir: &Path, stream: ExecOutputStream) -> Option<Self> {
+        let filename = match stream {
+            ExecOutputStream::Stdout => STDOUT_FILENAME,
+            ExecOutputStream::Stderr => STDERR_FILENAME,
+        };
+        let path = dir.join(filename);
+        let file = std::fs::create_dir_all(dir).and_then(|()| File::create(&path));
+        match file {
+            Ok(file) => Some(Self {
+                path,
+                encoder: Some(GzEncoder::new(file, Compression::default())),
+            }),
+            Err(err) => {
+                warn!("failed to spool exec output to {}: {err}", path.display());
+                None
+            }
+        }
+    }
+
+    pub(crate) fn write(&mut self, chunk: &[u8]) {
+        let Some(encoder) = self.encoder.as_mut() else {
+            return;
+        };
+        if let Err(err) = encoder.write_all(chunk) {
+            self.abandon(err);
+        }
+    }
+
+    pub(crate) fn finish(mut self) {
+        let Some(encoder) = self.encoder.take() else {
+            return;
+        };
+        if let Err(err) = encoder.finish().and_then(|file| file.sync_all()) {
+            self.abandon(err);
+        }
+    }
+
+    fn abandon(&mut self, err: io::Error) {
+        warn!(
+            "failed to spool exec output to {}: {err}",
+            self.path.display()
+        );
+        self.encoder = None;
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Reads back the archived output for `call_id`. Returns
+/// [`io::ErrorKind::NotFound`] when nothing was archived for the call.
+pub async fn read_exec_output(
+    codex_home: &Path,
+    conversation_id: &ConversationId,
+    call_id: &str,
+) -> io::Result<ArchivedExecOutput> {
+    let dir = exec_output_dir(codex_home, conversation_id, call_id)?;
+    tokio::task::spawn_blocking(move || {
+        Ok(ArchivedExecOutput {
+            stdout: read_compressed(&dir.join(STDOUT_FILENAME))?,
+            stderr: read_compressed(&dir.join(STDERR_FILENAME))?,
+        })
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+fn write_compressed(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut encoder = GzEncoder::new(File::create(path)?, Compression::default());
+    encoder.write_all(contents)?;
+    encoder.finish()?.sync_all()
+}
+
+fn read_compressed(path: &Path) -> io::Result<String> {
+    let mut decoder = GzDecoder::new(File::open(path)?);
+    let mut contents = Vec::new();
+    decoder.read_to_end(&mut contents)?;
+    Ok(String::from_utf8_lossy(&contents).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn archived_output_round_trips() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let conversation_id = ConversationId::new();
+        let stdout = "line\n".repeat(10_000);
+
+        let dir = archive_exec_output(
+            codex_home.path(),
+            &conversation_id,
+            "call_1",
+            &stdout,
+            "boom",
+        )
+        .await
+        .expect("archive output");
+
+        assert_eq!(
+            dir,
+            codex_home
+                .path()
+                .join(EXEC_LOGS_SUBDIR)
+                .join(conversation_id.to_string())
+                .join("call_1")
+        );
+        let archived = read_exec_output(codex_home.path(), &conversation_id, "call_1")
+            .await
+            .expect("read output");
+        assert_eq!(
+            archived,
+            ArchivedExecOutput {
+                stdout,
+                stderr: "boom".to_string(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn spooled_output_reads_back_like_archived_output() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let conversation_id = ConversationId::new();
+        let dir = exec_output_dir(codex_home.path(), &conversation_id, "call_1").expect("dir");
+        assert_eq!(
+            spooled_exec_output_dir(codex_home.path(), &conversation_id, "call_1"),
+            None
+        );
+
+        let mut stdout = ExecOutputSpool::create(&dir, ExecOutputStream::Stdout).expect("spool");
+        stdout.write(b"first ");
+        stdout.write(b"second\n");
+        stdout.finish();
+        ExecOutputSpool::create(&dir, ExecOutputStream::Stderr)
+            .expect("spool")
+            .finish();
+
+        assert_eq!(
+            spooled_exec_output_dir(codex_home.path(), &conversation_id, "call_1"),
+            Some(dir)
+        );
+        let archived = read_exec_output(codex_home.path(), &conversation_id, "call_1")
+            .await
+            .expect("read output");
+        assert_eq!(
+            archived,
+            ArchivedExecOutput {
+                stdout: "first second\n".to_string(),
+                stderr: String::new(),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_call_ids_that_escape_the_conversation_dir() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let conversation_id = ConversationId::new();
+
+        for call_id in ["", "..", "../other", "a/b", "a\\b"] {
+            let err = read_exec_output(codex_home.path(), &conversation_id, call_id)
+                .await
+                .expect_err("call id should be rejected");
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput, "{call_id:?}");
+        }
+
+        let err = read_exec_output(codex_home.path(), &conversation_id, "missing")
+            .await
+            .expect_err("nothing archived");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    }
+}
@@ -0,0 +1,174 @@
+//! Limits on how much of a command's output is streamed and kept.
+//!
+//! A command that prints megabytes would otherwise flood clients with
+//! `ExecCommandOutputDelta` events and hold all of its output in memory. The
+//! session's [`ExecOutputConfig`] caps the bytes streamed per call, after
+//! which only one chunk in `stream_sample_every` is streamed, and can keep
+//! just the head and tail of the output. What was left out is reported on the
+//! completed `CommandExecution` item.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use codex_protocol::items::CommandOutputTruncation;
+
+use crate::config::types::ExecOutputConfig;
+
+/// Output of one stream, keeping the head and tail allowed by the policy.
+#[derive(Debug)]
+pub(crate) struct RetainedOutput {
+    head: Vec<u8>,
+    tail: VecDeque<u8>,
+    head_limit: Option<usize>,
+    tail_limit: usize,
+    omitted_bytes: u64,
+}
+
+impl RetainedOutput {
+    pub(crate) fn new(policy: &ExecOutputConfig) -> Self {
+        let bounded = policy.retain_head_bytes.is_some() || policy.retain_tail_bytes.is_some();
+        Self {
+            head: Vec::new(),
+            tail: VecDeque::new(),
+            head_limit: bounded.then(|| policy.retain_head_bytes.unwrap_or(0)),
+            tail_limit: policy.retain_tail_bytes.unwrap_or(0),
+            omitted_bytes: 0,
+        }
+    }
+
+    pub(crate) fn push(&mut self, mut chunk: &[u8]) {
+        let Some(head_limit) = self.head_limit else {
+            self.head.extend_from_slice(chunk);
+            return;
+        };
+        let head_room = head_limit.saturating_sub(self.head.len());
+        let (head, rest) = chunk.split_at(head_room.min(chunk.len()));
+        self.head.extend_from_slice(head);
+        chunk = rest;
+
+        self.tail.extend(chunk);
+        let overflow = self.tail.len().saturating_sub(self.tail_limit);
+        if overflow > 0 {
+            self.tail.drain(..overflow);
+            self.omitted_bytes += overflow as u64;
+        }
+    }
+
+    pub(crate) fn omitted_bytes(&self) -> u64 {
+        self.omitted_bytes
+    }
+
+    /// The retained bytes, with a marker where the middle was dropped.
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        let mut bytes = self.head;
+        if self.omitted_bytes > 0 {
+            let marker = format!("\n[... {} bytes omitted ...]\n", self.omitted_bytes);
+            bytes.extend_from_slice(marker.as_bytes());
+        }
+        bytes.extend(self.tail);
+        bytes
+    }
+}
+
+#[derive(Debug, Default)]
+struct BudgetState {
+    streamed_bytes: usize,
+    chunks_past_cap: usize,
+    unstreamed_bytes: u64,
+}
+
+/// Decides which output chunks of a call are streamed as deltas. Shared by
+/// the readers of stdout and stderr.
+#[derive(Debug)]
+pub(crate) struct StreamBudget {
+    max_bytes: usize,
+    sample_every: usize,
+    state: Mutex<BudgetState>,
+}
+
+impl StreamBudget {
+    pub(crate) fn new(policy: &ExecOutputConfig) -> Self {
+        Self {
+            max_bytes: policy.stream_max_bytes,
+            sample_every: policy.stream_sample_every,
+            state: Mutex::new(BudgetState::default()),
+        }
+    }
+
+    /// Whether a chunk of `len` bytes should be streamed.
+    pub(crate) fn admit(&self, len: usize) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let admitted = if state.streamed_bytes + len <= self.max_bytes {
+            true
+        } else {
+            let sampled =
+                self.sample_every > 0 && state.chunks_past_cap.is_multiple_of(self.sample_every);
+            state.chunks_past_cap += 1;
+            sampled
+        };
+        if admitted {
+            state.streamed_bytes += len;
+        } else {
+            state.unstreamed_bytes += len as u64;
+        }
+        admitted
+    }
+
+    pub(crate) fn unstreamed_bytes(&self) -> u64 {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .unstreamed_bytes
+    }
+}
+
+/// What was left out of a call's output, if anything.
+pub(crate) fn output_truncation(
+    omitted_bytes: u64,
+    unstreamed_bytes: u64,
+) -> Option<CommandOutputTruncation> {
+    (omitted_bytes > 0 || unstreamed_bytes > 0).then_some(CommandOutputTruncation {
+        omitted_bytes,
+        unstreamed_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn keeps_head_and_tail() {
+        let mut output = RetainedOutput::new(&ExecOutputConfig {
+            retain_head_bytes: Some(4),
+            retain_tail_bytes: Some(3),
+            ..Default::default()
+        });
+        output.push(b"abcdef");
+        output.push(b"ghij");
+        assert_eq!(output.omitted_bytes(), 3);
+        assert_eq!(
+            String::from_utf8_lossy(&output.into_bytes()),
+            "abcd\n[... 3 bytes omitted ...]\nhij"
+        );
+
+        let mut unbounded = RetainedOutput::new(&ExecOutputConfig::default());
+        unbounded.push(b"abcdef");
+        assert_eq!(unbounded.omitted_bytes(), 0);
+        assert_eq!(unbounded.into_bytes(), b"abcdef".to_vec());
+    }
+
+    #[test]
+    fn samples_chunks_past_the_cap() {
+        let budget = StreamBudget::new(&ExecOutputConfig {
+            stream_max_bytes: 10,
+            stream_sample_every: 2,
+            ..Default::default()
+        });
+        let admitted: Vec<bool> = (0..6).map(|_| budget.admit(4)).collect();
+        assert_eq!(admitted, vec![true, true, true, false, true, false]);
+        assert_eq!(budget.unstreamed_bytes(), 8);
+    }
+}
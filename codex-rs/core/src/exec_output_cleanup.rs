@@ -0,0 +1,250 @@
+//! Cleanup of command output before it is sent to the model.
+//!
+//! Output meant for a terminal spends many tokens on things the model cannot
+//! use: ANSI color and cursor sequences, lines redrawn with `\r`, and progress
+//! bars printed hundreds of times. Depending on the session's
+//! [`ExecOutputConfig`], the text the model sees has escape sequences
+//! removed, keeps only the final state of every `\r`-rewritten line, and,
+//! when `fold_progress` is enabled, folds runs of progress-bar updates into
+//! their last line. Clients and the
+//! `exec_output_archive` still receive the raw output.
+
+use std::borrow::Cow;
+
+use crate::config::types::ExecOutputConfig;
+use crate::exec::ExecToolCallOutput;
+
+const ESC: char = '\u{1b}';
+const BEL: char = '\u{7}';
+
+/// Characters progress bars are drawn with.
+const BAR_CHARS: &[char] = &[
+    '#', '=', '>', '█', '▉', '▊', '▋', '▌', '▍', '▎', '▏', '░', '▒', '▓', '━', '╸', '╺', '■', '□',
+];
+
+/// Shorter runs of progress updates are left alone.
+const MIN_FOLDED_RUN: usize = 3;
+
+/// `output` with its aggregated text cleaned up for the model, or `None` when
+/// cleanup changed nothing.
+pub(crate) fn clean_exec_output(
+    output: &ExecToolCallOutput,
+    config: &ExecOutputConfig,
+) -> Option<ExecToolCallOutput> {
+    match clean_output(&output.aggregated_output.text, config) {
+        Cow::Borrowed(_) => None,
+        Cow::Owned(text) => {
+            let mut cleaned = output.clone();
+            cleaned.aggregated_output.text = text;
+            Some(cleaned)
+        }
+    }
+}
+
+/// Apply every cleanup step enabled in `config` to `text`.
+pub(crate) fn clean_output<'a>(text: &'a str, config: &ExecOutputConfig) -> Cow<'a, str> {
+    let mut text = Cow::Borrowed(text);
+    if config.strip_ansi && text.contains(ESC) {
+        text = Cow::Owned(strip_ansi(&text));
+    }
+    if config.collapse_carriage_returns && text.contains('\r') {
+        text = Cow::Owned(collapse_carriage_returns(&text));
+    }
+    if config.fold_progress
+        && let Some(folded) = fold_progress(&text)
+    {
+        text = Cow::Owned(folded);
+    }
+    text
+}
+
+/// Remove CSI (`ESC [ ... final`), OSC (`ESC ] ... BEL` or `ESC ] ... ESC \`)
+/// and two-character escape sequences.
+fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != ESC {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('\u{40}'..='\u{7e}').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == BEL {
+                        break;
+                    }
+                    if c == ESC && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Keep the last non-empty segment of every line that was rewritten with
+/// `\r`, which is what the terminal ends up showing for progress output.
+fn collapse_carriage_returns(text: &str) -> String {
+    let lines = text.split('\n').map(|line| {
+        line.trim_end_matches('\r')
+            .rsplit('\r')
+            .find(|segment| !segment.is_empty())
+            .unwrap_or_default()
+    });
+    lines.collect::<Vec<_>>().join("\n")
+}
+
+/// Replace runs of progress updates that differ only in their numbers and
+/// bar with a marker followed by the last update. `None` when there is
+/// nothing to fold.
+fn fold_progress(text: &str) -> Option<String> {
+    let lines = text.split('\n').collect::<Vec<_>>();
+    let mut out = Vec::with_capacity(lines.len());
+    let mut folded_any = false;
+    let mut start = 0;
+    while start < lines.len() {
+        let mut end = start + 1;
+        if let Some(shape) = progress_shape(lines[start]) {
+            while end < lines.len() && progress_shape(lines[end]).as_ref() == Some(&shape) {
+                end += 1;
+            }
+        }
+        let run = end - start;
+        if run >= MIN_FOLDED_RUN {
+            folded_any = true;
+            out.push(format!("[... {} progress updates folded ...]", run - 1));
+            out.push(lines[end - 1].to_string());
+        } else {
+            out.extend(lines[start..end].iter().map(ToString::to_string));
+        }
+        start = end;
+    }
+    folded_any.then(|| out.join("\n"))
+}
+
+/// What stays of a progress line once its numbers and bar are removed.
+/// Only lines that draw a bar and report a percentage or an `n/m` count are
+/// progress, so log lines that merely differ in their numbers (timestamps,
+/// ids, dates) are never folded.
+fn progress_shape(line: &str) -> Option<String> {
+    let has_bar = line
+        .chars()
+        .collect::<Vec<_>>()
+        .windows(3)
+        .any(|window| window.iter().all(|c| BAR_CHARS.contains(c)));
+    let has_count = line.as_bytes().windows(3).any(|window| {
+        window[0].is_ascii_digit() && window[1] == b'/' && window[2].is_ascii_digit()
+    });
+    if !(has_bar && (line.contains('%') || has_count)) {
+        return None;
+    }
+    Some(
+        line.chars()
+            .filter(|c| !c.is_ascii_digit() && !BAR_CHARS.contains(c) && !c.is_whitespace())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn all_enabled() -> ExecOutputConfig {
+        ExecOutputConfig {
+            fold_progress: true,
+            ..ExecOutputConfig::default()
+        }
+    }
+
+    #[test]
+    fn strips_color_cursor_and_title_sequences() {
+        let text = "\u{1b}[1;32mok\u{1b}[0m done\u{1b}[2K\u{1b}]0;title\u{7}!";
+        assert_eq!(clean_output(text, &all_enabled()), "ok done!");
+    }
+
+    #[test]
+    fn keeps_the_final_state_of_rewritten_lines() {
+        let text = "Downloading 10%\rDownloading 55%\rDownloading 100%\r\nDone\r\n";
+        assert_eq!(
+            clean_output(text, &all_enabled()),
+            "Downloading 100%\nDone\n"
+        );
+    }
+
+    #[test]
+    fn folds_runs_of_progress_updates() {
+        let text = [
+            "Compiling crate",
+            "[====>     ] 1/4 fetch",
+            "[======>   ] 2/4 fetch",
+            "[========> ] 3/4 fetch",
+            "[==========] 4/4 fetch",
+            "finished",
+        ]
+        .join("\n");
+        assert_eq!(
+            clean_output(&text, &all_enabled()),
+            [
+                "Compiling crate",
+                "[... 3 progress updates folded ...]",
+                "[==========] 4/4 fetch",
+                "finished",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn leaves_similar_lines_that_are_not_progress() {
+        let text = "test a_1 ... ok\ntest a_2 ... ok\ntest a_3 ... ok\nsrc/a.rs 80%\nsrc/b.rs 75%\nsrc/c.rs 90%";
+        assert!(matches!(
+            clean_output(text, &all_enabled()),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn leaves_log_lines_that_differ_only_in_numbers() {
+        let text = [
+            "2024/10/16 12:00:01 GET /items/17 200",
+            "2024/10/16 12:00:02 GET /items/18 200",
+            "2024/10/16 12:00:03 GET /items/19 404",
+            "2024/10/16 12:00:04 GET /items/20 200",
+        ]
+        .join("\n");
+        assert!(matches!(
+            clean_output(&text, &all_enabled()),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn progress_is_not_folded_by_default() {
+        let text = "[===>  ] 1/3\n[====> ] 2/3\n[======] 3/3";
+        assert_eq!(clean_output(text, &ExecOutputConfig::default()), text);
+    }
+
+    #[test]
+    fn does_nothing_when_disabled() {
+        let config = ExecOutputConfig {
+            strip_ansi: false,
+            collapse_carriage_returns: false,
+            fold_progress: false,
+            ..ExecOutputConfig::default()
+        };
+        let text = "\u{1b}[31m1%\r2%\n3%\n4%\n5%";
+        assert_eq!(clean_output(text, &config), text);
+    }
+}
@@ -13,9 +13,10 @@ use codex_protocol::protocol::SandboxPolicy;
 use thiserror::Error;
 use tokio::fs;
 
-use crate::bash::parse_shell_lc_plain_commands;
+use crate::bash::parse_shell_lc_script_commands;
 use crate::features::Feature;
 use crate::features::Features;
+use crate::parse_command::parse_shell_plain_commands;
 use crate::sandboxing::SandboxPermissions;
 use crate::tools::sandboxing::ApprovalRequirement;
 
@@ -89,39 +90,67 @@ fn evaluate_with_policy(
     command: &[String],
     approval_policy: AskForApproval,
 ) -> Option<ApprovalRequirement> {
-    let commands = parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
-    let evaluation = policy.check_multiple(commands.iter());
-
-    match evaluation {
-        Evaluation::Match { decision, .. } => match decision {
-            Decision::Forbidden => Some(ApprovalRequirement::Forbidden {
-                reason: FORBIDDEN_REASON.to_string(),
-            }),
-            Decision::Prompt => {
-                let reason = PROMPT_REASON.to_string();
-                if matches!(approval_policy, AskForApproval::Never) {
-                    Some(ApprovalRequirement::Forbidden { reason })
-                } else {
-                    Some(ApprovalRequirement::NeedsApproval {
-                        reason: Some(reason),
-                    })
-                }
+    // Allowing the script needs every command to be allowed; otherwise the
+    // unmatched commands fall back to the default approval logic.
+    let (strictest, all_matched) = strictest_decision(policy, command);
+    match strictest? {
+        Decision::Forbidden => Some(ApprovalRequirement::Forbidden {
+            reason: FORBIDDEN_REASON.to_string(),
+        }),
+        Decision::Prompt => {
+            let reason = PROMPT_REASON.to_string();
+            if matches!(approval_policy, AskForApproval::Never) {
+                Some(ApprovalRequirement::Forbidden { reason })
+            } else {
+                Some(ApprovalRequirement::NeedsApproval {
+                    reason: Some(reason),
+                })
             }
-            Decision::Allow => Some(ApprovalRequirement::Skip),
-        },
-        Evaluation::NoMatch => None,
+        }
+        Decision::Allow if all_matched => Some(ApprovalRequirement::Skip),
+        Decision::Allow => None,
     }
 }
 
+/// Evaluates every command `command` runs on its own and returns the
+/// strictest decision among the matching rules, along with whether every
+/// command matched a rule.
+pub(crate) fn strictest_decision(policy: &Policy, command: &[String]) -> (Option<Decision>, bool) {
+    let commands = parse_shell_lc_script_commands(command)
+        .or_else(|| parse_shell_plain_commands(command))
+        .unwrap_or_else(|| vec![command.to_vec()]);
+
+    let mut all_matched = true;
+    let mut strictest = None;
+    for command in &commands {
+        match policy.check(command) {
+            Evaluation::Match { decision, .. } => {
+                strictest = strictest.max(Some(decision));
+            }
+            Evaluation::NoMatch => all_matched = false,
+        }
+    }
+    (strictest, all_matched)
+}
+
+/// `approved_by_rule` is true when a remembered approval rule matched the
+/// command. It never overrides a command execpolicy forbids, and only skips
+/// approval for commands that run in the sandbox.
 pub(crate) fn create_approval_requirement_for_command(
     policy: &Policy,
     command: &[String],
     approval_policy: AskForApproval,
     sandbox_policy: &SandboxPolicy,
     sandbox_permissions: SandboxPermissions,
+    approved_by_rule: bool,
 ) -> ApprovalRequirement {
-    if let Some(requirement) = evaluate_with_policy(policy, command, approval_policy) {
-        return requirement;
+    match evaluate_with_policy(policy, command, approval_policy) {
+        Some(forbidden @ ApprovalRequirement::Forbidden { .. }) => return forbidden,
+        _ if approved_by_rule && sandbox_permissions == SandboxPermissions::UseDefault => {
+            return ApprovalRequirement::Skip;
+        }
+        Some(requirement) => return requirement,
+        None => {}
     }
 
     if requires_initial_appoval(
@@ -292,6 +321,49 @@ prefix_rule(pattern=["rm"], decision="forbidden")
         );
     }
 
+    #[test]
+    fn evaluates_heredoc_write_targets_and_takes_strictest_decision() {
+        let policy_src = r#"
+prefix_rule(pattern=["cat"], decision="allow")
+prefix_rule(pattern=["cat", ">"], decision="prompt")
+prefix_rule(pattern=["git", "status"], decision="allow")
+"#;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("test.codexpolicy", policy_src)
+            .expect("parse policy");
+        let policy = parser.build();
+        let bash = |script: &str| vec!["bash".to_string(), "-lc".to_string(), script.to_string()];
+
+        assert_eq!(
+            evaluate_with_policy(
+                &policy,
+                &bash("cat <<EOF\nhi\nEOF\ngit status"),
+                AskForApproval::OnRequest
+            ),
+            Some(ApprovalRequirement::Skip)
+        );
+        assert_eq!(
+            evaluate_with_policy(
+                &policy,
+                &bash("git status && cat <<EOF > notes.txt\nhi\nEOF"),
+                AskForApproval::OnRequest
+            ),
+            Some(ApprovalRequirement::NeedsApproval {
+                reason: Some(PROMPT_REASON.to_string())
+            })
+        );
+        // An allowed command does not vouch for an unmatched one.
+        assert_eq!(
+            evaluate_with_policy(
+                &policy,
+                &bash("git status && python setup.py"),
+                AskForApproval::OnRequest
+            ),
+            None
+        );
+    }
+
     #[test]
     fn approval_requirement_prefers_execpolicy_match() {
         let policy_src = r#"prefix_rule(pattern=["rm"], decision="prompt")"#;
@@ -308,6 +380,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             AskForApproval::OnRequest,
             &SandboxPolicy::DangerFullAccess,
             SandboxPermissions::UseDefault,
+            false,
         );
 
         assert_eq!(
@@ -334,6 +407,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             AskForApproval::Never,
             &SandboxPolicy::DangerFullAccess,
             SandboxPermissions::UseDefault,
+            false,
         );
 
         assert_eq!(
@@ -344,6 +418,79 @@ prefix_rule(pattern=["rm"], decision="forbidden")
         );
     }
 
+    #[test]
+    fn approval_rule_skips_prompt_for_sandboxed_commands() {
+        let policy_src = r#"prefix_rule(pattern=["rm"], decision="prompt")"#;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("test.codexpolicy", policy_src)
+            .expect("parse policy");
+        let policy = parser.build();
+        let command = vec!["rm".to_string()];
+
+        let requirement = create_approval_requirement_for_command(
+            &policy,
+            &command,
+            AskForApproval::OnRequest,
+            &SandboxPolicy::ReadOnly,
+            SandboxPermissions::UseDefault,
+            true,
+        );
+
+        assert_eq!(requirement, ApprovalRequirement::Skip);
+    }
+
+    #[test]
+    fn approval_rule_never_overrides_forbidden_command() {
+        let policy_src = r#"prefix_rule(pattern=["rm"], decision="forbidden")"#;
+        let mut parser = PolicyParser::new();
+        parser
+            .parse("test.codexpolicy", policy_src)
+            .expect("parse policy");
+        let policy = parser.build();
+        let command = vec!["rm".to_string()];
+
+        for sandbox_permissions in [
+            SandboxPermissions::UseDefault,
+            SandboxPermissions::RequireEscalated,
+        ] {
+            let requirement = create_approval_requirement_for_command(
+                &policy,
+                &command,
+                AskForApproval::OnRequest,
+                &SandboxPolicy::ReadOnly,
+                sandbox_permissions,
+                true,
+            );
+
+            assert_eq!(
+                requirement,
+                ApprovalRequirement::Forbidden {
+                    reason: FORBIDDEN_REASON.to_string()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn approval_rule_never_skips_approval_for_escalated_command() {
+        let command = vec!["rm".to_string(), "-rf".to_string(), "build".to_string()];
+
+        let requirement = create_approval_requirement_for_command(
+            &Policy::empty(),
+            &command,
+            AskForApproval::OnRequest,
+            &SandboxPolicy::ReadOnly,
+            SandboxPermissions::RequireEscalated,
+            true,
+        );
+
+        assert_eq!(
+            requirement,
+            ApprovalRequirement::NeedsApproval { reason: None }
+        );
+    }
+
     #[test]
     fn approval_requirement_falls_back_to_heuristics() {
         let command = vec!["python".to_string()];
@@ -355,6 +502,7 @@ prefix_rule(pattern=["rm"], decision="forbidden")
             AskForApproval::UnlessTrusted,
             &SandboxPolicy::ReadOnly,
             SandboxPermissions::UseDefault,
+            false,
         );
 
         assert_eq!(
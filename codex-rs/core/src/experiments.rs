@@ -0,0 +1,288 @@
+//! A/B experiments over prompt fragments, approval policies and models.
+//!
+//! Experiments are declared in `config.toml` under
+//! `[experiments.<name>.variants.<label>]`. A conversation joins an experiment
+//! when `experiment = "<name>"` is set (usually via `-c` or the per-thread
+//! config overrides), and is assigned `experiment_variant` when given or a
+//! uniformly random variant otherwise. Every completed or aborted turn of an
+//! assigned conversation appends an outcome record to
+//! `$CODEX_HOME/experiments/<name>.jsonl`, which [`experiment_stats`]
+//! aggregates per variant.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ConversationId;
+use rand::Rng;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+use crate::config::types::ExperimentToml;
+
+/// Directory under `CODEX_HOME` holding per-experiment outcome logs.
+pub const EXPERIMENTS_SUBDIR: &str = "experiments";
+
+/// The experiment variant a conversation was assigned to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExperimentAssignment {
+    pub experiment: String,
+    pub variant: String,
+}
+
+/// Outcome metrics aggregated for one variant of an experiment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExperimentVariantStats {
+    pub variant: String,
+    /// Number of distinct conversations with at least one recorded turn.
+    pub conversations: i64,
+    pub turns: i64,
+    /// Turns that ended with a final agent message rather than an abort.
+    pub successful_turns: i64,
+    pub success_rate: f64,
+    /// Tokens consumed across all conversations in this variant.
+    pub total_tokens: i64,
+    pub average_turns_per_conversation: f64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TurnOutcome {
+    conversation_id: ConversationId,
+    variant: String,
+    success: bool,
+    /// Cumulative token usage of the conversation after this turn.
+    total_tokens: i64,
+}
+
+/// Picks the variant for `experiment`, honoring `requested_variant` when set.
+pub(crate) fn assign_variant(
+    experiment: &str,
+    definition: &ExperimentToml,
+    requested_variant: Option<&str>,
+) -> io::Result<String> {
+    if let Some(variant) = requested_variant {
+        if !definition.variants.contains_key(variant) {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("experiment `{experiment}` has no variant `{variant}`"),
+            ));
+        }
+        return Ok(variant.to_string());
+    }
+    let labels: Vec<&String> = definition.variants.keys().collect();
+    if labels.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("experiment `{experiment}` declares no variants"),
+        ));
+    }
+    let index = rand::rng().random_range(0..labels.len());
+    Ok(labels[index].clone())
+}
+
+/// Path of the outcome log for `experiment`.
+pub fn experiment_log_path(codex_home: &Path, experiment: &str) -> io::Result<PathBuf> {
+    if experiment.is_empty()
+        || experiment == "."
+        || experiment == ".."
+        || experiment.contains(['/', '\\'])
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid experiment name `{experiment}`"),
+        ));
+    }
+    Ok(codex_home
+        .join(EXPERIMENTS_SUBDIR)
+        .join(format!("{experiment}.jsonl")))
+}
+
+/// Appends the outcome of one turn to the experiment's log.
+pub(crate) async fn record_turn_outcome(
+    codex_home: &Path,
+    assignment: &ExperimentAssignment,
+    conversation_id: ConversationId,
+    success: bool,
+    total_tokens: i64,
+) -> io::Result<()> {
+    let path = experiment_log_path(codex_home, &assignment.experiment)?;
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let outcome = TurnOutcome {
+        conversation_id,
+        variant: assignment.variant.clone(),
+        success,
+        total_tokens,
+    };
+    let mut line = serde_json::to_string(&outcome).map_err(io::Error::other)?;
+    line.push('\n');
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .await?;
+    file.write_all(line.as_bytes()).await
+}
+
+/// Aggregates recorded outcomes per variant, sorted by variant label. An
+/// experiment without recorded turns yields an empty list.
+pub async fn experiment_stats(
+    codex_home: &Path,
+    experiment: &str,
+) -> io::Result<Vec<ExperimentVariantStats>> {
+    let path = experiment_log_path(codex_home, experiment)?;
+    let contents = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    #[derive(Default)]
+    struct Accumulator {
+        turns: i64,
+        successful_turns: i64,
+        tokens_by_conversation: HashMap<ConversationId, i64>,
+    }
+
+    let mut by_variant: BTreeMap<String, Accumulator> = BTreeMap::new();
+    for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+        let outcome: TurnOutcome = match serde_json::from_str(line) {
+            Ok(outcome) => outcome,
+            Err(err) => {
+                tracing::warn!("skipping malformed experiment outcome in {path:?}: {err}");
+                continue;
+            }
+        };
+        let acc = by_variant.entry(outcome.variant).or_default();
+        acc.turns += 1;
+        if outcome.success {
+            acc.successful_turns += 1;
+        }
+        let tokens = acc
+            .tokens_by_conversation
+            .entry(outcome.conversation_id)
+            .or_default();
+        *tokens = (*tokens).max(outcome.total_tokens);
+    }
+
+    Ok(by_variant
+        .into_iter()
+        .map(|(variant, acc)| {
+            let conversations = acc.tokens_by_conversation.len() as i64;
+            ExperimentVariantStats {
+                variant,
+                conversations,
+                turns: acc.turns,
+                successful_turns: acc.successful_turns,
+                success_rate: acc.successful_turns as f64 / acc.turns as f64,
+                total_tokens: acc.tokens_by_conversation.values().sum(),
+                average_turns_per_conversation: acc.turns as f64 / conversations as f64,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::ExperimentVariantToml;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn assignment(variant: &str) -> ExperimentAssignment {
+        ExperimentAssignment {
+            experiment: "prompt-tuning".to_string(),
+            variant: variant.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn stats_aggregate_outcomes_per_variant() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let first = ConversationId::new();
+        let second = ConversationId::new();
+        let third = ConversationId::new();
+
+        for (variant, conversation_id, success, total_tokens) in [
+            ("control", first, true, 100),
+            ("control", first, false, 250),
+            ("control", second, true, 50),
+            ("terse", third, true, 80),
+        ] {
+            record_turn_outcome(
+                codex_home.path(),
+                &assignment(variant),
+                conversation_id,
+                success,
+                total_tokens,
+            )
+            .await
+            .expect("record outcome");
+        }
+
+        let stats = experiment_stats(codex_home.path(), "prompt-tuning")
+            .await
+            .expect("stats");
+        assert_eq!(
+            stats,
+            vec![
+                ExperimentVariantStats {
+                    variant: "control".to_string(),
+                    conversations: 2,
+                    turns: 3,
+                    successful_turns: 2,
+                    success_rate: 2.0 / 3.0,
+                    total_tokens: 300,
+                    average_turns_per_conversation: 1.5,
+                },
+                ExperimentVariantStats {
+                    variant: "terse".to_string(),
+                    conversations: 1,
+                    turns: 1,
+                    successful_turns: 1,
+                    success_rate: 1.0,
+                    total_tokens: 80,
+                    average_turns_per_conversation: 1.0,
+                },
+            ]
+        );
+        assert_eq!(
+            experiment_stats(codex_home.path(), "unknown")
+                .await
+                .expect("stats for unknown experiment"),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn assign_variant_validates_requested_label() {
+        let definition = ExperimentToml {
+            variants: BTreeMap::from([("control".to_string(), ExperimentVariantToml::default())]),
+        };
+
+        assert_eq!(
+            assign_variant("exp", &definition, Some("control")).expect("known variant"),
+            "control"
+        );
+        assert_eq!(
+            assign_variant("exp", &definition, None).expect("random variant"),
+            "control"
+        );
+        assert_eq!(
+            assign_variant("exp", &definition, Some("missing"))
+                .expect_err("unknown variant")
+                .kind(),
+            io::ErrorKind::NotFound
+        );
+        assert_eq!(
+            experiment_log_path(Path::new("/tmp"), "../escape")
+                .expect_err("path traversal")
+                .kind(),
+            io::ErrorKind::InvalidInput
+        );
+    }
+}
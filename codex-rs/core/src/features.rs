@@ -31,6 +31,53 @@ pub enum Feature {
     GhostCommit,
     /// Use the single unified PTY-backed exec tool.
     UnifiedExec,
+    /// Run shell tool commands attached to a pseudo-terminal.
+    ShellPty,
+    /// Archive the complete stdout/stderr of exec tool calls under
+    /// `~/.codex/exec-logs`.
+    ExecOutputArchive,
+    /// Let the model propose saving user corrections as workspace
+    /// instructions and include saved ones in the prompt.
+    WorkspaceInstructions,
+    /// Route sandboxed commands through a local proxy that only reaches the
+    /// project's `network_allowed_hosts`.
+    NetworkProxy,
+    /// Ping running MCP servers and restart stdio servers that stop answering.
+    McpHealthMonitor,
+    /// When an apply_patch no longer matches the file, three-way merge it
+    /// against the turn's ghost snapshot instead of failing.
+    ApplyPatchMerge,
+    /// Include the tool that reads the output of background tasks.
+    BackgroundTaskTool,
+    /// Include the spawn_agent tool, which delegates work to child
+    /// conversations.
+    SpawnAgentTool,
+    /// Include the create_pull_request tool, which pushes the current branch
+    /// and opens a GitHub pull request or GitLab merge request.
+    PullRequestTool,
+    /// Give the model an outline of the repository (directory tree and
+    /// public symbols) at the start of a conversation, plus the repo_map
+    /// tool to refresh it.
+    RepoMap,
+    /// Include the code_search tool, which finds code by meaning using an
+    /// embeddings index of the repository.
+    CodeSearch,
+    /// Include the ask_user tool, which pauses the turn until the user
+    /// answers a clarifying question.
+    AskUserTool,
+    /// Load WebAssembly plugins from `~/.codex/plugins` and offer the
+    /// functions they declare as tools.
+    WasmPlugins,
+    /// Replace secrets (cloud keys, private keys, JWTs, credential-looking
+    /// `.env` values) in tool output with placeholders before the model or
+    /// the rollout sees it.
+    SecretRedaction,
+    /// Ask for approval only for the risky segments of a compound shell
+    /// command and run its segments one at a time.
+    SegmentedApproval,
+    /// Name each thread with a short model-generated title after its first
+    /// exchange.
+    ThreadTitles,
     /// Use the shell command tool that takes `command` as a single string of
     /// shell instead of an array of args passed to `execvp(3)`.
     ShellCommandTool,
@@ -275,6 +322,102 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::Experimental,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::ShellPty,
+        key: "shell_pty",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ExecOutputArchive,
+        key: "exec_output_archive",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::WorkspaceInstructions,
+        key: "workspace_instructions",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::NetworkProxy,
+        key: "network_proxy",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::McpHealthMonitor,
+        key: "mcp_health_monitor",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ApplyPatchMerge,
+        key: "apply_patch_merge",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::BackgroundTaskTool,
+        key: "background_task_tool",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::SpawnAgentTool,
+        key: "spawn_agent_tool",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::PullRequestTool,
+        key: "pull_request_tool",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::RepoMap,
+        key: "repo_map",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::CodeSearch,
+        key: "code_search",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::AskUserTool,
+        key: "ask_user_tool",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::WasmPlugins,
+        key: "wasm_plugins",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::SecretRedaction,
+        key: "secret_redaction",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::SegmentedApproval,
+        key: "segmented_approval",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ThreadTitles,
+        key: "thread_titles",
+        stage: Stage::Experimental,
+        default_enabled: false,
+    },
     FeatureSpec {
         id: Feature::ShellCommandTool,
         key: "shell_command_tool",
@@ -0,0 +1,312 @@
+//! Advisory per-file locks that serialize writes from concurrent tool calls.
+//!
+//! Parallel tool calls in one turn, and tool calls from other conversations
+//! in the same process, can patch the same file at once. Writers take a lock
+//! on every file they touch before running; a writer that finds any of its
+//! files held queues until they are all free. Locks are keyed by canonical
+//! path so `./src/lib.rs` and `/repo/src/lib.rs` contend.
+//!
+//! Each writer takes its whole file set in one step, so writers never hold
+//! one lock while waiting for another. An owner that already holds locks can
+//! still ask for more; before it waits, the wait-for graph is checked and the
+//! request fails with [`FileLockError::Deadlock`] if waiting would never end.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use tokio::sync::Notify;
+
+static SHARED: LazyLock<Arc<FileLockManager>> = LazyLock::new(Arc::default);
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub(crate) enum FileLockError {
+    #[error(
+        "waiting for {} would deadlock: it is held by a tool call that is waiting on a file this call holds",
+        display_paths(paths)
+    )]
+    Deadlock { paths: Vec<PathBuf> },
+}
+
+#[derive(Debug, Default)]
+struct LockTable {
+    /// Lock owner for each held path.
+    holders: HashMap<PathBuf, String>,
+    /// Paths each blocked owner is waiting for.
+    waiting: HashMap<String, Vec<PathBuf>>,
+}
+
+impl LockTable {
+    /// Paths in `paths` held by someone other than `owner`.
+    fn blocked(&self, owner: &str, paths: &[PathBuf]) -> Vec<PathBuf> {
+        paths
+            .iter()
+            .filter(|path| {
+                self.holders
+                    .get(*path)
+                    .is_some_and(|holder| holder != owner)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Whether `owner` waiting for `paths` would close a cycle in the
+    /// wait-for graph.
+    fn would_deadlock(&self, owner: &str, paths: &[PathBuf]) -> bool {
+        let mut pending: Vec<&str> = paths
+            .iter()
+            .filter_map(|path| self.holders.get(path).map(String::as_str))
+            .collect();
+        let mut visited = HashSet::new();
+        while let Some(holder) = pending.pop() {
+            if holder == owner {
+                return true;
+            }
+            if !visited.insert(holder) {
+                continue;
+            }
+            if let Some(wanted) = self.waiting.get(holder) {
+                pending.extend(
+                    wanted
+                        .iter()
+                        .filter_map(|path| self.holders.get(path).map(String::as_str)),
+                );
+            }
+        }
+        false
+    }
+}
+
+/// Process-wide table of advisory file locks.
+#[derive(Debug, Default)]
+pub(crate) struct FileLockManager {
+    table: Mutex<LockTable>,
+    released: Notify,
+}
+
+impl FileLockManager {
+    /// The manager shared by every conversation in this process.
+    pub(crate) fn shared() -> Arc<Self> {
+        Arc::clone(&SHARED)
+    }
+
+    /// Take every lock in `paths` for `owner` if none is held by another
+    /// owner. Otherwise returns the paths that are held elsewhere.
+    pub(crate) fn try_acquire(
+        self: &Arc<Self>,
+        owner: &str,
+        paths: &[PathBuf],
+    ) -> Result<FileLockGuard, Vec<PathBuf>> {
+        let paths = canonical_lock_paths(paths);
+        let mut table = self.lock_table();
+        let blocked = table.blocked(owner, &paths);
+        if !blocked.is_empty() {
+            return Err(blocked);
+        }
+        Ok(self.take(&mut table, owner, paths))
+    }
+
+    /// Wait until every lock in `paths` can be taken for `owner`.
+    pub(crate) async fn acquire(
+        self: &Arc<Self>,
+        owner: &str,
+        paths: &[PathBuf],
+    ) -> Result<FileLockGuard, FileLockError> {
+        let paths = canonical_lock_paths(paths);
+        let _registration = WaitRegistration {
+            manager: self,
+            owner,
+        };
+        loop {
+            let released = self.released.notified();
+            tokio::pin!(released);
+            // Register interest before checking so a release between the
+            // check and the await is not missed.
+            released.as_mut().enable();
+            {
+                let mut table = self.lock_table();
+                let blocked = table.blocked(owner, &paths);
+                if blocked.is_empty() {
+                    table.waiting.remove(owner);
+                    return Ok(self.take(&mut table, owner, paths));
+                }
+                if table.would_deadlock(owner, &blocked) {
+                    table.waiting.remove(owner);
+                    return Err(FileLockError::Deadlock { paths: blocked });
+                }
+                table.waiting.insert(owner.to_string(), blocked);
+            }
+            released.await;
+        }
+    }
+
+    fn take(
+        self: &Arc<Self>,
+        table: &mut LockTable,
+        owner: &str,
+        paths: Vec<PathBuf>,
+    ) -> FileLockGuard {
+        // Only release on drop what this call newly took so nested
+        // acquisitions by the same owner keep the outer locks.
+        let paths = paths
+            .into_iter()
+            .filter(|path| {
+                table
+                    .holders
+                    .insert(path.clone(), owner.to_string())
+                    .is_none()
+            })
+            .collect();
+        FileLockGuard {
+            manager: Arc::clone(self),
+            paths,
+        }
+    }
+
+    fn lock_table(&self) -> std::sync::MutexGuard<'_, LockTable> {
+        self.table
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Releases its locks when dropped.
+#[derive(Debug)]
+pub(crate) struct FileLockGuard {
+    manager: Arc<FileLockManager>,
+    paths: Vec<PathBuf>,
+}
+
+impl Drop for FileLockGuard {
+    fn drop(&mut self) {
+        if self.paths.is_empty() {
+            return;
+        }
+        {
+            let mut table = self.manager.lock_table();
+            for path in &self.paths {
+                table.holders.remove(path);
+            }
+        }
+        self.manager.released.notify_waiters();
+    }
+}
+
+/// Clears an owner's wait-for entry if its `acquire` future is dropped while
+/// waiting, e.g. because the turn was interrupted.
+struct WaitRegistration<'a> {
+    manager: &'a FileLockManager,
+    owner: &'a str,
+}
+
+impl Drop for WaitRegistration<'_> {
+    fn drop(&mut self) {
+        self.manager.lock_table().waiting.remove(self.owner);
+    }
+}
+
+/// Canonical, de-duplicated lock keys for `paths`. Files that do not exist
+/// yet are keyed by their canonical parent directory.
+fn canonical_lock_paths(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut canonical: Vec<PathBuf> = paths.iter().map(|path| canonical_lock_path(path)).collect();
+    canonical.sort();
+    canonical.dedup();
+    canonical
+}
+
+fn canonical_lock_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = path.canonicalize() {
+        return canonical;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => parent
+            .canonicalize()
+            .map(|parent| parent.join(name))
+            .unwrap_or_else(|_| path.to_path_buf()),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|path| path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn writers_to_the_same_file_queue() {
+        let tmp = tempdir().expect("tmp");
+        let path = tmp.path().join("a.txt");
+        let manager = Arc::new(FileLockManager::default());
+
+        let first = manager
+            .try_acquire("call-1", std::slice::from_ref(&path))
+            .expect("first lock");
+        // A relative spelling of the same file contends for the same lock.
+        let alias = tmp.path().join(".").join("a.txt");
+        let blocked = manager
+            .try_acquire("call-2", std::slice::from_ref(&alias))
+            .expect_err("second call should be blocked");
+        assert_eq!(blocked, vec![canonical_lock_path(&path)]);
+
+        let waiter = tokio::spawn({
+            let manager = Arc::clone(&manager);
+            async move { manager.acquire("call-2", &[alias]).await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        drop(first);
+        let second = tokio::time::timeout(Duration::from_secs(5), waiter)
+            .await
+            .expect("waiter should wake")
+            .expect("join waiter");
+        assert!(second.is_ok());
+    }
+
+    #[tokio::test]
+    async fn waiting_in_a_cycle_is_reported_as_deadlock() {
+        let tmp = tempdir().expect("tmp");
+        let a = tmp.path().join("a.txt");
+        let b = tmp.path().join("b.txt");
+        let manager = Arc::new(FileLockManager::default());
+
+        let _held_a = manager
+            .try_acquire("call-1", std::slice::from_ref(&a))
+            .expect("lock a");
+        let _held_b = manager
+            .try_acquire("call-2", std::slice::from_ref(&b))
+            .expect("lock b");
+
+        let waiter = tokio::spawn({
+            let manager = Arc::clone(&manager);
+            let b = b.clone();
+            async move { manager.acquire("call-1", &[b]).await.map(drop) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(
+            manager
+                .acquire("call-2", std::slice::from_ref(&a))
+                .await
+                .map(drop),
+            Err(FileLockError::Deadlock {
+                paths: vec![canonical_lock_path(&a)],
+            })
+        );
+        waiter.abort();
+    }
+}
@@ -3,4 +3,9 @@ use env_flags::env_flags;
 env_flags! {
     /// Fixture path for offline tests (see client.rs).
     pub CODEX_RS_SSE_FIXTURE: Option<&str> = None;
+    /// Directory to record model requests and streamed responses into (see
+    /// model_recording.rs).
+    pub CODEX_RECORD_DIR: Option<&str> = None;
+    /// Directory of recorded responses to replay instead of calling the model.
+    pub CODEX_REPLAY_DIR: Option<&str> = None;
 }
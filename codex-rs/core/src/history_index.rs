@@ -0,0 +1,529 @@
+//! Full-text search over recorded conversations.
+//!
+//! Messages, reasoning summaries, commands, and patches from the rollouts
+//! under `~/.codex/sessions` are indexed into an SQLite FTS5 table at
+//! `~/.codex/history_index.sqlite`. Indexing is incremental: each rollout's
+//! indexed byte offset is stored, so later passes only read lines appended
+//! since. A rollout whose first line changed (for example because it was
+//! rewritten by a schema migration) is indexed again from the start.
+//!
+//! Sessions update the index for their own rollout when a turn completes,
+//! and [`HistoryIndex::sync`] catches up on everything else before a search.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use rusqlite::Connection;
+use rusqlite::OptionalExtension;
+use rusqlite::params;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use tracing::warn;
+
+use crate::rollout::SESSIONS_SUBDIR;
+use crate::transcript_export::TranscriptEntry;
+use crate::transcript_export::entry_for_response_item;
+
+pub const HISTORY_INDEX_FILENAME: &str = "history_index.sqlite";
+
+/// Matches returned per thread.
+const MAX_MATCHES_PER_THREAD: usize = 5;
+/// Tokens of context on each side of a match in snippets.
+const SNIPPET_TOKENS: i64 = 16;
+/// Matched terms in snippets are wrapped in these markers.
+pub const SNIPPET_MATCH_START: &str = "**";
+pub const SNIPPET_MATCH_END: &str = "**";
+
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS rollouts (
+    path TEXT PRIMARY KEY,
+    thread_id TEXT,
+    cwd TEXT,
+    started_at TEXT,
+    head_digest TEXT NOT NULL,
+    indexed_bytes INTEGER NOT NULL
+);
+CREATE VIRTUAL TABLE IF NOT EXISTS items USING fts5(
+    text,
+    path UNINDEXED,
+    kind UNINDEXED,
+    timestamp UNINDEXED,
+    tokenize = 'porter unicode61'
+);
+"#;
+
+#[derive(Debug, thiserror::Error)]
+pub enum HistoryIndexError {
+    #[error("history index database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("failed to read rollout: {0}")]
+    Io(#[from] io::Error),
+}
+
+type Result<T> = std::result::Result<T, HistoryIndexError>;
+
+/// Kind of conversation item a search match came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexedItemKind {
+    UserMessage,
+    AgentMessage,
+    Reasoning,
+    Command,
+    Patch,
+    ToolCall,
+    WebSearch,
+}
+
+impl IndexedItemKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::UserMessage => "user_message",
+            Self::AgentMessage => "agent_message",
+            Self::Reasoning => "reasoning",
+            Self::Command => "command",
+            Self::Patch => "patch",
+            Self::ToolCall => "tool_call",
+            Self::WebSearch => "web_search",
+        }
+    }
+
+    fn parse(raw: &str) -> Option<Self> {
+        [
+            Self::UserMessage,
+            Self::AgentMessage,
+            Self::Reasoning,
+            Self::Command,
+            Self::Patch,
+            Self::ToolCall,
+            Self::WebSearch,
+        ]
+        .into_iter()
+        .find(|kind| kind.as_str() == raw)
+    }
+}
+
+/// A thread with at least one item matching a search, best match first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadSearchHit {
+    pub thread_id: String,
+    pub path: PathBuf,
+    pub cwd: Option<PathBuf>,
+    pub started_at: Option<String>,
+    pub matches: Vec<ItemSearchHit>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemSearchHit {
+    pub kind: IndexedItemKind,
+    pub timestamp: String,
+    /// Excerpt around the match with matched terms wrapped in
+    /// [`SNIPPET_MATCH_START`] / [`SNIPPET_MATCH_END`].
+    pub snippet: String,
+}
+
+pub struct HistoryIndex {
+    conn: Connection,
+    codex_home: PathBuf,
+}
+
+impl HistoryIndex {
+    /// Opens (creating if needed) the index under `codex_home`.
+    pub fn open(codex_home: &Path) -> Result<Self> {
+        std::fs::create_dir_all(codex_home)?;
+        let conn = Connection::open(codex_home.join(HISTORY_INDEX_FILENAME))?;
+        // Several Codex processes may update the index at once.
+        conn.busy_timeout(Duration::from_secs(5))?;
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn,
+            codex_home: codex_home.to_path_buf(),
+        })
+    }
+
+    /// Brings the index up to date with every rollout under `sessions/` and
+    /// forgets rollouts that no longer exist there.
+    pub fn sync(&mut self) -> Result<()> {
+        let mut rollouts = Vec::new();
+        collect_rollout_files(&self.codex_home.join(SESSIONS_SUBDIR), &mut rollouts)?;
+        for path in &rollouts {
+            if let Err(err) = self.index_rollout(path) {
+                warn!("failed to index rollout {}: {err}", path.display());
+            }
+        }
+
+        let present: HashSet<String> = rollouts.iter().map(|path| path_key(path)).collect();
+        let indexed: Vec<String> = self
+            .conn
+            .prepare("SELECT path FROM rollouts")?
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<_>>()?;
+        for path in indexed.iter().filter(|path| !present.contains(*path)) {
+            self.forget(path)?;
+        }
+        Ok(())
+    }
+
+    /// Indexes lines appended to the rollout at `path` since it was last
+    /// indexed.
+    pub fn index_rollout(&mut self, path: &Path) -> Result<()> {
+        let key = path_key(path);
+        let mut file = File::open(path)?;
+        let head_digest = head_digest(&mut file)?;
+        let stored: Option<(String, i64)> = self
+            .conn
+            .query_row(
+                "SELECT head_digest, indexed_bytes FROM rollouts WHERE path = ?1",
+                params![key],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let len = file.metadata()?.len();
+        let offset = match &stored {
+            Some((digest, indexed)) if *digest == head_digest && *indexed as u64 <= len => {
+                *indexed as u64
+            }
+            Some(_) => {
+                self.forget(&key)?;
+                0
+            }
+            None => 0,
+        };
+        if stored.is_some() && offset == len {
+            return Ok(());
+        }
+
+        file.seek(SeekFrom::Start(offset))?;
+        let mut appended = Vec::new();
+        file.read_to_end(&mut appended)?;
+        // Leave a partially written last line for the next pass.
+        let complete = appended
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |idx| idx + 1);
+
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO rollouts (path, head_digest, indexed_bytes) VALUES (?1, ?2, 0)
+             ON CONFLICT(path) DO NOTHING",
+            params![key, head_digest],
+        )?;
+        for line in String::from_utf8_lossy(&appended[..complete]).lines() {
+            let Ok(line) = serde_json::from_str::<RolloutLine>(line) else {
+                continue;
+            };
+            match line.item {
+                RolloutItem::SessionMeta(meta_line) => {
+                    tx.execute(
+                        "UPDATE rollouts SET thread_id = ?2, cwd = ?3, started_at = ?4
+                         WHERE path = ?1 AND thread_id IS NULL",
+                        params![
+                            key,
+                            meta_line.meta.id.to_string(),
+                            meta_line.meta.cwd.to_string_lossy(),
+                            meta_line.meta.timestamp,
+                        ],
+                    )?;
+                }
+                RolloutItem::ResponseItem(item) => {
+                    let Some((kind, text)) =
+                        entry_for_response_item(&item).and_then(searchable_text)
+                    else {
+                        continue;
+                    };
+                    tx.execute(
+                        "INSERT INTO items (text, path, kind, timestamp) VALUES (?1, ?2, ?3, ?4)",
+                        params![text, key, kind.as_str(), line.timestamp],
+                    )?;
+                }
+                _ => {}
+            }
+        }
+        tx.execute(
+            "UPDATE rollouts SET indexed_bytes = ?2 WHERE path = ?1",
+            params![key, (offset + complete as u64) as i64],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Threads whose items contain every term in `query`, best match first.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<ThreadSearchHit>> {
+        let Some(fts_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = self.conn.prepare(
+            "SELECT rollouts.thread_id, rollouts.path, rollouts.cwd, rollouts.started_at,
+                    items.kind, items.timestamp,
+                    snippet(items, 0, ?2, ?3, '…', ?4)
+             FROM items JOIN rollouts ON rollouts.path = items.path
+             WHERE items MATCH ?1 AND rollouts.thread_id IS NOT NULL
+             ORDER BY bm25(items)",
+        )?;
+        let mut rows = stmt.query(params![
+            fts_query,
+            SNIPPET_MATCH_START,
+            SNIPPET_MATCH_END,
+            SNIPPET_TOKENS
+        ])?;
+
+        let mut hits: Vec<ThreadSearchHit> = Vec::new();
+        while let Some(row) = rows.next()? {
+            let thread_id: String = row.get(0)?;
+            let kind: String = row.get(4)?;
+            let Some(kind) = IndexedItemKind::parse(&kind) else {
+                continue;
+            };
+            let item = ItemSearchHit {
+                kind,
+                timestamp: row.get(5)?,
+                snippet: row.get(6)?,
+            };
+            match hits.iter().position(|hit| hit.thread_id == thread_id) {
+                Some(index) => {
+                    let hit = &mut hits[index];
+                    if hit.matches.len() < MAX_MATCHES_PER_THREAD {
+                        hit.matches.push(item);
+                    }
+                }
+                None if hits.len() < limit => hits.push(ThreadSearchHit {
+                    thread_id,
+                    path: PathBuf::from(row.get::<_, String>(1)?),
+                    cwd: row.get::<_, Option<String>>(2)?.map(PathBuf::from),
+                    started_at: row.get(3)?,
+                    matches: vec![item],
+                }),
+                None => {}
+            }
+        }
+        Ok(hits)
+    }
+
+    fn forget(&self, key: &str) -> Result<()> {
+        self.conn
+            .execute("DELETE FROM items WHERE path = ?1", params![key])?;
+        self.conn
+            .execute("DELETE FROM rollouts WHERE path = ?1", params![key])?;
+        Ok(())
+    }
+}
+
+/// Indexes `rollout_path` on a blocking thread, logging failures. Used when
+/// a turn completes so the conversation is searchable right away.
+pub(crate) fn spawn_index_rollout(codex_home: PathBuf, rollout_path: PathBuf) {
+    tokio::task::spawn_blocking(move || {
+        let result = HistoryIndex::open(&codex_home)
+            .and_then(|mut index| index.index_rollout(&rollout_path));
+        if let Err(err) = result {
+            warn!(
+                "failed to update history index for {}: {err}",
+                rollout_path.display()
+            );
+        }
+    });
+}
+
+fn searchable_text(entry: TranscriptEntry) -> Option<(IndexedItemKind, String)> {
+    let (kind, text) = match entry {
+        TranscriptEntry::UserMessage { text } => (IndexedItemKind::UserMessage, text),
+        TranscriptEntry::AgentMessage { text } => (IndexedItemKind::AgentMessage, text),
+        TranscriptEntry::Reasoning { summary } => (IndexedItemKind::Reasoning, summary),
+        TranscriptEntry::Command { command, .. } => (IndexedItemKind::Command, command),
+        TranscriptEntry::Patch { patch, .. } => (IndexedItemKind::Patch, patch),
+        TranscriptEntry::ToolCall {
+            name, arguments, ..
+        } => (IndexedItemKind::ToolCall, format!("{name} {arguments}")),
+        TranscriptEntry::WebSearch { query } => (IndexedItemKind::WebSearch, query),
+    };
+    (!text.trim().is_empty()).then_some((kind, text))
+}
+
+/// Quotes each whitespace-separated term so user input never trips FTS5
+/// query syntax; terms are ANDed.
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// Digest of the first line, used to notice rollouts rewritten in place.
+fn head_digest(file: &mut File) -> io::Result<String> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 4096];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        if let Some(end) = buf[..read].iter().position(|byte| *byte == b'\n') {
+            head.extend_from_slice(&buf[..end]);
+            break;
+        }
+        head.extend_from_slice(&buf[..read]);
+    }
+    Ok(format!("{:x}", Sha256::digest(&head)))
+}
+
+fn collect_rollout_files(dir: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_rollout_files(&path, out)?;
+        } else if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("rollout-") && name.ends_with(".jsonl"))
+        {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn path_key(path: &Path) -> String {
+    path.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::ConversationId;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::models::ResponseItem;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use pretty_assertions::assert_eq;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    fn rollout_line(item: RolloutItem) -> String {
+        let line = RolloutLine {
+            timestamp: "2025-06-01T08:00:00.000Z".to_string(),
+            item,
+        };
+        format!("{}\n", serde_json::to_string(&line).expect("serialize"))
+    }
+
+    fn message(role: &str, text: &str) -> String {
+        let content = if role == "user" {
+            ContentItem::InputText {
+                text: text.to_string(),
+            }
+        } else {
+            ContentItem::OutputText {
+                text: text.to_string(),
+            }
+        };
+        rollout_line(RolloutItem::ResponseItem(ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![content],
+        }))
+    }
+
+    fn write_rollout(codex_home: &Path, id: ConversationId) -> PathBuf {
+        let dir = codex_home.join(SESSIONS_SUBDIR).join("2025/06/01");
+        std::fs::create_dir_all(&dir).expect("sessions dir");
+        let path = dir.join(format!("rollout-2025-06-01T08-00-00-{id}.jsonl"));
+        let meta = rollout_line(RolloutItem::SessionMeta(SessionMetaLine {
+            meta: SessionMeta {
+                id,
+                cwd: PathBuf::from("/repo"),
+                ..SessionMeta::default()
+            },
+            git: None,
+        }));
+        std::fs::write(&path, meta).expect("write rollout");
+        path
+    }
+
+    fn append(path: &Path, contents: &str) {
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(path)
+            .expect("open rollout");
+        file.write_all(contents.as_bytes()).expect("append");
+    }
+
+    #[test]
+    fn finds_threads_by_message_text() -> Result<()> {
+        let home = TempDir::new()?;
+        let parser_thread = ConversationId::new();
+        let parser_rollout = write_rollout(home.path(), parser_thread);
+        append(&parser_rollout, &message("user", "fix the parser panic"));
+        let other_rollout = write_rollout(home.path(), ConversationId::new());
+        append(&other_rollout, &message("user", "update the README"));
+
+        let mut index = HistoryIndex::open(home.path())?;
+        index.sync()?;
+        let hits = index.search("parser", 10)?;
+
+        assert_eq!(
+            hits,
+            vec![ThreadSearchHit {
+                thread_id: parser_thread.to_string(),
+                path: parser_rollout,
+                cwd: Some(PathBuf::from("/repo")),
+                started_at: Some(String::new()),
+                matches: vec![ItemSearchHit {
+                    kind: IndexedItemKind::UserMessage,
+                    timestamp: "2025-06-01T08:00:00.000Z".to_string(),
+                    snippet: "fix the **parser** panic".to_string(),
+                }],
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn indexes_appended_lines_incrementally() -> Result<()> {
+        let home = TempDir::new()?;
+        let rollout = write_rollout(home.path(), ConversationId::new());
+        append(&rollout, &message("user", "first turn"));
+
+        let mut index = HistoryIndex::open(home.path())?;
+        index.index_rollout(&rollout)?;
+        // A line still being written is picked up once it is complete.
+        append(&rollout, &message("assistant", "deadlock fixed"));
+        append(&rollout, "{\"timestamp\":");
+        index.index_rollout(&rollout)?;
+
+        let hits = index.search("deadlock", 10)?;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].matches[0].kind, IndexedItemKind::AgentMessage);
+        assert_eq!(index.search("first", 10)?[0].matches.len(), 1);
+
+        std::fs::remove_file(&rollout)?;
+        index.sync()?;
+        assert_eq!(index.search("deadlock", 10)?, Vec::new());
+        Ok(())
+    }
+
+    #[test]
+    fn quotes_query_terms() {
+        assert_eq!(
+            fts_query(r#"foo "bar" OR"#),
+            Some(r#""foo" """bar""" "OR""#.to_string())
+        );
+        assert_eq!(fts_query("   "), None);
+    }
+}
@@ -0,0 +1,356 @@
+//! Lifecycle hook scripts configured under `[hooks]`.
+//!
+//! Each hook is an argv run in the turn's working directory with a JSON
+//! payload describing the lifecycle point on stdin. Hooks for a point run in
+//! order; the first one that exits non-zero, times out, or cannot be started
+//! stops the rest and blocks the action. Its stdout (or stderr, when stdout is
+//! empty) is the reason shown to the user and the model.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config::types::HooksConfig;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::EventMsg;
+use crate::protocol::WarningEvent;
+
+/// Longest reason kept from a hook's output, in bytes.
+const MAX_REASON_BYTES: usize = 2_000;
+
+/// The lifecycle point a hook runs at, serialized as its stdin payload.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "hook", rename_all = "kebab-case")]
+pub(crate) enum HookPayload {
+    #[serde(rename_all = "kebab-case")]
+    PreTurn {
+        thread_id: String,
+        turn_id: String,
+        cwd: PathBuf,
+        input_messages: Vec<String>,
+    },
+    #[serde(rename_all = "kebab-case")]
+    PostTurn {
+        thread_id: String,
+        turn_id: String,
+        cwd: PathBuf,
+        last_assistant_message: Option<String>,
+    },
+    #[serde(rename_all = "kebab-case")]
+    PreExec {
+        thread_id: String,
+        turn_id: String,
+        cwd: PathBuf,
+        call_id: String,
+        command: Vec<String>,
+        /// Variables set for the command on top of the inherited environment:
+        /// `shell_environment_policy.set` plus the conversation's own.
+        env: BTreeMap<String, String>,
+    },
+    #[serde(rename_all = "kebab-case")]
+    PostPatch {
+        thread_id: String,
+        turn_id: String,
+        cwd: PathBuf,
+        call_id: String,
+        paths: Vec<PathBuf>,
+    },
+}
+
+impl HookPayload {
+    fn name(&self) -> &'static str {
+        match self {
+            HookPayload::PreTurn { .. } => "pre_turn",
+            HookPayload::PostTurn { .. } => "post_turn",
+            HookPayload::PreExec { .. } => "pre_exec",
+            HookPayload::PostPatch { .. } => "post_patch",
+        }
+    }
+
+    fn commands<'a>(&self, config: &'a HooksConfig) -> &'a [Vec<String>] {
+        match self {
+            HookPayload::PreTurn { .. } => &config.pre_turn,
+            HookPayload::PostTurn { .. } => &config.post_turn,
+            HookPayload::PreExec { .. } => &config.pre_exec,
+            HookPayload::PostPatch { .. } => &config.post_patch,
+        }
+    }
+}
+
+/// A hook that blocked the action it ran for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct HookBlocked {
+    /// Which lifecycle point, e.g. `pre_exec`.
+    pub(crate) point: &'static str,
+    /// The hook's program.
+    pub(crate) program: String,
+    pub(crate) reason: String,
+}
+
+impl std::fmt::Display for HookBlocked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hook `{}` failed: {}",
+            self.point, self.program, self.reason
+        )
+    }
+}
+
+/// Runs the hooks configured for `payload` in the turn's working directory.
+pub(crate) async fn run_hooks(
+    turn_context: &TurnContext,
+    payload: HookPayload,
+) -> Result<(), HookBlocked> {
+    let config = turn_context.client.config();
+    run_hook_commands(&config.hooks, &turn_context.cwd, &payload).await
+}
+
+/// Runs the `pre_exec` hooks for a command the model proposed. A block is
+/// shown to the user as a warning and returned to the model as the tool
+/// output.
+pub(crate) async fn check_pre_exec(
+    session: &Session,
+    turn_context: &TurnContext,
+    call_id: &str,
+    command: &[String],
+    cwd: &Path,
+) -> Result<(), FunctionCallError> {
+    let payload = HookPayload::PreExec {
+        thread_id: session.conversation_id().to_string(),
+        turn_id: turn_context.sub_id.clone(),
+        cwd: cwd.to_path_buf(),
+        call_id: call_id.to_string(),
+        command: command.to_vec(),
+        env: turn_context
+            .shell_environment_policy
+            .r#set
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+    };
+    let Err(blocked) = run_hooks(turn_context, payload).await else {
+        return Ok(());
+    };
+    let message = format!("Command blocked: {blocked}");
+    session
+        .send_event(
+            turn_context,
+            EventMsg::Warning(WarningEvent {
+                message: message.clone(),
+            }),
+        )
+        .await;
+    Err(FunctionCallError::RespondToModel(message))
+}
+
+/// Runs the `post_patch` hooks after a patch was applied. When one fails the
+/// user is warned and the returned note is appended to the tool output so the
+/// model can address it; the patch itself stays applied.
+pub(crate) async fn post_patch_note(
+    session: &Session,
+    turn_context: &TurnContext,
+    call_id: &str,
+    paths: Vec<PathBuf>,
+) -> Option<String> {
+    let payload = HookPayload::PostPatch {
+        thread_id: session.conversation_id().to_string(),
+        turn_id: turn_context.sub_id.clone(),
+        cwd: turn_context.cwd.clone(),
+        call_id: call_id.to_string(),
+        paths,
+    };
+    let blocked = run_hooks(turn_context, payload).await.err()?;
+    let message = blocked.to_string();
+    session
+        .send_event(
+            turn_context,
+            EventMsg::Warning(WarningEvent {
+                message: message.clone(),
+            }),
+        )
+        .await;
+    Some(format!("\n\nThe patch was applied, but {message}"))
+}
+
+async fn run_hook_commands(
+    config: &HooksConfig,
+    cwd: &Path,
+    payload: &HookPayload,
+) -> Result<(), HookBlocked> {
+    let commands = payload.commands(config);
+    if commands.is_empty() {
+        return Ok(());
+    }
+    let input = serde_json::to_vec(payload).unwrap_or_default();
+    let timeout = Duration::from_millis(config.timeout_ms);
+    for argv in commands {
+        let Some((program, args)) = argv.split_first() else {
+            continue;
+        };
+        if let Err(reason) = run_hook(program, args, cwd, &input, timeout).await {
+            return Err(HookBlocked {
+                point: payload.name(),
+                program: program.clone(),
+                reason,
+            });
+        }
+    }
+    Ok(())
+}
+
+async fn run_hook(
+    program: &str,
+    args: &[String],
+    cwd: &Path,
+    input: &[u8],
+    timeout: Duration,
+) -> Result<(), String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|err| format!("could not start: {err}"))?;
+    let run = async move {
+        if let Some(mut stdin) = child.stdin.take() {
+            // A hook that ignores its payload may exit before reading it.
+            let _ = stdin.write_all(input).await;
+        }
+        child.wait_with_output().await
+    };
+
+    // Dropping `run` on timeout kills the hook.
+    let output = match tokio::time::timeout(timeout, run).await {
+        Ok(output) => output.map_err(|err| format!("could not wait for exit: {err}"))?,
+        Err(_) => return Err(format!("timed out after {} ms", timeout.as_millis())),
+    };
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let reason = [stdout.trim(), stderr.trim()]
+        .into_iter()
+        .find(|text| !text.is_empty())
+        .map(|text| truncate_reason(text).to_string())
+        .unwrap_or_else(|| format!("exited with {}", output.status));
+    Err(reason)
+}
+
+fn truncate_reason(text: &str) -> &str {
+    if text.len() <= MAX_REASON_BYTES {
+        return text;
+    }
+    let mut end = MAX_REASON_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn pre_exec(command: &[&str]) -> HookPayload {
+        HookPayload::PreExec {
+            thread_id: "thread".to_string(),
+            turn_id: "turn".to_string(),
+            cwd: PathBuf::from("/repo"),
+            call_id: "call".to_string(),
+            command: command.iter().map(ToString::to_string).collect(),
+            env: BTreeMap::from([(
+                "NODE_OPTIONS".to_string(),
+                "--max-old-space-size=4096".to_string(),
+            )]),
+        }
+    }
+
+    fn sh(script: &str) -> Vec<String> {
+        vec!["sh".to_string(), "-c".to_string(), script.to_string()]
+    }
+
+    #[tokio::test]
+    async fn hooks_receive_the_payload_on_stdin() {
+        let dir = TempDir::new().expect("tempdir");
+        let config = HooksConfig {
+            pre_exec: vec![sh("cat > payload.json")],
+            ..HooksConfig::default()
+        };
+
+        run_hook_commands(&config, dir.path(), &pre_exec(&["ls", "-la"]))
+            .await
+            .expect("hook passes");
+
+        let payload: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(dir.path().join("payload.json")).expect("payload written"),
+        )
+        .expect("payload is json");
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "hook": "pre-exec",
+                "thread-id": "thread",
+                "turn-id": "turn",
+                "cwd": "/repo",
+                "call-id": "call",
+                "command": ["ls", "-la"],
+                "env": { "NODE_OPTIONS": "--max-old-space-size=4096" },
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn first_failing_hook_blocks_with_its_output() {
+        let dir = TempDir::new().expect("tempdir");
+        let config = HooksConfig {
+            pre_exec: vec![
+                sh("exit 0"),
+                sh("echo 'rm is not allowed' >&2; exit 3"),
+                sh("touch should-not-run"),
+            ],
+            ..HooksConfig::default()
+        };
+
+        let blocked = run_hook_commands(&config, dir.path(), &pre_exec(&["rm", "-rf", "/"]))
+            .await
+            .expect_err("second hook blocks");
+
+        assert_eq!(
+            blocked.to_string(),
+            "pre_exec hook `sh` failed: rm is not allowed"
+        );
+        assert!(!dir.path().join("should-not-run").exists());
+    }
+
+    #[tokio::test]
+    async fn slow_hooks_time_out() {
+        let dir = TempDir::new().expect("tempdir");
+        let config = HooksConfig {
+            pre_exec: vec![sh("sleep 5")],
+            timeout_ms: 50,
+            ..HooksConfig::default()
+        };
+
+        let blocked = run_hook_commands(&config, dir.path(), &pre_exec(&["ls"]))
+            .await
+            .expect_err("hook times out");
+
+        assert_eq!(blocked.reason, "timed out after 50 ms");
+    }
+}
@@ -6,11 +6,15 @@
 #![deny(clippy::print_stdout, clippy::print_stderr)]
 
 mod apply_patch;
+mod approval_rules;
+mod audio_transcription;
 pub mod auth;
+pub mod background_tasks;
 pub mod bash;
 mod chat_completions;
 mod client;
 mod client_common;
+mod code_search;
 pub mod codex;
 mod codex_conversation;
 mod compact_remote;
@@ -20,34 +24,58 @@ mod command_safety;
 pub mod config;
 pub mod config_loader;
 mod context_manager;
+mod cost;
+pub mod custom_commands;
 pub mod custom_prompts;
 mod environment_context;
 pub mod error;
 pub mod exec;
+pub mod exec_assessment;
 pub mod exec_env;
+pub mod exec_log;
+mod exec_output;
+mod exec_output_cleanup;
 mod exec_policy;
+pub mod experiments;
 pub mod features;
+mod file_locks;
 mod flags;
 pub mod git_info;
+pub mod history_index;
+mod hooks;
 pub mod landlock;
+mod lsp;
 pub mod mcp;
 mod mcp_connection_manager;
 mod mcp_tool_call;
 mod message_history;
+pub mod model_provider;
 mod model_provider_info;
+mod model_recording;
+mod model_router;
+mod network_proxy;
 pub mod parse_command;
+pub mod pinned_context;
+#[cfg(feature = "wasm-plugins")]
+mod plugins;
+#[cfg(not(feature = "wasm-plugins"))]
+#[path = "plugins_unavailable.rs"]
+mod plugins;
 pub mod powershell;
 mod response_processing;
 pub mod sandboxing;
 pub mod token_data;
+pub mod tool_filter;
 mod truncate;
 mod unified_exec;
 mod user_instructions;
 pub use model_provider_info::DEFAULT_LMSTUDIO_PORT;
 pub use model_provider_info::DEFAULT_OLLAMA_PORT;
+pub use model_provider_info::DEFAULT_VLLM_PORT;
 pub use model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
 pub use model_provider_info::ModelProviderInfo;
 pub use model_provider_info::OLLAMA_OSS_PROVIDER_ID;
+pub use model_provider_info::VLLM_PROVIDER_ID;
 pub use model_provider_info::WireApi;
 pub use model_provider_info::built_in_model_providers;
 pub use model_provider_info::create_oss_provider_with_base_url;
@@ -64,17 +92,33 @@ pub mod default_client;
 pub mod model_family;
 mod openai_model_info;
 pub mod project_doc;
+mod provider_health;
+pub mod pull_request;
+mod rate_limit_scheduler;
+pub mod reasoning_redaction;
+mod redaction;
+mod repo_map;
+mod response_cache;
 mod rollout;
 pub(crate) mod safety;
+pub mod scheduler;
 pub mod seatbelt;
 pub mod shell;
 pub mod spawn;
 pub mod terminal;
+mod test_failures;
+pub mod thread_bundle;
+pub mod thread_metadata;
+pub mod thread_templates;
 mod tools;
+pub mod transcript_export;
+mod turn_commit;
 pub mod turn_diff_tracker;
 pub use rollout::ARCHIVED_SESSIONS_SUBDIR;
 pub use rollout::INTERACTIVE_SESSION_SOURCES;
+pub use rollout::ROLLOUT_SCHEMA_VERSION;
 pub use rollout::RolloutRecorder;
+pub use rollout::RolloutVersionError;
 pub use rollout::SESSIONS_SUBDIR;
 pub use rollout::SessionMeta;
 pub use rollout::find_conversation_path_by_id_str;
@@ -89,9 +133,14 @@ mod tasks;
 mod user_notification;
 mod user_shell_command;
 pub mod util;
+pub mod windows_shell;
+pub mod workspace_instructions;
+mod worktree;
+mod write_grants;
 
 pub use apply_patch::CODEX_APPLY_PATCH_ARG1;
 pub use command_safety::is_safe_command;
+pub use exec_policy::ExecPolicyError;
 pub use safety::get_platform_sandbox;
 pub use safety::set_windows_sandbox_enabled;
 // Re-export the protocol types from the standalone `codex-protocol` crate so existing
@@ -101,6 +150,7 @@ pub use codex_protocol::protocol;
 // as those in the protocol crate when constructing protocol messages.
 pub use codex_protocol::config_types as protocol_config_types;
 
+pub use chat_completions::ChatCompletionsProvider;
 pub use client::ModelClient;
 pub use client_common::Prompt;
 pub use client_common::REVIEW_PROMPT;
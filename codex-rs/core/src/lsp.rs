@@ -0,0 +1,657 @@
+//! Diagnostics from language servers for the files a patch changed.
+//!
+//! Each server configured under `lsp_servers` is started lazily, the first
+//! time a patch touches a file with one of its extensions, and kept running
+//! for the rest of the session. After every applied patch the changed files
+//! are opened in (or re-synced with) the servers that handle them, and the
+//! diagnostics each server publishes in response are reported to clients as
+//! a [`PatchDiagnosticsEvent`] and appended to the tool output, so the model
+//! sees type errors without running a full build.
+//!
+//! Only the slice of the protocol needed for that is implemented: the
+//! `initialize` handshake, document synchronization with full text, and
+//! `textDocument/publishDiagnostics`. Requests from the server are answered
+//! with `null`.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use codex_protocol::protocol::Diagnostic;
+use codex_protocol::protocol::DiagnosticSeverity;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::FileDiagnostics;
+use codex_protocol::protocol::PatchDiagnosticsEvent;
+use codex_protocol::protocol::WarningEvent;
+use futures::future::join_all;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::io::AsyncBufRead;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufReader;
+use tokio::process::Child;
+use tokio::process::ChildStdin;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tokio::sync::Notify;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+use tokio::time::timeout;
+use tracing::debug;
+use url::Url;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config::types::LspServerConfig;
+use crate::exec_env::create_env;
+
+/// Diagnostics beyond this many are counted but not listed in the note.
+const MAX_NOTE_DIAGNOSTICS: usize = 50;
+
+/// Servers keyed by name and workspace root; `None` marks one that failed
+/// to start.
+type ServerMap = HashMap<(String, PathBuf), Option<Arc<LspClient>>>;
+
+/// Requests awaiting a response, keyed by JSON-RPC id.
+type PendingRequests = Arc<Mutex<HashMap<i64, oneshot::Sender<Result<Value, String>>>>>;
+
+/// Language servers started by a session, keyed by server name and the
+/// workspace root they were started in.
+#[derive(Default)]
+pub(crate) struct LspManager {
+    servers: Mutex<ServerMap>,
+}
+
+impl LspManager {
+    /// Diagnostics for every file in `paths` that a configured server
+    /// answered for. Files no server handles, and files whose server timed
+    /// out, are left out. Servers that fail to start are reported once in the
+    /// returned errors and not retried.
+    async fn diagnostics(
+        &self,
+        servers: &BTreeMap<String, LspServerConfig>,
+        root: &Path,
+        env: &HashMap<String, String>,
+        paths: &[PathBuf],
+    ) -> (Vec<FileDiagnostics>, Vec<String>) {
+        let mut queries = Vec::new();
+        let mut errors = Vec::new();
+        for (name, config) in servers {
+            let files = paths
+                .iter()
+                .filter(|path| handles(config, path))
+                .collect::<Vec<_>>();
+            if files.is_empty() {
+                continue;
+            }
+            match self.client(name, config, root, env).await {
+                Ok(Some(client)) => {
+                    queries.extend(files.into_iter().map(|path| (client.clone(), path.clone())))
+                }
+                Ok(None) => {}
+                Err(err) => errors.push(format!("language server `{name}` failed to start: {err}")),
+            }
+        }
+
+        let results = join_all(
+            queries
+                .into_iter()
+                .map(|(client, path)| async move { (client.file_diagnostics(&path).await, path) }),
+        )
+        .await;
+        let mut by_path = BTreeMap::<PathBuf, Vec<Diagnostic>>::new();
+        for (diagnostics, path) in results {
+            if let Some(diagnostics) = diagnostics {
+                by_path.entry(path).or_default().extend(diagnostics);
+            }
+        }
+        let files = by_path
+            .into_iter()
+            .map(|(path, diagnostics)| FileDiagnostics { path, diagnostics })
+            .collect();
+        (files, errors)
+    }
+
+    /// The running client for `name` in `root`, starting it if needed.
+    /// `Ok(None)` when it already failed to start earlier in the session.
+    async fn client(
+        &self,
+        name: &str,
+        config: &LspServerConfig,
+        root: &Path,
+        env: &HashMap<String, String>,
+    ) -> Result<Option<Arc<LspClient>>, String> {
+        let mut servers = self.servers.lock().await;
+        let key = (name.to_string(), root.to_path_buf());
+        if let Some(client) = servers.get(&key) {
+            return Ok(client.clone());
+        }
+        match LspClient::start(name, config, root, env).await {
+            Ok(client) => {
+                let client = Arc::new(client);
+                servers.insert(key, Some(client.clone()));
+                Ok(Some(client))
+            }
+            Err(err) => {
+                servers.insert(key, None);
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Queries the language servers configured for the files in `paths` after a
+/// patch was applied, reports their diagnostics to clients, and returns a
+/// note listing the errors and warnings for the tool output.
+pub(crate) async fn patch_diagnostics_note(
+    session: &Session,
+    turn_context: &TurnContext,
+    call_id: &str,
+    paths: &[PathBuf],
+) -> Option<String> {
+    let config = turn_context.client.config();
+    if config.lsp_servers.is_empty() {
+        return None;
+    }
+    let env = create_env(&turn_context.shell_environment_policy);
+    let (files, errors) = session
+        .services
+        .lsp
+        .diagnostics(&config.lsp_servers, &turn_context.cwd, &env, paths)
+        .await;
+    for message in errors {
+        session
+            .send_event(turn_context, EventMsg::Warning(WarningEvent { message }))
+            .await;
+    }
+    if files.is_empty() {
+        return None;
+    }
+    let note = render_note(&files, &turn_context.cwd);
+    session
+        .send_event(
+            turn_context,
+            EventMsg::PatchDiagnostics(PatchDiagnosticsEvent {
+                call_id: call_id.to_string(),
+                files,
+            }),
+        )
+        .await;
+    note
+}
+
+/// Errors and warnings as `path:line:column: severity: message` lines, or
+/// `None` when there are none.
+fn render_note(files: &[FileDiagnostics], cwd: &Path) -> Option<String> {
+    let mut lines = Vec::new();
+    for file in files {
+        let path = file.path.strip_prefix(cwd).unwrap_or(&file.path);
+        for diagnostic in &file.diagnostics {
+            if !matches!(
+                diagnostic.severity,
+                DiagnosticSeverity::Error | DiagnosticSeverity::Warning
+            ) {
+                continue;
+            }
+            let mut line = format!(
+                "{}:{}:{}: {}: {}",
+                path.display(),
+                diagnostic.line,
+                diagnostic.column,
+                diagnostic.severity,
+                diagnostic.message
+            );
+            match (&diagnostic.source, &diagnostic.code) {
+                (Some(source), Some(code)) => line.push_str(&format!(" [{source} {code}]")),
+                (Some(label), None) | (None, Some(label)) => line.push_str(&format!(" [{label}]")),
+                (None, None) => {}
+            }
+            lines.push(line);
+        }
+    }
+    if lines.is_empty() {
+        return None;
+    }
+    let total = lines.len();
+    lines.truncate(MAX_NOTE_DIAGNOSTICS);
+    let mut note = format!(
+        "\n\nLanguage servers reported problems in the changed files:\n{}",
+        lines.join("\n")
+    );
+    if total > MAX_NOTE_DIAGNOSTICS {
+        note.push_str(&format!("\n... and {} more", total - MAX_NOTE_DIAGNOSTICS));
+    }
+    Some(note)
+}
+
+fn handles(config: &LspServerConfig, path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| config.extensions.iter().any(|e| e == extension))
+}
+
+/// A running language server.
+struct LspClient {
+    stdin: Arc<Mutex<ChildStdin>>,
+    next_id: AtomicI64,
+    pending: PendingRequests,
+    published: Arc<PublishedDiagnostics>,
+    /// Version of every document opened in the server.
+    versions: Mutex<HashMap<Url, i32>>,
+    language_id: Option<String>,
+    diagnostics_timeout: Duration,
+    _child: Child,
+}
+
+/// Latest `publishDiagnostics` payload per document, tagged with a sequence
+/// number so a caller can wait for one newer than its change.
+#[derive(Default)]
+struct PublishedDiagnostics {
+    seq: AtomicU64,
+    by_uri: Mutex<HashMap<Url, (u64, Vec<Diagnostic>)>>,
+    notify: Notify,
+}
+
+impl LspClient {
+    async fn start(
+        name: &str,
+        config: &LspServerConfig,
+        root: &Path,
+        env: &HashMap<String, String>,
+    ) -> Result<Self, String> {
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .current_dir(root)
+            .env_clear()
+            .envs(env)
+            .envs(&config.env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| format!("{}: {err}", config.command))?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err("stdio was not captured".to_string());
+        };
+
+        let client = Self {
+            stdin: Arc::new(Mutex::new(stdin)),
+            next_id: AtomicI64::new(1),
+            pending: Arc::default(),
+            published: Arc::default(),
+            versions: Mutex::default(),
+            language_id: config.language_id.clone(),
+            diagnostics_timeout: Duration::from_millis(config.diagnostics_timeout_ms),
+            _child: child,
+        };
+        tokio::spawn(read_loop(
+            BufReader::new(stdout),
+            client.stdin.clone(),
+            client.pending.clone(),
+            client.published.clone(),
+        ));
+
+        let root_uri = Url::from_directory_path(root)
+            .map_err(|()| format!("{} is not an absolute path", root.display()))?;
+        let params = json!({
+            "processId": std::process::id(),
+            "clientInfo": { "name": "codex" },
+            "rootUri": root_uri,
+            "workspaceFolders": [{ "uri": root_uri, "name": name }],
+            "capabilities": {
+                "textDocument": {
+                    "synchronization": { "didSave": true },
+                    "publishDiagnostics": { "versionSupport": false },
+                },
+                "workspace": { "configuration": true, "workspaceFolders": true },
+            },
+        });
+        timeout(
+            Duration::from_millis(config.startup_timeout_ms),
+            client.request("initialize", params),
+        )
+        .await
+        .map_err(|_| "timed out waiting for `initialize`".to_string())??;
+        client.notify("initialized", json!({})).await?;
+        Ok(client)
+    }
+
+    /// Sync the current contents of `path` with the server and wait for the
+    /// diagnostics it publishes in response. `None` on timeout or when the
+    /// file cannot be read, e.g. because the patch deleted it.
+    async fn file_diagnostics(&self, path: &Path) -> Option<Vec<Diagnostic>> {
+        let uri = Url::from_file_path(path).ok()?;
+        let Ok(text) = tokio::fs::read_to_string(path).await else {
+            if self.versions.lock().await.remove(&uri).is_some() {
+                let params = json!({ "textDocument": { "uri": uri } });
+                let _ = self.notify("textDocument/didClose", params).await;
+            }
+            return None;
+        };
+
+        let seq = self.published.seq.load(Ordering::SeqCst);
+        let synced = {
+            let mut versions = self.versions.lock().await;
+            match versions.get_mut(&uri) {
+                Some(version) => {
+                    *version += 1;
+                    let params = json!({
+                        "textDocument": { "uri": uri, "version": *version },
+                        "contentChanges": [{ "text": text }],
+                    });
+                    self.notify("textDocument/didChange", params).await
+                }
+                None => {
+                    versions.insert(uri.clone(), 1);
+                    let language_id = self.language_id.clone().unwrap_or_else(|| {
+                        path.extension()
+                            .and_then(|extension| extension.to_str())
+                            .unwrap_or_default()
+                            .to_string()
+                    });
+                    let params = json!({
+                        "textDocument": {
+                            "uri": uri,
+                            "languageId": language_id,
+                            "version": 1,
+                            "text": text,
+                        },
+                    });
+                    self.notify("textDocument/didOpen", params).await
+                }
+            }
+        };
+        if let Err(err) = synced {
+            debug!(
+                "failed to sync {} with language server: {err}",
+                path.display()
+            );
+            return None;
+        }
+        let params = json!({ "textDocument": { "uri": uri } });
+        let _ = self.notify("textDocument/didSave", params).await;
+
+        let deadline = Instant::now() + self.diagnostics_timeout;
+        loop {
+            let notified = self.published.notify.notified();
+            if let Some((published_seq, diagnostics)) = self.published.by_uri.lock().await.get(&uri)
+                && *published_seq > seq
+            {
+                return Some(diagnostics.clone());
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return None;
+            }
+        }
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().await.insert(id, tx);
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        write_message(&self.stdin, &message).await?;
+        rx.await.map_err(|_| "language server exited".to_string())?
+    }
+
+    async fn notify(&self, method: &str, params: Value) -> Result<(), String> {
+        let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        write_message(&self.stdin, &message).await
+    }
+}
+
+/// Dispatches messages from the server until its stdout closes.
+async fn read_loop<R: AsyncBufRead + Unpin>(
+    mut stdout: R,
+    stdin: Arc<Mutex<ChildStdin>>,
+    pending: PendingRequests,
+    published: Arc<PublishedDiagnostics>,
+) {
+    loop {
+        let message = match read_message(&mut stdout).await {
+            Ok(Some(message)) => message,
+            Ok(None) => break,
+            Err(err) => {
+                debug!("language server sent an invalid message: {err}");
+                break;
+            }
+        };
+        let method = message.get("method").and_then(Value::as_str);
+        match (method, message.get("id")) {
+            (Some(method), Some(id)) => {
+                // Configuration requests expect one entry per requested item.
+                let result = match method {
+                    "workspace/configuration" => {
+                        let items = message["params"]["items"].as_array().map_or(0, Vec::len);
+                        Value::Array(vec![Value::Null; items])
+                    }
+                    _ => Value::Null,
+                };
+                let reply = json!({ "jsonrpc": "2.0", "id": id, "result": result });
+                let _ = write_message(&stdin, &reply).await;
+            }
+            (Some("textDocument/publishDiagnostics"), None) => {
+                let Ok(params) =
+                    serde_json::from_value::<PublishDiagnosticsParams>(message["params"].clone())
+                else {
+                    continue;
+                };
+                let seq = published.seq.fetch_add(1, Ordering::SeqCst) + 1;
+                let diagnostics = params
+                    .diagnostics
+                    .into_iter()
+                    .map(LspDiagnostic::into_diagnostic)
+                    .collect();
+                published
+                    .by_uri
+                    .lock()
+                    .await
+                    .insert(params.uri, (seq, diagnostics));
+                published.notify.notify_waiters();
+            }
+            (None, Some(id)) => {
+                let Some(id) = id.as_i64() else {
+                    continue;
+                };
+                let Some(tx) = pending.lock().await.remove(&id) else {
+                    continue;
+                };
+                let result = match message.get("error") {
+                    Some(error) => Err(error["message"]
+                        .as_str()
+                        .unwrap_or("request failed")
+                        .to_string()),
+                    None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+                };
+                let _ = tx.send(result);
+            }
+            _ => {}
+        }
+    }
+    // Fail outstanding requests instead of leaving them waiting.
+    pending.lock().await.clear();
+}
+
+async fn write_message(stdin: &Mutex<ChildStdin>, message: &Value) -> Result<(), String> {
+    let mut stdin = stdin.lock().await;
+    stdin
+        .write_all(&frame(message))
+        .await
+        .map_err(|err| err.to_string())?;
+    stdin.flush().await.map_err(|err| err.to_string())
+}
+
+fn frame(message: &Value) -> Vec<u8> {
+    let body = message.to_string();
+    let mut framed = format!("Content-Length: {}\r\n\r\n", body.len()).into_bytes();
+    framed.extend_from_slice(body.as_bytes());
+    framed
+}
+
+/// Reads one `Content-Length` framed message. `Ok(None)` at end of stream.
+async fn read_message<R: AsyncBufRead + Unpin>(reader: &mut R) -> std::io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let Some(content_length) = content_length else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "missing Content-Length header",
+        ));
+    };
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).await?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(std::io::Error::other)
+}
+
+#[derive(Deserialize)]
+struct PublishDiagnosticsParams {
+    uri: Url,
+    diagnostics: Vec<LspDiagnostic>,
+}
+
+#[derive(Deserialize)]
+struct LspDiagnostic {
+    range: LspRange,
+    severity: Option<u8>,
+    code: Option<Value>,
+    source: Option<String>,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct LspRange {
+    start: LspPosition,
+    end: LspPosition,
+}
+
+#[derive(Deserialize)]
+struct LspPosition {
+    line: u32,
+    character: u32,
+}
+
+impl LspDiagnostic {
+    /// Positions become 1-based; a missing severity is an error, as the
+    /// protocol leaves its interpretation to the client.
+    fn into_diagnostic(self) -> Diagnostic {
+        let severity = match self.severity {
+            Some(2) => DiagnosticSeverity::Warning,
+            Some(3) => DiagnosticSeverity::Information,
+            Some(4) => DiagnosticSeverity::Hint,
+            _ => DiagnosticSeverity::Error,
+        };
+        let code = match self.code {
+            Some(Value::String(code)) => Some(code),
+            Some(Value::Number(code)) => Some(code.to_string()),
+            _ => None,
+        };
+        Diagnostic {
+            severity,
+            line: self.range.start.line + 1,
+            column: self.range.start.character + 1,
+            end_line: self.range.end.line + 1,
+            end_column: self.range.end.character + 1,
+            message: self.message,
+            source: self.source,
+            code,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn reads_framed_messages_in_sequence() {
+        let first = json!({ "jsonrpc": "2.0", "id": 1, "result": null });
+        let second = json!({ "jsonrpc": "2.0", "method": "window/logMessage", "params": {} });
+        let mut bytes = frame(&first);
+        bytes.extend(frame(&second));
+        let mut reader = bytes.as_slice();
+
+        assert_eq!(read_message(&mut reader).await.expect("read"), Some(first));
+        assert_eq!(read_message(&mut reader).await.expect("read"), Some(second));
+        assert_eq!(read_message(&mut reader).await.expect("read"), None);
+    }
+
+    #[test]
+    fn converts_published_diagnostics_and_lists_problems() {
+        let params: PublishDiagnosticsParams = serde_json::from_value(json!({
+            "uri": "file:///repo/src/lib.rs",
+            "diagnostics": [
+                {
+                    "range": { "start": { "line": 4, "character": 8 }, "end": { "line": 4, "character": 12 } },
+                    "severity": 1,
+                    "code": "E0308",
+                    "source": "rustc",
+                    "message": "mismatched types",
+                },
+                {
+                    "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 3 } },
+                    "severity": 4,
+                    "message": "consider a shorter name",
+                },
+            ],
+        }))
+        .expect("params");
+        let diagnostics = params
+            .diagnostics
+            .into_iter()
+            .map(LspDiagnostic::into_diagnostic)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            diagnostics[0],
+            Diagnostic {
+                severity: DiagnosticSeverity::Error,
+                line: 5,
+                column: 9,
+                end_line: 5,
+                end_column: 13,
+                message: "mismatched types".to_string(),
+                source: Some("rustc".to_string()),
+                code: Some("E0308".to_string()),
+            }
+        );
+
+        let files = vec![FileDiagnostics {
+            path: PathBuf::from("/repo/src/lib.rs"),
+            diagnostics,
+        }];
+        assert_eq!(
+            render_note(&files, Path::new("/repo")).as_deref(),
+            Some(
+                "\n\nLanguage servers reported problems in the changed files:\n\
+                 src/lib.rs:5:9: error: mismatched types [rustc E0308]"
+            )
+        );
+    }
+}
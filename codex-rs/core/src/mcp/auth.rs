@@ -3,7 +3,9 @@ use std::collections::HashMap;
 use anyhow::Result;
 use codex_protocol::protocol::McpAuthStatus;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
+pub use codex_rmcp_client::OAuthTokenInfo;
 use codex_rmcp_client::determine_streamable_http_auth_status;
+use codex_rmcp_client::read_oauth_token_info;
 use futures::future::join_all;
 use tracing::warn;
 
@@ -57,6 +59,7 @@ async fn compute_auth_status(
             bearer_token_env_var,
             http_headers,
             env_http_headers,
+            ..
         } => {
             determine_streamable_http_auth_status(
                 server_name,
@@ -70,3 +73,18 @@ async fn compute_auth_status(
         }
     }
 }
+
+/// Read the scopes and claims of the OAuth token stored for `server_name`.
+/// Returns `None` for stdio servers and for servers without stored OAuth credentials.
+pub fn oauth_token_info(
+    server_name: &str,
+    config: &McpServerConfig,
+    store_mode: OAuthCredentialsStoreMode,
+) -> Result<Option<OAuthTokenInfo>> {
+    match &config.transport {
+        McpServerTransportConfig::Stdio { .. } => Ok(None),
+        McpServerTransportConfig::StreamableHttp { url, .. } => {
+            read_oauth_token_info(server_name, url, store_mode)
+        }
+    }
+}
@@ -0,0 +1,188 @@
+//! Conversions for MCP resources and prompts that are surfaced to clients or
+//! submitted into the conversation.
+
+use codex_protocol::user_input::UserInput;
+use mcp_types::ContentBlock;
+use mcp_types::EmbeddedResourceResource;
+use mcp_types::GetPromptResult;
+use mcp_types::ReadResourceResultContents;
+
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_text;
+
+/// Upper bound on the bytes of resource contents returned for a single
+/// `Op::ReadMcpResource` and on the text of an expanded MCP prompt.
+pub(crate) const MAX_MCP_CONTENT_BYTES: usize = 256 * 1024;
+
+/// Keeps `contents` within `max_bytes`. Text entries are truncated in the
+/// middle; blobs that do not fit are dropped. Returns whether anything was
+/// cut.
+pub(crate) fn limit_resource_contents(
+    contents: Vec<ReadResourceResultContents>,
+    max_bytes: usize,
+) -> (Vec<ReadResourceResultContents>, bool) {
+    let mut remaining = max_bytes;
+    let mut truncated = false;
+    let mut limited = Vec::with_capacity(contents.len());
+    for entry in contents {
+        match entry {
+            ReadResourceResultContents::TextResourceContents(mut text) => {
+                if remaining == 0 {
+                    truncated = true;
+                    continue;
+                }
+                if text.text.len() > remaining {
+                    text.text = truncate_text(&text.text, TruncationPolicy::Bytes(remaining));
+                    truncated = true;
+                }
+                remaining = remaining.saturating_sub(text.text.len());
+                limited.push(ReadResourceResultContents::TextResourceContents(text));
+            }
+            ReadResourceResultContents::BlobResourceContents(blob) => {
+                if blob.blob.len() > remaining {
+                    truncated = true;
+                    continue;
+                }
+                remaining -= blob.blob.len();
+                limited.push(ReadResourceResultContents::BlobResourceContents(blob));
+            }
+        }
+    }
+    (limited, truncated)
+}
+
+/// Turns an expanded MCP prompt into user input. Text and embedded text
+/// resources are joined into one text item; images become image items.
+/// Audio, blobs, and resource links are referenced by a short placeholder.
+pub(crate) fn prompt_to_user_input(prompt: GetPromptResult) -> Vec<UserInput> {
+    let mut sections = Vec::new();
+    let mut images = Vec::new();
+    for message in prompt.messages {
+        match message.content {
+            ContentBlock::TextContent(text) => sections.push(text.text),
+            ContentBlock::ImageContent(image) => images.push(UserInput::Image {
+                image_url: format!("data:{};base64,{}", image.mime_type, image.data),
+            }),
+            ContentBlock::AudioContent(audio) => {
+                sections.push(format!("[audio omitted: {}]", audio.mime_type));
+            }
+            ContentBlock::ResourceLink(link) => {
+                sections.push(format!("[resource: {}]", link.uri));
+            }
+            ContentBlock::EmbeddedResource(embedded) => match embedded.resource {
+                EmbeddedResourceResource::TextResourceContents(text) => sections.push(text.text),
+                EmbeddedResourceResource::BlobResourceContents(blob) => {
+                    sections.push(format!("[resource: {}]", blob.uri));
+                }
+            },
+        }
+    }
+
+    let text = sections.join("\n\n");
+    let mut items = Vec::with_capacity(images.len() + 1);
+    if !text.is_empty() {
+        items.push(UserInput::Text {
+            text: truncate_text(&text, TruncationPolicy::Bytes(MAX_MCP_CONTENT_BYTES)),
+        });
+    }
+    items.extend(images);
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::BlobResourceContents;
+    use mcp_types::ImageContent;
+    use mcp_types::PromptMessage;
+    use mcp_types::Role;
+    use mcp_types::TextContent;
+    use mcp_types::TextResourceContents;
+    use pretty_assertions::assert_eq;
+
+    fn text_contents(text: &str) -> ReadResourceResultContents {
+        ReadResourceResultContents::TextResourceContents(TextResourceContents {
+            mime_type: None,
+            text: text.to_string(),
+            uri: "file:///a.txt".to_string(),
+        })
+    }
+
+    fn blob_contents(blob: &str) -> ReadResourceResultContents {
+        ReadResourceResultContents::BlobResourceContents(BlobResourceContents {
+            blob: blob.to_string(),
+            mime_type: None,
+            uri: "file:///a.bin".to_string(),
+        })
+    }
+
+    #[test]
+    fn limit_resource_contents_keeps_small_contents() {
+        let contents = vec![text_contents("hello"), blob_contents("aGk=")];
+
+        let (limited, truncated) = limit_resource_contents(contents.clone(), 64);
+
+        assert_eq!((contents, false), (limited, truncated));
+    }
+
+    #[test]
+    fn limit_resource_contents_drops_blobs_over_budget() {
+        let contents = vec![text_contents("hello"), blob_contents("0123456789")];
+
+        let (limited, truncated) = limit_resource_contents(contents, 8);
+
+        assert_eq!((vec![text_contents("hello")], true), (limited, truncated));
+    }
+
+    #[test]
+    fn prompt_to_user_input_joins_text_and_keeps_images() {
+        let prompt = GetPromptResult {
+            description: None,
+            messages: vec![
+                PromptMessage {
+                    content: ContentBlock::TextContent(TextContent {
+                        annotations: None,
+                        text: "Review this diff.".to_string(),
+                        r#type: "text".to_string(),
+                    }),
+                    role: Role::User,
+                },
+                PromptMessage {
+                    content: ContentBlock::ImageContent(ImageContent {
+                        annotations: None,
+                        data: "aGk=".to_string(),
+                        mime_type: "image/png".to_string(),
+                        r#type: "image".to_string(),
+                    }),
+                    role: Role::User,
+                },
+                PromptMessage {
+                    content: ContentBlock::EmbeddedResource(mcp_types::EmbeddedResource {
+                        annotations: None,
+                        resource: EmbeddedResourceResource::TextResourceContents(
+                            TextResourceContents {
+                                mime_type: None,
+                                text: "diff --git a/x b/x".to_string(),
+                                uri: "git://diff".to_string(),
+                            },
+                        ),
+                        r#type: "resource".to_string(),
+                    }),
+                    role: Role::User,
+                },
+            ],
+        };
+
+        assert_eq!(
+            vec![
+                UserInput::Text {
+                    text: "Review this diff.\n\ndiff --git a/x b/x".to_string(),
+                },
+                UserInput::Image {
+                    image_url: "data:image/png;base64,aGk=".to_string(),
+                },
+            ],
+            prompt_to_user_input(prompt)
+        );
+    }
+}
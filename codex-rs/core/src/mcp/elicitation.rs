@@ -0,0 +1,315 @@
+//! Routes MCP `elicitation/create` requests to the client as
+//! [`EventMsg::ElicitationRequest`] events and delivers the answers back to
+//! the waiting server.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::PoisonError;
+use std::sync::RwLock as StdRwLock;
+use std::time::Duration;
+
+use anyhow::Result;
+use anyhow::anyhow;
+use async_channel::Sender;
+use codex_protocol::protocol::ElicitationAction;
+use codex_protocol::protocol::ElicitationRequestEvent;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_rmcp_client::SendElicitation;
+use futures::FutureExt;
+use mcp_types::ElicitRequestParamsRequestedSchema;
+use mcp_types::ElicitResult;
+use mcp_types::RequestId;
+use serde_json::Value;
+use tokio::sync::Mutex;
+use tokio::sync::oneshot;
+
+use crate::codex::INITIAL_SUBMIT_ID;
+
+/// How long a server waits for the user before the request is declined.
+const ELICITATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+struct PendingElicitation {
+    /// The event that announced the request, re-sent when an answer fails
+    /// validation so the client can prompt again.
+    request: Event,
+    requested_schema: ElicitRequestParamsRequestedSchema,
+    responder: oneshot::Sender<ElicitResult>,
+    tx_event: Sender<Event>,
+}
+
+type PendingElicitations = HashMap<(String, RequestId), PendingElicitation>;
+
+/// Elicitations waiting for an answer, keyed by server name and the
+/// server's request id.
+#[derive(Clone, Default)]
+pub(crate) struct ElicitationRequestManager {
+    pending: Arc<Mutex<PendingElicitations>>,
+}
+
+impl ElicitationRequestManager {
+    /// Builds the callback handed to the MCP client for `server_name`.
+    pub(crate) fn make_sender(
+        &self,
+        server_name: String,
+        tx_event: Sender<Event>,
+    ) -> SendElicitation {
+        let pending = Arc::clone(&self.pending);
+        Box::new(move |id, params| {
+            let pending = Arc::clone(&pending);
+            let tx_event = tx_event.clone();
+            let server_name = server_name.clone();
+            async move {
+                let key = (server_name.clone(), id.clone());
+                let (responder, response) = oneshot::channel();
+                let event = Event {
+                    id: INITIAL_SUBMIT_ID.to_owned(),
+                    msg: EventMsg::ElicitationRequest(ElicitationRequestEvent {
+                        server_name,
+                        id,
+                        message: params.message,
+                        requested_schema: params.requested_schema.clone(),
+                    }),
+                };
+                pending.lock().await.insert(
+                    key.clone(),
+                    PendingElicitation {
+                        request: event.clone(),
+                        requested_schema: params.requested_schema,
+                        responder,
+                        tx_event: tx_event.clone(),
+                    },
+                );
+                if tx_event.send(event).await.is_err() {
+                    pending.lock().await.remove(&key);
+                    return Err(anyhow!("no client is listening for elicitation requests"));
+                }
+                match tokio::time::timeout(ELICITATION_TIMEOUT, response).await {
+                    Ok(Ok(result)) => Ok(result),
+                    Ok(Err(_)) => Err(anyhow!("elicitation request was dropped")),
+                    Err(_) => {
+                        pending.lock().await.remove(&key);
+                        Err(anyhow!(
+                            "elicitation timed out after {ELICITATION_TIMEOUT:?}"
+                        ))
+                    }
+                }
+            }
+            .boxed()
+        })
+    }
+
+    /// Delivers the user's answer. An accepted answer whose `content` does
+    /// not satisfy the requested schema is rejected; the request stays
+    /// pending and is announced again so the user can retry.
+    pub(crate) async fn resolve(
+        &self,
+        server_name: String,
+        id: RequestId,
+        decision: ElicitationAction,
+        content: Option<Value>,
+    ) -> Result<()> {
+        let mut pending = self.pending.lock().await;
+        let key = (server_name, id);
+        let Some(request) = pending.get(&key) else {
+            return Err(anyhow!(
+                "no pending elicitation {:?} for MCP server '{}'",
+                key.1,
+                key.0
+            ));
+        };
+        let content = match decision {
+            ElicitationAction::Accept => {
+                let content = content.unwrap_or_else(|| Value::Object(Default::default()));
+                if let Err(error) =
+                    validate_elicitation_content(&request.requested_schema, &content)
+                {
+                    let _ = request.tx_event.send(request.request.clone()).await;
+                    return Err(anyhow!("invalid elicitation response: {error}"));
+                }
+                Some(content)
+            }
+            ElicitationAction::Decline | ElicitationAction::Cancel => None,
+        };
+        if let Some(request) = pending.remove(&key) {
+            let _ = request.responder.send(ElicitResult {
+                action: decision.to_string(),
+                content,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The elicitation callback a server was started with, made swappable so a
+/// server kept in the warm pool asks the user of whichever conversation is
+/// using it now rather than the one that started it.
+#[derive(Clone)]
+pub(crate) struct ElicitationRoute {
+    sender: Arc<StdRwLock<SendElicitation>>,
+}
+
+impl ElicitationRoute {
+    pub(crate) fn new(sender: SendElicitation) -> Self {
+        Self {
+            sender: Arc::new(StdRwLock::new(sender)),
+        }
+    }
+
+    pub(crate) fn set(&self, sender: SendElicitation) {
+        *self.sender.write().unwrap_or_else(PoisonError::into_inner) = sender;
+    }
+
+    /// The callback to hand to the MCP client; it forwards to whichever
+    /// sender is current when a request arrives.
+    pub(crate) fn forwarder(&self) -> SendElicitation {
+        let sender = Arc::clone(&self.sender);
+        Box::new(move |id, params| {
+            let sender = sender.read().unwrap_or_else(PoisonError::into_inner);
+            (*sender)(id, params)
+        })
+    }
+}
+
+/// Checks `content` against the flat, primitive-typed object schema that MCP
+/// elicitations are limited to.
+fn validate_elicitation_content(
+    schema: &ElicitRequestParamsRequestedSchema,
+    content: &Value,
+) -> Result<(), String> {
+    let Some(content) = content.as_object() else {
+        return Err("content must be an object".to_string());
+    };
+    let properties = schema.properties.as_object();
+    for name in schema.required.iter().flatten() {
+        if !content.contains_key(name) {
+            return Err(format!("missing required field `{name}`"));
+        }
+    }
+    for (name, value) in content {
+        let Some(property) = properties.and_then(|properties| properties.get(name)) else {
+            return Err(format!("unexpected field `{name}`"));
+        };
+        let type_matches = match property.get("type").and_then(Value::as_str) {
+            Some("string") => value.is_string(),
+            Some("number") => value.is_number(),
+            Some("integer") => value.is_i64() || value.is_u64(),
+            Some("boolean") => value.is_boolean(),
+            _ => true,
+        };
+        if !type_matches {
+            return Err(format!("field `{name}` has the wrong type"));
+        }
+        if let Some(options) = property.get("enum").and_then(Value::as_array)
+            && !options.contains(value)
+        {
+            return Err(format!("field `{name}` must be one of the allowed values"));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn schema() -> ElicitRequestParamsRequestedSchema {
+        ElicitRequestParamsRequestedSchema {
+            properties: json!({
+                "name": { "type": "string" },
+                "count": { "type": "integer" },
+                "color": { "type": "string", "enum": ["red", "blue"] },
+            }),
+            required: Some(vec!["name".to_string()]),
+            r#type: "object".to_string(),
+        }
+    }
+
+    #[test]
+    fn validate_accepts_matching_content() {
+        let content = json!({ "name": "codex", "count": 2, "color": "red" });
+        assert_eq!(Ok(()), validate_elicitation_content(&schema(), &content));
+    }
+
+    #[test]
+    fn validate_rejects_bad_content() {
+        let cases = [
+            (json!("codex"), "content must be an object"),
+            (json!({ "count": 2 }), "missing required field `name`"),
+            (
+                json!({ "name": "codex", "age": 3 }),
+                "unexpected field `age`",
+            ),
+            (
+                json!({ "name": "codex", "count": "2" }),
+                "field `count` has the wrong type",
+            ),
+            (
+                json!({ "name": "codex", "color": "green" }),
+                "field `color` must be one of the allowed values",
+            ),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(
+                Err(expected.to_string()),
+                validate_elicitation_content(&schema(), &content)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn resolve_delivers_answer_to_waiting_server() {
+        let manager = ElicitationRequestManager::default();
+        let (tx_event, rx_event) = async_channel::unbounded();
+        let send = manager.make_sender("docs".to_string(), tx_event);
+        let waiting = send(
+            RequestId::Integer(7),
+            mcp_types::ElicitRequestParams {
+                message: "Who are you?".to_string(),
+                requested_schema: schema(),
+            },
+        );
+        let waiting = tokio::spawn(waiting);
+
+        let event = rx_event.recv().await.expect("elicitation event");
+        let EventMsg::ElicitationRequest(request) = event.msg.clone() else {
+            panic!("expected ElicitationRequest, got {:?}", event.msg);
+        };
+        assert!(
+            manager
+                .resolve(
+                    request.server_name.clone(),
+                    request.id.clone(),
+                    ElicitationAction::Accept,
+                    Some(json!({ "count": 1 })),
+                )
+                .await
+                .is_err()
+        );
+        let reprompt = rx_event.recv().await.expect("repeated elicitation event");
+        assert_eq!(
+            serde_json::to_value(&event.msg).expect("serialize"),
+            serde_json::to_value(&reprompt.msg).expect("serialize")
+        );
+        manager
+            .resolve(
+                request.server_name,
+                request.id,
+                ElicitationAction::Accept,
+                Some(json!({ "name": "codex" })),
+            )
+            .await
+            .expect("valid answer");
+
+        let result = waiting.await.expect("join").expect("answer");
+        assert_eq!(
+            ElicitResult {
+                action: "accept".to_string(),
+                content: Some(json!({ "name": "codex" })),
+            },
+            result
+        );
+    }
+}
@@ -1 +1,7 @@
 pub mod auth;
+pub(crate) mod content;
+pub(crate) mod elicitation;
+pub(crate) mod roots;
+pub(crate) mod sampling;
+pub(crate) mod tool_cache;
+pub(crate) mod warm_pool;
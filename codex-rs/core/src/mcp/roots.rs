@@ -0,0 +1,92 @@
+//! Answers MCP `roots/list` requests with the conversation's workspace: its
+//! working directory followed by any roots added with `/add-dir`.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use mcp_types::Root;
+use tokio::sync::watch;
+use url::Url;
+
+/// One server's view of the workspace roots. Each new list is pushed to the
+/// server as `notifications/roots/list_changed`; like the other routes it
+/// is re-pointed when a server from the warm pool joins a conversation.
+#[derive(Clone)]
+pub(crate) struct RootsRoute {
+    roots: Arc<watch::Sender<Vec<Root>>>,
+}
+
+impl RootsRoute {
+    pub(crate) fn new(paths: &[PathBuf]) -> Self {
+        let (roots, _) = watch::channel(to_roots(paths));
+        Self {
+            roots: Arc::new(roots),
+        }
+    }
+
+    /// Replaces the roots, notifying the server only if they changed.
+    pub(crate) fn set(&self, paths: &[PathBuf]) {
+        let roots = to_roots(paths);
+        self.roots.send_if_modified(|current| {
+            if *current == roots {
+                return false;
+            }
+            *current = roots;
+            true
+        });
+    }
+
+    pub(crate) fn subscribe(&self) -> watch::Receiver<Vec<Root>> {
+        self.roots.subscribe()
+    }
+}
+
+/// Paths that cannot be written as a `file://` URL are left out.
+fn to_roots(paths: &[PathBuf]) -> Vec<Root> {
+    paths.iter().filter_map(|path| to_root(path)).collect()
+}
+
+fn to_root(path: &Path) -> Option<Root> {
+    let uri = Url::from_directory_path(path).ok()?;
+    Some(Root {
+        name: path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned()),
+        uri: uri.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn roots_are_directory_urls_named_after_the_directory() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let project = dir.path().join("my project");
+        let root = to_root(&project).expect("root");
+
+        assert_eq!(Some("my project".to_string()), root.name);
+        assert!(root.uri.starts_with("file://"), "{}", root.uri);
+        assert!(root.uri.ends_with("/my%20project/"), "{}", root.uri);
+        assert_eq!(None, to_root(Path::new("relative")));
+    }
+
+    #[test]
+    fn set_notifies_only_when_the_roots_change() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let first = dir.path().join("a");
+        let second = dir.path().join("b");
+        let route = RootsRoute::new(std::slice::from_ref(&first));
+        let mut roots = route.subscribe();
+
+        route.set(std::slice::from_ref(&first));
+        assert!(!roots.has_changed().expect("open"));
+
+        route.set(&[first, second]);
+        assert!(roots.has_changed().expect("open"));
+        assert_eq!(2, roots.borrow_and_update().len());
+    }
+}
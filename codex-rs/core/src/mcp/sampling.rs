@@ -0,0 +1,389 @@
+//! Answers MCP `sampling/createMessage` requests with the conversation's
+//! model.
+//!
+//! A server is only offered sampling when its config has a `sampling` table.
+//! Unless that table sets `approval = "auto"`, each request is first shown to
+//! the user as an elicitation to accept or decline. The server's messages are
+//! sent to the model on their own; nothing from the conversation is included.
+
+use std::sync::Arc;
+use std::sync::PoisonError;
+use std::sync::RwLock as StdRwLock;
+
+use anyhow::Result;
+use anyhow::anyhow;
+use anyhow::bail;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ElicitationAction;
+use codex_rmcp_client::SendElicitation;
+use codex_rmcp_client::SendSamplingRequest;
+use codex_utils_string::take_bytes_at_char_boundary;
+use futures::FutureExt;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::CreateMessageResultContent;
+use mcp_types::ElicitRequestParams;
+use mcp_types::ElicitRequestParamsRequestedSchema;
+use mcp_types::ModelPreferences;
+use mcp_types::RequestId;
+use mcp_types::Role;
+use mcp_types::SamplingMessageContent;
+use mcp_types::TextContent;
+use serde_json::json;
+
+use crate::client::ModelClient;
+use crate::client_common::Prompt;
+use crate::config::types::McpSamplingApproval;
+use crate::config::types::McpSamplingConfig;
+use crate::mcp::elicitation::ElicitationRoute;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::approx_token_count;
+use crate::turn_commit::request_message;
+
+/// Used when the server sends no system prompt, in place of Codex's own
+/// coding instructions.
+const DEFAULT_SAMPLING_INSTRUCTIONS: &str =
+    "You are a helpful assistant. Reply to the last message in the conversation.";
+
+/// The model client of the conversation's latest turn, which sampling
+/// requests are sent with.
+#[derive(Clone, Default)]
+pub(crate) struct SamplingModel {
+    client: Arc<StdRwLock<Option<ModelClient>>>,
+}
+
+impl SamplingModel {
+    pub(crate) fn set(&self, client: ModelClient) {
+        *self.client.write().unwrap_or_else(PoisonError::into_inner) = Some(client);
+    }
+
+    fn client(&self) -> Option<ModelClient> {
+        self.client
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+/// One server's way to the model, swappable like [`ElicitationRoute`] so a
+/// server from the warm pool samples with the conversation now using it.
+#[derive(Clone)]
+pub(crate) struct SamplingRoute {
+    model: Arc<StdRwLock<SamplingModel>>,
+}
+
+impl SamplingRoute {
+    pub(crate) fn new(model: SamplingModel) -> Self {
+        Self {
+            model: Arc::new(StdRwLock::new(model)),
+        }
+    }
+
+    pub(crate) fn set(&self, model: SamplingModel) {
+        *self.model.write().unwrap_or_else(PoisonError::into_inner) = model;
+    }
+
+    /// The callback to hand to the MCP client for `server_name`.
+    pub(crate) fn sender(
+        &self,
+        server_name: String,
+        config: McpSamplingConfig,
+        elicitation: &ElicitationRoute,
+    ) -> SendSamplingRequest {
+        let model = Arc::clone(&self.model);
+        let ask = Arc::new(elicitation.forwarder());
+        let server_name = Arc::new(server_name);
+        let config = Arc::new(config);
+        Box::new(move |id, params| {
+            let client = model
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .client();
+            let ask = Arc::clone(&ask);
+            let server_name = Arc::clone(&server_name);
+            let config = Arc::clone(&config);
+            async move { sample(&server_name, &config, client, &ask, id, params).await }.boxed()
+        })
+    }
+}
+
+async fn sample(
+    server_name: &str,
+    config: &McpSamplingConfig,
+    client: Option<ModelClient>,
+    ask: &SendElicitation,
+    id: RequestId,
+    params: CreateMessageRequestParams,
+) -> Result<CreateMessageResult> {
+    let client = client.ok_or_else(|| anyhow!("no model is available for sampling yet"))?;
+    let main_model = client.get_model();
+    let model = choose_model(config, &main_model, params.model_preferences.as_ref());
+    let prompt = build_prompt(&params)?;
+
+    if config.approval == McpSamplingApproval::Ask {
+        let answer = ask(id, approval_request(server_name, &model, &params)).await?;
+        if answer.action != ElicitationAction::Accept.to_string() {
+            bail!("the user declined the sampling request");
+        }
+    }
+
+    let client = if model == main_model {
+        client
+    } else {
+        client.for_model(&model)
+    };
+    let text = request_message(&client, &prompt)
+        .await?
+        .ok_or_else(|| anyhow!("the model returned no message"))?;
+    let max_tokens = config.max_tokens.map_or(params.max_tokens, |limit| {
+        params.max_tokens.min(i64::from(limit))
+    });
+    let (text, truncated) = cap_tokens(text, max_tokens);
+    Ok(CreateMessageResult {
+        content: CreateMessageResultContent::TextContent(TextContent {
+            annotations: None,
+            text,
+            r#type: "text".to_string(),
+        }),
+        model,
+        role: Role::Assistant,
+        stop_reason: Some(if truncated { "maxTokens" } else { "endTurn" }.to_string()),
+    })
+}
+
+/// With an allowlist, the first allowed model a hint names, else the
+/// conversation's model if allowed, else the first allowed model. Without
+/// one, always the conversation's model.
+fn choose_model(
+    config: &McpSamplingConfig,
+    main_model: &str,
+    preferences: Option<&ModelPreferences>,
+) -> String {
+    let Some(allowed) = config.models.as_ref().filter(|models| !models.is_empty()) else {
+        return main_model.to_string();
+    };
+    let hints = preferences
+        .and_then(|preferences| preferences.hints.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|hint| hint.name.as_deref());
+    for hint in hints {
+        if let Some(model) = allowed.iter().find(|model| model.contains(hint)) {
+            return model.clone();
+        }
+    }
+    if allowed.iter().any(|model| model == main_model) {
+        return main_model.to_string();
+    }
+    allowed
+        .first()
+        .cloned()
+        .unwrap_or_else(|| main_model.to_string())
+}
+
+fn build_prompt(params: &CreateMessageRequestParams) -> Result<Prompt> {
+    let mut input = Vec::with_capacity(params.messages.len());
+    for message in &params.messages {
+        let content = match (&message.role, &message.content) {
+            (Role::User, SamplingMessageContent::TextContent(text)) => ContentItem::InputText {
+                text: text.text.clone(),
+            },
+            (Role::Assistant, SamplingMessageContent::TextContent(text)) => {
+                ContentItem::OutputText {
+                    text: text.text.clone(),
+                }
+            }
+            (Role::User, SamplingMessageContent::ImageContent(image)) => ContentItem::InputImage {
+                image_url: format!("data:{};base64,{}", image.mime_type, image.data),
+            },
+            (Role::Assistant, SamplingMessageContent::ImageContent(_)) => {
+                bail!("assistant images are not supported in sampling requests")
+            }
+            (_, SamplingMessageContent::AudioContent(_)) => {
+                bail!("audio is not supported in sampling requests")
+            }
+        };
+        let role = match message.role {
+            Role::User => "user",
+            Role::Assistant => "assistant",
+        };
+        input.push(ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![content],
+        });
+    }
+    Ok(Prompt {
+        input,
+        base_instructions_override: Some(
+            params
+                .system_prompt
+                .clone()
+                .unwrap_or_else(|| DEFAULT_SAMPLING_INSTRUCTIONS.to_string()),
+        ),
+        ..Default::default()
+    })
+}
+
+/// The elicitation shown to the user before a request runs: which server is
+/// asking, the model, and the last message it wants answered.
+fn approval_request(
+    server_name: &str,
+    model: &str,
+    params: &CreateMessageRequestParams,
+) -> ElicitRequestParams {
+    let last_text = params
+        .messages
+        .iter()
+        .rev()
+        .find_map(|message| match &message.content {
+            SamplingMessageContent::TextContent(text) => Some(text.text.as_str()),
+            _ => None,
+        })
+        .unwrap_or_default();
+    let (excerpt, _) = cap_tokens(last_text.to_string(), 200);
+    ElicitRequestParams {
+        message: format!(
+            "MCP server '{server_name}' wants to ask {model} (up to {} tokens):\n\n{excerpt}",
+            params.max_tokens
+        ),
+        requested_schema: ElicitRequestParamsRequestedSchema {
+            properties: json!({}),
+            required: None,
+            r#type: "object".to_string(),
+        },
+    }
+}
+
+/// Cuts `text` to about `max_tokens` tokens. Returns whether it was cut.
+fn cap_tokens(text: String, max_tokens: i64) -> (String, bool) {
+    let max_tokens = usize::try_from(max_tokens).unwrap_or(0);
+    if approx_token_count(&text) <= max_tokens {
+        return (text, false);
+    }
+    let max_bytes = TruncationPolicy::Tokens(max_tokens).byte_budget();
+    (
+        take_bytes_at_char_boundary(&text, max_bytes).to_string(),
+        true,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::ModelHint;
+    use mcp_types::SamplingMessage;
+    use pretty_assertions::assert_eq;
+
+    fn config(models: &[&str]) -> McpSamplingConfig {
+        McpSamplingConfig {
+            models: Some(models.iter().map(ToString::to_string).collect()),
+            ..Default::default()
+        }
+    }
+
+    fn hints(names: &[&str]) -> ModelPreferences {
+        ModelPreferences {
+            cost_priority: None,
+            hints: Some(
+                names
+                    .iter()
+                    .map(|name| ModelHint {
+                        name: Some(name.to_string()),
+                    })
+                    .collect(),
+            ),
+            intelligence_priority: None,
+            speed_priority: None,
+        }
+    }
+
+    fn text(role: Role, text: &str) -> SamplingMessage {
+        SamplingMessage {
+            content: SamplingMessageContent::TextContent(TextContent {
+                annotations: None,
+                text: text.to_string(),
+                r#type: "text".to_string(),
+            }),
+            role,
+        }
+    }
+
+    #[test]
+    fn choose_model_stays_within_the_allowlist() {
+        let main = "gpt-5";
+        assert_eq!(
+            "gpt-5",
+            choose_model(&McpSamplingConfig::default(), main, Some(&hints(&["mini"])))
+        );
+        assert_eq!(
+            "gpt-5-mini",
+            choose_model(
+                &config(&["gpt-5", "gpt-5-mini"]),
+                main,
+                Some(&hints(&["claude", "mini"]))
+            )
+        );
+        assert_eq!(
+            "gpt-5",
+            choose_model(&config(&["gpt-5-mini", "gpt-5"]), main, None)
+        );
+        assert_eq!(
+            "gpt-5-mini",
+            choose_model(&config(&["gpt-5-mini"]), main, Some(&hints(&["opus"])))
+        );
+    }
+
+    #[test]
+    fn build_prompt_maps_roles_and_uses_the_server_system_prompt() {
+        let params = CreateMessageRequestParams {
+            include_context: None,
+            max_tokens: 100,
+            messages: vec![text(Role::User, "hi"), text(Role::Assistant, "hello")],
+            metadata: None,
+            model_preferences: None,
+            stop_sequences: None,
+            system_prompt: Some("Be brief.".to_string()),
+            temperature: None,
+        };
+
+        let prompt = build_prompt(&params).expect("prompt");
+
+        assert_eq!(
+            Some("Be brief.".to_string()),
+            prompt.base_instructions_override
+        );
+        assert_eq!(
+            vec![
+                ResponseItem::Message {
+                    id: None,
+                    role: "user".to_string(),
+                    content: vec![ContentItem::InputText {
+                        text: "hi".to_string()
+                    }],
+                },
+                ResponseItem::Message {
+                    id: None,
+                    role: "assistant".to_string(),
+                    content: vec![ContentItem::OutputText {
+                        text: "hello".to_string()
+                    }],
+                },
+            ],
+            prompt.input
+        );
+    }
+
+    #[test]
+    fn cap_tokens_cuts_long_completions() {
+        assert_eq!(
+            ("short".to_string(), false),
+            cap_tokens("short".to_string(), 10)
+        );
+        assert_eq!(
+            ("abcdefgh".to_string(), true),
+            cap_tokens("abcdefghijkl".to_string(), 2)
+        );
+    }
+}
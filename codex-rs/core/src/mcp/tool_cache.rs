@@ -0,0 +1,178 @@
+//! Tool lists remembered from each MCP server's last run.
+//!
+//! The list a server reported the last time it ran is stored under
+//! `~/.codex/cache/mcp_tools`, tagged with the server's name and version from
+//! its `initialize` result. When the same version starts again its cached list
+//! is used right away and checked against the server in the background,
+//! saving a `tools/list` round trip at startup. A lazy server, which is not
+//! started until the model calls one of its tools, is offered from the cache
+//! before it has reported a version at all.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use mcp_types::Tool;
+use serde::Deserialize;
+use serde::Serialize;
+use sha1::Digest;
+use sha1::Sha1;
+use tracing::warn;
+
+use crate::config::types::McpServerTransportConfig;
+
+const TOOL_CACHE_DIR: &str = "cache/mcp_tools";
+
+#[derive(Serialize, Deserialize)]
+struct ToolCacheEntry {
+    fingerprint: String,
+    #[serde(default)]
+    server_version: Option<String>,
+    tools: Vec<Tool>,
+}
+
+/// A tool list read back from the cache.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CachedTools {
+    /// `<name>@<version>` of the server that reported the list.
+    pub(crate) server_version: Option<String>,
+    pub(crate) tools: Vec<Tool>,
+}
+
+/// The cached tool list of one server.
+#[derive(Debug, Clone)]
+pub(crate) struct McpToolCache {
+    path: PathBuf,
+    fingerprint: String,
+}
+
+impl McpToolCache {
+    /// `server_name` must already be a valid server name, since it becomes
+    /// the file name.
+    pub(crate) fn new(
+        codex_home: &Path,
+        server_name: &str,
+        transport: &McpServerTransportConfig,
+    ) -> Self {
+        Self {
+            path: codex_home
+                .join(TOOL_CACHE_DIR)
+                .join(format!("{server_name}.json")),
+            fingerprint: fingerprint(transport),
+        }
+    }
+
+    /// The tools stored for this server, or `None` when there are none or
+    /// they were recorded for a different command or URL.
+    pub(crate) async fn load(&self) -> Option<CachedTools> {
+        let contents = tokio::fs::read(&self.path).await.ok()?;
+        match serde_json::from_slice::<ToolCacheEntry>(&contents) {
+            Ok(entry) if entry.fingerprint == self.fingerprint => Some(CachedTools {
+                server_version: entry.server_version,
+                tools: entry.tools,
+            }),
+            Ok(_) => None,
+            Err(err) => {
+                warn!(
+                    "ignoring unreadable MCP tool cache {}: {err}",
+                    self.path.display()
+                );
+                None
+            }
+        }
+    }
+
+    pub(crate) async fn store(&self, server_version: &str, tools: &[Tool]) {
+        let entry = ToolCacheEntry {
+            fingerprint: self.fingerprint.clone(),
+            server_version: Some(server_version.to_string()),
+            tools: tools.to_vec(),
+        };
+        let result = async {
+            if let Some(parent) = self.path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            let contents = serde_json::to_vec(&entry).map_err(std::io::Error::other)?;
+            tokio::fs::write(&self.path, contents).await
+        }
+        .await;
+        if let Err(err) = result {
+            warn!(
+                "failed to write MCP tool cache {}: {err}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// Identifies the program a server runs, so a cached list is not offered for
+/// a different server that reuses the name.
+fn fingerprint(transport: &McpServerTransportConfig) -> String {
+    let mut hasher = Sha1::new();
+    match transport {
+        McpServerTransportConfig::Stdio { command, args, .. } => {
+            hasher.update(b"stdio\0");
+            hasher.update(command.as_bytes());
+            for arg in args {
+                hasher.update(b"\0");
+                hasher.update(arg.as_bytes());
+            }
+        }
+        McpServerTransportConfig::StreamableHttp { url, .. } => {
+            hasher.update(b"streamable_http\0");
+            hasher.update(url.as_bytes());
+        }
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::ToolInputSchema;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn stdio(command: &str) -> McpServerTransportConfig {
+        McpServerTransportConfig::Stdio {
+            command: command.to_string(),
+            args: vec!["--stdio".to_string()],
+            env: None,
+            env_vars: Vec::new(),
+            cwd: None,
+        }
+    }
+
+    fn tool(name: &str) -> Tool {
+        Tool {
+            annotations: None,
+            description: Some(format!("{name} tool")),
+            input_schema: ToolInputSchema {
+                properties: None,
+                required: None,
+                r#type: "object".to_string(),
+            },
+            name: name.to_string(),
+            output_schema: None,
+            title: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn stored_tools_load_for_the_same_command_only() {
+        let home = tempdir().expect("tempdir");
+        let cache = McpToolCache::new(home.path(), "docs", &stdio("docs-server"));
+        assert_eq!(cache.load().await, None);
+
+        cache.store("docs@1.0.0", &[tool("search")]).await;
+        assert_eq!(
+            cache.load().await,
+            Some(CachedTools {
+                server_version: Some("docs@1.0.0".to_string()),
+                tools: vec![tool("search")],
+            })
+        );
+
+        let other = McpToolCache::new(home.path(), "docs", &stdio("other-server"));
+        assert_eq!(other.load().await, None);
+    }
+}
@@ -0,0 +1,154 @@
+//! Started MCP servers kept running between conversations.
+//!
+//! When a conversation ends, servers configured with `warm_pool = true` are
+//! checked in here instead of being stopped. The next conversation in the
+//! same process that configures a server with the same name and settings
+//! checks it out and skips the startup handshake. A server nobody checks out
+//! is dropped, which stops it, once its keep-alive period passes.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use std::time::Duration;
+
+use crate::config::types::McpServerConfig;
+
+struct PoolEntry<T> {
+    id: u64,
+    server_name: String,
+    config: McpServerConfig,
+    value: T,
+}
+
+struct PoolState<T> {
+    next_id: u64,
+    entries: Vec<PoolEntry<T>>,
+}
+
+pub(crate) struct WarmPool<T> {
+    state: Mutex<PoolState<T>>,
+}
+
+impl<T: Send + 'static> WarmPool<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            state: Mutex::new(PoolState {
+                next_id: 0,
+                entries: Vec::new(),
+            }),
+        }
+    }
+
+    /// Keeps `value` for up to `keep_for`. Without a Tokio runtime to expire
+    /// the entry, `value` is dropped right away instead.
+    pub(crate) fn check_in(
+        self: &Arc<Self>,
+        server_name: String,
+        config: McpServerConfig,
+        value: T,
+        keep_for: Duration,
+    ) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let id = {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            let id = state.next_id;
+            state.next_id += 1;
+            state.entries.push(PoolEntry {
+                id,
+                server_name,
+                config,
+                value,
+            });
+            id
+        };
+        let pool = Arc::downgrade(self);
+        runtime.spawn(async move {
+            tokio::time::sleep(keep_for).await;
+            if let Some(pool) = pool.upgrade() {
+                pool.remove(id);
+            }
+        });
+    }
+
+    /// Takes a value checked in for `server_name` with exactly `config`.
+    pub(crate) fn check_out(&self, server_name: &str, config: &McpServerConfig) -> Option<T> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let index = state
+            .entries
+            .iter()
+            .position(|entry| entry.server_name == server_name && entry.config == *config)?;
+        Some(state.entries.swap_remove(index).value)
+    }
+
+    fn remove(&self, id: u64) {
+        let expired = {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            let index = state.entries.iter().position(|entry| entry.id == id);
+            index.map(|index| state.entries.swap_remove(index))
+        };
+        // Dropped outside the lock, since dropping a client stops its server.
+        drop(expired);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::McpServerStartup;
+    use crate::config::types::McpServerTransportConfig;
+    use pretty_assertions::assert_eq;
+
+    fn config(command: &str) -> McpServerConfig {
+        McpServerConfig {
+            transport: McpServerTransportConfig::Stdio {
+                command: command.to_string(),
+                args: Vec::new(),
+                env: None,
+                env_vars: Vec::new(),
+                cwd: None,
+            },
+            enabled: true,
+            startup_timeout_sec: None,
+            tool_timeout_sec: None,
+            enabled_tools: None,
+            disabled_tools: None,
+            startup: McpServerStartup::Eager,
+            idle_timeout_sec: None,
+            warm_pool: true,
+            tool_aliases: None,
+            sampling: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn check_out_requires_matching_name_and_config() {
+        let pool = Arc::new(WarmPool::new());
+        pool.check_in(
+            "docs".to_string(),
+            config("docs-server"),
+            1,
+            Duration::from_secs(60),
+        );
+
+        assert_eq!(pool.check_out("other", &config("docs-server")), None);
+        assert_eq!(pool.check_out("docs", &config("changed")), None);
+        assert_eq!(pool.check_out("docs", &config("docs-server")), Some(1));
+        assert_eq!(pool.check_out("docs", &config("docs-server")), None);
+    }
+
+    #[tokio::test]
+    async fn entries_expire_after_keep_alive() {
+        let pool = Arc::new(WarmPool::new());
+        pool.check_in(
+            "docs".to_string(),
+            config("docs-server"),
+            1,
+            Duration::from_millis(10),
+        );
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(pool.check_out("docs", &config("docs-server")), None);
+    }
+}
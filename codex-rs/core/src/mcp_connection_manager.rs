@@ -10,48 +10,84 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::env;
 use std::ffi::OsString;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex as StdMutex;
+use std::sync::PoisonError;
+use std::sync::RwLock as StdRwLock;
+use std::sync::Weak;
 use std::time::Duration;
+use std::time::Instant;
 
 use crate::mcp::auth::McpAuthStatusEntry;
+use crate::mcp::elicitation::ElicitationRequestManager;
+use crate::mcp::elicitation::ElicitationRoute;
+use crate::mcp::roots::RootsRoute;
+use crate::mcp::sampling::SamplingModel;
+use crate::mcp::sampling::SamplingRoute;
+use crate::mcp::tool_cache::CachedTools;
+use crate::mcp::tool_cache::McpToolCache;
+use crate::mcp::warm_pool::WarmPool;
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
 use async_channel::Sender;
 use codex_async_utils::CancelErr;
 use codex_async_utils::OrCancelExt;
+use codex_protocol::protocol::ElicitationAction;
 use codex_protocol::protocol::Event;
 use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::McpServerStatus;
+use codex_protocol::protocol::McpServerStatusChangedEvent;
 use codex_protocol::protocol::McpStartupCompleteEvent;
 use codex_protocol::protocol::McpStartupFailure;
 use codex_protocol::protocol::McpStartupStatus;
 use codex_protocol::protocol::McpStartupUpdateEvent;
+use codex_protocol::protocol::McpToolConflict;
+use codex_protocol::protocol::McpToolConflictsEvent;
+use codex_protocol::protocol::McpToolRef;
+use codex_protocol::protocol::ToolsChangedEvent;
+use codex_rmcp_client::McpProgress;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
 use codex_rmcp_client::RmcpClient;
+use codex_rmcp_client::SendElicitation;
+use codex_rmcp_client::SendSamplingRequest;
 use futures::future::BoxFuture;
 use futures::future::FutureExt;
 use futures::future::Shared;
 use mcp_types::ClientCapabilities;
+use mcp_types::ClientCapabilitiesRoots;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::Implementation;
+use mcp_types::ListPromptsRequestParams;
 use mcp_types::ListResourceTemplatesRequestParams;
 use mcp_types::ListResourceTemplatesResult;
 use mcp_types::ListResourcesRequestParams;
 use mcp_types::ListResourcesResult;
+use mcp_types::Prompt;
 use mcp_types::ReadResourceRequestParams;
 use mcp_types::ReadResourceResult;
 use mcp_types::Resource;
 use mcp_types::ResourceTemplate;
+use mcp_types::Root;
 use mcp_types::Tool;
 
 use serde_json::json;
 use sha1::Digest;
 use sha1::Sha1;
+use tokio::sync::Mutex;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::watch;
 use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
 use tracing::warn;
 
 use crate::codex::INITIAL_SUBMIT_ID;
 use crate::config::types::McpServerConfig;
+use crate::config::types::McpServerStartup;
 use crate::config::types::McpServerTransportConfig;
 
 /// Delimiter used to separate the server name from the tool name in a fully
@@ -68,16 +104,56 @@ pub const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
 /// Default timeout for individual tool calls.
 const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// How often the health monitor pings each running server.
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long a single health-check ping may take before it counts as a miss.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Missed pings in a row before a server is reported unhealthy and, for stdio
+/// servers, restarted.
+const MAX_CONSECUTIVE_PING_FAILURES: i32 = 3;
+
+/// Restart attempts before the health monitor gives up on a server.
+const MAX_RESTART_ATTEMPTS: i32 = 5;
+
+const RESTART_BACKOFF_INITIAL: Duration = Duration::from_secs(1);
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(60);
+
+/// How long a server in the warm pool waits for the next conversation when
+/// it has no `idle_timeout_sec`.
+const DEFAULT_WARM_POOL_KEEP_ALIVE: Duration = Duration::from_secs(600);
+
+static WARM_POOL: LazyLock<Arc<WarmPool<PooledServer>>> =
+    LazyLock::new(|| Arc::new(WarmPool::new()));
+
 fn qualify_tools<I>(tools: I) -> HashMap<String, ToolInfo>
 where
     I: IntoIterator<Item = ToolInfo>,
 {
-    let mut used_names = HashSet::new();
-    let mut qualified_tools = HashMap::new();
+    resolve_tool_names(tools).0
+}
+
+/// Gives every tool its `mcp__<server>__<tool>` name, using the tool's alias
+/// in place of its own name when it has one. Tools are named in order of
+/// server name, then tool name, so when two tools end up with the same name
+/// the same one always keeps it; the others are left out and returned as
+/// conflicts.
+fn resolve_tool_names<I>(tools: I) -> (HashMap<String, ToolInfo>, Vec<McpToolConflict>)
+where
+    I: IntoIterator<Item = ToolInfo>,
+{
+    let mut tools: Vec<ToolInfo> = tools.into_iter().collect();
+    tools.sort_by(|a, b| (&a.server_name, &a.tool_name).cmp(&(&b.server_name, &b.tool_name)));
+    let mut qualified_tools: HashMap<String, ToolInfo> = HashMap::new();
+    let mut conflicts: Vec<McpToolConflict> = Vec::new();
     for tool in tools {
         let mut qualified_name = format!(
             "mcp{}{}{}{}",
-            MCP_TOOL_NAME_DELIMITER, tool.server_name, MCP_TOOL_NAME_DELIMITER, tool.tool_name
+            MCP_TOOL_NAME_DELIMITER,
+            tool.server_name,
+            MCP_TOOL_NAME_DELIMITER,
+            tool.alias.as_deref().unwrap_or(&tool.tool_name)
         );
         if qualified_name.len() > MAX_TOOL_NAME_LENGTH {
             let mut hasher = Sha1::new();
@@ -91,31 +167,86 @@ where
             qualified_name = format!("{}{}", &qualified_name[..prefix_len], sha1_str);
         }
 
-        if used_names.contains(&qualified_name) {
+        if let Some(kept) = qualified_tools.get(&qualified_name) {
             warn!("skipping duplicated tool {}", qualified_name);
+            let dropped = McpToolRef {
+                server: tool.server_name,
+                tool: tool.tool_name,
+            };
+            match conflicts
+                .iter_mut()
+                .find(|conflict| conflict.name == qualified_name)
+            {
+                Some(conflict) => conflict.dropped.push(dropped),
+                None => conflicts.push(McpToolConflict {
+                    name: qualified_name,
+                    kept: McpToolRef {
+                        server: kept.server_name.clone(),
+                        tool: kept.tool_name.clone(),
+                    },
+                    dropped: vec![dropped],
+                }),
+            }
             continue;
         }
 
-        used_names.insert(qualified_name.clone());
         qualified_tools.insert(qualified_name, tool);
     }
 
-    qualified_tools
+    (qualified_tools, conflicts)
 }
 
 #[derive(Clone)]
 pub(crate) struct ToolInfo {
     pub(crate) server_name: String,
+    /// The name the server knows the tool by, used when calling it.
     pub(crate) tool_name: String,
+    /// Configured in `tool_aliases`; replaces `tool_name` in the name the
+    /// model sees.
+    pub(crate) alias: Option<String>,
     pub(crate) tool: Tool,
 }
 
 #[derive(Clone)]
 struct ManagedClient {
     client: Arc<RmcpClient>,
-    tools: Vec<ToolInfo>,
+    /// Kept current by [`watch_tool_list`] as the server reports changes.
+    tools: Arc<StdRwLock<Vec<ToolInfo>>>,
     tool_filter: ToolFilter,
     tool_timeout: Option<Duration>,
+    list_cache: Arc<ListCache>,
+}
+
+impl ManagedClient {
+    fn tools(&self) -> Vec<ToolInfo> {
+        self.tools
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+/// Resource and prompt lists last fetched from a server, each tagged with the
+/// client's list-changed version at fetch time. A `list_changed`
+/// notification bumps the version and invalidates the entry.
+#[derive(Default)]
+struct ListCache {
+    resources: Mutex<Option<(i64, Vec<Resource>)>>,
+    prompts: Mutex<Option<(i64, Vec<Prompt>)>>,
+}
+
+/// Everything needed to start, or restart, one server.
+#[derive(Clone)]
+struct ServerLaunch {
+    server_name: String,
+    config: McpServerConfig,
+    store_mode: OAuthCredentialsStoreMode,
+    elicitation: ElicitationRoute,
+    sampling: SamplingRoute,
+    roots: RootsRoute,
+    /// Unset only for servers whose name is invalid, which never start.
+    tool_cache: Option<McpToolCache>,
+    tx_event: Sender<Event>,
 }
 
 #[derive(Clone)]
@@ -124,41 +255,341 @@ struct AsyncManagedClient {
 }
 
 impl AsyncManagedClient {
-    fn new(
-        server_name: String,
-        config: McpServerConfig,
-        store_mode: OAuthCredentialsStoreMode,
-        cancel_token: CancellationToken,
-    ) -> Self {
-        let tool_filter = ToolFilter::from_config(&config);
-        let fut = start_server_task(
-            server_name,
-            config.transport,
-            store_mode,
-            config
+    fn new(launch: &ServerLaunch, cancel_token: CancellationToken) -> Self {
+        let launch = launch.clone();
+        let send_elicitation = launch.elicitation.forwarder();
+        let send_sampling = launch.config.sampling.clone().map(|sampling| {
+            launch
+                .sampling
+                .sender(launch.server_name.clone(), sampling, &launch.elicitation)
+        });
+        let roots = launch.roots.subscribe();
+        let fut = async move {
+            let config = &launch.config;
+            let cached_tools = match &launch.tool_cache {
+                Some(tool_cache) => tool_cache.load().await,
+                None => None,
+            };
+            let startup_timeout = config
                 .startup_timeout_sec
-                .unwrap_or(DEFAULT_STARTUP_TIMEOUT),
-            config.tool_timeout_sec.unwrap_or(DEFAULT_TOOL_TIMEOUT),
-            tool_filter,
-            cancel_token,
-        );
+                .unwrap_or(DEFAULT_STARTUP_TIMEOUT);
+            let started = start_server_task(
+                launch.server_name.clone(),
+                config.transport.clone(),
+                launch.store_mode,
+                startup_timeout,
+                config.tool_timeout_sec.unwrap_or(DEFAULT_TOOL_TIMEOUT),
+                ToolFilter::from_config(config),
+                send_elicitation,
+                send_sampling,
+                roots,
+                cached_tools,
+                cancel_token,
+            )
+            .await?;
+            let managed = started.managed;
+            if !started.from_cache
+                && let Some(tool_cache) = &launch.tool_cache
+            {
+                tool_cache
+                    .store(&started.server_version, &raw_tools(&managed.tools()))
+                    .await;
+            }
+            tokio::spawn(watch_tool_list(
+                launch.clone(),
+                Arc::downgrade(&managed.client),
+                Arc::clone(&managed.tools),
+                started.server_version,
+                started.from_cache,
+                startup_timeout,
+            ));
+            Ok::<_, StartupOutcomeError>(managed)
+        };
         Self {
             client: fut.boxed().shared(),
         }
     }
 
+    /// Wraps a client whose server is already running.
+    fn ready(managed: ManagedClient) -> Self {
+        Self {
+            client: futures::future::ready(Ok(managed)).boxed().shared(),
+        }
+    }
+
     async fn client(&self) -> Result<ManagedClient, StartupOutcomeError> {
         self.client.clone().await
     }
+
+    /// The client if the server finished starting successfully, without
+    /// waiting for a start in progress.
+    fn started(&self) -> Option<ManagedClient> {
+        match self.client.peek() {
+            Some(Ok(managed)) => Some(managed.clone()),
+            _ => None,
+        }
+    }
+
+    fn ptr_eq(&self, other: &Self) -> bool {
+        Shared::ptr_eq(&self.client, &other.client)
+    }
+}
+
+/// A started server waiting in the warm pool for the next conversation.
+struct PooledServer {
+    client: ManagedClient,
+    elicitation: ElicitationRoute,
+    sampling: SamplingRoute,
+    roots: RootsRoute,
+}
+
+struct Activity {
+    in_flight: usize,
+    last_used: Instant,
+}
+
+enum IdleCheck {
+    Stopped,
+    NotRunning,
+    Wait(Duration),
+}
+
+/// One configured server. Holds the current client so the health monitor can
+/// swap in a restarted one without callers noticing, and starts or stops the
+/// server as requests come and go.
+struct SupervisedClient {
+    launch: ServerLaunch,
+    /// `None` while the server is not running: a lazy server before its first
+    /// request, or a server stopped after going idle.
+    current: StdRwLock<Option<AsyncManagedClient>>,
+    /// Tools offered while the server is not running.
+    stopped_tools: StdRwLock<Vec<ToolInfo>>,
+    activity: StdMutex<Activity>,
+    /// Cancels starts made on demand once the manager shuts down.
+    cancel_token: CancellationToken,
+}
+
+/// Marks a server busy while held, so it is not stopped mid-request.
+struct InUse {
+    supervised: Arc<SupervisedClient>,
+}
+
+impl Drop for InUse {
+    fn drop(&mut self) {
+        let mut activity = self
+            .supervised
+            .activity
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        activity.in_flight = activity.in_flight.saturating_sub(1);
+        activity.last_used = Instant::now();
+    }
+}
+
+impl SupervisedClient {
+    fn new(
+        launch: ServerLaunch,
+        current: Option<AsyncManagedClient>,
+        stopped_tools: Vec<ToolInfo>,
+        cancel_token: CancellationToken,
+    ) -> Self {
+        Self {
+            launch,
+            current: StdRwLock::new(current),
+            stopped_tools: StdRwLock::new(stopped_tools),
+            activity: StdMutex::new(Activity {
+                in_flight: 0,
+                last_used: Instant::now(),
+            }),
+            cancel_token,
+        }
+    }
+
+    fn server_name(&self) -> &str {
+        &self.launch.server_name
+    }
+
+    /// The running server's client, or `None` when the server is stopped.
+    /// Never starts the server.
+    async fn running(&self) -> Option<Result<ManagedClient, StartupOutcomeError>> {
+        let current = self
+            .current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()?;
+        Some(current.client().await)
+    }
+
+    /// The client for a request, starting the server first if it is not
+    /// running. The server counts as busy until the returned guard drops.
+    async fn acquire(self: &Arc<Self>) -> Result<(ManagedClient, InUse), StartupOutcomeError> {
+        {
+            let mut activity = self.activity.lock().unwrap_or_else(PoisonError::into_inner);
+            activity.in_flight += 1;
+            activity.last_used = Instant::now();
+        }
+        let in_use = InUse {
+            supervised: Arc::clone(self),
+        };
+
+        let (client, started_now) = {
+            let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+            match current.clone() {
+                Some(client) => (client, false),
+                None => {
+                    let client =
+                        AsyncManagedClient::new(&self.launch, self.cancel_token.child_token());
+                    *current = Some(client.clone());
+                    (client, true)
+                }
+            }
+        };
+        if !started_now {
+            return client.client().await.map(|managed| (managed, in_use));
+        }
+
+        let _ = emit_status(
+            &self.launch.tx_event,
+            self.server_name(),
+            McpServerStatus::Starting,
+        )
+        .await;
+        match client.client().await {
+            Ok(managed) => {
+                let _ = emit_status(
+                    &self.launch.tx_event,
+                    self.server_name(),
+                    McpServerStatus::Ready,
+                )
+                .await;
+                Ok((managed, in_use))
+            }
+            Err(error) => {
+                // Leave the slot empty so the next request tries again.
+                {
+                    let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+                    if current
+                        .as_ref()
+                        .is_some_and(|current| current.ptr_eq(&client))
+                    {
+                        *current = None;
+                    }
+                }
+                if let StartupOutcomeError::Failed { error } = &error {
+                    let status = McpServerStatus::Failed {
+                        error: error.clone(),
+                    };
+                    let _ = emit_status(&self.launch.tx_event, self.server_name(), status).await;
+                }
+                Err(error)
+            }
+        }
+    }
+
+    fn replace(&self, client: AsyncManagedClient) {
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = Some(client);
+    }
+
+    /// Tools to offer the model: the running server's, or the ones it had
+    /// when it last ran.
+    async fn tools(&self) -> Vec<ToolInfo> {
+        let tools = match self.running().await {
+            Some(Ok(managed)) => filter_tools(managed.tools(), managed.tool_filter),
+            Some(Err(_)) => Vec::new(),
+            None => {
+                let tools = self
+                    .stopped_tools
+                    .read()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .clone();
+                filter_tools(tools, ToolFilter::from_config(&self.launch.config))
+            }
+        };
+        apply_aliases(tools, self.launch.config.tool_aliases.as_ref())
+    }
+
+    /// Stops the server when it has had no requests for `idle_timeout`.
+    fn stop_if_idle(&self, idle_timeout: Duration) -> IdleCheck {
+        let activity = self.activity.lock().unwrap_or_else(PoisonError::into_inner);
+        if activity.in_flight > 0 {
+            return IdleCheck::Wait(idle_timeout);
+        }
+        let idle_for = activity.last_used.elapsed();
+        if idle_for < idle_timeout {
+            return IdleCheck::Wait(idle_timeout - idle_for);
+        }
+        let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+        let Some(client) = current.clone() else {
+            return IdleCheck::NotRunning;
+        };
+        // A server that is still starting is not idle, and one that failed to
+        // start has no process to stop.
+        let Some(managed) = client.started() else {
+            return IdleCheck::Wait(idle_timeout);
+        };
+        *self
+            .stopped_tools
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = managed.tools();
+        *current = None;
+        IdleCheck::Stopped
+    }
+
+    /// Takes the started client out of this slot when the server is
+    /// configured for the warm pool.
+    fn take_for_pool(&self) -> Option<PooledServer> {
+        if !self.launch.config.warm_pool {
+            return None;
+        }
+        let client = self
+            .current
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()?;
+        Some(PooledServer {
+            client: client.started()?,
+            elicitation: self.launch.elicitation.clone(),
+            sampling: self.launch.sampling.clone(),
+            roots: self.launch.roots.clone(),
+        })
+    }
 }
 
 /// A thin wrapper around a set of running [`RmcpClient`] instances.
 #[derive(Default)]
 pub(crate) struct McpConnectionManager {
-    clients: HashMap<String, AsyncManagedClient>,
+    clients: HashMap<String, Arc<SupervisedClient>>,
+    /// Stops the health monitor and idle timers and cancels starts made on
+    /// demand. Separate from the startup token so that interrupting startup
+    /// does not also end supervision.
+    health_monitor_cancel_token: CancellationToken,
+    elicitation_requests: ElicitationRequestManager,
+}
+
+impl Drop for McpConnectionManager {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
 }
 
 impl McpConnectionManager {
+    /// Stop supervising the servers and drop every client, which closes
+    /// their transports and kills stdio servers. Started servers configured
+    /// with `warm_pool` are handed to the warm pool instead.
+    pub fn shutdown(&mut self) {
+        self.health_monitor_cancel_token.cancel();
+        for (server_name, supervised) in self.clients.drain() {
+            if let Some(pooled) = supervised.take_for_pool() {
+                let config = supervised.launch.config.clone();
+                let keep_for = config
+                    .idle_timeout_sec
+                    .unwrap_or(DEFAULT_WARM_POOL_KEEP_ALIVE);
+                WARM_POOL.check_in(server_name, config, pooled, keep_for);
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub async fn initialize(
         &mut self,
         mcp_servers: HashMap<String, McpServerConfig>,
@@ -166,13 +597,80 @@ impl McpConnectionManager {
         auth_entries: HashMap<String, McpAuthStatusEntry>,
         tx_event: Sender<Event>,
         cancel_token: CancellationToken,
+        health_monitor: bool,
+        codex_home: &Path,
+        sampling_model: &SamplingModel,
+        workspace_roots: &[PathBuf],
     ) {
         if cancel_token.is_cancelled() {
             return;
         }
         let mut clients = HashMap::new();
+        let mut deferred = Vec::new();
         let mut join_set = JoinSet::new();
         for (server_name, cfg) in mcp_servers.into_iter().filter(|(_, cfg)| cfg.enabled) {
+            let send_elicitation = self
+                .elicitation_requests
+                .make_sender(server_name.clone(), tx_event.clone());
+            let pooled = if cfg.warm_pool {
+                WARM_POOL.check_out(&server_name, &cfg)
+            } else {
+                None
+            };
+            let (elicitation, sampling, roots) = match &pooled {
+                Some(pooled) => {
+                    pooled.elicitation.set(send_elicitation);
+                    pooled.sampling.set(sampling_model.clone());
+                    pooled.roots.set(workspace_roots);
+                    (
+                        pooled.elicitation.clone(),
+                        pooled.sampling.clone(),
+                        pooled.roots.clone(),
+                    )
+                }
+                None => (
+                    ElicitationRoute::new(send_elicitation),
+                    SamplingRoute::new(sampling_model.clone()),
+                    RootsRoute::new(workspace_roots),
+                ),
+            };
+            let tool_cache = validate_mcp_server_name(&server_name)
+                .is_ok()
+                .then(|| McpToolCache::new(codex_home, &server_name, &cfg.transport));
+            let launch = ServerLaunch {
+                server_name: server_name.clone(),
+                config: cfg,
+                store_mode,
+                elicitation,
+                sampling,
+                roots,
+                tool_cache,
+                tx_event: tx_event.clone(),
+            };
+
+            // A lazy server waits for its first request only when there is a
+            // tool list from an earlier run to offer the model until then.
+            // Otherwise it starts now so its tools can be discovered.
+            let remembered_tools = match (&pooled, &launch.tool_cache) {
+                (None, Some(tool_cache)) if launch.config.startup == McpServerStartup::Lazy => {
+                    tool_cache.load().await
+                }
+                _ => None,
+            };
+            if let Some(cached) = remembered_tools {
+                let tools = tool_infos(&server_name, cached.tools);
+                let supervised = Arc::new(SupervisedClient::new(
+                    launch,
+                    None,
+                    tools,
+                    self.health_monitor_cancel_token.child_token(),
+                ));
+                self.supervise(&supervised, health_monitor);
+                clients.insert(server_name.clone(), supervised);
+                deferred.push(server_name);
+                continue;
+            }
+
             let cancel_token = cancel_token.child_token();
             let _ = emit_update(
                 &tx_event,
@@ -182,9 +680,18 @@ impl McpConnectionManager {
                 },
             )
             .await;
-            let async_managed_client =
-                AsyncManagedClient::new(server_name.clone(), cfg, store_mode, cancel_token.clone());
-            clients.insert(server_name.clone(), async_managed_client.clone());
+            let async_managed_client = match pooled {
+                Some(pooled) => AsyncManagedClient::ready(pooled.client),
+                None => AsyncManagedClient::new(&launch, cancel_token.clone()),
+            };
+            let supervised = Arc::new(SupervisedClient::new(
+                launch,
+                Some(async_managed_client.clone()),
+                Vec::new(),
+                self.health_monitor_cancel_token.child_token(),
+            ));
+            self.supervise(&supervised, health_monitor);
+            clients.insert(server_name.clone(), supervised);
             let tx_event = tx_event.clone();
             let auth_entry = auth_entries.get(&server_name).cloned();
             join_set.spawn(async move {
@@ -216,10 +723,14 @@ impl McpConnectionManager {
                 (server_name, outcome)
             });
         }
+        let supervised_clients: Vec<Arc<SupervisedClient>> = clients.values().cloned().collect();
         self.clients = clients;
         tokio::spawn(async move {
             let outcomes = join_set.join_all().await;
-            let mut summary = McpStartupCompleteEvent::default();
+            let mut summary = McpStartupCompleteEvent {
+                deferred,
+                ..Default::default()
+            };
             for (server_name, outcome) in outcomes {
                 match outcome {
                     Ok(_) => summary.ready.push(server_name),
@@ -238,47 +749,100 @@ impl McpConnectionManager {
                     msg: EventMsg::McpStartupComplete(summary),
                 })
                 .await;
+
+            let (_, conflicts) = resolve_tool_names(all_tools(&supervised_clients).await);
+            if !conflicts.is_empty() {
+                let _ = tx_event
+                    .send(Event {
+                        id: INITIAL_SUBMIT_ID.to_owned(),
+                        msg: EventMsg::McpToolConflicts(McpToolConflictsEvent { conflicts }),
+                    })
+                    .await;
+            }
         });
     }
 
-    async fn client_by_name(&self, name: &str) -> Result<ManagedClient> {
+    /// Starts the background tasks that watch over `supervised`.
+    fn supervise(&self, supervised: &Arc<SupervisedClient>, health_monitor: bool) {
+        if health_monitor {
+            tokio::spawn(supervise_server(
+                Arc::downgrade(supervised),
+                self.health_monitor_cancel_token.clone(),
+            ));
+        }
+        let config = &supervised.launch.config;
+        if let Some(idle_timeout) = config.idle_timeout_sec
+            && matches!(config.transport, McpServerTransportConfig::Stdio { .. })
+        {
+            tokio::spawn(stop_when_idle(
+                Arc::downgrade(supervised),
+                idle_timeout,
+                self.health_monitor_cancel_token.clone(),
+            ));
+        }
+    }
+
+    /// Tell every server about the conversation's new workspace roots,
+    /// including servers that are stopped or still starting.
+    pub(crate) fn set_workspace_roots(&self, workspace_roots: &[PathBuf]) {
+        for supervised in self.clients.values() {
+            supervised.launch.roots.set(workspace_roots);
+        }
+    }
+
+    /// Answer a pending elicitation from `server_name`.
+    pub async fn resolve_elicitation(
+        &self,
+        server_name: String,
+        id: mcp_types::RequestId,
+        decision: ElicitationAction,
+        content: Option<serde_json::Value>,
+    ) -> Result<()> {
+        self.elicitation_requests
+            .resolve(server_name, id, decision, content)
+            .await
+    }
+
+    async fn client_by_name(&self, name: &str) -> Result<(ManagedClient, InUse)> {
         self.clients
             .get(name)
             .ok_or_else(|| anyhow!("unknown MCP server '{name}'"))?
-            .client()
+            .acquire()
             .await
             .context("failed to get client")
     }
 
     /// Returns a single map that contains all tools. Each key is the
-    /// fully-qualified name for the tool.
+    /// fully-qualified name for the tool. Servers that are not running
+    /// contribute the tools they had when they last ran.
     pub async fn list_all_tools(&self) -> HashMap<String, ToolInfo> {
-        let mut tools = HashMap::new();
-        for managed_client in self.clients.values() {
-            if let Ok(client) = managed_client.client().await {
-                tools.extend(qualify_tools(filter_tools(
-                    client.tools,
-                    client.tool_filter,
-                )));
-            }
-        }
-        tools
+        qualify_tools(all_tools(self.clients.values()).await)
     }
 
     /// Returns a single map that contains all resources. Each key is the
     /// server name and the value is a vector of resources.
     pub async fn list_all_resources(&self) -> HashMap<String, Vec<Resource>> {
         let mut join_set = JoinSet::new();
+        let mut aggregated: HashMap<String, Vec<Resource>> = HashMap::new();
 
         let clients_snapshot = &self.clients;
 
         for (server_name, async_managed_client) in clients_snapshot {
             let server_name = server_name.clone();
-            let Ok(managed_client) = async_managed_client.client().await else {
+            let Some(Ok(managed_client)) = async_managed_client.running().await else {
                 continue;
             };
             let timeout = managed_client.tool_timeout;
             let client = managed_client.client.clone();
+            let version = client.resource_list_version();
+            if let Some((cached_version, resources)) =
+                &*managed_client.list_cache.resources.lock().await
+                && *cached_version == version
+            {
+                aggregated.insert(server_name, resources.clone());
+                continue;
+            }
+            let list_cache = Arc::clone(&managed_client.list_cache);
 
             join_set.spawn(async move {
                 let mut collected: Vec<Resource> = Vec::new();
@@ -305,14 +869,15 @@ impl McpConnectionManager {
                             }
                             cursor = Some(next);
                         }
-                        None => return (server_name, Ok(collected)),
+                        None => {
+                            *list_cache.resources.lock().await = Some((version, collected.clone()));
+                            return (server_name, Ok(collected));
+                        }
                     }
                 }
             });
         }
 
-        let mut aggregated: HashMap<String, Vec<Resource>> = HashMap::new();
-
         while let Some(join_res) = join_set.join_next().await {
             match join_res {
                 Ok((server_name, Ok(resources))) => {
@@ -339,7 +904,7 @@ impl McpConnectionManager {
 
         for (server_name, async_managed_client) in clients_snapshot {
             let server_name_cloned = server_name.clone();
-            let Ok(managed_client) = async_managed_client.client().await else {
+            let Some(Ok(managed_client)) = async_managed_client.running().await else {
                 continue;
             };
             let client = managed_client.client.clone();
@@ -401,14 +966,110 @@ impl McpConnectionManager {
         aggregated
     }
 
+    /// Returns a single map that contains all prompts. Each key is the server
+    /// name and the value is a vector of prompts. Lists are cached per server
+    /// until it sends `notifications/prompts/list_changed`.
+    pub async fn list_all_prompts(&self) -> HashMap<String, Vec<Prompt>> {
+        let mut join_set = JoinSet::new();
+        let mut aggregated: HashMap<String, Vec<Prompt>> = HashMap::new();
+
+        for (server_name, async_managed_client) in &self.clients {
+            let server_name = server_name.clone();
+            let Some(Ok(managed_client)) = async_managed_client.running().await else {
+                continue;
+            };
+            let timeout = managed_client.tool_timeout;
+            let client = managed_client.client.clone();
+            let version = client.prompt_list_version();
+            if let Some((cached_version, prompts)) =
+                &*managed_client.list_cache.prompts.lock().await
+                && *cached_version == version
+            {
+                aggregated.insert(server_name, prompts.clone());
+                continue;
+            }
+            let list_cache = Arc::clone(&managed_client.list_cache);
+
+            join_set.spawn(async move {
+                let mut collected: Vec<Prompt> = Vec::new();
+                let mut cursor: Option<String> = None;
+
+                loop {
+                    let params = cursor.as_ref().map(|next| ListPromptsRequestParams {
+                        cursor: Some(next.clone()),
+                    });
+                    let response = match client.list_prompts(params, timeout).await {
+                        Ok(result) => result,
+                        Err(err) => return (server_name, Err(err)),
+                    };
+
+                    collected.extend(response.prompts);
+
+                    match response.next_cursor {
+                        Some(next) => {
+                            if cursor.as_ref() == Some(&next) {
+                                return (
+                                    server_name,
+                                    Err(anyhow!("prompts/list returned duplicate cursor")),
+                                );
+                            }
+                            cursor = Some(next);
+                        }
+                        None => {
+                            *list_cache.prompts.lock().await = Some((version, collected.clone()));
+                            return (server_name, Ok(collected));
+                        }
+                    }
+                }
+            });
+        }
+
+        while let Some(join_res) = join_set.join_next().await {
+            match join_res {
+                Ok((server_name, Ok(prompts))) => {
+                    aggregated.insert(server_name, prompts);
+                }
+                Ok((server_name, Err(err))) => {
+                    warn!("Failed to list prompts for MCP server '{server_name}': {err:#}");
+                }
+                Err(err) => {
+                    warn!("Task panic when listing prompts for MCP server: {err:#}");
+                }
+            }
+        }
+
+        aggregated
+    }
+
+    /// Expand the prompt `name` on `server` with the given arguments.
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        name: &str,
+        arguments: Option<serde_json::Value>,
+    ) -> Result<GetPromptResult> {
+        let (managed, _in_use) = self.client_by_name(server).await?;
+        let params = GetPromptRequestParams {
+            arguments,
+            name: name.to_string(),
+        };
+
+        managed
+            .client
+            .get_prompt(params, managed.tool_timeout)
+            .await
+            .with_context(|| format!("prompts/get failed for `{server}/{name}`"))
+    }
+
     /// Invoke the tool indicated by the (server, tool) pair.
     pub async fn call_tool(
         &self,
         server: &str,
         tool: &str,
         arguments: Option<serde_json::Value>,
+        progress: Option<UnboundedSender<McpProgress>>,
     ) -> Result<mcp_types::CallToolResult> {
-        let client = self.client_by_name(server).await?;
+        let (client, _in_use) = self.client_by_name(server).await?;
         if !client.tool_filter.allows(tool) {
             return Err(anyhow!(
                 "tool '{tool}' is disabled for MCP server '{server}'"
@@ -417,7 +1078,7 @@ impl McpConnectionManager {
 
         client
             .client
-            .call_tool(tool.to_string(), arguments, client.tool_timeout)
+            .call_tool(tool.to_string(), arguments, client.tool_timeout, progress)
             .await
             .with_context(|| format!("tool call failed for `{server}/{tool}`"))
     }
@@ -428,7 +1089,7 @@ impl McpConnectionManager {
         server: &str,
         params: Option<ListResourcesRequestParams>,
     ) -> Result<ListResourcesResult> {
-        let managed = self.client_by_name(server).await?;
+        let (managed, _in_use) = self.client_by_name(server).await?;
         let timeout = managed.tool_timeout;
 
         managed
@@ -444,7 +1105,7 @@ impl McpConnectionManager {
         server: &str,
         params: Option<ListResourceTemplatesRequestParams>,
     ) -> Result<ListResourceTemplatesResult> {
-        let managed = self.client_by_name(server).await?;
+        let (managed, _in_use) = self.client_by_name(server).await?;
         let client = managed.client.clone();
         let timeout = managed.tool_timeout;
 
@@ -460,7 +1121,7 @@ impl McpConnectionManager {
         server: &str,
         params: ReadResourceRequestParams,
     ) -> Result<ReadResourceResult> {
-        let managed = self.client_by_name(server).await?;
+        let (managed, _in_use) = self.client_by_name(server).await?;
         let client = managed.client.clone();
         let timeout = managed.tool_timeout;
         let uri = params.uri.clone();
@@ -479,6 +1140,295 @@ impl McpConnectionManager {
     }
 }
 
+/// Pings `server_name` every [`HEALTH_CHECK_INTERVAL`] once it has started.
+/// After [`MAX_CONSECUTIVE_PING_FAILURES`] misses the server is reported
+/// unhealthy and, if it is a stdio server, restarted with exponential backoff.
+/// Streamable HTTP servers are only reported; their transport reconnects on
+/// its own.
+async fn supervise_server(slot: Weak<SupervisedClient>, cancel_token: CancellationToken) {
+    let Some((server_name, tx_event, restartable)) = slot.upgrade().map(|supervised| {
+        (
+            supervised.server_name().to_string(),
+            supervised.launch.tx_event.clone(),
+            matches!(
+                supervised.launch.config.transport,
+                McpServerTransportConfig::Stdio { .. }
+            ),
+        )
+    }) else {
+        return;
+    };
+    let mut consecutive_failures = 0;
+    loop {
+        if tokio::time::sleep(HEALTH_CHECK_INTERVAL)
+            .or_cancel(&cancel_token)
+            .await
+            .is_err()
+        {
+            return;
+        }
+        let Some(supervised) = slot.upgrade() else {
+            return;
+        };
+        // A stopped server has nothing to ping, and one that failed to start
+        // was already reported.
+        let Some(Ok(managed)) = supervised.running().await else {
+            consecutive_failures = 0;
+            continue;
+        };
+        let error = match managed.client.ping(Some(HEALTH_CHECK_TIMEOUT)).await {
+            Ok(()) => {
+                if consecutive_failures >= MAX_CONSECUTIVE_PING_FAILURES
+                    && emit_status(&tx_event, &server_name, McpServerStatus::Healthy)
+                        .await
+                        .is_err()
+                {
+                    return;
+                }
+                consecutive_failures = 0;
+                continue;
+            }
+            Err(error) => error,
+        };
+        consecutive_failures += 1;
+        warn!("MCP server '{server_name}' missed a health check: {error:#}");
+        if consecutive_failures != MAX_CONSECUTIVE_PING_FAILURES {
+            continue;
+        }
+        let status = McpServerStatus::Unhealthy {
+            consecutive_failures,
+            error: format!("{error:#}"),
+        };
+        if emit_status(&tx_event, &server_name, status).await.is_err() {
+            return;
+        }
+        if !restartable {
+            continue;
+        }
+
+        let mut last_error = format!("{error:#}");
+        let mut restarted = false;
+        for attempt in 1..=MAX_RESTART_ATTEMPTS {
+            let status = McpServerStatus::Restarting { attempt };
+            if emit_status(&tx_event, &server_name, status).await.is_err() {
+                return;
+            }
+            if tokio::time::sleep(restart_backoff(attempt))
+                .or_cancel(&cancel_token)
+                .await
+                .is_err()
+            {
+                return;
+            }
+            let candidate = AsyncManagedClient::new(&supervised.launch, cancel_token.child_token());
+            match candidate.client().await {
+                Ok(_) => {
+                    supervised.replace(candidate);
+                    restarted = true;
+                    break;
+                }
+                Err(StartupOutcomeError::Cancelled) => return,
+                Err(StartupOutcomeError::Failed { error }) => {
+                    warn!("failed to restart MCP server '{server_name}': {error}");
+                    last_error = error;
+                }
+            }
+        }
+
+        if !restarted {
+            let status = McpServerStatus::Failed { error: last_error };
+            let _ = emit_status(&tx_event, &server_name, status).await;
+            return;
+        }
+        if emit_status(&tx_event, &server_name, McpServerStatus::Healthy)
+            .await
+            .is_err()
+        {
+            return;
+        }
+        consecutive_failures = 0;
+    }
+}
+
+/// Stops the server in `slot` whenever it goes `idle_timeout` without
+/// requests. The next request starts it again.
+async fn stop_when_idle(
+    slot: Weak<SupervisedClient>,
+    idle_timeout: Duration,
+    cancel_token: CancellationToken,
+) {
+    let mut wait = idle_timeout;
+    loop {
+        if tokio::time::sleep(wait)
+            .or_cancel(&cancel_token)
+            .await
+            .is_err()
+        {
+            return;
+        }
+        let Some(supervised) = slot.upgrade() else {
+            return;
+        };
+        wait = match supervised.stop_if_idle(idle_timeout) {
+            IdleCheck::Wait(remaining) => remaining,
+            IdleCheck::NotRunning => idle_timeout,
+            IdleCheck::Stopped => {
+                let status = McpServerStatus::Stopped {
+                    idle_secs: idle_timeout.as_secs(),
+                };
+                if emit_status(
+                    &supervised.launch.tx_event,
+                    supervised.server_name(),
+                    status,
+                )
+                .await
+                .is_err()
+                {
+                    return;
+                }
+                idle_timeout
+            }
+        };
+    }
+}
+
+async fn all_tools<'a, I>(clients: I) -> Vec<ToolInfo>
+where
+    I: IntoIterator<Item = &'a Arc<SupervisedClient>>,
+{
+    let mut tools = Vec::new();
+    for supervised in clients {
+        tools.extend(supervised.tools().await);
+    }
+    tools
+}
+
+/// Keeps `tools` in step with the server behind `client`: re-lists them once
+/// right away when they came from the cache, then again each time the server
+/// sends `notifications/tools/list_changed`. Every change is written back to
+/// the cache and reported as [`EventMsg::ToolsChanged`]; the next turn reads
+/// the updated list. Ends once the client is dropped.
+async fn watch_tool_list(
+    launch: ServerLaunch,
+    client: Weak<RmcpClient>,
+    tools: Arc<StdRwLock<Vec<ToolInfo>>>,
+    server_version: String,
+    mut revalidate: bool,
+    timeout: Duration,
+) {
+    let Some(mut changes) = client
+        .upgrade()
+        .map(|client| client.subscribe_tool_list_changes())
+    else {
+        return;
+    };
+    loop {
+        if !revalidate && changes.changed().await.is_err() {
+            return;
+        }
+        revalidate = false;
+        let Some(client) = client.upgrade() else {
+            return;
+        };
+        let listed = match list_tools_for_client(&launch.server_name, &client, timeout).await {
+            Ok(listed) => listed,
+            Err(error) => {
+                warn!(
+                    "failed to refresh tools for MCP server '{}': {error:#}",
+                    launch.server_name
+                );
+                continue;
+            }
+        };
+        drop(client);
+
+        let previous = std::mem::replace(
+            &mut *tools.write().unwrap_or_else(PoisonError::into_inner),
+            listed.clone(),
+        );
+        let Some(changed) = diff_tools(&launch.server_name, &previous, &listed) else {
+            continue;
+        };
+        if let Some(tool_cache) = &launch.tool_cache {
+            tool_cache.store(&server_version, &raw_tools(&listed)).await;
+        }
+        let _ = launch
+            .tx_event
+            .send(Event {
+                id: INITIAL_SUBMIT_ID.to_owned(),
+                msg: EventMsg::ToolsChanged(changed),
+            })
+            .await;
+    }
+}
+
+/// Names of the tools added, removed, or redefined between two lists, or
+/// `None` when the lists hold the same tools.
+fn diff_tools(
+    server_name: &str,
+    previous: &[ToolInfo],
+    current: &[ToolInfo],
+) -> Option<ToolsChangedEvent> {
+    let previous: HashMap<&str, &Tool> = previous
+        .iter()
+        .map(|info| (info.tool_name.as_str(), &info.tool))
+        .collect();
+    let current: HashMap<&str, &Tool> = current
+        .iter()
+        .map(|info| (info.tool_name.as_str(), &info.tool))
+        .collect();
+    let mut event = ToolsChangedEvent {
+        server: server_name.to_string(),
+        added: Vec::new(),
+        removed: Vec::new(),
+        updated: Vec::new(),
+    };
+    for (name, tool) in &current {
+        match previous.get(name) {
+            None => event.added.push((*name).to_string()),
+            Some(old) if old != tool => event.updated.push((*name).to_string()),
+            Some(_) => {}
+        }
+    }
+    event.removed = previous
+        .keys()
+        .filter(|name| !current.contains_key(*name))
+        .map(|name| (*name).to_string())
+        .collect();
+    if event.added.is_empty() && event.removed.is_empty() && event.updated.is_empty() {
+        return None;
+    }
+    event.added.sort();
+    event.removed.sort();
+    event.updated.sort();
+    Some(event)
+}
+
+/// Delay before restart `attempt` (1-based): doubles from
+/// [`RESTART_BACKOFF_INITIAL`] up to [`RESTART_BACKOFF_MAX`].
+fn restart_backoff(attempt: i32) -> Duration {
+    let factor = 2f64.powi(attempt.saturating_sub(1).max(0));
+    RESTART_BACKOFF_INITIAL
+        .mul_f64(factor)
+        .min(RESTART_BACKOFF_MAX)
+}
+
+async fn emit_status(
+    tx_event: &Sender<Event>,
+    server_name: &str,
+    status: McpServerStatus,
+) -> Result<(), async_channel::SendError<Event>> {
+    tx_event
+        .send(Event {
+            id: INITIAL_SUBMIT_ID.to_owned(),
+            msg: EventMsg::McpServerStatusChanged(McpServerStatusChangedEvent {
+                server: server_name.to_string(),
+                status,
+            }),
+        })
+        .await
+}
+
 async fn emit_update(
     tx_event: &Sender<Event>,
     update: McpStartupUpdateEvent,
@@ -578,6 +1528,7 @@ impl From<anyhow::Error> for StartupOutcomeError {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn start_server_task(
     server_name: String,
     transport: McpServerTransportConfig,
@@ -585,8 +1536,12 @@ async fn start_server_task(
     startup_timeout: Duration, // TODO: cancel_token should handle this.
     tool_timeout: Duration,
     tool_filter: ToolFilter,
+    send_elicitation: SendElicitation,
+    send_sampling: Option<SendSamplingRequest>,
+    roots: watch::Receiver<Vec<Root>>,
+    cached_tools: Option<CachedTools>,
     cancel_token: CancellationToken,
-) -> Result<ManagedClient, StartupOutcomeError> {
+) -> Result<StartedServer, StartupOutcomeError> {
     if cancel_token.is_cancelled() {
         return Err(StartupOutcomeError::Cancelled);
     }
@@ -601,6 +1556,10 @@ async fn start_server_task(
         startup_timeout,
         tool_timeout,
         tool_filter,
+        send_elicitation,
+        send_sampling,
+        roots,
+        cached_tools,
     )
     .or_cancel(&cancel_token)
     .await
@@ -610,6 +1569,17 @@ async fn start_server_task(
     }
 }
 
+/// A server that finished its startup handshake.
+struct StartedServer {
+    managed: ManagedClient,
+    /// `<name>@<version>` from the server's `initialize` result.
+    server_version: String,
+    /// The tools came from the cache rather than `tools/list`, so they still
+    /// need checking against the server.
+    from_cache: bool,
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn start_server_work(
     server_name: String,
     transport: McpServerTransportConfig,
@@ -617,12 +1587,18 @@ async fn start_server_work(
     startup_timeout: Duration,
     tool_timeout: Duration,
     tool_filter: ToolFilter,
-) -> Result<ManagedClient, StartupOutcomeError> {
+    send_elicitation: SendElicitation,
+    send_sampling: Option<SendSamplingRequest>,
+    roots: watch::Receiver<Vec<Root>>,
+    cached_tools: Option<CachedTools>,
+) -> Result<StartedServer, StartupOutcomeError> {
     let params = mcp_types::InitializeRequestParams {
         capabilities: ClientCapabilities {
             experimental: None,
-            roots: None,
-            sampling: None,
+            roots: Some(ClientCapabilitiesRoots {
+                list_changed: Some(true),
+            }),
+            sampling: send_sampling.is_some().then(|| json!({})),
             // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
             // indicates this should be an empty object.
             elicitation: Some(json!({})),
@@ -653,9 +1629,15 @@ async fn start_server_work(
                 Ok(client) => {
                     let client = Arc::new(client);
                     client
-                        .initialize(params.clone(), Some(startup_timeout))
+                        .initialize(
+                            params.clone(),
+                            Some(startup_timeout),
+                            send_elicitation,
+                            send_sampling,
+                            Some(roots),
+                        )
                         .await
-                        .map(|_| client)
+                        .map(|initialized| (client, initialized))
                 }
                 Err(err) => Err(err.into()),
             }
@@ -665,6 +1647,7 @@ async fn start_server_work(
             http_headers,
             env_http_headers,
             bearer_token_env_var,
+            ..
         } => {
             let resolved_bearer_token =
                 match resolve_bearer_token(&server_name, bearer_token_env_var.as_deref()) {
@@ -684,37 +1667,60 @@ async fn start_server_work(
                 Ok(client) => {
                     let client = Arc::new(client);
                     client
-                        .initialize(params.clone(), Some(startup_timeout))
+                        .initialize(
+                            params.clone(),
+                            Some(startup_timeout),
+                            send_elicitation,
+                            send_sampling,
+                            Some(roots),
+                        )
                         .await
-                        .map(|_| client)
+                        .map(|initialized| (client, initialized))
                 }
                 Err(err) => Err(err),
             }
         }
     };
 
-    let client = match client_result {
-        Ok(client) => client,
+    let (client, initialized) = match client_result {
+        Ok(result) => result,
         Err(error) => {
             return Err(error.into());
         }
     };
-
-    let tools = match list_tools_for_client(&server_name, &client, startup_timeout).await {
-        Ok(tools) => tools,
-        Err(error) => {
-            return Err(error.into());
-        }
+    let server_version = format!(
+        "{}@{}",
+        initialized.server_info.name, initialized.server_info.version
+    );
+
+    // The same server version is trusted to report the same tools, so its
+    // cached list is used now and checked in the background.
+    let cached_tools = cached_tools
+        .filter(|cached| cached.server_version.as_deref() == Some(server_version.as_str()));
+    let from_cache = cached_tools.is_some();
+    let tools = match cached_tools {
+        Some(cached) => tool_infos(&server_name, cached.tools),
+        None => match list_tools_for_client(&server_name, &client, startup_timeout).await {
+            Ok(tools) => tools,
+            Err(error) => {
+                return Err(error.into());
+            }
+        },
     };
 
     let managed = ManagedClient {
         client: Arc::clone(&client),
-        tools,
+        tools: Arc::new(StdRwLock::new(tools)),
         tool_timeout: Some(tool_timeout),
         tool_filter,
+        list_cache: Arc::default(),
     };
 
-    Ok(managed)
+    Ok(StartedServer {
+        managed,
+        server_version,
+        from_cache,
+    })
 }
 
 async fn list_tools_for_client(
@@ -723,15 +1729,35 @@ async fn list_tools_for_client(
     timeout: Duration,
 ) -> Result<Vec<ToolInfo>> {
     let resp = client.list_tools(None, Some(timeout)).await?;
-    Ok(resp
-        .tools
+    Ok(tool_infos(server_name, resp.tools))
+}
+
+fn tool_infos(server_name: &str, tools: Vec<Tool>) -> Vec<ToolInfo> {
+    tools
         .into_iter()
         .map(|tool| ToolInfo {
             server_name: server_name.to_owned(),
             tool_name: tool.name.clone(),
+            alias: None,
             tool,
         })
-        .collect())
+        .collect()
+}
+
+fn apply_aliases(
+    mut tools: Vec<ToolInfo>,
+    aliases: Option<&HashMap<String, String>>,
+) -> Vec<ToolInfo> {
+    if let Some(aliases) = aliases {
+        for tool in &mut tools {
+            tool.alias = aliases.get(&tool.tool_name).cloned();
+        }
+    }
+    tools
+}
+
+fn raw_tools(tools: &[ToolInfo]) -> Vec<Tool> {
+    tools.iter().map(|info| info.tool.clone()).collect()
 }
 
 fn validate_mcp_server_name(server_name: &str) -> Result<()> {
@@ -815,6 +1841,7 @@ mod tests {
         ToolInfo {
             server_name: server_name.to_string(),
             tool_name: tool_name.to_string(),
+            alias: None,
             tool: Tool {
                 annotations: None,
                 description: Some(format!("Test tool: {tool_name}")),
@@ -970,12 +1997,18 @@ mod tests {
                     bearer_token_env_var: None,
                     http_headers: None,
                     env_http_headers: None,
+                    scopes: None,
                 },
                 enabled: true,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
             auth_status: McpAuthStatus::Unsupported,
         };
@@ -1014,12 +2047,18 @@ mod tests {
                     bearer_token_env_var: Some("TOKEN".to_string()),
                     http_headers: None,
                     env_http_headers: None,
+                    scopes: None,
                 },
                 enabled: true,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
             auth_status: McpAuthStatus::Unsupported,
         };
@@ -1044,4 +2083,104 @@ mod tests {
             display
         );
     }
+
+    #[test]
+    fn restart_backoff_doubles_up_to_max() {
+        let delays: Vec<Duration> = (1..=8).map(restart_backoff).collect();
+        assert_eq!(
+            vec![
+                Duration::from_secs(1),
+                Duration::from_secs(2),
+                Duration::from_secs(4),
+                Duration::from_secs(8),
+                Duration::from_secs(16),
+                Duration::from_secs(32),
+                Duration::from_secs(60),
+                Duration::from_secs(60),
+            ],
+            delays
+        );
+    }
+
+    #[test]
+    fn resolve_tool_names_keeps_first_by_server_and_reports_conflicts() {
+        let mut aliased = create_test_tool("a", "other");
+        aliased.alias = Some("b__c".to_string());
+        let tools = vec![
+            create_test_tool("a__b", "c"),
+            aliased,
+            create_test_tool("a", "b__c"),
+        ];
+
+        let (qualified_tools, conflicts) = resolve_tool_names(tools);
+
+        assert_eq!(qualified_tools.len(), 1);
+        let kept = &qualified_tools["mcp__a__b__c"];
+        assert_eq!(
+            ("a", "b__c"),
+            (kept.server_name.as_str(), kept.tool_name.as_str())
+        );
+        assert_eq!(
+            vec![McpToolConflict {
+                name: "mcp__a__b__c".to_string(),
+                kept: McpToolRef {
+                    server: "a".to_string(),
+                    tool: "b__c".to_string(),
+                },
+                dropped: vec![
+                    McpToolRef {
+                        server: "a".to_string(),
+                        tool: "other".to_string(),
+                    },
+                    McpToolRef {
+                        server: "a__b".to_string(),
+                        tool: "c".to_string(),
+                    },
+                ],
+            }],
+            conflicts
+        );
+    }
+
+    #[test]
+    fn aliases_rename_tools_but_not_the_tool_called() {
+        let aliases = HashMap::from([("search".to_string(), "find".to_string())]);
+        let tools = apply_aliases(vec![create_test_tool("docs", "search")], Some(&aliases));
+
+        let qualified_tools = qualify_tools(tools);
+
+        assert_eq!(
+            Some("search"),
+            qualified_tools
+                .get("mcp__docs__find")
+                .map(|tool| tool.tool_name.as_str())
+        );
+    }
+
+    #[test]
+    fn diff_tools_reports_added_removed_and_updated_tools() {
+        let previous = vec![
+            create_test_tool("docs", "search"),
+            create_test_tool("docs", "fetch"),
+            create_test_tool("docs", "stale"),
+        ];
+        let mut fetch = create_test_tool("docs", "fetch");
+        fetch.tool.description = Some("Fetch a page".to_string());
+        let current = vec![
+            create_test_tool("docs", "search"),
+            fetch,
+            create_test_tool("docs", "summarize"),
+        ];
+
+        assert_eq!(
+            Some(ToolsChangedEvent {
+                server: "docs".to_string(),
+                added: vec!["summarize".to_string()],
+                removed: vec!["stale".to_string()],
+                updated: vec!["fetch".to_string()],
+            }),
+            diff_tools("docs", &previous, &current)
+        );
+        assert_eq!(None, diff_tools("docs", &current, &current));
+    }
 }
@@ -1,5 +1,6 @@
 use std::time::Instant;
 
+use tokio::sync::mpsc;
 use tracing::error;
 
 use crate::codex::Session;
@@ -8,6 +9,7 @@ use crate::protocol::EventMsg;
 use crate::protocol::McpInvocation;
 use crate::protocol::McpToolCallBeginEvent;
 use crate::protocol::McpToolCallEndEvent;
+use crate::protocol::McpToolCallProgressEvent;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
 
@@ -55,11 +57,31 @@ pub(crate) async fn handle_mcp_tool_call(
     notify_mcp_tool_call_event(sess, turn_context, tool_call_begin_event).await;
 
     let start = Instant::now();
-    // Perform the tool call.
-    let result = sess
-        .call_tool(&server, &tool_name, arguments_value.clone())
-        .await
-        .map_err(|e| format!("tool call error: {e:?}"));
+    // Perform the tool call, relaying any progress the server reports while
+    // it runs.
+    let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+    let call = sess.call_tool(
+        &server,
+        &tool_name,
+        arguments_value.clone(),
+        Some(progress_tx),
+    );
+    tokio::pin!(call);
+    let result = loop {
+        tokio::select! {
+            result = &mut call => break result,
+            Some(progress) = progress_rx.recv() => {
+                let progress_event = EventMsg::McpToolCallProgress(McpToolCallProgressEvent {
+                    call_id: call_id.clone(),
+                    progress: progress.progress,
+                    total: progress.total,
+                    message: progress.message,
+                });
+                notify_mcp_tool_call_event(sess, turn_context, progress_event).await;
+            }
+        }
+    };
+    let result = result.map_err(|e| format!("tool call error: {e:?}"));
     if let Err(e) = &result {
         tracing::warn!("MCP tool call error: {e:?}");
     }
@@ -0,0 +1,38 @@
+//! Pluggable model backends.
+//!
+//! A [`ModelProvider`] runs a single model turn: it sends a [`Prompt`] and
+//! streams back [`ResponseEvent`](crate::ResponseEvent)s. Implementations
+//! must report tool calls as `OutputItemDone` items (`FunctionCall`,
+//! `CustomToolCall`, ...) so the agent loop can dispatch them, and must end
+//! every stream with `Completed`, carrying the turn's token usage when the
+//! backend reports it.
+//!
+//! [`crate::ModelClient`] picks a backend from the configured provider's
+//! `wire_api`; callers embedding Codex can install their own with
+//! [`crate::ModelClient::with_model_provider`].
+
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use codex_otel::otel_event_manager::OtelEventManager;
+use codex_protocol::protocol::SessionSource;
+
+use crate::client_common::Prompt;
+use crate::client_common::ResponseStream;
+use crate::error::Result;
+use crate::model_family::ModelFamily;
+
+/// Everything a backend needs to run one turn.
+#[derive(Debug, Clone, Copy)]
+pub struct ModelRequest<'a> {
+    pub prompt: &'a Prompt,
+    pub model_family: &'a ModelFamily,
+    pub otel_event_manager: &'a OtelEventManager,
+    pub session_source: &'a SessionSource,
+}
+
+#[async_trait]
+pub trait ModelProvider: Debug + Send + Sync {
+    /// Start streaming the model's response to `request`.
+    async fn stream(&self, request: ModelRequest<'_>) -> Result<ResponseStream>;
+}
@@ -150,6 +150,54 @@ impl ModelProviderInfo {
         Ok(self.apply_http_headers(builder))
     }
 
+    /// Like [`ModelProviderInfo::create_request_builder`] for the
+    /// provider's `/audio/transcriptions` endpoint.
+    pub(crate) async fn create_transcription_request_builder<'a>(
+        &'a self,
+        client: &'a CodexHttpClient,
+        auth: &Option<CodexAuth>,
+    ) -> crate::error::Result<CodexRequestBuilder> {
+        let effective_auth = self.effective_auth(auth)?;
+
+        let url = format!(
+            "{}/audio/transcriptions{}",
+            self.get_base_url(&effective_auth),
+            self.get_query_string()
+        );
+
+        let mut builder = client.post(url);
+
+        if let Some(auth) = effective_auth.as_ref() {
+            builder = builder.bearer_auth(auth.get_token().await?);
+        }
+
+        Ok(self.apply_http_headers(builder))
+    }
+
+    /// Like [`ModelProviderInfo::create_request_builder`] for the
+    /// provider's `/embeddings` endpoint.
+    pub(crate) async fn create_embeddings_request_builder<'a>(
+        &'a self,
+        client: &'a CodexHttpClient,
+        auth: &Option<CodexAuth>,
+    ) -> crate::error::Result<CodexRequestBuilder> {
+        let effective_auth = self.effective_auth(auth)?;
+
+        let url = format!(
+            "{}/embeddings{}",
+            self.get_base_url(&effective_auth),
+            self.get_query_string()
+        );
+
+        let mut builder = client.post(url);
+
+        if let Some(auth) = effective_auth.as_ref() {
+            builder = builder.bearer_auth(auth.get_token().await?);
+        }
+
+        Ok(self.apply_http_headers(builder))
+    }
+
     fn effective_auth(&self, auth: &Option<CodexAuth>) -> crate::error::Result<Option<CodexAuth>> {
         if let Some(secret_key) = &self.experimental_bearer_token {
             return Ok(Some(CodexAuth::from_api_key(secret_key)));
@@ -181,7 +229,7 @@ impl ModelProviderInfo {
             })
     }
 
-    pub(crate) fn get_full_url(&self, auth: &Option<CodexAuth>) -> String {
+    fn get_base_url(&self, auth: &Option<CodexAuth>) -> String {
         let default_base_url = if matches!(
             auth,
             Some(CodexAuth {
@@ -193,11 +241,14 @@ impl ModelProviderInfo {
         } else {
             "https://api.openai.com/v1"
         };
-        let query_string = self.get_query_string();
-        let base_url = self
-            .base_url
+        self.base_url
             .clone()
-            .unwrap_or(default_base_url.to_string());
+            .unwrap_or(default_base_url.to_string())
+    }
+
+    pub(crate) fn get_full_url(&self, auth: &Option<CodexAuth>) -> String {
+        let query_string = self.get_query_string();
+        let base_url = self.get_base_url(auth);
 
         match self.wire_api {
             WireApi::Responses => format!("{base_url}/responses{query_string}"),
@@ -304,9 +355,11 @@ impl ModelProviderInfo {
 
 pub const DEFAULT_LMSTUDIO_PORT: u16 = 1234;
 pub const DEFAULT_OLLAMA_PORT: u16 = 11434;
+pub const DEFAULT_VLLM_PORT: u16 = 8000;
 
 pub const LMSTUDIO_OSS_PROVIDER_ID: &str = "lmstudio";
 pub const OLLAMA_OSS_PROVIDER_ID: &str = "ollama";
+pub const VLLM_PROVIDER_ID: &str = "vllm";
 
 /// Built-in default provider list.
 pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
@@ -365,6 +418,7 @@ pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
             LMSTUDIO_OSS_PROVIDER_ID,
             create_oss_provider(DEFAULT_LMSTUDIO_PORT, WireApi::Responses),
         ),
+        (VLLM_PROVIDER_ID, create_vllm_provider()),
     ]
     .into_iter()
     .map(|(k, v)| (k.to_string(), v))
@@ -391,6 +445,19 @@ pub fn create_oss_provider(default_provider_port: u16, wire_api: WireApi) -> Mod
     create_oss_provider_with_base_url(&codex_oss_base_url, wire_api)
 }
 
+/// vLLM's OpenAI-compatible server, at `CODEX_VLLM_BASE_URL` or
+/// `http://localhost:8000/v1`.
+fn create_vllm_provider() -> ModelProviderInfo {
+    let base_url = std::env::var("CODEX_VLLM_BASE_URL")
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| format!("http://localhost:{DEFAULT_VLLM_PORT}/v1"));
+    ModelProviderInfo {
+        name: "vLLM".into(),
+        ..create_oss_provider_with_base_url(&base_url, WireApi::Chat)
+    }
+}
+
 pub fn create_oss_provider_with_base_url(base_url: &str, wire_api: WireApi) -> ModelProviderInfo {
     ModelProviderInfo {
         name: "gpt-oss".into(),
@@ -0,0 +1,213 @@
+//! Recording and deterministic replay of model API traffic.
+//!
+//! With `CODEX_RECORD_DIR` set, every streaming model request is numbered in
+//! the order it is sent and saved as `<n>.json` (URL and request body) next to
+//! `<n>.sse`, the raw SSE bytes exactly as they were received. With
+//! `CODEX_REPLAY_DIR` set, requests never reach the network: the `n`th
+//! request is answered with the recorded `<n>.sse`, so a session recorded
+//! once (for example from a bug report) plays back the same way every time.
+//! A directory holds the traffic of a single session.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::OnceLock;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+
+use bytes::Bytes;
+use futures::Stream;
+use futures::StreamExt;
+use serde_json::Value;
+use serde_json::json;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::flags::CODEX_RECORD_DIR;
+use crate::flags::CODEX_REPLAY_DIR;
+
+pub(crate) type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes>> + Send>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TrafficMode {
+    Record,
+    Replay,
+}
+
+#[derive(Debug)]
+struct TrafficLog {
+    dir: PathBuf,
+    mode: TrafficMode,
+    next_exchange: AtomicU64,
+}
+
+/// The log selected by the environment; replay wins over record.
+fn traffic_log() -> Option<&'static TrafficLog> {
+    static LOG: OnceLock<Option<TrafficLog>> = OnceLock::new();
+    LOG.get_or_init(|| {
+        if let Some(dir) = *CODEX_REPLAY_DIR {
+            Some(TrafficLog::new(PathBuf::from(dir), TrafficMode::Replay))
+        } else {
+            (*CODEX_RECORD_DIR).map(|dir| TrafficLog::new(PathBuf::from(dir), TrafficMode::Record))
+        }
+    })
+    .as_ref()
+}
+
+/// The recorded response to the next request, or `None` when not replaying.
+pub(crate) async fn replay_model_stream() -> Option<Result<ByteStream>> {
+    match traffic_log() {
+        Some(log) if log.mode == TrafficMode::Replay => Some(log.replay().await),
+        _ => None,
+    }
+}
+
+/// Pass `stream` through, saving the request and every chunk when recording.
+pub(crate) fn record_model_stream<S>(url: &str, request: &Value, stream: S) -> ByteStream
+where
+    S: Stream<Item = Result<Bytes>> + Send + 'static,
+{
+    match traffic_log() {
+        Some(log) if log.mode == TrafficMode::Record => log.record(url, request, stream),
+        _ => Box::pin(stream),
+    }
+}
+
+impl TrafficLog {
+    fn new(dir: PathBuf, mode: TrafficMode) -> Self {
+        Self {
+            dir,
+            mode,
+            next_exchange: AtomicU64::new(1),
+        }
+    }
+
+    fn next_paths(&self) -> (PathBuf, PathBuf) {
+        let exchange = self.next_exchange.fetch_add(1, Ordering::SeqCst);
+        (
+            self.dir.join(format!("{exchange:04}.json")),
+            self.dir.join(format!("{exchange:04}.sse")),
+        )
+    }
+
+    async fn replay(&self) -> Result<ByteStream> {
+        let (_, response_path) = self.next_paths();
+        let body = tokio::fs::read(&response_path).await.map_err(|err| {
+            CodexErr::Fatal(format!(
+                "no recorded response to replay at {}: {err}",
+                response_path.display()
+            ))
+        })?;
+        Ok(Box::pin(futures::stream::once(async move {
+            Ok(Bytes::from(body))
+        })))
+    }
+
+    fn record<S>(&self, url: &str, request: &Value, stream: S) -> ByteStream
+    where
+        S: Stream<Item = Result<Bytes>> + Send + 'static,
+    {
+        let (request_path, response_path) = self.next_paths();
+        let exchange = json!({ "url": url, "request": request });
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            if let Err(err) = write_exchange(&request_path, &response_path, &exchange, rx).await {
+                warn!(
+                    "failed to record model traffic to {}: {err}",
+                    response_path.display()
+                );
+            }
+        });
+        Box::pin(stream.inspect(move |chunk| {
+            if let Ok(bytes) = chunk {
+                let _ = tx.send(bytes.clone());
+            }
+        }))
+    }
+}
+
+async fn write_exchange(
+    request_path: &Path,
+    response_path: &Path,
+    exchange: &Value,
+    mut chunks: mpsc::UnboundedReceiver<Bytes>,
+) -> io::Result<()> {
+    if let Some(parent) = request_path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let request = serde_json::to_vec_pretty(exchange).map_err(io::Error::other)?;
+    tokio::fs::write(request_path, request).await?;
+    let mut response = tokio::fs::File::create(response_path).await?;
+    // Flush every chunk so an interrupted session still leaves what arrived.
+    while let Some(chunk) = chunks.recv().await {
+        response.write_all(&chunk).await?;
+        response.flush().await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    async fn collect(stream: ByteStream) -> Vec<u8> {
+        stream
+            .map(|chunk| chunk.expect("chunk").to_vec())
+            .concat()
+            .await
+    }
+
+    /// Recording writes happen in the background; wait for them to land.
+    async fn wait_for_contents(path: &Path, expected: &[u8]) {
+        for _ in 0..100 {
+            if std::fs::read(path).is_ok_and(|contents| contents == expected) {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("{} was never written", path.display());
+    }
+
+    #[tokio::test]
+    async fn replays_recorded_streams_in_request_order() {
+        let dir = tempdir().expect("tmp");
+        let recorder = TrafficLog::new(dir.path().to_path_buf(), TrafficMode::Record);
+        for body in ["first", "second"] {
+            let chunks = vec![
+                Ok(Bytes::from(format!("data: {body}\n"))),
+                Ok(Bytes::from_static(b"\n")),
+            ];
+            let request = json!({ "input": body });
+            let recorded = recorder.record(
+                "http://localhost/v1/responses",
+                &request,
+                futures::stream::iter(chunks),
+            );
+            assert_eq!(
+                collect(recorded).await,
+                format!("data: {body}\n\n").as_bytes()
+            );
+        }
+        wait_for_contents(&dir.path().join("0001.sse"), b"data: first\n\n").await;
+        wait_for_contents(&dir.path().join("0002.sse"), b"data: second\n\n").await;
+
+        let request: Value = serde_json::from_str(
+            &std::fs::read_to_string(dir.path().join("0001.json")).expect("request"),
+        )
+        .expect("request json");
+        assert_eq!(request["request"]["input"], "first");
+
+        let replayer = TrafficLog::new(dir.path().to_path_buf(), TrafficMode::Replay);
+        let first = replayer.replay().await.expect("first");
+        let second = replayer.replay().await.expect("second");
+        assert_eq!(collect(first).await, b"data: first\n\n");
+        assert_eq!(collect(second).await, b"data: second\n\n");
+        assert!(matches!(replayer.replay().await, Err(CodexErr::Fatal(_))));
+    }
+}
@@ -0,0 +1,229 @@
+//! Per-task model selection from `[model_routing]`, with fallbacks for models
+//! the provider reports as unavailable.
+
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+use codex_protocol::protocol::ModelTask;
+use codex_protocol::protocol::ModelUsageItem;
+use reqwest::StatusCode;
+use tracing::warn;
+
+use crate::client::ModelClient;
+use crate::config::types::ModelRoutingConfig;
+use crate::error::CodexErr;
+
+/// Phrases in a 400/403 body that mean the model itself was rejected rather
+/// than the request.
+const MODEL_UNAVAILABLE_PHRASES: &[&str] = &[
+    "model_not_found",
+    "not found",
+    "does not exist",
+    "not supported",
+    "unsupported model",
+    "not available",
+    "do not have access",
+];
+
+/// Models to try for `task`, in order and without duplicates. Auxiliary
+/// tasks end with the main model so they still run when every routed model
+/// is unavailable.
+pub(crate) fn routed_models(
+    routing: &ModelRoutingConfig,
+    main_model: &str,
+    task: ModelTask,
+) -> Vec<String> {
+    let route = match task {
+        ModelTask::Main => None,
+        ModelTask::Summarization => routing.summarization.as_ref(),
+        ModelTask::RiskAssessment => routing.risk_assessment.as_ref(),
+        ModelTask::CommitMessage => routing.commit_message.as_ref(),
+        ModelTask::ThreadTitle => routing.thread_title.as_ref(),
+    };
+
+    let mut models = Vec::new();
+    if let Some(route) = route {
+        models.push(route.model.clone());
+        models.extend(route.fallbacks.iter().cloned());
+    }
+    models.push(main_model.to_string());
+    if task == ModelTask::Main {
+        models.extend(routing.fallbacks.iter().cloned());
+    }
+
+    let mut seen = HashSet::new();
+    models.retain(|model| seen.insert(model.clone()));
+    models
+}
+
+/// Whether `err` means the requested model cannot serve requests at all, as
+/// opposed to a failure that is worth retrying with the same model.
+pub(crate) fn is_model_unavailable(err: &CodexErr) -> bool {
+    let CodexErr::UnexpectedStatus(err) = err else {
+        return false;
+    };
+    match err.status {
+        StatusCode::NOT_FOUND => true,
+        StatusCode::BAD_REQUEST | StatusCode::FORBIDDEN => {
+            let body = err.body.to_ascii_lowercase();
+            body.contains("model")
+                && MODEL_UNAVAILABLE_PHRASES
+                    .iter()
+                    .any(|phrase| body.contains(phrase))
+        }
+        _ => false,
+    }
+}
+
+/// The client for one task's current model, plus the models still left to
+/// fall back to.
+pub(crate) struct RoutedClient {
+    task: ModelTask,
+    main: ModelClient,
+    current: ModelClient,
+    remaining: VecDeque<String>,
+    unavailable: Vec<String>,
+}
+
+impl RoutedClient {
+    pub(crate) fn new(main: &ModelClient, task: ModelTask) -> Self {
+        let main_model = main.get_model();
+        let mut remaining: VecDeque<String> =
+            routed_models(&main.config().model_routing, &main_model, task).into();
+        let first = remaining.pop_front().unwrap_or(main_model);
+        Self {
+            task,
+            current: client_for(main, &first),
+            main: main.clone(),
+            remaining,
+            unavailable: Vec::new(),
+        }
+    }
+
+    /// Moves past models found unavailable earlier in the session. The last
+    /// candidate is kept even when listed, so there is always a model to try.
+    pub(crate) fn skipping(mut self, unavailable: &HashSet<String>) -> Self {
+        while unavailable.contains(&self.current.get_model()) {
+            let Some(next) = self.remaining.pop_front() else {
+                break;
+            };
+            self.unavailable.push(self.current.get_model());
+            self.current = client_for(&self.main, &next);
+        }
+        self
+    }
+
+    pub(crate) fn client(&self) -> &ModelClient {
+        &self.current
+    }
+
+    /// Models this client has given up on so far.
+    pub(crate) fn unavailable_models(&self) -> &[String] {
+        &self.unavailable
+    }
+
+    /// Switches to the next routed model when `err` says the current one is
+    /// unavailable. Returns `false`, leaving the client unchanged, for any
+    /// other error or when no models are left.
+    pub(crate) fn fall_back(&mut self, err: &CodexErr) -> bool {
+        if !is_model_unavailable(err) {
+            return false;
+        }
+        let Some(next) = self.remaining.pop_front() else {
+            return false;
+        };
+        let current = self.current.get_model();
+        warn!(
+            "model {current} is unavailable for {:?}; falling back to {next}",
+            self.task
+        );
+        self.unavailable.push(current);
+        self.current = client_for(&self.main, &next);
+        true
+    }
+
+    /// The history record for a request this client completed, or `None`
+    /// when the main model served it without any fallback, since turn
+    /// context items already record that model.
+    pub(crate) fn usage(&self) -> Option<ModelUsageItem> {
+        let model = self.current.get_model();
+        if model == self.main.get_model() && self.unavailable.is_empty() {
+            return None;
+        }
+        Some(ModelUsageItem {
+            task: self.task,
+            model,
+            unavailable: self.unavailable.clone(),
+        })
+    }
+}
+
+fn client_for(main: &ModelClient, model: &str) -> ModelClient {
+    if main.get_model() == model {
+        main.clone()
+    } else {
+        main.for_model(model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::ModelRoute;
+    use crate::error::UnexpectedResponseError;
+    use pretty_assertions::assert_eq;
+
+    fn routing() -> ModelRoutingConfig {
+        ModelRoutingConfig {
+            fallbacks: vec!["backup".to_string(), "main".to_string()],
+            summarization: Some(ModelRoute {
+                model: "small".to_string(),
+                fallbacks: vec!["main".to_string(), "tiny".to_string()],
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn status_err(status: StatusCode, body: &str) -> CodexErr {
+        CodexErr::UnexpectedStatus(UnexpectedResponseError {
+            status,
+            body: body.to_string(),
+            request_id: None,
+        })
+    }
+
+    #[test]
+    fn routes_list_task_models_then_main_model() {
+        let routing = routing();
+        assert_eq!(
+            routed_models(&routing, "main", ModelTask::Summarization),
+            vec!["small", "main", "tiny"]
+        );
+        assert_eq!(
+            routed_models(&routing, "main", ModelTask::CommitMessage),
+            vec!["main"]
+        );
+        assert_eq!(
+            routed_models(&routing, "main", ModelTask::Main),
+            vec!["main", "backup"]
+        );
+    }
+
+    #[test]
+    fn only_model_rejections_count_as_unavailable() {
+        assert!(is_model_unavailable(&status_err(StatusCode::NOT_FOUND, "")));
+        assert!(is_model_unavailable(&status_err(
+            StatusCode::BAD_REQUEST,
+            r#"{"error":{"code":"model_not_found","message":"The model `x` does not exist"}}"#,
+        )));
+        assert!(!is_model_unavailable(&status_err(
+            StatusCode::BAD_REQUEST,
+            "invalid value for 'input'",
+        )));
+        assert!(!is_model_unavailable(&status_err(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "model not found",
+        )));
+        assert!(!is_model_unavailable(&CodexErr::Interrupted));
+    }
+}
@@ -0,0 +1,440 @@
+//! Local egress proxy that restricts which hosts agent commands can reach.
+//!
+//! When the `network_proxy` feature is enabled, each session starts a proxy on
+//! `127.0.0.1` that understands HTTP `CONNECT`, absolute-form HTTP requests and
+//! SOCKS5. Sandboxed commands receive `HTTP_PROXY`, `HTTPS_PROXY` and
+//! `ALL_PROXY` pointing at it, and only hosts listed in the active project's
+//! `network_allowed_hosts` are reachable through it. Each refused host is
+//! reported once per session as a [`NetworkHostBlockedEvent`] so clients can
+//! offer to extend the allowlist.
+//!
+//! Under Seatbelt and the Linux sandbox, the proxy is the only network
+//! destination sandboxed commands can connect to, so programs that ignore the
+//! proxy variables cannot reach the network either. Other sandboxes only set
+//! the variables.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_channel::Sender;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpListener;
+use tokio::net::TcpStream;
+use tokio_util::task::AbortOnDropHandle;
+use tracing::debug;
+
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::NetworkHostBlockedEvent;
+
+/// Upper bound on the size of an HTTP request head read by the proxy.
+const MAX_REQUEST_HEAD_BYTES: usize = 16 * 1024;
+
+const SOCKS5_VERSION: u8 = 0x05;
+
+/// How long the accept loop waits after `accept` fails before trying again.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+const FORBIDDEN_RESPONSE: &[u8] = b"HTTP/1.1 403 Forbidden\r\nContent-Type: text/plain\r\nContent-Length: 44\r\nConnection: close\r\n\r\nhost is not in codex's network_allowed_hosts";
+const BAD_REQUEST_RESPONSE: &[u8] =
+    b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+const BAD_GATEWAY_RESPONSE: &[u8] =
+    b"HTTP/1.1 502 Bad Gateway\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+
+/// A running proxy. The accept loop stops when this value is dropped.
+pub(crate) struct NetworkProxy {
+    addr: SocketAddr,
+    _accept_loop: AbortOnDropHandle<()>,
+}
+
+impl NetworkProxy {
+    /// Starts a proxy that only forwards to `allowed_hosts`. Blocked hosts are
+    /// reported on `tx_event` using `event_id`.
+    pub(crate) async fn start(
+        allowed_hosts: Vec<String>,
+        tx_event: Sender<Event>,
+        event_id: String,
+    ) -> io::Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let policy = Arc::new(ProxyPolicy {
+            allowed_hosts,
+            reported: Mutex::new(HashSet::new()),
+            tx_event,
+            event_id,
+        });
+        let accept_loop = tokio::spawn(async move {
+            loop {
+                let stream = match listener.accept().await {
+                    Ok((stream, _)) => stream,
+                    Err(err) => {
+                        // Errors such as running out of file descriptors
+                        // persist for a while, so don't retry right away.
+                        debug!("network proxy accept failed: {err}");
+                        tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                        continue;
+                    }
+                };
+                let policy = Arc::clone(&policy);
+                tokio::spawn(async move {
+                    if let Err(err) = handle_connection(stream, &policy).await {
+                        debug!("network proxy connection failed: {err}");
+                    }
+                });
+            }
+        });
+        Ok(Self {
+            addr,
+            _accept_loop: AbortOnDropHandle::new(accept_loop),
+        })
+    }
+
+    /// Port the proxy listens on, on `127.0.0.1`.
+    pub(crate) fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    /// Environment variables that route a child process through the proxy.
+    pub(crate) fn env(&self) -> HashMap<String, String> {
+        let http = format!("http://{}", self.addr);
+        let socks = format!("socks5h://{}", self.addr);
+        let no_proxy = "localhost,127.0.0.1,::1".to_string();
+        HashMap::from([
+            ("HTTP_PROXY".to_string(), http.clone()),
+            ("HTTPS_PROXY".to_string(), http.clone()),
+            ("ALL_PROXY".to_string(), socks.clone()),
+            ("NO_PROXY".to_string(), no_proxy.clone()),
+            ("http_proxy".to_string(), http.clone()),
+            ("https_proxy".to_string(), http),
+            ("all_proxy".to_string(), socks),
+            ("no_proxy".to_string(), no_proxy),
+        ])
+    }
+}
+
+struct ProxyPolicy {
+    allowed_hosts: Vec<String>,
+    reported: Mutex<HashSet<String>>,
+    tx_event: Sender<Event>,
+    event_id: String,
+}
+
+impl ProxyPolicy {
+    /// Returns whether `host` may be reached, reporting it the first time it
+    /// is refused.
+    async fn check(&self, host: &str, port: u16) -> bool {
+        if host_is_allowed(&self.allowed_hosts, host) {
+            return true;
+        }
+        let first_report = self
+            .reported
+            .lock()
+            .map(|mut reported| reported.insert(host.to_ascii_lowercase()))
+            .unwrap_or(false);
+        if first_report {
+            let event = Event {
+                id: self.event_id.clone(),
+                msg: EventMsg::NetworkHostBlocked(NetworkHostBlockedEvent {
+                    host: host.to_string(),
+                    port: i32::from(port),
+                }),
+            };
+            if let Err(err) = self.tx_event.send(event).await {
+                debug!("failed to report blocked host {host}: {err}");
+            }
+        }
+        false
+    }
+}
+
+/// Returns whether `host` matches an entry in `allowed_hosts`. Entries match
+/// exactly, ignoring case, or as `*.example.com` for any subdomain of
+/// `example.com`.
+pub(crate) fn host_is_allowed(allowed_hosts: &[String], host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowed_hosts.iter().any(|pattern| {
+        let pattern = pattern.trim().trim_end_matches('.').to_ascii_lowercase();
+        match pattern.strip_prefix("*.") {
+            Some(domain) => host
+                .strip_suffix(domain)
+                .is_some_and(|prefix| prefix.ends_with('.')),
+            None => host == pattern,
+        }
+    })
+}
+
+async fn handle_connection(mut client: TcpStream, policy: &ProxyPolicy) -> io::Result<()> {
+    let mut first = [0u8; 1];
+    if client.peek(&mut first).await? == 0 {
+        return Ok(());
+    }
+    if first[0] == SOCKS5_VERSION {
+        handle_socks5(client, policy).await
+    } else {
+        handle_http(&mut client, policy).await
+    }
+}
+
+async fn handle_http(client: &mut TcpStream, policy: &ProxyPolicy) -> io::Result<()> {
+    let mut buf = Vec::new();
+    let head_end = loop {
+        if let Some(pos) = buf.windows(4).position(|window| window == b"\r\n\r\n") {
+            break pos + 4;
+        }
+        if buf.len() > MAX_REQUEST_HEAD_BYTES {
+            return client.write_all(BAD_REQUEST_RESPONSE).await;
+        }
+        let mut chunk = [0u8; 4096];
+        let read = client.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    };
+
+    let request_line = String::from_utf8_lossy(&buf[..head_end]);
+    let request_line = request_line.lines().next().unwrap_or_default().to_string();
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return client.write_all(BAD_REQUEST_RESPONSE).await;
+    };
+    let is_connect = method.eq_ignore_ascii_case("CONNECT");
+    let authority = if is_connect {
+        Some(target)
+    } else {
+        target
+            .strip_prefix("http://")
+            .map(|rest| rest.split('/').next().unwrap_or(rest))
+    };
+    let default_port = if is_connect { 443 } else { 80 };
+    let Some((host, port)) =
+        authority.and_then(|authority| split_host_port(authority, default_port))
+    else {
+        return client.write_all(BAD_REQUEST_RESPONSE).await;
+    };
+
+    if !policy.check(&host, port).await {
+        return client.write_all(FORBIDDEN_RESPONSE).await;
+    }
+    let mut upstream = match TcpStream::connect((host.as_str(), port)).await {
+        Ok(upstream) => upstream,
+        Err(_) => return client.write_all(BAD_GATEWAY_RESPONSE).await,
+    };
+
+    if is_connect {
+        client
+            .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+            .await?;
+        upstream.write_all(&buf[head_end..]).await?;
+    } else {
+        // Origin servers must accept absolute-form targets, so the request is
+        // forwarded unchanged.
+        upstream.write_all(&buf).await?;
+    }
+    tokio::io::copy_bidirectional(client, &mut upstream).await?;
+    Ok(())
+}
+
+async fn handle_socks5(mut client: TcpStream, policy: &ProxyPolicy) -> io::Result<()> {
+    // Greeting: VER, NMETHODS, METHODS...
+    let mut greeting = [0u8; 2];
+    client.read_exact(&mut greeting).await?;
+    let mut methods = vec![0u8; usize::from(greeting[1])];
+    client.read_exact(&mut methods).await?;
+    if !methods.contains(&0x00) {
+        return client.write_all(&[SOCKS5_VERSION, 0xff]).await;
+    }
+    client.write_all(&[SOCKS5_VERSION, 0x00]).await?;
+
+    // Request: VER, CMD, RSV, ATYP, DST.ADDR, DST.PORT
+    let mut request = [0u8; 4];
+    client.read_exact(&mut request).await?;
+    if request[1] != 0x01 {
+        return write_socks5_reply(&mut client, 0x07).await;
+    }
+    let host = match request[3] {
+        0x01 => {
+            let mut octets = [0u8; 4];
+            client.read_exact(&mut octets).await?;
+            std::net::Ipv4Addr::from(octets).to_string()
+        }
+        0x03 => {
+            let mut len = [0u8; 1];
+            client.read_exact(&mut len).await?;
+            let mut name = vec![0u8; usize::from(len[0])];
+            client.read_exact(&mut name).await?;
+            String::from_utf8_lossy(&name).into_owned()
+        }
+        0x04 => {
+            let mut octets = [0u8; 16];
+            client.read_exact(&mut octets).await?;
+            std::net::Ipv6Addr::from(octets).to_string()
+        }
+        _ => return write_socks5_reply(&mut client, 0x08).await,
+    };
+    let mut port = [0u8; 2];
+    client.read_exact(&mut port).await?;
+    let port = u16::from_be_bytes(port);
+
+    if !policy.check(&host, port).await {
+        // 0x02: connection not allowed by ruleset.
+        return write_socks5_reply(&mut client, 0x02).await;
+    }
+    let mut upstream = match TcpStream::connect((host.as_str(), port)).await {
+        Ok(upstream) => upstream,
+        Err(_) => return write_socks5_reply(&mut client, 0x05).await,
+    };
+    write_socks5_reply(&mut client, 0x00).await?;
+    tokio::io::copy_bidirectional(&mut client, &mut upstream).await?;
+    Ok(())
+}
+
+async fn write_socks5_reply(client: &mut TcpStream, status: u8) -> io::Result<()> {
+    client
+        .write_all(&[SOCKS5_VERSION, status, 0x00, 0x01, 0, 0, 0, 0, 0, 0])
+        .await
+}
+
+/// Splits `host:port`, `[v6]:port` or a bare host into its parts.
+fn split_host_port(authority: &str, default_port: u16) -> Option<(String, u16)> {
+    let authority = authority.rsplit('@').next().unwrap_or(authority);
+    if let Some(rest) = authority.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']')?;
+        let port = match rest.strip_prefix(':') {
+            Some(port) => port.parse().ok()?,
+            None => default_port,
+        };
+        return Some((host.to_string(), port));
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) => Some((host.to_string(), port.parse().ok()?)),
+        None if !authority.is_empty() => Some((authority.to_string(), default_port)),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn allowlist_matches_exact_hosts_and_wildcard_subdomains() {
+        let allowed = vec!["github.com".to_string(), "*.crates.io".to_string()];
+
+        assert!(host_is_allowed(&allowed, "GitHub.com"));
+        assert!(host_is_allowed(&allowed, "static.crates.io"));
+        assert!(!host_is_allowed(&allowed, "crates.io"));
+        assert!(!host_is_allowed(&allowed, "evilcrates.io"));
+        assert!(!host_is_allowed(&allowed, "api.github.com"));
+    }
+
+    #[test]
+    fn split_host_port_handles_ipv6_and_defaults() {
+        assert_eq!(
+            split_host_port("example.com:8443", 443),
+            Some(("example.com".to_string(), 8443))
+        );
+        assert_eq!(
+            split_host_port("example.com", 80),
+            Some(("example.com".to_string(), 80))
+        );
+        assert_eq!(
+            split_host_port("[::1]:9000", 443),
+            Some(("::1".to_string(), 9000))
+        );
+        assert_eq!(split_host_port("", 443), None);
+    }
+
+    #[tokio::test]
+    async fn connect_requests_are_filtered_and_blocked_hosts_reported_once() {
+        let echo = TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .expect("bind echo");
+        let echo_port = echo.local_addr().expect("echo addr").port();
+        tokio::spawn(async move {
+            let (mut stream, _) = echo.accept().await.expect("accept");
+            let mut buf = [0u8; 4];
+            stream.read_exact(&mut buf).await.expect("read");
+            stream.write_all(&buf).await.expect("write");
+        });
+
+        let (tx_event, rx_event) = async_channel::unbounded();
+        let proxy = NetworkProxy::start(vec!["127.0.0.1".to_string()], tx_event, "sub".into())
+            .await
+            .expect("start proxy");
+
+        let mut allowed = TcpStream::connect(proxy.addr).await.expect("connect");
+        allowed
+            .write_all(
+                format!("CONNECT 127.0.0.1:{echo_port} HTTP/1.1\r\nHost: x\r\n\r\nping").as_bytes(),
+            )
+            .await
+            .expect("send connect");
+        let mut response = vec![0u8; "HTTP/1.1 200 Connection Established\r\n\r\nping".len()];
+        allowed
+            .read_exact(&mut response)
+            .await
+            .expect("read tunnel");
+        assert_eq!(
+            String::from_utf8_lossy(&response),
+            "HTTP/1.1 200 Connection Established\r\n\r\nping"
+        );
+
+        for _ in 0..2 {
+            let mut blocked = TcpStream::connect(proxy.addr).await.expect("connect");
+            blocked
+                .write_all(b"CONNECT blocked.example:443 HTTP/1.1\r\n\r\n")
+                .await
+                .expect("send connect");
+            let mut response = Vec::new();
+            blocked
+                .read_to_end(&mut response)
+                .await
+                .expect("read response");
+            assert!(response.starts_with(b"HTTP/1.1 403 Forbidden"));
+        }
+
+        let event = rx_event.recv().await.expect("blocked event");
+        assert_eq!(event.id, "sub");
+        match event.msg {
+            EventMsg::NetworkHostBlocked(ev) => assert_eq!(
+                ev,
+                NetworkHostBlockedEvent {
+                    host: "blocked.example".to_string(),
+                    port: 443,
+                }
+            ),
+            other => panic!("unexpected event: {other:?}"),
+        }
+        assert!(rx_event.is_empty());
+    }
+
+    #[tokio::test]
+    async fn socks5_requests_to_blocked_hosts_are_refused() {
+        let (tx_event, _rx_event) = async_channel::unbounded();
+        let proxy = NetworkProxy::start(Vec::new(), tx_event, "sub".into())
+            .await
+            .expect("start proxy");
+
+        let mut client = TcpStream::connect(proxy.addr).await.expect("connect");
+        client.write_all(&[0x05, 0x01, 0x00]).await.expect("greet");
+        let mut method = [0u8; 2];
+        client.read_exact(&mut method).await.expect("method");
+        assert_eq!(method, [0x05, 0x00]);
+
+        let mut request = vec![0x05, 0x01, 0x00, 0x03, 11];
+        request.extend_from_slice(b"example.com");
+        request.extend_from_slice(&443u16.to_be_bytes());
+        client.write_all(&request).await.expect("request");
+        let mut reply = [0u8; 10];
+        client.read_exact(&mut reply).await.expect("reply");
+        assert_eq!(reply[1], 0x02);
+    }
+}
@@ -1,9 +1,11 @@
 use crate::config::Config;
 use crate::config::types::OtelExporterKind as Kind;
 use crate::config::types::OtelHttpProtocol as Protocol;
+use crate::config::types::OtelScrubConfig;
 use crate::default_client::originator;
 use codex_otel::config::OtelExporter;
 use codex_otel::config::OtelHttpProtocol;
+use codex_otel::config::OtelScrubSettings;
 use codex_otel::config::OtelSettings;
 use codex_otel::config::OtelTlsConfig as OtelTlsSettings;
 use codex_otel::otel_provider::OtelProvider;
@@ -23,6 +25,7 @@ pub fn build_provider(
             headers,
             protocol,
             tls,
+            scrub,
         } => {
             let protocol = match protocol {
                 Protocol::Json => OtelHttpProtocol::Json,
@@ -41,12 +44,14 @@ pub fn build_provider(
                     client_certificate: config.client_certificate.clone(),
                     client_private_key: config.client_private_key.clone(),
                 }),
+                scrub: scrub_settings(scrub),
             }
         }
         Kind::OtlpGrpc {
             endpoint,
             headers,
             tls,
+            scrub,
         } => OtelExporter::OtlpGrpc {
             endpoint: endpoint.clone(),
             headers: headers
@@ -58,6 +63,7 @@ pub fn build_provider(
                 client_certificate: config.client_certificate.clone(),
                 client_private_key: config.client_private_key.clone(),
             }),
+            scrub: scrub_settings(scrub),
         },
     };
 
@@ -70,6 +76,14 @@ pub fn build_provider(
     })
 }
 
+fn scrub_settings(config: &OtelScrubConfig) -> OtelScrubSettings {
+    OtelScrubSettings {
+        hash_paths: config.hash_paths,
+        drop_content: config.drop_content,
+        allowed_attributes: config.allowed_attributes.clone(),
+    }
+}
+
 /// Filter predicate for exporting only Codex-owned events via OTEL.
 /// Keeps events that originated from codex_otel module
 pub fn codex_export_filter(meta: &tracing::Metadata<'_>) -> bool {
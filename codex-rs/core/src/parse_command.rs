@@ -1,7 +1,12 @@
 use crate::bash::extract_bash_command;
+use crate::bash::parse_shell_lc_plain_commands;
 use crate::bash::try_parse_shell;
 use crate::bash::try_parse_word_only_commands_sequence;
 use crate::powershell::extract_powershell_command;
+use crate::windows_shell::extract_cmd_command;
+use crate::windows_shell::parse_cmd_plain_commands;
+use crate::windows_shell::parse_powershell_plain_commands;
+use crate::windows_shell::windows_join;
 use codex_protocol::parse_command::ParsedCommand;
 use shlex::split as shlex_split;
 use shlex::try_join as shlex_try_join;
@@ -14,7 +19,18 @@ pub fn shlex_join(tokens: &[String]) -> String {
 
 /// Extracts the shell and script from a command, regardless of platform
 pub fn extract_shell_command(command: &[String]) -> Option<(&str, &str)> {
-    extract_bash_command(command).or_else(|| extract_powershell_command(command))
+    extract_bash_command(command)
+        .or_else(|| extract_powershell_command(command))
+        .or_else(|| extract_cmd_command(command))
+}
+
+/// Returns the plain commands within a `bash -lc`, `powershell -Command`, or
+/// `cmd /c` script when it only joins word-only commands with sequencing and
+/// pipe operators.
+pub fn parse_shell_plain_commands(command: &[String]) -> Option<Vec<Vec<String>>> {
+    parse_shell_lc_plain_commands(command)
+        .or_else(|| parse_powershell_plain_commands(command))
+        .or_else(|| parse_cmd_plain_commands(command))
 }
 
 /// DO NOT REVIEW THIS CODE BY HAND
@@ -888,8 +904,57 @@ mod tests {
     fn powershell_command_is_stripped() {
         assert_parsed(
             &vec_str(&["powershell", "-Command", "Get-ChildItem"]),
-            vec![ParsedCommand::Unknown {
+            vec![ParsedCommand::ListFiles {
                 cmd: "Get-ChildItem".to_string(),
+                path: None,
+            }],
+        );
+    }
+
+    #[test]
+    fn powershell_pipeline_is_summarized_per_command() {
+        assert_parsed(
+            &vec_str(&[
+                "pwsh",
+                "-NoProfile",
+                "-Command",
+                "Get-Content src/main.rs | Select-String 'fn main'",
+            ]),
+            vec![
+                ParsedCommand::Read {
+                    cmd: "Get-Content src/main.rs".to_string(),
+                    name: "main.rs".to_string(),
+                    path: PathBuf::from("src/main.rs"),
+                },
+                ParsedCommand::Search {
+                    cmd: "Select-String \"fn main\"".to_string(),
+                    query: Some("fn main".to_string()),
+                    path: None,
+                },
+            ],
+        );
+    }
+
+    #[test]
+    fn cmd_payload_is_summarized() {
+        assert_parsed(
+            &vec_str(&["cmd.exe", "/c", "dir /b src & type README.md"]),
+            vec![
+                ParsedCommand::ListFiles {
+                    cmd: "dir /b src".to_string(),
+                    path: Some("src".to_string()),
+                },
+                ParsedCommand::Read {
+                    cmd: "type README.md".to_string(),
+                    name: "README.md".to_string(),
+                    path: PathBuf::from("README.md"),
+                },
+            ],
+        );
+        assert_parsed(
+            &vec_str(&["cmd", "/c", "echo %PATH%"]),
+            vec![ParsedCommand::Unknown {
+                cmd: "echo %PATH%".to_string(),
             }],
         );
     }
@@ -926,7 +991,18 @@ pub fn parse_command_impl(command: &[String]) -> Vec<ParsedCommand> {
         return commands;
     }
 
-    if let Some((_, script)) = extract_powershell_command(command) {
+    if let Some(commands) =
+        parse_powershell_plain_commands(command).or_else(|| parse_cmd_plain_commands(command))
+    {
+        return commands
+            .iter()
+            .map(|tokens| summarize_windows_tokens(tokens))
+            .collect();
+    }
+
+    if let Some((_, script)) =
+        extract_powershell_command(command).or_else(|| extract_cmd_command(command))
+    {
         return vec![ParsedCommand::Unknown {
             cmd: script.to_string(),
         }];
@@ -1407,6 +1483,48 @@ fn drop_small_formatting_commands(mut commands: Vec<Vec<String>>) -> Vec<Vec<Str
     commands
 }
 
+/// Summarizes one PowerShell or cmd.exe command. Cmdlet names and their
+/// aliases are matched case-insensitively, as Windows does.
+fn summarize_windows_tokens(tokens: &[String]) -> ParsedCommand {
+    let cmd = windows_join(tokens);
+    let Some((head, tail)) = tokens.split_first() else {
+        return ParsedCommand::Unknown { cmd };
+    };
+    let operands: Vec<&String> = tail.iter().filter(|arg| !is_windows_flag(arg)).collect();
+    match head.to_ascii_lowercase().as_str() {
+        "get-content" | "gc" | "cat" | "type" if operands.len() == 1 => {
+            let path = operands[0].clone();
+            ParsedCommand::Read {
+                cmd,
+                name: short_display_path(&path),
+                path: PathBuf::from(path),
+            }
+        }
+        "get-childitem" | "gci" | "ls" | "dir" => ParsedCommand::ListFiles {
+            cmd,
+            path: operands.first().map(|path| short_display_path(path)),
+        },
+        "select-string" | "sls" | "findstr" => ParsedCommand::Search {
+            cmd,
+            query: operands.first().map(|query| (*query).clone()),
+            path: operands.get(1).map(|path| short_display_path(path)),
+        },
+        _ => ParsedCommand::Unknown { cmd },
+    }
+}
+
+/// PowerShell parameters start with `-`; cmd.exe switches with `/` followed
+/// by a short option name (`/s`, `/b`, `/a:h`), unlike absolute paths.
+fn is_windows_flag(arg: &str) -> bool {
+    if arg.starts_with('-') {
+        return true;
+    }
+    arg.strip_prefix('/').is_some_and(|name| {
+        let name = name.split(':').next().unwrap_or(name);
+        !name.is_empty() && name.len() <= 2 && name.chars().all(|c| c.is_ascii_alphanumeric())
+    })
+}
+
 fn summarize_main_tokens(main_cmd: &[String]) -> ParsedCommand {
     match main_cmd.split_first() {
         Some((head, tail)) if head == "ls" => {
@@ -0,0 +1,368 @@
+//! Files and directories pinned as persistent context for a conversation.
+//!
+//! Pins are stored per conversation in
+//! `$CODEX_HOME/pinned_context/<conversation id>.json`. Before every request to
+//! the model the pinned paths are read again and sent as one user message
+//! after the history, so the model always sees their current contents. The
+//! message is not recorded in the history, and it is truncated to a share of
+//! the model's context window so pins cannot crowd out the conversation.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ConversationId;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::truncate::TruncationPolicy;
+use crate::truncate::approx_token_count;
+use crate::truncate::truncate_text;
+
+/// Directory under `CODEX_HOME` that holds the pins of each conversation.
+pub const PINNED_CONTEXT_DIR: &str = "pinned_context";
+
+/// Share of the model context window, in percent, that pinned context may use.
+const CONTEXT_WINDOW_PERCENT: i64 = 10;
+
+/// Budget used when the model's context window is unknown.
+const DEFAULT_BUDGET_TOKENS: usize = 8_000;
+
+/// Files larger than this are skipped instead of read.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+const PINNED_CONTEXT_OPEN_TAG: &str = "<pinned_context>";
+const PINNED_CONTEXT_CLOSE_TAG: &str = "</pinned_context>";
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct PinnedContextFile {
+    #[serde(default)]
+    paths: Vec<PathBuf>,
+}
+
+fn pins_path(codex_home: &Path, conversation_id: ConversationId) -> PathBuf {
+    codex_home
+        .join(PINNED_CONTEXT_DIR)
+        .join(format!("{conversation_id}.json"))
+}
+
+/// Paths pinned to `conversation_id`, in the order they were added.
+pub async fn list_pinned_context(
+    codex_home: &Path,
+    conversation_id: ConversationId,
+) -> io::Result<Vec<PathBuf>> {
+    Ok(read_file(&pins_path(codex_home, conversation_id))
+        .await?
+        .paths)
+}
+
+/// Pins `path`, which must be an absolute path to an existing file or
+/// directory. Pinning a path twice is a no-op. Returns the updated pins.
+pub async fn add_pinned_context(
+    codex_home: &Path,
+    conversation_id: ConversationId,
+    path: &Path,
+) -> io::Result<Vec<PathBuf>> {
+    if !path.is_absolute() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("pinned path must be absolute: {}", path.display()),
+        ));
+    }
+    if let Err(err) = tokio::fs::metadata(path).await {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("cannot pin {}: {err}", path.display()),
+        ));
+    }
+    let file_path = pins_path(codex_home, conversation_id);
+    let mut file = read_file(&file_path).await?;
+    if !file.paths.iter().any(|pinned| pinned == path) {
+        file.paths.push(path.to_path_buf());
+        write_file(&file_path, &file).await?;
+    }
+    Ok(file.paths)
+}
+
+/// Unpins `path`. Returns the updated pins, or `None` when `path` was not
+/// pinned.
+pub async fn remove_pinned_context(
+    codex_home: &Path,
+    conversation_id: ConversationId,
+    path: &Path,
+) -> io::Result<Option<Vec<PathBuf>>> {
+    let file_path = pins_path(codex_home, conversation_id);
+    let mut file = read_file(&file_path).await?;
+    let before = file.paths.len();
+    file.paths.retain(|pinned| pinned != path);
+    if file.paths.len() == before {
+        return Ok(None);
+    }
+    write_file(&file_path, &file).await?;
+    Ok(Some(file.paths))
+}
+
+/// Token budget for pinned context given the model's context window.
+pub(crate) fn pinned_context_budget(context_window: Option<i64>) -> usize {
+    context_window
+        .map(|window| window.saturating_mul(CONTEXT_WINDOW_PERCENT) / 100)
+        .and_then(|tokens| usize::try_from(tokens).ok())
+        .unwrap_or(DEFAULT_BUDGET_TOKENS)
+}
+
+/// Reads the current contents of every pin and renders them as a user message
+/// of at most `budget_tokens`, or `None` when nothing is pinned.
+pub(crate) async fn pinned_context_item(
+    codex_home: &Path,
+    conversation_id: ConversationId,
+    budget_tokens: usize,
+) -> Option<ResponseItem> {
+    let paths = match list_pinned_context(codex_home, conversation_id).await {
+        Ok(paths) => paths,
+        Err(err) => {
+            tracing::warn!("failed to load pinned context: {err}");
+            return None;
+        }
+    };
+    if paths.is_empty() {
+        return None;
+    }
+
+    let mut sections = Vec::new();
+    for path in &paths {
+        sections.extend(read_pin(path).await);
+    }
+    Some(ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: render_sections(&sections, budget_tokens),
+        }],
+    })
+}
+
+/// One pinned file, or a note explaining why it could not be read.
+#[derive(Debug, PartialEq, Eq)]
+struct Section {
+    path: PathBuf,
+    body: String,
+}
+
+/// Sections for a pinned file, or for the regular files directly inside a
+/// pinned directory.
+async fn read_pin(path: &Path) -> Vec<Section> {
+    let metadata = match tokio::fs::metadata(path).await {
+        Ok(metadata) => metadata,
+        Err(err) => {
+            return vec![Section {
+                path: path.to_path_buf(),
+                body: format!("[unavailable: {err}]"),
+            }];
+        }
+    };
+    if !metadata.is_dir() {
+        return vec![read_section(path.to_path_buf()).await];
+    }
+
+    let mut files = Vec::new();
+    match tokio::fs::read_dir(path).await {
+        Ok(mut entries) => {
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                let is_file = entry
+                    .file_type()
+                    .await
+                    .map(|file_type| file_type.is_file())
+                    .unwrap_or(false);
+                let hidden = entry.file_name().to_string_lossy().starts_with('.');
+                if is_file && !hidden {
+                    files.push(entry.path());
+                }
+            }
+        }
+        Err(err) => {
+            return vec![Section {
+                path: path.to_path_buf(),
+                body: format!("[unavailable: {err}]"),
+            }];
+        }
+    }
+    files.sort();
+
+    let mut sections = Vec::with_capacity(files.len());
+    for file in files {
+        sections.push(read_section(file).await);
+    }
+    sections
+}
+
+async fn read_section(path: PathBuf) -> Section {
+    let body = match tokio::fs::metadata(&path).await {
+        Ok(metadata) if metadata.len() > MAX_FILE_BYTES => format!(
+            "[skipped: {} bytes exceeds the {MAX_FILE_BYTES} byte limit]",
+            metadata.len()
+        ),
+        Ok(_) => match tokio::fs::read(&path).await {
+            Ok(bytes) => match String::from_utf8(bytes) {
+                Ok(text) => text,
+                Err(_) => "[skipped: not a UTF-8 text file]".to_string(),
+            },
+            Err(err) => format!("[unavailable: {err}]"),
+        },
+        Err(err) => format!("[unavailable: {err}]"),
+    };
+    Section { path, body }
+}
+
+/// Renders `sections`, splitting the budget evenly across the sections that
+/// remain so a short file leaves its unused share to the ones after it.
+fn render_sections(sections: &[Section], budget_tokens: usize) -> String {
+    let mut text = format!(
+        "{PINNED_CONTEXT_OPEN_TAG}\nThe user pinned these files as context. Their contents are current as of this request.\n"
+    );
+    let mut remaining = budget_tokens.saturating_sub(approx_token_count(&text));
+    for (index, section) in sections.iter().enumerate() {
+        let share = remaining / (sections.len() - index);
+        let body = truncate_text(&section.body, TruncationPolicy::Tokens(share));
+        let rendered = format!(
+            "<file path=\"{}\">\n{}\n</file>\n",
+            section.path.display(),
+            body.trim_end()
+        );
+        remaining = remaining.saturating_sub(approx_token_count(&rendered));
+        text.push_str(&rendered);
+    }
+    text.push_str(PINNED_CONTEXT_CLOSE_TAG);
+    text
+}
+
+async fn read_file(path: &Path) -> io::Result<PinnedContextFile> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(PinnedContextFile::default()),
+        Err(err) => Err(err),
+    }
+}
+
+async fn write_file(path: &Path, file: &PinnedContextFile) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut contents = serde_json::to_string_pretty(file).map_err(io::Error::other)?;
+    contents.push('\n');
+    tokio::fs::write(path, contents).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn pins_are_added_once_and_removed() {
+        let codex_home = TempDir::new().expect("codex home");
+        let workspace = TempDir::new().expect("workspace");
+        let notes = workspace.path().join("notes.md");
+        std::fs::write(&notes, "remember this").expect("write notes");
+        let conversation_id = ConversationId::new();
+
+        let pins = add_pinned_context(codex_home.path(), conversation_id, &notes)
+            .await
+            .expect("add");
+        assert_eq!(pins, vec![notes.clone()]);
+        let pins = add_pinned_context(codex_home.path(), conversation_id, workspace.path())
+            .await
+            .expect("add dir");
+        assert_eq!(pins, vec![notes.clone(), workspace.path().to_path_buf()]);
+        add_pinned_context(codex_home.path(), conversation_id, &notes)
+            .await
+            .expect("add again");
+
+        assert_eq!(
+            remove_pinned_context(codex_home.path(), conversation_id, &notes)
+                .await
+                .expect("remove"),
+            Some(vec![workspace.path().to_path_buf()])
+        );
+        assert_eq!(
+            remove_pinned_context(codex_home.path(), conversation_id, &notes)
+                .await
+                .expect("remove again"),
+            None
+        );
+        assert_eq!(
+            list_pinned_context(codex_home.path(), ConversationId::new())
+                .await
+                .expect("other conversation"),
+            Vec::<PathBuf>::new()
+        );
+    }
+
+    #[tokio::test]
+    async fn relative_and_missing_paths_are_rejected() {
+        let codex_home = TempDir::new().expect("codex home");
+        let conversation_id = ConversationId::new();
+        for path in [
+            PathBuf::from("notes.md"),
+            codex_home.path().join("missing.md"),
+        ] {
+            let err = add_pinned_context(codex_home.path(), conversation_id, &path)
+                .await
+                .expect_err("invalid pin");
+            assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        }
+    }
+
+    #[tokio::test]
+    async fn pinned_directories_render_their_files_with_current_contents() {
+        let codex_home = TempDir::new().expect("codex home");
+        let workspace = TempDir::new().expect("workspace");
+        std::fs::write(workspace.path().join("b.txt"), "bee").expect("write b");
+        std::fs::write(workspace.path().join("a.txt"), "old").expect("write a");
+        std::fs::write(workspace.path().join(".hidden"), "secret").expect("write hidden");
+        let conversation_id = ConversationId::new();
+        add_pinned_context(codex_home.path(), conversation_id, workspace.path())
+            .await
+            .expect("add");
+        std::fs::write(workspace.path().join("a.txt"), "new").expect("rewrite a");
+
+        let Some(ResponseItem::Message { role, content, .. }) =
+            pinned_context_item(codex_home.path(), conversation_id, 1_000).await
+        else {
+            panic!("expected a pinned context message");
+        };
+        assert_eq!(role, "user");
+        let [ContentItem::InputText { text }] = content.as_slice() else {
+            panic!("expected a single text item");
+        };
+        let root = workspace.path();
+        assert_eq!(
+            text,
+            &format!(
+                "<pinned_context>\nThe user pinned these files as context. Their contents are current as of this request.\n<file path=\"{}\">\nnew\n</file>\n<file path=\"{}\">\nbee\n</file>\n</pinned_context>",
+                root.join("a.txt").display(),
+                root.join("b.txt").display()
+            )
+        );
+    }
+
+    #[test]
+    fn rendering_respects_the_token_budget() {
+        let sections = vec![
+            Section {
+                path: PathBuf::from("/big.txt"),
+                body: "x".repeat(40_000),
+            },
+            Section {
+                path: PathBuf::from("/small.txt"),
+                body: "small".to_string(),
+            },
+        ];
+        let text = render_sections(&sections, 500);
+        assert!(approx_token_count(&text) <= 600, "{}", text.len());
+        assert!(text.contains("<file path=\"/small.txt\">\nsmall\n</file>"));
+    }
+}
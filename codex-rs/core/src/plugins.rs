@@ -0,0 +1,649 @@
+//! WebAssembly plugins that add function tools, loaded from
+//! `$CODEX_HOME/plugins/`.
+//!
+//! Each plugin is a directory holding a `plugin.json` manifest and a core
+//! WebAssembly module. The manifest declares the functions the module
+//! implements and the capabilities it is granted. Plugins run without WASI:
+//! a module may only import the `codex` host functions its capabilities
+//! allow, so it has no ambient access to the filesystem, network, clock, or
+//! environment. Every call runs in a fresh instance with bounded memory and
+//! an instruction budget.
+//!
+//! Module ABI:
+//! - export `memory`;
+//! - export `alloc(len: i32) -> i32`, returning a guest buffer of `len` bytes;
+//! - export `call(name_ptr: i32, name_len: i32, args_ptr: i32, args_len: i32) -> i32`,
+//!   called with the function name and its JSON arguments, returning `0` on
+//!   success and anything else on failure;
+//! - import `codex.output(ptr: i32, len: i32)` to append UTF-8 text to the
+//!   tool output;
+//! - with the `read_workspace` capability, import
+//!   `codex.read_file(path_ptr: i32, path_len: i32) -> i64` to read a file
+//!   inside the turn's working directory. It returns `(ptr << 32) | len` of a
+//!   buffer obtained from `alloc`, or `-1` when the file cannot be read.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use tracing::warn;
+use wasmtime::Caller;
+use wasmtime::Engine;
+use wasmtime::Extern;
+use wasmtime::Linker;
+use wasmtime::Memory;
+use wasmtime::Module;
+use wasmtime::Store;
+use wasmtime::StoreLimits;
+use wasmtime::StoreLimitsBuilder;
+use wasmtime::TypedFunc;
+
+use crate::config::types::McpServerConfig;
+
+/// Directory under `CODEX_HOME` that holds one subdirectory per plugin.
+pub const PLUGINS_DIR: &str = "plugins";
+
+const MANIFEST_FILE: &str = "plugin.json";
+const DEFAULT_MODULE_FILE: &str = "plugin.wasm";
+/// Separator between the plugin and function name in a tool name, matching
+/// the one used for MCP tools.
+const TOOL_NAME_DELIMITER: &str = "__";
+/// Longest tool name the Responses API accepts.
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Linear memory a plugin instance may grow to.
+const MAX_MEMORY_BYTES: usize = 64 * 1024 * 1024;
+/// Instructions a single call may execute before it is stopped.
+const MAX_FUEL: u64 = 2_000_000_000;
+/// Output a single call may produce.
+const MAX_OUTPUT_BYTES: usize = 1024 * 1024;
+/// Largest file `codex.read_file` returns.
+const MAX_READ_BYTES: u64 = 1024 * 1024;
+const MAX_PATH_BYTES: usize = 4096;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PluginManifest {
+    #[serde(default = "default_module_file")]
+    module: String,
+    #[serde(default)]
+    capabilities: Vec<Capability>,
+    functions: Vec<FunctionManifest>,
+}
+
+fn default_module_file() -> String {
+    DEFAULT_MODULE_FILE.to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Capability {
+    /// Read files inside the turn's working directory.
+    ReadWorkspace,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FunctionManifest {
+    name: String,
+    #[serde(default)]
+    description: String,
+    /// JSON Schema for the function's arguments.
+    #[serde(default = "empty_object_schema")]
+    parameters: JsonValue,
+}
+
+fn empty_object_schema() -> JsonValue {
+    serde_json::json!({ "type": "object", "properties": {} })
+}
+
+/// A function tool declared by a plugin.
+#[derive(Debug, Clone)]
+pub(crate) struct PluginTool {
+    /// Name the model calls the tool by: `<plugin>__<function>`.
+    pub(crate) name: String,
+    pub(crate) plugin: String,
+    pub(crate) description: String,
+    pub(crate) parameters: JsonValue,
+}
+
+#[derive(Clone)]
+struct PluginFunction {
+    plugin: String,
+    function: String,
+    description: String,
+    parameters: JsonValue,
+    module: Module,
+    read_workspace: bool,
+}
+
+/// What a plugin call produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PluginCallOutput {
+    pub(crate) content: String,
+    pub(crate) success: bool,
+}
+
+/// The compiled plugins of a session, keyed by tool name.
+pub(crate) struct PluginHost {
+    engine: Engine,
+    functions: BTreeMap<String, PluginFunction>,
+}
+
+impl PluginHost {
+    /// Compiles every plugin under `codex_home/plugins/`. Plugins that cannot
+    /// be loaded are skipped; the returned messages explain why. Returns no
+    /// host when there are no plugins.
+    pub(crate) fn load(
+        codex_home: &Path,
+        mcp_servers: &HashMap<String, McpServerConfig>,
+    ) -> (Option<Self>, Vec<String>) {
+        let dir = codex_home.join(PLUGINS_DIR);
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return (None, Vec::new());
+        };
+        let mut dirs: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        if dirs.is_empty() {
+            return (None, Vec::new());
+        }
+        dirs.sort();
+
+        let engine = match new_engine() {
+            Ok(engine) => engine,
+            Err(err) => {
+                return (
+                    None,
+                    vec![format!("Could not start the plugin host: {err:#}")],
+                );
+            }
+        };
+        let mut functions = BTreeMap::new();
+        let mut warnings = Vec::new();
+        for dir in dirs {
+            let Some(name) = dir.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if name.starts_with('.') {
+                continue;
+            }
+            let loaded = if mcp_servers.contains_key(name) {
+                Err(anyhow::anyhow!("an MCP server has the same name"))
+            } else {
+                load_plugin(&engine, name, &dir)
+            };
+            match loaded {
+                Ok(plugin_functions) => {
+                    for function in plugin_functions {
+                        let tool_name = format!(
+                            "{}{TOOL_NAME_DELIMITER}{}",
+                            function.plugin, function.function
+                        );
+                        functions.insert(tool_name, function);
+                    }
+                }
+                Err(err) => {
+                    warn!("skipping plugin {}: {err:#}", dir.display());
+                    warnings.push(format!("Skipped plugin `{name}`: {err:#}"));
+                }
+            }
+        }
+
+        if functions.is_empty() {
+            return (None, warnings);
+        }
+        (Some(Self { engine, functions }), warnings)
+    }
+
+    /// The tools declared by all loaded plugins, sorted by name.
+    pub(crate) fn tools(&self) -> Vec<PluginTool> {
+        self.functions
+            .iter()
+            .map(|(name, function)| PluginTool {
+                name: name.clone(),
+                plugin: function.plugin.clone(),
+                description: function.description.clone(),
+                parameters: function.parameters.clone(),
+            })
+            .collect()
+    }
+
+    /// Calls the plugin function behind `tool_name` with the model's JSON
+    /// `arguments`. `cwd` bounds what the `read_workspace` capability can see.
+    pub(crate) async fn call(
+        &self,
+        tool_name: &str,
+        arguments: String,
+        cwd: &Path,
+    ) -> Result<PluginCallOutput, String> {
+        let function = self
+            .functions
+            .get(tool_name)
+            .cloned()
+            .ok_or_else(|| format!("unknown plugin tool `{tool_name}`"))?;
+        let engine = self.engine.clone();
+        let workspace = function.read_workspace.then(|| cwd.to_path_buf());
+        let plugin = function.plugin.clone();
+
+        tokio::task::spawn_blocking(move || {
+            call_function(
+                &engine,
+                &function.module,
+                workspace,
+                &function.function,
+                &arguments,
+            )
+        })
+        .await
+        .map_err(|err| format!("plugin `{plugin}` did not finish: {err}"))?
+        .map_err(|err| format!("plugin `{plugin}` failed: {err:#}"))
+    }
+}
+
+fn new_engine() -> anyhow::Result<Engine> {
+    let mut config = wasmtime::Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config)
+}
+
+fn load_plugin(engine: &Engine, name: &str, dir: &Path) -> anyhow::Result<Vec<PluginFunction>> {
+    if !is_valid_name(name) {
+        anyhow::bail!("plugin names may only contain letters, digits, `_`, and `-`");
+    }
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let manifest = std::fs::read_to_string(&manifest_path)
+        .with_context(|| format!("could not read {}", manifest_path.display()))?;
+    let manifest: PluginManifest = serde_json::from_str(&manifest)
+        .with_context(|| format!("invalid {}", manifest_path.display()))?;
+
+    let module_path = dir.join(&manifest.module);
+    if !module_path.starts_with(dir) || manifest.module.contains("..") {
+        anyhow::bail!(
+            "module `{}` is outside the plugin directory",
+            manifest.module
+        );
+    }
+    let module = Module::from_file(engine, &module_path)
+        .with_context(|| format!("could not compile {}", module_path.display()))?;
+    check_module(&module, &manifest.capabilities)?;
+
+    let read_workspace = manifest.capabilities.contains(&Capability::ReadWorkspace);
+    let mut functions = Vec::with_capacity(manifest.functions.len());
+    for function in manifest.functions {
+        if !is_valid_name(&function.name) {
+            anyhow::bail!(
+                "function `{}`: names may only contain letters, digits, `_`, and `-`",
+                function.name
+            );
+        }
+        let tool_len = name.len() + TOOL_NAME_DELIMITER.len() + function.name.len();
+        if tool_len > MAX_TOOL_NAME_LEN {
+            anyhow::bail!(
+                "function `{}`: the tool name would be longer than {MAX_TOOL_NAME_LEN} characters",
+                function.name
+            );
+        }
+        if !function.parameters.is_object() {
+            anyhow::bail!(
+                "function `{}`: `parameters` must be a JSON Schema object",
+                function.name
+            );
+        }
+        functions.push(PluginFunction {
+            plugin: name.to_string(),
+            function: function.name,
+            description: function.description,
+            parameters: function.parameters,
+            module: module.clone(),
+            read_workspace,
+        });
+    }
+    Ok(functions)
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Rejects modules that miss part of the ABI or import anything their
+/// capabilities do not grant.
+fn check_module(module: &Module, capabilities: &[Capability]) -> anyhow::Result<()> {
+    for import in module.imports() {
+        match (import.module(), import.name()) {
+            ("codex", "output") => {}
+            ("codex", "read_file") if capabilities.contains(&Capability::ReadWorkspace) => {}
+            ("codex", "read_file") => {
+                anyhow::bail!("imports `codex.read_file` without the `read_workspace` capability")
+            }
+            (module, name) => {
+                anyhow::bail!("imports `{module}.{name}`, which plugins cannot use")
+            }
+        }
+    }
+    for export in ["memory", "alloc", "call"] {
+        if module.get_export(export).is_none() {
+            anyhow::bail!("does not export `{export}`");
+        }
+    }
+    Ok(())
+}
+
+struct HostState {
+    limits: StoreLimits,
+    output: Vec<u8>,
+    /// Root `codex.read_file` may read from; only set with `read_workspace`.
+    workspace: Option<PathBuf>,
+}
+
+fn call_function(
+    engine: &Engine,
+    module: &Module,
+    workspace: Option<PathBuf>,
+    function: &str,
+    arguments: &str,
+) -> anyhow::Result<PluginCallOutput> {
+    let read_workspace = workspace.is_some();
+    let mut store = Store::new(
+        engine,
+        HostState {
+            limits: StoreLimitsBuilder::new()
+                .memory_size(MAX_MEMORY_BYTES)
+                .instances(1)
+                .build(),
+            output: Vec::new(),
+            workspace,
+        },
+    );
+    store.limiter(|state| &mut state.limits);
+    store.set_fuel(MAX_FUEL)?;
+
+    let mut linker = Linker::new(engine);
+    linker.func_wrap("codex", "output", host_output)?;
+    if read_workspace {
+        linker.func_wrap("codex", "read_file", host_read_file)?;
+    }
+    let instance = linker.instantiate(&mut store, module)?;
+    let memory = instance
+        .get_memory(&mut store, "memory")
+        .context("plugin does not export `memory`")?;
+    let alloc = instance.get_typed_func::<i32, i32>(&mut store, "alloc")?;
+    let call = instance.get_typed_func::<(i32, i32, i32, i32), i32>(&mut store, "call")?;
+
+    let (name_ptr, name_len) = write_guest(&mut store, memory, &alloc, function.as_bytes())?;
+    let (args_ptr, args_len) = write_guest(&mut store, memory, &alloc, arguments.as_bytes())?;
+    let status = call.call(&mut store, (name_ptr, name_len, args_ptr, args_len))?;
+
+    Ok(PluginCallOutput {
+        content: String::from_utf8_lossy(&store.data().output).into_owned(),
+        success: status == 0,
+    })
+}
+
+fn write_guest(
+    store: &mut Store<HostState>,
+    memory: Memory,
+    alloc: &TypedFunc<i32, i32>,
+    bytes: &[u8],
+) -> anyhow::Result<(i32, i32)> {
+    let len = i32::try_from(bytes.len())?;
+    let ptr = alloc.call(&mut *store, len)?;
+    memory.write(&mut *store, usize::try_from(ptr)?, bytes)?;
+    Ok((ptr, len))
+}
+
+fn host_output(mut caller: Caller<'_, HostState>, ptr: i32, len: i32) -> anyhow::Result<()> {
+    let bytes = read_guest(&mut caller, ptr, len, MAX_OUTPUT_BYTES)?;
+    let output = &mut caller.data_mut().output;
+    if output.len() + bytes.len() > MAX_OUTPUT_BYTES {
+        anyhow::bail!("output is longer than {MAX_OUTPUT_BYTES} bytes");
+    }
+    output.extend_from_slice(&bytes);
+    Ok(())
+}
+
+fn host_read_file(mut caller: Caller<'_, HostState>, ptr: i32, len: i32) -> anyhow::Result<i64> {
+    let path = read_guest(&mut caller, ptr, len, MAX_PATH_BYTES)?;
+    let contents = match (String::from_utf8(path), caller.data().workspace.as_deref()) {
+        (Ok(path), Some(root)) => read_workspace_file(root, &path),
+        _ => None,
+    };
+    let Some(contents) = contents else {
+        return Ok(-1);
+    };
+
+    let alloc = caller
+        .get_export("alloc")
+        .and_then(Extern::into_func)
+        .context("plugin does not export `alloc`")?
+        .typed::<i32, i32>(&caller)?;
+    let memory = guest_memory(&mut caller)?;
+    let len = i32::try_from(contents.len())?;
+    let dst = alloc.call(&mut caller, len)?;
+    memory.write(&mut caller, usize::try_from(dst)?, &contents)?;
+    Ok((i64::from(dst) << 32) | i64::from(len))
+}
+
+fn guest_memory(caller: &mut Caller<'_, HostState>) -> anyhow::Result<Memory> {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .context("plugin does not export `memory`")
+}
+
+fn read_guest(
+    caller: &mut Caller<'_, HostState>,
+    ptr: i32,
+    len: i32,
+    max_len: usize,
+) -> anyhow::Result<Vec<u8>> {
+    let len = usize::try_from(len)?;
+    if len > max_len {
+        anyhow::bail!("buffer of {len} bytes is longer than {max_len}");
+    }
+    let memory = guest_memory(caller)?;
+    let mut buf = vec![0; len];
+    memory.read(&*caller, usize::try_from(ptr)?, &mut buf)?;
+    Ok(buf)
+}
+
+/// Reads `path` (relative to `root`) if it resolves to a regular file inside
+/// `root`, following symlinks before the check.
+fn read_workspace_file(root: &Path, path: &str) -> Option<Vec<u8>> {
+    let root = root.canonicalize().ok()?;
+    let candidate = root.join(path).canonicalize().ok()?;
+    if !candidate.starts_with(&root) {
+        return None;
+    }
+    let metadata = std::fs::metadata(&candidate).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_READ_BYTES {
+        return None;
+    }
+    std::fs::read(candidate).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    /// Echoes the function name and arguments back, then fails for `fail`.
+    const ECHO_WAT: &str = r#"
+        (module
+          (import "codex" "output" (func $output (param i32 i32)))
+          (memory (export "memory") 1)
+          (global $next (mut i32) (i32.const 1024))
+          (func (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func (export "call") (param $name i32) (param $name_len i32) (param $args i32) (param $args_len i32) (result i32)
+            (call $output (local.get $name) (local.get $name_len))
+            (call $output (local.get $args) (local.get $args_len))
+            ;; Fail when the name starts with "f".
+            (i32.eq (i32.load8_u (local.get $name)) (i32.const 102))))
+    "#;
+
+    /// Reads the file named by the arguments and outputs its contents, or
+    /// `denied` when the host refuses.
+    const READ_WAT: &str = r#"
+        (module
+          (import "codex" "output" (func $output (param i32 i32)))
+          (import "codex" "read_file" (func $read_file (param i32 i32) (result i64)))
+          (memory (export "memory") 1)
+          (data (i32.const 0) "denied")
+          (global $next (mut i32) (i32.const 1024))
+          (func (export "alloc") (param $len i32) (result i32)
+            (local $ptr i32)
+            (local.set $ptr (global.get $next))
+            (global.set $next (i32.add (global.get $next) (local.get $len)))
+            (local.get $ptr))
+          (func (export "call") (param i32 i32) (param $args i32) (param $args_len i32) (result i32)
+            (local $result i64)
+            (local.set $result (call $read_file (local.get $args) (local.get $args_len)))
+            (if (i64.lt_s (local.get $result) (i64.const 0))
+              (then (call $output (i32.const 0) (i32.const 6)) (return (i32.const 1))))
+            (call $output
+              (i32.wrap_i64 (i64.shr_u (local.get $result) (i64.const 32)))
+              (i32.wrap_i64 (local.get $result)))
+            (i32.const 0)))
+    "#;
+
+    fn write_plugin(home: &Path, name: &str, manifest: &str, wat: &str) {
+        let dir = home.join(PLUGINS_DIR).join(name);
+        std::fs::create_dir_all(&dir).expect("create plugin dir");
+        std::fs::write(dir.join(MANIFEST_FILE), manifest).expect("write manifest");
+        let wasm = wat::parse_str(wat).expect("valid wat");
+        std::fs::write(dir.join(DEFAULT_MODULE_FILE), wasm).expect("write module");
+    }
+
+    fn load(home: &Path) -> (Option<PluginHost>, Vec<String>) {
+        PluginHost::load(home, &HashMap::new())
+    }
+
+    #[tokio::test]
+    async fn plugin_functions_become_tools_and_run() {
+        let home = TempDir::new().expect("tempdir");
+        write_plugin(
+            home.path(),
+            "echo",
+            r#"{"functions": [
+                {"name": "say", "description": "Echo the arguments."},
+                {"name": "fail"}
+            ]}"#,
+            ECHO_WAT,
+        );
+
+        let (host, warnings) = load(home.path());
+        assert_eq!(warnings, Vec::<String>::new());
+        let host = host.expect("plugin loaded");
+        let names: Vec<String> = host.tools().into_iter().map(|tool| tool.name).collect();
+        assert_eq!(
+            names,
+            vec!["echo__fail".to_string(), "echo__say".to_string()]
+        );
+
+        let output = host
+            .call("echo__say", r#"{"x":1}"#.to_string(), home.path())
+            .await
+            .expect("call succeeds");
+        assert_eq!(
+            output,
+            PluginCallOutput {
+                content: r#"say{"x":1}"#.to_string(),
+                success: true,
+            }
+        );
+        let output = host
+            .call("echo__fail", "{}".to_string(), home.path())
+            .await
+            .expect("call runs");
+        assert!(!output.success);
+    }
+
+    #[tokio::test]
+    async fn read_file_requires_the_capability_and_stays_in_the_workspace() {
+        let home = TempDir::new().expect("tempdir");
+        write_plugin(
+            home.path(),
+            "nocap",
+            r#"{"functions": [{"name": "read"}]}"#,
+            READ_WAT,
+        );
+        write_plugin(
+            home.path(),
+            "reader",
+            r#"{"capabilities": ["read_workspace"], "functions": [{"name": "read"}]}"#,
+            READ_WAT,
+        );
+        let (host, warnings) = load(home.path());
+        assert_eq!(
+            warnings,
+            vec![
+                "Skipped plugin `nocap`: imports `codex.read_file` without the `read_workspace` capability"
+                    .to_string()
+            ]
+        );
+        let host = host.expect("reader loaded");
+
+        let workspace = TempDir::new().expect("workspace");
+        std::fs::write(workspace.path().join("notes.txt"), "hello").expect("write file");
+        let outside = home
+            .path()
+            .join(PLUGINS_DIR)
+            .join("reader")
+            .join(MANIFEST_FILE);
+        let outside = outside.to_string_lossy().into_owned();
+
+        assert_eq!(read(&host, workspace.path(), "notes.txt").await, "hello");
+        assert_eq!(read(&host, workspace.path(), &outside).await, "denied");
+        assert_eq!(
+            read(&host, workspace.path(), "../etc/passwd").await,
+            "denied"
+        );
+    }
+
+    async fn read(host: &PluginHost, workspace: &Path, path: &str) -> String {
+        host.call("reader__read", path.to_string(), workspace)
+            .await
+            .expect("call runs")
+            .content
+    }
+
+    #[test]
+    fn wasi_imports_are_rejected() {
+        let home = TempDir::new().expect("tempdir");
+        write_plugin(
+            home.path(),
+            "wasi",
+            r#"{"functions": [{"name": "run"}]}"#,
+            r#"(module
+                 (import "wasi_snapshot_preview1" "fd_write"
+                   (func (param i32 i32 i32 i32) (result i32)))
+                 (memory (export "memory") 1)
+                 (func (export "alloc") (param i32) (result i32) (i32.const 0))
+                 (func (export "call") (param i32 i32 i32 i32) (result i32) (i32.const 0)))"#,
+        );
+
+        let (host, warnings) = load(home.path());
+        assert!(host.is_none());
+        assert_eq!(
+            warnings,
+            vec![
+                "Skipped plugin `wasi`: imports `wasi_snapshot_preview1.fd_write`, which plugins cannot use"
+                    .to_string()
+            ]
+        );
+    }
+}
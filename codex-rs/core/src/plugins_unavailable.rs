@@ -0,0 +1,61 @@
+//! Stand-in for the WebAssembly plugin host in builds without the
+//! `wasm-plugins` cargo feature. No plugin can be loaded, so a session never
+//! holds a [`PluginHost`].
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde_json::Value as JsonValue;
+
+use crate::config::types::McpServerConfig;
+
+/// A function tool declared by a plugin.
+#[derive(Debug, Clone)]
+pub(crate) struct PluginTool {
+    /// Name the model calls the tool by: `<plugin>__<function>`.
+    pub(crate) name: String,
+    pub(crate) plugin: String,
+    pub(crate) description: String,
+    pub(crate) parameters: JsonValue,
+}
+
+/// What a plugin call produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PluginCallOutput {
+    pub(crate) content: String,
+    pub(crate) success: bool,
+}
+
+/// Uninhabited: this build cannot run plugins.
+pub(crate) enum PluginHost {}
+
+impl PluginHost {
+    /// Loads nothing and explains why the `wasm_plugins` feature has no
+    /// effect.
+    pub(crate) fn load(
+        _codex_home: &Path,
+        _mcp_servers: &HashMap<String, McpServerConfig>,
+    ) -> (Option<Self>, Vec<String>) {
+        (
+            None,
+            vec![
+                "Plugins were not loaded: this build of Codex was compiled without the \
+                 `wasm-plugins` cargo feature."
+                    .to_string(),
+            ],
+        )
+    }
+
+    pub(crate) fn tools(&self) -> Vec<PluginTool> {
+        match *self {}
+    }
+
+    pub(crate) async fn call(
+        &self,
+        _tool_name: &str,
+        _arguments: String,
+        _cwd: &Path,
+    ) -> Result<PluginCallOutput, String> {
+        match *self {}
+    }
+}
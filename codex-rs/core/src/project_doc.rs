@@ -12,12 +12,29 @@
 //!     current working directory (inclusive) and concatenate their contents in
 //!     that order.
 //! 3.  We do **not** walk past the Git root.
+//!
+//! The effective instructions are layered from the most general to the most
+//! specific, so later layers refine or override earlier ones: the
+//! organization's `org_instructions_file`, the user's `$CODEX_HOME/AGENTS.md`,
+//! the repository root's `AGENTS.md`, then those of each directory down to
+//! the working directory.
+//!
+//! A line consisting of `@include <path>` is replaced by the contents of that
+//! file, resolved relative to the file containing the directive. Includes
+//! nest up to eight levels; cycles are skipped.
 
 use crate::config::Config;
+use crate::features::Feature;
+use crate::workspace_instructions::WORKSPACE_INSTRUCTIONS_SEPARATOR;
+use crate::workspace_instructions::load_workspace_instructions;
+use crate::workspace_instructions::render_workspace_instructions;
 use dunce::canonicalize as normalize_path;
+use std::io::Read;
+use std::path::Path;
 use std::path::PathBuf;
 use tokio::io::AsyncReadExt;
 use tracing::error;
+use tracing::warn;
 
 /// Default filename scanned for project-level docs.
 pub const DEFAULT_PROJECT_DOC_FILENAME: &str = "AGENTS.md";
@@ -28,22 +45,73 @@ pub const LOCAL_PROJECT_DOC_FILENAME: &str = "AGENTS.override.md";
 /// be concatenated with the following separator.
 const PROJECT_DOC_SEPARATOR: &str = "\n\n--- project-doc ---\n\n";
 
-/// Combines `Config::instructions` and `AGENTS.md` (if present) into a single
-/// string of instructions.
+const INCLUDE_DIRECTIVE: &str = "@include ";
+/// How deeply `@include` directives may nest.
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Effective user instructions and the instruction files they were read
+/// from, including `@include`d ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct LoadedInstructions {
+    pub(crate) text: Option<String>,
+    pub(crate) sources: Vec<PathBuf>,
+}
+
+/// Combines `Config::instructions`, `AGENTS.md` (if present) and, when the
+/// `workspace_instructions` feature is enabled, the saved workspace
+/// instructions into a single string of instructions.
 pub(crate) async fn get_user_instructions(config: &Config) -> Option<String> {
-    match read_project_docs(config).await {
-        Ok(Some(project_doc)) => match &config.user_instructions {
+    load_user_instructions(config).await.text
+}
+
+/// Like [`get_user_instructions`], but also reports the files read.
+pub(crate) async fn load_user_instructions(config: &Config) -> LoadedInstructions {
+    let mut loaded = load_config_and_project_doc_instructions(config).await;
+    if !config.features.enabled(Feature::WorkspaceInstructions) {
+        return loaded;
+    }
+
+    let saved = match load_workspace_instructions(&config.cwd).await {
+        Ok(saved) => saved,
+        Err(e) => {
+            error!("error trying to load workspace instructions: {e:#}");
+            return loaded;
+        }
+    };
+    loaded.text = match (loaded.text, render_workspace_instructions(&saved)) {
+        (Some(instructions), Some(saved)) => Some(format!(
+            "{instructions}{WORKSPACE_INSTRUCTIONS_SEPARATOR}{saved}"
+        )),
+        (instructions, saved) => instructions.or(saved),
+    };
+    loaded
+}
+
+async fn load_config_and_project_doc_instructions(config: &Config) -> LoadedInstructions {
+    let mut sources = Vec::new();
+    let org_instructions = match &config.org_instructions_file {
+        Some(path) => read_instruction_file(path, config.project_doc_max_bytes, &mut sources),
+        None => None,
+    };
+    let base_instructions = match (org_instructions, &config.user_instructions) {
+        (Some(org), Some(user)) => Some(format!("{org}\n\n{user}")),
+        (org, user) => org.or_else(|| user.clone()),
+    };
+
+    let text = match read_project_docs_with_sources(config, &mut sources).await {
+        Ok(Some(project_doc)) => match base_instructions {
             Some(original_instructions) => Some(format!(
                 "{original_instructions}{PROJECT_DOC_SEPARATOR}{project_doc}"
             )),
             None => Some(project_doc),
         },
-        Ok(None) => config.user_instructions.clone(),
+        Ok(None) => base_instructions,
         Err(e) => {
             error!("error trying to find project doc: {e:#}");
-            config.user_instructions.clone()
+            base_instructions
         }
-    }
+    };
+    LoadedInstructions { text, sources }
 }
 
 /// Attempt to locate and load the project documentation.
@@ -53,6 +121,13 @@ pub(crate) async fn get_user_instructions(config: &Config) -> Option<String> {
 /// function returns `Ok(None)`. Unexpected I/O failures bubble up as `Err` so
 /// callers can decide how to handle them.
 pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String>> {
+    read_project_docs_with_sources(config, &mut Vec::new()).await
+}
+
+async fn read_project_docs_with_sources(
+    config: &Config,
+    sources: &mut Vec<PathBuf>,
+) -> std::io::Result<Option<String>> {
     let max_total = config.project_doc_max_bytes;
 
     if max_total == 0 {
@@ -92,9 +167,14 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
         }
 
         let text = String::from_utf8_lossy(&data).to_string();
+        let dir = p.parent().unwrap_or(Path::new(""));
+        let mut stack = vec![normalize_path(&p).unwrap_or_else(|_| p.clone())];
+        let text = expand_includes(&text, dir, remaining, &mut stack, sources);
+        let text = truncate_to_bytes(text, remaining);
         if !text.trim().is_empty() {
+            remaining = remaining.saturating_sub(text.len() as u64);
             parts.push(text);
-            remaining = remaining.saturating_sub(data.len() as u64);
+            sources.push(p);
         }
     }
 
@@ -105,6 +185,109 @@ pub async fn read_project_docs(config: &Config) -> std::io::Result<Option<String
     }
 }
 
+/// Read a standalone instruction file, expanding its includes. Returns
+/// `None` when it is missing, unreadable, or blank.
+fn read_instruction_file(
+    path: &Path,
+    max_bytes: usize,
+    sources: &mut Vec<PathBuf>,
+) -> Option<String> {
+    if max_bytes == 0 {
+        return None;
+    }
+    let limit = max_bytes as u64;
+    let text = match read_limited(path, limit) {
+        Ok(text) => text,
+        Err(e) => {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("failed to read instructions `{}`: {e}", path.display());
+            }
+            return None;
+        }
+    };
+    let dir = path.parent().unwrap_or(Path::new(""));
+    let mut stack = vec![normalize_path(path).unwrap_or_else(|_| path.to_path_buf())];
+    let text = truncate_to_bytes(
+        expand_includes(&text, dir, limit, &mut stack, sources),
+        limit,
+    );
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+    sources.push(path.to_path_buf());
+    Some(text.to_string())
+}
+
+fn read_limited(path: &Path, limit: u64) -> std::io::Result<String> {
+    let mut data = Vec::new();
+    std::fs::File::open(path)?
+        .take(limit)
+        .read_to_end(&mut data)?;
+    Ok(String::from_utf8_lossy(&data).into_owned())
+}
+
+/// Replace each `@include <path>` line of `text`, a file in `dir`, with the
+/// (recursively expanded) contents of that file. `stack` holds the files
+/// being expanded, to detect cycles.
+fn expand_includes(
+    text: &str,
+    dir: &Path,
+    limit: u64,
+    stack: &mut Vec<PathBuf>,
+    sources: &mut Vec<PathBuf>,
+) -> String {
+    let mut out = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        let Some(target) = line.trim().strip_prefix(INCLUDE_DIRECTIVE) else {
+            out.push_str(line);
+            continue;
+        };
+        let path = dir.join(target.trim());
+        let canonical = normalize_path(&path).unwrap_or_else(|_| path.clone());
+        if stack.contains(&canonical) {
+            warn!("skipping recursive @include of `{}`", path.display());
+            continue;
+        }
+        if stack.len() > MAX_INCLUDE_DEPTH {
+            warn!(
+                "skipping @include of `{}`: includes nest more than {MAX_INCLUDE_DEPTH} levels",
+                path.display()
+            );
+            continue;
+        }
+        let included = match read_limited(&path, limit) {
+            Ok(included) => included,
+            Err(e) => {
+                warn!("failed to read @include `{}`: {e}", path.display());
+                continue;
+            }
+        };
+        stack.push(canonical);
+        let included_dir = path.parent().unwrap_or(dir).to_path_buf();
+        let expanded = expand_includes(&included, &included_dir, limit, stack, sources);
+        stack.pop();
+        sources.push(path);
+        out.push_str(expanded.trim_end_matches('\n'));
+        if line.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn truncate_to_bytes(mut text: String, limit: u64) -> String {
+    let limit = usize::try_from(limit).unwrap_or(usize::MAX);
+    if text.len() > limit {
+        let mut end = limit;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+    text
+}
+
 /// Discover the list of AGENTS.md files using the same search rules as
 /// `read_project_docs`, but return the file paths instead of concatenated
 /// contents. The list is ordered from repository root to the current working
@@ -447,4 +630,84 @@ mod tests {
                 .eq(DEFAULT_PROJECT_DOC_FILENAME)
         );
     }
+
+    /// `@include` lines are replaced by the included file, relative to the
+    /// including file; cycles are dropped.
+    #[tokio::test]
+    async fn expands_include_directives() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        fs::create_dir_all(tmp.path().join("docs")).unwrap();
+        fs::write(
+            tmp.path().join("AGENTS.md"),
+            "intro\n@include docs/style.md\noutro",
+        )
+        .unwrap();
+        fs::write(
+            tmp.path().join("docs/style.md"),
+            "use tabs\n  @include ../AGENTS.md\n@include testing.md\n",
+        )
+        .unwrap();
+        fs::write(tmp.path().join("docs/testing.md"), "run the tests\n").unwrap();
+
+        let loaded = load_user_instructions(&make_config(&tmp, 4096, None)).await;
+
+        assert_eq!(
+            loaded.text.as_deref(),
+            Some("intro\nuse tabs\nrun the tests\noutro")
+        );
+        assert_eq!(
+            loaded
+                .sources
+                .iter()
+                .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+                .collect::<Vec<_>>(),
+            vec!["testing.md", "style.md", "AGENTS.md"]
+        );
+    }
+
+    /// The organization's instructions come first, then the user's, then the
+    /// project docs.
+    #[tokio::test]
+    async fn layers_org_user_and_project_instructions() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        fs::write(tmp.path().join("AGENTS.md"), "project").unwrap();
+        let org = tempfile::tempdir().expect("tempdir");
+        fs::write(org.path().join("org.md"), "org\n").unwrap();
+
+        let mut cfg = make_config(&tmp, 4096, Some("user"));
+        cfg.org_instructions_file = Some(org.path().join("org.md"));
+
+        let res = get_user_instructions(&cfg).await.expect("instructions");
+        assert_eq!(res, format!("org\n\nuser{PROJECT_DOC_SEPARATOR}project"));
+    }
+
+    /// Saved workspace instructions are appended only when the feature is on.
+    #[tokio::test]
+    async fn workspace_instructions_follow_project_doc_when_enabled() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        fs::write(tmp.path().join("AGENTS.md"), "project doc").unwrap();
+        crate::workspace_instructions::add_workspace_instruction(
+            tmp.path(),
+            "never touch generated/ files",
+        )
+        .await
+        .expect("save instruction");
+
+        let mut cfg = make_config(&tmp, 4096, None);
+        assert_eq!(
+            get_user_instructions(&cfg).await,
+            Some("project doc".to_string())
+        );
+
+        cfg.features.enable(Feature::WorkspaceInstructions);
+        let res = get_user_instructions(&cfg)
+            .await
+            .expect("instructions expected");
+        assert_eq!(
+            res,
+            format!(
+                "project doc{WORKSPACE_INSTRUCTIONS_SEPARATOR}The user asked for the following to always apply in this workspace:\n- never touch generated/ files\n"
+            )
+        );
+    }
 }
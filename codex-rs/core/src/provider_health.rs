@@ -0,0 +1,114 @@
+//! Circuit breaker over the model provider.
+//!
+//! Each outage-like failure (dropped stream, 5xx, 429) counts towards
+//! [`FAILURE_THRESHOLD`]; once it is reached the provider is considered
+//! degraded and requests wait out a cooldown before trying again instead of
+//! burning through the retry budget and failing the turn. Every failure while
+//! degraded doubles the cooldown up to [`MAX_COOLDOWN`]; the first success
+//! closes the breaker again.
+
+use std::time::Duration;
+use std::time::Instant;
+
+pub(crate) const FAILURE_THRESHOLD: u32 = 3;
+const INITIAL_COOLDOWN: Duration = Duration::from_secs(15);
+const MAX_COOLDOWN: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug)]
+pub(crate) struct ProviderHealth {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    cooldown: Duration,
+}
+
+impl Default for ProviderHealth {
+    fn default() -> Self {
+        Self {
+            consecutive_failures: 0,
+            open_until: None,
+            cooldown: INITIAL_COOLDOWN,
+        }
+    }
+}
+
+impl ProviderHealth {
+    /// Record an outage-like failure. Returns the cooldown when this failure
+    /// is the one that marks the provider as degraded.
+    pub(crate) fn record_failure(&mut self, now: Instant) -> Option<Duration> {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        if self.consecutive_failures < FAILURE_THRESHOLD {
+            return None;
+        }
+        let newly_degraded = self.open_until.is_none();
+        if !newly_degraded {
+            self.cooldown = (self.cooldown * 2).min(MAX_COOLDOWN);
+        }
+        self.open_until = Some(now + self.cooldown);
+        newly_degraded.then_some(self.cooldown)
+    }
+
+    /// Record a successful request. Returns `true` when this recovers a
+    /// degraded provider.
+    pub(crate) fn record_success(&mut self) -> bool {
+        let was_degraded = self.is_degraded();
+        *self = Self::default();
+        was_degraded
+    }
+
+    pub(crate) fn is_degraded(&self) -> bool {
+        self.open_until.is_some()
+    }
+
+    /// How long requests should still hold off, if the breaker is open.
+    pub(crate) fn cooldown_remaining(&self, now: Instant) -> Option<Duration> {
+        self.open_until
+            .map(|until| until.saturating_duration_since(now))
+            .filter(|remaining| !remaining.is_zero())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn degrades_after_consecutive_failures_and_recovers_on_success() {
+        let mut health = ProviderHealth::default();
+        let now = Instant::now();
+
+        for _ in 1..FAILURE_THRESHOLD {
+            assert_eq!(health.record_failure(now), None);
+        }
+        assert_eq!(health.cooldown_remaining(now), None);
+
+        assert_eq!(health.record_failure(now), Some(INITIAL_COOLDOWN));
+        assert!(health.is_degraded());
+        assert_eq!(health.cooldown_remaining(now), Some(INITIAL_COOLDOWN));
+        assert_eq!(
+            health.cooldown_remaining(now + INITIAL_COOLDOWN + Duration::from_secs(1)),
+            None
+        );
+
+        assert!(health.record_success());
+        assert!(!health.is_degraded());
+        assert!(!health.record_success());
+    }
+
+    #[test]
+    fn failures_while_degraded_extend_the_cooldown() {
+        let mut health = ProviderHealth::default();
+        let now = Instant::now();
+        for _ in 0..FAILURE_THRESHOLD {
+            health.record_failure(now);
+        }
+
+        assert_eq!(health.record_failure(now), None);
+        assert_eq!(health.cooldown_remaining(now), Some(INITIAL_COOLDOWN * 2));
+
+        for _ in 0..10 {
+            health.record_failure(now);
+        }
+        assert_eq!(health.cooldown_remaining(now), Some(MAX_COOLDOWN));
+    }
+}
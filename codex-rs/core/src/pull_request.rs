@@ -0,0 +1,394 @@
+//! Opening pull requests (GitHub) and merge requests (GitLab) for the current
+//! branch through the forges' REST APIs.
+//!
+//! Tokens come from the OS keyring, keyed by the remote's host; store one
+//! with `codex forge login <host>`. Core pushes the branch itself with that
+//! token, so this needs neither network access for the sandbox nor an
+//! installed and authenticated `gh`/`glab`.
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Context;
+use base64::Engine;
+use codex_keyring_store::DefaultKeyringStore;
+use codex_keyring_store::KeyringStore;
+use codex_protocol::protocol::ForgeProvider;
+use codex_protocol::protocol::PullRequestCreatedEvent;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::default_client::create_client;
+use crate::git_info::current_branch_name;
+use crate::git_info::default_branch_name;
+
+const FORGE_KEYRING_SERVICE: &str = "Codex Forge Credentials";
+const REMOTE: &str = "origin";
+const GIT_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PullRequestRequest {
+    pub(crate) title: String,
+    pub(crate) body: Option<String>,
+    /// Target branch; defaults to the remote's default branch.
+    pub(crate) base: Option<String>,
+    pub(crate) draft: bool,
+}
+
+/// Store the API token used for `host` (e.g. `github.com`).
+pub fn save_forge_token(host: &str, token: &str) -> anyhow::Result<()> {
+    DefaultKeyringStore
+        .save(FORGE_KEYRING_SERVICE, host, token)
+        .map_err(|err| anyhow::anyhow!("failed to save token for {host}: {}", err.message()))
+}
+
+/// Remove the token stored for `host`. Returns whether one was stored.
+pub fn delete_forge_token(host: &str) -> anyhow::Result<bool> {
+    DefaultKeyringStore
+        .delete(FORGE_KEYRING_SERVICE, host)
+        .map_err(|err| anyhow::anyhow!("failed to delete token for {host}: {}", err.message()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ForgeRemote {
+    provider: ForgeProvider,
+    /// Host, with the port for HTTP(S) remotes.
+    host: String,
+    /// `owner/repo`, or the full group path on GitLab.
+    project: String,
+    https: bool,
+}
+
+impl ForgeRemote {
+    fn api_base(&self) -> String {
+        match self.provider {
+            ForgeProvider::Github if self.host == "github.com" => {
+                "https://api.github.com".to_string()
+            }
+            ForgeProvider::Github => format!("https://{}/api/v3", self.host),
+            ForgeProvider::Gitlab => format!("https://{}/api/v4", self.host),
+        }
+    }
+
+    /// User name the forge expects with a token in HTTP basic auth.
+    fn basic_auth_user(&self) -> &'static str {
+        match self.provider {
+            ForgeProvider::Github => "x-access-token",
+            ForgeProvider::Gitlab => "oauth2",
+        }
+    }
+}
+
+/// Push the current branch of the repository at `cwd` to `origin` and open a
+/// pull/merge request for it.
+pub(crate) async fn create_pull_request(
+    cwd: &Path,
+    keyring_store: &dyn KeyringStore,
+    request: PullRequestRequest,
+) -> anyhow::Result<PullRequestCreatedEvent> {
+    let head = current_branch_name(cwd)
+        .await
+        .context("not on a branch; check out the branch to open a pull request for")?;
+    let url = git(cwd, &["remote", "get-url", REMOTE], &[]).await?;
+    let remote = parse_remote_url(&url).with_context(|| {
+        format!("remote `{REMOTE}` ({url}) is not a GitHub or GitLab repository")
+    })?;
+    let token = keyring_store
+        .load(FORGE_KEYRING_SERVICE, &remote.host)
+        .map_err(|err| anyhow::anyhow!("failed to read token from keyring: {}", err.message()))?
+        .with_context(|| {
+            format!(
+                "no token stored for {}; run `codex forge login {}`",
+                remote.host, remote.host
+            )
+        })?;
+    let base = match request.base {
+        Some(base) => base,
+        None => default_branch_name(cwd)
+            .await
+            .context("could not determine the default branch; pass a base branch")?,
+    };
+    if base == head {
+        anyhow::bail!(
+            "the current branch `{head}` is the base branch; commit to a new branch first"
+        );
+    }
+
+    push(cwd, &remote, &token, &head).await?;
+
+    let client = create_client();
+    let (provider, url, number) = match remote.provider {
+        ForgeProvider::Github => {
+            #[derive(Deserialize)]
+            struct Created {
+                html_url: String,
+                number: i64,
+            }
+            let endpoint = format!("{}/repos/{}/pulls", remote.api_base(), remote.project);
+            let response = client
+                .post(endpoint)
+                .bearer_auth(&token)
+                .header("Accept", "application/vnd.github+json")
+                .json(&json!({
+                    "title": request.title,
+                    "body": request.body.unwrap_or_default(),
+                    "head": head,
+                    "base": base,
+                    "draft": request.draft,
+                }))
+                .send()
+                .await?;
+            let created: Created = parse_response(response, "GitHub").await?;
+            (ForgeProvider::Github, created.html_url, created.number)
+        }
+        ForgeProvider::Gitlab => {
+            #[derive(Deserialize)]
+            struct Created {
+                web_url: String,
+                iid: i64,
+            }
+            let endpoint = format!(
+                "{}/projects/{}/merge_requests",
+                remote.api_base(),
+                encode_path_segment(&remote.project)
+            );
+            let title = if request.draft {
+                format!("Draft: {}", request.title)
+            } else {
+                request.title.clone()
+            };
+            let response = client
+                .post(endpoint)
+                .bearer_auth(&token)
+                .json(&json!({
+                    "title": title,
+                    "description": request.body.unwrap_or_default(),
+                    "source_branch": head,
+                    "target_branch": base,
+                }))
+                .send()
+                .await?;
+            let created: Created = parse_response(response, "GitLab").await?;
+            (ForgeProvider::Gitlab, created.web_url, created.iid)
+        }
+    };
+
+    Ok(PullRequestCreatedEvent {
+        provider,
+        url,
+        number,
+        title: request.title,
+        head,
+        base,
+    })
+}
+
+async fn parse_response<T: DeserializeOwned>(
+    response: reqwest::Response,
+    forge: &str,
+) -> anyhow::Result<T> {
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        anyhow::bail!("{forge} returned {status}: {body}");
+    }
+    serde_json::from_str(&body).with_context(|| format!("unexpected {forge} response: {body}"))
+}
+
+/// Push `branch` to the remote, authenticating HTTPS remotes with `token`.
+/// SSH remotes use the user's SSH setup.
+async fn push(cwd: &Path, remote: &ForgeRemote, token: &str, branch: &str) -> anyhow::Result<()> {
+    let mut env = vec![("GIT_TERMINAL_PROMPT".to_string(), "0".to_string())];
+    if remote.https {
+        let credentials = base64::engine::general_purpose::STANDARD
+            .encode(format!("{}:{token}", remote.basic_auth_user()));
+        // Passed through the environment so the token never shows up in the
+        // process list.
+        env.extend([
+            ("GIT_CONFIG_COUNT".to_string(), "1".to_string()),
+            (
+                "GIT_CONFIG_KEY_0".to_string(),
+                "http.extraHeader".to_string(),
+            ),
+            (
+                "GIT_CONFIG_VALUE_0".to_string(),
+                format!("Authorization: Basic {credentials}"),
+            ),
+        ]);
+    }
+    let refspec = format!("HEAD:refs/heads/{branch}");
+    git(cwd, &["push", REMOTE, &refspec], &env).await?;
+    Ok(())
+}
+
+async fn git(cwd: &Path, args: &[&str], env: &[(String, String)]) -> anyhow::Result<String> {
+    let output = timeout(
+        GIT_TIMEOUT,
+        Command::new("git")
+            .args(args)
+            .envs(env.iter().cloned())
+            .current_dir(cwd)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .with_context(|| format!("`git {}` timed out", args.join(" ")))??;
+    if !output.status.success() {
+        anyhow::bail!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Parse HTTPS (`https://host/owner/repo.git`), SSH
+/// (`ssh://git@host/owner/repo.git`), and scp-like (`git@host:owner/repo.git`)
+/// remote URLs of GitHub and GitLab hosts.
+fn parse_remote_url(url: &str) -> Option<ForgeRemote> {
+    let url = url.trim();
+    let (host, path, https) = if let Some(rest) = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+    {
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        (host.to_string(), path, true)
+    } else if let Some(rest) = url.strip_prefix("ssh://") {
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        // The SSH port says nothing about where the API is served.
+        let host = host.split_once(':').map_or(host, |(host, _)| host);
+        (host.to_string(), path, false)
+    } else if !url.contains("://") {
+        let (authority, path) = url.split_once(':')?;
+        let host = authority
+            .rsplit_once('@')
+            .map_or(authority, |(_, host)| host);
+        (host.to_string(), path, false)
+    } else {
+        return None;
+    };
+
+    let project = path.trim_matches('/');
+    let project = project.strip_suffix(".git").unwrap_or(project);
+    if host.is_empty() || !project.contains('/') {
+        return None;
+    }
+    let host = host.to_ascii_lowercase();
+    let provider = if host.contains("github") {
+        ForgeProvider::Github
+    } else if host.contains("gitlab") {
+        ForgeProvider::Gitlab
+    } else {
+        return None;
+    };
+    Some(ForgeRemote {
+        provider,
+        host,
+        project: project.to_string(),
+        https,
+    })
+}
+
+/// Percent-encode everything but unreserved characters, as GitLab expects
+/// for a project path used as an id.
+fn encode_path_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn remote(provider: ForgeProvider, host: &str, project: &str, https: bool) -> ForgeRemote {
+        ForgeRemote {
+            provider,
+            host: host.to_string(),
+            project: project.to_string(),
+            https,
+        }
+    }
+
+    #[test]
+    fn parses_common_remote_urls() {
+        assert_eq!(
+            parse_remote_url("https://github.com/openai/codex.git"),
+            Some(remote(
+                ForgeProvider::Github,
+                "github.com",
+                "openai/codex",
+                true
+            ))
+        );
+        assert_eq!(
+            parse_remote_url("git@github.com:openai/codex.git"),
+            Some(remote(
+                ForgeProvider::Github,
+                "github.com",
+                "openai/codex",
+                false
+            ))
+        );
+        assert_eq!(
+            parse_remote_url("ssh://git@gitlab.example.com:2222/group/sub/project"),
+            Some(remote(
+                ForgeProvider::Gitlab,
+                "gitlab.example.com",
+                "group/sub/project",
+                false
+            ))
+        );
+        assert_eq!(
+            parse_remote_url("https://user@GitLab.example.com:8443/group/project/"),
+            Some(remote(
+                ForgeProvider::Gitlab,
+                "gitlab.example.com:8443",
+                "group/project",
+                true
+            ))
+        );
+        assert_eq!(
+            parse_remote_url("https://bitbucket.org/team/repo.git"),
+            None
+        );
+        assert_eq!(parse_remote_url("/srv/git/repo.git"), None);
+    }
+
+    #[test]
+    fn api_base_depends_on_host() {
+        assert_eq!(
+            remote(ForgeProvider::Github, "github.com", "a/b", true).api_base(),
+            "https://api.github.com"
+        );
+        assert_eq!(
+            remote(ForgeProvider::Github, "github.example.com", "a/b", true).api_base(),
+            "https://github.example.com/api/v3"
+        );
+        assert_eq!(
+            remote(ForgeProvider::Gitlab, "gitlab.com", "a/b", true).api_base(),
+            "https://gitlab.com/api/v4"
+        );
+        assert_eq!(
+            encode_path_segment("group/sub project"),
+            "group%2Fsub%20project"
+        );
+    }
+}
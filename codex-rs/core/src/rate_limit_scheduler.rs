@@ -0,0 +1,187 @@
+//! Pre-emptive throttling on the account's rate-limit windows.
+//!
+//! Every model response reports how much of the primary and secondary
+//! rate-limit windows has been used. Once a window reaches the configured
+//! pause threshold, new model requests wait for that window to reset instead
+//! of running into 429s partway through a turn. The windows belong to the
+//! account rather than to a conversation, so the latest snapshot for each
+//! provider and account is shared by every conversation in the process that
+//! uses them, and they all queue behind the same reset.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+use crate::protocol::RateLimitSnapshot;
+use crate::protocol::RateLimitWindow;
+
+static SHARED: LazyLock<Arc<RateLimitScheduler>> = LazyLock::new(Arc::default);
+
+/// Why and until when the next model request should wait.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RateLimitPause {
+    /// `primary` or `secondary`.
+    pub(crate) window: &'static str,
+    pub(crate) used_percent: f64,
+    /// Unix timestamp (seconds) at which the window resets.
+    pub(crate) resume_at: i64,
+}
+
+/// The rate-limit windows a snapshot applies to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct RateLimitKey {
+    pub(crate) provider_id: String,
+    /// Stored account name, or `None` for the default account.
+    pub(crate) account: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct RateLimitScheduler {
+    latest: Mutex<HashMap<RateLimitKey, RateLimitSnapshot>>,
+}
+
+impl RateLimitScheduler {
+    /// The scheduler shared by every conversation in this process.
+    pub(crate) fn shared() -> Arc<Self> {
+        Arc::clone(&SHARED)
+    }
+
+    pub(crate) fn record(&self, key: RateLimitKey, snapshot: RateLimitSnapshot) {
+        self.latest
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key, snapshot);
+    }
+
+    /// The pause the next request should take when a window is at or above
+    /// `pause_percent` and has not reset by `now` (unix seconds). When both
+    /// windows are exhausted the later reset wins, since requests cannot go
+    /// through before then.
+    pub(crate) fn pause(
+        &self,
+        key: &RateLimitKey,
+        pause_percent: f64,
+        now: i64,
+    ) -> Option<RateLimitPause> {
+        let latest = self.latest.lock().unwrap_or_else(PoisonError::into_inner);
+        let snapshot = latest.get(key)?;
+        [
+            ("primary", snapshot.primary.as_ref()),
+            ("secondary", snapshot.secondary.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(label, window)| {
+            let RateLimitWindow {
+                used_percent,
+                resets_at,
+                ..
+            } = window?;
+            let resume_at = (*resets_at)?;
+            (*used_percent >= pause_percent && resume_at > now).then_some(RateLimitPause {
+                window: label,
+                used_percent: *used_percent,
+                resume_at,
+            })
+        })
+        .max_by_key(|pause| pause.resume_at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn key(provider_id: &str, account: Option<&str>) -> RateLimitKey {
+        RateLimitKey {
+            provider_id: provider_id.to_string(),
+            account: account.map(str::to_string),
+        }
+    }
+
+    fn window(used_percent: f64, resets_at: i64) -> Option<RateLimitWindow> {
+        Some(RateLimitWindow {
+            used_percent,
+            window_minutes: Some(300),
+            resets_at: Some(resets_at),
+        })
+    }
+
+    #[test]
+    fn pauses_until_the_exhausted_window_resets() {
+        let scheduler = RateLimitScheduler::default();
+        let openai = key("openai", None);
+        assert_eq!(scheduler.pause(&openai, 98.0, 1_000), None);
+
+        scheduler.record(
+            openai.clone(),
+            RateLimitSnapshot {
+                primary: window(99.0, 1_300),
+                secondary: window(40.0, 9_000),
+                credits: None,
+            },
+        );
+
+        assert_eq!(
+            scheduler.pause(&openai, 98.0, 1_000),
+            Some(RateLimitPause {
+                window: "primary",
+                used_percent: 99.0,
+                resume_at: 1_300,
+            })
+        );
+        // Below the threshold, or once the window has reset, nothing waits.
+        assert_eq!(scheduler.pause(&openai, 99.5, 1_000), None);
+        assert_eq!(scheduler.pause(&openai, 98.0, 1_300), None);
+    }
+
+    #[test]
+    fn windows_are_tracked_per_provider_and_account() {
+        let scheduler = RateLimitScheduler::default();
+        scheduler.record(
+            key("openai", Some("work")),
+            RateLimitSnapshot {
+                primary: window(99.0, 1_300),
+                secondary: None,
+                credits: None,
+            },
+        );
+
+        assert!(
+            scheduler
+                .pause(&key("openai", Some("work")), 98.0, 1_000)
+                .is_some()
+        );
+        assert_eq!(
+            scheduler.pause(&key("openai", Some("personal")), 98.0, 1_000),
+            None
+        );
+        assert_eq!(
+            scheduler.pause(&key("azure", Some("work")), 98.0, 1_000),
+            None
+        );
+    }
+
+    #[test]
+    fn waits_for_the_later_reset_when_both_windows_are_exhausted() {
+        let scheduler = RateLimitScheduler::default();
+        let openai = key("openai", None);
+        scheduler.record(
+            openai.clone(),
+            RateLimitSnapshot {
+                primary: window(100.0, 1_300),
+                secondary: window(98.5, 9_000),
+                credits: None,
+            },
+        );
+
+        assert_eq!(
+            scheduler
+                .pause(&openai, 98.0, 1_000)
+                .map(|pause| pause.window),
+            Some("secondary")
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Removes model reasoning from events and rollout items according to the
+//! `reasoning_redaction` setting.
+//!
+//! Redaction happens on the way out of the session: the conversation history
+//! sent back to the model keeps its reasoning, while clients and the rollout
+//! only ever see a marker in its place.
+
+use codex_protocol::items::TurnItem;
+use codex_protocol::models::ReasoningItemContent;
+use codex_protocol::models::ReasoningItemReasoningSummary;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::AgentReasoningEvent;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::RawResponseItemEvent;
+use codex_protocol::protocol::RolloutItem;
+
+use crate::config::types::ReasoningRedaction;
+
+/// Text that stands in for redacted reasoning.
+pub const REDACTED_REASONING_MARKER: &str = "[reasoning redacted]";
+
+/// Returns `msg` with reasoning removed, or `None` when the whole event only
+/// carried reasoning that must not be emitted (streaming deltas).
+pub(crate) fn redact_event(mode: ReasoningRedaction, msg: EventMsg) -> Option<EventMsg> {
+    if mode == ReasoningRedaction::None {
+        return Some(msg);
+    }
+    let redact_summaries = mode == ReasoningRedaction::All;
+    match msg {
+        EventMsg::AgentReasoningRawContent(_)
+        | EventMsg::AgentReasoningRawContentDelta(_)
+        | EventMsg::ReasoningRawContentDelta(_) => None,
+        EventMsg::AgentReasoningDelta(_)
+        | EventMsg::ReasoningContentDelta(_)
+        | EventMsg::AgentReasoningSectionBreak(_)
+            if redact_summaries =>
+        {
+            None
+        }
+        EventMsg::AgentReasoning(_) if redact_summaries => {
+            Some(EventMsg::AgentReasoning(AgentReasoningEvent {
+                text: REDACTED_REASONING_MARKER.to_string(),
+            }))
+        }
+        EventMsg::ItemStarted(mut event) => {
+            redact_turn_item(mode, &mut event.item);
+            Some(EventMsg::ItemStarted(event))
+        }
+        EventMsg::ItemCompleted(mut event) => {
+            redact_turn_item(mode, &mut event.item);
+            Some(EventMsg::ItemCompleted(event))
+        }
+        EventMsg::RawResponseItem(RawResponseItemEvent { item }) => {
+            Some(EventMsg::RawResponseItem(RawResponseItemEvent {
+                item: redact_response_item(mode, item),
+            }))
+        }
+        msg => Some(msg),
+    }
+}
+
+/// Returns `item` with reasoning removed, or `None` when nothing of it may be
+/// persisted.
+pub(crate) fn redact_rollout_item(
+    mode: ReasoningRedaction,
+    item: RolloutItem,
+) -> Option<RolloutItem> {
+    if mode == ReasoningRedaction::None {
+        return Some(item);
+    }
+    match item {
+        RolloutItem::ResponseItem(item) => {
+            Some(RolloutItem::ResponseItem(redact_response_item(mode, item)))
+        }
+        RolloutItem::Compacted(mut compacted) => {
+            compacted.replacement_history = compacted.replacement_history.map(|history| {
+                history
+                    .into_iter()
+                    .map(|item| redact_response_item(mode, item))
+                    .collect()
+            });
+            Some(RolloutItem::Compacted(compacted))
+        }
+        RolloutItem::EventMsg(msg) => redact_event(mode, msg).map(RolloutItem::EventMsg),
+        item => Some(item),
+    }
+}
+
+/// Replaces the content of a reasoning item with the marker. Encrypted
+/// reasoning is dropped in every mode since it is the reasoning itself.
+pub(crate) fn redact_response_item(mode: ReasoningRedaction, item: ResponseItem) -> ResponseItem {
+    match item {
+        ResponseItem::Reasoning {
+            id,
+            summary,
+            content,
+            encrypted_content: _,
+        } if mode != ReasoningRedaction::None => {
+            let summary = if mode == ReasoningRedaction::All && !summary.is_empty() {
+                vec![ReasoningItemReasoningSummary::SummaryText {
+                    text: REDACTED_REASONING_MARKER.to_string(),
+                }]
+            } else {
+                summary
+            };
+            ResponseItem::Reasoning {
+                id,
+                summary,
+                content: content.map(|_| {
+                    vec![ReasoningItemContent::ReasoningText {
+                        text: REDACTED_REASONING_MARKER.to_string(),
+                    }]
+                }),
+                encrypted_content: None,
+            }
+        }
+        item => item,
+    }
+}
+
+fn redact_turn_item(mode: ReasoningRedaction, item: &mut TurnItem) {
+    let TurnItem::Reasoning(reasoning) = item else {
+        return;
+    };
+    if !reasoning.raw_content.is_empty() {
+        reasoning.raw_content = vec![REDACTED_REASONING_MARKER.to_string()];
+    }
+    if mode == ReasoningRedaction::All && !reasoning.summary_text.is_empty() {
+        reasoning.summary_text = vec![REDACTED_REASONING_MARKER.to_string()];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::items::ReasoningItem;
+    use codex_protocol::protocol::AgentReasoningDeltaEvent;
+    use codex_protocol::protocol::AgentReasoningRawContentEvent;
+    use codex_protocol::protocol::CompactedItem;
+    use pretty_assertions::assert_eq;
+
+    fn reasoning_item() -> ResponseItem {
+        ResponseItem::Reasoning {
+            id: "rs_1".to_string(),
+            summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                text: "summary".to_string(),
+            }],
+            content: Some(vec![ReasoningItemContent::ReasoningText {
+                text: "thinking".to_string(),
+            }]),
+            encrypted_content: Some("opaque".to_string()),
+        }
+    }
+
+    #[test]
+    fn raw_mode_drops_raw_events_and_keeps_summaries() {
+        let raw = EventMsg::AgentReasoningRawContent(AgentReasoningRawContentEvent {
+            text: "thinking".to_string(),
+        });
+        assert!(redact_event(ReasoningRedaction::Raw, raw).is_none());
+
+        let delta = EventMsg::AgentReasoningDelta(AgentReasoningDeltaEvent {
+            delta: "sum".to_string(),
+        });
+        assert!(redact_event(ReasoningRedaction::Raw, delta.clone()).is_some());
+        assert!(redact_event(ReasoningRedaction::All, delta).is_none());
+
+        let Some(RolloutItem::ResponseItem(ResponseItem::Reasoning {
+            summary,
+            content,
+            encrypted_content,
+            ..
+        })) = redact_rollout_item(
+            ReasoningRedaction::Raw,
+            RolloutItem::ResponseItem(reasoning_item()),
+        )
+        else {
+            panic!("reasoning item should be kept");
+        };
+        assert_eq!(
+            summary,
+            vec![ReasoningItemReasoningSummary::SummaryText {
+                text: "summary".to_string(),
+            }]
+        );
+        assert_eq!(
+            content,
+            Some(vec![ReasoningItemContent::ReasoningText {
+                text: REDACTED_REASONING_MARKER.to_string(),
+            }])
+        );
+        assert_eq!(encrypted_content, None);
+    }
+
+    #[test]
+    fn all_mode_replaces_summaries_everywhere() {
+        let completed = EventMsg::ItemCompleted(codex_protocol::protocol::ItemCompletedEvent {
+            thread_id: codex_protocol::ConversationId::new(),
+            turn_id: "turn-1".to_string(),
+            item: TurnItem::Reasoning(ReasoningItem {
+                id: "rs_1".to_string(),
+                summary_text: vec!["summary".to_string()],
+                raw_content: vec!["thinking".to_string()],
+            }),
+        });
+        let Some(EventMsg::ItemCompleted(event)) = redact_event(ReasoningRedaction::All, completed)
+        else {
+            panic!("item completion should be kept");
+        };
+        let TurnItem::Reasoning(reasoning) = event.item else {
+            panic!("expected a reasoning item");
+        };
+        assert_eq!(reasoning.summary_text, vec![REDACTED_REASONING_MARKER]);
+        assert_eq!(reasoning.raw_content, vec![REDACTED_REASONING_MARKER]);
+
+        let compacted = RolloutItem::Compacted(CompactedItem {
+            message: "summary of the conversation".to_string(),
+            replacement_history: Some(vec![reasoning_item()]),
+        });
+        let Some(RolloutItem::Compacted(compacted)) =
+            redact_rollout_item(ReasoningRedaction::All, compacted)
+        else {
+            panic!("compaction should be kept");
+        };
+        assert_eq!(
+            compacted.replacement_history,
+            Some(vec![ResponseItem::Reasoning {
+                id: "rs_1".to_string(),
+                summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                    text: REDACTED_REASONING_MARKER.to_string(),
+                }],
+                content: Some(vec![ReasoningItemContent::ReasoningText {
+                    text: REDACTED_REASONING_MARKER.to_string(),
+                }]),
+                encrypted_content: None,
+            }])
+        );
+    }
+}
@@ -0,0 +1,493 @@
+//! Repository map: a condensed outline of the repository (its directory
+//! tree and the public symbols declared in each source file) given to the
+//! model as context, so its first turns in a large repository start from a
+//! picture of the code instead of blind greps.
+//!
+//! Building the map reads every tracked source file, so each session caches
+//! it and rebuilds only when the git state changes: `HEAD`, the output of
+//! `git status`, and the modification times of the files it lists. Symbols
+//! come from tree-sitter parses of Rust, Python, JavaScript/TypeScript, Go,
+//! and shell files.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::UNIX_EPOCH;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use tokio::process::Command;
+use tokio::time::timeout;
+use tracing::warn;
+use tree_sitter::Language;
+use tree_sitter::Node;
+use tree_sitter::Parser;
+use tree_sitter::Tree;
+use tree_sitter_bash::LANGUAGE as BASH;
+use tree_sitter_go::LANGUAGE as GO;
+use tree_sitter_javascript::LANGUAGE as JAVASCRIPT;
+use tree_sitter_python::LANGUAGE as PYTHON;
+use tree_sitter_rust::LANGUAGE as RUST;
+use tree_sitter_typescript::LANGUAGE_TSX as TSX;
+use tree_sitter_typescript::LANGUAGE_TYPESCRIPT as TYPESCRIPT;
+
+pub(crate) const REPO_MAP_OPEN_TAG: &str = "<repo_map>";
+const REPO_MAP_CLOSE_TAG: &str = "</repo_map>";
+/// Maps larger than this drop symbols first, then trailing files.
+const MAX_MAP_BYTES: usize = 24_000;
+/// Larger files are listed without symbols.
+const MAX_SOURCE_BYTES: u64 = 512 * 1024;
+const MAX_SYMBOLS_PER_FILE: usize = 12;
+const GIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Default)]
+pub(crate) struct RepoMapCache {
+    cached: Mutex<Option<CachedRepoMap>>,
+}
+
+struct CachedRepoMap {
+    root: PathBuf,
+    git_state: String,
+    outline: Arc<str>,
+}
+
+impl RepoMapCache {
+    /// The most recently built map, if any.
+    pub(crate) fn current(&self) -> Option<Arc<str>> {
+        self.cached
+            .lock()
+            .ok()?
+            .as_ref()
+            .map(|cached| cached.outline.clone())
+    }
+
+    /// Map of the repository containing `cwd`, rebuilt if the git state
+    /// changed since it was last built. `None` outside a git repository.
+    pub(crate) async fn refresh(&self, cwd: &Path) -> Option<Arc<str>> {
+        let root = PathBuf::from(git(cwd, &["rev-parse", "--show-toplevel"]).await?.trim());
+        let git_state = git_state(&root).await?;
+        if let Ok(cached) = self.cached.lock()
+            && let Some(cached) = cached.as_ref()
+            && cached.root == root
+            && cached.git_state == git_state
+        {
+            return Some(cached.outline.clone());
+        }
+
+        let files = git(
+            &root,
+            &["ls-files", "--cached", "--others", "--exclude-standard"],
+        )
+        .await?;
+        let files: Vec<String> = files.lines().map(str::to_string).collect();
+        let build_root = root.clone();
+        let outline: Arc<str> =
+            match tokio::task::spawn_blocking(move || build_repo_map(&build_root, &files)).await {
+                Ok(outline) => outline.into(),
+                Err(err) => {
+                    warn!("failed to build repository map: {err}");
+                    return None;
+                }
+            };
+        if let Ok(mut cached) = self.cached.lock() {
+            *cached = Some(CachedRepoMap {
+                root,
+                git_state,
+                outline: outline.clone(),
+            });
+        }
+        Some(outline)
+    }
+}
+
+/// Context message carrying the map.
+pub(crate) fn repo_map_item(outline: &str) -> ResponseItem {
+    ResponseItem::Message {
+        id: None,
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: format!("{REPO_MAP_OPEN_TAG}\n{outline}\n{REPO_MAP_CLOSE_TAG}"),
+        }],
+    }
+}
+
+/// Stdout of `git <args>` run in `cwd`; `None` when git fails or times out.
+pub(crate) async fn git(cwd: &Path, args: &[&str]) -> Option<String> {
+    let output = timeout(
+        GIT_TIMEOUT,
+        Command::new("git")
+            .args(args)
+            .current_dir(cwd)
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .ok()?
+    .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// `HEAD`, `git status`, and the modification times of the changed files:
+/// editing an already modified file does not change `git status`.
+async fn git_state(root: &Path) -> Option<String> {
+    // Repositories without commits have no `HEAD` yet.
+    let head = git(root, &["rev-parse", "HEAD"]).await.unwrap_or_default();
+    let status = git(root, &["status", "--porcelain", "--untracked-files=all"]).await?;
+    let mut state = format!("{}\n{status}", head.trim());
+    for line in status.lines() {
+        let Some(path) = line.get(3..) else {
+            continue;
+        };
+        let path = path.rsplit_once(" -> ").map_or(path, |(_, to)| to);
+        let modified = std::fs::metadata(root.join(path))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok());
+        if let Some(modified) = modified {
+            state.push_str(&format!("{path} {}\n", modified.as_nanos()));
+        }
+    }
+    Some(state)
+}
+
+/// Render the outline of `files` (paths relative to `root`, sorted as
+/// `git ls-files` prints them).
+fn build_repo_map(root: &Path, files: &[String]) -> String {
+    let symbols: Vec<Vec<String>> = files
+        .iter()
+        .map(|file| file_symbols(&root.join(file)))
+        .collect();
+    let header = format!(
+        "Outline of the repository at {} ({} files): its directory tree, with the public symbols each source file declares.",
+        root.display(),
+        files.len()
+    );
+    let with_symbols = render_tree(&header, files, Some(symbols.as_slice()));
+    if with_symbols.len() <= MAX_MAP_BYTES {
+        return with_symbols;
+    }
+    render_tree(&header, files, None)
+}
+
+fn render_tree(header: &str, files: &[String], symbols: Option<&[Vec<String>]>) -> String {
+    let mut out = header.to_string();
+    let mut open_dirs: Vec<&str> = Vec::new();
+    for (index, file) in files.iter().enumerate() {
+        let mut components: Vec<&str> = file.split('/').collect();
+        let Some(name) = components.pop() else {
+            continue;
+        };
+        let shared = open_dirs
+            .iter()
+            .zip(&components)
+            .take_while(|(open, dir)| open == dir)
+            .count();
+        open_dirs.truncate(shared);
+        let mut lines = Vec::new();
+        for dir in &components[shared..] {
+            lines.push(format!("\n{}{dir}/", "  ".repeat(open_dirs.len())));
+            open_dirs.push(*dir);
+        }
+        let mut line = format!("\n{}{name}", "  ".repeat(open_dirs.len()));
+        if let Some(file_symbols) = symbols.and_then(|symbols| symbols.get(index))
+            && !file_symbols.is_empty()
+        {
+            line.push_str(": ");
+            line.push_str(&file_symbols.join(", "));
+        }
+        lines.push(line);
+
+        let added: usize = lines.iter().map(String::len).sum();
+        if out.len() + added > MAX_MAP_BYTES {
+            out.push_str(&format!("\n… {} more files not shown", files.len() - index));
+            break;
+        }
+        out.extend(lines);
+    }
+    out
+}
+
+fn file_symbols(path: &Path) -> Vec<String> {
+    let Some(extension) = path.extension().and_then(|ext| ext.to_str()) else {
+        return Vec::new();
+    };
+    let scan: fn(&str) -> Vec<String> = match extension {
+        "rs" => rust_symbols,
+        "py" => python_symbols,
+        "js" | "jsx" | "mjs" | "cjs" => javascript_symbols,
+        "ts" | "mts" | "cts" => typescript_symbols,
+        "tsx" => tsx_symbols,
+        "go" => go_symbols,
+        "sh" | "bash" => shell_symbols,
+        _ => return Vec::new(),
+    };
+    let readable = std::fs::metadata(path)
+        .is_ok_and(|metadata| metadata.is_file() && metadata.len() <= MAX_SOURCE_BYTES);
+    if !readable {
+        return Vec::new();
+    }
+    let Ok(source) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let mut symbols = scan(&source);
+    if symbols.len() > MAX_SYMBOLS_PER_FILE {
+        symbols.truncate(MAX_SYMBOLS_PER_FILE);
+        symbols.push("…".to_string());
+    }
+    symbols
+}
+
+fn parse(source: &str, language: Language) -> Option<Tree> {
+    let mut parser = Parser::new();
+    parser.set_language(&language).ok()?;
+    parser.parse(source, None)
+}
+
+fn named_children(node: Node<'_>) -> Vec<Node<'_>> {
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor).collect()
+}
+
+fn text<'a>(node: Node<'_>, source: &'a str) -> &'a str {
+    node.utf8_text(source.as_bytes()).unwrap_or_default()
+}
+
+/// `kind name` for `node`, named by its `name` field.
+fn named_symbol(kind: &str, node: Node<'_>, source: &str) -> Option<String> {
+    let name = text(node.child_by_field_name("name")?, source);
+    (!name.is_empty()).then(|| format!("{kind} {name}"))
+}
+
+fn rust_symbols(source: &str) -> Vec<String> {
+    let mut symbols = Vec::new();
+    if let Some(tree) = parse(source, RUST.into()) {
+        rust_items(tree.root_node(), source, &mut symbols);
+    }
+    symbols
+}
+
+/// `pub` items declared in `node`, including those inside `impl` blocks and
+/// inline modules.
+fn rust_items(node: Node<'_>, source: &str, symbols: &mut Vec<String>) {
+    for item in named_children(node) {
+        let kind = match item.kind() {
+            "function_item" | "function_signature_item" => "fn",
+            "struct_item" => "struct",
+            "enum_item" => "enum",
+            "trait_item" => "trait",
+            "type_item" => "type",
+            "const_item" => "const",
+            "static_item" => "static",
+            "mod_item" => "mod",
+            "union_item" => "union",
+            "impl_item" => {
+                if let Some(body) = item.child_by_field_name("body") {
+                    rust_items(body, source, symbols);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        let public = named_children(item)
+            .into_iter()
+            .any(|child| child.kind() == "visibility_modifier" && text(child, source) == "pub");
+        if public {
+            symbols.extend(named_symbol(kind, item, source));
+        }
+        if kind == "mod"
+            && let Some(body) = item.child_by_field_name("body")
+        {
+            rust_items(body, source, symbols);
+        }
+    }
+}
+
+fn python_symbols(source: &str) -> Vec<String> {
+    let Some(tree) = parse(source, PYTHON.into()) else {
+        return Vec::new();
+    };
+    named_children(tree.root_node())
+        .into_iter()
+        .filter_map(|node| {
+            let node = match node.kind() {
+                "decorated_definition" => node.child_by_field_name("definition")?,
+                _ => node,
+            };
+            let kind = match node.kind() {
+                "function_definition" => "def",
+                "class_definition" => "class",
+                _ => return None,
+            };
+            named_symbol(kind, node, source)
+        })
+        .filter(|symbol| {
+            !symbol
+                .split(' ')
+                .nth(1)
+                .is_some_and(|name| name.starts_with('_'))
+        })
+        .collect()
+}
+
+fn javascript_symbols(source: &str) -> Vec<String> {
+    export_symbols(source, JAVASCRIPT.into())
+}
+
+fn typescript_symbols(source: &str) -> Vec<String> {
+    export_symbols(source, TYPESCRIPT.into())
+}
+
+fn tsx_symbols(source: &str) -> Vec<String> {
+    export_symbols(source, TSX.into())
+}
+
+/// Declarations exported at the top level of a JavaScript or TypeScript
+/// module.
+fn export_symbols(source: &str, language: Language) -> Vec<String> {
+    let Some(tree) = parse(source, language) else {
+        return Vec::new();
+    };
+    named_children(tree.root_node())
+        .into_iter()
+        .filter(|node| node.kind() == "export_statement")
+        .filter_map(|node| {
+            node.child_by_field_name("declaration")
+                .or_else(|| node.child_by_field_name("value"))
+        })
+        .flat_map(|declaration| declaration_symbols(declaration, source))
+        .collect()
+}
+
+fn declaration_symbols(node: Node<'_>, source: &str) -> Vec<String> {
+    let kind = match node.kind() {
+        "function_declaration" | "function_expression" => "function",
+        "generator_function_declaration" => "function*",
+        "class_declaration" | "abstract_class_declaration" | "class" => "class",
+        "interface_declaration" => "interface",
+        "type_alias_declaration" => "type",
+        "enum_declaration" => "enum",
+        "internal_module" | "module" => "namespace",
+        // `export declare ...`
+        "ambient_declaration" => {
+            return named_children(node)
+                .into_iter()
+                .flat_map(|child| declaration_symbols(child, source))
+                .collect();
+        }
+        "lexical_declaration" | "variable_declaration" => {
+            let keyword = node
+                .child_by_field_name("kind")
+                .map_or("var", |kind| text(kind, source));
+            return named_children(node)
+                .into_iter()
+                .filter(|child| child.kind() == "variable_declarator")
+                .filter_map(|declarator| named_symbol(keyword, declarator, source))
+                .collect();
+        }
+        _ => return Vec::new(),
+    };
+    named_symbol(kind, node, source).into_iter().collect()
+}
+
+fn go_symbols(source: &str) -> Vec<String> {
+    let Some(tree) = parse(source, GO.into()) else {
+        return Vec::new();
+    };
+    named_children(tree.root_node())
+        .into_iter()
+        .flat_map(|node| match node.kind() {
+            "function_declaration" | "method_declaration" => {
+                named_symbol("func", node, source).into_iter().collect()
+            }
+            "type_declaration" => named_children(node)
+                .into_iter()
+                .filter(|spec| matches!(spec.kind(), "type_spec" | "type_alias"))
+                .filter_map(|spec| named_symbol("type", spec, source))
+                .collect(),
+            _ => Vec::new(),
+        })
+        .filter(|symbol| {
+            symbol
+                .split(' ')
+                .nth(1)
+                .and_then(|name| name.chars().next())
+                .is_some_and(char::is_uppercase)
+        })
+        .collect()
+}
+
+fn shell_symbols(source: &str) -> Vec<String> {
+    let Some(tree) = parse(source, BASH.into()) else {
+        return Vec::new();
+    };
+    named_children(tree.root_node())
+        .into_iter()
+        .filter(|node| node.kind() == "function_definition")
+        .filter_map(|node| named_symbol("function", node, source))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn extracts_public_declarations() {
+        assert_eq!(
+            rust_symbols(
+                "pub struct Config;\nstruct Private;\npub(crate) fn hidden() {}\nimpl Config {\n    pub const fn new() -> Self {}\n    pub const MAX: usize = 1;\n}\npub use foo::Bar;\n"
+            ),
+            vec!["struct Config", "fn new", "const MAX"]
+        );
+        assert_eq!(
+            python_symbols(
+                "class Store:\n    def get(self): ...\nasync def fetch(url): ...\ndef _private(): ...\n"
+            ),
+            vec!["class Store", "def fetch"]
+        );
+        assert_eq!(
+            javascript_symbols(
+                "export default async function main() {}\nexport const API_URL = '';\nconst local = 1;\nexport class App {}\n"
+            ),
+            vec!["function main", "const API_URL", "class App"]
+        );
+        assert_eq!(
+            typescript_symbols(
+                "export interface Props {}\nexport declare function load(): void;\ntype Local = string;\nexport type Id = string;\n"
+            ),
+            vec!["interface Props", "function load", "type Id"]
+        );
+        assert_eq!(
+            go_symbols(
+                "func (s *Server) Serve() error {}\nfunc helper() {}\ntype Handler interface {}\n"
+            ),
+            vec!["func Serve", "type Handler"]
+        );
+        assert_eq!(
+            shell_symbols("#!/bin/bash\nsetup() {\n  echo hi\n}\nfunction run { :; }\n"),
+            vec!["function setup", "function run"]
+        );
+    }
+
+    #[test]
+    fn renders_nested_directories_once() {
+        let files =
+            ["README.md", "src/lib.rs", "src/util/mod.rs", "tests/it.rs"].map(str::to_string);
+        let symbols = vec![
+            Vec::new(),
+            vec!["fn run".to_string()],
+            Vec::new(),
+            Vec::new(),
+        ];
+        assert_eq!(
+            render_tree("Outline", &files, Some(symbols.as_slice())),
+            "Outline\nREADME.md\nsrc/\n  lib.rs: fn run\n  util/\n    mod.rs\ntests/\n  it.rs"
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Opt-in on-disk cache for deterministic auxiliary model calls.
+//!
+//! Compaction summaries and sandbox command assessments depend only on the
+//! model, the instructions, and the input, so re-running the same scenario
+//! would otherwise pay for identical requests again. With
+//! `[response_cache] enabled = true`, the output items of those calls are
+//! stored under `~/.codex/cache/responses`, one JSON file per request named by
+//! a hash of (model, instructions hash, input hash). Entries older than
+//! `ttl_secs` are treated as misses, and once more than `max_entries` are
+//! stored the oldest are evicted.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use codex_protocol::models::ResponseItem;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use tracing::warn;
+
+use crate::client_common::Prompt;
+use crate::config::Config;
+use crate::model_family::ModelFamily;
+
+const RESPONSE_CACHE_DIR: &str = "cache/responses";
+
+/// Identifies a cached response: the model slug plus hashes of the full
+/// instructions and of everything else sent as input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ResponseCacheKey(String);
+
+impl ResponseCacheKey {
+    pub(crate) fn for_prompt(model: &str, model_family: &ModelFamily, prompt: &Prompt) -> Self {
+        let instructions = prompt.get_full_instructions(model_family);
+        let input = serde_json::json!({
+            "input": prompt.get_formatted_input(),
+            "tools": prompt.tools,
+            "output_schema": prompt.output_schema,
+        });
+        Self::new(model, &instructions, input.to_string().as_bytes())
+    }
+
+    fn new(model: &str, instructions: &str, input: &[u8]) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(model.as_bytes());
+        hasher.update([0]);
+        hasher.update(Sha256::digest(instructions.as_bytes()));
+        hasher.update(Sha256::digest(input));
+        Self(format!("{:x}", hasher.finalize()))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedResponse {
+    model: String,
+    items: Vec<ResponseItem>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+    max_entries: usize,
+}
+
+impl ResponseCache {
+    /// The cache configured for `config`, or `None` when caching is off.
+    pub(crate) fn from_config(config: &Config) -> Option<Self> {
+        let settings = &config.response_cache;
+        settings.enabled.then(|| {
+            Self::new(
+                config.codex_home.join(RESPONSE_CACHE_DIR),
+                Duration::from_secs(settings.ttl_secs),
+                settings.max_entries,
+            )
+        })
+    }
+
+    fn new(dir: PathBuf, ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            dir,
+            ttl,
+            max_entries,
+        }
+    }
+
+    /// Output items previously stored for `key`, if they have not expired.
+    pub(crate) async fn get(&self, key: &ResponseCacheKey) -> Option<Vec<ResponseItem>> {
+        let path = self.entry_path(key);
+        let modified = tokio::fs::metadata(&path).await.ok()?.modified().ok()?;
+        if self.is_expired(modified) {
+            let _ = tokio::fs::remove_file(&path).await;
+            return None;
+        }
+        let contents = tokio::fs::read(&path).await.ok()?;
+        match serde_json::from_slice::<CachedResponse>(&contents) {
+            Ok(entry) => Some(entry.items),
+            Err(err) => {
+                warn!(
+                    "ignoring unreadable response cache entry {}: {err}",
+                    path.display()
+                );
+                None
+            }
+        }
+    }
+
+    /// Store the output items of a completed request. Failures are logged
+    /// and otherwise ignored.
+    pub(crate) async fn put(&self, key: &ResponseCacheKey, model: &str, items: &[ResponseItem]) {
+        if items.is_empty() {
+            return;
+        }
+        if let Err(err) = self.try_put(key, model, items).await {
+            warn!("failed to write response cache entry: {err}");
+        }
+    }
+
+    async fn try_put(
+        &self,
+        key: &ResponseCacheKey,
+        model: &str,
+        items: &[ResponseItem],
+    ) -> io::Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let entry = CachedResponse {
+            model: model.to_string(),
+            items: items.to_vec(),
+        };
+        let contents = serde_json::to_vec(&entry).map_err(io::Error::other)?;
+        let path = self.entry_path(key);
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, contents).await?;
+        tokio::fs::rename(&tmp_path, &path).await?;
+        self.prune().await
+    }
+
+    /// Remove expired entries, then the oldest ones beyond `max_entries`.
+    async fn prune(&self) -> io::Result<()> {
+        let mut entries = Vec::new();
+        let mut dir = tokio::fs::read_dir(&self.dir).await?;
+        while let Some(entry) = dir.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+            let modified = entry.metadata().await?.modified()?;
+            if self.is_expired(modified) {
+                remove_entry(&path).await;
+            } else {
+                entries.push((modified, path));
+            }
+        }
+        if entries.len() > self.max_entries {
+            entries.sort();
+            let excess = entries.len() - self.max_entries;
+            for (_, path) in entries.drain(..excess) {
+                remove_entry(&path).await;
+            }
+        }
+        Ok(())
+    }
+
+    fn entry_path(&self, key: &ResponseCacheKey) -> PathBuf {
+        self.dir.join(format!("{}.json", key.0))
+    }
+
+    fn is_expired(&self, modified: SystemTime) -> bool {
+        SystemTime::now()
+            .duration_since(modified)
+            .is_ok_and(|age| age >= self.ttl)
+    }
+}
+
+async fn remove_entry(path: &Path) {
+    if let Err(err) = tokio::fs::remove_file(path).await
+        && err.kind() != io::ErrorKind::NotFound
+    {
+        warn!(
+            "failed to evict response cache entry {}: {err}",
+            path.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    fn message(text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: "assistant".to_string(),
+            content: vec![ContentItem::OutputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    fn key(input: &str) -> ResponseCacheKey {
+        ResponseCacheKey::new("gpt-5", "instructions", input.as_bytes())
+    }
+
+    #[tokio::test]
+    async fn returns_stored_items_for_identical_requests() {
+        let dir = tempdir().expect("tmp");
+        let cache = ResponseCache::new(dir.path().to_path_buf(), Duration::from_secs(60), 8);
+
+        cache
+            .put(&key("summarize"), "gpt-5", &[message("summary")])
+            .await;
+
+        assert_eq!(
+            cache.get(&key("summarize")).await,
+            Some(vec![message("summary")])
+        );
+        assert_eq!(cache.get(&key("summarize again")).await, None);
+        assert_ne!(
+            ResponseCacheKey::new("gpt-5", "instructions", b"summarize"),
+            ResponseCacheKey::new("gpt-5-codex", "instructions", b"summarize")
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_misses() {
+        let dir = tempdir().expect("tmp");
+        let cache = ResponseCache::new(dir.path().to_path_buf(), Duration::ZERO, 8);
+
+        cache
+            .put(&key("summarize"), "gpt-5", &[message("summary")])
+            .await;
+
+        assert_eq!(cache.get(&key("summarize")).await, None);
+    }
+
+    #[tokio::test]
+    async fn evicts_oldest_entries_beyond_max() {
+        let dir = tempdir().expect("tmp");
+        let cache = ResponseCache::new(dir.path().to_path_buf(), Duration::from_secs(3600), 2);
+        cache.put(&key("first"), "gpt-5", &[message("1")]).await;
+        let minute_ago = SystemTime::now() - Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(cache.entry_path(&key("first")))
+            .and_then(|file| file.set_modified(minute_ago))
+            .expect("backdate entry");
+
+        cache.put(&key("second"), "gpt-5", &[message("2")]).await;
+        cache.put(&key("third"), "gpt-5", &[message("3")]).await;
+
+        assert_eq!(cache.get(&key("first")).await, None);
+        assert_eq!(cache.get(&key("second")).await, Some(vec![message("2")]));
+        assert_eq!(cache.get(&key("third")).await, Some(vec![message("3")]));
+    }
+}
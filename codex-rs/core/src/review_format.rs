@@ -49,6 +49,13 @@ pub fn format_review_findings_block(
         for body_line in item.body.lines() {
             lines.push(format!("  {body_line}"));
         }
+
+        if let Some(suggestion) = &item.suggestion {
+            lines.push("  Suggested change:".to_string());
+            for suggestion_line in suggestion.lines() {
+                lines.push(format!("    {suggestion_line}"));
+            }
+        }
     }
 
     lines.join("\n")
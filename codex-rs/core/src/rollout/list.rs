@@ -435,6 +435,9 @@ async fn read_head_and_tail(
             RolloutItem::Compacted(_) => {
                 // Not included in `head`; skip.
             }
+            RolloutItem::ExecOutputArchive(_) | RolloutItem::ModelUsage(_) => {
+                // Not included in `head`; skip.
+            }
             RolloutItem::EventMsg(ev) => {
                 if matches!(ev, EventMsg::UserMessage(_)) {
                     summary.saw_user_event = true;
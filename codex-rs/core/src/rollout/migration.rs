@@ -0,0 +1,232 @@
+//! Schema versioning for rollout files.
+//!
+//! The session meta line of every rollout records the schema version the
+//! file was written with. Files from before versioning carry no version and
+//! read as version 0. When a rollout is loaded, every migration from its
+//! version up to [`ROLLOUT_SCHEMA_VERSION`] runs over its lines in order;
+//! files written by a newer Codex are refused with
+//! [`RolloutVersionError::Unsupported`] rather than misread.
+//!
+//! To change the on-disk format, bump [`ROLLOUT_SCHEMA_VERSION`] and append a
+//! `Migration` from the previous version to `MIGRATIONS`.
+
+use serde_json::Value;
+
+/// Schema version written to new rollout files.
+pub const ROLLOUT_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum RolloutVersionError {
+    #[error(
+        "rollout schema version {found} is newer than the newest version this Codex supports ({supported}); upgrade Codex to open it"
+    )]
+    Unsupported { found: u32, supported: u32 },
+}
+
+/// Upgrades the lines of a rollout from schema version `from` to `from + 1`.
+struct Migration {
+    from: u32,
+    migrate: fn(Vec<Value>) -> Vec<Value>,
+}
+
+const MIGRATIONS: &[Migration] = &[Migration {
+    from: 0,
+    migrate: wrap_legacy_lines,
+}];
+
+/// Result of [`migrate_rollout_lines`].
+#[derive(Debug, PartialEq)]
+pub(crate) struct MigratedLines {
+    pub lines: Vec<Value>,
+    /// Version the lines were read as, before migrating.
+    pub from_version: u32,
+}
+
+impl MigratedLines {
+    pub(crate) fn was_migrated(&self) -> bool {
+        self.from_version != ROLLOUT_SCHEMA_VERSION
+    }
+}
+
+/// Schema version recorded in the first session meta line, or 0 when there
+/// is none.
+pub(crate) fn rollout_schema_version(lines: &[Value]) -> u32 {
+    lines
+        .iter()
+        .find(|line| line.get("type").and_then(Value::as_str) == Some("session_meta"))
+        .and_then(|line| line.get("payload"))
+        .and_then(|payload| payload.get("schema_version"))
+        .and_then(Value::as_u64)
+        .map_or(0, |version| u32::try_from(version).unwrap_or(u32::MAX))
+}
+
+/// Runs every migration needed to bring `lines` up to
+/// [`ROLLOUT_SCHEMA_VERSION`] and stamps the result with that version.
+pub(crate) fn migrate_rollout_lines(
+    lines: Vec<Value>,
+) -> Result<MigratedLines, RolloutVersionError> {
+    let from_version = rollout_schema_version(&lines);
+    if from_version > ROLLOUT_SCHEMA_VERSION {
+        return Err(RolloutVersionError::Unsupported {
+            found: from_version,
+            supported: ROLLOUT_SCHEMA_VERSION,
+        });
+    }
+    let mut lines = lines;
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|migration| migration.from >= from_version)
+    {
+        lines = (migration.migrate)(lines);
+    }
+    if from_version != ROLLOUT_SCHEMA_VERSION {
+        stamp_version(&mut lines);
+    }
+    Ok(MigratedLines {
+        lines,
+        from_version,
+    })
+}
+
+fn stamp_version(lines: &mut [Value]) {
+    let payload = lines
+        .iter_mut()
+        .find(|line| line.get("type").and_then(Value::as_str) == Some("session_meta"))
+        .and_then(|line| line.get_mut("payload"))
+        .and_then(Value::as_object_mut);
+    if let Some(payload) = payload {
+        payload.insert(
+            "schema_version".to_string(),
+            Value::from(ROLLOUT_SCHEMA_VERSION),
+        );
+    }
+}
+
+/// v0 -> v1: the earliest rollouts stored the session meta as a bare object
+/// on the first line, response items without the `{timestamp, type,
+/// payload}` envelope, and interleaved `record_type: "state"` lines. Wrap
+/// those in the envelope and drop the state lines. Lines that already have
+/// the envelope are kept as they are.
+fn wrap_legacy_lines(lines: Vec<Value>) -> Vec<Value> {
+    let mut timestamp: Option<Value> = None;
+    let mut migrated = Vec::with_capacity(lines.len());
+    for line in lines {
+        let Value::Object(mut object) = line else {
+            continue;
+        };
+        if object.contains_key("payload") {
+            if timestamp.is_none() {
+                timestamp = object.get("timestamp").cloned();
+            }
+            migrated.push(Value::Object(object));
+            continue;
+        }
+        if object.contains_key("record_type") {
+            continue;
+        }
+        let kind = if object.contains_key("type") {
+            "response_item"
+        } else if object.contains_key("id") && object.contains_key("timestamp") {
+            timestamp = object.get("timestamp").cloned();
+            // Fields that became required after these files were written.
+            for key in ["cwd", "originator", "cli_version"] {
+                object
+                    .entry(key)
+                    .or_insert_with(|| Value::String(String::new()));
+            }
+            "session_meta"
+        } else {
+            continue;
+        };
+        migrated.push(serde_json::json!({
+            "timestamp": timestamp.clone().unwrap_or_else(|| Value::String(String::new())),
+            "type": kind,
+            "payload": Value::Object(object),
+        }));
+    }
+    migrated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::RolloutItem;
+    use codex_protocol::protocol::RolloutLine;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn upgrades_legacy_rollout_lines() {
+        let lines = vec![
+            json!({
+                "id": "5973b6c0-94b8-487b-a530-2aeb6098ae0e",
+                "timestamp": "2025-05-07T17:24:21.123Z",
+                "instructions": null,
+            }),
+            json!({"record_type": "state"}),
+            json!({
+                "type": "message",
+                "role": "user",
+                "content": [{"type": "input_text", "text": "hello"}],
+            }),
+        ];
+
+        let migrated = migrate_rollout_lines(lines).expect("migrate");
+
+        assert_eq!(migrated.from_version, 0);
+        assert!(migrated.was_migrated());
+        assert_eq!(
+            rollout_schema_version(&migrated.lines),
+            ROLLOUT_SCHEMA_VERSION
+        );
+        let items: Vec<RolloutItem> = migrated
+            .lines
+            .into_iter()
+            .map(|line| {
+                serde_json::from_value::<RolloutLine>(line)
+                    .expect("migrated line parses")
+                    .item
+            })
+            .collect();
+        assert!(matches!(
+            items.as_slice(),
+            [RolloutItem::SessionMeta(_), RolloutItem::ResponseItem(_)]
+        ));
+    }
+
+    #[test]
+    fn current_rollouts_are_left_alone() {
+        let lines = vec![json!({
+            "timestamp": "2025-05-07T17:24:21.123Z",
+            "type": "session_meta",
+            "payload": {"id": "5973b6c0-94b8-487b-a530-2aeb6098ae0e", "schema_version": 1},
+        })];
+
+        let migrated = migrate_rollout_lines(lines.clone()).expect("migrate");
+
+        assert_eq!(
+            migrated,
+            MigratedLines {
+                lines,
+                from_version: ROLLOUT_SCHEMA_VERSION,
+            }
+        );
+    }
+
+    #[test]
+    fn refuses_future_versions() {
+        let lines = vec![json!({
+            "timestamp": "2025-05-07T17:24:21.123Z",
+            "type": "session_meta",
+            "payload": {"schema_version": ROLLOUT_SCHEMA_VERSION + 1},
+        })];
+
+        assert_eq!(
+            migrate_rollout_lines(lines),
+            Err(RolloutVersionError::Unsupported {
+                found: ROLLOUT_SCHEMA_VERSION + 1,
+                supported: ROLLOUT_SCHEMA_VERSION,
+            })
+        );
+    }
+}
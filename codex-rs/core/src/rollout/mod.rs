@@ -8,11 +8,14 @@ pub const INTERACTIVE_SESSION_SOURCES: &[SessionSource] =
     &[SessionSource::Cli, SessionSource::VSCode];
 
 pub mod list;
+pub mod migration;
 pub(crate) mod policy;
 pub mod recorder;
 
 pub use codex_protocol::protocol::SessionMeta;
 pub use list::find_conversation_path_by_id_str;
+pub use migration::ROLLOUT_SCHEMA_VERSION;
+pub use migration::RolloutVersionError;
 pub use recorder::RolloutRecorder;
 pub use recorder::RolloutRecorderParams;
 
@@ -9,9 +9,11 @@ pub(crate) fn is_persisted_response_item(item: &RolloutItem) -> bool {
         RolloutItem::ResponseItem(item) => should_persist_response_item(item),
         RolloutItem::EventMsg(ev) => should_persist_event_msg(ev),
         // Persist Codex executive markers so we can analyze flows (e.g., compaction, API turns).
-        RolloutItem::Compacted(_) | RolloutItem::TurnContext(_) | RolloutItem::SessionMeta(_) => {
-            true
-        }
+        RolloutItem::Compacted(_)
+        | RolloutItem::TurnContext(_)
+        | RolloutItem::SessionMeta(_)
+        | RolloutItem::ExecOutputArchive(_)
+        | RolloutItem::ModelUsage(_) => true,
     }
 }
 
@@ -57,6 +59,7 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::RawResponseItem(_)
         | EventMsg::SessionConfigured(_)
         | EventMsg::McpToolCallBegin(_)
+        | EventMsg::McpToolCallProgress(_)
         | EventMsg::McpToolCallEnd(_)
         | EventMsg::WebSearchBegin(_)
         | EventMsg::WebSearchEnd(_)
@@ -65,17 +68,45 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::ExecCommandEnd(_)
         | EventMsg::ExecApprovalRequest(_)
         | EventMsg::ApplyPatchApprovalRequest(_)
+        | EventMsg::WriteScopeApprovalRequest(_)
+        | EventMsg::ElicitationRequest(_)
+        | EventMsg::UserInputRequest(_)
         | EventMsg::BackgroundEvent(_)
         | EventMsg::StreamError(_)
         | EventMsg::PatchApplyBegin(_)
         | EventMsg::PatchApplyEnd(_)
+        | EventMsg::PatchDiagnostics(_)
+        | EventMsg::FileLockWait(_)
         | EventMsg::TurnDiff(_)
         | EventMsg::GetHistoryEntryResponse(_)
+        | EventMsg::PatchContentResponse(_)
+        | EventMsg::WorkspaceInstructionProposed(_)
+        | EventMsg::NetworkHostBlocked(_)
+        | EventMsg::BudgetExhausted(_)
+        | EventMsg::ContextCompacted(_)
+        | EventMsg::ProviderHealthChanged(_)
+        | EventMsg::RateLimited(_)
+        | EventMsg::BackgroundTaskUpdate(_)
+        | EventMsg::SubAgentUpdate(_)
+        | EventMsg::WorktreeDiff(_)
+        | EventMsg::WorktreeClosed(_)
+        | EventMsg::PullRequestCreated(_)
+        | EventMsg::InstructionsChanged(_)
+        | EventMsg::ProfileChanged(_)
+        | EventMsg::ThreadMetadataChanged(_)
+        | EventMsg::SecretsRedacted(_)
+        | EventMsg::TurnHeartbeat(_)
+        | EventMsg::McpServerStatusChanged(_)
+        | EventMsg::ToolsChanged(_)
+        | EventMsg::McpToolConflicts(_)
         | EventMsg::UndoStarted(_)
         | EventMsg::McpListToolsResponse(_)
         | EventMsg::McpStartupUpdate(_)
         | EventMsg::McpStartupComplete(_)
         | EventMsg::ListCustomPromptsResponse(_)
+        | EventMsg::ListApprovalRulesResponse(_)
+        | EventMsg::McpListPromptsResponse(_)
+        | EventMsg::McpResourceReadResponse(_)
         | EventMsg::PlanUpdate(_)
         | EventMsg::ShutdownComplete
         | EventMsg::ViewImageToolCall(_)
@@ -22,6 +22,8 @@ use super::SESSIONS_SUBDIR;
 use super::list::ConversationsPage;
 use super::list::Cursor;
 use super::list::get_conversations;
+use super::migration::ROLLOUT_SCHEMA_VERSION;
+use super::migration::migrate_rollout_lines;
 use super::policy::is_persisted_response_item;
 use crate::config::Config;
 use crate::default_client::originator;
@@ -148,6 +150,7 @@ impl RolloutRecorder {
                         instructions,
                         source,
                         model_provider: Some(config.model_provider_id.clone()),
+                        schema_version: ROLLOUT_SCHEMA_VERSION,
                     }),
                 )
             }
@@ -214,20 +217,33 @@ impl RolloutRecorder {
             return Err(IoError::other("empty session file"));
         }
 
-        let mut items: Vec<RolloutItem> = Vec::new();
-        let mut conversation_id: Option<ConversationId> = None;
+        let mut lines: Vec<Value> = Vec::new();
         for line in text.lines() {
             if line.trim().is_empty() {
                 continue;
             }
-            let v: Value = match serde_json::from_str(line) {
-                Ok(v) => v,
+            match serde_json::from_str(line) {
+                Ok(v) => lines.push(v),
                 Err(e) => {
                     warn!("failed to parse line as JSON: {line:?}, error: {e}");
-                    continue;
                 }
-            };
+            }
+        }
+        let migrated = migrate_rollout_lines(lines)
+            .map_err(|err| IoError::new(std::io::ErrorKind::InvalidData, err))?;
+        if migrated.was_migrated() {
+            info!(
+                "Upgraded rollout {path:?} from schema version {} to {ROLLOUT_SCHEMA_VERSION}",
+                migrated.from_version
+            );
+            if let Err(err) = rewrite_rollout(path, &migrated.lines).await {
+                warn!("failed to save upgraded rollout {path:?}: {err}");
+            }
+        }
 
+        let mut items: Vec<RolloutItem> = Vec::new();
+        let mut conversation_id: Option<ConversationId> = None;
+        for v in migrated.lines {
             // Parse the rollout line structure
             match serde_json::from_value::<RolloutLine>(v.clone()) {
                 Ok(rollout_line) => match rollout_line.item {
@@ -251,6 +267,12 @@ impl RolloutRecorder {
                     RolloutItem::EventMsg(_ev) => {
                         items.push(RolloutItem::EventMsg(_ev));
                     }
+                    RolloutItem::ExecOutputArchive(item) => {
+                        items.push(RolloutItem::ExecOutputArchive(item));
+                    }
+                    RolloutItem::ModelUsage(item) => {
+                        items.push(RolloutItem::ModelUsage(item));
+                    }
                 },
                 Err(e) => {
                     warn!("failed to parse rollout line: {v:?}, error: {e}");
@@ -346,6 +368,22 @@ fn create_log_file(
     })
 }
 
+/// Replaces the rollout at `path` with `lines`. The new contents are written
+/// next to the original and renamed over it so a crash never leaves a
+/// half-written rollout behind.
+async fn rewrite_rollout(path: &Path, lines: &[Value]) -> std::io::Result<()> {
+    let mut contents = String::new();
+    for line in lines {
+        contents.push_str(&serde_json::to_string(line)?);
+        contents.push('\n');
+    }
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".upgrade");
+    let tmp_path = path.with_file_name(tmp_name);
+    tokio::fs::write(&tmp_path, contents).await?;
+    tokio::fs::rename(&tmp_path, path).await
+}
+
 async fn rollout_writer(
     file: tokio::fs::File,
     mut rx: mpsc::Receiver<RolloutCmd>,
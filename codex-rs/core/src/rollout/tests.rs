@@ -13,6 +13,7 @@ use time::macros::format_description;
 use uuid::Uuid;
 
 use crate::rollout::INTERACTIVE_SESSION_SOURCES;
+use crate::rollout::ROLLOUT_SCHEMA_VERSION;
 use crate::rollout::list::ConversationItem;
 use crate::rollout::list::ConversationsPage;
 use crate::rollout::list::Cursor;
@@ -594,6 +595,7 @@ async fn test_tail_includes_last_response_items() -> Result<()> {
                 cli_version: "test_version".into(),
                 source: SessionSource::VSCode,
                 model_provider: Some("test-provider".into()),
+                schema_version: ROLLOUT_SCHEMA_VERSION,
             },
             git: None,
         }),
@@ -687,6 +689,7 @@ async fn test_tail_handles_short_sessions() -> Result<()> {
                 cli_version: "test_version".into(),
                 source: SessionSource::VSCode,
                 model_provider: Some("test-provider".into()),
+                schema_version: ROLLOUT_SCHEMA_VERSION,
             },
             git: None,
         }),
@@ -781,6 +784,7 @@ async fn test_tail_skips_trailing_non_responses() -> Result<()> {
                 cli_version: "test_version".into(),
                 source: SessionSource::VSCode,
                 model_provider: Some("test-provider".into()),
+                schema_version: ROLLOUT_SCHEMA_VERSION,
             },
             git: None,
         }),
@@ -51,6 +51,12 @@ pub fn assess_patch_safety(
         };
     }
 
+    if sandbox_policy.is_audit() {
+        return SafetyCheck::Reject {
+            reason: "file edits are disabled in audit mode".to_string(),
+        };
+    }
+
     match policy {
         AskForApproval::OnFailure | AskForApproval::Never | AskForApproval::OnRequest => {
             // Continue to see if this can be auto-approved.
@@ -120,19 +126,28 @@ fn is_write_patch_constrained_to_writable_paths(
     cwd: &Path,
 ) -> bool {
     // Early‑exit if there are no declared writable roots.
-    let writable_roots = match sandbox_policy {
-        SandboxPolicy::ReadOnly => {
-            return false;
+    match sandbox_policy {
+        SandboxPolicy::ReadOnly | SandboxPolicy::Audit => false,
+        SandboxPolicy::DangerFullAccess => true,
+        SandboxPolicy::WorkspaceWrite { .. } => {
+            paths_outside_writable_roots(action, sandbox_policy, cwd).is_empty()
         }
-        SandboxPolicy::DangerFullAccess => {
-            return true;
-        }
-        SandboxPolicy::WorkspaceWrite { .. } => sandbox_policy.get_writable_roots_with_cwd(cwd),
-    };
+    }
+}
+
+/// Absolute, normalized paths the patch writes (including move
+/// destinations) that no writable root of `sandbox_policy` covers. Only
+/// meaningful for `workspace-write`; other policies have no writable roots.
+pub(crate) fn paths_outside_writable_roots(
+    action: &ApplyPatchAction,
+    sandbox_policy: &SandboxPolicy,
+    cwd: &Path,
+) -> Vec<PathBuf> {
+    let writable_roots = sandbox_policy.get_writable_roots_with_cwd(cwd);
 
     // Normalize a path by removing `.` and resolving `..` without touching the
     // filesystem (works even if the file does not exist).
-    fn normalize(path: &Path) -> Option<PathBuf> {
+    fn normalize(path: &Path) -> PathBuf {
         let mut out = PathBuf::new();
         for comp in path.components() {
             match comp {
@@ -143,49 +158,40 @@ fn is_write_patch_constrained_to_writable_paths(
                 other => out.push(other.as_os_str()),
             }
         }
-        Some(out)
+        out
     }
 
-    // Determine whether `path` is inside **any** writable root. Both `path`
-    // and roots are converted to absolute, normalized forms before the
-    // prefix check.
-    let is_path_writable = |p: &PathBuf| {
+    // Both `path` and roots are converted to absolute, normalized forms
+    // before the prefix check.
+    let mut outside = Vec::new();
+    let mut check = |p: &PathBuf| {
         let abs = if p.is_absolute() {
             p.clone()
         } else {
             cwd.join(p)
         };
-        let abs = match normalize(&abs) {
-            Some(v) => v,
-            None => return false,
-        };
-
-        writable_roots
+        let abs = normalize(&abs);
+        if !writable_roots
             .iter()
             .any(|writable_root| writable_root.is_path_writable(&abs))
+        {
+            outside.push(abs);
+        }
     };
 
     for (path, change) in action.changes() {
         match change {
-            ApplyPatchFileChange::Add { .. } | ApplyPatchFileChange::Delete { .. } => {
-                if !is_path_writable(path) {
-                    return false;
-                }
-            }
+            ApplyPatchFileChange::Add { .. } | ApplyPatchFileChange::Delete { .. } => check(path),
             ApplyPatchFileChange::Update { move_path, .. } => {
-                if !is_path_writable(path) {
-                    return false;
-                }
-                if let Some(dest) = move_path
-                    && !is_path_writable(dest)
-                {
-                    return false;
+                check(path);
+                if let Some(dest) = move_path {
+                    check(dest);
                 }
             }
         }
     }
 
-    true
+    outside
 }
 
 #[cfg(test)]
@@ -242,4 +248,23 @@ mod tests {
             &cwd,
         ));
     }
+
+    #[test]
+    fn audit_rejects_every_patch() {
+        let tmp = TempDir::new().unwrap();
+        let cwd = tmp.path().to_path_buf();
+        let add_inside = ApplyPatchAction::new_add_for_test(&cwd.join("inner.txt"), "".to_string());
+
+        assert_eq!(
+            assess_patch_safety(
+                &add_inside,
+                AskForApproval::OnRequest,
+                &SandboxPolicy::new_audit_policy(),
+                &cwd,
+            ),
+            SafetyCheck::Reject {
+                reason: "file edits are disabled in audit mode".to_string(),
+            }
+        );
+    }
 }
@@ -10,12 +10,19 @@ use crate::client::ModelClient;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::config::Config;
+use crate::error::Result;
+use crate::model_router::RoutedClient;
 use crate::protocol::SandboxPolicy;
+use crate::response_cache::ResponseCache;
+use crate::response_cache::ResponseCacheKey;
+use crate::tool_filter::ToolFilter;
 use askama::Template;
 use codex_otel::otel_event_manager::OtelEventManager;
 use codex_protocol::ConversationId;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ModelTask;
+use codex_protocol::protocol::ModelUsageItem;
 use codex_protocol::protocol::SandboxCommandAssessment;
 use codex_protocol::protocol::SessionSource;
 use futures::StreamExt;
@@ -50,7 +57,7 @@ pub(crate) async fn assess_command(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
     failure_message: Option<&str>,
-) -> Option<SandboxCommandAssessment> {
+) -> Option<(SandboxCommandAssessment, Option<ModelUsageItem>)> {
     if !config.experimental_sandbox_command_assessment || command.is_empty() {
         return None;
     }
@@ -120,6 +127,7 @@ pub(crate) async fn assess_command(
         parallel_tool_calls: false,
         base_instructions_override: Some(system_prompt),
         output_schema: Some(sandbox_assessment_schema()),
+        tool_filter: ToolFilter::default(),
     };
 
     let child_otel =
@@ -136,24 +144,15 @@ pub(crate) async fn assess_command(
         session_source,
     );
 
+    let mut routed = RoutedClient::new(&client, ModelTask::RiskAssessment);
     let start = Instant::now();
-    let assessment_result = timeout(SANDBOX_ASSESSMENT_TIMEOUT, async move {
-        let mut stream = client.stream(&prompt).await?;
-        let mut last_json: Option<String> = None;
-        while let Some(event) = stream.next().await {
-            match event {
-                Ok(ResponseEvent::OutputItemDone(item)) => {
-                    if let Some(text) = response_item_text(&item) {
-                        last_json = Some(text);
-                    }
-                }
-                Ok(ResponseEvent::RateLimits(_)) => {}
-                Ok(ResponseEvent::Completed { .. }) => break,
-                Ok(_) => continue,
-                Err(err) => return Err(err),
+    let assessment_result = timeout(SANDBOX_ASSESSMENT_TIMEOUT, async {
+        loop {
+            match request_assessment(routed.client(), &prompt).await {
+                Err(err) if routed.fall_back(&err) => continue,
+                result => return result,
             }
         }
-        Ok(last_json)
     })
     .await;
     let duration = start.elapsed();
@@ -168,7 +167,7 @@ pub(crate) async fn assess_command(
                     Some(assessment.risk_level),
                     duration,
                 );
-                return Some(assessment);
+                return Some((assessment, routed.usage()));
             }
             Err(err) => {
                 warn!("failed to parse sandbox assessment JSON: {err}");
@@ -192,10 +191,47 @@ pub(crate) async fn assess_command(
     None
 }
 
+async fn request_assessment(client: &ModelClient, prompt: &Prompt) -> Result<Option<String>> {
+    let config = client.config();
+    let cache = ResponseCache::from_config(&config).map(|cache| {
+        let key = ResponseCacheKey::for_prompt(&config.model, &config.model_family, prompt);
+        (cache, key)
+    });
+    if let Some((cache, key)) = &cache
+        && let Some(items) = cache.get(key).await
+    {
+        return Ok(items.iter().rev().find_map(response_item_text));
+    }
+    let mut stream = client.stream(prompt).await?;
+    let mut last_json: Option<String> = None;
+    let mut output_items = Vec::new();
+    while let Some(event) = stream.next().await {
+        match event {
+            Ok(ResponseEvent::OutputItemDone(item)) => {
+                if let Some(text) = response_item_text(&item) {
+                    last_json = Some(text);
+                }
+                output_items.push(item);
+            }
+            Ok(ResponseEvent::RateLimits(_)) => {}
+            Ok(ResponseEvent::Completed { .. }) => {
+                if let Some((cache, key)) = &cache {
+                    cache.put(key, &config.model, &output_items).await;
+                }
+                break;
+            }
+            Ok(_) => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(last_json)
+}
+
 fn summarize_sandbox_policy(policy: &SandboxPolicy) -> String {
     match policy {
         SandboxPolicy::DangerFullAccess => "danger-full-access".to_string(),
         SandboxPolicy::ReadOnly => "read-only".to_string(),
+        SandboxPolicy::Audit => "audit".to_string(),
         SandboxPolicy::WorkspaceWrite { network_access, .. } => {
             let network = if *network_access {
                 "network"
@@ -0,0 +1,206 @@
+//! Five-field cron expressions: `minute hour day-of-month month day-of-week`.
+//!
+//! Each field is `*` or a comma-separated list of values, `a-b` ranges, and
+//! `/step` suffixes (`*/15`, `1-5`, `0,30`). Day-of-week runs from 0 (Sunday)
+//! to 7 (Sunday again). As in classic cron, when both day fields are
+//! restricted a day matches if either one does.
+
+use chrono::DateTime;
+use chrono::Datelike;
+use chrono::Duration;
+use chrono::Local;
+use chrono::NaiveDate;
+use chrono::NaiveDateTime;
+use chrono::TimeZone;
+use chrono::Timelike;
+
+/// Give up looking for a match this far ahead (e.g. `0 0 30 2 *`).
+const MAX_LOOKAHEAD_DAYS: i64 = 366 * 5;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    day_of_month_restricted: bool,
+    day_of_week_restricted: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(format!(
+                "expected 5 fields (minute hour day-of-month month day-of-week), got {}",
+                fields.len()
+            ));
+        };
+        let mut days_of_week = parse_field(day_of_week, 0, 7, "day-of-week")?;
+        // Both 0 and 7 mean Sunday.
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week |= 1;
+        }
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, "minute")?,
+            hours: parse_field(hour, 0, 23, "hour")?,
+            days_of_month: parse_field(day_of_month, 1, 31, "day-of-month")?,
+            months: parse_field(month, 1, 12, "month")?,
+            days_of_week,
+            day_of_month_restricted: day_of_month != "*",
+            day_of_week_restricted: day_of_week != "*",
+        })
+    }
+
+    /// The first matching minute strictly after `after`.
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(MAX_LOOKAHEAD_DAYS);
+        let mut candidate = start;
+        while candidate < limit {
+            if !self.matches_day(candidate.date()) {
+                candidate = start_of_day(candidate.date() + Duration::days(1));
+                continue;
+            }
+            if !contains(self.hours, candidate.hour()) {
+                candidate = candidate.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !contains(self.minutes, candidate.minute()) {
+                candidate += Duration::minutes(1);
+                continue;
+            }
+            // Minutes skipped by a daylight-saving jump never happen locally.
+            match Local.from_local_datetime(&candidate).earliest() {
+                Some(time) => return Some(time),
+                None => candidate += Duration::minutes(1),
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        if !contains(self.months, date.month()) {
+            return false;
+        }
+        let day_of_month = contains(self.days_of_month, date.day());
+        let day_of_week = contains(self.days_of_week, date.weekday().num_days_from_sunday());
+        match (self.day_of_month_restricted, self.day_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        }
+    }
+}
+
+fn start_of_day(date: NaiveDate) -> NaiveDateTime {
+    date.and_time(chrono::NaiveTime::MIN)
+}
+
+fn contains(set: u64, value: u32) -> bool {
+    set & (1 << value) != 0
+}
+
+/// Parse one field into a bit set of the values it allows.
+fn parse_field(field: &str, min: u32, max: u32, name: &str) -> Result<u64, String> {
+    let mut set = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 =
+                    step.parse().ok().filter(|step| *step > 0).ok_or_else(|| {
+                        format!("invalid step `{step}` in {name} field `{field}`")
+                    })?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_value(start, min, max, name)?,
+                parse_value(end, min, max, name)?,
+            )
+        } else {
+            let value = parse_value(range, min, max, name)?;
+            // `5/10` means "from 5 every 10", like `5-max/10`.
+            (value, if step > 1 { max } else { value })
+        };
+        if start > end {
+            return Err(format!("range `{range}` in {name} field is reversed"));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+fn parse_value(value: &str, min: u32, max: u32, name: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|value| (min..=max).contains(value))
+        .ok_or_else(|| format!("{name} value `{value}` is not between {min} and {max}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn local(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .earliest()
+            .expect("valid local time")
+    }
+
+    fn next(expression: &str, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        CronSchedule::parse(expression)
+            .expect("valid expression")
+            .next_after(after)
+    }
+
+    #[test]
+    fn finds_the_next_matching_minute() {
+        // 2025-03-12 is a Wednesday.
+        let after = local(2025, 3, 12, 10, 7);
+        assert_eq!(
+            next("*/15 * * * *", after),
+            Some(local(2025, 3, 12, 10, 15))
+        );
+        assert_eq!(next("0 9 * * *", after), Some(local(2025, 3, 13, 9, 0)));
+        assert_eq!(next("30 8 * * 1-5", after), Some(local(2025, 3, 13, 8, 30)));
+        assert_eq!(next("0 0 1 * *", after), Some(local(2025, 4, 1, 0, 0)));
+        assert_eq!(next("0 12 * * 0", after), Some(local(2025, 3, 16, 12, 0)));
+        assert_eq!(next("0 12 * * 7", after), Some(local(2025, 3, 16, 12, 0)));
+        // Strictly after: a match at the current minute is skipped.
+        assert_eq!(next("7 10 * * *", after), Some(local(2025, 3, 13, 10, 7)));
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either_day() {
+        let after = local(2025, 3, 12, 10, 7);
+        // The 20th, or any Friday (the 14th comes first).
+        assert_eq!(next("0 0 20 * 5", after), Some(local(2025, 3, 14, 0, 0)));
+    }
+
+    #[test]
+    fn rejects_malformed_expressions() {
+        for expression in [
+            "* * * *",
+            "60 * * * *",
+            "* * * * mon",
+            "*/0 * * * *",
+            "5-1 * * * *",
+        ] {
+            assert!(
+                CronSchedule::parse(expression).is_err(),
+                "{expression} should not parse"
+            );
+        }
+        assert_eq!(next("0 0 30 2 *", local(2025, 1, 1, 0, 0)), None);
+    }
+}
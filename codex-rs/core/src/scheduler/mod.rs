@@ -0,0 +1,221 @@
+//! Recurring prompts configured under `[[schedules]]`.
+//!
+//! `codex schedule run` keeps a [`Scheduler`] alive. Whenever a schedule's
+//! cron expression comes due it starts a fresh conversation in the
+//! schedule's `cwd`, sends the fixed prompt, waits for the turn to finish,
+//! and reports the outcome through the configured notification sinks as a
+//! `scheduled-run-complete` notification. Runs are ordinary conversations:
+//! they honor budgets and are recorded as rollouts like any other session.
+//! Runs never prompt for approval, and a schedule whose previous run is still
+//! going is skipped rather than stacked.
+
+mod cron;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use chrono::DateTime;
+use chrono::Local;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use toml::Value as TomlValue;
+use tracing::info;
+use tracing::warn;
+
+pub use cron::CronSchedule;
+
+use crate::ConversationManager;
+use crate::NewConversation;
+use crate::config::Config;
+use crate::config::ConfigOverrides;
+use crate::config::types::ScheduleConfig;
+use crate::user_notification::UserNotification;
+use crate::user_notification::UserNotifier;
+
+#[derive(Debug, Clone)]
+struct Schedule {
+    config: ScheduleConfig,
+    cron: CronSchedule,
+}
+
+/// How a single scheduled run ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledRunOutcome {
+    pub thread_id: String,
+    pub last_assistant_message: Option<String>,
+    pub error: Option<String>,
+}
+
+pub struct Scheduler {
+    schedules: Vec<Schedule>,
+    conversation_manager: Arc<ConversationManager>,
+    cli_overrides: Vec<(String, TomlValue)>,
+    running: Mutex<HashSet<String>>,
+}
+
+impl Scheduler {
+    /// Validate the schedules in `config`. `cli_overrides` are re-applied
+    /// when each run loads its configuration.
+    pub fn new(
+        config: &Config,
+        cli_overrides: Vec<(String, TomlValue)>,
+        conversation_manager: Arc<ConversationManager>,
+    ) -> anyhow::Result<Self> {
+        let mut names = HashSet::new();
+        let mut schedules = Vec::new();
+        for schedule in &config.schedules {
+            if !names.insert(schedule.name.clone()) {
+                anyhow::bail!("schedule `{}` is defined more than once", schedule.name);
+            }
+            let cron = CronSchedule::parse(&schedule.cron).map_err(|err| {
+                anyhow::anyhow!("invalid cron for schedule `{}`: {err}", schedule.name)
+            })?;
+            schedules.push(Schedule {
+                config: schedule.clone(),
+                cron,
+            });
+        }
+        Ok(Self {
+            schedules,
+            conversation_manager,
+            cli_overrides,
+            running: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Each schedule's name and its next run from now.
+    pub fn upcoming(&self) -> Vec<(String, Option<DateTime<Local>>)> {
+        let after = Local::now();
+        self.schedules
+            .iter()
+            .map(|schedule| {
+                (
+                    schedule.config.name.clone(),
+                    schedule.cron.next_after(after),
+                )
+            })
+            .collect()
+    }
+
+    /// Run schedules as they come due. Returns only when no schedule will
+    /// ever be due again.
+    pub async fn run(self: Arc<Self>) {
+        let mut after = Local::now();
+        loop {
+            let Some(due) = self
+                .schedules
+                .iter()
+                .filter_map(|schedule| schedule.cron.next_after(after))
+                .min()
+            else {
+                info!("no scheduled runs are upcoming; scheduler exiting");
+                return;
+            };
+            if let Ok(wait) = (due - Local::now()).to_std() {
+                tokio::time::sleep(wait).await;
+            }
+            for schedule in &self.schedules {
+                if schedule.cron.next_after(after) == Some(due) {
+                    let scheduler = Arc::clone(&self);
+                    let schedule = schedule.config.clone();
+                    tokio::spawn(async move { scheduler.run_guarded(schedule).await });
+                }
+            }
+            after = due.max(Local::now());
+        }
+    }
+
+    async fn run_guarded(&self, schedule: ScheduleConfig) {
+        let newly_started = self
+            .running
+            .lock()
+            .is_ok_and(|mut running| running.insert(schedule.name.clone()));
+        if !newly_started {
+            warn!(
+                "skipping scheduled run of `{}`: the previous run is still going",
+                schedule.name
+            );
+            return;
+        }
+        if let Err(err) = self.run_now(&schedule).await {
+            warn!(
+                "scheduled run of `{}` failed to start: {err:#}",
+                schedule.name
+            );
+        }
+        if let Ok(mut running) = self.running.lock() {
+            running.remove(&schedule.name);
+        }
+    }
+
+    /// Run `schedule` once in a fresh conversation and notify the outcome.
+    pub async fn run_now(&self, schedule: &ScheduleConfig) -> anyhow::Result<ScheduledRunOutcome> {
+        info!("starting scheduled run of `{}`", schedule.name);
+        let overrides = ConfigOverrides {
+            cwd: schedule.cwd.clone(),
+            approval_policy: Some(AskForApproval::Never),
+            ..Default::default()
+        };
+        let config = Config::load_with_cli_overrides(self.cli_overrides.clone(), overrides).await?;
+        let notifier = UserNotifier::from_config(&config);
+        let cwd = config.cwd.display().to_string();
+        let NewConversation {
+            conversation_id,
+            conversation,
+            ..
+        } = self.conversation_manager.new_conversation(config).await?;
+
+        conversation
+            .submit(Op::UserInput {
+                items: vec![UserInput::Text {
+                    text: schedule.prompt.clone(),
+                }],
+            })
+            .await?;
+        let mut last_assistant_message = None;
+        let mut error = None;
+        loop {
+            match conversation.next_event().await?.msg {
+                EventMsg::TaskComplete(event) => {
+                    last_assistant_message = event.last_agent_message;
+                    break;
+                }
+                EventMsg::Error(event) => error = Some(event.message),
+                EventMsg::TurnAborted(event) => {
+                    error = Some(format!("turn aborted: {:?}", event.reason));
+                    break;
+                }
+                EventMsg::ShutdownComplete => break,
+                _ => {}
+            }
+        }
+
+        if let Err(err) = conversation.submit(Op::Shutdown).await {
+            warn!("failed to shut down scheduled conversation {conversation_id}: {err}");
+        }
+        self.conversation_manager
+            .remove_conversation(&conversation_id)
+            .await;
+
+        let outcome = ScheduledRunOutcome {
+            thread_id: conversation_id.to_string(),
+            last_assistant_message,
+            error,
+        };
+        notifier.notify(&UserNotification::ScheduledRunComplete {
+            schedule: schedule.name.clone(),
+            thread_id: outcome.thread_id.clone(),
+            cwd,
+            last_assistant_message: outcome.last_assistant_message.clone(),
+            error: outcome.error.clone(),
+        });
+        info!(
+            "scheduled run of `{}` finished in thread {}",
+            schedule.name, outcome.thread_id
+        );
+        Ok(outcome)
+    }
+}
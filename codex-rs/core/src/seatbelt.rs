@@ -28,7 +28,7 @@ pub async fn spawn_command_under_seatbelt(
     stdio_policy: StdioPolicy,
     mut env: HashMap<String, String>,
 ) -> std::io::Result<Child> {
-    let args = create_seatbelt_command_args(command, sandbox_policy, sandbox_policy_cwd);
+    let args = create_seatbelt_command_args(command, sandbox_policy, sandbox_policy_cwd, None);
     let arg0 = None;
     env.insert(CODEX_SANDBOX_ENV_VAR.to_string(), "seatbelt".to_string());
     spawn_child_async(
@@ -47,6 +47,7 @@ pub(crate) fn create_seatbelt_command_args(
     command: Vec<String>,
     sandbox_policy: &SandboxPolicy,
     sandbox_policy_cwd: &Path,
+    network_proxy_port: Option<u16>,
 ) -> Vec<String> {
     let (file_write_policy, file_write_dir_params) = {
         if sandbox_policy.has_full_disk_write_access() {
@@ -105,10 +106,14 @@ pub(crate) fn create_seatbelt_command_args(
     };
 
     // TODO(mbolin): apply_patch calls must also honor the SandboxPolicy.
-    let network_policy = if sandbox_policy.has_full_network_access() {
-        MACOS_SEATBELT_NETWORK_POLICY
-    } else {
-        ""
+    // With an egress proxy, the proxy is the only reachable destination
+    // whatever the policy says, so commands cannot bypass it.
+    let network_policy = match network_proxy_port {
+        Some(port) => format!("(allow network-outbound (remote ip \"localhost:{port}\"))"),
+        None if sandbox_policy.has_full_network_access() => {
+            MACOS_SEATBELT_NETWORK_POLICY.to_string()
+        }
+        None => String::new(),
     };
 
     let full_policy = format!(
@@ -156,6 +161,7 @@ fn macos_dir_params() -> Vec<(String, PathBuf)> {
 #[cfg(test)]
 mod tests {
     use super::MACOS_SEATBELT_BASE_POLICY;
+    use super::MACOS_SEATBELT_NETWORK_POLICY;
     use super::create_seatbelt_command_args;
     use super::macos_dir_params;
     use crate::protocol::SandboxPolicy;
@@ -192,6 +198,7 @@ mod tests {
             vec!["/bin/echo".to_string(), "hello".to_string()],
             &policy,
             &cwd,
+            None,
         );
 
         // Build the expected policy text using a raw string for readability.
@@ -268,6 +275,7 @@ mod tests {
             vec!["/bin/echo".to_string(), "hello".to_string()],
             &policy,
             root_with_git.as_path(),
+            None,
         );
 
         let tmpdir_env_var = std::env::var("TMPDIR")
@@ -336,6 +344,31 @@ mod tests {
         assert_eq!(expected_args, args);
     }
 
+    #[test]
+    fn create_seatbelt_args_limits_network_to_proxy_port() {
+        let tmp = TempDir::new().expect("tempdir");
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec![],
+            network_access: true,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        };
+
+        let args = create_seatbelt_command_args(
+            vec![
+                "/usr/bin/curl".to_string(),
+                "https://example.com".to_string(),
+            ],
+            &policy,
+            tmp.path(),
+            Some(8123),
+        );
+
+        let profile = &args[1];
+        assert!(profile.ends_with("(allow network-outbound (remote ip \"localhost:8123\"))"));
+        assert!(!profile.contains(MACOS_SEATBELT_NETWORK_POLICY));
+    }
+
     struct PopulatedTmp {
         root_with_git: PathBuf,
         root_without_git: PathBuf,
@@ -1,3 +1,4 @@
+use codex_utils_pty::TerminalSize;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::process::Stdio;
@@ -26,6 +27,12 @@ pub const CODEX_SANDBOX_ENV_VAR: &str = "CODEX_SANDBOX";
 pub enum StdioPolicy {
     RedirectForShellTool,
     Inherit,
+    /// Attach the child to a pseudo-terminal of the given size so commands
+    /// that detect a TTY behave as they would interactively. Output from
+    /// stdout and stderr is merged by the terminal. Processes using this
+    /// policy are spawned through `codex_utils_pty` rather than
+    /// [`spawn_child_async`].
+    Pty(TerminalSize),
 }
 
 /// Spawns the appropriate child process for the ExecParams and SandboxPolicy,
@@ -96,6 +103,12 @@ pub(crate) async fn spawn_child_async(
     }
 
     match stdio_policy {
+        StdioPolicy::Pty(_) => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "PTY stdio is not supported by spawn_child_async",
+            ));
+        }
         StdioPolicy::RedirectForShellTool => {
             // Do not create a file descriptor for stdin because otherwise some
             // commands may hang forever waiting for input. For example, ripgrep has
@@ -1,11 +1,27 @@
 use std::sync::Arc;
+use std::sync::RwLock as StdRwLock;
 
 use crate::AuthManager;
 use crate::RolloutRecorder;
+use crate::approval_rules::ApprovalRuleStore;
+use crate::background_tasks::BackgroundTaskManager;
+use crate::code_search::CodeSearchIndex;
+use crate::config::types::ExecOutputConfig;
+use crate::config::types::ReasoningRedaction;
+use crate::file_locks::FileLockManager;
+use crate::lsp::LspManager;
+use crate::mcp::sampling::SamplingModel;
 use crate::mcp_connection_manager::McpConnectionManager;
+use crate::network_proxy::NetworkProxy;
+use crate::plugins::PluginHost;
+use crate::provider_health::ProviderHealth;
+use crate::rate_limit_scheduler::RateLimitScheduler;
+use crate::repo_map::RepoMapCache;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_notification::UserNotifier;
+use crate::worktree::SessionWorktree;
+use crate::write_grants::WriteGrantStore;
 use codex_otel::otel_event_manager::OtelEventManager;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
@@ -19,7 +35,23 @@ pub(crate) struct SessionServices {
     pub(crate) rollout: Mutex<Option<RolloutRecorder>>,
     pub(crate) user_shell: crate::shell::Shell,
     pub(crate) show_raw_agent_reasoning: bool,
-    pub(crate) auth_manager: Arc<AuthManager>,
+    pub(crate) reasoning_redaction: ReasoningRedaction,
+    pub(crate) exec_output: ExecOutputConfig,
+    /// Replaced when a profile switch selects another stored account.
+    pub(crate) auth_manager: StdRwLock<Arc<AuthManager>>,
     pub(crate) otel_event_manager: OtelEventManager,
     pub(crate) tool_approvals: Mutex<ApprovalStore>,
+    pub(crate) approval_rules: Mutex<ApprovalRuleStore>,
+    pub(crate) write_grants: Mutex<WriteGrantStore>,
+    pub(crate) network_proxy: Option<NetworkProxy>,
+    pub(crate) file_locks: Arc<FileLockManager>,
+    pub(crate) provider_health: Mutex<ProviderHealth>,
+    pub(crate) rate_limit_scheduler: Arc<RateLimitScheduler>,
+    pub(crate) background_tasks: Arc<BackgroundTaskManager>,
+    pub(crate) worktree: Mutex<Option<SessionWorktree>>,
+    pub(crate) repo_map: RepoMapCache,
+    pub(crate) lsp: LspManager,
+    pub(crate) code_search: CodeSearchIndex,
+    pub(crate) plugins: Option<Arc<PluginHost>>,
+    pub(crate) sampling_model: SamplingModel,
 }
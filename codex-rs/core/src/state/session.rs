@@ -1,19 +1,53 @@
 //! Session-wide mutable state.
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
 use codex_protocol::models::ResponseItem;
+use codex_utils_pty::TerminalSize;
 
 use crate::codex::SessionConfiguration;
 use crate::context_manager::ContextManager;
+use crate::protocol::CostEstimate;
 use crate::protocol::RateLimitSnapshot;
+use crate::protocol::RateLimitWindow;
 use crate::protocol::TokenUsage;
+use crate::protocol::TokenUsageBreakdown;
 use crate::protocol::TokenUsageInfo;
+use crate::redaction::SecretRedactor;
 use crate::truncate::TruncationPolicy;
 
+/// Rate-limit usage percentages that trigger a `rate-limit-warning` user
+/// notification, matching the warnings the TUI shows.
+const RATE_LIMIT_WARNING_THRESHOLDS: [f64; 3] = [75.0, 90.0, 95.0];
+
 /// Persistent, session-scoped state previously stored directly on `Session`.
 pub(crate) struct SessionState {
     pub(crate) session_configuration: SessionConfiguration,
     pub(crate) history: ContextManager,
     pub(crate) latest_rate_limits: Option<RateLimitSnapshot>,
+    /// Running cost estimate; `None` until a priced model reports usage.
+    pub(crate) cost_estimate: Option<CostEstimate>,
+    /// Token usage attributed to what each model request produced.
+    pub(crate) usage_breakdown: TokenUsageBreakdown,
+    /// Window size last reported by the client, used for PTY-backed commands.
+    pub(crate) terminal_size: Option<TerminalSize>,
+    /// Placeholders handed out so far by the `secret_redaction` feature.
+    pub(crate) secret_redactor: SecretRedactor,
+    /// Modification time of each file as Codex last read or wrote it, used to
+    /// spot edits made by someone else in the meantime.
+    seen_file_mtimes: HashMap<PathBuf, SystemTime>,
+    /// Warning thresholds already crossed by the primary and secondary
+    /// rate-limit windows.
+    rate_limit_thresholds_crossed: [usize; 2],
+    /// Whether this session already tried to title the thread with the
+    /// `thread_titles` feature.
+    pub(crate) title_requested: bool,
+    /// Models the provider rejected as unavailable, skipped by later turns.
+    pub(crate) unavailable_models: HashSet<String>,
 }
 
 impl SessionState {
@@ -24,6 +58,14 @@ impl SessionState {
             session_configuration,
             history,
             latest_rate_limits: None,
+            cost_estimate: None,
+            usage_breakdown: TokenUsageBreakdown::default(),
+            terminal_size: None,
+            secret_redactor: SecretRedactor::default(),
+            seen_file_mtimes: HashMap::new(),
+            rate_limit_thresholds_crossed: [0; 2],
+            title_requested: false,
+            unavailable_models: HashSet::new(),
         }
     }
 
@@ -61,8 +103,44 @@ impl SessionState {
         self.history.token_info()
     }
 
-    pub(crate) fn set_rate_limits(&mut self, snapshot: RateLimitSnapshot) {
+    /// Store `snapshot` and return the windows (labelled `primary` or
+    /// `secondary`) whose usage crossed a new warning threshold since the
+    /// previous snapshot.
+    pub(crate) fn set_rate_limits(
+        &mut self,
+        snapshot: RateLimitSnapshot,
+    ) -> Vec<(&'static str, RateLimitWindow)> {
+        let mut warnings = Vec::new();
+        let windows = [
+            ("primary", snapshot.primary.as_ref()),
+            ("secondary", snapshot.secondary.as_ref()),
+        ];
+        for ((label, window), crossed) in windows
+            .into_iter()
+            .zip(self.rate_limit_thresholds_crossed.iter_mut())
+        {
+            let Some(window) = window else {
+                continue;
+            };
+            let now_crossed = RATE_LIMIT_WARNING_THRESHOLDS
+                .iter()
+                .filter(|threshold| window.used_percent >= **threshold)
+                .count();
+            if now_crossed > *crossed {
+                warnings.push((label, window.clone()));
+            }
+            // Usage drops again once the window resets; warn again next time.
+            *crossed = now_crossed;
+        }
         self.latest_rate_limits = Some(snapshot);
+        warnings
+    }
+
+    /// Forgets the rate-limit snapshot after switching to another account,
+    /// whose windows it does not describe.
+    pub(crate) fn clear_rate_limits(&mut self) {
+        self.latest_rate_limits = None;
+        self.rate_limit_thresholds_crossed = [0; 2];
     }
 
     pub(crate) fn token_info_and_rate_limits(
@@ -71,7 +149,24 @@ impl SessionState {
         (self.token_info(), self.latest_rate_limits.clone())
     }
 
+    pub(crate) fn add_cost(&mut self, last_usd: f64) {
+        let total_usd = self.cost_estimate.map_or(0.0, |cost| cost.total_usd) + last_usd;
+        self.cost_estimate = Some(CostEstimate {
+            total_usd,
+            last_usd,
+        });
+    }
+
     pub(crate) fn set_token_usage_full(&mut self, context_window: i64) {
         self.history.set_token_usage_full(context_window);
     }
+
+    // File freshness helpers
+    pub(crate) fn record_file_mtime(&mut self, path: PathBuf, mtime: SystemTime) {
+        self.seen_file_mtimes.insert(path, mtime);
+    }
+
+    pub(crate) fn seen_file_mtime(&self, path: &Path) -> Option<SystemTime> {
+        self.seen_file_mtimes.get(path).copied()
+    }
 }
@@ -2,6 +2,8 @@
 
 use indexmap::IndexMap;
 use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::sync::Notify;
@@ -12,7 +14,9 @@ use codex_protocol::models::ResponseInputItem;
 use tokio::sync::oneshot;
 
 use crate::codex::TurnContext;
+use crate::protocol::FileChange;
 use crate::protocol::ReviewDecision;
+use crate::protocol::WriteScopeDecision;
 use crate::tasks::SessionTask;
 
 /// Metadata about the currently running turn.
@@ -67,7 +71,13 @@ impl ActiveTurn {
 #[derive(Default)]
 pub(crate) struct TurnState {
     pending_approvals: HashMap<String, oneshot::Sender<ReviewDecision>>,
+    pending_write_scopes: HashMap<String, oneshot::Sender<WriteScopeDecision>>,
+    /// Questions from the `ask_user` tool awaiting an answer, keyed by call id.
+    pending_user_inputs: HashMap<String, oneshot::Sender<Option<String>>>,
     pending_input: Vec<ResponseInputItem>,
+    /// Full change sets for patch approvals that were sent as summaries,
+    /// keyed by call id.
+    deferred_patches: HashMap<String, HashMap<PathBuf, FileChange>>,
 }
 
 impl TurnState {
@@ -86,9 +96,57 @@ impl TurnState {
         self.pending_approvals.remove(key)
     }
 
+    pub(crate) fn insert_pending_write_scope(
+        &mut self,
+        key: String,
+        tx: oneshot::Sender<WriteScopeDecision>,
+    ) -> Option<oneshot::Sender<WriteScopeDecision>> {
+        self.pending_write_scopes.insert(key, tx)
+    }
+
+    pub(crate) fn remove_pending_write_scope(
+        &mut self,
+        key: &str,
+    ) -> Option<oneshot::Sender<WriteScopeDecision>> {
+        self.pending_write_scopes.remove(key)
+    }
+
+    pub(crate) fn insert_pending_user_input(
+        &mut self,
+        call_id: String,
+        tx: oneshot::Sender<Option<String>>,
+    ) -> Option<oneshot::Sender<Option<String>>> {
+        self.pending_user_inputs.insert(call_id, tx)
+    }
+
+    pub(crate) fn remove_pending_user_input(
+        &mut self,
+        call_id: &str,
+    ) -> Option<oneshot::Sender<Option<String>>> {
+        self.pending_user_inputs.remove(call_id)
+    }
+
     pub(crate) fn clear_pending(&mut self) {
         self.pending_approvals.clear();
+        self.pending_write_scopes.clear();
+        self.pending_user_inputs.clear();
         self.pending_input.clear();
+        self.deferred_patches.clear();
+    }
+
+    pub(crate) fn insert_deferred_patch(
+        &mut self,
+        call_id: String,
+        changes: HashMap<PathBuf, FileChange>,
+    ) {
+        self.deferred_patches.insert(call_id, changes);
+    }
+
+    pub(crate) fn deferred_patch_change(&self, call_id: &str, path: &Path) -> Option<FileChange> {
+        self.deferred_patches
+            .get(call_id)
+            .and_then(|changes| changes.get(path))
+            .cloned()
     }
 
     pub(crate) fn push_pending_input(&mut self, input: ResponseInputItem) {
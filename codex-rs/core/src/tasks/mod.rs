@@ -11,6 +11,8 @@ use std::time::Duration;
 use async_trait::async_trait;
 use tokio::select;
 use tokio::sync::Notify;
+use tokio::time::Instant;
+use tokio::time::MissedTickBehavior;
 use tokio_util::sync::CancellationToken;
 use tokio_util::task::AbortOnDropHandle;
 use tracing::trace;
@@ -53,7 +55,7 @@ impl SessionTaskContext {
     }
 
     pub(crate) fn auth_manager(&self) -> Arc<AuthManager> {
-        Arc::clone(&self.session.services.auth_manager)
+        self.session.auth_manager()
     }
 }
 
@@ -120,19 +122,26 @@ impl Session {
             let task_cancellation_token = cancellation_token.child_token();
             tokio::spawn(async move {
                 let ctx_for_finish = Arc::clone(&ctx);
-                let last_agent_message = task_for_run
-                    .run(
-                        Arc::clone(&session_ctx),
-                        ctx,
-                        input,
-                        task_cancellation_token.child_token(),
-                    )
-                    .await;
+                let run = task_for_run.run(
+                    Arc::clone(&session_ctx),
+                    ctx,
+                    input,
+                    task_cancellation_token.child_token(),
+                );
+                let last_agent_message =
+                    with_heartbeat(&session_ctx.clone_session(), &ctx_for_finish, run).await;
                 session_ctx.clone_session().flush_rollout().await;
                 if !task_cancellation_token.is_cancelled() {
                     // Emit completion uniformly from spawn site so all tasks share the same lifecycle.
                     let sess = session_ctx.clone_session();
-                    sess.on_task_finished(ctx_for_finish, last_agent_message)
+                    if task_kind == TaskKind::Regular {
+                        sess.record_experiment_outcome(
+                            ctx_for_finish.as_ref(),
+                            last_agent_message.is_some(),
+                        )
+                        .await;
+                    }
+                    sess.on_task_finished(ctx_for_finish, task_kind, last_agent_message)
                         .await;
                 }
                 done_clone.notify_waiters();
@@ -159,6 +168,7 @@ impl Session {
     pub async fn on_task_finished(
         self: &Arc<Self>,
         turn_context: Arc<TurnContext>,
+        task_kind: TaskKind,
         last_agent_message: Option<String>,
     ) {
         let mut active = self.active_turn.lock().await;
@@ -168,8 +178,29 @@ impl Session {
             *active = None;
         }
         drop(active);
-        let event = EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message });
+        let (turn_commit, diff_summary) = if task_kind == TaskKind::Regular {
+            let diff_summary = self.turn_diff_summary(turn_context.as_ref()).await;
+            let turn_commit = self
+                .commit_turn_changes(turn_context.as_ref(), last_agent_message.as_deref())
+                .await;
+            (turn_commit, diff_summary)
+        } else {
+            (None, None)
+        };
+        let event = EventMsg::TaskComplete(TaskCompleteEvent {
+            last_agent_message: last_agent_message.clone(),
+            turn_commit,
+            diff_summary,
+        });
         self.send_event(turn_context.as_ref(), event).await;
+        self.update_history_index(turn_context.as_ref()).await;
+        if task_kind == TaskKind::Regular {
+            let sess = Arc::clone(self);
+            tokio::spawn(async move {
+                sess.title_thread(turn_context.as_ref(), last_agent_message.as_deref())
+                    .await;
+            });
+        }
     }
 
     async fn register_new_active_task(&self, task: RunningTask) {
@@ -216,10 +247,40 @@ impl Session {
             .abort(session_ctx, Arc::clone(&task.turn_context))
             .await;
 
+        if task.kind == TaskKind::Regular {
+            self.record_experiment_outcome(task.turn_context.as_ref(), false)
+                .await;
+        }
+
         let event = EventMsg::TurnAborted(TurnAbortedEvent { reason });
         self.send_event(task.turn_context.as_ref(), event).await;
     }
 }
 
+/// Drive `run` to completion, emitting a `TurnHeartbeat` every
+/// `turn_heartbeat_interval` while it is still going.
+async fn with_heartbeat<F>(session: &Session, turn_context: &TurnContext, run: F) -> F::Output
+where
+    F: Future,
+{
+    let Some(period) = turn_context.client.config().turn_heartbeat_interval else {
+        return run.await;
+    };
+    let start = Instant::now();
+    let mut ticks = tokio::time::interval_at(start + period, period);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    tokio::pin!(run);
+    loop {
+        select! {
+            output = &mut run => return output,
+            _ = ticks.tick() => {
+                session
+                    .send_turn_heartbeat(turn_context, start.elapsed())
+                    .await;
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {}
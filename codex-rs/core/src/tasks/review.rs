@@ -11,6 +11,7 @@ use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::ExitedReviewModeEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
 use codex_protocol::protocol::ReviewOutputEvent;
+use codex_protocol::protocol::SubAgentSource;
 use tokio_util::sync::CancellationToken;
 
 use crate::codex::Session;
@@ -92,6 +93,8 @@ async fn start_review_conversation(
 ) -> Option<async_channel::Receiver<Event>> {
     let config = ctx.client.config();
     let mut sub_agent_config = config.as_ref().clone();
+    // Review the session's current checkout (its worktree, if isolated).
+    sub_agent_config.cwd = ctx.cwd.clone();
     // Run with only reviewer rubric — drop outer user_instructions
     sub_agent_config.user_instructions = None;
     // Avoid loading project docs; reviewer only needs findings
@@ -113,10 +116,11 @@ async fn start_review_conversation(
         ctx.clone(),
         cancellation_token,
         None,
+        SubAgentSource::Review,
     )
     .await)
         .ok()
-        .map(|io| io.rx_event)
+        .map(|io| io.codex.rx_event)
 }
 
 async fn process_review_events(
@@ -7,6 +7,7 @@ use crate::protocol::UndoStartedEvent;
 use crate::state::TaskKind;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
+use crate::user_notification::UserNotification;
 use async_trait::async_trait;
 use codex_git::restore_ghost_commit;
 use codex_protocol::models::ResponseItem;
@@ -97,6 +98,12 @@ impl SessionTask for UndoTask {
                 info!(commit_id = commit_id, "Undo restored ghost snapshot");
                 completed.success = true;
                 completed.message = Some(format!("Undo restored snapshot {short_id}."));
+                sess.notifier().notify(&UserNotification::SnapshotRestored {
+                    thread_id: sess.conversation_id().to_string(),
+                    turn_id: ctx.sub_id.clone(),
+                    cwd: ctx.cwd.display().to_string(),
+                    commit_id: commit_id.clone(),
+                });
             }
             Ok(Err(err)) => {
                 let message = format!("Failed to restore snapshot {commit_id}: {err}");
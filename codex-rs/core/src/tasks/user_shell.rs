@@ -24,6 +24,7 @@ use crate::protocol::ExecCommandSource;
 use crate::protocol::SandboxPolicy;
 use crate::protocol::TaskStartedEvent;
 use crate::sandboxing::ExecEnv;
+use crate::spawn::StdioPolicy;
 use crate::state::TaskKind;
 use crate::tools::format_exec_output_str;
 use crate::user_shell_command::user_shell_command_record_item;
@@ -75,9 +76,9 @@ impl SessionTask for UserShellCommandTask {
 
         let parsed_cmd = parse_command(&command);
         session
-            .send_event(
+            .send_exec_command_begin(
                 turn_context.as_ref(),
-                EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+                ExecCommandBeginEvent {
                     call_id: call_id.clone(),
                     turn_id: turn_context.sub_id.clone(),
                     command: command.clone(),
@@ -85,7 +86,7 @@ impl SessionTask for UserShellCommandTask {
                     parsed_cmd: parsed_cmd.clone(),
                     source: ExecCommandSource::UserShell,
                     interaction_input: None,
-                }),
+                },
             )
             .await;
 
@@ -98,12 +99,15 @@ impl SessionTask for UserShellCommandTask {
             with_escalated_permissions: None,
             justification: None,
             arg0: None,
+            stdio_policy: StdioPolicy::RedirectForShellTool,
         };
 
         let stdout_stream = Some(StdoutStream {
             sub_id: turn_context.sub_id.clone(),
             call_id: call_id.clone(),
             tx_event: session.get_tx_event(),
+            policy: session.services.exec_output,
+            archive_dir: None,
         });
 
         let sandbox_policy = SandboxPolicy::DangerFullAccess;
@@ -121,6 +125,7 @@ impl SessionTask for UserShellCommandTask {
                     aggregated_output: StreamOutput::new(aborted_message.clone()),
                     duration: Duration::ZERO,
                     timed_out: false,
+                    output_truncation: None,
                 };
                 let output_items = [user_shell_command_record_item(
                     &raw_command,
@@ -131,9 +136,9 @@ impl SessionTask for UserShellCommandTask {
                     .record_conversation_items(turn_context.as_ref(), &output_items)
                     .await;
                 session
-                    .send_event(
+                    .send_exec_command_end(
                         turn_context.as_ref(),
-                        EventMsg::ExecCommandEnd(ExecCommandEndEvent {
+                        ExecCommandEndEvent {
                             call_id,
                             turn_id: turn_context.sub_id.clone(),
                             command: command.clone(),
@@ -147,15 +152,16 @@ impl SessionTask for UserShellCommandTask {
                             exit_code: -1,
                             duration: Duration::ZERO,
                             formatted_output: aborted_message,
-                        }),
+                        },
+                        None,
                     )
                     .await;
             }
             Ok(Ok(output)) => {
                 session
-                    .send_event(
+                    .send_exec_command_end(
                         turn_context.as_ref(),
-                        EventMsg::ExecCommandEnd(ExecCommandEndEvent {
+                        ExecCommandEndEvent {
                             call_id: call_id.clone(),
                             turn_id: turn_context.sub_id.clone(),
                             command: command.clone(),
@@ -172,7 +178,8 @@ impl SessionTask for UserShellCommandTask {
                                 &output,
                                 turn_context.truncation_policy,
                             ),
-                        }),
+                        },
+                        output.output_truncation,
                     )
                     .await;
 
@@ -195,11 +202,12 @@ impl SessionTask for UserShellCommandTask {
                     aggregated_output: StreamOutput::new(message.clone()),
                     duration: Duration::ZERO,
                     timed_out: false,
+                    output_truncation: None,
                 };
                 session
-                    .send_event(
+                    .send_exec_command_end(
                         turn_context.as_ref(),
-                        EventMsg::ExecCommandEnd(ExecCommandEndEvent {
+                        ExecCommandEndEvent {
                             call_id,
                             turn_id: turn_context.sub_id.clone(),
                             command,
@@ -216,7 +224,8 @@ impl SessionTask for UserShellCommandTask {
                                 &exec_output,
                                 turn_context.truncation_policy,
                             ),
-                        }),
+                        },
+                        None,
                     )
                     .await;
                 let output_items = [user_shell_command_record_item(
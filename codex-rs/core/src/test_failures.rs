@@ -0,0 +1,503 @@
+//! Summaries of failed test runs for the model.
+//!
+//! When a command that runs `cargo test`, `pytest`, or `jest` fails, the
+//! interesting part of its output (which tests failed, where, and the first
+//! assertion message) is often buried in thousands of lines or cut away by
+//! truncation. The output is parsed in full and a short, structured summary
+//! is attached to the tool result, so the model does not have to re-run the
+//! suite or grep the output to find out what broke.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+/// At most this many failures are listed; the rest are only counted.
+const MAX_FAILURES: usize = 20;
+
+/// Lines of assertion message kept per failure.
+const MAX_MESSAGE_LINES: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TestRunner {
+    Cargo,
+    Pytest,
+    Jest,
+}
+
+impl TestRunner {
+    fn name(self) -> &'static str {
+        match self {
+            TestRunner::Cargo => "cargo test",
+            TestRunner::Pytest => "pytest",
+            TestRunner::Jest => "jest",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct TestFailure {
+    pub(crate) name: String,
+    /// `file:line` (and column when the runner reports one).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) location: Option<String>,
+    /// The first assertion message or panic, including diffs such as
+    /// `left`/`right` or `Expected`/`Received`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) message: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct TestFailureSummary {
+    pub(crate) runner: &'static str,
+    /// Number of failed tests, including those not listed in `failures`.
+    pub(crate) failed: usize,
+    pub(crate) failures: Vec<TestFailure>,
+}
+
+impl TestFailureSummary {
+    /// Plain-text rendering for freeform tool output.
+    pub(crate) fn render(&self) -> String {
+        let mut lines = vec![format!(
+            "Test failures ({}, {} failed):",
+            self.runner, self.failed
+        )];
+        for failure in &self.failures {
+            let mut line = format!("- {}", failure.name);
+            if let Some(location) = &failure.location {
+                line.push_str(&format!(" ({location})"));
+            }
+            lines.push(line);
+            if let Some(message) = &failure.message {
+                lines.extend(message.lines().map(|line| format!("    {line}")));
+            }
+        }
+        let unlisted = self.failed.saturating_sub(self.failures.len());
+        if unlisted > 0 {
+            lines.push(format!("- ... and {unlisted} more"));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Summarize the failures in `output` when `command` runs a supported test
+/// runner. `None` for other commands or when no failure could be parsed.
+pub(crate) fn summarize_test_failures(
+    command: &[String],
+    output: &str,
+) -> Option<TestFailureSummary> {
+    let runner = detect_runner(command, output)?;
+    let mut failures = match runner {
+        TestRunner::Cargo => parse_cargo(output),
+        TestRunner::Pytest => parse_pytest(output),
+        TestRunner::Jest => parse_jest(output),
+    };
+    // Jest repeats failures in its closing summary.
+    let mut seen = HashSet::new();
+    failures.retain(|failure| seen.insert(failure.name.clone()));
+    if failures.is_empty() {
+        return None;
+    }
+    let failed = failures.len();
+    failures.truncate(MAX_FAILURES);
+    Some(TestFailureSummary {
+        runner: runner.name(),
+        failed,
+        failures,
+    })
+}
+
+/// Detect the runner from the command's words, so `bash -lc "cargo test"`
+/// is recognized too. Package-manager scripts (`npm test`, ...) could run
+/// anything, so for them the output decides.
+fn detect_runner(command: &[String], output: &str) -> Option<TestRunner> {
+    let words = command
+        .iter()
+        .flat_map(|arg| arg.split(|c: char| c.is_whitespace() || c == ';' || c == '&'))
+        .map(|word| word.rsplit('/').next().unwrap_or(word))
+        .collect::<Vec<_>>();
+    let follows = |first: &str, second: &[&str]| {
+        words
+            .windows(2)
+            .any(|pair| pair[0] == first && second.contains(&pair[1]))
+    };
+
+    if words
+        .windows(2)
+        .any(|pair| pair[0] == "cargo" && pair[1] == "test")
+        || words
+            .windows(3)
+            .any(|w| w[0] == "cargo" && w[1].starts_with('+') && w[2] == "test")
+    {
+        return Some(TestRunner::Cargo);
+    }
+    if words
+        .iter()
+        .any(|word| matches!(*word, "pytest" | "py.test"))
+    {
+        return Some(TestRunner::Pytest);
+    }
+    if words.contains(&"jest") {
+        return Some(TestRunner::Jest);
+    }
+    let script = ["npm", "yarn", "pnpm", "bun"]
+        .iter()
+        .any(|manager| follows(manager, &["test", "run"]));
+    if script {
+        if output.contains("Test Suites:") {
+            return Some(TestRunner::Jest);
+        }
+        if output.contains("short test summary info") {
+            return Some(TestRunner::Pytest);
+        }
+    }
+    None
+}
+
+fn parse_cargo(output: &str) -> Vec<TestFailure> {
+    let lines = output.lines().collect::<Vec<_>>();
+    let mut failures = Vec::new();
+
+    // Each failed test's output is printed as `---- name stdout ----`.
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(name) = lines[i]
+            .strip_prefix("---- ")
+            .and_then(|rest| rest.strip_suffix(" stdout ----"))
+        else {
+            i += 1;
+            continue;
+        };
+        let start = i + 1;
+        let mut end = start;
+        while end < lines.len()
+            && !lines[end].starts_with("---- ")
+            && lines[end].trim_end() != "failures:"
+        {
+            end += 1;
+        }
+        let (location, message) = cargo_panic(&lines[start..end]);
+        failures.push(TestFailure {
+            name: name.to_string(),
+            location,
+            message,
+        });
+        i = end;
+    }
+    if !failures.is_empty() {
+        return failures;
+    }
+
+    // Without captured output (e.g. `--nocapture`), fall back to the result
+    // lines.
+    lines
+        .iter()
+        .filter_map(|line| {
+            line.strip_prefix("test ")?
+                .strip_suffix(" ... FAILED")
+                .map(|name| TestFailure {
+                    name: name.to_string(),
+                    location: None,
+                    message: None,
+                })
+        })
+        .collect()
+}
+
+/// Location and message of the first panic in a cargo test's output, in
+/// either the current (`panicked at file:line:col:` followed by the message)
+/// or the older (`panicked at 'message', file:line:col`) format.
+fn cargo_panic(lines: &[&str]) -> (Option<String>, Option<String>) {
+    for (index, line) in lines.iter().enumerate() {
+        let Some((_, rest)) = line.split_once("panicked at ") else {
+            continue;
+        };
+        if let Some(location) = rest.strip_suffix(':') {
+            let message = collect_message(lines[index + 1..].iter().copied().take_while(|line| {
+                !line.starts_with("note: ") && !line.starts_with("stack backtrace:")
+            }));
+            return (Some(location.to_string()), message);
+        }
+        if let Some((message, location)) = rest.rsplit_once("', ") {
+            let message = message.trim_start_matches('\'');
+            return (
+                Some(location.to_string()),
+                Some(message.to_string()).filter(|message| !message.is_empty()),
+            );
+        }
+    }
+    (None, None)
+}
+
+fn parse_pytest(output: &str) -> Vec<TestFailure> {
+    let lines = output.lines().collect::<Vec<_>>();
+    lines
+        .iter()
+        .filter_map(|line| {
+            let rest = line
+                .strip_prefix("FAILED ")
+                .or_else(|| line.strip_prefix("ERROR "))?;
+            let (name, summary) = match rest.split_once(" - ") {
+                Some((name, summary)) => (name.trim(), Some(summary.trim())),
+                None => (rest.trim(), None),
+            };
+            let (location, details) = pytest_details(&lines, name);
+            let message = details.or_else(|| summary.map(str::to_string));
+            Some(TestFailure {
+                name: name.to_string(),
+                location,
+                message,
+            })
+        })
+        .collect()
+}
+
+/// Location and `E` lines of a failure from its section in the pytest
+/// report, headed `____ test_name ____`.
+fn pytest_details(lines: &[&str], name: &str) -> (Option<String>, Option<String>) {
+    let file = name.split("::").next().unwrap_or(name);
+    let function = name.rsplit("::").next().unwrap_or(name);
+    let header = |line: &str| {
+        line.starts_with('_')
+            && line
+                .trim_matches(|c| c == '_' || c == ' ')
+                .rsplit('.')
+                .next()
+                == Some(function)
+    };
+    let Some(start) = lines.iter().position(|line| header(line)) else {
+        return (None, None);
+    };
+    let section = lines[start + 1..]
+        .iter()
+        .take_while(|line| !line.starts_with('_') && !line.starts_with('='))
+        .copied()
+        .collect::<Vec<_>>();
+
+    let location = section.iter().rev().find_map(|line| {
+        let rest = line.strip_prefix(file)?.strip_prefix(':')?;
+        let (number, _) = rest.split_once(':')?;
+        number
+            .chars()
+            .all(|c| c.is_ascii_digit())
+            .then(|| format!("{file}:{number}"))
+    });
+    let message = collect_message(
+        section
+            .iter()
+            .filter_map(|line| line.strip_prefix("E "))
+            .map(str::trim_end),
+    );
+    (location, message)
+}
+
+fn parse_jest(output: &str) -> Vec<TestFailure> {
+    let lines = output.lines().collect::<Vec<_>>();
+    let mut failures = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let Some(name) = lines[i].trim_start().strip_prefix("● ") else {
+            i += 1;
+            continue;
+        };
+        let start = i + 1;
+        let mut end = start;
+        while end < lines.len()
+            && !lines[end].trim_start().starts_with("● ")
+            && !lines[end].starts_with("Test Suites:")
+        {
+            end += 1;
+        }
+        let block = &lines[start..end];
+        let location = block.iter().find_map(|line| {
+            let frame = line.trim_start().strip_prefix("at ")?;
+            let frame = frame
+                .rsplit_once('(')
+                .map_or(frame, |(_, inner)| inner.trim_end_matches(')'));
+            (!frame.contains("node_modules")).then(|| frame.to_string())
+        });
+        // The message ends where the code frame or the stack starts.
+        let message = collect_message(block.iter().map(|line| line.trim()).take_while(|line| {
+            !line.starts_with("at ")
+                && !line.starts_with('>')
+                && !line.starts_with('|')
+                && !line.split('|').next().is_some_and(|prefix| {
+                    !prefix.trim().is_empty() && prefix.trim().chars().all(|c| c.is_ascii_digit())
+                })
+        }));
+        failures.push(TestFailure {
+            name: name.trim().to_string(),
+            location,
+            message,
+        });
+        i = end;
+    }
+    failures
+}
+
+/// Join the non-empty lines of a message, keeping at most
+/// `MAX_MESSAGE_LINES` and removing their common indentation.
+fn collect_message<'a>(lines: impl Iterator<Item = &'a str>) -> Option<String> {
+    let lines = lines
+        .map(str::trim_end)
+        .filter(|line| !line.trim().is_empty())
+        .take(MAX_MESSAGE_LINES)
+        .collect::<Vec<_>>();
+    let indent = lines
+        .iter()
+        .map(|line| line.len() - line.trim_start().len())
+        .min()?;
+    Some(
+        lines
+            .iter()
+            .map(|line| &line[indent..])
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn command(script: &str) -> Vec<String> {
+        vec!["bash".to_string(), "-lc".to_string(), script.to_string()]
+    }
+
+    #[test]
+    fn summarizes_cargo_test_panics() {
+        let output = "\
+running 2 tests
+test tests::adds ... FAILED
+test tests::subtracts ... ok
+
+failures:
+
+---- tests::adds stdout ----
+
+thread 'tests::adds' panicked at src/lib.rs:12:9:
+assertion `left == right` failed
+  left: 3
+ right: 4
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+
+
+failures:
+    tests::adds
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored
+";
+        assert_eq!(
+            summarize_test_failures(&command("cd core && cargo test -p codex-core"), output),
+            Some(TestFailureSummary {
+                runner: "cargo test",
+                failed: 1,
+                failures: vec![TestFailure {
+                    name: "tests::adds".to_string(),
+                    location: Some("src/lib.rs:12:9".to_string()),
+                    message: Some(
+                        "assertion `left == right` failed\n  left: 3\n right: 4".to_string()
+                    ),
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn summarizes_pytest_failures() {
+        let output = "\
+=================================== FAILURES ===================================
+_________________________________ test_totals __________________________________
+
+    def test_totals():
+>       assert total([1, 2]) == 4
+E       assert 3 == 4
+E        +  where 3 = total([1, 2])
+
+tests/test_cart.py:7: AssertionError
+=========================== short test summary info ============================
+FAILED tests/test_cart.py::test_totals - assert 3 == 4
+========================= 1 failed, 3 passed in 0.05s ==========================
+";
+        assert_eq!(
+            summarize_test_failures(&["pytest".to_string(), "-q".to_string()], output),
+            Some(TestFailureSummary {
+                runner: "pytest",
+                failed: 1,
+                failures: vec![TestFailure {
+                    name: "tests/test_cart.py::test_totals".to_string(),
+                    location: Some("tests/test_cart.py:7".to_string()),
+                    message: Some("assert 3 == 4\n +  where 3 = total([1, 2])".to_string()),
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn summarizes_jest_failures_from_npm_test() {
+        let output = "\
+FAIL src/sum.test.js
+  ● sum › adds two numbers
+
+    expect(received).toBe(expected) // Object.is equality
+
+    Expected: 4
+    Received: 3
+
+      3 | test('adds two numbers', () => {
+    > 4 |   expect(sum(1, 2)).toBe(4);
+        |                     ^
+      5 | });
+
+      at Object.toBe (src/sum.test.js:4:21)
+
+Test Suites: 1 failed, 1 total
+Tests:       1 failed, 1 total
+";
+        assert_eq!(
+            summarize_test_failures(&command("npm test"), output),
+            Some(TestFailureSummary {
+                runner: "jest",
+                failed: 1,
+                failures: vec![TestFailure {
+                    name: "sum › adds two numbers".to_string(),
+                    location: Some("src/sum.test.js:4:21".to_string()),
+                    message: Some(
+                        "expect(received).toBe(expected) // Object.is equality\nExpected: 4\nReceived: 3"
+                            .to_string()
+                    ),
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn ignores_other_commands() {
+        let output = "test a ... FAILED\n";
+        assert_eq!(
+            summarize_test_failures(&command("make check"), output),
+            None
+        );
+        assert_eq!(
+            summarize_test_failures(&command("npm test"), "1 failed"),
+            None
+        );
+    }
+
+    #[test]
+    fn renders_a_compact_summary() {
+        let summary = TestFailureSummary {
+            runner: "cargo test",
+            failed: 3,
+            failures: vec![TestFailure {
+                name: "tests::adds".to_string(),
+                location: Some("src/lib.rs:12:9".to_string()),
+                message: Some("assertion failed\n  left: 3".to_string()),
+            }],
+        };
+        assert_eq!(
+            summary.render(),
+            "Test failures (cargo test, 3 failed):\n- tests::adds (src/lib.rs:12:9)\n    assertion failed\n      left: 3\n- ... and 2 more"
+        );
+    }
+}
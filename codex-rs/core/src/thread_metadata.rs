@@ -0,0 +1,198 @@
+//! Thread metadata that can change after the rollout is written, currently
+//! just the title. It lives in a `.meta.json` file beside the rollout so
+//! listing threads does not have to scan whole rollouts for it.
+//!
+//! With the `thread_titles` feature, an untitled thread is named by a model
+//! after its first exchange; `Op::SetThreadTitle` renames it at any time.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ModelTask;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::time::timeout;
+use tracing::warn;
+
+use crate::client_common::Prompt;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::model_router::RoutedClient;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_text;
+use crate::turn_commit::request_message;
+
+const THREAD_TITLE_PROMPT: &str = include_str!("../templates/thread_title/prompt.md");
+/// Each message sent to the model for the title is cut to this size.
+const MAX_MESSAGE_BYTES: usize = 4_000;
+const TITLE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Titles are cut to this many characters.
+pub const MAX_TITLE_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// `rollout-<timestamp>-<id>.jsonl` becomes `rollout-<timestamp>-<id>.meta.json`.
+pub fn thread_metadata_path(rollout_path: &Path) -> PathBuf {
+    rollout_path.with_extension("meta.json")
+}
+
+/// The metadata of the thread recorded at `rollout_path`. Threads without a
+/// metadata file have the default.
+pub async fn read_thread_metadata(rollout_path: &Path) -> io::Result<ThreadMetadata> {
+    match tokio::fs::read(thread_metadata_path(rollout_path)).await {
+        Ok(bytes) => serde_json::from_slice(&bytes).map_err(io::Error::other),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(ThreadMetadata::default()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Replaces the metadata file through a rename, so readers never see a
+/// partial write.
+pub async fn write_thread_metadata(
+    rollout_path: &Path,
+    metadata: &ThreadMetadata,
+) -> io::Result<()> {
+    let path = thread_metadata_path(rollout_path);
+    let tmp_path = path.with_extension("json.tmp");
+    let json = serde_json::to_vec_pretty(metadata).map_err(io::Error::other)?;
+    tokio::fs::write(&tmp_path, json).await?;
+    tokio::fs::rename(&tmp_path, &path).await
+}
+
+/// Collapses whitespace and cuts `title` to [`MAX_TITLE_CHARS`]. Returns
+/// `None` for a blank title.
+pub fn normalize_title(title: &str) -> Option<String> {
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    if title.is_empty() {
+        return None;
+    }
+    Some(title.chars().take(MAX_TITLE_CHARS).collect())
+}
+
+/// Asks the `thread_title` model to name the exchange between
+/// `user_message` and `agent_message`.
+pub(crate) async fn generate_title(
+    sess: &Session,
+    turn_context: &TurnContext,
+    user_message: &str,
+    agent_message: Option<&str>,
+) -> Option<String> {
+    let user_message = truncate_text(user_message, TruncationPolicy::Bytes(MAX_MESSAGE_BYTES));
+    let mut text = format!("User:\n{user_message}");
+    if let Some(reply) = agent_message.filter(|reply| !reply.trim().is_empty()) {
+        let reply = truncate_text(reply, TruncationPolicy::Bytes(MAX_MESSAGE_BYTES));
+        text.push_str(&format!("\n\nAgent:\n{reply}"));
+    }
+    let prompt = Prompt {
+        input: vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText { text }],
+        }],
+        base_instructions_override: Some(THREAD_TITLE_PROMPT.to_string()),
+        ..Default::default()
+    };
+
+    let mut routed = RoutedClient::new(&turn_context.client, ModelTask::ThreadTitle);
+    let result = timeout(TITLE_TIMEOUT, async {
+        loop {
+            match request_message(routed.client(), &prompt).await {
+                Err(err) if routed.fall_back(&err) => continue,
+                result => return result,
+            }
+        }
+    })
+    .await;
+    if matches!(result, Ok(Ok(_))) {
+        sess.record_model_usage(&routed).await;
+    }
+    match result {
+        Ok(Ok(reply)) => reply.and_then(|reply| clean_title(&reply)),
+        Ok(Err(err)) => {
+            warn!("failed to generate thread title: {err:#}");
+            None
+        }
+        Err(_) => {
+            warn!("timed out generating thread title");
+            None
+        }
+    }
+}
+
+/// Strip wrapping the model may add despite instructions.
+fn clean_title(reply: &str) -> Option<String> {
+    let line = reply.lines().map(str::trim).find(|line| !line.is_empty())?;
+    let line = line.strip_prefix("Title:").unwrap_or(line);
+    let line = line
+        .trim()
+        .trim_matches(|c| matches!(c, '"' | '\'' | '`' | '*'))
+        .trim_end_matches('.');
+    normalize_title(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn clean_title_strips_wrapping_and_extra_lines() {
+        assert_eq!(
+            clean_title("\"Fix flaky login test.\"\n\nThis names the thread."),
+            Some("Fix flaky login test".to_string())
+        );
+        assert_eq!(
+            clean_title("Title: `Add CSV export`"),
+            Some("Add CSV export".to_string())
+        );
+        assert_eq!(clean_title(" \n"), None);
+    }
+
+    #[test]
+    fn normalize_title_collapses_whitespace_and_caps_length() {
+        assert_eq!(
+            normalize_title("  Rename\n the   thread "),
+            Some("Rename the thread".to_string())
+        );
+        assert_eq!(
+            normalize_title(&"x".repeat(200)).map(|title| title.len()),
+            Some(MAX_TITLE_CHARS)
+        );
+        assert_eq!(normalize_title("\t"), None);
+    }
+
+    #[tokio::test]
+    async fn metadata_round_trips_next_to_the_rollout() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let rollout = dir.path().join("rollout-2025-06-01T08-00-00-abc.jsonl");
+        assert_eq!(
+            ThreadMetadata::default(),
+            read_thread_metadata(&rollout).await.expect("read")
+        );
+
+        let metadata = ThreadMetadata {
+            title: Some("Fix flaky login test".to_string()),
+        };
+        write_thread_metadata(&rollout, &metadata)
+            .await
+            .expect("write");
+
+        assert!(
+            dir.path()
+                .join("rollout-2025-06-01T08-00-00-abc.meta.json")
+                .exists()
+        );
+        assert_eq!(
+            metadata,
+            read_thread_metadata(&rollout).await.expect("read")
+        );
+    }
+}
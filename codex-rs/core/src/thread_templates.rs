@@ -0,0 +1,305 @@
+//! Thread templates: named starting points that set a new thread's model,
+//! sandbox, instructions, pinned context, and tools at once.
+//!
+//! Templates come from `[templates.<name>]` in `config.toml` and from
+//! `$CODEX_HOME/templates/<name>.toml`, a directory a team can share. A
+//! template in `config.toml` takes precedence over a file with the same
+//! name. Files are re-read every time templates are listed or used, so edits
+//! take effect without restarting.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use tokio::fs;
+
+use crate::config::Config;
+use crate::config::ConfigOverrides;
+use crate::config::template::ThreadTemplate;
+use crate::tool_filter::ToolFilter;
+
+/// Directory under `CODEX_HOME` that holds template files.
+pub const TEMPLATES_DIR: &str = "templates";
+
+/// Keys a template file may contain.
+const TEMPLATE_KEYS: &[&str] = &[
+    "description",
+    "model",
+    "model_provider",
+    "model_reasoning_effort",
+    "approval_policy",
+    "sandbox_mode",
+    "developer_instructions",
+    "pinned_context",
+    "tools_enabled",
+    "tools_disabled",
+];
+
+/// A template as found on disk or in `config.toml`, with the problems that
+/// keep it from being used.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadedTemplate {
+    pub name: String,
+    /// File the template was read from; `None` for `config.toml`.
+    pub path: Option<PathBuf>,
+    pub template: ThreadTemplate,
+    /// Empty when the template can be used.
+    pub errors: Vec<String>,
+}
+
+/// Return the templates directory for `codex_home`.
+pub fn templates_dir(codex_home: &Path) -> PathBuf {
+    codex_home.join(TEMPLATES_DIR)
+}
+
+/// Every template, sorted by name and validated against `config`. Invalid
+/// templates are included with their errors so clients can show why they
+/// are unavailable.
+pub async fn list_templates(config: &Config) -> Vec<LoadedTemplate> {
+    let mut templates = BTreeMap::new();
+    for (name, path, parsed) in read_template_files(&templates_dir(&config.codex_home)).await {
+        let (template, errors) = match parsed {
+            Ok(template) => (template, Vec::new()),
+            Err(errors) => (ThreadTemplate::default(), errors),
+        };
+        templates.insert(
+            name.clone(),
+            LoadedTemplate {
+                name,
+                path: Some(path),
+                template,
+                errors,
+            },
+        );
+    }
+    for (name, template) in &config.templates {
+        templates.insert(
+            name.clone(),
+            LoadedTemplate {
+                name: name.clone(),
+                path: None,
+                template: template.clone(),
+                errors: Vec::new(),
+            },
+        );
+    }
+    templates
+        .into_values()
+        .map(|mut loaded| {
+            if loaded.errors.is_empty() {
+                loaded.errors = validate_template(config, &loaded.template);
+            }
+            loaded
+        })
+        .collect()
+}
+
+/// The template called `name`, or why it cannot be used.
+pub async fn load_template(config: &Config, name: &str) -> Result<ThreadTemplate, String> {
+    let Some(loaded) = list_templates(config)
+        .await
+        .into_iter()
+        .find(|loaded| loaded.name == name)
+    else {
+        return Err(format!("unknown template `{name}`"));
+    };
+    if !loaded.errors.is_empty() {
+        return Err(format!(
+            "template `{name}` is invalid: {}",
+            loaded.errors.join("; ")
+        ));
+    }
+    Ok(loaded.template)
+}
+
+/// Fill in `overrides` from `template`. Values the caller already set win.
+pub fn apply_template(template: &ThreadTemplate, overrides: &mut ConfigOverrides) {
+    overrides.model = overrides.model.take().or_else(|| template.model.clone());
+    overrides.model_provider = overrides
+        .model_provider
+        .take()
+        .or_else(|| template.model_provider.clone());
+    overrides.approval_policy = overrides.approval_policy.or(template.approval_policy);
+    overrides.sandbox_mode = overrides.sandbox_mode.or(template.sandbox_mode);
+    overrides.developer_instructions = overrides
+        .developer_instructions
+        .take()
+        .or_else(|| template.developer_instructions.clone());
+    overrides.tools_enabled = overrides
+        .tools_enabled
+        .take()
+        .or_else(|| template.tools_enabled.clone());
+    overrides.tools_disabled = overrides
+        .tools_disabled
+        .take()
+        .or_else(|| template.tools_disabled.clone());
+}
+
+/// The template's pinned paths, with relative ones resolved against `cwd`.
+pub fn resolve_pinned_context(template: &ThreadTemplate, cwd: &Path) -> Vec<PathBuf> {
+    template
+        .pinned_context
+        .iter()
+        .map(|path| cwd.join(path))
+        .collect()
+}
+
+fn validate_template(config: &Config, template: &ThreadTemplate) -> Vec<String> {
+    let mut errors = Vec::new();
+    if template.model.as_deref().is_some_and(str::is_empty) {
+        errors.push("`model` must not be empty".to_string());
+    }
+    if let Some(provider) = &template.model_provider
+        && !config.model_providers.contains_key(provider)
+    {
+        errors.push(format!(
+            "model_provider `{provider}` is not in model_providers"
+        ));
+    }
+    if template.tools_enabled.is_some() || template.tools_disabled.is_some() {
+        let disabled = template.tools_disabled.clone().unwrap_or_default();
+        if let Err(err) = ToolFilter::new(
+            template.tools_enabled.as_deref(),
+            &disabled,
+            &config.mcp_servers,
+        ) {
+            errors.push(err.to_string());
+        }
+    }
+    if template
+        .pinned_context
+        .iter()
+        .any(|path| path.as_os_str().is_empty())
+    {
+        errors.push("`pinned_context` entries must not be empty".to_string());
+    }
+    errors
+}
+
+/// `(name, path, template or errors)` for every `*.toml` file in `dir`. A
+/// missing directory yields nothing.
+async fn read_template_files(
+    dir: &Path,
+) -> Vec<(String, PathBuf, Result<ThreadTemplate, Vec<String>>)> {
+    let mut out = Vec::new();
+    let Ok(mut entries) = fs::read_dir(dir).await else {
+        return out;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("toml") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let parsed = match fs::read_to_string(&path).await {
+            Ok(contents) => parse_template(&contents),
+            Err(err) => Err(vec![format!("failed to read {}: {err}", path.display())]),
+        };
+        out.push((name.to_string(), path, parsed));
+    }
+    out
+}
+
+fn parse_template(contents: &str) -> Result<ThreadTemplate, Vec<String>> {
+    let table = toml::from_str::<toml::Table>(contents).map_err(|err| vec![err.to_string()])?;
+    let unknown = table
+        .keys()
+        .filter(|key| !TEMPLATE_KEYS.contains(&key.as_str()))
+        .map(|key| format!("unknown key `{key}`"))
+        .collect::<Vec<_>>();
+    let template = toml::Value::Table(table)
+        .try_into::<ThreadTemplate>()
+        .map_err(|err| vec![err.to_string()])?;
+    if unknown.is_empty() {
+        Ok(template)
+    } else {
+        Err(unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ConfigToml;
+    use codex_protocol::config_types::SandboxMode;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    fn config_with(codex_home: &TempDir, config_toml: &str) -> Config {
+        let config_toml = toml::from_str::<ConfigToml>(config_toml).expect("config.toml");
+        Config::load_from_base_config_with_overrides(
+            config_toml,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect("config")
+    }
+
+    #[tokio::test]
+    async fn lists_config_and_file_templates_with_their_errors() {
+        let codex_home = TempDir::new().expect("tempdir");
+        let dir = templates_dir(codex_home.path());
+        std::fs::create_dir_all(&dir).expect("create dir");
+        std::fs::write(dir.join("docs.toml"), "sandbox_mode = \"read-only\"\n").expect("write");
+        std::fs::write(dir.join("review.toml"), "model = \"shadowed\"\n").expect("write");
+        std::fs::write(dir.join("typo.toml"), "modle = \"o3\"\n").expect("write");
+        let config = config_with(
+            &codex_home,
+            r#"
+[templates.review]
+model = "o3"
+
+[templates.bugfix]
+model_provider = "missing"
+"#,
+        );
+
+        let templates = list_templates(&config).await;
+
+        assert_eq!(
+            templates
+                .iter()
+                .map(|loaded| (loaded.name.as_str(), loaded.errors.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "bugfix",
+                    vec!["model_provider `missing` is not in model_providers".to_string()]
+                ),
+                ("docs", Vec::new()),
+                ("review", Vec::new()),
+                ("typo", vec!["unknown key `modle`".to_string()]),
+            ]
+        );
+        assert_eq!(
+            templates[1].template.sandbox_mode,
+            Some(SandboxMode::ReadOnly)
+        );
+        assert_eq!(templates[2].template.model.as_deref(), Some("o3"));
+        assert_eq!(templates[2].path, None);
+        assert!(load_template(&config, "bugfix").await.is_err());
+        assert!(load_template(&config, "nope").await.is_err());
+    }
+
+    #[test]
+    fn explicit_overrides_win_over_the_template() {
+        let template = ThreadTemplate {
+            model: Some("o3".to_string()),
+            sandbox_mode: Some(SandboxMode::ReadOnly),
+            tools_disabled: Some(vec!["shell".to_string()]),
+            ..ThreadTemplate::default()
+        };
+        let mut overrides = ConfigOverrides {
+            model: Some("gpt-5".to_string()),
+            ..ConfigOverrides::default()
+        };
+
+        apply_template(&template, &mut overrides);
+
+        assert_eq!(overrides.model.as_deref(), Some("gpt-5"));
+        assert_eq!(overrides.sandbox_mode, Some(SandboxMode::ReadOnly));
+        assert_eq!(overrides.tools_disabled, Some(vec!["shell".to_string()]));
+    }
+}
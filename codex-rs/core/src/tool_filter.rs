@@ -0,0 +1,318 @@
+//! Which tools a conversation may offer the model, configured with
+//! `tools.enabled` / `tools.disabled`, their profile equivalents, or
+//! per-conversation overrides.
+//!
+//! Entries name a built-in tool (`shell`, `apply_patch`, `web_search`, ...),
+//! every tool of an MCP server (`mcp:<server>`), a single MCP tool
+//! (`mcp:<server>/<tool>`), or every tool of a WebAssembly plugin
+//! (`plugin:<name>`). `shell` also covers the other shell tool variants
+//! (`exec_command`, `shell_command`, `local_shell`, ...).
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::config::types::McpServerConfig;
+
+/// Built-in tools that can be named in a filter.
+const BUILTIN_TOOLS: &[&str] = &[
+    "shell",
+    "exec_command",
+    "write_stdin",
+    "shell_command",
+    "local_shell",
+    "container.exec",
+    "apply_patch",
+    "web_search",
+    "view_image",
+    "update_plan",
+    "read_file",
+    "list_dir",
+    "grep_files",
+    "test_sync_tool",
+    "list_mcp_resources",
+    "list_mcp_resource_templates",
+    "read_mcp_resource",
+    "propose_workspace_instruction",
+    "background_task_output",
+    "spawn_agent",
+    "create_pull_request",
+    "repo_map",
+    "ask_user",
+];
+
+/// Tools covered by the `shell` entry.
+const SHELL_TOOLS: &[&str] = &[
+    "shell",
+    "exec_command",
+    "write_stdin",
+    "shell_command",
+    "local_shell",
+    "container.exec",
+];
+
+const MCP_PREFIX: &str = "mcp:";
+const PLUGIN_PREFIX: &str = "plugin:";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ToolSelector {
+    Builtin(String),
+    McpServer(String),
+    McpTool {
+        server: String,
+        tool: String,
+    },
+    /// Plugins are loaded per session, so their names are not checked here.
+    Plugin(String),
+}
+
+impl ToolSelector {
+    fn parse(
+        entry: &str,
+        mcp_servers: &HashMap<String, McpServerConfig>,
+    ) -> Result<Self, ToolFilterError> {
+        if let Some(plugin) = entry.strip_prefix(PLUGIN_PREFIX) {
+            if plugin.is_empty() {
+                return Err(ToolFilterError::UnknownTool(entry.to_string()));
+            }
+            return Ok(ToolSelector::Plugin(plugin.to_string()));
+        }
+        let Some(mcp) = entry.strip_prefix(MCP_PREFIX) else {
+            if BUILTIN_TOOLS.contains(&entry) {
+                return Ok(ToolSelector::Builtin(entry.to_string()));
+            }
+            return Err(ToolFilterError::UnknownTool(entry.to_string()));
+        };
+        let (server, tool) = match mcp.split_once('/') {
+            Some((server, tool)) => (server, Some(tool)),
+            None => (mcp, None),
+        };
+        if !mcp_servers.contains_key(server) {
+            return Err(ToolFilterError::UnknownMcpServer(server.to_string()));
+        }
+        Ok(match tool {
+            Some(tool) if !tool.is_empty() => ToolSelector::McpTool {
+                server: server.to_string(),
+                tool: tool.to_string(),
+            },
+            Some(_) => return Err(ToolFilterError::UnknownTool(entry.to_string())),
+            None => ToolSelector::McpServer(server.to_string()),
+        })
+    }
+
+    fn matches_builtin(&self, name: &str) -> bool {
+        match self {
+            ToolSelector::Builtin(builtin) => {
+                builtin == name || (builtin == "shell" && SHELL_TOOLS.contains(&name))
+            }
+            _ => false,
+        }
+    }
+
+    fn matches_mcp(&self, server_name: &str, tool_name: &str) -> bool {
+        match self {
+            ToolSelector::McpServer(server) => server == server_name,
+            ToolSelector::McpTool { server, tool } => server == server_name && tool == tool_name,
+            ToolSelector::Builtin(_) | ToolSelector::Plugin(_) => false,
+        }
+    }
+
+    fn matches_plugin(&self, plugin_name: &str) -> bool {
+        matches!(self, ToolSelector::Plugin(plugin) if plugin == plugin_name)
+    }
+}
+
+impl fmt::Display for ToolSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToolSelector::Builtin(name) => write!(f, "{name}"),
+            ToolSelector::McpServer(server) => write!(f, "{MCP_PREFIX}{server}"),
+            ToolSelector::McpTool { server, tool } => write!(f, "{MCP_PREFIX}{server}/{tool}"),
+            ToolSelector::Plugin(plugin) => write!(f, "{PLUGIN_PREFIX}{plugin}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ToolFilterError {
+    #[error("unknown tool `{0}`")]
+    UnknownTool(String),
+    #[error("unknown MCP server `{0}`")]
+    UnknownMcpServer(String),
+}
+
+/// The tools a conversation may offer the model. The default allows every
+/// tool.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolFilter {
+    /// When set, only these tools are offered.
+    enabled: Option<Vec<ToolSelector>>,
+    /// Tools never offered, even when also enabled.
+    disabled: Vec<ToolSelector>,
+}
+
+impl ToolFilter {
+    pub fn new(
+        enabled: Option<&[String]>,
+        disabled: &[String],
+        mcp_servers: &HashMap<String, McpServerConfig>,
+    ) -> Result<Self, ToolFilterError> {
+        let parse = |entries: &[String]| {
+            entries
+                .iter()
+                .map(|entry| ToolSelector::parse(entry, mcp_servers))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        Ok(Self {
+            enabled: enabled.map(parse).transpose()?,
+            disabled: parse(disabled)?,
+        })
+    }
+
+    /// Whether the built-in tool `name` may be offered.
+    pub fn allows_builtin(&self, name: &str) -> bool {
+        self.allows(|selector| selector.matches_builtin(name))
+    }
+
+    /// Whether `tool_name` from the MCP server `server_name` may be offered.
+    pub fn allows_mcp_tool(&self, server_name: &str, tool_name: &str) -> bool {
+        self.allows(|selector| selector.matches_mcp(server_name, tool_name))
+    }
+
+    /// Whether the tools of the WebAssembly plugin `plugin_name` may be
+    /// offered.
+    pub(crate) fn allows_plugin(&self, plugin_name: &str) -> bool {
+        self.allows(|selector| selector.matches_plugin(plugin_name))
+    }
+
+    fn allows(&self, matches: impl Fn(&ToolSelector) -> bool) -> bool {
+        if self.disabled.iter().any(&matches) {
+            return false;
+        }
+        match &self.enabled {
+            Some(enabled) => enabled.iter().any(matches),
+            None => true,
+        }
+    }
+
+    /// A note for the model's instructions describing the restriction, so it
+    /// does not try to reach a withheld tool's result some other way.
+    pub(crate) fn instructions(&self) -> Option<String> {
+        let list = |selectors: &[ToolSelector]| {
+            selectors
+                .iter()
+                .map(|selector| format!("`{selector}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut lines = Vec::new();
+        if let Some(enabled) = &self.enabled {
+            lines.push(format!(
+                "The user has limited this conversation to these tools: {}.",
+                list(enabled)
+            ));
+        }
+        if !self.disabled.is_empty() {
+            lines.push(format!(
+                "The user has turned off these tools for this conversation: {}.",
+                list(&self.disabled)
+            ));
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        lines.push(
+            "Do not try to work around this restriction with other tools; if a task needs a tool that is unavailable, say so."
+                .to_string(),
+        );
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::types::McpServerStartup;
+    use crate::config::types::McpServerTransportConfig;
+    use pretty_assertions::assert_eq;
+
+    fn mcp_servers() -> HashMap<String, McpServerConfig> {
+        let server = McpServerConfig {
+            transport: McpServerTransportConfig::Stdio {
+                command: "docs-server".to_string(),
+                args: Vec::new(),
+                env: None,
+                env_vars: Vec::new(),
+                cwd: None,
+            },
+            enabled: true,
+            startup_timeout_sec: None,
+            tool_timeout_sec: None,
+            enabled_tools: None,
+            disabled_tools: None,
+            startup: McpServerStartup::Eager,
+            idle_timeout_sec: None,
+            warm_pool: false,
+            tool_aliases: None,
+            sampling: None,
+        };
+        HashMap::from([("docs".to_string(), server)])
+    }
+
+    fn strings(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn disabled_shell_covers_every_shell_variant() {
+        let filter = ToolFilter::new(
+            None,
+            &strings(&["shell", "mcp:docs/search"]),
+            &mcp_servers(),
+        )
+        .expect("valid filter");
+
+        assert!(!filter.allows_builtin("shell"));
+        assert!(!filter.allows_builtin("exec_command"));
+        assert!(filter.allows_builtin("apply_patch"));
+        assert!(!filter.allows_mcp_tool("docs", "search"));
+        assert!(filter.allows_mcp_tool("docs", "fetch"));
+    }
+
+    #[test]
+    fn enabled_tools_are_the_only_ones_offered() {
+        let enabled = strings(&["read_file", "mcp:docs"]);
+        let filter = ToolFilter::new(
+            Some(&enabled),
+            &strings(&["mcp:docs/fetch"]),
+            &mcp_servers(),
+        )
+        .expect("valid filter");
+
+        assert!(filter.allows_builtin("read_file"));
+        assert!(!filter.allows_builtin("shell"));
+        assert!(filter.allows_mcp_tool("docs", "search"));
+        assert!(!filter.allows_mcp_tool("docs", "fetch"));
+        assert_eq!(
+            filter.instructions(),
+            Some(
+                "The user has limited this conversation to these tools: `read_file`, `mcp:docs`.\n\
+                 The user has turned off these tools for this conversation: `mcp:docs/fetch`.\n\
+                 Do not try to work around this restriction with other tools; if a task needs a tool that is unavailable, say so."
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn unknown_entries_are_rejected() {
+        assert_eq!(
+            ToolFilter::new(None, &strings(&["shel"]), &mcp_servers()),
+            Err(ToolFilterError::UnknownTool("shel".to_string()))
+        );
+        assert_eq!(
+            ToolFilter::new(None, &strings(&["mcp:github"]), &mcp_servers()),
+            Err(ToolFilterError::UnknownMcpServer("github".to_string()))
+        );
+        assert_eq!(ToolFilter::default().instructions(), None);
+    }
+}
@@ -3,6 +3,7 @@ use crate::codex::TurnContext;
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecToolCallOutput;
+use crate::exec_output_cleanup::clean_exec_output;
 use crate::function_tool::FunctionCallError;
 use crate::parse_command::parse_command;
 use crate::protocol::EventMsg;
@@ -13,8 +14,13 @@ use crate::protocol::FileChange;
 use crate::protocol::PatchApplyBeginEvent;
 use crate::protocol::PatchApplyEndEvent;
 use crate::protocol::TurnDiffEvent;
+use crate::test_failures::summarize_test_failures;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::sandboxing::ToolError;
+use codex_protocol::items::CommandOutputTruncation;
+use codex_protocol::items::FileChangeItem;
+use codex_protocol::items::FileChangeStatus;
+use codex_protocol::items::TurnItem;
 use codex_protocol::parse_command::ParsedCommand;
 use std::collections::HashMap;
 use std::path::Path;
@@ -56,6 +62,8 @@ pub(crate) enum ToolEventStage {
 pub(crate) enum ToolEventFailure {
     Output(ExecToolCallOutput),
     Message(String),
+    /// The call, or a segment of it, was rejected by the user or by policy.
+    Rejected(String),
 }
 
 pub(crate) async fn emit_exec_command_begin(
@@ -67,9 +75,9 @@ pub(crate) async fn emit_exec_command_begin(
     interaction_input: Option<String>,
 ) {
     ctx.session
-        .send_event(
+        .send_exec_command_begin(
             ctx.turn,
-            EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+            ExecCommandBeginEvent {
                 call_id: ctx.call_id.to_string(),
                 turn_id: ctx.turn.sub_id.clone(),
                 command: command.to_vec(),
@@ -77,7 +85,7 @@ pub(crate) async fn emit_exec_command_begin(
                 parsed_cmd: parsed_cmd.to_vec(),
                 source,
                 interaction_input,
-            }),
+            },
         )
         .await;
 }
@@ -93,6 +101,7 @@ pub(crate) enum ToolEmitter {
     ApplyPatch {
         changes: HashMap<PathBuf, FileChange>,
         auto_approved: bool,
+        merged_with_conflicts: bool,
     },
     UnifiedExec {
         command: Vec<String>,
@@ -120,10 +129,15 @@ impl ToolEmitter {
         }
     }
 
-    pub fn apply_patch(changes: HashMap<PathBuf, FileChange>, auto_approved: bool) -> Self {
+    pub fn apply_patch(
+        changes: HashMap<PathBuf, FileChange>,
+        auto_approved: bool,
+        merged_with_conflicts: bool,
+    ) -> Self {
         Self::ApplyPatch {
             changes,
             auto_approved,
+            merged_with_conflicts,
         }
     }
 
@@ -167,6 +181,7 @@ impl ToolEmitter {
                 Self::ApplyPatch {
                     changes,
                     auto_approved,
+                    ..
                 },
                 ToolEventStage::Begin,
             ) => {
@@ -184,33 +199,64 @@ impl ToolEmitter {
                         }),
                     )
                     .await;
+                ctx.session
+                    .emit_turn_item_started(
+                        ctx.turn,
+                        &file_change_item(ctx, changes, FileChangeStatus::InProgress),
+                    )
+                    .await;
             }
-            (Self::ApplyPatch { .. }, ToolEventStage::Success(output)) => {
+            (
+                Self::ApplyPatch {
+                    changes,
+                    merged_with_conflicts,
+                    ..
+                },
+                ToolEventStage::Success(output),
+            ) => {
                 emit_patch_end(
                     ctx,
+                    changes,
                     output.stdout.text.clone(),
                     output.stderr.text.clone(),
                     output.exit_code == 0,
+                    *merged_with_conflicts,
                 )
                 .await;
             }
             (
-                Self::ApplyPatch { .. },
+                Self::ApplyPatch {
+                    changes,
+                    merged_with_conflicts,
+                    ..
+                },
                 ToolEventStage::Failure(ToolEventFailure::Output(output)),
             ) => {
                 emit_patch_end(
                     ctx,
+                    changes,
                     output.stdout.text.clone(),
                     output.stderr.text.clone(),
                     output.exit_code == 0,
+                    *merged_with_conflicts,
                 )
                 .await;
             }
             (
-                Self::ApplyPatch { .. },
-                ToolEventStage::Failure(ToolEventFailure::Message(message)),
+                Self::ApplyPatch { changes, .. },
+                ToolEventStage::Failure(
+                    ToolEventFailure::Message(message) | ToolEventFailure::Rejected(message),
+                ),
             ) => {
-                emit_patch_end(ctx, String::new(), (*message).to_string(), false).await;
+                emit_patch_end(
+                    ctx,
+                    changes,
+                    String::new(),
+                    (*message).to_string(),
+                    false,
+                    false,
+                )
+                .await;
             }
             (
                 Self::UnifiedExec {
@@ -247,11 +293,27 @@ impl ToolEmitter {
         output: &ExecToolCallOutput,
         ctx: ToolEventCtx<'_>,
     ) -> String {
-        match self {
-            Self::Shell { freeform: true, .. } => {
-                super::format_exec_output_for_model_freeform(output, ctx.turn.truncation_policy)
+        let cleaned = clean_exec_output(output, &ctx.turn.client.config().exec_output);
+        let output = cleaned.as_ref().unwrap_or(output);
+        let test_failures = match self {
+            Self::Shell { command, .. } | Self::UnifiedExec { command, .. }
+                if output.exit_code != 0 && !output.timed_out =>
+            {
+                summarize_test_failures(command, &output.aggregated_output.text)
             }
-            _ => super::format_exec_output_for_model_structured(output, ctx.turn.truncation_policy),
+            _ => None,
+        };
+        match self {
+            Self::Shell { freeform: true, .. } => super::format_exec_output_for_model_freeform(
+                output,
+                test_failures.as_ref(),
+                ctx.turn.truncation_policy,
+            ),
+            _ => super::format_exec_output_for_model_structured(
+                output,
+                test_failures.as_ref(),
+                ctx.turn.truncation_policy,
+            ),
         }
     }
 
@@ -262,7 +324,9 @@ impl ToolEmitter {
     ) -> Result<String, FunctionCallError> {
         let (event, result) = match out {
             Ok(output) => {
-                let content = self.format_exec_output_for_model(&output, ctx);
+                let redacted = ctx.session.redact_exec_output(ctx.turn, &output).await;
+                let content =
+                    self.format_exec_output_for_model(redacted.as_ref().unwrap_or(&output), ctx);
                 let exit_code = output.exit_code;
                 let event = ToolEventStage::Success(output);
                 let result = if exit_code == 0 {
@@ -274,7 +338,9 @@ impl ToolEmitter {
             }
             Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Timeout { output })))
             | Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output }))) => {
-                let response = self.format_exec_output_for_model(&output, ctx);
+                let redacted = ctx.session.redact_exec_output(ctx.turn, &output).await;
+                let response =
+                    self.format_exec_output_for_model(redacted.as_ref().unwrap_or(&output), ctx);
                 let event = ToolEventStage::Failure(ToolEventFailure::Output(*output));
                 let result = Err(FunctionCallError::RespondToModel(response));
                 (event, result)
@@ -293,7 +359,7 @@ impl ToolEmitter {
                 } else {
                     msg
                 };
-                let event = ToolEventStage::Failure(ToolEventFailure::Message(normalized.clone()));
+                let event = ToolEventStage::Failure(ToolEventFailure::Rejected(normalized.clone()));
                 let result = Err(FunctionCallError::RespondToModel(normalized));
                 (event, result)
             }
@@ -336,6 +402,9 @@ struct ExecCommandResult {
     exit_code: i32,
     duration: Duration,
     formatted_output: String,
+    output_truncation: Option<CommandOutputTruncation>,
+    /// The command was rejected before it ran.
+    declined: bool,
 }
 
 async fn emit_exec_stage(
@@ -357,6 +426,9 @@ async fn emit_exec_stage(
         }
         ToolEventStage::Success(output)
         | ToolEventStage::Failure(ToolEventFailure::Output(output)) => {
+            ctx.session
+                .archive_exec_output(ctx.turn, ctx.call_id, &output)
+                .await;
             let exec_result = ExecCommandResult {
                 stdout: output.stdout.text.clone(),
                 stderr: output.stderr.text.clone(),
@@ -364,52 +436,88 @@ async fn emit_exec_stage(
                 exit_code: output.exit_code,
                 duration: output.duration,
                 formatted_output: format_exec_output_str(&output, ctx.turn.truncation_policy),
+                output_truncation: output.output_truncation,
+                declined: false,
             };
             emit_exec_end(ctx, exec_input, exec_result).await;
         }
         ToolEventStage::Failure(ToolEventFailure::Message(message)) => {
-            let text = message.to_string();
+            emit_exec_end(ctx, exec_input, message_result(message)).await;
+        }
+        ToolEventStage::Failure(ToolEventFailure::Rejected(message)) => {
             let exec_result = ExecCommandResult {
-                stdout: String::new(),
-                stderr: text.clone(),
-                aggregated_output: text.clone(),
-                exit_code: -1,
-                duration: Duration::ZERO,
-                formatted_output: text,
+                declined: true,
+                ..message_result(message)
             };
             emit_exec_end(ctx, exec_input, exec_result).await;
         }
     }
 }
 
+fn message_result(message: String) -> ExecCommandResult {
+    ExecCommandResult {
+        stdout: String::new(),
+        stderr: message.clone(),
+        aggregated_output: message.clone(),
+        exit_code: -1,
+        duration: Duration::ZERO,
+        formatted_output: message,
+        output_truncation: None,
+        declined: false,
+    }
+}
+
 async fn emit_exec_end(
     ctx: ToolEventCtx<'_>,
     exec_input: ExecCommandInput<'_>,
     exec_result: ExecCommandResult,
 ) {
-    ctx.session
-        .send_event(
-            ctx.turn,
-            EventMsg::ExecCommandEnd(ExecCommandEndEvent {
-                call_id: ctx.call_id.to_string(),
-                turn_id: ctx.turn.sub_id.clone(),
-                command: exec_input.command.to_vec(),
-                cwd: exec_input.cwd.to_path_buf(),
-                parsed_cmd: exec_input.parsed_cmd.to_vec(),
-                source: exec_input.source,
-                interaction_input: exec_input.interaction_input.map(str::to_owned),
-                stdout: exec_result.stdout,
-                stderr: exec_result.stderr,
-                aggregated_output: exec_result.aggregated_output,
-                exit_code: exec_result.exit_code,
-                duration: exec_result.duration,
-                formatted_output: exec_result.formatted_output,
-            }),
-        )
-        .await;
+    let event = ExecCommandEndEvent {
+        call_id: ctx.call_id.to_string(),
+        turn_id: ctx.turn.sub_id.clone(),
+        command: exec_input.command.to_vec(),
+        cwd: exec_input.cwd.to_path_buf(),
+        parsed_cmd: exec_input.parsed_cmd.to_vec(),
+        source: exec_input.source,
+        interaction_input: exec_input.interaction_input.map(str::to_owned),
+        stdout: exec_result.stdout,
+        stderr: exec_result.stderr,
+        aggregated_output: exec_result.aggregated_output,
+        exit_code: exec_result.exit_code,
+        duration: exec_result.duration,
+        formatted_output: exec_result.formatted_output,
+    };
+    if exec_result.declined {
+        ctx.session
+            .send_exec_command_declined(ctx.turn, event)
+            .await;
+    } else {
+        ctx.session
+            .send_exec_command_end(ctx.turn, event, exec_result.output_truncation)
+            .await;
+    }
+}
+
+fn file_change_item(
+    ctx: ToolEventCtx<'_>,
+    changes: &HashMap<PathBuf, FileChange>,
+    status: FileChangeStatus,
+) -> TurnItem {
+    TurnItem::FileChange(FileChangeItem {
+        id: ctx.call_id.to_string(),
+        changes: changes.clone(),
+        status,
+    })
 }
 
-async fn emit_patch_end(ctx: ToolEventCtx<'_>, stdout: String, stderr: String, success: bool) {
+async fn emit_patch_end(
+    ctx: ToolEventCtx<'_>,
+    changes: &HashMap<PathBuf, FileChange>,
+    stdout: String,
+    stderr: String,
+    success: bool,
+    merged_with_conflicts: bool,
+) {
     ctx.session
         .send_event(
             ctx.turn,
@@ -421,6 +529,28 @@ async fn emit_patch_end(ctx: ToolEventCtx<'_>, stdout: String, stderr: String, s
             }),
         )
         .await;
+    let status = if success {
+        for (path, change) in changes {
+            let written = match change {
+                FileChange::Update {
+                    move_path: Some(dest),
+                    ..
+                } => dest,
+                _ => path,
+            };
+            ctx.session.record_file_seen(written).await;
+        }
+        if merged_with_conflicts {
+            FileChangeStatus::MergedWithConflicts
+        } else {
+            FileChangeStatus::Completed
+        }
+    } else {
+        FileChangeStatus::Failed
+    };
+    ctx.session
+        .emit_turn_item_completed(ctx.turn, file_change_item(ctx, changes, status))
+        .await;
 
     if let Some(tracker) = ctx.turn_diff_tracker {
         let unified_diff = {
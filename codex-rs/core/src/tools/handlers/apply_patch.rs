@@ -2,12 +2,16 @@ use std::collections::BTreeMap;
 
 use crate::apply_patch;
 use crate::apply_patch::InternalApplyPatchInvocation;
+use crate::apply_patch::PatchMerge;
 use crate::apply_patch::convert_apply_patch_to_protocol;
+use crate::apply_patch::patch_write_paths;
 use crate::client_common::tools::FreeformTool;
 use crate::client_common::tools::FreeformToolFormat;
 use crate::client_common::tools::ResponsesApiTool;
 use crate::client_common::tools::ToolSpec;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::post_patch_note;
+use crate::lsp::patch_diagnostics_note;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -56,16 +60,16 @@ impl ToolHandler for ApplyPatchHandler {
             payload,
         } = invocation;
 
-        let patch_input = match payload {
+        let (patch_input, workdir) = match payload {
             ToolPayload::Function { arguments } => {
                 let args: ApplyPatchToolArgs = serde_json::from_str(&arguments).map_err(|e| {
                     FunctionCallError::RespondToModel(format!(
                         "failed to parse function arguments: {e:?}"
                     ))
                 })?;
-                args.input
+                (args.input, args.workdir)
             }
-            ToolPayload::Custom { input } => input,
+            ToolPayload::Custom { input } => (input, None),
             _ => {
                 return Err(FunctionCallError::RespondToModel(
                     "apply_patch handler received unsupported payload".to_string(),
@@ -75,9 +79,11 @@ impl ToolHandler for ApplyPatchHandler {
 
         // Re-parse and verify the patch so we can compute changes and approval.
         // Avoid building temporary ExecParams/command vectors; derive directly from inputs.
-        let cwd = turn.cwd.clone();
+        let cwd = turn.resolve_path(workdir);
         let command = vec!["apply_patch".to_string(), patch_input.clone()];
-        match codex_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd) {
+        let (verified, merge) =
+            apply_patch::verify_apply_patch(session.as_ref(), turn.as_ref(), &command, &cwd).await;
+        match verified {
             codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
                 match apply_patch::apply_patch(session.as_ref(), turn.as_ref(), &call_id, changes)
                     .await
@@ -91,9 +97,19 @@ impl ToolHandler for ApplyPatchHandler {
                         })
                     }
                     InternalApplyPatchInvocation::DelegateToExec(apply) => {
+                        // Held until the patch has been written.
+                        let _file_locks = session
+                            .lock_files_for_write(
+                                turn.as_ref(),
+                                &call_id,
+                                &patch_write_paths(&apply.action),
+                            )
+                            .await
+                            .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
                         let emitter = ToolEmitter::apply_patch(
                             convert_apply_patch_to_protocol(&apply.action),
                             !apply.user_explicitly_approved_this_action,
+                            merge.as_ref().is_some_and(PatchMerge::has_conflicts),
                         );
                         let event_ctx = ToolEventCtx::new(
                             session.as_ref(),
@@ -128,7 +144,31 @@ impl ToolHandler for ApplyPatchHandler {
                             &call_id,
                             Some(&tracker),
                         );
-                        let content = emitter.finish(event_ctx, out).await?;
+                        let mut content = emitter.finish(event_ctx, out).await?;
+                        if let Some(merge) = &merge {
+                            content.insert_str(0, &merge.note());
+                        }
+                        let paths = patch_write_paths(&apply.action);
+                        if let Some(note) = post_patch_note(
+                            session.as_ref(),
+                            turn.as_ref(),
+                            &call_id,
+                            paths.clone(),
+                        )
+                        .await
+                        {
+                            content.push_str(&note);
+                        }
+                        if let Some(note) = patch_diagnostics_note(
+                            session.as_ref(),
+                            turn.as_ref(),
+                            &call_id,
+                            &paths,
+                        )
+                        .await
+                        {
+                            content.push_str(&note);
+                        }
                         Ok(ToolOutput::Function {
                             content,
                             content_items: None,
@@ -187,6 +227,15 @@ pub(crate) fn create_apply_patch_json_tool() -> ToolSpec {
             description: Some(r#"The entire contents of the apply_patch command"#.to_string()),
         },
     );
+    properties.insert(
+        "workdir".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "The directory patch paths are relative to, e.g. one of the workspace roots. Defaults to the current working directory."
+                    .to_string(),
+            ),
+        },
+    );
 
     ToolSpec::Function(ResponsesApiTool {
         name: "apply_patch".to_string(),
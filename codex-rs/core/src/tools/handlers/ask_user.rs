@@ -0,0 +1,100 @@
+use crate::client_common::tools::ResponsesApiTool;
+use crate::client_common::tools::ToolSpec;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::spec::JsonSchema;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+pub struct AskUserHandler;
+
+pub static ASK_USER_TOOL: LazyLock<ToolSpec> = LazyLock::new(|| {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "question".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "The question to ask, self-contained and answerable in a sentence or two."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "ask_user".to_string(),
+        description: r#"Asks the user a clarifying question and waits for their answer before continuing the turn.
+Only call this when you cannot make reasonable progress without the answer, for example when the request is ambiguous between options with very different outcomes.
+The user may not answer; in that case continue with your best judgement and say which assumption you made.
+"#
+        .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["question".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+});
+
+#[derive(Deserialize)]
+struct AskUserArgs {
+    question: String,
+}
+
+#[async_trait]
+impl ToolHandler for AskUserHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "ask_user handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: AskUserArgs = serde_json::from_str(&arguments).map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e}"))
+        })?;
+        let question = args.question.trim().to_string();
+        if question.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "question must not be empty".to_string(),
+            ));
+        }
+
+        match session
+            .request_user_input(turn.as_ref(), call_id, question)
+            .await
+        {
+            Some(answer) => Ok(ToolOutput::Function {
+                content: answer,
+                content_items: None,
+                success: Some(true),
+            }),
+            None => Ok(ToolOutput::Function {
+                content: "The user did not answer. Continue with your best judgement and state the assumption you made.".to_string(),
+                content_items: None,
+                success: Some(false),
+            }),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::background_tasks::BackgroundTaskSnapshot;
+use crate::client_common::tools::ResponsesApiTool;
+use crate::client_common::tools::ToolSpec;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::BackgroundTaskStatus;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::spec::JsonSchema;
+use crate::truncate::formatted_truncate_text;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+pub struct BackgroundTaskOutputHandler;
+
+pub static BACKGROUND_TASK_OUTPUT_TOOL: LazyLock<ToolSpec> = LazyLock::new(|| {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "task_id".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Id of the background task, e.g. \"bg-1\". Omit to list all background tasks."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "background_task_output".to_string(),
+        description: r#"Reads the recent output of a long-running command the user started in the background (a dev server, a test watcher), along with whether it is still running.
+Call it without a task_id to list the background tasks of this session.
+"#
+        .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+});
+
+#[derive(Deserialize)]
+struct BackgroundTaskOutputArgs {
+    #[serde(default)]
+    task_id: Option<String>,
+}
+
+#[async_trait]
+impl ToolHandler for BackgroundTaskOutputHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "background_task_output handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: BackgroundTaskOutputArgs = serde_json::from_str(&arguments).map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e}"))
+        })?;
+
+        let tasks = &session.services.background_tasks;
+        let conversation_id = session.conversation_id();
+        let content = match args.task_id {
+            None => {
+                let snapshots = tasks.list(conversation_id);
+                if snapshots.is_empty() {
+                    "No background tasks.".to_string()
+                } else {
+                    snapshots
+                        .iter()
+                        .map(describe)
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+            }
+            Some(task_id) => {
+                let snapshot = tasks
+                    .snapshot(conversation_id, &task_id)
+                    .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
+                let mut content = describe(&snapshot);
+                if snapshot.dropped_bytes > 0 {
+                    content.push_str(&format!(
+                        "\n({} earlier bytes of output were dropped)",
+                        snapshot.dropped_bytes
+                    ));
+                }
+                content.push_str("\nOutput:\n");
+                content.push_str(&formatted_truncate_text(
+                    &snapshot.output,
+                    turn.truncation_policy,
+                ));
+                content
+            }
+        };
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
+
+fn describe(snapshot: &BackgroundTaskSnapshot) -> String {
+    let status = match (snapshot.status, snapshot.exit_code) {
+        (BackgroundTaskStatus::Running, _) => "running".to_string(),
+        (BackgroundTaskStatus::Exited, Some(code)) => format!("exited with code {code}"),
+        (BackgroundTaskStatus::Exited, None) => "exited".to_string(),
+        (BackgroundTaskStatus::Terminated, _) => "terminated".to_string(),
+    };
+    format!("{} ({status}): {}", snapshot.task_id, snapshot.command)
+}
@@ -0,0 +1,133 @@
+use crate::client_common::tools::ResponsesApiTool;
+use crate::client_common::tools::ToolSpec;
+use crate::code_search::ProviderEmbedder;
+use crate::code_search::render_hits;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::spec::JsonSchema;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+pub struct CodeSearchHandler;
+
+const DEFAULT_LIMIT: usize = 8;
+const MAX_LIMIT: usize = 20;
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(Deserialize)]
+struct CodeSearchArgs {
+    query: String,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+pub static CODE_SEARCH_TOOL: LazyLock<ToolSpec> = LazyLock::new(|| {
+    let properties = BTreeMap::from([
+        (
+            "query".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "What the code you are looking for does, in plain words (e.g. \"where failed requests are retried\")."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "limit".to_string(),
+            JsonSchema::Number {
+                description: Some(format!(
+                    "Maximum number of snippets to return (default {DEFAULT_LIMIT}, at most {MAX_LIMIT})."
+                )),
+            },
+        ),
+    ]);
+    ToolSpec::Function(ResponsesApiTool {
+        name: "code_search".to_string(),
+        description: r#"Finds the parts of the repository most related to a natural-language description, using an embeddings index of its files.
+Use it to locate code by behavior when you do not know the names involved; use grep for exact identifiers or strings.
+Returns file paths with line ranges, a relevance score, and the first lines of each match.
+"#
+        .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["query".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+});
+
+#[async_trait]
+impl ToolHandler for CodeSearchHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "code_search handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: CodeSearchArgs = serde_json::from_str(&arguments).map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to parse function arguments: {err:?}"
+            ))
+        })?;
+        let query = args.query.trim();
+        if query.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "query must not be empty".to_string(),
+            ));
+        }
+
+        let config = turn.client.config();
+        let embedder =
+            ProviderEmbedder::for_turn(&turn).map_err(FunctionCallError::RespondToModel)?;
+        let hits = session
+            .services
+            .code_search
+            .search(
+                &config.codex_home,
+                &turn.cwd,
+                &config.code_search,
+                &embedder,
+                query,
+                args.limit.clamp(1, MAX_LIMIT),
+            )
+            .await
+            .map_err(FunctionCallError::RespondToModel)?;
+
+        if hits.is_empty() {
+            return Ok(ToolOutput::Function {
+                content: "No indexed files.".to_string(),
+                content_items: None,
+                success: Some(false),
+            });
+        }
+        Ok(ToolOutput::Function {
+            content: render_hits(&hits, &turn.cwd).await,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
@@ -1,26 +1,49 @@
 pub mod apply_patch;
+mod ask_user;
+mod background_task;
+mod code_search;
 mod grep_files;
 mod list_dir;
 mod mcp;
 mod mcp_resource;
 mod plan;
+mod plugin;
+mod pull_request;
 mod read_file;
+mod repo_map;
 mod shell;
+mod spawn_agent;
 mod test_sync;
 mod unified_exec;
 mod view_image;
+mod workspace_instructions;
 
+pub use ask_user::ASK_USER_TOOL;
+pub use background_task::BACKGROUND_TASK_OUTPUT_TOOL;
+pub use code_search::CODE_SEARCH_TOOL;
 pub use plan::PLAN_TOOL;
+pub use pull_request::CREATE_PULL_REQUEST_TOOL;
+pub use repo_map::REPO_MAP_TOOL;
+pub use spawn_agent::SPAWN_AGENT_TOOL;
+pub use workspace_instructions::PROPOSE_WORKSPACE_INSTRUCTION_TOOL;
 
 pub use apply_patch::ApplyPatchHandler;
+pub use ask_user::AskUserHandler;
+pub use background_task::BackgroundTaskOutputHandler;
+pub use code_search::CodeSearchHandler;
 pub use grep_files::GrepFilesHandler;
 pub use list_dir::ListDirHandler;
 pub use mcp::McpHandler;
 pub use mcp_resource::McpResourceHandler;
 pub use plan::PlanHandler;
+pub use plugin::PluginHandler;
+pub use pull_request::CreatePullRequestHandler;
 pub use read_file::ReadFileHandler;
+pub use repo_map::RepoMapHandler;
 pub use shell::ShellCommandHandler;
 pub use shell::ShellHandler;
+pub use spawn_agent::SpawnAgentHandler;
 pub use test_sync::TestSyncHandler;
 pub use unified_exec::UnifiedExecHandler;
 pub use view_image::ViewImageHandler;
+pub use workspace_instructions::WorkspaceInstructionHandler;
@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+/// Runs functions declared by WebAssembly plugins.
+pub struct PluginHandler;
+
+#[async_trait]
+impl ToolHandler for PluginHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            tool_name,
+            payload,
+            ..
+        } = invocation;
+
+        let ToolPayload::Function { arguments } = payload else {
+            return Err(FunctionCallError::RespondToModel(
+                "plugin handler received unsupported payload".to_string(),
+            ));
+        };
+        let Some(plugins) = session.services.plugins.as_ref() else {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "unknown plugin tool `{tool_name}`"
+            )));
+        };
+
+        let output = plugins
+            .call(&tool_name, arguments, &turn.cwd)
+            .await
+            .map_err(FunctionCallError::RespondToModel)?;
+        Ok(ToolOutput::Function {
+            content: output.content,
+            content_items: None,
+            success: Some(output.success),
+        })
+    }
+}
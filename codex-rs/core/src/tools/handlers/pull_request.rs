@@ -0,0 +1,162 @@
+use crate::client_common::tools::ResponsesApiTool;
+use crate::client_common::tools::ToolSpec;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::AskForApproval;
+use crate::protocol::EventMsg;
+use crate::protocol::ReviewDecision;
+use crate::pull_request::PullRequestRequest;
+use crate::pull_request::create_pull_request;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::spec::JsonSchema;
+use async_trait::async_trait;
+use codex_keyring_store::DefaultKeyringStore;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+pub struct CreatePullRequestHandler;
+
+pub static CREATE_PULL_REQUEST_TOOL: LazyLock<ToolSpec> = LazyLock::new(|| {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "title".to_string(),
+        JsonSchema::String {
+            description: Some("Title of the pull request.".to_string()),
+        },
+    );
+    properties.insert(
+        "body".to_string(),
+        JsonSchema::String {
+            description: Some("Description of the pull request in Markdown.".to_string()),
+        },
+    );
+    properties.insert(
+        "base".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Branch to merge into. Defaults to the repository's default branch.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "draft".to_string(),
+        JsonSchema::Boolean {
+            description: Some("Open the pull request as a draft.".to_string()),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "create_pull_request".to_string(),
+        description: r#"Pushes the current branch to `origin` and opens a GitHub pull request or GitLab merge request for it. Returns the URL.
+Commit your changes on a feature branch first; the branch must not be the base branch. Works without sandbox network access or an installed `gh`/`glab` CLI.
+"#
+        .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["title".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+});
+
+#[derive(Deserialize)]
+struct CreatePullRequestArgs {
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    base: Option<String>,
+    #[serde(default)]
+    draft: bool,
+}
+
+#[async_trait]
+impl ToolHandler for CreatePullRequestHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "create_pull_request handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: CreatePullRequestArgs = serde_json::from_str(&arguments).map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e}"))
+        })?;
+
+        // Pushing and opening a pull request is visible to others, so ask
+        // first unless the user opted out of approvals entirely.
+        if turn.approval_policy != AskForApproval::Never {
+            let decision = session
+                .request_command_approval(
+                    turn.as_ref(),
+                    call_id,
+                    vec![
+                        "git".to_string(),
+                        "push".to_string(),
+                        "origin".to_string(),
+                        "HEAD".to_string(),
+                    ],
+                    turn.cwd.clone(),
+                    Some(format!(
+                        "Push this branch and open a pull request: {}",
+                        args.title
+                    )),
+                    None,
+                )
+                .await;
+            match decision {
+                ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {}
+                ReviewDecision::Denied | ReviewDecision::Abort => {
+                    return Err(FunctionCallError::RespondToModel(
+                        "the user declined to open the pull request".to_string(),
+                    ));
+                }
+            }
+        }
+
+        let request = PullRequestRequest {
+            title: args.title,
+            body: args.body,
+            base: args.base,
+            draft: args.draft,
+        };
+        let created = create_pull_request(&turn.cwd, &DefaultKeyringStore, request)
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("failed to open pull request: {err:#}"))
+            })?;
+        let content = format!(
+            "Opened #{} ({} -> {}): {}",
+            created.number, created.head, created.base, created.url
+        );
+        session
+            .send_event(turn.as_ref(), EventMsg::PullRequestCreated(created))
+            .await;
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
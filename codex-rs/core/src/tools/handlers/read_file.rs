@@ -2,6 +2,8 @@ use std::collections::VecDeque;
 use std::path::PathBuf;
 
 use async_trait::async_trait;
+use codex_protocol::items::FileReadItem;
+use codex_protocol::items::TurnItem;
 use codex_utils_string::take_bytes_at_char_boundary;
 use serde::Deserialize;
 
@@ -96,7 +98,13 @@ impl ToolHandler for ReadFileHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -140,13 +148,21 @@ impl ToolHandler for ReadFileHandler {
             ));
         }
 
+        let item = TurnItem::FileRead(FileReadItem {
+            id: call_id,
+            path: path.clone(),
+        });
+        session.emit_turn_item_started(&turn, &item).await;
         let collected = match mode {
-            ReadMode::Slice => slice::read(&path, offset, limit).await?,
+            ReadMode::Slice => slice::read(&path, offset, limit).await,
             ReadMode::Indentation => {
                 let indentation = indentation.unwrap_or_default();
-                indentation::read_block(&path, offset, limit, indentation).await?
+                indentation::read_block(&path, offset, limit, indentation).await
             }
         };
+        session.emit_turn_item_completed(&turn, item).await;
+        let collected = collected?;
+        session.record_file_seen(&path).await;
         Ok(ToolOutput::Function {
             content: collected.join("\n"),
             content_items: None,
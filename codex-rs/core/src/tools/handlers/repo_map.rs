@@ -0,0 +1,64 @@
+use crate::client_common::tools::ResponsesApiTool;
+use crate::client_common::tools::ToolSpec;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::spec::JsonSchema;
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+pub struct RepoMapHandler;
+
+pub static REPO_MAP_TOOL: LazyLock<ToolSpec> = LazyLock::new(|| {
+    ToolSpec::Function(ResponsesApiTool {
+        name: "repo_map".to_string(),
+        description: r#"Returns an outline of the repository: its directory tree and the public symbols each source file declares.
+The outline given at the start of the conversation goes stale as files change; call this for a current one before exploring unfamiliar parts of the code.
+"#
+        .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties: BTreeMap::new(),
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+});
+
+#[async_trait]
+impl ToolHandler for RepoMapHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        if !matches!(payload, ToolPayload::Function { .. }) {
+            return Err(FunctionCallError::RespondToModel(
+                "repo_map handler received unsupported payload".to_string(),
+            ));
+        }
+
+        let Some(outline) = session.services.repo_map.refresh(&turn.cwd).await else {
+            return Err(FunctionCallError::RespondToModel(
+                "the working directory is not inside a git repository".to_string(),
+            ));
+        };
+
+        Ok(ToolOutput::Function {
+            content: outline.to_string(),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
@@ -1,17 +1,29 @@
 use async_trait::async_trait;
 use codex_protocol::models::ShellCommandToolCallParams;
 use codex_protocol::models::ShellToolCallParams;
+use codex_utils_pty::TerminalSize;
 use std::sync::Arc;
 
 use crate::apply_patch;
 use crate::apply_patch::InternalApplyPatchInvocation;
+use crate::apply_patch::PatchMerge;
 use crate::apply_patch::convert_apply_patch_to_protocol;
+use crate::apply_patch::patch_write_paths;
+use crate::bash::ScriptSegment;
+use crate::bash::SegmentJoin;
+use crate::bash::split_shell_lc_segments;
 use crate::codex::TurnContext;
 use crate::exec::ExecParams;
+use crate::exec::ExecToolCallOutput;
+use crate::exec::StreamOutput;
 use crate::exec_env::create_env;
 use crate::exec_policy::create_approval_requirement_for_command;
+use crate::features::Feature;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::check_pre_exec;
+use crate::hooks::post_patch_note;
 use crate::is_safe_command::is_known_safe_command;
+use crate::lsp::patch_diagnostics_note;
 use crate::protocol::ExecCommandSource;
 use crate::sandboxing::SandboxPermissions;
 use crate::tools::context::ToolInvocation;
@@ -26,7 +38,9 @@ use crate::tools::runtimes::apply_patch::ApplyPatchRequest;
 use crate::tools::runtimes::apply_patch::ApplyPatchRuntime;
 use crate::tools::runtimes::shell::ShellRequest;
 use crate::tools::runtimes::shell::ShellRuntime;
+use crate::tools::sandboxing::ApprovalRequirement;
 use crate::tools::sandboxing::ToolCtx;
+use crate::tools::sandboxing::ToolError;
 
 pub struct ShellHandler;
 
@@ -209,11 +223,28 @@ impl ShellHandler {
             )));
         }
 
+        turn.check_workdir(
+            &exec_params.cwd,
+            exec_params.with_escalated_permissions.unwrap_or(false),
+        )
+        .map_err(FunctionCallError::RespondToModel)?;
+
         // Intercept apply_patch if present.
-        match codex_apply_patch::maybe_parse_apply_patch_verified(
+        let (verified, merge) = apply_patch::verify_apply_patch(
+            session.as_ref(),
+            turn.as_ref(),
             &exec_params.command,
             &exec_params.cwd,
-        ) {
+        )
+        .await;
+        match verified {
+            codex_apply_patch::MaybeApplyPatchVerified::Body(_)
+                if !turn.tools_config.tool_filter.allows_builtin("apply_patch") =>
+            {
+                return Err(FunctionCallError::RespondToModel(
+                    "apply_patch is disabled for this conversation".to_string(),
+                ));
+            }
             codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
                 match apply_patch::apply_patch(session.as_ref(), turn.as_ref(), &call_id, changes)
                     .await
@@ -228,9 +259,19 @@ impl ShellHandler {
                         });
                     }
                     InternalApplyPatchInvocation::DelegateToExec(apply) => {
+                        // Held until the patch has been written.
+                        let _file_locks = session
+                            .lock_files_for_write(
+                                turn.as_ref(),
+                                &call_id,
+                                &patch_write_paths(&apply.action),
+                            )
+                            .await
+                            .map_err(|err| FunctionCallError::RespondToModel(err.to_string()))?;
                         let emitter = ToolEmitter::apply_patch(
                             convert_apply_patch_to_protocol(&apply.action),
                             !apply.user_explicitly_approved_this_action,
+                            merge.as_ref().is_some_and(PatchMerge::has_conflicts),
                         );
                         let event_ctx = ToolEventCtx::new(
                             session.as_ref(),
@@ -264,7 +305,31 @@ impl ShellHandler {
                             &call_id,
                             Some(&tracker),
                         );
-                        let content = emitter.finish(event_ctx, out).await?;
+                        let mut content = emitter.finish(event_ctx, out).await?;
+                        if let Some(merge) = &merge {
+                            content.insert_str(0, &merge.note());
+                        }
+                        let paths = patch_write_paths(&apply.action);
+                        if let Some(note) = post_patch_note(
+                            session.as_ref(),
+                            turn.as_ref(),
+                            &call_id,
+                            paths.clone(),
+                        )
+                        .await
+                        {
+                            content.push_str(&note);
+                        }
+                        if let Some(note) = patch_diagnostics_note(
+                            session.as_ref(),
+                            turn.as_ref(),
+                            &call_id,
+                            &paths,
+                        )
+                        .await
+                        {
+                            content.push_str(&note);
+                        }
                         return Ok(ToolOutput::Function {
                             content,
                             content_items: None,
@@ -287,6 +352,15 @@ impl ShellHandler {
             }
         }
 
+        check_pre_exec(
+            session.as_ref(),
+            turn.as_ref(),
+            &call_id,
+            &exec_params.command,
+            &exec_params.cwd,
+        )
+        .await?;
+
         let source = ExecCommandSource::Agent;
         let emitter = ToolEmitter::shell(
             exec_params.command.clone(),
@@ -297,32 +371,58 @@ impl ShellHandler {
         let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
         emitter.begin(event_ctx).await;
 
-        let req = ShellRequest {
-            command: exec_params.command.clone(),
-            cwd: exec_params.cwd.clone(),
-            timeout_ms: exec_params.timeout_ms,
-            env: exec_params.env.clone(),
-            with_escalated_permissions: exec_params.with_escalated_permissions,
-            justification: exec_params.justification.clone(),
-            approval_requirement: create_approval_requirement_for_command(
-                &turn.exec_policy,
-                &exec_params.command,
-                turn.approval_policy,
-                &turn.sandbox_policy,
-                SandboxPermissions::from(exec_params.with_escalated_permissions.unwrap_or(false)),
-            ),
-        };
-        let mut orchestrator = ToolOrchestrator::new();
-        let mut runtime = ShellRuntime::new();
+        let approved_by_rule = session
+            .approved_by_rule(&exec_params.command, &exec_params.cwd)
+            .await;
+        let sandbox_permissions =
+            SandboxPermissions::from(exec_params.with_escalated_permissions.unwrap_or(false));
+        let approval_requirement = create_approval_requirement_for_command(
+            &turn.exec_policy,
+            &exec_params.command,
+            turn.approval_policy,
+            &turn.sandbox_policy,
+            sandbox_permissions,
+            approved_by_rule,
+        );
         let tool_ctx = ToolCtx {
             session: session.as_ref(),
             turn: turn.as_ref(),
             call_id: call_id.clone(),
             tool_name: tool_name.to_string(),
         };
-        let out = orchestrator
-            .run(&mut runtime, &req, &tool_ctx, &turn, turn.approval_policy)
-            .await;
+        let pty = session.shell_pty_size().await;
+        let segments = if matches!(
+            approval_requirement,
+            ApprovalRequirement::NeedsApproval { .. }
+        ) && sandbox_permissions == SandboxPermissions::UseDefault
+            && session.enabled(Feature::SegmentedApproval).await
+        {
+            plan_segments(session.as_ref(), turn.as_ref(), &exec_params).await
+        } else {
+            None
+        };
+        let out = match segments {
+            Some(segments) => {
+                run_segments(&tool_ctx, turn.as_ref(), &exec_params, pty, segments).await
+            }
+            None => {
+                let req = ShellRequest {
+                    command: exec_params.command.clone(),
+                    cwd: exec_params.cwd.clone(),
+                    timeout_ms: exec_params.timeout_ms,
+                    env: exec_params.env.clone(),
+                    with_escalated_permissions: exec_params.with_escalated_permissions,
+                    justification: exec_params.justification.clone(),
+                    approval_requirement,
+                    pty,
+                };
+                let mut orchestrator = ToolOrchestrator::new();
+                let mut runtime = ShellRuntime::new();
+                orchestrator
+                    .run(&mut runtime, &req, &tool_ctx, &turn, turn.approval_policy)
+                    .await
+            }
+        };
         let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
         let content = emitter.finish(event_ctx, out).await?;
         Ok(ToolOutput::Function {
@@ -333,6 +433,133 @@ impl ShellHandler {
     }
 }
 
+/// A segment of a compound command together with its own approval decision.
+struct PlannedSegment {
+    segment: ScriptSegment,
+    command: Vec<String>,
+    approval_requirement: ApprovalRequirement,
+}
+
+/// Splits a command that needs approval into segments when only some of them
+/// do, so the user is asked about the risky parts alone. Returns `None` when
+/// the command should be approved as a whole.
+async fn plan_segments(
+    session: &crate::codex::Session,
+    turn: &TurnContext,
+    exec_params: &ExecParams,
+) -> Option<Vec<PlannedSegment>> {
+    let (shell, flag) = match exec_params.command.as_slice() {
+        [shell, flag, _] => (shell, flag),
+        _ => return None,
+    };
+    let mut planned = Vec::new();
+    for segment in split_shell_lc_segments(&exec_params.command)? {
+        let command = vec![shell.clone(), flag.clone(), segment.source.clone()];
+        let approved_by_rule = session.approved_by_rule(&command, &exec_params.cwd).await;
+        let approval_requirement = create_approval_requirement_for_command(
+            &turn.exec_policy,
+            &command,
+            turn.approval_policy,
+            &turn.sandbox_policy,
+            SandboxPermissions::UseDefault,
+            approved_by_rule,
+        );
+        planned.push(PlannedSegment {
+            segment,
+            command,
+            approval_requirement,
+        });
+    }
+    let needs_approval = |planned: &PlannedSegment| {
+        matches!(
+            planned.approval_requirement,
+            ApprovalRequirement::NeedsApproval { .. }
+        )
+    };
+    let mixed = planned.iter().any(needs_approval) && !planned.iter().all(needs_approval);
+    let forbidden = planned.iter().any(|planned| {
+        matches!(
+            planned.approval_requirement,
+            ApprovalRequirement::Forbidden { .. }
+        )
+    });
+    (mixed && !forbidden).then_some(planned)
+}
+
+/// Runs the segments in order with the script's `&&` semantics. A rejected
+/// segment stops the command; its error names the segment and carries the
+/// output of the segments that already ran.
+async fn run_segments(
+    tool_ctx: &ToolCtx<'_>,
+    turn: &TurnContext,
+    exec_params: &ExecParams,
+    pty: Option<TerminalSize>,
+    segments: Vec<PlannedSegment>,
+) -> Result<ExecToolCallOutput, ToolError> {
+    let total = segments.len();
+    let mut outputs: Vec<ExecToolCallOutput> = Vec::new();
+    let mut last_exit_code = 0;
+    let mut orchestrator = ToolOrchestrator::new();
+    let mut runtime = ShellRuntime::new();
+    for (index, planned) in segments.into_iter().enumerate() {
+        if planned.segment.join == SegmentJoin::And && last_exit_code != 0 {
+            continue;
+        }
+        let req = ShellRequest {
+            command: planned.command,
+            cwd: exec_params.cwd.clone(),
+            timeout_ms: exec_params.timeout_ms,
+            env: exec_params.env.clone(),
+            with_escalated_permissions: None,
+            justification: exec_params.justification.clone(),
+            approval_requirement: planned.approval_requirement,
+            pty,
+        };
+        match orchestrator
+            .run(&mut runtime, &req, tool_ctx, turn, turn.approval_policy)
+            .await
+        {
+            Ok(output) => {
+                last_exit_code = output.exit_code;
+                outputs.push(output);
+            }
+            Err(ToolError::Rejected(reason)) => {
+                let mut message = format!(
+                    "segment {} of {total} (`{}`) was rejected: {reason}; earlier segments ran and the remaining ones were skipped",
+                    index + 1,
+                    planned.segment.source
+                );
+                if let Some(output) = combine_outputs(outputs)
+                    && !output.aggregated_output.text.is_empty()
+                {
+                    message.push('\n');
+                    message.push_str(&output.aggregated_output.text);
+                }
+                return Err(ToolError::Rejected(message));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    combine_outputs(outputs)
+        .ok_or_else(|| ToolError::Rejected("no segment of the command was run".to_string()))
+}
+
+fn combine_outputs(outputs: Vec<ExecToolCallOutput>) -> Option<ExecToolCallOutput> {
+    let last_exit_code = outputs.last()?.exit_code;
+    let join = |text: fn(&ExecToolCallOutput) -> &str| {
+        StreamOutput::new(outputs.iter().map(text).collect::<String>())
+    };
+    Some(ExecToolCallOutput {
+        exit_code: last_exit_code,
+        stdout: join(|output| &output.stdout.text),
+        stderr: join(|output| &output.stderr.text),
+        aggregated_output: join(|output| &output.aggregated_output.text),
+        duration: outputs.iter().map(|output| output.duration).sum(),
+        timed_out: outputs.iter().any(|output| output.timed_out),
+        output_truncation: outputs.iter().find_map(|output| output.output_truncation),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
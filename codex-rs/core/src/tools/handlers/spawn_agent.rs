@@ -0,0 +1,301 @@
+use crate::client_common::tools::ResponsesApiTool;
+use crate::client_common::tools::ToolSpec;
+use crate::codex::CodexSpawnOk;
+use crate::codex_delegate::run_codex_conversation_one_shot;
+use crate::config::Config;
+use crate::features::Feature;
+use crate::function_tool::FunctionCallError;
+use crate::model_family::derive_default_model_family;
+use crate::model_family::find_family_for_model;
+use crate::openai_model_info::get_model_info;
+use crate::protocol::EventMsg;
+use crate::protocol::SandboxPolicy;
+use crate::protocol::SubAgentStatus;
+use crate::protocol::SubAgentUpdateEvent;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::spec::JsonSchema;
+use async_trait::async_trait;
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::protocol::SubAgentSource;
+use codex_protocol::user_input::UserInput;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+use tokio_util::sync::CancellationToken;
+
+/// Label sent as the sub-agent source of spawned conversations.
+const SPAWN_AGENT_SOURCE: &str = "spawn_agent";
+
+pub struct SpawnAgentHandler;
+
+pub static SPAWN_AGENT_TOOL: LazyLock<ToolSpec> = LazyLock::new(|| {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "prompt".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Complete instructions for the sub-agent. It does not see this conversation."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "model".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Model for the sub-agent. Defaults to the current model.".to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "sandbox_mode".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "One of \"read-only\", \"workspace-write\", \"danger-full-access\", or \"audit\". May only be as strict as or stricter than the current sandbox. Defaults to the current sandbox."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "token_budget".to_string(),
+        JsonSchema::Number {
+            description: Some(
+                "Maximum tokens the sub-agent may use. Capped by this conversation's budget."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "spawn_agent".to_string(),
+        description: r#"Starts a sub-agent: a fresh conversation that works on the given prompt in the same working directory and returns its final message.
+Use it to hand off self-contained pieces of work, for example to investigate a question or implement a well-specified change. Several sub-agents can run at the same time.
+Sub-agents cannot spawn further sub-agents.
+"#
+        .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["prompt".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+});
+
+#[derive(Deserialize)]
+struct SpawnAgentArgs {
+    prompt: String,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    sandbox_mode: Option<SandboxMode>,
+    #[serde(default)]
+    token_budget: Option<i64>,
+}
+
+#[async_trait]
+impl ToolHandler for SpawnAgentHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "spawn_agent handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: SpawnAgentArgs = serde_json::from_str(&arguments).map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e}"))
+        })?;
+
+        let mut config = turn.client.config().as_ref().clone();
+        config.cwd = turn.cwd.clone();
+        config.approval_policy = turn.approval_policy;
+        config.sandbox_policy = restrict_sandbox(&turn.sandbox_policy, args.sandbox_mode)
+            .map_err(FunctionCallError::RespondToModel)?;
+        if let Some(model) = args.model {
+            set_model(&mut config, model);
+        }
+        config.conversation_token_budget =
+            match (args.token_budget, config.conversation_token_budget) {
+                (Some(requested), Some(parent)) => Some(requested.min(parent)),
+                (requested, parent) => requested.or(parent),
+            };
+        config.features.disable(Feature::SpawnAgentTool);
+        let model = config.model.clone();
+
+        // Dropping the handler's future (e.g. on interrupt) cancels the child.
+        let cancel_token = CancellationToken::new();
+        let _cancel_on_drop = cancel_token.clone().drop_guard();
+        let CodexSpawnOk {
+            codex,
+            conversation_id,
+        } = run_codex_conversation_one_shot(
+            config,
+            session.auth_manager(),
+            vec![UserInput::Text { text: args.prompt }],
+            session.clone(),
+            turn.clone(),
+            cancel_token,
+            None,
+            SubAgentSource::Other(SPAWN_AGENT_SOURCE.to_string()),
+        )
+        .await
+        .map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to start sub-agent: {err}"))
+        })?;
+
+        let update = |status, message| {
+            EventMsg::SubAgentUpdate(SubAgentUpdateEvent {
+                call_id: call_id.clone(),
+                conversation_id,
+                model: model.clone(),
+                status,
+                message,
+            })
+        };
+        session
+            .send_event(turn.as_ref(), update(SubAgentStatus::Running, None))
+            .await;
+
+        let mut error = None;
+        let result = loop {
+            let Ok(event) = codex.next_event().await else {
+                break Err(error.unwrap_or_else(|| "sub-agent ended before finishing".to_string()));
+            };
+            match event.msg {
+                EventMsg::TaskComplete(event) => {
+                    break match error {
+                        Some(error) => Err(error),
+                        None => Ok(event.last_agent_message.unwrap_or_default()),
+                    };
+                }
+                EventMsg::TurnAborted(event) => {
+                    break Err(format!("sub-agent turn aborted: {:?}", event.reason));
+                }
+                EventMsg::Error(event) => error = Some(event.message),
+                _ => {}
+            }
+        };
+
+        let (status, content, success) = match result {
+            Ok(message) => (SubAgentStatus::Completed, message, true),
+            Err(error) => (SubAgentStatus::Failed, error, false),
+        };
+        session
+            .send_event(turn.as_ref(), update(status, Some(content.clone())))
+            .await;
+
+        Ok(ToolOutput::Function {
+            content,
+            content_items: None,
+            success: Some(success),
+        })
+    }
+}
+
+fn set_model(config: &mut Config, model: String) {
+    config.model_family =
+        find_family_for_model(&model).unwrap_or_else(|| derive_default_model_family(&model));
+    config.model_context_window =
+        get_model_info(&config.model_family).map(|info| info.context_window);
+    config.model = model;
+}
+
+/// The child's sandbox: the parent's, or a stricter one when requested.
+fn restrict_sandbox(
+    parent: &SandboxPolicy,
+    requested: Option<SandboxMode>,
+) -> Result<SandboxPolicy, String> {
+    let Some(requested) = requested else {
+        return Ok(parent.clone());
+    };
+    let parent_mode = match parent {
+        SandboxPolicy::ReadOnly => SandboxMode::ReadOnly,
+        SandboxPolicy::WorkspaceWrite { .. } => SandboxMode::WorkspaceWrite,
+        SandboxPolicy::DangerFullAccess => SandboxMode::DangerFullAccess,
+        SandboxPolicy::Audit => SandboxMode::Audit,
+    };
+    if strictness(requested) < strictness(parent_mode) {
+        return Err(format!(
+            "sub-agents cannot use a looser sandbox than this conversation ({parent_mode})"
+        ));
+    }
+    Ok(match requested {
+        _ if requested == parent_mode => parent.clone(),
+        SandboxMode::ReadOnly => SandboxPolicy::new_read_only_policy(),
+        SandboxMode::WorkspaceWrite => SandboxPolicy::new_workspace_write_policy(),
+        SandboxMode::DangerFullAccess => SandboxPolicy::DangerFullAccess,
+        SandboxMode::Audit => SandboxPolicy::new_audit_policy(),
+    })
+}
+
+fn strictness(mode: SandboxMode) -> u8 {
+    match mode {
+        SandboxMode::DangerFullAccess => 0,
+        SandboxMode::WorkspaceWrite => 1,
+        SandboxMode::ReadOnly => 2,
+        SandboxMode::Audit => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn sandbox_can_only_be_tightened() {
+        let workspace_write = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec!["/extra".into()],
+            network_access: true,
+            exclude_tmpdir_env_var: false,
+            exclude_slash_tmp: false,
+        };
+
+        assert_eq!(
+            restrict_sandbox(&workspace_write, None),
+            Ok(workspace_write.clone())
+        );
+        assert_eq!(
+            restrict_sandbox(&workspace_write, Some(SandboxMode::WorkspaceWrite)),
+            Ok(workspace_write.clone())
+        );
+        assert_eq!(
+            restrict_sandbox(&workspace_write, Some(SandboxMode::ReadOnly)),
+            Ok(SandboxPolicy::new_read_only_policy())
+        );
+        assert!(restrict_sandbox(&workspace_write, Some(SandboxMode::DangerFullAccess)).is_err());
+        assert!(
+            restrict_sandbox(
+                &SandboxPolicy::new_read_only_policy(),
+                Some(SandboxMode::WorkspaceWrite)
+            )
+            .is_err()
+        );
+        assert_eq!(
+            restrict_sandbox(
+                &SandboxPolicy::DangerFullAccess,
+                Some(SandboxMode::WorkspaceWrite)
+            ),
+            Ok(SandboxPolicy::new_workspace_write_policy())
+        );
+    }
+}
@@ -1,12 +1,16 @@
 use std::path::PathBuf;
 
+use crate::config::types::ExecOutputConfig;
+use crate::exec_output_cleanup::clean_output;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::check_pre_exec;
 use crate::is_safe_command::is_known_safe_command;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandOutputDeltaEvent;
 use crate::protocol::ExecCommandSource;
 use crate::protocol::ExecOutputStream;
 use crate::shell::get_shell_by_model_provided_path;
+use crate::test_failures::summarize_test_failures;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -121,6 +125,8 @@ impl ToolHandler for UnifiedExecHandler {
         let manager: &UnifiedExecSessionManager = &session.services.unified_exec_manager;
         let context = UnifiedExecContext::new(session.clone(), turn.clone(), call_id.clone());
 
+        // Only a command started by this call can be summarized as a test run.
+        let mut started_command = None;
         let response = match tool_name.as_str() {
             "exec_command" => {
                 let args: ExecCommandArgs = serde_json::from_str(&arguments).map_err(|err| {
@@ -130,6 +136,7 @@ impl ToolHandler for UnifiedExecHandler {
                 })?;
 
                 let command = get_command(&args);
+                started_command = Some(command.clone());
                 let ExecCommandArgs {
                     workdir,
                     yield_time_ms,
@@ -151,11 +158,21 @@ impl ToolHandler for UnifiedExecHandler {
                     )));
                 }
 
-                let workdir = workdir
-                    .as_deref()
-                    .filter(|value| !value.is_empty())
-                    .map(PathBuf::from);
-                let cwd = workdir.clone().unwrap_or_else(|| context.turn.cwd.clone());
+                let cwd = context
+                    .turn
+                    .resolve_path(workdir.filter(|value| !value.is_empty()));
+                context
+                    .turn
+                    .check_workdir(&cwd, with_escalated_permissions.unwrap_or(false))
+                    .map_err(FunctionCallError::RespondToModel)?;
+                check_pre_exec(
+                    context.session.as_ref(),
+                    context.turn.as_ref(),
+                    &context.call_id,
+                    &command,
+                    &cwd,
+                )
+                .await?;
 
                 let event_ctx = ToolEventCtx::new(
                     context.session.as_ref(),
@@ -177,7 +194,7 @@ impl ToolHandler for UnifiedExecHandler {
                             command,
                             yield_time_ms,
                             max_output_tokens,
-                            workdir,
+                            workdir: Some(cwd.clone()),
                             with_escalated_permissions,
                             justification,
                         },
@@ -226,7 +243,11 @@ impl ToolHandler for UnifiedExecHandler {
                 .await;
         }
 
-        let content = format_response(&response);
+        let content = format_response(
+            &response,
+            started_command.as_deref(),
+            &turn.client.config().exec_output,
+        );
 
         Ok(ToolOutput::Function {
             content,
@@ -241,7 +262,11 @@ fn get_command(args: &ExecCommandArgs) -> Vec<String> {
     shell.derive_exec_args(&args.cmd, args.login)
 }
 
-fn format_response(response: &UnifiedExecResponse) -> String {
+fn format_response(
+    response: &UnifiedExecResponse,
+    command: Option<&[String]>,
+    exec_output: &ExecOutputConfig,
+) -> String {
     let mut sections = Vec::new();
 
     if !response.chunk_id.is_empty() {
@@ -263,8 +288,16 @@ fn format_response(response: &UnifiedExecResponse) -> String {
         sections.push(format!("Original token count: {original_token_count}"));
     }
 
+    let output = clean_output(&response.output, exec_output);
+    if let Some(command) = command
+        && response.exit_code.is_some_and(|exit_code| exit_code != 0)
+        && let Some(test_failures) = summarize_test_failures(command, &output)
+    {
+        sections.push(test_failures.render());
+    }
+
     sections.push("Output:".to_string());
-    sections.push(response.output.clone());
+    sections.push(output.into_owned());
 
     sections.join("\n")
 }
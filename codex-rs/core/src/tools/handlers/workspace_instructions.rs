@@ -0,0 +1,105 @@
+use crate::client_common::tools::ResponsesApiTool;
+use crate::client_common::tools::ToolSpec;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::spec::JsonSchema;
+use async_trait::async_trait;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::WorkspaceInstructionProposedEvent;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+pub struct WorkspaceInstructionHandler;
+
+pub static PROPOSE_WORKSPACE_INSTRUCTION_TOOL: LazyLock<ToolSpec> = LazyLock::new(|| {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "instruction".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "The rule to remember, phrased as a standalone imperative sentence.".to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "propose_workspace_instruction".to_string(),
+        description: r#"Proposes saving an explicit user correction as a workspace instruction that applies to all future sessions in this project.
+Only call this when the user states a lasting rule (for example "never touch generated/ files" or "always run tests with -j4"), not for one-off requests.
+The user must confirm the proposal before it is saved.
+"#
+        .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["instruction".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+});
+
+#[derive(Deserialize)]
+struct ProposeWorkspaceInstructionArgs {
+    instruction: String,
+}
+
+#[async_trait]
+impl ToolHandler for WorkspaceInstructionHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "propose_workspace_instruction handler received unsupported payload"
+                        .to_string(),
+                ));
+            }
+        };
+
+        let args: ProposeWorkspaceInstructionArgs =
+            serde_json::from_str(&arguments).map_err(|e| {
+                FunctionCallError::RespondToModel(format!(
+                    "failed to parse function arguments: {e}"
+                ))
+            })?;
+        let instruction = args.instruction.trim().to_string();
+        if instruction.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "instruction must not be empty".to_string(),
+            ));
+        }
+
+        session
+            .send_event(
+                turn.as_ref(),
+                EventMsg::WorkspaceInstructionProposed(WorkspaceInstructionProposedEvent {
+                    call_id,
+                    instruction,
+                }),
+            )
+            .await;
+
+        Ok(ToolOutput::Function {
+            content: "Proposed to the user. If they confirm, it will apply to future sessions; keep following it for the rest of this session.".to_string(),
+            content_items: None,
+            success: Some(true),
+        })
+    }
+}
@@ -10,6 +10,7 @@ pub mod sandboxing;
 pub mod spec;
 
 use crate::exec::ExecToolCallOutput;
+use crate::test_failures::TestFailureSummary;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::formatted_truncate_text;
 use crate::truncate::truncate_text;
@@ -23,9 +24,11 @@ pub(crate) const TELEMETRY_PREVIEW_TRUNCATION_NOTICE: &str =
     "[... telemetry preview truncated ...]";
 
 /// Format the combined exec output for sending back to the model.
-/// Includes exit code and duration metadata; truncates large bodies safely.
-pub fn format_exec_output_for_model_structured(
+/// Includes exit code and duration metadata, and the failed tests when the
+/// command was a failed test run; truncates large bodies safely.
+pub(crate) fn format_exec_output_for_model_structured(
     exec_output: &ExecToolCallOutput,
+    test_failures: Option<&TestFailureSummary>,
     truncation_policy: TruncationPolicy,
 ) -> String {
     let ExecToolCallOutput {
@@ -44,6 +47,8 @@ pub fn format_exec_output_for_model_structured(
     struct ExecOutput<'a> {
         output: &'a str,
         metadata: ExecMetadata,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        test_failures: Option<&'a TestFailureSummary>,
     }
 
     // round to 1 decimal place
@@ -57,14 +62,16 @@ pub fn format_exec_output_for_model_structured(
             exit_code: *exit_code,
             duration_seconds,
         },
+        test_failures,
     };
 
     #[expect(clippy::expect_used)]
     serde_json::to_string(&payload).expect("serialize ExecOutput")
 }
 
-pub fn format_exec_output_for_model_freeform(
+pub(crate) fn format_exec_output_for_model_freeform(
     exec_output: &ExecToolCallOutput,
+    test_failures: Option<&TestFailureSummary>,
     truncation_policy: TruncationPolicy,
 ) -> String {
     // round to 1 decimal place
@@ -82,6 +89,10 @@ pub fn format_exec_output_for_model_freeform(
         sections.push(format!("Total output lines: {total_lines}"));
     }
 
+    if let Some(test_failures) = test_failures {
+        sections.push(test_failures.render());
+    }
+
     sections.push("Output:".to_string());
     sections.push(formatted_output);
 
@@ -3,7 +3,8 @@ Module: orchestrator
 
 Central place for approvals + sandbox selection + retry semantics. Drives a
 simple sequence for any ToolRuntime: approval → select sandbox → attempt →
-retry without sandbox on denial (no re‑approval thanks to caching).
+on denial, widen the sandbox's write scope if the user grants it, otherwise
+retry without sandbox (no re‑approval thanks to caching).
 */
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
@@ -18,8 +19,12 @@ use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::default_approval_requirement;
+use crate::write_grants::denied_write_paths;
+use crate::write_grants::grant_root_for;
+use codex_protocol::items::BlockedAction;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::SandboxPolicy;
 
 pub(crate) struct ToolOrchestrator {
     sandbox: SandboxManager,
@@ -103,17 +108,50 @@ impl ToolOrchestrator {
         let mut initial_sandbox = self
             .sandbox
             .select_initial(&turn_ctx.sandbox_policy, tool.sandbox_preference());
-        if tool.wants_escalated_first_attempt(req) {
+        let escalated_first_attempt = tool.wants_escalated_first_attempt(req);
+        if escalated_first_attempt {
             initial_sandbox = crate::exec::SandboxType::None;
         }
+        // Audit mode only runs commands the platform sandbox can confine.
+        let audit = turn_ctx.sandbox_policy.is_audit();
+        if audit && initial_sandbox == crate::exec::SandboxType::None {
+            let reason = if escalated_first_attempt {
+                "commands cannot run outside the sandbox in audit mode"
+            } else {
+                "audit mode requires a platform sandbox, which is unavailable here"
+            };
+            tool_ctx
+                .session
+                .emit_blocked_action(
+                    turn_ctx,
+                    &tool_ctx.call_id,
+                    BlockedAction::UnsandboxedCommand,
+                    reason,
+                )
+                .await;
+            return Err(ToolError::Rejected(reason.to_string()));
+        }
+        // Commands the user explicitly let run without restrictions also
+        // bypass the egress proxy.
+        let network_proxy = match turn_ctx.sandbox_policy {
+            SandboxPolicy::DangerFullAccess => None,
+            _ if escalated_first_attempt => None,
+            _ => tool_ctx.session.services.network_proxy.as_ref(),
+        };
+        // Roots the user made writable on demand widen `workspace-write`.
+        let sandbox_policy = tool_ctx
+            .session
+            .sandbox_policy_with_grants(&turn_ctx.sandbox_policy)
+            .await;
         // Platform-specific flag gating is handled by SandboxManager::select_initial
         // via crate::safety::get_platform_sandbox().
         let initial_attempt = SandboxAttempt {
             sandbox: initial_sandbox,
-            policy: &turn_ctx.sandbox_policy,
+            policy: &sandbox_policy,
             manager: &self.sandbox,
             sandbox_cwd: &turn_ctx.cwd,
             codex_linux_sandbox_exe: turn_ctx.codex_linux_sandbox_exe.as_ref(),
+            network_proxy,
         };
 
         match tool.run(req, &initial_attempt, tool_ctx).await {
@@ -122,6 +160,51 @@ impl ToolOrchestrator {
                 Ok(out)
             }
             Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output }))) => {
+                if audit {
+                    tool_ctx
+                        .session
+                        .emit_blocked_action(
+                            turn_ctx,
+                            &tool_ctx.call_id,
+                            BlockedAction::SandboxDenied,
+                            "the sandbox blocked a write or network access in audit mode",
+                        )
+                        .await;
+                    return Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied {
+                        output,
+                    })));
+                }
+                // Prefer widening the sandbox to the directory the command
+                // tried to write over dropping the sandbox altogether.
+                match request_write_scope(
+                    req,
+                    tool_ctx,
+                    turn_ctx,
+                    approval_policy,
+                    initial_sandbox,
+                    &sandbox_policy,
+                    &output,
+                )
+                .await
+                {
+                    WriteScopeOutcome::Granted(widened_policy) => {
+                        let widened_attempt = SandboxAttempt {
+                            sandbox: initial_sandbox,
+                            policy: &widened_policy,
+                            manager: &self.sandbox,
+                            sandbox_cwd: &turn_ctx.cwd,
+                            codex_linux_sandbox_exe: turn_ctx.codex_linux_sandbox_exe.as_ref(),
+                            network_proxy,
+                        };
+                        return tool.run(req, &widened_attempt, tool_ctx).await;
+                    }
+                    WriteScopeOutcome::Denied => {
+                        return Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied {
+                            output,
+                        })));
+                    }
+                    WriteScopeOutcome::NotRequested => {}
+                }
                 if !tool.escalate_on_failure() {
                     return Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied {
                         output,
@@ -179,10 +262,11 @@ impl ToolOrchestrator {
 
                 let escalated_attempt = SandboxAttempt {
                     sandbox: crate::exec::SandboxType::None,
-                    policy: &turn_ctx.sandbox_policy,
+                    policy: &sandbox_policy,
                     manager: &self.sandbox,
                     sandbox_cwd: &turn_ctx.cwd,
                     codex_linux_sandbox_exe: None,
+                    network_proxy: None,
                 };
 
                 // Second attempt.
@@ -193,6 +277,57 @@ impl ToolOrchestrator {
     }
 }
 
+enum WriteScopeOutcome {
+    /// The denial did not name paths that could be granted, or the approval
+    /// policy does not allow asking.
+    NotRequested,
+    Granted(SandboxPolicy),
+    Denied,
+}
+
+/// When the `sandbox` a `workspace-write` command ran in denied it writing
+/// outside the writable roots, ask the user to make the directory it
+/// targeted writable.
+async fn request_write_scope<Rq: ProvidesSandboxRetryData>(
+    req: &Rq,
+    tool_ctx: &ToolCtx<'_>,
+    turn_ctx: &crate::codex::TurnContext,
+    approval_policy: AskForApproval,
+    sandbox: crate::exec::SandboxType,
+    sandbox_policy: &SandboxPolicy,
+    output: &ExecToolCallOutput,
+) -> WriteScopeOutcome {
+    if approval_policy == AskForApproval::Never
+        || !matches!(sandbox_policy, SandboxPolicy::WorkspaceWrite { .. })
+    {
+        return WriteScopeOutcome::NotRequested;
+    }
+    let paths = denied_write_paths(output, sandbox, sandbox_policy, &turn_ctx.cwd);
+    let Some(root) = grant_root_for(&paths) else {
+        return WriteScopeOutcome::NotRequested;
+    };
+    let session = tool_ctx.session;
+    let decision = session
+        .request_write_scope(
+            turn_ctx,
+            tool_ctx.call_id.clone(),
+            root.clone(),
+            paths,
+            req.sandbox_retry_data().map(|data| data.command),
+            Some("command was denied writing outside the writable roots".to_string()),
+        )
+        .await;
+    if !decision.is_granted() {
+        return WriteScopeOutcome::Denied;
+    }
+    session.grant_write_root(root, decision).await;
+    WriteScopeOutcome::Granted(
+        session
+            .sandbox_policy_with_grants(&turn_ctx.sandbox_policy)
+            .await,
+    )
+}
+
 fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
     // Keep approval reason terse and stable for UX/tests, but accept the
     // output so we can evolve heuristics later without touching call sites.
@@ -5,6 +5,7 @@ use crate::client_common::tools::ToolSpec;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
+use crate::plugins::PluginHost;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolPayload;
@@ -12,6 +13,7 @@ use crate::tools::registry::ConfiguredToolSpec;
 use crate::tools::registry::ToolRegistry;
 use crate::tools::spec::ToolsConfig;
 use crate::tools::spec::build_specs;
+use crate::tools::spec::register_plugin_tools;
 use codex_protocol::models::LocalShellAction;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
@@ -33,8 +35,12 @@ impl ToolRouter {
     pub fn from_config(
         config: &ToolsConfig,
         mcp_tools: Option<HashMap<String, mcp_types::Tool>>,
+        plugins: Option<&PluginHost>,
     ) -> Self {
-        let builder = build_specs(config, mcp_tools);
+        let mut builder = build_specs(config, mcp_tools);
+        if let Some(plugins) = plugins {
+            register_plugin_tools(&mut builder, config, plugins);
+        }
         let (specs, registry) = builder.build();
 
         Self { registry, specs }
@@ -56,8 +62,9 @@ impl ToolRouter {
 
     pub async fn build_tool_call(
         session: &Session,
-        item: ResponseItem,
+        mut item: ResponseItem,
     ) -> Result<Option<ToolCall>, FunctionCallError> {
+        session.restore_redacted_secrets(&mut item).await;
         match item {
             ResponseItem::FunctionCall {
                 name,
@@ -75,11 +75,16 @@ impl ApplyPatchRuntime {
         })
     }
 
-    fn stdout_stream(ctx: &ToolCtx<'_>) -> Option<crate::exec::StdoutStream> {
+    async fn stdout_stream(ctx: &ToolCtx<'_>) -> Option<crate::exec::StdoutStream> {
         Some(crate::exec::StdoutStream {
             sub_id: ctx.turn.sub_id.clone(),
             call_id: ctx.call_id.clone(),
             tx_event: ctx.session.get_tx_event(),
+            policy: ctx.session.services.exec_output,
+            archive_dir: ctx
+                .session
+                .exec_output_archive_dir(ctx.turn, &ctx.call_id)
+                .await,
         })
     }
 }
@@ -155,7 +160,7 @@ impl ToolRuntime<ApplyPatchRequest, ExecToolCallOutput> for ApplyPatchRuntime {
         let env = attempt
             .env_for(&spec)
             .map_err(|err| ToolError::Codex(err.into()))?;
-        let out = execute_env(&env, attempt.policy, Self::stdout_stream(ctx))
+        let out = execute_env(&env, attempt.policy, Self::stdout_stream(ctx).await)
             .await
             .map_err(ToolError::Codex)?;
         Ok(out)
@@ -6,6 +6,7 @@ builds a CommandSpec, and runs it under the current SandboxAttempt.
 */
 use crate::exec::ExecToolCallOutput;
 use crate::sandboxing::execute_env;
+use crate::spawn::StdioPolicy;
 use crate::tools::runtimes::build_command_spec;
 use crate::tools::sandboxing::Approvable;
 use crate::tools::sandboxing::ApprovalCtx;
@@ -20,6 +21,7 @@ use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::with_cached_approval;
 use codex_protocol::protocol::ReviewDecision;
+use codex_utils_pty::TerminalSize;
 use futures::future::BoxFuture;
 use std::path::PathBuf;
 
@@ -32,6 +34,8 @@ pub struct ShellRequest {
     pub with_escalated_permissions: Option<bool>,
     pub justification: Option<String>,
     pub approval_requirement: ApprovalRequirement,
+    /// When set, the command runs attached to a PTY of this size.
+    pub pty: Option<TerminalSize>,
 }
 
 impl ProvidesSandboxRetryData for ShellRequest {
@@ -58,11 +62,16 @@ impl ShellRuntime {
         Self
     }
 
-    fn stdout_stream(ctx: &ToolCtx<'_>) -> Option<crate::exec::StdoutStream> {
+    async fn stdout_stream(ctx: &ToolCtx<'_>) -> Option<crate::exec::StdoutStream> {
         Some(crate::exec::StdoutStream {
             sub_id: ctx.turn.sub_id.clone(),
             call_id: ctx.call_id.clone(),
             tx_event: ctx.session.get_tx_event(),
+            policy: ctx.session.services.exec_output,
+            archive_dir: ctx
+                .session
+                .exec_output_archive_dir(ctx.turn, &ctx.call_id)
+                .await,
         })
     }
 }
@@ -137,10 +146,13 @@ impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
             req.with_escalated_permissions,
             req.justification.clone(),
         )?;
-        let env = attempt
+        let mut env = attempt
             .env_for(&spec)
             .map_err(|err| ToolError::Codex(err.into()))?;
-        let out = execute_env(&env, attempt.policy, Self::stdout_stream(ctx))
+        if let Some(size) = req.pty {
+            env.stdio_policy = StdioPolicy::Pty(size);
+        }
+        let out = execute_env(&env, attempt.policy, Self::stdout_stream(ctx).await)
             .await
             .map_err(ToolError::Codex)?;
         Ok(out)
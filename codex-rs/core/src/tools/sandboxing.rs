@@ -7,6 +7,7 @@
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::error::CodexErr;
+use crate::network_proxy::NetworkProxy;
 use crate::protocol::SandboxCommandAssessment;
 use crate::protocol::SandboxPolicy;
 use crate::sandboxing::CommandSpec;
@@ -211,6 +212,9 @@ pub(crate) struct SandboxAttempt<'a> {
     pub(crate) manager: &'a SandboxManager,
     pub(crate) sandbox_cwd: &'a Path,
     pub codex_linux_sandbox_exe: Option<&'a std::path::PathBuf>,
+    /// Egress proxy that the command's proxy variables point at and that the
+    /// platform sandbox, where it can, makes the only reachable destination.
+    pub(crate) network_proxy: Option<&'a NetworkProxy>,
 }
 
 impl<'a> SandboxAttempt<'a> {
@@ -218,12 +222,17 @@ impl<'a> SandboxAttempt<'a> {
         &self,
         spec: &CommandSpec,
     ) -> Result<crate::sandboxing::ExecEnv, SandboxTransformError> {
-        self.manager.transform(
+        let mut env = self.manager.transform(
             spec,
             self.policy,
             self.sandbox,
             self.sandbox_cwd,
             self.codex_linux_sandbox_exe,
-        )
+            self.network_proxy.map(NetworkProxy::port),
+        )?;
+        if let Some(proxy) = self.network_proxy {
+            env.env.extend(proxy.env());
+        }
+        Ok(env)
     }
 }
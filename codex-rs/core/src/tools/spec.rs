@@ -3,7 +3,17 @@ use crate::client_common::tools::ToolSpec;
 use crate::features::Feature;
 use crate::features::Features;
 use crate::model_family::ModelFamily;
+use crate::plugins::PluginHost;
+use crate::plugins::PluginTool;
+use crate::tool_filter::ToolFilter;
+use crate::tools::handlers::ASK_USER_TOOL;
+use crate::tools::handlers::BACKGROUND_TASK_OUTPUT_TOOL;
+use crate::tools::handlers::CODE_SEARCH_TOOL;
+use crate::tools::handlers::CREATE_PULL_REQUEST_TOOL;
 use crate::tools::handlers::PLAN_TOOL;
+use crate::tools::handlers::PROPOSE_WORKSPACE_INSTRUCTION_TOOL;
+use crate::tools::handlers::REPO_MAP_TOOL;
+use crate::tools::handlers::SPAWN_AGENT_TOOL;
 use crate::tools::handlers::apply_patch::ApplyPatchToolType;
 use crate::tools::handlers::apply_patch::create_apply_patch_freeform_tool;
 use crate::tools::handlers::apply_patch::create_apply_patch_json_tool;
@@ -35,7 +45,16 @@ pub(crate) struct ToolsConfig {
     pub apply_patch_tool_type: Option<ApplyPatchToolType>,
     pub web_search_request: bool,
     pub include_view_image_tool: bool,
+    pub include_workspace_instruction_tool: bool,
+    pub include_background_task_tool: bool,
+    pub include_spawn_agent_tool: bool,
+    pub include_pull_request_tool: bool,
+    pub include_repo_map_tool: bool,
+    pub include_code_search_tool: bool,
+    pub include_ask_user_tool: bool,
     pub experimental_supported_tools: Vec<String>,
+    /// Tools withheld from the model; set from `Config::tool_filter`.
+    pub tool_filter: ToolFilter,
 }
 
 pub(crate) struct ToolsConfigParams<'a> {
@@ -52,6 +71,13 @@ impl ToolsConfig {
         let include_apply_patch_tool = features.enabled(Feature::ApplyPatchFreeform);
         let include_web_search_request = features.enabled(Feature::WebSearchRequest);
         let include_view_image_tool = features.enabled(Feature::ViewImageTool);
+        let include_workspace_instruction_tool = features.enabled(Feature::WorkspaceInstructions);
+        let include_background_task_tool = features.enabled(Feature::BackgroundTaskTool);
+        let include_spawn_agent_tool = features.enabled(Feature::SpawnAgentTool);
+        let include_pull_request_tool = features.enabled(Feature::PullRequestTool);
+        let include_repo_map_tool = features.enabled(Feature::RepoMap);
+        let include_code_search_tool = features.enabled(Feature::CodeSearch);
+        let include_ask_user_tool = features.enabled(Feature::AskUserTool);
 
         let shell_type = if !features.enabled(Feature::ShellTool) {
             ConfigShellToolType::Disabled
@@ -80,7 +106,15 @@ impl ToolsConfig {
             apply_patch_tool_type,
             web_search_request: include_web_search_request,
             include_view_image_tool,
+            include_workspace_instruction_tool,
+            include_background_task_tool,
+            include_spawn_agent_tool,
+            include_pull_request_tool,
+            include_repo_map_tool,
+            include_code_search_tool,
+            include_ask_user_tool,
             experimental_supported_tools: model_family.experimental_supported_tools.clone(),
+            tool_filter: ToolFilter::default(),
         }
     }
 }
@@ -776,6 +810,9 @@ fn create_read_mcp_resource_tool() -> ToolSpec {
 #[derive(Serialize, Deserialize)]
 pub(crate) struct ApplyPatchToolArgs {
     pub(crate) input: String,
+    /// Directory the patch paths are relative to; defaults to the turn cwd.
+    #[serde(default)]
+    pub(crate) workdir: Option<String>,
 }
 
 /// Returns JSON values that are compatible with Function Calling in the
@@ -859,6 +896,29 @@ pub(crate) fn mcp_tool_to_openai_tool(
     })
 }
 
+fn plugin_tool_to_openai_tool(tool: PluginTool) -> Result<ResponsesApiTool, serde_json::Error> {
+    let PluginTool {
+        name,
+        description,
+        mut parameters,
+        ..
+    } = tool;
+
+    // As for MCP tools, the model requires a top-level "properties".
+    if let JsonValue::Object(map) = &mut parameters {
+        map.entry("properties")
+            .or_insert_with(|| JsonValue::Object(serde_json::Map::new()));
+    }
+    sanitize_json_schema(&mut parameters);
+
+    Ok(ResponsesApiTool {
+        name,
+        description,
+        strict: false,
+        parameters: serde_json::from_value::<JsonSchema>(parameters)?,
+    })
+}
+
 /// Sanitize a JSON Schema (as serde_json::Value) so it can fit our limited
 /// JsonSchema enum. This function:
 /// - Ensures every schema object has a "type". If missing, infers it from
@@ -976,17 +1036,24 @@ pub(crate) fn build_specs(
     mcp_tools: Option<HashMap<String, mcp_types::Tool>>,
 ) -> ToolRegistryBuilder {
     use crate::tools::handlers::ApplyPatchHandler;
+    use crate::tools::handlers::AskUserHandler;
+    use crate::tools::handlers::BackgroundTaskOutputHandler;
+    use crate::tools::handlers::CodeSearchHandler;
+    use crate::tools::handlers::CreatePullRequestHandler;
     use crate::tools::handlers::GrepFilesHandler;
     use crate::tools::handlers::ListDirHandler;
     use crate::tools::handlers::McpHandler;
     use crate::tools::handlers::McpResourceHandler;
     use crate::tools::handlers::PlanHandler;
     use crate::tools::handlers::ReadFileHandler;
+    use crate::tools::handlers::RepoMapHandler;
     use crate::tools::handlers::ShellCommandHandler;
     use crate::tools::handlers::ShellHandler;
+    use crate::tools::handlers::SpawnAgentHandler;
     use crate::tools::handlers::TestSyncHandler;
     use crate::tools::handlers::UnifiedExecHandler;
     use crate::tools::handlers::ViewImageHandler;
+    use crate::tools::handlers::WorkspaceInstructionHandler;
     use std::sync::Arc;
 
     let mut builder = ToolRegistryBuilder::new();
@@ -1097,6 +1164,54 @@ pub(crate) fn build_specs(
         builder.register_handler("view_image", view_image_handler);
     }
 
+    if config.include_workspace_instruction_tool {
+        let workspace_instruction_handler = Arc::new(WorkspaceInstructionHandler);
+        builder.push_spec(PROPOSE_WORKSPACE_INSTRUCTION_TOOL.clone());
+        builder.register_handler(
+            "propose_workspace_instruction",
+            workspace_instruction_handler,
+        );
+    }
+
+    if config.include_background_task_tool {
+        let background_task_handler = Arc::new(BackgroundTaskOutputHandler);
+        builder.push_spec_with_parallel_support(BACKGROUND_TASK_OUTPUT_TOOL.clone(), true);
+        builder.register_handler("background_task_output", background_task_handler);
+    }
+
+    if config.include_spawn_agent_tool {
+        let spawn_agent_handler = Arc::new(SpawnAgentHandler);
+        builder.push_spec_with_parallel_support(SPAWN_AGENT_TOOL.clone(), true);
+        builder.register_handler("spawn_agent", spawn_agent_handler);
+    }
+
+    if config.include_pull_request_tool {
+        let pull_request_handler = Arc::new(CreatePullRequestHandler);
+        builder.push_spec(CREATE_PULL_REQUEST_TOOL.clone());
+        builder.register_handler("create_pull_request", pull_request_handler);
+    }
+
+    if config.include_repo_map_tool {
+        let repo_map_handler = Arc::new(RepoMapHandler);
+        builder.push_spec_with_parallel_support(REPO_MAP_TOOL.clone(), true);
+        builder.register_handler("repo_map", repo_map_handler);
+    }
+
+    if config.include_code_search_tool {
+        let code_search_handler = Arc::new(CodeSearchHandler);
+        builder.push_spec_with_parallel_support(CODE_SEARCH_TOOL.clone(), true);
+        builder.register_handler("code_search", code_search_handler);
+    }
+
+    if config.include_ask_user_tool {
+        let ask_user_handler = Arc::new(AskUserHandler);
+        builder.push_spec(ASK_USER_TOOL.clone());
+        builder.register_handler("ask_user", ask_user_handler);
+    }
+
+    // MCP tools are filtered by the caller, which knows their servers.
+    builder.retain_tools(|name| config.tool_filter.allows_builtin(name));
+
     if let Some(mcp_tools) = mcp_tools {
         let mut entries: Vec<(String, mcp_types::Tool)> = mcp_tools.into_iter().collect();
         entries.sort_by(|a, b| a.0.cmp(&b.0));
@@ -1117,6 +1232,34 @@ pub(crate) fn build_specs(
     builder
 }
 
+/// Registers the functions declared by WebAssembly plugins. Plugins are
+/// stateless, so their calls may run in parallel.
+pub(crate) fn register_plugin_tools(
+    builder: &mut ToolRegistryBuilder,
+    config: &ToolsConfig,
+    plugins: &PluginHost,
+) {
+    use crate::tools::handlers::PluginHandler;
+    use std::sync::Arc;
+
+    let plugin_handler = Arc::new(PluginHandler);
+    for tool in plugins.tools() {
+        if !config.tool_filter.allows_plugin(&tool.plugin) {
+            continue;
+        }
+        let name = tool.name.clone();
+        match plugin_tool_to_openai_tool(tool) {
+            Ok(converted_tool) => {
+                builder.push_spec_with_parallel_support(ToolSpec::Function(converted_tool), true);
+                builder.register_handler(name, plugin_handler.clone());
+            }
+            Err(e) => {
+                tracing::error!("Failed to convert {name:?} plugin tool to OpenAI tool: {e:?}");
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::client_common::tools::FreeformTool;
@@ -1468,6 +1611,33 @@ mod tests {
         assert_contains_tool_names(&tools, &subset);
     }
 
+    #[test]
+    fn test_build_specs_drops_disabled_tools() {
+        let model_family = find_family_for_model("gpt-5-codex")
+            .expect("gpt-5-codex should be a valid model family");
+        let mut features = Features::with_defaults();
+        features.enable(Feature::UnifiedExec);
+        features.enable(Feature::WebSearchRequest);
+        let mut config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            features: &features,
+        });
+        config.tool_filter = ToolFilter::new(
+            None,
+            &["shell".to_string(), "web_search".to_string()],
+            &HashMap::new(),
+        )
+        .expect("valid filter");
+        let (tools, registry) = build_specs(&config, None).build();
+
+        let names: Vec<&str> = tools.iter().map(|t| tool_name(&t.spec)).collect();
+        for withheld in ["exec_command", "write_stdin", "web_search"] {
+            assert!(!names.contains(&withheld), "{withheld} offered: {names:?}");
+        }
+        assert!(registry.handler("exec_command").is_none());
+        assert_contains_tool_names(&tools, &["apply_patch", "update_plan"]);
+    }
+
     #[test]
     fn test_build_specs_shell_command_present() {
         assert_model_tools(
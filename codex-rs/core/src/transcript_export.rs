@@ -0,0 +1,731 @@
+//! Renders a conversation into a Markdown, HTML, or JSON transcript.
+//!
+//! The rollout format is a persistence detail that changes between releases,
+//! so tools that want a readable record of a conversation should use this
+//! export instead. It keeps what a reader cares about: messages, reasoning
+//! summaries, commands with their exit codes and output, and patches. The
+//! JSON form is versioned by [`TRANSCRIPT_SCHEMA_VERSION`].
+
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ConversationId;
+use codex_protocol::items::AgentMessageContent;
+use codex_protocol::items::TurnItem;
+use codex_protocol::models::LocalShellAction;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::RolloutItem;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use tokio::io::AsyncWrite;
+use tokio::io::AsyncWriteExt;
+use tokio::io::BufWriter;
+
+use crate::bash::extract_bash_command;
+use crate::event_mapping::parse_turn_item;
+use crate::rollout::RolloutRecorder;
+
+/// Bumped whenever a field of the JSON transcript changes meaning or is
+/// removed. Adding fields does not bump it.
+pub const TRANSCRIPT_SCHEMA_VERSION: u32 = 1;
+
+const SHELL_TOOLS: &[&str] = &["shell", "container.exec", "shell_command", "exec_command"];
+const APPLY_PATCH_TOOL: &str = "apply_patch";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TranscriptFormat {
+    Markdown,
+    Html,
+    Json,
+}
+
+impl TranscriptFormat {
+    /// Conventional file extension for this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Json => "json",
+        }
+    }
+}
+
+/// One entry of a transcript, in conversation order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TranscriptEntry {
+    UserMessage {
+        text: String,
+    },
+    AgentMessage {
+        text: String,
+    },
+    Reasoning {
+        summary: String,
+    },
+    Command {
+        call_id: String,
+        command: String,
+        exit_code: Option<i32>,
+        output: Option<String>,
+    },
+    Patch {
+        call_id: String,
+        patch: String,
+        output: Option<String>,
+    },
+    ToolCall {
+        call_id: String,
+        name: String,
+        arguments: String,
+        output: Option<String>,
+    },
+    WebSearch {
+        query: String,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Transcript {
+    pub thread_id: Option<ConversationId>,
+    pub started_at: Option<String>,
+    pub cwd: Option<PathBuf>,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+impl Transcript {
+    pub fn from_rollout_items(items: &[RolloutItem]) -> Self {
+        let mut transcript = Self::default();
+        // Tool outputs arrive after their call; remember where each call
+        // landed so the output can be attached to it.
+        let mut calls: HashMap<String, usize> = HashMap::new();
+        for item in items {
+            match item {
+                RolloutItem::SessionMeta(meta_line) if transcript.thread_id.is_none() => {
+                    transcript.thread_id = Some(meta_line.meta.id);
+                    transcript.started_at = Some(meta_line.meta.timestamp.clone());
+                    transcript.cwd = Some(meta_line.meta.cwd.clone());
+                }
+                RolloutItem::ResponseItem(item) => {
+                    transcript.push_response_item(item, &mut calls);
+                }
+                _ => {}
+            }
+        }
+        transcript
+    }
+
+    fn push_response_item(&mut self, item: &ResponseItem, calls: &mut HashMap<String, usize>) {
+        match item {
+            ResponseItem::FunctionCallOutput { call_id, output } => {
+                self.attach_output(calls, call_id, &output.content);
+                return;
+            }
+            ResponseItem::CustomToolCallOutput { call_id, output } => {
+                self.attach_output(calls, call_id, output);
+                return;
+            }
+            _ => {}
+        }
+        let Some(entry) = entry_for_response_item(item) else {
+            return;
+        };
+        if let TranscriptEntry::Command { call_id, .. }
+        | TranscriptEntry::Patch { call_id, .. }
+        | TranscriptEntry::ToolCall { call_id, .. } = &entry
+        {
+            calls.insert(call_id.clone(), self.entries.len());
+        }
+        self.entries.push(entry);
+    }
+
+    fn attach_output(&mut self, calls: &HashMap<String, usize>, call_id: &str, raw: &str) {
+        let Some(entry) = calls
+            .get(call_id)
+            .and_then(|idx| self.entries.get_mut(*idx))
+        else {
+            return;
+        };
+        match entry {
+            TranscriptEntry::Command {
+                exit_code, output, ..
+            } => {
+                let (code, text) = parse_exec_output(raw);
+                *exit_code = code;
+                *output = Some(text);
+            }
+            TranscriptEntry::Patch { output, .. } | TranscriptEntry::ToolCall { output, .. } => {
+                *output = Some(raw.to_string());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Transcript entry for `item`, without tool output. Returns `None` for
+/// tool outputs and items a reader would not care about.
+pub(crate) fn entry_for_response_item(item: &ResponseItem) -> Option<TranscriptEntry> {
+    let entry = match item {
+        ResponseItem::FunctionCall {
+            name,
+            arguments,
+            call_id,
+            ..
+        } => call_entry(call_id, name, arguments),
+        ResponseItem::CustomToolCall {
+            call_id,
+            name,
+            input,
+            ..
+        } if name == APPLY_PATCH_TOOL => TranscriptEntry::Patch {
+            call_id: call_id.clone(),
+            patch: input.clone(),
+            output: None,
+        },
+        ResponseItem::CustomToolCall {
+            call_id,
+            name,
+            input,
+            ..
+        } => TranscriptEntry::ToolCall {
+            call_id: call_id.clone(),
+            name: name.clone(),
+            arguments: input.clone(),
+            output: None,
+        },
+        ResponseItem::LocalShellCall {
+            id,
+            call_id,
+            action: LocalShellAction::Exec(action),
+            ..
+        } => TranscriptEntry::Command {
+            call_id: call_id.clone().or_else(|| id.clone()).unwrap_or_default(),
+            command: display_command(&action.command),
+            exit_code: None,
+            output: None,
+        },
+        item => match parse_turn_item(item)? {
+            TurnItem::UserMessage(message) => TranscriptEntry::UserMessage {
+                text: message.message(),
+            },
+            TurnItem::AgentMessage(message) => TranscriptEntry::AgentMessage {
+                text: message
+                    .content
+                    .iter()
+                    .map(|content| match content {
+                        AgentMessageContent::Text { text } => text.as_str(),
+                    })
+                    .collect(),
+            },
+            TurnItem::Reasoning(reasoning) if !reasoning.summary_text.is_empty() => {
+                TranscriptEntry::Reasoning {
+                    summary: reasoning.summary_text.join("\n\n"),
+                }
+            }
+            TurnItem::WebSearch(search) => TranscriptEntry::WebSearch {
+                query: search.query,
+            },
+            _ => return None,
+        },
+    };
+    Some(entry)
+}
+
+fn call_entry(call_id: &str, name: &str, arguments: &str) -> TranscriptEntry {
+    let args: Option<serde_json::Value> = serde_json::from_str(arguments).ok();
+    let string_arg = |key: &str| {
+        args.as_ref()
+            .and_then(|args| args.get(key))
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_string)
+    };
+    if name == APPLY_PATCH_TOOL
+        && let Some(patch) = string_arg("input")
+    {
+        return TranscriptEntry::Patch {
+            call_id: call_id.to_string(),
+            patch,
+            output: None,
+        };
+    }
+    if SHELL_TOOLS.contains(&name) {
+        let argv: Option<Vec<String>> = args
+            .as_ref()
+            .and_then(|args| args.get("command"))
+            .and_then(|command| serde_json::from_value(command.clone()).ok());
+        let command = argv
+            .map(|argv| display_command(&argv))
+            .or_else(|| string_arg("command"))
+            .or_else(|| string_arg("cmd"));
+        if let Some(command) = command {
+            return TranscriptEntry::Command {
+                call_id: call_id.to_string(),
+                command,
+                exit_code: None,
+                output: None,
+            };
+        }
+    }
+    TranscriptEntry::ToolCall {
+        call_id: call_id.to_string(),
+        name: name.to_string(),
+        arguments: arguments.to_string(),
+        output: None,
+    }
+}
+
+/// `bash -lc` scripts are shown as the script itself; anything else is
+/// shell-quoted.
+fn display_command(argv: &[String]) -> String {
+    match extract_bash_command(argv) {
+        Some((_, script)) => script.to_string(),
+        None => shlex::try_join(argv.iter().map(String::as_str)).unwrap_or_else(|_| argv.join(" ")),
+    }
+}
+
+/// Splits the exit code from exec output as it was reported to the model.
+/// Handles the structured JSON form and the freeform `Exit code: N` /
+/// `Process exited with code N` headers.
+fn parse_exec_output(raw: &str) -> (Option<i32>, String) {
+    #[derive(Deserialize)]
+    struct Structured {
+        output: String,
+        metadata: Metadata,
+    }
+    #[derive(Deserialize)]
+    struct Metadata {
+        exit_code: i32,
+    }
+    if let Ok(structured) = serde_json::from_str::<Structured>(raw) {
+        return (Some(structured.metadata.exit_code), structured.output);
+    }
+
+    let Some((header, output)) = raw
+        .split_once("\nOutput:\n")
+        .or_else(|| raw.strip_suffix("\nOutput:").map(|header| (header, "")))
+    else {
+        return (None, raw.to_string());
+    };
+    let exit_code = header.lines().find_map(|line| {
+        line.strip_prefix("Exit code: ")
+            .or_else(|| line.strip_prefix("Process exited with code "))
+            .and_then(|code| code.trim().parse().ok())
+    });
+    (exit_code, output.to_string())
+}
+
+/// Reads the rollout at `rollout_path` and writes its transcript to
+/// `output_path`. Returns the number of entries written.
+pub async fn export_transcript(
+    rollout_path: &Path,
+    format: TranscriptFormat,
+    output_path: &Path,
+) -> io::Result<usize> {
+    let history = RolloutRecorder::get_rollout_history(rollout_path).await?;
+    let transcript = Transcript::from_rollout_items(&history.get_rollout_items());
+    if let Some(parent) = output_path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let file = tokio::fs::File::create(output_path).await?;
+    let mut writer = BufWriter::new(file);
+    write_transcript(&transcript, format, &mut writer).await?;
+    writer.flush().await?;
+    Ok(transcript.entries.len())
+}
+
+/// Writes `transcript` to `writer` one entry at a time.
+pub async fn write_transcript<W>(
+    transcript: &Transcript,
+    format: TranscriptFormat,
+    writer: &mut W,
+) -> io::Result<()>
+where
+    W: AsyncWrite + Unpin,
+{
+    match format {
+        TranscriptFormat::Markdown => write_markdown(transcript, writer).await,
+        TranscriptFormat::Html => write_html(transcript, writer).await,
+        TranscriptFormat::Json => write_json(transcript, writer).await,
+    }
+}
+
+async fn write_json<W: AsyncWrite + Unpin>(
+    transcript: &Transcript,
+    writer: &mut W,
+) -> io::Result<()> {
+    let header = json!({
+        "version": TRANSCRIPT_SCHEMA_VERSION,
+        "thread_id": transcript.thread_id.map(|id| id.to_string()),
+        "started_at": transcript.started_at,
+        "cwd": transcript.cwd,
+    });
+    let header = serde_json::to_string(&header).map_err(io::Error::other)?;
+    // Reopen the header object so entries can be streamed into it.
+    let header = header.strip_suffix('}').unwrap_or(&header);
+    writer.write_all(header.as_bytes()).await?;
+    writer.write_all(b",\"entries\":[").await?;
+    for (idx, entry) in transcript.entries.iter().enumerate() {
+        if idx > 0 {
+            writer.write_all(b",").await?;
+        }
+        let entry = serde_json::to_vec(entry).map_err(io::Error::other)?;
+        writer.write_all(&entry).await?;
+    }
+    writer.write_all(b"]}\n").await
+}
+
+async fn write_markdown<W: AsyncWrite + Unpin>(
+    transcript: &Transcript,
+    writer: &mut W,
+) -> io::Result<()> {
+    let mut header = String::from("# Codex transcript\n\n");
+    if let Some(thread_id) = transcript.thread_id {
+        header.push_str(&format!("- Thread: `{thread_id}`\n"));
+    }
+    if let Some(started_at) = &transcript.started_at {
+        header.push_str(&format!("- Started: {started_at}\n"));
+    }
+    if let Some(cwd) = &transcript.cwd {
+        header.push_str(&format!("- Working directory: `{}`\n", cwd.display()));
+    }
+    writer.write_all(header.as_bytes()).await?;
+    for entry in &transcript.entries {
+        writer.write_all(markdown_entry(entry).as_bytes()).await?;
+    }
+    Ok(())
+}
+
+fn markdown_entry(entry: &TranscriptEntry) -> String {
+    match entry {
+        TranscriptEntry::UserMessage { text } => format!("\n## User\n\n{text}\n"),
+        TranscriptEntry::AgentMessage { text } => format!("\n## Codex\n\n{text}\n"),
+        TranscriptEntry::Reasoning { summary } => {
+            let quoted: Vec<String> = summary
+                .lines()
+                .map(|line| format!("> {line}").trim_end().to_string())
+                .collect();
+            format!("\n> **Reasoning**\n>\n{}\n", quoted.join("\n"))
+        }
+        TranscriptEntry::Command {
+            command,
+            exit_code,
+            output,
+            ..
+        } => {
+            let status = match exit_code {
+                Some(code) => format!(" (exit {code})"),
+                None => String::new(),
+            };
+            let mut section = format!("\n### Command{status}\n\n{}", code_block("sh", command));
+            if let Some(output) = output.as_deref().filter(|output| !output.is_empty()) {
+                section.push('\n');
+                section.push_str(&code_block("text", output));
+            }
+            section
+        }
+        TranscriptEntry::Patch { patch, output, .. } => {
+            let mut section = format!("\n### Patch\n\n{}", code_block("diff", patch));
+            if let Some(output) = output {
+                section.push('\n');
+                section.push_str(&code_block("text", output));
+            }
+            section
+        }
+        TranscriptEntry::ToolCall {
+            name,
+            arguments,
+            output,
+            ..
+        } => {
+            let mut section = format!("\n### Tool `{name}`\n\n{}", code_block("json", arguments));
+            if let Some(output) = output {
+                section.push('\n');
+                section.push_str(&code_block("text", output));
+            }
+            section
+        }
+        TranscriptEntry::WebSearch { query } => format!("\n_Searched the web for: {query}_\n"),
+    }
+}
+
+/// Fenced code block whose fence is longer than any backtick run in `body`.
+fn code_block(lang: &str, body: &str) -> String {
+    let longest_run = body.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    let newline = if body.ends_with('\n') { "" } else { "\n" };
+    format!("{fence}{lang}\n{body}{newline}{fence}\n")
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:60rem;margin:2rem auto;padding:0 1rem;line-height:1.5}\
+pre{background:#f5f5f5;padding:.75rem;overflow-x:auto;white-space:pre-wrap}\
+section{margin:1.5rem 0}.reasoning{color:#555;border-left:3px solid #ccc;padding-left:1rem}\
+.exit-error{color:#b00}h2,h3{margin-bottom:.5rem}";
+
+async fn write_html<W: AsyncWrite + Unpin>(
+    transcript: &Transcript,
+    writer: &mut W,
+) -> io::Result<()> {
+    let mut header = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Codex transcript</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n<h1>Codex transcript</h1>\n<ul class=\"meta\">\n"
+    );
+    if let Some(thread_id) = transcript.thread_id {
+        header.push_str(&format!("<li>Thread: <code>{thread_id}</code></li>\n"));
+    }
+    if let Some(started_at) = &transcript.started_at {
+        header.push_str(&format!("<li>Started: {}</li>\n", escape_html(started_at)));
+    }
+    if let Some(cwd) = &transcript.cwd {
+        header.push_str(&format!(
+            "<li>Working directory: <code>{}</code></li>\n",
+            escape_html(&cwd.display().to_string())
+        ));
+    }
+    header.push_str("</ul>\n");
+    writer.write_all(header.as_bytes()).await?;
+    for entry in &transcript.entries {
+        writer.write_all(html_entry(entry).as_bytes()).await?;
+    }
+    writer.write_all(b"</body>\n</html>\n").await
+}
+
+fn html_entry(entry: &TranscriptEntry) -> String {
+    let pre = |body: &str| format!("<pre>{}</pre>\n", escape_html(body));
+    match entry {
+        TranscriptEntry::UserMessage { text } => {
+            format!(
+                "<section class=\"user\">\n<h2>User</h2>\n{}</section>\n",
+                pre(text)
+            )
+        }
+        TranscriptEntry::AgentMessage { text } => {
+            format!(
+                "<section class=\"agent\">\n<h2>Codex</h2>\n{}</section>\n",
+                pre(text)
+            )
+        }
+        TranscriptEntry::Reasoning { summary } => format!(
+            "<section class=\"reasoning\">\n<h3>Reasoning</h3>\n{}</section>\n",
+            pre(summary)
+        ),
+        TranscriptEntry::Command {
+            command,
+            exit_code,
+            output,
+            ..
+        } => {
+            let status = match exit_code {
+                Some(0) => " <span class=\"exit\">(exit 0)</span>".to_string(),
+                Some(code) => format!(" <span class=\"exit-error\">(exit {code})</span>"),
+                None => String::new(),
+            };
+            let mut section = format!(
+                "<section class=\"command\">\n<h3>Command{status}</h3>\n{}",
+                pre(command)
+            );
+            if let Some(output) = output.as_deref().filter(|output| !output.is_empty()) {
+                section.push_str(&pre(output));
+            }
+            section.push_str("</section>\n");
+            section
+        }
+        TranscriptEntry::Patch { patch, output, .. } => {
+            let mut section = format!("<section class=\"patch\">\n<h3>Patch</h3>\n{}", pre(patch));
+            if let Some(output) = output {
+                section.push_str(&pre(output));
+            }
+            section.push_str("</section>\n");
+            section
+        }
+        TranscriptEntry::ToolCall {
+            name,
+            arguments,
+            output,
+            ..
+        } => {
+            let mut section = format!(
+                "<section class=\"tool\">\n<h3>Tool <code>{}</code></h3>\n{}",
+                escape_html(name),
+                pre(arguments)
+            );
+            if let Some(output) = output {
+                section.push_str(&pre(output));
+            }
+            section.push_str("</section>\n");
+            section
+        }
+        TranscriptEntry::WebSearch { query } => format!(
+            "<section class=\"web-search\"><em>Searched the web for: {}</em></section>\n",
+            escape_html(query)
+        ),
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use codex_protocol::models::ReasoningItemReasoningSummary;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> RolloutItem {
+        let content = if role == "user" {
+            ContentItem::InputText {
+                text: text.to_string(),
+            }
+        } else {
+            ContentItem::OutputText {
+                text: text.to_string(),
+            }
+        };
+        RolloutItem::ResponseItem(ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![content],
+        })
+    }
+
+    fn function_call(call_id: &str, name: &str, arguments: serde_json::Value) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::FunctionCall {
+            id: None,
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+            call_id: call_id.to_string(),
+        })
+    }
+
+    fn function_output(call_id: &str, content: &str) -> RolloutItem {
+        RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload {
+                content: content.to_string(),
+                ..Default::default()
+            },
+        })
+    }
+
+    fn sample_items() -> Vec<RolloutItem> {
+        vec![
+            message("user", "<environment_context>ignored</environment_context>"),
+            message("user", "run the tests"),
+            RolloutItem::ResponseItem(ResponseItem::Reasoning {
+                id: "r1".to_string(),
+                summary: vec![ReasoningItemReasoningSummary::SummaryText {
+                    text: "Running cargo test".to_string(),
+                }],
+                content: None,
+                encrypted_content: None,
+            }),
+            function_call(
+                "call-1",
+                "shell",
+                json!({"command": ["bash", "-lc", "cargo test"]}),
+            ),
+            function_output(
+                "call-1",
+                &json!({
+                    "output": "test result: FAILED",
+                    "metadata": {"exit_code": 101, "duration_seconds": 1.5},
+                })
+                .to_string(),
+            ),
+            function_call(
+                "call-2",
+                "apply_patch",
+                json!({"input": "*** Begin Patch\n*** End Patch"}),
+            ),
+            function_output("call-2", "Done!"),
+            message("assistant", "Fixed <it>."),
+        ]
+    }
+
+    #[test]
+    fn collects_entries_from_rollout_items() {
+        let transcript = Transcript::from_rollout_items(&sample_items());
+
+        assert_eq!(
+            transcript.entries,
+            vec![
+                TranscriptEntry::UserMessage {
+                    text: "run the tests".to_string(),
+                },
+                TranscriptEntry::Reasoning {
+                    summary: "Running cargo test".to_string(),
+                },
+                TranscriptEntry::Command {
+                    call_id: "call-1".to_string(),
+                    command: "cargo test".to_string(),
+                    exit_code: Some(101),
+                    output: Some("test result: FAILED".to_string()),
+                },
+                TranscriptEntry::Patch {
+                    call_id: "call-2".to_string(),
+                    patch: "*** Begin Patch\n*** End Patch".to_string(),
+                    output: Some("Done!".to_string()),
+                },
+                TranscriptEntry::AgentMessage {
+                    text: "Fixed <it>.".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_freeform_exec_output() {
+        assert_eq!(
+            parse_exec_output("Exit code: 2\nWall time: 0.1 seconds\nOutput:\nboom\n"),
+            (Some(2), "boom\n".to_string())
+        );
+        assert_eq!(
+            parse_exec_output("Wall time: 0.0100 seconds\nProcess exited with code 0\nOutput:"),
+            (Some(0), String::new())
+        );
+        assert_eq!(parse_exec_output("plain"), (None, "plain".to_string()));
+    }
+
+    #[tokio::test]
+    async fn renders_each_format() -> io::Result<()> {
+        let transcript = Transcript::from_rollout_items(&sample_items());
+        let render = |format| {
+            let transcript = transcript.clone();
+            async move {
+                let mut out = Vec::new();
+                write_transcript(&transcript, format, &mut out).await?;
+                String::from_utf8(out).map_err(io::Error::other)
+            }
+        };
+
+        let json: serde_json::Value = serde_json::from_str(&render(TranscriptFormat::Json).await?)?;
+        assert_eq!(json["version"], TRANSCRIPT_SCHEMA_VERSION);
+        assert_eq!(json["entries"][2]["type"], "command");
+        assert_eq!(json["entries"][2]["exit_code"], 101);
+
+        let markdown = render(TranscriptFormat::Markdown).await?;
+        assert!(markdown.contains("### Command (exit 101)\n\n```sh\ncargo test\n```\n"));
+
+        let html = render(TranscriptFormat::Html).await?;
+        assert!(html.contains("<pre>Fixed &lt;it&gt;.</pre>"));
+        Ok(())
+    }
+}
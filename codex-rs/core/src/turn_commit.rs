@@ -0,0 +1,184 @@
+//! `auto_commit`: when a turn completes, its changes are committed to the
+//! conversation's `codex/turns/<conversation id>` branch with a
+//! model-generated conventional-commit message.
+//!
+//! Commits are written with plumbing commands, so the user's `HEAD`, index,
+//! and working tree stay as they are; only the turn branch moves.
+
+use std::time::Duration;
+
+use codex_git::GitToolingError;
+use codex_git::prepare_branch_commit;
+use codex_protocol::ConversationId;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ModelTask;
+use codex_protocol::protocol::TurnCommit;
+use futures::StreamExt;
+use tokio::time::timeout;
+use tracing::warn;
+
+use crate::client::ModelClient;
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::error::Result as CodexResult;
+use crate::model_router::RoutedClient;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_text;
+
+const TURN_COMMIT_PROMPT: &str = include_str!("../templates/turn_commit/prompt.md");
+/// Diffs sent to the model for the commit message are cut to this size.
+const MAX_DIFF_BYTES: usize = 60_000;
+const MESSAGE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Used when no message could be generated.
+const FALLBACK_MESSAGE: &str = "chore: apply agent changes";
+
+fn turn_branch(conversation_id: ConversationId) -> String {
+    format!("codex/turns/{conversation_id}")
+}
+
+/// Commit the working tree of the turn's repository to the turn branch.
+/// Returns `Ok(None)` outside a git repository or when nothing changed.
+pub(crate) async fn commit_turn(
+    sess: &Session,
+    turn_context: &TurnContext,
+    last_agent_message: Option<&str>,
+) -> anyhow::Result<Option<TurnCommit>> {
+    let cwd = turn_context.cwd.clone();
+    let branch = turn_branch(sess.conversation_id());
+    let prepared = tokio::task::spawn_blocking(move || {
+        let pending = match prepare_branch_commit(&cwd, &branch) {
+            Ok(Some(pending)) => pending,
+            Ok(None) | Err(GitToolingError::NotAGitRepository { .. }) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let diff = pending.diff()?;
+        Ok(Some((pending, diff)))
+    })
+    .await??;
+    let Some((pending, diff)) = prepared else {
+        return Ok(None);
+    };
+
+    let message = generate_message(sess, turn_context, &diff, last_agent_message)
+        .await
+        .unwrap_or_else(|| FALLBACK_MESSAGE.to_string());
+    let commit = {
+        let pending = pending.clone();
+        let message = message.clone();
+        tokio::task::spawn_blocking(move || pending.commit(&message)).await??
+    };
+    Ok(Some(TurnCommit {
+        commit,
+        branch: pending.branch().to_string(),
+        message,
+    }))
+}
+
+async fn generate_message(
+    sess: &Session,
+    turn_context: &TurnContext,
+    diff: &str,
+    last_agent_message: Option<&str>,
+) -> Option<String> {
+    let diff = truncate_text(diff, TruncationPolicy::Bytes(MAX_DIFF_BYTES));
+    let mut text = format!("Diff:\n{diff}");
+    if let Some(summary) = last_agent_message.filter(|summary| !summary.trim().is_empty()) {
+        text.push_str(&format!("\n\nAgent summary:\n{summary}"));
+    }
+    let prompt = Prompt {
+        input: vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText { text }],
+        }],
+        base_instructions_override: Some(TURN_COMMIT_PROMPT.to_string()),
+        ..Default::default()
+    };
+
+    let mut routed = RoutedClient::new(&turn_context.client, ModelTask::CommitMessage);
+    let result = timeout(MESSAGE_TIMEOUT, async {
+        loop {
+            match request_message(routed.client(), &prompt).await {
+                Err(err) if routed.fall_back(&err) => continue,
+                result => return result,
+            }
+        }
+    })
+    .await;
+    if matches!(result, Ok(Ok(_))) {
+        sess.record_model_usage(&routed).await;
+    }
+    match result {
+        Ok(Ok(message)) => message.and_then(|message| clean_message(&message)),
+        Ok(Err(err)) => {
+            warn!("failed to generate turn commit message: {err:#}");
+            None
+        }
+        Err(_) => {
+            warn!("timed out generating turn commit message");
+            None
+        }
+    }
+}
+
+pub(crate) async fn request_message(
+    client: &ModelClient,
+    prompt: &Prompt,
+) -> CodexResult<Option<String>> {
+    let mut stream = client.stream(prompt).await?;
+    let mut message = None;
+    while let Some(event) = stream.next().await {
+        match event? {
+            ResponseEvent::OutputItemDone(ResponseItem::Message { content, .. }) => {
+                let text: String = content
+                    .into_iter()
+                    .filter_map(|item| match item {
+                        ContentItem::OutputText { text } => Some(text),
+                        _ => None,
+                    })
+                    .collect();
+                message = Some(text);
+            }
+            ResponseEvent::Completed { .. } => break,
+            _ => {}
+        }
+    }
+    Ok(message)
+}
+
+/// Strip wrapping the model may add despite instructions.
+fn clean_message(message: &str) -> Option<String> {
+    let trimmed = message.trim();
+    let trimmed = match trimmed.strip_prefix("```") {
+        Some(rest) => rest
+            .split_once('\n')
+            .map_or("", |(_, body)| body)
+            .trim_end()
+            .trim_end_matches("```"),
+        None => trimmed,
+    };
+    let trimmed = trimmed.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn clean_message_strips_code_fences() {
+        assert_eq!(
+            clean_message("```\nfix(core): handle empty diffs\n```\n"),
+            Some("fix(core): handle empty diffs".to_string())
+        );
+        assert_eq!(
+            clean_message("feat: add x\n\nBody line.\n"),
+            Some("feat: add x\n\nBody line.".to_string())
+        );
+        assert_eq!(clean_message("  \n"), None);
+    }
+}
@@ -164,6 +164,7 @@ impl UnifiedExecSession {
             aggregated_output: StreamOutput::new(aggregated_text.clone()),
             duration: Duration::ZERO,
             timed_out: false,
+            output_truncation: None,
         };
 
         if is_likely_sandbox_denied(self.sandbox_type(), &exec_output) {
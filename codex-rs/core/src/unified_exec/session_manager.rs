@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use codex_utils_pty::TerminalSize;
 use tokio::sync::Notify;
 use tokio::sync::mpsc;
 use tokio::time::Duration;
@@ -217,6 +218,7 @@ impl UnifiedExecSessionManager {
             aggregated_output: StreamOutput::new(response.output.clone()),
             duration: response.wall_time,
             timed_out: false,
+            output_truncation: None,
         };
         interaction_emitter
             .emit(
@@ -352,6 +354,7 @@ impl UnifiedExecSessionManager {
             aggregated_output: StreamOutput::new(aggregated_output),
             duration,
             timed_out: false,
+            output_truncation: None,
         };
         let event_ctx = ToolEventCtx::new(
             entry.session_ref.as_ref(),
@@ -385,6 +388,7 @@ impl UnifiedExecSessionManager {
             aggregated_output: StreamOutput::new(aggregated_output),
             duration,
             timed_out: false,
+            output_truncation: None,
         };
         let event_ctx = ToolEventCtx::new(
             context.session.as_ref(),
@@ -429,6 +433,7 @@ impl UnifiedExecSessionManager {
             env.cwd.as_path(),
             &env.env,
             &env.arg0,
+            TerminalSize::default(),
         )
         .await
         .map_err(|err| UnifiedExecError::create_session(err.to_string()))?;
@@ -443,6 +448,7 @@ impl UnifiedExecSessionManager {
         justification: Option<String>,
         context: &UnifiedExecContext,
     ) -> Result<UnifiedExecSession, UnifiedExecError> {
+        let approved_by_rule = context.session.approved_by_rule(command, &cwd).await;
         let mut orchestrator = ToolOrchestrator::new();
         let mut runtime = UnifiedExecRuntime::new(self);
         let req = UnifiedExecToolRequest::new(
@@ -457,6 +463,7 @@ impl UnifiedExecSessionManager {
                 context.turn.approval_policy,
                 &context.turn.sandbox_policy,
                 SandboxPermissions::from(with_escalated_permissions.unwrap_or(false)),
+                approved_by_rule,
             ),
         );
         let tool_ctx = ToolCtx {
@@ -1,19 +1,59 @@
+mod webhook;
+
+use std::path::PathBuf;
+
 use serde::Serialize;
 use tracing::error;
 use tracing::warn;
 
-#[derive(Debug, Default)]
+use crate::config::Config;
+use crate::config::types::NotificationWebhookConfig;
+use crate::config::types::Notifications;
+use webhook::WebhookSink;
+
+/// File under `~/.codex/log` that collects notifications no webhook accepted.
+pub(crate) const NOTIFICATION_DEAD_LETTER_FILENAME: &str = "notification_dead_letters.jsonl";
+
+/// Longest body shown in a desktop notification, in characters.
+const DESKTOP_BODY_MAX_CHARS: usize = 200;
+
+#[derive(Debug)]
 pub(crate) struct UserNotifier {
     notify_command: Option<Vec<String>>,
+    /// Notification types passed to `notify_command`; `None` passes all.
+    notify_types: Option<Vec<String>>,
+    /// Which notification types to show with the built-in desktop backend.
+    desktop: Notifications,
+    webhooks: Option<WebhookSink>,
 }
 
 impl UserNotifier {
     pub(crate) fn notify(&self, notification: &UserNotification) {
         if let Some(notify_command) = &self.notify_command
             && !notify_command.is_empty()
+            && self.command_allows(notification)
         {
             self.invoke_notify(notify_command, notification)
         }
+        if self.desktop_allows(notification) {
+            show_desktop_notification(notification);
+        }
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.deliver(notification);
+        }
+    }
+
+    fn command_allows(&self, notification: &UserNotification) -> bool {
+        self.notify_types
+            .as_ref()
+            .is_none_or(|types| types.iter().any(|t| t == notification.kind()))
+    }
+
+    fn desktop_allows(&self, notification: &UserNotification) -> bool {
+        match &self.desktop {
+            Notifications::Enabled(enabled) => *enabled,
+            Notifications::Custom(types) => types.iter().any(|t| t == notification.kind()),
+        }
     }
 
     fn invoke_notify(&self, notify_command: &[String], notification: &UserNotification) {
@@ -34,16 +74,109 @@ impl UserNotifier {
         }
     }
 
-    pub(crate) fn new(notify: Option<Vec<String>>) -> Self {
+    pub(crate) fn new(
+        notify: Option<Vec<String>>,
+        notify_types: Option<Vec<String>>,
+        desktop: Notifications,
+    ) -> Self {
         Self {
             notify_command: notify,
+            notify_types,
+            desktop,
+            webhooks: None,
+        }
+    }
+
+    /// The notifier described by the notification settings in `config`.
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self::new(
+            config.notify.clone(),
+            config.notify_types.clone(),
+            config.desktop_notifications.clone(),
+        )
+        .with_webhooks(
+            config.notification_webhooks.clone(),
+            config
+                .codex_home
+                .join("log")
+                .join(NOTIFICATION_DEAD_LETTER_FILENAME),
+        )
+    }
+
+    /// Also POST notifications to `webhooks`. Notifications that cannot be
+    /// delivered are appended to `dead_letter_path`.
+    pub(crate) fn with_webhooks(
+        mut self,
+        webhooks: Vec<NotificationWebhookConfig>,
+        dead_letter_path: PathBuf,
+    ) -> Self {
+        if !webhooks.is_empty() {
+            self.webhooks = Some(WebhookSink::new(webhooks, dead_letter_path));
         }
+        self
+    }
+}
+
+impl Default for UserNotifier {
+    fn default() -> Self {
+        Self::new(None, None, Notifications::Enabled(false))
+    }
+}
+
+/// Show `notification` with the platform's notification center. Delivery
+/// happens on a background thread because some backends block on IPC.
+fn show_desktop_notification(notification: &UserNotification) {
+    let (title, body) = notification.desktop_text();
+    let body: String = body.chars().take(DESKTOP_BODY_MAX_CHARS).collect();
+    let spawned = std::thread::Builder::new()
+        .name("codex-desktop-notification".to_string())
+        .spawn(move || {
+            if let Err(err) = platform::show(&title, &body) {
+                warn!("failed to show desktop notification: {err}");
+            }
+        });
+    if let Err(err) = spawned {
+        warn!("failed to spawn desktop notification thread: {err}");
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod platform {
+    pub(super) fn show(title: &str, body: &str) -> Result<(), String> {
+        notify_rust::Notification::new()
+            .appname("Codex")
+            .summary(title)
+            .body(body)
+            .show()
+            .map(drop)
+            .map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use tauri_winrt_notification::Toast;
+
+    pub(super) fn show(title: &str, body: &str) -> Result<(), String> {
+        Toast::new(Toast::POWERSHELL_APP_ID)
+            .title(title)
+            .text1(body)
+            .show()
+            .map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+mod platform {
+    pub(super) fn show(_title: &str, _body: &str) -> Result<(), String> {
+        Err("desktop notifications are not supported on this platform".to_string())
     }
 }
 
 /// User can configure a program that will receive notifications. Each
 /// notification is serialized as JSON and passed as an argument to the
-/// program.
+/// program. The same notifications can also be shown by the built-in
+/// desktop backend (`desktop_notifications`).
 #[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(tag = "type", rename_all = "kebab-case")]
 pub(crate) enum UserNotification {
@@ -59,6 +192,124 @@ pub(crate) enum UserNotification {
         /// The last message sent by the assistant in the turn.
         last_assistant_message: Option<String>,
     },
+
+    #[serde(rename_all = "kebab-case")]
+    ApprovalRequested {
+        thread_id: String,
+        turn_id: String,
+        cwd: String,
+        call_id: String,
+
+        /// One-line description of what needs approval, e.g. the command.
+        summary: String,
+    },
+
+    #[serde(rename_all = "kebab-case")]
+    TurnFailed {
+        thread_id: String,
+        turn_id: String,
+        cwd: String,
+        error: String,
+    },
+
+    /// Usage of a rate-limit window crossed 75%, 90%, or 95%.
+    #[serde(rename_all = "kebab-case")]
+    RateLimitWarning {
+        thread_id: String,
+
+        /// `primary` (short) or `secondary` (weekly) window.
+        window: String,
+        used_percent: f64,
+        window_minutes: Option<i64>,
+
+        /// Unix timestamp (seconds) when the window resets.
+        resets_at: Option<i64>,
+    },
+
+    /// `/undo` restored the working tree to a ghost snapshot.
+    #[serde(rename_all = "kebab-case")]
+    SnapshotRestored {
+        thread_id: String,
+        turn_id: String,
+        cwd: String,
+        commit_id: String,
+    },
+
+    /// A run started by `codex schedule run` finished.
+    #[serde(rename_all = "kebab-case")]
+    ScheduledRunComplete {
+        /// `name` of the schedule in config.
+        schedule: String,
+        thread_id: String,
+        cwd: String,
+
+        /// The last message sent by the assistant, when the run succeeded.
+        last_assistant_message: Option<String>,
+
+        /// Why the run failed, when it did.
+        error: Option<String>,
+    },
+}
+
+impl UserNotification {
+    /// The `type` tag this notification serializes with.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::AgentTurnComplete { .. } => "agent-turn-complete",
+            Self::ApprovalRequested { .. } => "approval-requested",
+            Self::TurnFailed { .. } => "turn-failed",
+            Self::RateLimitWarning { .. } => "rate-limit-warning",
+            Self::SnapshotRestored { .. } => "snapshot-restored",
+            Self::ScheduledRunComplete { .. } => "scheduled-run-complete",
+        }
+    }
+
+    fn desktop_text(&self) -> (String, String) {
+        match self {
+            Self::AgentTurnComplete {
+                last_assistant_message,
+                ..
+            } => (
+                "Codex: turn complete".to_string(),
+                last_assistant_message
+                    .clone()
+                    .unwrap_or_else(|| "Agent turn complete".to_string()),
+            ),
+            Self::ApprovalRequested { summary, .. } => {
+                ("Codex: approval needed".to_string(), summary.clone())
+            }
+            Self::TurnFailed { error, .. } => ("Codex: turn failed".to_string(), error.clone()),
+            Self::RateLimitWarning {
+                window,
+                used_percent,
+                ..
+            } => (
+                "Codex: approaching rate limit".to_string(),
+                format!("{used_percent:.0}% of the {window} rate limit used"),
+            ),
+            Self::SnapshotRestored { commit_id, .. } => {
+                let short_id: String = commit_id.chars().take(7).collect();
+                (
+                    "Codex: snapshot restored".to_string(),
+                    format!("Restored the working tree to snapshot {short_id}"),
+                )
+            }
+            Self::ScheduledRunComplete {
+                schedule,
+                last_assistant_message,
+                error,
+                ..
+            } => match error {
+                Some(error) => (format!("Codex: {schedule} failed"), error.clone()),
+                None => (
+                    format!("Codex: {schedule} finished"),
+                    last_assistant_message
+                        .clone()
+                        .unwrap_or_else(|| "Scheduled run complete".to_string()),
+                ),
+            },
+        }
+    }
 }
 
 #[cfg(test)]
@@ -84,4 +335,58 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn notifications_filter_by_type() {
+        let approval = UserNotification::ApprovalRequested {
+            thread_id: "thread".to_string(),
+            turn_id: "1".to_string(),
+            cwd: "/repo".to_string(),
+            call_id: "call".to_string(),
+            summary: "cargo test".to_string(),
+        };
+        let failed = UserNotification::TurnFailed {
+            thread_id: "thread".to_string(),
+            turn_id: "1".to_string(),
+            cwd: "/repo".to_string(),
+            error: "stream disconnected".to_string(),
+        };
+
+        let only_approvals = UserNotifier::new(
+            None,
+            None,
+            Notifications::Custom(vec!["approval-requested".into()]),
+        );
+        assert!(only_approvals.desktop_allows(&approval));
+        assert!(!only_approvals.desktop_allows(&failed));
+        assert!(!UserNotifier::default().desktop_allows(&approval));
+
+        let urgent_only = UserNotifier::new(
+            Some(vec!["notify-send".to_string()]),
+            Some(vec!["approval-requested".into(), "turn-failed".into()]),
+            Notifications::Enabled(false),
+        );
+        assert!(urgent_only.command_allows(&approval));
+        assert!(urgent_only.command_allows(&failed));
+        assert!(
+            !urgent_only.command_allows(&UserNotification::RateLimitWarning {
+                thread_id: "thread".to_string(),
+                window: "primary".to_string(),
+                used_percent: 90.0,
+                window_minutes: Some(300),
+                resets_at: None,
+            })
+        );
+
+        assert_eq!(
+            serde_json::to_value(&failed).expect("serialize"),
+            serde_json::json!({
+                "type": "turn-failed",
+                "thread-id": "thread",
+                "turn-id": "1",
+                "cwd": "/repo",
+                "error": "stream disconnected",
+            })
+        );
+    }
 }
@@ -0,0 +1,310 @@
+//! Delivers user notifications to HTTP webhooks.
+//!
+//! Each notification is POSTed as the same JSON payload `notify` programs
+//! receive. When the webhook names a secret, the body is signed with
+//! HMAC-SHA256 and the signature sent in `X-Codex-Signature-256` as
+//! `sha256=<hex>`, so receivers can verify requests came from Codex.
+//! Failed deliveries are retried with exponential backoff; notifications that
+//! still cannot be delivered are appended to a JSONL dead-letter log.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::Utc;
+use hmac::Hmac;
+use hmac::Mac;
+use serde_json::json;
+use sha2::Sha256;
+use tokio::io::AsyncWriteExt;
+use tracing::warn;
+
+use crate::config::types::NotificationWebhookConfig;
+use crate::default_client::create_client;
+use crate::util::backoff;
+
+use super::UserNotification;
+
+/// Delivery attempts per notification before it is dead-lettered.
+const MAX_DELIVERY_ATTEMPTS: u64 = 5;
+
+const EVENT_HEADER: &str = "X-Codex-Event";
+const SIGNATURE_HEADER: &str = "X-Codex-Signature-256";
+
+#[derive(Debug, Clone)]
+pub(crate) struct WebhookSink {
+    webhooks: Vec<NotificationWebhookConfig>,
+    dead_letter_path: PathBuf,
+    max_attempts: u64,
+}
+
+impl WebhookSink {
+    pub(crate) fn new(webhooks: Vec<NotificationWebhookConfig>, dead_letter_path: PathBuf) -> Self {
+        Self {
+            webhooks,
+            dead_letter_path,
+            max_attempts: MAX_DELIVERY_ATTEMPTS,
+        }
+    }
+
+    /// Start delivering `notification` to every webhook that accepts its
+    /// type. Delivery runs in the background; this returns immediately.
+    pub(crate) fn deliver(&self, notification: &UserNotification) {
+        let webhooks: Vec<NotificationWebhookConfig> = self
+            .webhooks
+            .iter()
+            .filter(|webhook| accepts(webhook, notification))
+            .cloned()
+            .collect();
+        if webhooks.is_empty() {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("cannot deliver webhook notifications outside a tokio runtime");
+            return;
+        };
+        let payload = match serde_json::to_value(notification) {
+            Ok(payload) => payload,
+            Err(err) => {
+                warn!("failed to serialize webhook notification: {err}");
+                return;
+            }
+        };
+        for webhook in webhooks {
+            let delivery = Delivery {
+                webhook,
+                kind: notification.kind(),
+                payload: payload.clone(),
+                dead_letter_path: self.dead_letter_path.clone(),
+                max_attempts: self.max_attempts,
+            };
+            runtime.spawn(delivery.run());
+        }
+    }
+}
+
+fn accepts(webhook: &NotificationWebhookConfig, notification: &UserNotification) -> bool {
+    webhook
+        .types
+        .as_ref()
+        .is_none_or(|types| types.iter().any(|t| t == notification.kind()))
+}
+
+enum AttemptError {
+    /// Network failures, 429s, and 5xx responses.
+    Retryable(String),
+    /// Other responses the receiver rejected; retrying will not help.
+    Permanent(String),
+}
+
+impl AttemptError {
+    fn message(&self) -> &str {
+        match self {
+            Self::Retryable(message) | Self::Permanent(message) => message,
+        }
+    }
+}
+
+struct Delivery {
+    webhook: NotificationWebhookConfig,
+    kind: &'static str,
+    payload: serde_json::Value,
+    dead_letter_path: PathBuf,
+    max_attempts: u64,
+}
+
+impl Delivery {
+    async fn run(self) {
+        let mut attempts = 0;
+        let error = match self.body_and_signature() {
+            Ok((body, signature)) => loop {
+                attempts += 1;
+                match self.attempt(&body, signature.as_deref()).await {
+                    Ok(()) => return,
+                    Err(AttemptError::Retryable(_)) if attempts < self.max_attempts => {
+                        tokio::time::sleep(backoff(attempts)).await;
+                    }
+                    Err(err) => break err.message().to_string(),
+                }
+            },
+            Err(err) => err,
+        };
+        warn!(
+            "giving up on {} notification for webhook {}: {error}",
+            self.kind, self.webhook.url
+        );
+        if let Err(err) = self.dead_letter(attempts, &error).await {
+            warn!(
+                "failed to write notification dead letter to {}: {err}",
+                self.dead_letter_path.display()
+            );
+        }
+    }
+
+    fn body_and_signature(&self) -> Result<(Vec<u8>, Option<String>), String> {
+        let body = serde_json::to_vec(&self.payload).map_err(|err| err.to_string())?;
+        let signature = match &self.webhook.secret_env_var {
+            Some(var) => {
+                let secret = std::env::var(var)
+                    .map_err(|_| format!("webhook secret env var {var} is not set"))?;
+                Some(sign(secret.as_bytes(), &body)?)
+            }
+            None => None,
+        };
+        Ok((body, signature))
+    }
+
+    async fn attempt(&self, body: &[u8], signature: Option<&str>) -> Result<(), AttemptError> {
+        let mut request = create_client()
+            .post(self.webhook.url.as_str())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, self.kind);
+        if let Some(signature) = signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+        let response = request
+            .body(body.to_vec())
+            .send()
+            .await
+            .map_err(|err| AttemptError::Retryable(err.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            Err(AttemptError::Retryable(format!(
+                "webhook responded {status}"
+            )))
+        } else {
+            Err(AttemptError::Permanent(format!(
+                "webhook responded {status}"
+            )))
+        }
+    }
+
+    async fn dead_letter(&self, attempts: u64, error: &str) -> std::io::Result<()> {
+        let entry = json!({
+            "timestamp": Utc::now().to_rfc3339(),
+            "url": self.webhook.url,
+            "type": self.kind,
+            "attempts": attempts,
+            "error": error,
+            "notification": self.payload,
+        });
+        let mut line = serde_json::to_string(&entry).map_err(std::io::Error::other)?;
+        line.push('\n');
+        append(&self.dead_letter_path, line.as_bytes()).await
+    }
+}
+
+async fn append(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(contents).await
+}
+
+/// `sha256=<hex>` HMAC-SHA256 signature of `body` under `secret`.
+fn sign(secret: &[u8], body: &[u8]) -> Result<String, String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).map_err(|err| err.to_string())?;
+    mac.update(body);
+    Ok(format!("sha256={:x}", mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+    use wiremock::Mock;
+    use wiremock::MockServer;
+    use wiremock::ResponseTemplate;
+    use wiremock::matchers::header;
+    use wiremock::matchers::method;
+
+    fn turn_failed() -> UserNotification {
+        UserNotification::TurnFailed {
+            thread_id: "thread".to_string(),
+            turn_id: "1".to_string(),
+            cwd: "/repo".to_string(),
+            error: "stream disconnected".to_string(),
+        }
+    }
+
+    fn delivery(url: String, dead_letter_path: PathBuf) -> Delivery {
+        let notification = turn_failed();
+        Delivery {
+            webhook: NotificationWebhookConfig {
+                url,
+                secret_env_var: None,
+                types: None,
+            },
+            kind: notification.kind(),
+            payload: serde_json::to_value(&notification).expect("serialize"),
+            dead_letter_path,
+            max_attempts: 3,
+        }
+    }
+
+    #[test]
+    fn signs_bodies_with_hmac_sha256() {
+        // RFC 4231, test case 2.
+        assert_eq!(
+            sign(b"Jefe", b"what do ya want for nothing?"),
+            Ok(
+                "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+                    .to_string()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_until_delivered() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(header(EVENT_HEADER, "turn-failed"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let dir = tempdir().expect("tmp");
+        let dead_letters = dir.path().join("dead_letters.jsonl");
+
+        delivery(server.uri(), dead_letters.clone()).run().await;
+
+        assert_eq!(server.received_requests().await.map(|r| r.len()), Some(2));
+        assert!(!dead_letters.exists());
+    }
+
+    #[tokio::test]
+    async fn rejected_notifications_are_dead_lettered() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400))
+            .mount(&server)
+            .await;
+        let dir = tempdir().expect("tmp");
+        let dead_letters = dir.path().join("log").join("dead_letters.jsonl");
+
+        delivery(server.uri(), dead_letters.clone()).run().await;
+
+        let contents = std::fs::read_to_string(&dead_letters).expect("dead letter log");
+        let entry: serde_json::Value =
+            serde_json::from_str(contents.trim_end()).expect("dead letter entry");
+        assert_eq!(entry["attempts"], 1);
+        assert_eq!(entry["type"], "turn-failed");
+        assert_eq!(entry["error"], "webhook responded 400 Bad Request");
+        assert_eq!(
+            entry["notification"],
+            serde_json::to_value(turn_failed()).expect("json")
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! Plain-command parsing for `powershell -Command` and `cmd /c` scripts.
+//!
+//! This is the Windows counterpart of
+//! [`parse_shell_lc_plain_commands`](crate::bash::parse_shell_lc_plain_commands):
+//! a script is split into its individual commands only when it consists of
+//! plain words joined by sequencing and pipe operators. Anything that could
+//! expand, redirect, or nest (variables, subexpressions, escapes, redirects)
+//! makes the script opaque and the parsers return `None`.
+
+use std::borrow::Cow;
+
+use crate::powershell::extract_powershell_command;
+
+/// `cmd.exe` switches accepted before `/c`. They only affect echo, AutoRun,
+/// and quote handling, not what runs.
+const CMD_FLAGS: &[&str] = &["/d", "/q", "/s"];
+
+enum Token {
+    Word(String),
+    Separator,
+}
+
+/// Returns the plain commands within a `powershell -Command "..."` (or
+/// `pwsh -c "..."`) invocation.
+pub fn parse_powershell_plain_commands(command: &[String]) -> Option<Vec<Vec<String>>> {
+    let (_, script) = extract_powershell_command(command)?;
+    split_commands(tokenize_powershell(script)?)
+}
+
+/// Returns the plain commands within a `cmd /c "..."` invocation.
+pub fn parse_cmd_plain_commands(command: &[String]) -> Option<Vec<Vec<String>>> {
+    let payload = cmd_payload(command)?;
+    split_commands(tokenize_cmd(&payload)?)
+}
+
+/// Extracts the shell and script from `cmd /c "<script>"`. Invocations that
+/// pass the script as several arguments are left alone.
+pub fn extract_cmd_command(command: &[String]) -> Option<(&str, &str)> {
+    let (shell, rest) = split_cmd_invocation(command)?;
+    match rest {
+        [script] => Some((shell, script.as_str())),
+        _ => None,
+    }
+}
+
+/// Joins words for display, quoting the ones `cmd` and PowerShell would
+/// otherwise split.
+pub fn windows_join(words: &[String]) -> String {
+    words
+        .iter()
+        .map(|word| {
+            if word.is_empty() || word.contains(char::is_whitespace) {
+                format!("\"{word}\"")
+            } else {
+                word.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Splits `[cmd, flags.., /c, args..]` into the executable and the arguments
+/// after `/c`.
+fn split_cmd_invocation(command: &[String]) -> Option<(&str, &[String])> {
+    let (shell, args) = command.split_first()?;
+    let name = shell.rsplit(['/', '\\']).next().unwrap_or(shell);
+    if !name.eq_ignore_ascii_case("cmd") && !name.eq_ignore_ascii_case("cmd.exe") {
+        return None;
+    }
+    let run = args.iter().position(|arg| arg.eq_ignore_ascii_case("/c"))?;
+    let (flags, rest) = args.split_at(run);
+    let rest = &rest[1..];
+    if rest.is_empty()
+        || !flags
+            .iter()
+            .all(|flag| CMD_FLAGS.contains(&flag.to_ascii_lowercase().as_str()))
+    {
+        return None;
+    }
+    Some((shell, rest))
+}
+
+/// The command line `cmd` executes after `/c`, with its outer-quote
+/// stripping applied.
+fn cmd_payload(command: &[String]) -> Option<Cow<'_, str>> {
+    let (_, rest) = split_cmd_invocation(command)?;
+    let strip_quotes = command[1..]
+        .iter()
+        .take_while(|arg| !arg.eq_ignore_ascii_case("/c"))
+        .any(|arg| arg.eq_ignore_ascii_case("/s"));
+    let payload = match rest {
+        [script] => Cow::Borrowed(script.as_str()),
+        args => Cow::Owned(windows_join(args)),
+    };
+    if !payload.starts_with('"') {
+        return Some(payload);
+    }
+    // Without `/s`, cmd keeps the quotes only around a lone quoted program
+    // name; otherwise it removes the first and the last quote.
+    let quoted = payload.trim_end();
+    let keep_quotes = !strip_quotes
+        && quoted.matches('"').count() == 2
+        && quoted.ends_with('"')
+        && quoted.contains(char::is_whitespace)
+        && !quoted.contains(['&', '<', '>', '(', ')', '@', '^', '|']);
+    if keep_quotes {
+        return Some(payload);
+    }
+    let inner = &payload[1..];
+    let inner = match inner.rfind('"') {
+        Some(last) => format!("{}{}", &inner[..last], &inner[last + 1..]),
+        None => inner.to_string(),
+    };
+    Some(Cow::Owned(inner))
+}
+
+fn tokenize_powershell(script: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut current: Option<String> = None;
+    // Set after a quoted string: the word must end there, since PowerShell
+    // does not concatenate `'a'b` the way POSIX shells do.
+    let mut closed = false;
+    let mut chars = script.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {
+                tokens.extend(current.take().map(Token::Word));
+                closed = false;
+            }
+            '|' | ';' => {
+                if c == '|' && chars.peek() == Some(&'|') {
+                    chars.next();
+                }
+                tokens.extend(current.take().map(Token::Word));
+                tokens.push(Token::Separator);
+                closed = false;
+            }
+            '&' => {
+                // A lone `&` is the call operator (or a background job).
+                if chars.next() != Some('&') {
+                    return None;
+                }
+                tokens.extend(current.take().map(Token::Word));
+                tokens.push(Token::Separator);
+                closed = false;
+            }
+            '\'' | '"' => {
+                if current.is_some() {
+                    return None;
+                }
+                let mut word = String::new();
+                loop {
+                    match chars.next()? {
+                        q if q == c => {
+                            if chars.peek() == Some(&c) {
+                                chars.next();
+                                word.push(c);
+                            } else {
+                                break;
+                            }
+                        }
+                        '$' | '`' if c == '"' => return None,
+                        other => word.push(other),
+                    }
+                }
+                current = Some(word);
+                closed = true;
+            }
+            '$' | '`' | '(' | ')' | '{' | '}' | '[' | ']' | '@' | '<' | '>' | '#' | '%' => {
+                return None;
+            }
+            c => {
+                if closed {
+                    return None;
+                }
+                current.get_or_insert_with(String::new).push(c);
+            }
+        }
+    }
+    tokens.extend(current.map(Token::Word));
+    Some(tokens)
+}
+
+fn tokenize_cmd(script: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut current: Option<String> = None;
+    let mut in_quotes = false;
+    let mut chars = script.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            // Variables expand even inside quotes.
+            '%' | '!' => return None,
+            '"' => {
+                in_quotes = !in_quotes;
+                current.get_or_insert_with(String::new);
+            }
+            c if in_quotes => current.get_or_insert_with(String::new).push(c),
+            c if c.is_whitespace() => tokens.extend(current.take().map(Token::Word)),
+            '&' | '|' => {
+                if chars.peek() == Some(&c) {
+                    chars.next();
+                }
+                tokens.extend(current.take().map(Token::Word));
+                tokens.push(Token::Separator);
+            }
+            '^' | '<' | '>' | '(' | ')' | '@' => return None,
+            c => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if in_quotes {
+        return None;
+    }
+    tokens.extend(current.map(Token::Word));
+    Some(tokens)
+}
+
+/// Groups tokens into commands. Empty commands (a leading, trailing, or
+/// doubled separator) make the whole script unparseable.
+fn split_commands(tokens: Vec<Token>) -> Option<Vec<Vec<String>>> {
+    let mut commands = Vec::new();
+    let mut current = Vec::new();
+    for token in tokens {
+        match token {
+            Token::Word(word) => current.push(word),
+            Token::Separator => {
+                if current.is_empty() {
+                    return None;
+                }
+                commands.push(std::mem::take(&mut current));
+            }
+        }
+    }
+    if current.is_empty() {
+        return None;
+    }
+    commands.push(current);
+    Some(commands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn vec_str(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    fn words(commands: &[&[&str]]) -> Vec<Vec<String>> {
+        commands.iter().map(|command| vec_str(command)).collect()
+    }
+
+    #[test]
+    fn powershell_scripts_split_into_plain_commands() {
+        let command = vec_str(&[
+            "pwsh",
+            "-NoProfile",
+            "-Command",
+            "Get-Content 'my file.txt' | Select-String \"needle\"; git status",
+        ]);
+        assert_eq!(
+            parse_powershell_plain_commands(&command),
+            Some(words(&[
+                &["Get-Content", "my file.txt"],
+                &["Select-String", "needle"],
+                &["git", "status"],
+            ]))
+        );
+    }
+
+    #[test]
+    fn powershell_rejects_expansion_and_call_operators() {
+        for script in [
+            "Remove-Item $env:TEMP",
+            "& git status",
+            "Write-Output (Get-Date)",
+            "echo \"$(whoami)\"",
+            "Get-Content foo > bar",
+            "echo 'a'b",
+            "git status;",
+        ] {
+            let command = vec_str(&["powershell", "-Command", script]);
+            assert_eq!(parse_powershell_plain_commands(&command), None, "{script}");
+        }
+    }
+
+    #[test]
+    fn cmd_scripts_split_into_plain_commands() {
+        assert_eq!(
+            parse_cmd_plain_commands(&vec_str(&[
+                "cmd.exe",
+                "/d",
+                "/c",
+                "dir src && type \"a b.txt\""
+            ])),
+            Some(words(&[&["dir", "src"], &["type", "a b.txt"]]))
+        );
+        assert_eq!(
+            parse_cmd_plain_commands(&vec_str(&["cmd", "/c", "git", "log", "-1"])),
+            Some(words(&[&["git", "log", "-1"]]))
+        );
+        assert_eq!(
+            parse_cmd_plain_commands(&vec_str(&["cmd", "/s", "/c", "\"del x & echo y\""])),
+            Some(words(&[&["del", "x"], &["echo", "y"]]))
+        );
+    }
+
+    #[test]
+    fn cmd_rejects_variables_and_redirection() {
+        for script in ["echo %PATH%", "type a > b", "echo ^& hi", "(dir)", "dir |"] {
+            let command = vec_str(&["cmd", "/c", script]);
+            assert_eq!(parse_cmd_plain_commands(&command), None, "{script}");
+        }
+        assert_eq!(
+            parse_cmd_plain_commands(&vec_str(&["cmd", "/k", "dir"])),
+            None
+        );
+    }
+}
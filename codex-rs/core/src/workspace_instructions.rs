@@ -0,0 +1,238 @@
+//! Workspace instructions saved from explicit user corrections.
+//!
+//! When the `workspace_instructions` feature is enabled, the model may propose
+//! turning a durable correction ("never touch generated/ files") into a
+//! workspace instruction. Nothing is written until a client confirms the
+//! proposal by calling [`add_workspace_instruction`]. Saved entries live in
+//! `<project root>/.codex/workspace_instructions.json`, where the project root
+//! is the Git repository root containing the working directory (or the working
+//! directory itself outside a repository), and are appended to the user
+//! instructions of every new session started in that project.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::git_info::get_git_repo_root;
+
+/// Filename that stores workspace instructions inside the project's `.codex/`.
+pub const WORKSPACE_INSTRUCTIONS_FILENAME: &str = "workspace_instructions.json";
+
+/// Separator placed between the other user instructions and the rendered
+/// workspace instructions.
+pub(crate) const WORKSPACE_INSTRUCTIONS_SEPARATOR: &str = "\n\n--- workspace-instructions ---\n\n";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceInstruction {
+    pub id: String,
+    pub text: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct WorkspaceInstructionsFile {
+    #[serde(default)]
+    instructions: Vec<WorkspaceInstruction>,
+}
+
+/// Path of the workspace instructions file for the project containing `cwd`.
+pub fn workspace_instructions_path(cwd: &Path) -> PathBuf {
+    get_git_repo_root(cwd)
+        .unwrap_or_else(|| cwd.to_path_buf())
+        .join(".codex")
+        .join(WORKSPACE_INSTRUCTIONS_FILENAME)
+}
+
+/// Loads the saved instructions for the project containing `cwd`, in the order
+/// they were added. A missing file yields an empty list.
+pub async fn load_workspace_instructions(cwd: &Path) -> io::Result<Vec<WorkspaceInstruction>> {
+    Ok(read_file(&workspace_instructions_path(cwd))
+        .await?
+        .instructions)
+}
+
+/// Saves a new instruction for the project containing `cwd`.
+pub async fn add_workspace_instruction(cwd: &Path, text: &str) -> io::Result<WorkspaceInstruction> {
+    let text = normalize_text(text)?;
+    let path = workspace_instructions_path(cwd);
+    let mut file = read_file(&path).await?;
+    let instruction = WorkspaceInstruction {
+        id: Uuid::new_v4().to_string(),
+        text,
+    };
+    file.instructions.push(instruction.clone());
+    write_file(&path, &file).await?;
+    Ok(instruction)
+}
+
+/// Replaces the text of instruction `id`. Returns `None` when no such
+/// instruction exists.
+pub async fn update_workspace_instruction(
+    cwd: &Path,
+    id: &str,
+    text: &str,
+) -> io::Result<Option<WorkspaceInstruction>> {
+    let text = normalize_text(text)?;
+    let path = workspace_instructions_path(cwd);
+    let mut file = read_file(&path).await?;
+    let Some(instruction) = file.instructions.iter_mut().find(|entry| entry.id == id) else {
+        return Ok(None);
+    };
+    instruction.text = text;
+    let updated = instruction.clone();
+    write_file(&path, &file).await?;
+    Ok(Some(updated))
+}
+
+/// Removes instruction `id`. Returns whether an instruction was removed.
+pub async fn remove_workspace_instruction(cwd: &Path, id: &str) -> io::Result<bool> {
+    let path = workspace_instructions_path(cwd);
+    let mut file = read_file(&path).await?;
+    let before = file.instructions.len();
+    file.instructions.retain(|entry| entry.id != id);
+    if file.instructions.len() == before {
+        return Ok(false);
+    }
+    write_file(&path, &file).await?;
+    Ok(true)
+}
+
+/// Renders saved instructions as a bullet list for the model, or `None` when
+/// there are none.
+pub(crate) fn render_workspace_instructions(
+    instructions: &[WorkspaceInstruction],
+) -> Option<String> {
+    if instructions.is_empty() {
+        return None;
+    }
+    let mut rendered =
+        "The user asked for the following to always apply in this workspace:\n".to_string();
+    for instruction in instructions {
+        rendered.push_str(&format!("- {}\n", instruction.text));
+    }
+    Some(rendered)
+}
+
+fn normalize_text(text: &str) -> io::Result<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "workspace instruction must not be empty",
+        ));
+    }
+    Ok(text)
+}
+
+async fn read_file(path: &Path) -> io::Result<WorkspaceInstructionsFile> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(contents) => serde_json::from_str(&contents)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            Ok(WorkspaceInstructionsFile::default())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+async fn write_file(path: &Path, file: &WorkspaceInstructionsFile) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut contents = serde_json::to_string_pretty(file).map_err(io::Error::other)?;
+    contents.push('\n');
+    tokio::fs::write(path, contents).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn instructions_are_stored_at_the_git_root() {
+        let repo = TempDir::new().expect("tempdir");
+        std::fs::create_dir(repo.path().join(".git")).expect("create .git");
+        let nested = repo.path().join("crates/app");
+        std::fs::create_dir_all(&nested).expect("create nested dir");
+
+        let added = add_workspace_instruction(&nested, "  never touch\n generated/ files ")
+            .await
+            .expect("add instruction");
+
+        assert_eq!(added.text, "never touch generated/ files");
+        assert!(
+            repo.path()
+                .join(".codex")
+                .join(WORKSPACE_INSTRUCTIONS_FILENAME)
+                .exists()
+        );
+        assert_eq!(
+            load_workspace_instructions(repo.path())
+                .await
+                .expect("load instructions"),
+            vec![added]
+        );
+    }
+
+    #[tokio::test]
+    async fn update_and_remove_report_missing_ids() {
+        let cwd = TempDir::new().expect("tempdir");
+        let first = add_workspace_instruction(cwd.path(), "always run tests with -j4")
+            .await
+            .expect("add first");
+        let second = add_workspace_instruction(cwd.path(), "prefer rg over grep")
+            .await
+            .expect("add second");
+
+        let updated = update_workspace_instruction(cwd.path(), &first.id, "run tests with -j8")
+            .await
+            .expect("update");
+        assert_eq!(
+            updated,
+            Some(WorkspaceInstruction {
+                id: first.id.clone(),
+                text: "run tests with -j8".to_string(),
+            })
+        );
+        assert_eq!(
+            update_workspace_instruction(cwd.path(), "missing", "text")
+                .await
+                .expect("update missing"),
+            None
+        );
+
+        assert!(
+            remove_workspace_instruction(cwd.path(), &second.id)
+                .await
+                .expect("remove")
+        );
+        assert!(
+            !remove_workspace_instruction(cwd.path(), &second.id)
+                .await
+                .expect("remove again")
+        );
+
+        let remaining = load_workspace_instructions(cwd.path()).await.expect("load");
+        assert_eq!(
+            render_workspace_instructions(&remaining),
+            Some(
+                "The user asked for the following to always apply in this workspace:\n- run tests with -j8\n"
+                    .to_string()
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn empty_instructions_are_rejected() {
+        let cwd = TempDir::new().expect("tempdir");
+        let err = add_workspace_instruction(cwd.path(), " \n ")
+            .await
+            .expect_err("empty text");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
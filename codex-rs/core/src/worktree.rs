@@ -0,0 +1,125 @@
+//! Per-conversation `git worktree` isolation (`worktree_isolation = true`).
+//!
+//! The session works in `<codex_home>/worktrees/<conversation id>`, checked
+//! out on the `codex/<conversation id>` branch from the primary checkout's
+//! `HEAD`. The primary checkout only changes when the worktree is merged back
+//! with `Op::MergeWorktree`; `Op::DiscardWorktree` drops it instead.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_git::GitToolingError;
+use codex_git::IsolatedWorktree;
+use codex_git::create_isolated_worktree;
+use codex_git::open_isolated_worktree;
+use codex_protocol::ConversationId;
+
+const WORKTREES_SUBDIR: &str = "worktrees";
+
+pub(crate) struct SessionWorktree {
+    worktree: IsolatedWorktree,
+    /// The session's cwd in the primary checkout.
+    original_cwd: PathBuf,
+}
+
+/// What merging a worktree back did to the primary checkout.
+pub(crate) struct WorktreeMerge {
+    /// Files left with conflict markers.
+    pub(crate) conflicted_paths: Vec<String>,
+}
+
+impl SessionWorktree {
+    /// Reopen the conversation's worktree, or create it if there is none.
+    /// Returns `Ok(None)` when `cwd` is not inside a git repository.
+    pub(crate) async fn open_or_create(
+        codex_home: &Path,
+        conversation_id: ConversationId,
+        cwd: &Path,
+    ) -> anyhow::Result<Option<Self>> {
+        let path = codex_home
+            .join(WORKTREES_SUBDIR)
+            .join(conversation_id.to_string());
+        let branch = format!("codex/{conversation_id}");
+        let original_cwd = cwd.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            let worktree = match open_isolated_worktree(&original_cwd, &path, &branch) {
+                Ok(Some(worktree)) => worktree,
+                Ok(None) => create_isolated_worktree(&original_cwd, &path, &branch)?,
+                Err(GitToolingError::NotAGitRepository { .. }) => return Ok(None),
+                Err(err) => return Err(err.into()),
+            };
+            Ok(Some(Self {
+                worktree,
+                original_cwd,
+            }))
+        })
+        .await?
+    }
+
+    /// The session's cwd inside the worktree.
+    pub(crate) fn cwd(&self) -> PathBuf {
+        self.worktree.map_path(&self.original_cwd)
+    }
+
+    pub(crate) fn original_cwd(&self) -> &Path {
+        &self.original_cwd
+    }
+
+    /// Translate a path in the primary checkout to the worktree.
+    pub(crate) fn map_path(&self, path: &Path) -> PathBuf {
+        self.worktree.map_path(path)
+    }
+
+    /// Translate a path in the worktree back to the primary checkout.
+    pub(crate) fn unmap_path(&self, path: &Path) -> PathBuf {
+        match path.strip_prefix(self.worktree.path()) {
+            Ok(relative) => self.worktree.repo_root().join(relative),
+            Err(_) => path.to_path_buf(),
+        }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        self.worktree.path()
+    }
+
+    pub(crate) fn branch(&self) -> &str {
+        self.worktree.branch()
+    }
+
+    pub(crate) fn base_commit(&self) -> &str {
+        self.worktree.base_commit()
+    }
+
+    pub(crate) async fn diff(&self) -> anyhow::Result<String> {
+        let worktree = self.worktree.clone();
+        Ok(tokio::task::spawn_blocking(move || worktree.diff()).await??)
+    }
+
+    /// Apply the worktree's changes to the primary checkout and remove the
+    /// worktree. The worktree is kept when the changes could not be applied.
+    pub(crate) async fn merge(&self) -> anyhow::Result<WorktreeMerge> {
+        let worktree = self.worktree.clone();
+        tokio::task::spawn_blocking(move || {
+            let conflicted_paths = match worktree.merge_into_primary()? {
+                None => Vec::new(),
+                Some(result) if result.exit_code == 0 || !result.conflicted_paths.is_empty() => {
+                    result.conflicted_paths
+                }
+                Some(result) => anyhow::bail!(
+                    "could not apply the worktree's changes to {}: {}",
+                    worktree.repo_root().display(),
+                    result.stderr.trim()
+                ),
+            };
+            worktree.remove()?;
+            Ok(WorktreeMerge { conflicted_paths })
+        })
+        .await?
+    }
+
+    /// Remove the worktree and its branch without applying its changes.
+    pub(crate) async fn discard(&self) -> anyhow::Result<()> {
+        let worktree = self.worktree.clone();
+        Ok(tokio::task::spawn_blocking(move || worktree.remove()).await??)
+    }
+}
@@ -0,0 +1,390 @@
+//! Writable roots granted on demand.
+//!
+//! Under `workspace-write`, a command or patch that targets paths outside the
+//! writable roots does not have to fail outright: Codex can ask the user to
+//! make the enclosing directory writable (`EventMsg::WriteScopeApprovalRequest`)
+//! and retry inside the sandbox. Grants last for the session, or are stored
+//! per project in `~/.codex/write_grants.json`, keyed like approval rules by
+//! the project root (the git repository root, or the session cwd outside of
+//! a repository).
+
+use std::collections::BTreeMap;
+use std::io;
+use std::io::ErrorKind;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::protocol::SandboxPolicy;
+use codex_protocol::protocol::WriteScopeDecision;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::fs;
+use tracing::warn;
+
+use crate::exec::ExecToolCallOutput;
+use crate::exec::SandboxType;
+use crate::git_info::get_git_repo_root;
+
+/// Filename that stores per-project write grants inside `~/.codex`.
+const WRITE_GRANTS_FILENAME: &str = "write_grants.json";
+
+/// Error messages that mean the sandbox refused a write.
+const WRITE_DENIED_MARKERS: &[&str] = &[
+    "Read-only file system",
+    "Permission denied",
+    "Operation not permitted",
+];
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct WriteGrantsFile {
+    #[serde(default)]
+    projects: BTreeMap<PathBuf, Vec<PathBuf>>,
+}
+
+/// Extra writable roots for a single project.
+#[derive(Debug)]
+pub(crate) struct WriteGrantStore {
+    path: PathBuf,
+    cwd: PathBuf,
+    project: PathBuf,
+    persisted: Vec<PathBuf>,
+    session: Vec<PathBuf>,
+}
+
+impl WriteGrantStore {
+    /// Load the roots granted for the project containing `cwd`. A missing or
+    /// unreadable store yields no grants.
+    pub(crate) fn load(codex_home: &Path, cwd: &Path) -> Self {
+        let path = codex_home.join(WRITE_GRANTS_FILENAME);
+        let project = get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+        let persisted = load_project_grants(&path, &project);
+        Self {
+            path,
+            cwd: cwd.to_path_buf(),
+            project,
+            persisted,
+            session: Vec::new(),
+        }
+    }
+
+    /// Switch to the roots granted for the project containing `cwd` when the
+    /// session moved into another project. Session grants are kept.
+    pub(crate) fn follow_cwd(&mut self, cwd: &Path) {
+        if cwd == self.cwd {
+            return;
+        }
+        self.cwd = cwd.to_path_buf();
+        let project = get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf());
+        if project != self.project {
+            self.persisted = load_project_grants(&self.path, &project);
+            self.project = project;
+        }
+    }
+
+    /// Remember `root` as writable. `GrantForProject` also persists it; the
+    /// grant applies even when persisting fails. `Deny` is a no-op.
+    pub(crate) async fn grant(
+        &mut self,
+        root: PathBuf,
+        decision: WriteScopeDecision,
+    ) -> io::Result<()> {
+        match decision {
+            WriteScopeDecision::Deny => Ok(()),
+            WriteScopeDecision::GrantForSession => {
+                if !self.covers(&root) {
+                    self.session.push(root);
+                }
+                Ok(())
+            }
+            WriteScopeDecision::GrantForProject => {
+                if self.persisted.contains(&root) {
+                    return Ok(());
+                }
+                self.session.retain(|existing| existing != &root);
+                self.persisted.push(root);
+                self.persist().await
+            }
+        }
+    }
+
+    /// `policy` with the granted roots added to its writable roots. Only
+    /// `workspace-write` is widened; other policies are returned unchanged.
+    pub(crate) fn apply(&self, policy: &SandboxPolicy) -> SandboxPolicy {
+        let mut policy = policy.clone();
+        if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = &mut policy {
+            for root in self.persisted.iter().chain(&self.session) {
+                if !writable_roots.contains(root) {
+                    writable_roots.push(root.clone());
+                }
+            }
+        }
+        policy
+    }
+
+    fn covers(&self, root: &Path) -> bool {
+        self.persisted
+            .iter()
+            .chain(&self.session)
+            .any(|granted| root.starts_with(granted))
+    }
+
+    async fn persist(&self) -> io::Result<()> {
+        // Re-read so grants written by other sessions for other projects survive.
+        let mut file = parse_grants_file(fs::read_to_string(&self.path).await)?;
+        file.projects
+            .insert(self.project.clone(), self.persisted.clone());
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        let contents = serde_json::to_string_pretty(&file).map_err(io::Error::other)?;
+        fs::write(&self.path, contents).await
+    }
+}
+
+/// Absolute paths named in the error output of a command the sandbox
+/// stopped, limited to those outside the writable roots of `policy`. The
+/// errors of a run without a sandbox are ordinary permission errors that no
+/// grant can fix, so that output yields no paths.
+pub(crate) fn denied_write_paths(
+    output: &ExecToolCallOutput,
+    sandbox: SandboxType,
+    policy: &SandboxPolicy,
+    cwd: &Path,
+) -> Vec<PathBuf> {
+    if sandbox == SandboxType::None {
+        return Vec::new();
+    }
+    let writable_roots = policy.get_writable_roots_with_cwd(cwd);
+    let mut paths = Vec::new();
+    for line in output.aggregated_output.text.lines() {
+        if !WRITE_DENIED_MARKERS
+            .iter()
+            .any(|marker| line.contains(marker))
+        {
+            continue;
+        }
+        for token in line.split_whitespace() {
+            let token = token.trim_matches(|c: char| {
+                matches!(c, '\'' | '"' | '`' | '‘' | '’' | ':' | ',' | '(' | ')')
+            });
+            let path = Path::new(token);
+            if !path.is_absolute() {
+                continue;
+            }
+            let writable = writable_roots
+                .iter()
+                .any(|root| root.is_path_writable(path));
+            if !writable && !paths.iter().any(|known: &PathBuf| known == path) {
+                paths.push(path.to_path_buf());
+            }
+        }
+    }
+    paths
+}
+
+/// The directory to offer for `paths`: the deepest existing directory that
+/// contains all of them. Returns `None` when that directory is too broad to
+/// grant; see [`is_grantable`].
+pub(crate) fn grant_root_for(paths: &[PathBuf]) -> Option<PathBuf> {
+    let mut root: Option<PathBuf> = None;
+    for path in paths {
+        let dir = existing_dir(path)?;
+        root = Some(match root {
+            None => dir,
+            Some(current) => common_ancestor(&current, &dir)?,
+        });
+    }
+    let home = dirs::home_dir();
+    root.filter(|root| is_grantable(root, home.as_deref()))
+}
+
+/// Whether `root` may be offered as a writable root. The filesystem root,
+/// top-level directories such as `/etc` or `/usr`, and the home directory
+/// or any of its ancestors are never offered.
+fn is_grantable(root: &Path, home: Option<&Path>) -> bool {
+    let depth = root
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .count();
+    depth >= 2 && !home.is_some_and(|home| home.starts_with(root))
+}
+
+/// `path` itself when it is a directory, otherwise its nearest existing
+/// ancestor directory.
+fn existing_dir(path: &Path) -> Option<PathBuf> {
+    if path.is_dir() {
+        return Some(path.to_path_buf());
+    }
+    path.ancestors()
+        .skip(1)
+        .find(|ancestor| ancestor.is_dir())
+        .map(Path::to_path_buf)
+}
+
+fn common_ancestor(a: &Path, b: &Path) -> Option<PathBuf> {
+    a.ancestors()
+        .find(|ancestor| b.starts_with(ancestor))
+        .map(Path::to_path_buf)
+}
+
+fn load_project_grants(path: &Path, project: &Path) -> Vec<PathBuf> {
+    match parse_grants_file(std::fs::read_to_string(path)) {
+        Ok(mut file) => file.projects.remove(project).unwrap_or_default(),
+        Err(err) => {
+            warn!("failed to load write grants from {}: {err}", path.display());
+            Vec::new()
+        }
+    }
+}
+
+fn parse_grants_file(contents: io::Result<String>) -> io::Result<WriteGrantsFile> {
+    match contents {
+        Ok(contents) => serde_json::from_str(&contents).map_err(io::Error::other),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(WriteGrantsFile::default()),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exec::StreamOutput;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    fn workspace_write() -> SandboxPolicy {
+        SandboxPolicy::WorkspaceWrite {
+            writable_roots: Vec::new(),
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn project_grants_persist_and_widen_workspace_write() {
+        let codex_home = tempdir().expect("codex home");
+        let project = tempdir().expect("project");
+        let extra = tempdir().expect("extra root");
+        let extra_root = extra.path().to_path_buf();
+
+        let mut store = WriteGrantStore::load(codex_home.path(), project.path());
+        store
+            .grant(extra_root.clone(), WriteScopeDecision::GrantForSession)
+            .await
+            .expect("session grant");
+        assert!(
+            WriteGrantStore::load(codex_home.path(), project.path())
+                .persisted
+                .is_empty()
+        );
+
+        store
+            .grant(extra_root.clone(), WriteScopeDecision::GrantForProject)
+            .await
+            .expect("project grant");
+        let reloaded = WriteGrantStore::load(codex_home.path(), project.path());
+        let SandboxPolicy::WorkspaceWrite { writable_roots, .. } =
+            reloaded.apply(&workspace_write())
+        else {
+            panic!("expected workspace-write");
+        };
+        assert_eq!(writable_roots, vec![extra_root]);
+        assert_eq!(
+            reloaded.apply(&SandboxPolicy::ReadOnly),
+            SandboxPolicy::ReadOnly
+        );
+    }
+
+    #[tokio::test]
+    async fn following_cwd_switches_to_the_new_projects_grants() {
+        let codex_home = tempdir().expect("codex home");
+        let project_a = tempdir().expect("project a");
+        let project_b = tempdir().expect("project b");
+        let extra = tempdir().expect("extra root");
+        let extra_root = extra.path().to_path_buf();
+
+        let mut store = WriteGrantStore::load(codex_home.path(), project_a.path());
+        store
+            .grant(extra_root.clone(), WriteScopeDecision::GrantForProject)
+            .await
+            .expect("project grant");
+
+        store.follow_cwd(project_b.path());
+        assert_eq!(store.apply(&workspace_write()), workspace_write());
+
+        store.follow_cwd(project_a.path());
+        let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = store.apply(&workspace_write())
+        else {
+            panic!("expected workspace-write");
+        };
+        assert_eq!(writable_roots, vec![extra_root]);
+    }
+
+    #[test]
+    fn finds_denied_paths_outside_writable_roots() {
+        let cwd = tempdir().expect("cwd");
+        let inside = cwd.path().join("out.txt");
+        let text = format!(
+            "touch: cannot touch '/opt/cache/data.bin': Read-only file system\n\
+             mkdir: cannot create directory ‘{}’: Permission denied\n\
+             note: see /usr/share/doc for details\n",
+            inside.display()
+        );
+        let output = ExecToolCallOutput {
+            exit_code: 1,
+            stdout: StreamOutput::new(String::new()),
+            stderr: StreamOutput::new(text.clone()),
+            aggregated_output: StreamOutput::new(text),
+            duration: Duration::ZERO,
+            timed_out: false,
+            output_truncation: None,
+        };
+
+        assert_eq!(
+            denied_write_paths(
+                &output,
+                SandboxType::LinuxSeccomp,
+                &workspace_write(),
+                cwd.path()
+            ),
+            vec![PathBuf::from("/opt/cache/data.bin")]
+        );
+        assert_eq!(
+            denied_write_paths(&output, SandboxType::None, &workspace_write(), cwd.path()),
+            Vec::<PathBuf>::new()
+        );
+    }
+
+    #[test]
+    fn grant_root_is_the_deepest_common_existing_directory() {
+        let base = tempdir().expect("base");
+        let a = base.path().join("a");
+        let b = base.path().join("b");
+        std::fs::create_dir_all(&a).expect("mkdir a");
+        std::fs::create_dir_all(&b).expect("mkdir b");
+
+        assert_eq!(grant_root_for(&[a.join("new/file.txt")]), Some(a.clone()));
+        assert_eq!(
+            grant_root_for(&[a.join("x.txt"), b.join("y.txt")]),
+            Some(base.path().to_path_buf())
+        );
+        assert_eq!(grant_root_for(&[PathBuf::from("/x.txt")]), None);
+    }
+
+    #[test]
+    fn home_and_top_level_directories_are_never_granted() {
+        let home = Path::new("/home/alice");
+
+        assert!(!is_grantable(Path::new("/"), Some(home)));
+        assert!(!is_grantable(Path::new("/etc"), Some(home)));
+        assert!(!is_grantable(Path::new("/usr"), Some(home)));
+        assert!(!is_grantable(Path::new("/home"), Some(home)));
+        assert!(!is_grantable(home, Some(home)));
+        assert!(is_grantable(Path::new("/home/alice/scratch"), Some(home)));
+        assert!(is_grantable(Path::new("/opt/cache"), Some(home)));
+        assert!(is_grantable(Path::new("/opt/cache"), None));
+    }
+}
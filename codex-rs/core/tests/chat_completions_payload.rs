@@ -317,3 +317,12 @@ async fn suppresses_duplicate_assistant_messages() {
         Value::String("dup".into())
     );
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn requests_usage_in_stream() {
+    skip_if_no_network!();
+
+    let body = run_request(vec![user_message("u1")]).await;
+
+    assert_eq!(body["stream_options"]["include_usage"], Value::Bool(true));
+}
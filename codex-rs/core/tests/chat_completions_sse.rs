@@ -381,6 +381,39 @@ async fn streams_reasoning_before_tool_call() {
     assert_matches!(events[4], ResponseEvent::Completed { .. });
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn reports_usage_from_trailing_chunk() {
+    skip_if_no_network!();
+
+    let sse = concat!(
+        "data: {\"choices\":[{\"delta\":{\"content\":\"ok\"}}]}\n\n",
+        "data: {\"choices\":[{\"delta\":{},\"finish_reason\":\"stop\"}],\"usage\":null}\n\n",
+        "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":12,\"completion_tokens\":3,\"total_tokens\":15,\"prompt_tokens_details\":{\"cached_tokens\":4}}}\n\n",
+        "data: [DONE]\n\n",
+    );
+
+    let events = run_stream(sse).await;
+    assert_eq!(events.len(), 4, "unexpected events: {events:?}");
+
+    match &events[2] {
+        ResponseEvent::OutputItemDone(item) => assert_message(item, "ok"),
+        other => panic!("expected terminal message, got {other:?}"),
+    }
+
+    match &events[3] {
+        ResponseEvent::Completed {
+            token_usage: Some(usage),
+            ..
+        } => {
+            assert_eq!(usage.input_tokens, 12);
+            assert_eq!(usage.cached_input_tokens, 4);
+            assert_eq!(usage.output_tokens, 3);
+            assert_eq!(usage.total_tokens, 15);
+        }
+        other => panic!("expected completed with usage, got {other:?}"),
+    }
+}
+
 #[tokio::test]
 #[traced_test]
 async fn chat_sse_emits_failed_on_parse_error() {
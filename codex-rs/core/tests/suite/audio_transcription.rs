@@ -0,0 +1,95 @@
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use wiremock::Mock;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn local_audio_is_replaced_by_its_transcript() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/audio/transcriptions"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "text": "please fix the failing test"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let mock = responses::mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_assistant_message("msg-1", "on it"),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    let TestCodex { codex, cwd, .. } = test_codex().build(&server).await?;
+    let audio_path = cwd.path().join("note.wav");
+    std::fs::write(&audio_path, b"RIFF fake wav")?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::LocalAudio {
+                path: audio_path.clone(),
+            }],
+        })
+        .await?;
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+
+    let texts = mock.single_request().message_input_texts("user");
+    assert_eq!(
+        texts.last().cloned(),
+        Some(format!(
+            "Transcript of audio `{}`:\nplease fix the failing test",
+            audio_path.display()
+        ))
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn unsupported_audio_format_is_rejected() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let TestCodex { codex, cwd, .. } = test_codex().build(&server).await?;
+    let audio_path = cwd.path().join("note.txt");
+    std::fs::write(&audio_path, b"not audio")?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::LocalAudio { path: audio_path }],
+        })
+        .await?;
+    let EventMsg::Error(error) =
+        wait_for_event(&codex, |event| matches!(event, EventMsg::Error(_))).await
+    else {
+        unreachable!();
+    };
+    assert!(
+        error.message.contains("supported audio formats"),
+        "unexpected error: {}",
+        error.message
+    );
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn run_command_expands_template_and_overrides_model_for_one_turn() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let turn = |id: &str| {
+        sse(vec![
+            ev_response_created(id),
+            ev_assistant_message(&format!("msg-{id}"), "done"),
+            ev_completed(id),
+        ])
+    };
+    let mock = responses::mount_sse_sequence(&server, vec![turn("resp-1"), turn("resp-2")]).await;
+
+    let TestCodex { codex, home, .. } = test_codex()
+        .with_config(|config| config.model = "gpt-5-codex".to_string())
+        .build(&server)
+        .await?;
+    let commands = home.path().join("commands");
+    std::fs::create_dir_all(&commands)?;
+    std::fs::write(
+        commands.join("release-notes.md"),
+        "---\nargs: VERSION\nmodel: gpt-5\n---\nDraft release notes for $VERSION.",
+    )?;
+
+    codex
+        .submit(Op::RunCommand {
+            name: "release-notes".to_string(),
+            args: HashMap::from([("VERSION".to_string(), "1.4.0".to_string())]),
+        })
+        .await?;
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "thanks".to_string(),
+            }],
+        })
+        .await?;
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].body_json()["model"], "gpt-5");
+    assert_eq!(
+        requests[0].message_input_texts("user").last().cloned(),
+        Some("Draft release notes for 1.4.0.".to_string())
+    );
+    assert_eq!(requests[1].body_json()["model"], "gpt-5-codex");
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn run_command_reports_missing_args() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let TestCodex { codex, home, .. } = test_codex().build(&server).await?;
+    let commands = home.path().join("commands");
+    std::fs::create_dir_all(&commands)?;
+    std::fs::write(
+        commands.join("release-notes.md"),
+        "---\nargs: VERSION\n---\nDraft release notes for $VERSION.",
+    )?;
+
+    codex
+        .submit(Op::RunCommand {
+            name: "release-notes".to_string(),
+            args: HashMap::new(),
+        })
+        .await?;
+    let EventMsg::Error(error) =
+        wait_for_event(&codex, |event| matches!(event, EventMsg::Error(_))).await
+    else {
+        unreachable!();
+    };
+    assert_eq!(error.message, "missing args for /release-notes: VERSION");
+
+    Ok(())
+}
@@ -19,6 +19,7 @@ mod abort_tasks;
 mod apply_patch_cli;
 #[cfg(not(target_os = "windows"))]
 mod approvals;
+mod audio_transcription;
 mod auth_refresh;
 mod cli_stream;
 mod client;
@@ -26,6 +27,7 @@ mod codex_delegate;
 mod compact;
 mod compact_remote;
 mod compact_resume_fork;
+mod custom_commands;
 mod deprecation_notice;
 mod exec;
 mod exec_policy;
@@ -38,9 +40,11 @@ mod live_cli;
 mod model_overrides;
 mod model_tools;
 mod otel;
+mod profiles;
 mod prompt_caching;
 mod quota_exceeded;
 mod read_file;
+mod remote_enforced_overrides;
 mod resume;
 mod review;
 mod rmcp_client;
@@ -49,6 +53,7 @@ mod seatbelt;
 mod shell_serialization;
 mod stream_error_allows_next_turn;
 mod stream_no_completed;
+mod token_budget;
 mod tool_harness;
 mod tool_parallelism;
 mod tools;
@@ -0,0 +1,53 @@
+use codex_core::CodexAuth;
+use codex_core::ConversationManager;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use core_test_support::load_default_config_for_test;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+use tempfile::TempDir;
+
+const CONFIG_TOML: &str = "config.toml";
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn set_profile_keeps_cli_overrides() {
+    let codex_home = TempDir::new().unwrap();
+    tokio::fs::write(
+        codex_home.path().join(CONFIG_TOML),
+        "[profiles.review]\nmodel = \"o3\"\napproval_policy = \"on-request\"\n",
+    )
+    .await
+    .expect("seed config.toml");
+
+    // Stands in for launching with `-c approval_policy=never`.
+    let mut config = load_default_config_for_test(&codex_home);
+    config.cli_overrides = vec![(
+        "approval_policy".to_string(),
+        toml::Value::String("never".to_string()),
+    )];
+    config.approval_policy = AskForApproval::Never;
+
+    let conversation_manager =
+        ConversationManager::with_auth(CodexAuth::from_api_key("Test API Key"));
+    let codex = conversation_manager
+        .new_conversation(config)
+        .await
+        .expect("create conversation")
+        .conversation;
+
+    codex
+        .submit(Op::SetProfile {
+            profile: "review".to_string(),
+        })
+        .await
+        .expect("submit profile switch");
+    let changed = wait_for_event_match(&codex, |ev| match ev {
+        EventMsg::ProfileChanged(event) => Some(event.clone()),
+        _ => None,
+    })
+    .await;
+
+    assert_eq!(changed.model, "o3");
+    assert_eq!(changed.approval_policy, AskForApproval::Never);
+}
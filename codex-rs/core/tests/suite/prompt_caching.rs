@@ -737,3 +737,71 @@ async fn send_user_turn_with_changes_sends_environment_context() -> anyhow::Resu
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn add_workspace_root_sends_environment_context() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+    use pretty_assertions::assert_eq;
+
+    let server = start_mock_server().await;
+    let req1 = mount_sse_once(&server, sse_completed("resp-1")).await;
+    let req2 = mount_sse_once(&server, sse_completed("resp-2")).await;
+
+    let TestCodex { codex, .. } = test_codex()
+        .with_config(|config| {
+            config.sandbox_policy = SandboxPolicy::new_workspace_write_policy();
+        })
+        .build(&server)
+        .await?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello 1".into(),
+            }],
+        })
+        .await?;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let extra_root = TempDir::new().unwrap();
+    let extra_root_path = extra_root.path().canonicalize()?;
+    codex
+        .submit(Op::AddWorkspaceRoot {
+            path: extra_root_path.clone(),
+        })
+        .await?;
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello 2".into(),
+            }],
+        })
+        .await?;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let body1 = req1.single_request().body_json();
+    let body2 = req2.single_request().body_json();
+    let expected_env_msg_2 = text_user_input(format!(
+        r#"<environment_context>
+  <sandbox_mode>workspace-write</sandbox_mode>
+  <network_access>restricted</network_access>
+  <writable_roots>
+    <root>{root}</root>
+  </writable_roots>
+  <workspace_roots>
+    <root>{root}</root>
+  </workspace_roots>
+</environment_context>"#,
+        root = extra_root_path.to_string_lossy(),
+    ));
+    let expected_input_2 = serde_json::json!(
+        [
+            body1["input"].as_array().unwrap().as_slice(),
+            [expected_env_msg_2, text_user_input("hello 2".to_string())].as_slice(),
+        ]
+        .concat()
+    );
+    assert_eq!(body2["input"], expected_input_2);
+
+    Ok(())
+}
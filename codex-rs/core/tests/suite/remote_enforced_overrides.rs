@@ -0,0 +1,141 @@
+use std::collections::BTreeSet;
+
+use anyhow::Result;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ResponseMock;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+
+async fn enforced_test_codex(server: &wiremock::MockServer) -> Result<TestCodex> {
+    test_codex()
+        .with_config(|config| {
+            config.approval_policy = AskForApproval::OnRequest;
+            config.sandbox_policy = SandboxPolicy::ReadOnly;
+            config.remote_enforced_keys =
+                BTreeSet::from(["approval_policy".to_string(), "sandbox_mode".to_string()]);
+        })
+        .build(server)
+        .await
+}
+
+async fn mount_completed_turn(server: &wiremock::MockServer) -> ResponseMock {
+    mount_sse_once(
+        server,
+        sse(vec![ev_response_created("resp-1"), ev_completed("resp-1")]),
+    )
+    .await
+}
+
+async fn ignored_override_warnings(test: &TestCodex) -> Vec<String> {
+    let mut warnings = Vec::new();
+    for _ in 0..2 {
+        let warning = wait_for_event_match(&test.codex, |ev| match ev {
+            EventMsg::Warning(warning) => Some(warning.message.clone()),
+            _ => None,
+        })
+        .await;
+        warnings.push(warning);
+    }
+    warnings
+}
+
+fn assert_enforced_policies_in_request(request: &ResponseMock) {
+    let input = request.single_request().body_json()["input"].to_string();
+    assert!(
+        input.contains("<approval_policy>on-request</approval_policy>")
+            && input.contains("<sandbox_mode>read-only</sandbox_mode>"),
+        "environment context should keep the enforced policies: {input}"
+    );
+    assert!(
+        !input.contains("<approval_policy>never</approval_policy>")
+            && !input.contains("<sandbox_mode>danger-full-access</sandbox_mode>"),
+        "environment context should not reflect the ignored overrides: {input}"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn override_turn_context_cannot_loosen_enforced_policies() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let request = mount_completed_turn(&server).await;
+    let test = enforced_test_codex(&server).await?;
+
+    test.codex
+        .submit(Op::OverrideTurnContext {
+            cwd: None,
+            approval_policy: Some(AskForApproval::Never),
+            sandbox_policy: Some(SandboxPolicy::DangerFullAccess),
+            model: None,
+            effort: None,
+            summary: None,
+        })
+        .await?;
+    assert_eq!(
+        ignored_override_warnings(&test).await,
+        vec![
+            "Ignored the override of `approval_policy`, which remote config enforces.".to_string(),
+            "Ignored the override of `sandbox_mode`, which remote config enforces.".to_string(),
+        ]
+    );
+
+    test.codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await?;
+    wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    assert_enforced_policies_in_request(&request);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn user_turn_cannot_loosen_enforced_policies() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let request = mount_completed_turn(&server).await;
+    let test = enforced_test_codex(&server).await?;
+
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "hello".into(),
+            }],
+            cwd: test.cwd_path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: test.config.model_reasoning_effort,
+            summary: test.config.model_reasoning_summary,
+            final_output_json_schema: None,
+        })
+        .await?;
+    assert_eq!(
+        ignored_override_warnings(&test).await,
+        vec![
+            "Ignored the override of `approval_policy`, which remote config enforces.".to_string(),
+            "Ignored the override of `sandbox_mode`, which remote config enforces.".to_string(),
+        ]
+    );
+    wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    assert_enforced_policies_in_request(&request);
+    Ok(())
+}
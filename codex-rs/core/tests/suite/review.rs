@@ -109,6 +109,7 @@ async fn review_op_emits_lifecycle_and_review_output() {
                 absolute_file_path: PathBuf::from("/tmp/file.rs"),
                 line_range: ReviewLineRange { start: 10, end: 20 },
             },
+            suggestion: None,
         }],
         overall_correctness: "good".to_string(),
         overall_explanation: "All good with some improvements suggested.".to_string(),
@@ -9,6 +9,7 @@ use std::time::SystemTime;
 use std::time::UNIX_EPOCH;
 
 use codex_core::config::types::McpServerConfig;
+use codex_core::config::types::McpServerStartup;
 use codex_core::config::types::McpServerTransportConfig;
 use codex_core::features::Feature;
 
@@ -98,6 +99,11 @@ async fn stdio_server_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    startup: McpServerStartup::Eager,
+                    idle_timeout_sec: None,
+                    warm_pool: false,
+                    tool_aliases: None,
+                    sampling: None,
                 },
             );
         })
@@ -236,6 +242,11 @@ async fn stdio_image_responses_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    startup: McpServerStartup::Eager,
+                    idle_timeout_sec: None,
+                    warm_pool: false,
+                    tool_aliases: None,
+                    sampling: None,
                 },
             );
         })
@@ -432,6 +443,11 @@ async fn stdio_image_completions_round_trip() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    startup: McpServerStartup::Eager,
+                    idle_timeout_sec: None,
+                    warm_pool: false,
+                    tool_aliases: None,
+                    sampling: None,
                 },
             );
         })
@@ -572,6 +588,11 @@ async fn stdio_server_propagates_whitelisted_env_vars() -> anyhow::Result<()> {
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    startup: McpServerStartup::Eager,
+                    idle_timeout_sec: None,
+                    warm_pool: false,
+                    tool_aliases: None,
+                    sampling: None,
                 },
             );
         })
@@ -715,12 +736,18 @@ async fn streamable_http_tool_call_round_trip() -> anyhow::Result<()> {
                         bearer_token_env_var: None,
                         http_headers: None,
                         env_http_headers: None,
+                        scopes: None,
                     },
                     enabled: true,
                     startup_timeout_sec: Some(Duration::from_secs(10)),
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    startup: McpServerStartup::Eager,
+                    idle_timeout_sec: None,
+                    warm_pool: false,
+                    tool_aliases: None,
+                    sampling: None,
                 },
             );
         })
@@ -896,12 +923,18 @@ async fn streamable_http_with_oauth_round_trip() -> anyhow::Result<()> {
                         bearer_token_env_var: None,
                         http_headers: None,
                         env_http_headers: None,
+                        scopes: None,
                     },
                     enabled: true,
                     startup_timeout_sec: Some(Duration::from_secs(10)),
                     tool_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
+                    startup: McpServerStartup::Eager,
+                    idle_timeout_sec: None,
+                    warm_pool: false,
+                    tool_aliases: None,
+                    sampling: None,
                 },
             );
         })
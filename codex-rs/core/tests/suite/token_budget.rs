@@ -0,0 +1,89 @@
+use anyhow::Result;
+use codex_core::protocol::BudgetExhaustedEvent;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_protocol::user_input::UserInput;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed_with_tokens;
+use core_test_support::responses::ev_response_created;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use core_test_support::wait_for_event_match;
+use pretty_assertions::assert_eq;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn exhausted_token_budget_refuses_turns_until_raised() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let responses = mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_assistant_message("msg-1", "first reply"),
+                ev_completed_with_tokens("resp-1", 500),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-2", "second reply"),
+                ev_completed_with_tokens("resp-2", 100),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.conversation_token_budget = Some(400);
+        })
+        .build(&server)
+        .await?;
+    let codex = test.codex.clone();
+
+    let submit_text = |text: &str| Op::UserInput {
+        items: vec![UserInput::Text { text: text.into() }],
+    };
+
+    codex.submit(submit_text("first")).await?;
+    let exhausted = wait_for_event_match(&codex, |ev| match ev {
+        EventMsg::BudgetExhausted(ev) => Some(ev.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(
+        exhausted,
+        BudgetExhaustedEvent {
+            budget: 400,
+            used: 500,
+        }
+    );
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    codex.submit(submit_text("second")).await?;
+    let error = wait_for_event_match(&codex, |ev| match ev {
+        EventMsg::Error(err) => Some(err.message.clone()),
+        _ => None,
+    })
+    .await;
+    assert_eq!(
+        error,
+        "This conversation used 500 tokens, exceeding its budget of 400. Raise the budget to continue."
+    );
+    assert_eq!(responses.requests().len(), 1);
+
+    codex
+        .submit(Op::SetTokenBudget {
+            budget: Some(1_000),
+        })
+        .await?;
+    codex.submit(submit_text("third")).await?;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+    assert_eq!(responses.requests().len(), 2);
+
+    Ok(())
+}
@@ -446,3 +446,75 @@ async fn apply_patch_reports_parse_diagnostics() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn ask_user_tool_returns_the_answer_to_the_model() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+
+    let mut builder = test_codex().with_config(|config| {
+        config.features.enable(Feature::AskUserTool);
+    });
+    let TestCodex {
+        codex,
+        cwd,
+        session_configured,
+        ..
+    } = builder.build(&server).await?;
+
+    let call_id = "ask-user-call";
+    let args = json!({ "question": "Which database should I target?" }).to_string();
+    let first_response = sse(vec![
+        ev_response_created("resp-1"),
+        ev_function_call(call_id, "ask_user", &args),
+        ev_completed("resp-1"),
+    ]);
+    responses::mount_sse_once(&server, first_response).await;
+
+    let second_response = sse(vec![
+        ev_assistant_message("msg-1", "targeting postgres"),
+        ev_completed("resp-2"),
+    ]);
+    let second_mock = responses::mount_sse_once(&server, second_response).await;
+
+    codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "migrate the schema".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+
+    let request = wait_for_event(&codex, |event| {
+        matches!(event, EventMsg::UserInputRequest(_))
+    })
+    .await;
+    let EventMsg::UserInputRequest(request) = request else {
+        panic!("expected UserInputRequest");
+    };
+    assert_eq!(request.call_id, call_id);
+    assert_eq!(request.question, "Which database should I target?");
+
+    codex
+        .submit(Op::UserInputAnswer {
+            call_id: call_id.to_string(),
+            answer: Some("postgres".to_string()),
+        })
+        .await?;
+    wait_for_event(&codex, |event| matches!(event, EventMsg::TaskComplete(_))).await;
+
+    let req = second_mock.single_request();
+    let (output_text, success) = call_output(&req, call_id);
+    assert_eq!(output_text, "postgres");
+    assert_eq!(success, Some(true));
+
+    Ok(())
+}
@@ -4,6 +4,7 @@
 use anyhow::Context;
 use anyhow::Result;
 use codex_core::config::types::McpServerConfig;
+use codex_core::config::types::McpServerStartup;
 use codex_core::config::types::McpServerTransportConfig;
 use codex_core::features::Feature;
 use codex_core::model_family::find_family_for_model;
@@ -467,6 +468,11 @@ async fn mcp_tool_call_output_exceeds_limit_truncated_for_model() -> Result<()>
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         );
         config.tool_output_token_limit = Some(500);
@@ -560,6 +566,11 @@ async fn mcp_image_output_preserves_image_and_no_text_summary() -> Result<()> {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         );
     });
@@ -825,6 +836,11 @@ async fn mcp_tool_call_output_not_truncated_with_custom_limit() -> Result<()> {
                 tool_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
+                startup: McpServerStartup::Eager,
+                idle_timeout_sec: None,
+                warm_pool: false,
+                tool_aliases: None,
+                sampling: None,
             },
         );
     });
@@ -1,9 +1,12 @@
 #![cfg(not(target_os = "windows"))]
 
 use std::fs;
+use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
+use std::path::PathBuf;
 use std::process::Command;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Context;
 use anyhow::Result;
@@ -14,7 +17,11 @@ use codex_core::features::Feature;
 use codex_core::model_family::find_family_for_model;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
+use codex_core::protocol::TurnDiffFile;
+use codex_core::protocol::TurnDiffSummary;
 use codex_core::protocol::UndoCompletedEvent;
+use codex_protocol::user_input::UserInput;
+use core_test_support::fs_wait;
 use core_test_support::responses::ev_apply_patch_function_call;
 use core_test_support::responses::ev_assistant_message;
 use core_test_support::responses::ev_completed;
@@ -25,14 +32,24 @@ use core_test_support::skip_if_no_network;
 use core_test_support::test_codex::TestCodexHarness;
 use core_test_support::wait_for_event_match;
 use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use tempfile::TempDir;
 
-#[allow(clippy::expect_used)]
 async fn undo_harness() -> Result<TestCodexHarness> {
-    TestCodexHarness::with_config(|config: &mut Config| {
+    undo_harness_with(|_| {}).await
+}
+
+#[allow(clippy::expect_used)]
+async fn undo_harness_with(
+    mutator: impl FnOnce(&mut Config) + Send + 'static,
+) -> Result<TestCodexHarness> {
+    TestCodexHarness::with_config(move |config: &mut Config| {
         config.include_apply_patch_tool = true;
         config.model = "gpt-5.1".to_string();
         config.model_family = find_family_for_model("gpt-5.1").expect("gpt-5.1 is valid");
         config.features.enable(Feature::GhostCommit);
+        mutator(config);
     })
     .await
 }
@@ -168,6 +185,51 @@ async fn undo_removes_new_file_created_during_turn() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn undo_sends_snapshot_restored_notification() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let notify_dir = TempDir::new()?;
+    let notify_script = notify_dir.path().join("notify.sh");
+    // Write through a temporary file so the payload appears all at once.
+    fs::write(
+        &notify_script,
+        r#"#!/bin/bash
+set -e
+dir=$(dirname "${0}")
+echo -n "${@: -1}" > "$dir/notify.tmp"
+mv "$dir/notify.tmp" "$dir/notify.txt""#,
+    )?;
+    fs::set_permissions(&notify_script, fs::Permissions::from_mode(0o755))?;
+    let notify_file = notify_dir.path().join("notify.txt");
+    let notify_command = notify_script.to_string_lossy().into_owned();
+
+    let harness = undo_harness_with(move |config| {
+        config.notify = Some(vec![notify_command]);
+        config.notify_types = Some(vec!["snapshot-restored".to_string()]);
+    })
+    .await?;
+    init_git_repo(harness.cwd())?;
+
+    let patch = "*** Begin Patch\n*** Add File: new_file.txt\n+from turn\n*** End Patch";
+    run_apply_patch_turn(&harness, "create file", "undo-notify", patch, "ok").await?;
+    assert!(
+        !notify_file.exists(),
+        "only snapshot-restored notifications should reach the notify program"
+    );
+
+    let codex = Arc::clone(&harness.test().codex);
+    expect_successful_undo(&codex).await?;
+
+    fs_wait::wait_for_path_exists(&notify_file, Duration::from_secs(5)).await?;
+    let payload: Value = serde_json::from_str(&fs::read_to_string(&notify_file)?)?;
+    assert_eq!(payload["type"], json!("snapshot-restored"));
+    let commit_id = payload["commit-id"].as_str().unwrap_or_default();
+    assert!(!commit_id.is_empty(), "missing commit-id in {payload}");
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn undo_restores_tracked_file_edit() -> Result<()> {
     skip_if_no_network!(Ok(()));
@@ -489,3 +551,50 @@ async fn undo_overwrites_manual_edits_after_turn() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn task_complete_reports_turn_diff_summary() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let harness = undo_harness().await?;
+    init_git_repo(harness.cwd())?;
+
+    let tracked = harness.path("tracked.txt");
+    fs::write(&tracked, "one\ntwo\n")?;
+    git(harness.cwd(), &["add", "tracked.txt"])?;
+    git(harness.cwd(), &["commit", "-m", "track file"])?;
+    // Changed before the turn, so not part of its diff.
+    fs::write(harness.path("README.txt"), "edited by the user\n")?;
+
+    let patch =
+        "*** Begin Patch\n*** Update File: tracked.txt\n@@\n-two\n+three\n+four\n*** End Patch";
+    mount_sse_sequence(
+        harness.server(),
+        apply_patch_responses("diff-summary", patch, "done"),
+    )
+    .await;
+    let codex = Arc::clone(&harness.test().codex);
+    codex
+        .submit(Op::UserInput {
+            items: vec![UserInput::Text {
+                text: "update tracked file".into(),
+            }],
+        })
+        .await?;
+    let diff_summary = wait_for_event_match(&codex, |msg| match msg {
+        EventMsg::TaskComplete(done) => Some(done.diff_summary.clone()),
+        _ => None,
+    })
+    .await;
+
+    assert_eq!(
+        diff_summary,
+        Some(TurnDiffSummary::new(vec![TurnDiffFile {
+            path: PathBuf::from("tracked.txt"),
+            insertions: 2,
+            deletions: 1,
+        }]))
+    );
+
+    Ok(())
+}
@@ -4,32 +4,64 @@ use codex_core::config::Config;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::BackgroundTaskStatus;
+use codex_core::protocol::BackgroundTaskUpdateEvent;
+use codex_core::protocol::BudgetExhaustedEvent;
+use codex_core::protocol::CompactionTrigger;
+use codex_core::protocol::ContextCompactedEvent;
 use codex_core::protocol::DeprecationNoticeEvent;
+use codex_core::protocol::DiagnosticSeverity;
+use codex_core::protocol::ElicitationRequestEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::FileLockWaitEvent;
+use codex_core::protocol::InstructionsChangedEvent;
 use codex_core::protocol::McpInvocation;
+use codex_core::protocol::McpServerStatus;
+use codex_core::protocol::McpServerStatusChangedEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::McpToolConflictsEvent;
+use codex_core::protocol::NetworkHostBlockedEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::PatchDiagnosticsEvent;
+use codex_core::protocol::ProfileChangedEvent;
+use codex_core::protocol::ProviderHealthChangedEvent;
+use codex_core::protocol::ProviderHealthStatus;
+use codex_core::protocol::PullRequestCreatedEvent;
+use codex_core::protocol::RateLimitedEvent;
+use codex_core::protocol::SecretsRedactedEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::StreamErrorEvent;
+use codex_core::protocol::SubAgentStatus;
+use codex_core::protocol::SubAgentUpdateEvent;
 use codex_core::protocol::TaskCompleteEvent;
+use codex_core::protocol::ThreadMetadataChangedEvent;
+use codex_core::protocol::ToolsChangedEvent;
 use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::TurnCommit;
 use codex_core::protocol::TurnDiffEvent;
+use codex_core::protocol::UserInputRequestEvent;
 use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::protocol::WorktreeClosedEvent;
+use codex_core::protocol::WorktreeDiffEvent;
+use codex_core::protocol::WorktreeOutcome;
 use codex_protocol::num_format::format_with_separators;
 use owo_colors::OwoColorize;
 use owo_colors::Style;
 use shlex::try_join;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
 
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
@@ -172,6 +204,219 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     "warning:".style(self.yellow).style(self.bold)
                 );
             }
+            EventMsg::NetworkHostBlocked(NetworkHostBlockedEvent { host, port }) => {
+                ts_msg!(
+                    self,
+                    "{} {host}:{port} is not in network_allowed_hosts",
+                    "network blocked:".style(self.yellow).style(self.bold)
+                );
+            }
+            EventMsg::BudgetExhausted(BudgetExhaustedEvent { budget, used }) => {
+                ts_msg!(
+                    self,
+                    "{} used {used} of {budget} tokens",
+                    "token budget exhausted:"
+                        .style(self.yellow)
+                        .style(self.bold)
+                );
+            }
+            EventMsg::ContextCompacted(ContextCompactedEvent {
+                trigger,
+                tokens_before,
+                tokens_after,
+            }) => {
+                let trigger = match trigger {
+                    CompactionTrigger::Manual => "manual",
+                    CompactionTrigger::Auto => "auto",
+                };
+                let tokens = match (tokens_before, tokens_after) {
+                    (Some(before), Some(after)) => format!(" ({before} -> {after} tokens)"),
+                    _ => String::new(),
+                };
+                ts_msg!(
+                    self,
+                    "{} {trigger}{tokens}",
+                    "context compacted:".style(self.cyan).style(self.bold)
+                );
+            }
+            EventMsg::ProviderHealthChanged(ProviderHealthChangedEvent {
+                provider,
+                status,
+                message,
+                retry_in_ms,
+            }) => match status {
+                ProviderHealthStatus::Degraded => {
+                    let retry = retry_in_ms
+                        .map(|ms| format!("; retrying in {}s", ms.div_ceil(1000)))
+                        .unwrap_or_default();
+                    let reason = message.map(|m| format!(": {m}")).unwrap_or_default();
+                    ts_msg!(
+                        self,
+                        "{} {provider} is failing{reason}{retry}",
+                        "provider degraded:".style(self.yellow).style(self.bold)
+                    );
+                }
+                ProviderHealthStatus::Healthy => {
+                    ts_msg!(
+                        self,
+                        "{} {provider} is responding again",
+                        "provider recovered:".style(self.green).style(self.bold)
+                    );
+                }
+            },
+            EventMsg::RateLimited(RateLimitedEvent {
+                resume_at,
+                window,
+                used_percent,
+            }) => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_secs());
+                let wait =
+                    Duration::from_secs(u64::try_from(resume_at).unwrap_or(0).saturating_sub(now));
+                ts_msg!(
+                    self,
+                    "{} {window} window at {used_percent:.0}%; waiting {} for it to reset",
+                    "rate limited:".style(self.yellow).style(self.bold),
+                    format_duration(wait)
+                );
+            }
+            EventMsg::BackgroundTaskUpdate(BackgroundTaskUpdateEvent {
+                task_id,
+                command,
+                status,
+                exit_code,
+            }) => {
+                let status = match (status, exit_code) {
+                    (BackgroundTaskStatus::Running, _) => "started".to_string(),
+                    (BackgroundTaskStatus::Exited, Some(code)) => format!("exited with {code}"),
+                    (BackgroundTaskStatus::Exited, None) => "exited".to_string(),
+                    (BackgroundTaskStatus::Terminated, _) => "terminated".to_string(),
+                };
+                ts_msg!(
+                    self,
+                    "{} {task_id} {status}: {command}",
+                    "background task".style(self.magenta).style(self.bold)
+                );
+            }
+            EventMsg::SubAgentUpdate(SubAgentUpdateEvent {
+                conversation_id,
+                model,
+                status,
+                message,
+                ..
+            }) => {
+                let status = match status {
+                    SubAgentStatus::Running => "started",
+                    SubAgentStatus::Completed => "completed",
+                    SubAgentStatus::Failed => "failed",
+                };
+                ts_msg!(
+                    self,
+                    "{} {conversation_id} ({model}) {status}",
+                    "sub-agent".style(self.magenta).style(self.bold)
+                );
+                if let Some(message) = message {
+                    ts_msg!(self, "{}", message.style(self.dimmed));
+                }
+            }
+            EventMsg::WorktreeDiff(WorktreeDiffEvent {
+                worktree_path,
+                branch,
+                unified_diff,
+                ..
+            }) => {
+                ts_msg!(
+                    self,
+                    "{} {} ({branch})",
+                    "worktree".style(self.magenta).style(self.bold),
+                    worktree_path.display()
+                );
+                ts_msg!(self, "{unified_diff}");
+            }
+            EventMsg::WorktreeClosed(WorktreeClosedEvent {
+                outcome,
+                conflicted_paths,
+                cwd,
+            }) => {
+                let outcome = match outcome {
+                    WorktreeOutcome::Merged => "merged into",
+                    WorktreeOutcome::Discarded => "discarded; now working in",
+                };
+                ts_msg!(
+                    self,
+                    "{} {outcome} {}",
+                    "worktree".style(self.magenta).style(self.bold),
+                    cwd.display()
+                );
+                for path in conflicted_paths {
+                    ts_msg!(self, "{} {path}", "conflict".style(self.red));
+                }
+            }
+            EventMsg::PullRequestCreated(PullRequestCreatedEvent {
+                url,
+                number,
+                head,
+                base,
+                ..
+            }) => {
+                ts_msg!(
+                    self,
+                    "{} #{number} ({head} -> {base}) {url}",
+                    "pull request".style(self.magenta).style(self.bold)
+                );
+            }
+            EventMsg::InstructionsChanged(InstructionsChangedEvent { sources }) => {
+                let sources = sources
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ts_msg!(
+                    self,
+                    "{} {sources}",
+                    "instructions updated:".style(self.magenta).style(self.bold)
+                );
+            }
+            EventMsg::ProfileChanged(ProfileChangedEvent {
+                profile, changes, ..
+            }) => {
+                ts_msg!(
+                    self,
+                    "{} {profile}",
+                    "profile:".style(self.magenta).style(self.bold)
+                );
+                for change in changes {
+                    ts_msg!(
+                        self,
+                        "  {}: {} -> {}",
+                        change.setting,
+                        change.previous,
+                        change.current
+                    );
+                }
+            }
+            EventMsg::ThreadMetadataChanged(ThreadMetadataChangedEvent { title }) => {
+                if let Some(title) = title {
+                    ts_msg!(
+                        self,
+                        "{} {title}",
+                        "title:".style(self.magenta).style(self.bold)
+                    );
+                }
+            }
+            EventMsg::SecretsRedacted(SecretsRedactedEvent { counts }) => {
+                let counts = counts
+                    .iter()
+                    .map(|entry| format!("{} {}", entry.count, entry.kind))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ts_msg!(
+                    self,
+                    "{} {counts}",
+                    "secrets redacted:".style(self.magenta).style(self.bold)
+                );
+            }
             EventMsg::DeprecationNotice(DeprecationNoticeEvent { summary, details }) => {
                 ts_msg!(
                     self,
@@ -218,6 +463,76 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 };
                 ts_msg!(self, "{} {}", "mcp startup:".style(self.cyan), joined);
             }
+            EventMsg::ElicitationRequest(ElicitationRequestEvent {
+                server_name,
+                message,
+                ..
+            }) => {
+                ts_msg!(
+                    self,
+                    "{} {server_name} asked for input and was declined: {message}",
+                    "mcp:".style(self.cyan)
+                );
+            }
+            EventMsg::UserInputRequest(UserInputRequestEvent { question, .. }) => {
+                ts_msg!(
+                    self,
+                    "{} asked the user and got no answer: {question}",
+                    "codex".style(self.italic).style(self.magenta)
+                );
+            }
+            EventMsg::McpServerStatusChanged(McpServerStatusChangedEvent { server, status }) => {
+                let status_text = match status {
+                    McpServerStatus::Healthy => "healthy".to_string(),
+                    McpServerStatus::Unhealthy {
+                        consecutive_failures,
+                        error,
+                    } => format!("unhealthy ({consecutive_failures} failed pings): {error}"),
+                    McpServerStatus::Restarting { attempt } => {
+                        format!("restarting (attempt {attempt})")
+                    }
+                    McpServerStatus::Failed { error } => format!("failed: {error}"),
+                    McpServerStatus::Starting => "starting".to_string(),
+                    McpServerStatus::Ready => "ready".to_string(),
+                    McpServerStatus::Stopped { idle_secs } => {
+                        format!("stopped after {idle_secs}s idle")
+                    }
+                };
+                ts_msg!(self, "{} {server} {status_text}", "mcp:".style(self.cyan));
+            }
+            EventMsg::ToolsChanged(ToolsChangedEvent {
+                server,
+                added,
+                removed,
+                updated,
+            }) => {
+                ts_msg!(
+                    self,
+                    "{} {server} tools changed (added: {}, removed: {}, updated: {})",
+                    "mcp:".style(self.cyan),
+                    added.len(),
+                    removed.len(),
+                    updated.len()
+                );
+            }
+            EventMsg::McpToolConflicts(McpToolConflictsEvent { conflicts }) => {
+                for conflict in conflicts {
+                    let dropped = conflict
+                        .dropped
+                        .iter()
+                        .map(|tool| format!("{}/{}", tool.server, tool.tool))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    ts_msg!(
+                        self,
+                        "{} tool name {} kept for {}/{}, skipped {dropped}",
+                        "mcp:".style(self.yellow),
+                        conflict.name,
+                        conflict.kept.server,
+                        conflict.kept.tool
+                    );
+                }
+            }
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 ts_msg!(self, "{}", message.style(self.dimmed));
             }
@@ -227,7 +542,24 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::TaskStarted(_) => {
                 // Ignore.
             }
-            EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+            EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message,
+                turn_commit,
+                ..
+            }) => {
+                if let Some(TurnCommit {
+                    commit,
+                    branch,
+                    message,
+                }) = turn_commit
+                {
+                    ts_msg!(
+                        self,
+                        "{} {commit} on {branch}: {}",
+                        "committed".style(self.magenta).style(self.bold),
+                        message.lines().next().unwrap_or_default()
+                    );
+                }
                 let last_message = last_agent_message.as_deref();
                 if let Some(output_file) = self.last_message_path.as_deref() {
                     handle_last_message(last_message, output_file);
@@ -458,6 +790,40 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     eprintln!("{}", line.style(self.dimmed));
                 }
             }
+            EventMsg::PatchDiagnostics(PatchDiagnosticsEvent { files, .. }) => {
+                for file in files {
+                    for diagnostic in file.diagnostics {
+                        let style = match diagnostic.severity {
+                            DiagnosticSeverity::Error => self.red,
+                            DiagnosticSeverity::Warning => self.yellow,
+                            DiagnosticSeverity::Information | DiagnosticSeverity::Hint => {
+                                self.dimmed
+                            }
+                        };
+                        ts_msg!(
+                            self,
+                            "{} {}:{}:{}: {}",
+                            format!("{}:", diagnostic.severity).style(style),
+                            file.path.display(),
+                            diagnostic.line,
+                            diagnostic.column,
+                            diagnostic.message
+                        );
+                    }
+                }
+            }
+            EventMsg::FileLockWait(FileLockWaitEvent { paths, .. }) => {
+                let paths = paths
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ts_msg!(
+                    self,
+                    "{} another tool call is writing {paths}",
+                    "waiting:".style(self.cyan)
+                );
+            }
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => {
                 ts_msg!(
                     self,
@@ -549,10 +915,18 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::WebSearchBegin(_)
             | EventMsg::ExecApprovalRequest(_)
             | EventMsg::ApplyPatchApprovalRequest(_)
+            | EventMsg::WriteScopeApprovalRequest(_)
             | EventMsg::ExecCommandOutputDelta(_)
+            | EventMsg::TurnHeartbeat(_)
             | EventMsg::GetHistoryEntryResponse(_)
+            | EventMsg::PatchContentResponse(_)
+            | EventMsg::WorkspaceInstructionProposed(_)
             | EventMsg::McpListToolsResponse(_)
             | EventMsg::ListCustomPromptsResponse(_)
+            | EventMsg::ListApprovalRulesResponse(_)
+            | EventMsg::McpListPromptsResponse(_)
+            | EventMsg::McpToolCallProgress(_)
+            | EventMsg::McpResourceReadResponse(_)
             | EventMsg::RawResponseItem(_)
             | EventMsg::UserMessage(_)
             | EventMsg::EnteredReviewMode(_)
@@ -26,6 +26,7 @@ use codex_core::config::load_config_as_toml_with_cli_overrides;
 use codex_core::config::resolve_oss_provider;
 use codex_core::git_info::get_git_repo_root;
 use codex_core::protocol::AskForApproval;
+use codex_core::protocol::ElicitationAction;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::Op;
@@ -34,7 +35,6 @@ use codex_protocol::config_types::SandboxMode;
 use codex_protocol::user_input::UserInput;
 use event_processor_with_human_output::EventProcessorWithHumanOutput;
 use event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
-use opentelemetry_appender_tracing::layer::OpenTelemetryTracingBridge;
 use serde_json::Value;
 use std::io::IsTerminal;
 use std::io::Read;
@@ -231,6 +231,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         tools_web_search_request: None,
         experimental_sandbox_command_assessment: None,
         additional_writable_roots: add_dir,
+        tools_enabled: None,
+        tools_disabled: None,
+        account: None,
     };
 
     let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
@@ -252,9 +255,11 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     };
 
     if let Some(provider) = otel.as_ref() {
-        let otel_layer = OpenTelemetryTracingBridge::new(&provider.logger).with_filter(
-            tracing_subscriber::filter::filter_fn(codex_core::otel_init::codex_export_filter),
-        );
+        let otel_layer = provider
+            .log_layer()
+            .with_filter(tracing_subscriber::filter::filter_fn(
+                codex_core::otel_init::codex_export_filter,
+            ));
 
         let _ = tracing_subscriber::registry()
             .with(fmt_layer)
@@ -302,8 +307,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         std::process::exit(1);
     }
 
-    let auth_manager = AuthManager::shared(
+    let auth_manager = AuthManager::shared_with_account(
         config.codex_home.clone(),
+        config.account.clone(),
         true,
         config.cli_auth_credentials_store_mode,
     );
@@ -404,6 +410,27 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         if matches!(event.msg, EventMsg::Error(_)) {
             error_seen = true;
         }
+        // Nobody can answer an MCP elicitation in exec mode; decline right away
+        // instead of leaving the server waiting for the timeout.
+        if let EventMsg::ElicitationRequest(request) = &event.msg {
+            conversation
+                .submit(Op::ResolveElicitation {
+                    server_name: request.server_name.clone(),
+                    request_id: request.id.clone(),
+                    decision: ElicitationAction::Decline,
+                    content: None,
+                })
+                .await?;
+        }
+        // Likewise for questions from the ask_user tool.
+        if let EventMsg::UserInputRequest(request) = &event.msg {
+            conversation
+                .submit(Op::UserInputAnswer {
+                    call_id: request.call_id.clone(),
+                    answer: None,
+                })
+                .await?;
+        }
         let shutdown: CodexStatus = event_processor.process_event(event);
         match shutdown {
             CodexStatus::Running => continue,
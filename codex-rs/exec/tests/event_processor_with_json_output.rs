@@ -1,6 +1,7 @@
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::AgentReasoningEvent;
 use codex_core::protocol::AskForApproval;
+use codex_core::protocol::CodexErrorInfo;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
@@ -217,6 +218,8 @@ fn plan_update_emits_todo_list_started_updated_and_completed() {
         "p3",
         EventMsg::TaskComplete(codex_core::protocol::TaskCompleteEvent {
             last_agent_message: None,
+            turn_commit: None,
+            diff_summary: None,
         }),
     );
     let out_complete = ep.collect_thread_events(&complete);
@@ -461,6 +464,8 @@ fn plan_update_after_complete_starts_new_todo_list_with_new_id() {
         "t2",
         EventMsg::TaskComplete(codex_core::protocol::TaskCompleteEvent {
             last_agent_message: None,
+            turn_commit: None,
+            diff_summary: None,
         }),
     );
     let _ = ep.collect_thread_events(&complete);
@@ -540,6 +545,7 @@ fn error_event_produces_error() {
         EventMsg::Error(codex_core::protocol::ErrorEvent {
             message: "boom".to_string(),
             http_status_code: Some(500),
+            info: CodexErrorInfo::default(),
         }),
     ));
     assert_eq!(
@@ -580,6 +586,7 @@ fn stream_error_event_produces_error() {
         EventMsg::StreamError(codex_core::protocol::StreamErrorEvent {
             message: "retrying".to_string(),
             http_status_code: Some(500),
+            info: CodexErrorInfo::default(),
         }),
     ));
     assert_eq!(
@@ -599,6 +606,7 @@ fn error_followed_by_task_complete_produces_turn_failed() {
         EventMsg::Error(ErrorEvent {
             message: "boom".to_string(),
             http_status_code: Some(500),
+            info: CodexErrorInfo::default(),
         }),
     );
     assert_eq!(
@@ -612,6 +620,8 @@ fn error_followed_by_task_complete_produces_turn_failed() {
         "e2",
         EventMsg::TaskComplete(codex_core::protocol::TaskCompleteEvent {
             last_agent_message: None,
+            turn_commit: None,
+            diff_summary: None,
         }),
     );
     assert_eq!(
@@ -952,6 +962,8 @@ fn task_complete_produces_turn_completed_with_usage() {
         EventMsg::TokenCount(codex_core::protocol::TokenCountEvent {
             info: Some(info),
             rate_limits: None,
+            cost: None,
+            breakdown: None,
         }),
     );
     assert!(ep.collect_thread_events(&token_count_event).is_empty());
@@ -961,6 +973,8 @@ fn task_complete_produces_turn_completed_with_usage() {
         "e2",
         EventMsg::TaskComplete(codex_core::protocol::TaskCompleteEvent {
             last_agent_message: Some("done".to_string()),
+            turn_commit: None,
+            diff_summary: None,
         }),
     );
     let out = ep.collect_thread_events(&complete_event);
@@ -7,6 +7,8 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub enum Error {
     #[error("invalid decision: {0}")]
     InvalidDecision(String),
+    #[error("invalid risk level: {0}")]
+    InvalidRisk(String),
     #[error("invalid pattern element: {0}")]
     InvalidPattern(String),
     #[error("invalid example: {0}")]
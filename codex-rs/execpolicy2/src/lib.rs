@@ -2,6 +2,7 @@ pub mod decision;
 pub mod error;
 pub mod parser;
 pub mod policy;
+pub mod risk;
 pub mod rule;
 
 pub use decision::Decision;
@@ -10,6 +11,9 @@ pub use error::Result;
 pub use parser::PolicyParser;
 pub use policy::Evaluation;
 pub use policy::Policy;
+pub use risk::RiskLevel;
+pub use risk::RiskMatch;
+pub use risk::RiskRule;
 pub use rule::Rule;
 pub use rule::RuleMatch;
 pub use rule::RuleRef;
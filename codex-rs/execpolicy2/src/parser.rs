@@ -18,6 +18,8 @@ use std::sync::Arc;
 use crate::decision::Decision;
 use crate::error::Error;
 use crate::error::Result;
+use crate::risk::RiskLevel;
+use crate::risk::RiskRule;
 use crate::rule::PatternToken;
 use crate::rule::PrefixPattern;
 use crate::rule::PrefixRule;
@@ -71,12 +73,14 @@ impl PolicyParser {
 #[derive(Debug, ProvidesStaticType)]
 struct PolicyBuilder {
     rules_by_program: MultiMap<String, RuleRef>,
+    risk_rules_by_program: MultiMap<String, RiskRule>,
 }
 
 impl PolicyBuilder {
     fn new() -> Self {
         Self {
             rules_by_program: MultiMap::new(),
+            risk_rules_by_program: MultiMap::new(),
         }
     }
 
@@ -85,8 +89,13 @@ impl PolicyBuilder {
             .insert(rule.program().to_string(), rule);
     }
 
+    fn add_risk_rule(&mut self, rule: RiskRule) {
+        self.risk_rules_by_program
+            .insert(rule.program().to_string(), rule);
+    }
+
     fn build(self) -> crate::policy::Policy {
-        crate::policy::Policy::new(self.rules_by_program)
+        crate::policy::Policy::new(self.rules_by_program, self.risk_rules_by_program)
     }
 }
 
@@ -256,4 +265,51 @@ fn policy_builtins(builder: &mut GlobalsBuilder) {
         rules.into_iter().for_each(|rule| builder.add_rule(rule));
         Ok(NoneType)
     }
+    fn risk_rule<'v>(
+        pattern: UnpackList<Value<'v>>,
+        risk: &'v str,
+        description: &'v str,
+        r#match: Option<UnpackList<Value<'v>>>,
+        not_match: Option<UnpackList<Value<'v>>>,
+        eval: &mut Evaluator<'v, '_, '_>,
+    ) -> anyhow::Result<NoneType> {
+        let risk = RiskLevel::parse(risk)?;
+        let pattern_tokens = parse_pattern(pattern)?;
+
+        let matches: Vec<Vec<String>> =
+            r#match.map(parse_examples).transpose()?.unwrap_or_default();
+        let not_matches: Vec<Vec<String>> = not_match
+            .map(parse_examples)
+            .transpose()?
+            .unwrap_or_default();
+
+        let mut builder = policy_builder(eval);
+
+        let (first_token, remaining_tokens) = pattern_tokens
+            .split_first()
+            .ok_or_else(|| Error::InvalidPattern("pattern cannot be empty".to_string()))?;
+
+        let rest: Arc<[PatternToken]> = remaining_tokens.to_vec().into();
+
+        let rules: Vec<RiskRule> = first_token
+            .alternatives()
+            .iter()
+            .map(|head| RiskRule {
+                pattern: PrefixPattern {
+                    first: Arc::from(head.as_str()),
+                    rest: rest.clone(),
+                },
+                risk,
+                description: description.to_string(),
+            })
+            .collect();
+
+        validate_not_match_examples(&rules, &not_matches)?;
+        validate_match_examples(&rules, &matches)?;
+
+        rules
+            .into_iter()
+            .for_each(|rule| builder.add_risk_rule(rule));
+        Ok(NoneType)
+    }
 }
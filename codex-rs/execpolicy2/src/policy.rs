@@ -1,4 +1,6 @@
 use crate::decision::Decision;
+use crate::risk::RiskMatch;
+use crate::risk::RiskRule;
 use crate::rule::RuleMatch;
 use crate::rule::RuleRef;
 use multimap::MultiMap;
@@ -8,21 +10,41 @@ use serde::Serialize;
 #[derive(Clone, Debug)]
 pub struct Policy {
     rules_by_program: MultiMap<String, RuleRef>,
+    risk_rules_by_program: MultiMap<String, RiskRule>,
 }
 
 impl Policy {
-    pub fn new(rules_by_program: MultiMap<String, RuleRef>) -> Self {
-        Self { rules_by_program }
+    pub fn new(
+        rules_by_program: MultiMap<String, RuleRef>,
+        risk_rules_by_program: MultiMap<String, RiskRule>,
+    ) -> Self {
+        Self {
+            rules_by_program,
+            risk_rules_by_program,
+        }
     }
 
     pub fn empty() -> Self {
-        Self::new(MultiMap::new())
+        Self::new(MultiMap::new(), MultiMap::new())
     }
 
     pub fn rules(&self) -> &MultiMap<String, RuleRef> {
         &self.rules_by_program
     }
 
+    pub fn risk_rules(&self) -> &MultiMap<String, RiskRule> {
+        &self.risk_rules_by_program
+    }
+
+    /// Returns the riskiest `risk_rule` matching `cmd`, if any.
+    pub fn assess_risk(&self, cmd: &[String]) -> Option<RiskMatch> {
+        let rules = self.risk_rules_by_program.get_vec(cmd.first()?)?;
+        rules
+            .iter()
+            .filter_map(|rule| rule.matches(cmd))
+            .max_by_key(|risk_match| risk_match.risk)
+    }
+
     pub fn check(&self, cmd: &[String]) -> Evaluation {
         let rules = match cmd.first() {
             Some(first) => match self.rules_by_program.get_vec(first) {
@@ -0,0 +1,68 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::error::Error;
+use crate::error::Result;
+use crate::rule::MatchesExample;
+use crate::rule::PrefixPattern;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RiskLevel {
+    /// Read-only or otherwise easily reversible.
+    Low,
+    /// Changes state outside the workspace or fetches from the network.
+    Medium,
+    /// Destructive, privileged, or able to exfiltrate data.
+    High,
+}
+
+impl RiskLevel {
+    pub fn parse(raw: &str) -> Result<Self> {
+        match raw {
+            "low" => Ok(Self::Low),
+            "medium" => Ok(Self::Medium),
+            "high" => Ok(Self::High),
+            other => Err(Error::InvalidRisk(other.to_string())),
+        }
+    }
+}
+
+/// Assigns a risk level to commands that start with `pattern`, overriding
+/// whatever a client's built-in heuristics would conclude.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RiskRule {
+    pub pattern: PrefixPattern,
+    pub risk: RiskLevel,
+    pub description: String,
+}
+
+impl RiskRule {
+    pub fn program(&self) -> &str {
+        self.pattern.first.as_ref()
+    }
+
+    pub fn matches(&self, cmd: &[String]) -> Option<RiskMatch> {
+        self.pattern
+            .matches_prefix(cmd)
+            .map(|matched_prefix| RiskMatch {
+                matched_prefix,
+                risk: self.risk,
+                description: self.description.clone(),
+            })
+    }
+}
+
+impl MatchesExample for RiskRule {
+    fn matches_example(&self, cmd: &[String]) -> bool {
+        self.matches(cmd).is_some()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskMatch {
+    pub matched_prefix: Vec<String>,
+    pub risk: RiskLevel,
+    pub description: String,
+}
@@ -88,6 +88,17 @@ pub trait Rule: Any + Debug + Send + Sync {
 
 pub type RuleRef = Arc<dyn Rule>;
 
+/// Rules whose `match` / `not_match` examples are validated at load time.
+pub(crate) trait MatchesExample: Debug {
+    fn matches_example(&self, cmd: &[String]) -> bool;
+}
+
+impl MatchesExample for RuleRef {
+    fn matches_example(&self, cmd: &[String]) -> bool {
+        self.matches(cmd).is_some()
+    }
+}
+
 impl Rule for PrefixRule {
     fn program(&self) -> &str {
         self.pattern.first.as_ref()
@@ -104,11 +115,14 @@ impl Rule for PrefixRule {
 }
 
 /// Count how many rules match each provided example and error if any example is unmatched.
-pub(crate) fn validate_match_examples(rules: &[RuleRef], matches: &[Vec<String>]) -> Result<()> {
+pub(crate) fn validate_match_examples<R: MatchesExample>(
+    rules: &[R],
+    matches: &[Vec<String>],
+) -> Result<()> {
     let mut unmatched_examples = Vec::new();
 
     for example in matches {
-        if rules.iter().any(|rule| rule.matches(example).is_some()) {
+        if rules.iter().any(|rule| rule.matches_example(example)) {
             continue;
         }
 
@@ -129,12 +143,12 @@ pub(crate) fn validate_match_examples(rules: &[RuleRef], matches: &[Vec<String>]
 }
 
 /// Ensure that no rule matches any provided negative example.
-pub(crate) fn validate_not_match_examples(
-    rules: &[RuleRef],
+pub(crate) fn validate_not_match_examples<R: MatchesExample>(
+    rules: &[R],
     not_matches: &[Vec<String>],
 ) -> Result<()> {
     for example in not_matches {
-        if let Some(rule) = rules.iter().find(|rule| rule.matches(example).is_some()) {
+        if let Some(rule) = rules.iter().find(|rule| rule.matches_example(example)) {
             return Err(Error::ExampleDidMatch {
                 rule: format!("{rule:?}"),
                 example: try_join(example.iter().map(String::as_str))
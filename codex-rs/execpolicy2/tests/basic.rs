@@ -4,6 +4,8 @@ use std::sync::Arc;
 use codex_execpolicy2::Decision;
 use codex_execpolicy2::Evaluation;
 use codex_execpolicy2::PolicyParser;
+use codex_execpolicy2::RiskLevel;
+use codex_execpolicy2::RiskMatch;
 use codex_execpolicy2::RuleMatch;
 use codex_execpolicy2::RuleRef;
 use codex_execpolicy2::rule::PatternToken;
@@ -373,3 +375,62 @@ prefix_rule(
         evaluation
     );
 }
+
+#[test]
+fn riskiest_risk_rule_wins_and_does_not_affect_decisions() {
+    let policy_src = r#"
+risk_rule(
+    pattern = ["npm", ["install", "ci"]],
+    risk = "low",
+    description = "Installs pinned workspace dependencies.",
+    match = ["npm install", "npm ci"],
+    not_match = ["npm publish"],
+)
+risk_rule(
+    pattern = ["npm", "install", "-g"],
+    risk = "high",
+    description = "Installs a package globally.",
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    parser
+        .parse("test.codexpolicy", policy_src)
+        .expect("parse policy");
+    let policy = parser.build();
+
+    assert_eq!(
+        Some(RiskMatch {
+            matched_prefix: tokens(&["npm", "ci"]),
+            risk: RiskLevel::Low,
+            description: "Installs pinned workspace dependencies.".to_string(),
+        }),
+        policy.assess_risk(&tokens(&["npm", "ci"]))
+    );
+    assert_eq!(
+        Some(RiskLevel::High),
+        policy
+            .assess_risk(&tokens(&["npm", "install", "-g", "left-pad"]))
+            .map(|risk_match| risk_match.risk)
+    );
+    assert_eq!(None, policy.assess_risk(&tokens(&["npm", "publish"])));
+    assert_eq!(Evaluation::NoMatch, policy.check(&tokens(&["npm", "ci"])));
+}
+
+#[test]
+fn risk_rule_rejects_unknown_level() {
+    let policy_src = r#"
+risk_rule(
+    pattern = ["rm"],
+    risk = "severe",
+    description = "Deletes files.",
+)
+    "#;
+    let mut parser = PolicyParser::new();
+    let err = parser
+        .parse("test.codexpolicy", policy_src)
+        .expect_err("unknown risk level should fail");
+    assert!(
+        err.to_string().contains("invalid risk level: severe"),
+        "unexpected error: {err}"
+    );
+}
@@ -1,4 +1,5 @@
 use std::collections::BTreeMap;
+use std::os::fd::RawFd;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -25,14 +26,26 @@ use seccompiler::SeccompRule;
 use seccompiler::TargetArch;
 use seccompiler::apply_filter;
 
+use crate::proxy_namespace::enter_proxy_network_namespace;
+
+/// `SOCK_TYPE_MASK` from the kernel: the bits of `socket(2)`'s `type`
+/// argument that hold the socket type rather than flags.
+const SOCK_TYPE_MASK: u64 = 0xf;
+
 /// Apply sandbox policies inside this thread so only the child inherits
 /// them, not the entire CLI process.
 pub(crate) fn apply_sandbox_policy_to_current_thread(
     sandbox_policy: &SandboxPolicy,
     cwd: &Path,
+    network_proxy_port: Option<u16>,
+    proxy_namespace_fd: Option<RawFd>,
 ) -> Result<()> {
-    if !sandbox_policy.has_full_network_access() {
-        install_network_seccomp_filter_on_current_thread()?;
+    match network_proxy_port {
+        Some(port) => install_proxy_only_network_rules_on_current_thread(port, proxy_namespace_fd)?,
+        None if !sandbox_policy.has_full_network_access() => {
+            install_network_seccomp_filter_on_current_thread()?;
+        }
+        None => {}
     }
 
     if !sandbox_policy.has_full_disk_write_access() {
@@ -82,6 +95,111 @@ fn install_filesystem_landlock_rules_on_current_thread(writable_roots: Vec<PathB
     Ok(())
 }
 
+/// Limits network access to TCP connections to `proxy_port` on loopback,
+/// where Codex's egress proxy listens. The command moves into a network
+/// namespace that only holds a forwarder to the proxy (see
+/// [`enter_proxy_network_namespace`]), and a seccomp filter keeps the thread
+/// from creating any socket but a TCP or AF_UNIX one. When the namespace
+/// cannot be set up, the command gets no network access at all rather than
+/// unfiltered access.
+fn install_proxy_only_network_rules_on_current_thread(
+    proxy_port: u16,
+    proxy_namespace_fd: Option<RawFd>,
+) -> Result<()> {
+    if let Err(err) = enter_proxy_network_namespace(proxy_port, proxy_namespace_fd) {
+        eprintln!(
+            "codex-linux-sandbox: cannot limit network access to the egress proxy ({err}); blocking all network access"
+        );
+        install_network_seccomp_filter_on_current_thread()?;
+        return Ok(());
+    }
+    install_proxy_socket_seccomp_filter_on_current_thread()?;
+    Ok(())
+}
+
+/// Installs a seccomp filter that only lets the thread create TCP and AF_UNIX
+/// sockets and keeps it from accepting connections, as the no-network filter
+/// does. Where the TCP sockets can connect is left to the network namespace.
+fn install_proxy_socket_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
+    let mut rules: BTreeMap<i64, Vec<SeccompRule>> = BTreeMap::new();
+
+    for nr in [
+        libc::SYS_accept,
+        libc::SYS_accept4,
+        libc::SYS_bind,
+        libc::SYS_listen,
+        libc::SYS_ptrace,
+        // io_uring can create sockets without going through `socket`.
+        libc::SYS_io_uring_setup,
+    ] {
+        rules.insert(nr, vec![]);
+    }
+
+    let domain_is = |op: SeccompCmpOp, domain: libc::c_int| {
+        SeccompCondition::new(0, SeccompCmpArgLen::Dword, op, domain as u64)
+    };
+
+    // For `socket` we deny every domain but AF_UNIX, AF_INET and AF_INET6,
+    // and within the last two every type but SOCK_STREAM and every protocol
+    // but TCP, since only TCP is forwarded to the proxy.
+    let mut socket_rules = vec![SeccompRule::new(vec![
+        domain_is(SeccompCmpOp::Ne, libc::AF_UNIX)?,
+        domain_is(SeccompCmpOp::Ne, libc::AF_INET)?,
+        domain_is(SeccompCmpOp::Ne, libc::AF_INET6)?,
+    ])?];
+    for domain in [libc::AF_INET, libc::AF_INET6] {
+        for socket_type in [
+            libc::SOCK_DGRAM,
+            libc::SOCK_RAW,
+            libc::SOCK_RDM,
+            libc::SOCK_SEQPACKET,
+            libc::SOCK_DCCP,
+        ] {
+            socket_rules.push(SeccompRule::new(vec![
+                domain_is(SeccompCmpOp::Eq, domain)?,
+                SeccompCondition::new(
+                    1, // second argument (type)
+                    SeccompCmpArgLen::Dword,
+                    SeccompCmpOp::MaskedEq(SOCK_TYPE_MASK),
+                    socket_type as u64,
+                )?,
+            ])?);
+        }
+        socket_rules.push(SeccompRule::new(vec![
+            domain_is(SeccompCmpOp::Eq, domain)?,
+            // third argument (protocol): 0 picks TCP for SOCK_STREAM
+            SeccompCondition::new(2, SeccompCmpArgLen::Dword, SeccompCmpOp::Ne, 0)?,
+            SeccompCondition::new(
+                2,
+                SeccompCmpArgLen::Dword,
+                SeccompCmpOp::Ne,
+                libc::IPPROTO_TCP as u64,
+            )?,
+        ])?);
+    }
+    rules.insert(libc::SYS_socket, socket_rules);
+    rules.insert(
+        libc::SYS_socketpair,
+        vec![SeccompRule::new(vec![domain_is(
+            SeccompCmpOp::Ne,
+            libc::AF_UNIX,
+        )?])?],
+    );
+
+    let filter = SeccompFilter::new(
+        rules,
+        SeccompAction::Allow,
+        SeccompAction::Errno(libc::EPERM as u32),
+        seccomp_target_arch(),
+    )?;
+
+    let prog: BpfProgram = filter.try_into()?;
+
+    apply_filter(&prog)?;
+
+    Ok(())
+}
+
 /// Installs a seccomp filter that blocks outbound network access except for
 /// AF_UNIX domain sockets.
 fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(), SandboxErr> {
@@ -128,13 +246,7 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
         rules,
         SeccompAction::Allow,                     // default – allow
         SeccompAction::Errno(libc::EPERM as u32), // when rule matches – return EPERM
-        if cfg!(target_arch = "x86_64") {
-            TargetArch::x86_64
-        } else if cfg!(target_arch = "aarch64") {
-            TargetArch::aarch64
-        } else {
-            unimplemented!("unsupported architecture for seccomp filter");
-        },
+        seccomp_target_arch(),
     )?;
 
     let prog: BpfProgram = filter.try_into()?;
@@ -143,3 +255,13 @@ fn install_network_seccomp_filter_on_current_thread() -> std::result::Result<(),
 
     Ok(())
 }
+
+fn seccomp_target_arch() -> TargetArch {
+    if cfg!(target_arch = "x86_64") {
+        TargetArch::x86_64
+    } else if cfg!(target_arch = "aarch64") {
+        TargetArch::aarch64
+    } else {
+        unimplemented!("unsupported architecture for seccomp filter");
+    }
+}
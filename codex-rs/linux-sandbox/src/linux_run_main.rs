@@ -1,5 +1,6 @@
 use clap::Parser;
 use std::ffi::CString;
+use std::os::fd::RawFd;
 use std::path::PathBuf;
 
 use crate::landlock::apply_sandbox_policy_to_current_thread;
@@ -14,6 +15,22 @@ pub struct LandlockCommand {
     #[arg(long = "sandbox-policy")]
     pub sandbox_policy: codex_core::protocol::SandboxPolicy,
 
+    /// Port of Codex's egress proxy on localhost. When set, TCP connections to
+    /// this port are the only network access the command gets, whatever the
+    /// sandbox policy says.
+    #[arg(long = "network-proxy-port")]
+    pub network_proxy_port: Option<u16>,
+
+    /// Set only when the helper re-executes itself to enter the proxy's
+    /// network namespace: the inherited socket to send the namespace's
+    /// listener over.
+    #[arg(
+        long = "proxy-namespace-fd",
+        hide = true,
+        requires = "network_proxy_port"
+    )]
+    pub proxy_namespace_fd: Option<RawFd>,
+
     /// Full command args to run under landlock.
     #[arg(trailing_var_arg = true)]
     pub command: Vec<String>,
@@ -23,10 +40,17 @@ pub fn run_main() -> ! {
     let LandlockCommand {
         sandbox_policy_cwd,
         sandbox_policy,
+        network_proxy_port,
+        proxy_namespace_fd,
         command,
     } = LandlockCommand::parse();
 
-    if let Err(e) = apply_sandbox_policy_to_current_thread(&sandbox_policy, &sandbox_policy_cwd) {
+    if let Err(e) = apply_sandbox_policy_to_current_thread(
+        &sandbox_policy,
+        &sandbox_policy_cwd,
+        network_proxy_port,
+        proxy_namespace_fd,
+    ) {
         panic!("error running landlock: {e:?}");
     }
 
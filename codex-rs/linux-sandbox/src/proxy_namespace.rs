@@ -0,0 +1,350 @@
+//! Confines a sandboxed command's network access to Codex's egress proxy.
+//!
+//! Landlock can only limit the port a TCP socket connects to, not the
+//! address, so it cannot keep a command from reaching the proxy's port on
+//! another host. Instead the command runs in a new network namespace whose
+//! only interface is loopback. A listener on the proxy's port inside that
+//! namespace is handed back to the original process, which stays outside it
+//! and forwards each connection to the real proxy on `127.0.0.1`.
+//!
+//! The forked child re-executes this binary straight away rather than set up
+//! the namespace itself: between `fork` and `execve` it may only make
+//! async-signal-safe calls, and the setup needs to allocate.
+
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::fs;
+use std::io;
+use std::mem;
+use std::net::Ipv4Addr;
+use std::net::Shutdown;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::net::UdpSocket;
+use std::os::fd::AsRawFd;
+use std::os::fd::FromRawFd;
+use std::os::fd::OwnedFd;
+use std::os::fd::RawFd;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::net::UnixStream;
+use std::ptr;
+use std::thread;
+use std::time::Duration;
+
+/// How long the forwarder waits before accepting again after `accept` fails,
+/// e.g. because it ran out of file descriptors.
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Re-executing through `/proc/self/exe` works even if the binary was
+/// replaced on disk since this process started.
+const CURRENT_EXE: &CStr = c"/proc/self/exe";
+
+/// Flag that hands the re-executed child the socket to send the namespace's
+/// listener over. It must match `LandlockCommand::proxy_namespace_fd`.
+const PROXY_NAMESPACE_FD_FLAG: &str = "--proxy-namespace-fd";
+
+/// Written by the forked child when it cannot re-execute; formatting an
+/// error there is not async-signal-safe.
+const REEXEC_FAILED_MESSAGE: &[u8] =
+    b"codex-linux-sandbox: cannot re-execute to enter the proxy network namespace\n";
+
+/// Moves the command into a new user and network namespace in which
+/// `127.0.0.1:proxy_port` leads to the egress proxy and nothing else is
+/// reachable.
+///
+/// Without `namespace_socket` this forks. The original process forwards
+/// connections until the child exits and then exits the same way; the child
+/// re-executes this binary with `namespace_socket` set, and only that
+/// re-executed process returns from here. When the namespace cannot be set up
+/// it returns the error, and the caller is expected to block network access
+/// entirely.
+pub(crate) fn enter_proxy_network_namespace(
+    proxy_port: u16,
+    namespace_socket: Option<RawFd>,
+) -> io::Result<()> {
+    let Some(namespace_socket) = namespace_socket else {
+        return fork_forwarder(proxy_port);
+    };
+    // SAFETY: the fd was inherited from the forwarder for this purpose alone,
+    // so nothing else in this process owns it.
+    let socket = unsafe { UnixStream::from_raw_fd(namespace_socket) };
+    let listener = isolate_network(proxy_port)?;
+    send_fd(&socket, listener.as_raw_fd())
+}
+
+/// Forks and turns the original process into the forwarder. The child
+/// re-executes this binary to set up the namespace; see
+/// [`exec_namespace_child`].
+///
+/// Returns only when forking fails.
+fn fork_forwarder(proxy_port: u16) -> io::Result<()> {
+    let (parent_socket, child_socket) = UnixStream::pair()?;
+
+    // Everything the child needs is allocated here, before the fork.
+    let mut args = std::env::args_os();
+    let mut argv = vec![CString::new(args.next().unwrap_or_default().as_bytes())?];
+    argv.push(CString::new(PROXY_NAMESPACE_FD_FLAG)?);
+    argv.push(CString::new(child_socket.as_raw_fd().to_string())?);
+    for arg in args {
+        argv.push(CString::new(arg.as_bytes())?);
+    }
+    let envp = std::env::vars_os()
+        .map(|(key, value)| {
+            let mut entry = key.as_bytes().to_vec();
+            entry.push(b'=');
+            entry.extend_from_slice(value.as_bytes());
+            CString::new(entry)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let argv_ptrs = null_terminated(&argv);
+    let envp_ptrs = null_terminated(&envp);
+
+    // SAFETY: getpid has no preconditions.
+    let parent_pid = unsafe { libc::getpid() };
+    // SAFETY: this process is still single-threaded, so no other thread can
+    // hold a lock the child would inherit. The child only runs
+    // `exec_namespace_child`, which never returns.
+    match unsafe { libc::fork() } {
+        -1 => Err(io::Error::last_os_error()),
+        // SAFETY: the pointers refer to `argv` and `envp`, which the child's
+        // copy of memory keeps alive until `execve` replaces it.
+        0 => unsafe {
+            exec_namespace_child(parent_pid, child_socket.as_raw_fd(), &argv_ptrs, &envp_ptrs)
+        },
+        child_pid => {
+            drop(child_socket);
+            // The child hangs up without sending a listener when it cannot set
+            // up the namespace.
+            if let Ok(Some(listener)) = recv_fd(&parent_socket) {
+                let listener = TcpListener::from(listener);
+                thread::spawn(move || forward_connections(listener, proxy_port));
+            }
+            exit_like_child(child_pid)
+        }
+    }
+}
+
+/// Runs in the forked child until `execve` replaces it. Only async-signal-safe
+/// calls are made, with no allocation and no locking.
+///
+/// # Safety
+///
+/// Must only be called in the child of `fork`. `argv` and `envp` must be
+/// null-terminated arrays of pointers to NUL-terminated strings.
+unsafe fn exec_namespace_child(
+    parent_pid: libc::pid_t,
+    socket: RawFd,
+    argv: &[*const libc::c_char],
+    envp: &[*const libc::c_char],
+) -> ! {
+    // SAFETY: prctl, getppid, fcntl, execve, write and _exit are all
+    // async-signal-safe, and every pointer passed to them stays valid for the
+    // call.
+    unsafe {
+        // The command must not outlive the forwarder it depends on. The
+        // death signal survives `execve`.
+        libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL);
+        if libc::getppid() != parent_pid {
+            libc::_exit(1);
+        }
+        // `UnixStream::pair` creates close-on-exec sockets; the re-executed
+        // process needs this one.
+        if libc::fcntl(socket, libc::F_SETFD, 0) == 0 {
+            libc::execve(CURRENT_EXE.as_ptr(), argv.as_ptr(), envp.as_ptr());
+        }
+        libc::write(
+            libc::STDERR_FILENO,
+            REEXEC_FAILED_MESSAGE.as_ptr().cast(),
+            REEXEC_FAILED_MESSAGE.len(),
+        );
+        libc::_exit(1)
+    }
+}
+
+fn null_terminated(strings: &[CString]) -> Vec<*const libc::c_char> {
+    strings
+        .iter()
+        .map(|string| string.as_ptr())
+        .chain([ptr::null()])
+        .collect()
+}
+
+/// Enters a new user and network namespace, brings up its loopback interface
+/// and listens on `127.0.0.1:proxy_port` inside it.
+fn isolate_network(proxy_port: u16) -> io::Result<TcpListener> {
+    // SAFETY: geteuid and getegid have no preconditions.
+    let (uid, gid) = unsafe { (libc::geteuid(), libc::getegid()) };
+    // SAFETY: unshare takes no pointers. Entering a user namespace requires a
+    // single-threaded process, which the re-executed child still is.
+    if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // Keep the same IDs inside the user namespace so file ownership looks
+    // unchanged to the command.
+    match fs::write("/proc/self/setgroups", "deny") {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+        _ => {}
+    }
+    fs::write("/proc/self/uid_map", format!("{uid} {uid} 1"))?;
+    fs::write("/proc/self/gid_map", format!("{gid} {gid} 1"))?;
+
+    bring_up_loopback()?;
+    TcpListener::bind((Ipv4Addr::LOCALHOST, proxy_port))
+}
+
+/// A new network namespace starts with `lo` down.
+fn bring_up_loopback() -> io::Result<()> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    // SAFETY: `ifreq` is plain old data, for which all zeroes is valid.
+    let mut request: libc::ifreq = unsafe { mem::zeroed() };
+    for (dst, src) in request.ifr_name.iter_mut().zip(b"lo") {
+        *dst = *src as libc::c_char;
+    }
+    request.ifr_ifru.ifru_flags = (libc::IFF_UP | libc::IFF_RUNNING) as libc::c_short;
+    // SAFETY: SIOCSIFFLAGS reads an `ifreq`, and `request` outlives the call.
+    if unsafe { libc::ioctl(socket.as_raw_fd(), libc::SIOCSIFFLAGS, &request) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Waits for `child_pid` and exits with its status, re-raising the signal
+/// that killed it, if any.
+fn exit_like_child(child_pid: libc::pid_t) -> ! {
+    let mut status = 0;
+    loop {
+        // SAFETY: `status` is a valid `c_int` to write the status to.
+        if unsafe { libc::waitpid(child_pid, &mut status, 0) } != -1 {
+            break;
+        }
+        if io::Error::last_os_error().kind() != io::ErrorKind::Interrupted {
+            std::process::exit(1);
+        }
+    }
+
+    if libc::WIFEXITED(status) {
+        std::process::exit(libc::WEXITSTATUS(status));
+    }
+    if libc::WIFSIGNALED(status) {
+        let signal = libc::WTERMSIG(status);
+        // SAFETY: restoring the default disposition installs no handler, and
+        // `signal` is one the kernel just reported.
+        unsafe {
+            libc::signal(signal, libc::SIG_DFL);
+            libc::raise(signal);
+        }
+        std::process::exit(128 + signal);
+    }
+    std::process::exit(1);
+}
+
+/// Forwards every connection accepted inside the namespace to the proxy.
+fn forward_connections(listener: TcpListener, proxy_port: u16) {
+    loop {
+        match listener.accept() {
+            Ok((inbound, _)) => {
+                thread::spawn(move || {
+                    if let Ok(outbound) = TcpStream::connect((Ipv4Addr::LOCALHOST, proxy_port)) {
+                        let _ = relay(inbound, outbound);
+                    }
+                });
+            }
+            Err(_) => thread::sleep(ACCEPT_RETRY_DELAY),
+        }
+    }
+}
+
+/// Copies bytes both ways until each side has finished sending.
+fn relay(inbound: TcpStream, outbound: TcpStream) -> io::Result<()> {
+    let mut inbound_reader = inbound.try_clone()?;
+    let mut outbound_writer = outbound.try_clone()?;
+    let upstream = thread::spawn(move || {
+        let _ = io::copy(&mut inbound_reader, &mut outbound_writer);
+        let _ = outbound_writer.shutdown(Shutdown::Write);
+    });
+
+    let (mut outbound_reader, mut inbound_writer) = (outbound, inbound);
+    let _ = io::copy(&mut outbound_reader, &mut inbound_writer);
+    let _ = inbound_writer.shutdown(Shutdown::Write);
+    let _ = upstream.join();
+    Ok(())
+}
+
+/// Buffer for one `SCM_RIGHTS` control message, aligned for `cmsghdr`.
+type FdControlBuffer = [u64; 4];
+
+fn send_fd(socket: &UnixStream, fd: RawFd) -> io::Result<()> {
+    let mut byte = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: byte.as_mut_ptr().cast(),
+        iov_len: byte.len(),
+    };
+    let mut control: FdControlBuffer = [0; 4];
+    // SAFETY: `msghdr` is plain old data, for which all zeroes is valid.
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr().cast();
+    // SAFETY: CMSG_SPACE only does arithmetic.
+    msg.msg_controllen = unsafe { libc::CMSG_SPACE(mem::size_of::<RawFd>() as u32) } as _;
+
+    // SAFETY: `control` is aligned for `cmsghdr` and has room for one header
+    // and one fd, so CMSG_FIRSTHDR is non-null and CMSG_DATA points inside
+    // it. The fd is written unaligned because CMSG_DATA need not be aligned
+    // for `RawFd`.
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        (*cmsg).cmsg_level = libc::SOL_SOCKET;
+        (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+        (*cmsg).cmsg_len = libc::CMSG_LEN(mem::size_of::<RawFd>() as u32) as _;
+        ptr::write_unaligned(libc::CMSG_DATA(cmsg).cast::<RawFd>(), fd);
+    }
+
+    // SAFETY: `msg` points at `iov`, `byte` and `control`, which outlive the
+    // call. The kernel duplicates `fd` for the receiver, so this process
+    // keeps ownership of it.
+    if unsafe { libc::sendmsg(socket.as_raw_fd(), &msg, 0) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Receives a file descriptor sent with [`send_fd`], or `None` when the peer
+/// hung up without sending one.
+fn recv_fd(socket: &UnixStream) -> io::Result<Option<OwnedFd>> {
+    let mut byte = [0u8; 1];
+    let mut iov = libc::iovec {
+        iov_base: byte.as_mut_ptr().cast(),
+        iov_len: byte.len(),
+    };
+    let mut control: FdControlBuffer = [0; 4];
+    // SAFETY: `msghdr` is plain old data, for which all zeroes is valid.
+    let mut msg: libc::msghdr = unsafe { mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.as_mut_ptr().cast();
+    msg.msg_controllen = mem::size_of::<FdControlBuffer>() as _;
+
+    // SAFETY: `msg` points at `iov`, `byte` and `control`, which outlive the
+    // call and are large enough for what it writes.
+    let received = unsafe { libc::recvmsg(socket.as_raw_fd(), &mut msg, libc::MSG_CMSG_CLOEXEC) };
+    if received < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // SAFETY: CMSG_FIRSTHDR returns null or a header inside `control`,
+    // checked before it is read. An `SCM_RIGHTS` message carries an fd the
+    // kernel just installed in this process, which nothing else owns yet.
+    unsafe {
+        let cmsg = libc::CMSG_FIRSTHDR(&msg);
+        if cmsg.is_null()
+            || (*cmsg).cmsg_level != libc::SOL_SOCKET
+            || (*cmsg).cmsg_type != libc::SCM_RIGHTS
+        {
+            return Ok(None);
+        }
+        let fd = ptr::read_unaligned(libc::CMSG_DATA(cmsg).cast::<RawFd>());
+        Ok(Some(OwnedFd::from_raw_fd(fd)))
+    }
+}
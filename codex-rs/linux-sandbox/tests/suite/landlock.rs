@@ -236,3 +236,94 @@ async fn sandbox_blocks_dev_tcp_redirection() {
     // all images ship bash, so we guard against 127 as well.
     assert_network_blocked(&["bash", "-c", "echo hi > /dev/tcp/127.0.0.1/80"]).await;
 }
+
+/// Runs `bash -c script` under the helper with the egress proxy on
+/// `proxy_port` and returns whether it succeeded, or `None` when the helper
+/// cannot confine network access to the proxy.
+#[expect(clippy::expect_used)]
+fn run_with_network_proxy(proxy_port: u16, script: &str) -> Option<bool> {
+    let cwd = std::env::current_dir().expect("cwd should exist");
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_codex-linux-sandbox"))
+        .arg("--sandbox-policy-cwd")
+        .arg(&cwd)
+        .args(["--sandbox-policy", r#"{"type":"read-only"}"#])
+        .arg("--network-proxy-port")
+        .arg(proxy_port.to_string())
+        .args(["--", "bash", "-c", script])
+        .output()
+        .expect("run codex-linux-sandbox");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if stderr.contains("cannot limit network access to the egress proxy") {
+        return None;
+    }
+    Some(output.status.success())
+}
+
+#[test]
+fn sandbox_with_network_proxy_only_reaches_the_proxy_port() {
+    // Listen on every address so that reaching the proxy's port on another
+    // address would succeed if the sandbox allowed it.
+    let proxy = std::net::TcpListener::bind("0.0.0.0:0").expect("bind proxy");
+    let other = std::net::TcpListener::bind("127.0.0.1:0").expect("bind other");
+    let proxy_port = proxy.local_addr().expect("proxy addr").port();
+    let other_port = other.local_addr().expect("other addr").port();
+    let received = std::thread::spawn(move || {
+        let (mut stream, _) = proxy.accept().expect("accept");
+        let mut received = String::new();
+        std::io::Read::read_to_string(&mut stream, &mut received).expect("read");
+        received
+    });
+
+    let Some(reached_proxy) = run_with_network_proxy(
+        proxy_port,
+        &format!("echo hi > /dev/tcp/127.0.0.1/{proxy_port}"),
+    ) else {
+        eprintln!("skipping: network namespaces are unavailable");
+        return;
+    };
+    assert!(reached_proxy, "the proxy port should be reachable");
+    assert_eq!(received.join().expect("proxy thread"), "hi\n");
+    assert_eq!(
+        run_with_network_proxy(
+            proxy_port,
+            &format!("echo hi > /dev/tcp/127.0.0.2/{proxy_port}")
+        ),
+        Some(false),
+        "the proxy port on other addresses must stay blocked"
+    );
+    assert_eq!(
+        run_with_network_proxy(
+            proxy_port,
+            &format!("echo hi > /dev/tcp/127.0.0.1/{other_port}")
+        ),
+        Some(false),
+        "other ports must stay blocked"
+    );
+    assert_eq!(
+        run_with_network_proxy(
+            proxy_port,
+            &format!("echo hi > /dev/udp/127.0.0.1/{proxy_port}")
+        ),
+        Some(false),
+        "UDP must stay blocked"
+    );
+}
+
+#[test]
+fn sandbox_with_network_proxy_allows_unix_sockets() {
+    let proxy = std::net::TcpListener::bind("127.0.0.1:0").expect("bind proxy");
+    let proxy_port = proxy.local_addr().expect("proxy addr").port();
+    let script = r#"
+        command -v python3 >/dev/null || exit 0
+        python3 -c 'import socket; socket.socket(socket.AF_UNIX, socket.SOCK_STREAM).close(); socket.socketpair()'
+    "#;
+
+    let Some(succeeded) = run_with_network_proxy(proxy_port, script) else {
+        eprintln!("skipping: network namespaces are unavailable");
+        return;
+    };
+    assert!(
+        succeeded,
+        "AF_UNIX sockets should work as they do with networking off"
+    );
+}
@@ -15,6 +15,12 @@ const ANSI_YELLOW: &str = "\x1b[93m";
 const ANSI_BOLD: &str = "\x1b[1m";
 const ANSI_RESET: &str = "\x1b[0m";
 
+/// How long a device code stays valid when the server does not say.
+const DEFAULT_DEVICE_CODE_LIFETIME: Duration = Duration::from_secs(15 * 60);
+
+/// RFC 8628 §3.5: back off by this much whenever the server asks to slow down.
+const SLOW_DOWN_INCREMENT_SECS: u64 = 5;
+
 #[derive(Deserialize)]
 struct UserCodeResp {
     device_auth_id: String,
@@ -22,6 +28,27 @@ struct UserCodeResp {
     user_code: String,
     #[serde(default, deserialize_with = "deserialize_interval")]
     interval: u64,
+    #[serde(default)]
+    verification_uri: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+/// A pending device authorization: the user approves the login by entering
+/// `user_code` at `verification_url` in a browser on any machine.
+#[derive(Debug, Clone)]
+pub struct DeviceCode {
+    pub verification_url: String,
+    pub user_code: String,
+    /// How long the code stays valid.
+    pub expires_in: Duration,
+    device_auth_id: String,
+    interval: u64,
+}
+
+#[derive(Deserialize)]
+struct PollErrorResp {
+    error: String,
 }
 
 #[derive(Serialize)]
@@ -94,10 +121,10 @@ async fn poll_for_token(
     auth_base_url: &str,
     device_auth_id: &str,
     user_code: &str,
-    interval: u64,
+    mut interval: u64,
+    max_wait: Duration,
 ) -> std::io::Result<CodeSuccessResp> {
     let url = format!("{auth_base_url}/deviceauth/token");
-    let max_wait = Duration::from_secs(15 * 60);
     let start = Instant::now();
 
     loop {
@@ -120,21 +147,51 @@ async fn poll_for_token(
             return resp.json().await.map_err(std::io::Error::other);
         }
 
-        if status == StatusCode::FORBIDDEN || status == StatusCode::NOT_FOUND {
-            if start.elapsed() >= max_wait {
-                return Err(std::io::Error::other(
-                    "device auth timed out after 15 minutes",
-                ));
+        let pending = if status == StatusCode::FORBIDDEN || status == StatusCode::NOT_FOUND {
+            true
+        } else if status == StatusCode::TOO_MANY_REQUESTS {
+            interval += SLOW_DOWN_INCREMENT_SECS;
+            true
+        } else {
+            // RFC 8628 error responses carry the reason in `error`.
+            let body = resp.text().await.unwrap_or_default();
+            match serde_json::from_str::<PollErrorResp>(&body)
+                .map(|err| err.error)
+                .as_deref()
+            {
+                Ok("authorization_pending") => true,
+                Ok("slow_down") => {
+                    interval += SLOW_DOWN_INCREMENT_SECS;
+                    true
+                }
+                Ok("access_denied") => {
+                    return Err(std::io::Error::new(
+                        io::ErrorKind::PermissionDenied,
+                        "device auth was denied",
+                    ));
+                }
+                Ok("expired_token") => {
+                    return Err(std::io::Error::other(
+                        "device code expired; start the login again",
+                    ));
+                }
+                _ => false,
             }
-            let sleep_for = Duration::from_secs(interval).min(max_wait - start.elapsed());
-            tokio::time::sleep(sleep_for).await;
-            continue;
+        };
+        if !pending {
+            return Err(std::io::Error::other(format!(
+                "device auth failed with status {status}"
+            )));
         }
 
-        return Err(std::io::Error::other(format!(
-            "device auth failed with status {}",
-            resp.status()
-        )));
+        if start.elapsed() >= max_wait {
+            return Err(std::io::Error::other(format!(
+                "device auth timed out after {} minutes",
+                max_wait.as_secs() / 60
+            )));
+        }
+        let sleep_for = Duration::from_secs(interval).min(max_wait - start.elapsed());
+        tokio::time::sleep(sleep_for).await;
     }
 }
 
@@ -148,25 +205,46 @@ fn print_colored_warning_device_code() {
     let _ = stdout.flush();
 }
 
-/// Full device code login flow.
-pub async fn run_device_code_login(opts: ServerOptions) -> std::io::Result<()> {
-    let client = reqwest::Client::new();
+/// Start a device authorization. Show the returned code to the user, then
+/// wait for them to approve it with [`complete_device_code_login`].
+pub async fn request_device_code(opts: &ServerOptions) -> std::io::Result<DeviceCode> {
+    let client = codex_utils_http_client::builder()
+        .build()
+        .map_err(io::Error::other)?;
     let base_url = opts.issuer.trim_end_matches('/');
-    let api_base_url = format!("{}/api/accounts", opts.issuer.trim_end_matches('/'));
-    print_colored_warning_device_code();
+    let api_base_url = format!("{base_url}/api/accounts");
     let uc = request_user_code(&client, &api_base_url, &opts.client_id).await?;
+    Ok(DeviceCode {
+        verification_url: uc
+            .verification_uri
+            .unwrap_or_else(|| format!("{base_url}/codex/device")),
+        user_code: uc.user_code,
+        expires_in: uc
+            .expires_in
+            .map_or(DEFAULT_DEVICE_CODE_LIFETIME, Duration::from_secs),
+        device_auth_id: uc.device_auth_id,
+        interval: uc.interval,
+    })
+}
 
-    println!(
-        "To authenticate:\n  1. Open in your browser: {ANSI_BOLD}https://auth.openai.com/codex/device{ANSI_RESET}\n  2. Enter the one-time code below within 15 minutes:\n\n     {ANSI_BOLD}{}{ANSI_RESET}\n",
-        uc.user_code
-    );
-
+/// Poll until the user approves `device_code`, then exchange it for tokens
+/// and store them in `opts.codex_home`.
+pub async fn complete_device_code_login(
+    opts: ServerOptions,
+    device_code: DeviceCode,
+) -> std::io::Result<()> {
+    let client = codex_utils_http_client::builder()
+        .build()
+        .map_err(io::Error::other)?;
+    let base_url = opts.issuer.trim_end_matches('/');
+    let api_base_url = format!("{base_url}/api/accounts");
     let code_resp = poll_for_token(
         &client,
         &api_base_url,
-        &uc.device_auth_id,
-        &uc.user_code,
-        uc.interval,
+        &device_code.device_auth_id,
+        &device_code.user_code,
+        device_code.interval,
+        device_code.expires_in,
     )
     .await?;
 
@@ -203,3 +281,18 @@ pub async fn run_device_code_login(opts: ServerOptions) -> std::io::Result<()> {
     )
     .await
 }
+
+/// Full device code login flow, printing the code to the terminal.
+pub async fn run_device_code_login(opts: ServerOptions) -> std::io::Result<()> {
+    print_colored_warning_device_code();
+    let device_code = request_device_code(&opts).await?;
+
+    println!(
+        "To authenticate:\n  1. Open in your browser: {ANSI_BOLD}{}{ANSI_RESET}\n  2. Enter the one-time code below within {} minutes:\n\n     {ANSI_BOLD}{}{ANSI_RESET}\n",
+        device_code.verification_url,
+        device_code.expires_in.as_secs() / 60,
+        device_code.user_code
+    );
+
+    complete_device_code_login(opts, device_code).await
+}
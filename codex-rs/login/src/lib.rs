@@ -2,6 +2,9 @@ mod device_code_auth;
 mod pkce;
 mod server;
 
+pub use device_code_auth::DeviceCode;
+pub use device_code_auth::complete_device_code_login;
+pub use device_code_auth::request_device_code;
 pub use device_code_auth::run_device_code_login;
 pub use server::LoginServer;
 pub use server::ServerOptions;
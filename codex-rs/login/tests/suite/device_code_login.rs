@@ -6,6 +6,8 @@ use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use codex_core::auth::AuthCredentialsStoreMode;
 use codex_core::auth::load_auth_dot_json;
 use codex_login::ServerOptions;
+use codex_login::complete_device_code_login;
+use codex_login::request_device_code;
 use codex_login::run_device_code_login;
 use serde_json::json;
 use std::sync::Arc;
@@ -151,6 +153,67 @@ async fn device_code_login_integration_succeeds() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn device_code_login_in_two_steps_waits_through_authorization_pending() -> anyhow::Result<()>
+{
+    skip_if_no_network!(Ok(()));
+
+    let codex_home = tempdir().unwrap();
+    let mock_server = MockServer::start().await;
+
+    mock_usercode_success(&mock_server).await;
+
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let c = attempts.clone();
+    Mock::given(method("POST"))
+        .and(path("/api/accounts/deviceauth/token"))
+        .respond_with(move |_: &Request| {
+            if c.fetch_add(1, Ordering::SeqCst) == 0 {
+                ResponseTemplate::new(400).set_body_json(json!({
+                    "error": "authorization_pending"
+                }))
+            } else {
+                ResponseTemplate::new(200).set_body_json(json!({
+                    "authorization_code": "poll-code-321",
+                    "code_challenge": "code-challenge-321",
+                    "code_verifier": "code-verifier-321"
+                }))
+            }
+        })
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let jwt = make_jwt(json!({
+        "https://api.openai.com/auth": {
+            "chatgpt_account_id": "acct_321"
+        }
+    }));
+    mock_oauth_token_single(&mock_server, jwt).await;
+
+    let issuer = mock_server.uri();
+    let opts = server_opts(&codex_home, issuer.clone(), AuthCredentialsStoreMode::File);
+
+    let device_code = request_device_code(&opts).await?;
+    assert_eq!(device_code.user_code, "CODE-12345");
+    assert_eq!(
+        device_code.verification_url,
+        format!("{issuer}/codex/device")
+    );
+
+    complete_device_code_login(opts, device_code).await?;
+
+    let auth = load_auth_dot_json(codex_home.path(), AuthCredentialsStoreMode::File)
+        .context("auth.json should load after login succeeds")?
+        .context("auth.json written")?;
+    assert_eq!(
+        auth.tokens.expect("tokens persisted").account_id.as_deref(),
+        Some("acct_321")
+    );
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    Ok(())
+}
+
 #[tokio::test]
 async fn device_code_login_rejects_workspace_mismatch() -> anyhow::Result<()> {
     skip_if_no_network!(Ok(()));
@@ -171,6 +171,9 @@ impl CodexToolCallParam {
             tools_web_search_request: None,
             experimental_sandbox_command_assessment: None,
             additional_writable_roots: Vec::new(),
+            tools_enabled: None,
+            tools_disabled: None,
+            account: None,
         };
 
         let cli_overrides = cli_overrides
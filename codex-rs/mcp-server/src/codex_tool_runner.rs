@@ -15,12 +15,14 @@ use codex_core::NewConversation;
 use codex_core::config::Config as CodexConfig;
 use codex_core::protocol::AgentMessageEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
+use codex_core::protocol::ElicitationAction;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::Submission;
 use codex_core::protocol::TaskCompleteEvent;
+use codex_core::protocol::WriteScopeDecision;
 use codex_protocol::ConversationId;
 use codex_protocol::user_input::UserInput;
 use mcp_types::CallToolResult;
@@ -178,9 +180,11 @@ async fn run_codex_tool_session_inner(
                         command,
                         cwd,
                         call_id,
+                        item_id: _,
                         reason: _,
                         risk,
                         parsed_cmd,
+                        proposed_rules: _,
                     }) => {
                         handle_exec_approval_request(
                             command,
@@ -208,17 +212,67 @@ async fn run_codex_tool_session_inner(
                     EventMsg::Warning(_) => {
                         continue;
                     }
+                    EventMsg::ElicitationRequest(request) => {
+                        // Elicitations from downstream MCP servers are not
+                        // forwarded to this server's client yet; decline them
+                        // rather than leave the downstream server waiting.
+                        if let Err(err) = codex
+                            .submit(Op::ResolveElicitation {
+                                server_name: request.server_name,
+                                request_id: request.id,
+                                decision: ElicitationAction::Decline,
+                                content: None,
+                            })
+                            .await
+                        {
+                            tracing::error!("failed to decline MCP elicitation: {err}");
+                        }
+                        continue;
+                    }
+                    EventMsg::WriteScopeApprovalRequest(_) => {
+                        // Write scope prompts are not forwarded to this
+                        // server's client yet; keep the sandbox as configured.
+                        if let Err(err) = codex
+                            .submit(Op::WriteScopeApproval {
+                                id: event.id.clone(),
+                                decision: WriteScopeDecision::Deny,
+                            })
+                            .await
+                        {
+                            tracing::error!("failed to deny write scope request: {err}");
+                        }
+                        continue;
+                    }
+                    EventMsg::UserInputRequest(request) => {
+                        // Questions from the ask_user tool are not forwarded
+                        // to this server's client yet; the model continues
+                        // without an answer.
+                        if let Err(err) = codex
+                            .submit(Op::UserInputAnswer {
+                                call_id: request.call_id,
+                                answer: None,
+                            })
+                            .await
+                        {
+                            tracing::error!("failed to answer user input request: {err}");
+                        }
+                        continue;
+                    }
                     EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
                         call_id,
                         reason,
                         grant_root,
                         changes,
+                        deferred_changes,
+                        file_statuses,
                     }) => {
                         handle_patch_approval_request(
                             call_id,
                             reason,
                             grant_root,
                             changes,
+                            deferred_changes,
+                            file_statuses,
                             outgoing.clone(),
                             codex.clone(),
                             request_id.clone(),
@@ -228,7 +282,9 @@ async fn run_codex_tool_session_inner(
                         .await;
                         continue;
                     }
-                    EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+                    EventMsg::TaskComplete(TaskCompleteEvent {
+                        last_agent_message, ..
+                    }) => {
                         let text = match last_agent_message {
                             Some(msg) => msg,
                             None => "".to_string(),
@@ -272,9 +328,13 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::AgentReasoning(_)
                     | EventMsg::AgentReasoningSectionBreak(_)
                     | EventMsg::McpToolCallBegin(_)
+                    | EventMsg::McpToolCallProgress(_)
                     | EventMsg::McpToolCallEnd(_)
                     | EventMsg::McpListToolsResponse(_)
                     | EventMsg::ListCustomPromptsResponse(_)
+                    | EventMsg::ListApprovalRulesResponse(_)
+                    | EventMsg::McpListPromptsResponse(_)
+                    | EventMsg::McpResourceReadResponse(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::ExecCommandOutputDelta(_)
                     | EventMsg::ExecCommandEnd(_)
@@ -282,10 +342,32 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::StreamError(_)
                     | EventMsg::PatchApplyBegin(_)
                     | EventMsg::PatchApplyEnd(_)
+                    | EventMsg::FileLockWait(_)
                     | EventMsg::TurnDiff(_)
                     | EventMsg::WebSearchBegin(_)
                     | EventMsg::WebSearchEnd(_)
                     | EventMsg::GetHistoryEntryResponse(_)
+                    | EventMsg::PatchContentResponse(_)
+                    | EventMsg::WorkspaceInstructionProposed(_)
+                    | EventMsg::NetworkHostBlocked(_)
+                    | EventMsg::BudgetExhausted(_)
+                    | EventMsg::ContextCompacted(_)
+                    | EventMsg::ProviderHealthChanged(_)
+                    | EventMsg::RateLimited(_)
+                    | EventMsg::BackgroundTaskUpdate(_)
+                    | EventMsg::SubAgentUpdate(_)
+                    | EventMsg::WorktreeDiff(_)
+                    | EventMsg::WorktreeClosed(_)
+                    | EventMsg::PullRequestCreated(_)
+                    | EventMsg::InstructionsChanged(_)
+                    | EventMsg::ProfileChanged(_)
+                    | EventMsg::ThreadMetadataChanged(_)
+                    | EventMsg::SecretsRedacted(_)
+                    | EventMsg::PatchDiagnostics(_)
+                    | EventMsg::TurnHeartbeat(_)
+                    | EventMsg::McpServerStatusChanged(_)
+                    | EventMsg::ToolsChanged(_)
+                    | EventMsg::McpToolConflicts(_)
                     | EventMsg::PlanUpdate(_)
                     | EventMsg::TurnAborted(_)
                     | EventMsg::UserMessage(_)
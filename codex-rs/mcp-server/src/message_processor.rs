@@ -53,8 +53,9 @@ impl MessageProcessor {
         config: Arc<Config>,
     ) -> Self {
         let outgoing = Arc::new(outgoing);
-        let auth_manager = AuthManager::shared(
+        let auth_manager = AuthManager::shared_with_account(
             config.codex_home.clone(),
+            config.account.clone(),
             false,
             config.cli_auth_credentials_store_mode,
         );
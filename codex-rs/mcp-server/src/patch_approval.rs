@@ -3,8 +3,10 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use codex_core::CodexConversation;
+use codex_core::protocol::DeferredFileChange;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::Op;
+use codex_core::protocol::PatchFileStatus;
 use codex_core::protocol::ReviewDecision;
 use mcp_types::ElicitRequest;
 use mcp_types::ElicitRequestParamsRequestedSchema;
@@ -33,6 +35,10 @@ pub struct PatchApprovalElicitRequestParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub codex_grant_root: Option<PathBuf>,
     pub codex_changes: HashMap<PathBuf, FileChange>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub codex_deferred_changes: Vec<DeferredFileChange>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub codex_file_statuses: HashMap<PathBuf, PatchFileStatus>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -46,6 +52,8 @@ pub(crate) async fn handle_patch_approval_request(
     reason: Option<String>,
     grant_root: Option<PathBuf>,
     changes: HashMap<PathBuf, FileChange>,
+    deferred_changes: Vec<DeferredFileChange>,
+    file_statuses: HashMap<PathBuf, PatchFileStatus>,
     outgoing: Arc<OutgoingMessageSender>,
     codex: Arc<CodexConversation>,
     request_id: RequestId,
@@ -72,6 +80,8 @@ pub(crate) async fn handle_patch_approval_request(
         codex_reason: reason,
         codex_grant_root: grant_root,
         codex_changes: changes,
+        codex_deferred_changes: deferred_changes,
+        codex_file_statuses: file_statuses,
     };
     let params_json = match serde_json::to_value(&params) {
         Ok(value) => value,
@@ -5,6 +5,7 @@ use std::path::PathBuf;
 
 use codex_core::parse_command;
 use codex_core::protocol::FileChange;
+use codex_core::protocol::PatchFileStatus;
 use codex_core::protocol::ReviewDecision;
 use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
 use codex_mcp_server::CodexToolCallParam;
@@ -406,6 +407,10 @@ fn create_expected_patch_approval_elicitation_request(
         message_lines.push(r.clone());
     }
     message_lines.push("Allow Codex to apply proposed code changes?".to_string());
+    let file_statuses = changes
+        .keys()
+        .map(|path| (path.clone(), PatchFileStatus::AppliesCleanly))
+        .collect();
 
     Ok(JSONRPCRequest {
         jsonrpc: JSONRPC_VERSION.into(),
@@ -424,6 +429,8 @@ fn create_expected_patch_approval_elicitation_request(
             codex_reason: reason,
             codex_grant_root: grant_root,
             codex_changes: changes,
+            codex_deferred_changes: Vec::new(),
+            codex_file_statuses: file_statuses,
             codex_call_id: "call1234".to_string(),
         })?),
     })
@@ -25,6 +25,18 @@ pub struct OtelTlsConfig {
     pub client_private_key: Option<PathBuf>,
 }
 
+/// What an exporter strips from events before sending them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OtelScrubSettings {
+    /// Replace file paths in attribute values with a short hash.
+    pub hash_paths: bool,
+    /// Drop prompt text and tool arguments and output.
+    pub drop_content: bool,
+    /// When set, only these attributes are exported. Entries ending in `*`
+    /// match by prefix.
+    pub allowed_attributes: Option<Vec<String>>,
+}
+
 #[derive(Clone, Debug)]
 pub enum OtelExporter {
     None,
@@ -32,11 +44,13 @@ pub enum OtelExporter {
         endpoint: String,
         headers: HashMap<String, String>,
         tls: Option<OtelTlsConfig>,
+        scrub: OtelScrubSettings,
     },
     OtlpHttp {
         endpoint: String,
         headers: HashMap<String, String>,
         protocol: OtelHttpProtocol,
         tls: Option<OtelTlsConfig>,
+        scrub: OtelScrubSettings,
     },
 }
@@ -0,0 +1,19 @@
+//! Telemetry for deprecated client APIs, so their remaining use can be
+//! measured before they are removed.
+
+use crate::otel_event_manager::timestamp;
+
+/// Record a call of the deprecated app-server method `method`.
+/// `call_count` is the number of calls of that method since the server
+/// started, including this one.
+pub fn deprecated_api_call(method: &str, replacement: Option<&str>, call_count: u64) {
+    tracing::event!(
+        tracing::Level::INFO,
+        event.name = "codex.deprecated_api_call",
+        event.timestamp = %timestamp(),
+        app.version = env!("CARGO_PKG_VERSION"),
+        api.method = method,
+        api.replacement = replacement,
+        call_count = call_count,
+    );
+}
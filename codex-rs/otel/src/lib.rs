@@ -1,8 +1,12 @@
 pub mod config;
+pub mod deprecation;
 
+#[cfg(feature = "otel")]
+pub mod log_layer;
 pub mod otel_event_manager;
 #[cfg(feature = "otel")]
 pub mod otel_provider;
+pub mod scrub;
 
 #[cfg(not(feature = "otel"))]
 mod imp {
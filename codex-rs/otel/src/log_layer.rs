@@ -0,0 +1,127 @@
+//! `tracing` layer that turns Codex telemetry events into OpenTelemetry log
+//! records, passing every field through the exporter's [`Scrubber`] first.
+//!
+//! Fields become attributes, except `message`, which becomes the record body.
+//! Dropped fields never reach the SDK, so nothing scrubbed is buffered by
+//! the batch exporter either.
+
+use opentelemetry::Key;
+use opentelemetry::logs::AnyValue;
+use opentelemetry::logs::LogRecord;
+use opentelemetry::logs::Logger;
+use opentelemetry::logs::LoggerProvider;
+use opentelemetry::logs::Severity;
+use opentelemetry_sdk::logs::SdkLogger;
+use opentelemetry_sdk::logs::SdkLoggerProvider;
+use std::fmt;
+use tracing::Event;
+use tracing::Level;
+use tracing::Subscriber;
+use tracing::field::Field;
+use tracing::field::Visit;
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+
+use crate::scrub::Scrubber;
+
+const MESSAGE_FIELD: &str = "message";
+
+pub struct OtelLogLayer {
+    logger: SdkLogger,
+    scrubber: Scrubber,
+}
+
+impl OtelLogLayer {
+    pub fn new(provider: &SdkLoggerProvider, scrubber: Scrubber) -> Self {
+        Self {
+            logger: provider.logger("codex_otel"),
+            scrubber,
+        }
+    }
+}
+
+impl<S> Layer<S> for OtelLogLayer
+where
+    S: Subscriber,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut record = self.logger.create_log_record();
+        record.set_target(metadata.target());
+        record.set_event_name(metadata.name());
+        record.set_severity_number(severity(*metadata.level()));
+        record.set_severity_text(metadata.level().as_str());
+
+        let mut visitor = ScrubbingVisitor {
+            record: &mut record,
+            scrubber: &self.scrubber,
+        };
+        event.record(&mut visitor);
+
+        self.logger.emit(record);
+    }
+}
+
+fn severity(level: Level) -> Severity {
+    match level {
+        Level::TRACE => Severity::Trace,
+        Level::DEBUG => Severity::Debug,
+        Level::INFO => Severity::Info,
+        Level::WARN => Severity::Warn,
+        Level::ERROR => Severity::Error,
+    }
+}
+
+struct ScrubbingVisitor<'a, R: LogRecord> {
+    record: &'a mut R,
+    scrubber: &'a Scrubber,
+}
+
+impl<R: LogRecord> ScrubbingVisitor<'_, R> {
+    fn record_text(&mut self, field: &Field, value: &str) {
+        let Some(value) = self.scrubber.scrub(field.name(), value) else {
+            return;
+        };
+        let value = AnyValue::from(value.into_owned());
+        if field.name() == MESSAGE_FIELD {
+            self.record.set_body(value);
+        } else {
+            self.record.add_attribute(Key::new(field.name()), value);
+        }
+    }
+
+    fn record_value(&mut self, field: &Field, value: AnyValue) {
+        if self.scrubber.keeps(field.name()) {
+            self.record.add_attribute(Key::new(field.name()), value);
+        }
+    }
+}
+
+impl<R: LogRecord> Visit for ScrubbingVisitor<'_, R> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record_text(field, value);
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record_text(field, &format!("{value:?}"));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.record_value(field, AnyValue::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match i64::try_from(value) {
+            Ok(value) => self.record_value(field, AnyValue::from(value)),
+            Err(_) => self.record_text(field, &value.to_string()),
+        }
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.record_value(field, AnyValue::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.record_value(field, AnyValue::from(value));
+    }
+}
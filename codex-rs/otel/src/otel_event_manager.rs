@@ -513,6 +513,6 @@ impl OtelEventManager {
     }
 }
 
-fn timestamp() -> String {
+pub(crate) fn timestamp() -> String {
     Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
 }
@@ -1,7 +1,10 @@
 use crate::config::OtelExporter;
 use crate::config::OtelHttpProtocol;
+use crate::config::OtelScrubSettings;
 use crate::config::OtelSettings;
 use crate::config::OtelTlsConfig;
+use crate::log_layer::OtelLogLayer;
+use crate::scrub::Scrubber;
 use http::Uri;
 use opentelemetry::KeyValue;
 use opentelemetry_otlp::LogExporter;
@@ -38,6 +41,7 @@ const ENV_ATTRIBUTE: &str = "env";
 
 pub struct OtelProvider {
     pub logger: SdkLoggerProvider,
+    scrub: OtelScrubSettings,
 }
 
 impl OtelProvider {
@@ -45,6 +49,12 @@ impl OtelProvider {
         let _ = self.logger.shutdown();
     }
 
+    /// `tracing` layer that exports events through this provider, scrubbed
+    /// according to the exporter's settings.
+    pub fn log_layer(&self) -> OtelLogLayer {
+        OtelLogLayer::new(&self.logger, Scrubber::new(self.scrub.clone()))
+    }
+
     pub fn from(settings: &OtelSettings) -> Result<Option<Self>, Box<dyn Error>> {
         let resource = Resource::builder()
             .with_service_name(settings.service_name.clone())
@@ -59,7 +69,7 @@ impl OtelProvider {
 
         let mut builder = SdkLoggerProvider::builder().with_resource(resource);
 
-        match &settings.exporter {
+        let scrub = match &settings.exporter {
             OtelExporter::None => {
                 debug!("No exporter enabled in OTLP settings.");
                 return Ok(None);
@@ -68,6 +78,7 @@ impl OtelProvider {
                 endpoint,
                 headers,
                 tls,
+                scrub,
             } => {
                 debug!("Using OTLP Grpc exporter: {endpoint}");
 
@@ -102,12 +113,14 @@ impl OtelProvider {
                     .build()?;
 
                 builder = builder.with_batch_exporter(exporter);
+                scrub
             }
             OtelExporter::OtlpHttp {
                 endpoint,
                 headers,
                 protocol,
                 tls,
+                scrub,
             } => {
                 debug!("Using OTLP Http exporter: {endpoint}");
 
@@ -130,11 +143,13 @@ impl OtelProvider {
                 let exporter = exporter_builder.build()?;
 
                 builder = builder.with_batch_exporter(exporter);
+                scrub
             }
-        }
+        };
 
         Ok(Some(Self {
             logger: builder.build(),
+            scrub: scrub.clone(),
         }))
     }
 }
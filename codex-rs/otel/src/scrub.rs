@@ -0,0 +1,187 @@
+//! Scrubbing applied to every exported attribute.
+//!
+//! Security teams often want Codex telemetry without the source code and
+//! directory layout it can carry. [`Scrubber`] drops attributes that are not
+//! on the exporter's allowlist, drops prompt text and tool arguments/output,
+//! and replaces file paths with a short hash that is stable across runs, so
+//! that events about the same file can still be correlated.
+
+use std::borrow::Cow;
+
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::config::OtelScrubSettings;
+
+/// Attributes every event keeps regardless of the allowlist, so that the
+/// collector can still tell events apart and order them.
+const ALWAYS_EXPORTED: &[&str] = &["event.name", "event.timestamp"];
+
+/// Attributes that carry prompt text or tool input/output.
+const CONTENT_ATTRIBUTES: &[&str] = &["prompt", "arguments", "output"];
+
+/// Hex digits of the SHA-256 digest kept in a hashed path.
+const PATH_HASH_LEN: usize = 12;
+
+#[derive(Clone, Debug, Default)]
+pub struct Scrubber {
+    settings: OtelScrubSettings,
+}
+
+impl Scrubber {
+    pub fn new(settings: OtelScrubSettings) -> Self {
+        Self { settings }
+    }
+
+    /// Whether the attribute is exported at all.
+    pub fn keeps(&self, key: &str) -> bool {
+        if ALWAYS_EXPORTED.contains(&key) {
+            return true;
+        }
+        if self.settings.drop_content && CONTENT_ATTRIBUTES.contains(&key) {
+            return false;
+        }
+        match &self.settings.allowed_attributes {
+            Some(allowed) => allowed.iter().any(|pattern| matches_pattern(pattern, key)),
+            None => true,
+        }
+    }
+
+    /// Returns the value to export for a string attribute, or `None` when
+    /// the attribute is dropped.
+    pub fn scrub<'a>(&self, key: &str, value: &'a str) -> Option<Cow<'a, str>> {
+        if !self.keeps(key) {
+            return None;
+        }
+        if self.settings.hash_paths && !ALWAYS_EXPORTED.contains(&key) {
+            Some(hash_paths(value))
+        } else {
+            Some(Cow::Borrowed(value))
+        }
+    }
+}
+
+/// `pattern` is an attribute name, or a prefix followed by `*`.
+fn matches_pattern(pattern: &str, key: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => key.starts_with(prefix),
+        None => pattern == key,
+    }
+}
+
+/// Replaces every path-looking token in `value` with `<path:HASH>`.
+fn hash_paths(value: &str) -> Cow<'_, str> {
+    let mut result = String::new();
+    let mut last = 0;
+    let mut token_start = None;
+    for (index, ch) in value
+        .char_indices()
+        .chain(std::iter::once((value.len(), ' ')))
+    {
+        if !is_delimiter(ch) {
+            token_start.get_or_insert(index);
+            continue;
+        }
+        let Some(start) = token_start.take() else {
+            continue;
+        };
+        let token = &value[start..index];
+        if looks_like_path(token) {
+            result.push_str(&value[last..start]);
+            result.push_str(&hashed_path(token));
+            last = index;
+        }
+    }
+    if last == 0 {
+        return Cow::Borrowed(value);
+    }
+    result.push_str(&value[last..]);
+    Cow::Owned(result)
+}
+
+fn is_delimiter(ch: char) -> bool {
+    ch.is_whitespace()
+        || matches!(
+            ch,
+            '"' | '\'' | '`' | ',' | ';' | '=' | '(' | ')' | '[' | ']' | '{' | '}'
+        )
+}
+
+fn looks_like_path(token: &str) -> bool {
+    let bytes = token.as_bytes();
+    let unix = token.len() > 1
+        && (token.starts_with('/')
+            || token.starts_with("~/")
+            || token.starts_with("./")
+            || token.starts_with("../"));
+    let windows = bytes.len() > 3
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes[2] == b'\\' || bytes[2] == b'/');
+    unix || windows
+}
+
+fn hashed_path(path: &str) -> String {
+    let digest = Sha256::digest(path.as_bytes());
+    let hex = digest
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    format!("<path:{}>", &hex[..PATH_HASH_LEN])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn drops_content_and_attributes_outside_the_allowlist() {
+        let scrubber = Scrubber::new(OtelScrubSettings {
+            hash_paths: false,
+            drop_content: true,
+            allowed_attributes: Some(vec!["tool_name".to_string(), "duration_*".to_string()]),
+        });
+
+        assert_eq!(
+            scrubber.scrub("event.name", "codex.tool_result"),
+            Some(Cow::Borrowed("codex.tool_result"))
+        );
+        assert_eq!(
+            scrubber.scrub("tool_name", "shell"),
+            Some(Cow::Borrowed("shell"))
+        );
+        assert!(scrubber.keeps("duration_ms"));
+        assert!(!scrubber.keeps("user.email"));
+        assert_eq!(scrubber.scrub("output", "fn main() {}"), None);
+    }
+
+    #[test]
+    fn hashes_paths_inside_values() {
+        let scrubber = Scrubber::new(OtelScrubSettings {
+            hash_paths: true,
+            ..Default::default()
+        });
+
+        let scrubbed = scrubber
+            .scrub(
+                "arguments",
+                r#"{"command":["cat","/home/me/src/main.rs"],"workdir":"C:\\repo"}"#,
+            )
+            .expect("kept");
+        let main_rs = hashed_path("/home/me/src/main.rs");
+        let repo = hashed_path("C:\\\\repo");
+        assert_eq!(
+            scrubbed,
+            format!(r#"{{"command":["cat","{main_rs}"],"workdir":"{repo}"}}"#)
+        );
+        assert_eq!(
+            scrubber.scrub("tool_name", "shell"),
+            Some(Cow::Borrowed("shell"))
+        );
+        assert_eq!(
+            scrubber.scrub("error.message", "see https://example.com/a/b"),
+            Some(Cow::Borrowed("see https://example.com/a/b"))
+        );
+    }
+}
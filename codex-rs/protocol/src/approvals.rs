@@ -36,6 +36,10 @@ impl SandboxRiskLevel {
 pub struct ExecApprovalRequestEvent {
     /// Identifier for the associated exec call, if available.
     pub call_id: String,
+    /// Id of the item the approval is for: the `CommandExecution` item of the
+    /// run, or the call id when no such item exists (e.g. patches).
+    #[serde(default)]
+    pub item_id: String,
     /// Turn ID that this command belongs to.
     /// Uses `#[serde(default)]` for backwards compatibility.
     #[serde(default)]
@@ -47,10 +51,78 @@ pub struct ExecApprovalRequestEvent {
     /// Optional human-readable reason for the approval (e.g. retry without sandbox).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>,
-    /// Optional model-provided risk assessment describing the blocked command.
+    /// Optional risk assessment describing the blocked command, from the model
+    /// when enabled and from local heuristics and execpolicy otherwise. Unset
+    /// when nothing risky was found.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub risk: Option<SandboxCommandAssessment>,
     pub parsed_cmd: Vec<ParsedCommand>,
+    /// Rules the client can offer to remember alongside the approval, from
+    /// most to least specific. Persist one with `Op::AddApprovalRule`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub proposed_rules: Vec<ApprovalRule>,
+}
+
+/// A remembered "always allow" decision for exec commands in a project.
+/// Commands matching a rule run without asking for approval.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type")]
+pub enum ApprovalRule {
+    /// Exactly this argv, e.g. `["cargo", "test", "-p", "codex-core"]`.
+    ExactCommand { command: Vec<String> },
+    /// Any invocation of `program` whose first argument is `subcommand`,
+    /// e.g. `git status --short`.
+    ProgramSubcommand { program: String, subcommand: String },
+    /// Any program located under `path`, e.g. the project's `scripts/` dir.
+    PathPrefix { path: PathBuf },
+}
+
+/// Asks the user to make `root` writable so a command or patch that targets
+/// paths outside the sandbox's writable roots can proceed inside the sandbox.
+/// Answer with `Op::WriteScopeApproval`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct WriteScopeApprovalRequestEvent {
+    /// Id of the exec or patch call that needs the extra root.
+    pub call_id: String,
+    /// Id of the item the request is for, as in `ExecApprovalRequestEvent`.
+    #[serde(default)]
+    pub item_id: String,
+    /// Turn ID that this request belongs to.
+    #[serde(default)]
+    pub turn_id: String,
+    /// The directory that would become writable.
+    pub root: PathBuf,
+    /// The paths outside the writable roots that the call targets.
+    pub paths: Vec<PathBuf>,
+    /// The command that was blocked, when the request comes from an exec call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<Vec<String>>,
+    /// Optional human-readable explanation of why the root is needed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// User's answer to a `WriteScopeApprovalRequest`.
+#[derive(
+    Debug, Default, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema, TS,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteScopeDecision {
+    /// Make the root writable for the rest of the session.
+    GrantForSession,
+    /// Make the root writable now and in future sessions in this project.
+    GrantForProject,
+    /// Keep the root read-only; the call fails as it would have without
+    /// asking.
+    #[default]
+    Deny,
+}
+
+impl WriteScopeDecision {
+    pub fn is_granted(self) -> bool {
+        !matches!(self, WriteScopeDecision::Deny)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -64,4 +136,46 @@ pub struct ApplyPatchApprovalRequestEvent {
     /// When set, the agent is asking the user to allow writes under this root for the remainder of the session.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub grant_root: Option<PathBuf>,
+    /// Files omitted from `changes` because the patch was too large to inline.
+    /// Fetch each change with `Op::GetPatchContent`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deferred_changes: Vec<DeferredFileChange>,
+    /// Result of dry-running the patch against the working tree when approval
+    /// was requested, keyed by the same paths as the changes.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub file_statuses: HashMap<PathBuf, PatchFileStatus>,
+}
+
+/// Whether a file in a pending patch still applies as reviewed.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(tag = "status", rename_all = "snake_case")]
+#[ts(tag = "status")]
+pub enum PatchFileStatus {
+    /// Applying the change now produces the reviewed result.
+    AppliesCleanly,
+    /// The change can no longer be applied as written.
+    Conflict { reason: String },
+    /// The file changed since Codex last read or wrote it, so approving may
+    /// clobber concurrent edits.
+    ModifiedSinceRead,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DeferredFileChangeKind {
+    Add,
+    Delete,
+    Update,
+}
+
+/// Summary of a file change whose body is held by the agent until requested.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct DeferredFileChange {
+    pub path: PathBuf,
+    pub kind: DeferredFileChangeKind,
+    pub added_lines: i64,
+    pub removed_lines: i64,
+    /// Hex-encoded SHA-256 of the change body: `content` for additions and
+    /// deletions, `unified_diff` for updates.
+    pub sha256: String,
 }
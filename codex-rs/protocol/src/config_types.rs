@@ -90,6 +90,10 @@ pub enum SandboxMode {
 
     #[serde(rename = "danger-full-access")]
     DangerFullAccess,
+
+    /// Read-only, with patches and unsandboxed commands forbidden.
+    #[serde(rename = "audit")]
+    Audit,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Display, JsonSchema, TS)]
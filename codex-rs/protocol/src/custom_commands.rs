@@ -0,0 +1,25 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use std::path::PathBuf;
+use ts_rs::TS;
+
+use crate::protocol::AskForApproval;
+
+/// A user-defined command loaded from `$CODEX_HOME/commands/<name>.md` and
+/// run with `Op::RunCommand`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+pub struct CustomCommand {
+    pub name: String,
+    pub path: PathBuf,
+    pub description: Option<String>,
+    /// Names of the `$NAME` placeholders the template requires, in the order
+    /// declared by the `args` frontmatter key.
+    pub args: Vec<String>,
+    /// Model used for the command's turn instead of the session model.
+    pub model: Option<String>,
+    /// Approval policy used for the command's turn instead of the session's.
+    pub approval_policy: Option<AskForApproval>,
+    /// Prompt template with the frontmatter removed.
+    pub template: String,
+}
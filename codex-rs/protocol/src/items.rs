@@ -1,13 +1,19 @@
+use crate::parse_command::ParsedCommand;
 use crate::protocol::AgentMessageEvent;
 use crate::protocol::AgentReasoningEvent;
 use crate::protocol::AgentReasoningRawContentEvent;
 use crate::protocol::EventMsg;
+use crate::protocol::ExecCommandBeginEvent;
+use crate::protocol::ExecCommandEndEvent;
+use crate::protocol::FileChange;
 use crate::protocol::UserMessageEvent;
 use crate::protocol::WebSearchEndEvent;
 use crate::user_input::UserInput;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
 use ts_rs::TS;
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS, JsonSchema)]
@@ -18,6 +24,10 @@ pub enum TurnItem {
     AgentMessage(AgentMessageItem),
     Reasoning(ReasoningItem),
     WebSearch(WebSearchItem),
+    CommandExecution(CommandExecutionItem),
+    FileRead(FileReadItem),
+    FileChange(FileChangeItem),
+    BlockedAction(BlockedActionItem),
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS, JsonSchema)]
@@ -53,6 +63,94 @@ pub struct WebSearchItem {
     pub query: String,
 }
 
+/// One run of a command by the agent or the user. The id is allocated when
+/// the run starts and also names its approval requests, so it stays unique
+/// when the same tool call is run again (e.g. after a stream retry).
+#[derive(Debug, Clone, Deserialize, Serialize, TS, JsonSchema)]
+pub struct CommandExecutionItem {
+    pub id: String,
+    /// The tool call that ran the command.
+    pub call_id: String,
+    pub command: Vec<String>,
+    pub cwd: PathBuf,
+    pub parsed_cmd: Vec<ParsedCommand>,
+    pub status: CommandExecutionStatus,
+    /// Output aggregated from stdout and stderr, once the command has ended.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aggregated_output: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<i64>,
+    /// Set when the session's output policy left part of the output out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_truncation: Option<CommandOutputTruncation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS, JsonSchema)]
+pub struct CommandOutputTruncation {
+    /// Bytes dropped from the middle of `aggregated_output`.
+    pub omitted_bytes: u64,
+    /// Bytes of output that were not streamed as deltas.
+    pub unstreamed_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandExecutionStatus {
+    InProgress,
+    Completed,
+    Failed,
+    /// The command, or one of its segments, was rejected before it ran.
+    Declined,
+}
+
+/// A file the agent read through the `read_file` tool.
+#[derive(Debug, Clone, Deserialize, Serialize, TS, JsonSchema)]
+pub struct FileReadItem {
+    pub id: String,
+    pub path: PathBuf,
+}
+
+/// A patch applied by the agent. Started items carry `InProgress`; the
+/// completed item reports whether the patch applied.
+#[derive(Debug, Clone, Deserialize, Serialize, TS, JsonSchema)]
+pub struct FileChangeItem {
+    pub id: String,
+    pub changes: HashMap<PathBuf, FileChange>,
+    pub status: FileChangeStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileChangeStatus {
+    InProgress,
+    Completed,
+    /// Applied via a three-way merge that left conflict markers in at least
+    /// one file.
+    MergedWithConflicts,
+    Failed,
+}
+
+/// A tool call the sandbox policy refused before or while running it.
+#[derive(Debug, Clone, Deserialize, Serialize, TS, JsonSchema)]
+pub struct BlockedActionItem {
+    pub id: String,
+    pub action: BlockedAction,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockedAction {
+    /// A file edit through `apply_patch`.
+    ApplyPatch,
+    /// A command that would have run outside the sandbox.
+    UnsandboxedCommand,
+    /// A command the sandbox stopped, e.g. because it tried to write.
+    SandboxDenied,
+}
+
 impl UserMessageItem {
     pub fn new(content: &[UserInput]) -> Self {
         Self {
@@ -142,6 +240,48 @@ impl WebSearchItem {
     }
 }
 
+impl CommandExecutionItem {
+    pub fn started(id: String, event: &ExecCommandBeginEvent) -> Self {
+        Self {
+            id,
+            call_id: event.call_id.clone(),
+            command: event.command.clone(),
+            cwd: event.cwd.clone(),
+            parsed_cmd: event.parsed_cmd.clone(),
+            status: CommandExecutionStatus::InProgress,
+            aggregated_output: None,
+            exit_code: None,
+            duration_ms: None,
+            output_truncation: None,
+        }
+    }
+
+    pub fn completed(
+        id: String,
+        event: &ExecCommandEndEvent,
+        output_truncation: Option<CommandOutputTruncation>,
+    ) -> Self {
+        let status = if event.exit_code == 0 {
+            CommandExecutionStatus::Completed
+        } else {
+            CommandExecutionStatus::Failed
+        };
+        Self {
+            id,
+            call_id: event.call_id.clone(),
+            command: event.command.clone(),
+            cwd: event.cwd.clone(),
+            parsed_cmd: event.parsed_cmd.clone(),
+            status,
+            aggregated_output: (!event.aggregated_output.is_empty())
+                .then(|| event.aggregated_output.clone()),
+            exit_code: Some(event.exit_code),
+            duration_ms: Some(i64::try_from(event.duration.as_millis()).unwrap_or(i64::MAX)),
+            output_truncation,
+        }
+    }
+}
+
 impl TurnItem {
     pub fn id(&self) -> String {
         match self {
@@ -149,6 +289,10 @@ impl TurnItem {
             TurnItem::AgentMessage(item) => item.id.clone(),
             TurnItem::Reasoning(item) => item.id.clone(),
             TurnItem::WebSearch(item) => item.id.clone(),
+            TurnItem::CommandExecution(item) => item.id.clone(),
+            TurnItem::FileRead(item) => item.id.clone(),
+            TurnItem::FileChange(item) => item.id.clone(),
+            TurnItem::BlockedAction(item) => item.id.clone(),
         }
     }
 
@@ -158,6 +302,13 @@ impl TurnItem {
             TurnItem::AgentMessage(item) => item.as_legacy_events(),
             TurnItem::WebSearch(item) => vec![item.as_legacy_event()],
             TurnItem::Reasoning(item) => item.as_legacy_events(show_raw_agent_reasoning),
+            // Commands and patch applications keep emitting their Begin/End
+            // events directly, and file reads and blocked actions have no
+            // legacy counterpart.
+            TurnItem::CommandExecution(_)
+            | TurnItem::FileRead(_)
+            | TurnItem::FileChange(_)
+            | TurnItem::BlockedAction(_) => Vec::new(),
         }
     }
 }
@@ -3,6 +3,7 @@ mod conversation_id;
 pub use conversation_id::ConversationId;
 pub mod approvals;
 pub mod config_types;
+pub mod custom_commands;
 pub mod custom_prompts;
 pub mod items;
 pub mod message_history;
@@ -258,6 +258,9 @@ impl From<Vec<UserInput>> for ResponseInputItem {
                 .map(|c| match c {
                     UserInput::Text { text } => ContentItem::InputText { text },
                     UserInput::Image { image_url } => ContentItem::InputImage { image_url },
+                    UserInput::LocalAudio { path } => ContentItem::InputText {
+                        text: format!("Audio at `{}` was not transcribed.", path.display()),
+                    },
                     UserInput::LocalImage { path } => match load_and_resize_to_fit(&path) {
                         Ok(image) => ContentItem::InputImage {
                             image_url: image.into_data_url(),
@@ -3,6 +3,7 @@
 //! Uses a SQ (Submission Queue) / EQ (Event Queue) pattern to asynchronously communicate
 //! between user and agent.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::fmt;
 use std::path::Path;
@@ -23,6 +24,10 @@ use crate::parse_command::ParsedCommand;
 use crate::plan_tool::UpdatePlanArgs;
 use crate::user_input::UserInput;
 use mcp_types::CallToolResult;
+use mcp_types::ElicitRequestParamsRequestedSchema;
+use mcp_types::Prompt as McpPrompt;
+use mcp_types::ReadResourceResultContents as McpResourceContents;
+use mcp_types::RequestId as McpRequestId;
 use mcp_types::Resource as McpResource;
 use mcp_types::ResourceTemplate as McpResourceTemplate;
 use mcp_types::Tool as McpTool;
@@ -35,9 +40,15 @@ use strum_macros::Display;
 use ts_rs::TS;
 
 pub use crate::approvals::ApplyPatchApprovalRequestEvent;
+pub use crate::approvals::ApprovalRule;
+pub use crate::approvals::DeferredFileChange;
+pub use crate::approvals::DeferredFileChangeKind;
 pub use crate::approvals::ExecApprovalRequestEvent;
+pub use crate::approvals::PatchFileStatus;
 pub use crate::approvals::SandboxCommandAssessment;
 pub use crate::approvals::SandboxRiskLevel;
+pub use crate::approvals::WriteScopeApprovalRequestEvent;
+pub use crate::approvals::WriteScopeDecision;
 
 /// Open/close tags for special user-input blocks. Used across crates to avoid
 /// duplicated hardcoded strings.
@@ -153,6 +164,33 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Answer a `WriteScopeApprovalRequest`.
+    WriteScopeApproval {
+        /// The id of the submission that asked for the root
+        id: String,
+        /// The user's decision in response to the request.
+        decision: WriteScopeDecision,
+    },
+
+    /// Answer an `ElicitationRequest` from an MCP server. With `Accept`,
+    /// `content` must satisfy the requested schema.
+    ResolveElicitation {
+        server_name: String,
+        request_id: McpRequestId,
+        decision: ElicitationAction,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content: Option<Value>,
+    },
+
+    /// Answer a `UserInputRequest` from the `ask_user` tool. `None` means
+    /// the user did not answer.
+    UserInputAnswer {
+        /// The `call_id` of the request.
+        call_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        answer: Option<String>,
+    },
+
     /// Append an entry to the persistent cross-session message history.
     ///
     /// Note the entry is not guaranteed to be logged if the user has
@@ -172,6 +210,23 @@ pub enum Op {
     /// Request the list of available custom prompts.
     ListCustomPrompts,
 
+    /// Request the prompts offered by all configured MCP servers.
+    /// Reply is delivered via `EventMsg::McpListPromptsResponse`.
+    ListMcpPrompts,
+
+    /// Read a single resource from an MCP server.
+    /// Reply is delivered via `EventMsg::McpResourceReadResponse`.
+    ReadMcpResource { server: String, uri: String },
+
+    /// Expand an MCP prompt and submit the resulting messages as user input,
+    /// the same way a custom prompt slash command would.
+    RunMcpPrompt {
+        server: String,
+        name: String,
+        #[serde(default)]
+        arguments: HashMap<String, String>,
+    },
+
     /// Request the agent to summarize the current conversation context.
     /// The agent will use its existing context (either conversation history or previous response id)
     /// to generate a summary which will be returned as an AgentMessage event.
@@ -195,6 +250,108 @@ pub enum Op {
         /// The raw command string after '!'
         command: String,
     },
+
+    /// Start a long-running command (dev server, test watcher) in the user's
+    /// shell, detached from any turn. It keeps running until it exits, is
+    /// stopped with `Op::TerminateBackground`, or the session shuts down.
+    /// Lifecycle changes are reported via `EventMsg::BackgroundTaskUpdate`.
+    RunBackground { command: String },
+
+    /// Stop a task started with `Op::RunBackground`.
+    TerminateBackground { task_id: String },
+
+    /// Request the changes made in the conversation's isolated worktree (see
+    /// `worktree_isolation`). Reply is delivered via `EventMsg::WorktreeDiff`.
+    WorktreeDiff,
+
+    /// Apply the isolated worktree's changes to the primary checkout, remove
+    /// the worktree, and continue the conversation in the primary checkout.
+    /// Reported via `EventMsg::WorktreeClosed`.
+    MergeWorktree,
+
+    /// Remove the isolated worktree without applying its changes and continue
+    /// the conversation in the primary checkout. Reported via
+    /// `EventMsg::WorktreeClosed`.
+    DiscardWorktree,
+
+    /// Push the current branch and open a pull request (GitHub) or merge
+    /// request (GitLab) for it, using the forge token stored for the remote's
+    /// host. Reported via `EventMsg::PullRequestCreated`.
+    CreatePullRequest {
+        title: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        body: Option<String>,
+        /// Target branch; defaults to the remote's default branch.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base: Option<String>,
+        #[serde(default)]
+        draft: bool,
+    },
+
+    /// Request the full body of a file change that was deferred from an
+    /// `ApplyPatchApprovalRequest`.
+    /// Reply is delivered via `EventMsg::PatchContentResponse`.
+    GetPatchContent { call_id: String, path: PathBuf },
+
+    /// Report the size of the client's terminal window. Commands that run
+    /// attached to a pseudo-terminal are started with this size.
+    SetTerminalSize { rows: i32, cols: i32 },
+
+    /// Add a folder to the session's workspace alongside `cwd`. Workspace
+    /// roots are writable under `workspace-write` and can be used as the
+    /// `workdir` of tool calls. Relative paths are resolved against `cwd`.
+    AddWorkspaceRoot { path: PathBuf },
+
+    /// Remove a folder previously added with `AddWorkspaceRoot` or listed in
+    /// `workspace_roots` in config.
+    RemoveWorkspaceRoot { path: PathBuf },
+
+    /// Replace the conversation's token budget. `None` removes the limit.
+    /// Use this to raise the budget after a `BudgetExhausted` event.
+    SetTokenBudget { budget: Option<i64> },
+
+    /// Replace the extra environment variables set for every command the
+    /// conversation runs from now on. They are added on top of
+    /// `shell_environment_policy.set`, so `include_only` still filters them.
+    SetEnvironment { env: HashMap<String, String> },
+
+    /// Move the conversation to another working directory. Relative paths
+    /// are resolved against the current `cwd`. Under `workspace-write` the
+    /// directory must already be writable. Instruction files (`AGENTS.md`)
+    /// for the new directory are loaded when the next turn starts.
+    SetCwd { path: PathBuf },
+
+    /// Remember an approval rule for the current project so matching commands
+    /// skip the approval prompt in this and future sessions.
+    AddApprovalRule { rule: ApprovalRule },
+
+    /// Forget a remembered approval rule for the current project.
+    RemoveApprovalRule { rule: ApprovalRule },
+
+    /// Request the approval rules remembered for the current project.
+    /// Reply is delivered via `EventMsg::ListApprovalRulesResponse`.
+    ListApprovalRules,
+
+    /// Switch to another profile from `config.toml` for subsequent turns.
+    /// The model, provider, reasoning settings, approval policy, sandbox,
+    /// and MCP servers are re-derived from the profile. Reported via
+    /// `EventMsg::ProfileChanged`.
+    SetProfile { profile: String },
+
+    /// Rename the thread. The title is saved in the thread's metadata and
+    /// reported via `EventMsg::ThreadMetadataChanged`.
+    SetThreadTitle { title: String },
+
+    /// Run the custom command `$CODEX_HOME/commands/<name>.md` as user input.
+    /// `args` supplies a value for each `$NAME` placeholder the command
+    /// declares. The command's `model` and `approval_policy` apply to the turn
+    /// it starts only; if a task is already running, the expanded text is
+    /// added to it like `Op::UserInput` and the overrides are ignored.
+    RunCommand {
+        name: String,
+        #[serde(default)]
+        args: HashMap<String, String>,
+    },
 }
 
 /// Determines the conditions under which the user is consulted to approve
@@ -251,6 +408,12 @@ pub enum SandboxPolicy {
     #[serde(rename = "read-only")]
     ReadOnly,
 
+    /// Same as `ReadOnly`, but guarantees nothing changes: `apply_patch` is
+    /// disabled and commands are never re-run outside the sandbox, even
+    /// with approval.
+    #[serde(rename = "audit")]
+    Audit,
+
     /// Same as `ReadOnly` but additionally grants write access to the current
     /// working directory ("workspace").
     #[serde(rename = "workspace-write")]
@@ -323,6 +486,17 @@ impl SandboxPolicy {
         SandboxPolicy::ReadOnly
     }
 
+    /// Returns the read-only policy that also forbids patches and unsandboxed
+    /// commands.
+    pub fn new_audit_policy() -> Self {
+        SandboxPolicy::Audit
+    }
+
+    /// Whether this is the audit policy, under which nothing may be written.
+    pub fn is_audit(&self) -> bool {
+        matches!(self, SandboxPolicy::Audit)
+    }
+
     /// Returns a policy that can read the entire disk, but can only write to
     /// the current working directory and the per-user tmp dir on macOS. It does
     /// not allow network access.
@@ -343,7 +517,7 @@ impl SandboxPolicy {
     pub fn has_full_disk_write_access(&self) -> bool {
         match self {
             SandboxPolicy::DangerFullAccess => true,
-            SandboxPolicy::ReadOnly => false,
+            SandboxPolicy::ReadOnly | SandboxPolicy::Audit => false,
             SandboxPolicy::WorkspaceWrite { .. } => false,
         }
     }
@@ -351,7 +525,7 @@ impl SandboxPolicy {
     pub fn has_full_network_access(&self) -> bool {
         match self {
             SandboxPolicy::DangerFullAccess => true,
-            SandboxPolicy::ReadOnly => false,
+            SandboxPolicy::ReadOnly | SandboxPolicy::Audit => false,
             SandboxPolicy::WorkspaceWrite { network_access, .. } => *network_access,
         }
     }
@@ -362,7 +536,7 @@ impl SandboxPolicy {
     pub fn get_writable_roots_with_cwd(&self, cwd: &Path) -> Vec<WritableRoot> {
         match self {
             SandboxPolicy::DangerFullAccess => Vec::new(),
-            SandboxPolicy::ReadOnly => Vec::new(),
+            SandboxPolicy::ReadOnly | SandboxPolicy::Audit => Vec::new(),
             SandboxPolicy::WorkspaceWrite {
                 writable_roots,
                 exclude_tmpdir_env_var,
@@ -448,6 +622,10 @@ pub enum EventMsg {
     /// Agent has completed all actions
     TaskComplete(TaskCompleteEvent),
 
+    /// Sent periodically while a turn runs so clients can tell a long, quiet
+    /// turn from a hung one.
+    TurnHeartbeat(TurnHeartbeatEvent),
+
     /// Usage update for the current session, including totals and last turn.
     /// Optional means unknown — UIs should not display when `None`.
     TokenCount(TokenCountEvent),
@@ -484,8 +662,22 @@ pub enum EventMsg {
     /// Aggregate MCP startup completion summary.
     McpStartupComplete(McpStartupCompleteEvent),
 
+    /// An MCP server's health changed after startup (missed pings, restart,
+    /// recovery).
+    McpServerStatusChanged(McpServerStatusChangedEvent),
+
+    /// An MCP server's tool list changed. The next turn offers the new list.
+    ToolsChanged(ToolsChangedEvent),
+
+    /// Tools from different MCP servers, or aliased tools, that resolved to
+    /// the same name at startup. Only the first of each is offered.
+    McpToolConflicts(McpToolConflictsEvent),
+
     McpToolCallBegin(McpToolCallBeginEvent),
 
+    /// Progress reported by the MCP server for an in-flight tool call.
+    McpToolCallProgress(McpToolCallProgressEvent),
+
     McpToolCallEnd(McpToolCallEndEvent),
 
     WebSearchBegin(WebSearchBeginEvent),
@@ -507,6 +699,19 @@ pub enum EventMsg {
 
     ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent),
 
+    /// A command or patch targets paths outside the writable roots. Answer
+    /// with `Op::WriteScopeApproval`.
+    WriteScopeApprovalRequest(WriteScopeApprovalRequestEvent),
+
+    /// An MCP server asked the user for structured input. Answer with
+    /// `Op::ResolveElicitation`; unanswered requests are declined after a
+    /// timeout.
+    ElicitationRequest(ElicitationRequestEvent),
+
+    /// The model asked the user a question with the `ask_user` tool. Answer
+    /// with `Op::UserInputAnswer`.
+    UserInputRequest(UserInputRequestEvent),
+
     /// Notification advising the user that something they are using has been
     /// deprecated and should be phased out.
     DeprecationNotice(DeprecationNoticeEvent),
@@ -528,19 +733,98 @@ pub enum EventMsg {
     /// Notification that a patch application has finished.
     PatchApplyEnd(PatchApplyEndEvent),
 
+    /// Diagnostics the configured language servers reported for the files a
+    /// patch changed. Files without diagnostics are listed with an empty
+    /// list, so clients can clear stale markers.
+    PatchDiagnostics(PatchDiagnosticsEvent),
+
+    /// A tool call is waiting for another tool call, possibly in another
+    /// conversation, to finish writing files it also writes.
+    FileLockWait(FileLockWaitEvent),
+
     TurnDiff(TurnDiffEvent),
 
     /// Response to GetHistoryEntryRequest.
     GetHistoryEntryResponse(GetHistoryEntryResponseEvent),
 
+    /// Response to `Op::GetPatchContent`.
+    PatchContentResponse(PatchContentResponseEvent),
+
     /// List of MCP tools available to the agent.
     McpListToolsResponse(McpListToolsResponseEvent),
 
     /// List of custom prompts available to the agent.
     ListCustomPromptsResponse(ListCustomPromptsResponseEvent),
 
+    /// Response to `Op::ListApprovalRules`.
+    ListApprovalRulesResponse(ListApprovalRulesResponseEvent),
+
+    /// Response to `Op::ListMcpPrompts`.
+    McpListPromptsResponse(McpListPromptsResponseEvent),
+
+    /// Response to `Op::ReadMcpResource`.
+    McpResourceReadResponse(McpResourceReadResponseEvent),
+
     PlanUpdate(UpdatePlanArgs),
 
+    /// The model proposed saving a user correction as a workspace
+    /// instruction. Nothing is saved until the client confirms it.
+    WorkspaceInstructionProposed(WorkspaceInstructionProposedEvent),
+
+    /// A command tried to reach a host outside the project's network allowlist
+    /// through the network proxy.
+    NetworkHostBlocked(NetworkHostBlockedEvent),
+
+    /// The conversation used up its token budget. New turns are refused
+    /// until the budget is raised with `Op::SetTokenBudget`.
+    BudgetExhausted(BudgetExhaustedEvent),
+
+    /// Conversation history was replaced with a summary to free up room in
+    /// the model's context window.
+    ContextCompacted(ContextCompactedEvent),
+
+    /// The model provider started failing repeatedly (the circuit breaker
+    /// opened) or recovered. While degraded, Codex keeps retrying with
+    /// growing pauses instead of failing turns.
+    ProviderHealthChanged(ProviderHealthChangedEvent),
+
+    /// The account is close to a rate limit; the next model request waits
+    /// until `resume_at` instead of running into 429s mid-turn.
+    RateLimited(RateLimitedEvent),
+
+    /// A background task started by `Op::RunBackground` started, exited, or
+    /// was terminated.
+    BackgroundTaskUpdate(BackgroundTaskUpdateEvent),
+
+    /// A child conversation started with the `spawn_agent` tool started or
+    /// finished.
+    SubAgentUpdate(SubAgentUpdateEvent),
+
+    /// Reply to `Op::WorktreeDiff`.
+    WorktreeDiff(WorktreeDiffEvent),
+
+    /// The isolated worktree was merged back or discarded.
+    WorktreeClosed(WorktreeClosedEvent),
+
+    /// A pull or merge request was opened by `Op::CreatePullRequest` or the
+    /// `create_pull_request` tool.
+    PullRequestCreated(PullRequestCreatedEvent),
+
+    /// The instruction files (AGENTS.md and their includes) changed between
+    /// turns; the new instructions were sent to the model.
+    InstructionsChanged(InstructionsChangedEvent),
+
+    /// The session switched profiles after `Op::SetProfile`.
+    ProfileChanged(ProfileChangedEvent),
+
+    /// The thread was renamed with `Op::SetThreadTitle`, or titled
+    /// automatically after its first exchange.
+    ThreadMetadataChanged(ThreadMetadataChangedEvent),
+
+    /// Secrets were replaced with placeholders in tool output before it was
+    /// sent to the model or recorded in the rollout.
+    SecretsRedacted(SecretsRedactedEvent),
+
     TurnAborted(TurnAbortedEvent),
 
     /// Notification that the agent is shutting down.
@@ -688,6 +972,151 @@ pub struct ErrorEvent {
     pub message: String,
     #[serde(default)]
     pub http_status_code: Option<u16>,
+    /// What went wrong, for clients that want to offer a remedy without
+    /// parsing `message`. Events recorded before this field existed read as
+    /// an internal error.
+    #[serde(default)]
+    pub info: CodexErrorInfo,
+}
+
+/// Structured description of an error reported by Codex.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct CodexErrorInfo {
+    pub category: ErrorCategory,
+    pub code: ErrorCode,
+    /// Whether submitting the same request again may succeed without the
+    /// user changing anything.
+    pub retryable: bool,
+    pub origin: ErrorOrigin,
+}
+
+impl CodexErrorInfo {
+    /// Info for `code`, with the category and retryability it usually has.
+    pub fn new(code: ErrorCode, origin: ErrorOrigin) -> Self {
+        Self {
+            category: code.category(),
+            code,
+            retryable: code.is_retryable(),
+            origin,
+        }
+    }
+
+    pub fn with_retryable(mut self, retryable: bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+}
+
+/// Broad kind of an error, which decides the remedy a client offers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// Credentials are missing, expired, or were rejected: sign in again.
+    Auth,
+    /// A rate limit, usage limit, quota, or token budget was reached.
+    Usage,
+    /// The conversation no longer fits in the model's context window.
+    ContextWindow,
+    /// The model provider or an MCP server could not be reached or failed
+    /// to answer.
+    Provider,
+    /// The request was rejected as invalid.
+    InvalidRequest,
+    /// The sandbox stopped a command or could not be set up.
+    Sandbox,
+    /// Configuration is invalid or incomplete.
+    Config,
+    /// The user interrupted the turn.
+    Interrupted,
+    /// Anything else, including bugs in Codex.
+    #[default]
+    Internal,
+}
+
+/// Stable identifier of a specific error.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    Unauthorized,
+    RefreshTokenFailed,
+    UsageLimitReached,
+    UsageNotIncluded,
+    QuotaExceeded,
+    TokenBudgetExhausted,
+    ContextWindowExceeded,
+    StreamDisconnected,
+    ConnectionFailed,
+    ServerOverloaded,
+    RetryLimitReached,
+    UnexpectedStatus,
+    BadRequest,
+    UnsupportedOperation,
+    ConversationNotFound,
+    SandboxDenied,
+    SandboxTimeout,
+    SandboxUnavailable,
+    MissingEnvVar,
+    InvalidConfig,
+    McpServerFailed,
+    Interrupted,
+    #[default]
+    Internal,
+}
+
+impl ErrorCode {
+    pub fn category(self) -> ErrorCategory {
+        match self {
+            ErrorCode::Unauthorized | ErrorCode::RefreshTokenFailed => ErrorCategory::Auth,
+            ErrorCode::UsageLimitReached
+            | ErrorCode::UsageNotIncluded
+            | ErrorCode::QuotaExceeded
+            | ErrorCode::TokenBudgetExhausted => ErrorCategory::Usage,
+            ErrorCode::ContextWindowExceeded => ErrorCategory::ContextWindow,
+            ErrorCode::StreamDisconnected
+            | ErrorCode::ConnectionFailed
+            | ErrorCode::ServerOverloaded
+            | ErrorCode::RetryLimitReached
+            | ErrorCode::UnexpectedStatus
+            | ErrorCode::McpServerFailed => ErrorCategory::Provider,
+            ErrorCode::BadRequest
+            | ErrorCode::UnsupportedOperation
+            | ErrorCode::ConversationNotFound => ErrorCategory::InvalidRequest,
+            ErrorCode::SandboxDenied
+            | ErrorCode::SandboxTimeout
+            | ErrorCode::SandboxUnavailable => ErrorCategory::Sandbox,
+            ErrorCode::MissingEnvVar | ErrorCode::InvalidConfig => ErrorCategory::Config,
+            ErrorCode::Interrupted => ErrorCategory::Interrupted,
+            ErrorCode::Internal => ErrorCategory::Internal,
+        }
+    }
+
+    /// Whether errors with this code are usually transient.
+    pub fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            ErrorCode::StreamDisconnected
+                | ErrorCode::ConnectionFailed
+                | ErrorCode::ServerOverloaded
+                | ErrorCode::RetryLimitReached
+        )
+    }
+}
+
+/// The component an error came from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorOrigin {
+    /// The model provider's API.
+    ModelProvider,
+    /// The sandbox around commands Codex runs.
+    Sandbox,
+    /// An MCP server.
+    McpServer,
+    /// Git or a forge (GitHub, GitLab).
+    Git,
+    /// Codex itself: configuration, local state, or a bug.
+    #[default]
+    Codex,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -698,6 +1127,77 @@ pub struct WarningEvent {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct TaskCompleteEvent {
     pub last_agent_message: Option<String>,
+    /// Commit of the turn's changes when `auto_commit` is enabled and the
+    /// turn changed files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turn_commit: Option<TurnCommit>,
+    /// Files changed during the turn, against the ghost snapshot taken when
+    /// it started. Unset when no snapshot was taken.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diff_summary: Option<TurnDiffSummary>,
+}
+
+/// A commit recording the changes made during one turn.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct TurnCommit {
+    pub commit: String,
+    pub branch: String,
+    pub message: String,
+}
+
+/// Diffstat of the changes made during one turn.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct TurnDiffSummary {
+    pub files_changed: usize,
+    pub insertions: u64,
+    pub deletions: u64,
+    pub files: Vec<TurnDiffFile>,
+}
+
+/// Lines added and removed in one file. Binary files have no line counts.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct TurnDiffFile {
+    /// Path relative to the repository root.
+    pub path: PathBuf,
+    pub insertions: u64,
+    pub deletions: u64,
+}
+
+impl TurnDiffSummary {
+    pub fn new(files: Vec<TurnDiffFile>) -> Self {
+        Self {
+            files_changed: files.len(),
+            insertions: files.iter().map(|file| file.insertions).sum(),
+            deletions: files.iter().map(|file| file.deletions).sum(),
+            files,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct TurnHeartbeatEvent {
+    /// Time since the turn started.
+    pub elapsed_ms: u64,
+    /// What the turn is doing right now.
+    pub activity: TurnActivity,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TurnActivity {
+    /// Waiting for, or streaming, the model's response.
+    #[default]
+    WaitingOnModel,
+    /// Running a shell command.
+    RunningCommand { command: String },
+    /// Calling a tool on an MCP server.
+    RunningTool { server: String, tool: String },
+    /// Applying a patch.
+    ApplyingPatch,
+    /// Waiting for the user to answer an approval request.
+    WaitingOnApproval,
+    /// Waiting for the user to answer a question from the model.
+    WaitingOnUser,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -705,7 +1205,7 @@ pub struct TaskStartedEvent {
     pub model_context_window: Option<i64>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default, JsonSchema, TS)]
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Default, JsonSchema, TS)]
 pub struct TokenUsage {
     #[ts(type = "number")]
     pub input_tokens: i64,
@@ -786,6 +1286,130 @@ impl TokenUsageInfo {
 pub struct TokenCountEvent {
     pub info: Option<TokenUsageInfo>,
     pub rate_limits: Option<RateLimitSnapshot>,
+    /// Estimated cost, present when a price is configured for the model.
+    #[serde(default)]
+    pub cost: Option<CostEstimate>,
+    /// What the conversation's tokens were spent on.
+    #[serde(default)]
+    pub breakdown: Option<TokenUsageBreakdown>,
+}
+
+/// One item a model request produced, used to attribute its token usage.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct UsageAttribution {
+    /// Call id for tool calls, otherwise the item id when the provider sent
+    /// one.
+    pub id: Option<String>,
+    /// Name of the tool called, or `message`, `reasoning`, `web_search`,
+    /// `compaction`, or `other`.
+    pub source: String,
+}
+
+impl UsageAttribution {
+    pub fn for_item(item: &ResponseItem) -> Self {
+        let (id, source) = match item {
+            ResponseItem::FunctionCall { name, call_id, .. }
+            | ResponseItem::CustomToolCall { name, call_id, .. } => {
+                (Some(call_id.clone()), name.as_str())
+            }
+            ResponseItem::LocalShellCall { id, call_id, .. } => {
+                (call_id.clone().or_else(|| id.clone()), "local_shell")
+            }
+            ResponseItem::Message { id, .. } => (id.clone(), "message"),
+            ResponseItem::Reasoning { id, .. } => {
+                (Some(id.clone()).filter(|id| !id.is_empty()), "reasoning")
+            }
+            ResponseItem::WebSearchCall { id, .. } => (id.clone(), "web_search"),
+            _ => (None, "other"),
+        };
+        Self {
+            id,
+            source: source.to_string(),
+        }
+    }
+}
+
+/// Token usage the provider reported for a single model request.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct RequestTokenUsage {
+    pub turn_id: String,
+    pub task: ModelTask,
+    pub model: String,
+    /// Failed attempts at this request before it went through; non-zero
+    /// for retries.
+    pub attempt: u32,
+    /// What the request produced, in output order.
+    pub items: Vec<UsageAttribution>,
+    pub usage: TokenUsage,
+}
+
+/// Conversation token usage split by what consumed it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+pub struct TokenUsageBreakdown {
+    /// Totals per item source. A request's usage is shared evenly among the
+    /// items it produced; requests that produced nothing count as `other`.
+    pub by_source: BTreeMap<String, TokenUsage>,
+    /// Total usage of requests that were retries.
+    pub retries: TokenUsage,
+    /// Number of requests recorded, which tells repeated token counts apart
+    /// from ones reporting a new `last_request`.
+    #[ts(type = "number")]
+    pub requests: u64,
+    /// The request reported most recently.
+    pub last_request: Option<RequestTokenUsage>,
+}
+
+impl TokenUsageBreakdown {
+    pub fn record(&mut self, request: RequestTokenUsage) {
+        let sources: Vec<&str> = if request.items.is_empty() {
+            vec!["other"]
+        } else {
+            request
+                .items
+                .iter()
+                .map(|item| item.source.as_str())
+                .collect()
+        };
+        let parts = i64::try_from(sources.len()).unwrap_or(i64::MAX);
+        for (index, source) in (0..).zip(sources) {
+            self.by_source
+                .entry(source.to_string())
+                .or_default()
+                .add_assign(&request.usage.share(index, parts));
+        }
+        if request.attempt > 0 {
+            self.retries.add_assign(&request.usage);
+        }
+        self.requests += 1;
+        self.last_request = Some(request);
+    }
+
+    /// Every request reported by a sequence of token count breakdowns, such
+    /// as those recorded in a rollout, oldest first.
+    pub fn requests_from<'a>(
+        breakdowns: impl IntoIterator<Item = &'a TokenUsageBreakdown>,
+    ) -> Vec<RequestTokenUsage> {
+        let mut seen = 0;
+        let mut requests = Vec::new();
+        for breakdown in breakdowns {
+            if breakdown.requests > seen
+                && let Some(request) = &breakdown.last_request
+            {
+                requests.push(request.clone());
+            }
+            seen = breakdown.requests;
+        }
+        requests
+    }
+}
+
+/// Estimated spend in USD, derived from the configured model prices.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize, JsonSchema, TS)]
+pub struct CostEstimate {
+    /// Cumulative cost of the conversation so far.
+    pub total_usd: f64,
+    /// Cost of the most recent model response.
+    pub last_usd: f64,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
@@ -862,6 +1486,19 @@ impl TokenUsage {
             .round() as i64
     }
 
+    /// Share `index` of `parts` near-equal shares. The shares of all indexes
+    /// add up to `self`.
+    pub fn share(&self, index: i64, parts: i64) -> TokenUsage {
+        let split = |value: i64| value / parts + i64::from(index < value % parts);
+        TokenUsage {
+            input_tokens: split(self.input_tokens),
+            cached_input_tokens: split(self.cached_input_tokens),
+            output_tokens: split(self.output_tokens),
+            reasoning_output_tokens: split(self.reasoning_output_tokens),
+            total_tokens: split(self.total_tokens),
+        }
+    }
+
     /// In-place element-wise sum of token counts.
     pub fn add_assign(&mut self, other: &TokenUsage) {
         self.input_tokens += other.input_tokens;
@@ -976,6 +1613,17 @@ pub struct McpToolCallBeginEvent {
     pub invocation: McpInvocation,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct McpToolCallProgressEvent {
+    /// Identifier of the McpToolCallBegin this progress belongs to.
+    pub call_id: String,
+    /// Progress so far. Increases with each update, even when `total` is
+    /// unknown.
+    pub progress: f64,
+    pub total: Option<f64>,
+    pub message: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
 pub struct McpToolCallEndEvent {
     /// Identifier for the corresponding McpToolCallBegin that finished.
@@ -1098,6 +1746,16 @@ pub struct SessionMeta {
     #[serde(default)]
     pub source: SessionSource,
     pub model_provider: Option<String>,
+    /// Rollout schema version the file was written with. Files from before
+    /// versioning omit it and read as 0.
+    #[serde(default, skip_serializing_if = "is_unversioned")]
+    pub schema_version: u32,
+}
+
+// serde's `skip_serializing_if` passes the field by reference.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_unversioned(schema_version: &u32) -> bool {
+    *schema_version == 0
 }
 
 impl Default for SessionMeta {
@@ -1111,6 +1769,7 @@ impl Default for SessionMeta {
             instructions: None,
             source: SessionSource::default(),
             model_provider: None,
+            schema_version: 0,
         }
     }
 }
@@ -1131,6 +1790,8 @@ pub enum RolloutItem {
     Compacted(CompactedItem),
     TurnContext(TurnContextItem),
     EventMsg(EventMsg),
+    ExecOutputArchive(ExecOutputArchiveItem),
+    ModelUsage(ModelUsageItem),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
@@ -1163,6 +1824,39 @@ pub struct TurnContextItem {
     pub summary: ReasoningSummaryConfig,
 }
 
+/// Points at the complete stdout/stderr archived for an exec tool call. The
+/// `ExecCommandEnd` event only carries truncated output, so this is what
+/// post-mortems should read.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, TS)]
+pub struct ExecOutputArchiveItem {
+    pub call_id: String,
+    /// Directory holding the compressed `stdout.gz` and `stderr.gz` files.
+    pub path: PathBuf,
+}
+
+/// The kind of request a model call was made for.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelTask {
+    Main,
+    Summarization,
+    RiskAssessment,
+    CommitMessage,
+    ThreadTitle,
+}
+
+/// Records which model served a request whose model is not the one in the
+/// preceding `TurnContext` item: an auxiliary request, or a turn served by a
+/// fallback.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, JsonSchema, TS)]
+pub struct ModelUsageItem {
+    pub task: ModelTask,
+    pub model: String,
+    /// Models tried first that were unavailable.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unavailable: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, JsonSchema)]
 pub struct RolloutLine {
     pub timestamp: String,
@@ -1220,6 +1914,10 @@ pub struct ReviewFinding {
     pub confidence_score: f32,
     pub priority: i32,
     pub code_location: ReviewCodeLocation,
+    /// Replacement text for the lines in `code_location`, when the fix is
+    /// small enough to suggest inline.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggestion: Option<String>,
 }
 
 /// Location of the code related to a review finding.
@@ -1314,7 +2012,7 @@ pub struct ViewImageToolCallEvent {
     pub path: PathBuf,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecOutputStream {
     Stdout,
@@ -1367,6 +2065,9 @@ pub struct StreamErrorEvent {
     pub message: String,
     #[serde(default)]
     pub http_status_code: Option<u16>,
+    /// The error that interrupted the stream.
+    #[serde(default)]
+    pub info: CodexErrorInfo,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1396,6 +2097,58 @@ pub struct PatchApplyEndEvent {
     pub success: bool,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct PatchDiagnosticsEvent {
+    /// Identifier of the patch's PatchApplyBegin/PatchApplyEnd.
+    pub call_id: String,
+    pub files: Vec<FileDiagnostics>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct FileDiagnostics {
+    pub path: PathBuf,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// A problem a language server found in a file. Lines and columns are
+/// 1-based; columns count UTF-16 code units, as in the Language Server
+/// Protocol.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    pub line: u32,
+    pub column: u32,
+    pub end_line: u32,
+    pub end_column: u32,
+    pub message: String,
+    /// The tool that produced the diagnostic, e.g. `rustc` or `ts`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+impl fmt::Display for DiagnosticSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            DiagnosticSeverity::Error => "error",
+            DiagnosticSeverity::Warning => "warning",
+            DiagnosticSeverity::Information => "info",
+            DiagnosticSeverity::Hint => "hint",
+        };
+        f.write_str(text)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct TurnDiffEvent {
     pub unified_diff: String,
@@ -1410,6 +2163,254 @@ pub struct GetHistoryEntryResponseEvent {
     pub entry: Option<HistoryEntry>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PatchContentResponseEvent {
+    pub call_id: String,
+    pub path: PathBuf,
+    /// The requested change, or `None` when the approval is no longer pending
+    /// or the path was not part of the patch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub change: Option<FileChange>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct WorkspaceInstructionProposedEvent {
+    pub call_id: String,
+    /// Instruction text as it would be saved, e.g. "never touch generated/ files".
+    pub instruction: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct NetworkHostBlockedEvent {
+    pub host: String,
+    pub port: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct FileLockWaitEvent {
+    /// Identifier of the blocked tool call.
+    pub call_id: String,
+    /// Files held by other tool calls that this call is waiting for.
+    pub paths: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct BudgetExhaustedEvent {
+    /// Configured token budget for the conversation.
+    pub budget: i64,
+    /// Tokens (input + output) used by the conversation so far.
+    pub used: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+pub struct RateLimitedEvent {
+    /// Unix timestamp (seconds) at which the turn resumes.
+    #[ts(type = "number")]
+    pub resume_at: i64,
+    /// The exhausted window: `primary` or `secondary`.
+    pub window: String,
+    /// Percentage (0-100) of the window that has been consumed.
+    pub used_percent: f64,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderHealthStatus {
+    Healthy,
+    Degraded,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ProviderHealthChangedEvent {
+    /// Display name of the model provider.
+    pub provider: String,
+    pub status: ProviderHealthStatus,
+    /// The error that opened the circuit breaker, when degraded.
+    pub message: Option<String>,
+    /// Milliseconds until the provider is tried again, when degraded.
+    #[ts(type = "number | null")]
+    pub retry_in_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundTaskStatus {
+    Running,
+    /// The command exited on its own.
+    Exited,
+    /// The command was stopped by `Op::TerminateBackground` or on shutdown.
+    Terminated,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct BackgroundTaskUpdateEvent {
+    pub task_id: String,
+    pub command: String,
+    pub status: BackgroundTaskStatus,
+    /// Set once the command has exited on its own.
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SubAgentStatus {
+    Running,
+    Completed,
+    /// The child errored, was interrupted, or ended without finishing.
+    Failed,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct SubAgentUpdateEvent {
+    /// Id of the `spawn_agent` tool call that started the child.
+    pub call_id: String,
+    /// Id of the child conversation; its parent is the conversation that
+    /// emitted this event.
+    pub conversation_id: ConversationId,
+    pub model: String,
+    pub status: SubAgentStatus,
+    /// The child's final message once it completed, or why it failed.
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct WorktreeDiffEvent {
+    pub worktree_path: PathBuf,
+    pub branch: String,
+    /// Commit of the primary checkout the worktree was created from.
+    pub base_commit: String,
+    /// Changes since `base_commit`, including untracked files.
+    pub unified_diff: String,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum WorktreeOutcome {
+    Merged,
+    Discarded,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct WorktreeClosedEvent {
+    pub outcome: WorktreeOutcome,
+    /// Files in the primary checkout left with conflict markers by the merge.
+    pub conflicted_paths: Vec<String>,
+    /// Where the conversation continues.
+    pub cwd: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ForgeProvider {
+    Github,
+    Gitlab,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct PullRequestCreatedEvent {
+    pub provider: ForgeProvider,
+    /// Web URL of the pull/merge request.
+    pub url: String,
+    /// Pull request number (GitHub) or merge request IID (GitLab).
+    pub number: i64,
+    pub title: String,
+    /// Branch that was pushed.
+    pub head: String,
+    /// Branch the request targets.
+    pub base: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct InstructionsChangedEvent {
+    /// Instruction files that make up the new instructions, including
+    /// `@include`d ones.
+    pub sources: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ProfileChangedEvent {
+    pub profile: String,
+    pub model: String,
+    pub model_provider_id: String,
+    pub reasoning_effort: Option<ReasoningEffortConfig>,
+    pub approval_policy: AskForApproval,
+    pub sandbox_policy: SandboxPolicy,
+    /// Settings whose effective value changed, in a fixed order. Empty when
+    /// the profile matches the current settings.
+    pub changes: Vec<SettingChange>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ThreadMetadataChangedEvent {
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct SettingChange {
+    /// Setting name as written in `config.toml`, e.g. `sandbox_mode`.
+    pub setting: String,
+    pub previous: String,
+    pub current: String,
+}
+
+/// Kind of secret matched by the redaction pipeline.
+#[derive(
+    Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, PartialOrd, Ord, Hash, JsonSchema, TS,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretKind {
+    AwsAccessKey,
+    AwsSecretKey,
+    PrivateKey,
+    Jwt,
+    /// Value of a `.env`-style assignment whose name looks like a credential,
+    /// e.g. `DATABASE_PASSWORD=...`.
+    EnvValue,
+}
+
+impl fmt::Display for SecretKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            SecretKind::AwsAccessKey => "AWS access key",
+            SecretKind::AwsSecretKey => "AWS secret key",
+            SecretKind::PrivateKey => "private key",
+            SecretKind::Jwt => "JWT",
+            SecretKind::EnvValue => "credential value",
+        };
+        f.write_str(text)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct SecretsRedactedEvent {
+    /// Number of values replaced, one entry per kind that matched.
+    pub counts: Vec<RedactedSecretCount>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct RedactedSecretCount {
+    pub kind: SecretKind,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum CompactionTrigger {
+    /// Requested by the user with `Op::Compact`.
+    Manual,
+    /// Started because context window usage crossed the auto-compact limit.
+    Auto,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ContextCompactedEvent {
+    pub trigger: CompactionTrigger,
+    /// Tokens in the context window before compaction, if known.
+    pub tokens_before: Option<i64>,
+    /// Estimated tokens in the context window after compaction.
+    pub tokens_after: Option<i64>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct McpListToolsResponseEvent {
     /// Fully qualified tool name -> tool definition.
@@ -1422,6 +2423,62 @@ pub struct McpListToolsResponseEvent {
     pub auth_statuses: std::collections::HashMap<String, McpAuthStatus>,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ElicitationRequestEvent {
+    pub server_name: String,
+    pub id: McpRequestId,
+    pub message: String,
+    pub requested_schema: ElicitRequestParamsRequestedSchema,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct UserInputRequestEvent {
+    /// Id of the `ask_user` call; echo it in `Op::UserInputAnswer`.
+    pub call_id: String,
+    /// Turn ID that this request belongs to.
+    pub turn_id: String,
+    /// The question for the user.
+    pub question: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(rename_all = "lowercase")]
+pub enum ElicitationAction {
+    /// Provide the requested `content`.
+    Accept,
+    /// Refuse to provide the information; the server may continue without it.
+    Decline,
+    /// Abandon the operation that needed the information.
+    Cancel,
+}
+
+impl fmt::Display for ElicitationAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElicitationAction::Accept => f.write_str("accept"),
+            ElicitationAction::Decline => f.write_str("decline"),
+            ElicitationAction::Cancel => f.write_str("cancel"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct McpListPromptsResponseEvent {
+    /// Known prompts grouped by server name.
+    pub prompts: HashMap<String, Vec<McpPrompt>>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct McpResourceReadResponseEvent {
+    pub server: String,
+    pub uri: String,
+    pub contents: Vec<McpResourceContents>,
+    /// True when the resource exceeded the size limit and `contents` were
+    /// shortened or partially dropped.
+    pub truncated: bool,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct McpStartupUpdateEvent {
     /// Server name being started.
@@ -1445,6 +2502,9 @@ pub struct McpStartupCompleteEvent {
     pub ready: Vec<String>,
     pub failed: Vec<McpStartupFailure>,
     pub cancelled: Vec<String>,
+    /// Lazily started servers that will start on first use.
+    #[serde(default)]
+    pub deferred: Vec<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -1453,6 +2513,73 @@ pub struct McpStartupFailure {
     pub error: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ToolsChangedEvent {
+    /// Server name whose tools changed.
+    pub server: String,
+    /// Tool names, as the server reports them, that are new.
+    pub added: Vec<String>,
+    /// Tool names that the server no longer offers.
+    pub removed: Vec<String>,
+    /// Tool names whose description or schema changed.
+    pub updated: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct McpToolConflictsEvent {
+    pub conflicts: Vec<McpToolConflict>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct McpToolConflict {
+    /// The name, as the model sees it, that more than one tool resolved to.
+    pub name: String,
+    /// The tool offered under `name`: the one whose server, then tool name,
+    /// sorts first.
+    pub kept: McpToolRef,
+    /// Tools left out because `name` was already taken.
+    pub dropped: Vec<McpToolRef>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct McpToolRef {
+    pub server: String,
+    /// The name the server reports for the tool.
+    pub tool: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct McpServerStatusChangedEvent {
+    /// Server name whose status changed.
+    pub server: String,
+    pub status: McpServerStatus,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case", tag = "state")]
+#[ts(rename_all = "snake_case", tag = "state")]
+pub enum McpServerStatus {
+    /// The server answers pings again after previous failures.
+    Healthy,
+    /// The server missed `consecutive_failures` pings in a row.
+    Unhealthy {
+        consecutive_failures: i32,
+        error: String,
+    },
+    /// The server is being restarted; `attempt` starts at 1.
+    Restarting { attempt: i32 },
+    /// The server could not be started on demand, or supervision gave up on
+    /// it after repeated restarts.
+    Failed { error: String },
+    /// A server that was not running is starting for a request.
+    Starting,
+    /// A server started on demand is ready for requests.
+    Ready,
+    /// The server was stopped after `idle_secs` seconds without requests.
+    /// It starts again on next use.
+    Stopped { idle_secs: u64 },
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 #[ts(rename_all = "snake_case")]
@@ -1481,6 +2608,12 @@ pub struct ListCustomPromptsResponseEvent {
     pub custom_prompts: Vec<CustomPrompt>,
 }
 
+/// Response payload for `Op::ListApprovalRules`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ListApprovalRulesResponseEvent {
+    pub rules: Vec<ApprovalRule>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct SessionConfiguredEvent {
     /// Name left as session_id instead of conversation_id for backwards compatibility.
@@ -1590,6 +2723,91 @@ mod tests {
     use serde_json::json;
     use tempfile::NamedTempFile;
 
+    fn usage(input: i64, output: i64) -> TokenUsage {
+        TokenUsage {
+            input_tokens: input,
+            output_tokens: output,
+            total_tokens: input + output,
+            ..Default::default()
+        }
+    }
+
+    fn attribution(source: &str) -> UsageAttribution {
+        UsageAttribution {
+            id: None,
+            source: source.to_string(),
+        }
+    }
+
+    #[test]
+    fn breakdown_shares_request_usage_among_its_items() {
+        let mut breakdown = TokenUsageBreakdown::default();
+        breakdown.record(RequestTokenUsage {
+            turn_id: "turn-1".into(),
+            task: ModelTask::Main,
+            model: "gpt-5".into(),
+            attempt: 0,
+            items: vec![
+                attribution("reasoning"),
+                attribution("shell"),
+                attribution("shell"),
+            ],
+            usage: usage(90, 12),
+        });
+        breakdown.record(RequestTokenUsage {
+            turn_id: "turn-1".into(),
+            task: ModelTask::Main,
+            model: "gpt-5".into(),
+            attempt: 1,
+            items: Vec::new(),
+            usage: usage(40, 0),
+        });
+
+        assert_eq!(breakdown.by_source["reasoning"], usage(30, 4));
+        assert_eq!(breakdown.by_source["shell"], usage(60, 8));
+        assert_eq!(breakdown.by_source["other"], usage(40, 0));
+        assert_eq!(breakdown.retries, usage(40, 0));
+        assert_eq!(breakdown.requests, 2);
+        assert_eq!(
+            breakdown
+                .last_request
+                .as_ref()
+                .map(|request| request.attempt),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn requests_from_skips_repeated_breakdowns() {
+        let request = |turn_id: &str| RequestTokenUsage {
+            turn_id: turn_id.into(),
+            task: ModelTask::Main,
+            model: "gpt-5".into(),
+            attempt: 0,
+            items: vec![attribution("message")],
+            usage: usage(10, 1),
+        };
+        let mut first = TokenUsageBreakdown::default();
+        first.record(request("turn-1"));
+        let mut second = first.clone();
+        second.record(request("turn-2"));
+
+        let requests = TokenUsageBreakdown::requests_from([&first, &first, &second]);
+        assert_eq!(requests, vec![request("turn-1"), request("turn-2")]);
+    }
+
+    #[test]
+    fn token_usage_shares_add_up_to_the_whole() {
+        let whole = usage(100, 11);
+        let mut sum = TokenUsage::default();
+        for index in 0..3 {
+            sum.add_assign(&whole.share(index, 3));
+        }
+        assert_eq!(sum, whole);
+        assert_eq!(whole.share(0, 3).input_tokens, 34);
+        assert_eq!(whole.share(2, 3).input_tokens, 33);
+    }
+
     #[test]
     fn item_started_event_from_web_search_emits_begin_event() {
         let event = ItemStartedEvent {
@@ -1714,6 +2932,7 @@ mod tests {
                     error: "bad".to_string(),
                 }],
                 cancelled: vec!["c".to_string()],
+                deferred: Vec::new(),
             }),
         };
 
@@ -1723,6 +2942,7 @@ mod tests {
         assert_eq!(value["msg"]["failed"][0]["server"], "b");
         assert_eq!(value["msg"]["failed"][0]["error"], "bad");
         assert_eq!(value["msg"]["cancelled"][0], "c");
+        assert_eq!(value["msg"]["deferred"], serde_json::json!([]));
         Ok(())
     }
 }
@@ -21,4 +21,10 @@ pub enum UserInput {
     LocalImage {
         path: std::path::PathBuf,
     },
+
+    /// Local audio file provided by the user. Core replaces it with a
+    /// transcript before the input reaches the model.
+    LocalAudio {
+        path: std::path::PathBuf,
+    },
 }
@@ -8,6 +8,7 @@ use reqwest::Client;
 use reqwest::StatusCode;
 use reqwest::Url;
 use reqwest::header::HeaderMap;
+use reqwest::header::WWW_AUTHENTICATE;
 use serde::Deserialize;
 use tracing::debug;
 
@@ -58,12 +59,46 @@ pub async fn supports_oauth_login(url: &str) -> Result<bool> {
 
 async fn supports_oauth_login_with_headers(url: &str, default_headers: &HeaderMap) -> Result<bool> {
     let base_url = Url::parse(url)?;
-    let builder = Client::builder().timeout(DISCOVERY_TIMEOUT);
+    let builder = codex_utils_http_client::builder().timeout(DISCOVERY_TIMEOUT);
     let client = apply_default_headers(builder, default_headers).build()?;
 
+    if discover_authorization_server(&client, &base_url).await {
+        return Ok(true);
+    }
+
+    let Some((metadata_url, metadata)) =
+        discover_protected_resource_metadata(&client, &base_url).await
+    else {
+        return Ok(false);
+    };
+
+    for candidate in metadata.authorization_servers {
+        let candidate = candidate.trim();
+        if candidate.is_empty() {
+            continue;
+        }
+        let issuer = match Url::parse(candidate).or_else(|_| metadata_url.join(candidate)) {
+            Ok(issuer) => issuer,
+            Err(err) => {
+                debug!("failed to resolve authorization server `{candidate}` for {url}: {err}");
+                continue;
+            }
+        };
+        if discover_authorization_server(&client, &issuer).await {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Probes the RFC 8414 well-known locations derived from `base_url` and reports whether any of
+/// them advertises both an authorization and a token endpoint.
+async fn discover_authorization_server(client: &Client, base_url: &Url) -> bool {
     let mut last_error: Option<Error> = None;
     for candidate_path in discovery_paths(base_url.path()) {
         let mut discovery_url = base_url.clone();
+        discovery_url.set_query(None);
         discovery_url.set_path(&candidate_path);
 
         let response = match client
@@ -92,15 +127,127 @@ async fn supports_oauth_login_with_headers(url: &str, default_headers: &HeaderMa
         };
 
         if metadata.authorization_endpoint.is_some() && metadata.token_endpoint.is_some() {
-            return Ok(true);
+            return true;
         }
     }
 
     if let Some(err) = last_error {
-        debug!("OAuth discovery requests failed for {url}: {err:?}");
+        debug!("OAuth discovery requests failed for {base_url}: {err:?}");
     }
 
-    Ok(false)
+    false
+}
+
+/// Locates the RFC 9728 protected resource metadata for `base_url`. The `resource_metadata`
+/// parameter of a `WWW-Authenticate` challenge on the server URL takes precedence over the
+/// well-known locations.
+async fn discover_protected_resource_metadata(
+    client: &Client,
+    base_url: &Url,
+) -> Option<(Url, ProtectedResourceMetadata)> {
+    let mut candidates = Vec::new();
+    match client
+        .get(base_url.clone())
+        .header(OAUTH_DISCOVERY_HEADER, OAUTH_DISCOVERY_VERSION)
+        .send()
+        .await
+    {
+        Ok(response) if response.status() == StatusCode::UNAUTHORIZED => {
+            let challenged = response
+                .headers()
+                .get_all(WWW_AUTHENTICATE)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .filter_map(resource_metadata_from_challenge)
+                .find_map(|value| base_url.join(&value).ok());
+            candidates.extend(challenged);
+        }
+        Ok(_) => {}
+        Err(err) => debug!("protected resource challenge probe failed for {base_url}: {err:?}"),
+    }
+
+    for candidate_path in protected_resource_paths(base_url.path()) {
+        let mut discovery_url = base_url.clone();
+        discovery_url.set_query(None);
+        discovery_url.set_path(&candidate_path);
+        candidates.push(discovery_url);
+    }
+
+    for metadata_url in candidates {
+        let response = match client
+            .get(metadata_url.clone())
+            .header(OAUTH_DISCOVERY_HEADER, OAUTH_DISCOVERY_VERSION)
+            .send()
+            .await
+        {
+            Ok(response) if response.status() == StatusCode::OK => response,
+            Ok(_) => continue,
+            Err(err) => {
+                debug!("protected resource metadata request to {metadata_url} failed: {err:?}");
+                continue;
+            }
+        };
+
+        match response.json::<ProtectedResourceMetadata>().await {
+            Ok(metadata) => return Some((metadata_url, metadata)),
+            Err(err) => {
+                debug!("invalid protected resource metadata at {metadata_url}: {err:?}");
+            }
+        }
+    }
+
+    None
+}
+
+/// Extracts the `resource_metadata` auth-param from a `WWW-Authenticate` header value.
+/// https://datatracker.ietf.org/doc/html/rfc9728#section-5.1
+fn resource_metadata_from_challenge(header: &str) -> Option<String> {
+    let mut rest = header.trim();
+    let (scheme, params) = rest.split_once(char::is_whitespace)?;
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+    rest = params;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        let (name, after_name) = rest.split_once('=')?;
+        let after_name = after_name.trim_start();
+        let (value, remainder) = if let Some(quoted) = after_name.strip_prefix('"') {
+            let mut value = String::new();
+            let mut chars = quoted.char_indices();
+            let mut end = None;
+            while let Some((idx, ch)) = chars.next() {
+                match ch {
+                    '\\' => {
+                        if let Some((_, escaped)) = chars.next() {
+                            value.push(escaped);
+                        }
+                    }
+                    '"' => {
+                        end = Some(idx + 1);
+                        break;
+                    }
+                    _ => value.push(ch),
+                }
+            }
+            (value, &quoted[end?..])
+        } else {
+            let end = after_name.find(',').unwrap_or(after_name.len());
+            (after_name[..end].trim().to_string(), &after_name[end..])
+        };
+
+        if name.trim().eq_ignore_ascii_case("resource_metadata") {
+            return Some(value);
+        }
+        rest = remainder;
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ProtectedResourceMetadata {
+    #[serde(default)]
+    authorization_servers: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -136,3 +283,141 @@ fn discovery_paths(base_path: &str) -> Vec<String> {
 
     candidates
 }
+
+/// Implements RFC 9728 section 3.1: the well-known suffix is inserted between the host and the
+/// path of the protected resource, with the root location as a fallback.
+/// https://datatracker.ietf.org/doc/html/rfc9728#section-3.1
+fn protected_resource_paths(base_path: &str) -> Vec<String> {
+    let trimmed = base_path.trim_start_matches('/').trim_end_matches('/');
+    let canonical = "/.well-known/oauth-protected-resource".to_string();
+
+    if trimmed.is_empty() {
+        vec![canonical]
+    } else {
+        vec![format!("{canonical}/{trimmed}"), canonical]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Router;
+    use axum::http::HeaderValue;
+    use axum::response::IntoResponse;
+    use axum::routing::get;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn resource_metadata_from_challenge_reads_quoted_and_token_values() {
+        assert_eq!(
+            resource_metadata_from_challenge(
+                r#"Bearer error="invalid_token", resource_metadata="https://example.com/.well-known/oauth-protected-resource""#
+            ),
+            Some("https://example.com/.well-known/oauth-protected-resource".to_string())
+        );
+        assert_eq!(
+            resource_metadata_from_challenge("bearer resource_metadata=/prm, scope=mcp"),
+            Some("/prm".to_string())
+        );
+        assert_eq!(
+            resource_metadata_from_challenge(r#"Basic realm="mcp", resource_metadata="/prm""#),
+            None
+        );
+        assert_eq!(resource_metadata_from_challenge("Bearer"), None);
+    }
+
+    #[test]
+    fn protected_resource_paths_insert_well_known_before_path() {
+        assert_eq!(
+            protected_resource_paths("/mcp/"),
+            vec![
+                "/.well-known/oauth-protected-resource/mcp".to_string(),
+                "/.well-known/oauth-protected-resource".to_string(),
+            ]
+        );
+        assert_eq!(
+            protected_resource_paths("/"),
+            vec!["/.well-known/oauth-protected-resource".to_string()]
+        );
+    }
+
+    async fn serve(router: Router) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, router).await;
+        });
+        format!("http://{addr}")
+    }
+
+    fn authorization_server_router() -> Router {
+        Router::new().route(
+            "/.well-known/oauth-authorization-server/issuer",
+            get(|| async {
+                json!({
+                    "authorization_endpoint": "https://auth.example.com/authorize",
+                    "token_endpoint": "https://auth.example.com/token",
+                })
+                .to_string()
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn supports_oauth_login_follows_www_authenticate_challenge() {
+        let router = authorization_server_router()
+            .route(
+                "/mcp",
+                get(|| async {
+                    let mut response = StatusCode::UNAUTHORIZED.into_response();
+                    response.headers_mut().insert(
+                        WWW_AUTHENTICATE,
+                        HeaderValue::from_static(r#"Bearer resource_metadata="/custom-prm""#),
+                    );
+                    response
+                }),
+            )
+            .route(
+                "/custom-prm",
+                get(|| async { json!({ "authorization_servers": ["/issuer"] }).to_string() }),
+            );
+        let base = serve(router).await;
+
+        assert_eq!(
+            supports_oauth_login(&format!("{base}/mcp"))
+                .await
+                .expect("discovery"),
+            true
+        );
+    }
+
+    #[tokio::test]
+    async fn supports_oauth_login_falls_back_to_well_known_protected_resource() {
+        let router = authorization_server_router().route(
+            "/.well-known/oauth-protected-resource/mcp",
+            get(|| async { json!({ "authorization_servers": ["/issuer"] }).to_string() }),
+        );
+        let base = serve(router).await;
+
+        assert_eq!(
+            supports_oauth_login(&format!("{base}/mcp"))
+                .await
+                .expect("discovery"),
+            true
+        );
+    }
+
+    #[tokio::test]
+    async fn supports_oauth_login_is_false_without_metadata() {
+        let base = serve(Router::new()).await;
+
+        assert_eq!(
+            supports_oauth_login(&format!("{base}/mcp"))
+                .await
+                .expect("discovery"),
+            false
+        );
+    }
+}
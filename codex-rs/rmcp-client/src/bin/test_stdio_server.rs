@@ -12,6 +12,7 @@ use rmcp::model::ListResourceTemplatesResult;
 use rmcp::model::ListResourcesResult;
 use rmcp::model::ListToolsResult;
 use rmcp::model::PaginatedRequestParam;
+use rmcp::model::ProgressNotificationParam;
 use rmcp::model::RawResource;
 use rmcp::model::RawResourceTemplate;
 use rmcp::model::ReadResourceRequestParam;
@@ -40,7 +41,7 @@ pub fn stdio() -> (tokio::io::Stdin, tokio::io::Stdout) {
 }
 impl TestToolServer {
     fn new() -> Self {
-        let tools = vec![Self::echo_tool(), Self::image_tool()];
+        let tools = vec![Self::echo_tool(), Self::image_tool(), Self::progress_tool()];
         let resources = vec![Self::memo_resource()];
         let resource_templates = vec![Self::memo_template()];
         Self {
@@ -86,6 +87,22 @@ impl TestToolServer {
         )
     }
 
+    fn progress_tool() -> Tool {
+        #[expect(clippy::expect_used)]
+        let schema: JsonObject = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {},
+            "additionalProperties": false
+        }))
+        .expect("progress tool schema should deserialize");
+
+        Tool::new(
+            Cow::Borrowed("progress"),
+            Cow::Borrowed("Report two progress notifications before returning."),
+            Arc::new(schema),
+        )
+    }
+
     fn memo_resource() -> Resource {
         let raw = RawResource {
             uri: MEMO_URI.to_string(),
@@ -200,9 +217,31 @@ impl ServerHandler for TestToolServer {
     async fn call_tool(
         &self,
         request: CallToolRequestParam,
-        _context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+        context: rmcp::service::RequestContext<rmcp::service::RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         match request.name.as_ref() {
+            "progress" => {
+                if let Some(progress_token) = context.meta.get_progress_token() {
+                    for (progress, message) in [(1.0, "halfway"), (2.0, "done")] {
+                        context
+                            .peer
+                            .notify_progress(ProgressNotificationParam {
+                                progress_token: progress_token.clone(),
+                                progress,
+                                total: Some(2.0),
+                                message: Some(message.to_string()),
+                            })
+                            .await
+                            .map_err(|err| McpError::internal_error(err.to_string(), None))?;
+                    }
+                    // Clients handle notifications on separate tasks; give them a
+                    // moment to land before the response completes the call.
+                    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                }
+                Ok(CallToolResult::success(vec![rmcp::model::Content::text(
+                    "finished",
+                )]))
+            }
             "echo" => {
                 let args: EchoArgs = match request.arguments {
                     Some(arguments) => serde_json::from_value(serde_json::Value::Object(
@@ -10,11 +10,16 @@ mod utils;
 pub use auth_status::determine_streamable_http_auth_status;
 pub use auth_status::supports_oauth_login;
 pub use codex_protocol::protocol::McpAuthStatus;
+pub use logging_client_handler::McpProgress;
 pub use oauth::OAuthCredentialsStoreMode;
+pub use oauth::OAuthTokenInfo;
 pub use oauth::StoredOAuthTokens;
 pub use oauth::WrappedOAuthTokenResponse;
 pub use oauth::delete_oauth_tokens;
-pub(crate) use oauth::load_oauth_tokens;
+pub use oauth::load_oauth_tokens;
+pub use oauth::read_oauth_token_info;
 pub use oauth::save_oauth_tokens;
 pub use perform_oauth_login::perform_oauth_login;
 pub use rmcp_client::RmcpClient;
+pub use rmcp_client::SendElicitation;
+pub use rmcp_client::SendSamplingRequest;
@@ -1,46 +1,284 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::sync::PoisonError;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+
+use mcp_types::Root;
 use rmcp::ClientHandler;
 use rmcp::RoleClient;
 use rmcp::model::CancelledNotificationParam;
 use rmcp::model::ClientInfo;
 use rmcp::model::CreateElicitationRequestParam;
 use rmcp::model::CreateElicitationResult;
+use rmcp::model::CreateMessageRequestParam;
+use rmcp::model::CreateMessageResult;
 use rmcp::model::ElicitationAction;
+use rmcp::model::ErrorCode;
+use rmcp::model::ListRootsResult;
 use rmcp::model::LoggingLevel;
 use rmcp::model::LoggingMessageNotificationParam;
+use rmcp::model::NumberOrString;
 use rmcp::model::ProgressNotificationParam;
+use rmcp::model::ProgressToken;
 use rmcp::model::ResourceUpdatedNotificationParam;
 use rmcp::service::NotificationContext;
 use rmcp::service::RequestContext;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::watch;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
 use tracing::warn;
 
-#[derive(Debug, Clone)]
+use crate::rmcp_client::SendElicitation;
+use crate::rmcp_client::SendSamplingRequest;
+use crate::utils::convert_to_mcp;
+use crate::utils::convert_to_rmcp;
+
+/// Counts `list_changed` notifications so callers can tell when a cached
+/// resource or prompt list is stale. Tool list changes are also pushed to
+/// subscribers, since the tool list is refreshed as soon as it changes.
+#[derive(Debug)]
+pub(crate) struct ListChangeCounters {
+    resources: AtomicI64,
+    prompts: AtomicI64,
+    tools: watch::Sender<i64>,
+}
+
+impl Default for ListChangeCounters {
+    fn default() -> Self {
+        Self {
+            resources: AtomicI64::default(),
+            prompts: AtomicI64::default(),
+            tools: watch::Sender::new(0),
+        }
+    }
+}
+
+impl ListChangeCounters {
+    pub(crate) fn subscribe_tools(&self) -> watch::Receiver<i64> {
+        self.tools.subscribe()
+    }
+
+    pub(crate) fn resources(&self) -> i64 {
+        self.resources.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn prompts(&self) -> i64 {
+        self.prompts.load(Ordering::Acquire)
+    }
+}
+
+/// Progress reported by a server for an in-flight request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct McpProgress {
+    pub progress: f64,
+    pub total: Option<f64>,
+    pub message: Option<String>,
+}
+
+/// Routes `notifications/progress` to the caller that issued the request
+/// owning the progress token.
+#[derive(Debug, Default)]
+pub(crate) struct ProgressSubscribers {
+    next_token: AtomicI64,
+    senders: StdMutex<HashMap<ProgressToken, UnboundedSender<McpProgress>>>,
+}
+
+impl ProgressSubscribers {
+    /// Allocates a fresh progress token whose notifications go to `sender`
+    /// until the returned subscription is dropped.
+    pub(crate) fn subscribe(
+        self: &Arc<Self>,
+        sender: UnboundedSender<McpProgress>,
+    ) -> ProgressSubscription {
+        let id = self.next_token.fetch_add(1, Ordering::Relaxed);
+        let token = ProgressToken(NumberOrString::String(format!("codex-{id}").into()));
+        self.senders
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(token.clone(), sender);
+        ProgressSubscription {
+            token,
+            subscribers: Arc::clone(self),
+        }
+    }
+
+    fn notify(&self, params: ProgressNotificationParam) -> bool {
+        let senders = self.senders.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(sender) = senders.get(&params.progress_token) else {
+            return false;
+        };
+        let _ = sender.send(McpProgress {
+            progress: params.progress,
+            total: params.total,
+            message: params.message,
+        });
+        true
+    }
+}
+
+pub(crate) struct ProgressSubscription {
+    token: ProgressToken,
+    subscribers: Arc<ProgressSubscribers>,
+}
+
+impl ProgressSubscription {
+    pub(crate) fn token(&self) -> ProgressToken {
+        self.token.clone()
+    }
+}
+
+impl Drop for ProgressSubscription {
+    fn drop(&mut self) {
+        self.subscribers
+            .senders
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&self.token);
+    }
+}
+
+#[derive(Clone)]
 pub(crate) struct LoggingClientHandler {
     client_info: ClientInfo,
+    list_changes: Arc<ListChangeCounters>,
+    progress: Arc<ProgressSubscribers>,
+    send_elicitation: Arc<SendElicitation>,
+    send_sampling: Option<Arc<SendSamplingRequest>>,
+    roots: Option<watch::Receiver<Vec<Root>>>,
+}
+
+impl fmt::Debug for LoggingClientHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LoggingClientHandler")
+            .field("client_info", &self.client_info)
+            .field("list_changes", &self.list_changes)
+            .field("progress", &self.progress)
+            .field("sampling", &self.send_sampling.is_some())
+            .field(
+                "roots",
+                &self.roots.as_ref().map(|roots| roots.borrow().clone()),
+            )
+            .finish_non_exhaustive()
+    }
 }
 
 impl LoggingClientHandler {
-    pub(crate) fn new(client_info: ClientInfo) -> Self {
-        Self { client_info }
+    pub(crate) fn new(
+        client_info: ClientInfo,
+        list_changes: Arc<ListChangeCounters>,
+        progress: Arc<ProgressSubscribers>,
+        send_elicitation: SendElicitation,
+        send_sampling: Option<SendSamplingRequest>,
+        roots: Option<watch::Receiver<Vec<Root>>>,
+    ) -> Self {
+        Self {
+            client_info,
+            list_changes,
+            progress,
+            send_elicitation: Arc::new(send_elicitation),
+            send_sampling: send_sampling.map(Arc::new),
+            roots,
+        }
+    }
+}
+
+fn decline_elicitation() -> CreateElicitationResult {
+    CreateElicitationResult {
+        action: ElicitationAction::Decline,
+        content: None,
     }
 }
 
 impl ClientHandler for LoggingClientHandler {
-    // TODO (CODEX-3571): support elicitations.
+    /// Forwards the elicitation to the user. Anything that prevents an answer
+    /// (conversion failures, no listener, timeouts) declines the request.
     async fn create_elicitation(
         &self,
         request: CreateElicitationRequestParam,
-        _context: RequestContext<RoleClient>,
+        context: RequestContext<RoleClient>,
     ) -> Result<CreateElicitationResult, rmcp::ErrorData> {
-        info!(
-            "MCP server requested elicitation ({}). Elicitations are not supported yet. Declining.",
-            request.message
-        );
-        Ok(CreateElicitationResult {
-            action: ElicitationAction::Decline,
-            content: None,
+        let (id, params) = match (convert_to_mcp(context.id), convert_to_mcp(request)) {
+            (Ok(id), Ok(params)) => (id, params),
+            (Err(error), _) | (_, Err(error)) => {
+                warn!("failed to convert MCP elicitation request: {error}");
+                return Ok(decline_elicitation());
+            }
+        };
+        match (self.send_elicitation)(id, params).await {
+            Ok(result) => Ok(convert_to_rmcp(result).unwrap_or_else(|error| {
+                warn!("failed to convert MCP elicitation result: {error}");
+                decline_elicitation()
+            })),
+            Err(error) => {
+                info!("MCP elicitation was not answered ({error}). Declining.");
+                Ok(decline_elicitation())
+            }
+        }
+    }
+
+    /// Asks Codex for a completion. Unlike elicitations, a request that
+    /// cannot be served is answered with an error, as the spec requires for
+    /// rejected sampling.
+    async fn create_message(
+        &self,
+        request: CreateMessageRequestParam,
+        context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, rmcp::ErrorData> {
+        let Some(send_sampling) = &self.send_sampling else {
+            return Err(rmcp::ErrorData::new(
+                ErrorCode::METHOD_NOT_FOUND,
+                "sampling is not enabled for this server",
+                None,
+            ));
+        };
+        let (id, params) = match (convert_to_mcp(context.id), convert_to_mcp(request)) {
+            (Ok(id), Ok(params)) => (id, params),
+            (Err(error), _) | (_, Err(error)) => {
+                return Err(rmcp::ErrorData::invalid_params(
+                    format!("failed to convert sampling request: {error}"),
+                    None,
+                ));
+            }
+        };
+        match (send_sampling)(id, params).await {
+            Ok(result) => convert_to_rmcp(result).map_err(|error| {
+                rmcp::ErrorData::internal_error(
+                    format!("failed to convert sampling result: {error}"),
+                    None,
+                )
+            }),
+            Err(error) => {
+                info!("MCP sampling request was rejected: {error:#}");
+                Err(rmcp::ErrorData::new(
+                    ErrorCode(-1),
+                    format!("{error:#}"),
+                    None,
+                ))
+            }
+        }
+    }
+
+    async fn list_roots(
+        &self,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<ListRootsResult, rmcp::ErrorData> {
+        let Some(roots) = &self.roots else {
+            return Err(rmcp::ErrorData::new(
+                ErrorCode::METHOD_NOT_FOUND,
+                "roots are not shared with this server",
+                None,
+            ));
+        };
+        let result = mcp_types::ListRootsResult {
+            roots: roots.borrow().clone(),
+        };
+        convert_to_rmcp(result).map_err(|error| {
+            rmcp::ErrorData::internal_error(format!("failed to convert roots: {error}"), None)
         })
     }
 
@@ -60,10 +298,14 @@ impl ClientHandler for LoggingClientHandler {
         params: ProgressNotificationParam,
         _context: NotificationContext<RoleClient>,
     ) {
-        info!(
+        debug!(
             "MCP server progress notification (token: {:?}, progress: {}, total: {:?}, message: {:?})",
             params.progress_token, params.progress, params.total, params.message
         );
+        let token = params.progress_token.clone();
+        if !self.progress.notify(params) {
+            debug!("no request is waiting on progress token {token:?}");
+        }
     }
 
     async fn on_resource_updated(
@@ -76,14 +318,17 @@ impl ClientHandler for LoggingClientHandler {
 
     async fn on_resource_list_changed(&self, _context: NotificationContext<RoleClient>) {
         info!("MCP server resource list changed");
+        self.list_changes.resources.fetch_add(1, Ordering::AcqRel);
     }
 
     async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
         info!("MCP server tool list changed");
+        self.list_changes.tools.send_modify(|count| *count += 1);
     }
 
     async fn on_prompt_list_changed(&self, _context: NotificationContext<RoleClient>) {
         info!("MCP server prompt list changed");
+        self.list_changes.prompts.fetch_add(1, Ordering::AcqRel);
     }
 
     fn get_info(&self) -> ClientInfo {
@@ -19,6 +19,7 @@
 use anyhow::Context;
 use anyhow::Error;
 use anyhow::Result;
+use base64::Engine;
 use oauth2::AccessToken;
 use oauth2::EmptyExtraTokenFields;
 use oauth2::RefreshToken;
@@ -28,6 +29,7 @@ use oauth2::basic::BasicTokenType;
 use rmcp::transport::auth::OAuthTokenResponse;
 use serde::Deserialize;
 use serde::Serialize;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 use serde_json::map::Map as JsonMap;
 use sha2::Digest;
@@ -35,6 +37,7 @@ use sha2::Sha256;
 use std::collections::BTreeMap;
 use std::fs;
 use std::io::ErrorKind;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
@@ -90,7 +93,7 @@ impl PartialEq for WrappedOAuthTokenResponse {
     }
 }
 
-pub(crate) fn load_oauth_tokens(
+pub fn load_oauth_tokens(
     server_name: &str,
     url: &str,
     store_mode: OAuthCredentialsStoreMode,
@@ -116,6 +119,75 @@ pub(crate) fn has_oauth_tokens(
     Ok(load_oauth_tokens(server_name, url, store_mode)?.is_some())
 }
 
+/// What the stored OAuth token for an MCP server authorizes Codex to do.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OAuthTokenInfo {
+    /// Scopes granted by the authorization server, taken from the token
+    /// response or, when absent there, from the access token's claims.
+    pub granted_scopes: Option<Vec<String>>,
+    /// Claims decoded from the access token when it is a JWT. The signature is
+    /// not verified; this is informational only.
+    pub claims: Option<Value>,
+    /// Expiration of the access token in milliseconds since the Unix epoch.
+    pub expires_at: Option<u64>,
+}
+
+/// Read the scopes and claims of the stored OAuth token for `server_name`, if any.
+pub fn read_oauth_token_info(
+    server_name: &str,
+    url: &str,
+    store_mode: OAuthCredentialsStoreMode,
+) -> Result<Option<OAuthTokenInfo>> {
+    Ok(load_oauth_tokens(server_name, url, store_mode)?.map(|tokens| oauth_token_info(&tokens)))
+}
+
+fn oauth_token_info(tokens: &StoredOAuthTokens) -> OAuthTokenInfo {
+    let response = &tokens.token_response.0;
+    let claims = decode_jwt_claims(response.access_token().secret());
+    let granted_scopes = response
+        .scopes()
+        .map(|scopes| scopes.iter().map(|scope| scope.to_string()).collect())
+        .or_else(|| claims.as_ref().and_then(scopes_from_claims));
+
+    OAuthTokenInfo {
+        granted_scopes,
+        claims,
+        expires_at: tokens.expires_at,
+    }
+}
+
+fn decode_jwt_claims(token: &str) -> Option<Value> {
+    // JWT format: header.payload.signature
+    let mut parts = token.split('.');
+    let (Some(_header), Some(payload), Some(_signature), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .ok()?;
+    serde_json::from_slice::<Value>(&bytes)
+        .ok()
+        .filter(Value::is_object)
+}
+
+/// Extracts scopes from the `scope` claim (RFC 9068) or the `scp` claim used
+/// by some providers.
+fn scopes_from_claims(claims: &Value) -> Option<Vec<String>> {
+    match claims.get("scope").or_else(|| claims.get("scp"))? {
+        Value::String(scopes) => Some(scopes.split_whitespace().map(str::to_string).collect()),
+        Value::Array(scopes) => Some(
+            scopes
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
 fn refresh_expires_in_from_timestamp(tokens: &mut StoredOAuthTokens) {
     let Some(expires_at) = tokens.expires_at else {
         return;
@@ -374,6 +446,118 @@ impl OAuthPersistor {
     }
 }
 
+const CLIENT_REGISTRATIONS_FILENAME: &str = ".mcp-client-registrations.json";
+
+/// OAuth client obtained through dynamic client registration (RFC 7591).
+/// Kept across logins so the authorization server sees the same client
+/// instead of a new registration every time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub(crate) struct StoredClientRegistration {
+    pub server_name: String,
+    pub url: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    /// Redirect URI the client was registered with. Logins reuse its port so
+    /// the registration stays valid.
+    pub redirect_uri: String,
+}
+
+type ClientRegistrationsFile = BTreeMap<String, StoredClientRegistration>;
+
+pub(crate) fn load_client_registration(
+    server_name: &str,
+    url: &str,
+    store_mode: OAuthCredentialsStoreMode,
+) -> Result<Option<StoredClientRegistration>> {
+    let keyring_store = DefaultKeyringStore;
+    load_client_registration_from_store(&keyring_store, store_mode, server_name, url)
+}
+
+fn load_client_registration_from_store<K: KeyringStore>(
+    keyring_store: &K,
+    store_mode: OAuthCredentialsStoreMode,
+    server_name: &str,
+    url: &str,
+) -> Result<Option<StoredClientRegistration>> {
+    let key = compute_client_registration_key(server_name, url)?;
+    if store_mode != OAuthCredentialsStoreMode::File {
+        match keyring_store.load(KEYRING_SERVICE, &key) {
+            Ok(Some(serialized)) => {
+                let registration = serde_json::from_str(&serialized)
+                    .context("failed to deserialize OAuth client registration from keyring")?;
+                return Ok(Some(registration));
+            }
+            Ok(None) => {}
+            Err(error) if store_mode == OAuthCredentialsStoreMode::Keyring => {
+                return Err(Error::new(error.into_error()))
+                    .context("failed to read OAuth client registration from keyring");
+            }
+            Err(error) => {
+                warn!(
+                    "failed to read OAuth client registration from keyring: {}",
+                    error.message()
+                );
+            }
+        }
+    }
+
+    let registrations: Option<ClientRegistrationsFile> =
+        read_store_file(&client_registrations_file_path()?)?;
+    Ok(registrations.and_then(|mut registrations| registrations.remove(&key)))
+}
+
+pub(crate) fn save_client_registration(
+    registration: &StoredClientRegistration,
+    store_mode: OAuthCredentialsStoreMode,
+) -> Result<()> {
+    let keyring_store = DefaultKeyringStore;
+    save_client_registration_to_store(&keyring_store, store_mode, registration)
+}
+
+fn save_client_registration_to_store<K: KeyringStore>(
+    keyring_store: &K,
+    store_mode: OAuthCredentialsStoreMode,
+    registration: &StoredClientRegistration,
+) -> Result<()> {
+    let key = compute_client_registration_key(&registration.server_name, &registration.url)?;
+    if store_mode != OAuthCredentialsStoreMode::File {
+        let serialized = serde_json::to_string(registration)
+            .context("failed to serialize OAuth client registration")?;
+        match keyring_store.save(KEYRING_SERVICE, &key, &serialized) {
+            Ok(()) => return Ok(()),
+            Err(error) if store_mode == OAuthCredentialsStoreMode::Keyring => {
+                return Err(Error::new(error.into_error()))
+                    .context("failed to write OAuth client registration to keyring");
+            }
+            Err(error) => {
+                warn!(
+                    "falling back to file storage for OAuth client registration: {}",
+                    error.message()
+                );
+            }
+        }
+    }
+
+    let path = client_registrations_file_path()?;
+    let mut registrations: ClientRegistrationsFile = read_store_file(&path)?.unwrap_or_default();
+    registrations.insert(key, registration.clone());
+    write_store_file(&path, &registrations)
+}
+
+fn compute_client_registration_key(server_name: &str, server_url: &str) -> Result<String> {
+    Ok(format!(
+        "{}|client",
+        compute_store_key(server_name, server_url)?
+    ))
+}
+
+fn client_registrations_file_path() -> Result<PathBuf> {
+    let mut path = find_codex_home()?;
+    path.push(CLIENT_REGISTRATIONS_FILENAME);
+    Ok(path)
+}
+
 const FALLBACK_FILENAME: &str = ".credentials.json";
 const MCP_SERVER_TYPE: &str = "http";
 
@@ -540,8 +724,15 @@ fn fallback_file_path() -> Result<PathBuf> {
 }
 
 fn read_fallback_file() -> Result<Option<FallbackFile>> {
-    let path = fallback_file_path()?;
-    let contents = match fs::read_to_string(&path) {
+    read_store_file(&fallback_file_path()?)
+}
+
+fn write_fallback_file(store: &FallbackFile) -> Result<()> {
+    write_store_file(&fallback_file_path()?, store)
+}
+
+fn read_store_file<T: DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    let contents = match fs::read_to_string(path) {
         Ok(contents) => contents,
         Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
         Err(err) => {
@@ -552,7 +743,7 @@ fn read_fallback_file() -> Result<Option<FallbackFile>> {
         }
     };
 
-    match serde_json::from_str::<FallbackFile>(&contents) {
+    match serde_json::from_str::<T>(&contents) {
         Ok(store) => Ok(Some(store)),
         Err(e) => Err(e).context(format!(
             "failed to parse credentials file at {}",
@@ -561,9 +752,7 @@ fn read_fallback_file() -> Result<Option<FallbackFile>> {
     }
 }
 
-fn write_fallback_file(store: &FallbackFile) -> Result<()> {
-    let path = fallback_file_path()?;
-
+fn write_store_file<T: Serialize>(path: &Path, store: &BTreeMap<String, T>) -> Result<()> {
     if store.is_empty() {
         if path.exists() {
             fs::remove_file(path)?;
@@ -576,13 +765,13 @@ fn write_fallback_file(store: &FallbackFile) -> Result<()> {
     }
 
     let serialized = serde_json::to_string(store)?;
-    fs::write(&path, serialized)?;
+    fs::write(path, serialized)?;
 
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
         let perms = fs::Permissions::from_mode(0o600);
-        fs::set_permissions(&path, perms)?;
+        fs::set_permissions(path, perms)?;
     }
 
     Ok(())
@@ -895,6 +1084,105 @@ mod tests {
         );
     }
 
+    #[test]
+    fn client_registration_round_trips_through_keyring() -> Result<()> {
+        let _env = TempCodexHome::new();
+        let store = MockKeyringStore::default();
+        let registration = sample_registration();
+
+        super::save_client_registration_to_store(
+            &store,
+            OAuthCredentialsStoreMode::Auto,
+            &registration,
+        )?;
+
+        let loaded = super::load_client_registration_from_store(
+            &store,
+            OAuthCredentialsStoreMode::Auto,
+            &registration.server_name,
+            &registration.url,
+        )?;
+        assert_eq!(Some(registration), loaded);
+        assert!(!super::client_registrations_file_path()?.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn client_registration_falls_back_to_file_when_keyring_fails() -> Result<()> {
+        let _env = TempCodexHome::new();
+        let store = MockKeyringStore::default();
+        let registration = sample_registration();
+        let key =
+            super::compute_client_registration_key(&registration.server_name, &registration.url)?;
+        store.set_error(&key, KeyringError::Invalid("error".into(), "save".into()));
+
+        super::save_client_registration_to_store(
+            &store,
+            OAuthCredentialsStoreMode::Auto,
+            &registration,
+        )?;
+
+        let loaded = super::load_client_registration_from_store(
+            &store,
+            OAuthCredentialsStoreMode::Auto,
+            &registration.server_name,
+            &registration.url,
+        )?;
+        assert_eq!(Some(registration), loaded);
+        assert!(store.saved_value(&key).is_none());
+        Ok(())
+    }
+
+    fn sample_registration() -> StoredClientRegistration {
+        StoredClientRegistration {
+            server_name: "test-server".to_string(),
+            url: "https://example.test".to_string(),
+            client_id: "registered-client".to_string(),
+            client_secret: Some("registered-secret".to_string()),
+            redirect_uri: "http://127.0.0.1:43123/callback".to_string(),
+        }
+    }
+
+    #[test]
+    fn oauth_token_info_prefers_token_response_scopes() {
+        let tokens = sample_tokens();
+        assert_eq!(
+            oauth_token_info(&tokens),
+            OAuthTokenInfo {
+                granted_scopes: Some(vec!["scope-a".to_string(), "scope-b".to_string()]),
+                claims: None,
+                expires_at: tokens.expires_at,
+            }
+        );
+    }
+
+    #[test]
+    fn oauth_token_info_reads_scopes_from_jwt_claims() {
+        let encode = |bytes: &[u8]| base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+        let claims = serde_json::json!({ "sub": "user-1", "scope": "repo:read issues:write" });
+        let jwt = format!(
+            "{}.{}.{}",
+            encode(br#"{"alg":"none"}"#),
+            encode(claims.to_string().as_bytes()),
+            encode(b"sig"),
+        );
+        let mut tokens = sample_tokens();
+        tokens
+            .token_response
+            .0
+            .set_access_token(AccessToken::new(jwt));
+        tokens.token_response.0.set_scopes(None);
+
+        assert_eq!(
+            oauth_token_info(&tokens),
+            OAuthTokenInfo {
+                granted_scopes: Some(vec!["repo:read".to_string(), "issues:write".to_string()]),
+                claims: Some(claims),
+                expires_at: tokens.expires_at,
+            }
+        );
+    }
+
     fn sample_tokens() -> StoredOAuthTokens {
         let mut response = OAuthTokenResponse::new(
             AccessToken::new("access-token".to_string()),
@@ -6,22 +6,44 @@ use std::time::Duration;
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
-use reqwest::ClientBuilder;
-use rmcp::transport::auth::OAuthState;
+use oauth2::AuthUrl;
+use oauth2::AuthorizationCode;
+use oauth2::ClientId;
+use oauth2::ClientSecret;
+use oauth2::CsrfToken;
+use oauth2::PkceCodeChallenge;
+use oauth2::RedirectUrl;
+use oauth2::Scope;
+use oauth2::TokenUrl;
+use oauth2::basic::BasicClient;
+use rmcp::transport::auth::AuthorizationManager;
+use rmcp::transport::auth::ClientRegistrationRequest;
+use rmcp::transport::auth::ClientRegistrationResponse;
 use tiny_http::Response;
 use tiny_http::Server;
 use tokio::sync::oneshot;
 use tokio::time::timeout;
+use tracing::warn;
 use urlencoding::decode;
 
 use crate::OAuthCredentialsStoreMode;
 use crate::StoredOAuthTokens;
 use crate::WrappedOAuthTokenResponse;
+use crate::oauth::StoredClientRegistration;
 use crate::oauth::compute_expires_at_millis;
+use crate::oauth::load_client_registration;
+use crate::oauth::save_client_registration;
 use crate::save_oauth_tokens;
 use crate::utils::apply_default_headers;
 use crate::utils::build_default_headers;
 
+/// Name Codex registers under when the server supports dynamic client
+/// registration.
+const CLIENT_NAME: &str = "Codex";
+/// Client id used when the server neither supports dynamic registration nor
+/// has a stored registration.
+const DEFAULT_CLIENT_ID: &str = "mcp-client";
+
 struct CallbackServerGuard {
     server: Arc<Server>,
 }
@@ -40,7 +62,33 @@ pub async fn perform_oauth_login(
     env_http_headers: Option<HashMap<String, String>>,
     scopes: &[String],
 ) -> Result<()> {
-    let server = Arc::new(Server::http("127.0.0.1:0").map_err(|err| anyhow!(err))?);
+    let default_headers = build_default_headers(http_headers, env_http_headers)?;
+    let http_client =
+        apply_default_headers(codex_utils_http_client::builder(), &default_headers).build()?;
+
+    let mut auth_manager = AuthorizationManager::new(server_url).await?;
+    auth_manager.with_client(http_client.clone())?;
+    let metadata = auth_manager.discover_metadata().await?;
+
+    let stored_registration = load_client_registration(server_name, server_url, store_mode)
+        .unwrap_or_else(|err| {
+            warn!("failed to read OAuth client registration for `{server_name}`: {err}");
+            None
+        });
+    // A registered client only accepts the redirect URIs it was registered
+    // with, so the stored registration is only usable if its port is free.
+    let (server, stored_registration) = match stored_registration.and_then(|registration| {
+        let port = redirect_port(&registration.redirect_uri)?;
+        let server = Server::http(("127.0.0.1", port)).ok()?;
+        Some((server, registration))
+    }) {
+        Some((server, registration)) => (server, Some(registration)),
+        None => (
+            Server::http("127.0.0.1:0").map_err(|err| anyhow!(err))?,
+            None,
+        ),
+    };
+    let server = Arc::new(server);
     let guard = CallbackServerGuard {
         server: Arc::clone(&server),
     };
@@ -56,18 +104,60 @@ pub async fn perform_oauth_login(
         _ => return Err(anyhow!("unable to determine callback address")),
     };
 
-    let (tx, rx) = oneshot::channel();
-    spawn_callback_server(server, tx);
+    let registration = match (
+        stored_registration,
+        metadata.registration_endpoint.as_deref(),
+    ) {
+        (Some(registration), _) => Some(registration),
+        (None, Some(registration_endpoint)) => {
+            match register_client(
+                &http_client,
+                registration_endpoint,
+                server_name,
+                server_url,
+                &redirect_uri,
+            )
+            .await
+            {
+                Ok(registration) => {
+                    if let Err(err) = save_client_registration(&registration, store_mode) {
+                        warn!(
+                            "failed to save OAuth client registration for `{server_name}`: {err}"
+                        );
+                    }
+                    Some(registration)
+                }
+                Err(err) => {
+                    warn!("dynamic client registration failed for `{server_name}`: {err:#}");
+                    None
+                }
+            }
+        }
+        (None, None) => None,
+    };
+    let (client_id, client_secret) = match registration {
+        Some(registration) => (registration.client_id, registration.client_secret),
+        None => (DEFAULT_CLIENT_ID.to_string(), None),
+    };
 
-    let default_headers = build_default_headers(http_headers, env_http_headers)?;
-    let http_client = apply_default_headers(ClientBuilder::new(), &default_headers).build()?;
+    let mut oauth_client = BasicClient::new(ClientId::new(client_id.clone()))
+        .set_auth_uri(AuthUrl::new(metadata.authorization_endpoint.clone())?)
+        .set_token_uri(TokenUrl::new(metadata.token_endpoint.clone())?)
+        .set_redirect_uri(RedirectUrl::new(redirect_uri)?);
+    if let Some(client_secret) = client_secret {
+        oauth_client = oauth_client.set_client_secret(ClientSecret::new(client_secret));
+    }
+
+    let (pkce_challenge, pkce_verifier) = PkceCodeChallenge::new_random_sha256();
+    let (auth_url, expected_state) = oauth_client
+        .authorize_url(CsrfToken::new_random)
+        .set_pkce_challenge(pkce_challenge)
+        .add_scopes(scopes.iter().cloned().map(Scope::new))
+        .url();
+    let auth_url = auth_url.to_string();
 
-    let mut oauth_state = OAuthState::new(server_url, Some(http_client)).await?;
-    let scope_refs: Vec<&str> = scopes.iter().map(String::as_str).collect();
-    oauth_state
-        .start_authorization(&scope_refs, &redirect_uri, Some("Codex"))
-        .await?;
-    let auth_url = oauth_state.get_authorization_url().await?;
+    let (tx, rx) = oneshot::channel();
+    spawn_callback_server(server, tx);
 
     println!("Authorize `{server_name}` by opening this URL in your browser:\n{auth_url}\n");
 
@@ -79,18 +169,19 @@ pub async fn perform_oauth_login(
         .await
         .context("timed out waiting for OAuth callback")?
         .context("OAuth callback was cancelled")?;
+    if csrf_state != *expected_state.secret() {
+        return Err(anyhow!("OAuth callback state did not match the request"));
+    }
 
-    oauth_state
-        .handle_callback(&code, &csrf_state)
-        .await
-        .context("failed to handle OAuth callback")?;
-
-    let (client_id, credentials_opt) = oauth_state
-        .get_credentials()
+    let token_http_client = codex_utils_http_client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()?;
+    let credentials = oauth_client
+        .exchange_code(AuthorizationCode::new(code))
+        .set_pkce_verifier(pkce_verifier)
+        .request_async(&token_http_client)
         .await
-        .context("failed to retrieve OAuth credentials")?;
-    let credentials =
-        credentials_opt.ok_or_else(|| anyhow!("OAuth provider did not return credentials"))?;
+        .map_err(|err| anyhow!("failed to handle OAuth callback: {err}"))?;
 
     let expires_at = compute_expires_at_millis(&credentials);
     let stored = StoredOAuthTokens {
@@ -106,6 +197,55 @@ pub async fn perform_oauth_login(
     Ok(())
 }
 
+/// Registers Codex as a public OAuth client (RFC 7591) that redirects to
+/// `redirect_uri`.
+async fn register_client(
+    http_client: &reqwest::Client,
+    registration_endpoint: &str,
+    server_name: &str,
+    server_url: &str,
+    redirect_uri: &str,
+) -> Result<StoredClientRegistration> {
+    let request = ClientRegistrationRequest {
+        client_name: CLIENT_NAME.to_string(),
+        redirect_uris: vec![redirect_uri.to_string()],
+        grant_types: vec![
+            "authorization_code".to_string(),
+            "refresh_token".to_string(),
+        ],
+        token_endpoint_auth_method: "none".to_string(),
+        response_types: vec!["code".to_string()],
+    };
+    let response = http_client
+        .post(registration_endpoint)
+        .json(&request)
+        .send()
+        .await
+        .context("client registration request failed")?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow!("client registration returned {status}: {body}"));
+    }
+    let response: ClientRegistrationResponse = response
+        .json()
+        .await
+        .context("failed to parse client registration response")?;
+
+    Ok(StoredClientRegistration {
+        server_name: server_name.to_string(),
+        url: server_url.to_string(),
+        client_id: response.client_id,
+        // Some servers send an empty secret for public clients.
+        client_secret: response.client_secret.filter(|secret| !secret.is_empty()),
+        redirect_uri: redirect_uri.to_string(),
+    })
+}
+
+fn redirect_port(redirect_uri: &str) -> Option<u16> {
+    reqwest::Url::parse(redirect_uri).ok()?.port()
+}
+
 fn spawn_callback_server(server: Arc<Server>, tx: oneshot::Sender<(String, String)>) {
     tokio::task::spawn_blocking(move || {
         while let Ok(request) = server.recv() {
@@ -9,10 +9,19 @@ use std::time::Duration;
 use anyhow::Result;
 use anyhow::anyhow;
 use futures::FutureExt;
+use futures::future::BoxFuture;
 use mcp_types::CallToolRequestParams;
 use mcp_types::CallToolResult;
+use mcp_types::CreateMessageRequestParams;
+use mcp_types::CreateMessageResult;
+use mcp_types::ElicitRequestParams;
+use mcp_types::ElicitResult;
+use mcp_types::GetPromptRequestParams;
+use mcp_types::GetPromptResult;
 use mcp_types::InitializeRequestParams;
 use mcp_types::InitializeResult;
+use mcp_types::ListPromptsRequestParams;
+use mcp_types::ListPromptsResult;
 use mcp_types::ListResourceTemplatesRequestParams;
 use mcp_types::ListResourceTemplatesResult;
 use mcp_types::ListResourcesRequestParams;
@@ -21,16 +30,26 @@ use mcp_types::ListToolsRequestParams;
 use mcp_types::ListToolsResult;
 use mcp_types::ReadResourceRequestParams;
 use mcp_types::ReadResourceResult;
+use mcp_types::RequestId;
+use mcp_types::Root;
 use reqwest::header::HeaderMap;
+use rmcp::model::CallToolRequest;
 use rmcp::model::CallToolRequestParam;
+use rmcp::model::ClientRequest;
+use rmcp::model::GetPromptRequestParam;
 use rmcp::model::InitializeRequestParam;
+use rmcp::model::Meta;
 use rmcp::model::PaginatedRequestParam;
+use rmcp::model::PingRequest;
 use rmcp::model::ReadResourceRequestParam;
+use rmcp::model::ServerResult;
+use rmcp::service::PeerRequestOptions;
 use rmcp::service::RoleClient;
 use rmcp::service::RunningService;
 use rmcp::service::{self};
 use rmcp::transport::StreamableHttpClientTransport;
 use rmcp::transport::auth::AuthClient;
+use rmcp::transport::auth::OAuthClientConfig;
 use rmcp::transport::auth::OAuthState;
 use rmcp::transport::child_process::TokioChildProcess;
 use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig;
@@ -38,15 +57,22 @@ use tokio::io::AsyncBufReadExt;
 use tokio::io::BufReader;
 use tokio::process::Command;
 use tokio::sync::Mutex;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::watch;
 use tokio::time;
+use tracing::debug;
 use tracing::info;
 use tracing::warn;
 
 use crate::load_oauth_tokens;
+use crate::logging_client_handler::ListChangeCounters;
 use crate::logging_client_handler::LoggingClientHandler;
+use crate::logging_client_handler::McpProgress;
+use crate::logging_client_handler::ProgressSubscribers;
 use crate::oauth::OAuthCredentialsStoreMode;
 use crate::oauth::OAuthPersistor;
 use crate::oauth::StoredOAuthTokens;
+use crate::oauth::load_client_registration;
 use crate::program_resolver;
 use crate::utils::apply_default_headers;
 use crate::utils::build_default_headers;
@@ -77,10 +103,29 @@ enum ClientState {
     },
 }
 
+/// Asks the user to answer an `elicitation/create` request from the server
+/// and resolves to the result that is sent back. An error declines the
+/// request.
+pub type SendElicitation = Box<
+    dyn Fn(RequestId, ElicitRequestParams) -> BoxFuture<'static, Result<ElicitResult>>
+        + Send
+        + Sync,
+>;
+
+/// Runs a `sampling/createMessage` request from the server and resolves to
+/// the completion that is sent back. An error rejects the request.
+pub type SendSamplingRequest = Box<
+    dyn Fn(RequestId, CreateMessageRequestParams) -> BoxFuture<'static, Result<CreateMessageResult>>
+        + Send
+        + Sync,
+>;
+
 /// MCP client implemented on top of the official `rmcp` SDK.
 /// https://github.com/modelcontextprotocol/rust-sdk
 pub struct RmcpClient {
     state: Mutex<ClientState>,
+    list_changes: Arc<ListChangeCounters>,
+    progress: Arc<ProgressSubscribers>,
 }
 
 impl RmcpClient {
@@ -137,6 +182,8 @@ impl RmcpClient {
             state: Mutex::new(ClientState::Connecting {
                 transport: Some(PendingTransport::ChildProcess(transport)),
             }),
+            list_changes: Arc::default(),
+            progress: Arc::default(),
         })
     }
 
@@ -182,7 +229,8 @@ impl RmcpClient {
             }
 
             let http_client =
-                apply_default_headers(reqwest::Client::builder(), &default_headers).build()?;
+                apply_default_headers(codex_utils_http_client::builder(), &default_headers)
+                    .build()?;
 
             let transport = StreamableHttpClientTransport::with_client(http_client, http_config);
             PendingTransport::StreamableHttp { transport }
@@ -191,18 +239,35 @@ impl RmcpClient {
             state: Mutex::new(ClientState::Connecting {
                 transport: Some(transport),
             }),
+            list_changes: Arc::default(),
+            progress: Arc::default(),
         })
     }
 
     /// Perform the initialization handshake with the MCP server.
     /// https://modelcontextprotocol.io/specification/2025-06-18/basic/lifecycle#initialization
+    ///
+    /// Sampling requests are refused unless `send_sampling` is set, which
+    /// should match whether `params` declares the sampling capability. The
+    /// same goes for `roots/list` and `roots`; the server is also notified
+    /// each time a new list is sent on `roots`.
     pub async fn initialize(
         &self,
         params: InitializeRequestParams,
         timeout: Option<Duration>,
+        send_elicitation: SendElicitation,
+        send_sampling: Option<SendSamplingRequest>,
+        roots: Option<watch::Receiver<Vec<Root>>>,
     ) -> Result<InitializeResult> {
         let rmcp_params: InitializeRequestParam = convert_to_rmcp(params.clone())?;
-        let client_handler = LoggingClientHandler::new(rmcp_params);
+        let client_handler = LoggingClientHandler::new(
+            rmcp_params,
+            Arc::clone(&self.list_changes),
+            Arc::clone(&self.progress),
+            send_elicitation,
+            send_sampling,
+            roots.clone(),
+        );
 
         let (transport, oauth_persistor) = {
             let mut guard = self.state.lock().await;
@@ -245,6 +310,19 @@ impl RmcpClient {
             .ok_or_else(|| anyhow!("handshake succeeded but server info was missing"))?;
         let initialize_result = convert_to_mcp(initialize_result_rmcp)?;
 
+        if let Some(mut roots) = roots {
+            roots.mark_unchanged();
+            let peer = service.peer().clone();
+            tokio::spawn(async move {
+                while roots.changed().await.is_ok() {
+                    if let Err(error) = peer.notify_roots_list_changed().await {
+                        debug!("stopped sending roots/list_changed: {error}");
+                        return;
+                    }
+                }
+            });
+        }
+
         {
             let mut guard = self.state.lock().await;
             *guard = ClientState::Ready {
@@ -316,6 +394,56 @@ impl RmcpClient {
         Ok(converted)
     }
 
+    pub async fn list_prompts(
+        &self,
+        params: Option<ListPromptsRequestParams>,
+        timeout: Option<Duration>,
+    ) -> Result<ListPromptsResult> {
+        self.refresh_oauth_if_needed().await;
+        let service = self.service().await?;
+        let rmcp_params = params
+            .map(convert_to_rmcp::<_, PaginatedRequestParam>)
+            .transpose()?;
+
+        let fut = service.list_prompts(rmcp_params);
+        let result = run_with_timeout(fut, timeout, "prompts/list").await?;
+        let converted = convert_to_mcp(result)?;
+        self.persist_oauth_tokens().await;
+        Ok(converted)
+    }
+
+    pub async fn get_prompt(
+        &self,
+        params: GetPromptRequestParams,
+        timeout: Option<Duration>,
+    ) -> Result<GetPromptResult> {
+        self.refresh_oauth_if_needed().await;
+        let service = self.service().await?;
+        let rmcp_params: GetPromptRequestParam = convert_to_rmcp(params)?;
+        let fut = service.get_prompt(rmcp_params);
+        let result = run_with_timeout(fut, timeout, "prompts/get").await?;
+        let converted = convert_to_mcp(result)?;
+        self.persist_oauth_tokens().await;
+        Ok(converted)
+    }
+
+    /// Number of `notifications/resources/list_changed` received so far.
+    /// A cached resource list is stale once this value moves.
+    pub fn resource_list_version(&self) -> i64 {
+        self.list_changes.resources()
+    }
+
+    /// Number of `notifications/prompts/list_changed` received so far.
+    pub fn prompt_list_version(&self) -> i64 {
+        self.list_changes.prompts()
+    }
+
+    /// Receives the running count of `notifications/tools/list_changed`,
+    /// marked as changed each time another one arrives.
+    pub fn subscribe_tool_list_changes(&self) -> watch::Receiver<i64> {
+        self.list_changes.subscribe_tools()
+    }
+
     pub async fn read_resource(
         &self,
         params: ReadResourceRequestParams,
@@ -331,23 +459,57 @@ impl RmcpClient {
         Ok(converted)
     }
 
+    /// Calls a tool. When `progress` is set, the request carries a progress
+    /// token and the server's progress notifications for it are forwarded
+    /// there until the call finishes.
     pub async fn call_tool(
         &self,
         name: String,
         arguments: Option<serde_json::Value>,
         timeout: Option<Duration>,
+        progress: Option<UnboundedSender<McpProgress>>,
     ) -> Result<CallToolResult> {
         self.refresh_oauth_if_needed().await;
         let service = self.service().await?;
         let params = CallToolRequestParams { arguments, name };
         let rmcp_params: CallToolRequestParam = convert_to_rmcp(params)?;
-        let fut = service.call_tool(rmcp_params);
-        let rmcp_result = run_with_timeout(fut, timeout, "tools/call").await?;
+        let subscription = progress.map(|sender| self.progress.subscribe(sender));
+        let mut options = PeerRequestOptions::no_options();
+        if let Some(subscription) = &subscription {
+            let mut meta = Meta::new();
+            meta.set_progress_token(subscription.token());
+            options.meta = Some(meta);
+        }
+        let fut = async {
+            service
+                .send_request_with_option(
+                    ClientRequest::CallToolRequest(CallToolRequest::new(rmcp_params)),
+                    options,
+                )
+                .await?
+                .await_response()
+                .await
+        };
+        let rmcp_result = match run_with_timeout(fut, timeout, "tools/call").await? {
+            ServerResult::CallToolResult(result) => result,
+            other => return Err(anyhow!("unexpected response to tools/call: {other:?}")),
+        };
         let converted = convert_call_tool_result(rmcp_result)?;
         self.persist_oauth_tokens().await;
         Ok(converted)
     }
 
+    /// Sends an MCP `ping` request. Used to check that the server is still
+    /// responsive without side effects.
+    pub async fn ping(&self, timeout: Option<Duration>) -> Result<()> {
+        let service = self.service().await?;
+        let fut = service.send_request(ClientRequest::PingRequest(PingRequest::default()));
+        match run_with_timeout(fut, timeout, "ping").await? {
+            ServerResult::EmptyResult(_) => Ok(()),
+            other => Err(anyhow!("unexpected response to ping: {other:?}")),
+        }
+    }
+
     async fn service(&self) -> Result<Arc<RunningService<RoleClient, LoggingClientHandler>>> {
         let guard = self.state.lock().await;
         match &*guard {
@@ -397,7 +559,7 @@ async fn create_oauth_transport_and_runtime(
     OAuthPersistor,
 )> {
     let http_client =
-        apply_default_headers(reqwest::Client::builder(), &default_headers).build()?;
+        apply_default_headers(codex_utils_http_client::builder(), &default_headers).build()?;
     let mut oauth_state = OAuthState::new(url.to_string(), Some(http_client.clone())).await?;
 
     oauth_state
@@ -407,7 +569,7 @@ async fn create_oauth_transport_and_runtime(
         )
         .await?;
 
-    let manager = match oauth_state {
+    let mut manager = match oauth_state {
         OAuthState::Authorized(manager) => manager,
         OAuthState::Unauthorized(manager) => manager,
         OAuthState::Session(_) | OAuthState::AuthorizedHttpClient(_) => {
@@ -415,6 +577,26 @@ async fn create_oauth_transport_and_runtime(
         }
     };
 
+    // Confidential clients from dynamic registration need their secret to
+    // refresh tokens; `set_credentials` only configures the client id.
+    match load_client_registration(server_name, url, credentials_store) {
+        Ok(Some(registration))
+            if registration.client_id == initial_tokens.client_id
+                && registration.client_secret.is_some() =>
+        {
+            manager.configure_client(OAuthClientConfig {
+                client_id: registration.client_id,
+                client_secret: registration.client_secret,
+                scopes: Vec::new(),
+                redirect_uri: registration.redirect_uri,
+            })?;
+        }
+        Ok(_) => {}
+        Err(err) => {
+            warn!("failed to read OAuth client registration for `{server_name}`: {err}");
+        }
+    }
+
     let auth_client = AuthClient::new(http_client, manager);
     let auth_manager = auth_client.auth_manager.clone();
 
@@ -0,0 +1,103 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use codex_rmcp_client::McpProgress;
+use codex_rmcp_client::RmcpClient;
+use escargot::CargoBuild;
+use futures::FutureExt as _;
+use mcp_types::ClientCapabilities;
+use mcp_types::ElicitResult;
+use mcp_types::Implementation;
+use mcp_types::InitializeRequestParams;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+fn stdio_server_bin() -> anyhow::Result<PathBuf> {
+    let build = CargoBuild::new()
+        .package("codex-rmcp-client")
+        .bin("test_stdio_server")
+        .run()?;
+    Ok(build.path().to_path_buf())
+}
+
+fn init_params() -> InitializeRequestParams {
+    InitializeRequestParams {
+        capabilities: ClientCapabilities {
+            experimental: None,
+            roots: None,
+            sampling: None,
+            elicitation: Some(json!({})),
+        },
+        client_info: Implementation {
+            name: "codex-test".into(),
+            version: "0.0.0-test".into(),
+            title: Some("Codex rmcp progress test".into()),
+            user_agent: None,
+        },
+        protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_string(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+async fn rmcp_client_forwards_tool_call_progress() -> anyhow::Result<()> {
+    let client = RmcpClient::new_stdio_client(
+        stdio_server_bin()?.into(),
+        Vec::<OsString>::new(),
+        None,
+        &[],
+        None,
+    )
+    .await?;
+
+    client
+        .initialize(
+            init_params(),
+            Some(Duration::from_secs(5)),
+            Box::new(|_, _| {
+                async {
+                    Ok(ElicitResult {
+                        action: "decline".to_string(),
+                        content: None,
+                    })
+                }
+                .boxed()
+            }),
+            None,
+            None,
+        )
+        .await?;
+
+    let (progress_tx, mut progress_rx) = mpsc::unbounded_channel();
+    client
+        .call_tool(
+            "progress".to_string(),
+            Some(json!({})),
+            Some(Duration::from_secs(5)),
+            Some(progress_tx),
+        )
+        .await?;
+
+    let mut updates = Vec::new();
+    while let Some(update) = progress_rx.recv().await {
+        updates.push(update);
+    }
+    assert_eq!(
+        updates,
+        vec![
+            McpProgress {
+                progress: 1.0,
+                total: Some(2.0),
+                message: Some("halfway".to_string()),
+            },
+            McpProgress {
+                progress: 2.0,
+                total: Some(2.0),
+                message: Some("done".to_string()),
+            },
+        ]
+    );
+
+    Ok(())
+}
@@ -4,7 +4,9 @@ use std::time::Duration;
 
 use codex_rmcp_client::RmcpClient;
 use escargot::CargoBuild;
+use futures::FutureExt as _;
 use mcp_types::ClientCapabilities;
+use mcp_types::ElicitResult;
 use mcp_types::Implementation;
 use mcp_types::InitializeRequestParams;
 use mcp_types::ListResourceTemplatesResult;
@@ -55,7 +57,21 @@ async fn rmcp_client_can_list_and_read_resources() -> anyhow::Result<()> {
     .await?;
 
     client
-        .initialize(init_params(), Some(Duration::from_secs(5)))
+        .initialize(
+            init_params(),
+            Some(Duration::from_secs(5)),
+            Box::new(|_, _| {
+                async {
+                    Ok(ElicitResult {
+                        action: "decline".to_string(),
+                        content: None,
+                    })
+                }
+                .boxed()
+            }),
+            None,
+            None,
+        )
         .await?;
 
     let list = client
@@ -14,7 +14,7 @@ pub fn add_dir_warning_message(
 
     match sandbox_policy {
         SandboxPolicy::WorkspaceWrite { .. } | SandboxPolicy::DangerFullAccess => None,
-        SandboxPolicy::ReadOnly => Some(format_warning(additional_dirs)),
+        SandboxPolicy::ReadOnly | SandboxPolicy::Audit => Some(format_warning(additional_dirs)),
     }
 }
 
@@ -36,6 +36,8 @@ use codex_core::protocol::Op;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
+use codex_core::workspace_instructions::add_workspace_instruction;
+use codex_core::workspace_instructions::workspace_instructions_path;
 use codex_protocol::ConversationId;
 use color_eyre::eyre::Result;
 use color_eyre::eyre::WrapErr;
@@ -332,6 +334,7 @@ impl App {
                     app.config.sandbox_policy,
                     codex_core::protocol::SandboxPolicy::WorkspaceWrite { .. }
                         | codex_core::protocol::SandboxPolicy::ReadOnly
+                        | codex_core::protocol::SandboxPolicy::Audit
                 )
                 && !app
                     .config
@@ -688,12 +691,16 @@ impl App {
                     policy,
                     codex_core::protocol::SandboxPolicy::WorkspaceWrite { .. }
                         | codex_core::protocol::SandboxPolicy::ReadOnly
+                        | codex_core::protocol::SandboxPolicy::Audit
                 );
 
                 self.config.sandbox_policy = policy.clone();
                 #[cfg(target_os = "windows")]
-                if !matches!(policy, codex_core::protocol::SandboxPolicy::ReadOnly)
-                    || codex_core::get_platform_sandbox().is_some()
+                if !matches!(
+                    policy,
+                    codex_core::protocol::SandboxPolicy::ReadOnly
+                        | codex_core::protocol::SandboxPolicy::Audit
+                ) || codex_core::get_platform_sandbox().is_some()
                 {
                     self.config.forced_auto_mode_downgraded_on_windows = false;
                 }
@@ -734,6 +741,23 @@ impl App {
             AppEvent::UpdateRateLimitSwitchPromptHidden(hidden) => {
                 self.chat_widget.set_rate_limit_switch_prompt_hidden(hidden);
             }
+            AppEvent::SaveWorkspaceInstruction { instruction } => {
+                match add_workspace_instruction(&self.config.cwd, &instruction).await {
+                    Ok(_) => self.chat_widget.add_info_message(
+                        format!("Saved workspace instruction: {instruction}"),
+                        Some(format!(
+                            "Stored in {}",
+                            workspace_instructions_path(&self.config.cwd).display()
+                        )),
+                    ),
+                    Err(err) => {
+                        tracing::error!(error = %err, "failed to save workspace instruction");
+                        self.chat_widget.add_error_message(format!(
+                            "Failed to save workspace instruction: {err}"
+                        ));
+                    }
+                }
+            }
             AppEvent::PersistFullAccessWarningAcknowledged => {
                 if let Err(err) = ConfigEditsBuilder::new(&self.config.codex_home)
                     .set_hide_full_access_warning(true)
@@ -821,6 +845,30 @@ impl App {
                         "E X E C".to_string(),
                     ));
                 }
+                ApprovalRequest::McpElicitation {
+                    server_name,
+                    message,
+                    ..
+                } => {
+                    let _ = tui.enter_alt_screen();
+                    self.overlay = Some(Overlay::new_static_with_lines(
+                        vec![Line::from(format!("{server_name}:")), Line::from(message)],
+                        "M C P".to_string(),
+                    ));
+                }
+                ApprovalRequest::WriteScope { root, paths, .. } => {
+                    let _ = tui.enter_alt_screen();
+                    let mut lines = vec![Line::from(format!("{}:", root.display()))];
+                    lines.extend(
+                        paths
+                            .iter()
+                            .map(|path| Line::from(format!("  {}", path.display()))),
+                    );
+                    self.overlay = Some(Overlay::new_static_with_lines(
+                        lines,
+                        "W R I T E".to_string(),
+                    ));
+                }
             },
         }
         Ok(true)
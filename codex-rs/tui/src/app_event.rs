@@ -119,6 +119,12 @@ pub(crate) enum AppEvent {
     /// Update whether the rate limit switch prompt has been acknowledged for the session.
     UpdateRateLimitSwitchPromptHidden(bool),
 
+    /// Save an instruction the model proposed and the user confirmed to the
+    /// workspace's instructions file.
+    SaveWorkspaceInstruction {
+        instruction: String,
+    },
+
     /// Persist the acknowledgement flag for the full access warning prompt.
     PersistFullAccessWarningAcknowledged,
 
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::Path;
 use std::path::PathBuf;
 
 use crate::app_event::AppEvent;
@@ -9,6 +10,7 @@ use crate::bottom_pane::list_selection_view::ListSelectionView;
 use crate::bottom_pane::list_selection_view::SelectionItem;
 use crate::bottom_pane::list_selection_view::SelectionViewParams;
 use crate::diff_render::DiffSummary;
+use crate::diff_render::display_path_for;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::history_cell;
 use crate::key_hint;
@@ -16,15 +18,20 @@ use crate::key_hint::KeyBinding;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
+use codex_core::protocol::ApprovalRule;
+use codex_core::protocol::ElicitationAction;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::Op;
+use codex_core::protocol::PatchFileStatus;
 use codex_core::protocol::ReviewDecision;
 use codex_core::protocol::SandboxCommandAssessment;
 use codex_core::protocol::SandboxRiskLevel;
+use codex_core::protocol::WriteScopeDecision;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
+use mcp_types::RequestId;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::Stylize;
@@ -41,12 +48,30 @@ pub(crate) enum ApprovalRequest {
         command: Vec<String>,
         reason: Option<String>,
         risk: Option<SandboxCommandAssessment>,
+        /// Rules core offered to remember for this command.
+        proposed_rules: Vec<ApprovalRule>,
     },
     ApplyPatch {
         id: String,
         reason: Option<String>,
         cwd: PathBuf,
         changes: HashMap<PathBuf, FileChange>,
+        /// Dry-run result per file; non-clean entries are shown as warnings.
+        file_statuses: HashMap<PathBuf, PatchFileStatus>,
+    },
+    McpElicitation {
+        server_name: String,
+        request_id: RequestId,
+        message: String,
+        /// Whether the request can be accepted without entering any fields.
+        can_accept: bool,
+    },
+    WriteScope {
+        id: String,
+        root: PathBuf,
+        paths: Vec<PathBuf>,
+        command: Option<Vec<String>>,
+        reason: Option<String>,
     },
 }
 
@@ -97,14 +122,26 @@ impl ApprovalOverlay {
         header: Box<dyn Renderable>,
     ) -> (Vec<ApprovalOption>, SelectionViewParams) {
         let (options, title) = match &variant {
-            ApprovalVariant::Exec { .. } => (
-                exec_options(),
+            ApprovalVariant::Exec { always_allow, .. } => (
+                exec_options(always_allow.as_ref()),
                 "Would you like to run the following command?".to_string(),
             ),
             ApprovalVariant::ApplyPatch { .. } => (
                 patch_options(),
                 "Would you like to make the following edits?".to_string(),
             ),
+            ApprovalVariant::McpElicitation {
+                server_name,
+                can_accept,
+                ..
+            } => (
+                elicitation_options(*can_accept),
+                format!("{server_name} is asking for input"),
+            ),
+            ApprovalVariant::WriteScope { root, .. } => (
+                write_scope_options(),
+                format!("Allow Codex to write to {}?", root.display()),
+            ),
         };
 
         let header = Box::new(ColumnRenderable::with([
@@ -150,12 +187,43 @@ impl ApprovalOverlay {
         };
         if let Some(variant) = self.current_variant.as_ref() {
             match (&variant, option.decision) {
-                (ApprovalVariant::Exec { id, command }, decision) => {
+                (ApprovalVariant::Exec { id, command, .. }, ApprovalDecision::Review(decision)) => {
                     self.handle_exec_decision(id, command, decision);
                 }
-                (ApprovalVariant::ApplyPatch { id, .. }, decision) => {
+                (
+                    ApprovalVariant::Exec {
+                        id,
+                        command,
+                        always_allow: Some(rule),
+                    },
+                    ApprovalDecision::AlwaysAllow,
+                ) => {
+                    self.app_event_tx
+                        .send(AppEvent::CodexOp(Op::AddApprovalRule {
+                            rule: rule.clone(),
+                        }));
+                    self.handle_exec_decision(id, command, ReviewDecision::Approved);
+                }
+                (ApprovalVariant::ApplyPatch { id, .. }, ApprovalDecision::Review(decision)) => {
                     self.handle_patch_decision(id, decision);
                 }
+                (
+                    ApprovalVariant::McpElicitation {
+                        server_name,
+                        request_id,
+                        ..
+                    },
+                    ApprovalDecision::Elicitation(decision),
+                ) => {
+                    self.handle_elicitation_decision(server_name, request_id, decision);
+                }
+                (
+                    ApprovalVariant::WriteScope { id, .. },
+                    ApprovalDecision::WriteScope(decision),
+                ) => {
+                    self.handle_write_scope_decision(id, decision);
+                }
+                _ => {}
             }
         }
 
@@ -179,6 +247,31 @@ impl ApprovalOverlay {
         }));
     }
 
+    fn handle_write_scope_decision(&self, id: &str, decision: WriteScopeDecision) {
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::WriteScopeApproval {
+                id: id.to_string(),
+                decision,
+            }));
+    }
+
+    fn handle_elicitation_decision(
+        &self,
+        server_name: &str,
+        request_id: &RequestId,
+        decision: ElicitationAction,
+    ) {
+        let content = (decision == ElicitationAction::Accept)
+            .then(|| serde_json::Value::Object(Default::default()));
+        self.app_event_tx
+            .send(AppEvent::CodexOp(Op::ResolveElicitation {
+                server_name: server_name.to_string(),
+                request_id: request_id.clone(),
+                decision,
+                content,
+            }));
+    }
+
     fn advance_queue(&mut self) {
         if let Some(next) = self.queue.pop() {
             self.set_current(next);
@@ -238,12 +331,26 @@ impl BottomPaneView for ApprovalOverlay {
             && let Some(variant) = self.current_variant.as_ref()
         {
             match &variant {
-                ApprovalVariant::Exec { id, command } => {
+                ApprovalVariant::Exec { id, command, .. } => {
                     self.handle_exec_decision(id, command, ReviewDecision::Abort);
                 }
                 ApprovalVariant::ApplyPatch { id, .. } => {
                     self.handle_patch_decision(id, ReviewDecision::Abort);
                 }
+                ApprovalVariant::McpElicitation {
+                    server_name,
+                    request_id,
+                    ..
+                } => {
+                    self.handle_elicitation_decision(
+                        server_name,
+                        request_id,
+                        ElicitationAction::Cancel,
+                    );
+                }
+                ApprovalVariant::WriteScope { id, .. } => {
+                    self.handle_write_scope_decision(id, WriteScopeDecision::Deny);
+                }
             }
         }
         self.queue.clear();
@@ -291,6 +398,7 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                 command,
                 reason,
                 risk,
+                proposed_rules,
             } => {
                 let reason = reason.filter(|item| !item.is_empty());
                 let has_reason = reason.is_some();
@@ -310,7 +418,11 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                 }
                 header.extend(full_cmd_lines);
                 Self {
-                    variant: ApprovalVariant::Exec { id, command },
+                    variant: ApprovalVariant::Exec {
+                        id,
+                        command,
+                        always_allow: rule_to_offer(proposed_rules),
+                    },
                     header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
                 }
             }
@@ -319,6 +431,7 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                 reason,
                 cwd,
                 changes,
+                file_statuses,
             } => {
                 let mut header: Vec<Box<dyn Renderable>> = Vec::new();
                 if let Some(reason) = reason
@@ -330,16 +443,108 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     ));
                     header.push(Box::new(Line::from("")));
                 }
+                let warnings = render_patch_status_lines(&file_statuses, &cwd);
+                if !warnings.is_empty() {
+                    header.push(Box::new(
+                        Paragraph::new(warnings).wrap(Wrap { trim: false }),
+                    ));
+                    header.push(Box::new(Line::from("")));
+                }
                 header.push(DiffSummary::new(changes, cwd).into());
                 Self {
                     variant: ApprovalVariant::ApplyPatch { id },
                     header: Box::new(ColumnRenderable::with(header)),
                 }
             }
+            ApprovalRequest::McpElicitation {
+                server_name,
+                request_id,
+                message,
+                can_accept,
+            } => {
+                let mut header = vec![Line::from(message)];
+                if !can_accept {
+                    header.push(Line::from(""));
+                    header.push(
+                        "This request needs input fields, which are not supported here yet."
+                            .dim()
+                            .into(),
+                    );
+                }
+                Self {
+                    variant: ApprovalVariant::McpElicitation {
+                        server_name,
+                        request_id,
+                        can_accept,
+                    },
+                    header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
+                }
+            }
+            ApprovalRequest::WriteScope {
+                id,
+                root,
+                paths,
+                command,
+                reason,
+            } => {
+                let mut header: Vec<Line<'static>> = Vec::new();
+                if let Some(reason) = reason.filter(|reason| !reason.is_empty()) {
+                    header.push(Line::from(vec!["Reason: ".into(), reason.italic()]));
+                    header.push(Line::from(""));
+                }
+                if let Some(command) = command {
+                    let full_cmd = strip_bash_lc_and_escape(&command);
+                    let mut full_cmd_lines = highlight_bash_to_lines(&full_cmd);
+                    if let Some(first) = full_cmd_lines.first_mut() {
+                        first.spans.insert(0, Span::from("$ "));
+                    }
+                    header.extend(full_cmd_lines);
+                    header.push(Line::from(""));
+                }
+                header.push("Outside the writable roots:".dim().into());
+                header.extend(
+                    paths
+                        .iter()
+                        .map(|path| Line::from(format!("  {}", path.display()))),
+                );
+                Self {
+                    variant: ApprovalVariant::WriteScope { id, root },
+                    header: Box::new(Paragraph::new(header).wrap(Wrap { trim: false })),
+                }
+            }
         }
     }
 }
 
+fn render_patch_status_lines(
+    file_statuses: &HashMap<PathBuf, PatchFileStatus>,
+    cwd: &Path,
+) -> Vec<Line<'static>> {
+    let mut entries: Vec<_> = file_statuses
+        .iter()
+        .filter(|(_, status)| !matches!(status, PatchFileStatus::AppliesCleanly))
+        .collect();
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+    entries
+        .into_iter()
+        .map(|(path, status)| {
+            let path = display_path_for(path, cwd);
+            match status {
+                PatchFileStatus::Conflict { reason } => Line::from(vec![
+                    "⚠ ".red().bold(),
+                    path.bold(),
+                    format!(" will not apply: {reason}").into(),
+                ]),
+                _ => Line::from(vec![
+                    "⚠ ".cyan().bold(),
+                    path.bold(),
+                    " changed since Codex last read it; approving may overwrite those edits".into(),
+                ]),
+            }
+        })
+        .collect()
+}
+
 fn render_risk_lines(risk: &SandboxCommandAssessment) -> Vec<Line<'static>> {
     let level_span = match risk.risk_level {
         SandboxRiskLevel::Low => "LOW".green().bold(),
@@ -364,14 +569,38 @@ fn render_risk_lines(risk: &SandboxCommandAssessment) -> Vec<Line<'static>> {
 
 #[derive(Clone)]
 enum ApprovalVariant {
-    Exec { id: String, command: Vec<String> },
-    ApplyPatch { id: String },
+    Exec {
+        id: String,
+        command: Vec<String>,
+        always_allow: Option<ApprovalRule>,
+    },
+    ApplyPatch {
+        id: String,
+    },
+    McpElicitation {
+        server_name: String,
+        request_id: RequestId,
+        can_accept: bool,
+    },
+    WriteScope {
+        id: String,
+        root: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum ApprovalDecision {
+    Review(ReviewDecision),
+    /// Approve and remember the variant's `always_allow` rule for the project.
+    AlwaysAllow,
+    Elicitation(ElicitationAction),
+    WriteScope(WriteScopeDecision),
 }
 
 #[derive(Clone)]
 struct ApprovalOption {
     label: String,
-    decision: ReviewDecision,
+    decision: ApprovalDecision,
     display_shortcut: Option<KeyBinding>,
     additional_shortcuts: Vec<KeyBinding>,
 }
@@ -384,40 +613,125 @@ impl ApprovalOption {
     }
 }
 
-fn exec_options() -> Vec<ApprovalOption> {
-    vec![
+fn exec_options(always_allow: Option<&ApprovalRule>) -> Vec<ApprovalOption> {
+    let mut options = vec![
         ApprovalOption {
             label: "Yes, proceed".to_string(),
-            decision: ReviewDecision::Approved,
+            decision: ApprovalDecision::Review(ReviewDecision::Approved),
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
         },
         ApprovalOption {
             label: "Yes, and don't ask again for this command".to_string(),
-            decision: ReviewDecision::ApprovedForSession,
+            decision: ApprovalDecision::Review(ReviewDecision::ApprovedForSession),
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
         },
+    ];
+    if let Some(rule) = always_allow {
+        options.push(ApprovalOption {
+            label: always_allow_label(rule),
+            decision: ApprovalDecision::AlwaysAllow,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('p'))],
+        });
+    }
+    options.push(ApprovalOption {
+        label: "No, and tell Codex what to do differently".to_string(),
+        decision: ApprovalDecision::Review(ReviewDecision::Abort),
+        display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+    });
+    options
+}
+
+/// Offer the program+subcommand rule when core proposed one, since it covers
+/// the common "run this tool again with different flags" case.
+fn rule_to_offer(proposed_rules: Vec<ApprovalRule>) -> Option<ApprovalRule> {
+    let subcommand_rule = proposed_rules
+        .iter()
+        .find(|rule| matches!(rule, ApprovalRule::ProgramSubcommand { .. }))
+        .cloned();
+    subcommand_rule.or_else(|| proposed_rules.into_iter().next())
+}
+
+fn always_allow_label(rule: &ApprovalRule) -> String {
+    match rule {
+        ApprovalRule::ExactCommand { .. } => {
+            "Yes, and always allow this command in this project".to_string()
+        }
+        ApprovalRule::ProgramSubcommand {
+            program,
+            subcommand,
+        } => format!("Yes, and always allow `{program} {subcommand}` in this project"),
+        ApprovalRule::PathPrefix { path } => format!(
+            "Yes, and always allow programs under {} in this project",
+            path.display()
+        ),
+    }
+}
+
+fn patch_options() -> Vec<ApprovalOption> {
+    vec![
+        ApprovalOption {
+            label: "Yes, proceed".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Approved),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+        },
         ApprovalOption {
             label: "No, and tell Codex what to do differently".to_string(),
-            decision: ReviewDecision::Abort,
+            decision: ApprovalDecision::Review(ReviewDecision::Abort),
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
         },
     ]
 }
 
-fn patch_options() -> Vec<ApprovalOption> {
+fn elicitation_options(can_accept: bool) -> Vec<ApprovalOption> {
+    let mut options = Vec::new();
+    if can_accept {
+        options.push(ApprovalOption {
+            label: "Yes, continue".to_string(),
+            decision: ApprovalDecision::Elicitation(ElicitationAction::Accept),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+        });
+    }
+    options.extend([
+        ApprovalOption {
+            label: "No, decline".to_string(),
+            decision: ApprovalDecision::Elicitation(ElicitationAction::Decline),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
+        },
+        ApprovalOption {
+            label: "Cancel".to_string(),
+            decision: ApprovalDecision::Elicitation(ElicitationAction::Cancel),
+            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+            additional_shortcuts: Vec::new(),
+        },
+    ]);
+    options
+}
+
+fn write_scope_options() -> Vec<ApprovalOption> {
     vec![
         ApprovalOption {
-            label: "Yes, proceed".to_string(),
-            decision: ReviewDecision::Approved,
+            label: "Yes, for this session".to_string(),
+            decision: ApprovalDecision::WriteScope(WriteScopeDecision::GrantForSession),
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
         },
         ApprovalOption {
-            label: "No, and tell Codex what to do differently".to_string(),
-            decision: ReviewDecision::Abort,
+            label: "Yes, and always allow it in this project".to_string(),
+            decision: ApprovalDecision::WriteScope(WriteScopeDecision::GrantForProject),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('p'))],
+        },
+        ApprovalOption {
+            label: "No, keep it read-only".to_string(),
+            decision: ApprovalDecision::WriteScope(WriteScopeDecision::Deny),
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
         },
@@ -437,6 +751,7 @@ mod tests {
             command: vec!["echo".to_string(), "hi".to_string()],
             reason: Some("reason".to_string()),
             risk: None,
+            proposed_rules: Vec::new(),
         }
     }
 
@@ -479,6 +794,7 @@ mod tests {
             command,
             reason: None,
             risk: None,
+            proposed_rules: Vec::new(),
         };
 
         let view = ApprovalOverlay::new(exec_request, tx);
@@ -500,6 +816,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn patch_header_warns_about_stale_files() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let cwd = PathBuf::from("/repo");
+        let path = cwd.join("notes.txt");
+        let request = ApprovalRequest::ApplyPatch {
+            id: "patch".into(),
+            reason: None,
+            cwd,
+            changes: HashMap::from([(
+                path.clone(),
+                FileChange::Add {
+                    content: "hello\n".into(),
+                },
+            )]),
+            file_statuses: HashMap::from([(path, PatchFileStatus::ModifiedSinceRead)]),
+        };
+
+        let view = ApprovalOverlay::new(request, tx);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 100, view.desired_height(100)));
+        view.render(Rect::new(0, 0, 100, view.desired_height(100)), &mut buf);
+
+        let rendered: Vec<String> = (0..buf.area.height)
+            .map(|row| {
+                (0..buf.area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect()
+            })
+            .collect();
+        assert!(
+            rendered
+                .iter()
+                .any(|line| line.contains("notes.txt changed since Codex last read it")),
+            "expected stale-file warning, got {rendered:?}"
+        );
+    }
+
     #[test]
     fn exec_history_cell_wraps_with_two_space_indent() {
         let command = vec![
@@ -549,4 +903,77 @@ mod tests {
         }
         assert_eq!(decision, Some(ReviewDecision::ApprovedForSession));
     }
+
+    #[test]
+    fn always_allow_shortcut_remembers_rule_and_approves() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let rule = ApprovalRule::ProgramSubcommand {
+            program: "git".to_string(),
+            subcommand: "status".to_string(),
+        };
+        let request = ApprovalRequest::Exec {
+            id: "test".to_string(),
+            command: vec!["git".to_string(), "status".to_string()],
+            reason: None,
+            risk: None,
+            proposed_rules: vec![
+                ApprovalRule::ExactCommand {
+                    command: vec!["git".to_string(), "status".to_string()],
+                },
+                rule.clone(),
+            ],
+        };
+        let mut view = ApprovalOverlay::new(request, tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
+
+        let mut remembered = None;
+        let mut decision = None;
+        while let Ok(ev) = rx.try_recv() {
+            match ev {
+                AppEvent::CodexOp(Op::AddApprovalRule { rule }) => remembered = Some(rule),
+                AppEvent::CodexOp(Op::ExecApproval { decision: d, .. }) => decision = Some(d),
+                _ => {}
+            }
+        }
+        assert_eq!(remembered, Some(rule));
+        assert_eq!(decision, Some(ReviewDecision::Approved));
+    }
+
+    #[test]
+    fn elicitation_accept_sends_empty_content() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let request = ApprovalRequest::McpElicitation {
+            server_name: "docs".to_string(),
+            request_id: RequestId::Integer(3),
+            message: "Continue?".to_string(),
+            can_accept: true,
+        };
+        let mut view = ApprovalOverlay::new(request, tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+
+        let mut resolved = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::ResolveElicitation {
+                server_name,
+                request_id,
+                decision,
+                content,
+            }) = ev
+            {
+                resolved = Some((server_name, request_id, decision, content));
+                break;
+            }
+        }
+        assert_eq!(
+            resolved,
+            Some((
+                "docs".to_string(),
+                RequestId::Integer(3),
+                ElicitationAction::Accept,
+                Some(serde_json::json!({})),
+            ))
+        );
+    }
 }
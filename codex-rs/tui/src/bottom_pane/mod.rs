@@ -540,6 +540,7 @@ mod tests {
             command: vec!["echo".into(), "ok".into()],
             reason: None,
             risk: None,
+            proposed_rules: Vec::new(),
         }
     }
 
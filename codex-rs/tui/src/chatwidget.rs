@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -19,7 +20,14 @@ use codex_core::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_core::protocol::AgentReasoningRawContentEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::BackgroundTaskStatus;
+use codex_core::protocol::BackgroundTaskUpdateEvent;
+use codex_core::protocol::BudgetExhaustedEvent;
+use codex_core::protocol::CompactionTrigger;
+use codex_core::protocol::ContextCompactedEvent;
 use codex_core::protocol::DeprecationNoticeEvent;
+use codex_core::protocol::DiagnosticSeverity;
+use codex_core::protocol::ElicitationRequestEvent;
 use codex_core::protocol::ErrorEvent;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
@@ -28,30 +36,55 @@ use codex_core::protocol::ExecCommandBeginEvent;
 use codex_core::protocol::ExecCommandEndEvent;
 use codex_core::protocol::ExecCommandSource;
 use codex_core::protocol::ExitedReviewModeEvent;
+use codex_core::protocol::FileLockWaitEvent;
+use codex_core::protocol::InstructionsChangedEvent;
 use codex_core::protocol::ListCustomPromptsResponseEvent;
 use codex_core::protocol::McpListToolsResponseEvent;
+use codex_core::protocol::McpServerStatus;
+use codex_core::protocol::McpServerStatusChangedEvent;
 use codex_core::protocol::McpStartupCompleteEvent;
 use codex_core::protocol::McpStartupStatus;
 use codex_core::protocol::McpStartupUpdateEvent;
 use codex_core::protocol::McpToolCallBeginEvent;
 use codex_core::protocol::McpToolCallEndEvent;
+use codex_core::protocol::McpToolCallProgressEvent;
+use codex_core::protocol::McpToolConflictsEvent;
+use codex_core::protocol::NetworkHostBlockedEvent;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
+use codex_core::protocol::PatchContentResponseEvent;
+use codex_core::protocol::PatchDiagnosticsEvent;
+use codex_core::protocol::ProfileChangedEvent;
+use codex_core::protocol::ProviderHealthChangedEvent;
+use codex_core::protocol::ProviderHealthStatus;
+use codex_core::protocol::PullRequestCreatedEvent;
 use codex_core::protocol::RateLimitSnapshot;
+use codex_core::protocol::RateLimitedEvent;
 use codex_core::protocol::ReviewRequest;
+use codex_core::protocol::SecretsRedactedEvent;
 use codex_core::protocol::StreamErrorEvent;
+use codex_core::protocol::SubAgentStatus;
+use codex_core::protocol::SubAgentUpdateEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
+use codex_core::protocol::ToolsChangedEvent;
 use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::TurnCommit;
 use codex_core::protocol::TurnDiffEvent;
 use codex_core::protocol::UndoCompletedEvent;
 use codex_core::protocol::UndoStartedEvent;
+use codex_core::protocol::UserInputRequestEvent;
 use codex_core::protocol::UserMessageEvent;
 use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WarningEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::protocol::WorkspaceInstructionProposedEvent;
+use codex_core::protocol::WorktreeClosedEvent;
+use codex_core::protocol::WorktreeDiffEvent;
+use codex_core::protocol::WorktreeOutcome;
+use codex_core::protocol::WriteScopeApprovalRequestEvent;
 use codex_protocol::ConversationId;
 use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::user_input::UserInput;
@@ -115,7 +148,9 @@ use self::session_header::SessionHeader;
 use crate::streaming::controller::StreamController;
 use std::path::Path;
 
+use chrono::DateTime;
 use chrono::Local;
+use chrono::Utc;
 use codex_common::approval_presets::ApprovalPreset;
 use codex_common::approval_presets::builtin_approval_presets;
 use codex_common::model_presets::ModelPreset;
@@ -265,6 +300,8 @@ pub(crate) struct ChatWidget {
     // Stream lifecycle controller
     stream_controller: Option<StreamController>,
     running_commands: HashMap<String, RunningCommand>,
+    // Edit approval held back until the bodies of its deferred files arrive
+    pending_patch_approval: Option<PendingPatchApproval>,
     task_complete_pending: bool,
     mcp_startup_status: Option<HashMap<String, McpStartupStatus>>,
     // Queue of interruptive UI events deferred during an active write cycle
@@ -286,6 +323,8 @@ pub(crate) struct ChatWidget {
     suppress_session_configured_redraw: bool,
     // User messages queued while a turn is in progress
     queued_user_messages: VecDeque<UserMessage>,
+    // Call id of an `ask_user` question; the next submitted message answers it.
+    pending_user_input: Option<String>,
     // Pending notification to show when unfocused on next Draw
     pending_notification: Option<Notification>,
     // Simple review mode flag; used to adjust layout and banners.
@@ -302,6 +341,14 @@ pub(crate) struct ChatWidget {
     current_rollout_path: Option<PathBuf>,
 }
 
+/// An edit approval whose patch was too large to inline; its files are fetched
+/// with `Op::GetPatchContent` before the approval is shown.
+struct PendingPatchApproval {
+    id: String,
+    ev: ApplyPatchApprovalRequestEvent,
+    awaiting: HashSet<PathBuf>,
+}
+
 struct UserMessage {
     text: String,
     image_paths: Vec<PathBuf>,
@@ -475,6 +522,7 @@ impl ChatWidget {
     fn on_task_complete(&mut self, last_agent_message: Option<String>) {
         // If a stream is currently active, finalize it.
         self.flush_answer_stream_with_separator();
+        self.pending_user_input = None;
         // Mark task stopped and request redraw now that all content is in history.
         self.bottom_pane.set_task_running(false);
         self.running_commands.clear();
@@ -673,12 +721,82 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_tools_changed(&mut self, ev: ToolsChangedEvent) {
+        let ToolsChangedEvent {
+            server,
+            added,
+            removed,
+            updated,
+        } = ev;
+        let mut parts = Vec::new();
+        if !added.is_empty() {
+            parts.push(format!("added {}", added.join(", ")));
+        }
+        if !removed.is_empty() {
+            parts.push(format!("removed {}", removed.join(", ")));
+        }
+        if !updated.is_empty() {
+            parts.push(format!("updated {}", updated.join(", ")));
+        }
+        self.add_info_message(
+            format!(
+                "MCP server '{server}' changed its tools: {}",
+                parts.join("; ")
+            ),
+            None,
+        );
+    }
+
+    fn on_mcp_tool_conflicts(&mut self, ev: McpToolConflictsEvent) {
+        for conflict in ev.conflicts {
+            let dropped = conflict
+                .dropped
+                .iter()
+                .map(|tool| format!("'{}' from '{}'", tool.tool, tool.server))
+                .collect::<Vec<_>>()
+                .join(", ");
+            self.on_warning(format!(
+                "MCP tool name {} is used by '{}' from '{}'; skipped {dropped}. Set `tool_aliases` to rename one.",
+                conflict.name, conflict.kept.tool, conflict.kept.server
+            ));
+        }
+    }
+
+    fn on_mcp_server_status_changed(&mut self, ev: McpServerStatusChangedEvent) {
+        let McpServerStatusChangedEvent { server, status } = ev;
+        match status {
+            McpServerStatus::Healthy => {
+                self.add_info_message(format!("MCP server '{server}' is healthy again"), None)
+            }
+            McpServerStatus::Unhealthy {
+                consecutive_failures,
+                error,
+            } => self.on_warning(format!(
+                "MCP server '{server}' missed {consecutive_failures} pings: {error}"
+            )),
+            McpServerStatus::Restarting { attempt } => self.add_info_message(
+                format!("Restarting MCP server '{server}' (attempt {attempt})"),
+                None,
+            ),
+            McpServerStatus::Failed { error } => {
+                self.on_warning(format!("MCP server '{server}' is unavailable: {error}"))
+            }
+            McpServerStatus::Starting => {
+                self.add_info_message(format!("Starting MCP server '{server}'"), None)
+            }
+            // Stopping and starting on demand is routine; only surface the
+            // start, since it can delay the tool call that triggered it.
+            McpServerStatus::Ready | McpServerStatus::Stopped { .. } => {}
+        }
+    }
+
     /// Handle a turn aborted due to user interrupt (Esc).
     /// When there are queued user messages, restore them into the composer
     /// separated by newlines rather than auto‑submitting the next one.
     fn on_interrupted_turn(&mut self, reason: TurnAbortReason) {
         // Finalize, log a gentle prompt, and clear running state.
         self.finalize_turn();
+        self.pending_user_input = None;
 
         if reason != TurnAbortReason::ReviewEnded {
             self.add_to_history(history_cell::new_error_event(
@@ -733,6 +851,60 @@ impl ChatWidget {
         );
     }
 
+    fn on_write_scope_approval_request(&mut self, id: String, ev: WriteScopeApprovalRequestEvent) {
+        self.flush_answer_stream_with_separator();
+        self.bottom_pane
+            .push_approval_request(ApprovalRequest::WriteScope {
+                id,
+                root: ev.root,
+                paths: ev.paths,
+                command: ev.command,
+                reason: ev.reason,
+            });
+        self.request_redraw();
+    }
+
+    fn on_user_input_request(&mut self, ev: UserInputRequestEvent, from_replay: bool) {
+        self.flush_answer_stream_with_separator();
+        let hint = (!from_replay).then(|| "Your next message answers it.".to_string());
+        self.add_info_message(format!("Codex asks: {}", ev.question), hint);
+        if !from_replay {
+            self.pending_user_input = Some(ev.call_id);
+        }
+    }
+
+    /// Send `text` as the answer to the pending `ask_user` question.
+    fn answer_user_input(&mut self, text: String) {
+        let Some(call_id) = self.pending_user_input.take() else {
+            return;
+        };
+        self.add_to_history(history_cell::new_user_prompt(text.clone()));
+        self.submit_op(Op::UserInputAnswer {
+            call_id,
+            answer: Some(text),
+        });
+        self.request_redraw();
+    }
+
+    fn on_elicitation_request(&mut self, ev: ElicitationRequestEvent) {
+        self.flush_answer_stream_with_separator();
+        // Field entry is not supported yet, so only requests with nothing
+        // required can be accepted from here.
+        let can_accept = ev
+            .requested_schema
+            .required
+            .as_ref()
+            .is_none_or(Vec::is_empty);
+        self.bottom_pane
+            .push_approval_request(ApprovalRequest::McpElicitation {
+                server_name: ev.server_name,
+                request_id: ev.id,
+                message: ev.message,
+                can_accept,
+            });
+        self.request_redraw();
+    }
+
     fn on_exec_command_begin(&mut self, ev: ExecCommandBeginEvent) {
         self.flush_answer_stream_with_separator();
         let ev2 = ev.clone();
@@ -770,6 +942,49 @@ impl ChatWidget {
         );
     }
 
+    fn on_patch_diagnostics(&mut self, ev: PatchDiagnosticsEvent) {
+        let diagnostics = ev
+            .files
+            .iter()
+            .flat_map(|file| {
+                file.diagnostics
+                    .iter()
+                    .filter(|diagnostic| {
+                        matches!(
+                            diagnostic.severity,
+                            DiagnosticSeverity::Error | DiagnosticSeverity::Warning
+                        )
+                    })
+                    .map(move |diagnostic| (file, diagnostic))
+            })
+            .collect::<Vec<_>>();
+        if diagnostics.is_empty() {
+            return;
+        }
+        let errors = diagnostics
+            .iter()
+            .filter(|(_, diagnostic)| diagnostic.severity == DiagnosticSeverity::Error)
+            .count();
+        let warnings = diagnostics.len() - errors;
+        let hint = diagnostics
+            .iter()
+            .take(3)
+            .map(|(file, diagnostic)| {
+                let path = file
+                    .path
+                    .strip_prefix(&self.config.cwd)
+                    .unwrap_or(&file.path)
+                    .display();
+                format!("{path}:{}: {}", diagnostic.line, diagnostic.message)
+            })
+            .collect::<Vec<_>>()
+            .join("; ");
+        self.add_info_message(
+            format!("Language servers report {errors} error(s) and {warnings} warning(s) in the patched files"),
+            Some(hint),
+        );
+    }
+
     fn on_exec_command_end(&mut self, ev: ExecCommandEndEvent) {
         let ev2 = ev.clone();
         self.defer_or_handle(|q| q.push_exec_end(ev), |s| s.handle_exec_end_now(ev2));
@@ -780,6 +995,20 @@ impl ChatWidget {
         self.defer_or_handle(|q| q.push_mcp_begin(ev), |s| s.handle_mcp_begin_now(ev2));
     }
 
+    fn on_mcp_tool_call_progress(&mut self, ev: McpToolCallProgressEvent) {
+        let amount = match ev.total {
+            Some(total) if total > 0.0 => {
+                format!("{:.0}%", (ev.progress / total * 100.0).min(100.0))
+            }
+            _ => ev.progress.to_string(),
+        };
+        let header = match ev.message.filter(|message| !message.is_empty()) {
+            Some(message) => format!("{message} ({amount})"),
+            None => format!("Running MCP tool ({amount})"),
+        };
+        self.set_status_header(header);
+    }
+
     fn on_mcp_tool_call_end(&mut self, ev: McpToolCallEndEvent) {
         let ev2 = ev.clone();
         self.defer_or_handle(|q| q.push_mcp_end(ev), |s| s.handle_mcp_end_now(ev2));
@@ -824,6 +1053,268 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_context_compacted(&mut self, ev: ContextCompactedEvent) {
+        // Manual compaction already reports completion as an agent message.
+        if ev.trigger == CompactionTrigger::Auto {
+            self.add_info_message(
+                "Context compacted automatically to make room in the model's context window."
+                    .to_string(),
+                None,
+            );
+        }
+    }
+
+    fn on_provider_health_changed(&mut self, ev: ProviderHealthChangedEvent) {
+        let ProviderHealthChangedEvent {
+            provider,
+            status,
+            message,
+            retry_in_ms,
+        } = ev;
+        match status {
+            ProviderHealthStatus::Degraded => {
+                let retry = retry_in_ms
+                    .map(|ms| format!(" Retrying in {}s.", ms.div_ceil(1000)))
+                    .unwrap_or_default();
+                self.on_warning(format!(
+                    "{provider} is failing repeatedly; Codex will keep retrying instead of failing the turn.{retry}"
+                ));
+                if let Some(message) = message {
+                    debug!("provider degraded: {message}");
+                }
+            }
+            ProviderHealthStatus::Healthy => {
+                self.add_info_message(format!("{provider} is responding again."), None);
+            }
+        }
+    }
+
+    /// Asks the user whether to save an instruction the model proposed. It is
+    /// only written to the workspace when they confirm.
+    fn on_workspace_instruction_proposed(&mut self, ev: WorkspaceInstructionProposedEvent) {
+        let WorkspaceInstructionProposedEvent { instruction, .. } = ev;
+        let save_actions: Vec<SelectionAction> = vec![Box::new({
+            let instruction = instruction.clone();
+            move |tx| {
+                tx.send(AppEvent::SaveWorkspaceInstruction {
+                    instruction: instruction.clone(),
+                });
+            }
+        })];
+
+        let items = vec![
+            SelectionItem {
+                name: "Save for this project".to_string(),
+                description: Some("Follow it in future sessions in this workspace".to_string()),
+                actions: save_actions,
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+            SelectionItem {
+                name: "Don't save".to_string(),
+                description: Some("Only follow it for the rest of this session".to_string()),
+                actions: Vec::new(),
+                dismiss_on_select: true,
+                ..Default::default()
+            },
+        ];
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Save workspace instruction?".to_string()),
+            subtitle: Some(instruction),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
+    fn on_rate_limited(&mut self, ev: RateLimitedEvent) {
+        let RateLimitedEvent {
+            resume_at,
+            window,
+            used_percent,
+        } = ev;
+        let now = Local::now();
+        let resume = DateTime::<Utc>::from_timestamp(resume_at, 0)
+            .map(|dt| crate::status::format_reset_timestamp(dt.with_timezone(&Local), now))
+            .unwrap_or_else(|| "the limit resets".to_string());
+        self.on_warning(format!(
+            "You've used {used_percent:.0}% of your {window} rate limit. Codex will wait until {resume} before sending the next request."
+        ));
+    }
+
+    fn on_background_task_update(&mut self, ev: BackgroundTaskUpdateEvent) {
+        let BackgroundTaskUpdateEvent {
+            task_id,
+            command,
+            status,
+            exit_code,
+        } = ev;
+        let message = match (status, exit_code) {
+            (BackgroundTaskStatus::Running, _) => {
+                format!("Started background task {task_id}: {command}")
+            }
+            (BackgroundTaskStatus::Exited, Some(code)) => {
+                format!("Background task {task_id} exited with code {code}: {command}")
+            }
+            (BackgroundTaskStatus::Exited, None) => {
+                format!("Background task {task_id} exited: {command}")
+            }
+            (BackgroundTaskStatus::Terminated, _) => {
+                format!("Terminated background task {task_id}: {command}")
+            }
+        };
+        self.add_info_message(message, None);
+    }
+
+    fn on_sub_agent_update(&mut self, ev: SubAgentUpdateEvent) {
+        let SubAgentUpdateEvent {
+            model,
+            status,
+            message,
+            ..
+        } = ev;
+        match status {
+            SubAgentStatus::Running => {
+                self.add_info_message(format!("Started a {model} sub-agent"), None);
+            }
+            SubAgentStatus::Completed => {
+                self.add_info_message(format!("{model} sub-agent finished"), message);
+            }
+            SubAgentStatus::Failed => {
+                self.add_error_message(format!(
+                    "{model} sub-agent failed: {}",
+                    message.unwrap_or_default()
+                ));
+            }
+        }
+    }
+
+    fn on_turn_committed(&mut self, turn_commit: TurnCommit) {
+        let TurnCommit {
+            commit,
+            branch,
+            message,
+        } = turn_commit;
+        let short = commit.get(..7).unwrap_or(&commit);
+        let subject = message.lines().next().unwrap_or_default().to_string();
+        self.add_info_message(format!("Committed {short} on {branch}"), Some(subject));
+    }
+
+    fn on_worktree_diff(&mut self, ev: WorktreeDiffEvent) {
+        let WorktreeDiffEvent {
+            worktree_path,
+            branch,
+            unified_diff,
+            ..
+        } = ev;
+        let files = unified_diff
+            .lines()
+            .filter(|line| line.starts_with("diff --git "))
+            .count();
+        let hint = if files == 0 {
+            "no changes yet".to_string()
+        } else {
+            format!("{files} file(s) changed")
+        };
+        self.add_info_message(
+            format!("Worktree {} on {branch}", worktree_path.display()),
+            Some(hint),
+        );
+    }
+
+    fn on_worktree_closed(&mut self, ev: WorktreeClosedEvent) {
+        let WorktreeClosedEvent {
+            outcome,
+            conflicted_paths,
+            cwd,
+        } = ev;
+        let message = match outcome {
+            WorktreeOutcome::Merged => format!("Merged worktree changes into {}", cwd.display()),
+            WorktreeOutcome::Discarded => {
+                format!("Discarded worktree; now working in {}", cwd.display())
+            }
+        };
+        let hint = (!conflicted_paths.is_empty())
+            .then(|| format!("Resolve conflicts in: {}", conflicted_paths.join(", ")));
+        self.add_info_message(message, hint);
+    }
+
+    fn on_pull_request_created(&mut self, ev: PullRequestCreatedEvent) {
+        let PullRequestCreatedEvent {
+            url,
+            number,
+            title,
+            head,
+            base,
+            ..
+        } = ev;
+        self.add_info_message(
+            format!("Opened pull request #{number}: {title} ({head} into {base})"),
+            Some(url),
+        );
+    }
+
+    fn on_instructions_changed(&mut self, ev: InstructionsChangedEvent) {
+        let hint = (!ev.sources.is_empty()).then(|| {
+            ev.sources
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+        self.add_info_message("Instructions updated".to_string(), hint);
+    }
+
+    fn on_profile_changed(&mut self, ev: ProfileChangedEvent) {
+        let ProfileChangedEvent {
+            profile,
+            model,
+            model_provider_id,
+            reasoning_effort,
+            approval_policy,
+            sandbox_policy,
+            changes,
+        } = ev;
+        self.set_model(&model);
+        self.set_reasoning_effort(reasoning_effort);
+        self.set_approval_policy(approval_policy);
+        self.set_sandbox_policy(sandbox_policy);
+        self.config.model_provider_id = model_provider_id;
+        self.config.active_profile = Some(profile.clone());
+
+        let hint = if changes.is_empty() {
+            "no settings changed".to_string()
+        } else {
+            changes
+                .iter()
+                .map(|change| {
+                    format!(
+                        "{}: {} -> {}",
+                        change.setting, change.previous, change.current
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        self.add_info_message(format!("Switched to profile {profile}"), Some(hint));
+    }
+
+    fn on_secrets_redacted(&mut self, ev: SecretsRedactedEvent) {
+        let total: u32 = ev.counts.iter().map(|entry| entry.count).sum();
+        let hint = ev
+            .counts
+            .iter()
+            .map(|entry| format!("{} {}", entry.count, entry.kind))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let noun = if total == 1 { "secret" } else { "secrets" };
+        self.add_info_message(
+            format!("Hid {total} {noun} in tool output from the model"),
+            Some(hint),
+        );
+    }
+
     fn on_background_event(&mut self, message: String) {
         debug!("BackgroundEvent: {message}");
         self.bottom_pane.ensure_status_indicator();
@@ -1012,6 +1503,7 @@ impl ChatWidget {
             command: ev.command,
             reason: ev.reason,
             risk: ev.risk,
+            proposed_rules: ev.proposed_rules,
         };
         self.bottom_pane.push_approval_request(request);
         self.request_redraw();
@@ -1022,6 +1514,10 @@ impl ChatWidget {
         id: String,
         ev: ApplyPatchApprovalRequestEvent,
     ) {
+        if !ev.deferred_changes.is_empty() {
+            self.fetch_deferred_patch_changes(id, ev);
+            return;
+        }
         self.flush_answer_stream_with_separator();
 
         let request = ApprovalRequest::ApplyPatch {
@@ -1029,6 +1525,7 @@ impl ChatWidget {
             reason: ev.reason,
             changes: ev.changes.clone(),
             cwd: self.config.cwd.clone(),
+            file_statuses: ev.file_statuses,
         };
         self.bottom_pane.push_approval_request(request);
         self.request_redraw();
@@ -1038,6 +1535,46 @@ impl ChatWidget {
         });
     }
 
+    fn fetch_deferred_patch_changes(&mut self, id: String, mut ev: ApplyPatchApprovalRequestEvent) {
+        let awaiting: HashSet<PathBuf> = std::mem::take(&mut ev.deferred_changes)
+            .into_iter()
+            .map(|change| change.path)
+            .collect();
+        for path in &awaiting {
+            self.submit_op(Op::GetPatchContent {
+                call_id: ev.call_id.clone(),
+                path: path.clone(),
+            });
+        }
+        self.pending_patch_approval = Some(PendingPatchApproval { id, ev, awaiting });
+    }
+
+    fn on_patch_content_response(&mut self, event: PatchContentResponseEvent) {
+        let PatchContentResponseEvent {
+            call_id,
+            path,
+            change,
+        } = event;
+        let Some(pending) = self.pending_patch_approval.as_mut() else {
+            return;
+        };
+        if pending.ev.call_id != call_id || !pending.awaiting.remove(&path) {
+            return;
+        }
+        let Some(change) = change else {
+            // The approval was resolved (e.g. the turn was interrupted) before
+            // its files could be fetched, so there is nothing left to show.
+            self.pending_patch_approval = None;
+            return;
+        };
+        pending.ev.changes.insert(path, change);
+        if pending.awaiting.is_empty()
+            && let Some(PendingPatchApproval { id, ev, .. }) = self.pending_patch_approval.take()
+        {
+            self.handle_apply_patch_approval_now(id, ev);
+        }
+    }
+
     pub(crate) fn handle_exec_begin_now(&mut self, ev: ExecCommandBeginEvent) {
         // Ensure the status indicator is visible while the command runs.
         self.running_commands.insert(
@@ -1162,6 +1699,7 @@ impl ChatWidget {
             rate_limit_poller: None,
             stream_controller: None,
             running_commands: HashMap::new(),
+            pending_patch_approval: None,
             task_complete_pending: false,
             mcp_startup_status: None,
             interrupts: InterruptManager::new(),
@@ -1171,6 +1709,7 @@ impl ChatWidget {
             retry_status_header: None,
             conversation_id: None,
             queued_user_messages: VecDeque::new(),
+            pending_user_input: None,
             show_welcome_banner: true,
             suppress_session_configured_redraw: false,
             pending_notification: None,
@@ -1236,6 +1775,7 @@ impl ChatWidget {
             rate_limit_poller: None,
             stream_controller: None,
             running_commands: HashMap::new(),
+            pending_patch_approval: None,
             task_complete_pending: false,
             mcp_startup_status: None,
             interrupts: InterruptManager::new(),
@@ -1245,6 +1785,7 @@ impl ChatWidget {
             retry_status_header: None,
             conversation_id: None,
             queued_user_messages: VecDeque::new(),
+            pending_user_input: None,
             show_welcome_banner: true,
             suppress_session_configured_redraw: true,
             pending_notification: None,
@@ -1318,6 +1859,9 @@ impl ChatWidget {
             }
             _ => {
                 match self.bottom_pane.handle_key_event(key_event) {
+                    InputResult::Submitted(text) if self.pending_user_input.is_some() => {
+                        self.answer_user_input(text);
+                    }
                     InputResult::Submitted(text) => {
                         // If a task is running, queue the user input to be sent after the turn completes.
                         let user_message = UserMessage {
@@ -1481,6 +2025,8 @@ impl ChatWidget {
                         ]),
                         reason: None,
                         grant_root: Some(PathBuf::from("/tmp")),
+                        deferred_changes: Vec::new(),
+                        file_statuses: HashMap::new(),
                     }),
                 }));
             }
@@ -1646,7 +2192,14 @@ impl ChatWidget {
             }
             EventMsg::AgentReasoningSectionBreak(_) => self.on_reasoning_section_break(),
             EventMsg::TaskStarted(_) => self.on_task_started(),
-            EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+            EventMsg::TaskComplete(TaskCompleteEvent {
+                last_agent_message,
+                turn_commit,
+                ..
+            }) => {
+                if let Some(turn_commit) = turn_commit {
+                    self.on_turn_committed(turn_commit);
+                }
                 self.on_task_complete(last_agent_message)
             }
             EventMsg::TokenCount(ev) => {
@@ -1657,6 +2210,9 @@ impl ChatWidget {
             EventMsg::Error(ErrorEvent { message, .. }) => self.on_error(message),
             EventMsg::McpStartupUpdate(ev) => self.on_mcp_startup_update(ev),
             EventMsg::McpStartupComplete(ev) => self.on_mcp_startup_complete(ev),
+            EventMsg::McpServerStatusChanged(ev) => self.on_mcp_server_status_changed(ev),
+            EventMsg::ToolsChanged(ev) => self.on_tools_changed(ev),
+            EventMsg::McpToolConflicts(ev) => self.on_mcp_tool_conflicts(ev),
             EventMsg::TurnAborted(ev) => match ev.reason {
                 TurnAbortReason::Interrupted => {
                     self.on_interrupted_turn(ev.reason);
@@ -1676,22 +2232,61 @@ impl ChatWidget {
             EventMsg::ApplyPatchApprovalRequest(ev) => {
                 self.on_apply_patch_approval_request(id.unwrap_or_default(), ev)
             }
+            EventMsg::WriteScopeApprovalRequest(ev) => {
+                self.on_write_scope_approval_request(id.unwrap_or_default(), ev)
+            }
+            EventMsg::ElicitationRequest(ev) => self.on_elicitation_request(ev),
+            EventMsg::UserInputRequest(ev) => self.on_user_input_request(ev, from_replay),
             EventMsg::ExecCommandBegin(ev) => self.on_exec_command_begin(ev),
             EventMsg::ExecCommandOutputDelta(delta) => self.on_exec_command_output_delta(delta),
             EventMsg::PatchApplyBegin(ev) => self.on_patch_apply_begin(ev),
             EventMsg::PatchApplyEnd(ev) => self.on_patch_apply_end(ev),
+            EventMsg::PatchDiagnostics(ev) => self.on_patch_diagnostics(ev),
+            EventMsg::FileLockWait(FileLockWaitEvent { paths, .. }) => {
+                self.on_background_event(format!(
+                    "Waiting for another tool call to finish writing {}",
+                    paths
+                        .iter()
+                        .map(|path| display_path_for(path, &self.config.cwd))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ))
+            }
             EventMsg::ExecCommandEnd(ev) => self.on_exec_command_end(ev),
             EventMsg::ViewImageToolCall(ev) => self.on_view_image_tool_call(ev),
             EventMsg::McpToolCallBegin(ev) => self.on_mcp_tool_call_begin(ev),
+            EventMsg::McpToolCallProgress(ev) => self.on_mcp_tool_call_progress(ev),
             EventMsg::McpToolCallEnd(ev) => self.on_mcp_tool_call_end(ev),
             EventMsg::WebSearchBegin(ev) => self.on_web_search_begin(ev),
             EventMsg::WebSearchEnd(ev) => self.on_web_search_end(ev),
             EventMsg::GetHistoryEntryResponse(ev) => self.on_get_history_entry_response(ev),
+            EventMsg::PatchContentResponse(ev) => self.on_patch_content_response(ev),
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
             EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
             EventMsg::DeprecationNotice(ev) => self.on_deprecation_notice(ev),
+            EventMsg::NetworkHostBlocked(NetworkHostBlockedEvent { host, port }) => self.on_warning(
+                format!(
+                    "Blocked network access to {host}:{port}. Add it to network_allowed_hosts for this project to allow it."
+                ),
+            ),
+            EventMsg::BudgetExhausted(BudgetExhaustedEvent { budget, used }) => self.on_warning(
+                format!(
+                    "This conversation used {used} of its {budget}-token budget. New messages are refused until the budget is raised."
+                ),
+            ),
+            EventMsg::ContextCompacted(ev) => self.on_context_compacted(ev),
+            EventMsg::ProviderHealthChanged(ev) => self.on_provider_health_changed(ev),
+            EventMsg::RateLimited(ev) => self.on_rate_limited(ev),
+            EventMsg::BackgroundTaskUpdate(ev) => self.on_background_task_update(ev),
+            EventMsg::SubAgentUpdate(ev) => self.on_sub_agent_update(ev),
+            EventMsg::WorktreeDiff(ev) => self.on_worktree_diff(ev),
+            EventMsg::WorktreeClosed(ev) => self.on_worktree_closed(ev),
+            EventMsg::PullRequestCreated(ev) => self.on_pull_request_created(ev),
+            EventMsg::InstructionsChanged(ev) => self.on_instructions_changed(ev),
+            EventMsg::ProfileChanged(ev) => self.on_profile_changed(ev),
+            EventMsg::SecretsRedacted(ev) => self.on_secrets_redacted(ev),
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 self.on_background_event(message)
             }
@@ -1709,7 +2304,17 @@ impl ChatWidget {
                 self.on_entered_review_mode(review_request)
             }
             EventMsg::ExitedReviewMode(review) => self.on_exited_review_mode(review),
+            EventMsg::WorkspaceInstructionProposed(ev) => {
+                if !from_replay {
+                    self.on_workspace_instruction_proposed(ev);
+                }
+            }
             EventMsg::RawResponseItem(_)
+            | EventMsg::McpListPromptsResponse(_)
+            | EventMsg::ListApprovalRulesResponse(_)
+            | EventMsg::McpResourceReadResponse(_)
+            | EventMsg::ThreadMetadataChanged(_)
+            | EventMsg::TurnHeartbeat(_)
             | EventMsg::ItemStarted(_)
             | EventMsg::ItemCompleted(_)
             | EventMsg::AgentMessageContentDelta(_)
@@ -2426,6 +3031,7 @@ impl ChatWidget {
         let describe_policy = |policy: &SandboxPolicy| match policy {
             SandboxPolicy::WorkspaceWrite { .. } => "Agent mode",
             SandboxPolicy::ReadOnly => "Read-Only mode",
+            SandboxPolicy::Audit => "Audit mode",
             _ => "Agent mode",
         };
         let mode_label = preset
@@ -2604,8 +3210,9 @@ impl ChatWidget {
     /// Set the sandbox policy in the widget's config copy.
     pub(crate) fn set_sandbox_policy(&mut self, policy: SandboxPolicy) {
         #[cfg(target_os = "windows")]
-        let should_clear_downgrade = !matches!(policy, SandboxPolicy::ReadOnly)
-            || codex_core::get_platform_sandbox().is_some();
+        let should_clear_downgrade =
+            !matches!(policy, SandboxPolicy::ReadOnly | SandboxPolicy::Audit)
+                || codex_core::get_platform_sandbox().is_some();
 
         self.config.sandbox_policy = policy;
 
@@ -40,6 +40,7 @@ pub(crate) fn spawn_agent(
                     msg: EventMsg::Error(ErrorEvent {
                         message,
                         http_status_code: None,
+                        info: err.error_info(),
                     }),
                 }));
                 app_event_tx_clone.send(AppEvent::ExitRequest);
@@ -18,6 +18,9 @@ use codex_core::protocol::AgentReasoningDeltaEvent;
 use codex_core::protocol::AgentReasoningEvent;
 use codex_core::protocol::ApplyPatchApprovalRequestEvent;
 use codex_core::protocol::BackgroundEventEvent;
+use codex_core::protocol::CodexErrorInfo;
+use codex_core::protocol::DeferredFileChange;
+use codex_core::protocol::DeferredFileChangeKind;
 use codex_core::protocol::Event;
 use codex_core::protocol::EventMsg;
 use codex_core::protocol::ExecApprovalRequestEvent;
@@ -29,6 +32,7 @@ use codex_core::protocol::FileChange;
 use codex_core::protocol::Op;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
+use codex_core::protocol::PatchContentResponseEvent;
 use codex_core::protocol::RateLimitWindow;
 use codex_core::protocol::ReviewCodeLocation;
 use codex_core::protocol::ReviewFinding;
@@ -45,6 +49,7 @@ use codex_core::protocol::UndoCompletedEvent;
 use codex_core::protocol::UndoStartedEvent;
 use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WarningEvent;
+use codex_core::protocol::WorkspaceInstructionProposedEvent;
 use codex_protocol::ConversationId;
 use codex_protocol::parse_command::ParsedCommand;
 use codex_protocol::plan_tool::PlanItemArg;
@@ -199,6 +204,7 @@ fn exited_review_mode_emits_results_and_finishes() {
                 absolute_file_path: PathBuf::from("src/lib.rs"),
                 line_range: ReviewLineRange { start: 10, end: 12 },
             },
+            suggestion: None,
         }],
         overall_correctness: "needs work".to_string(),
         overall_explanation: "Investigate the failure".to_string(),
@@ -232,6 +238,8 @@ fn review_restores_context_window_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(pre_review_tokens, context_window)),
             rate_limits: None,
+            cost: None,
+            breakdown: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(30));
@@ -250,6 +258,8 @@ fn review_restores_context_window_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(review_tokens, context_window)),
             rate_limits: None,
+            cost: None,
+            breakdown: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(97));
@@ -279,6 +289,8 @@ fn token_count_none_resets_context_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(pre_compact_tokens, context_window)),
             rate_limits: None,
+            cost: None,
+            breakdown: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(30));
@@ -288,6 +300,8 @@ fn token_count_none_resets_context_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: None,
             rate_limits: None,
+            cost: None,
+            breakdown: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), None);
@@ -356,6 +370,7 @@ fn make_chatwidget_manual() -> (
         rate_limit_poller: None,
         stream_controller: None,
         running_commands: HashMap::new(),
+        pending_patch_approval: None,
         task_complete_pending: false,
         mcp_startup_status: None,
         interrupts: InterruptManager::new(),
@@ -367,6 +382,7 @@ fn make_chatwidget_manual() -> (
         frame_requester: FrameRequester::test_dummy(),
         show_welcome_banner: true,
         queued_user_messages: VecDeque::new(),
+        pending_user_input: None,
         suppress_session_configured_redraw: false,
         pending_notification: None,
         is_review_mode: false,
@@ -580,6 +596,7 @@ fn exec_approval_emits_proposed_command_and_decision_history() {
     // Trigger an exec approval request with a short, single-line command
     let ev = ExecApprovalRequestEvent {
         call_id: "call-short".into(),
+        item_id: "call-short".into(),
         turn_id: "turn-short".into(),
         command: vec!["bash".into(), "-lc".into(), "echo hello world".into()],
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
@@ -588,6 +605,7 @@ fn exec_approval_emits_proposed_command_and_decision_history() {
         ),
         risk: None,
         parsed_cmd: vec![],
+        proposed_rules: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-short".into(),
@@ -624,6 +642,7 @@ fn exec_approval_decision_truncates_multiline_and_long_commands() {
     // Multiline command: modal should show full command, history records decision only
     let ev_multi = ExecApprovalRequestEvent {
         call_id: "call-multi".into(),
+        item_id: "call-multi".into(),
         turn_id: "turn-multi".into(),
         command: vec!["bash".into(), "-lc".into(), "echo line1\necho line2".into()],
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
@@ -632,6 +651,7 @@ fn exec_approval_decision_truncates_multiline_and_long_commands() {
         ),
         risk: None,
         parsed_cmd: vec![],
+        proposed_rules: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-multi".into(),
@@ -676,12 +696,14 @@ fn exec_approval_decision_truncates_multiline_and_long_commands() {
     let long = format!("echo {}", "a".repeat(200));
     let ev_long = ExecApprovalRequestEvent {
         call_id: "call-long".into(),
+        item_id: "call-long".into(),
         turn_id: "turn-long".into(),
         command: vec!["bash".into(), "-lc".into(), long],
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
         reason: None,
         risk: None,
         parsed_cmd: vec![],
+        proposed_rules: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-long".into(),
@@ -1041,6 +1063,40 @@ fn review_popup_custom_prompt_action_sends_event() {
     assert!(found, "expected OpenReviewCustomPrompt event to be sent");
 }
 
+/// A proposed workspace instruction is only saved once the user confirms it.
+#[test]
+fn workspace_instruction_proposal_asks_before_saving() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+
+    chat.handle_codex_event(Event {
+        id: "t1".into(),
+        msg: EventMsg::WorkspaceInstructionProposed(WorkspaceInstructionProposedEvent {
+            call_id: "call-1".into(),
+            instruction: "Never touch generated/ files.".into(),
+        }),
+    });
+
+    let popup = render_bottom_popup(&chat, 80);
+    assert!(
+        popup.contains("Save workspace instruction?"),
+        "expected a confirmation popup, got: {popup}"
+    );
+    assert!(popup.contains("Never touch generated/ files."));
+    assert!(
+        !std::iter::from_fn(|| rx.try_recv().ok())
+            .any(|ev| matches!(ev, AppEvent::SaveWorkspaceInstruction { .. })),
+        "nothing should be saved before the user confirms"
+    );
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    let saved = std::iter::from_fn(|| rx.try_recv().ok()).find_map(|ev| match ev {
+        AppEvent::SaveWorkspaceInstruction { instruction } => Some(instruction),
+        _ => None,
+    });
+    assert_eq!(saved.as_deref(), Some("Never touch generated/ files."));
+}
+
 #[test]
 fn slash_init_skips_when_project_doc_exists() {
     let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
@@ -1823,6 +1879,7 @@ fn approval_modal_exec_snapshot() {
     // Inject an exec approval request to display the approval modal.
     let ev = ExecApprovalRequestEvent {
         call_id: "call-approve-cmd".into(),
+        item_id: "call-approve-cmd".into(),
         turn_id: "turn-approve-cmd".into(),
         command: vec!["bash".into(), "-lc".into(), "echo hello world".into()],
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
@@ -1831,6 +1888,7 @@ fn approval_modal_exec_snapshot() {
         ),
         risk: None,
         parsed_cmd: vec![],
+        proposed_rules: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-approve".into(),
@@ -1871,12 +1929,14 @@ fn approval_modal_exec_without_reason_snapshot() {
 
     let ev = ExecApprovalRequestEvent {
         call_id: "call-approve-cmd-noreason".into(),
+        item_id: "call-approve-cmd-noreason".into(),
         turn_id: "turn-approve-cmd-noreason".into(),
         command: vec!["bash".into(), "-lc".into(), "echo hello world".into()],
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
         reason: None,
         risk: None,
         parsed_cmd: vec![],
+        proposed_rules: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-noreason".into(),
@@ -1915,6 +1975,8 @@ fn approval_modal_patch_snapshot() {
         changes,
         reason: Some("The model wants to apply changes".into()),
         grant_root: Some(PathBuf::from("/tmp")),
+        deferred_changes: Vec::new(),
+        file_statuses: HashMap::new(),
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-patch".into(),
@@ -2081,6 +2143,7 @@ fn status_widget_and_approval_modal_snapshot() {
     // Now show an approval modal (e.g. exec approval).
     let ev = ExecApprovalRequestEvent {
         call_id: "call-approve-exec".into(),
+        item_id: "call-approve-exec".into(),
         turn_id: "turn-approve-exec".into(),
         command: vec!["echo".into(), "hello world".into()],
         cwd: PathBuf::from("/tmp"),
@@ -2089,6 +2152,7 @@ fn status_widget_and_approval_modal_snapshot() {
         ),
         risk: None,
         parsed_cmd: vec![],
+        proposed_rules: vec![],
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-exec".into(),
@@ -2167,6 +2231,8 @@ fn apply_patch_events_emit_history_cells() {
         changes,
         reason: None,
         grant_root: None,
+        deferred_changes: Vec::new(),
+        file_statuses: HashMap::new(),
     };
     chat.handle_codex_event(Event {
         id: "s1".into(),
@@ -2255,6 +2321,8 @@ fn apply_patch_manual_approval_adjusts_header() {
             changes: proposed_changes,
             reason: None,
             grant_root: None,
+            deferred_changes: Vec::new(),
+            file_statuses: HashMap::new(),
         }),
     });
     drain_insert_history(&mut rx);
@@ -2302,6 +2370,8 @@ fn apply_patch_manual_flow_snapshot() {
             changes: proposed_changes,
             reason: Some("Manual review required".into()),
             grant_root: None,
+            deferred_changes: Vec::new(),
+            file_statuses: HashMap::new(),
         }),
     });
     let history_before_apply = drain_insert_history(&mut rx);
@@ -2351,6 +2421,8 @@ fn apply_patch_approval_sends_op_with_submission_id() {
         changes,
         reason: None,
         grant_root: None,
+        deferred_changes: Vec::new(),
+        file_statuses: HashMap::new(),
     };
     chat.handle_codex_event(Event {
         id: "sub-123".into(),
@@ -2373,6 +2445,70 @@ fn apply_patch_approval_sends_op_with_submission_id() {
     assert!(found, "expected PatchApproval op to be sent");
 }
 
+#[test]
+fn apply_patch_approval_fetches_deferred_changes_first() {
+    let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
+    chat.handle_codex_event(Event {
+        id: "sub-large".into(),
+        msg: EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
+            call_id: "call-large".into(),
+            changes: HashMap::new(),
+            reason: None,
+            grant_root: None,
+            deferred_changes: vec![DeferredFileChange {
+                path: PathBuf::from("big.txt"),
+                kind: DeferredFileChangeKind::Add,
+                added_lines: 1,
+                removed_lines: 0,
+                sha256: String::new(),
+            }],
+            file_statuses: HashMap::new(),
+        }),
+    });
+
+    match op_rx.try_recv() {
+        Ok(Op::GetPatchContent { call_id, path }) => {
+            assert_eq!(call_id, "call-large");
+            assert_eq!(path, PathBuf::from("big.txt"));
+        }
+        other => panic!("expected GetPatchContent op, got {other:?}"),
+    }
+    // Nothing to approve until the file's body arrives.
+    chat.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+    while let Ok(app_ev) = rx.try_recv() {
+        assert!(
+            !matches!(app_ev, AppEvent::CodexOp(Op::PatchApproval { .. })),
+            "approval shown before its deferred changes were fetched"
+        );
+    }
+
+    chat.handle_codex_event(Event {
+        id: "sub-large".into(),
+        msg: EventMsg::PatchContentResponse(PatchContentResponseEvent {
+            call_id: "call-large".into(),
+            path: PathBuf::from("big.txt"),
+            change: Some(FileChange::Add {
+                content: "hello\n".into(),
+            }),
+        }),
+    });
+    chat.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+
+    let mut found = false;
+    while let Ok(app_ev) = rx.try_recv() {
+        if let AppEvent::CodexOp(Op::PatchApproval { id, decision }) = app_ev {
+            assert_eq!(id, "sub-large");
+            assert_matches!(decision, codex_core::protocol::ReviewDecision::Approved);
+            found = true;
+            break;
+        }
+    }
+    assert!(
+        found,
+        "expected PatchApproval op once the changes were fetched"
+    );
+}
+
 #[test]
 fn apply_patch_full_flow_integration_like() {
     let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
@@ -2390,6 +2526,8 @@ fn apply_patch_full_flow_integration_like() {
             changes,
             reason: None,
             grant_root: None,
+            deferred_changes: Vec::new(),
+            file_statuses: HashMap::new(),
         }),
     });
 
@@ -2461,6 +2599,8 @@ fn apply_patch_untrusted_shows_approval_modal() {
             changes,
             reason: None,
             grant_root: None,
+            deferred_changes: Vec::new(),
+            file_statuses: HashMap::new(),
         }),
     });
 
@@ -2509,6 +2649,8 @@ fn apply_patch_request_shows_diff_summary() {
             changes,
             reason: None,
             grant_root: None,
+            deferred_changes: Vec::new(),
+            file_statuses: HashMap::new(),
         }),
     });
 
@@ -2597,6 +2739,7 @@ fn stream_error_updates_status_indicator() {
         msg: EventMsg::StreamError(StreamErrorEvent {
             message: msg.to_string(),
             http_status_code: None,
+            info: CodexErrorInfo::default(),
         }),
     });
 
@@ -2664,6 +2807,8 @@ fn multiple_agent_messages_in_single_turn_emit_multiple_headers() {
         id: "s1".into(),
         msg: EventMsg::TaskComplete(TaskCompleteEvent {
             last_agent_message: None,
+            turn_commit: None,
+            diff_summary: None,
         }),
     });
 
@@ -2947,6 +3092,8 @@ printf 'fenced within fenced\n'
         id: "t1".into(),
         msg: EventMsg::TaskComplete(TaskCompleteEvent {
             last_agent_message: None,
+            turn_commit: None,
+            diff_summary: None,
         }),
     });
     for lines in drain_insert_history(&mut rx) {
@@ -1465,6 +1465,7 @@ mod tests {
     use codex_core::config::ConfigOverrides;
     use codex_core::config::ConfigToml;
     use codex_core::config::types::McpServerConfig;
+    use codex_core::config::types::McpServerStartup;
     use codex_core::config::types::McpServerTransportConfig;
     use codex_core::protocol::McpAuthStatus;
     use codex_protocol::parse_command::ParsedCommand;
@@ -1523,6 +1524,11 @@ mod tests {
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            startup: McpServerStartup::Eager,
+            idle_timeout_sec: None,
+            warm_pool: false,
+            tool_aliases: None,
+            sampling: None,
         };
         config.mcp_servers.insert("docs".to_string(), stdio_config);
 
@@ -1536,12 +1542,18 @@ mod tests {
                 bearer_token_env_var: Some("MCP_TOKEN".to_string()),
                 http_headers: Some(headers),
                 env_http_headers: Some(env_headers),
+                scopes: None,
             },
             enabled: true,
             startup_timeout_sec: None,
             tool_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
+            startup: McpServerStartup::Eager,
+            idle_timeout_sec: None,
+            warm_pool: false,
+            tool_aliases: None,
+            sampling: None,
         };
         config.mcp_servers.insert("http".to_string(), http_config);
 
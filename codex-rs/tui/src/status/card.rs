@@ -108,6 +108,7 @@ impl StatusHistoryCell {
         let sandbox = match &config.sandbox_policy {
             SandboxPolicy::DangerFullAccess => "danger-full-access".to_string(),
             SandboxPolicy::ReadOnly => "read-only".to_string(),
+            SandboxPolicy::Audit => "audit".to_string(),
             SandboxPolicy::WorkspaceWrite { .. } => "workspace-write".to_string(),
         };
         let agents_summary = compose_agents_summary(config);
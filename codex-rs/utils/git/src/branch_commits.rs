@@ -0,0 +1,241 @@
+//! Commits of the working tree onto a side branch, leaving `HEAD`, the index,
+//! and the working tree untouched.
+//!
+//! [`prepare_branch_commit`] snapshots the working tree into a tree object
+//! using a temporary index. The first commit on a branch starts from `HEAD`;
+//! later ones stack on the branch's previous commit, so the branch reads as
+//! one commit per snapshot.
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::path::PathBuf;
+
+use tempfile::Builder;
+
+use crate::GitToolingError;
+use crate::operations::ensure_git_repository;
+use crate::operations::resolve_head;
+use crate::operations::resolve_repository_root;
+use crate::operations::run_git_for_status;
+use crate::operations::run_git_for_stdout;
+use crate::operations::run_git_for_stdout_all;
+
+const COMMIT_AUTHOR_NAME: &str = "Codex";
+const COMMIT_AUTHOR_EMAIL: &str = "codex@codex.local";
+
+/// A working tree snapshot that differs from the branch and is ready to be
+/// committed onto it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingBranchCommit {
+    repo_root: PathBuf,
+    branch: String,
+    /// Current tip of `branch`, or `None` when the branch does not exist yet.
+    branch_tip: Option<String>,
+    parent: String,
+    tree: String,
+}
+
+/// Snapshot the working tree of the repository containing `repo_path` for a
+/// commit onto `branch`. Returns `Ok(None)` when nothing changed since the
+/// branch's last commit (or since `HEAD` for a new branch).
+pub fn prepare_branch_commit(
+    repo_path: &Path,
+    branch: &str,
+) -> Result<Option<PendingBranchCommit>, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+    let branch_ref = format!("refs/heads/{branch}");
+    let branch_tip = match run_git_for_stdout(
+        repo_root.as_path(),
+        vec![
+            OsString::from("rev-parse"),
+            OsString::from("--verify"),
+            OsString::from(format!("{branch_ref}^{{commit}}")),
+        ],
+        None,
+    ) {
+        Ok(sha) => Some(sha),
+        Err(GitToolingError::GitCommand { .. }) => None,
+        Err(other) => return Err(other),
+    };
+    let parent = match &branch_tip {
+        Some(tip) => tip.clone(),
+        None => resolve_head(repo_root.as_path())?.ok_or_else(|| GitToolingError::NoCommits {
+            path: repo_root.clone(),
+        })?,
+    };
+
+    let index_tempdir = Builder::new().prefix("codex-git-index-").tempdir()?;
+    let index_path = index_tempdir.path().join("index");
+    let env = vec![(
+        OsString::from("GIT_INDEX_FILE"),
+        OsString::from(index_path.as_os_str()),
+    )];
+    run_git_for_status(
+        repo_root.as_path(),
+        vec![OsString::from("read-tree"), OsString::from(&parent)],
+        Some(env.as_slice()),
+    )?;
+    run_git_for_status(
+        repo_root.as_path(),
+        vec![OsString::from("add"), OsString::from("--all")],
+        Some(env.as_slice()),
+    )?;
+    let tree = run_git_for_stdout(
+        repo_root.as_path(),
+        vec![OsString::from("write-tree")],
+        Some(env.as_slice()),
+    )?;
+    let parent_tree = run_git_for_stdout(
+        repo_root.as_path(),
+        vec![
+            OsString::from("rev-parse"),
+            OsString::from(format!("{parent}^{{tree}}")),
+        ],
+        None,
+    )?;
+    if tree == parent_tree {
+        return Ok(None);
+    }
+
+    Ok(Some(PendingBranchCommit {
+        repo_root,
+        branch: branch.to_string(),
+        branch_tip,
+        parent,
+        tree,
+    }))
+}
+
+impl PendingBranchCommit {
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    /// Commit the snapshot will be stacked on.
+    pub fn parent(&self) -> &str {
+        &self.parent
+    }
+
+    /// Unified diff from the parent commit to the snapshot.
+    pub fn diff(&self) -> Result<String, GitToolingError> {
+        run_git_for_stdout_all(
+            self.repo_root.as_path(),
+            vec![
+                OsString::from("diff"),
+                OsString::from(&self.parent),
+                OsString::from(&self.tree),
+            ],
+            None,
+        )
+    }
+
+    /// Create the commit and advance the branch to it. Fails without moving
+    /// the branch if it changed since the snapshot was prepared.
+    pub fn commit(&self, message: &str) -> Result<String, GitToolingError> {
+        let identity = [
+            ("GIT_AUTHOR_NAME", COMMIT_AUTHOR_NAME),
+            ("GIT_AUTHOR_EMAIL", COMMIT_AUTHOR_EMAIL),
+            ("GIT_COMMITTER_NAME", COMMIT_AUTHOR_NAME),
+            ("GIT_COMMITTER_EMAIL", COMMIT_AUTHOR_EMAIL),
+        ]
+        .map(|(key, value)| (OsString::from(key), OsString::from(value)));
+        let commit = run_git_for_stdout(
+            self.repo_root.as_path(),
+            vec![
+                OsString::from("commit-tree"),
+                OsString::from(&self.tree),
+                OsString::from("-p"),
+                OsString::from(&self.parent),
+                OsString::from("-m"),
+                OsString::from(message),
+            ],
+            Some(identity.as_slice()),
+        )?;
+        // An empty old value makes `update-ref` require that the branch does
+        // not exist yet.
+        run_git_for_status(
+            self.repo_root.as_path(),
+            vec![
+                OsString::from("update-ref"),
+                OsString::from(format!("refs/heads/{}", self.branch)),
+                OsString::from(&commit),
+                OsString::from(self.branch_tip.as_deref().unwrap_or_default()),
+            ],
+            None,
+        )?;
+        Ok(commit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    fn run_git_in(repo_path: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .output()
+            .expect("git command");
+        assert!(output.status.success(), "git command failed: {args:?}");
+        String::from_utf8(output.stdout)
+            .expect("utf8 output")
+            .trim()
+            .to_string()
+    }
+
+    #[test]
+    fn stacks_commits_on_branch_without_touching_head() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path();
+        run_git_in(repo, &["init", "--initial-branch=main"]);
+        std::fs::write(repo.join("tracked.txt"), "one\n")?;
+        run_git_in(repo, &["add", "tracked.txt"]);
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "initial",
+            ],
+        );
+        let head = run_git_in(repo, &["rev-parse", "HEAD"]);
+
+        assert_eq!(prepare_branch_commit(repo, "codex/turns/test")?, None);
+
+        std::fs::write(repo.join("tracked.txt"), "two\n")?;
+        let first = prepare_branch_commit(repo, "codex/turns/test")?.expect("changes");
+        assert_eq!(first.parent(), head);
+        assert!(first.diff()?.contains("+two"));
+        let first_commit = first.commit("fix: first")?;
+
+        std::fs::write(repo.join("new.txt"), "new\n")?;
+        let second = prepare_branch_commit(repo, "codex/turns/test")?.expect("changes");
+        assert_eq!(second.parent(), first_commit);
+        let diff = second.diff()?;
+        assert!(diff.contains("new.txt") && !diff.contains("+two"), "{diff}");
+        let second_commit = second.commit("feat: second")?;
+
+        assert_eq!(
+            run_git_in(repo, &["rev-parse", "codex/turns/test"]),
+            second_commit
+        );
+        assert_eq!(run_git_in(repo, &["rev-parse", "HEAD"]), head);
+        assert_eq!(
+            run_git_in(repo, &["status", "--porcelain"]),
+            "M tracked.txt\n?? new.txt"
+        );
+        assert_eq!(prepare_branch_commit(repo, "codex/turns/test")?, None);
+        // A stale snapshot cannot move the branch.
+        assert!(second.commit("feat: again").is_err());
+        Ok(())
+    }
+}
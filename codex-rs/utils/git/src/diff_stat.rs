@@ -0,0 +1,160 @@
+//! Line counts of the changes between a commit and the working tree.
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::path::PathBuf;
+
+use tempfile::Builder;
+
+use crate::GitToolingError;
+use crate::operations::ensure_git_repository;
+use crate::operations::resolve_repository_root;
+use crate::operations::run_git_for_status;
+use crate::operations::run_git_for_stdout_all;
+
+/// Lines added and removed in one file. Binary files have no line counts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiffStat {
+    /// Path relative to the repository root.
+    pub path: PathBuf,
+    pub insertions: u64,
+    pub deletions: u64,
+}
+
+/// Diffstat of the working tree of the repository containing `repo_path`
+/// against `commit`, untracked files included. The user's index is left
+/// untouched; the working tree is staged into a temporary one.
+pub fn diff_stat_against_commit(
+    repo_path: &Path,
+    commit: &str,
+) -> Result<Vec<FileDiffStat>, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+
+    let index_tempdir = Builder::new().prefix("codex-git-index-").tempdir()?;
+    let index_path = index_tempdir.path().join("index");
+    let env = vec![(
+        OsString::from("GIT_INDEX_FILE"),
+        OsString::from(index_path.as_os_str()),
+    )];
+    run_git_for_status(
+        repo_root.as_path(),
+        vec![OsString::from("read-tree"), OsString::from(commit)],
+        Some(env.as_slice()),
+    )?;
+    run_git_for_status(
+        repo_root.as_path(),
+        vec![OsString::from("add"), OsString::from("--all")],
+        Some(env.as_slice()),
+    )?;
+    let numstat = run_git_for_stdout_all(
+        repo_root.as_path(),
+        vec![
+            OsString::from("diff"),
+            OsString::from("--cached"),
+            OsString::from("--numstat"),
+            OsString::from("--no-renames"),
+            OsString::from("-z"),
+            OsString::from(commit),
+        ],
+        Some(env.as_slice()),
+    )?;
+    Ok(parse_numstat(&numstat))
+}
+
+/// Parse `git diff --numstat -z --no-renames` output, where each record is
+/// `<added>\t<deleted>\t<path>\0` and binary files count `-` lines.
+fn parse_numstat(output: &str) -> Vec<FileDiffStat> {
+    output
+        .split('\0')
+        .filter_map(|record| {
+            let mut fields = record.splitn(3, '\t');
+            let insertions = fields.next()?;
+            let deletions = fields.next()?;
+            let path = fields.next()?;
+            Some(FileDiffStat {
+                path: PathBuf::from(path),
+                insertions: insertions.parse().unwrap_or(0),
+                deletions: deletions.parse().unwrap_or(0),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    fn run_git_in(repo_path: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .output()
+            .expect("git command");
+        assert!(output.status.success(), "git command failed: {args:?}");
+        String::from_utf8(output.stdout)
+            .expect("utf8 output")
+            .trim()
+            .to_string()
+    }
+
+    #[test]
+    fn counts_tracked_and_untracked_changes() -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path();
+        run_git_in(repo, &["init", "--initial-branch=main"]);
+        std::fs::write(repo.join("tracked.txt"), "one\ntwo\n")?;
+        std::fs::write(repo.join("gone.txt"), "bye\n")?;
+        run_git_in(repo, &["add", "."]);
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "initial",
+            ],
+        );
+        let head = run_git_in(repo, &["rev-parse", "HEAD"]);
+
+        assert_eq!(diff_stat_against_commit(repo, &head)?, Vec::new());
+
+        std::fs::write(repo.join("tracked.txt"), "one\nthree\nfour\n")?;
+        std::fs::remove_file(repo.join("gone.txt"))?;
+        std::fs::write(repo.join("new.txt"), "new\n")?;
+        let mut stats = diff_stat_against_commit(repo, &head)?;
+        stats.sort_by(|a, b| a.path.cmp(&b.path));
+        assert_eq!(
+            stats,
+            vec![
+                FileDiffStat {
+                    path: PathBuf::from("gone.txt"),
+                    insertions: 0,
+                    deletions: 1,
+                },
+                FileDiffStat {
+                    path: PathBuf::from("new.txt"),
+                    insertions: 1,
+                    deletions: 0,
+                },
+                FileDiffStat {
+                    path: PathBuf::from("tracked.txt"),
+                    insertions: 2,
+                    deletions: 1,
+                },
+            ]
+        );
+        // The user's index is not touched.
+        assert_eq!(
+            run_git_in(repo, &["status", "--porcelain"]),
+            "D gone.txt\n M tracked.txt\n?? new.txt"
+        );
+        Ok(())
+    }
+}
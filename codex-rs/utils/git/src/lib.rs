@@ -3,10 +3,14 @@ use std::path::PathBuf;
 
 mod apply;
 mod branch;
+mod branch_commits;
+mod diff_stat;
 mod errors;
 mod ghost_commits;
+mod merge;
 mod operations;
 mod platform;
+mod worktree;
 
 pub use apply::ApplyGitRequest;
 pub use apply::ApplyGitResult;
@@ -15,6 +19,10 @@ pub use apply::extract_paths_from_patch;
 pub use apply::parse_git_apply_output;
 pub use apply::stage_paths;
 pub use branch::merge_base_with_head;
+pub use branch_commits::PendingBranchCommit;
+pub use branch_commits::prepare_branch_commit;
+pub use diff_stat::FileDiffStat;
+pub use diff_stat::diff_stat_against_commit;
 pub use errors::GitToolingError;
 pub use ghost_commits::CreateGhostCommitOptions;
 pub use ghost_commits::GhostSnapshotReport;
@@ -24,11 +32,18 @@ pub use ghost_commits::create_ghost_commit;
 pub use ghost_commits::create_ghost_commit_with_report;
 pub use ghost_commits::restore_ghost_commit;
 pub use ghost_commits::restore_to_commit;
+pub use merge::MergeLabels;
+pub use merge::MergedFile;
+pub use merge::merge_file_contents;
+pub use merge::read_file_at_commit;
 pub use platform::create_symlink;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use serde::Serialize;
 use ts_rs::TS;
+pub use worktree::IsolatedWorktree;
+pub use worktree::create_isolated_worktree;
+pub use worktree::open_isolated_worktree;
 
 type CommitID = String;
 
@@ -0,0 +1,198 @@
+use std::ffi::OsString;
+use std::path::Path;
+use std::process::Command;
+
+use crate::GitToolingError;
+use crate::operations::ensure_git_repository;
+use crate::operations::repo_subdir;
+use crate::operations::resolve_repository_root;
+use crate::operations::run_git_for_status;
+use crate::operations::run_git_for_stdout_all;
+
+/// Outcome of a three-way merge of a single file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedFile {
+    /// Merged contents. Overlapping edits are wrapped in conflict markers.
+    pub contents: String,
+    /// Whether any hunk could not be merged automatically.
+    pub has_conflicts: bool,
+}
+
+/// Labels shown on conflict markers, e.g. `<<<<<<< current`.
+#[derive(Debug, Clone, Copy)]
+pub struct MergeLabels<'a> {
+    pub ours: &'a str,
+    pub base: &'a str,
+    pub theirs: &'a str,
+}
+
+/// Reads `path` as it existed in `commit_id`. `path` may be absolute or
+/// relative to `repo_path`. Returns `None` when the commit does not contain
+/// the file.
+pub fn read_file_at_commit(
+    repo_path: &Path,
+    commit_id: &str,
+    path: &Path,
+) -> Result<Option<String>, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        repo_path.join(path)
+    };
+    let Some(relative) = repo_subdir(&repo_root, &absolute) else {
+        return Err(GitToolingError::PathEscapesRepository { path: absolute });
+    };
+    // Git object paths always use forward slashes.
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    let object = OsString::from(format!("{commit_id}:{relative}"));
+
+    if run_git_for_status(
+        &repo_root,
+        [
+            OsString::from("cat-file"),
+            OsString::from("-e"),
+            object.clone(),
+        ],
+        None,
+    )
+    .is_err()
+    {
+        return Ok(None);
+    }
+    run_git_for_stdout_all(&repo_root, [OsString::from("show"), object], None).map(Some)
+}
+
+/// Merges the changes between `base` and `theirs` into `ours` with
+/// `git merge-file`, keeping conflicting hunks marked in the output.
+pub fn merge_file_contents(
+    ours: &str,
+    base: &str,
+    theirs: &str,
+    labels: MergeLabels<'_>,
+) -> Result<MergedFile, GitToolingError> {
+    let dir = tempfile::tempdir()?;
+    let ours_path = dir.path().join("ours");
+    let base_path = dir.path().join("base");
+    let theirs_path = dir.path().join("theirs");
+    std::fs::write(&ours_path, ours)?;
+    std::fs::write(&base_path, base)?;
+    std::fs::write(&theirs_path, theirs)?;
+
+    let output = Command::new("git")
+        .current_dir(dir.path())
+        .arg("merge-file")
+        .arg("-p")
+        .args(["-L", labels.ours, "-L", labels.base, "-L", labels.theirs])
+        .arg(&ours_path)
+        .arg(&base_path)
+        .arg(&theirs_path)
+        .output()?;
+    let command = "git merge-file -p".to_string();
+
+    // `git merge-file` exits with the number of conflicts (capped at 127) and
+    // with a negative status on error.
+    let has_conflicts = match output.status.code() {
+        Some(0) => false,
+        Some(code) if (1..=127).contains(&code) => true,
+        _ => {
+            return Err(GitToolingError::GitCommand {
+                command,
+                status: output.status,
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+    };
+    let contents = String::from_utf8(output.stdout)
+        .map_err(|source| GitToolingError::GitOutputUtf8 { command, source })?;
+    Ok(MergedFile {
+        contents,
+        has_conflicts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const LABELS: MergeLabels<'static> = MergeLabels {
+        ours: "current",
+        base: "snapshot",
+        theirs: "patch",
+    };
+
+    fn run_git_in(repo_path: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .status()
+            .expect("git command");
+        assert!(status.success(), "git command failed: {args:?}");
+    }
+
+    #[test]
+    fn merges_non_overlapping_edits() -> Result<(), GitToolingError> {
+        let merged = merge_file_contents(
+            "one\ntwo\nTHREE\n",
+            "one\ntwo\nthree\n",
+            "ONE\ntwo\nthree\n",
+            LABELS,
+        )?;
+
+        assert_eq!(
+            merged,
+            MergedFile {
+                contents: "ONE\ntwo\nTHREE\n".to_string(),
+                has_conflicts: false,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn marks_overlapping_edits_as_conflicts() -> Result<(), GitToolingError> {
+        let merged = merge_file_contents("mine\n", "original\n", "theirs\n", LABELS)?;
+
+        assert!(merged.has_conflicts);
+        assert_eq!(
+            merged.contents,
+            "<<<<<<< current\nmine\n=======\ntheirs\n>>>>>>> patch\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reads_files_from_commits() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let repo = temp.path();
+        run_git_in(repo, &["init", "--initial-branch=main"]);
+        std::fs::create_dir(repo.join("src"))?;
+        std::fs::write(repo.join("src/lib.rs"), "committed\n")?;
+        run_git_in(repo, &["add", "."]);
+        run_git_in(
+            repo,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        );
+        std::fs::write(repo.join("src/lib.rs"), "edited\n")?;
+
+        assert_eq!(
+            read_file_at_commit(repo, "HEAD", &repo.join("src/lib.rs"))?,
+            Some("committed\n".to_string())
+        );
+        assert_eq!(
+            read_file_at_commit(repo, "HEAD", Path::new("src/missing.rs"))?,
+            None
+        );
+        Ok(())
+    }
+}
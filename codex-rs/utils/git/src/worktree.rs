@@ -0,0 +1,262 @@
+//! Dedicated `git worktree` checkouts that an agent can edit without touching
+//! the user's primary checkout.
+//!
+//! [`create_isolated_worktree`] checks out the primary checkout's `HEAD` on a
+//! new branch in a separate directory. Its changes can later be inspected
+//! with [`IsolatedWorktree::diff`], applied back onto the primary checkout
+//! with [`IsolatedWorktree::merge_into_primary`], or thrown away with
+//! [`IsolatedWorktree::remove`].
+
+use std::ffi::OsString;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::ApplyGitRequest;
+use crate::ApplyGitResult;
+use crate::GitToolingError;
+use crate::apply_git_patch;
+use crate::branch::merge_base_with_head;
+use crate::operations::ensure_git_repository;
+use crate::operations::repo_subdir;
+use crate::operations::resolve_head;
+use crate::operations::resolve_repository_root;
+use crate::operations::run_git_for_status;
+use crate::operations::run_git_for_stdout_all;
+
+/// A worktree checked out on its own branch from the primary checkout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IsolatedWorktree {
+    repo_root: PathBuf,
+    path: PathBuf,
+    branch: String,
+    base_commit: String,
+}
+
+/// Create a worktree at `worktree_path` on a new `branch` starting at the
+/// `HEAD` of the repository containing `repo_path`.
+pub fn create_isolated_worktree(
+    repo_path: &Path,
+    worktree_path: &Path,
+    branch: &str,
+) -> Result<IsolatedWorktree, GitToolingError> {
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+    let base_commit =
+        resolve_head(repo_root.as_path())?.ok_or_else(|| GitToolingError::NoCommits {
+            path: repo_root.clone(),
+        })?;
+    if let Some(parent) = worktree_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    run_git_for_status(
+        repo_root.as_path(),
+        vec![
+            OsString::from("worktree"),
+            OsString::from("add"),
+            OsString::from("-b"),
+            OsString::from(branch),
+            worktree_path.as_os_str().to_os_string(),
+            OsString::from(&base_commit),
+        ],
+        None,
+    )?;
+    Ok(IsolatedWorktree {
+        repo_root,
+        path: worktree_path.to_path_buf(),
+        branch: branch.to_string(),
+        base_commit,
+    })
+}
+
+/// Reattach to a worktree previously created by [`create_isolated_worktree`].
+/// Returns `Ok(None)` when there is no such worktree any more.
+pub fn open_isolated_worktree(
+    repo_path: &Path,
+    worktree_path: &Path,
+    branch: &str,
+) -> Result<Option<IsolatedWorktree>, GitToolingError> {
+    if !worktree_path.join(".git").exists() {
+        return Ok(None);
+    }
+    ensure_git_repository(repo_path)?;
+    let repo_root = resolve_repository_root(repo_path)?;
+    let Some(base_commit) = merge_base_with_head(repo_root.as_path(), branch)? else {
+        return Ok(None);
+    };
+    Ok(Some(IsolatedWorktree {
+        repo_root,
+        path: worktree_path.to_path_buf(),
+        branch: branch.to_string(),
+        base_commit,
+    }))
+}
+
+impl IsolatedWorktree {
+    /// Root of the primary checkout.
+    pub fn repo_root(&self) -> &Path {
+        &self.repo_root
+    }
+
+    /// Directory of the worktree checkout.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn branch(&self) -> &str {
+        &self.branch
+    }
+
+    /// Commit of the primary checkout the worktree started from.
+    pub fn base_commit(&self) -> &str {
+        &self.base_commit
+    }
+
+    /// The location inside the worktree that corresponds to `path` in the
+    /// primary checkout. Paths outside the repository are returned as is.
+    pub fn map_path(&self, path: &Path) -> PathBuf {
+        if path == self.repo_root {
+            return self.path.clone();
+        }
+        match repo_subdir(&self.repo_root, path) {
+            Some(subdir) => self.path.join(subdir),
+            None => path.to_path_buf(),
+        }
+    }
+
+    /// Everything changed in the worktree since it was created, including
+    /// commits made on its branch and untracked files, as a binary-safe
+    /// unified diff against the base commit.
+    pub fn diff(&self) -> Result<String, GitToolingError> {
+        run_git_for_status(
+            self.path.as_path(),
+            vec![OsString::from("add"), OsString::from("--all")],
+            None,
+        )?;
+        run_git_for_stdout_all(
+            self.path.as_path(),
+            vec![
+                OsString::from("diff"),
+                OsString::from("--cached"),
+                OsString::from("--binary"),
+                OsString::from(&self.base_commit),
+            ],
+            None,
+        )
+    }
+
+    /// Apply the worktree's changes to the primary checkout's working tree
+    /// with a three-way merge. Nothing is committed. Returns `Ok(None)` when
+    /// the worktree has no changes.
+    pub fn merge_into_primary(&self) -> Result<Option<ApplyGitResult>, GitToolingError> {
+        let diff = self.diff()?;
+        if diff.trim().is_empty() {
+            return Ok(None);
+        }
+        let result = apply_git_patch(&ApplyGitRequest {
+            cwd: self.repo_root.clone(),
+            diff,
+            revert: false,
+            preflight: false,
+        })?;
+        Ok(Some(result))
+    }
+
+    /// Delete the worktree directory and its branch.
+    pub fn remove(&self) -> Result<(), GitToolingError> {
+        run_git_for_status(
+            self.repo_root.as_path(),
+            vec![
+                OsString::from("worktree"),
+                OsString::from("remove"),
+                OsString::from("--force"),
+                self.path.as_os_str().to_os_string(),
+            ],
+            None,
+        )?;
+        run_git_for_status(
+            self.repo_root.as_path(),
+            vec![
+                OsString::from("branch"),
+                OsString::from("-D"),
+                OsString::from(&self.branch),
+            ],
+            None,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::process::Command;
+    use tempfile::tempdir;
+
+    fn run_git_in(repo_path: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .current_dir(repo_path)
+            .args(args)
+            .status()
+            .expect("git command");
+        assert!(status.success(), "git command failed: {args:?}");
+    }
+
+    fn init_repo_with_commit(repo_path: &Path) {
+        run_git_in(repo_path, &["init", "--initial-branch=main"]);
+        run_git_in(repo_path, &["config", "core.autocrlf", "false"]);
+        std::fs::write(repo_path.join("tracked.txt"), "one\n").expect("write file");
+        run_git_in(repo_path, &["add", "tracked.txt"]);
+        run_git_in(
+            repo_path,
+            &[
+                "-c",
+                "user.name=Tester",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "initial",
+            ],
+        );
+    }
+
+    #[test]
+    fn worktree_changes_merge_back_without_touching_primary_until_then()
+    -> Result<(), GitToolingError> {
+        let temp = tempdir()?;
+        let repo = temp.path().join("repo");
+        std::fs::create_dir(&repo)?;
+        init_repo_with_commit(&repo);
+        let worktree_path = temp.path().join("worktrees").join("conversation");
+
+        let worktree = create_isolated_worktree(&repo, &worktree_path, "codex/conversation")?;
+        assert_eq!(
+            worktree.map_path(&worktree.repo_root().join("src")),
+            worktree_path.join("src")
+        );
+
+        std::fs::write(worktree_path.join("tracked.txt"), "two\n")?;
+        std::fs::write(worktree_path.join("new.txt"), "new\n")?;
+        assert_eq!(std::fs::read_to_string(repo.join("tracked.txt"))?, "one\n");
+
+        let diff = worktree.diff()?;
+        assert!(diff.contains("+two"), "{diff}");
+        assert!(diff.contains("new.txt"), "{diff}");
+
+        let reopened = open_isolated_worktree(&repo, &worktree_path, "codex/conversation")?;
+        assert_eq!(reopened.as_ref(), Some(&worktree));
+
+        let result = worktree.merge_into_primary()?.expect("changes to merge");
+        assert_eq!(result.exit_code, 0, "{}", result.stderr);
+        assert_eq!(std::fs::read_to_string(repo.join("tracked.txt"))?, "two\n");
+        assert_eq!(std::fs::read_to_string(repo.join("new.txt"))?, "new\n");
+
+        worktree.remove()?;
+        assert!(!worktree_path.exists());
+        assert_eq!(
+            open_isolated_worktree(&repo, &worktree_path, "codex/conversation")?,
+            None
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+//! Proxy and TLS settings shared by every outbound HTTP client.
+//!
+//! The settings are resolved once, when the config is loaded, and installed
+//! process-wide. Crates that build a `reqwest::Client` start from
+//! [`builder`] so a proxy or CA bundle configured for the model provider also
+//! reaches MCP servers, webhooks, and login.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::RwLock;
+
+use reqwest::Certificate;
+use reqwest::ClientBuilder;
+use reqwest::NoProxy;
+use reqwest::Proxy;
+use serde::Deserialize;
+
+/// Environment variable naming a PEM bundle of extra root certificates,
+/// used when `ca_bundle` is not configured.
+pub const SSL_CERT_FILE_ENV_VAR: &str = "SSL_CERT_FILE";
+
+/// The `[http]` config table.
+#[derive(Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct HttpClientSettings {
+    /// Proxy for every request, overriding `HTTPS_PROXY`/`HTTP_PROXY`.
+    pub proxy: Option<String>,
+
+    /// Hosts reached directly even when `proxy` is set.
+    pub no_proxy: Vec<String>,
+
+    /// PEM file with root certificates to trust in addition to the built-in
+    /// ones. Falls back to `SSL_CERT_FILE`.
+    pub ca_bundle: Option<PathBuf>,
+
+    /// Trust only the certificates in `ca_bundle`, pinning connections to the
+    /// corporate CA.
+    pub trust_only_ca_bundle: bool,
+
+    pub min_tls_version: Option<TlsVersion>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TlsVersion {
+    #[serde(rename = "1.2")]
+    Tls1_2,
+    #[serde(rename = "1.3")]
+    Tls1_3,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum HttpClientError {
+    #[error("invalid http.proxy `{url}`: {source}")]
+    InvalidProxy {
+        url: String,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("failed to read CA bundle {}: {source}", path.display())]
+    ReadCaBundle {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("CA bundle {} is not valid PEM: {source}", path.display())]
+    InvalidCaBundle {
+        path: PathBuf,
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("CA bundle {} contains no certificates", path.display())]
+    EmptyCaBundle { path: PathBuf },
+    #[error("http.trust_only_ca_bundle requires http.ca_bundle or SSL_CERT_FILE")]
+    MissingCaBundle,
+}
+
+/// Settings checked and loaded, ready to apply to client builders.
+#[derive(Debug, Clone, Default)]
+pub struct HttpClientConfig {
+    proxy: Option<Proxy>,
+    certificates: Vec<Certificate>,
+    trust_only_ca_bundle: bool,
+    min_tls_version: Option<TlsVersion>,
+}
+
+impl HttpClientSettings {
+    /// Parses the proxy URL and loads the CA bundle, so a typo surfaces when
+    /// the config loads rather than as a connection failure later on.
+    pub fn resolve(&self) -> Result<HttpClientConfig, HttpClientError> {
+        let env_bundle = std::env::var_os(SSL_CERT_FILE_ENV_VAR)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from);
+        self.resolve_with_env_bundle(env_bundle)
+    }
+
+    fn resolve_with_env_bundle(
+        &self,
+        env_bundle: Option<PathBuf>,
+    ) -> Result<HttpClientConfig, HttpClientError> {
+        let proxy = match &self.proxy {
+            Some(url) => {
+                let proxy = Proxy::all(url).map_err(|source| HttpClientError::InvalidProxy {
+                    url: url.clone(),
+                    source,
+                })?;
+                let no_proxy = NoProxy::from_string(&self.no_proxy.join(","));
+                Some(proxy.no_proxy(no_proxy))
+            }
+            None => None,
+        };
+
+        let certificates = match self.ca_bundle.clone().or(env_bundle) {
+            Some(path) => load_ca_bundle(&path)?,
+            None if self.trust_only_ca_bundle => return Err(HttpClientError::MissingCaBundle),
+            None => Vec::new(),
+        };
+
+        Ok(HttpClientConfig {
+            proxy,
+            certificates,
+            trust_only_ca_bundle: self.trust_only_ca_bundle,
+            min_tls_version: self.min_tls_version,
+        })
+    }
+}
+
+fn load_ca_bundle(path: &Path) -> Result<Vec<Certificate>, HttpClientError> {
+    let pem = std::fs::read(path).map_err(|source| HttpClientError::ReadCaBundle {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let certificates =
+        Certificate::from_pem_bundle(&pem).map_err(|source| HttpClientError::InvalidCaBundle {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    if certificates.is_empty() {
+        return Err(HttpClientError::EmptyCaBundle {
+            path: path.to_path_buf(),
+        });
+    }
+    Ok(certificates)
+}
+
+impl HttpClientConfig {
+    pub fn apply(&self, mut builder: ClientBuilder) -> ClientBuilder {
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        for certificate in &self.certificates {
+            builder = builder.add_root_certificate(certificate.clone());
+        }
+        if self.trust_only_ca_bundle {
+            builder = builder.tls_built_in_root_certs(false);
+        }
+        if let Some(version) = self.min_tls_version {
+            builder = builder.min_tls_version(match version {
+                TlsVersion::Tls1_2 => reqwest::tls::Version::TLS_1_2,
+                TlsVersion::Tls1_3 => reqwest::tls::Version::TLS_1_3,
+            });
+        }
+        builder
+    }
+}
+
+static INSTALLED: RwLock<Option<Arc<HttpClientConfig>>> = RwLock::new(None);
+
+/// Makes `config` apply to every client built from now on.
+pub fn install(config: HttpClientConfig) {
+    if let Ok(mut installed) = INSTALLED.write() {
+        *installed = Some(Arc::new(config));
+    }
+}
+
+/// A client builder with the installed proxy and TLS settings applied.
+pub fn builder() -> ClientBuilder {
+    apply(ClientBuilder::new())
+}
+
+/// Applies the installed proxy and TLS settings to `builder`.
+pub fn apply(builder: ClientBuilder) -> ClientBuilder {
+    let installed = INSTALLED
+        .read()
+        .ok()
+        .and_then(|installed| installed.clone());
+    match installed {
+        Some(config) => config.apply(builder),
+        None => builder,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn default_settings_resolve_to_nothing() {
+        let config = HttpClientSettings::default()
+            .resolve_with_env_bundle(None)
+            .expect("resolve");
+        assert!(config.proxy.is_none());
+        assert!(config.certificates.is_empty());
+        assert!(config.apply(ClientBuilder::new()).build().is_ok());
+    }
+
+    #[test]
+    fn proxy_is_parsed_when_resolving() {
+        let settings = HttpClientSettings {
+            proxy: Some("http://proxy.corp:8080".to_string()),
+            no_proxy: vec!["localhost".to_string()],
+            ..Default::default()
+        };
+        let config = settings.resolve_with_env_bundle(None).expect("resolve");
+        assert!(config.proxy.is_some());
+        assert!(config.apply(ClientBuilder::new()).build().is_ok());
+
+        let settings = HttpClientSettings {
+            proxy: Some("not a url".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            settings.resolve_with_env_bundle(None),
+            Err(HttpClientError::InvalidProxy { .. })
+        ));
+    }
+
+    #[test]
+    fn ca_bundle_problems_are_reported() {
+        let settings = HttpClientSettings {
+            trust_only_ca_bundle: true,
+            ..Default::default()
+        };
+        assert!(matches!(
+            settings.resolve_with_env_bundle(None),
+            Err(HttpClientError::MissingCaBundle)
+        ));
+
+        let empty = NamedTempFile::new().expect("tempfile");
+        let settings = HttpClientSettings {
+            ca_bundle: Some(empty.path().to_path_buf()),
+            ..Default::default()
+        };
+        let err = settings
+            .resolve_with_env_bundle(None)
+            .expect_err("empty bundle");
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "CA bundle {} contains no certificates",
+                empty.path().display()
+            )
+        );
+
+        let settings = HttpClientSettings {
+            ca_bundle: Some(PathBuf::from("/nonexistent/ca.pem")),
+            ..Default::default()
+        };
+        assert!(matches!(
+            settings.resolve_with_env_bundle(None),
+            Err(HttpClientError::ReadCaBundle { .. })
+        ));
+    }
+}
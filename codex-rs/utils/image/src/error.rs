@@ -17,6 +17,13 @@ pub enum ImageProcessingError {
         #[source]
         source: image::ImageError,
     },
+    #[error("failed to decode image: {source}")]
+    DecodeData {
+        #[source]
+        source: image::ImageError,
+    },
+    #[error("unsupported image format; expected PNG or JPEG")]
+    UnsupportedFormat,
     #[error("failed to encode image as {format:?}: {source}")]
     Encode {
         format: ImageFormat,
@@ -32,6 +39,8 @@ impl ImageProcessingError {
             ImageProcessingError::Decode {
                 source: ImageError::Decoding(_),
                 ..
+            } | ImageProcessingError::DecodeData {
+                source: ImageError::Decoding(_),
             }
         )
     }
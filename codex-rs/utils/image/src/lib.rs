@@ -48,55 +48,77 @@ pub fn load_and_resize_to_fit(path: &Path) -> Result<EncodedImage, ImageProcessi
     let key = sha1_digest(&file_bytes);
 
     IMAGE_CACHE.get_or_try_insert_with(key, move || {
-        let format = match image::guess_format(&file_bytes) {
-            Ok(ImageFormat::Png) => Some(ImageFormat::Png),
-            Ok(ImageFormat::Jpeg) => Some(ImageFormat::Jpeg),
-            _ => None,
-        };
-
-        let dynamic = image::load_from_memory(&file_bytes).map_err(|source| {
-            ImageProcessingError::Decode {
-                path: path_buf.clone(),
-                source,
-            }
-        })?;
-
-        let (width, height) = dynamic.dimensions();
-
-        let encoded = if width <= MAX_WIDTH && height <= MAX_HEIGHT {
-            if let Some(format) = format {
-                let mime = format_to_mime(format);
-                EncodedImage {
-                    bytes: file_bytes,
-                    mime,
-                    width,
-                    height,
-                }
-            } else {
-                let (bytes, output_format) = encode_image(&dynamic, ImageFormat::Png)?;
-                let mime = format_to_mime(output_format);
-                EncodedImage {
-                    bytes,
-                    mime,
-                    width,
-                    height,
-                }
+        encode_to_fit(file_bytes, |source| ImageProcessingError::Decode {
+            path: path_buf,
+            source,
+        })
+    })
+}
+
+/// Like [`load_and_resize_to_fit`] for image bytes already in memory, such as
+/// a pasted screenshot. Only PNG and JPEG are accepted.
+pub fn resize_to_fit(bytes: Vec<u8>) -> Result<EncodedImage, ImageProcessingError> {
+    if !matches!(
+        image::guess_format(&bytes),
+        Ok(ImageFormat::Png | ImageFormat::Jpeg)
+    ) {
+        return Err(ImageProcessingError::UnsupportedFormat);
+    }
+
+    let key = sha1_digest(&bytes);
+
+    IMAGE_CACHE.get_or_try_insert_with(key, move || {
+        encode_to_fit(bytes, |source| ImageProcessingError::DecodeData { source })
+    })
+}
+
+fn encode_to_fit(
+    file_bytes: Vec<u8>,
+    decode_error: impl FnOnce(image::ImageError) -> ImageProcessingError,
+) -> Result<EncodedImage, ImageProcessingError> {
+    let format = match image::guess_format(&file_bytes) {
+        Ok(ImageFormat::Png) => Some(ImageFormat::Png),
+        Ok(ImageFormat::Jpeg) => Some(ImageFormat::Jpeg),
+        _ => None,
+    };
+
+    let dynamic = image::load_from_memory(&file_bytes).map_err(decode_error)?;
+
+    let (width, height) = dynamic.dimensions();
+
+    let encoded = if width <= MAX_WIDTH && height <= MAX_HEIGHT {
+        if let Some(format) = format {
+            let mime = format_to_mime(format);
+            EncodedImage {
+                bytes: file_bytes,
+                mime,
+                width,
+                height,
             }
         } else {
-            let resized = dynamic.resize(MAX_WIDTH, MAX_HEIGHT, FilterType::Triangle);
-            let target_format = format.unwrap_or(ImageFormat::Png);
-            let (bytes, output_format) = encode_image(&resized, target_format)?;
+            let (bytes, output_format) = encode_image(&dynamic, ImageFormat::Png)?;
             let mime = format_to_mime(output_format);
             EncodedImage {
                 bytes,
                 mime,
-                width: resized.width(),
-                height: resized.height(),
+                width,
+                height,
             }
-        };
+        }
+    } else {
+        let resized = dynamic.resize(MAX_WIDTH, MAX_HEIGHT, FilterType::Triangle);
+        let target_format = format.unwrap_or(ImageFormat::Png);
+        let (bytes, output_format) = encode_image(&resized, target_format)?;
+        let mime = format_to_mime(output_format);
+        EncodedImage {
+            bytes,
+            mime,
+            width: resized.width(),
+            height: resized.height(),
+        }
+    };
 
-        Ok(encoded)
-    })
+    Ok(encoded)
 }
 
 fn read_file_bytes(path: &Path, path_for_error: &Path) -> Result<Vec<u8>, ImageProcessingError> {
@@ -222,6 +244,27 @@ mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn downscales_in_memory_image() {
+        let image = ImageBuffer::from_pixel(4096, 1024, Rgba([0u8, 90, 180, 255]));
+        let mut bytes = std::io::Cursor::new(Vec::new());
+        image
+            .write_to(&mut bytes, ImageFormat::Png)
+            .expect("encode png");
+
+        let processed = resize_to_fit(bytes.into_inner()).expect("process image");
+
+        assert_eq!(processed.mime, "image/png");
+        assert!(processed.width <= MAX_WIDTH);
+        assert!(processed.height <= MAX_HEIGHT);
+    }
+
+    #[test]
+    fn rejects_in_memory_data_that_is_not_png_or_jpeg() {
+        let err = resize_to_fit(b"GIF89a not really".to_vec()).expect_err("gif should fail");
+        assert!(matches!(err, ImageProcessingError::UnsupportedFormat));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn reprocesses_updated_file_contents() {
         {
@@ -8,7 +8,10 @@ use std::time::Duration;
 
 use anyhow::Result;
 use portable_pty::native_pty_system;
+use portable_pty::Child;
+#[cfg(not(target_os = "linux"))]
 use portable_pty::CommandBuilder;
+use portable_pty::PtyPair;
 use portable_pty::PtySize;
 use tokio::sync::broadcast;
 use tokio::sync::mpsc;
@@ -99,6 +102,32 @@ impl Drop for ExecCommandSession {
     }
 }
 
+/// Window size used when opening a PTY. Values are clamped to the range
+/// supported by the platform PTY implementation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalSize {
+    pub rows: i32,
+    pub cols: i32,
+}
+
+impl Default for TerminalSize {
+    fn default() -> Self {
+        Self { rows: 24, cols: 80 }
+    }
+}
+
+impl TerminalSize {
+    fn to_pty_size(self) -> PtySize {
+        let clamp = |value: i32| value.clamp(1, i32::from(u16::MAX)) as u16;
+        PtySize {
+            rows: clamp(self.rows),
+            cols: clamp(self.cols),
+            pixel_width: 0,
+            pixel_height: 0,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct SpawnedPty {
     pub session: ExecCommandSession,
@@ -112,30 +141,16 @@ pub async fn spawn_pty_process(
     cwd: &Path,
     env: &HashMap<String, String>,
     arg0: &Option<String>,
+    size: TerminalSize,
 ) -> Result<SpawnedPty> {
     if program.is_empty() {
         anyhow::bail!("missing program for PTY spawn");
     }
 
     let pty_system = native_pty_system();
-    let pair = pty_system.openpty(PtySize {
-        rows: 24,
-        cols: 80,
-        pixel_width: 0,
-        pixel_height: 0,
-    })?;
-
-    let mut command_builder = CommandBuilder::new(arg0.as_ref().unwrap_or(&program.to_string()));
-    command_builder.cwd(cwd);
-    command_builder.env_clear();
-    for arg in args {
-        command_builder.arg(arg);
-    }
-    for (key, value) in env {
-        command_builder.env(key, value);
-    }
+    let pair = pty_system.openpty(size.to_pty_size())?;
 
-    let mut child = pair.slave.spawn_command(command_builder)?;
+    let mut child = spawn_child(&pair, program, args, cwd, env, arg0)?;
     let killer = child.clone_killer();
 
     let (writer_tx, mut writer_rx) = mpsc::channel::<Vec<u8>>(128);
@@ -209,3 +224,84 @@ pub async fn spawn_pty_process(
         exit_rx,
     })
 }
+
+#[cfg(not(target_os = "linux"))]
+fn spawn_child(
+    pair: &PtyPair,
+    program: &str,
+    args: &[String],
+    cwd: &Path,
+    env: &HashMap<String, String>,
+    arg0: &Option<String>,
+) -> Result<Box<dyn Child + Send + Sync>> {
+    let mut command_builder = CommandBuilder::new(arg0.as_ref().unwrap_or(&program.to_string()));
+    command_builder.cwd(cwd);
+    command_builder.env_clear();
+    for arg in args {
+        command_builder.arg(arg);
+    }
+    for (key, value) in env {
+        command_builder.env(key, value);
+    }
+    Ok(pair.slave.spawn_command(command_builder)?)
+}
+
+/// Spawns the child on the terminal's secondary side as the leader of a new
+/// session, like `portable_pty` does, and additionally asks the kernel to send
+/// it SIGTERM when the spawning process dies, matching how Codex spawns
+/// commands without a terminal.
+#[cfg(target_os = "linux")]
+fn spawn_child(
+    pair: &PtyPair,
+    program: &str,
+    args: &[String],
+    cwd: &Path,
+    env: &HashMap<String, String>,
+    arg0: &Option<String>,
+) -> Result<Box<dyn Child + Send + Sync>> {
+    use std::os::unix::fs::OpenOptionsExt;
+    use std::os::unix::process::CommandExt;
+
+    let tty_name = pair
+        .master
+        .tty_name()
+        .ok_or_else(|| anyhow::anyhow!("PTY has no terminal device"))?;
+    let tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .custom_flags(libc::O_NOCTTY)
+        .open(tty_name)?;
+
+    let mut command = std::process::Command::new(arg0.as_deref().unwrap_or(program));
+    command
+        .args(args)
+        .current_dir(cwd)
+        .env_clear()
+        .envs(env)
+        .stdin(tty.try_clone()?)
+        .stdout(tty.try_clone()?)
+        .stderr(tty);
+
+    let parent_pid = unsafe { libc::getpid() };
+    unsafe {
+        command.pre_exec(move || {
+            if libc::setsid() == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            // stdin is the terminal; make it the session's controlling terminal.
+            if libc::ioctl(0, libc::TIOCSCTTY, 0) == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            if libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGTERM) == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+            // The parent may have exited before prctl ran.
+            if libc::getppid() != parent_pid {
+                libc::raise(libc::SIGTERM);
+            }
+            Ok(())
+        });
+    }
+
+    Ok(Box::new(command.spawn()?))
+}
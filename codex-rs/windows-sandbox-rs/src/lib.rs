@@ -210,7 +210,7 @@ mod windows_impl {
 
         let (h_token, psid_to_use): (HANDLE, *mut c_void) = unsafe {
             match &policy {
-                SandboxPolicy::ReadOnly => {
+                SandboxPolicy::ReadOnly | SandboxPolicy::Audit => {
                     let caps = load_or_create_cap_sids(codex_home);
                     ensure_dir(&cap_sid_path)?;
                     fs::write(&cap_sid_path, serde_json::to_string(&caps)?)?;
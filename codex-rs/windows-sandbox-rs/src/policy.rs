@@ -4,6 +4,7 @@ pub use codex_protocol::protocol::SandboxPolicy;
 pub fn parse_policy(value: &str) -> Result<SandboxPolicy> {
     match value {
         "read-only" => Ok(SandboxPolicy::ReadOnly),
+        "audit" => Ok(SandboxPolicy::Audit),
         "workspace-write" => Ok(SandboxPolicy::new_workspace_write_policy()),
         "danger-full-access" => anyhow::bail!("DangerFullAccess is not supported for sandboxing"),
         other => {